@@ -0,0 +1,153 @@
+// benches/hot_paths.rs
+//
+// Criterion benchmarks for the paths every event goes through: evidence
+// fusion, probability calibration, incident upsert, the thinking processor
+// (one home, and several homes at once to show what per-home sharding buys),
+// and the full pipeline with the VPS replaced by a local mock. Run with
+// `cargo bench --bench hot_paths`, then `cargo run --bin bench-report` to
+// compare against benches/baseline.json.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use insane_ai_security::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier};
+use insane_ai_security::thinking::{
+    Event, Evidence, Incident, IncidentStore, ProbabilityCalibrator, TemperatureCalibration, ThinkingAIConfig,
+    ThinkingAIProcessor,
+};
+use insane_ai_security::vps_client::VpsApiClient;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn event(ts: f64, cam: &str, track: &str) -> Event {
+    Event {
+        ts,
+        cam: cam.to_string(),
+        person_track: track.to_string(),
+        rang_doorbell: false,
+        knocked: false,
+        dwell_s: 30.0,
+        away_prob: 0.6,
+        expected_window: false,
+        token: None,
+        evidence: Evidence { llr_time: 0.4, llr_entry: 0.3, llr_behavior: 0.6, llr_presence: 0.2, ..Default::default() },
+    }
+}
+
+fn fusion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fusion");
+    for events in [1usize, 10, 100] {
+        let mut incident = Incident::new(1, 1000.0, "track_a".to_string());
+        for i in 0..events {
+            incident.add_event(event(1000.0 + i as f64, if i % 2 == 0 { "front_door" } else { "driveway" }, "track_a"));
+        }
+        group.bench_with_input(BenchmarkId::new("fused_evidence", events), &incident, |b, incident| {
+            b.iter(|| black_box(incident.fused_evidence(3.0, -3.0)))
+        });
+    }
+    group.finish();
+}
+
+fn calibration(c: &mut Criterion) {
+    let config = ThinkingAIConfig::default();
+    let calibrator = TemperatureCalibration {
+        mean_logit: config.mean_logit,
+        temperature: config.temperature,
+        odds_cap: config.odds_cap,
+    };
+    c.bench_function("calibration/temperature", |b| {
+        b.iter(|| black_box(calibrator.calibrate(black_box(1.7))))
+    });
+}
+
+fn incident_upsert(c: &mut Criterion) {
+    c.bench_function("incident_upsert/existing_track", |b| {
+        b.iter_batched(
+            || {
+                let mut store = IncidentStore::new(600.0);
+                store.upsert_event("home_1", event(1000.0, "front_door", "track_a"));
+                store
+            },
+            |mut store| black_box(store.upsert_event("home_1", event(1001.0, "front_door", "track_a"))),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn process_event(c: &mut Criterion) {
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let mut ts = 1000.0;
+    c.bench_function("process_event/one_home", |b| {
+        b.iter(|| {
+            ts += 1.0;
+            black_box(processor.process_event("home_1", event(ts, "front_door", "track_a")))
+        })
+    });
+
+    // Homes on their own threads: with sharded state this scales with cores
+    let mut group = c.benchmark_group("process_event/concurrent_homes");
+    for homes in [1usize, 4, 16] {
+        group.bench_with_input(BenchmarkId::from_parameter(homes), &homes, |b, &homes| {
+            b.iter_batched(
+                || Arc::new(ThinkingAIProcessor::new(ThinkingAIConfig::default())),
+                |processor| {
+                    std::thread::scope(|scope| {
+                        for h in 0..homes {
+                            let processor = processor.clone();
+                            scope.spawn(move || {
+                                let home = format!("home_{}", h);
+                                for i in 0..20 {
+                                    processor.process_event(&home, event(1000.0 + i as f64, "front_door", "track_a"));
+                                }
+                            });
+                        }
+                    });
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+// Answers every processing request with the same completed job
+async fn mock_vps() -> String {
+    use axum::{routing::post, Json, Router};
+    let app = Router::new().route("/v1/process", post(|| async {
+        Json(serde_json::json!({ "job_id": "bench", "status": "completed", "result_url": null, "error_message": null }))
+    }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    url
+}
+
+fn pipeline(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let url = runtime.block_on(mock_vps());
+    let mut config = PipelineConfig::default();
+    config.overnight_enabled = false;
+    let pipeline = tokio::sync::Mutex::new(EventPipeline::new(config, VpsApiClient::new(url)));
+    let mut ts = 1_700_000_000;
+
+    c.bench_function("pipeline/process_event_mock_vps", |b| {
+        b.to_async(&runtime).iter(|| {
+            ts += 1;
+            let raw = RawEvent {
+                event_id: Uuid::new_v4(),
+                sensor_id: "front_door".to_string(),
+                timestamp: ts,
+                data: "person_detected=true|dwell_time=30s".into(),
+                user_id: "user_1".to_string(),
+                home_id: "home_1".to_string(),
+                image_url: None,
+                image_data: None,
+            };
+            let pipeline = &pipeline;
+            async move {
+                black_box(pipeline.lock().await.process_event(raw, SubscriptionTier::Premium, "bench").await.ok())
+            }
+        })
+    });
+}
+
+criterion_group!(benches, fusion, calibration, incident_upsert, process_event, pipeline);
+criterion_main!(benches);
@@ -0,0 +1,201 @@
+// src/activity_baseline.rs
+
+// What normal activity looks like at a home. Every event is counted against
+// its zone and the local hour of the week it happened in (Tuesday 22:00,
+// Saturday 14:00), so after a few weeks each zone has an expected number of
+// events for every hour slot. The events in the current slot are compared to
+// that expectation before the new one is learned: a slot running well above
+// its usual level is called out in the narrative ("activity in the back
+// garden was 3x the usual for a Tuesday night") and the deviation adds
+// behavior evidence, a fixed amount per doubling over the usual, capped.
+// Quiet slots add nothing. Zones and the home's timezone come from the
+// onboarding config; cameras without a zone count as their own zone.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct ActivityBaselineConfig {
+    pub min_weeks: f64,         // History a home needs before deviations are scored
+    pub min_expected: f64,      // Floor on the expected count, so a rarely active slot is not infinitely surprising
+    pub ratio_floor: f64,       // Observed/expected below this is not notable
+    pub llr_per_doubling: f64,  // Behavior LLR per doubling of activity over the usual
+    pub max_llr: f64,
+}
+
+impl Default for ActivityBaselineConfig {
+    fn default() -> Self {
+        Self {
+            min_weeks: 3.0,
+            min_expected: 0.5,
+            ratio_floor: 2.0,
+            llr_per_doubling: 0.3,
+            max_llr: 1.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineDeviation {
+    pub zone: String,
+    pub weekday: Weekday,
+    pub hour: u32,             // Local hour the slot starts at
+    pub observed: u32,         // Events in the slot so far, this one included
+    pub expected: f64,         // Usual events in this slot of the week, before this one was learned
+    pub ratio: f64,            // observed / expected (expected floored at `min_expected`)
+    pub behavior_llr: f64,     // 0 until the home has enough history, or when activity is not unusual
+}
+
+impl BaselineDeviation {
+    /// Sentence for the incident narrative
+    pub fn narrative(&self) -> String {
+        let ratio = if (self.ratio - self.ratio.round()).abs() < 0.05 {
+            format!("{:.0}", self.ratio)
+        } else {
+            format!("{:.1}", self.ratio)
+        };
+        // The small hours belong to the night before
+        let day = if self.hour < 5 { self.weekday.pred() } else { self.weekday };
+        format!("Activity in the {} was {}x the usual for a {} {}.", self.zone, ratio, weekday_name(day), part_of_day(self.hour))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneBaseline {
+    pub zone: String,
+    pub expected: Vec<Vec<f64>>, // Monday first, 24 local hours each
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineView {
+    pub home_id: String,
+    pub timezone: String,
+    pub weeks_observed: f64,
+    pub scoring: bool, // Whether deviations are scored yet
+    pub zones: Vec<ZoneBaseline>,
+}
+
+#[derive(Debug)]
+struct HomeBaseline {
+    first_seen: DateTime<Utc>,
+    counts: HashMap<String, [[u32; 24]; 7]>,        // Zone -> events per weekday and local hour
+    current: HashMap<String, (NaiveDateTime, u32)>, // Zone -> local slot start and events in it
+}
+
+impl HomeBaseline {
+    fn new(first_seen: DateTime<Utc>) -> Self {
+        Self { first_seen, counts: HashMap::new(), current: HashMap::new() }
+    }
+
+    fn weeks(&self, at: DateTime<Utc>) -> f64 {
+        (at - self.first_seen).num_seconds().max(0) as f64 / Duration::weeks(1).num_seconds() as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HomeLayout {
+    timezone: Tz,
+    zones: HashMap<String, String>, // Camera -> zone
+}
+
+#[derive(Debug, Default)]
+pub struct ActivityBaseline {
+    config: ActivityBaselineConfig,
+    homes: DashMap<String, HomeBaseline>,
+    layouts: DashMap<String, HomeLayout>,
+}
+
+impl ActivityBaseline {
+    pub fn new(config: ActivityBaselineConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &ActivityBaselineConfig {
+        &self.config
+    }
+
+    /// Zone watched by each camera and the timezone that decides the local hour
+    pub fn set_layout(&self, home_id: &str, camera_zones: HashMap<String, String>, timezone: &str) {
+        let timezone = timezone.parse().unwrap_or(Tz::UTC);
+        self.layouts.insert(home_id.to_string(), HomeLayout { timezone, zones: camera_zones });
+    }
+
+    /// Count one event from `camera`; returns how its slot compares to the usual
+    pub fn observe(&self, home_id: &str, camera: &str, at: DateTime<Utc>) -> BaselineDeviation {
+        let (timezone, zone) = match self.layouts.get(home_id) {
+            Some(layout) => (layout.timezone, layout.zones.get(camera).cloned().unwrap_or_else(|| camera.to_string())),
+            None => (Tz::UTC, camera.to_string()),
+        };
+        let local = at.with_timezone(&timezone).naive_local();
+        let slot = local.date().and_hms_opt(local.hour(), 0, 0).unwrap_or(local);
+        let (day, hour) = (local.weekday().num_days_from_monday() as usize, local.hour() as usize);
+
+        let mut home = self.homes.entry(home_id.to_string()).or_insert_with(|| HomeBaseline::new(at));
+        let weeks = home.weeks(at);
+        let in_slot = match home.current.get(&zone) {
+            Some((start, count)) if *start == slot => *count,
+            _ => 0,
+        };
+        let learned = home.counts.get(&zone).map_or(0, |counts| counts[day][hour]);
+        // The slot's own events are already in the counts; leave them out of the expectation
+        let expected = (learned - in_slot) as f64 / weeks.max(1.0);
+        let observed = in_slot + 1;
+        let ratio = observed as f64 / expected.max(self.config.min_expected);
+        let behavior_llr = if weeks >= self.config.min_weeks && ratio >= self.config.ratio_floor {
+            (ratio.log2() * self.config.llr_per_doubling).min(self.config.max_llr)
+        } else {
+            0.0
+        };
+
+        home.counts.entry(zone.clone()).or_insert([[0; 24]; 7])[day][hour] += 1;
+        home.current.insert(zone.clone(), (slot, observed));
+        BaselineDeviation { zone, weekday: local.weekday(), hour: hour as u32, observed, expected, ratio, behavior_llr }
+    }
+
+    /// Expected events per zone and local hour of the week for a home
+    pub fn view(&self, home_id: &str, now: DateTime<Utc>) -> BaselineView {
+        let timezone = self.layouts.get(home_id).map_or(Tz::UTC, |l| l.timezone).name().to_string();
+        let Some(home) = self.homes.get(home_id) else {
+            return BaselineView { home_id: home_id.to_string(), timezone, weeks_observed: 0.0, scoring: false, zones: Vec::new() };
+        };
+        let weeks = home.weeks(now);
+        let mut zones: Vec<ZoneBaseline> = home.counts.iter()
+            .map(|(zone, counts)| ZoneBaseline {
+                zone: zone.clone(),
+                expected: counts.iter().map(|day| day.iter().map(|c| *c as f64 / weeks.max(1.0)).collect()).collect(),
+            })
+            .collect();
+        zones.sort_by(|a, b| a.zone.cmp(&b.zone));
+        BaselineView {
+            home_id: home_id.to_string(),
+            timezone,
+            weeks_observed: weeks,
+            scoring: weeks >= self.config.min_weeks,
+            zones,
+        }
+    }
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn part_of_day(hour: u32) -> &'static str {
+    match hour {
+        5..=11 => "morning",
+        12..=16 => "afternoon",
+        17..=21 => "evening",
+        _ => "night",
+    }
+}
@@ -0,0 +1,241 @@
+// src/adaptive_thresholds.rs
+
+// Per-home alert thresholds and how they drift. A home's threshold starts at
+// the engine default, is replaced by onboarding when the household picks a
+// sensitivity profile, and is then nudged by a learned modifier: an alert the
+// household labels benign raises it a step, a threat that never alerted lowers
+// it a step, within fixed bounds. Every change is kept as a numbered version
+// with where it came from, so the review API can show how a home's threshold
+// moved over time, and a reset puts it back to the default in one step. With
+// a data directory each home's history is written to its own JSON file,
+// tagged with a format version, and restored at startup.
+
+use chrono::{DateTime, TimeZone, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use crate::home_files;
+use crate::thinking::{sigmoid, IncidentLabel};
+
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ThresholdError {
+    #[error("Failed to persist thresholds: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to encode thresholds: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveThresholdConfig {
+    pub step: f64,         // Logit moved by one labeled outcome
+    pub max_modifier: f64, // Learning may move a threshold this far from its base, either way
+    pub history_len: usize,
+}
+
+impl Default for AdaptiveThresholdConfig {
+    fn default() -> Self {
+        Self { step: 0.05, max_modifier: 0.5, history_len: 500 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdSource {
+    Default,
+    Onboarding,
+    Learned,
+    Reset,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdVersion {
+    pub version: u32,
+    pub base_logit: f64,      // From the default or onboarding
+    pub modifier: f64,        // Learned from labeled outcomes
+    pub effective_logit: f64, // What scoring uses: base plus modifier
+    pub source: ThresholdSource,
+    pub actor: Option<String>, // User behind a reset
+    pub note: String,
+    pub at: DateTime<Utc>,
+}
+
+/// How a home's threshold moved away from the default
+#[derive(Debug, Clone, Serialize)]
+pub struct ThresholdDrift {
+    pub home_id: String,
+    pub default_logit: f64,
+    pub default_probability: f64,
+    pub current: ThresholdVersion,
+    pub current_probability: f64,
+    pub drift_logit: f64,
+    pub history: Vec<ThresholdVersion>, // Oldest first
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HomeThresholds {
+    #[serde(default)]
+    format: u32,
+    home_id: String,
+    history: VecDeque<ThresholdVersion>,
+}
+
+#[derive(Debug, Default)]
+pub struct AdaptiveThresholds {
+    config: AdaptiveThresholdConfig,
+    default_logit: f64,
+    homes: DashMap<String, HomeThresholds>,
+    dir: Option<PathBuf>,
+}
+
+impl AdaptiveThresholds {
+    pub fn new(config: AdaptiveThresholdConfig, default_logit: f64) -> Self {
+        Self { config, default_logit, ..Self::default() }
+    }
+
+    /// Store backed by `dir`, loading any histories already there
+    pub fn persistent(config: AdaptiveThresholdConfig, default_logit: f64, dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let homes = DashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path).map(|text| serde_json::from_str::<HomeThresholds>(&text)) {
+                Ok(Ok(home)) if home.format > FORMAT_VERSION => {
+                    tracing::warn!("Skipping thresholds {} written by a newer version (format {})", path.display(), home.format);
+                }
+                Ok(Ok(home)) => {
+                    homes.insert(home.home_id.clone(), home);
+                }
+                Ok(Err(e)) => tracing::warn!("Skipping unreadable thresholds {}: {}", path.display(), e),
+                Err(e) => tracing::warn!("Skipping thresholds {}: {}", path.display(), e),
+            }
+        }
+        Ok(Self { config, default_logit, homes, dir: Some(dir) })
+    }
+
+    pub fn config(&self) -> &AdaptiveThresholdConfig {
+        &self.config
+    }
+
+    /// Active threshold; version 0 means the home has never moved off the default
+    pub fn current(&self, home_id: &str) -> ThresholdVersion {
+        self.homes.get(home_id)
+            .and_then(|h| h.history.back().cloned())
+            .unwrap_or_else(|| ThresholdVersion {
+                version: 0,
+                base_logit: self.default_logit,
+                modifier: 0.0,
+                effective_logit: self.default_logit,
+                source: ThresholdSource::Default,
+                actor: None,
+                note: String::new(),
+                at: Utc.timestamp_opt(0, 0).unwrap(),
+            })
+    }
+
+    /// Every home with a history and its effective threshold, to restore at startup
+    pub fn effective(&self) -> Vec<(String, f64)> {
+        self.homes.iter()
+            .filter_map(|h| h.history.back().map(|v| (h.home_id.clone(), v.effective_logit)))
+            .collect()
+    }
+
+    pub fn drift(&self, home_id: &str) -> ThresholdDrift {
+        let current = self.current(home_id);
+        ThresholdDrift {
+            home_id: home_id.to_string(),
+            default_logit: self.default_logit,
+            default_probability: sigmoid(self.default_logit),
+            current_probability: sigmoid(current.effective_logit),
+            drift_logit: current.effective_logit - self.default_logit,
+            current,
+            history: self.homes.get(home_id).map(|h| h.history.iter().cloned().collect()).unwrap_or_default(),
+        }
+    }
+
+    /// Take the threshold from an onboarding config configured at `configured_at`,
+    /// keeping the learned modifier. A config already applied, as when saved
+    /// configs are re-applied at startup, records nothing.
+    pub fn onboard(&self, home_id: &str, base_logit: f64, configured_at: DateTime<Utc>, now: DateTime<Utc>) -> Result<Option<ThresholdVersion>, ThresholdError> {
+        let applied = self.homes.get(home_id)
+            .is_some_and(|h| h.history.iter().any(|v| v.source == ThresholdSource::Onboarding && v.at >= configured_at));
+        if applied {
+            return Ok(None);
+        }
+        let modifier = self.current(home_id).modifier;
+        self.push(home_id, base_logit, modifier, ThresholdSource::Onboarding, None, "sensitivity profile chosen at onboarding", now).map(Some)
+    }
+
+    /// Learn from a labeled incident: a benign alert raises the threshold a
+    /// step, a threat that never alerted lowers it. None when nothing moved.
+    pub fn learn(&self, home_id: &str, alerted: bool, label: IncidentLabel, now: DateTime<Utc>) -> Result<Option<ThresholdVersion>, ThresholdError> {
+        let (delta, note) = match (alerted, label) {
+            (true, IncidentLabel::Benign) => (self.config.step, "alert labeled benign"),
+            (false, IncidentLabel::Threat) => (-self.config.step, "threat labeled without an alert"),
+            _ => return Ok(None),
+        };
+        let current = self.current(home_id);
+        let modifier = (current.modifier + delta).clamp(-self.config.max_modifier, self.config.max_modifier);
+        if (modifier - current.modifier).abs() < f64::EPSILON {
+            return Ok(None);
+        }
+        self.push(home_id, current.base_logit, modifier, ThresholdSource::Learned, None, note, now).map(Some)
+    }
+
+    /// Back to the engine default with no learned modifier
+    pub fn reset(&self, home_id: &str, actor: &str, now: DateTime<Utc>) -> Result<ThresholdVersion, ThresholdError> {
+        self.push(home_id, self.default_logit, 0.0, ThresholdSource::Reset, Some(actor), "reset to defaults", now)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &self,
+        home_id: &str,
+        base_logit: f64,
+        modifier: f64,
+        source: ThresholdSource,
+        actor: Option<&str>,
+        note: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ThresholdVersion, ThresholdError> {
+        let (version, snapshot) = {
+            let mut home = self.homes.entry(home_id.to_string())
+                .or_insert_with(|| HomeThresholds { format: FORMAT_VERSION, home_id: home_id.to_string(), ..HomeThresholds::default() });
+            let version = ThresholdVersion {
+                version: home.history.back().map_or(1, |v| v.version + 1),
+                base_logit,
+                modifier,
+                effective_logit: base_logit + modifier,
+                source,
+                actor: actor.map(str::to_string),
+                note: note.to_string(),
+                at: now,
+            };
+            home.format = FORMAT_VERSION;
+            home.history.push_back(version.clone());
+            while home.history.len() > self.config.history_len {
+                home.history.pop_front();
+            }
+            (version, self.dir.is_some().then(|| home.clone()))
+        };
+        if let Some(home) = snapshot {
+            self.persist(&home)?;
+        }
+        Ok(version)
+    }
+
+    fn persist(&self, home: &HomeThresholds) -> Result<(), ThresholdError> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        home_files::write_json(dir, &home.home_id, &serde_json::to_vec_pretty(home)?)?;
+        Ok(())
+    }
+}
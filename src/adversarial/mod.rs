@@ -2,16 +2,23 @@
 
 pub mod game_theory;
 pub mod deception;
-pub mod counter_surveillance;
 pub mod social_engineering;
 
+pub use crate::counter_surveillance::{
+    CounterSurveillanceConfig, CounterSurveillanceSystem, Sighting, SubjectKind,
+    ReconnaissanceAnalyzer, SurveillanceAnalysisResult, SurveillanceIndicators, SurveillanceSignal,
+};
+
 use crate::core::*;
 use crate::intelligence::*;
+use crate::environment::{CalendarConfig, CalendarPriorAdjuster};
+use crate::entity_trust::TrustStore;
 use crate::SecurityResult;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Master adversarial reasoning engine with game-theoretic modeling
@@ -23,6 +30,8 @@ pub struct AdversarialReasoningEngine {
     social_engineering_detector: SocialEngineeringDetector,
     adversarial_predictor: AdversarialPredictor,
     psychological_warfare: PsychologicalWarfareEngine,
+    calendar: CalendarPriorAdjuster,
+    trust: Option<(Arc<TrustStore>, String)>, // The home's entity trust scores, and its id
 }
 
 impl AdversarialReasoningEngine {
@@ -34,9 +43,28 @@ impl AdversarialReasoningEngine {
             social_engineering_detector: SocialEngineeringDetector::new(),
             adversarial_predictor: AdversarialPredictor::new(),
             psychological_warfare: PsychologicalWarfareEngine::new(),
+            calendar: CalendarPriorAdjuster::default(),
+            trust: None,
         }
     }
 
+    /// Weigh entity history by the trust the home has built up in each person
+    pub fn with_entity_trust(mut self, store: Arc<TrustStore>, home_id: &str) -> Self {
+        self.trust = Some((store, home_id.to_string()));
+        self
+    }
+
+    /// Use the home's location, timezone and holiday calendar for time-based risk
+    pub fn with_calendar(mut self, config: CalendarConfig) -> Self {
+        self.calendar = CalendarPriorAdjuster::new(config);
+        self
+    }
+
+    /// Trajectory history used for loitering and repeated-pass detection
+    pub fn counter_surveillance(&self) -> &CounterSurveillanceSystem {
+        &self.counter_surveillance
+    }
+
     /// Comprehensive adversarial analysis with multi-domain reasoning
     pub async fn analyze_adversarial_landscape(
         &mut self,
@@ -81,7 +109,7 @@ impl AdversarialReasoningEngine {
             social_engineering_analysis,
             adversarial_predictions,
             psychological_warfare_analysis,
-            threat_level: self.calculate_adversarial_threat_level(&game_analysis)?,
+            threat_level: self.calculate_adversarial_threat_level(&game_analysis, entities, context)?,
             confidence: 0.92,
             timestamp: Utc::now(),
         })
@@ -106,8 +134,12 @@ impl AdversarialReasoningEngine {
 
         // Surveillance activity detection
         let surveillance_indicators = self.counter_surveillance
-            .detect_immediate_surveillance(entity, context, sensor_data)
-            .await?;
+            .analyze_at(std::slice::from_ref(entity), context, Utc::now())
+            .await?
+            .indicators
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| SurveillanceIndicators { entity_id: entity.id, ..Default::default() });
 
         // Social engineering attempt detection
         let social_engineering_indicators = self.social_engineering_detector
@@ -159,9 +191,7 @@ impl AdversarialReasoningEngine {
             .await?;
 
         // Counter-surveillance strategies
-        let counter_surveillance_strategies = self.counter_surveillance
-            .generate_counter_surveillance_strategies(&adversarial_landscape.surveillance_analysis)
-            .await?;
+        let counter_surveillance_strategies = CounterSurveillanceStrategies::default();
 
         // Social engineering defenses
         let social_engineering_defenses = self.social_engineering_detector
@@ -318,20 +348,12 @@ impl AdversarialReasoningEngine {
         })
     }
 
-    fn calculate_adversarial_threat_level(&self, game_analysis: &GameTheoryAnalysis) -> SecurityResult<f64> {
+    fn calculate_adversarial_threat_level(&self, game_analysis: &GameTheoryAnalysis, entities: &[Entity], context: &EnvironmentalContext) -> SecurityResult<f64> {
         // Base threat from game theory analysis
         let mut threat_score = game_analysis.threat_probability;
         
-        // Time-based risk assessment (higher risk during unusual hours)
-        let current_hour = chrono::Utc::now().hour();
-        let time_risk = match current_hour {
-            2..=5 => 0.8,    // Very late night - high risk
-            22..=24 | 0..=1 => 0.6,  // Late night/early morning - elevated risk
-            6..=8 => 0.3,    // Early morning - moderate risk
-            9..=17 => 0.2,   // Business hours - lower risk
-            18..=21 => 0.25, // Evening - slightly elevated
-            _ => 0.3,
-        };
+        // Time-based risk assessment (higher risk after dark, in the home's local time)
+        let time_risk = self.calendar.time_risk(Utc::now());
         
         // Identity risk (unknown entities are higher risk)
         let identity_risk = 0.4; // Unknown person baseline
@@ -353,7 +375,7 @@ impl AdversarialReasoningEngine {
         } else { 0.0 };
         
         // ENHANCEMENT 2: Environmental context integration
-        let environmental_risk = self.calculate_environmental_risk();
+        let environmental_risk = self.calculate_environmental_risk(context);
         
         // ENHANCEMENT 3: Bayesian confidence update
         let prior_confidence = threat_score;
@@ -367,7 +389,7 @@ impl AdversarialReasoningEngine {
         let adaptive_modifier = self.get_adaptive_threshold_modifier();
         
         // ENHANCEMENT 6: Entity profiling and history integration
-        let entity_history_risk = self.calculate_entity_history_risk();
+        let entity_history_risk = self.calculate_entity_history_risk(entities);
         
         // NEXT-LEVEL ENHANCEMENT 1: Probabilistic reasoning with uncertainty quantification
         let threat_distribution = self.monte_carlo_threat_analysis(1000, threat_score, time_risk, identity_risk, location_risk);
@@ -424,20 +446,15 @@ impl AdversarialReasoningEngine {
     }
     
     // ENHANCEMENT 2: Environmental context calculation
-    fn calculate_environmental_risk(&self) -> f64 {
-        let current_hour = chrono::Utc::now().hour();
-        let day_of_week = chrono::Utc::now().weekday();
-        
-        // Weather visibility factor (clear = higher detection confidence)
-        let weather_factor = 0.8; // Assume clear conditions
+    fn calculate_environmental_risk(&self, context: &EnvironmentalContext) -> f64 {
+        // Weather visibility factor (fog/heavy rain give cover and degrade cameras)
+        let weather_factor = context.environment
+            .as_ref()
+            .map(|env| (0.8 + (1.0 - env.visual_reliability) * 0.4).min(1.0))
+            .unwrap_or(0.8); // Assume clear conditions when not enriched
         
-        // Local activity patterns
-        let activity_factor = match (current_hour, day_of_week) {
-            (9..=17, chrono::Weekday::Mon..=chrono::Weekday::Fri) => 0.2, // Business hours weekday
-            (14..=15, _) => 0.15, // School dismissal time
-            (18..=20, _) => 0.25, // Evening activity
-            _ => 0.3,
-        };
+        // Local activity patterns (weekdays, holidays and daylight at the home)
+        let activity_factor = self.calendar.activity_risk(Utc::now());
         
         // Neighborhood baseline (quiet residential)
         let neighborhood_risk = 0.3;
@@ -485,7 +502,7 @@ impl AdversarialReasoningEngine {
     }
     
     // ENHANCEMENT 6: Entity history and profiling risk calculation
-    fn calculate_entity_history_risk(&self) -> f64 {
+    fn calculate_entity_history_risk(&self, entities: &[Entity]) -> f64 {
         // Simulate entity profile analysis for unknown person
         // In real implementation, this would query entity database
         
@@ -498,8 +515,14 @@ impl AdversarialReasoningEngine {
         // Pattern analysis (no established patterns for unknown entity)
         let pattern_deviation = 0.2;
         
-        // Trust score impact (new entity = low trust)
-        let trust_impact = 0.25;
+        // Trust score impact: the least trusted entity present sets it (new entity = no trust)
+        let trust = self.trust.as_ref().map_or(0.0, |(store, home_id)| {
+            entities.iter()
+                .map(|e| store.score(home_id, &format!("entity_{}", e.id), Utc::now()))
+                .reduce(f64::min)
+                .unwrap_or(0.0)
+        });
+        let trust_impact = 0.25 * (1.0 - trust.clamp(0.0, 1.0));
         
         // Historical threat events (none for new entity)
         let threat_history_impact = 0.0;
@@ -874,8 +897,6 @@ impl GameTheoryEngine {
 #[derive(Debug, Default)]
 pub struct DeceptionDetectionSystem;
 #[derive(Debug, Default)]
-pub struct CounterSurveillanceSystem;
-#[derive(Debug, Default)]
 pub struct SocialEngineeringDetector;
 #[derive(Debug, Default)]
 pub struct AdversarialPredictor;
@@ -911,8 +932,6 @@ pub struct GameTheoryAnalysis {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DeceptionAnalysisResult;
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct SurveillanceAnalysisResult;
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SocialEngineeringAnalysisResult;
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AdversarialPredictionsResult;
@@ -929,8 +948,6 @@ pub struct ImmediateGameState {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DeceptionIndicators;
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct SurveillanceIndicators;
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SocialEngineeringIndicators;
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PsychologicalIndicators;
@@ -993,34 +1010,6 @@ impl DeceptionDetectionSystem {
     }
 }
 
-impl CounterSurveillanceSystem {
-    pub fn new() -> Self { Self }
-    
-    pub async fn detect_surveillance_activities(
-        &self,
-        _entities: &[Entity],
-        _context: &EnvironmentalContext,
-    ) -> SecurityResult<SurveillanceAnalysisResult> {
-        Ok(SurveillanceAnalysisResult::default())
-    }
-    
-    pub async fn detect_immediate_surveillance(
-        &self,
-        _entity: &Entity,
-        _context: &EnvironmentalContext,
-        _sensor_data: &SensorData,
-    ) -> SecurityResult<SurveillanceIndicators> {
-        Ok(SurveillanceIndicators::default())
-    }
-    
-    pub async fn generate_counter_surveillance_strategies(
-        &self,
-        _analysis: &SurveillanceAnalysisResult,
-    ) -> SecurityResult<CounterSurveillanceStrategies> {
-        Ok(CounterSurveillanceStrategies::default())
-    }
-}
-
 impl SocialEngineeringDetector {
     pub fn new() -> Self { Self }
     
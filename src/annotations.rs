@@ -0,0 +1,337 @@
+// src/annotations.rs
+
+// Notes and tags homeowners attach to incidents: "that was my brother", "same
+// van as last week", #family, #suspicious. Each annotation is tied to the
+// incident and to the person the incident is about (its re-identified
+// entity), so what was said about someone follows them to later incidents.
+// Annotations are searchable by text, tag and entity.
+//
+// Some tags carry meaning beyond the note. Trust tags (family, friend,
+// resident, trusted, known) pin the entity as trusted in the trust store; watch
+// tags (suspicious, watch, watchlist) put it on the home's watchlist, where its
+// detections carry positive identity evidence instead. The two are exclusive:
+// tagging someone trusted takes them off the watchlist. Earlier notes on an
+// entity are appended to the narrative of any later incident about it.
+//
+// With a data directory, each home's annotations and watchlist are written to
+// one JSON file so they survive restarts.
+
+use crate::home_files;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const TRUST_TAGS: [&str; 5] = ["family", "friend", "resident", "trusted", "known"];
+const WATCH_TAGS: [&str; 3] = ["suspicious", "watch", "watchlist"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum AnnotationError {
+    #[error("An annotation needs a note or at least one tag")]
+    Empty,
+
+    #[error("Invalid annotation: {0}")]
+    Invalid(String),
+
+    #[error("Unknown annotation {0}")]
+    NotFound(String),
+
+    #[error("Failed to persist annotations: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to encode annotations: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct AnnotationConfig {
+    pub max_note_len: usize,       // Characters
+    pub max_tags: usize,
+    pub watchlist_identity_llr: f64, // Identity evidence for a detection of a watchlisted entity
+    pub narrative_notes: usize,    // Most recent notes quoted in a later narrative
+}
+
+impl Default for AnnotationConfig {
+    fn default() -> Self {
+        Self {
+            max_note_len: 2000,
+            max_tags: 16,
+            watchlist_identity_llr: 1.0,
+            narrative_notes: 3,
+        }
+    }
+}
+
+/// What a user sends to annotate an incident
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct AnnotationRequest {
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncidentAnnotation {
+    pub id: Uuid,
+    pub incident_id: u64,
+    pub entity_id: Option<String>, // Person the incident is about, when it has one
+    pub author: String,
+    pub note: Option<String>,
+    pub tags: Vec<String>, // Lowercase, without '#'
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagEffect {
+    Trust, // Pin the entity as trusted
+    Watch, // Put the entity on the watchlist
+}
+
+/// What a tag does to the annotated entity, if anything
+pub fn tag_effect(tag: &str) -> Option<TagEffect> {
+    if TRUST_TAGS.contains(&tag) {
+        Some(TagEffect::Trust)
+    } else if WATCH_TAGS.contains(&tag) {
+        Some(TagEffect::Watch)
+    } else {
+        None
+    }
+}
+
+impl IncidentAnnotation {
+    /// Whether the annotation's tags trust or watch its entity; trust wins if both appear
+    pub fn effect(&self) -> Option<TagEffect> {
+        let effects: Vec<TagEffect> = self.tags.iter().filter_map(|t| tag_effect(t)).collect();
+        [TagEffect::Trust, TagEffect::Watch].into_iter().find(|e| effects.contains(e))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchlistEntry {
+    pub entity_id: String,
+    pub incident_id: u64, // Incident whose annotation put the entity on the list
+    pub reason: Option<String>,
+    pub added_by: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Filters for an annotation search; all given filters must match
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnnotationQuery {
+    pub q: Option<String>, // Case-insensitive text in the note or a tag
+    pub tag: Option<String>,
+    pub entity_id: Option<String>,
+    pub incident_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HomeAnnotations {
+    home_id: String,
+    annotations: Vec<IncidentAnnotation>,
+    watchlist: BTreeMap<String, WatchlistEntry>,
+}
+
+#[derive(Debug, Default)]
+pub struct AnnotationStore {
+    config: AnnotationConfig,
+    homes: DashMap<String, HomeAnnotations>,
+    dir: Option<PathBuf>,
+}
+
+impl AnnotationStore {
+    pub fn new(config: AnnotationConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// Store backed by `dir`, loading any annotations already there
+    pub fn persistent(config: AnnotationConfig, dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let homes = DashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path).map(|text| serde_json::from_str::<HomeAnnotations>(&text)) {
+                Ok(Ok(home)) => {
+                    homes.insert(home.home_id.clone(), home);
+                }
+                Ok(Err(e)) => tracing::warn!("Skipping unreadable annotations {}: {}", path.display(), e),
+                Err(e) => tracing::warn!("Skipping annotations {}: {}", path.display(), e),
+            }
+        }
+        Ok(Self { config, homes, dir: Some(dir) })
+    }
+
+    pub fn config(&self) -> &AnnotationConfig {
+        &self.config
+    }
+
+    /// Attach a note and tags to an incident; watch and trust tags update the watchlist
+    pub fn annotate(
+        &self,
+        home_id: &str,
+        incident_id: u64,
+        entity_id: Option<&str>,
+        author: &str,
+        request: AnnotationRequest,
+        now: DateTime<Utc>,
+    ) -> Result<IncidentAnnotation, AnnotationError> {
+        let note = request.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        if note.as_ref().is_some_and(|n| n.chars().count() > self.config.max_note_len) {
+            return Err(AnnotationError::Invalid(format!("note is longer than {} characters", self.config.max_note_len)));
+        }
+        let mut tags: Vec<String> = Vec::new();
+        for tag in request.tags.iter().map(|t| t.trim().trim_start_matches('#').to_lowercase()).filter(|t| !t.is_empty()) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if tags.len() > self.config.max_tags {
+            return Err(AnnotationError::Invalid(format!("more than {} tags", self.config.max_tags)));
+        }
+        if note.is_none() && tags.is_empty() {
+            return Err(AnnotationError::Empty);
+        }
+
+        let annotation = IncidentAnnotation {
+            id: Uuid::new_v4(),
+            incident_id,
+            entity_id: entity_id.map(str::to_string),
+            author: author.to_string(),
+            note,
+            tags,
+            created_at: now,
+        };
+        let snapshot = {
+            let mut home = self.homes.entry(home_id.to_string())
+                .or_insert_with(|| HomeAnnotations { home_id: home_id.to_string(), ..HomeAnnotations::default() });
+            if let Some(entity) = &annotation.entity_id {
+                match annotation.effect() {
+                    Some(TagEffect::Watch) => {
+                        home.watchlist.insert(entity.clone(), WatchlistEntry {
+                            entity_id: entity.clone(),
+                            incident_id,
+                            reason: annotation.note.clone(),
+                            added_by: author.to_string(),
+                            added_at: now,
+                        });
+                    }
+                    Some(TagEffect::Trust) => {
+                        home.watchlist.remove(entity);
+                    }
+                    None => {}
+                }
+            }
+            home.annotations.push(annotation.clone());
+            self.dir.is_some().then(|| home.clone())
+        };
+        if let Some(home) = snapshot {
+            self.persist(&home)?;
+        }
+        Ok(annotation)
+    }
+
+    /// Delete one annotation; watchlist entries it created stay until removed
+    pub fn remove(&self, home_id: &str, annotation_id: Uuid) -> Result<IncidentAnnotation, AnnotationError> {
+        let (removed, snapshot) = {
+            let mut home = self.homes.get_mut(home_id).ok_or_else(|| AnnotationError::NotFound(annotation_id.to_string()))?;
+            let index = home.annotations.iter().position(|a| a.id == annotation_id)
+                .ok_or_else(|| AnnotationError::NotFound(annotation_id.to_string()))?;
+            let removed = home.annotations.remove(index);
+            (removed, self.dir.is_some().then(|| home.clone()))
+        };
+        if let Some(home) = snapshot {
+            self.persist(&home)?;
+        }
+        Ok(removed)
+    }
+
+    /// Annotations matching every filter in the query, newest first
+    pub fn search(&self, home_id: &str, query: &AnnotationQuery) -> Vec<IncidentAnnotation> {
+        let Some(home) = self.homes.get(home_id) else {
+            return Vec::new();
+        };
+        let text = query.q.as_ref().map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+        let tag = query.tag.as_ref().map(|t| t.trim().trim_start_matches('#').to_lowercase());
+        home.annotations.iter().rev()
+            .filter(|a| query.incident_id.map_or(true, |id| a.incident_id == id))
+            .filter(|a| query.entity_id.is_none() || a.entity_id == query.entity_id)
+            .filter(|a| tag.as_ref().map_or(true, |t| a.tags.contains(t)))
+            .filter(|a| text.as_ref().map_or(true, |q| {
+                a.note.as_ref().is_some_and(|n| n.to_lowercase().contains(q.as_str())) || a.tags.iter().any(|t| t.contains(q.as_str()))
+            }))
+            .cloned()
+            .collect()
+    }
+
+    pub fn for_incident(&self, home_id: &str, incident_id: u64) -> Vec<IncidentAnnotation> {
+        self.search(home_id, &AnnotationQuery { incident_id: Some(incident_id), ..AnnotationQuery::default() })
+    }
+
+    /// The home's watchlist, most recently added first
+    pub fn watchlist(&self, home_id: &str) -> Vec<WatchlistEntry> {
+        let mut entries: Vec<WatchlistEntry> = self.homes.get(home_id)
+            .map(|h| h.watchlist.values().cloned().collect())
+            .unwrap_or_default();
+        entries.sort_by(|a, b| b.added_at.cmp(&a.added_at).then_with(|| a.entity_id.cmp(&b.entity_id)));
+        entries
+    }
+
+    pub fn unwatch(&self, home_id: &str, entity_id: &str) -> Result<WatchlistEntry, AnnotationError> {
+        let (removed, snapshot) = {
+            let mut home = self.homes.get_mut(home_id).ok_or_else(|| AnnotationError::NotFound(entity_id.to_string()))?;
+            let removed = home.watchlist.remove(entity_id).ok_or_else(|| AnnotationError::NotFound(entity_id.to_string()))?;
+            (removed, self.dir.is_some().then(|| home.clone()))
+        };
+        if let Some(home) = snapshot {
+            self.persist(&home)?;
+        }
+        Ok(removed)
+    }
+
+    /// Identity evidence for a detection of this entity, if it is on the watchlist
+    pub fn watchlist_llr(&self, home_id: &str, entity_id: &str) -> Option<f64> {
+        self.homes.get(home_id)
+            .filter(|h| h.watchlist.contains_key(entity_id))
+            .map(|_| self.config.watchlist_identity_llr)
+    }
+
+    /// One sentence quoting what the household has said about an entity, for a narrative
+    pub fn entity_context(&self, home_id: &str, entity_id: &str) -> Option<String> {
+        let annotations = self.search(home_id, &AnnotationQuery { entity_id: Some(entity_id.to_string()), ..AnnotationQuery::default() });
+        if annotations.is_empty() {
+            return None;
+        }
+        let notes: Vec<String> = annotations.iter()
+            .filter_map(|a| a.note.as_ref().map(|n| format!("\"{}\" (incident {})", n, a.incident_id)))
+            .take(self.config.narrative_notes)
+            .collect();
+        let tags: BTreeSet<&str> = annotations.iter().flat_map(|a| a.tags.iter().map(String::as_str)).collect();
+        let mut context = String::from("Household notes on this person:");
+        if !notes.is_empty() {
+            context.push_str(&format!(" {}", notes.join("; ")));
+        }
+        if !tags.is_empty() {
+            let tags: Vec<&str> = tags.into_iter().collect();
+            context.push_str(&format!("{} tagged {}", if notes.is_empty() { "" } else { ";" }, tags.join(", ")));
+        }
+        if self.watchlist_llr(home_id, entity_id).is_some() {
+            context.push_str("; on the watchlist");
+        }
+        context.push('.');
+        Some(context)
+    }
+
+    fn persist(&self, home: &HomeAnnotations) -> Result<(), AnnotationError> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        home_files::write_json(dir, &home.home_id, &serde_json::to_vec_pretty(home)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+// src/anti_spoofing.rs
+
+// Face anti-spoofing. Re-identification matches appearance, so a printed photo
+// or a phone screen held up to the doorbell can pass for a trusted resident or
+// an expected guest and earn their benign identity evidence. A liveness score
+// (the probability that the face is a live person rather than a presentation
+// attack) arrives with the VPS response or from a local liveness model. When
+// spoofing is suspected the negative identity LLR a face match earned is
+// scaled back, all the way to nothing once spoofing is near certain; identity
+// evidence that points toward a threat is left alone. Every suspicion is
+// called out in the incident narrative and the assessment's explanation.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpoofConfig {
+    pub suspect_above: f64,    // Spoof likelihood (1 - liveness) from which a face match is doubted
+    pub full_discount_at: f64, // Spoof likelihood at which the face match counts for nothing
+}
+
+impl Default for SpoofConfig {
+    fn default() -> Self {
+        Self { suspect_above: 0.5, full_discount_at: 0.9 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LivenessSource {
+    Vps,
+    Edge, // Local liveness model
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpoofAssessment {
+    pub source: LivenessSource,
+    pub liveness: f64,
+    pub spoof_likelihood: f64,
+    pub discount: f64,       // Share of the face match's benign evidence removed, 0.0 to 1.0
+    pub identity_llr_before: f64,
+    pub identity_llr_after: f64,
+}
+
+impl SpoofAssessment {
+    /// Sentence for the incident narrative and explanation
+    pub fn narrative(&self) -> String {
+        let effect = if (self.identity_llr_after - self.identity_llr_before).abs() > 1e-9 {
+            format!(", so the face match counts for {:.0}% less", self.discount * 100.0)
+        } else {
+            String::new()
+        };
+        format!(
+            "Possible spoofed face: liveness {:.2} suggests a photo or screen rather than a live person{}.",
+            self.liveness, effect,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpoofDetector {
+    config: SpoofConfig,
+}
+
+impl SpoofDetector {
+    pub fn new(config: SpoofConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &SpoofConfig {
+        &self.config
+    }
+
+    /// Doubt a face match given its liveness score; None when the face looks live.
+    /// `face_identity_llr` is the identity evidence earned by matching the face.
+    pub fn assess(&self, source: LivenessSource, liveness: f64, face_identity_llr: f64) -> Option<SpoofAssessment> {
+        if !liveness.is_finite() {
+            return None;
+        }
+        let liveness = liveness.clamp(0.0, 1.0);
+        let spoof_likelihood = 1.0 - liveness;
+        if spoof_likelihood < self.config.suspect_above {
+            return None;
+        }
+        let span = (self.config.full_discount_at - self.config.suspect_above).max(f64::EPSILON);
+        let discount = ((spoof_likelihood - self.config.suspect_above) / span).clamp(0.0, 1.0);
+        let identity_llr_after = if face_identity_llr < 0.0 {
+            face_identity_llr * (1.0 - discount)
+        } else {
+            face_identity_llr
+        };
+        Some(SpoofAssessment {
+            source,
+            liveness,
+            spoof_likelihood,
+            discount,
+            identity_llr_before: face_identity_llr,
+            identity_llr_after,
+        })
+    }
+}
@@ -0,0 +1,522 @@
+//! Administration API
+//!
+//! Operator endpoints behind the `admin` scope, used by `novictl`: list the
+//! homes the pipeline knows, force a morning summary, replay captured events,
+//! inspect and flush undeliverable webhooks, rotate webhook signing secrets,
+//! dump an incident's timeline and read VPS metrics, LLM usage, question
+//! value calibration, the watchdog's self-health alerts and the model report
+//! card scoring quoted probabilities against outcomes, and throw the LLM kill
+//! switch for one home or the whole deployment.
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::delivery::{DeadLetter, WebhookDeliveryRecord};
+use crate::overnight::MorningSummary;
+use crate::prediction::ReportCard;
+use crate::pipeline::{RawEvent, SubscriptionTier};
+use crate::thinking::{AlertDecision, ChannelSaturation, Incident, KillSwitch, LlmGuardReport, LlmUsageReport, QuestionValue};
+use crate::vps_client::{VpsCacheStats, VpsEndpointStatus};
+use crate::watchdog::HealthAlert;
+
+#[derive(Debug, Serialize)]
+pub struct HomeSummary {
+    pub home_id: String,
+    pub incidents: usize,
+    pub open_incidents: usize,
+    pub last_activity: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SystemMetrics {
+    pub vps_endpoints: Vec<VpsEndpointStatus>,
+    pub vps_cache: VpsCacheStats,
+    pub evidence_saturation: Vec<ChannelSaturation>, // Per channel, how often fusion clamped it at a cap
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayRequest {
+    pub events: Vec<RawEvent>,
+    #[serde(default = "default_replay_tier")]
+    pub tier: SubscriptionTier,
+    #[serde(default)]
+    pub api_key: String,
+}
+
+fn default_replay_tier() -> SubscriptionTier {
+    SubscriptionTier::Premium
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplayResult {
+    pub event_id: Uuid,
+    pub status: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DeadLetterQuery {
+    pub home_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct HealthAlertQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LlmKillSwitchRequest {
+    #[serde(default)]
+    pub home_id: Option<String>, // None switches the LLM path for every home
+    pub enabled: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LlmKillSwitchResult {
+    pub home_id: Option<String>,
+    pub enabled: bool,
+    pub changed: bool,
+    pub switch: Option<KillSwitch>, // The switch now in place when disabling
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotatedSecret {
+    pub endpoint_id: Uuid,
+    pub secret: String, // Shown once; receivers must be updated before the next delivery
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimelineEntry {
+    Event { ts: f64, camera: String, fused_llr: f64, dwell_s: f64 },
+    Assessment { ts: f64, event_count: usize, probability: f64, decision: AlertDecision },
+}
+
+impl TimelineEntry {
+    fn ts(&self) -> f64 {
+        match self {
+            TimelineEntry::Event { ts, .. } | TimelineEntry::Assessment { ts, .. } => *ts,
+        }
+    }
+}
+
+/// Events and assessments of an incident in time order; assessments follow
+/// the event that triggered them
+pub fn incident_timeline(incident: &Incident) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = incident.events.iter()
+        .map(|e| TimelineEntry::Event { ts: e.ts, camera: e.cam.clone(), fused_llr: e.evidence.sum(), dwell_s: e.dwell_s })
+        .chain(incident.probability_trace.iter().map(|p| TimelineEntry::Assessment {
+            ts: p.ts,
+            event_count: p.event_count,
+            probability: p.calibrated_probability,
+            decision: p.decision.clone(),
+        }))
+        .collect();
+    entries.sort_by(|a, b| a.ts().total_cmp(&b.ts()).then_with(|| matches!(a, TimelineEntry::Assessment { .. }).cmp(&matches!(b, TimelineEntry::Assessment { .. }))));
+    entries
+}
+
+/// Homes the pipeline has incidents for
+#[utoipa::path(
+    get,
+    path = "/api/admin/homes",
+    tag = "admin",
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_homes(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<ResponseJson<ApiResponse<Vec<HomeSummary>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let pipeline = state.pipeline.read().await;
+    let homes = pipeline.homes().into_iter().map(|home_id| {
+        let incidents = pipeline.home_incidents(&home_id);
+        HomeSummary {
+            incidents: incidents.len(),
+            open_incidents: incidents.iter().filter(|i| i.status.is_active()).count(),
+            last_activity: incidents.iter().map(|i| i.last_updated).reduce(f64::max),
+            home_id,
+        }
+    }).collect();
+    Ok(ResponseJson(ApiResponse::success(homes)))
+}
+
+/// Build and send a home's morning summary now instead of at its scheduled time
+#[utoipa::path(
+    post,
+    path = "/api/admin/homes/{home_id}/morning-summary",
+    tag = "admin",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No overnight events to summarize"),
+        (status = 503, description = "Overnight review is not enabled"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn force_morning_summary(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<MorningSummary>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let pipeline = state.pipeline.read().await;
+    let summary = pipeline.generate_morning_summary(&home_id).await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    state.notification_router.dispatch_summary(&summary).await;
+    let thumbnails = pipeline.summary_thumbnails(&summary).await;
+    state.notification_router.email_summary(&summary, &thumbnails).await;
+    if let Err(e) = pipeline.mark_morning_summary_delivered(&summary).await {
+        tracing::warn!("Forced summary for {} sent but not marked delivered: {}", home_id, e);
+    }
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+/// Run captured events through the pipeline again, in order
+#[utoipa::path(
+    post,
+    path = "/api/admin/events/replay",
+    tag = "admin",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn replay_events(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(request): Json<ReplayRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReplayResult>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let mut pipeline = state.pipeline.write().await;
+    let mut results = Vec::with_capacity(request.events.len());
+    for event in request.events {
+        let event_id = event.event_id;
+        let result = match pipeline.process_event(event, request.tier.clone(), &request.api_key).await {
+            Ok(processed) => ReplayResult { event_id, status: processed.status, detail: processed.result_summary },
+            Err(e) => ReplayResult { event_id, status: "error".to_string(), detail: e.to_string() },
+        };
+        results.push(result);
+    }
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+/// Webhook deliveries that exhausted their retries
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhook-dead-letters",
+    tag = "admin",
+    params(("home_id" = Option<String>, Query, description = "Only this home")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_dead_letters(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DeadLetter>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    Ok(ResponseJson(ApiResponse::success(state.webhook_dispatcher.dead_letters(query.home_id.as_deref()).await)))
+}
+
+/// Redeliver dead-lettered webhooks; ones that fail again are re-queued
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhook-dead-letters/flush",
+    tag = "admin",
+    params(("home_id" = Option<String>, Query, description = "Only this home")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn flush_dead_letters(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDeliveryRecord>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    Ok(ResponseJson(ApiResponse::success(state.webhook_dispatcher.flush_dead_letters(query.home_id.as_deref()).await)))
+}
+
+/// Replace a webhook endpoint's signing secret
+#[utoipa::path(
+    post,
+    path = "/api/admin/homes/{home_id}/webhooks/{endpoint_id}/rotate-secret",
+    tag = "admin",
+    params(("home_id" = String, Path, description = "Home id"), ("endpoint_id" = Uuid, Path, description = "Webhook endpoint id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown endpoint"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn rotate_webhook_secret(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, endpoint_id)): Path<(String, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<RotatedSecret>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let secret = state.webhook_dispatcher.rotate_secret(&home_id, endpoint_id).await.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(RotatedSecret { endpoint_id, secret })))
+}
+
+/// An incident's events and assessments in time order
+#[utoipa::path(
+    get,
+    path = "/api/admin/homes/{home_id}/incidents/{incident_id}/timeline",
+    tag = "admin",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn incident_timeline_handler(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+) -> Result<ResponseJson<ApiResponse<Vec<TimelineEntry>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let pipeline = state.pipeline.read().await;
+    let incident = pipeline.find_incident(&home_id, incident_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(incident_timeline(&incident))))
+}
+
+/// VPS endpoint health, response cache hit/miss counts and evidence channel saturation
+#[utoipa::path(
+    get,
+    path = "/api/admin/metrics",
+    tag = "admin",
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn metrics(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<ResponseJson<ApiResponse<SystemMetrics>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let pipeline = state.pipeline.read().await;
+    Ok(ResponseJson(ApiResponse::success(SystemMetrics {
+        vps_endpoints: pipeline.get_vps_endpoint_status(),
+        vps_cache: pipeline.get_vps_cache_stats(),
+        evidence_saturation: pipeline.get_evidence_saturation(),
+    })))
+}
+
+/// Today's LLM calls and tokens against the daily budgets, with deferred narratives
+#[utoipa::path(
+    get,
+    path = "/api/admin/llm-usage",
+    tag = "admin",
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn llm_usage(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<ResponseJson<ApiResponse<LlmUsageReport>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    Ok(ResponseJson(ApiResponse::success(state.llm_budget.report(chrono::Utc::now()))))
+}
+
+/// LLM kill switches in place and guardrail counters: filtered inputs, redactions and rejected responses
+#[utoipa::path(
+    get,
+    path = "/api/admin/llm-guard",
+    tag = "admin",
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn llm_guard(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<ResponseJson<ApiResponse<LlmGuardReport>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    Ok(ResponseJson(ApiResponse::success(state.llm_guard.report())))
+}
+
+/// Turn LLM narratives off or back on for one home, or for every home when no home is given
+#[utoipa::path(
+    post,
+    path = "/api/admin/llm-guard/kill-switch",
+    tag = "admin",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Empty home id"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn llm_kill_switch(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(request): Json<LlmKillSwitchRequest>,
+) -> Result<ResponseJson<ApiResponse<LlmKillSwitchResult>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let home_id = request.home_id.as_deref().map(str::trim);
+    if home_id == Some("") {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let (changed, switch) = if request.enabled {
+        (state.llm_guard.enable(home_id), None)
+    } else {
+        let reason = request.reason.unwrap_or_else(|| format!("disabled by {}", user.user_id));
+        (true, Some(state.llm_guard.disable(home_id, &reason, chrono::Utc::now())))
+    };
+    tracing::info!("LLM path {} for {} by {}", if request.enabled { "enabled" } else { "disabled" }, home_id.unwrap_or("every home"), user.user_id);
+    Ok(ResponseJson(ApiResponse::success(LlmKillSwitchResult { home_id: home_id.map(str::to_string), enabled: request.enabled, changed, switch })))
+}
+
+/// Recent watchdog alerts: stuck incidents, stalled queue lanes, missed job ticks and clock jumps
+#[utoipa::path(
+    get,
+    path = "/api/admin/health-alerts",
+    tag = "admin",
+    params(("limit" = Option<usize>, Query, description = "Most recent alerts to return, default 50")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn health_alerts(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<HealthAlertQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<HealthAlert>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    Ok(ResponseJson(ApiResponse::success(state.admin_alerts.recent(query.limit.unwrap_or(50)))))
+}
+
+/// Per question type: how often answers changed decisions, and the resulting ranking factor
+#[utoipa::path(
+    get,
+    path = "/api/admin/question-value",
+    tag = "admin",
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn question_value(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<ResponseJson<ApiResponse<Vec<QuestionValue>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    Ok(ResponseJson(ApiResponse::success(state.pipeline.read().await.question_value())))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportCardQuery {
+    #[serde(default)]
+    pub home_id: Option<String>, // Every home when absent
+    #[serde(default)]
+    pub days: Option<i64>,       // Window ending now, default 7
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportCardsQuery {
+    pub limit: Option<usize>,
+}
+
+/// Brier score, skill and calibration per model component over predictions settled in the last days
+#[utoipa::path(
+    get,
+    path = "/api/admin/report-card",
+    tag = "admin",
+    params(
+        ("home_id" = Option<String>, Query, description = "Limit to one home"),
+        ("days" = Option<i64>, Query, description = "Days to cover, default 7"),
+    ),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Days out of range"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn report_card(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ReportCardQuery>,
+) -> Result<ResponseJson<ApiResponse<ReportCard>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let days = query.days.unwrap_or(7);
+    if !(1..=365).contains(&days) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let now = chrono::Utc::now();
+    let card = state.prediction_scoring.report_card(query.home_id.as_deref(), now - chrono::Duration::days(days), now, now);
+    Ok(ResponseJson(ApiResponse::success(card)))
+}
+
+/// Report cards closed at the end of each period, newest first
+#[utoipa::path(
+    get,
+    path = "/api/admin/report-cards",
+    tag = "admin",
+    params(("limit" = Option<usize>, Query, description = "Cards to return, default 12")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn report_cards(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ReportCardsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReportCard>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    Ok(ResponseJson(ApiResponse::success(state.prediction_scoring.report_cards(query.limit.unwrap_or(12)))))
+}
@@ -0,0 +1,211 @@
+//! Home Analytics API
+//!
+//! Read-only views over offline analysis jobs, starting with modus operandi
+//! clusters (repeat visitors grouped by behavior across weeks), the mix of
+//! threat vectors in a home's history, and what-if replays of stored
+//! incidents for threshold tuning. The one write is a
+//! reset of a home's learned alert threshold back to the default.
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use std::collections::{BTreeMap, HashMap};
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::validation::Validate;
+use crate::adaptive_thresholds::{ThresholdDrift, ThresholdVersion};
+use crate::core::ThreatVector;
+use super::incidents::outcome_status;
+use crate::thinking::{ChannelWeights, IncidentLabel, MoCluster, SensorReliability, ThinkingAIConfig, WhatIfReport};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ClusterQuery {
+    #[serde(default)]
+    pub suspicious_only: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WhatIfRequest {
+    pub candidate: ThinkingAIConfig, // Omitted fields keep their defaults
+    #[serde(default)]
+    pub labels: HashMap<u64, IncidentLabel>, // Feedback per incident id
+}
+
+/// Modus operandi clusters from the last clustering run, most recent first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/analytics/mo-clusters",
+    tag = "analytics",
+    params(("home_id" = String, Path, description = "Home id"), ClusterQuery),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_mo_clusters(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<ClusterQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<MoCluster>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+
+    let mut clusters = state.mo_clusters.clusters(&home_id).await;
+    if query.suspicious_only {
+        clusters.retain(|c| c.suspicious);
+    }
+    clusters.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    Ok(ResponseJson(ApiResponse::success(clusters)))
+}
+
+/// Incidents in the clustering history per threat vector, keyed by the stable vector names
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/analytics/threat-vectors",
+    tag = "analytics",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn threat_vectors(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<BTreeMap<ThreatVector, usize>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.mo_clusters.threat_vector_counts(&home_id).await)))
+}
+
+/// Alerts a candidate config would have raised on the home's stored incidents, versus the current config
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/analytics/what-if",
+    tag = "analytics",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid candidate config"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn what_if_thresholds(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<WhatIfRequest>,
+) -> Result<ResponseJson<ApiResponse<WhatIfReport>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if request.candidate.validate().is_err() {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    let report = state.pipeline.read().await.what_if(&home_id, &request.candidate, &request.labels);
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// LLR channel weights currently learned for a home
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/analytics/channel-weights",
+    tag = "analytics",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn channel_weights(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<ChannelWeights>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let weights = state.pipeline.read().await.channel_weights(&home_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(weights)))
+}
+
+/// Reliability learned for each of a home's sensors from labeled outcomes
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/analytics/sensor-reliability",
+    tag = "analytics",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn sensor_reliability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<SensorReliability>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let report = state.pipeline.read().await.sensor_reliability(&home_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// How a home's alert threshold drifted from the default, with every version
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/analytics/thresholds",
+    tag = "analytics",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn threshold_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<ThresholdDrift>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let drift = state.pipeline.read().await.threshold_drift(&home_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(drift)))
+}
+
+/// Reset a home's alert threshold to the default, dropping learned modifiers
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/analytics/thresholds/reset",
+    tag = "analytics",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 503, description = "Adaptive thresholds not enabled"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn reset_thresholds(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<ThresholdVersion>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let version = state.pipeline.read().await.reset_thresholds(&home_id, &user.user_id).map_err(outcome_status)?;
+    Ok(ResponseJson(ApiResponse::success(version)))
+}
@@ -1,17 +1,129 @@
 use axum::{
     extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
+    http::{header, request::Parts, StatusCode},
     async_trait,
 };
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-// Placeholder auth user struct
+// Who the caller is acting as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Homeowner,
+    Operator,   // Professional monitoring center staff
+    Supervisor, // Monitoring center lead, can reassign and override
+    Billing,    // Hosted-offering billing service account
+    Admin,      // Operators of the service itself, e.g. via novictl
+}
+
+// Fine-grained permissions checked by handlers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename = "home:manage")]
+    HomeManage,
+    #[serde(rename = "monitoring:read")]
+    MonitoringRead,
+    #[serde(rename = "monitoring:claim")]
+    MonitoringClaim,
+    #[serde(rename = "monitoring:notes")]
+    MonitoringNotes,
+    #[serde(rename = "monitoring:dispatch")]
+    MonitoringDispatch,
+    #[serde(rename = "monitoring:override")]
+    MonitoringOverride,
+    #[serde(rename = "billing:read")]
+    BillingRead,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl Role {
+    pub fn default_scopes(&self) -> HashSet<Scope> {
+        let scopes: &[Scope] = match self {
+            Role::Homeowner => &[Scope::HomeManage],
+            Role::Operator => &[
+                Scope::MonitoringRead,
+                Scope::MonitoringClaim,
+                Scope::MonitoringNotes,
+                Scope::MonitoringDispatch,
+            ],
+            Role::Supervisor => &[
+                Scope::MonitoringRead,
+                Scope::MonitoringClaim,
+                Scope::MonitoringNotes,
+                Scope::MonitoringDispatch,
+                Scope::MonitoringOverride,
+            ],
+            Role::Billing => &[Scope::BillingRead],
+            Role::Admin => &[Scope::Admin, Scope::HomeManage, Scope::MonitoringRead],
+        };
+        scopes.iter().copied().collect()
+    }
+}
+
+// Credentials of a process connected over the local UNIX socket, checked by
+// the kernel; requests that arrive over TCP never carry this extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalPeer {
+    pub uid: u32,
+    pub gid: u32,
+    pub pid: Option<i32>,
+}
+
+// Authenticated caller
 #[derive(Debug, Clone)]
 pub struct AuthUser {
     pub user_id: String,
     pub username: String,
+    pub role: Role,
+    pub scopes: HashSet<Scope>,
+    pub homes: Option<HashSet<String>>, // Homes the caller may act on; None is every home
+}
+
+impl AuthUser {
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Reject the request with 403 unless the caller holds `scope`
+    pub fn require(&self, scope: Scope) -> Result<(), StatusCode> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+
+    pub fn can_access_home(&self, home_id: &str) -> bool {
+        self.homes.as_ref().map_or(true, |homes| homes.contains(home_id))
+    }
+
+    /// Reject the request with 403 unless the caller holds `scope` for `home_id`
+    pub fn require_home(&self, scope: Scope, home_id: &str) -> Result<(), StatusCode> {
+        self.require(scope)?;
+        if self.can_access_home(home_id) {
+            Ok(())
+        } else {
+            Err(StatusCode::FORBIDDEN)
+        }
+    }
+}
+
+// JWT claims issued to monitoring center staff and apps
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    username: Option<String>,
+    role: Role,
+    #[serde(default)]
+    scopes: Option<HashSet<Scope>>, // Overrides the role defaults when present
+    #[serde(default)]
+    homes: Option<HashSet<String>>, // Homes the token is for; homeowner tokens without it reach none
 }
 
-// Simple placeholder authentication (no JWT for now)
 #[async_trait]
 impl<S> FromRequestParts<S> for AuthUser
 where
@@ -19,12 +131,42 @@ where
 {
     type Rejection = StatusCode;
 
-    async fn from_request_parts(_parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        // For now, just accept any request as an authenticated admin user
-        // TODO: Implement proper JWT validation
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(peer) = parts.extensions.get::<LocalPeer>() {
+            // Admitted by the socket server on its peer credentials, no token needed
+            return Ok(AuthUser {
+                user_id: format!("local:{}", peer.uid),
+                username: format!("local uid {}", peer.uid),
+                role: Role::Homeowner,
+                scopes: Role::Homeowner.default_scopes(),
+                homes: None,
+            });
+        }
+
+        // Every TCP request needs a bearer token; a missing or malformed header is 401
+        let token = parts.headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        let secret = std::env::var("JWT_SECRET").map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let claims = decode::<Claims>(token, &DecodingKey::from_secret(secret.as_bytes()), &Validation::new(Algorithm::HS256))
+            .map_err(|_| StatusCode::UNAUTHORIZED)?
+            .claims;
+
         Ok(AuthUser {
-            user_id: "admin".to_string(),
-            username: "admin".to_string(),
+            username: claims.username.unwrap_or_else(|| claims.sub.clone()),
+            user_id: claims.sub,
+            scopes: claims.scopes.unwrap_or_else(|| claims.role.default_scopes()),
+            homes: match claims.homes {
+                Some(homes) => Some(homes),
+                None if claims.role == Role::Homeowner => Some(HashSet::new()),
+                None => None,
+            },
+            role: claims.role,
         })
     }
 }
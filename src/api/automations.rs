@@ -0,0 +1,193 @@
+//! Automations API
+//!
+//! Declare, list and remove a home's automations, preview what they would do
+//! for a hypothetical decision, read the execution history, and acknowledge an
+//! incident so its pending "unless acknowledged" actions are dropped.
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::automations::{Automation, AutomationError, AutomationRequest, ExecutionRecord, PlannedAction, TriggerContext};
+use crate::thinking::AlertDecision;
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewRequest {
+    pub decision: AlertDecision,
+    pub zone: String,
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>, // Defaults to now
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    50
+}
+
+fn automation_status(err: AutomationError) -> StatusCode {
+    match err {
+        AutomationError::NotFound(_) => StatusCode::NOT_FOUND,
+        AutomationError::TooMany(_) => StatusCode::CONFLICT,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/automations",
+    tag = "automations",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_automations(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<Automation>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.automations.list(&home_id))))
+}
+
+/// Declare an automation: a trigger on alert decisions, zones and local time, and the device actions it runs
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/automations",
+    tag = "automations",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Missing name, decisions or actions, bad delay, window or timezone"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 409, description = "Home already has the maximum number of automations"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn create_automation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<AutomationRequest>,
+) -> Result<ResponseJson<ApiResponse<Automation>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let automation = state.automations.create(&home_id, request, Utc::now()).map_err(automation_status)?;
+    Ok(ResponseJson(ApiResponse::success(automation)))
+}
+
+/// Remove an automation along with any of its actions still waiting to run
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/automations/{automation_id}",
+    tag = "automations",
+    params(("home_id" = String, Path, description = "Home id"), ("automation_id" = Uuid, Path, description = "Automation id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown automation"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_automation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, automation_id)): Path<(String, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<Automation>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let removed = state.automations.remove(&home_id, automation_id).map_err(automation_status)?;
+    Ok(ResponseJson(ApiResponse::success(removed)))
+}
+
+/// Dry run: the actions the home's automations would take for a decision in a zone, without running them
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/automations/preview",
+    tag = "automations",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn preview_automations(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<PreviewRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<PlannedAction>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let context = TriggerContext {
+        home_id,
+        incident_id: 0,
+        decision: request.decision,
+        zone: request.zone,
+        at: request.at.unwrap_or_else(Utc::now),
+    };
+    Ok(ResponseJson(ApiResponse::success(state.automations.preview(&context))))
+}
+
+/// Executed, failed and cancelled automation actions, newest first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/automations/history",
+    tag = "automations",
+    params(("home_id" = String, Path, description = "Home id"), ("limit" = Option<usize>, Query, description = "Records to return, default 50")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn automation_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionRecord>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.automations.history(&home_id, query.limit))))
+}
+
+/// Acknowledge an incident so its pending "unless acknowledged" actions, like a delayed siren, do not run
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/automations/acknowledge",
+    tag = "automations",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn acknowledge_automations(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionRecord>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let cancelled = state.automations.acknowledge(&home_id, incident_id, &user.user_id, Utc::now());
+    Ok(ResponseJson(ApiResponse::success(cancelled)))
+}
@@ -0,0 +1,138 @@
+//! Billing Usage Export API
+//!
+//! Daily billable-unit rollups per account for the hosted offering's billing
+//! service. JSON by default, CSV with `?format=csv`.
+//!
+//! Also per-account API usage over hourly or daily windows: integrators read
+//! their own, the billing service any account's. Requests are counted by the
+//! `meter_api_calls` middleware wrapped around every route.
+use axum::{
+    extract::{FromRequestParts, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::metering::{export_csv, ApiUsageReport, UsageWindow};
+
+const MAX_EXPORT_DAYS: i64 = 366;
+const MAX_HOURLY_DAYS: i64 = 31;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UsageQuery {
+    pub from: Option<NaiveDate>, // Defaults to 30 days ago
+    pub to: Option<NaiveDate>,   // Defaults to today (UTC)
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub format: Option<String>,  // "json" (default) or "csv"
+}
+
+/// Export daily usage rollups
+#[utoipa::path(
+    get,
+    path = "/api/billing/usage",
+    tag = "billing",
+    params(UsageQuery),
+    responses(
+        (status = 200, description = "Daily rollups as JSON, or CSV with format=csv", body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid date range or format"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn export_usage(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<UsageQuery>,
+) -> Result<Response, StatusCode> {
+    user.require(Scope::BillingRead)?;
+
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(30));
+    if from > to || (to - from).num_days() > MAX_EXPORT_DAYS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let rows = state.usage_meter.rollups(query.account_id.as_deref(), from, to);
+
+    match query.format.as_deref() {
+        None | Some("json") => Ok(ResponseJson(ApiResponse::success(rows)).into_response()),
+        Some("csv") => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"usage_{}_{}.csv\"", from, to)),
+            ],
+            export_csv(&rows),
+        ).into_response()),
+        Some(_) => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ApiUsageQuery {
+    pub from: Option<DateTime<Utc>>, // Defaults to 24 hours (hourly) or 30 days (daily) before `to`
+    pub to: Option<DateTime<Utc>>,   // Defaults to now
+    #[serde(default)]
+    pub window: UsageWindow,         // "hour" (default) or "day"
+    pub account_id: Option<String>,  // Another account; requires billing:read
+}
+
+/// API requests, errors, rate-limit rejections and image bytes fetched, per time window
+#[utoipa::path(
+    get,
+    path = "/api/usage",
+    tag = "billing",
+    params(ApiUsageQuery),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Another account's usage without billing:read"),
+        (status = 400, description = "Invalid time range"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn api_usage(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<ApiUsageQuery>,
+) -> Result<ResponseJson<ApiResponse<ApiUsageReport>>, StatusCode> {
+    let account_id = match query.account_id {
+        Some(account_id) if account_id != user.user_id => {
+            user.require(Scope::BillingRead)?;
+            account_id
+        }
+        _ => user.user_id.clone(),
+    };
+
+    let to = query.to.unwrap_or_else(Utc::now);
+    let (default_span, max_days) = match query.window {
+        UsageWindow::Hour => (Duration::hours(24), MAX_HOURLY_DAYS),
+        UsageWindow::Day => (Duration::days(30), MAX_EXPORT_DAYS),
+    };
+    let from = query.from.unwrap_or(to - default_span);
+    if from >= to || (to - from).num_days() > max_days {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(state.usage_meter.api_usage(&account_id, from, to, query.window))))
+}
+
+/// Count each authenticated request against the caller's account, by response status
+pub async fn meter_api_calls(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let account_id = AuthUser::from_request_parts(&mut parts, &state).await.ok().map(|u| u.user_id);
+    let response = next.run(Request::from_parts(parts, body)).await;
+    if let Some(account_id) = account_id {
+        state.usage_meter.record_api_call(&account_id, response.status().as_u16(), Utc::now());
+    }
+    response
+}
@@ -0,0 +1,270 @@
+//! Camera Pin API
+//!
+//! Homeowners (or the installer app) pin the hosts and certificate
+//! fingerprints each camera's snapshots are served from, so forged events
+//! cannot point analysis at an injected image. Periodic snapshots can also be
+//! checked against each camera's usual view to catch blinding, covering and
+//! moved cameras. Registering a camera issues it its own ingest token and
+//! starts tracking when it was last heard from.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::camera_health::{CameraHealth, CameraHealthError, CameraRegistrationRequest, IssuedCamera, RegisteredCamera};
+use crate::camera_registry::{CameraPin, PinError};
+use crate::tamper::{TamperAlert, TamperCheck, TamperError};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CameraPinRequest {
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub cert_sha256: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ClearTamperRequest {
+    #[serde(default)]
+    pub rebaseline: bool, // Take the next snapshot as the new usual view, for a camera moved on purpose
+}
+
+fn status_for(err: PinError) -> StatusCode {
+    match err {
+        PinError::InvalidFingerprint(_) | PinError::NoHosts(_) | PinError::InvalidUrl(_) => StatusCode::BAD_REQUEST,
+        PinError::UnexpectedHost { .. } | PinError::InsecureScheme(_) => StatusCode::FORBIDDEN,
+    }
+}
+
+/// Pinned cameras for a home
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/cameras",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_pins(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<CameraPin>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.cameras.home_pins(&home_id))))
+}
+
+/// Set the hosts and certificates a camera's snapshots may come from
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/pin",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera (sensor) id")),
+    request_body = CameraPinRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "No hosts or malformed fingerprint"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_pin(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+    Json(request): Json<CameraPinRequest>,
+) -> Result<ResponseJson<ApiResponse<CameraPin>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let pin = CameraPin { camera_id, home_id, hosts: request.hosts, cert_sha256: request.cert_sha256 };
+    state.cameras.register(pin.clone()).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(pin)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/pin",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera (sensor) id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Camera is not pinned"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn remove_pin(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<CameraPin>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let pin = state.cameras.remove(&home_id, &camera_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(pin)))
+}
+
+/// Open tamper alerts for a home's cameras
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/cameras/tamper",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn tamper_alerts(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<TamperAlert>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.tamper.alerts(&home_id))))
+}
+
+/// Compare a periodic reference snapshot (raw image body) with the camera's usual view
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/tamper-check",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera (sensor) id")),
+    request_body(content = Vec<u8>, content_type = "image/jpeg"),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 422, description = "Body is not a decodable image"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn check_tamper(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<TamperCheck>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let check = state.pipeline.read().await
+        .check_camera_tamper(&home_id, &camera_id, &user.user_id, &body, Utc::now())
+        .map_err(|e| match e {
+            TamperError::Decode(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+    Ok(ResponseJson(ApiResponse::success(check)))
+}
+
+/// Clear a camera's tamper alert and restore its evidence reliability
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/tamper/clear",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera (sensor) id")),
+    request_body = ClearTamperRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Camera has no open tamper alert"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn clear_tamper(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+    Json(request): Json<ClearTamperRequest>,
+) -> Result<ResponseJson<ApiResponse<TamperAlert>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let alert = state.tamper.clear(&home_id, &camera_id, request.rebaseline).map_err(|_| StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(alert)))
+}
+
+/// Register a camera and issue its ingest token; registering again rotates the token
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/registration",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera (sensor) id")),
+    request_body = CameraRegistrationRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Non-positive silence window or frame rate"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 409, description = "Camera is registered to another home"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn register_camera(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+    Json(request): Json<CameraRegistrationRequest>,
+) -> Result<ResponseJson<ApiResponse<IssuedCamera>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let issued = state.camera_health.register(&home_id, &camera_id, request, Utc::now()).map_err(|e| match e {
+        CameraHealthError::OtherHome(_) => StatusCode::CONFLICT,
+        _ => StatusCode::BAD_REQUEST,
+    })?;
+    Ok(ResponseJson(ApiResponse::success(issued)))
+}
+
+/// Unregister a camera; its ingest token stops working
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/registration",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera (sensor) id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Camera is not registered to this home"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn unregister_camera(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<RegisteredCamera>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let camera = state.camera_health.remove(&home_id, &camera_id).map_err(|_| StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(camera)))
+}
+
+/// Liveness of a home's registered cameras, silent and degraded ones first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/cameras/health",
+    tag = "cameras",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn camera_health(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<CameraHealth>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.camera_health.health(&home_id, Utc::now()))))
+}
@@ -0,0 +1,153 @@
+//! Central station API
+//!
+//! Point a home at its professional monitoring center: receiver address,
+//! account, camera-to-zone mapping and the Contact ID codes sent for each
+//! severity. Read the link's supervision status and message history, and send
+//! a manual test report to check the station receives it.
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use serde::Deserialize;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::delivery::{CentralStation, CentralStationError, CentralStationRequest, StationRecord, StationView};
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    50
+}
+
+fn station_status(err: CentralStationError) -> StatusCode {
+    match err {
+        CentralStationError::NotConfigured(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+/// The home's central station with message counts and supervision status
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/central-station",
+    tag = "central_station",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No central station configured"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_station(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<StationView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let view = state.central_station.get(&home_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(view)))
+}
+
+/// Set or replace the home's central station: receiver, account, zones, event codes and heartbeat
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/central-station",
+    tag = "central_station",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Bad account, prefix, zone, partition, event code or minimum decision"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_station(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<CentralStationRequest>,
+) -> Result<ResponseJson<ApiResponse<CentralStation>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let station = state.central_station.configure(&home_id, request, state.clock.now()).map_err(station_status)?;
+    Ok(ResponseJson(ApiResponse::success(station)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/central-station",
+    tag = "central_station",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No central station configured"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_station(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<CentralStation>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let removed = state.central_station.remove(&home_id).map_err(station_status)?;
+    Ok(ResponseJson(ApiResponse::success(removed)))
+}
+
+/// Alarms, restores, heartbeats and tests sent to the station, newest first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/central-station/history",
+    tag = "central_station",
+    params(("home_id" = String, Path, description = "Home id"), ("limit" = Option<usize>, Query, description = "Records to return, default 50")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn station_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<StationRecord>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.central_station.history(&home_id, query.limit))))
+}
+
+/// Send a Contact ID manual test report (601); the result says whether the receiver acknowledged it
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/central-station/test",
+    tag = "central_station",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No central station configured"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn test_station(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<StationRecord>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let record = state.central_station.test(&home_id, state.clock.now()).await.map_err(station_status)?;
+    Ok(ResponseJson(ApiResponse::success(record)))
+}
@@ -0,0 +1,104 @@
+//! Edge Device Enrollment API
+//!
+//! Edge devices enroll an Ed25519 public key for their home and sign every
+//! event they send with the matching private key. Revoking a device makes
+//! its signatures fail, e.g. after a hub is lost or replaced.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::device_signing::{DeviceKey, SigningError};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EnrollDeviceRequest {
+    pub device_id: String,
+    pub public_key: String, // Base64 of the 32-byte Ed25519 public key
+}
+
+fn status_for(err: SigningError) -> StatusCode {
+    match err {
+        SigningError::InvalidKey(_) => StatusCode::BAD_REQUEST,
+        SigningError::HomeMismatch { .. } => StatusCode::CONFLICT,
+        SigningError::UnknownDevice(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::UNPROCESSABLE_ENTITY,
+    }
+}
+
+/// Enrolled devices for a home, including revoked ones
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/devices",
+    tag = "devices",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_devices(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<DeviceKey>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.devices.devices(&home_id))))
+}
+
+/// Enroll a device's public key, or rotate the key of an enrolled device
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/devices",
+    tag = "devices",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = EnrollDeviceRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Malformed public key"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 409, description = "Device is enrolled by another home"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn enroll_device(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<EnrollDeviceRequest>,
+) -> Result<ResponseJson<ApiResponse<DeviceKey>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let key = state.devices.enroll(&home_id, &request.device_id, &request.public_key, Utc::now()).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(key)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/devices/{device_id}",
+    tag = "devices",
+    params(("home_id" = String, Path, description = "Home id"), ("device_id" = String, Path, description = "Device id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Device is not enrolled for this home"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn revoke_device(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, device_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<DeviceKey>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let key = state.devices.revoke(&home_id, &device_id, Utc::now()).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(key)))
+}
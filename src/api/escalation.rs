@@ -0,0 +1,113 @@
+//! Escalation chain API
+//!
+//! Homeowners set who is alerted, in what order and after what delay, when a
+//! rule sends an incident straight to Critical (an interior detection while
+//! everyone is away, for example). Any recipient can acknowledge to stop the
+//! remaining steps.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::escalation_rules::{ActiveEscalation, EscalationChain};
+
+/// The home's escalation chain; homes without one alert the incident's account once
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/escalation-chain",
+    tag = "escalation",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_chain(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<EscalationChain>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.escalation.chain(&home_id))))
+}
+
+/// Replace the home's escalation chain; steps are ordered by their delay
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/escalation-chain",
+    tag = "escalation",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Empty chain, negative delay or step without channels"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_chain(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(chain): Json<EscalationChain>,
+) -> Result<ResponseJson<ApiResponse<EscalationChain>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if chain.steps.is_empty() || chain.steps.iter().any(|s| s.after_secs < 0 || s.channels.is_empty()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    state.escalation.set_chain(&home_id, chain);
+    Ok(ResponseJson(ApiResponse::success(state.escalation.chain(&home_id))))
+}
+
+/// Escalations currently running for a home
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/escalations",
+    tag = "escalation",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn active_escalations(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<ActiveEscalation>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.escalation.active(&home_id))))
+}
+
+/// Acknowledge an incident's escalation so no further steps are sent
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/escalation/acknowledge",
+    tag = "escalation",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No escalation running for the incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn acknowledge_escalation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+) -> Result<ResponseJson<ApiResponse<ActiveEscalation>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    state.automations.acknowledge(&home_id, incident_id, &user.user_id, chrono::Utc::now());
+    let escalation = state.escalation.acknowledge(&home_id, incident_id, &user.user_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(escalation)))
+}
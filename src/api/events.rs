@@ -1,26 +1,61 @@
 //! Event Ingestion API
 use axum::{
-    extract::{Json, Path},
+    extract::{Extension, Path, State},
     response::{Result, Json as ResponseJson},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
 };
-use crate::pipeline::{RawEvent, ProcessedEvent, SubscriptionTier, EventPipeline, PipelineConfig};
-use crate::vps_client::VpsApiClient;
+use super::auth::LocalPeer;
+use super::local_socket::LocalSocketConfig;
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::camera_health::RegisteredCamera;
+use crate::pipeline::{RawEvent, ProcessedEvent, SubscriptionTier};
+use crate::device_signing::EventSignature;
+use crate::ingest::EventPayload;
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::Arc;
 use uuid::Uuid;
 use chrono::Utc;
 
+/// A submitted event, borrowing its strings from the request body
 #[derive(Debug, Deserialize)]
-pub struct EventSubmission {
-    pub sensor_id: String,
-    pub data: String,
-    pub user_id: String,
-    pub home_id: String,
-    pub api_key: String,
+pub struct EventSubmission<'a> {
+    pub event_id: Option<Uuid>, // Camera-assigned id; retries must reuse it for dedup
+    #[serde(borrow)]
+    pub sensor_id: Cow<'a, str>,
+    #[serde(borrow)]
+    pub data: Cow<'a, str>,
+    #[serde(borrow)]
+    pub user_id: Cow<'a, str>,
+    #[serde(borrow)]
+    pub home_id: Cow<'a, str>,
+    #[serde(borrow, default)]
+    pub image_url: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub api_key: Cow<'a, str>,
     pub subscription_tier: SubscriptionTier,
+    pub signature: Option<EventSignature>, // From enrolled edge devices
 }
 
-#[derive(Debug, Serialize)]
+/// An event from a registered camera; home, account and sensor come from its registration
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraEventSubmission<'a> {
+    pub event_id: Option<Uuid>, // Retries must reuse it for dedup
+    pub timestamp: Option<i64>, // Capture time; defaults to receipt
+    #[serde(borrow)]
+    pub data: Cow<'a, str>,
+    #[serde(borrow, default)]
+    pub image_url: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    pub api_key: Cow<'a, str>, // Required over HTTP; local socket clients may leave it to the server's configured key
+}
+
+/// Header carrying a camera's ingest token
+pub const CAMERA_TOKEN_HEADER: &str = "x-camera-token";
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct EventResponse {
     pub event_id: Uuid,
     pub status: String,
@@ -28,39 +63,42 @@ pub struct EventResponse {
     pub processed_at: i64,
 }
 
-/// Submit an event for processing through the full AI pipeline
-pub async fn submit_event(Json(submission): Json<EventSubmission>) -> Result<ResponseJson<EventResponse>, StatusCode> {
-    let event_id = Uuid::new_v4();
+/// Submit an event for processing through the full AI pipeline. The body is
+/// read as raw bytes so the sensor payload is never copied out of it. Events
+/// go through the shared pipeline, so a retried submission with the same
+/// event id replays the stored result instead of being processed twice.
+pub async fn submit_event(State(state): State<AppState>, body: Bytes) -> Result<ResponseJson<EventResponse>, StatusCode> {
+    let submission: EventSubmission<'_> = serde_json::from_slice(&body).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let event_id = submission.event_id.unwrap_or_else(Uuid::new_v4);
     
     // Create raw event
     let raw_event = RawEvent {
         event_id,
-        sensor_id: submission.sensor_id,
+        sensor_id: submission.sensor_id.into_owned(),
         timestamp: Utc::now().timestamp(),
-        data: submission.data,
-        user_id: submission.user_id,
-        home_id: submission.home_id,
+        data: EventPayload::from_field(&body, submission.data),
+        user_id: submission.user_id.into_owned(),
+        home_id: submission.home_id.into_owned(),
+        image_url: submission.image_url.map(Cow::into_owned),
+        image_data: None,
     };
 
-    // Initialize pipeline
-    let vps_client = VpsApiClient::new("https://api.vps.example.com".to_string());
-    let config = PipelineConfig::default();
-    let mut pipeline = EventPipeline::new(config, vps_client);
-
     // Process through full AI pipeline
-    match pipeline.process_event(raw_event, submission.subscription_tier, &submission.api_key).await {
+    let result = state.pipeline.write().await
+        .process_signed_event(raw_event, submission.signature.as_ref(), submission.subscription_tier, &submission.api_key).await;
+    match result {
         Ok(processed_event) => {
-            println!("✅ Event {} processed successfully: {}", event_id, processed_event.result_summary);
+            tracing::info!("Event {} processed: {}", event_id, processed_event.result_summary);
             
             Ok(ResponseJson(EventResponse {
                 event_id,
                 status: "processed".to_string(),
                 message: format!("Event processed through AI pipeline: {}", processed_event.result_summary),
-                processed_at: Utc::now().timestamp(),
+                processed_at: processed_event.processing_timestamp,
             }))
         }
         Err(e) => {
-            eprintln!("❌ Event processing error: {}", e);
+            tracing::warn!("Event {} failed: {}", event_id, e);
             Ok(ResponseJson(EventResponse {
                 event_id,
                 status: "error".to_string(),
@@ -71,6 +109,96 @@ pub async fn submit_event(Json(submission): Json<EventSubmission>) -> Result<Res
     }
 }
 
+/// Submit an event from a registered camera, authenticated by its own ingest token
+#[utoipa::path(
+    post,
+    path = "/api/ingest/cameras/{camera_id}/events",
+    tag = "cameras",
+    params(
+        ("camera_id" = String, Path, description = "Camera (sensor) id"),
+        ("x-camera-token" = String, Header, description = "The camera's ingest token"),
+    ),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Malformed event"),
+        (status = 401, description = "Unknown camera or wrong ingest token"),
+        (status = 500, description = "Processing failed"),
+    ),
+)]
+pub async fn ingest_camera_event(
+    State(state): State<AppState>,
+    Path(camera_id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<EventResponse>>, StatusCode> {
+    let token = headers.get(CAMERA_TOKEN_HEADER).and_then(|v| v.to_str().ok()).ok_or(StatusCode::UNAUTHORIZED)?;
+    let camera = state.camera_health.authenticate(&camera_id, token).map_err(|e| {
+        tracing::warn!("Rejected camera ingest: {}", e);
+        StatusCode::UNAUTHORIZED
+    })?;
+    let submission: CameraEventSubmission<'_> = serde_json::from_slice(&body).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if submission.api_key.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    process_camera_submission(&state, camera, submission, &body).await
+}
+
+/// Submit an event from a camera process on the same machine over the local
+/// UNIX socket; the kernel-checked peer credentials stand in for the ingest token
+pub async fn ingest_local_camera_event(
+    State(state): State<AppState>,
+    Extension(peer): Extension<LocalPeer>,
+    Extension(local): Extension<Arc<LocalSocketConfig>>,
+    Path(camera_id): Path<String>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<EventResponse>>, StatusCode> {
+    let camera = state.camera_health.get(&camera_id).ok_or_else(|| {
+        tracing::warn!("Rejected local ingest from uid {}: unknown camera {}", peer.uid, camera_id);
+        StatusCode::NOT_FOUND
+    })?;
+    let mut submission: CameraEventSubmission<'_> = serde_json::from_slice(&body).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if submission.api_key.is_empty() {
+        let Some(key) = local.vps_api_key.as_deref() else {
+            return Err(StatusCode::BAD_REQUEST);
+        };
+        submission.api_key = Cow::Owned(key.to_string());
+    }
+    process_camera_submission(&state, camera, submission, &body).await
+}
+
+async fn process_camera_submission(
+    state: &AppState,
+    camera: RegisteredCamera,
+    submission: CameraEventSubmission<'_>,
+    body: &Bytes,
+) -> Result<ResponseJson<ApiResponse<EventResponse>>, StatusCode> {
+    let camera_id = camera.camera_id.clone();
+    let event_id = submission.event_id.unwrap_or_else(Uuid::new_v4);
+    let raw_event = RawEvent {
+        event_id,
+        sensor_id: camera.camera_id,
+        timestamp: submission.timestamp.unwrap_or_else(|| Utc::now().timestamp()),
+        data: EventPayload::from_field(body, submission.data),
+        user_id: camera.user_id,
+        home_id: camera.home_id,
+        image_url: submission.image_url.map(Cow::into_owned),
+        image_data: None,
+    };
+    let processed = state.pipeline.write().await
+        .process_event(raw_event, camera.tier, &submission.api_key).await
+        .map_err(|e| {
+            tracing::warn!("Camera {} event {} failed: {}", camera_id, event_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(ResponseJson(ApiResponse::success(EventResponse {
+        event_id,
+        status: processed.status,
+        message: processed.result_summary,
+        processed_at: processed.processing_timestamp,
+    })))
+}
+
 /// Get recent events for a home
 pub async fn get_events(Path(_home_id): Path<String>) -> Result<ResponseJson<Vec<ProcessedEvent>>, StatusCode> {
     Ok(ResponseJson(vec![]))
@@ -0,0 +1,96 @@
+//! Guest Access API
+//!
+//! Homeowners set up time-boxed guest profiles (the babysitter on Friday
+//! evenings, builders for a week) with expected windows, enrolled faces and an
+//! optional access code, list the ones still running and end them early.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use uuid::Uuid;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::guest_access::{CreatedGuest, GuestError, GuestProfile, GuestProfileRequest};
+
+fn status_for(err: GuestError) -> StatusCode {
+    match err {
+        GuestError::NotFound(_) => StatusCode::NOT_FOUND,
+        GuestError::Disabled | GuestError::Unsupported(_) => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+/// Create a guest profile; an access code, if requested, is only returned here
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/guests",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Invalid window, timezone or face samples"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn create_guest(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<GuestProfileRequest>,
+) -> Result<ResponseJson<ApiResponse<CreatedGuest>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let created = state.pipeline.read().await.create_guest(&home_id, request).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(created)))
+}
+
+/// Guest profiles that have not expired, soonest to expire first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/guests",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_guests(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<GuestProfile>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.guests.list(&home_id, Utc::now()))))
+}
+
+/// End a guest profile early; its access code is revoked too
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/guests/{guest_id}",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id"), ("guest_id" = Uuid, Path, description = "Guest profile id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown guest profile"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn remove_guest(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, guest_id)): Path<(String, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<GuestProfile>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let removed = state.guests.remove(&home_id, guest_id, Some(&state.visitor_tokens)).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(removed)))
+}
@@ -0,0 +1,188 @@
+//! Household API
+//!
+//! Residents of a home with their roles, delivery channels and alert
+//! thresholds, presence updates from phones or geofences, and the aggregated
+//! dwelling state.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::household::{DwellingState, HouseholdError, Presence, Resident, ResidentRequest};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PresenceUpdate {
+    pub presence: Presence,
+}
+
+fn status_for(err: HouseholdError) -> StatusCode {
+    match err {
+        HouseholdError::NotFound(_) => StatusCode::NOT_FOUND,
+        HouseholdError::Duplicate(_) | HouseholdError::LastOwner => StatusCode::CONFLICT,
+    }
+}
+
+// Residents' names must never reach the LLM service
+fn sync_llm_redactions(state: &AppState, home_id: &str) {
+    state.llm_guard.set_redaction_terms(home_id, state.household.residents(home_id).into_iter().map(|r| r.name));
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/residents",
+    tag = "household",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = ResidentListResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_residents(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<Resident>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.household.residents(&home_id))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/residents",
+    tag = "household",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = ResidentRequest,
+    responses(
+        (status = 200, body = ResidentResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 409, description = "Already a resident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn add_resident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<ResidentRequest>,
+) -> Result<ResponseJson<ApiResponse<Resident>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if request.user_id.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let resident = state.household.add_resident(&home_id, request).map_err(status_for)?;
+    sync_llm_redactions(&state, &home_id);
+    Ok(ResponseJson(ApiResponse::success(resident)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/residents/{user_id}",
+    tag = "household",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    request_body = ResidentRequest,
+    responses(
+        (status = 200, body = ResidentResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown resident"),
+        (status = 409, description = "Would remove the last owner"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn update_resident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+    Json(request): Json<ResidentRequest>,
+) -> Result<ResponseJson<ApiResponse<Resident>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if request.user_id != user_id {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let resident = state.household.update_resident(&home_id, request).map_err(status_for)?;
+    sync_llm_redactions(&state, &home_id);
+    Ok(ResponseJson(ApiResponse::success(resident)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/residents/{user_id}",
+    tag = "household",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    responses(
+        (status = 200, body = ResidentResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown resident"),
+        (status = 409, description = "Would remove the last owner"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn remove_resident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<Resident>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let resident = state.household.remove_resident(&home_id, &user_id).map_err(status_for)?;
+    sync_llm_redactions(&state, &home_id);
+    Ok(ResponseJson(ApiResponse::success(resident)))
+}
+
+/// Report a resident arriving or leaving
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/residents/{user_id}/presence",
+    tag = "household",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    request_body = PresenceUpdate,
+    responses(
+        (status = 200, body = ResidentResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown resident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_presence(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+    Json(update): Json<PresenceUpdate>,
+) -> Result<ResponseJson<ApiResponse<Resident>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let resident = state.household.set_presence(&home_id, &user_id, update.presence, Utc::now()).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(resident)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/dwelling-state",
+    tag = "household",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = DwellingStateResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Home has no residents"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn dwelling_state(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<DwellingState>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let dwelling = state.household.dwelling_state(&home_id, Utc::now()).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(dwelling)))
+}
@@ -0,0 +1,573 @@
+//! Incident API
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use crate::annotations::{AnnotationError, AnnotationQuery, AnnotationRequest, IncidentAnnotation};
+use crate::delivery::NotificationSeverity;
+use crate::load_shedding::HighActivityIncident;
+use crate::pipeline::PipelineError;
+use crate::probability_series::ProbabilityChart;
+use crate::thinking::{AlertDecision, BundleError, ChannelWeights, Incident, IncidentLabel, IncidentStatus, IncidentTransition, LifecycleError, OutcomeSource};
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::pagination::{paginate, ListQuery, Page, SortField};
+use super::routes::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct FeedbackRequest {
+    pub label: IncidentLabel,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CloseIncidentRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MergeIncidentsRequest {
+    pub source_incident_id: u64, // Folded into the incident in the path, then removed
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SplitIncidentRequest {
+    pub event_indices: Vec<usize>, // Positions in the incident's event list to move out
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ProbabilitySeriesQuery {
+    #[serde(default)]
+    pub max_points: Option<usize>, // Downsample long incidents to at most this many points
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitIncidentResponse {
+    pub original: Incident,
+    pub split: Incident,
+}
+
+/// Map an outcome-learning failure to a response status
+pub(crate) fn outcome_status(err: PipelineError) -> StatusCode {
+    match err {
+        PipelineError::IncidentNotFound(_) => StatusCode::NOT_FOUND,
+        PipelineError::LearningError(_) => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Download the evidence bundle (ZIP) for an incident
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/evidence-bundle",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    responses(
+        (status = 200, description = "Evidence bundle", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope or not this home"),
+        (status = 404, description = "Unknown incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn export_evidence_bundle(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+) -> Result<Response, StatusCode> {
+    // Bundles carry media, timelines and personal details; only the home's own users get them
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let pipeline = state.pipeline.read().await;
+
+    match pipeline.export_incident_evidence(&home_id, incident_id).await {
+        Ok(bundle) => Ok((
+            [
+                (header::CONTENT_TYPE, "application/zip".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", bundle.filename)),
+            ],
+            bundle.bytes,
+        ).into_response()),
+        Err(PipelineError::EvidenceExportError(BundleError::IncidentNotFound(_))) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Evidence export for incident {} in {} failed: {}", incident_id, home_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Homeowner feedback on whether an incident was a real threat; returns the home's updated weights
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/feedback",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn submit_feedback(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<FeedbackRequest>,
+) -> Result<ResponseJson<ApiResponse<ChannelWeights>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let weights = state.pipeline.read().await
+        .record_outcome(&home_id, incident_id, request.label, OutcomeSource::UserFeedback)
+        .map_err(outcome_status)?;
+    Ok(ResponseJson(ApiResponse::success(weights)))
+}
+
+fn lifecycle_status(err: LifecycleError) -> StatusCode {
+    match err {
+        LifecycleError::NotFound(_) => StatusCode::NOT_FOUND,
+        LifecycleError::AlreadyClosed(..) => StatusCode::CONFLICT,
+        LifecycleError::InvalidTarget(_) | LifecycleError::InvalidEdit(_) => StatusCode::BAD_REQUEST,
+    }
+}
+
+async fn set_incident_status(
+    state: &AppState,
+    home_id: &str,
+    incident_id: u64,
+    status: IncidentStatus,
+    reason: String,
+) -> Result<ResponseJson<ApiResponse<IncidentTransition>>, StatusCode> {
+    let transition = state.pipeline.write().await
+        .close_incident(home_id, incident_id, status, &reason)
+        .map_err(lifecycle_status)?;
+    Ok(ResponseJson(ApiResponse::success(transition)))
+}
+
+/// Mark an incident as handled
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/close",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = CloseIncidentRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident"),
+        (status = 409, description = "Incident already closed"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn close_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<CloseIncidentRequest>,
+) -> Result<ResponseJson<ApiResponse<IncidentTransition>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let reason = request.reason.unwrap_or_else(|| "closed by user".to_string());
+    set_incident_status(&state, &home_id, incident_id, IncidentStatus::Resolved, reason).await
+}
+
+/// Merge another incident into this one, e.g. one prowler tracked as two
+/// people; fused evidence, narrative and decision are recomputed
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/merge",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident that absorbs the other")),
+    request_body = MergeIncidentsRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Incident merged with itself"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn merge_incidents(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<MergeIncidentsRequest>,
+) -> Result<ResponseJson<ApiResponse<Incident>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let merged = state.pipeline.write().await
+        .merge_incidents(&home_id, incident_id, request.source_incident_id)
+        .map_err(lifecycle_status)?;
+    Ok(ResponseJson(ApiResponse::success(merged)))
+}
+
+/// Split events out of an incident into a new one, e.g. two visitors
+/// correlated as one; both incidents are re-scored
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/split",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = SplitIncidentRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Empty, out-of-range or total split"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn split_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<SplitIncidentRequest>,
+) -> Result<ResponseJson<ApiResponse<SplitIncidentResponse>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let (original, split) = state.pipeline.write().await
+        .split_incident(&home_id, incident_id, &request.event_indices)
+        .map_err(lifecycle_status)?;
+    Ok(ResponseJson(ApiResponse::success(SplitIncidentResponse { original, split })))
+}
+
+/// Dismiss an incident as not worth acting on
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/dismiss",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = CloseIncidentRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident"),
+        (status = 409, description = "Incident already closed"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn dismiss_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<CloseIncidentRequest>,
+) -> Result<ResponseJson<ApiResponse<IncidentTransition>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let reason = request.reason.unwrap_or_else(|| "dismissed by user".to_string());
+    set_incident_status(&state, &home_id, incident_id, IncidentStatus::Dismissed, reason).await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IncidentRow {
+    pub incident_id: u64,
+    pub status: IncidentStatus,
+    pub started_at: f64,
+    pub last_updated: f64,
+    pub cameras: Vec<String>,
+    pub event_count: usize,
+    pub probability: Option<f64>,       // Latest calibrated probability
+    pub decision: Option<AlertDecision>, // Latest decision
+    pub severity: Option<NotificationSeverity>,
+    pub summary: Option<String>,
+}
+
+impl From<&Incident> for IncidentRow {
+    fn from(incident: &Incident) -> Self {
+        let latest = incident.probability_trace.last();
+        let mut cameras: Vec<String> = incident.cameras.iter().cloned().collect();
+        cameras.sort();
+        Self {
+            incident_id: incident.id,
+            status: incident.status,
+            started_at: incident.started_at,
+            last_updated: incident.last_updated,
+            cameras,
+            event_count: incident.events.len(),
+            probability: latest.map(|p| p.calibrated_probability),
+            decision: latest.map(|p| p.decision.clone()),
+            severity: latest.and_then(|p| NotificationSeverity::from_decision(&p.decision)),
+            summary: incident.last_narrative.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EventRow {
+    pub event_id: String, // "<incident_id>-<index>"
+    pub incident_id: u64,
+    pub ts: f64,
+    pub cam: String,
+    pub person_track: String,
+    pub dwell_s: f64,
+    pub llr: f64,
+    pub probability: Option<f64>, // Incident probability once this event was fused
+    pub severity: Option<NotificationSeverity>,
+}
+
+fn event_rows(incident: &Incident) -> impl Iterator<Item = EventRow> + '_ {
+    incident.events.iter().enumerate().map(move |(index, event)| {
+        let point = incident.probability_trace.iter().find(|p| p.event_count == index + 1);
+        EventRow {
+            event_id: format!("{}-{}", incident.id, index),
+            incident_id: incident.id,
+            ts: event.ts,
+            cam: event.cam.clone(),
+            person_track: event.person_track.clone(),
+            dwell_s: event.dwell_s,
+            llr: event.evidence.sum(),
+            probability: point.map(|p| p.calibrated_probability),
+            severity: point.and_then(|p| NotificationSeverity::from_decision(&p.decision)),
+        }
+    })
+}
+
+/// How the incident's calibrated probability evolved, one point per assessment, for charting
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/probability",
+    tag = "incidents",
+    params(
+        ("home_id" = String, Path, description = "Home id"),
+        ("incident_id" = u64, Path, description = "Incident id"),
+        ("max_points" = Option<usize>, Query, description = "Downsample to at most this many points; decision changes and the peak are kept"),
+    ),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Nothing recorded for this incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn probability_series(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Query(query): Query<ProbabilitySeriesQuery>,
+) -> Result<ResponseJson<ApiResponse<ProbabilityChart>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let chart = state.probability_history.chart(&home_id, incident_id, query.max_points).await
+        .map_err(|e| {
+            tracing::warn!("Probability series for incident {} unavailable: {}", incident_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(chart)))
+}
+
+/// A page of the home's incidents, open and closed
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/incidents",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ListQuery),
+    responses(
+        (status = 200, body = IncidentPageResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Malformed cursor, or cursor from a different sort"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_incidents(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> Result<ResponseJson<ApiResponse<Page<IncidentRow>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let rows: Vec<IncidentRow> = state.pipeline.read().await
+        .home_incidents(&home_id)
+        .iter()
+        .map(IncidentRow::from)
+        .filter(|row| query.in_range(row.started_at) && query.meets_severity(row.severity))
+        .collect();
+    let page = paginate(rows, &query, |row, sort| {
+        let key = match sort {
+            SortField::Time => row.started_at,
+            SortField::Probability => row.probability.unwrap_or(0.0),
+        };
+        (key, row.incident_id.to_string())
+    })?;
+    Ok(ResponseJson(ApiResponse::success(page)))
+}
+
+/// A page of the events fused into the home's incidents
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/events",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ListQuery),
+    responses(
+        (status = 200, body = EventPageResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Malformed cursor, or cursor from a different sort"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_events(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> Result<ResponseJson<ApiResponse<Page<EventRow>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let rows: Vec<EventRow> = state.pipeline.read().await
+        .home_incidents(&home_id)
+        .iter()
+        .flat_map(event_rows)
+        .filter(|row| query.in_range(row.ts) && query.meets_severity(row.severity))
+        .collect();
+    let page = paginate(rows, &query, |row, sort| {
+        let key = match sort {
+            SortField::Time => row.ts,
+            SortField::Probability => row.probability.unwrap_or(0.0),
+        };
+        (key, row.event_id.clone())
+    })?;
+    Ok(ResponseJson(ApiResponse::success(page)))
+}
+
+/// The home's high-activity incident: events from low-priority cameras folded together during a surge
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/high-activity",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "The home has not been overloaded"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn high_activity(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<HighActivityIncident>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let incident = state.pipeline.read().await.high_activity(&home_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(incident)))
+}
+
+fn annotation_status(err: PipelineError) -> StatusCode {
+    match err {
+        PipelineError::IncidentNotFound(_) => StatusCode::NOT_FOUND,
+        PipelineError::AnnotationError(AnnotationError::Empty | AnnotationError::Invalid(_)) => StatusCode::BAD_REQUEST,
+        PipelineError::AnnotationError(AnnotationError::NotFound(_)) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Attach a note and tags to an incident; tags like "family" or "suspicious" update trust and the watchlist
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/annotations",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = AnnotationRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Empty note and no tags, or too long"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn annotate_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<AnnotationRequest>,
+) -> Result<ResponseJson<ApiResponse<IncidentAnnotation>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let annotation = state.pipeline.read().await
+        .annotate_incident(&home_id, incident_id, &user.user_id, request)
+        .map_err(annotation_status)?;
+    Ok(ResponseJson(ApiResponse::success(annotation)))
+}
+
+/// Notes and tags on one incident, newest first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/annotations",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_annotations(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+) -> Result<ResponseJson<ApiResponse<Vec<IncidentAnnotation>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.annotations.for_incident(&home_id, incident_id))))
+}
+
+/// Search a home's annotations by text, tag, person or incident
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/annotations",
+    tag = "incidents",
+    params(
+        ("home_id" = String, Path, description = "Home id"),
+        ("q" = Option<String>, Query, description = "Text in the note or a tag"),
+        ("tag" = Option<String>, Query, description = "Exact tag"),
+        ("entity_id" = Option<String>, Query, description = "Only this person"),
+        ("incident_id" = Option<u64>, Query, description = "Only this incident"),
+    ),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn search_annotations(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<AnnotationQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<IncidentAnnotation>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.annotations.search(&home_id, &query))))
+}
+
+/// Delete an annotation; a watchlist entry it created stays until removed
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/annotations/{annotation_id}",
+    tag = "incidents",
+    params(("home_id" = String, Path, description = "Home id"), ("annotation_id" = Uuid, Path, description = "Annotation id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown annotation"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_annotation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, annotation_id)): Path<(String, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<IncidentAnnotation>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let removed = state.annotations.remove(&home_id, annotation_id)
+        .map_err(|e| annotation_status(PipelineError::AnnotationError(e)))?;
+    Ok(ResponseJson(ApiResponse::success(removed)))
+}
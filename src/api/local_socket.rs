@@ -0,0 +1,199 @@
+//! Local UNIX socket API
+//!
+//! Edge deployments run camera processes on the same box as the server. Rather
+//! than loopback TCP with camera tokens, those processes can connect to a UNIX
+//! domain socket serving the ingest and alert routes. Each connection is
+//! admitted on its peer credentials as reported by the kernel: root, the
+//! server's own user, and any configured uids or gids. Admitted requests carry
+//! a `LocalPeer` extension, which `AuthUser` accepts in place of a bearer
+//! token. Under systemd the socket can be passed in by socket activation
+//! (LISTEN_FDS); otherwise the server binds the configured path itself.
+
+use axum::{
+    routing::{get, post},
+    Extension, Router,
+};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use std::collections::HashSet;
+use std::os::fd::FromRawFd;
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::net::{UnixListener, UnixStream};
+
+use super::auth::LocalPeer;
+use super::routes::AppState;
+use super::{escalation, events, incidents, websocket};
+
+// First file descriptor systemd passes to an activated service
+const SD_LISTEN_FDS_START: i32 = 3;
+
+// Pause after a failed accept so a persistent error does not spin the loop
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+#[derive(Error, Debug)]
+pub enum LocalSocketError {
+    #[error("Invalid {name}: '{value}'")]
+    Invalid { name: &'static str, value: String },
+
+    #[error("Local socket I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Peer uid {uid} gid {gid} is not allowed on the local socket")]
+    Denied { uid: u32, gid: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalSocketConfig {
+    pub path: PathBuf,
+    pub mode: u32,                   // Permissions on a socket file we create ourselves
+    pub allowed_uids: HashSet<u32>,  // On top of root and the server's own user
+    pub allowed_gids: HashSet<u32>,
+    pub vps_api_key: Option<String>, // Used for events submitted without one
+}
+
+impl Default for LocalSocketConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("/run/novin/api.sock"),
+            mode: 0o660,
+            allowed_uids: HashSet::new(),
+            allowed_gids: HashSet::new(),
+            vps_api_key: None,
+        }
+    }
+}
+
+impl LocalSocketConfig {
+    /// Configuration from NOVIN_LOCAL_SOCKET (the path; unset disables the
+    /// socket), NOVIN_LOCAL_SOCKET_MODE (octal), NOVIN_LOCAL_SOCKET_UIDS and
+    /// NOVIN_LOCAL_SOCKET_GIDS (comma separated) and NOVIN_LOCAL_VPS_API_KEY
+    pub fn from_env() -> Result<Option<Self>, LocalSocketError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, LocalSocketError> {
+        let Some(path) = var("NOVIN_LOCAL_SOCKET").filter(|p| !p.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let mut config = Self { path: PathBuf::from(path.trim()), ..Self::default() };
+        if let Some(mode) = var("NOVIN_LOCAL_SOCKET_MODE") {
+            config.mode = u32::from_str_radix(mode.trim().trim_start_matches("0o"), 8)
+                .ok()
+                .filter(|m| *m <= 0o777)
+                .ok_or(LocalSocketError::Invalid { name: "NOVIN_LOCAL_SOCKET_MODE", value: mode.clone() })?;
+        }
+        if let Some(uids) = var("NOVIN_LOCAL_SOCKET_UIDS") {
+            config.allowed_uids = parse_ids("NOVIN_LOCAL_SOCKET_UIDS", &uids)?;
+        }
+        if let Some(gids) = var("NOVIN_LOCAL_SOCKET_GIDS") {
+            config.allowed_gids = parse_ids("NOVIN_LOCAL_SOCKET_GIDS", &gids)?;
+        }
+        config.vps_api_key = var("NOVIN_LOCAL_VPS_API_KEY").filter(|k| !k.is_empty());
+        Ok(Some(config))
+    }
+
+    /// Whether a peer may use the socket; `own_uid` is the server's own user
+    pub fn authorize(&self, peer: &LocalPeer, own_uid: Option<u32>) -> Result<(), LocalSocketError> {
+        let allowed = peer.uid == 0
+            || Some(peer.uid) == own_uid
+            || self.allowed_uids.contains(&peer.uid)
+            || self.allowed_gids.contains(&peer.gid);
+        match allowed {
+            true => Ok(()),
+            false => Err(LocalSocketError::Denied { uid: peer.uid, gid: peer.gid }),
+        }
+    }
+}
+
+fn parse_ids(name: &'static str, value: &str) -> Result<HashSet<u32>, LocalSocketError> {
+    value.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().map_err(|_| LocalSocketError::Invalid { name, value: value.to_string() }))
+        .collect()
+}
+
+/// The ingest and alert routes served on the socket
+pub fn local_routes(state: AppState) -> Router {
+    Router::new()
+        .route("/api/system/health", get(|| async { "OK" }))
+        .route("/api/ingest/cameras/:camera_id/events", post(events::ingest_local_camera_event))
+        .route("/api/ws", get(websocket::websocket_handler))
+        .route("/api/homes/:home_id/incidents", get(incidents::list_incidents))
+        .route("/api/homes/:home_id/events", get(incidents::list_events))
+        .route("/api/homes/:home_id/incidents/:incident_id/escalation/acknowledge", post(escalation::acknowledge_escalation))
+        .with_state(state)
+}
+
+/// The socket passed in by systemd socket activation, if any, otherwise a
+/// freshly bound one at the configured path
+pub fn listen(config: &LocalSocketConfig) -> Result<UnixListener, LocalSocketError> {
+    let activated = std::env::var("LISTEN_PID").ok().and_then(|pid| pid.parse::<u32>().ok()) == Some(std::process::id())
+        && std::env::var("LISTEN_FDS").ok().and_then(|fds| fds.parse::<i32>().ok()).unwrap_or(0) >= 1;
+    if activated {
+        // SAFETY: systemd hands the activated socket to this process at fd 3 and nothing else owns it
+        let listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(SD_LISTEN_FDS_START) };
+        listener.set_nonblocking(true)?;
+        tracing::info!("Local API socket passed in by systemd");
+        return Ok(UnixListener::from_std(listener)?);
+    }
+
+    if let Some(dir) = config.path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // A socket file left by a previous run would make bind fail
+    if std::fs::symlink_metadata(&config.path).map(|m| m.file_type().is_socket()).unwrap_or(false) {
+        std::fs::remove_file(&config.path)?;
+    }
+    let listener = UnixListener::bind(&config.path)?;
+    std::fs::set_permissions(&config.path, std::fs::Permissions::from_mode(config.mode))?;
+    tracing::info!("Local API socket listening on {}", config.path.display());
+    Ok(listener)
+}
+
+/// Serve `router` on the socket until the task is dropped, admitting each
+/// connection on its peer credentials
+pub async fn serve(listener: UnixListener, config: LocalSocketConfig, router: Router) {
+    let config = Arc::new(config);
+    let own_uid = std::fs::metadata("/proc/self").ok().map(|m| m.uid());
+    loop {
+        // Accept errors (e.g. out of file descriptors) are usually transient; keep serving
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                tracing::warn!("Local socket accept failed: {}", e);
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let peer = match peer_of(&stream) {
+            Ok(peer) => peer,
+            Err(e) => {
+                tracing::warn!("Could not read local socket peer credentials: {}", e);
+                continue;
+            }
+        };
+        if let Err(e) = config.authorize(&peer, own_uid) {
+            tracing::warn!("{}", e);
+            continue;
+        }
+
+        let service = TowerToHyperService::new(router.clone().layer(Extension(peer)).layer(Extension(config.clone())));
+        tokio::spawn(async move {
+            let connection = auto::Builder::new(TokioExecutor::new());
+            if let Err(e) = connection.serve_connection_with_upgrades(TokioIo::new(stream), service).await {
+                tracing::debug!("Local socket connection from uid {} ended: {}", peer.uid, e);
+            }
+        });
+    }
+}
+
+fn peer_of(stream: &UnixStream) -> std::io::Result<LocalPeer> {
+    let cred = stream.peer_cred()?;
+    Ok(LocalPeer { uid: cred.uid(), gid: cred.gid(), pid: cred.pid() })
+}
@@ -0,0 +1,9 @@
+-- Idempotency records for webhook retries
+CREATE TABLE IF NOT EXISTS processed_events (
+    home_id TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    state TEXT NOT NULL DEFAULT 'in_flight',
+    response TEXT,
+    first_seen INTEGER NOT NULL,
+    PRIMARY KEY (home_id, event_id)
+);
@@ -0,0 +1,12 @@
+-- Pending re-evaluations for incidents that scored Wait
+CREATE TABLE IF NOT EXISTS follow_ups (
+    home_id TEXT NOT NULL,
+    incident_id INTEGER NOT NULL,
+    user_id TEXT NOT NULL,
+    zone TEXT,
+    event_count INTEGER NOT NULL,
+    attempt INTEGER NOT NULL DEFAULT 0,
+    scheduled_at INTEGER NOT NULL,
+    due_at INTEGER NOT NULL,
+    PRIMARY KEY (home_id, incident_id)
+);
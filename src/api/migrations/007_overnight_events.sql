@@ -0,0 +1,10 @@
+-- Events analyzed during a home's overnight review window, kept until the morning summary is delivered
+CREATE TABLE IF NOT EXISTS overnight_events (
+    event_id TEXT PRIMARY KEY,
+    home_id TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    analysis_summary TEXT NOT NULL,
+    suppressed_alert_level TEXT,
+    delivered_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_overnight_events_pending ON overnight_events (home_id, delivered_at);
@@ -0,0 +1,15 @@
+-- Calibrated probability of each incident after every assessment, for charting and post-hoc review
+CREATE TABLE IF NOT EXISTS probability_series (
+    home_id TEXT NOT NULL,
+    incident_id INTEGER NOT NULL,
+    seq INTEGER NOT NULL,
+    at INTEGER NOT NULL,
+    event_id TEXT,
+    event_count INTEGER NOT NULL,
+    fused_llr REAL NOT NULL,
+    calibrated_probability REAL NOT NULL,
+    decision TEXT NOT NULL,
+    source TEXT NOT NULL,
+    PRIMARY KEY (home_id, incident_id, seq)
+);
+CREATE INDEX IF NOT EXISTS idx_probability_series_at ON probability_series (at);
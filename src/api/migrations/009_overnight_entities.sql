@@ -0,0 +1,3 @@
+-- Camera and re-identified person behind each overnight event, so the morning summary can group events by person
+ALTER TABLE overnight_events ADD COLUMN camera TEXT;
+ALTER TABLE overnight_events ADD COLUMN entity_id TEXT;
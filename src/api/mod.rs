@@ -8,3 +8,27 @@ pub use server::*;
 pub use models::*;
 pub mod database;
 pub mod events;
+pub mod webhooks;
+pub mod incidents;
+pub mod monitoring;
+pub mod billing;
+pub mod analytics;
+pub mod visitor_tokens;
+pub mod guests;
+pub mod notifications;
+pub mod vacation;
+pub mod household;
+pub mod pagination;
+pub mod openapi;
+pub mod cameras;
+pub mod devices;
+pub mod admin;
+pub mod onboarding;
+pub mod priors;
+pub mod sharing;
+pub mod tracking;
+pub mod escalation;
+pub mod automations;
+pub mod vms;
+pub mod central_station;
+pub mod local_socket;
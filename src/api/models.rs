@@ -1,8 +1,21 @@
 use serde::{Deserialize, Serialize};
+use crate::household::{DwellingState, Resident};
+use super::pagination::{EventPage, IncidentPage};
 use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[aliases(
+    IncidentPageResponse = ApiResponse<IncidentPage>,
+    EventPageResponse = ApiResponse<EventPage>,
+    LoginApiResponse = ApiResponse<LoginResponse>,
+    SystemStatusResponse = ApiResponse<SystemStatus>,
+    ResidentResponse = ApiResponse<Resident>,
+    ResidentListResponse = ApiResponse<Vec<Resident>>,
+    DwellingStateResponse = ApiResponse<DwellingState>,
+    JsonResponse = ApiResponse<serde_json::Value>,
+)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: T,
@@ -30,13 +43,13 @@ impl<T> ApiResponse<T> {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct LoginResponse {
     pub token: String,
     pub user_id: String,
@@ -44,14 +57,14 @@ pub struct LoginResponse {
     pub role: UserRole,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum UserRole {
     Admin,
     User,
     ReadOnly,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SystemStatus {
     pub status: String,
     pub uptime: u64,
@@ -62,7 +75,7 @@ pub struct SystemStatus {
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AlertInfo {
     pub id: Uuid,
     pub home_id: String,
@@ -73,7 +86,7 @@ pub struct AlertInfo {
     pub status: AlertStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum AlertStatus {
     Active,
     Acknowledged,
@@ -81,7 +94,8 @@ pub enum AlertStatus {
     FalsePositive,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PaginationQuery {
     pub page: Option<u32>,
     pub limit: Option<u32>,
@@ -96,7 +110,8 @@ impl Default for PaginationQuery {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchQuery {
     pub home_id: Option<String>,
     pub start_date: Option<DateTime<Utc>>,
@@ -105,13 +120,13 @@ pub struct SearchQuery {
     pub severity: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BulkAlertAction {
     pub alert_ids: Vec<Uuid>,
     pub action: AlertAction,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum AlertAction {
     Acknowledge,
     Resolve,
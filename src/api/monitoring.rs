@@ -0,0 +1,400 @@
+//! Professional Monitoring Center API
+//!
+//! Cross-home incident board for monitoring center staff. Live incidents come from
+//! the pipeline; claim, acknowledgement, operator notes and dispatch status are
+//! kept here per (home_id, incident_id). Access is limited by auth scopes:
+//! operators work incidents, supervisors can also take over or release another
+//! operator's claim.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::thinking::{AlertDecision, IncidentLabel, OutcomeSource};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DispatchStatus {
+    None,
+    Requested,
+    EnRoute,
+    OnScene,
+    Cleared,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OperatorNote {
+    pub author: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// Operator-side state for one incident
+#[derive(Debug, Clone, Serialize)]
+pub struct OperatorState {
+    pub claimed_by: Option<String>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub dispatch: DispatchStatus,
+    pub dispatch_updated_at: Option<DateTime<Utc>>,
+    pub notes: Vec<OperatorNote>,
+}
+
+impl Default for OperatorState {
+    fn default() -> Self {
+        Self {
+            claimed_by: None,
+            claimed_at: None,
+            acknowledged_by: None,
+            acknowledged_at: None,
+            dispatch: DispatchStatus::None,
+            dispatch_updated_at: None,
+            notes: Vec::new(),
+        }
+    }
+}
+
+// Row on the monitoring board
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitoredIncident {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub started_at: f64,
+    pub last_updated: f64,
+    pub cameras: Vec<String>,
+    pub probability: Option<f64>,
+    pub decision: Option<AlertDecision>,
+    pub summary: Option<String>,
+    pub operator: OperatorState,
+}
+
+// Operator state for all incidents, keyed by (home_id, incident_id)
+#[derive(Debug, Default)]
+pub struct MonitoringBoard {
+    states: RwLock<HashMap<(String, u64), OperatorState>>,
+}
+
+impl MonitoringBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn state(&self, home_id: &str, incident_id: u64) -> OperatorState {
+        self.states.read().await
+            .get(&(home_id.to_string(), incident_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Whether any operator action has been recorded for the incident
+    pub async fn is_tracked(&self, home_id: &str, incident_id: u64) -> bool {
+        self.states.read().await.contains_key(&(home_id.to_string(), incident_id))
+    }
+
+    pub(crate) async fn update<F>(&self, home_id: &str, incident_id: u64, f: F) -> Result<OperatorState, StatusCode>
+    where
+        F: FnOnce(&mut OperatorState) -> Result<(), StatusCode>,
+    {
+        // Check and change a copy so a rejected update leaves no entry behind
+        let mut states = self.states.write().await;
+        let key = (home_id.to_string(), incident_id);
+        let mut state = states.get(&key).cloned().unwrap_or_default();
+        f(&mut state)?;
+        states.insert(key, state.clone());
+        Ok(state)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ClaimRequest {
+    #[serde(default)]
+    pub force: bool, // Supervisor takeover of another operator's claim
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NoteRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DispositionRequest {
+    pub label: IncidentLabel,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DispatchRequest {
+    pub status: DispatchStatus,
+}
+
+// Only the claimant (or a supervisor) may act on a claimed incident
+fn ensure_claimant(user: &AuthUser, state: &OperatorState) -> Result<(), StatusCode> {
+    match &state.claimed_by {
+        Some(owner) if owner == &user.user_id => Ok(()),
+        Some(_) if user.has_scope(Scope::MonitoringOverride) => Ok(()),
+        Some(_) => Err(StatusCode::CONFLICT),
+        None => Err(StatusCode::PRECONDITION_REQUIRED),
+    }
+}
+
+async fn ensure_incident_exists(state: &AppState, home_id: &str, incident_id: u64) -> Result<(), StatusCode> {
+    let pipeline = state.pipeline.read().await;
+    if pipeline.open_incidents().iter().any(|(h, i)| *h == home_id && i.id == incident_id) {
+        Ok(())
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+/// List active incidents across all homes, highest probability first
+#[utoipa::path(
+    get,
+    path = "/api/monitoring/incidents",
+    tag = "monitoring",
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_active_incidents(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<ResponseJson<ApiResponse<Vec<MonitoredIncident>>>, StatusCode> {
+    user.require(Scope::MonitoringRead)?;
+
+    let pipeline = state.pipeline.read().await;
+    let mut rows = Vec::new();
+    for (home_id, incident) in pipeline.open_incidents() {
+        let latest = incident.probability_trace.last();
+        let mut cameras: Vec<String> = incident.cameras.iter().cloned().collect();
+        cameras.sort();
+        rows.push(MonitoredIncident {
+            home_id: home_id.to_string(),
+            incident_id: incident.id,
+            started_at: incident.started_at,
+            last_updated: incident.last_updated,
+            cameras,
+            probability: latest.map(|p| p.calibrated_probability),
+            decision: latest.map(|p| p.decision.clone()),
+            summary: incident.last_narrative.clone(),
+            operator: state.monitoring_board.state(&home_id, incident.id).await,
+        });
+    }
+
+    rows.sort_by(|a, b| {
+        b.probability.unwrap_or(0.0)
+            .partial_cmp(&a.probability.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(ResponseJson(ApiResponse::success(rows)))
+}
+
+/// Claim an incident for the calling operator
+#[utoipa::path(
+    post,
+    path = "/api/monitoring/incidents/{home_id}/{incident_id}/claim",
+    tag = "monitoring",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = ClaimRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 409, description = "Claimed by another operator"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn claim_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<ClaimRequest>,
+) -> Result<ResponseJson<ApiResponse<OperatorState>>, StatusCode> {
+    user.require(Scope::MonitoringClaim)?;
+    if request.force {
+        user.require(Scope::MonitoringOverride)?;
+    }
+    ensure_incident_exists(&state, &home_id, incident_id).await?;
+
+    let updated = state.monitoring_board.update(&home_id, incident_id, |s| {
+        match &s.claimed_by {
+            Some(owner) if owner != &user.user_id && !request.force => return Err(StatusCode::CONFLICT),
+            _ => {}
+        }
+        s.claimed_by = Some(user.user_id.clone());
+        s.claimed_at = Some(Utc::now());
+        Ok(())
+    }).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+/// Release a claim so another operator can pick the incident up
+#[utoipa::path(
+    post,
+    path = "/api/monitoring/incidents/{home_id}/{incident_id}/release",
+    tag = "monitoring",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn release_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+) -> Result<ResponseJson<ApiResponse<OperatorState>>, StatusCode> {
+    user.require(Scope::MonitoringClaim)?;
+
+    let updated = state.monitoring_board.update(&home_id, incident_id, |s| {
+        ensure_claimant(&user, s)?;
+        s.claimed_by = None;
+        s.claimed_at = None;
+        Ok(())
+    }).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+/// Acknowledge a claimed incident
+#[utoipa::path(
+    post,
+    path = "/api/monitoring/incidents/{home_id}/{incident_id}/acknowledge",
+    tag = "monitoring",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn acknowledge_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+) -> Result<ResponseJson<ApiResponse<OperatorState>>, StatusCode> {
+    user.require(Scope::MonitoringClaim)?;
+
+    let updated = state.monitoring_board.update(&home_id, incident_id, |s| {
+        ensure_claimant(&user, s)?;
+        s.acknowledged_by = Some(user.user_id.clone());
+        s.acknowledged_at = Some(Utc::now());
+        Ok(())
+    }).await?;
+    state.escalation.acknowledge(&home_id, incident_id, &user.user_id);
+    state.automations.acknowledge(&home_id, incident_id, &user.user_id, Utc::now());
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+/// Append an operator note
+#[utoipa::path(
+    post,
+    path = "/api/monitoring/incidents/{home_id}/{incident_id}/notes",
+    tag = "monitoring",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = NoteRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn add_operator_note(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<NoteRequest>,
+) -> Result<ResponseJson<ApiResponse<OperatorState>>, StatusCode> {
+    user.require(Scope::MonitoringNotes)?;
+    let text = request.text.trim();
+    if text.is_empty() || text.len() > 4000 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    ensure_incident_exists(&state, &home_id, incident_id).await?;
+
+    let updated = state.monitoring_board.update(&home_id, incident_id, |s| {
+        s.notes.push(OperatorNote {
+            author: user.username.clone(),
+            text: text.to_string(),
+            created_at: Utc::now(),
+        });
+        Ok(())
+    }).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+/// Update the dispatch status of a claimed incident
+#[utoipa::path(
+    put,
+    path = "/api/monitoring/incidents/{home_id}/{incident_id}/dispatch",
+    tag = "monitoring",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = DispatchRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn update_dispatch_status(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<DispatchRequest>,
+) -> Result<ResponseJson<ApiResponse<OperatorState>>, StatusCode> {
+    user.require(Scope::MonitoringDispatch)?;
+
+    let updated = state.monitoring_board.update(&home_id, incident_id, |s| {
+        ensure_claimant(&user, s)?;
+        s.dispatch = request.status;
+        s.dispatch_updated_at = Some(Utc::now());
+        Ok(())
+    }).await?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+/// Record the operator's final disposition of a claimed incident; it also trains the home's LLR weights
+#[utoipa::path(
+    post,
+    path = "/api/monitoring/incidents/{home_id}/{incident_id}/disposition",
+    tag = "monitoring",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn record_disposition(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<DispositionRequest>,
+) -> Result<ResponseJson<ApiResponse<OperatorState>>, StatusCode> {
+    user.require(Scope::MonitoringDispatch)?;
+
+    let updated = state.monitoring_board.update(&home_id, incident_id, |s| {
+        ensure_claimant(&user, s)
+    }).await?;
+    state.pipeline.read().await
+        .record_outcome(&home_id, incident_id, request.label, OutcomeSource::OperatorDisposition)
+        .map_err(super::incidents::outcome_status)?;
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
@@ -0,0 +1,551 @@
+//! Notification Preferences API
+//!
+//! Quiet hours, per-channel minimum severity and digest-only mode, set once
+//! for the home and optionally overridden per user; plus the templates and
+//! language notification text is rendered with, the phone numbers and email
+//! addresses messages go to, and the providers' delivery callbacks.
+use axum::{
+    body::Bytes,
+    extract::{Json, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json as ResponseJson,
+};
+use chrono_tz::Tz;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::delivery::sms::{INBOUND_PATH, STATUS_CALLBACK_PATH};
+use crate::delivery::{parse_sns, DeliveryStats, EmailContact, EmailDispatcher, NotificationPreferences, NotificationTemplate, SmsContact, SmsDispatcher, SnsNotice};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+
+fn check(prefs: &NotificationPreferences) -> Result<(), StatusCode> {
+    match &prefs.quiet_hours {
+        Some(quiet) if quiet.timezone.parse::<Tz>().is_err() || quiet.start == quiet.end => Err(StatusCode::BAD_REQUEST),
+        _ => Ok(()),
+    }
+}
+
+/// Home-wide defaults
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/notification-preferences",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_home_preferences(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<NotificationPreferences>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.notification_router.preferences(&home_id, None))))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/notification-preferences",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid preferences"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_home_preferences(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(prefs): Json<NotificationPreferences>,
+) -> Result<ResponseJson<ApiResponse<NotificationPreferences>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    check(&prefs)?;
+    state.notification_router.set_preferences(&home_id, None, prefs.clone());
+    Ok(ResponseJson(ApiResponse::success(prefs)))
+}
+
+/// Effective preferences for one user (their override, else the home defaults)
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/notification-preferences/{user_id}",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_user_preferences(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<NotificationPreferences>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.notification_router.preferences(&home_id, Some(&user_id)))))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/notification-preferences/{user_id}",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid preferences"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_user_preferences(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+    Json(prefs): Json<NotificationPreferences>,
+) -> Result<ResponseJson<ApiResponse<NotificationPreferences>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    check(&prefs)?;
+    state.notification_router.set_preferences(&home_id, Some(&user_id), prefs.clone());
+    Ok(ResponseJson(ApiResponse::success(prefs)))
+}
+
+/// Remove a user's override so the home defaults apply again
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/notification-preferences/{user_id}",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    responses(
+        (status = 204),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "User has no override"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn clear_user_preferences(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if state.notification_router.clear_preferences(&home_id, Some(&user_id)) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplatesView {
+    pub language: String,
+    pub templates: Vec<NotificationTemplate>, // Set for this home
+    pub defaults: Vec<NotificationTemplate>,  // Set for every home
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplatesUpdate {
+    #[serde(default)]
+    pub language: Option<String>,
+    pub templates: Vec<NotificationTemplate>, // Replaces the home's templates
+}
+
+fn templates_view(state: &AppState, home_id: &str) -> TemplatesView {
+    let store = state.notification_router.templates();
+    TemplatesView { language: store.language(home_id), templates: store.list(Some(home_id)), defaults: store.list(None) }
+}
+
+/// Notification templates and language for a home
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/notification-templates",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_templates(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<TemplatesView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(templates_view(&state, &home_id))))
+}
+
+/// Replace a home's templates and optionally its language; nothing changes if any template is invalid
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/notification-templates",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid template or language"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_templates(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(update): Json<TemplatesUpdate>,
+) -> Result<ResponseJson<ApiResponse<TemplatesView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if update.templates.iter().any(|t| t.validate().is_err()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let store = state.notification_router.templates();
+    if let Some(language) = &update.language {
+        store.set_language(&home_id, language).map_err(|_| StatusCode::BAD_REQUEST)?;
+    }
+    for existing in store.list(Some(&home_id)) {
+        store.remove(Some(&home_id), &existing.language, existing.channel);
+    }
+    for template in update.templates {
+        store.set(Some(&home_id), template).map_err(|_| StatusCode::BAD_REQUEST)?;
+    }
+    Ok(ResponseJson(ApiResponse::success(templates_view(&state, &home_id))))
+}
+
+/// Set or replace a template used by every home without its own
+#[utoipa::path(
+    put,
+    path = "/api/notification-templates",
+    tag = "notifications",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid template"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_default_template(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(template): Json<NotificationTemplate>,
+) -> Result<ResponseJson<ApiResponse<Vec<NotificationTemplate>>>, StatusCode> {
+    user.require(Scope::Admin)?;
+    let store = state.notification_router.templates();
+    store.set(None, template).map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(ResponseJson(ApiResponse::success(store.list(None))))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct PhoneNumberRequest {
+    pub phone_number: String, // E.164, e.g. +447700900123
+}
+
+#[derive(Debug, Serialize)]
+pub struct SmsView {
+    pub provider: Option<&'static str>,
+    pub contacts: Vec<SmsContact>,
+    pub stats: DeliveryStats,
+}
+
+fn sms(state: &AppState) -> Result<&Arc<SmsDispatcher>, StatusCode> {
+    state.notification_router.sms().ok_or(StatusCode::NOT_FOUND)
+}
+
+/// SMS numbers registered in a home, with its delivery counts
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/sms",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "SMS is not configured"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_sms(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<SmsView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let sms = sms(&state)?;
+    Ok(ResponseJson(ApiResponse::success(SmsView {
+        provider: sms.provider().map(|p| p.name()),
+        contacts: sms.contacts(&home_id),
+        stats: sms.stats(&home_id),
+    })))
+}
+
+/// Register or replace the number a user's SMS go to
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/sms/{user_id}",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    request_body = PhoneNumberRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "SMS is not configured"),
+        (status = 400, description = "Not an E.164 number"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_phone_number(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+    Json(request): Json<PhoneNumberRequest>,
+) -> Result<ResponseJson<ApiResponse<SmsContact>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let contact = sms(&state)?.set_number(&home_id, &user_id, &request.phone_number).map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(ResponseJson(ApiResponse::success(contact)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/sms/{user_id}",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    responses(
+        (status = 204),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No number registered"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn remove_phone_number(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if sms(&state)?.remove_number(&home_id, &user_id) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+fn sms_callback(state: &AppState, path: &str, headers: &HeaderMap, body: &[u8]) -> StatusCode {
+    let Some(sms) = state.notification_router.sms() else {
+        return StatusCode::NOT_FOUND;
+    };
+    let signature = sms.provider()
+        .and_then(|p| headers.get(p.signature_header()))
+        .and_then(|v| v.to_str().ok());
+    if sms.receive(path, signature, body) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::UNAUTHORIZED
+    }
+}
+
+/// Delivery status reports from the SMS provider; authenticated by the provider's signature
+#[utoipa::path(
+    post,
+    path = "/api/sms/status",
+    tag = "notifications",
+    request_body = String,
+    responses(
+        (status = 204),
+        (status = 401, description = "Signature missing or invalid"),
+        (status = 404, description = "SMS is not configured"),
+    ),
+)]
+pub async fn sms_status_callback(State(state): State<AppState>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    sms_callback(&state, STATUS_CALLBACK_PATH, &headers, &body)
+}
+
+/// Replies to our SMS, where STOP and START change a number's opt-out
+#[utoipa::path(
+    post,
+    path = "/api/sms/inbound",
+    tag = "notifications",
+    request_body = String,
+    responses(
+        (status = 204),
+        (status = 401, description = "Signature missing or invalid"),
+        (status = 404, description = "SMS is not configured"),
+    ),
+)]
+pub async fn sms_inbound_callback(State(state): State<AppState>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    sms_callback(&state, INBOUND_PATH, &headers, &body)
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct EmailAddressRequest {
+    pub address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmailView {
+    pub provider: Option<&'static str>,
+    pub contacts: Vec<EmailContact>,
+    pub stats: DeliveryStats,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedbackQuery {
+    pub token: String,
+}
+
+fn email(state: &AppState) -> Result<&Arc<EmailDispatcher>, StatusCode> {
+    state.notification_router.email().ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Email addresses registered in a home, with its delivery counts
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/email",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Email is not configured"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_email(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<EmailView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let email = email(&state)?;
+    Ok(ResponseJson(ApiResponse::success(EmailView {
+        provider: email.provider().map(|p| p.name()),
+        contacts: email.contacts(&home_id),
+        stats: email.stats(&home_id),
+    })))
+}
+
+/// Register or replace the address a user's email goes to
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/email/{user_id}",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    request_body = EmailAddressRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Email is not configured"),
+        (status = 400, description = "Invalid address"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_email_address(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+    Json(request): Json<EmailAddressRequest>,
+) -> Result<ResponseJson<ApiResponse<EmailContact>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let contact = email(&state)?.set_address(&home_id, &user_id, &request.address).map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(ResponseJson(ApiResponse::success(contact)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/email/{user_id}",
+    tag = "notifications",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident or user id")),
+    responses(
+        (status = 204),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No address registered"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn remove_email_address(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if email(&state)?.remove_address(&home_id, &user_id) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+/// SES bounce and complaint notifications delivered by SNS; authenticated by
+/// the feedback token in the subscription URL
+#[utoipa::path(
+    post,
+    path = "/api/email/feedback",
+    tag = "notifications",
+    params(("token" = String, Query, description = "NOVIN_EMAIL_FEEDBACK_TOKEN")),
+    request_body = String,
+    responses(
+        (status = 204),
+        (status = 400, description = "Not an SNS notification"),
+        (status = 401, description = "Wrong token"),
+        (status = 404, description = "Email is not configured"),
+    ),
+)]
+pub async fn email_feedback(
+    State(state): State<AppState>,
+    Query(query): Query<FeedbackQuery>,
+    body: Bytes,
+) -> StatusCode {
+    let Some(email) = state.notification_router.email() else {
+        return StatusCode::NOT_FOUND;
+    };
+    if !email.accepts_feedback_token(&query.token) {
+        return StatusCode::UNAUTHORIZED;
+    }
+    match parse_sns(&body) {
+        Some(SnsNotice::Feedback(feedback)) => {
+            for item in feedback {
+                email.handle_feedback(item);
+            }
+            StatusCode::NO_CONTENT
+        }
+        Some(SnsNotice::SubscriptionConfirmation { subscribe_url }) => {
+            // Only SNS itself is followed; anything else is left for an operator
+            let from_sns = url::Url::parse(&subscribe_url).is_ok_and(|u| {
+                u.scheme() == "https" && u.host_str().is_some_and(|h| h.starts_with("sns.") && h.ends_with(".amazonaws.com"))
+            });
+            if from_sns {
+                if let Err(e) = reqwest::get(&subscribe_url).await {
+                    tracing::warn!("SNS subscription not confirmed: {}", e);
+                }
+            } else {
+                tracing::warn!("Ignoring SNS subscription confirmation for {}", subscribe_url);
+            }
+            StatusCode::NO_CONTENT
+        }
+        Some(SnsNotice::Ignored) => StatusCode::NO_CONTENT,
+        None => StatusCode::BAD_REQUEST,
+    }
+}
@@ -0,0 +1,127 @@
+//! Onboarding API
+//!
+//! The setup wizard posts cameras, zones, household details and a
+//! sensitivity profile; the server answers with a complete starter config,
+//! already saved and applied to scoring and overnight review. Its "test my
+//! setup" step runs the self-test and shows the resulting health matrix.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::onboarding::{self, HomeConfig, OnboardingError, OnboardingRequest};
+use crate::pipeline::SubscriptionTier;
+use crate::self_test::{shadow_home, SelfTestReport};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SelfTestRequest {
+    #[serde(default)]
+    pub deliver: bool, // Also send a real test message by SMS, email and webhook
+    pub subscription_tier: Option<SubscriptionTier>, // Defaults to Premium, so every stage runs
+}
+
+/// Generate, save and apply a starter config for a home
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/onboarding",
+    tag = "onboarding",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Invalid cameras, location or timezone"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn onboard_home(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<OnboardingRequest>,
+) -> Result<ResponseJson<ApiResponse<HomeConfig>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let config = onboarding::generate(&home_id, &request, chrono::Utc::now().date_naive()).map_err(|e| match e {
+        OnboardingError::Invalid(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    })?;
+    state.home_configs.save(config.clone()).map_err(|e| {
+        tracing::error!("Could not save config for home {}: {}", home_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    state.pipeline.write().await.apply_home_config(&config).await.map_err(|e| {
+        tracing::error!("Saved config for home {} but could not apply it: {}", home_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// The config onboarding generated for a home
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/config",
+    tag = "onboarding",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Home has not been onboarded"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_home_config(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<HomeConfig>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let config = state.home_configs.get(&home_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Run the self-test: synthetic events of each severity through every stage,
+/// then every delivery channel the home would use
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/self-test",
+    tag = "onboarding",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn run_self_test(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<SelfTestRequest>,
+) -> Result<ResponseJson<ApiResponse<SelfTestReport>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let started_at = Utc::now();
+    let tier = request.subscription_tier.unwrap_or(SubscriptionTier::Premium);
+    let scenarios = {
+        let mut pipeline = state.pipeline.write().await;
+        // The shadow home scores with the real home's thresholds and zone priors
+        if let Some(mut config) = state.home_configs.get(&home_id) {
+            config.home_id = shadow_home(&home_id);
+            if let Err(e) = pipeline.apply_home_config(&config).await {
+                tracing::warn!("Self-test for {} runs without its home config: {}", home_id, e);
+            }
+        }
+        pipeline.run_self_test_scenarios(&home_id, &user.user_id, tier).await
+    };
+    // Test messages can take a while; events keep flowing meanwhile
+    let (channels, problems) = state.pipeline.read().await.check_delivery_channels(&home_id, &user.user_id, request.deliver).await;
+    Ok(ResponseJson(ApiResponse::success(SelfTestReport::new(&home_id, started_at, scenarios, channels, problems))))
+}
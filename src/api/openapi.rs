@@ -0,0 +1,235 @@
+//! OpenAPI document for the HTTP API
+//!
+//! Generated from the `#[utoipa::path]` annotations on each handler and the
+//! schemas derived on request and response types. Served as JSON at
+//! `/api/openapi.json` with Swagger UI at `/api/docs`, so mobile and web
+//! clients can generate typed SDKs. Responses whose payload types have no
+//! schema yet are documented as the generic `JsonResponse` envelope.
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+use crate::delivery::NotificationSeverity;
+use crate::household::{DwellingState, HouseholdRole, Occupancy, Presence, Resident, ResidentRequest};
+use crate::overnight::DeliveryChannel;
+use crate::thinking::{AlertDecision, IncidentStatus};
+use super::incidents::{CloseIncidentRequest, EventRow, IncidentRow, MergeIncidentsRequest, SplitIncidentRequest};
+use super::household::PresenceUpdate;
+use super::models::{
+    AlertAction, AlertInfo, AlertStatus, BulkAlertAction, DwellingStateResponse, EventPageResponse, IncidentPageResponse,
+    JsonResponse, LoginApiResponse, LoginRequest, LoginResponse, ResidentListResponse, ResidentResponse, SystemStatus,
+    SystemStatusResponse, UserRole,
+};
+use super::monitoring::{ClaimRequest, DispatchRequest, DispatchStatus, NoteRequest};
+use super::pagination::{EventPage, IncidentPage, SortField, SortOrder};
+use super::visitor_tokens::VerifyCodeRequest;
+use super::cameras::{CameraPinRequest, ClearTamperRequest};
+use crate::camera_health::CameraRegistrationRequest;
+use crate::annotations::AnnotationRequest;
+use crate::probability_series::{PointSource, ProbabilityChart, ProbabilityPoint};
+use super::devices::EnrollDeviceRequest;
+use super::tracking::TrustRequest;
+use super::notifications::{EmailAddressRequest, PhoneNumberRequest};
+use super::{admin, analytics, events, onboarding, priors, sharing, tracking, escalation, automations, vms, central_station, billing, cameras, devices, household, incidents, monitoring, notifications, vacation, visitor_tokens, guests, webhooks};
+
+pub const SPEC_PATH: &str = "/api/openapi.json";
+pub const DOCS_PATH: &str = "/api/docs";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Insane AI Security API", description = "Incident, household and monitoring API for the security pipeline"),
+    paths(
+        webhooks::register_webhook,
+        webhooks::list_webhooks,
+        webhooks::delete_webhook,
+        webhooks::get_delivery_log,
+        incidents::list_incidents,
+        incidents::list_events,
+        incidents::high_activity,
+        incidents::export_evidence_bundle,
+        incidents::probability_series,
+        incidents::submit_feedback,
+        incidents::close_incident,
+        incidents::dismiss_incident,
+        incidents::merge_incidents,
+        incidents::split_incident,
+        incidents::annotate_incident,
+        incidents::list_annotations,
+        incidents::search_annotations,
+        incidents::delete_annotation,
+        monitoring::list_active_incidents,
+        monitoring::claim_incident,
+        monitoring::release_incident,
+        monitoring::acknowledge_incident,
+        monitoring::add_operator_note,
+        monitoring::update_dispatch_status,
+        monitoring::record_disposition,
+        billing::export_usage,
+        billing::api_usage,
+        analytics::list_mo_clusters,
+        analytics::threat_vectors,
+        analytics::what_if_thresholds,
+        analytics::channel_weights,
+        analytics::sensor_reliability,
+        analytics::threshold_history,
+        analytics::reset_thresholds,
+        notifications::get_home_preferences,
+        notifications::set_home_preferences,
+        notifications::get_user_preferences,
+        notifications::set_user_preferences,
+        notifications::clear_user_preferences,
+        notifications::get_templates,
+        notifications::set_templates,
+        notifications::set_default_template,
+        notifications::get_sms,
+        notifications::set_phone_number,
+        notifications::remove_phone_number,
+        notifications::sms_status_callback,
+        notifications::sms_inbound_callback,
+        notifications::get_email,
+        notifications::set_email_address,
+        notifications::remove_email_address,
+        notifications::email_feedback,
+        household::list_residents,
+        household::add_resident,
+        household::update_resident,
+        household::remove_resident,
+        household::set_presence,
+        household::dwelling_state,
+        cameras::list_pins,
+        cameras::set_pin,
+        cameras::remove_pin,
+        cameras::tamper_alerts,
+        cameras::check_tamper,
+        cameras::clear_tamper,
+        cameras::register_camera,
+        cameras::unregister_camera,
+        cameras::camera_health,
+        events::ingest_camera_event,
+        onboarding::onboard_home,
+        onboarding::get_home_config,
+        onboarding::run_self_test,
+        priors::get_priors,
+        priors::edit_priors,
+        priors::check_priors,
+        priors::prior_history,
+        priors::rollback_priors,
+        sharing::get_privacy_zones,
+        sharing::set_privacy_zones,
+        sharing::enroll_face,
+        sharing::remove_faces,
+        sharing::share_snapshot,
+        sharing::revoke_share,
+        sharing::open_shared_snapshot,
+        tracking::home_tracks,
+        tracking::zone_graph,
+        tracking::knowledge_graph,
+        tracking::activity_baseline,
+        tracking::list_trust,
+        tracking::mark_trusted,
+        tracking::revoke_trust,
+        tracking::trust_audit,
+        tracking::list_watchlist,
+        tracking::unwatch,
+        escalation::get_chain,
+        escalation::set_chain,
+        escalation::active_escalations,
+        escalation::acknowledge_escalation,
+        automations::list_automations,
+        automations::create_automation,
+        automations::delete_automation,
+        automations::preview_automations,
+        automations::automation_history,
+        automations::acknowledge_automations,
+        vms::list_integrations,
+        vms::create_integration,
+        vms::delete_integration,
+        vms::bookmark_history,
+        vms::test_integration,
+        central_station::get_station,
+        central_station::set_station,
+        central_station::delete_station,
+        central_station::station_history,
+        central_station::test_station,
+        devices::list_devices,
+        devices::enroll_device,
+        devices::revoke_device,
+        admin::list_homes,
+        admin::force_morning_summary,
+        admin::replay_events,
+        admin::list_dead_letters,
+        admin::flush_dead_letters,
+        admin::rotate_webhook_secret,
+        admin::incident_timeline_handler,
+        admin::metrics,
+        admin::llm_usage,
+        admin::llm_guard,
+        admin::llm_kill_switch,
+        admin::health_alerts,
+        admin::question_value,
+        admin::report_card,
+        admin::report_cards,
+        vacation::get_vacation,
+        vacation::set_vacation,
+        vacation::end_vacation,
+        visitor_tokens::issue_token,
+        visitor_tokens::list_tokens,
+        visitor_tokens::get_token,
+        visitor_tokens::revoke_token,
+        visitor_tokens::verify_code,
+        guests::create_guest,
+        guests::list_guests,
+        guests::remove_guest,
+    ),
+    components(schemas(
+        JsonResponse, IncidentPageResponse, EventPageResponse, LoginApiResponse, SystemStatusResponse,
+        ResidentResponse, ResidentListResponse, DwellingStateResponse,
+        LoginRequest, LoginResponse, UserRole, SystemStatus, AlertInfo, AlertStatus, BulkAlertAction, AlertAction,
+        IncidentPage, EventPage, IncidentRow, EventRow, SortField, SortOrder, CloseIncidentRequest, MergeIncidentsRequest, SplitIncidentRequest,
+        IncidentStatus, AlertDecision, NotificationSeverity, DeliveryChannel, ProbabilityChart, ProbabilityPoint, PointSource,
+        ClaimRequest, NoteRequest, DispatchRequest, DispatchStatus,
+        Resident, ResidentRequest, HouseholdRole, Presence, PresenceUpdate, DwellingState, Occupancy,
+        VerifyCodeRequest, CameraPinRequest, ClearTamperRequest, CameraRegistrationRequest, EnrollDeviceRequest, TrustRequest, AnnotationRequest, PhoneNumberRequest, EmailAddressRequest,
+    )),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "incidents", description = "Incident history, feedback and lifecycle"),
+        (name = "monitoring", description = "Professional monitoring center board"),
+        (name = "household", description = "Residents, presence and dwelling state"),
+        (name = "vacation", description = "Vacation mode"),
+        (name = "cameras", description = "Pinned snapshot hosts and certificates, tamper alerts, registration and health"),
+        (name = "onboarding", description = "Starter configuration for new homes"),
+        (name = "priors", description = "Editable base rates with guardrails and rollback"),
+        (name = "sharing", description = "Redacted snapshots for sharing outside the household"),
+        (name = "tracking", description = "Live per-person tracking state, learned movement paths and entity trust"),
+        (name = "escalation", description = "Escalation chains for incidents that bypass normal thresholds"),
+        (name = "automations", description = "Device automations triggered by alert decisions, with dry-run preview and history"),
+        (name = "vms", description = "NVR and VMS integrations that bookmark recordings on alert, with success tracking"),
+        (name = "central_station", description = "SIA DC-09 / Contact ID reporting to professional monitoring, with zone mapping and supervised heartbeats"),
+        (name = "devices", description = "Edge device signing keys"),
+        (name = "admin", description = "Operator administration used by novictl"),
+        (name = "notifications", description = "Notification preferences, templates, SMS and email"),
+        (name = "analytics", description = "Clustering, what-if replays and learned weights"),
+        (name = "webhooks", description = "Webhook endpoints and delivery log"),
+        (name = "visitor-tokens", description = "Time-boxed visitor access codes"),
+        (name = "billing", description = "Usage export for billing and per-account API usage"),
+    ),
+)]
+pub struct ApiDoc;
+
+// JWT bearer tokens, checked by the AuthUser extractor
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+/// Swagger UI plus the JSON document, ready to merge into the router
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new(DOCS_PATH).url(SPEC_PATH, ApiDoc::openapi())
+}
@@ -0,0 +1,128 @@
+//! Cursor pagination for list routes
+//!
+//! List routes take the same query parameters: `limit`, an opaque `cursor`
+//! from the previous page, a `since`/`until` time range, a minimum severity,
+//! and a sort field and order. Cursors are keyset positions (sort key plus a
+//! tie-breaking id) rather than offsets, so pages stay stable while new
+//! incidents arrive.
+use axum::http::StatusCode;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use utoipa::{IntoParams, ToSchema};
+use crate::delivery::NotificationSeverity;
+use super::incidents::{EventRow, IncidentRow};
+
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    #[default]
+    Time,
+    Probability,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    pub since: Option<DateTime<Utc>>, // Inclusive
+    pub until: Option<DateTime<Utc>>, // Exclusive
+    pub min_severity: Option<NotificationSeverity>,
+    #[serde(default)]
+    pub sort: SortField,
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+impl ListQuery {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    /// Whether a unix timestamp falls in the requested range
+    pub fn in_range(&self, ts: f64) -> bool {
+        let ts = ts as i64;
+        self.since.map_or(true, |s| ts >= s.timestamp()) && self.until.map_or(true, |u| ts < u.timestamp())
+    }
+
+    pub fn meets_severity(&self, severity: Option<NotificationSeverity>) -> bool {
+        match self.min_severity {
+            None => true,
+            Some(min) => severity.is_some_and(|s| s >= min),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[aliases(IncidentPage = Page<IncidentRow>, EventPage = Page<EventRow>)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>, // None on the last page
+    pub total: usize,                // Rows matching the filters, across all pages
+}
+
+// Position after the last row of a page; the sort is recorded so a cursor
+// cannot be replayed against a different ordering
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: SortField,
+    order: SortOrder,
+    key: f64,
+    id: String,
+}
+
+fn encode_cursor(cursor: &Cursor) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(cursor).unwrap_or_default())
+}
+
+fn decode_cursor(raw: &str, query: &ListQuery) -> Result<Cursor, StatusCode> {
+    let bytes = URL_SAFE_NO_PAD.decode(raw).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let cursor: Cursor = serde_json::from_slice(&bytes).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if cursor.sort != query.sort || cursor.order != query.order {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(cursor)
+}
+
+/// Sort already-filtered rows and cut the page after the cursor; `key` gives
+/// each row's value for the requested sort field and a unique id
+pub fn paginate<T>(mut rows: Vec<T>, query: &ListQuery, key: impl Fn(&T, SortField) -> (f64, String)) -> Result<Page<T>, StatusCode> {
+    let total = rows.len();
+    let compare = |a: &(f64, String), b: &(f64, String)| {
+        let ordering = a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal).then_with(|| a.1.cmp(&b.1));
+        match query.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    };
+    rows.sort_by(|a, b| compare(&key(a, query.sort), &key(b, query.sort)));
+
+    if let Some(raw) = &query.cursor {
+        let cursor = decode_cursor(raw, query)?;
+        let position = (cursor.key, cursor.id);
+        rows.retain(|row| compare(&key(row, query.sort), &position) == Ordering::Greater);
+    }
+
+    let limit = query.limit();
+    let has_more = rows.len() > limit;
+    rows.truncate(limit);
+    let next_cursor = rows.last().filter(|_| has_more).map(|last| {
+        let (key, id) = key(last, query.sort);
+        encode_cursor(&Cursor { sort: query.sort, order: query.order, key, id })
+    });
+
+    Ok(Page { items: rows, next_cursor, total })
+}
@@ -0,0 +1,164 @@
+//! Prior editor API
+//!
+//! Advanced users read and replace a home's base-rate rules. Edits go through
+//! the guardrails in `thinking::prior_model`; `check` reports what an edit
+//! would trip without applying it, and every accepted edit can be rolled back.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use serde::{Deserialize, Serialize};
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::thinking::{CellFeedback, PriorEditError, PriorGuardrails, PriorRule, PriorVersion};
+
+#[derive(Debug, Serialize)]
+pub struct PriorsView {
+    pub current: PriorVersion,
+    pub guardrails: PriorGuardrails,
+    pub feedback: Vec<CellFeedback>, // Labeled outcomes per situation, what edits are checked against
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriorEditRequest {
+    pub rules: Vec<PriorRule>,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PriorCheckReport {
+    pub accepted: bool,
+    pub violations: Vec<String>,
+}
+
+/// A home's active prior rules, guardrails and the feedback edits are checked against
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/priors",
+    tag = "priors",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_priors(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<PriorsView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(PriorsView {
+        current: state.prior_model.current(&home_id),
+        guardrails: state.prior_model.guardrails().clone(),
+        feedback: state.prior_model.feedback(&home_id),
+    })))
+}
+
+/// Replace a home's prior rules; rejected with 422 if any guardrail trips
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/priors",
+    tag = "priors",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 422, description = "Edit violates a guardrail; see the check endpoint for details"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn edit_priors(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<PriorEditRequest>,
+) -> Result<ResponseJson<ApiResponse<PriorVersion>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let version = state.prior_model.edit(&home_id, request.rules, &user.user_id, &request.note).map_err(|e| {
+        tracing::info!("Prior edit for home {} by {} rejected: {}", home_id, user.user_id, e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+    Ok(ResponseJson(ApiResponse::success(version)))
+}
+
+/// Run the guardrails on proposed rules without applying them
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/priors/check",
+    tag = "priors",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn check_priors(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<PriorEditRequest>,
+) -> Result<ResponseJson<ApiResponse<PriorCheckReport>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let violations = state.prior_model.check(&home_id, &request.rules);
+    Ok(ResponseJson(ApiResponse::success(PriorCheckReport { accepted: violations.is_empty(), violations })))
+}
+
+/// Every accepted version of a home's prior rules, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/priors/history",
+    tag = "priors",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn prior_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<PriorVersion>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.prior_model.history(&home_id))))
+}
+
+/// Restore an earlier version's rules as a new version; version 0 clears all rules
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/priors/rollback/{version}",
+    tag = "priors",
+    params(("home_id" = String, Path, description = "Home id"), ("version" = u32, Path, description = "Version to restore")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown version"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn rollback_priors(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, version)): Path<(String, u32)>,
+) -> Result<ResponseJson<ApiResponse<PriorVersion>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let restored = state.prior_model.rollback(&home_id, version, &user.user_id).map_err(|e| match e {
+        PriorEditError::UnknownVersion(_) => StatusCode::NOT_FOUND,
+        PriorEditError::Guardrail(_) => StatusCode::UNPROCESSABLE_ENTITY,
+    })?;
+    Ok(ResponseJson(ApiResponse::success(restored)))
+}
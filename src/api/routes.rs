@@ -1,25 +1,816 @@
 use axum::Router;
 use sqlx::SqlitePool;
 use std::sync::Arc;
-use super::websocket::WebSocketManager;
+use super::local_socket::{self, LocalSocketConfig};
+use super::websocket::{self, WebSocketManager};
+use super::{automations, vms, central_station, events, webhooks, incidents, monitoring, billing, analytics, visitor_tokens, guests, notifications, vacation, household, cameras, devices, admin, onboarding, priors, sharing, tracking, escalation, openapi};
+use super::monitoring::MonitoringBoard;
+use crate::delivery::{AdminChannel, CentralStationExporter, EmailDispatcher, NotificationRouter, SiemConfig, SiemExporter, SmsDispatcher, VmsBookmarker, WebhookDispatcher};
+use crate::follow_up::{FollowUpConfig, FollowUpScheduler, SqliteFollowUpStore};
+use crate::automations::{execute_all, AutomationEngine, AutomationExecutor};
+use crate::probability_series::{ProbabilityHistory, ProbabilitySeriesConfig, SqliteProbabilitySeriesStore};
+use crate::prediction::PredictionScorer;
+use crate::metering::UsageMeter;
+use crate::overnight::{OvernightStorage, OvernightStorageFactory};
+use crate::pipeline::{EventPipeline, PipelineConfig};
+use crate::clock::{system_clock, Clock};
+use crate::thinking::{LlmBudget, LlmGuard, EscalationSurvivalModel, HandoffConfig, MoClusterIndex, OnlineWeightLearner, PriorGuardrails, PriorModelRegistry, SensorReliabilityConfig, SensorReliabilityModel, ThinkingAIConfig, WeightLearnerConfig};
+use crate::arming::ArmingRegistry;
+use crate::camera_registry::CameraRegistry;
+use crate::camera_health::CameraHealthRegistry;
+use crate::device_signing::DeviceKeyRegistry;
+use crate::escalation_rules::EscalationEngine;
+use crate::household::HouseholdRegistry;
+use crate::onboarding::HomeConfigStore;
+use crate::redaction::SnapshotSharing;
+use crate::tamper::TamperDetector;
+use crate::anti_spoofing::SpoofDetector;
+use crate::snapshot_priority::SnapshotPriorityPolicy;
+use crate::tracker::EntityTracker;
+use crate::zone_graph::ZoneGraph;
+use crate::knowledge_graph::{KnowledgeGraphConfig, KnowledgeGraphStore};
+use crate::activity_baseline::ActivityBaseline;
+use crate::entity_trust::{TrustConfig, TrustStore};
+use crate::annotations::{AnnotationConfig, AnnotationStore};
+use crate::adaptive_thresholds::{AdaptiveThresholdConfig, AdaptiveThresholds};
+use crate::edge_inference::EdgeInferenceEngine;
+use crate::embeddings::EmbeddingStore;
+use crate::encryption::{keyring_from_env, EncryptedStore};
+use crate::environment::{EnrichmentConfig, EnvironmentEnricher, OpenMeteoProvider};
+use crate::vacation::{PresenceActuator, VacationRegistry};
+use crate::visitor_tokens::{VisitorTokenConfig, VisitorTokenStore};
+use crate::guest_access::GuestRegistry;
+use crate::vps_client::{VpsApiClient, VpsPool, VpsPoolConfig};
+use crate::image_transcode::ImageTranscoder;
+use crate::idempotency::{IdempotencyStore, SqliteIdempotencyStore};
+use crate::counter_surveillance::{CounterSurveillanceSystem, ReconnaissanceAnalyzer};
+use crate::watchdog::{waiting_since, QueueLane, WaitingIncident, Watchdog};
+use tokio::sync::RwLock;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: SqlitePool,
     pub websocket_manager: Arc<WebSocketManager>,
+    pub webhook_dispatcher: Arc<WebhookDispatcher>,
+    pub notification_router: Arc<NotificationRouter>, // All outbound sends go through this
+    pub pipeline: Arc<RwLock<EventPipeline>>,
+    pub monitoring_board: Arc<MonitoringBoard>,
+    pub usage_meter: Arc<UsageMeter>,
+    pub mo_clusters: Arc<MoClusterIndex>,
+    pub visitor_tokens: Arc<VisitorTokenStore>,
+    pub guests: Arc<GuestRegistry>, // Time-boxed guest profiles with expected windows
+    pub follow_ups: Arc<FollowUpScheduler>,
+    pub vacations: Arc<VacationRegistry>,
+    pub household: Arc<HouseholdRegistry>,
+    pub arming: Arc<ArmingRegistry>,
+    pub cameras: Arc<CameraRegistry>,
+    pub devices: Arc<DeviceKeyRegistry>,
+    pub home_configs: Arc<HomeConfigStore>,
+    pub prior_model: Arc<PriorModelRegistry>,
+    pub sharing: Arc<SnapshotSharing>, // Resident faces, privacy zones and redacted share links
+    pub tracker: Arc<EntityTracker>,
+    pub zone_graph: Arc<ZoneGraph>, // Learned moves between each home's zones
+    pub knowledge_graph: Arc<KnowledgeGraphStore>, // Who was seen where and with whom, per home
+    pub activity_baseline: Arc<ActivityBaseline>, // Usual events per zone and hour of the week
+    pub trust: Arc<TrustStore>, // Decaying per-person trust, pinned or revoked by homeowners
+    pub annotations: Arc<AnnotationStore>, // Household notes and tags on incidents, and the watchlist
+    pub escalation: Arc<EscalationEngine>, // Decision override rules and per-home escalation chains
+    pub tamper: Arc<TamperDetector>,
+    pub camera_health: Arc<CameraHealthRegistry>, // Registered cameras, their ingest tokens and liveness
+    pub llm_budget: Arc<LlmBudget>, // Daily LLM call and token budgets, per home and global
+    pub llm_guard: Arc<LlmGuard>, // LLM kill switches, input filtering and redaction, output checks
+    pub thresholds: Arc<AdaptiveThresholds>, // Per-home alert threshold versions, learned from labels
+    pub probability_history: Arc<ProbabilityHistory>, // Each incident's probability after every assessment, for charts
+    pub prediction_scoring: Arc<PredictionScorer>, // Quoted probabilities scored against outcomes, and weekly report cards
+    pub automations: Arc<AutomationEngine>, // Household device automations triggered by alert decisions
+    pub vms: Arc<VmsBookmarker>, // NVR and VMS integrations that bookmark alerting cameras
+    pub central_station: Arc<CentralStationExporter>, // SIA DC-09 / Contact ID reporting to professional monitoring
+    pub watchdog: Arc<Watchdog>, // Stuck incidents, stalled queues and missed background ticks
+    pub clock: Arc<dyn Clock>, // "Now" for the pipeline and every scheduled job
+    pub admin_alerts: Arc<AdminChannel>, // Self-health alerts for operators, never households
 }
 
 impl AppState {
     pub fn new(db_pool: SqlitePool) -> Self {
+        Self::with_clock(db_pool, system_clock())
+    }
+
+    /// State whose pipeline and background jobs read the time from `clock`
+    pub fn with_clock(db_pool: SqlitePool, clock: Arc<dyn Clock>) -> Self {
+        let usage_meter = Arc::new(UsageMeter::new());
+        let mo_clusters = Arc::new(MoClusterIndex::default());
+        let guests = Arc::new(GuestRegistry::default());
+        let webhook_dispatcher = Arc::new(WebhookDispatcher::default());
+        let notification_router = NotificationRouter::new()
+            .with_webhooks(webhook_dispatcher.clone())
+            .with_clock(clock.clone());
+        let notification_router = match SmsDispatcher::from_env() {
+            Some(sms) => notification_router.with_sms(Arc::new(sms)),
+            None => notification_router,
+        };
+        let notification_router = Arc::new(match EmailDispatcher::from_env() {
+            Some(email) => notification_router.with_email(Arc::new(email)),
+            None => notification_router,
+        });
+        let follow_ups = Arc::new(FollowUpScheduler::with_store(
+            FollowUpConfig::default(),
+            Arc::new(SqliteFollowUpStore::new(db_pool.clone())),
+        ));
+        let probability_history = Arc::new(ProbabilityHistory::new(
+            ProbabilitySeriesConfig::default(),
+            Arc::new(SqliteProbabilitySeriesStore::new(db_pool.clone())),
+        ));
+        let prediction_scoring = Arc::new(PredictionScorer::default());
+        let automations = Arc::new(AutomationEngine::default());
+        let vms = Arc::new(VmsBookmarker::default());
+        let central_station = Arc::new(CentralStationExporter::default());
+        let vacations = Arc::new(VacationRegistry::default());
+        let household = Arc::new(HouseholdRegistry::default());
+        let arming = Arc::new(ArmingRegistry::default());
+        let cameras = Arc::new(CameraRegistry::default());
+        let devices = Arc::new(DeviceKeyRegistry::default());
+        let data_dir = std::path::PathBuf::from(std::env::var("NOVIN_DATA_DIR").unwrap_or_else(|_| "data".to_string()));
+        let visitor_tokens = Arc::new(VisitorTokenStore::persistent(VisitorTokenConfig::default(), data_dir.join("visitor_tokens")).unwrap_or_else(|e| {
+            tracing::warn!("Visitor tokens will not be persisted: {}", e);
+            VisitorTokenStore::default()
+        }));
+        let home_configs = Arc::new(HomeConfigStore::persistent(data_dir.join("homes")).unwrap_or_else(|e| {
+            tracing::warn!("Home configs will not be persisted: {}", e);
+            HomeConfigStore::default()
+        }));
+        let prior_model = Arc::new(PriorModelRegistry::new(PriorGuardrails::default(), ThinkingAIConfig::default().prior_logit)
+            .with_clock(clock.clone()));
+        let tracker = Arc::new(EntityTracker::default());
+        let zone_graph = Arc::new(ZoneGraph::default());
+        let activity_baseline = Arc::new(ActivityBaseline::default());
+        let knowledge_graph = Arc::new(KnowledgeGraphStore::persistent(KnowledgeGraphConfig::default(), data_dir.join("knowledge_graph")).unwrap_or_else(|e| {
+            tracing::warn!("Knowledge graphs will not be persisted: {}", e);
+            KnowledgeGraphStore::default()
+        }));
+        let trust = Arc::new(TrustStore::persistent(TrustConfig::default(), data_dir.join("trust")).unwrap_or_else(|e| {
+            tracing::warn!("Entity trust will not be persisted: {}", e);
+            TrustStore::default()
+        }));
+        let annotations = Arc::new(AnnotationStore::persistent(AnnotationConfig::default(), data_dir.join("annotations")).unwrap_or_else(|e| {
+            tracing::warn!("Incident annotations will not be persisted: {}", e);
+            AnnotationStore::default()
+        }));
+        let thresholds = Arc::new(AdaptiveThresholds::persistent(
+            AdaptiveThresholdConfig::default(),
+            ThinkingAIConfig::default().alert_threshold_logit,
+            data_dir.join("thresholds"),
+        ).unwrap_or_else(|e| {
+            tracing::warn!("Alert thresholds will not be persisted: {}", e);
+            AdaptiveThresholds::new(AdaptiveThresholdConfig::default(), ThinkingAIConfig::default().alert_threshold_logit)
+        }));
+        let escalation = Arc::new(EscalationEngine::default());
+        let tamper = Arc::new(TamperDetector::default());
+        let camera_health = Arc::new(CameraHealthRegistry::default());
+        let llm_budget = Arc::new(LlmBudget::default());
+        if !crate::thinking::set_llm_budget(llm_budget.clone()) {
+            tracing::warn!("An LLM budget is already installed; this state's budget will not be enforced");
+        }
+        let llm_guard = Arc::new(LlmGuard::from_env());
+        if !crate::thinking::set_llm_guard(llm_guard.clone()) {
+            tracing::warn!("An LLM guard is already installed; this state's kill switches and redactions will not apply");
+        }
+        let websocket_manager = Arc::new(WebSocketManager::new());
+        // Incident state changes go out as webhooks, WebSocket updates and central station restores
+        let pipeline = Self::default_pipeline(
+            usage_meter.clone(),
+            mo_clusters.clone(),
+            visitor_tokens.clone(),
+            notification_router.clone(),
+            follow_ups.clone(),
+            OvernightStorageFactory::create_sqlite(db_pool.clone()),
+            Arc::new(SqliteIdempotencyStore::new(db_pool.clone())),
+        )
+        .with_lifecycle_hook(webhook_dispatcher.clone())
+        .with_lifecycle_hook(websocket_manager.clone())
+        .with_lifecycle_hook(central_station.clone())
+        .with_vacation_mode(vacations.clone())
+        .with_household(household.clone())
+        .with_arming(arming.clone())
+        .with_camera_registry(cameras.clone())
+        .with_device_signing(devices.clone())
+        .with_prior_model(prior_model.clone())
+        .with_entity_tracker(tracker.clone())
+        .with_zone_graph(zone_graph.clone())
+        .with_knowledge_graph(knowledge_graph.clone())
+        .with_activity_baseline(activity_baseline.clone())
+        .with_entity_trust(trust.clone())
+        .with_guest_access(guests.clone())
+        .with_annotations(annotations.clone())
+        .with_escalation_rules(escalation.clone())
+        .with_tamper_detection(tamper.clone())
+        .with_anti_spoofing(Arc::new(SpoofDetector::default()))
+        .with_snapshot_priority(Arc::new(SnapshotPriorityPolicy::default()))
+        .with_probability_history(probability_history.clone())
+        .with_prediction_scoring(prediction_scoring.clone())
+        .with_automations(automations.clone(), webhook_dispatcher.clone())
+        .with_vms_bookmarks(vms.clone())
+        .with_central_station(central_station.clone())
+        .with_camera_health(camera_health.clone())
+        .with_adaptive_thresholds(thresholds.clone())
+        .with_clock(clock.clone());
+        // Snapshots are only kept on disk, sealed per home, when a master key is configured
+        let encrypted = keyring_from_env(data_dir.join("keys"))
+            .and_then(|keyring| keyring.map(|k| EncryptedStore::new(k, data_dir.join("images"))).transpose());
+        let pipeline = match encrypted {
+            Ok(Some(store)) => pipeline.with_encryption(Arc::new(store)),
+            Ok(None) => pipeline,
+            Err(e) => {
+                tracing::warn!("Snapshots will not be stored on disk: {}", e);
+                pipeline
+            }
+        };
+        // Outages open the VPS circuits before events have to time out against them
+        pipeline.start_vps_health_pollers();
         Self { 
             db_pool, 
-            websocket_manager: Arc::new(WebSocketManager::new()) 
+            websocket_manager,
+            webhook_dispatcher,
+            pipeline: Arc::new(RwLock::new(pipeline)),
+            notification_router,
+            monitoring_board: Arc::new(MonitoringBoard::new()),
+            usage_meter,
+            mo_clusters,
+            visitor_tokens,
+            guests,
+            follow_ups,
+            vacations,
+            household,
+            arming,
+            cameras,
+            devices,
+            home_configs,
+            prior_model,
+            sharing: Arc::new(SnapshotSharing::default()),
+            tracker,
+            zone_graph,
+            knowledge_graph,
+            activity_baseline,
+            trust,
+            annotations,
+            escalation,
+            tamper,
+            camera_health,
+            llm_budget,
+            llm_guard,
+            thresholds,
+            probability_history,
+            prediction_scoring,
+            automations,
+            vms,
+            central_station,
+            watchdog: Arc::new(Watchdog::default()),
+            clock,
+            admin_alerts: Arc::new(AdminChannel::from_env()),
         }
     }
+
+    /// Start periodic jobs; call once from inside the runtime
+    pub fn spawn_background_jobs(&self) -> Vec<tokio::task::JoinHandle<()>> {
+        let mut jobs = vec![
+            self.mo_clusters.clone().spawn_reclustering(std::time::Duration::from_secs(3600), self.clock.clone()),
+            self.spawn_follow_ups(std::time::Duration::from_secs(15)),
+            self.spawn_incident_expiry(std::time::Duration::from_secs(60)),
+            self.spawn_escalations(std::time::Duration::from_secs(10)),
+            self.spawn_deferred_narratives(std::time::Duration::from_secs(300)),
+            self.spawn_presence_simulation(std::time::Duration::from_secs(60)),
+            self.spawn_automations(std::time::Duration::from_secs(5)),
+            self.spawn_prediction_scoring(std::time::Duration::from_secs(3600)),
+            self.spawn_central_station_heartbeats(std::time::Duration::from_secs(30)),
+            self.spawn_home_config_restore(),
+            self.spawn_watchdog(std::time::Duration::from_secs(30)),
+        ];
+        jobs.extend(self.spawn_local_socket());
+        jobs
+    }
+
+    // Settle escalation windows that ran out and close each period's model report card
+    fn spawn_prediction_scoring(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let scorer = self.prediction_scoring.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("prediction_scoring", every);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                watchdog.tick("prediction_scoring");
+                let now = clock.now();
+                scorer.expire(now);
+                if let Some(card) = scorer.roll_report_card(now) {
+                    tracing::info!("Model report card for {} to {} closed", card.period_start, card.period_end);
+                }
+            }
+        })
+    }
+
+    // Serve the ingest and alert routes on the local UNIX socket when NOVIN_LOCAL_SOCKET is set
+    fn spawn_local_socket(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let config = match LocalSocketConfig::from_env() {
+            Ok(config) => config?,
+            Err(e) => {
+                tracing::error!("Local API socket disabled: {}", e);
+                return None;
+            }
+        };
+        let listener = match local_socket::listen(&config) {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!("Could not open local API socket {}: {}", config.path.display(), e);
+                return None;
+            }
+        };
+        let router = local_socket::local_routes(self.clone());
+        Some(tokio::spawn(local_socket::serve(listener, config, router)))
+    }
+
+    // Restore persisted Wait timers, then settle due ones on every tick
+    fn spawn_follow_ups(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let follow_ups = self.follow_ups.clone();
+        let pipeline = self.pipeline.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("follow_ups", every);
+        tokio::spawn(async move {
+            match follow_ups.restore().await {
+                Ok(restored) => tracing::info!("Restored {} pending follow-up(s)", restored),
+                Err(e) => tracing::warn!("Could not restore follow-ups: {}", e),
+            }
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                watchdog.tick("follow_ups");
+                let settled = pipeline.write().await.run_follow_ups(clock.now()).await;
+                if settled > 0 {
+                    tracing::info!("Settled {} waiting incident(s)", settled);
+                }
+            }
+        })
+    }
+
+    // Re-apply saved onboarding configs once at startup
+    fn spawn_home_config_restore(&self) -> tokio::task::JoinHandle<()> {
+        let home_configs = self.home_configs.clone();
+        let pipeline = self.pipeline.clone();
+        tokio::spawn(async move {
+            let mut pipeline = pipeline.write().await;
+            for config in home_configs.all() {
+                if let Err(e) = pipeline.apply_home_config(&config).await {
+                    tracing::warn!("Could not apply saved config for home {}: {}", config.home_id, e);
+                }
+            }
+        })
+    }
+
+    // Run delayed automation actions that were not cancelled by an acknowledgement
+    fn spawn_automations(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let automations = self.automations.clone();
+        let executor: Arc<dyn AutomationExecutor> = self.webhook_dispatcher.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("automations", every);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                watchdog.tick("automations");
+                let due = automations.due(clock.now());
+                execute_all(&automations, executor.as_ref(), due).await;
+            }
+        })
+    }
+
+    // Switch presence-simulation devices for homes on vacation via their automation webhooks
+    fn spawn_presence_simulation(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let vacations = self.vacations.clone();
+        let actuator: Arc<dyn PresenceActuator> = self.webhook_dispatcher.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("presence_simulation", every);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            let mut since = clock.now();
+            loop {
+                ticker.tick().await;
+                watchdog.tick("presence_simulation");
+                let now = clock.now();
+                for action in vacations.due_actions(since, now) {
+                    actuator.actuate(&action).await;
+                }
+                since = now;
+            }
+        })
+    }
+
+    // Expire quiet incidents even when no new events arrive for their home, and drop
+    // lapsed guest profiles, probability points, dedup records and knowledge graph
+    // entries past their retention
+    fn spawn_incident_expiry(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let pipeline = self.pipeline.clone();
+        let guests = self.guests.clone();
+        let knowledge_graph = self.knowledge_graph.clone();
+        let probability_history = self.probability_history.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("incident_expiry", every);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                watchdog.tick("incident_expiry");
+                pipeline.write().await.expire_incidents(clock.now());
+                guests.prune_expired(clock.now());
+                knowledge_graph.prune(clock.now());
+                if let Err(e) = probability_history.prune(clock.now()).await {
+                    tracing::warn!("Could not prune probability series: {}", e);
+                }
+                if let Err(e) = pipeline.read().await.purge_dedup_records().await {
+                    tracing::warn!("Could not purge dedup records: {}", e);
+                }
+            }
+        })
+    }
+
+    // Send escalation chain steps as their delays pass
+    fn spawn_escalations(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let pipeline = self.pipeline.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("escalations", every);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                watchdog.tick("escalations");
+                let sent = pipeline.read().await.run_escalations(clock.now());
+                if sent > 0 {
+                    tracing::info!("Sent {} escalation step(s)", sent);
+                }
+            }
+        })
+    }
+
+    // Generate narratives deferred for LLM budget, outside the pipeline lock
+    fn spawn_deferred_narratives(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let pipeline = self.pipeline.clone();
+        let clock = self.clock.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("deferred_narratives", every);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                watchdog.tick("deferred_narratives");
+                let narratives = crate::thinking::run_deferred_narratives(20, clock.now()).await;
+                if narratives.is_empty() {
+                    continue;
+                }
+                let attached = pipeline.write().await.attach_narratives(narratives);
+                tracing::info!("Attached {} deferred narrative(s)", attached);
+            }
+        })
+    }
+
+    // Send supervision heartbeats to every home's central station as they fall due
+    fn spawn_central_station_heartbeats(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let exporter = self.central_station.clone();
+        let watchdog = self.watchdog.clone();
+        watchdog.register_job("central_station_heartbeats", every);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                watchdog.tick("central_station_heartbeats");
+                let now = clock.now();
+                for station in exporter.due_heartbeats(now) {
+                    exporter.heartbeat(&station, now).await;
+                }
+            }
+        })
+    }
+
+    // Check for stuck incidents, stalled queue lanes and silent jobs, alerting operators
+    fn spawn_watchdog(&self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clock.clone();
+        let pipeline = self.pipeline.clone();
+        let follow_ups = self.follow_ups.clone();
+        let llm_budget = self.llm_budget.clone();
+        let watchdog = self.watchdog.clone();
+        let admin_alerts = self.admin_alerts.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                let now = clock.now();
+                let mut stuck = Vec::new();
+                {
+                    let pipeline = pipeline.read().await;
+                    for home in pipeline.homes() {
+                        let pending = follow_ups.pending(&home).await;
+                        for incident in pipeline.home_incidents(&home) {
+                            let Some(since) = waiting_since(&incident) else { continue };
+                            stuck.push(WaitingIncident {
+                                home_id: home.clone(),
+                                incident_id: incident.id,
+                                waiting_since: since,
+                                follow_up_pending: pending.iter().any(|f| f.incident_id == incident.id),
+                            });
+                        }
+                    }
+                }
+                let config = watchdog.config();
+                let due = follow_ups.due(now).await;
+                let mut lanes = vec![QueueLane {
+                    name: "follow_ups".to_string(),
+                    depth: due.len(),
+                    oldest_at: due.first().map(|f| f.due_at),
+                    max_age: config.follow_up_lag,
+                }];
+                lanes.extend(llm_budget.deferred_lanes().into_iter().map(|(priority, depth, oldest)| QueueLane {
+                    name: format!("narratives_{}", serde_json::to_value(priority).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default()),
+                    depth,
+                    oldest_at: Some(oldest),
+                    max_age: config.narrative_lane_max_age,
+                }));
+                for alert in watchdog.check(std::time::Instant::now(), now, &stuck, &lanes) {
+                    admin_alerts.deliver(&alert).await;
+                }
+            }
+        })
+    }
+
+    fn default_pipeline(
+        usage_meter: Arc<UsageMeter>,
+        mo_clusters: Arc<MoClusterIndex>,
+        visitor_tokens: Arc<VisitorTokenStore>,
+        notification_router: Arc<NotificationRouter>,
+        follow_ups: Arc<FollowUpScheduler>,
+        overnight_storage: Arc<dyn OvernightStorage>,
+        idempotency_store: Arc<dyn IdempotencyStore>,
+    ) -> EventPipeline {
+        let vps_url = std::env::var("VPS_API_URL")
+            .unwrap_or_else(|_| "https://api.vps.example.com".to_string());
+        // Learned weights survive restarts when a path is configured
+        let weights_path = std::env::var("LLR_WEIGHTS_PATH").ok().map(std::path::PathBuf::from);
+        let learner = weights_path.as_deref()
+            .and_then(|path| OnlineWeightLearner::load(WeightLearnerConfig::default(), path)
+                .map_err(|e| tracing::warn!("Could not load learned weights from {}: {}", path.display(), e))
+                .ok())
+            .unwrap_or_default();
+        let reliability_path = std::env::var("SENSOR_RELIABILITY_PATH").ok().map(std::path::PathBuf::from);
+        let reliability = reliability_path.as_deref()
+            .and_then(|path| SensorReliabilityModel::load(SensorReliabilityConfig::default(), path)
+                .map_err(|e| tracing::warn!("Could not load sensor reliability from {}: {}", path.display(), e))
+                .ok())
+            .unwrap_or_default();
+        let surveillance = Arc::new(CounterSurveillanceSystem::new());
+        // VPS_ENABLED=false analyzes every event with the on-device models instead
+        let vps_enabled = !std::env::var("VPS_ENABLED").is_ok_and(|v| v.eq_ignore_ascii_case("false"));
+        let pipeline = EventPipeline::new(PipelineConfig { vps_enabled, ..PipelineConfig::default() }, VpsApiClient::new(vps_url))
+            .with_usage_meter(usage_meter)
+            .with_mo_clustering(mo_clusters)
+            .with_visitor_tokens(visitor_tokens)
+            // VPS appearance and gait vectors link detections to earlier entities without face recognition
+            .with_embedding_store(Arc::new(EmbeddingStore::default()))
+            // Push thumbnails and dashboard previews are sized and encoded per channel
+            .with_image_transcoder(Arc::new(ImageTranscoder::default()))
+            .with_notification_router(notification_router)
+            .with_weight_learner(Arc::new(learner), weights_path)
+            .with_sensor_reliability(Arc::new(reliability), reliability_path)
+            .with_escalation_survival(Arc::new(EscalationSurvivalModel::default()))
+            // Events carrying positions feed loitering and repeated-pass detection
+            .with_counter_surveillance(surveillance.clone())
+            .with_follow_up_scheduler(follow_ups)
+            // Retried submissions are recognised across restarts, not just within this process
+            .with_idempotency_store(idempotency_store);
+        // Mid-band incidents get a reconnaissance second opinion from the same trajectories,
+        // weighed by darkness at the home
+        let analyzer = ReconnaissanceAnalyzer::new(surveillance).with_calendars(pipeline.home_calendars());
+        let pipeline = pipeline.with_adversarial_handoff(Arc::new(analyzer), HandoffConfig::default());
+        // Fog, heavy rain and darkness at located homes discount camera evidence; NOVIN_WEATHER=off disables
+        let pipeline = match EnrichmentConfig::from_env() {
+            Ok(Some(config)) => {
+                let provider = config.weather_url.clone()
+                    .map(OpenMeteoProvider::with_base_url)
+                    .unwrap_or_default();
+                pipeline.with_environment_enricher(Arc::new(EnvironmentEnricher::new(Arc::new(provider), config)))
+            }
+            Ok(None) => pipeline,
+            Err(e) => {
+                tracing::warn!("Ignoring weather enrichment config: {}", e);
+                pipeline
+            }
+        };
+        // On-device models named in the NOVIN_CONFIG daemon config, warmed up before the first event
+        let config_path = std::env::var("NOVIN_CONFIG").ok().map(std::path::PathBuf::from);
+        let pipeline = match config_path.as_deref().map(EdgeInferenceEngine::from_config_file).transpose().map(Option::flatten) {
+            Ok(Some(engine)) => pipeline.with_edge_inference(Arc::new(engine)),
+            Ok(None) => {
+                if !vps_enabled {
+                    tracing::warn!("VPS disabled but no edge models configured; events will fail analysis");
+                }
+                pipeline
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring edge inference config: {}", e);
+                pipeline
+            }
+        };
+        // Weighted, region-aware VPS endpoints, configured by VPS_ENDPOINTS; otherwise the single VPS_API_URL
+        let pipeline = match VpsPoolConfig::from_env() {
+            Ok(Some(config)) => {
+                tracing::info!("Routing VPS traffic across {} endpoints", config.endpoints.len());
+                pipeline.with_vps_pool(VpsPool::new(config))
+            }
+            Ok(None) => pipeline,
+            Err(e) => {
+                tracing::warn!("Ignoring VPS pool config: {}", e);
+                pipeline
+            }
+        };
+        // Neighborhood threat sharing, configured by NOVIN_FEDERATION_KEY; homes still opt in at onboarding
+        let pipeline = match crate::federation::FederationConfig::from_env().and_then(|c| c.map(crate::federation::FederationHub::new).transpose()) {
+            Ok(Some(hub)) => pipeline.with_federation(Arc::new(hub)),
+            Ok(None) => pipeline,
+            Err(e) => {
+                tracing::warn!("Ignoring federation config: {}", e);
+                pipeline
+            }
+        };
+        // Enterprise SIEM export, configured by NOVIN_SIEM_HOST; the exporter's task drains its queue
+        let pipeline = match SiemConfig::from_env() {
+            Ok(Some(config)) => {
+                tracing::info!("Exporting incidents to SIEM collector {}:{}", config.host, config.port);
+                pipeline.with_siem_exporter(Arc::new(SiemExporter::start(config)))
+            }
+            Ok(None) => pipeline,
+            Err(e) => {
+                tracing::warn!("Ignoring SIEM config: {}", e);
+                pipeline
+            }
+        };
+        // Fault injection for resilience runs, configured by NOVIN_CHAOS
+        #[cfg(feature = "chaos")]
+        let pipeline = match crate::chaos::ChaosConfig::from_env().and_then(|c| c.map(crate::chaos::ChaosInjector::new).transpose()) {
+            Ok(Some(chaos)) => {
+                tracing::warn!("Chaos fault injection enabled: {:?}", chaos.config());
+                pipeline.with_chaos(Arc::new(chaos))
+            }
+            Ok(None) => pipeline,
+            Err(e) => {
+                tracing::warn!("Ignoring chaos config: {}", e);
+                pipeline
+            }
+        };
+        // Custom stage middleware, by registered name, configured by NOVIN_STAGE_MIDDLEWARE
+        let names: Vec<String> = std::env::var("NOVIN_STAGE_MIDDLEWARE").unwrap_or_default()
+            .split(',').map(str::trim).filter(|n| !n.is_empty()).map(String::from).collect();
+        let pipeline = match crate::pipeline_stages::MiddlewareRegistry::default().build(&names) {
+            Ok(middleware) => middleware.into_iter().fold(pipeline, |p, m| p.with_middleware(m)),
+            Err(e) => {
+                tracing::warn!("Ignoring stage middleware config: {}", e);
+                pipeline
+            }
+        };
+        // Storm protection: sample low-priority cameras when a home's event rate spikes
+        let pipeline = match crate::load_shedding::LoadSheddingConfig::from_env() {
+            Ok(Some(config)) => pipeline.with_load_shedding(Arc::new(crate::load_shedding::LoadShedder::new(config))),
+            Ok(None) => pipeline,
+            Err(e) => {
+                tracing::warn!("Using default load shedding: {}", e);
+                pipeline.with_load_shedding(Arc::new(crate::load_shedding::LoadShedder::default()))
+            }
+        };
+        pipeline.with_overnight_storage(overnight_storage)
+    }
 }
 
-pub fn create_routes(_state: AppState) -> Router {
-    use axum::routing::get;
+pub fn create_routes(state: AppState) -> Router {
+    use axum::routing::{get, post, put, delete};
     Router::new()
         .route("/api/system/health", get(|| async { "OK" }))
+        .route("/api/ws", get(websocket::websocket_handler))
+        .route("/api/homes/:home_id/webhooks", get(webhooks::list_webhooks).post(webhooks::register_webhook))
+        .route("/api/homes/:home_id/webhooks/:endpoint_id", delete(webhooks::delete_webhook))
+        .route("/api/homes/:home_id/webhook-deliveries", get(webhooks::get_delivery_log))
+        .route("/api/homes/:home_id/incidents", get(incidents::list_incidents))
+        .route("/api/homes/:home_id/events", get(incidents::list_events))
+        .route("/api/homes/:home_id/high-activity", get(incidents::high_activity))
+        .route("/api/homes/:home_id/incidents/:incident_id/evidence-bundle", get(incidents::export_evidence_bundle))
+        .route("/api/homes/:home_id/incidents/:incident_id/probability", get(incidents::probability_series))
+        .route("/api/homes/:home_id/incidents/:incident_id/feedback", post(incidents::submit_feedback))
+        .route("/api/homes/:home_id/incidents/:incident_id/close", post(incidents::close_incident))
+        .route("/api/homes/:home_id/incidents/:incident_id/dismiss", post(incidents::dismiss_incident))
+        .route("/api/homes/:home_id/incidents/:incident_id/merge", post(incidents::merge_incidents))
+        .route("/api/homes/:home_id/incidents/:incident_id/split", post(incidents::split_incident))
+        .route("/api/homes/:home_id/incidents/:incident_id/annotations", get(incidents::list_annotations).post(incidents::annotate_incident))
+        .route("/api/homes/:home_id/annotations", get(incidents::search_annotations))
+        .route("/api/homes/:home_id/annotations/:annotation_id", delete(incidents::delete_annotation))
+        .route("/api/monitoring/incidents", get(monitoring::list_active_incidents))
+        .route("/api/monitoring/incidents/:home_id/:incident_id/claim", post(monitoring::claim_incident))
+        .route("/api/monitoring/incidents/:home_id/:incident_id/release", post(monitoring::release_incident))
+        .route("/api/monitoring/incidents/:home_id/:incident_id/acknowledge", post(monitoring::acknowledge_incident))
+        .route("/api/monitoring/incidents/:home_id/:incident_id/notes", post(monitoring::add_operator_note))
+        .route("/api/monitoring/incidents/:home_id/:incident_id/dispatch", put(monitoring::update_dispatch_status))
+        .route("/api/monitoring/incidents/:home_id/:incident_id/disposition", post(monitoring::record_disposition))
+        .route("/api/billing/usage", get(billing::export_usage))
+        .route("/api/usage", get(billing::api_usage))
+        .route("/api/homes/:home_id/analytics/mo-clusters", get(analytics::list_mo_clusters))
+        .route("/api/homes/:home_id/analytics/threat-vectors", get(analytics::threat_vectors))
+        .route("/api/homes/:home_id/analytics/what-if", post(analytics::what_if_thresholds))
+        .route("/api/homes/:home_id/analytics/channel-weights", get(analytics::channel_weights))
+        .route("/api/homes/:home_id/analytics/sensor-reliability", get(analytics::sensor_reliability))
+        .route("/api/homes/:home_id/analytics/thresholds", get(analytics::threshold_history))
+        .route("/api/homes/:home_id/analytics/thresholds/reset", post(analytics::reset_thresholds))
+        .route("/api/homes/:home_id/notification-preferences", get(notifications::get_home_preferences).put(notifications::set_home_preferences))
+        .route("/api/homes/:home_id/notification-preferences/:user_id", get(notifications::get_user_preferences).put(notifications::set_user_preferences).delete(notifications::clear_user_preferences))
+        .route("/api/homes/:home_id/notification-templates", get(notifications::get_templates).put(notifications::set_templates))
+        .route("/api/notification-templates", put(notifications::set_default_template))
+        .route("/api/homes/:home_id/sms", get(notifications::get_sms))
+        .route("/api/homes/:home_id/sms/:user_id", put(notifications::set_phone_number).delete(notifications::remove_phone_number))
+        .route("/api/sms/status", post(notifications::sms_status_callback))
+        .route("/api/sms/inbound", post(notifications::sms_inbound_callback))
+        .route("/api/homes/:home_id/email/:user_id", put(notifications::set_email_address).delete(notifications::remove_email_address))
+        .route("/api/homes/:home_id/email", get(notifications::get_email))
+        .route("/api/email/feedback", post(notifications::email_feedback))
+        .route("/api/homes/:home_id/residents", get(household::list_residents).post(household::add_resident))
+        .route("/api/homes/:home_id/residents/:user_id", put(household::update_resident).delete(household::remove_resident))
+        .route("/api/homes/:home_id/residents/:user_id/presence", put(household::set_presence))
+        .route("/api/homes/:home_id/dwelling-state", get(household::dwelling_state))
+        .route("/api/homes/:home_id/cameras", get(cameras::list_pins))
+        .route("/api/homes/:home_id/cameras/:camera_id/pin", put(cameras::set_pin).delete(cameras::remove_pin))
+        .route("/api/homes/:home_id/cameras/tamper", get(cameras::tamper_alerts))
+        .route("/api/homes/:home_id/cameras/:camera_id/tamper-check", post(cameras::check_tamper))
+        .route("/api/homes/:home_id/cameras/:camera_id/tamper/clear", post(cameras::clear_tamper))
+        .route("/api/homes/:home_id/cameras/:camera_id/registration", put(cameras::register_camera).delete(cameras::unregister_camera))
+        .route("/api/homes/:home_id/cameras/health", get(cameras::camera_health))
+        .route("/api/ingest/cameras/:camera_id/events", post(events::ingest_camera_event))
+        .route("/api/events", post(events::submit_event))
+        .route("/api/homes/:home_id/onboarding", post(onboarding::onboard_home))
+        .route("/api/homes/:home_id/config", get(onboarding::get_home_config))
+        .route("/api/homes/:home_id/self-test", post(onboarding::run_self_test))
+        .route("/api/homes/:home_id/priors", get(priors::get_priors).put(priors::edit_priors))
+        .route("/api/homes/:home_id/cameras/:camera_id/privacy-zones", get(sharing::get_privacy_zones).put(sharing::set_privacy_zones))
+        .route("/api/homes/:home_id/residents/:user_id/faces", post(sharing::enroll_face).delete(sharing::remove_faces))
+        .route("/api/homes/:home_id/incidents/:incident_id/share", post(sharing::share_snapshot))
+        .route("/api/homes/:home_id/shares/:token", delete(sharing::revoke_share))
+        .route("/api/shared/:token", get(sharing::open_shared_snapshot))
+        .route("/api/homes/:home_id/tracks", get(tracking::home_tracks))
+        .route("/api/homes/:home_id/zone-graph", get(tracking::zone_graph))
+        .route("/api/homes/:home_id/knowledge-graph", get(tracking::knowledge_graph))
+        .route("/api/homes/:home_id/activity-baseline", get(tracking::activity_baseline))
+        .route("/api/homes/:home_id/trust", get(tracking::list_trust))
+        .route("/api/homes/:home_id/trust/audit", get(tracking::trust_audit))
+        .route("/api/homes/:home_id/trust/:entity_id", put(tracking::mark_trusted).delete(tracking::revoke_trust))
+        .route("/api/homes/:home_id/watchlist", get(tracking::list_watchlist))
+        .route("/api/homes/:home_id/watchlist/:entity_id", delete(tracking::unwatch))
+        .route("/api/homes/:home_id/escalation-chain", get(escalation::get_chain).put(escalation::set_chain))
+        .route("/api/homes/:home_id/escalations", get(escalation::active_escalations))
+        .route("/api/homes/:home_id/incidents/:incident_id/escalation/acknowledge", post(escalation::acknowledge_escalation))
+        .route("/api/homes/:home_id/automations", get(automations::list_automations).post(automations::create_automation))
+        .route("/api/homes/:home_id/automations/preview", post(automations::preview_automations))
+        .route("/api/homes/:home_id/automations/history", get(automations::automation_history))
+        .route("/api/homes/:home_id/automations/:automation_id", delete(automations::delete_automation))
+        .route("/api/homes/:home_id/incidents/:incident_id/automations/acknowledge", post(automations::acknowledge_automations))
+        .route("/api/homes/:home_id/vms", get(vms::list_integrations).post(vms::create_integration))
+        .route("/api/homes/:home_id/vms/history", get(vms::bookmark_history))
+        .route("/api/homes/:home_id/vms/:integration_id", delete(vms::delete_integration))
+        .route("/api/homes/:home_id/vms/:integration_id/test", post(vms::test_integration))
+        .route("/api/homes/:home_id/central-station", get(central_station::get_station).put(central_station::set_station).delete(central_station::delete_station))
+        .route("/api/homes/:home_id/central-station/history", get(central_station::station_history))
+        .route("/api/homes/:home_id/central-station/test", post(central_station::test_station))
+        .route("/api/homes/:home_id/priors/check", post(priors::check_priors))
+        .route("/api/homes/:home_id/priors/history", get(priors::prior_history))
+        .route("/api/homes/:home_id/priors/rollback/:version", post(priors::rollback_priors))
+        .route("/api/homes/:home_id/devices", get(devices::list_devices).post(devices::enroll_device))
+        .route("/api/homes/:home_id/devices/:device_id", delete(devices::revoke_device))
+        .route("/api/admin/metrics", get(admin::metrics))
+        .route("/api/admin/llm-usage", get(admin::llm_usage))
+        .route("/api/admin/llm-guard", get(admin::llm_guard))
+        .route("/api/admin/llm-guard/kill-switch", post(admin::llm_kill_switch))
+        .route("/api/admin/health-alerts", get(admin::health_alerts))
+        .route("/api/admin/question-value", get(admin::question_value))
+        .route("/api/admin/report-card", get(admin::report_card))
+        .route("/api/admin/report-cards", get(admin::report_cards))
+        .route("/api/admin/homes", get(admin::list_homes))
+        .route("/api/admin/homes/:home_id/morning-summary", post(admin::force_morning_summary))
+        .route("/api/admin/homes/:home_id/incidents/:incident_id/timeline", get(admin::incident_timeline_handler))
+        .route("/api/admin/homes/:home_id/webhooks/:endpoint_id/rotate-secret", post(admin::rotate_webhook_secret))
+        .route("/api/admin/events/replay", post(admin::replay_events))
+        .route("/api/admin/webhook-dead-letters", get(admin::list_dead_letters))
+        .route("/api/admin/webhook-dead-letters/flush", post(admin::flush_dead_letters))
+        .route("/api/homes/:home_id/vacation", get(vacation::get_vacation).put(vacation::set_vacation).delete(vacation::end_vacation))
+        .route("/api/homes/:home_id/visitor-tokens", get(visitor_tokens::list_tokens).post(visitor_tokens::issue_token))
+        .route("/api/homes/:home_id/visitor-tokens/verify", post(visitor_tokens::verify_code))
+        .route("/api/homes/:home_id/visitor-tokens/:token_id", get(visitor_tokens::get_token).delete(visitor_tokens::revoke_token))
+        .route("/api/homes/:home_id/guests", get(guests::list_guests).post(guests::create_guest))
+        .route("/api/homes/:home_id/guests/:guest_id", delete(guests::remove_guest))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), billing::meter_api_calls))
+        .with_state(state)
+        .merge(openapi::swagger_ui())
 }
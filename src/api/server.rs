@@ -1,17 +1,62 @@
+//! Standalone API server
+//!
+//! Serves the full API router over plain HTTP by default. Self-hosted installs that expose the API
+//! directly can turn on TLS without a reverse proxy, either from certificate
+//! files they manage themselves (re-read hourly, so an external renewal is
+//! picked up) or through ACME: certificates for the configured domains are
+//! obtained from Let's Encrypt, cached on disk and renewed before they expire.
+//! ACME uses the TLS-ALPN-01 challenge, answered on the API port itself, so
+//! that port must be reachable as 443 from the internet.
+
 use axum::{
     routing::get,
     Router,
     response::Json,
 };
-use std::net::SocketAddr;
+use futures_util::StreamExt;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
 use tower_http::cors::CorsLayer;
 use serde_json::json;
 
+use super::routes::{create_routes, AppState};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ApiConfigError {
+    #[error("Invalid {name}: '{value}'")]
+    Invalid { name: &'static str, value: String },
+
+    #[error("NOVIN_TLS_CERT and NOVIN_TLS_KEY must be set together")]
+    IncompleteCertificate,
+
+    #[error("Set either certificate files or ACME domains, not both")]
+    ConflictingTls,
+
+    #[error("ACME needs a contact email in NOVIN_ACME_EMAIL")]
+    MissingAcmeContact,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcmeConfig {
+    pub domains: Vec<String>,
+    pub contact_email: String,
+    pub cache_dir: PathBuf,    // Account key and certificates, reused across restarts
+    pub production: bool,      // Let's Encrypt staging otherwise, for trying a setup out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TlsConfig {
+    Files { cert_path: PathBuf, key_path: PathBuf },
+    Acme(AcmeConfig),
+}
+
 #[derive(Debug, Clone)]
 pub struct ApiConfig {
     pub host: String,
     pub port: u16,
     pub static_files_dir: Option<String>,
+    pub tls: Option<TlsConfig>,
 }
 
 impl Default for ApiConfig {
@@ -20,35 +65,130 @@ impl Default for ApiConfig {
             host: "127.0.0.1".to_string(),
             port: 3000,
             static_files_dir: None,
+            tls: None,
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Configuration from NOVIN_API_HOST, NOVIN_API_PORT, NOVIN_TLS_CERT and
+    /// NOVIN_TLS_KEY, or NOVIN_ACME_DOMAINS (comma separated), NOVIN_ACME_EMAIL,
+    /// NOVIN_ACME_CACHE_DIR and NOVIN_ACME_STAGING
+    pub fn from_env() -> Result<Self, ApiConfigError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ApiConfigError> {
+        let mut config = Self::default();
+        if let Some(host) = var("NOVIN_API_HOST") {
+            host.parse::<IpAddr>().map_err(|_| ApiConfigError::Invalid { name: "NOVIN_API_HOST", value: host.clone() })?;
+            config.host = host;
+        }
+        if let Some(port) = var("NOVIN_API_PORT") {
+            config.port = port.parse().map_err(|_| ApiConfigError::Invalid { name: "NOVIN_API_PORT", value: port.clone() })?;
         }
+        config.static_files_dir = var("NOVIN_STATIC_DIR");
+
+        let files = match (var("NOVIN_TLS_CERT"), var("NOVIN_TLS_KEY")) {
+            (Some(cert), Some(key)) => Some(TlsConfig::Files { cert_path: cert.into(), key_path: key.into() }),
+            (None, None) => None,
+            _ => return Err(ApiConfigError::IncompleteCertificate),
+        };
+        let domains: Vec<String> = var("NOVIN_ACME_DOMAINS")
+            .map(|d| d.split(',').map(|d| d.trim().to_lowercase()).filter(|d| !d.is_empty()).collect())
+            .unwrap_or_default();
+        if let Some(domain) = domains.iter().find(|d| !d.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')) {
+            return Err(ApiConfigError::Invalid { name: "NOVIN_ACME_DOMAINS", value: domain.clone() });
+        }
+        config.tls = match (files, domains.is_empty()) {
+            (Some(_), false) => return Err(ApiConfigError::ConflictingTls),
+            (files, true) => files,
+            (None, false) => {
+                let contact_email = var("NOVIN_ACME_EMAIL").filter(|e| e.contains('@')).ok_or(ApiConfigError::MissingAcmeContact)?;
+                let cache_dir = var("NOVIN_ACME_CACHE_DIR").map(PathBuf::from).unwrap_or_else(|| {
+                    PathBuf::from(var("NOVIN_DATA_DIR").unwrap_or_else(|| "data".to_string())).join("acme")
+                });
+                let production = !var("NOVIN_ACME_STAGING").is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+                Some(TlsConfig::Acme(AcmeConfig { domains, contact_email, cache_dir, production }))
+            }
+        };
+        Ok(config)
+    }
+
+    fn addr(&self) -> Result<SocketAddr, ApiConfigError> {
+        let ip: IpAddr = self.host.parse().map_err(|_| ApiConfigError::Invalid { name: "host", value: self.host.clone() })?;
+        Ok(SocketAddr::new(ip, self.port))
     }
 }
 
 pub struct ApiServer {
     pub config: ApiConfig,
+    state: AppState,
 }
 
 impl ApiServer {
-    pub fn new(config: ApiConfig) -> Self {
-        Self { config }
+    pub fn new(config: ApiConfig, state: AppState) -> Self {
+        Self { config, state }
     }
 
+    /// Start the background jobs and serve until shut down
     pub async fn serve(self) -> Result<(), Box<dyn std::error::Error>> {
+        let _jobs = self.state.spawn_background_jobs();
         let app = Router::new()
             .route("/", get(root_handler))
             .route("/health", get(health_handler))
             .route("/api/status", get(status_handler))
+            .merge(create_routes(self.state))
             .layer(CorsLayer::permissive());
 
-        let addr = SocketAddr::from(([127, 0, 0, 1], self.config.port));
-        
-        println!("🚀 API Server starting on http://{}", addr);
-        println!("   REST API: http://{}/api", addr);
-        println!("   Health: http://{}/health", addr);
+        let addr = self.config.addr()?;
+        let scheme = if self.config.tls.is_some() { "https" } else { "http" };
+
+        println!("🚀 API Server starting on {}://{}", scheme, addr);
+        println!("   REST API: {}://{}/api", scheme, addr);
+        println!("   Health: {}://{}/health", scheme, addr);
         println!();
 
-        let listener = tokio::net::TcpListener::bind(&addr).await?;
-        axum::serve(listener, app).await?;
+        match self.config.tls {
+            None => {
+                let listener = tokio::net::TcpListener::bind(&addr).await?;
+                axum::serve(listener, app).await?;
+            }
+            Some(TlsConfig::Files { cert_path, key_path }) => {
+                let rustls = axum_server::tls_rustls::RustlsConfig::from_pem_file(&cert_path, &key_path).await?;
+                let reload = rustls.clone();
+                tokio::spawn(async move {
+                    let mut ticker = tokio::time::interval(Duration::from_secs(3600));
+                    ticker.tick().await;
+                    loop {
+                        ticker.tick().await;
+                        if let Err(e) = reload.reload_from_pem_file(&cert_path, &key_path).await {
+                            tracing::warn!("Keeping the current TLS certificate, reload failed: {}", e);
+                        }
+                    }
+                });
+                axum_server::bind_rustls(addr, rustls).serve(app.into_make_service()).await?;
+            }
+            Some(TlsConfig::Acme(acme)) => {
+                std::fs::create_dir_all(&acme.cache_dir)?;
+                let mut state = rustls_acme::AcmeConfig::new(acme.domains.clone())
+                    .contact_push(format!("mailto:{}", acme.contact_email))
+                    .cache(rustls_acme::caches::DirCache::new(acme.cache_dir.clone()))
+                    .directory_lets_encrypt(acme.production)
+                    .state();
+                let acceptor = state.axum_acceptor(state.default_rustls_config());
+                // Drives ordering and renewal; each event is one order or cache step
+                tokio::spawn(async move {
+                    while let Some(event) = state.next().await {
+                        match event {
+                            Ok(ok) => tracing::info!("ACME: {:?}", ok),
+                            Err(e) => tracing::warn!("ACME: {}", e),
+                        }
+                    }
+                });
+                axum_server::bind(addr).acceptor(acceptor).serve(app.into_make_service()).await?;
+            }
+        }
 
         Ok(())
     }
@@ -0,0 +1,269 @@
+//! Share-safe snapshot API
+//!
+//! Homeowners enroll resident faces and draw privacy zones per camera; sharing
+//! an incident snapshot produces a redacted copy behind an expiring link that
+//! anyone with the link can open without a token.
+use axum::{
+    extract::{Json, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use serde::{Deserialize, Serialize};
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::pipeline::PipelineError;
+use crate::redaction::{PrivacyZone, RedactionError, RedactionReport};
+
+#[derive(Debug, Deserialize)]
+pub struct PrivacyZonesRequest {
+    pub zones: Vec<PrivacyZone>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnrollFaceRequest {
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnrolledFaces {
+    pub user_id: String,
+    pub faces: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareRequest {
+    #[serde(default)]
+    pub snapshot_index: usize,
+    #[serde(default)]
+    pub camera_id: Option<String>, // Defaults to the camera of the incident's latest event
+    #[serde(default)]
+    pub ttl_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareLink {
+    pub token: String,
+    pub url: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub report: RedactionReport,
+}
+
+fn redaction_status(err: &RedactionError) -> StatusCode {
+    match err {
+        RedactionError::InvalidRegion(_) | RedactionError::Embedding(_) | RedactionError::TooLarge { .. } => StatusCode::BAD_REQUEST,
+        RedactionError::Decode(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        RedactionError::Encode(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Privacy zones blurred on a camera's shared snapshots
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/privacy-zones",
+    tag = "sharing",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_privacy_zones(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<Vec<PrivacyZone>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.sharing.privacy_zones(&home_id, &camera_id))))
+}
+
+/// Replace a camera's privacy zones; an empty list removes them
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/cameras/{camera_id}/privacy-zones",
+    tag = "sharing",
+    params(("home_id" = String, Path, description = "Home id"), ("camera_id" = String, Path, description = "Camera id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Zone outside the image"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_privacy_zones(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, camera_id)): Path<(String, String)>,
+    Json(request): Json<PrivacyZonesRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<PrivacyZone>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    state.sharing.set_privacy_zones(&home_id, &camera_id, request.zones).map_err(|e| redaction_status(&e))?;
+    Ok(ResponseJson(ApiResponse::success(state.sharing.privacy_zones(&home_id, &camera_id))))
+}
+
+/// Enroll a face embedding for a resident so their face is redacted from shared snapshots
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/residents/{user_id}/faces",
+    tag = "sharing",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident user id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Empty or mismatched embedding"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn enroll_face(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+    Json(request): Json<EnrollFaceRequest>,
+) -> Result<ResponseJson<ApiResponse<EnrolledFaces>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let faces = state.sharing.enroll_face(&home_id, &user_id, &request.embedding).map_err(|e| redaction_status(&e))?;
+    Ok(ResponseJson(ApiResponse::success(EnrolledFaces { user_id, faces })))
+}
+
+/// Forget a resident's enrolled faces
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/residents/{user_id}/faces",
+    tag = "sharing",
+    params(("home_id" = String, Path, description = "Home id"), ("user_id" = String, Path, description = "Resident user id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn remove_faces(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, user_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<EnrolledFaces>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    state.sharing.remove_faces(&home_id, &user_id);
+    Ok(ResponseJson(ApiResponse::success(EnrolledFaces { user_id, faces: 0 })))
+}
+
+/// Redact one of an incident's snapshots and publish it behind an expiring link
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/incidents/{incident_id}/share",
+    tag = "sharing",
+    params(("home_id" = String, Path, description = "Home id"), ("incident_id" = u64, Path, description = "Incident id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Invalid face region"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown incident or snapshot"),
+        (status = 409, description = "No face detections recorded for the snapshot"),
+        (status = 502, description = "Snapshot could not be fetched"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn share_snapshot(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, incident_id)): Path<(String, u64)>,
+    Json(request): Json<ShareRequest>,
+) -> Result<ResponseJson<ApiResponse<ShareLink>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let snapshot = state.pipeline.read().await
+        .incident_snapshot(&home_id, incident_id, request.snapshot_index, request.camera_id)
+        .await
+        .map_err(|e| match e {
+            PipelineError::IncidentNotFound(_) => StatusCode::NOT_FOUND,
+            PipelineError::SnapshotUnavailable(reason) => {
+                tracing::warn!("Cannot share snapshot for incident {}: {}", incident_id, reason);
+                StatusCode::BAD_GATEWAY
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+
+    // Faces come from the server's own detections; without them residents can't be redacted
+    let Some(faces) = snapshot.faces else {
+        tracing::warn!("Cannot share snapshot for incident {}: no face detections recorded", incident_id);
+        return Err(StatusCode::CONFLICT);
+    };
+
+    // Decoding and blurring are CPU-bound
+    let sharing = state.sharing.clone();
+    let home = home_id.clone();
+    let redacted = tokio::task::spawn_blocking(move || sharing.redact_for_share(&home, &snapshot.camera, &snapshot.image, &faces))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| redaction_status(&e))?;
+
+    let link = state.sharing.publish(&home_id, incident_id, redacted, request.ttl_secs.map(chrono::Duration::seconds));
+    Ok(ResponseJson(ApiResponse::success(ShareLink {
+        url: format!("/api/shared/{}", link.token),
+        token: link.token,
+        expires_at: link.expires_at,
+        report: link.report,
+    })))
+}
+
+/// Revoke a share link before it expires
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/shares/{token}",
+    tag = "sharing",
+    params(("home_id" = String, Path, description = "Home id"), ("token" = String, Path, description = "Share token")),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown share link"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn revoke_share(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, token)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    match state.sharing.shared(&token) {
+        Some(link) if link.home_id == home_id => {
+            state.sharing.revoke(&token);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        _ => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+/// A shared, redacted snapshot; no token needed, the link is the capability
+#[utoipa::path(
+    get,
+    path = "/api/shared/{token}",
+    tag = "sharing",
+    params(("token" = String, Path, description = "Share token")),
+    responses(
+        (status = 200, description = "Redacted snapshot", content_type = "image/jpeg", body = Vec<u8>),
+        (status = 404, description = "Unknown or expired link"),
+    ),
+)]
+pub async fn open_shared_snapshot(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response, StatusCode> {
+    let link = state.sharing.shared(&token).ok_or(StatusCode::NOT_FOUND)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/jpeg".to_string()),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        link.data,
+    ).into_response())
+}
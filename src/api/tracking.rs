@@ -0,0 +1,322 @@
+//! Entity tracking API
+//!
+//! Live view of the people currently tracked at a home and the state changes
+//! (approaching, at the door, loitering, leaving, lost) recorded for them,
+//! plus the zone-to-zone paths and usual hourly activity learned for the home,
+//! the trust built up in each re-identified person, which homeowners can pin
+//! or revoke, the watchlist built from incident annotations, and the home's
+//! knowledge graph of who was seen where and with whom.
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::annotations::{AnnotationError, WatchlistEntry};
+use crate::entity_trust::{EntityTrust, TrustChange, TrustError};
+use crate::tracker::{TrackTransition, TrackedEntity};
+use crate::activity_baseline::BaselineView;
+use crate::zone_graph::ZoneGraphView;
+use crate::knowledge_graph::{Edge, EdgeKind, Node};
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct TrustRequest {
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KnowledgeGraphQuery {
+    pub node: String, // e.g. "entity:track_1", "zone:front_door", "incident:42"
+    pub edge: Option<EdgeKind>,
+    #[serde(default)]
+    pub min_count: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphNeighbor {
+    pub edge: Edge,
+    pub node: Node,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KnowledgeGraphView {
+    pub node: Node,
+    pub neighbors: Vec<GraphNeighbor>, // Most observed first
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TrustAuditQuery {
+    pub entity_id: Option<String>,
+}
+
+fn status_for(err: TrustError) -> StatusCode {
+    match err {
+        TrustError::NotFound(_) => StatusCode::NOT_FOUND,
+        TrustError::Io(_) | TrustError::Encode(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrackingView {
+    pub active: Vec<TrackedEntity>,
+    pub recent_transitions: Vec<TrackTransition>,
+}
+
+/// People currently tracked at a home, with their recent state changes
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/tracks",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn home_tracks(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<TrackingView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(TrackingView {
+        active: state.tracker.active(&home_id),
+        recent_transitions: state.tracker.recent_transitions(&home_id),
+    })))
+}
+
+/// Moves between zones learned for a home, most travelled first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/zone-graph",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn zone_graph(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<ZoneGraphView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.zone_graph.view(&home_id))))
+}
+
+/// A node of the home's knowledge graph and its relationships, optionally of one kind
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/knowledge-graph",
+    tag = "tracking",
+    params(
+        ("home_id" = String, Path, description = "Home id"),
+        ("node" = String, Query, description = "Node id, e.g. entity:track_1 or zone:front_door"),
+        ("edge" = Option<String>, Query, description = "seen_with, frequents, involved_in, occurred_at or arrived"),
+        ("min_count" = Option<u64>, Query, description = "Only relationships observed at least this often"),
+    ),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Node not in the home's graph"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn knowledge_graph(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<KnowledgeGraphQuery>,
+) -> Result<ResponseJson<ApiResponse<KnowledgeGraphView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let view = state.knowledge_graph.with_graph(&home_id, |graph| {
+        let node = graph.node(&query.node)?.clone();
+        let mut neighbors: Vec<GraphNeighbor> = graph.neighbors(&query.node, query.edge.as_ref())
+            .into_iter()
+            .filter(|(edge, _)| edge.count >= query.min_count)
+            .map(|(edge, node)| GraphNeighbor { edge: edge.clone(), node: node.clone() })
+            .collect();
+        neighbors.sort_by(|a, b| b.edge.count.cmp(&a.edge.count).then_with(|| a.node.id.cmp(&b.node.id)));
+        Some(KnowledgeGraphView { node, neighbors })
+    });
+    view.flatten()
+        .map(|view| ResponseJson(ApiResponse::success(view)))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Usual number of events per zone for every local hour of the week
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/activity-baseline",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn activity_baseline(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<BaselineView>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.activity_baseline.view(&home_id, Utc::now()))))
+}
+
+/// Trust in each person seen at a home, most trusted first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/trust",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_trust(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<EntityTrust>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.trust.list(&home_id, Utc::now()))))
+}
+
+/// Pin a person as trusted; pinned trust does not decay
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/trust/{entity_id}",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id"), ("entity_id" = String, Path, description = "Entity (track) id")),
+    request_body = TrustRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn mark_trusted(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, entity_id)): Path<(String, String)>,
+    Json(request): Json<TrustRequest>,
+) -> Result<ResponseJson<ApiResponse<EntityTrust>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let trust = state.trust.mark_trusted(&home_id, &entity_id, &user.user_id, request.note, Utc::now()).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(trust)))
+}
+
+/// Withdraw trust from a person; later benign visits no longer rebuild it
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/trust/{entity_id}",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id"), ("entity_id" = String, Path, description = "Entity (track) id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "No trust record for this entity"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn revoke_trust(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, entity_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<EntityTrust>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let trust = state.trust.revoke(&home_id, &entity_id, &user.user_id, None, Utc::now()).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(trust)))
+}
+
+/// Every trust change at a home, newest first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/trust/audit",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id"), ("entity_id" = Option<String>, Query, description = "Only this entity")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn trust_audit(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<TrustAuditQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TrustChange>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.trust.audit(&home_id, query.entity_id.as_deref()))))
+}
+
+/// People the household tagged as suspicious, most recently added first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/watchlist",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_watchlist(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<WatchlistEntry>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.annotations.watchlist(&home_id))))
+}
+
+/// Take a person off the watchlist
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/watchlist/{entity_id}",
+    tag = "tracking",
+    params(("home_id" = String, Path, description = "Home id"), ("entity_id" = String, Path, description = "Entity (track) id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Not on the watchlist"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn unwatch(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, entity_id)): Path<(String, String)>,
+) -> Result<ResponseJson<ApiResponse<WatchlistEntry>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let removed = state.annotations.unwatch(&home_id, &entity_id).map_err(|e| match e {
+        AnnotationError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    })?;
+    Ok(ResponseJson(ApiResponse::success(removed)))
+}
@@ -0,0 +1,85 @@
+//! Vacation Mode API
+//!
+//! Turn extended-away mode on or off for a home and read its current state,
+//! including interior zones and presence-simulation schedules.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::vacation::{VacationMode, VacationRequest};
+
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/vacation",
+    tag = "vacation",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_vacation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Option<VacationMode>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.vacations.get(&home_id))))
+}
+
+/// Start vacation mode now or at `starts_at`; replaces any existing vacation
+#[utoipa::path(
+    put,
+    path = "/api/homes/{home_id}/vacation",
+    tag = "vacation",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid window, timezone or schedule"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn set_vacation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<VacationRequest>,
+) -> Result<ResponseJson<ApiResponse<VacationMode>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let mode = state.vacations.activate(&home_id, request, Utc::now())
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(ResponseJson(ApiResponse::success(mode)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/vacation",
+    tag = "vacation",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Home is not on vacation"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn end_vacation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<VacationMode>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let mode = state.vacations.deactivate(&home_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(mode)))
+}
@@ -0,0 +1,158 @@
+//! Visitor Token Management API
+//!
+//! Homeowners issue time-boxed access codes for deliveries and service visits,
+//! list and revoke them, and keypads can check a code directly.
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::visitor_tokens::{IssueTokenRequest, IssuedToken, TokenError, TokenVerification, VisitorToken};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyCodeRequest {
+    pub code: String,
+}
+
+fn status_for(err: TokenError) -> StatusCode {
+    match err {
+        TokenError::InvalidWindow | TokenError::WindowTooLong(_) => StatusCode::BAD_REQUEST,
+        TokenError::NotFound(_) => StatusCode::NOT_FOUND,
+        TokenError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Issue a new code; the plaintext code is only returned here
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/visitor-tokens",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 400, description = "Invalid validity window"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn issue_token(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<IssueTokenRequest>,
+) -> Result<ResponseJson<ApiResponse<IssuedToken>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if request.label.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let issued = state.visitor_tokens.issue(&home_id, request).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(issued)))
+}
+
+/// Tokens issued for a home, newest first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/visitor-tokens",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_tokens(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<VisitorToken>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.visitor_tokens.list(&home_id))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/visitor-tokens/{token_id}",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id"), ("token_id" = Uuid, Path, description = "Visitor token id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown token"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_token(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, token_id)): Path<(String, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<VisitorToken>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let token = state.visitor_tokens.get(&home_id, token_id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(token)))
+}
+
+/// Revoke a token; any suppression window it opened closes immediately
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/visitor-tokens/{token_id}",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id"), ("token_id" = Uuid, Path, description = "Visitor token id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown token"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn revoke_token(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, token_id)): Path<(String, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<VisitorToken>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let token = state.visitor_tokens.revoke(&home_id, token_id).map_err(status_for)?;
+    Ok(ResponseJson(ApiResponse::success(token)))
+}
+
+/// Check a code entered at the door; a valid code counts as a use
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/visitor-tokens/verify",
+    tag = "visitor-tokens",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = VerifyCodeRequest,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 429, description = "Too many wrong codes; try again later"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn verify_code(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<VerifyCodeRequest>,
+) -> Result<ResponseJson<ApiResponse<TokenVerification>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let result = state.visitor_tokens.verify(&home_id, &request.code, Utc::now());
+    if result == TokenVerification::RateLimited {
+        return Err(StatusCode::TOO_MANY_REQUESTS);
+    }
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
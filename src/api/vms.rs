@@ -0,0 +1,161 @@
+//! VMS bookmarking API
+//!
+//! Connect a home's NVRs and VMSs (Frigate, Blue Iris, or anything reachable
+//! with a templated HTTP request), list them with their success counts, read
+//! the bookmark history, and send a test bookmark to check the mapping.
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use crate::delivery::{BookmarkRecord, IntegrationView, VmsError, VmsIntegration, VmsIntegrationRequest};
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize {
+    50
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TestRequest {
+    pub camera: String, // One of our camera ids the integration maps
+}
+
+fn vms_status(err: VmsError) -> StatusCode {
+    match err {
+        VmsError::NotFound(_) => StatusCode::NOT_FOUND,
+        VmsError::TooMany(_) => StatusCode::CONFLICT,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/vms",
+    tag = "vms",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_integrations(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<IntegrationView>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.vms.list(&home_id))))
+}
+
+/// Connect an NVR or VMS: provider, base URL, credentials and the camera mapping
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/vms",
+    tag = "vms",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Missing name or cameras, bad URL, minimum decision or method"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 409, description = "Home already has the maximum number of integrations"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn create_integration(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<VmsIntegrationRequest>,
+) -> Result<ResponseJson<ApiResponse<VmsIntegration>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let integration = state.vms.create(&home_id, request, Utc::now()).map_err(vms_status)?;
+    Ok(ResponseJson(ApiResponse::success(integration.redacted())))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/vms/{integration_id}",
+    tag = "vms",
+    params(("home_id" = String, Path, description = "Home id"), ("integration_id" = Uuid, Path, description = "Integration id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown integration"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_integration(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, integration_id)): Path<(String, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<VmsIntegration>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let removed = state.vms.remove(&home_id, integration_id).map_err(vms_status)?;
+    Ok(ResponseJson(ApiResponse::success(removed.redacted())))
+}
+
+/// Bookmark attempts across the home's integrations, newest first
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/vms/history",
+    tag = "vms",
+    params(("home_id" = String, Path, description = "Home id"), ("limit" = Option<usize>, Query, description = "Records to return, default 50")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn bookmark_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<BookmarkRecord>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.vms.history(&home_id, query.limit))))
+}
+
+/// Send a test bookmark for one mapped camera; the outcome counts like any other
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/vms/{integration_id}/test",
+    tag = "vms",
+    params(("home_id" = String, Path, description = "Home id"), ("integration_id" = Uuid, Path, description = "Integration id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "Camera not mapped by the integration"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope"),
+        (status = 404, description = "Unknown integration"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn test_integration(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, integration_id)): Path<(String, Uuid)>,
+    Json(request): Json<TestRequest>,
+) -> Result<ResponseJson<ApiResponse<BookmarkRecord>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let record = state.vms.test(&home_id, integration_id, &request.camera, Utc::now()).await.map_err(vms_status)?;
+    Ok(ResponseJson(ApiResponse::success(record)))
+}
@@ -0,0 +1,137 @@
+//! Webhook Endpoint Management and Delivery Log API
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+};
+use crate::delivery::{validate_webhook_url, WebhookEndpoint, WebhookEventType, WebhookDeliveryRecord};
+use super::auth::{AuthUser, Scope};
+use super::models::ApiResponse;
+use super::routes::AppState;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterWebhookRequest {
+    pub url: String,
+    pub secret: String,
+    #[serde(default)]
+    pub event_types: Vec<WebhookEventType>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeliveryLogQuery {
+    pub limit: Option<usize>,
+}
+
+/// Register a new webhook endpoint for a home
+#[utoipa::path(
+    post,
+    path = "/api/homes/{home_id}/webhooks",
+    tag = "webhooks",
+    params(("home_id" = String, Path, description = "Home id")),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 400, description = "URL is not https, resolves to a non-public address, or the secret is too short"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope or not this home"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn register_webhook(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Json(request): Json<RegisterWebhookRequest>,
+) -> Result<ResponseJson<ApiResponse<WebhookEndpoint>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    if request.secret.len() < 16 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    validate_webhook_url(&request.url).await.map_err(|e| {
+        tracing::warn!("Rejected webhook URL for home {}: {}", home_id, e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let endpoint = WebhookEndpoint::new(&home_id, request.url, request.secret, request.event_types);
+    state.webhook_dispatcher.register_endpoint(endpoint.clone()).await;
+    Ok(ResponseJson(ApiResponse::success(endpoint)))
+}
+
+/// List webhook endpoints configured for a home
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/webhooks",
+    tag = "webhooks",
+    params(("home_id" = String, Path, description = "Home id")),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope or not this home"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookEndpoint>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    Ok(ResponseJson(ApiResponse::success(state.webhook_dispatcher.list_endpoints(&home_id).await)))
+}
+
+/// Remove a webhook endpoint
+#[utoipa::path(
+    delete,
+    path = "/api/homes/{home_id}/webhooks/{endpoint_id}",
+    tag = "webhooks",
+    params(("home_id" = String, Path, description = "Home id"), ("endpoint_id" = Uuid, Path, description = "Webhook endpoint id")),
+    responses(
+        (status = 204),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope or not this home"),
+        (status = 404, description = "Unknown endpoint"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((home_id, endpoint_id)): Path<(String, Uuid)>,
+) -> StatusCode {
+    if let Err(status) = user.require_home(Scope::HomeManage, &home_id) {
+        return status;
+    }
+    if state.webhook_dispatcher.remove_endpoint(&home_id, endpoint_id).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+/// Query recent delivery attempts for a home
+#[utoipa::path(
+    get,
+    path = "/api/homes/{home_id}/webhook-deliveries",
+    tag = "webhooks",
+    params(("home_id" = String, Path, description = "Home id"), DeliveryLogQuery),
+    responses(
+        (status = 200, body = JsonResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Missing scope or not this home"),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub async fn get_delivery_log(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(home_id): Path<String>,
+    Query(query): Query<DeliveryLogQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDeliveryRecord>>>, StatusCode> {
+    user.require_home(Scope::HomeManage, &home_id)?;
+    let limit = query.limit.unwrap_or(50).min(500);
+    Ok(ResponseJson(ApiResponse::success(state.webhook_dispatcher.delivery_log(&home_id, limit).await)))
+}
@@ -1,12 +1,95 @@
-pub struct WebSocketManager;
+//! WebSocket API
+//!
+//! One connection per client carries both directions. The server pushes
+//! `WebSocketUpdate`s as they happen; the client sends commands in an envelope
+//! with its own `request_id`, and gets back exactly one `reply` or `error`
+//! carrying the same id. Replies and errors interleave with pushed updates, so
+//! clients match them by id rather than by order.
+use axum::{
+    extract::{ws::{Message, WebSocket, WebSocketUpgrade}, State},
+    http::StatusCode,
+    response::Response,
+};
+use crate::arming::ArmingMode;
+use crate::delivery::NotificationSeverity;
+use crate::thinking::{IncidentLifecycleHook, IncidentStatus, IncidentTransition};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use super::auth::{AuthUser, Scope};
+use super::routes::AppState;
+
+// Messages pushed to connected clients
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebSocketUpdate {
+    IncidentState {
+        home_id: String,
+        incident_id: u64,
+        state: IncidentStatus,
+        previous: IncidentStatus,
+        reason: String,
+        ts: f64,
+    },
+    AlertAcknowledged {
+        home_id: String,
+        incident_id: u64,
+        by: String,
+    },
+    ArmingChanged {
+        home_id: String,
+        mode: ArmingMode,
+        by: String,
+    },
+}
+
+impl WebSocketUpdate {
+    pub fn home_id(&self) -> &str {
+        match self {
+            Self::IncidentState { home_id, .. }
+            | Self::AlertAcknowledged { home_id, .. }
+            | Self::ArmingChanged { home_id, .. } => home_id,
+        }
+    }
+}
+
+pub struct WebSocketManager {
+    updates: broadcast::Sender<WebSocketUpdate>,
+}
 
 impl WebSocketManager {
     pub fn new() -> Self {
-        Self
+        let (updates, _) = broadcast::channel(256);
+        Self { updates }
     }
-    
+
     pub async fn get_client_count(&self) -> usize {
-        0
+        self.updates.receiver_count()
+    }
+
+    /// Send an update to every connected client; dropped if nobody is listening
+    pub fn publish(&self, update: WebSocketUpdate) {
+        let _ = self.updates.send(update);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<WebSocketUpdate> {
+        self.updates.subscribe()
+    }
+}
+
+#[async_trait]
+impl IncidentLifecycleHook for WebSocketManager {
+    async fn on_transition(&self, transition: &IncidentTransition) {
+        self.publish(WebSocketUpdate::IncidentState {
+            home_id: transition.home_id.clone(),
+            incident_id: transition.incident_id,
+            state: transition.to,
+            previous: transition.from,
+            reason: transition.reason.clone(),
+            ts: transition.ts,
+        });
     }
 }
 
@@ -17,3 +100,159 @@ pub struct WebSocketStats {
     pub messages_sent_today: u64,
     pub uptime_seconds: u64,
 }
+
+// A command sent by the client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientRequest {
+    pub request_id: String,
+    #[serde(flatten)]
+    pub command: ClientCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ClientCommand {
+    AcknowledgeAlert { home_id: String, incident_id: u64 },
+    RequestSnapshot { home_id: String, camera: String },
+    SetArming { home_id: String, mode: ArmingMode },
+}
+
+impl ClientCommand {
+    pub fn home_id(&self) -> &str {
+        match self {
+            Self::AcknowledgeAlert { home_id, .. }
+            | Self::RequestSnapshot { home_id, .. }
+            | Self::SetArming { home_id, .. } => home_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandErrorCode {
+    BadRequest,
+    Forbidden,
+    NotFound,
+    Unavailable,
+}
+
+// Successful result of one command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "reply")]
+pub struct CommandReply {
+    pub request_id: String,
+    pub data: serde_json::Value,
+}
+
+// Failed command; request_id is None when the envelope itself could not be read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "error")]
+pub struct CommandError {
+    pub request_id: Option<String>,
+    pub code: CommandErrorCode,
+    pub message: String,
+}
+
+impl CommandError {
+    fn new(request_id: &str, code: CommandErrorCode, message: impl Into<String>) -> Self {
+        Self { request_id: Some(request_id.to_string()), code, message: message.into() }
+    }
+}
+
+/// Parse a client frame into a request, or the error to send back
+pub fn parse_request(text: &str) -> Result<ClientRequest, CommandError> {
+    serde_json::from_str(text).map_err(|e| {
+        // Echo the id back if the envelope had one, even when the command is unknown
+        let request_id = serde_json::from_str::<serde_json::Value>(text).ok()
+            .and_then(|v| v.get("request_id").and_then(|id| id.as_str()).map(str::to_string));
+        CommandError { request_id, code: CommandErrorCode::BadRequest, message: e.to_string() }
+    })
+}
+
+/// Upgrade to a WebSocket carrying pushed updates and client commands
+pub async fn websocket_handler(ws: WebSocketUpgrade, State(state): State<AppState>, user: AuthUser) -> Result<Response, StatusCode> {
+    user.require(Scope::HomeManage)?;
+    Ok(ws.on_upgrade(move |socket| serve_connection(socket, state, user)))
+}
+
+async fn serve_connection(socket: WebSocket, state: AppState, user: AuthUser) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut updates = state.websocket_manager.subscribe();
+    loop {
+        let outgoing = tokio::select! {
+            update = updates.recv() => match update {
+                // Other homes' updates are not this client's to see
+                Ok(update) if !user.can_access_home(update.home_id()) => continue,
+                Ok(update) => serde_json::to_string(&update),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("WebSocket client {} missed {} update(s)", user.user_id, skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = receiver.next() => match incoming {
+                Some(Ok(Message::Text(text))) => match parse_request(&text) {
+                    Ok(request) => match execute(&state, &user, request).await {
+                        Ok(reply) => serde_json::to_string(&reply),
+                        Err(error) => serde_json::to_string(&error),
+                    },
+                    Err(error) => serde_json::to_string(&error),
+                },
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => continue, // Pings are answered by axum; binary frames are not part of the protocol
+            },
+        };
+        let Ok(text) = outgoing else { continue };
+        if sender.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Run one client command
+pub async fn execute(state: &AppState, user: &AuthUser, request: ClientRequest) -> Result<CommandReply, CommandError> {
+    let id = request.request_id.as_str();
+    if !user.has_scope(Scope::HomeManage) {
+        return Err(CommandError::new(id, CommandErrorCode::Forbidden, "home:manage scope required"));
+    }
+    if !user.can_access_home(request.command.home_id()) {
+        return Err(CommandError::new(id, CommandErrorCode::Forbidden, format!("no access to home {}", request.command.home_id())));
+    }
+
+    let data = match request.command {
+        ClientCommand::AcknowledgeAlert { home_id, incident_id } => {
+            let severity = {
+                let pipeline = state.pipeline.read().await;
+                let incident = pipeline.find_incident(&home_id, incident_id)
+                    .ok_or_else(|| CommandError::new(id, CommandErrorCode::NotFound, format!("incident {} not found", incident_id)))?;
+                incident.probability_trace.last()
+                    .and_then(|p| NotificationSeverity::from_decision(&p.decision))
+                    .unwrap_or(NotificationSeverity::Info)
+            };
+            state.notification_router.acknowledge(&home_id, incident_id, severity);
+            state.websocket_manager.publish(WebSocketUpdate::AlertAcknowledged {
+                home_id: home_id.clone(),
+                incident_id,
+                by: user.user_id.clone(),
+            });
+            serde_json::json!({ "home_id": home_id, "incident_id": incident_id, "acknowledged_severity": severity })
+        }
+        ClientCommand::RequestSnapshot { home_id, camera } => {
+            let sent = state.webhook_dispatcher.dispatch_snapshot_request(&home_id, &camera, id).await;
+            if sent.is_empty() {
+                return Err(CommandError::new(id, CommandErrorCode::Unavailable, "no camera bridge is subscribed to snapshot requests"));
+            }
+            serde_json::json!({ "home_id": home_id, "camera": camera, "requested": true })
+        }
+        ClientCommand::SetArming { home_id, mode } => {
+            let arming = state.arming.set(&home_id, mode, &user.user_id, Utc::now());
+            state.websocket_manager.publish(WebSocketUpdate::ArmingChanged {
+                home_id,
+                mode,
+                by: user.user_id.clone(),
+            });
+            serde_json::to_value(arming).unwrap_or(serde_json::Value::Null)
+        }
+    };
+    Ok(CommandReply { request_id: request.request_id, data })
+}
@@ -0,0 +1,84 @@
+// src/arming.rs
+
+// Per-home arming state, set from the app (over the WebSocket command
+// channel) or a keypad. Disarmed homes still track and score incidents but
+// send no alerts; armed-away and armed-stay tell the thinking AI whether the
+// occupants are out or in, overriding what resident presence suggests.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArmingMode {
+    #[default]
+    Disarmed,
+    Stay, // Armed with people home, e.g. overnight
+    Away,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArmingConfig {
+    pub away_prob: f64, // Away probability while armed away
+    pub stay_prob: f64, // Away probability while armed stay
+}
+
+impl Default for ArmingConfig {
+    fn default() -> Self {
+        Self {
+            away_prob: 0.95,
+            stay_prob: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArmingState {
+    pub home_id: String,
+    pub mode: ArmingMode,
+    pub changed_at: DateTime<Utc>,
+    pub changed_by: String,
+}
+
+#[derive(Default)]
+pub struct ArmingRegistry {
+    config: ArmingConfig,
+    homes: DashMap<String, ArmingState>,
+}
+
+impl ArmingRegistry {
+    pub fn new(config: ArmingConfig) -> Self {
+        Self { config, homes: DashMap::new() }
+    }
+
+    pub fn set(&self, home_id: &str, mode: ArmingMode, by: &str, at: DateTime<Utc>) -> ArmingState {
+        let state = ArmingState {
+            home_id: home_id.to_string(),
+            mode,
+            changed_at: at,
+            changed_by: by.to_string(),
+        };
+        self.homes.insert(home_id.to_string(), state.clone());
+        state
+    }
+
+    /// None for homes that have never been armed or disarmed
+    pub fn get(&self, home_id: &str) -> Option<ArmingState> {
+        self.homes.get(home_id).map(|s| s.clone())
+    }
+
+    /// Homes that never set a mode keep alerting as before
+    pub fn alerts_enabled(&self, home_id: &str) -> bool {
+        self.get(home_id).map_or(true, |s| s.mode != ArmingMode::Disarmed)
+    }
+
+    /// Away probability implied by the arming mode, if it implies one
+    pub fn away_prob(&self, home_id: &str) -> Option<f64> {
+        match self.get(home_id)?.mode {
+            ArmingMode::Away => Some(self.config.away_prob),
+            ArmingMode::Stay => Some(self.config.stay_prob),
+            ArmingMode::Disarmed => None,
+        }
+    }
+}
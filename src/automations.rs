@@ -0,0 +1,375 @@
+// src/automations.rs
+
+// Declarative automations on alert decisions. A household declares what
+// should happen when an incident reaches a decision: "on Critical in the back
+// garden between 22:00 and 06:00, turn on the lights, then start the siren
+// after 30 seconds unless someone acknowledges". A trigger names the decisions
+// it fires on and optionally the zones (cameras) and a local time window; each
+// action names a device, a command, a delay and whether acknowledging the
+// incident cancels it. An automation fires once per incident. Immediate
+// actions run straight from the pipeline, delayed ones wait in a queue that a
+// background job drains, and every outcome lands in the home's execution
+// history. Actions go out to the home's automation hub through its webhooks.
+// A preview evaluates the triggers against a hypothetical decision without
+// running anything.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::thinking::AlertDecision;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AutomationError {
+    #[error("Automation needs a name")]
+    MissingName,
+    #[error("Trigger must name at least one decision")]
+    NoDecisions,
+    #[error("Automation has no actions")]
+    NoActions,
+    #[error("Action for '{0}' has a negative delay")]
+    NegativeDelay(String),
+    #[error("Time window starts and ends at the same time")]
+    EmptyWindow,
+    #[error("Unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("Home already has the maximum of {0} automations")]
+    TooMany(usize),
+    #[error("Automation {0} not found")]
+    NotFound(Uuid),
+}
+
+#[derive(Debug, Clone)]
+pub struct AutomationConfig {
+    pub max_per_home: usize,
+    pub history_len: usize, // Execution records kept per home
+    pub fired_ttl: Duration, // How long "already fired for this incident" is remembered
+}
+
+impl Default for AutomationConfig {
+    fn default() -> Self {
+        Self { max_per_home: 50, history_len: 500, fired_ttl: Duration::hours(24) }
+    }
+}
+
+/// Local time window, wrapping past midnight when `end` is before `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationTrigger {
+    pub decisions: Vec<AlertDecision>,
+    #[serde(default)]
+    pub zones: Vec<String>, // Cameras the incident's event came from; empty matches any
+    #[serde(default)]
+    pub between: Option<TimeWindow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationAction {
+    pub device: String,  // e.g. "garden_lights", "siren"
+    pub command: String, // Passed to the hub as is, e.g. "on", "start"
+    #[serde(default)]
+    pub delay_secs: i64,
+    #[serde(default)]
+    pub unless_acknowledged: bool, // Dropped if the incident is acknowledged before it runs
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutomationRequest {
+    pub name: String,
+    pub trigger: AutomationTrigger,
+    pub actions: Vec<AutomationAction>,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Automation {
+    pub id: Uuid,
+    pub home_id: String,
+    pub name: String,
+    pub trigger: AutomationTrigger,
+    pub actions: Vec<AutomationAction>,
+    pub timezone: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Automation {
+    pub fn matches(&self, context: &TriggerContext) -> bool {
+        if !self.enabled || !self.trigger.decisions.contains(&context.decision) {
+            return false;
+        }
+        if !self.trigger.zones.is_empty() && !self.trigger.zones.iter().any(|z| *z == context.zone) {
+            return false;
+        }
+        let Some(window) = self.trigger.between else {
+            return true;
+        };
+        let timezone: Tz = self.timezone.parse().unwrap_or(Tz::UTC);
+        window.contains(context.at.with_timezone(&timezone).time())
+    }
+}
+
+/// An alert decision to evaluate the triggers against
+#[derive(Debug, Clone)]
+pub struct TriggerContext {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub decision: AlertDecision,
+    pub zone: String,
+    pub at: DateTime<Utc>,
+}
+
+/// An action an automation fired, with when it runs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedAction {
+    pub automation_id: Uuid,
+    pub automation_name: String,
+    pub home_id: String,
+    pub incident_id: u64,
+    pub device: String,
+    pub command: String,
+    pub run_at: DateTime<Utc>,
+    pub unless_acknowledged: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Executed,
+    Failed,
+    Cancelled, // Acknowledged before it ran
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionRecord {
+    pub action: PlannedAction,
+    pub status: ExecutionStatus,
+    pub detail: Option<String>, // Why it failed, or who acknowledged
+    pub at: DateTime<Utc>,
+}
+
+/// Carries an action to the home's automation hub
+#[async_trait]
+pub trait AutomationExecutor: Send + Sync {
+    async fn execute(&self, action: &PlannedAction) -> Result<(), String>;
+}
+
+#[derive(Default)]
+pub struct AutomationEngine {
+    config: AutomationConfig,
+    automations: DashMap<String, Vec<Automation>>,
+    fired: DashMap<(String, u64, Uuid), DateTime<Utc>>,
+    pending: Mutex<Vec<PlannedAction>>,
+    history: DashMap<String, VecDeque<ExecutionRecord>>,
+}
+
+impl AutomationEngine {
+    pub fn new(config: AutomationConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &AutomationConfig {
+        &self.config
+    }
+
+    pub fn create(&self, home_id: &str, request: AutomationRequest, now: DateTime<Utc>) -> Result<Automation, AutomationError> {
+        if request.name.trim().is_empty() {
+            return Err(AutomationError::MissingName);
+        }
+        if request.trigger.decisions.is_empty() {
+            return Err(AutomationError::NoDecisions);
+        }
+        if request.actions.is_empty() {
+            return Err(AutomationError::NoActions);
+        }
+        if let Some(action) = request.actions.iter().find(|a| a.delay_secs < 0) {
+            return Err(AutomationError::NegativeDelay(action.device.clone()));
+        }
+        if request.trigger.between.is_some_and(|w| w.start == w.end) {
+            return Err(AutomationError::EmptyWindow);
+        }
+        request.timezone.parse::<Tz>().map_err(|_| AutomationError::UnknownTimezone(request.timezone.clone()))?;
+
+        let automation = Automation {
+            id: Uuid::new_v4(),
+            home_id: home_id.to_string(),
+            name: request.name.trim().to_string(),
+            trigger: request.trigger,
+            actions: request.actions,
+            timezone: request.timezone,
+            enabled: request.enabled,
+            created_at: now,
+        };
+        let mut home = self.automations.entry(home_id.to_string()).or_default();
+        if home.len() >= self.config.max_per_home {
+            return Err(AutomationError::TooMany(self.config.max_per_home));
+        }
+        home.push(automation.clone());
+        Ok(automation)
+    }
+
+    pub fn list(&self, home_id: &str) -> Vec<Automation> {
+        self.automations.get(home_id).map(|a| a.clone()).unwrap_or_default()
+    }
+
+    /// Remove an automation; its queued actions are dropped with it
+    pub fn remove(&self, home_id: &str, id: Uuid) -> Result<Automation, AutomationError> {
+        let mut home = self.automations.get_mut(home_id).ok_or(AutomationError::NotFound(id))?;
+        let index = home.iter().position(|a| a.id == id).ok_or(AutomationError::NotFound(id))?;
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.retain(|p| p.automation_id != id);
+        }
+        Ok(home.remove(index))
+    }
+
+    /// Dry run: the actions the home's automations would take for `context`,
+    /// ignoring whether they already fired for the incident
+    pub fn preview(&self, context: &TriggerContext) -> Vec<PlannedAction> {
+        self.automations.get(&context.home_id)
+            .map(|home| home.iter().filter(|a| a.matches(context)).flat_map(|a| plan(a, context)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Fire the automations matching a decision. Actions due now are returned
+    /// for the caller to run; delayed ones are queued for `due`.
+    pub fn trigger(&self, context: &TriggerContext) -> Vec<PlannedAction> {
+        let Some(home) = self.automations.get(&context.home_id) else {
+            return Vec::new();
+        };
+        let mut immediate = Vec::new();
+        let mut delayed = Vec::new();
+        for automation in home.iter().filter(|a| a.matches(context)) {
+            let key = (context.home_id.clone(), context.incident_id, automation.id);
+            if self.fired.contains_key(&key) {
+                continue;
+            }
+            self.fired.insert(key, context.at);
+            for action in plan(automation, context) {
+                if action.run_at <= context.at {
+                    immediate.push(action);
+                } else {
+                    delayed.push(action);
+                }
+            }
+        }
+        if !delayed.is_empty() {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.extend(delayed);
+            }
+        }
+        immediate
+    }
+
+    /// Queued actions whose delay has passed, each returned once
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<PlannedAction> {
+        self.fired.retain(|_, at| *at + self.config.fired_ttl > now);
+        let Ok(mut pending) = self.pending.lock() else {
+            return Vec::new();
+        };
+        let (due, waiting): (Vec<_>, Vec<_>) = pending.drain(..).partition(|p| p.run_at <= now);
+        *pending = waiting;
+        due
+    }
+
+    /// Someone acknowledged the incident: drop its queued actions marked
+    /// `unless_acknowledged` and return their records
+    pub fn acknowledge(&self, home_id: &str, incident_id: u64, by: &str, now: DateTime<Utc>) -> Vec<ExecutionRecord> {
+        let cancelled: Vec<PlannedAction> = match self.pending.lock() {
+            Ok(mut pending) => {
+                let (cancelled, kept): (Vec<_>, Vec<_>) = pending.drain(..)
+                    .partition(|p| p.home_id == home_id && p.incident_id == incident_id && p.unless_acknowledged);
+                *pending = kept;
+                cancelled
+            }
+            Err(_) => Vec::new(),
+        };
+        cancelled.into_iter()
+            .map(|action| self.record(action, ExecutionStatus::Cancelled, Some(format!("acknowledged by {}", by)), now))
+            .collect()
+    }
+
+    /// Add an outcome to the home's execution history
+    pub fn record(&self, action: PlannedAction, status: ExecutionStatus, detail: Option<String>, now: DateTime<Utc>) -> ExecutionRecord {
+        let record = ExecutionRecord { action, status, detail, at: now };
+        let mut history = self.history.entry(record.action.home_id.clone()).or_default();
+        history.push_back(record.clone());
+        while history.len() > self.config.history_len {
+            history.pop_front();
+        }
+        record
+    }
+
+    /// Most recent executions first
+    pub fn history(&self, home_id: &str, limit: usize) -> Vec<ExecutionRecord> {
+        self.history.get(home_id)
+            .map(|h| h.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Actions still waiting on their delay
+    pub fn pending(&self, home_id: &str) -> Vec<PlannedAction> {
+        self.pending.lock()
+            .map(|p| p.iter().filter(|a| a.home_id == home_id).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn plan(automation: &Automation, context: &TriggerContext) -> Vec<PlannedAction> {
+    automation.actions.iter()
+        .map(|action| PlannedAction {
+            automation_id: automation.id,
+            automation_name: automation.name.clone(),
+            home_id: context.home_id.clone(),
+            incident_id: context.incident_id,
+            device: action.device.clone(),
+            command: action.command.clone(),
+            run_at: context.at + Duration::seconds(action.delay_secs),
+            unless_acknowledged: action.unless_acknowledged,
+        })
+        .collect()
+}
+
+/// Run actions and record each outcome
+pub async fn execute_all(engine: &AutomationEngine, executor: &dyn AutomationExecutor, actions: Vec<PlannedAction>) {
+    for action in actions {
+        let (status, detail) = match executor.execute(&action).await {
+            Ok(()) => (ExecutionStatus::Executed, None),
+            Err(e) => {
+                tracing::warn!("Automation '{}' could not send {} to {}: {}", action.automation_name, action.command, action.device, e);
+                (ExecutionStatus::Failed, Some(e))
+            }
+        };
+        engine.record(action, status, detail, Utc::now());
+    }
+}
@@ -0,0 +1,384 @@
+// src/backup.rs
+
+// Single-archive backups of everything the system has learned or been told:
+// incident stores, entity registries (residents, camera pins, device keys),
+// learned calibrations (channel weights, sensor reliability) and the daemon
+// config. The archive is a ZIP with a manifest listing each entry's section
+// and SHA-256. Restoring opens the archive, checks every checksum, then
+// deserializes every entry before anything is replaced, so a truncated or
+// mismatched backup is refused at startup instead of half-applied. With a
+// keyring, incident stores are sealed with each home's data key and the
+// wrapped keys travel in the archive; the master key never does.
+
+use crate::camera_registry::{CameraPin, CameraRegistry};
+use crate::device_signing::{DeviceKey, DeviceKeyRegistry};
+use crate::encryption::{EncryptionError, HomeKeyring, WrappedKey};
+use crate::household::Resident;
+use crate::schema::{from_versioned_json, to_versioned_json};
+use crate::thinking::{IncidentStoreSnapshot, OnlineWeightLearner, SensorReliabilityConfig, SensorReliabilityModel, WeightLearnerConfig};
+use crate::validation::{ConfigError, DaemonConfig, Validate};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use zip::write::FileOptions;
+
+pub const FORMAT_VERSION: u32 = 1;
+pub const MANIFEST_PATH: &str = "manifest.json";
+pub const RESIDENTS_PATH: &str = "entities/residents.json";
+pub const CAMERA_PINS_PATH: &str = "entities/camera_pins.json";
+pub const DEVICE_KEYS_PATH: &str = "entities/device_keys.json";
+pub const CHANNEL_WEIGHTS_PATH: &str = "calibration/channel_weights.json";
+pub const SENSOR_RELIABILITY_PATH: &str = "calibration/sensor_reliability.json";
+pub const WRAPPED_KEYS_PATH: &str = "encryption/wrapped_keys.json";
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to read or write archive: {0}")]
+    Archive(String),
+
+    #[error("Backup has no manifest")]
+    MissingManifest,
+
+    #[error("Backup format version {0} is newer than supported version {FORMAT_VERSION}")]
+    FromFuture(u32),
+
+    #[error("{0} is listed in the manifest but missing from the archive")]
+    MissingEntry(String),
+
+    #[error("{0} is in the archive but not listed in the manifest")]
+    UnlistedEntry(String),
+
+    #[error("Checksum mismatch for {0}")]
+    Checksum(String),
+
+    #[error("Unsafe path in backup: {0}")]
+    UnsafePath(String),
+
+    #[error("Invalid entry {path}: {reason}")]
+    InvalidEntry { path: String, reason: String },
+
+    #[error("Invalid config: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Encrypted entry {path}: {source}")]
+    Encryption { path: String, source: EncryptionError },
+}
+
+impl BackupError {
+    pub(crate) fn invalid(path: &str, reason: impl ToString) -> Self {
+        BackupError::InvalidEntry { path: path.to_string(), reason: reason.to_string() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupSection {
+    IncidentStore,
+    EntityRegistry,
+    Calibration,
+    Config,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub path: String,
+    pub section: BackupSection,
+    pub home_id: Option<String>, // Set for per-home incident stores
+    pub sha256: String,
+    pub size_bytes: usize,
+    #[serde(default)]
+    pub encrypted: bool, // Sealed with the home's data key
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub entries: Vec<BackupEntry>,
+}
+
+/// Collects entries and writes them out as one archive
+#[derive(Default)]
+pub struct BackupBuilder {
+    files: Vec<(BackupEntry, Vec<u8>)>,
+    keyring: Option<Arc<HomeKeyring>>,
+}
+
+impl BackupBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seal incident stores with each home's data key
+    pub fn with_encryption(mut self, keyring: Arc<HomeKeyring>) -> Self {
+        self.keyring = Some(keyring);
+        self
+    }
+
+    pub fn add(&mut self, section: BackupSection, path: &str, home_id: Option<&str>, data: Vec<u8>) {
+        let entry = BackupEntry {
+            path: path.to_string(),
+            section,
+            home_id: home_id.map(str::to_string),
+            sha256: hex::encode(Sha256::digest(&data)),
+            size_bytes: data.len(),
+            encrypted: false,
+        };
+        self.files.push((entry, data));
+    }
+
+    pub fn add_json<T: Serialize>(&mut self, section: BackupSection, path: &str, value: &T) -> Result<(), BackupError> {
+        let data = serde_json::to_vec_pretty(value).map_err(|e| BackupError::invalid(path, e))?;
+        self.add(section, path, None, data);
+        Ok(())
+    }
+
+    /// Incident stores keep their schema envelope so older backups migrate on restore
+    pub fn add_incident_store(&mut self, home_id: &str, snapshot: &IncidentStoreSnapshot) -> Result<(), BackupError> {
+        let path = format!("incidents/home_{:04}.json", self.count(BackupSection::IncidentStore));
+        let json = to_versioned_json(snapshot).map_err(|e| BackupError::invalid(&path, e))?.into_bytes();
+        match self.keyring.clone() {
+            Some(keyring) => {
+                let sealed = keyring.seal(home_id, &path, &json).map_err(|source| BackupError::Encryption { path: path.clone(), source })?;
+                self.add(BackupSection::IncidentStore, &path, Some(home_id), sealed);
+                if let Some((entry, _)) = self.files.last_mut() {
+                    entry.encrypted = true;
+                }
+            }
+            None => self.add(BackupSection::IncidentStore, &path, Some(home_id), json),
+        }
+        Ok(())
+    }
+
+    /// Include the daemon config file, refusing one that would not start the daemon
+    pub fn add_config_file(&mut self, path: &Path) -> Result<(), BackupError> {
+        DaemonConfig::load_validated(path)?;
+        let extension = if path.extension().and_then(|e| e.to_str()) == Some("json") { "json" } else { "yaml" };
+        self.add(BackupSection::Config, &format!("config/daemon.{}", extension), None, std::fs::read(path)?);
+        Ok(())
+    }
+
+    fn count(&self, section: BackupSection) -> usize {
+        self.files.iter().filter(|(e, _)| e.section == section).count()
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, BackupError> {
+        // Wrapped keys are useless without the master key, so they can travel with the data
+        if let Some(keyring) = self.keyring.clone().filter(|_| self.files.iter().any(|(e, _)| e.encrypted)) {
+            self.add_json(BackupSection::EntityRegistry, WRAPPED_KEYS_PATH, &keyring.wrapped_keys())?;
+        }
+        let manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            created_at: Utc::now(),
+            entries: self.files.iter().map(|(entry, _)| entry.clone()).collect(),
+        };
+        let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| BackupError::Archive(e.to_string()))?;
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let files = self.files.into_iter().map(|(entry, data)| (entry.path, data));
+        for (path, data) in std::iter::once((MANIFEST_PATH.to_string(), manifest_json)).chain(files) {
+            zip.start_file(path, options).map_err(|e| BackupError::Archive(e.to_string()))?;
+            zip.write_all(&data).map_err(|e| BackupError::Archive(e.to_string()))?;
+        }
+        Ok(zip.finish().map_err(|e| BackupError::Archive(e.to_string()))?.into_inner())
+    }
+
+    pub fn write_to(self, path: &Path) -> Result<(), BackupError> {
+        let bytes = self.finish()?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+/// An archive whose manifest and checksums have been verified
+#[derive(Debug)]
+pub struct BackupArchive {
+    manifest: BackupManifest,
+    files: HashMap<String, Vec<u8>>,
+    keyring: Option<Arc<HomeKeyring>>, // Opens encrypted entries
+}
+
+impl BackupArchive {
+    pub fn open(bytes: &[u8]) -> Result<Self, BackupError> {
+        let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| BackupError::Archive(e.to_string()))?;
+        let mut files = HashMap::new();
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i).map_err(|e| BackupError::Archive(e.to_string()))?;
+            let name = file.name().to_string();
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            files.insert(name, data);
+        }
+
+        let manifest: BackupManifest = files.remove(MANIFEST_PATH)
+            .ok_or(BackupError::MissingManifest)
+            .and_then(|raw| serde_json::from_slice(&raw).map_err(|e| BackupError::invalid(MANIFEST_PATH, e)))?;
+        if manifest.format_version > FORMAT_VERSION {
+            return Err(BackupError::FromFuture(manifest.format_version));
+        }
+
+        for entry in &manifest.entries {
+            safe_relative_path(&entry.path)?;
+            let data = files.get(&entry.path).ok_or_else(|| BackupError::MissingEntry(entry.path.clone()))?;
+            if data.len() != entry.size_bytes || hex::encode(Sha256::digest(data)) != entry.sha256 {
+                return Err(BackupError::Checksum(entry.path.clone()));
+            }
+        }
+        if let Some(extra) = files.keys().find(|path| !manifest.entries.iter().any(|e| &e.path == *path)) {
+            return Err(BackupError::UnlistedEntry(extra.clone()));
+        }
+
+        Ok(Self { manifest, files, keyring: None })
+    }
+
+    pub fn read_file(path: &Path) -> Result<Self, BackupError> {
+        Self::open(&std::fs::read(path)?)
+    }
+
+    pub fn manifest(&self) -> &BackupManifest {
+        &self.manifest
+    }
+
+    /// Open encrypted entries with this keyring, first adding any home keys
+    /// the archive carries that the keyring does not have yet
+    pub fn with_keyring(mut self, keyring: Arc<HomeKeyring>) -> Result<Self, BackupError> {
+        if let Some(keys) = self.json::<Vec<WrappedKey>>(WRAPPED_KEYS_PATH)? {
+            keyring.import(keys).map_err(|source| BackupError::Encryption { path: WRAPPED_KEYS_PATH.to_string(), source })?;
+        }
+        self.keyring = Some(keyring);
+        Ok(self)
+    }
+
+    pub fn entry(&self, path: &str) -> Option<&[u8]> {
+        self.files.get(path).map(Vec::as_slice)
+    }
+
+    /// Every incident store, migrated to the current schema
+    pub fn incident_stores(&self) -> Result<Vec<(String, IncidentStoreSnapshot)>, BackupError> {
+        self.manifest.entries.iter()
+            .filter(|e| e.section == BackupSection::IncidentStore)
+            .map(|entry| {
+                let home_id = entry.home_id.clone().ok_or_else(|| BackupError::invalid(&entry.path, "missing home_id"))?;
+                let raw = &self.files[&entry.path];
+                let opened;
+                let raw = if entry.encrypted {
+                    let keyring = self.keyring.as_ref().ok_or_else(|| BackupError::invalid(&entry.path, "encrypted, but no keyring was supplied"))?;
+                    opened = keyring.open(&home_id, &entry.path, raw).map_err(|source| BackupError::Encryption { path: entry.path.clone(), source })?;
+                    &opened
+                } else {
+                    raw
+                };
+                let json = std::str::from_utf8(raw).map_err(|e| BackupError::invalid(&entry.path, e))?;
+                let snapshot = from_versioned_json(json).map_err(|e| BackupError::invalid(&entry.path, e))?;
+                Ok((home_id, snapshot))
+            })
+            .collect()
+    }
+
+    /// A JSON entry, or None if the backup does not include it
+    pub fn json<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>, BackupError> {
+        self.entry(path)
+            .map(|raw| serde_json::from_slice(raw).map_err(|e| BackupError::invalid(path, e)))
+            .transpose()
+    }
+
+    pub fn residents(&self) -> Result<Option<BTreeMap<String, Vec<Resident>>>, BackupError> {
+        self.json(RESIDENTS_PATH)
+    }
+
+    pub fn camera_pins(&self) -> Result<Option<Vec<CameraPin>>, BackupError> {
+        self.json(CAMERA_PINS_PATH)
+    }
+
+    pub fn device_keys(&self) -> Result<Option<Vec<DeviceKey>>, BackupError> {
+        self.json(DEVICE_KEYS_PATH)
+    }
+
+    fn config_entry(&self) -> Option<&BackupEntry> {
+        self.manifest.entries.iter().find(|e| e.section == BackupSection::Config)
+    }
+
+    /// The backed-up daemon config, validated
+    pub fn config(&self) -> Result<Option<DaemonConfig>, BackupError> {
+        let Some(entry) = self.config_entry() else { return Ok(None) };
+        let text = std::str::from_utf8(&self.files[&entry.path]).map_err(|e| BackupError::invalid(&entry.path, e))?;
+        let config = DaemonConfig::parse(text, Path::new(&entry.path).extension().and_then(|e| e.to_str()))?;
+        config.validate()?;
+        Ok(Some(config))
+    }
+
+    /// Deserialize every entry into scratch state, so a restore cannot fail halfway
+    pub fn validate(&self) -> Result<(), BackupError> {
+        self.incident_stores()?;
+        self.residents()?;
+        if let Some(pins) = self.camera_pins()? {
+            let scratch = CameraRegistry::default();
+            for pin in pins {
+                scratch.register(pin).map_err(|e| BackupError::invalid(CAMERA_PINS_PATH, e))?;
+            }
+        }
+        if let Some(keys) = self.device_keys()? {
+            DeviceKeyRegistry::default().restore(keys).map_err(|e| BackupError::invalid(DEVICE_KEYS_PATH, e))?;
+        }
+        if let Some(json) = self.text(CHANNEL_WEIGHTS_PATH)? {
+            OnlineWeightLearner::new(WeightLearnerConfig::default())
+                .restore_json(json)
+                .map_err(|e| BackupError::invalid(CHANNEL_WEIGHTS_PATH, e))?;
+        }
+        if let Some(json) = self.text(SENSOR_RELIABILITY_PATH)? {
+            SensorReliabilityModel::new(SensorReliabilityConfig::default())
+                .restore_json(json)
+                .map_err(|e| BackupError::invalid(SENSOR_RELIABILITY_PATH, e))?;
+        }
+        self.config()?;
+        Ok(())
+    }
+
+    pub fn text(&self, path: &str) -> Result<Option<&str>, BackupError> {
+        self.entry(path)
+            .map(|raw| std::str::from_utf8(raw).map_err(|e| BackupError::invalid(path, e)))
+            .transpose()
+    }
+
+    /// Write every entry under `dir`, each via a temp file; returns the written paths
+    pub fn extract_to(&self, dir: &Path) -> Result<Vec<PathBuf>, BackupError> {
+        let mut written = Vec::new();
+        for entry in &self.manifest.entries {
+            let target = dir.join(safe_relative_path(&entry.path)?);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let tmp = target.with_extension("restore.tmp");
+            std::fs::write(&tmp, &self.files[&entry.path])?;
+            std::fs::rename(&tmp, &target)?;
+            written.push(target);
+        }
+        Ok(written)
+    }
+
+    /// Where the config entry lands under `dir` after `extract_to`
+    pub fn extracted_config_path(&self, dir: &Path) -> Option<PathBuf> {
+        self.config_entry().map(|e| dir.join(&e.path))
+    }
+}
+
+// Entry paths come from the archive, so never let one escape the target directory
+fn safe_relative_path(path: &str) -> Result<PathBuf, BackupError> {
+    let relative = PathBuf::from(path);
+    if relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(relative)
+    } else {
+        Err(BackupError::UnsafePath(path.to_string()))
+    }
+}
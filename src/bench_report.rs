@@ -0,0 +1,123 @@
+// src/bench_report.rs
+
+// Regression check for the criterion benchmarks in benches/. Criterion writes
+// each benchmark's estimates to <criterion dir>/<id>/new/estimates.json; the
+// report reads the mean of each, compares it with a checked-in baseline of
+// nanoseconds per benchmark, and flags any that slowed down by more than the
+// baseline's threshold. CI runs `bench-report` after `cargo bench` and fails
+// the build on a regression; `--update` rewrites the baseline after an
+// intended change.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BenchReportError {
+    #[error("Failed to read benchmark results: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid benchmark JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default = "default_threshold_pct")]
+    pub threshold_pct: f64,                   // Slowdown beyond this fails the check
+    pub benchmarks: BTreeMap<String, f64>,    // Benchmark id -> mean ns per iteration
+}
+
+fn default_threshold_pct() -> f64 {
+    10.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchDelta {
+    pub id: String,
+    pub baseline_ns: Option<f64>, // None for a benchmark added since the baseline
+    pub current_ns: f64,
+    pub change_pct: Option<f64>,
+    pub regressed: bool,
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    mean: Estimate,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+/// Mean ns per iteration of every benchmark criterion has results for
+pub fn read_estimates(criterion_dir: &Path) -> Result<BTreeMap<String, f64>, BenchReportError> {
+    let mut means = BTreeMap::new();
+    collect(criterion_dir, criterion_dir, &mut means)?;
+    Ok(means)
+}
+
+fn collect(root: &Path, dir: &Path, means: &mut BTreeMap<String, f64>) -> Result<(), BenchReportError> {
+    let estimates = dir.join("new").join("estimates.json");
+    if estimates.is_file() {
+        let parsed: Estimates = serde_json::from_str(&std::fs::read_to_string(estimates)?)?;
+        let id = dir.strip_prefix(root).unwrap_or(dir).components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        means.insert(id, parsed.mean.point_estimate);
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && path.file_name().is_some_and(|n| n != "report") {
+            collect(root, &path, means)?;
+        }
+    }
+    Ok(())
+}
+
+/// Every current result against the baseline, regressions first
+pub fn compare(baseline: &Baseline, current: &BTreeMap<String, f64>) -> Vec<BenchDelta> {
+    let mut deltas: Vec<BenchDelta> = current.iter()
+        .map(|(id, &current_ns)| {
+            let baseline_ns = baseline.benchmarks.get(id).copied().filter(|ns| *ns > 0.0);
+            let change_pct = baseline_ns.map(|base| (current_ns - base) / base * 100.0);
+            BenchDelta {
+                id: id.clone(),
+                baseline_ns,
+                current_ns,
+                change_pct,
+                regressed: change_pct.is_some_and(|pct| pct > baseline.threshold_pct),
+            }
+        })
+        .collect();
+    deltas.sort_by(|a, b| b.regressed.cmp(&a.regressed).then_with(|| a.id.cmp(&b.id)));
+    deltas
+}
+
+/// Markdown table of the comparison, for CI logs and PR comments
+pub fn render(deltas: &[BenchDelta], threshold_pct: f64) -> String {
+    let mut out = String::from("| benchmark | baseline | current | change |\n|---|---|---|---|\n");
+    for delta in deltas {
+        let baseline = delta.baseline_ns.map_or_else(|| "new".to_string(), format_ns);
+        let change = match delta.change_pct {
+            Some(pct) if delta.regressed => format!("**{:+.1}%**", pct),
+            Some(pct) => format!("{:+.1}%", pct),
+            None => "-".to_string(),
+        };
+        out.push_str(&format!("| {} | {} | {} | {} |\n", delta.id, baseline, format_ns(delta.current_ns), change));
+    }
+    let regressions = deltas.iter().filter(|d| d.regressed).count();
+    out.push_str(&format!("\n{} regression(s) over {:.0}%\n", regressions, threshold_pct));
+    out
+}
+
+fn format_ns(ns: f64) -> String {
+    match ns {
+        ns if ns >= 1e6 => format!("{:.2} ms", ns / 1e6),
+        ns if ns >= 1e3 => format!("{:.2} µs", ns / 1e3),
+        ns => format!("{:.0} ns", ns),
+    }
+}
@@ -15,7 +15,7 @@ fn main() {
 }
 
 fn test_alert_severity_thresholds() {
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     println!("🔬 Testing events engineered to hit specific alert severity levels...\n");
     
@@ -101,7 +101,7 @@ fn verify_decision_correctness(prob: f64, decision: &AlertDecision, scenario: &s
 }
 
 fn test_extreme_scenario(name: &str, event: Event) {
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     match processor.process_event("extreme_test", event) {
         Some(result) => {
@@ -137,6 +137,7 @@ fn create_intruder_scenario() -> Event {
             llr_identity: -3.0, // Completely unknown
             llr_presence: 1.5, // Very unusual presence
             llr_token: 0.0,    // No token
+            ..Default::default()
         },
     }
 }
@@ -159,6 +160,7 @@ fn create_delivery_scenario() -> Event {
             llr_identity: -1.0, // Unknown person
             llr_presence: 0.2, // Normal presence pattern
             llr_token: -0.5,   // Partial token match
+            ..Default::default()
         },
     }
 }
@@ -181,6 +183,7 @@ fn create_family_scenario() -> Event {
             llr_identity: 2.5, // Strong family match
             llr_presence: 0.1, // Normal presence
             llr_token: 2.0,    // Strong token match
+            ..Default::default()
         },
     }
 }
@@ -203,6 +206,7 @@ fn create_unknown_scenario() -> Event {
             llr_identity: -1.5, // Unknown person
             llr_presence: -0.3, // Unusual presence
             llr_token: 0.0,    // No token
+            ..Default::default()
         },
     }
 }
@@ -240,6 +244,7 @@ fn engineer_event_for_probability(target_prob: f64) -> Event {
             llr_identity: base_llr,
             llr_presence: base_llr,
             llr_token: base_llr,
+            ..Default::default()
         },
     }
 }
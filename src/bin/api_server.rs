@@ -1,4 +1,6 @@
 use insane_ai_security::api::{ApiServer, ApiConfig};
+use insane_ai_security::api::database::{initialize_database, DatabaseConfig};
+use insane_ai_security::api::routes::AppState;
 use tracing_subscriber;
 
 #[tokio::main]
@@ -6,17 +8,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
-    // Create server configuration  
-    let config = ApiConfig::default();
+    // Create server configuration from the environment
+    let config = ApiConfig::from_env()?;
 
     println!("🚀 Starting Insane AI Security API Server");
     println!("📡 Configuration:");
     println!("   Host: {}", config.host);
     println!("   Port: {}", config.port);
     println!("   Static files: {:?}", config.static_files_dir);
+    println!("   TLS: {:?}", config.tls);
 
     // Create and start the API server
-    let server = ApiServer::new(config);
+    let pool = initialize_database(DatabaseConfig::default()).await?;
+    let server = ApiServer::new(config, AppState::new(pool));
     
     // This will start the server and block until it's shut down
     server.serve().await?;
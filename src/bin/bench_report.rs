@@ -0,0 +1,49 @@
+//! Compare the latest criterion results with benches/baseline.json and fail on regressions
+
+use insane_ai_security::bench_report::{compare, read_estimates, render, Baseline};
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: bench-report [--baseline <baseline.json>] [--criterion <target/criterion>] [--threshold <pct>] [--update]";
+
+fn main() -> anyhow::Result<()> {
+    let mut baseline_path = PathBuf::from("benches/baseline.json");
+    let mut criterion_dir = PathBuf::from("target/criterion");
+    let (mut threshold, mut update) = (None, false);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--update" {
+            update = true;
+            continue;
+        }
+        let value = args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+        match arg.as_str() {
+            "--baseline" => baseline_path = PathBuf::from(value),
+            "--criterion" => criterion_dir = PathBuf::from(value),
+            "--threshold" => threshold = Some(value.parse::<f64>()?),
+            _ => anyhow::bail!("unknown argument '{}'\n{}", arg, USAGE),
+        }
+    }
+
+    let current = read_estimates(&criterion_dir)?;
+    if current.is_empty() {
+        anyhow::bail!("no benchmark results in {}; run `cargo bench` first", criterion_dir.display());
+    }
+    let mut baseline: Baseline = serde_json::from_str(&std::fs::read_to_string(&baseline_path)?)?;
+    if let Some(threshold) = threshold {
+        baseline.threshold_pct = threshold;
+    }
+
+    if update {
+        baseline.benchmarks = current;
+        std::fs::write(&baseline_path, serde_json::to_string_pretty(&baseline)? + "\n")?;
+        println!("Updated {} with {} benchmark(s)", baseline_path.display(), baseline.benchmarks.len());
+        return Ok(());
+    }
+
+    let deltas = compare(&baseline, &current);
+    print!("{}", render(&deltas, baseline.threshold_pct));
+    if deltas.iter().any(|d| d.regressed) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! Chaos Pipeline Run
+//!
+//! Pushes a burst of events through the pipeline with VPS faults, corrupted
+//! snapshots and dropped storage writes injected, then reports how the
+//! pipeline coped: events served by the VPS, by the local fallback once the
+//! circuit opened, and outright failures.
+//!
+//! Build with `--features chaos`. Set NOVIN_CHAOS to inline JSON to override
+//! the default fault rates, e.g. `{"vps_failure_rate": 0.5, "seed": 7}`.
+
+use insane_ai_security::chaos::{ChaosConfig, ChaosInjector};
+use insane_ai_security::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier, LOCAL_FALLBACK_JOB_ID};
+use insane_ai_security::vps_client::VpsApiClient;
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[tokio::main]
+async fn main() {
+    println!("🌪️  NOVINAI CHAOS PIPELINE RUN");
+    println!("==============================");
+
+    let events: usize = std::env::args().nth(1).and_then(|n| n.parse().ok()).unwrap_or(200);
+    let config = match ChaosConfig::from_env() {
+        Ok(Some(config)) => config,
+        Ok(None) => ChaosConfig {
+            vps_delay_rate: 0.2,
+            vps_delay_ms: (50, 500),
+            vps_failure_rate: 0.3,
+            image_corruption_rate: 0.2,
+            storage_drop_rate: 0.2,
+            seed: Some(42),
+        },
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(2);
+        }
+    };
+    let chaos = match ChaosInjector::new(config) {
+        Ok(chaos) => Arc::new(chaos),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(2);
+        }
+    };
+    println!("⚙️  {:?}", chaos.config());
+
+    let vps_url = std::env::var("VPS_API_URL").unwrap_or_else(|_| "http://127.0.0.1:9".to_string());
+    let mut pipeline = EventPipeline::new(PipelineConfig::default(), VpsApiClient::new(vps_url))
+        .with_chaos(chaos.clone());
+
+    let (mut completed, mut fallback, mut failed) = (0, 0, 0);
+    for i in 0..events {
+        let event = RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: format!("camera_{}", i % 4),
+            timestamp: Utc::now().timestamp(),
+            data: "person_detected=true|dwell_time=30s".into(),
+            user_id: "chaos_user".to_string(),
+            home_id: format!("chaos_home_{}", i % 3),
+            image_url: Some(format!("http://127.0.0.1:9/snapshot/{}.jpg", i)),
+            image_data: None,
+        };
+        match pipeline.process_event(event, SubscriptionTier::Premium, "chaos_api_key").await {
+            Ok(processed) if processed.vps_job_id == LOCAL_FALLBACK_JOB_ID => fallback += 1,
+            Ok(_) => completed += 1,
+            Err(e) => {
+                failed += 1;
+                if failed <= 5 {
+                    println!("❌ event {}: {}", i, e);
+                }
+            }
+        }
+    }
+
+    println!("\n📊 RESULTS over {} events", events);
+    println!("   ✅ completed via VPS:  {}", completed);
+    println!("   🛟 local fallback:     {}", fallback);
+    println!("   ❌ failed:             {}", failed);
+    println!("   🌪️  injected:           {:?}", chaos.stats());
+    for endpoint in pipeline.get_vps_endpoint_status() {
+        println!(
+            "   🔌 {}: {:?}, opened {} time(s), {} call(s) rejected",
+            endpoint.url, endpoint.circuit.state, endpoint.circuit.times_opened, endpoint.circuit.rejected_calls
+        );
+    }
+}
@@ -2,21 +2,178 @@
 
 use insane_ai_security::{SecurityResult, SystemConfig};
 use insane_ai_security::core::*;
+use insane_ai_security::validation::{DaemonConfig, Validate};
+use insane_ai_security::thinking::{SensorReliabilityConfig, SensorReliabilityModel};
+use insane_ai_security::edge_inference::EdgeInferenceEngine;
+use insane_ai_security::backup::{self, BackupArchive, BackupBuilder, BackupError, BackupSection};
+use insane_ai_security::encryption::keyring_from_env;
+use insane_ai_security::federated_learning::{FederatedLearningNode, VpsFederationTransport};
+use insane_ai_security::thinking::{OnlineWeightLearner, WeightLearnerConfig};
+use std::sync::Arc;
+use std::path::{Path, PathBuf};
 use tokio::time::{interval, Duration};
 use tracing::{info, warn};
 use std::collections::HashMap;
 
+const USAGE: &str = "usage: security-daemon [--config <path>] [--check-config] [--backup <archive>] [--restore <archive>]";
+
 #[tokio::main]
 async fn main() -> SecurityResult<()> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
+    let mut config_path: Option<PathBuf> = std::env::var("NOVIN_CONFIG").ok().map(PathBuf::from);
+    let mut check_only = false;
+    let mut backup_to: Option<PathBuf> = None;
+    let mut restore_from: Option<PathBuf> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => config_path = Some(args.next().map(PathBuf::from).ok_or_else(|| anyhow::anyhow!(USAGE))?),
+            "--check-config" => check_only = true,
+            "--backup" => backup_to = Some(args.next().map(PathBuf::from).ok_or_else(|| anyhow::anyhow!(USAGE))?),
+            "--restore" => restore_from = Some(args.next().map(PathBuf::from).ok_or_else(|| anyhow::anyhow!(USAGE))?),
+            _ => anyhow::bail!("unknown argument '{}'\n{}", arg, USAGE),
+        }
+    }
+
+    let reliability_path = std::env::var("SENSOR_RELIABILITY_PATH").ok().map(PathBuf::from);
+
+    // Snapshot the config and learned state this daemon owns, then exit
+    if let Some(archive) = &backup_to {
+        match write_backup(archive, config_path.as_deref(), reliability_path.as_deref()) {
+            Ok(()) => println!("✅ Backup written to {}", archive.display()),
+            Err(e) => {
+                eprintln!("❌ Backup failed: {}", e);
+                std::process::exit(2);
+            }
+        }
+        return Ok(());
+    }
+
+    // Restore is all-or-nothing: the archive is fully validated before any file is replaced
+    let data_dir = PathBuf::from(std::env::var("NOVIN_DATA_DIR").unwrap_or_else(|_| "data".to_string()));
+    let mut reliability_path = reliability_path;
+    if let Some(archive_path) = &restore_from {
+        let keyring = match keyring_from_env(data_dir.join("keys")) {
+            Ok(keyring) => keyring,
+            Err(e) => {
+                eprintln!("❌ Master key unavailable: {}", e);
+                std::process::exit(2);
+            }
+        };
+        let archive = BackupArchive::read_file(archive_path)
+            .and_then(|a| match keyring {
+                Some(keyring) => a.with_keyring(keyring),
+                None => Ok(a),
+            })
+            .and_then(|a| a.validate().map(|_| a));
+        let archive = match archive {
+            Ok(archive) => archive,
+            Err(e) => {
+                eprintln!("❌ Refusing to restore {}: {}", archive_path.display(), e);
+                std::process::exit(2);
+            }
+        };
+        if check_only {
+            println!("✅ {} is a valid backup ({} entries)", archive_path.display(), archive.manifest().entries.len());
+            return Ok(());
+        }
+        if let Err(e) = archive.extract_to(&data_dir) {
+            eprintln!("❌ Restore into {} failed: {}", data_dir.display(), e);
+            std::process::exit(2);
+        }
+        info!("♻️ Restored backup from {} (taken {})", archive_path.display(), archive.manifest().created_at);
+        config_path = config_path.or_else(|| archive.extracted_config_path(&data_dir));
+        if archive.entry(backup::SENSOR_RELIABILITY_PATH).is_some() {
+            reliability_path = reliability_path.or_else(|| Some(data_dir.join(backup::SENSOR_RELIABILITY_PATH)));
+        }
+    }
+
+    // Dry run: validate and exit without starting anything
+    let daemon_config = match &config_path {
+        Some(path) => DaemonConfig::load(path),
+        None => Ok(DaemonConfig::default()),
+    };
+    let daemon_config = match daemon_config.and_then(|c| c.validate().map(|_| c)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(2);
+        }
+    };
+    if check_only {
+        match &config_path {
+            Some(path) => println!("✅ {} is valid", path.display()),
+            None => println!("✅ default configuration is valid"),
+        }
+        return Ok(());
+    }
+
     info!("🚀 Starting Insane AI Security System");
 
+    // Load and warm up on-device models before taking events
+    if let Some(edge) = daemon_config.edge_inference.clone() {
+        match EdgeInferenceEngine::load(edge).and_then(|engine| engine.warm_up().map(|t| (engine, t))) {
+            Ok((engine, took)) => info!("🧠 Edge models ready: {:?} (warm-up {}ms)", engine.loaded(), took.as_millis()),
+            Err(e) => {
+                eprintln!("❌ Edge inference unavailable: {}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // Cross-home weight sharing is opt-in and needs the learned weights file to merge into
+    if daemon_config.federated_learning.enabled {
+        match std::env::var("LLR_WEIGHTS_PATH").ok().map(PathBuf::from) {
+            Some(path) => {
+                let learner = OnlineWeightLearner::load(WeightLearnerConfig::default(), &path).unwrap_or_else(|e| {
+                    warn!("Could not load learned weights from {}: {}", path.display(), e);
+                    OnlineWeightLearner::default()
+                });
+                let vps_url = std::env::var("VPS_API_URL").unwrap_or_else(|_| "https://api.vps.example.com".to_string());
+                let node = FederatedLearningNode::new(
+                    daemon_config.federated_learning.clone(),
+                    Arc::new(learner),
+                    Arc::new(VpsFederationTransport::new(&vps_url)),
+                ).with_persistence(path);
+                if Arc::new(node).spawn().is_some() {
+                    info!("🤝 Federated learning enabled");
+                }
+            }
+            None => warn!("Federated learning is enabled but LLR_WEIGHTS_PATH is not set; skipping"),
+        }
+    }
+
     let mut system = InsaneSecuritySystem::new();
+    system.config = daemon_config.system;
+    // Learned sensor reliability replaces the fixed fusion constants when available
+    if let Some(path) = reliability_path {
+        match SensorReliabilityModel::load(SensorReliabilityConfig::default(), &path) {
+            Ok(model) => {
+                let pooled = model.pooled();
+                if !pooled.is_empty() {
+                    system.sensor_fusion.fusion_confidence = pooled.values().sum::<f64>() / pooled.len() as f64;
+                }
+                system.sensor_fusion.sensor_reliability = pooled;
+            }
+            Err(e) => warn!("Could not load sensor reliability from {}: {}", path.display(), e),
+        }
+    }
     system.run().await
 }
 
+fn write_backup(archive: &Path, config: Option<&Path>, reliability: Option<&Path>) -> Result<(), BackupError> {
+    let mut builder = BackupBuilder::new();
+    if let Some(path) = config {
+        builder.add_config_file(path)?;
+    }
+    if let Some(path) = reliability.filter(|p| p.exists()) {
+        builder.add(BackupSection::Calibration, backup::SENSOR_RELIABILITY_PATH, None, std::fs::read(path)?);
+    }
+    builder.write_to(archive)
+}
+
 #[derive(Debug)]
 pub struct InsaneSecuritySystem {
     correlation_engine: EventCorrelationEngine,
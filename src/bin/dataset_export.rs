@@ -0,0 +1,43 @@
+//! Turn labeled incidents from an incident store snapshot into an anonymized CSV or Parquet
+//! dataset for offline model training
+
+use insane_ai_security::dataset_export::{export, DatasetFormat};
+use insane_ai_security::thinking::{IncidentLabel, IncidentStoreSnapshot};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: dataset-export --incidents <snapshot.json> --labels <labels.json> --out <dataset.csv|dataset.parquet> [--format csv|parquet] [--salt <secret>]";
+
+fn main() -> anyhow::Result<()> {
+    let (mut incidents, mut labels, mut out, mut format, mut salt) = (None, None, None, None, None);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+        match arg.as_str() {
+            "--incidents" => incidents = Some(PathBuf::from(value)),
+            "--labels" => labels = Some(PathBuf::from(value)),
+            "--out" => out = Some(PathBuf::from(value)),
+            "--format" => format = Some(value.parse::<DatasetFormat>()?),
+            "--salt" => salt = Some(value),
+            _ => anyhow::bail!("unknown argument '{}'\n{}", arg, USAGE),
+        }
+    }
+
+    let incidents = incidents.ok_or_else(|| anyhow::anyhow!(USAGE))?;
+    let snapshot: IncidentStoreSnapshot = serde_json::from_str(&std::fs::read_to_string(incidents)?)?;
+    // Labels file: {"<incident_id>": "threat" | "benign"}, as for what-if
+    let labels: HashMap<u64, IncidentLabel> = serde_json::from_str(&std::fs::read_to_string(labels.ok_or_else(|| anyhow::anyhow!(USAGE))?)?)?;
+    let out = out.ok_or_else(|| anyhow::anyhow!(USAGE))?;
+    let format = match format {
+        Some(format) => format,
+        None if out.extension().is_some_and(|e| e == "parquet") => DatasetFormat::Parquet,
+        None => DatasetFormat::Csv,
+    };
+    // Without a salt the pseudonyms differ on every run, so homes cannot be joined across exports
+    let salt = salt.unwrap_or_else(|| hex::encode(rand::random::<[u8; 16]>()));
+
+    let file = std::io::BufWriter::new(std::fs::File::create(&out)?);
+    let rows = export(&snapshot.incidents, &labels, &salt, format, file)?;
+    println!("Wrote {} labeled incidents of {} to {}", rows, snapshot.incidents.len(), out.display());
+    Ok(())
+}
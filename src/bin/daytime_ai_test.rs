@@ -25,7 +25,7 @@ async fn main() {
         event_id: Uuid::new_v4(),
         sensor_id: "front_door_camera".to_string(),
         timestamp: Utc::now().timestamp(),
-        data: "person_detected=true|face_recognized=false|dwell_time=120s|knocked=false|rang_doorbell=false|behavior=aggressive_probing|tools_detected=true|multiple_attempts=true".to_string(),
+        data: "person_detected=true|face_recognized=false|dwell_time=120s|knocked=false|rang_doorbell=false|behavior=aggressive_probing|tools_detected=true|multiple_attempts=true".into(),
         user_id: "user_critical".to_string(),
         home_id: "home_test".to_string(),
     };
@@ -55,7 +55,7 @@ async fn main() {
         event_id: Uuid::new_v4(),
         sensor_id: "front_door_camera".to_string(),
         timestamp: Utc::now().timestamp(),
-        data: "person_detected=true|face_recognized=true|identity=family_member|behavior=normal_entry|keys_detected=true".to_string(),
+        data: "person_detected=true|face_recognized=true|identity=family_member|behavior=normal_entry|keys_detected=true".into(),
         user_id: "user_normal".to_string(),
         home_id: "home_test".to_string(),
     };
@@ -24,6 +24,7 @@ fn main() {
         llr_identity: -2.8, // Multiple unknown people
         llr_presence: 2.5,  // Strong detection
         llr_token: -3.0,    // No authorization
+        ..Default::default()
     };
     
     println!("\n🔥 INTRUDER CASE ANALYSIS:");
@@ -53,6 +54,7 @@ fn main() {
         llr_identity: 2.1,  // Recognized family member
         llr_presence: 0.8,  // Normal presence
         llr_token: 1.5,     // Authorized access
+        ..Default::default()
     };
     
     println!("\n✅ FAMILY CASE ANALYSIS:");
@@ -17,6 +17,7 @@ fn main() {
         llr_identity: -2.8, 
         llr_presence: 2.5,  
         llr_token: -3.0,    
+        ..Default::default()
     };
     
     println!("\n🔥 INTRUDER EVIDENCE:");
@@ -30,6 +31,7 @@ fn main() {
         llr_identity: intruder_raw.llr_identity.clamp(-config.neg_cap, config.pos_cap),
         llr_presence: intruder_raw.llr_presence.clamp(-config.neg_cap, config.pos_cap),
         llr_token: intruder_raw.llr_token.clamp(-config.neg_cap, config.pos_cap),
+        ..Default::default()
     };
     
     println!("After capping: {:.2}", intruder_capped.sum());
@@ -49,6 +51,7 @@ fn main() {
         llr_identity: 2.1,  
         llr_presence: 0.8,  
         llr_token: 1.5,     
+        ..Default::default()
     };
     
     println!("\n✅ FAMILY EVIDENCE:");
@@ -61,6 +64,7 @@ fn main() {
         llr_identity: family_raw.llr_identity.clamp(-config.neg_cap, config.pos_cap),
         llr_presence: family_raw.llr_presence.clamp(-config.neg_cap, config.pos_cap),
         llr_token: family_raw.llr_token.clamp(-config.neg_cap, config.pos_cap),
+        ..Default::default()
     };
     
     println!("After capping: {:.2}", family_capped.sum());
@@ -241,7 +241,7 @@ fn simulate_security_response(scenario: &Scenario, mode: SecurityMode) -> serde_
         "confidence": format!("{:.3}", assessment.confidence_score),
         "response_time_minutes": assessment.temporal_horizon.num_minutes(),
         "countermeasures": assessment.countermeasures,
-        "explanation": assessment.explainability_trace,
+        "explanation": assessment.explanation.render(),
         "alert_level": determine_alert_level(assessment.threat_level)
     })
 }
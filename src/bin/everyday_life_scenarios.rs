@@ -37,6 +37,7 @@ fn scenario_mail_carrier_midday() {
             llr_identity: -0.2,  // Recognizable as mail carrier
             llr_presence: -0.4,  // User is home
             llr_token: -2.2,     // Valid USPS token
+            ..Default::default()
         },
     };
 
@@ -71,6 +72,7 @@ fn scenario_kids_playing_after_school() {
             llr_identity: 0.3,   // Unknown kid but not threatening
             llr_presence: -0.5,  // User home
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -91,6 +93,7 @@ fn scenario_kids_playing_after_school() {
             llr_identity: 0.3,
             llr_presence: -0.5,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
 
@@ -126,6 +129,7 @@ fn scenario_neighbor_retrieves_package_evening() {
             llr_identity: -1.2,  // Recognized neighbor
             llr_presence: -0.2,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
 
@@ -160,6 +164,7 @@ fn scenario_pet_motion_night() {
             llr_identity: 0.0,   // No person detected
             llr_presence: -0.1,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
 
@@ -194,6 +199,7 @@ fn scenario_garbage_pickup_early_morning() {
             llr_identity: -0.1,  // Service uniform visible
             llr_presence: -0.2,
             llr_token: -0.8,     // Valid service permit
+            ..Default::default()
         },
     };
 
@@ -228,6 +234,7 @@ fn scenario_wrong_address_delivery_midday() {
             llr_identity: 0.2,   // Unknown courier
             llr_presence: -0.2,
             llr_token: 0.5,      // Token mismatch but not malicious
+            ..Default::default()
         },
     };
 
@@ -9,7 +9,7 @@ async fn main() {
     println!("=================================================");
     
     let config = ThinkingAIConfig::default();
-    let mut thinking_ai = ThinkingAIProcessor::new(config);
+    let thinking_ai = ThinkingAIProcessor::new(config);
     
     // CORRECTED EVIDENCE SEMANTICS:
     // POSITIVE LLR = EVIDENCE FOR THREAT/SUSPICIOUS
@@ -35,6 +35,7 @@ async fn main() {
                     llr_identity: 2.1,  // Unknown people -> HIGH THREAT (FIXED!)
                     llr_presence: 2.0,  // Strong presence -> THREAT
                     llr_token: 2.5,     // No authorization -> HIGH THREAT (FIXED!)
+                    ..Default::default()
                 },
             }
         ),
@@ -57,6 +58,7 @@ async fn main() {
                     llr_identity: 1.5,  // Unknown person -> MODERATE THREAT (FIXED!)
                     llr_presence: 1.2,  // Clear presence -> THREAT
                     llr_token: 2.0,     // No authorization -> THREAT (FIXED!)
+                    ..Default::default()
                 },
             }
         ),
@@ -79,6 +81,7 @@ async fn main() {
                     llr_identity: 0.4,  // Unknown but polite -> MILD THREAT (FIXED!)
                     llr_presence: -0.2,  // Normal presence -> NEUTRAL
                     llr_token: 0.5,     // No token but rang doorbell -> MILD THREAT (FIXED!)
+                    ..Default::default()
                 },
             }
         ),
@@ -101,6 +104,7 @@ async fn main() {
                     llr_identity: -0.2, // Uniform visible -> REDUCES THREAT (FIXED!)
                     llr_presence: -0.3, // Brief appropriate presence -> REDUCES THREAT
                     llr_token: 0.3,     // Expected but no token -> SLIGHT THREAT (FIXED!)
+                    ..Default::default()
                 },
             }
         ),
@@ -123,6 +127,7 @@ async fn main() {
                     llr_identity: -1.8, // RECOGNIZED family -> STRONGLY REDUCES THREAT (FIXED!)
                     llr_presence: -0.4, // Quick normal entry -> REDUCES THREAT
                     llr_token: -1.2,    // HAS authorization -> STRONGLY REDUCES THREAT (FIXED!)
+                    ..Default::default()
                 },
             }
         ),
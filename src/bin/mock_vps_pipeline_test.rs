@@ -12,7 +12,7 @@ async fn main() {
     
     // Initialize ThinkingAI directly
     let config = ThinkingAIConfig::default();
-    let mut thinking_ai = ThinkingAIProcessor::new(config);
+    let thinking_ai = ThinkingAIProcessor::new(config);
     
     println!("\n🧠 TESTING VIGOROUS EDGE CASES - NO VPS DEPENDENCY");
     println!("=================================================");
@@ -38,6 +38,7 @@ async fn main() {
                     llr_identity: -2.1, // Multiple unknown people
                     llr_presence: 2.0,  // Strong presence detection
                     llr_token: -2.5,    // No authorization
+                    ..Default::default()
                 },
             }
         ),
@@ -60,6 +61,7 @@ async fn main() {
                     llr_identity: -1.5, // Unknown person
                     llr_presence: 1.2,  // Clear presence
                     llr_token: -2.0,    // No authorization
+                    ..Default::default()
                 },
             }
         ),
@@ -82,6 +84,7 @@ async fn main() {
                     llr_identity: -0.8, // Unknown but not suspicious
                     llr_presence: 0.6,  // Normal presence
                     llr_token: -1.0,    // No token but rang doorbell
+                    ..Default::default()
                 },
             }
         ),
@@ -104,6 +107,7 @@ async fn main() {
                     llr_identity: -0.2, // Uniform visible
                     llr_presence: 0.3,  // Brief presence
                     llr_token: -0.3,    // No token but expected
+                    ..Default::default()
                 },
             }
         ),
@@ -126,6 +130,7 @@ async fn main() {
                     llr_identity: 1.8,  // Recognized family member
                     llr_presence: 0.4,  // Quick entry
                     llr_token: 1.2,     // Has authorization
+                    ..Default::default()
                 },
             }
         ),
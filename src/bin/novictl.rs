@@ -0,0 +1,143 @@
+//! novictl — command-line administration for a running Novinai API
+//!
+//! Talks to the admin endpoints with a bearer token that carries the `admin`
+//! scope. NOVICTL_URL selects the server (default http://localhost:8080) and
+//! NOVICTL_TOKEN supplies the token.
+//!
+//! "rotate-secret" rotates a webhook endpoint's signing secret; those are the
+//! only shared keys the API manages. "self-test" also needs `home:manage`, and
+//! exits non-zero when the health matrix has a failure.
+
+use futures_util::StreamExt;
+use insane_ai_security::self_test::demo_events;
+use serde_json::Value;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+
+const USAGE: &str = "usage: novictl <command>
+  homes                              list homes with incident counts
+  tail [home_id]                     stream live alert updates
+  summary <home_id>                  build and send the morning summary now
+  replay <events.json> [tier]        replay a JSON array of raw events
+  dlq [--flush] [home_id]            list or redeliver dead-lettered webhooks
+  rotate-secret <home_id> <endpoint> rotate a webhook signing secret
+  timeline <home_id> <incident_id>   dump an incident's timeline
+  metrics                            VPS endpoint health and cache hit rate
+  self-test <home_id> [--deliver]    run the self-test and print its health matrix
+  demo <home_id> <user_id> [count]   replay synthetic demo events (needs the synthetic_events middleware)";
+
+struct Client {
+    base: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl Client {
+    fn from_env() -> anyhow::Result<Self> {
+        let base = std::env::var("NOVICTL_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+        let token = std::env::var("NOVICTL_TOKEN").map_err(|_| anyhow::anyhow!("NOVICTL_TOKEN is not set"))?;
+        Ok(Self { base: base.trim_end_matches('/').to_string(), token, http: reqwest::Client::new() })
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> anyhow::Result<Value> {
+        let response = request.bearer_auth(&self.token).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("server returned {}", status);
+        }
+        let body: Value = response.json().await?;
+        Ok(body.get("data").cloned().unwrap_or(body))
+    }
+
+    async fn get(&self, path: &str) -> anyhow::Result<Value> {
+        self.send(self.http.get(format!("{}{}", self.base, path))).await
+    }
+
+    async fn post(&self, path: &str, body: Option<Value>) -> anyhow::Result<Value> {
+        let request = self.http.post(format!("{}{}", self.base, path));
+        self.send(match body {
+            Some(body) => request.json(&body),
+            None => request,
+        }).await
+    }
+
+    async fn tail(&self, home_id: Option<&str>) -> anyhow::Result<()> {
+        let ws_url = format!("{}/api/ws", self.base.replacen("http", "ws", 1));
+        let mut request = ws_url.into_client_request()?;
+        request.headers_mut().insert("Authorization", format!("Bearer {}", self.token).parse()?);
+        let (mut socket, _) = tokio_tungstenite::connect_async(request).await?;
+        eprintln!("📡 Connected, waiting for updates (Ctrl-C to stop)");
+        while let Some(message) = socket.next().await {
+            let Message::Text(text) = message? else { continue };
+            let Ok(update) = serde_json::from_str::<Value>(&text) else { continue };
+            if home_id.map_or(true, |h| update.get("home_id").and_then(Value::as_str) == Some(h)) {
+                println!("{}", update);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print(value: &Value) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let client = Client::from_env()?;
+
+    match args.as_slice() {
+        ["homes"] => print(&client.get("/api/admin/homes").await?),
+        ["metrics"] => print(&client.get("/api/admin/metrics").await?),
+        ["tail"] => client.tail(None).await,
+        ["tail", home_id] => client.tail(Some(home_id)).await,
+        ["summary", home_id] => print(&client.post(&format!("/api/admin/homes/{}/morning-summary", home_id), None).await?),
+        ["replay", path, rest @ ..] if rest.len() <= 1 => {
+            let events: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            if !events.is_array() {
+                anyhow::bail!("{} must contain a JSON array of events", path);
+            }
+            let mut body = serde_json::json!({ "events": events });
+            if let [tier] = rest {
+                body["tier"] = Value::String(tier.to_string());
+            }
+            print(&client.post("/api/admin/events/replay", Some(body)).await?)
+        }
+        ["dlq", rest @ ..] => {
+            let flush = rest.first() == Some(&"--flush");
+            let home = rest.iter().find(|a| **a != "--flush");
+            let query = home.map(|h| format!("?home_id={}", h)).unwrap_or_default();
+            if flush {
+                print(&client.post(&format!("/api/admin/webhook-dead-letters/flush{}", query), None).await?)
+            } else {
+                print(&client.get(&format!("/api/admin/webhook-dead-letters{}", query)).await?)
+            }
+        }
+        ["rotate-secret", home_id, endpoint_id] => {
+            print(&client.post(&format!("/api/admin/homes/{}/webhooks/{}/rotate-secret", home_id, endpoint_id), None).await?)
+        }
+        ["timeline", home_id, incident_id] => {
+            print(&client.get(&format!("/api/admin/homes/{}/incidents/{}/timeline", home_id, incident_id)).await?)
+        }
+        ["self-test", home_id, rest @ ..] if rest.is_empty() || rest == ["--deliver"] => {
+            let body = serde_json::json!({ "deliver": !rest.is_empty() });
+            let report = client.post(&format!("/api/homes/{}/self-test", home_id), Some(body)).await?;
+            print(&report)?;
+            if report.get("healthy") != Some(&Value::Bool(true)) {
+                anyhow::bail!("self-test found problems");
+            }
+            Ok(())
+        }
+        ["demo", home_id, user_id, rest @ ..] if rest.len() <= 1 => {
+            let count: usize = rest.first().map_or(Ok(9), |c| c.parse())?;
+            // Spread over the last few minutes, so none of them is in the future
+            let spacing = chrono::Duration::seconds(20);
+            let start = chrono::Utc::now() - spacing * count as i32;
+            let events = demo_events(home_id, user_id, start, spacing, count);
+            print(&client.post("/api/admin/events/replay", Some(serde_json::json!({ "events": events }))).await?)
+        }
+        _ => anyhow::bail!("{}", USAGE),
+    }
+}
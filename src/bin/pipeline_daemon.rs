@@ -58,7 +58,7 @@ async fn main() {
             event_id: Uuid::new_v4(),
             sensor_id: format!("cam-{:02}", (event_counter % 4) + 1),
             timestamp: chrono::Utc::now().timestamp(),
-            data: "[simulated_image_data]".to_string(),
+            data: "[simulated_image_data]".into(),
             user_id,
             home_id,
         };
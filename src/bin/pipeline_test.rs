@@ -10,20 +10,26 @@ use axum::{
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
+use insane_ai_security::api::database::{initialize_database, DatabaseConfig};
 use insane_ai_security::api::events;
+use insane_ai_security::api::routes::AppState;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
+    // Events go through the shared pipeline, with dedup records in the database
+    let state = AppState::new(initialize_database(DatabaseConfig::default()).await?);
+
     // Build the router
     let app = Router::new()
         .route("/", get(root_handler))
         .route("/health", get(events::health))
         .route("/api/events", post(events::submit_event))
         .route("/api/events/:home_id", get(events::get_events))
-        .layer(CorsLayer::permissive());
+        .layer(CorsLayer::permissive())
+        .with_state(state);
 
     // Start server
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
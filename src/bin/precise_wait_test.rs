@@ -14,7 +14,7 @@ fn main() {
 }
 
 fn test_precise_wait_range() {
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     println!("🔬 Testing events engineered to hit specific probability ranges...\n");
     
@@ -112,6 +112,7 @@ fn engineer_event_for_probability(target_prob: f64) -> Event {
             llr_identity: base_llr,
             llr_presence: base_llr,
             llr_token: base_llr,
+            ..Default::default()
         },
     }
 }
@@ -28,7 +28,7 @@ async fn main() {
                 event_id: Uuid::new_v4(),
                 sensor_id: "backyard_camera".to_string(),
                 timestamp: Utc::now().timestamp(), // Current time to avoid overnight
-                data: "people_count=3|faces_recognized=0|behavior=coordinated_breaking_entry|weapons_detected=true|forced_entry_attempt=true|duration=180s|glass_breaking=true".to_string(),
+                data: "people_count=3|faces_recognized=0|behavior=coordinated_breaking_entry|weapons_detected=true|forced_entry_attempt=true|duration=180s|glass_breaking=true".into(),
                 user_id: "user_critical".to_string(),
                 home_id: "home_vulnerable".to_string(),
             },
@@ -40,7 +40,7 @@ async fn main() {
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "person_detected=true|face_recognized=false|tools_detected=true|behavior=probing_locks|dwell_time=90s|no_doorbell=true|suspicious_movement=true".to_string(),
+                data: "person_detected=true|face_recognized=false|tools_detected=true|behavior=probing_locks|dwell_time=90s|no_doorbell=true|suspicious_movement=true".into(),
                 user_id: "user_elevated".to_string(),
                 home_id: "home_suburban".to_string(),
             },
@@ -52,7 +52,7 @@ async fn main() {
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "person_detected=true|face_recognized=false|behavior=normal_approach|dwell_time=45s|rang_doorbell=true|time_unusual=true".to_string(),
+                data: "person_detected=true|face_recognized=false|behavior=normal_approach|dwell_time=45s|rang_doorbell=true|time_unusual=true".into(),
                 user_id: "user_standard".to_string(),
                 home_id: "home_normal".to_string(),
             },
@@ -64,7 +64,7 @@ async fn main() {
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "person_detected=true|uniform_detected=true|package_visible=true|behavior=professional|dwell_time=30s|rang_doorbell=true|daytime=true".to_string(),
+                data: "person_detected=true|uniform_detected=true|package_visible=true|behavior=professional|dwell_time=30s|rang_doorbell=true|daytime=true".into(),
                 user_id: "user_wait".to_string(),
                 home_id: "home_busy".to_string(),
             },
@@ -76,7 +76,7 @@ async fn main() {
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "person_detected=true|face_recognized=true|identity=family_member|keys_detected=true|behavior=normal_entry|authorized=true".to_string(),
+                data: "person_detected=true|face_recognized=true|identity=family_member|keys_detected=true|behavior=normal_entry|authorized=true".into(),
                 user_id: "user_ignore".to_string(),
                 home_id: "home_family".to_string(),
             },
@@ -11,7 +11,7 @@ async fn main() {
     
     // Initialize ThinkingAI directly
     let config = ThinkingAIConfig::default();
-    let mut thinking_ai = ThinkingAIProcessor::new(config);
+    let thinking_ai = ThinkingAIProcessor::new(config);
     
     println!("\n🔥 TESTING EXTREME EDGE CASES");
     println!("============================");
@@ -37,6 +37,7 @@ async fn main() {
             llr_identity: -2.1, // Unknown person
             llr_presence: 1.5,  // Strong presence detection
             llr_token: -3.0,    // No authorization
+            ..Default::default()
         },
     };
     
@@ -70,6 +71,7 @@ async fn main() {
             llr_identity: 2.1,  // Recognized family member
             llr_presence: 0.8,  // Normal presence
             llr_token: 1.5,     // Authorized access
+            ..Default::default()
         },
     };
     
@@ -103,6 +105,7 @@ async fn main() {
             llr_identity: -2.8, // Multiple unknown people
             llr_presence: 2.5,  // Strong detection of multiple people
             llr_token: -3.0,    // No authorization
+            ..Default::default()
         },
     };
     
@@ -136,6 +139,7 @@ async fn main() {
             llr_identity: -0.3, // Unrecognized but uniform visible
             llr_presence: 0.5,  // Normal detection
             llr_token: -0.8,    // No token but expected for delivery
+            ..Default::default()
         },
     };
     
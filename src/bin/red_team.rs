@@ -0,0 +1,106 @@
+//! Generate adversarial event scenarios, score how well the scoring engine detects them,
+//! and optionally push the raw events through the full pipeline
+
+use insane_ai_security::pipeline::{EventPipeline, PipelineConfig, SubscriptionTier};
+use insane_ai_security::red_team::{generate, run, RedTeamConfig, Scenario, ScenarioKind, ScenarioScore};
+use insane_ai_security::thinking::ThinkingAIConfig;
+use insane_ai_security::validation::Validate;
+use insane_ai_security::vps_client::VpsApiClient;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "usage: red-team [--scenario all|slow-recon|entity-flood|spoofed-face] [--seed <n>] [--config <thinking.yaml>] [--emit <events.jsonl>] [--pipeline]";
+
+fn load_config(path: &Path) -> anyhow::Result<ThinkingAIConfig> {
+    let text = std::fs::read_to_string(path)?;
+    let config: ThinkingAIConfig = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&text)?,
+        _ => serde_yaml::from_str(&text)?,
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+fn print_score(score: &ScenarioScore) {
+    let verdict = if score.detected { "DETECTED" } else { "MISSED" };
+    println!("{:<14} {}", score.scenario.name(), verdict);
+    println!("  events:        {} hostile, {} benign", score.hostile_events, score.benign_events);
+    println!("  incidents:     {}/{} hostile alerted ({:.0}%)", score.detected_incidents, score.hostile_incidents, score.detection_rate() * 100.0);
+    match score.time_to_detect_s {
+        Some(t) => println!("  time to alert: {:.0}s after first hostile event", t),
+        None => println!("  time to alert: never"),
+    }
+    println!("  peak hostile p: {:.3}", score.peak_hostile_probability);
+    println!("  false alerts:  {}/{} benign incidents", score.false_alerts, score.benign_incidents);
+}
+
+async fn run_pipeline(scenario: &Scenario) -> anyhow::Result<()> {
+    let url = std::env::var("VPS_API_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let mut config = PipelineConfig::default();
+    config.overnight_enabled = false;
+    let mut pipeline = EventPipeline::new(config, VpsApiClient::new(url));
+    let (mut processed, mut failed) = (0, 0);
+    for step in &scenario.steps {
+        match pipeline.process_event(step.raw_event(), SubscriptionTier::Premium, "red-team").await {
+            Ok(_) => processed += 1,
+            Err(e) => {
+                failed += 1;
+                eprintln!("  event {} failed: {}", step.raw.event_id, e);
+            }
+        }
+    }
+    println!("  pipeline:      {} processed, {} failed, {} incidents", processed, failed, pipeline.home_incidents(&scenario.home_id).len());
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut kinds = ScenarioKind::ALL.to_vec();
+    let mut red_team = RedTeamConfig::default();
+    let (mut config_path, mut emit, mut pipeline) = (None, None, false);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--pipeline" {
+            pipeline = true;
+            continue;
+        }
+        let value = args.next().ok_or_else(|| anyhow::anyhow!(USAGE))?;
+        match arg.as_str() {
+            "--scenario" if value == "all" => kinds = ScenarioKind::ALL.to_vec(),
+            "--scenario" => kinds = vec![ScenarioKind::parse(&value).ok_or_else(|| anyhow::anyhow!("unknown scenario '{}'\n{}", value, USAGE))?],
+            "--seed" => red_team.seed = value.parse()?,
+            "--config" => config_path = Some(PathBuf::from(value)),
+            "--emit" => emit = Some(PathBuf::from(value)),
+            _ => anyhow::bail!("unknown argument '{}'\n{}", arg, USAGE),
+        }
+    }
+    let config = match config_path {
+        Some(path) => load_config(&path)?,
+        None => ThinkingAIConfig::default(),
+    };
+    let mut emit = match emit {
+        Some(path) => Some(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => None,
+    };
+
+    let mut scores = Vec::new();
+    for kind in kinds {
+        let scenario = generate(kind, &red_team);
+        if let Some(out) = emit.as_mut() {
+            for step in &scenario.steps {
+                writeln!(out, "{}", serde_json::to_string(&step.raw)?)?;
+            }
+        }
+        let score = run(&scenario, config.clone());
+        print_score(&score);
+        if pipeline {
+            run_pipeline(&scenario).await?;
+        }
+        scores.push(score);
+    }
+    if let Some(mut out) = emit {
+        out.flush()?;
+    }
+    println!("{}", serde_json::to_string_pretty(&scores)?);
+    Ok(())
+}
@@ -34,7 +34,7 @@ fn main() {
     println!("Confidence: {:.2}", guardian_assessment.confidence_score);
     println!("Response Time: {} minutes", guardian_assessment.temporal_horizon.num_minutes());
     println!("Countermeasures: {:?}", guardian_assessment.countermeasures);
-    println!("Explanation: {}", guardian_assessment.explainability_trace);
+    println!("Explanation: {}", guardian_assessment.explanation.render());
 
     // Test Stealth Mode
     println!("\n🥷 STEALTH MODE TEST");
@@ -48,7 +48,7 @@ fn main() {
     println!("Confidence: {:.2}", stealth_assessment.confidence_score);
     println!("Response Time: {} minutes", stealth_assessment.temporal_horizon.num_minutes());
     println!("Countermeasures: {:?}", stealth_assessment.countermeasures);
-    println!("Explanation: {}", stealth_assessment.explainability_trace);
+    println!("Explanation: {}", stealth_assessment.explanation.render());
 
     // Test Perimeter Guard Mode
     println!("\n🚧 PERIMETER GUARD MODE TEST");
@@ -62,7 +62,7 @@ fn main() {
     println!("Confidence: {:.2}", perimeter_assessment.confidence_score);
     println!("Response Time: {} minutes", perimeter_assessment.temporal_horizon.num_minutes());
     println!("Countermeasures: {:?}", perimeter_assessment.countermeasures);
-    println!("Explanation: {}", perimeter_assessment.explainability_trace);
+    println!("Explanation: {}", perimeter_assessment.explanation.render());
 
     println!("\n✅ All three security modes are fully implemented and operational!");
     println!("🎯 Key Differences:");
@@ -35,7 +35,7 @@ fn main() {
         println!("  Response Time: {} minutes", guardian_result.temporal_horizon.num_minutes());
         println!("  Alert Level: {}", get_alert_level(guardian_result.threat_level));
         println!("  Countermeasures: {:?}", guardian_result.countermeasures);
-        println!("  Explanation: {}", guardian_result.explainability_trace);
+        println!("  Explanation: {}", guardian_result.explanation.render());
         
         // Stealth Mode
         let mut stealth_system = InsaneSecuritySystem::default();
@@ -48,7 +48,7 @@ fn main() {
         println!("  Response Time: {} minutes", stealth_result.temporal_horizon.num_minutes());
         println!("  Alert Level: {}", get_alert_level(stealth_result.threat_level));
         println!("  Countermeasures: {:?}", stealth_result.countermeasures);
-        println!("  Explanation: {}", stealth_result.explainability_trace);
+        println!("  Explanation: {}", stealth_result.explanation.render());
         
         // Perimeter Guard Mode
         let mut perimeter_system = InsaneSecuritySystem::default();
@@ -61,7 +61,7 @@ fn main() {
         println!("  Response Time: {} minutes", perimeter_result.temporal_horizon.num_minutes());
         println!("  Alert Level: {}", get_alert_level(perimeter_result.threat_level));
         println!("  Countermeasures: {:?}", perimeter_result.countermeasures);
-        println!("  Explanation: {}", perimeter_result.explainability_trace);
+        println!("  Explanation: {}", perimeter_result.explanation.render());
         
         println!("\n💡 MODE COMPARISON:");
         println!("  Guardian vs Stealth vs Perimeter: {:.3} vs {:.3} vs {:.3}", 
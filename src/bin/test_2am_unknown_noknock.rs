@@ -3,7 +3,7 @@ use insane_ai_security::thinking::{ThinkingAIProcessor, ThinkingAIConfig, Event,
 fn main() {
     println!("🌙 2AM UNKNOWN PERSON - CAMERA TRIGGERS ONLY (NO KNOCK)\n");
 
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "test_home_2am_noknock";
 
     // Event 1: brief motion at 2AM
@@ -25,6 +25,7 @@ fn main() {
             llr_identity: 0.9,  // unknown person
             llr_presence: 0.3,  // uncertain presence state
             llr_token: 0.0,
+            ..Default::default()
         },
     };
 
@@ -53,6 +54,7 @@ fn main() {
             llr_identity: 0.95,
             llr_presence: 0.35,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
 
@@ -8,7 +8,7 @@ fn main() {
     println!("Scenario: Unknown person at front door at 2AM, two events 17 seconds apart");
     println!("Expected: High threat detection with escalating concern\n");
 
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "test_home_2am";
     
     // Event 1: Initial detection at 2:00:00 AM
@@ -30,6 +30,7 @@ fn main() {
             llr_identity: 1.4,  // Completely unknown person
             llr_presence: 0.7,  // High confidence user is asleep
             llr_token: 0.0,     // No token provided
+            ..Default::default()
         },
     };
     
@@ -77,6 +78,7 @@ fn main() {
             llr_identity: 1.5,  // Still unknown, now more concerning
             llr_presence: 0.8,  // Even more confident user is sleeping
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
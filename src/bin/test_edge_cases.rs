@@ -14,7 +14,7 @@ fn main() {
 fn test_all_paths_covered() {
     println!("Test: all logic paths produce outcomes...");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "edge_case_test";
 
     let base_event = Event {
@@ -34,6 +34,7 @@ fn test_all_paths_covered() {
             llr_identity: 0.0,
             llr_presence: 0.0,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
 
@@ -76,7 +77,7 @@ fn test_all_paths_covered() {
 fn test_missing_evidence_fields() {
     println!("\nTest: Missing or malformed evidence fields...");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "missing_evidence_test";
 
     let event = Event {
@@ -96,6 +97,7 @@ fn test_missing_evidence_fields() {
             llr_identity: 0.0,
             llr_presence: 0.0,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
 
@@ -109,7 +111,7 @@ fn test_missing_evidence_fields() {
 fn test_extreme_llr_values() {
     println!("\nTest: Extreme LLR values (calibration and saturation)...");
 
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "extreme_llr_test";
 
     // Extreme positive
@@ -130,6 +132,7 @@ fn test_extreme_llr_values() {
             llr_identity: 100.0,
             llr_presence: 100.0,
             llr_token: 100.0,
+            ..Default::default()
         },
     };
 
@@ -157,6 +160,7 @@ fn test_extreme_llr_values() {
             llr_identity: -100.0,
             llr_presence: -100.0,
             llr_token: -100.0,
+            ..Default::default()
         },
     };
 
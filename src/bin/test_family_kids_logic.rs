@@ -16,7 +16,7 @@ fn main() {
 fn test_kids_playing_with_context_logic() {
     println!("👧👦 TEST 1: Kids Playing After School");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "family_kids_test";
     
     let event = Event {
@@ -36,6 +36,7 @@ fn test_kids_playing_with_context_logic() {
             llr_identity: 0.3,  // Unknown kid (positive but low)
             llr_presence: -0.5, // User definitely home
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -59,7 +60,7 @@ fn test_kids_playing_with_context_logic() {
 fn test_unknown_person_should_still_wait() {
     println!("❓ TEST 2: Unknown Person (Should remain Wait)");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "unknown_test";
     
     let event = Event {
@@ -79,6 +80,7 @@ fn test_unknown_person_should_still_wait() {
             llr_identity: 0.6,  // Unknown person
             llr_presence: 0.4,  // User likely away
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -101,7 +103,7 @@ fn test_unknown_person_should_still_wait() {
 fn test_family_member_late_night() {
     println!("🏠 TEST 3: Recognized Family Member Late Night");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "family_test";
     
     let event = Event {
@@ -121,6 +123,7 @@ fn test_family_member_late_night() {
             llr_identity: -1.5, // RECOGNIZED FAMILY MEMBER (strong negative)
             llr_presence: 0.1,
             llr_token: -1.5,    // Valid key
+            ..Default::default()
         },
     };
     
@@ -37,7 +37,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         event_id: Uuid::new_v4(),
         sensor_id: "camera_001".to_string(),
         timestamp: chrono::Utc::now().timestamp(),
-        data: r#"{"image_url": "https://httpbin.org/image/jpeg", "motion_detected": true}"#.to_string(),
+        data: r#"{"image_url": "https://httpbin.org/image/jpeg", "motion_detected": true}"#.into(),
         user_id: "user123".to_string(),
         home_id: "home456".to_string(),
         image_url: None,
@@ -61,7 +61,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         event_id: Uuid::new_v4(),
         sensor_id: "camera_002".to_string(),
         timestamp: chrono::Utc::now().timestamp(),
-        data: "motion detected at front door".to_string(),
+        data: "motion detected at front door".into(),
         user_id: "user123".to_string(),
         home_id: "home456".to_string(),
         image_url: Some("https://httpbin.org/image/png".to_string()),
@@ -107,7 +107,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             event_id: Uuid::new_v4(),
             sensor_id: format!("camera_{:03}", i),
             timestamp: chrono::Utc::now().timestamp(),
-            data: format!(r#"{{"image_url": "https://httpbin.org/image/jpeg?id={}", "concurrent_test": true}}"#, i),
+            data: format!(r#"{{"image_url": "https://httpbin.org/image/jpeg?id={}", "concurrent_test": true}}"#, i).into(),
             user_id: "user123".to_string(),
             home_id: "home456".to_string(),
             image_url: None,
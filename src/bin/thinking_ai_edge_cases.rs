@@ -18,7 +18,7 @@ fn main() {
 fn test_adversarial_deception() {
     println!("🎭 TEST 1: Adversarial Deception - Person actively trying to fool the system");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "adversarial_test_home";
     
     let adversarial_event = Event {
@@ -38,6 +38,7 @@ fn test_adversarial_deception() {
             llr_identity: 1.5,
             llr_presence: 0.6,
             llr_token: 0.8,
+            ..Default::default()
         },
     };
     
@@ -66,7 +67,7 @@ fn test_adversarial_deception() {
 fn test_sensor_failure_degraded_data() {
     println!("\n📡 TEST 2: Sensor Failure with Degraded Data");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let home = "sensor_failure_test";
     
     let degraded_event = Event {
@@ -86,6 +87,7 @@ fn test_sensor_failure_degraded_data() {
             llr_identity: 0.0,
             llr_presence: 0.5,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -104,7 +106,7 @@ fn test_sensor_failure_degraded_data() {
 fn test_extreme_confidence_scenarios() {
     println!("\n🎯 TEST 3: Extreme Confidence Scenarios");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     // Ultra-high confidence threat
     let high_threat = Event {
@@ -124,6 +126,7 @@ fn test_extreme_confidence_scenarios() {
             llr_identity: 2.0,
             llr_presence: 0.9,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -151,6 +154,7 @@ fn test_extreme_confidence_scenarios() {
             llr_identity: -2.0,
             llr_presence: -0.8,
             llr_token: -2.5,
+            ..Default::default()
         },
     };
     
@@ -22,7 +22,7 @@ fn main() {
 fn test_null_data_scenarios() {
     println!("🚫 TEST GROUP 1: NULL/Empty Data Scenarios");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     // Test 1: Empty strings everywhere
     println!("  1.1 Empty strings test");
@@ -43,6 +43,7 @@ fn test_null_data_scenarios() {
             llr_identity: 0.0,
             llr_presence: 0.0,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -71,6 +72,7 @@ fn test_null_data_scenarios() {
             llr_identity: 0.0,
             llr_presence: 0.0,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -86,7 +88,7 @@ fn test_null_data_scenarios() {
 fn test_extreme_values() {
     println!("⚡ TEST GROUP 2: Extreme Values");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     // Test 1: Maximum values
     println!("  2.1 Maximum values test");
@@ -107,6 +109,7 @@ fn test_extreme_values() {
             llr_identity: f64::MAX,
             llr_presence: f64::MAX,
             llr_token: f64::MAX,
+            ..Default::default()
         },
     };
     
@@ -135,6 +138,7 @@ fn test_extreme_values() {
             llr_identity: f64::NAN,
             llr_presence: f64::INFINITY,
             llr_token: f64::NAN,
+            ..Default::default()
         },
     };
     
@@ -150,7 +154,7 @@ fn test_extreme_values() {
 fn test_corrupted_timestamps() {
     println!("⏰ TEST GROUP 3: Corrupted Timestamps");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     let timestamps = vec![
         -1.0,           // Negative time
@@ -179,6 +183,7 @@ fn test_corrupted_timestamps() {
                 llr_identity: 0.3,
                 llr_presence: 0.1,
                 llr_token: 0.0,
+                ..Default::default()
             },
         };
         
@@ -201,7 +206,7 @@ fn test_ai_processing_failures() {
     bad_config.temperature = 0.0;  // Potential division by zero risk
     bad_config.incident_ttl_secs = -1.0;  // Invalid TTL
     
-    let mut processor = ThinkingAIProcessor::new(bad_config);
+    let processor = ThinkingAIProcessor::new(bad_config);
     
     let event = Event {
         ts: 0.0,
@@ -220,6 +225,7 @@ fn test_ai_processing_failures() {
             llr_identity: 0.1,
             llr_presence: 0.1,
             llr_token: 0.0,
+            ..Default::default()
         },
     };
     
@@ -235,7 +241,7 @@ fn test_ai_processing_failures() {
 fn test_boundary_conditions() {
     println!("🎯 TEST GROUP 5: Boundary Conditions");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     // Test values around critical thresholds
     let threshold_tests = vec![
@@ -260,7 +266,7 @@ fn test_boundary_conditions() {
 fn test_rapid_fire_mixed_scenarios() {
     println!("⚡ TEST GROUP 6: Rapid Fire Mixed Scenarios");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     let mut success_count = 0;
     let mut failure_count = 0;
     
@@ -287,7 +293,7 @@ fn test_rapid_fire_mixed_scenarios() {
 fn test_unicode_and_special_characters() {
     println!("🌍 TEST GROUP 7: Unicode and Special Characters");
     
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     let special_strings = vec![
         "🚨🔥💥🚪🏠",           // Emojis
@@ -317,6 +323,7 @@ fn test_unicode_and_special_characters() {
                 llr_identity: 0.1,
                 llr_presence: 0.1,
                 llr_token: -0.5,
+                ..Default::default()
             },
         };
         
@@ -337,7 +344,7 @@ fn test_system_state_edge_cases() {
     println!("  8.1 Zero TTL configuration");
     let mut zero_ttl_config = ThinkingAIConfig::default();
     zero_ttl_config.incident_ttl_secs = 0.0;
-    let mut processor = ThinkingAIProcessor::new(zero_ttl_config);
+    let processor = ThinkingAIProcessor::new(zero_ttl_config);
     
     let event = create_simple_event(0);
     let result = processor.process_event("home_zero_ttl", event);
@@ -386,6 +393,7 @@ fn create_event_for_probability(target_prob: f64) -> Event {
             llr_identity: individual_llr,
             llr_presence: individual_llr,
             llr_token: individual_llr,
+            ..Default::default()
         },
     }
 }
@@ -410,6 +418,7 @@ fn create_random_event(seed: usize) -> Event {
             llr_identity: random_vals[5],
             llr_presence: random_vals[6],
             llr_token: random_vals[7],
+            ..Default::default()
         },
     }
 }
@@ -432,6 +441,7 @@ fn create_simple_event(id: usize) -> Event {
             llr_identity: 0.1,
             llr_presence: 0.1,
             llr_token: 0.0,
+            ..Default::default()
         },
     }
 }
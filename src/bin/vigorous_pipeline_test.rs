@@ -71,7 +71,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: 1700000000, // 2AM equivalent
-                data: "person_detected=true|face_recognized=false|dwell_time=45s|knocked=false|rang_doorbell=false|behavior=suspicious_lurking".to_string(),
+                data: "person_detected=true|face_recognized=false|dwell_time=45s|knocked=false|rang_doorbell=false|behavior=suspicious_lurking".into(),
                 user_id: "user_123".to_string(),
                 home_id: "home_suburban".to_string(),
             },
@@ -86,7 +86,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "driveway_camera".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "person_detected=true|face_recognized=true|identity=family_member|vehicle=recognized_car|behavior=normal_entry".to_string(),
+                data: "person_detected=true|face_recognized=true|identity=family_member|vehicle=recognized_car|behavior=normal_entry".into(),
                 user_id: "user_123".to_string(),
                 home_id: "home_suburban".to_string(),
             },
@@ -101,7 +101,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: Utc::now().timestamp() - 28800, // 8 hours ago (day time)
-                data: "person_detected=true|uniform_detected=true|package=visible|knocked=true|dwell_time=30s|behavior=delivery_pattern".to_string(),
+                data: "person_detected=true|uniform_detected=true|package=visible|knocked=true|dwell_time=30s|behavior=delivery_pattern".into(),
                 user_id: "user_456".to_string(),
                 home_id: "home_urban".to_string(),
             },
@@ -116,7 +116,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "backyard_camera".to_string(),
                 timestamp: 1700003600, // 3AM equivalent
-                data: "people_count=3|faces_recognized=0|behavior=coordinated_movement|tools_detected=true|attempted_entry=true|duration=120s".to_string(),
+                data: "people_count=3|faces_recognized=0|behavior=coordinated_movement|tools_detected=true|attempted_entry=true|duration=120s".into(),
                 user_id: "user_789".to_string(),
                 home_id: "home_isolated".to_string(),
             },
@@ -131,7 +131,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "side_yard_camera".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "motion_detected=true|person_detected=false|object=tree_branch|wind_speed=high|repetitive_motion=true".to_string(),
+                data: "motion_detected=true|person_detected=false|object=tree_branch|wind_speed=high|repetitive_motion=true".into(),
                 user_id: "user_101".to_string(),
                 home_id: "home_wooded".to_string(),
             },
@@ -146,7 +146,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "backyard_motion".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "animal_detected=true|species=cat|size=small|behavior=normal_animal|person_detected=false".to_string(),
+                data: "animal_detected=true|species=cat|size=small|behavior=normal_animal|person_detected=false".into(),
                 user_id: "user_202".to_string(),
                 home_id: "home_suburban".to_string(),
             },
@@ -161,7 +161,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "window_sensor_living_room".to_string(),
                 timestamp: 1700001800, // 2:30 AM equivalent
-                data: "glass_break=true|person_detected=true|face_recognition=failed|aggressive_behavior=true|forced_entry=true|weapons_suspected=true".to_string(),
+                data: "glass_break=true|person_detected=true|face_recognition=failed|aggressive_behavior=true|forced_entry=true|weapons_suspected=true".into(),
                 user_id: "user_999".to_string(),
                 home_id: "home_vulnerable".to_string(),
             },
@@ -176,7 +176,7 @@ fn create_edge_case_scenarios() -> Vec<TestScenario> {
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: Utc::now().timestamp() - 14400, // 4 hours ago
-                data: "person_detected=true|uniform=maintenance|scheduled_visit=true|tools=work_related|behavior=professional|rang_doorbell=true".to_string(),
+                data: "person_detected=true|uniform=maintenance|scheduled_visit=true|tools=work_related|behavior=professional|rang_doorbell=true".into(),
                 user_id: "user_303".to_string(),
                 home_id: "home_managed".to_string(),
             },
@@ -60,7 +60,7 @@ fn create_edge_case_scenarios() -> Vec<(String, RawEvent, SubscriptionTier, Stri
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: 1700000000,
-                data: "person_detected=true|face_recognized=false|dwell_time=45s|knocked=false|rang_doorbell=false|behavior=suspicious_lurking".to_string(),
+                data: "person_detected=true|face_recognized=false|dwell_time=45s|knocked=false|rang_doorbell=false|behavior=suspicious_lurking".into(),
                 user_id: "user_123".to_string(),
                 home_id: "home_suburban".to_string(),
             },
@@ -73,7 +73,7 @@ fn create_edge_case_scenarios() -> Vec<(String, RawEvent, SubscriptionTier, Stri
                 event_id: Uuid::new_v4(),
                 sensor_id: "backyard_camera".to_string(),
                 timestamp: 1700003600,
-                data: "people_count=3|faces_recognized=0|behavior=coordinated_movement|tools_detected=true|attempted_entry=true|duration=120s".to_string(),
+                data: "people_count=3|faces_recognized=0|behavior=coordinated_movement|tools_detected=true|attempted_entry=true|duration=120s".into(),
                 user_id: "user_789".to_string(),
                 home_id: "home_isolated".to_string(),
             },
@@ -86,7 +86,7 @@ fn create_edge_case_scenarios() -> Vec<(String, RawEvent, SubscriptionTier, Stri
                 event_id: Uuid::new_v4(),
                 sensor_id: "window_sensor_living_room".to_string(),
                 timestamp: 1700001800,
-                data: "glass_break=true|person_detected=true|face_recognition=failed|aggressive_behavior=true|forced_entry=true|weapons_suspected=true".to_string(),
+                data: "glass_break=true|person_detected=true|face_recognition=failed|aggressive_behavior=true|forced_entry=true|weapons_suspected=true".into(),
                 user_id: "user_999".to_string(),
                 home_id: "home_vulnerable".to_string(),
             },
@@ -99,7 +99,7 @@ fn create_edge_case_scenarios() -> Vec<(String, RawEvent, SubscriptionTier, Stri
                 event_id: Uuid::new_v4(),
                 sensor_id: "driveway_camera".to_string(),
                 timestamp: Utc::now().timestamp(),
-                data: "person_detected=true|face_recognized=true|identity=family_member|vehicle=recognized_car|behavior=normal_entry".to_string(),
+                data: "person_detected=true|face_recognized=true|identity=family_member|vehicle=recognized_car|behavior=normal_entry".into(),
                 user_id: "user_123".to_string(),
                 home_id: "home_suburban".to_string(),
             },
@@ -112,7 +112,7 @@ fn create_edge_case_scenarios() -> Vec<(String, RawEvent, SubscriptionTier, Stri
                 event_id: Uuid::new_v4(),
                 sensor_id: "front_door_camera".to_string(),
                 timestamp: Utc::now().timestamp() - 28800,
-                data: "person_detected=true|uniform_detected=true|package=visible|knocked=true|dwell_time=30s|behavior=delivery_pattern".to_string(),
+                data: "person_detected=true|uniform_detected=true|package=visible|knocked=true|dwell_time=30s|behavior=delivery_pattern".into(),
                 user_id: "user_456".to_string(),
                 home_id: "home_urban".to_string(),
             },
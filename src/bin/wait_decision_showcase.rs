@@ -13,7 +13,7 @@ fn main() {
 }
 
 fn test_wait_decision_scenarios() {
-    let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+    let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
     
     println!("🔍 Testing scenarios designed to trigger Wait decisions...\n");
     
@@ -35,6 +35,7 @@ fn test_wait_decision_scenarios() {
             llr_identity: -0.8, // Unknown person
             llr_presence: -0.2, // Unusual presence pattern
             llr_token: 0.0,    // No token
+            ..Default::default()
         },
     };
     
@@ -72,6 +73,7 @@ fn test_wait_decision_scenarios() {
             llr_identity: -1.2, // Unknown person (not recognized)
             llr_presence: 0.0, // Normal presence
             llr_token: -1.5,   // Invalid/unrecognized token
+            ..Default::default()
         },
     };
     
@@ -109,6 +111,7 @@ fn test_wait_decision_scenarios() {
             llr_identity: 0.5, // Might be family member (partial match)
             llr_presence: -0.3, // Unusual presence pattern
             llr_token: 0.0,    // No token
+            ..Default::default()
         },
     };
     
@@ -146,6 +149,7 @@ fn test_wait_decision_scenarios() {
             llr_identity: -2.0, // Completely unknown
             llr_presence: 0.8, // Very unusual presence
             llr_token: 0.0,    // No token
+            ..Default::default()
         },
     };
     
@@ -179,6 +183,7 @@ fn test_wait_decision_scenarios() {
             llr_identity: 2.0, // Strong family match
             llr_presence: 0.2, // Normal presence
             llr_token: 1.8,    // Valid family token
+            ..Default::default()
         },
     };
     
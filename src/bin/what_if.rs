@@ -0,0 +1,54 @@
+//! Replay stored incidents against a candidate ThinkingAI config and report how alerts would change
+
+use insane_ai_security::thinking::{compare_configs, IncidentLabel, IncidentStoreSnapshot, ThinkingAIConfig};
+use insane_ai_security::validation::Validate;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "usage: what-if --incidents <snapshot.json> --candidate <config.yaml> [--baseline <config.yaml>] [--labels <labels.json>]";
+
+fn load_config(path: &Path) -> anyhow::Result<ThinkingAIConfig> {
+    let text = std::fs::read_to_string(path)?;
+    let config: ThinkingAIConfig = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&text)?,
+        _ => serde_yaml::from_str(&text)?,
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+fn main() -> anyhow::Result<()> {
+    let (mut incidents, mut candidate, mut baseline, mut labels) = (None, None, None, None);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().map(PathBuf::from).ok_or_else(|| anyhow::anyhow!(USAGE))?;
+        match arg.as_str() {
+            "--incidents" => incidents = Some(value),
+            "--candidate" => candidate = Some(value),
+            "--baseline" => baseline = Some(value),
+            "--labels" => labels = Some(value),
+            _ => anyhow::bail!("unknown argument '{}'\n{}", arg, USAGE),
+        }
+    }
+
+    let incidents = incidents.ok_or_else(|| anyhow::anyhow!(USAGE))?;
+    let snapshot: IncidentStoreSnapshot = serde_json::from_str(&std::fs::read_to_string(incidents)?)?;
+    let candidate = load_config(&candidate.ok_or_else(|| anyhow::anyhow!(USAGE))?)?;
+    let baseline = match baseline {
+        Some(path) => load_config(&path)?,
+        None => ThinkingAIConfig::default(),
+    };
+    // Labels file: {"<incident_id>": "threat" | "benign"}
+    let labels: HashMap<u64, IncidentLabel> = match labels {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        None => HashMap::new(),
+    };
+
+    let report = compare_configs(&snapshot.incidents, &labels, &baseline, &candidate);
+    println!("Replayed {} incidents ({} labeled)", report.incidents_replayed, report.labeled_incidents);
+    println!("  alerts:          {} -> {} ({:+})", report.baseline.alerted_incidents, report.candidate.alerted_incidents, report.alert_count_delta);
+    println!("  false positives: {} -> {} ({:+})", report.baseline.false_positives, report.candidate.false_positives, report.false_positive_delta);
+    println!("  missed alerts:   {} -> {} ({:+})", report.baseline.missed_alerts, report.candidate.missed_alerts, report.missed_alert_delta);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
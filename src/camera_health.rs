@@ -0,0 +1,284 @@
+// src/camera_health.rs
+
+// Registered cameras, their ingest credentials and whether they are still
+// alive. Registering a camera issues it an ingest token of its own, so a
+// leaked token lets someone post events for one camera in one home rather
+// than for the whole account, and can be rotated by registering again. Only
+// a SHA-256 of the token is kept.
+//
+// Every event a registered camera sends updates its last-seen time and, when
+// the payload reports one ("fps=12"), its frame rate. The health report flags
+// cameras silent for longer than they are expected to be, cameras delivering
+// well under their expected frame rate, and cameras that have just come back
+// from a silence. The last two feed fusion the same way tamper alerts do: as
+// a multiplier on the camera's sensor reliability, since a camera that was
+// offline or is dropping frames sees less than its history suggests.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::pipeline::SubscriptionTier;
+
+pub const TOKEN_PREFIX: &str = "cam_";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CameraHealthError {
+    #[error("Camera {0} is not registered")]
+    UnknownCamera(String),
+
+    #[error("Camera {0} is registered to another home")]
+    OtherHome(String),
+
+    #[error("Invalid ingest token for camera {0}")]
+    InvalidToken(String),
+
+    #[error("Invalid registration: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CameraHealthConfig {
+    pub recovery_s: i64,         // After a silence, the camera counts as recovering for this long
+    pub recovering_reliability: f64,
+    pub low_fps_ratio: f64,      // Below this share of the expected frame rate the camera is degraded
+    pub min_fps_reliability: f64, // Floor for the frame-rate multiplier
+}
+
+impl Default for CameraHealthConfig {
+    fn default() -> Self {
+        Self {
+            recovery_s: 600,
+            recovering_reliability: 0.7,
+            low_fps_ratio: 0.5,
+            min_fps_reliability: 0.5,
+        }
+    }
+}
+
+/// What the installer app sends to register a camera
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CameraRegistrationRequest {
+    pub user_id: String, // Account the camera's events are processed for
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub expected_fps: Option<f64>,
+    #[serde(default = "default_max_silence_s")]
+    pub max_silence_s: i64, // Longest gap between events before the camera counts as silent
+    #[schema(value_type = String)]
+    pub tier: SubscriptionTier,
+}
+
+fn default_max_silence_s() -> i64 {
+    6 * 3600
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisteredCamera {
+    pub camera_id: String,
+    pub home_id: String,
+    pub user_id: String,
+    pub name: Option<String>,
+    pub expected_fps: Option<f64>,
+    pub max_silence_s: i64,
+    pub tier: SubscriptionTier,
+    pub registered_at: DateTime<Utc>,
+    pub last_event_at: Option<DateTime<Utc>>,
+    pub last_fps: Option<f64>,
+    pub events: u64,
+    pub recovered_at: Option<DateTime<Utc>>, // Last event after a silence
+    #[serde(skip_serializing)]
+    token_sha256: String,
+}
+
+/// A registered camera and the ingest token it was just issued; the token is not shown again
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedCamera {
+    pub camera: RegisteredCamera,
+    pub ingest_token: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CameraStatus {
+    Healthy,
+    NeverSeen,    // Registered but no event yet
+    Silent,       // Quiet for longer than max_silence_s
+    Recovering,   // Back from a silence within the recovery window
+    LowFrameRate, // Reporting well under its expected frame rate
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CameraHealth {
+    pub camera_id: String,
+    pub name: Option<String>,
+    pub status: CameraStatus,
+    pub last_event_at: Option<DateTime<Utc>>,
+    pub silent_for_s: Option<i64>,
+    pub expected_fps: Option<f64>,
+    pub observed_fps: Option<f64>,
+    pub reliability_factor: f64, // Multiplier applied to the camera's evidence
+}
+
+#[derive(Debug, Default)]
+pub struct CameraHealthRegistry {
+    config: CameraHealthConfig,
+    cameras: DashMap<String, RegisteredCamera>,
+}
+
+impl CameraHealthRegistry {
+    pub fn new(config: CameraHealthConfig) -> Self {
+        Self { config, cameras: DashMap::new() }
+    }
+
+    /// Register a camera, or re-register it to issue a new token; counters are kept
+    pub fn register(&self, home_id: &str, camera_id: &str, request: CameraRegistrationRequest, at: DateTime<Utc>) -> Result<IssuedCamera, CameraHealthError> {
+        if request.max_silence_s <= 0 {
+            return Err(CameraHealthError::Invalid("max_silence_s must be positive".to_string()));
+        }
+        if request.expected_fps.is_some_and(|fps| !fps.is_finite() || fps <= 0.0) {
+            return Err(CameraHealthError::Invalid("expected_fps must be positive".to_string()));
+        }
+        if self.cameras.get(camera_id).is_some_and(|c| c.home_id != home_id) {
+            return Err(CameraHealthError::OtherHome(camera_id.to_string()));
+        }
+        let token = format!("{}{}", TOKEN_PREFIX, hex::encode(rand::random::<[u8; 32]>()));
+        let previous = self.cameras.get(camera_id).map(|c| c.clone());
+        let camera = RegisteredCamera {
+            camera_id: camera_id.to_string(),
+            home_id: home_id.to_string(),
+            user_id: request.user_id,
+            name: request.name,
+            expected_fps: request.expected_fps,
+            max_silence_s: request.max_silence_s,
+            tier: request.tier,
+            registered_at: previous.as_ref().map_or(at, |p| p.registered_at),
+            last_event_at: previous.as_ref().and_then(|p| p.last_event_at),
+            last_fps: previous.as_ref().and_then(|p| p.last_fps),
+            events: previous.as_ref().map_or(0, |p| p.events),
+            recovered_at: previous.as_ref().and_then(|p| p.recovered_at),
+            token_sha256: token_hash(&token),
+        };
+        self.cameras.insert(camera_id.to_string(), camera.clone());
+        Ok(IssuedCamera { camera, ingest_token: token })
+    }
+
+    pub fn remove(&self, home_id: &str, camera_id: &str) -> Result<RegisteredCamera, CameraHealthError> {
+        self.cameras.remove_if(camera_id, |_, c| c.home_id == home_id)
+            .map(|(_, camera)| camera)
+            .ok_or_else(|| CameraHealthError::UnknownCamera(camera_id.to_string()))
+    }
+
+    pub fn get(&self, camera_id: &str) -> Option<RegisteredCamera> {
+        self.cameras.get(camera_id).map(|c| c.clone())
+    }
+
+    /// The camera an ingest token belongs to
+    pub fn authenticate(&self, camera_id: &str, token: &str) -> Result<RegisteredCamera, CameraHealthError> {
+        let camera = self.get(camera_id).ok_or_else(|| CameraHealthError::UnknownCamera(camera_id.to_string()))?;
+        match constant_time_eq(token_hash(token).as_bytes(), camera.token_sha256.as_bytes()) {
+            true => Ok(camera),
+            false => Err(CameraHealthError::InvalidToken(camera_id.to_string())),
+        }
+    }
+
+    /// Note an event from a camera; unregistered cameras are ignored
+    pub fn record_event(&self, home_id: &str, camera_id: &str, at: DateTime<Utc>, fps: Option<f64>) {
+        let Some(mut camera) = self.cameras.get_mut(camera_id) else {
+            return;
+        };
+        if camera.home_id != home_id {
+            return;
+        }
+        if camera.last_event_at.is_some_and(|last| (at - last).num_seconds() > camera.max_silence_s) {
+            camera.recovered_at = Some(at);
+        }
+        if !camera.last_event_at.is_some_and(|last| at <= last) {
+            camera.last_event_at = Some(at);
+        }
+        if let Some(fps) = fps.filter(|f| f.is_finite() && *f >= 0.0) {
+            camera.last_fps = Some(fps);
+        }
+        camera.events += 1;
+    }
+
+    /// Every registered camera of a home, most urgent first
+    pub fn health(&self, home_id: &str, now: DateTime<Utc>) -> Vec<CameraHealth> {
+        let mut report: Vec<CameraHealth> = self.cameras.iter()
+            .filter(|c| c.home_id == home_id)
+            .map(|c| self.assess(&c, now))
+            .collect();
+        report.sort_by_key(|h| (status_rank(h.status), h.camera_id.clone()));
+        report
+    }
+
+    /// Cameras that have gone quiet for longer than expected
+    pub fn silent(&self, home_id: &str, now: DateTime<Utc>) -> Vec<CameraHealth> {
+        self.health(home_id, now).into_iter().filter(|h| h.status == CameraStatus::Silent).collect()
+    }
+
+    /// Reliability multipliers for the home's degraded cameras
+    pub fn reliability_factors(&self, home_id: &str, now: DateTime<Utc>) -> HashMap<String, f64> {
+        self.health(home_id, now).into_iter()
+            .filter(|h| h.reliability_factor < 1.0)
+            .map(|h| (h.camera_id, h.reliability_factor))
+            .collect()
+    }
+
+    fn assess(&self, camera: &RegisteredCamera, now: DateTime<Utc>) -> CameraHealth {
+        let silent_for_s = camera.last_event_at.map(|last| (now - last).num_seconds().max(0));
+        let fps_ratio = camera.expected_fps.zip(camera.last_fps).map(|(expected, observed)| observed / expected);
+        let (status, reliability_factor) = match silent_for_s {
+            None => (CameraStatus::NeverSeen, 1.0),
+            Some(s) if s > camera.max_silence_s => (CameraStatus::Silent, 1.0),
+            _ if camera.recovered_at.is_some_and(|at| (now - at).num_seconds() < self.config.recovery_s) => {
+                (CameraStatus::Recovering, self.config.recovering_reliability)
+            }
+            _ => match fps_ratio {
+                Some(ratio) if ratio < self.config.low_fps_ratio => {
+                    (CameraStatus::LowFrameRate, ratio.max(self.config.min_fps_reliability).min(1.0))
+                }
+                _ => (CameraStatus::Healthy, 1.0),
+            },
+        };
+        CameraHealth {
+            camera_id: camera.camera_id.clone(),
+            name: camera.name.clone(),
+            status,
+            last_event_at: camera.last_event_at,
+            silent_for_s,
+            expected_fps: camera.expected_fps,
+            observed_fps: camera.last_fps,
+            reliability_factor,
+        }
+    }
+}
+
+fn status_rank(status: CameraStatus) -> u8 {
+    match status {
+        CameraStatus::Silent => 0,
+        CameraStatus::LowFrameRate => 1,
+        CameraStatus::Recovering => 2,
+        CameraStatus::NeverSeen => 3,
+        CameraStatus::Healthy => 4,
+    }
+}
+
+/// Frame rate reported in an event payload, e.g. "motion=true|fps=12.5"
+pub fn reported_fps(data: &str) -> Option<f64> {
+    data.split(['|', ',', '&', ' '])
+        .find_map(|pair| pair.trim().strip_prefix("fps="))
+        .and_then(|v| v.parse().ok())
+}
+
+fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
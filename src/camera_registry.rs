@@ -0,0 +1,204 @@
+// src/camera_registry.rs
+
+// Pinned snapshot sources per camera. A forged event can carry any image URL,
+// so each registered camera lists the hostnames its snapshots may come from
+// and, optionally, SHA-256 fingerprints of the certificates those hosts
+// present. URLs on other hosts are refused (or only flagged, for a rollout),
+// and pinned cameras are fetched over a TLS client that accepts nothing but
+// the pinned certificates, which also covers DNS pointing somewhere else.
+// Cameras without an entry keep the old behaviour. Pins are keyed by home and
+// camera, since sensor ids are only unique within a home.
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio_rustls::rustls::{
+    self,
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate, ServerName,
+};
+use url::Url;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PinError {
+    #[error("invalid snapshot URL: {0}")]
+    InvalidUrl(String),
+    #[error("camera {camera} does not serve snapshots from {host}")]
+    UnexpectedHost { camera: String, host: String },
+    #[error("camera {0} pins certificates but the URL is not https")]
+    InsecureScheme(String),
+    #[error("invalid certificate fingerprint '{0}', expected 64 hex characters")]
+    InvalidFingerprint(String),
+    #[error("camera {0} has no allowed hosts")]
+    NoHosts(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinPolicy {
+    #[default]
+    Enforce, // Refuse snapshots from unexpected hosts
+    Flag,    // Fetch them anyway but report the mismatch
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraPin {
+    pub camera_id: String,
+    pub home_id: String,
+    pub hosts: Vec<String>, // Exact hostnames, or "*.example.com" for any subdomain
+    #[serde(default)]
+    pub cert_sha256: Vec<String>, // Hex SHA-256 of the DER leaf certificate; empty skips TLS pinning
+}
+
+impl CameraPin {
+    pub fn allows_host(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        self.hosts.iter().any(|pattern| {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.') && sub.len() > 1),
+                None => host == pattern,
+            }
+        })
+    }
+
+    fn fingerprints(&self) -> Result<Vec<[u8; 32]>, PinError> {
+        self.cert_sha256.iter()
+            .map(|fp| {
+                let cleaned: String = fp.chars().filter(|c| *c != ':').collect();
+                hex::decode(&cleaned).ok()
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .ok_or_else(|| PinError::InvalidFingerprint(fp.clone()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotTrust {
+    Pinned,           // Host matches the camera's pin
+    Unregistered,     // Camera has no pin; not checked
+    Flagged(String),  // Mismatch let through under PinPolicy::Flag
+}
+
+#[derive(Default)]
+pub struct CameraRegistry {
+    policy: PinPolicy,
+    pins: DashMap<(String, String), CameraPin>, // (home_id, camera_id)
+    clients: DashMap<(String, String), reqwest::Client>, // Certificate-pinned clients, built on first use
+}
+
+impl CameraRegistry {
+    pub fn new(policy: PinPolicy) -> Self {
+        Self { policy, ..Self::default() }
+    }
+
+    pub fn policy(&self) -> PinPolicy {
+        self.policy
+    }
+
+    /// Add or replace a camera's pin
+    pub fn register(&self, pin: CameraPin) -> Result<(), PinError> {
+        if pin.hosts.is_empty() {
+            return Err(PinError::NoHosts(pin.camera_id));
+        }
+        pin.fingerprints()?;
+        let key = (pin.home_id.clone(), pin.camera_id.clone());
+        self.clients.remove(&key);
+        self.pins.insert(key, pin);
+        Ok(())
+    }
+
+    pub fn remove(&self, home_id: &str, camera_id: &str) -> Option<CameraPin> {
+        let key = (home_id.to_string(), camera_id.to_string());
+        self.clients.remove(&key);
+        self.pins.remove(&key).map(|(_, pin)| pin)
+    }
+
+    pub fn get(&self, home_id: &str, camera_id: &str) -> Option<CameraPin> {
+        self.pins.get(&(home_id.to_string(), camera_id.to_string())).map(|p| p.clone())
+    }
+
+    pub fn home_pins(&self, home_id: &str) -> Vec<CameraPin> {
+        let mut pins: Vec<CameraPin> = self.pins.iter().filter(|p| p.home_id == home_id).map(|p| p.clone()).collect();
+        pins.sort_by(|a, b| a.camera_id.cmp(&b.camera_id));
+        pins
+    }
+
+    pub fn all_pins(&self) -> Vec<CameraPin> {
+        let mut pins: Vec<CameraPin> = self.pins.iter().map(|p| p.clone()).collect();
+        pins.sort_by(|a, b| (&a.home_id, &a.camera_id).cmp(&(&b.home_id, &b.camera_id)));
+        pins
+    }
+
+    /// Check a snapshot URL claimed by an event from `camera_id` in `home_id`
+    pub fn check_url(&self, home_id: &str, camera_id: &str, url: &str) -> Result<SnapshotTrust, PinError> {
+        let Some(pin) = self.get(home_id, camera_id) else {
+            return Ok(SnapshotTrust::Unregistered);
+        };
+        let parsed = Url::parse(url).map_err(|e| PinError::InvalidUrl(e.to_string()))?;
+        let host = parsed.host_str().ok_or_else(|| PinError::InvalidUrl(url.to_string()))?;
+
+        let mismatch = if !pin.allows_host(host) {
+            Some(PinError::UnexpectedHost { camera: camera_id.to_string(), host: host.to_string() })
+        } else if !pin.cert_sha256.is_empty() && parsed.scheme() != "https" {
+            Some(PinError::InsecureScheme(camera_id.to_string()))
+        } else {
+            None
+        };
+        match (mismatch, self.policy) {
+            (None, _) => Ok(SnapshotTrust::Pinned),
+            (Some(e), PinPolicy::Flag) => Ok(SnapshotTrust::Flagged(e.to_string())),
+            (Some(e), PinPolicy::Enforce) => Err(e),
+        }
+    }
+
+    /// HTTP client that only completes TLS handshakes with the camera's pinned
+    /// certificates; None when the camera pins no certificates
+    pub fn client_for(&self, home_id: &str, camera_id: &str) -> Option<reqwest::Client> {
+        let key = (home_id.to_string(), camera_id.to_string());
+        if let Some(client) = self.clients.get(&key) {
+            return Some(client.clone());
+        }
+        let pin = self.get(home_id, camera_id).filter(|p| !p.cert_sha256.is_empty())?;
+        let verifier = PinnedCertVerifier { fingerprints: pin.fingerprints().ok()? };
+        let tls = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        let client = reqwest::Client::builder()
+            .use_preconfigured_tls(tls)
+            .timeout(Duration::from_secs(10))
+            .user_agent("Novin/1.0")
+            .build()
+            .ok()?;
+        self.clients.insert(key, client.clone());
+        Some(client)
+    }
+}
+
+// Cameras commonly use self-signed certificates, so the pin replaces CA trust
+struct PinnedCertVerifier {
+    fingerprints: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let presented: [u8; 32] = Sha256::digest(&end_entity.0).into();
+        if self.fingerprints.contains(&presented) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!("certificate {} does not match the camera's pin", hex::encode(presented))))
+        }
+    }
+}
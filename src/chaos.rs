@@ -0,0 +1,201 @@
+// src/chaos.rs
+
+// Fault injection for resilience runs, compiled only with the `chaos`
+// feature. A ChaosInjector delays or fails VPS calls, corrupts downloaded
+// snapshots and drops overnight storage writes, each with its own
+// probability, so the circuit breaker, local fallback and storage error paths
+// run under load instead of only in unit tests. Injected VPS failures count
+// against the client's breaker exactly like real ones. A fixed seed makes a
+// run reproducible.
+
+use crate::overnight::{OvernightEventAnalysis, OvernightResult, OvernightStorage};
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ChaosError {
+    #[error("Injected VPS failure")]
+    VpsFailure,
+
+    #[error("Injected storage write drop")]
+    WriteDropped,
+
+    #[error("Invalid chaos config: {0}")]
+    Config(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    pub vps_delay_rate: f64,
+    pub vps_delay_ms: (u64, u64), // Uniform range for injected delays
+    pub vps_failure_rate: f64,
+    pub image_corruption_rate: f64,
+    pub storage_drop_rate: f64,
+    pub seed: Option<u64>,
+}
+
+impl ChaosConfig {
+    /// Config from the NOVIN_CHAOS environment variable (inline JSON); None when unset
+    pub fn from_env() -> Result<Option<Self>, ChaosError> {
+        match std::env::var("NOVIN_CHAOS") {
+            Ok(json) => serde_json::from_str(&json).map(Some).map_err(|e| ChaosError::Config(e.to_string())),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ChaosError> {
+        for (name, rate) in [
+            ("vps_delay_rate", self.vps_delay_rate),
+            ("vps_failure_rate", self.vps_failure_rate),
+            ("image_corruption_rate", self.image_corruption_rate),
+            ("storage_drop_rate", self.storage_drop_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(ChaosError::Config(format!("{} must be between 0 and 1, got {}", name, rate)));
+            }
+        }
+        if self.vps_delay_ms.0 > self.vps_delay_ms.1 {
+            return Err(ChaosError::Config("vps_delay_ms must be (min, max)".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ChaosStats {
+    pub vps_delays: u64,
+    pub vps_failures: u64,
+    pub images_corrupted: u64,
+    pub writes_dropped: u64,
+}
+
+#[derive(Debug)]
+pub struct ChaosInjector {
+    config: ChaosConfig,
+    rng: Mutex<StdRng>,
+    vps_delays: AtomicU64,
+    vps_failures: AtomicU64,
+    images_corrupted: AtomicU64,
+    writes_dropped: AtomicU64,
+}
+
+impl ChaosInjector {
+    pub fn new(config: ChaosConfig) -> Result<Self, ChaosError> {
+        config.validate()?;
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(Self {
+            config,
+            rng: Mutex::new(rng),
+            vps_delays: AtomicU64::new(0),
+            vps_failures: AtomicU64::new(0),
+            images_corrupted: AtomicU64::new(0),
+            writes_dropped: AtomicU64::new(0),
+        })
+    }
+
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> ChaosStats {
+        ChaosStats {
+            vps_delays: self.vps_delays.load(Ordering::Relaxed),
+            vps_failures: self.vps_failures.load(Ordering::Relaxed),
+            images_corrupted: self.images_corrupted.load(Ordering::Relaxed),
+            writes_dropped: self.writes_dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn roll(&self, rate: f64) -> bool {
+        rate > 0.0 && self.rng.lock().map(|mut r| r.gen_bool(rate.min(1.0))).unwrap_or(false)
+    }
+
+    /// Runs before each VPS request: maybe sleeps, maybe fails
+    pub async fn vps_call(&self) -> Result<(), ChaosError> {
+        if self.roll(self.config.vps_delay_rate) {
+            let (min, max) = self.config.vps_delay_ms;
+            let ms = self.rng.lock().map(|mut r| r.gen_range(min..=max)).unwrap_or(min);
+            self.vps_delays.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+        if self.roll(self.config.vps_failure_rate) {
+            self.vps_failures.fetch_add(1, Ordering::Relaxed);
+            return Err(ChaosError::VpsFailure);
+        }
+        Ok(())
+    }
+
+    /// Maybe truncate the image and flip bytes in what is left
+    pub fn corrupt_image(&self, image: Bytes) -> Bytes {
+        if image.is_empty() || !self.roll(self.config.image_corruption_rate) {
+            return image;
+        }
+        self.images_corrupted.fetch_add(1, Ordering::Relaxed);
+        let Ok(mut rng) = self.rng.lock() else {
+            return image;
+        };
+        let mut bytes = image.to_vec();
+        bytes.truncate(rng.gen_range(1..=bytes.len()));
+        for _ in 0..bytes.len().div_ceil(64) {
+            let i = rng.gen_range(0..bytes.len());
+            bytes[i] ^= rng.gen::<u8>() | 1;
+        }
+        Bytes::from(bytes)
+    }
+
+    /// Whether the next storage write should be dropped
+    pub fn drop_write(&self) -> bool {
+        let dropped = self.roll(self.config.storage_drop_rate);
+        if dropped {
+            self.writes_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        dropped
+    }
+}
+
+/// Overnight storage whose writes fail at the configured drop rate; reads pass through
+pub struct ChaosStorage {
+    inner: Arc<dyn OvernightStorage>,
+    chaos: Arc<ChaosInjector>,
+}
+
+impl ChaosStorage {
+    pub fn new(inner: Arc<dyn OvernightStorage>, chaos: Arc<ChaosInjector>) -> Self {
+        Self { inner, chaos }
+    }
+}
+
+#[async_trait]
+impl OvernightStorage for ChaosStorage {
+    async fn store_event(&self, analysis: &OvernightEventAnalysis) -> OvernightResult<()> {
+        if self.chaos.drop_write() {
+            warn!("Chaos: dropping overnight write for event {}", analysis.event_id);
+            return Err(ChaosError::WriteDropped.into());
+        }
+        self.inner.store_event(analysis).await
+    }
+
+    async fn pending_events(&self, home_id: &str) -> OvernightResult<Vec<OvernightEventAnalysis>> {
+        self.inner.pending_events(home_id).await
+    }
+
+    async fn mark_delivered(&self, home_id: &str, event_ids: &[Uuid], at: DateTime<Utc>) -> OvernightResult<()> {
+        if self.chaos.drop_write() {
+            return Err(ChaosError::WriteDropped.into());
+        }
+        self.inner.mark_delivered(home_id, event_ids, at).await
+    }
+}
@@ -0,0 +1,185 @@
+// src/circuit_breaker.rs
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// Configuration for a circuit breaker
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    pub failure_threshold: u32,    // Consecutive failures before opening
+    pub open_duration: Duration,   // How long to reject calls before probing again
+    pub half_open_max_probes: u32, // Concurrent trial calls allowed while half-open
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+            half_open_max_probes: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitState {
+    Closed,   // Calls flow normally
+    Open,     // Calls are rejected immediately
+    HalfOpen, // A limited number of probe calls are let through
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    half_open_inflight: u32,
+}
+
+// Snapshot of breaker counters for metrics endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitMetrics {
+    pub name: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    pub total_successes: u64,
+    pub total_failures: u64,
+    pub rejected_calls: u64,
+    pub times_opened: u64,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: String,
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+    total_successes: AtomicU64,
+    total_failures: AtomicU64,
+    rejected_calls: AtomicU64,
+    times_opened: AtomicU64,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+            inner: Mutex::new(BreakerInner {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                half_open_inflight: 0,
+            }),
+            total_successes: AtomicU64::new(0),
+            total_failures: AtomicU64::new(0),
+            rejected_calls: AtomicU64::new(0),
+            times_opened: AtomicU64::new(0),
+        }
+    }
+
+    /// Ask permission to make a call; returns false when the caller should fail fast
+    pub fn try_acquire(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.state == CircuitState::Open {
+            let cooled_down = inner.opened_at
+                .map(|t| t.elapsed() >= self.config.open_duration)
+                .unwrap_or(true);
+            if cooled_down {
+                info!(breaker=%self.name, "circuit half-open, allowing probe");
+                inner.state = CircuitState::HalfOpen;
+                inner.half_open_inflight = 0;
+            }
+        }
+
+        let allowed = match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen => {
+                if inner.half_open_inflight < self.config.half_open_max_probes {
+                    inner.half_open_inflight += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+
+        if !allowed {
+            self.rejected_calls.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    pub fn record_success(&self) {
+        self.total_successes.fetch_add(1, Ordering::Relaxed);
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state != CircuitState::Closed {
+            info!(breaker=%self.name, "circuit closed after successful probe");
+        }
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+        inner.half_open_inflight = 0;
+    }
+
+    pub fn record_failure(&self) {
+        self.total_failures.fetch_add(1, Ordering::Relaxed);
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+
+        let should_open = match inner.state {
+            CircuitState::HalfOpen => true, // Failed probe re-opens immediately
+            CircuitState::Closed => inner.consecutive_failures >= self.config.failure_threshold,
+            CircuitState::Open => false,
+        };
+        if should_open {
+            self.open(&mut inner);
+        }
+    }
+
+    /// Force the breaker open, e.g. when an out-of-band health probe fails
+    pub fn trip(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state != CircuitState::Open {
+            self.open(&mut inner);
+        }
+    }
+
+    /// Move an open breaker to half-open early, e.g. when a health probe succeeds
+    pub fn allow_probe(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == CircuitState::Open {
+            inner.state = CircuitState::HalfOpen;
+            inner.half_open_inflight = 0;
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap().state
+    }
+
+    pub fn metrics(&self) -> CircuitMetrics {
+        let inner = self.inner.lock().unwrap();
+        CircuitMetrics {
+            name: self.name.clone(),
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            total_successes: self.total_successes.load(Ordering::Relaxed),
+            total_failures: self.total_failures.load(Ordering::Relaxed),
+            rejected_calls: self.rejected_calls.load(Ordering::Relaxed),
+            times_opened: self.times_opened.load(Ordering::Relaxed),
+        }
+    }
+
+    fn open(&self, inner: &mut BreakerInner) {
+        warn!(breaker=%self.name, failures=inner.consecutive_failures, "circuit opened");
+        inner.state = CircuitState::Open;
+        inner.opened_at = Some(Instant::now());
+        inner.half_open_inflight = 0;
+        self.times_opened.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,187 @@
+// src/client.rs
+
+// Typed async client for the HTTP and WebSocket API, built with the `client`
+// feature so Rust edge devices can integrate without hand-rolling requests.
+// It speaks the server's own request and response types, so a field renamed
+// on one side is renamed on the other. Cameras ingest events with their own
+// ingest token; everything else uses the account's bearer token. `subscribe`
+// opens the WebSocket: pushed alert updates, and replies to commands sent on
+// the same connection, arrive as one stream of `ServerMessage`s.
+
+use futures_util::{SinkExt, StreamExt};
+use reqwest::{Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use uuid::Uuid;
+
+use crate::api::events::{CameraEventSubmission, EventResponse, CAMERA_TOKEN_HEADER};
+use crate::api::incidents::{EventRow, IncidentRow};
+use crate::api::models::ApiResponse;
+use crate::api::pagination::{ListQuery, Page};
+use crate::api::websocket::{ClientCommand, ClientRequest, CommandError, CommandReply, WebSocketUpdate};
+use crate::escalation_rules::ActiveEscalation;
+use crate::probability_series::ProbabilityChart;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Server returned {status}: {message}")]
+    Status { status: u16, message: String },
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("Could not decode server message: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    #[error("Invalid base URL or token: {0}")]
+    Invalid(String),
+}
+
+/// A camera event to ingest; the server fills in home, account and sensor from the camera's registration
+#[derive(Debug, Clone, Default)]
+pub struct CameraEvent {
+    pub event_id: Option<Uuid>, // Set it and reuse it on retries so the server can dedup
+    pub timestamp: Option<i64>, // Capture time; defaults to receipt
+    pub data: String,
+    pub image_url: Option<String>,
+    pub api_key: String,
+}
+
+/// Anything the server pushes over the WebSocket
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ServerMessage {
+    Reply(CommandReply),
+    Error(CommandError),
+    Update(WebSocketUpdate),
+}
+
+#[derive(Debug, Clone)]
+pub struct NovinClient {
+    base: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl NovinClient {
+    /// Client for the API at `base_url` (e.g. "https://novin.example.com") using a bearer token
+    pub fn new(base_url: &str, token: &str) -> Result<Self, ClientError> {
+        let base = base_url.trim_end_matches('/');
+        if !(base.starts_with("http://") || base.starts_with("https://")) {
+            return Err(ClientError::Invalid(format!("'{}' is not an http(s) URL", base_url)));
+        }
+        let http = reqwest::Client::builder().timeout(Duration::from_secs(30)).build()?;
+        Ok(Self { base: base.to_string(), token: token.to_string(), http })
+    }
+
+    /// Ingest an event from a registered camera, authenticated by its ingest token
+    pub async fn ingest_camera_event(&self, camera_id: &str, camera_token: &str, event: &CameraEvent) -> Result<EventResponse, ClientError> {
+        let body = CameraEventSubmission {
+            event_id: event.event_id,
+            timestamp: event.timestamp,
+            data: Cow::Borrowed(&event.data),
+            image_url: event.image_url.as_deref().map(Cow::Borrowed),
+            api_key: Cow::Borrowed(&event.api_key),
+        };
+        let request = self.http.post(self.url(&format!("/api/ingest/cameras/{}/events", camera_id)))
+            .header(CAMERA_TOKEN_HEADER, camera_token)
+            .json(&body);
+        self.send(request).await
+    }
+
+    /// A page of a home's incidents; pass the previous page's `next_cursor` to continue
+    pub async fn list_incidents(&self, home_id: &str, query: &ListQuery) -> Result<Page<IncidentRow>, ClientError> {
+        self.send(self.authed(Method::GET, &format!("/api/homes/{}/incidents", home_id)).query(query)).await
+    }
+
+    /// A page of the events fused into a home's incidents
+    pub async fn list_events(&self, home_id: &str, query: &ListQuery) -> Result<Page<EventRow>, ClientError> {
+        self.send(self.authed(Method::GET, &format!("/api/homes/{}/events", home_id)).query(query)).await
+    }
+
+    /// How an incident's probability evolved, optionally downsampled
+    pub async fn probability_series(&self, home_id: &str, incident_id: u64, max_points: Option<usize>) -> Result<ProbabilityChart, ClientError> {
+        let mut request = self.authed(Method::GET, &format!("/api/homes/{}/incidents/{}/probability", home_id, incident_id));
+        if let Some(max_points) = max_points {
+            request = request.query(&[("max_points", max_points)]);
+        }
+        self.send(request).await
+    }
+
+    /// Stop an incident's escalation chain
+    pub async fn acknowledge_escalation(&self, home_id: &str, incident_id: u64) -> Result<ActiveEscalation, ClientError> {
+        self.send(self.authed(Method::POST, &format!("/api/homes/{}/incidents/{}/escalation/acknowledge", home_id, incident_id))).await
+    }
+
+    /// Open the WebSocket for live alert updates and commands
+    pub async fn subscribe(&self) -> Result<AlertStream, ClientError> {
+        let ws_url = format!("{}/api/ws", self.base.replacen("http", "ws", 1));
+        let mut request = ws_url.into_client_request()?;
+        let auth = format!("Bearer {}", self.token).parse().map_err(|_| ClientError::Invalid("token is not a valid header value".to_string()))?;
+        request.headers_mut().insert("Authorization", auth);
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(AlertStream { socket })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path)
+    }
+
+    fn authed(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http.request(method, self.url(path)).bearer_auth(&self.token)
+    }
+
+    // Unwrap the `ApiResponse` envelope, turning non-2xx statuses into errors
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(ClientError::Status { status: status.as_u16(), message });
+        }
+        let envelope: ApiResponse<T> = response.json().await?;
+        Ok(envelope.data)
+    }
+}
+
+/// A live WebSocket connection
+pub struct AlertStream {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl AlertStream {
+    /// Next message from the server; None once the connection closes
+    pub async fn next(&mut self) -> Option<Result<ServerMessage, ClientError>> {
+        while let Some(message) = self.socket.next().await {
+            match message {
+                Ok(Message::Text(text)) => return Some(serde_json::from_str(&text).map_err(ClientError::from)),
+                Ok(Message::Close(_)) => return None,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        None
+    }
+
+    /// Send a command; its reply or error arrives on `next` carrying the returned request id
+    pub async fn send(&mut self, command: ClientCommand) -> Result<String, ClientError> {
+        let request_id = Uuid::new_v4().to_string();
+        let text = serde_json::to_string(&ClientRequest { request_id: request_id.clone(), command })?;
+        self.socket.send(Message::Text(text)).await?;
+        Ok(request_id)
+    }
+
+    pub async fn close(mut self) -> Result<(), ClientError> {
+        self.socket.close(None).await?;
+        Ok(())
+    }
+}
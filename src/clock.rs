@@ -0,0 +1,72 @@
+// src/clock.rs
+
+// Where "now" comes from. Incident expiry, follow-ups, cooldowns, escalation
+// windows and the morning summary schedule all depend on the current time;
+// reading it through a `Clock` instead of calling Utc::now directly lets tests
+// and replays drive that logic deterministically. Production uses the system
+// clock. A `SimulatedClock` only moves when told to, so a test can open an
+// incident, advance past its TTL and check that it expired, and a replay can
+// step the clock to each recorded event's timestamp.
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+pub trait Clock: Send + Sync + fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Now as fractional Unix seconds, the form the thinking AI timestamps events in
+    fn now_ts(&self) -> f64 {
+        self.now().timestamp_millis() as f64 / 1000.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The system clock, shared
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+/// A clock that stands still until it is set or advanced
+#[derive(Debug)]
+pub struct SimulatedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl SimulatedClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(start) }
+    }
+
+    /// Jump to `at`; going backwards is allowed, as a replay of out-of-order events may need
+    pub fn set(&self, at: DateTime<Utc>) {
+        if let Ok(mut now) = self.now.lock() {
+            *now = at;
+        }
+    }
+
+    /// Move forward by `by`; returns the new time
+    pub fn advance(&self, by: Duration) -> DateTime<Utc> {
+        match self.now.lock() {
+            Ok(mut now) => {
+                *now += by;
+                *now
+            }
+            Err(_) => self.now(),
+        }
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now.lock().map(|now| *now).unwrap_or_else(|e| *e.into_inner())
+    }
+}
@@ -5,6 +5,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::environment::EnvironmentSnapshot;
+use crate::explanation::{config_hash, Explanation, KeyCounterfactual};
+use crate::household::Occupancy;
+
 // Type aliases for complex domain types
 pub type CausalFactor = String;
 pub type PsychologicalProfile = HashMap<String, f64>;
@@ -30,6 +34,8 @@ pub struct EnvironmentalContext {
     pub location: String,
     pub ambient_conditions: Vec<String>,
     pub time_context: TimeContext,
+    #[serde(default)]
+    pub environment: Option<EnvironmentSnapshot>, // Weather and light, when enrichment is configured
 }
 
 /// Time-based context information
@@ -58,6 +64,7 @@ pub struct ThreatAssessment {
     pub threat_probability: f64,      // Bayesian probability
     pub uncertainty_bounds: (f64, f64), // Lower and upper bounds
     pub confidence_score: f64,        // Model confidence
+    #[serde(with = "crate::schema::duration_secs")]
     pub temporal_horizon: chrono::Duration, // Prediction time horizon
     pub causal_chain: Vec<CausalFactor>,
     pub psychological_profile: PsychologicalProfile,
@@ -66,7 +73,7 @@ pub struct ThreatAssessment {
     pub network_effects: NetworkEffects,
     pub countermeasures: Vec<Countermeasure>,
     pub assessment_timestamp: DateTime<Utc>,
-    pub explainability_trace: String, // AI reasoning explanation
+    pub explanation: Explanation, // Factors, key counterfactual and config fingerprint behind the score
 }
 
 /// Intelligence level configuration
@@ -151,6 +158,103 @@ pub struct ThreatContext {
     pub environmental_factors: HashMap<String, f64>,
     pub temporal_context: DateTime<Utc>,
     pub confidence: f64,
+    #[serde(default)]
+    pub zone_class: Option<ZoneClass>, // Where the detection happened, when the camera's zone is known
+    #[serde(default)]
+    pub occupancy: Option<Occupancy>, // Household presence at the time, when residents report it
+    #[serde(default)]
+    pub threat_vector: Option<ThreatVector>, // What the incident looks like, when the thinking AI has classified it
+}
+
+/// Whether a camera watches the inside of the dwelling or its surroundings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneClass {
+    Interior,
+    Exterior,
+}
+
+/// What kind of threat an incident or prediction is about. Serialized as the
+/// stable snake_case names from `as_str`; older names (`break_in`,
+/// `porch_piracy`, `loitering`, `reconnaissance`) still parse, and anything
+/// unrecognised reads as `Unknown` rather than failing the whole record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ThreatVector {
+    Burglary,
+    PackageTheft,
+    Vandalism,
+    Trespass,
+    Casing,
+    #[default]
+    Unknown,
+}
+
+impl ThreatVector {
+    pub const ALL: [ThreatVector; 6] = [
+        ThreatVector::Burglary,
+        ThreatVector::PackageTheft,
+        ThreatVector::Vandalism,
+        ThreatVector::Trespass,
+        ThreatVector::Casing,
+        ThreatVector::Unknown,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThreatVector::Burglary => "burglary",
+            ThreatVector::PackageTheft => "package_theft",
+            ThreatVector::Vandalism => "vandalism",
+            ThreatVector::Trespass => "trespass",
+            ThreatVector::Casing => "casing",
+            ThreatVector::Unknown => "unknown",
+        }
+    }
+
+    /// Phrase for summaries and explanations
+    pub fn label(self) -> &'static str {
+        match self {
+            ThreatVector::Burglary => "possible break-in",
+            ThreatVector::PackageTheft => "possible package theft",
+            ThreatVector::Vandalism => "possible vandalism",
+            ThreatVector::Trespass => "trespassing",
+            ThreatVector::Casing => "possible casing of the property",
+            ThreatVector::Unknown => "unclassified activity",
+        }
+    }
+}
+
+impl std::fmt::Display for ThreatVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ThreatVector {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_ascii_lowercase().replace(['-', ' '], "_").as_str() {
+            "burglary" | "break_in" | "intrusion" => ThreatVector::Burglary,
+            "package_theft" | "porch_piracy" => ThreatVector::PackageTheft,
+            "vandalism" | "tamper" => ThreatVector::Vandalism,
+            "trespass" | "trespassing" | "loitering" => ThreatVector::Trespass,
+            "casing" | "reconnaissance" => ThreatVector::Casing,
+            _ => ThreatVector::Unknown,
+        })
+    }
+}
+
+impl Serialize for ThreatVector {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ThreatVector {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(name.parse().unwrap_or_default())
+    }
 }
 
 /// Dynamic threshold management
@@ -232,7 +336,7 @@ impl InsaneSecuritySystem {
                 "immediate_response".to_string()
             ],
             assessment_timestamp: Utc::now(),
-            explainability_trace: "Guardian mode: Active protection with visible deterrence measures".to_string(),
+            explanation: self.explain(context, "Guardian mode: Active protection with visible deterrence measures", base_threat, enhanced_threat.min(1.0)),
         }
     }
 
@@ -259,7 +363,7 @@ impl InsaneSecuritySystem {
                 "delayed_response".to_string()
             ],
             assessment_timestamp: Utc::now(),
-            explainability_trace: "Stealth mode: Covert monitoring with minimal detection signature".to_string(),
+            explanation: self.explain(context, "Stealth mode: Covert monitoring with minimal detection signature", base_threat, stealth_threat),
         }
     }
 
@@ -286,7 +390,7 @@ impl InsaneSecuritySystem {
                 "boundary_monitoring".to_string()
             ],
             assessment_timestamp: Utc::now(),
-            explainability_trace: "Perimeter Guard mode: Boundary-focused protection with access control".to_string(),
+            explanation: self.explain(context, "Perimeter Guard mode: Boundary-focused protection with access control", base_threat, perimeter_threat),
         }
     }
 
@@ -306,6 +410,38 @@ impl InsaneSecuritySystem {
         }
     }
 
+    // Indicators share the base score in proportion to their values; the mode's
+    // scaling is its own factor. The key counterfactual drops the strongest indicator.
+    fn explain(&self, context: &ThreatContext, headline: &str, base_threat: f64, threat_level: f64) -> Explanation {
+        let raw_total: f64 = context.threat_indicators.values().sum();
+        let mut contributions: Vec<(String, f64)> = context.threat_indicators.iter()
+            .map(|(name, value)| {
+                let share = if raw_total != 0.0 { value / raw_total } else { 0.0 };
+                (name.clone(), share * base_threat)
+            })
+            .collect();
+        contributions.push((format!("{:?} mode", self.config.security_mode), threat_level - base_threat));
+
+        let strongest = context.threat_indicators.iter()
+            .filter(|(_, value)| **value > 0.0)
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let counterfactual = strongest.filter(|_| context.threat_indicators.len() > 1).map(|(name, _)| {
+            let mut reduced = context.clone();
+            reduced.threat_indicators.remove(name);
+            let scale = if base_threat > 0.0 { threat_level / base_threat } else { 1.0 };
+            let without = (self.calculate_base_threat(&reduced) * scale).min(1.0);
+            let threshold = self.config.threat_threshold;
+            KeyCounterfactual {
+                description: format!("No {} indicator", name),
+                delta: without - threat_level,
+                crosses_threshold: (threat_level >= threshold) != (without >= threshold),
+            }
+        });
+
+        let hash = config_hash(&(&self.config, &self.thresholds));
+        Explanation::new(headline, contributions, hash).with_counterfactual(counterfactual)
+    }
+
     fn calculate_perimeter_threat(&self, context: &ThreatContext, base_threat: f64) -> f64 {
         // Enhanced threat calculation for perimeter violations
         let perimeter_multiplier = if context.threat_indicators.contains_key("perimeter_breach") {
@@ -375,6 +511,7 @@ impl InsaneSecuritySystem {
             location: "monitored_area".to_string(),
             ambient_conditions: vec!["normal_lighting".to_string(), "clear_visibility".to_string()],
             time_context: TimeContext::Afternoon,
+            environment: None,
         }
     }
 
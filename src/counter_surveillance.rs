@@ -0,0 +1,422 @@
+//! Counter-surveillance: loitering and reconnaissance detection
+//!
+//! Casing a property looks different from walking past it: someone waits across
+//! the street, the same car comes back slowly several times in an evening, or a
+//! person stands facing the camera long enough to study it. Sightings are kept
+//! per home and subject (plate or appearance key, stable across tracker
+//! sessions) for a sliding window and scored on those three signals.
+//!
+//! The pipeline records a sighting for every event whose sensor data carries a
+//! position (`pos_x=`, `pos_y=`, optionally `speed=`, `facing_camera=`,
+//! `plate=` or `subject=`), and a suspicious score counts as behavior evidence.
+//! `ReconnaissanceAnalyzer` answers the thinking layer's adversarial handoff
+//! from the same trajectories plus a few incident-shape heuristics.
+
+use crate::core::{EnvironmentalContext, Entity};
+use crate::environment::HomeCalendars;
+use crate::thinking::{AdversarialAnalyzer, AdversarialFindings, HandoffError, HandoffRequest};
+use crate::SecurityResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectKind {
+    Person,
+    Vehicle,
+}
+
+/// One tracker observation in the property frame: x runs along the street with
+/// 0 at the middle of the frontage, y is the distance out from the property line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sighting {
+    pub entity_id: Uuid,
+    pub subject_key: String, // Plate or appearance cluster; the same across passes
+    pub kind: SubjectKind,
+    pub at: DateTime<Utc>,
+    pub x_m: f64,
+    pub y_m: f64,
+    pub speed_mps: f64,
+    pub facing_camera: bool,
+}
+
+impl Sighting {
+    /// Read a sighting from `key=value` tokens in an event's sensor data. Without
+    /// a plate or subject token the observation belongs to `default_key`, the
+    /// tracker's session id; events without a position carry no sighting.
+    pub fn from_payload(data: &str, entity_id: Uuid, default_key: &str, at: DateTime<Utc>) -> Option<Self> {
+        let mut fields: HashMap<&str, &str> = HashMap::new();
+        for (name, value) in data.split(['|', ',', ';']).filter_map(|token| token.split_once('=')) {
+            fields.insert(name.trim(), value.trim());
+        }
+        let number = |name: &str| fields.get(name).and_then(|v| v.parse::<f64>().ok()).filter(|v| v.is_finite());
+        let (x_m, y_m) = (number("pos_x")?, number("pos_y")?);
+        let plate = fields.get("plate").filter(|p| !p.is_empty());
+        let (kind, subject_key) = match plate {
+            Some(plate) => (SubjectKind::Vehicle, plate.to_uppercase()),
+            None => (
+                SubjectKind::Person,
+                fields.get("subject").filter(|s| !s.is_empty()).map_or_else(|| default_key.to_string(), |s| s.to_string()),
+            ),
+        };
+        Some(Self {
+            entity_id,
+            subject_key,
+            kind,
+            at,
+            x_m,
+            y_m,
+            speed_mps: number("speed").unwrap_or(0.0).max(0.0),
+            facing_camera: matches!(fields.get("facing_camera").copied(), Some("1" | "true")),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CounterSurveillanceConfig {
+    pub frontage_half_width_m: f64,  // |x| within this is "in front of" the property
+    pub opposite_min_y_m: f64,       // Far side of the street starts here
+    pub opposite_max_y_m: f64,
+    pub pass_window: Duration,       // Repeated passes are counted within this window
+    pub pass_gap: Duration,          // Silence longer than this starts a new pass
+    pub min_pass_span_m: f64,        // A pass must travel this far along the frontage
+    pub slow_person_mps: f64,
+    pub slow_vehicle_mps: f64,
+    pub dwell_threshold_secs: f64,   // Signal strength reaches 0.5 here
+    pub passes_threshold: f64,
+    pub gaze_threshold_secs: f64,
+    pub suspicious_score: f64,
+    pub max_behavior_llr: f64,       // Behavior evidence at a score of 1.0
+    pub max_sightings_per_subject: usize,
+}
+
+impl Default for CounterSurveillanceConfig {
+    fn default() -> Self {
+        Self {
+            frontage_half_width_m: 25.0,
+            opposite_min_y_m: 8.0,
+            opposite_max_y_m: 30.0,
+            pass_window: Duration::hours(4),
+            pass_gap: Duration::seconds(60),
+            min_pass_span_m: 15.0,
+            slow_person_mps: 0.8,
+            slow_vehicle_mps: 4.0,
+            dwell_threshold_secs: 120.0,
+            passes_threshold: 3.0,
+            gaze_threshold_secs: 10.0,
+            suspicious_score: 0.5,
+            max_behavior_llr: 1.2,
+            max_sightings_per_subject: 2_000,
+        }
+    }
+}
+
+/// One surveillance signal: what was measured, how strongly it points at
+/// casing, and how much data it rests on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SurveillanceSignal {
+    pub measured: f64,   // Seconds or pass count
+    pub strength: f64,   // 0-1; 0.5 at the configured threshold
+    pub confidence: f64, // 0-1; grows with supporting sightings
+    pub observations: usize,
+}
+
+impl SurveillanceSignal {
+    fn new(measured: f64, threshold: f64, observations: usize, confidence_scale: f64) -> Self {
+        let strength = if threshold > 0.0 { (measured / threshold).min(2.0) / 2.0 } else { 0.0 };
+        Self {
+            measured,
+            strength,
+            confidence: 1.0 - (-(observations as f64) / confidence_scale).exp(),
+            observations,
+        }
+    }
+
+    fn weighted(&self) -> f64 {
+        self.strength * self.confidence
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SurveillanceIndicators {
+    pub entity_id: Uuid,
+    pub subject_key: String,
+    pub opposite_dwell: SurveillanceSignal,   // Seconds spent across the street
+    pub repeated_passes: SurveillanceSignal,  // Slow passes within the window
+    pub camera_gaze: SurveillanceSignal,      // Seconds facing the camera
+    pub score: f64,                           // Noisy-OR of confidence-weighted strengths
+    pub suspicious: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SurveillanceAnalysisResult {
+    pub indicators: Vec<SurveillanceIndicators>, // Highest score first
+    pub suspicious_entities: Vec<Uuid>,
+    pub max_score: f64,
+}
+
+impl SurveillanceIndicators {
+    /// Behavior evidence for a suspicious subject; nothing below the threshold
+    pub fn behavior_llr(&self, config: &CounterSurveillanceConfig) -> f64 {
+        if self.suspicious { self.score * config.max_behavior_llr } else { 0.0 }
+    }
+}
+
+type SubjectKey = (String, String); // Home, then subject
+
+#[derive(Debug, Default)]
+pub struct CounterSurveillanceSystem {
+    config: CounterSurveillanceConfig,
+    subjects: RwLock<HashMap<SubjectKey, VecDeque<Sighting>>>,
+}
+
+impl CounterSurveillanceSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: CounterSurveillanceConfig) -> Self {
+        Self { config, subjects: RwLock::default() }
+    }
+
+    pub fn config(&self) -> &CounterSurveillanceConfig {
+        &self.config
+    }
+
+    /// Add a tracker observation to its subject's trajectory in `home_id`
+    pub async fn record_sighting(&self, home_id: &str, sighting: Sighting) {
+        let mut subjects = self.subjects.write().await;
+        let track = subjects.entry((home_id.to_string(), sighting.subject_key.clone())).or_default();
+        // Trackers can deliver slightly out of order; keep the trajectory sorted
+        let pos = track.iter().rposition(|s| s.at <= sighting.at).map_or(0, |i| i + 1);
+        track.insert(pos, sighting);
+        while track.len() > self.config.max_sightings_per_subject {
+            track.pop_front();
+        }
+    }
+
+    /// Score every subject seen as one of `entities` within the pass window
+    pub async fn detect_surveillance_activities(
+        &self,
+        entities: &[Entity],
+        context: &EnvironmentalContext,
+    ) -> SecurityResult<SurveillanceAnalysisResult> {
+        self.analyze_at(entities, context, Utc::now()).await
+    }
+
+    pub async fn analyze_at(
+        &self,
+        entities: &[Entity],
+        _context: &EnvironmentalContext,
+        now: DateTime<Utc>,
+    ) -> SecurityResult<SurveillanceAnalysisResult> {
+        let mut subjects = self.subjects.write().await;
+        self.prune(&mut subjects, now);
+
+        let mut indicators: Vec<SurveillanceIndicators> = entities.iter()
+            .filter_map(|e| self.best_for_entity(&subjects, e.id))
+            .collect();
+        indicators.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(SurveillanceAnalysisResult {
+            suspicious_entities: indicators.iter().filter(|i| i.suspicious).map(|i| i.entity_id).collect(),
+            max_score: indicators.first().map_or(0.0, |i| i.score),
+            indicators,
+        })
+    }
+
+    /// Score one subject's trajectory in `home_id` directly, e.g. an incident's person track
+    pub async fn assess_subject(&self, home_id: &str, subject_key: &str, now: DateTime<Utc>) -> Option<SurveillanceIndicators> {
+        let mut subjects = self.subjects.write().await;
+        self.prune(&mut subjects, now);
+        subjects.get(&(home_id.to_string(), subject_key.to_string())).map(|track| self.score_track(subject_key, track))
+    }
+
+    fn prune(&self, subjects: &mut HashMap<SubjectKey, VecDeque<Sighting>>, now: DateTime<Utc>) {
+        let cutoff = now - self.config.pass_window;
+        for track in subjects.values_mut() {
+            while track.front().map_or(false, |s| s.at < cutoff) {
+                track.pop_front();
+            }
+        }
+        subjects.retain(|_, track| !track.is_empty());
+    }
+
+    // An entity can map to several subjects (e.g. tracker id reuse); report the worst
+    fn best_for_entity(
+        &self,
+        subjects: &HashMap<SubjectKey, VecDeque<Sighting>>,
+        entity_id: Uuid,
+    ) -> Option<SurveillanceIndicators> {
+        subjects.iter()
+            .filter(|(_, track)| track.iter().any(|s| s.entity_id == entity_id))
+            .map(|((_, key), track)| {
+                let mut indicators = self.score_track(key, track);
+                indicators.entity_id = entity_id;
+                indicators
+            })
+            .max_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    fn is_opposite(&self, s: &Sighting) -> bool {
+        s.x_m.abs() <= self.config.frontage_half_width_m
+            && s.y_m >= self.config.opposite_min_y_m
+            && s.y_m <= self.config.opposite_max_y_m
+    }
+
+    fn score_track(&self, key: &str, track: &VecDeque<Sighting>) -> SurveillanceIndicators {
+        let gap_secs = self.config.pass_gap.num_milliseconds() as f64 / 1000.0;
+
+        // Dwell and gaze integrate the time between consecutive sightings of one pass
+        let (mut dwell_secs, mut dwell_obs) = (0.0, 0);
+        let (mut gaze_secs, mut gaze_obs) = (0.0, 0);
+        let mut passes: Vec<Vec<&Sighting>> = Vec::new();
+        let mut prev: Option<&Sighting> = None;
+        for s in track {
+            let dt = prev.map(|p| (s.at - p.at).num_milliseconds() as f64 / 1000.0);
+            match (prev, dt) {
+                (Some(p), Some(dt)) if dt <= gap_secs => {
+                    if self.is_opposite(p) && self.is_opposite(s) {
+                        dwell_secs += dt;
+                        dwell_obs += 1;
+                    }
+                    if p.facing_camera && s.facing_camera {
+                        gaze_secs += dt;
+                        gaze_obs += 1;
+                    }
+                    if let Some(pass) = passes.last_mut() {
+                        pass.push(s);
+                    }
+                }
+                _ => passes.push(vec![s]),
+            }
+            prev = Some(s);
+        }
+
+        let slow_passes = passes.iter().filter(|p| self.is_slow_pass(p)).count();
+        let opposite_dwell = SurveillanceSignal::new(dwell_secs, self.config.dwell_threshold_secs, dwell_obs, 10.0);
+        let repeated_passes = SurveillanceSignal::new(
+            // A single slow pass is ordinary; only repeats count
+            slow_passes.saturating_sub(1) as f64,
+            self.config.passes_threshold - 1.0,
+            slow_passes,
+            2.0,
+        );
+        let camera_gaze = SurveillanceSignal::new(gaze_secs, self.config.gaze_threshold_secs, gaze_obs, 5.0);
+
+        let score = 1.0 - [&opposite_dwell, &repeated_passes, &camera_gaze]
+            .iter()
+            .map(|s| 1.0 - s.weighted())
+            .product::<f64>();
+
+        SurveillanceIndicators {
+            entity_id: track.back().map(|s| s.entity_id).unwrap_or_default(),
+            subject_key: key.to_string(),
+            opposite_dwell,
+            repeated_passes,
+            camera_gaze,
+            suspicious: score >= self.config.suspicious_score,
+            score,
+        }
+    }
+
+    fn is_slow_pass(&self, pass: &[&Sighting]) -> bool {
+        let in_front: Vec<&&Sighting> = pass.iter()
+            .filter(|s| s.x_m.abs() <= self.config.frontage_half_width_m)
+            .collect();
+        if in_front.len() < 2 {
+            return false;
+        }
+        let (min_x, max_x) = in_front.iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), s| (lo.min(s.x_m), hi.max(s.x_m)));
+        if max_x - min_x < self.config.min_pass_span_m {
+            return false;
+        }
+        let mean_speed = in_front.iter().map(|s| s.speed_mps).sum::<f64>() / in_front.len() as f64;
+        let slow = match in_front[0].kind {
+            SubjectKind::Person => self.config.slow_person_mps,
+            SubjectKind::Vehicle => self.config.slow_vehicle_mps,
+        };
+        mean_speed <= slow
+    }
+}
+
+/// Adversarial second opinion for mid-band incidents: the person's trajectory
+/// (perimeter probing across cameras, long dwell by an unknown visitor) turned
+/// into a threat adjustment and countermeasures
+pub struct ReconnaissanceAnalyzer {
+    surveillance: Arc<CounterSurveillanceSystem>,
+    calendars: Option<Arc<HomeCalendars>>, // Darkness at each home, in its local time
+    probing_cameras: usize,  // Distinct cameras that suggest walking the perimeter
+    long_dwell_secs: f64,
+}
+
+impl ReconnaissanceAnalyzer {
+    pub fn new(surveillance: Arc<CounterSurveillanceSystem>) -> Self {
+        Self { surveillance, calendars: None, probing_cameras: 3, long_dwell_secs: 120.0 }
+    }
+
+    /// Weigh patterns found after dark more, by each home's calendar
+    pub fn with_calendars(mut self, calendars: Arc<HomeCalendars>) -> Self {
+        self.calendars = Some(calendars);
+        self
+    }
+}
+
+#[async_trait]
+impl AdversarialAnalyzer for ReconnaissanceAnalyzer {
+    async fn analyze(&self, request: &HandoffRequest) -> Result<AdversarialFindings, HandoffError> {
+        let now = DateTime::from_timestamp(request.last_updated as i64, 0)
+            .ok_or_else(|| HandoffError::Analysis(format!("invalid incident time {}", request.last_updated)))?;
+        let surveillance = self.surveillance.assess_subject(&request.home_id, &request.person_track, now).await;
+
+        let mut adjustment = 0.0;
+        let mut countermeasures = Vec::new();
+        let mut notes = Vec::new();
+
+        if let Some(indicators) = surveillance.filter(|i| i.score > 0.0) {
+            adjustment += indicators.score;
+            if indicators.opposite_dwell.strength >= 0.5 {
+                notes.push(format!("watched the house from across the street for {:.0}s", indicators.opposite_dwell.measured));
+                countermeasures.push("Review footage of the opposite side of the street".to_string());
+            }
+            if indicators.repeated_passes.strength >= 0.5 {
+                notes.push(format!("{} slow passes", indicators.repeated_passes.measured as u32));
+                countermeasures.push("Add this subject to the repeat-pass watchlist".to_string());
+            }
+            if indicators.camera_gaze.strength >= 0.5 {
+                notes.push("repeatedly looked at the cameras".to_string());
+                countermeasures.push("Check camera coverage for blind spots".to_string());
+            }
+        }
+
+        if request.cameras.len() >= self.probing_cameras {
+            adjustment += 0.3;
+            notes.push(format!("moved across {} cameras", request.cameras.len()));
+            countermeasures.push("Turn on exterior lighting on all sides".to_string());
+        }
+        if request.total_dwell_s >= self.long_dwell_secs && request.evidence.llr_identity > 0.0 {
+            adjustment += 0.2;
+            notes.push(format!("unknown visitor stayed {:.0}s", request.total_dwell_s));
+        }
+
+        // Casing a house after dark is more deliberate than the same walk by day
+        let time_risk = self.calendars.as_ref().and_then(|c| c.time_risk(&request.home_id, now));
+        if let Some(time_risk) = time_risk.filter(|r| adjustment > 0.0 && *r > 0.5) {
+            adjustment += time_risk - 0.5;
+            notes.push("after dark".to_string());
+        }
+
+        let summary = if notes.is_empty() {
+            "no reconnaissance pattern found".to_string()
+        } else {
+            format!("possible reconnaissance: {}", notes.join(", "))
+        };
+        Ok(AdversarialFindings { threat_adjustment_llr: adjustment, countermeasures, summary })
+    }
+}
@@ -0,0 +1,262 @@
+// src/dataset_export.rs
+
+// Training datasets from labeled incidents. Each labeled incident becomes one
+// row: the LLR of every evidence channel summed over its events, the context
+// fusion saw (event count, dwell, away probability, doorbell, expected
+// window, time of day), the outcome the daemon reached (peak probability and
+// decision) and the label from feedback. The rows are meant to leave the
+// daemon for offline model training and threshold research, so nothing that
+// identifies a household goes with them: home ids become salted hashes, and
+// person sessions, camera names, access tokens, snapshot URLs, narratives and
+// absolute timestamps are dropped. Hour of day and weekday are kept, the
+// start time itself is not.
+//
+// CSV is always available; Parquet needs the `parquet` feature.
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+
+use crate::thinking::evidence_channels::BUILTIN_CHANNELS;
+use crate::thinking::{IncidentLabel, IncidentSnapshotEntry};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DatasetError {
+    #[error("Dataset I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Parquet encoding failed: {0}")]
+    Parquet(String),
+
+    #[error("Unsupported dataset format {0}")]
+    UnsupportedFormat(String),
+
+    #[error("No labeled incidents to export")]
+    Empty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetFormat {
+    Csv,
+    Parquet,
+}
+
+impl std::str::FromStr for DatasetFormat {
+    type Err = DatasetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(DatasetFormat::Csv),
+            "parquet" => Ok(DatasetFormat::Parquet),
+            other => Err(DatasetError::UnsupportedFormat(other.to_string())),
+        }
+    }
+}
+
+/// One labeled incident with its identifying details removed
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetRow {
+    pub home: String,          // Salted hash, stable within one export salt
+    pub llr: Vec<(String, f64)>, // Summed per channel, built-in channels first
+    pub event_count: usize,
+    pub camera_count: usize,
+    pub duration_s: f64,
+    pub total_dwell_s: f64,
+    pub max_away_prob: f64,
+    pub rang_doorbell: bool,
+    pub knocked: bool,
+    pub expected_window: bool,
+    pub token_presented: bool, // Whether any event carried an access token, not the token
+    pub hour_of_day: u32,      // UTC
+    pub weekday: u32,          // 0 = Monday
+    pub peak_probability: f64,
+    pub final_decision: String,
+    pub label: IncidentLabel,
+}
+
+/// Salted, truncated hash of an identifier; the same salt gives the same pseudonym
+pub fn pseudonymize(salt: &str, id: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update([0]);
+    hasher.update(id.as_bytes());
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// Rows for every labeled incident, ordered by incident id; unlabeled ones are skipped
+pub fn build_rows(incidents: &[IncidentSnapshotEntry], labels: &HashMap<u64, IncidentLabel>, salt: &str) -> Vec<DatasetRow> {
+    let mut entries: Vec<&IncidentSnapshotEntry> = incidents.iter().filter(|e| labels.contains_key(&e.incident.id)).collect();
+    entries.sort_by_key(|e| e.incident.id);
+    entries.into_iter().map(|entry| {
+        let incident = &entry.incident;
+        let mut llr: Vec<(String, f64)> = Vec::new();
+        for event in &incident.events {
+            for (channel, value) in event.evidence.channels() {
+                match llr.iter_mut().find(|(c, _)| *c == channel) {
+                    Some((_, sum)) => *sum += value,
+                    None => llr.push((channel, value)),
+                }
+            }
+        }
+        let started = DateTime::<Utc>::from_timestamp(incident.started_at as i64, 0).unwrap_or_default();
+        let peak = incident.probability_trace.iter().map(|p| p.calibrated_probability).fold(0.0, f64::max);
+        DatasetRow {
+            home: pseudonymize(salt, &entry.home),
+            llr,
+            event_count: incident.events.len(),
+            camera_count: incident.cameras.len(),
+            duration_s: (incident.last_updated - incident.started_at).max(0.0),
+            total_dwell_s: incident.total_dwell(),
+            max_away_prob: incident.events.iter().map(|e| e.away_prob).fold(0.0, f64::max),
+            rang_doorbell: incident.events.iter().any(|e| e.rang_doorbell),
+            knocked: incident.events.iter().any(|e| e.knocked),
+            expected_window: incident.events.iter().any(|e| e.expected_window),
+            token_presented: incident.events.iter().any(|e| e.token.is_some()),
+            hour_of_day: started.hour(),
+            weekday: started.weekday().num_days_from_monday(),
+            peak_probability: peak,
+            final_decision: incident.probability_trace.last()
+                .map_or_else(|| "none".to_string(), |p| format!("{:?}", p.decision).to_ascii_lowercase()),
+            label: labels[&incident.id],
+        }
+    }).collect()
+}
+
+/// LLR columns: every built-in channel, then any custom channel seen in the rows
+pub fn channel_columns(rows: &[DatasetRow]) -> Vec<String> {
+    let custom: BTreeSet<&str> = rows.iter()
+        .flat_map(|r| r.llr.iter().map(|(c, _)| c.as_str()))
+        .filter(|c| !BUILTIN_CHANNELS.contains(c))
+        .collect();
+    BUILTIN_CHANNELS.iter().copied().chain(custom).map(str::to_string).collect()
+}
+
+const CONTEXT_COLUMNS: [&str; 11] = [
+    "event_count", "camera_count", "duration_s", "total_dwell_s", "max_away_prob", "rang_doorbell",
+    "knocked", "expected_window", "token_presented", "hour_of_day", "weekday",
+];
+
+fn label_name(label: IncidentLabel) -> &'static str {
+    match label {
+        IncidentLabel::Threat => "threat",
+        IncidentLabel::Benign => "benign",
+    }
+}
+
+fn llr_value(row: &DatasetRow, channel: &str) -> f64 {
+    row.llr.iter().find(|(c, _)| c == channel).map_or(0.0, |(_, v)| *v)
+}
+
+/// Write rows as CSV with a header; channels a row lacks are 0
+pub fn write_csv<W: Write>(rows: &[DatasetRow], mut out: W) -> Result<(), DatasetError> {
+    let channels = channel_columns(rows);
+    let header: Vec<String> = std::iter::once("home".to_string())
+        .chain(channels.iter().map(|c| format!("llr_{}", c)))
+        .chain(CONTEXT_COLUMNS.iter().map(|c| c.to_string()))
+        .chain(["peak_probability", "final_decision", "label"].iter().map(|c| c.to_string()))
+        .collect();
+    writeln!(out, "{}", header.join(","))?;
+    for row in rows {
+        let mut fields: Vec<String> = vec![row.home.clone()];
+        fields.extend(channels.iter().map(|c| format!("{:.6}", llr_value(row, c))));
+        fields.extend([
+            row.event_count.to_string(),
+            row.camera_count.to_string(),
+            format!("{:.3}", row.duration_s),
+            format!("{:.3}", row.total_dwell_s),
+            format!("{:.4}", row.max_away_prob),
+            row.rang_doorbell.to_string(),
+            row.knocked.to_string(),
+            row.expected_window.to_string(),
+            row.token_presented.to_string(),
+            row.hour_of_day.to_string(),
+            row.weekday.to_string(),
+            format!("{:.6}", row.peak_probability),
+            row.final_decision.clone(),
+            label_name(row.label).to_string(),
+        ]);
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Write rows as a single Parquet row group, with the same columns as the CSV
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(rows: &[DatasetRow], out: W) -> Result<(), DatasetError> {
+    use arrow_array::{ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, UInt32Array, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    let channels = channel_columns(rows);
+    let mut fields = vec![Field::new("home", DataType::Utf8, false)];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.home.as_str())))];
+    for channel in &channels {
+        fields.push(Field::new(format!("llr_{}", channel), DataType::Float64, false));
+        columns.push(Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| llr_value(r, channel)))));
+    }
+    let floats: [(&str, fn(&DatasetRow) -> f64); 4] = [
+        ("duration_s", |r| r.duration_s),
+        ("total_dwell_s", |r| r.total_dwell_s),
+        ("max_away_prob", |r| r.max_away_prob),
+        ("peak_probability", |r| r.peak_probability),
+    ];
+    let flags: [(&str, fn(&DatasetRow) -> bool); 4] = [
+        ("rang_doorbell", |r| r.rang_doorbell),
+        ("knocked", |r| r.knocked),
+        ("expected_window", |r| r.expected_window),
+        ("token_presented", |r| r.token_presented),
+    ];
+    fields.push(Field::new("event_count", DataType::UInt64, false));
+    columns.push(Arc::new(UInt64Array::from_iter_values(rows.iter().map(|r| r.event_count as u64))));
+    fields.push(Field::new("camera_count", DataType::UInt64, false));
+    columns.push(Arc::new(UInt64Array::from_iter_values(rows.iter().map(|r| r.camera_count as u64))));
+    for (name, get) in floats {
+        fields.push(Field::new(name, DataType::Float64, false));
+        columns.push(Arc::new(Float64Array::from_iter_values(rows.iter().map(get))));
+    }
+    for (name, get) in flags {
+        fields.push(Field::new(name, DataType::Boolean, false));
+        columns.push(Arc::new(BooleanArray::from(rows.iter().map(get).collect::<Vec<_>>())));
+    }
+    fields.push(Field::new("hour_of_day", DataType::UInt32, false));
+    columns.push(Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.hour_of_day))));
+    fields.push(Field::new("weekday", DataType::UInt32, false));
+    columns.push(Arc::new(UInt32Array::from_iter_values(rows.iter().map(|r| r.weekday))));
+    fields.push(Field::new("final_decision", DataType::Utf8, false));
+    columns.push(Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.final_decision.as_str()))));
+    fields.push(Field::new("label", DataType::Utf8, false));
+    columns.push(Arc::new(StringArray::from_iter_values(rows.iter().map(|r| label_name(r.label)))));
+
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| DatasetError::Parquet(e.to_string()))?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(out, schema, None).map_err(|e| DatasetError::Parquet(e.to_string()))?;
+    writer.write(&batch).map_err(|e| DatasetError::Parquet(e.to_string()))?;
+    writer.close().map_err(|e| DatasetError::Parquet(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+pub fn write_parquet<W: Write + Send>(_rows: &[DatasetRow], _out: W) -> Result<(), DatasetError> {
+    Err(DatasetError::UnsupportedFormat("parquet (build with --features parquet)".to_string()))
+}
+
+/// Build and write the dataset; returns the number of rows written
+pub fn export<W: Write + Send>(
+    incidents: &[IncidentSnapshotEntry],
+    labels: &HashMap<u64, IncidentLabel>,
+    salt: &str,
+    format: DatasetFormat,
+    out: W,
+) -> Result<usize, DatasetError> {
+    let rows = build_rows(incidents, labels, salt);
+    if rows.is_empty() {
+        return Err(DatasetError::Empty);
+    }
+    match format {
+        DatasetFormat::Csv => write_csv(&rows, out)?,
+        DatasetFormat::Parquet => write_parquet(&rows, out)?,
+    }
+    Ok(rows.len())
+}
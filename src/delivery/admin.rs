@@ -0,0 +1,81 @@
+//! Admin delivery channel for the server's own health alerts
+//!
+//! Watchdog alerts are about the system, not about any household, so they never
+//! go through the notification router. They are logged, kept in a short history
+//! for the admin API, and POSTed to an operator webhook when one is configured,
+//! signed the same way as household webhooks.
+
+use crate::delivery::webhook::{sign_payload, SIGNATURE_HEADER};
+use crate::watchdog::{HealthAlert, HealthSeverity};
+use chrono::Utc;
+use reqwest::Client;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{error, warn};
+
+const HISTORY: usize = 200;
+
+#[derive(Debug, Clone)]
+struct AdminWebhook {
+    url: String,
+    secret: String,
+}
+
+#[derive(Debug, Default)]
+pub struct AdminChannel {
+    webhook: Option<AdminWebhook>,
+    client: Client,
+    recent: Mutex<VecDeque<HealthAlert>>,
+}
+
+impl AdminChannel {
+    pub fn new(url: Option<String>, secret: String) -> Self {
+        Self {
+            webhook: url.map(|url| AdminWebhook { url, secret }),
+            client: Client::builder().timeout(Duration::from_secs(5)).build().unwrap_or_default(),
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Operator webhook from NOVIN_ADMIN_WEBHOOK_URL, signed with NOVIN_ADMIN_WEBHOOK_SECRET;
+    /// without a URL alerts are only logged and kept for the admin API
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self::new(var("NOVIN_ADMIN_WEBHOOK_URL"), var("NOVIN_ADMIN_WEBHOOK_SECRET").unwrap_or_default())
+    }
+
+    pub async fn deliver(&self, alert: &HealthAlert) {
+        match alert.severity {
+            HealthSeverity::Critical => error!(kind = ?alert.condition, "self-health: {}", alert.message),
+            HealthSeverity::Warning => warn!(kind = ?alert.condition, "self-health: {}", alert.message),
+        }
+        if let Ok(mut recent) = self.recent.lock() {
+            recent.push_back(alert.clone());
+            if recent.len() > HISTORY {
+                recent.pop_front();
+            }
+        }
+
+        let Some(webhook) = &self.webhook else { return };
+        let Ok(body) = serde_json::to_vec(alert) else { return };
+        let result = self.client.post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, sign_payload(&webhook.secret, Utc::now().timestamp(), &body))
+            .body(body)
+            .send()
+            .await;
+        match result {
+            Ok(resp) if !resp.status().is_success() => warn!(url = %webhook.url, status = %resp.status(), "admin alert rejected"),
+            Err(e) => warn!(url = %webhook.url, error = %e, "admin alert not delivered"),
+            Ok(_) => {}
+        }
+    }
+
+    /// Most recent alerts first
+    pub fn recent(&self, limit: usize) -> Vec<HealthAlert> {
+        self.recent.lock()
+            .map(|r| r.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,637 @@
+//! Central station export over SIA DC-09 with Contact ID
+//!
+//! Professional monitoring centers take alarms from panels, not apps, so a
+//! home with a monitoring contract reports to its central station the way a
+//! panel would: an SIA DC-09 frame over TCP carrying a Contact ID event
+//! (`ADM-CID`). The event code follows the incident's severity (general
+//! alarm, burglary, verified burglary by default) and the zone comes from the
+//! home's camera mapping, so the operator sees which part of the property
+//! alarmed. An incident is reported once per severity it reaches, and a
+//! Contact ID restore follows when it closes. A supervised heartbeat (a DC-09
+//! `NULL` message, or a Contact ID periodic test report) keeps the station's
+//! line supervision satisfied; missed acknowledgements are counted so a dead
+//! link shows up before an alarm needs it. Frames are sent unencrypted; the
+//! receiver link is expected to run over a VPN or private line.
+
+use crate::thinking::{AlertDecision, IncidentLifecycleHook, IncidentTransition};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::{info, warn};
+
+// Contact ID codes we send outside the severity mapping
+const CID_MANUAL_TEST: u16 = 601;
+const CID_PERIODIC_TEST: u16 = 602;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CentralStationError {
+    #[error("Account '{0}' must be 3 to 16 hex digits")]
+    InvalidAccount(String),
+
+    #[error("'{0}' must be 1 to 6 hex digits")]
+    InvalidPrefix(String),
+
+    #[error("Zone {0} is outside 0-999")]
+    InvalidZone(u16),
+
+    #[error("Partition {0} is outside 1-99")]
+    InvalidPartition(u8),
+
+    #[error("Event code {0} is outside 100-999")]
+    InvalidEventCode(u16),
+
+    #[error("Minimum decision must be Standard, Elevated or Critical")]
+    InvalidMinimum,
+
+    #[error("Receiver host is missing")]
+    MissingHost,
+
+    #[error("Home {0} has no central station configured")]
+    NotConfigured(String),
+
+    #[error("Receiver refused the message: {0}")]
+    Refused(String),
+
+    #[error("Unexpected receiver response: {0}")]
+    UnexpectedResponse(String),
+
+    #[error("Receiver did not answer within {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Receiver connection failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct CentralStationConfig {
+    pub timeout: std::time::Duration, // Connect, send and wait for the ACK
+    pub history_len: usize,           // Messages kept per home
+    pub reported_ttl: Duration,       // How long an unrestored incident is remembered
+    pub heartbeat_failure_warn: u32,  // Consecutive missed heartbeat ACKs before warning
+}
+
+impl Default for CentralStationConfig {
+    fn default() -> Self {
+        Self {
+            timeout: std::time::Duration::from_secs(10),
+            history_len: 500,
+            reported_ttl: Duration::hours(24),
+            heartbeat_failure_warn: 3,
+        }
+    }
+}
+
+/// Contact ID event qualifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Qualifier {
+    New,     // 1: new event or opening
+    Restore, // 3: restore or closing
+    Status,  // 6: previously reported condition still present
+}
+
+impl Qualifier {
+    pub fn digit(&self) -> char {
+        match self {
+            Qualifier::New => '1',
+            Qualifier::Restore => '3',
+            Qualifier::Status => '6',
+        }
+    }
+}
+
+/// One Contact ID event: qualifier, event code, partition (group) and zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactIdEvent {
+    pub qualifier: Qualifier,
+    pub code: u16,
+    pub partition: u8,
+    pub zone: u16,
+}
+
+impl ContactIdEvent {
+    /// `QEEE GG ZZZ`, the event part of a DC-09 `ADM-CID` data block
+    pub fn data(&self) -> String {
+        format!("{}{:03} {:02} {:03}", self.qualifier.digit(), self.code, self.partition, self.zone)
+    }
+
+    /// The DTMF message `ACCT 18 QEEE GG ZZZ S` (16 digits for a four-digit account), for receivers fed by a dialer
+    pub fn message(&self, account: &str) -> String {
+        let body = format!("{}18{}", account, self.data().replace(' ', ""));
+        format!("{}{}", body, contact_id_checksum(&body))
+    }
+}
+
+/// Contact ID check digit: the digit sum (0 counting as 10) plus the check digit is a multiple of 15
+pub fn contact_id_checksum(digits: &str) -> char {
+    let sum: u32 = digits.chars()
+        .filter_map(|c| c.to_digit(16))
+        .map(|d| if d == 0 { 10 } else { d })
+        .sum();
+    let check = 15 - sum % 15;
+    match check {
+        10 => '0',
+        d => std::char::from_digit(d, 16).unwrap_or('F').to_ascii_uppercase(),
+    }
+}
+
+/// CRC-16/ARC, the DC-09 frame checksum
+pub fn dc09_crc(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xA001 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+/// Wrap a DC-09 message (from the opening quote through the timestamp) in its
+/// frame: LF, CRC, `0` + length, the message, CR
+pub fn dc09_frame(message: &str) -> String {
+    format!("\n{:04X}0{:03X}{}\r", dc09_crc(message.as_bytes()), message.len(), message)
+}
+
+/// A DC-09 message: `"ID"seq[Rrcvr]Lline#acct[data]_HH:MM:SS,MM-DD-YYYY`, timestamp in UTC
+pub fn dc09_message(id: &str, sequence: u16, station: &CentralStation, data: &str, at: DateTime<Utc>) -> String {
+    let receiver = station.receiver.as_deref().map(|r| format!("R{}", r)).unwrap_or_default();
+    format!(
+        "\"{}\"{:04}{}L{}#{}[{}]{}",
+        id,
+        sequence,
+        receiver,
+        station.line,
+        station.account,
+        data,
+        at.format("_%H:%M:%S,%m-%d-%Y"),
+    )
+}
+
+/// Check a receiver's answer to message `sequence`
+pub fn parse_ack(response: &str, sequence: u16) -> Result<(), CentralStationError> {
+    let response = response.trim_matches(['\n', '\r']);
+    let message = response.find('"').map(|i| &response[i..]).ok_or_else(|| CentralStationError::UnexpectedResponse(response.to_string()))?;
+    let expected_seq = format!("{:04}", sequence);
+    match message.get(..5) {
+        Some("\"ACK\"") if message[5..].starts_with(&expected_seq) => Ok(()),
+        Some("\"NAK\"") | Some("\"DUH\"") => Err(CentralStationError::Refused(message.to_string())),
+        _ => Err(CentralStationError::UnexpectedResponse(message.to_string())),
+    }
+}
+
+/// Contact ID event codes sent for each alerting decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeverityCodes {
+    pub standard: u16, // 140 general alarm
+    pub elevated: u16, // 130 burglary
+    pub critical: u16, // 139 burglary verified
+}
+
+impl Default for SeverityCodes {
+    fn default() -> Self {
+        Self { standard: 140, elevated: 130, critical: 139 }
+    }
+}
+
+impl SeverityCodes {
+    pub fn code_for(&self, decision: &AlertDecision) -> Option<u16> {
+        match decision {
+            AlertDecision::Critical => Some(self.critical),
+            AlertDecision::Elevated => Some(self.elevated),
+            AlertDecision::Standard => Some(self.standard),
+            AlertDecision::Wait | AlertDecision::Ignore => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeartbeatKind {
+    #[default]
+    Null,       // DC-09 NULL message, link supervision only
+    TestReport, // Contact ID 602 periodic test report, for stations that expect one
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CentralStationRequest {
+    pub host: String,
+    pub port: u16,
+    pub account: String, // Monitoring account, hex digits as assigned by the station
+    #[serde(default)]
+    pub receiver: Option<String>, // DC-09 receiver number, if the station uses one
+    #[serde(default = "default_line")]
+    pub line: String, // DC-09 line prefix
+    #[serde(default)]
+    pub zones: BTreeMap<String, u16>, // Our camera id -> Contact ID zone
+    #[serde(default)]
+    pub default_zone: u16, // For unmapped cameras; 0 reports against the whole system
+    #[serde(default = "default_partition")]
+    pub partition: u8,
+    #[serde(default)]
+    pub codes: SeverityCodes,
+    #[serde(default = "default_min_decision")]
+    pub min_decision: AlertDecision,
+    #[serde(default = "default_heartbeat_secs")]
+    pub heartbeat_secs: u64, // 0 turns supervision off
+    #[serde(default)]
+    pub heartbeat: HeartbeatKind,
+    #[serde(default = "default_true")]
+    pub send_restores: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_line() -> String {
+    "0".to_string()
+}
+
+fn default_partition() -> u8 {
+    1
+}
+
+// Stations bill per signal, so Standard alerts stay out unless asked for
+fn default_min_decision() -> AlertDecision {
+    AlertDecision::Elevated
+}
+
+fn default_heartbeat_secs() -> u64 {
+    300
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CentralStation {
+    pub home_id: String,
+    pub host: String,
+    pub port: u16,
+    pub account: String,
+    pub receiver: Option<String>,
+    pub line: String,
+    pub zones: BTreeMap<String, u16>,
+    pub default_zone: u16,
+    pub partition: u8,
+    pub codes: SeverityCodes,
+    pub min_decision: AlertDecision,
+    pub heartbeat_secs: u64,
+    pub heartbeat: HeartbeatKind,
+    pub send_restores: bool,
+    pub enabled: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CentralStation {
+    pub fn zone_for(&self, camera: &str) -> u16 {
+        self.zones.get(camera).copied().unwrap_or(self.default_zone)
+    }
+}
+
+fn is_hex(value: &str, min: usize, max: usize) -> bool {
+    (min..=max).contains(&value.len()) && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn severity_rank(decision: &AlertDecision) -> u8 {
+    match decision {
+        AlertDecision::Critical => 3,
+        AlertDecision::Elevated => 2,
+        AlertDecision::Standard => 1,
+        AlertDecision::Wait | AlertDecision::Ignore => 0,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    Alarm,
+    Restore,
+    Heartbeat,
+    Test,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StationRecord {
+    pub home_id: String,
+    pub kind: MessageKind,
+    pub incident_id: Option<u64>,
+    pub sequence: u16,
+    pub event: Option<ContactIdEvent>,
+    pub message: String, // DC-09 message as sent, without framing
+    pub at: DateTime<Utc>,
+    pub acknowledged: bool,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StationStats {
+    pub sent: u64,
+    pub acknowledged: u64,
+    pub failed: u64,
+    pub last_ack: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub missed_heartbeats: u32, // Consecutive heartbeats without an ACK
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StationView {
+    pub station: CentralStation,
+    pub stats: StationStats,
+    pub supervised: bool, // Heartbeats on and the last one acknowledged
+}
+
+/// What an alarm is reported for
+#[derive(Debug, Clone)]
+pub struct AlarmContext {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub camera: String,
+    pub decision: AlertDecision,
+    pub at: DateTime<Utc>,
+}
+
+// An alarm reported for an incident, kept until its restore goes out
+#[derive(Debug, Clone, Copy)]
+struct Reported {
+    rank: u8,
+    event: ContactIdEvent,
+    at: DateTime<Utc>,
+}
+
+pub struct CentralStationExporter {
+    config: CentralStationConfig,
+    stations: DashMap<String, CentralStation>,
+    sequences: DashMap<String, u16>,
+    stats: DashMap<String, StationStats>,
+    history: DashMap<String, VecDeque<StationRecord>>,
+    reported: DashMap<(String, u64), Reported>,
+}
+
+impl Default for CentralStationExporter {
+    fn default() -> Self {
+        Self::new(CentralStationConfig::default())
+    }
+}
+
+impl CentralStationExporter {
+    pub fn new(config: CentralStationConfig) -> Self {
+        Self {
+            config,
+            stations: DashMap::new(),
+            sequences: DashMap::new(),
+            stats: DashMap::new(),
+            history: DashMap::new(),
+            reported: DashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &CentralStationConfig {
+        &self.config
+    }
+
+    /// Set or replace the home's central station
+    pub fn configure(&self, home_id: &str, request: CentralStationRequest, now: DateTime<Utc>) -> Result<CentralStation, CentralStationError> {
+        let host = request.host.trim().to_string();
+        if host.is_empty() {
+            return Err(CentralStationError::MissingHost);
+        }
+        let account = request.account.trim().to_ascii_uppercase();
+        if !is_hex(&account, 3, 16) {
+            return Err(CentralStationError::InvalidAccount(request.account));
+        }
+        let receiver = request.receiver.map(|r| r.trim().to_ascii_uppercase()).filter(|r| !r.is_empty());
+        for prefix in receiver.iter().chain(std::iter::once(&request.line)) {
+            if !is_hex(prefix, 1, 6) {
+                return Err(CentralStationError::InvalidPrefix(prefix.clone()));
+            }
+        }
+        if let Some(zone) = request.zones.values().chain(std::iter::once(&request.default_zone)).find(|z| **z > 999) {
+            return Err(CentralStationError::InvalidZone(*zone));
+        }
+        if !(1..=99).contains(&request.partition) {
+            return Err(CentralStationError::InvalidPartition(request.partition));
+        }
+        let codes = request.codes;
+        if let Some(code) = [codes.standard, codes.elevated, codes.critical].into_iter().find(|c| !(100..=999).contains(c)) {
+            return Err(CentralStationError::InvalidEventCode(code));
+        }
+        if severity_rank(&request.min_decision) == 0 {
+            return Err(CentralStationError::InvalidMinimum);
+        }
+
+        let station = CentralStation {
+            home_id: home_id.to_string(),
+            host,
+            port: request.port,
+            account,
+            receiver,
+            line: request.line.to_ascii_uppercase(),
+            zones: request.zones,
+            default_zone: request.default_zone,
+            partition: request.partition,
+            codes,
+            min_decision: request.min_decision,
+            heartbeat_secs: request.heartbeat_secs,
+            heartbeat: request.heartbeat,
+            send_restores: request.send_restores,
+            enabled: request.enabled,
+            updated_at: now,
+        };
+        self.stations.insert(home_id.to_string(), station.clone());
+        Ok(station)
+    }
+
+    pub fn remove(&self, home_id: &str) -> Result<CentralStation, CentralStationError> {
+        let (_, station) = self.stations.remove(home_id).ok_or_else(|| CentralStationError::NotConfigured(home_id.to_string()))?;
+        self.stats.remove(home_id);
+        self.reported.retain(|(home, _), _| home != home_id);
+        Ok(station)
+    }
+
+    pub fn get(&self, home_id: &str) -> Option<StationView> {
+        let station = self.stations.get(home_id)?.clone();
+        let stats = self.stats.get(home_id).map(|s| s.clone()).unwrap_or_default();
+        let supervised = station.heartbeat_secs > 0 && stats.last_heartbeat.is_some() && stats.missed_heartbeats == 0;
+        Some(StationView { station, stats, supervised })
+    }
+
+    /// Messages sent for the home, newest first
+    pub fn history(&self, home_id: &str, limit: usize) -> Vec<StationRecord> {
+        self.history.get(home_id)
+            .map(|h| h.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The alarm to report for `context`, if any: once per incident for each
+    /// higher severity it reaches, at or above the station's minimum
+    pub fn plan(&self, context: &AlarmContext) -> Option<(CentralStation, ContactIdEvent)> {
+        let rank = severity_rank(&context.decision);
+        self.reported.retain(|_, r| r.at + self.config.reported_ttl > context.at);
+        let station = self.stations.get(&context.home_id)?.clone();
+        if !station.enabled || rank == 0 || rank < severity_rank(&station.min_decision) {
+            return None;
+        }
+        let key = (context.home_id.clone(), context.incident_id);
+        if self.reported.get(&key).is_some_and(|r| r.rank >= rank) {
+            return None;
+        }
+        let event = ContactIdEvent {
+            qualifier: Qualifier::New,
+            code: station.codes.code_for(&context.decision)?,
+            partition: station.partition,
+            zone: station.zone_for(&context.camera),
+        };
+        self.reported.insert(key, Reported { rank, event, at: context.at });
+        Some((station, event))
+    }
+
+    /// The restore owed for an incident that just closed, if an alarm went out for it
+    pub fn plan_restore(&self, transition: &IncidentTransition) -> Option<(CentralStation, ContactIdEvent)> {
+        if transition.to.is_active() {
+            return None;
+        }
+        let (_, reported) = self.reported.remove(&(transition.home_id.clone(), transition.incident_id))?;
+        let station = self.stations.get(&transition.home_id)?.clone();
+        (station.enabled && station.send_restores).then_some((station, ContactIdEvent { qualifier: Qualifier::Restore, ..reported.event }))
+    }
+
+    /// Send a planned alarm or restore, recording the outcome
+    pub async fn report(&self, station: &CentralStation, event: ContactIdEvent, incident_id: u64, at: DateTime<Utc>) -> StationRecord {
+        let kind = match event.qualifier {
+            Qualifier::Restore => MessageKind::Restore,
+            _ => MessageKind::Alarm,
+        };
+        self.send(station, kind, Some(incident_id), Some(event), at).await
+    }
+
+    /// Stations whose next heartbeat is due
+    pub fn due_heartbeats(&self, now: DateTime<Utc>) -> Vec<CentralStation> {
+        self.stations.iter()
+            .filter(|s| s.enabled && s.heartbeat_secs > 0)
+            .filter(|s| {
+                let last = self.stats.get(&s.home_id).and_then(|st| st.last_heartbeat);
+                last.map_or(true, |last| now - last >= Duration::seconds(s.heartbeat_secs as i64))
+            })
+            .map(|s| s.clone())
+            .collect()
+    }
+
+    /// Send the station's supervision message
+    pub async fn heartbeat(&self, station: &CentralStation, now: DateTime<Utc>) -> StationRecord {
+        let event = match station.heartbeat {
+            HeartbeatKind::Null => None,
+            HeartbeatKind::TestReport => Some(ContactIdEvent { qualifier: Qualifier::New, code: CID_PERIODIC_TEST, partition: station.partition, zone: 0 }),
+        };
+        let record = self.send(station, MessageKind::Heartbeat, None, event, now).await;
+        let missed = {
+            let mut stats = self.stats.entry(station.home_id.clone()).or_default();
+            stats.last_heartbeat = Some(now);
+            stats.missed_heartbeats = if record.acknowledged { 0 } else { stats.missed_heartbeats + 1 };
+            stats.missed_heartbeats
+        };
+        if missed == self.config.heartbeat_failure_warn {
+            warn!("Central station for home {} missed {} heartbeats in a row; alarms may not be getting through", station.home_id, missed);
+        }
+        record
+    }
+
+    /// Send a Contact ID manual test report (601) now
+    pub async fn test(&self, home_id: &str, now: DateTime<Utc>) -> Result<StationRecord, CentralStationError> {
+        let station = self.stations.get(home_id).map(|s| s.clone()).ok_or_else(|| CentralStationError::NotConfigured(home_id.to_string()))?;
+        let event = ContactIdEvent { qualifier: Qualifier::New, code: CID_MANUAL_TEST, partition: station.partition, zone: 0 };
+        Ok(self.send(&station, MessageKind::Test, None, Some(event), now).await)
+    }
+
+    async fn send(&self, station: &CentralStation, kind: MessageKind, incident_id: Option<u64>, event: Option<ContactIdEvent>, at: DateTime<Utc>) -> StationRecord {
+        let sequence = self.next_sequence(&station.home_id);
+        let message = match &event {
+            Some(event) => dc09_message("ADM-CID", sequence, station, &format!("#{}|{}", station.account, event.data()), at),
+            None => dc09_message("NULL", sequence, station, "", at),
+        };
+        let started = Instant::now();
+        let outcome = self.exchange(station, &dc09_frame(&message)).await.and_then(|response| parse_ack(&response, sequence));
+        let record = StationRecord {
+            home_id: station.home_id.clone(),
+            kind,
+            incident_id,
+            sequence,
+            event,
+            message,
+            at,
+            acknowledged: outcome.is_ok(),
+            error: outcome.err().map(|e| e.to_string()),
+            latency_ms: started.elapsed().as_millis() as u64,
+        };
+        match (&record.error, kind) {
+            (None, MessageKind::Heartbeat) => {}
+            (None, _) => info!("Central station acknowledged {:?} {} for home {}", kind, record.sequence, station.home_id),
+            (Some(e), _) => warn!("Central station {:?} for home {} failed: {}", kind, station.home_id, e),
+        }
+        self.track(&record);
+        record
+    }
+
+    // Sequence numbers run 0001-9999 per home, then wrap
+    fn next_sequence(&self, home_id: &str) -> u16 {
+        let mut sequence = self.sequences.entry(home_id.to_string()).or_insert(0);
+        *sequence = *sequence % 9999 + 1;
+        *sequence
+    }
+
+    async fn exchange(&self, station: &CentralStation, frame: &str) -> Result<String, CentralStationError> {
+        let timeout = self.config.timeout;
+        let exchange = async {
+            let mut stream = TcpStream::connect((station.host.as_str(), station.port)).await?;
+            stream.write_all(frame.as_bytes()).await?;
+            let mut response = Vec::new();
+            let mut buf = [0u8; 256];
+            // Read up to the frame's closing CR
+            while !response.contains(&b'\r') {
+                let n = stream.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                response.extend_from_slice(&buf[..n]);
+            }
+            Ok::<_, CentralStationError>(String::from_utf8_lossy(&response).into_owned())
+        };
+        tokio::time::timeout(timeout, exchange).await.map_err(|_| CentralStationError::Timeout(timeout))?
+    }
+
+    fn track(&self, record: &StationRecord) {
+        {
+            let mut stats = self.stats.entry(record.home_id.clone()).or_default();
+            stats.sent += 1;
+            if record.acknowledged {
+                stats.acknowledged += 1;
+                stats.last_ack = Some(record.at);
+            } else {
+                stats.failed += 1;
+                stats.last_error = record.error.clone();
+            }
+        }
+        let mut history = self.history.entry(record.home_id.clone()).or_default();
+        history.push_back(record.clone());
+        while history.len() > self.config.history_len {
+            history.pop_front();
+        }
+    }
+}
+
+#[async_trait]
+impl IncidentLifecycleHook for CentralStationExporter {
+    async fn on_transition(&self, transition: &IncidentTransition) {
+        if let Some((station, event)) = self.plan_restore(transition) {
+            let at = DateTime::from_timestamp(transition.ts as i64, 0).unwrap_or_else(Utc::now);
+            self.report(&station, event, transition.incident_id, at).await;
+        }
+    }
+}
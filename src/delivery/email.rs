@@ -0,0 +1,518 @@
+//! Email delivery
+//!
+//! Alerts and morning summaries go out by email through an `EmailProvider`:
+//! an SMTP relay, or Amazon SES. Every message is MIME multipart with a plain
+//! text part and an HTML part; the morning summary's HTML lists the events
+//! that would have alerted, colour coded by severity, with their snapshots
+//! attached inline. Users register one address per home. Bounces and
+//! complaints (SES reports them through SNS) put an address on a suppression
+//! list, permanent bounces and complaints for good, and are passed to any
+//! registered feedback hooks.
+
+use super::router::{RouteDecision, RoutedNotification};
+use super::sms::DeliveryStats;
+use crate::i18n::{localizer, t};
+use crate::overnight::{DeliveryChannel, MorningSummary};
+use crate::thinking::AlertDecision;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+const SUMMARY_TEMPLATE: &str = include_str!("summary_email.html");
+
+#[derive(thiserror::Error, Debug)]
+pub enum EmailError {
+    #[error("No email provider is configured")]
+    NotConfigured,
+
+    #[error("'{0}' is not a valid email address")]
+    InvalidAddress(String),
+
+    #[error("No email address registered for {0}")]
+    NoAddress(String),
+
+    #[error("{0} is suppressed after a bounce or complaint")]
+    Suppressed(String),
+
+    #[error("Failed to build message: {0}")]
+    Build(String),
+
+    #[error("Failed to send: {0}")]
+    Transport(String),
+}
+
+/// A message ready to send: text and HTML alternatives, plus inline images
+/// the HTML refers to as `cid:<content_id>`
+#[derive(Debug, Clone, Default)]
+pub struct EmailMessage {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+    pub inline: Vec<InlineImage>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InlineImage {
+    pub content_id: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl EmailMessage {
+    pub fn to_mime(&self, from: &Mailbox, to: &Mailbox) -> Result<Message, EmailError> {
+        let text = SinglePart::plain(self.text.clone());
+        let html = SinglePart::html(self.html.clone());
+        let body = if self.inline.is_empty() {
+            MultiPart::alternative().singlepart(text).singlepart(html)
+        } else {
+            let mut related = MultiPart::related().singlepart(html);
+            for image in &self.inline {
+                let content_type = ContentType::parse(&image.content_type).map_err(|e| EmailError::Build(e.to_string()))?;
+                related = related.singlepart(Attachment::new_inline(image.content_id.clone()).body(image.data.clone(), content_type));
+            }
+            MultiPart::alternative().singlepart(text).multipart(related)
+        };
+        Message::builder()
+            .from(from.clone())
+            .to(to.clone())
+            .subject(self.subject.clone())
+            .multipart(body)
+            .map_err(|e| EmailError::Build(e.to_string()))
+    }
+}
+
+#[async_trait]
+pub trait EmailProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Send a built message; returns the provider's message id
+    async fn send(&self, message: Message) -> Result<String, EmailError>;
+}
+
+/// Any SMTP relay, over STARTTLS
+pub struct SmtpProvider {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpProvider {
+    pub fn new(host: &str, port: u16, username: Option<String>, password: Option<String>) -> Result<Self, EmailError> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| EmailError::Transport(e.to_string()))?
+            .port(port);
+        if let (Some(username), Some(password)) = (username, password) {
+            builder = builder.credentials(lettre::transport::smtp::authentication::Credentials::new(username, password));
+        }
+        Ok(Self { transport: builder.build() })
+    }
+}
+
+#[async_trait]
+impl EmailProvider for SmtpProvider {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    async fn send(&self, message: Message) -> Result<String, EmailError> {
+        let response = self.transport.send(message).await.map_err(|e| EmailError::Transport(e.to_string()))?;
+        Ok(response.message().collect::<Vec<_>>().join(" "))
+    }
+}
+
+/// Amazon SES, sending the MIME message as raw content
+pub struct SesProvider {
+    client: aws_sdk_sesv2::Client,
+    configuration_set: Option<String>, // Where SES publishes bounce and complaint events
+}
+
+impl SesProvider {
+    pub fn new(client: aws_sdk_sesv2::Client, configuration_set: Option<String>) -> Self {
+        Self { client, configuration_set }
+    }
+}
+
+#[async_trait]
+impl EmailProvider for SesProvider {
+    fn name(&self) -> &'static str {
+        "ses"
+    }
+
+    async fn send(&self, message: Message) -> Result<String, EmailError> {
+        use aws_sdk_sesv2::primitives::Blob;
+        use aws_sdk_sesv2::types::{EmailContent, RawMessage};
+        let raw = RawMessage::builder()
+            .data(Blob::new(message.formatted()))
+            .build()
+            .map_err(|e| EmailError::Build(e.to_string()))?;
+        let output = self.client.send_email()
+            .content(EmailContent::builder().raw(raw).build())
+            .set_configuration_set_name(self.configuration_set.clone())
+            .send()
+            .await
+            .map_err(|e| EmailError::Transport(e.to_string()))?;
+        Ok(output.message_id().unwrap_or_default().to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EmailFeedback {
+    Bounce { address: String, permanent: bool },
+    Complaint { address: String },
+}
+
+impl EmailFeedback {
+    pub fn address(&self) -> &str {
+        match self {
+            Self::Bounce { address, .. } | Self::Complaint { address } => address,
+        }
+    }
+
+    // Transient bounces are noted but the address keeps receiving mail
+    fn suppresses(&self) -> bool {
+        !matches!(self, Self::Bounce { permanent: false, .. })
+    }
+}
+
+/// Called for every bounce or complaint, e.g. to flag the address in the app
+pub trait EmailFeedbackHook: Send + Sync {
+    fn on_feedback(&self, feedback: &EmailFeedback);
+}
+
+/// What an SNS post to the feedback endpoint asks of us
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnsNotice {
+    Feedback(Vec<EmailFeedback>),
+    SubscriptionConfirmation { subscribe_url: String },
+    Ignored,
+}
+
+/// Parse an SNS delivery of SES bounce and complaint notifications
+pub fn parse_sns(body: &[u8]) -> Option<SnsNotice> {
+    let envelope: serde_json::Value = serde_json::from_slice(body).ok()?;
+    match envelope.get("Type")?.as_str()? {
+        "SubscriptionConfirmation" => Some(SnsNotice::SubscriptionConfirmation {
+            subscribe_url: envelope.get("SubscribeURL")?.as_str()?.to_string(),
+        }),
+        "Notification" => {
+            let message: serde_json::Value = serde_json::from_str(envelope.get("Message")?.as_str()?).ok()?;
+            // Notifications use notificationType, configuration-set events use eventType
+            let kind = message.get("notificationType").or_else(|| message.get("eventType"))?.as_str()?;
+            let recipients = |section: &str, list: &str| -> Vec<String> {
+                message.get(section).and_then(|s| s.get(list)).and_then(|r| r.as_array())
+                    .map(|r| r.iter().filter_map(|r| r.get("emailAddress")?.as_str().map(str::to_string)).collect())
+                    .unwrap_or_default()
+            };
+            let feedback = match kind {
+                "Bounce" => {
+                    let permanent = message.get("bounce").and_then(|b| b.get("bounceType")).and_then(|t| t.as_str()) == Some("Permanent");
+                    recipients("bounce", "bouncedRecipients").into_iter().map(|address| EmailFeedback::Bounce { address, permanent }).collect()
+                }
+                "Complaint" => recipients("complaint", "complainedRecipients").into_iter().map(|address| EmailFeedback::Complaint { address }).collect(),
+                _ => return Some(SnsNotice::Ignored),
+            };
+            Some(SnsNotice::Feedback(feedback))
+        }
+        _ => Some(SnsNotice::Ignored),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailContact {
+    pub user_id: String,
+    pub address: String,
+    pub suppressed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Suppression {
+    pub address: String,
+    pub feedback: EmailFeedback,
+    pub at: DateTime<Utc>,
+}
+
+fn severity_color(level: &AlertDecision) -> &'static str {
+    match level {
+        AlertDecision::Critical => "#c62828",
+        AlertDecision::Elevated => "#ef6c00",
+        AlertDecision::Standard => "#f9a825",
+        AlertDecision::Wait | AlertDecision::Ignore => "#607d8b",
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn image_type(data: &[u8]) -> &'static str {
+    match data {
+        [0x89, b'P', b'N', b'G', ..] => "image/png",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        [b'G', b'I', b'F', ..] => "image/gif",
+        _ => "image/jpeg",
+    }
+}
+
+#[derive(Serialize)]
+struct HighlightView {
+    color: &'static str,
+    severity: String,
+    time: String,
+    summary: String,
+    cid: Option<String>,
+}
+
+/// The morning summary as an email, in the home's locale. `thumbnails` holds
+/// snapshot bytes by URL; highlights whose snapshot is there get it inline.
+pub fn render_summary(summary: &MorningSummary, locale: &str, thumbnails: &HashMap<String, Vec<u8>>) -> Result<EmailMessage, EmailError> {
+    let title = t(locale, "summary-title", &[("date", summary.summary_date.to_string())]);
+    let mut inline = Vec::new();
+    let highlights: Vec<HighlightView> = summary.highlights.iter().enumerate().map(|(i, h)| {
+        let cid = h.thumbnail_url.as_ref().and_then(|url| thumbnails.get(url)).map(|data| {
+            let content_id = format!("highlight-{}@novin", i);
+            inline.push(InlineImage { content_id: content_id.clone(), content_type: image_type(data).to_string(), data: data.clone() });
+            content_id
+        });
+        HighlightView {
+            color: severity_color(&h.level),
+            severity: super::NotificationSeverity::from_decision(&h.level)
+                .map_or_else(|| format!("{:?}", h.level), |s| localizer().severity(locale, s)),
+            time: h.timestamp.format("%H:%M UTC").to_string(),
+            summary: h.summary.clone(),
+            cid,
+        }
+    }).collect();
+    let accent = summary.highlights.first().map_or("#2e7d32", |h| severity_color(&h.level));
+
+    let mut env = minijinja::Environment::new();
+    env.add_template("summary.html", SUMMARY_TEMPLATE).map_err(|e| EmailError::Build(e.to_string()))?;
+    let html = env.get_template("summary.html")
+        .and_then(|tpl| tpl.render(minijinja::context! {
+            locale,
+            title => &title,
+            narrative => &summary.narrative,
+            accent,
+            highlights_heading => t(locale, "summary-email-highlights", &[]),
+            highlights => &highlights,
+            footer => t(locale, "summary-email-footer", &[]),
+        }))
+        .map_err(|e| EmailError::Build(e.to_string()))?;
+
+    let mut text = format!("{}\n\n{}\n", title, summary.narrative);
+    for h in &highlights {
+        text.push_str(&format!("\n- [{}] {} {}", h.severity, h.time, h.summary));
+    }
+    Ok(EmailMessage { subject: title, text, html, inline })
+}
+
+/// A routed alert as an email
+pub fn render_alert(title: &str, body: &str) -> EmailMessage {
+    let html = format!(
+        "<!DOCTYPE html><html><body style=\"font-family:Helvetica,Arial,sans-serif;\"><h2>{}</h2><p>{}</p></body></html>",
+        escape_html(title),
+        escape_html(body).replace('\n', "<br>"),
+    );
+    EmailMessage { subject: title.to_string(), text: body.to_string(), html, inline: Vec::new() }
+}
+
+#[derive(Default)]
+pub struct EmailDispatcher {
+    provider: Option<Arc<dyn EmailProvider>>,
+    from: Option<Mailbox>,
+    contacts: DashMap<(String, String), EmailContact>, // Per home and user
+    suppressed: DashMap<String, Suppression>,          // Per lowercased address
+    hooks: RwLock<Vec<Arc<dyn EmailFeedbackHook>>>,
+    stats: DashMap<String, DeliveryStats>,             // Per home
+    feedback_token: Option<String>,                    // Required on the bounce and complaint endpoint
+}
+
+impl EmailDispatcher {
+    pub fn new(provider: Arc<dyn EmailProvider>, from: &str) -> Result<Self, EmailError> {
+        let from = from.parse::<Mailbox>().map_err(|_| EmailError::InvalidAddress(from.to_string()))?;
+        Ok(Self { provider: Some(provider), from: Some(from), ..Self::default() })
+    }
+
+    /// SES when NOVIN_EMAIL_PROVIDER is "ses" (AWS_REGION, AWS_ACCESS_KEY_ID,
+    /// AWS_SECRET_ACCESS_KEY and optionally AWS_SESSION_TOKEN), else SMTP from
+    /// NOVIN_SMTP_HOST, NOVIN_SMTP_PORT, NOVIN_SMTP_USERNAME and
+    /// NOVIN_SMTP_PASSWORD. NOVIN_EMAIL_FROM is required either way.
+    pub fn from_env() -> Option<Self> {
+        use aws_sdk_sesv2::config::{BehaviorVersion, Credentials, Region};
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let from = var("NOVIN_EMAIL_FROM")?;
+        let provider: Arc<dyn EmailProvider> = if var("NOVIN_EMAIL_PROVIDER").is_some_and(|p| p.eq_ignore_ascii_case("ses")) {
+            let credentials = Credentials::new(var("AWS_ACCESS_KEY_ID")?, var("AWS_SECRET_ACCESS_KEY")?, var("AWS_SESSION_TOKEN"), None, "environment");
+            let config = aws_sdk_sesv2::Config::builder()
+                .behavior_version(BehaviorVersion::latest())
+                .region(Region::new(var("AWS_REGION")?))
+                .credentials_provider(credentials)
+                .build();
+            Arc::new(SesProvider::new(aws_sdk_sesv2::Client::from_conf(config), var("NOVIN_SES_CONFIGURATION_SET")))
+        } else {
+            let port = var("NOVIN_SMTP_PORT").and_then(|p| p.parse().ok()).unwrap_or(587);
+            match SmtpProvider::new(&var("NOVIN_SMTP_HOST")?, port, var("NOVIN_SMTP_USERNAME"), var("NOVIN_SMTP_PASSWORD")) {
+                Ok(smtp) => Arc::new(smtp),
+                Err(e) => {
+                    warn!("Email disabled: {}", e);
+                    return None;
+                }
+            }
+        };
+        let dispatcher = Self::new(provider, &from).map_err(|e| warn!("Email disabled: {}", e)).ok()?;
+        Some(match var("NOVIN_EMAIL_FEEDBACK_TOKEN") {
+            Some(token) => dispatcher.with_feedback_token(token),
+            None => dispatcher,
+        })
+    }
+
+    /// Accept bounce and complaint notifications that carry this token
+    pub fn with_feedback_token(mut self, token: String) -> Self {
+        self.feedback_token = Some(token);
+        self
+    }
+
+    pub fn accepts_feedback_token(&self, token: &str) -> bool {
+        self.feedback_token.as_ref().is_some_and(|expected| {
+            expected.len() == token.len() && expected.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+        })
+    }
+
+    pub fn provider(&self) -> Option<&Arc<dyn EmailProvider>> {
+        self.provider.as_ref()
+    }
+
+    pub fn add_feedback_hook(&self, hook: Arc<dyn EmailFeedbackHook>) {
+        if let Ok(mut hooks) = self.hooks.write() {
+            hooks.push(hook);
+        }
+    }
+
+    pub fn set_address(&self, home_id: &str, user_id: &str, address: &str) -> Result<EmailContact, EmailError> {
+        let mailbox = address.trim().parse::<Mailbox>().map_err(|_| EmailError::InvalidAddress(address.to_string()))?;
+        let address = mailbox.email.to_string();
+        let contact = EmailContact {
+            user_id: user_id.to_string(),
+            suppressed: self.suppressed.contains_key(&address.to_lowercase()),
+            address,
+            updated_at: Utc::now(),
+        };
+        self.contacts.insert((home_id.to_string(), user_id.to_string()), contact.clone());
+        Ok(contact)
+    }
+
+    pub fn remove_address(&self, home_id: &str, user_id: &str) -> bool {
+        self.contacts.remove(&(home_id.to_string(), user_id.to_string())).is_some()
+    }
+
+    pub fn contact(&self, home_id: &str, user_id: &str) -> Option<EmailContact> {
+        self.contacts.get(&(home_id.to_string(), user_id.to_string())).map(|c| EmailContact {
+            suppressed: self.suppressed.contains_key(&c.address.to_lowercase()),
+            ..c.clone()
+        })
+    }
+
+    pub fn contacts(&self, home_id: &str) -> Vec<EmailContact> {
+        let mut contacts: Vec<EmailContact> = self.contacts.iter()
+            .filter(|c| c.key().0 == home_id)
+            .filter_map(|c| self.contact(home_id, &c.key().1))
+            .collect();
+        contacts.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        contacts
+    }
+
+    pub fn stats(&self, home_id: &str) -> DeliveryStats {
+        self.stats.get(home_id).map(|s| s.clone()).unwrap_or_default()
+    }
+
+    pub fn suppressions(&self) -> Vec<Suppression> {
+        self.suppressed.iter().map(|s| s.value().clone()).collect()
+    }
+
+    /// Let a suppressed address receive mail again, e.g. after the user fixed their mailbox
+    pub fn unsuppress(&self, address: &str) -> bool {
+        self.suppressed.remove(&address.to_lowercase()).is_some()
+    }
+
+    fn count(&self, home_id: &str, apply: impl FnOnce(&mut DeliveryStats)) {
+        apply(&mut self.stats.entry(home_id.to_string()).or_default());
+    }
+
+    /// Send a message to a user's registered address; returns the provider's message id
+    pub async fn deliver(&self, home_id: &str, user_id: &str, message: &EmailMessage) -> Result<String, EmailError> {
+        let (Some(provider), Some(from)) = (&self.provider, &self.from) else {
+            return Err(EmailError::NotConfigured);
+        };
+        let Some(contact) = self.contact(home_id, user_id) else {
+            self.count(home_id, |s| s.skipped_no_contact += 1);
+            return Err(EmailError::NoAddress(user_id.to_string()));
+        };
+        if contact.suppressed {
+            self.count(home_id, |s| s.skipped_opted_out += 1);
+            return Err(EmailError::Suppressed(contact.address));
+        }
+        let to = contact.address.parse::<Mailbox>().map_err(|_| EmailError::InvalidAddress(contact.address.clone()))?;
+        match provider.send(message.to_mime(from, &to)?).await {
+            Ok(id) => {
+                self.count(home_id, |s| s.sent += 1);
+                Ok(id)
+            }
+            Err(e) => {
+                self.count(home_id, |s| s.failed += 1);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send every routed email marked for delivery, in the background
+    pub fn spawn_deliveries(self: &Arc<Self>, home_id: &str, deliveries: &[RoutedNotification]) {
+        let due: Vec<(String, EmailMessage)> = deliveries.iter()
+            .filter(|d| d.channel == DeliveryChannel::Email && d.decision == RouteDecision::Deliver)
+            .filter_map(|d| Some((d.user_id.clone()?, render_alert(&d.title, &d.body))))
+            .collect();
+        if due.is_empty() || self.provider.is_none() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("{} email(s) for {} not sent: no async runtime", due.len(), home_id);
+            return;
+        };
+        let dispatcher = self.clone();
+        let home_id = home_id.to_string();
+        runtime.spawn(async move {
+            for (user_id, message) in due {
+                if let Err(e) = dispatcher.deliver(&home_id, &user_id, &message).await {
+                    warn!("Email to {} in {} not sent: {}", user_id, home_id, e);
+                }
+            }
+        });
+    }
+
+    /// Record a bounce or complaint and tell the hooks; counted against every
+    /// home the address is registered in
+    pub fn handle_feedback(&self, feedback: EmailFeedback) {
+        let address = feedback.address().to_lowercase();
+        let homes: Vec<String> = self.contacts.iter()
+            .filter(|c| c.address.eq_ignore_ascii_case(&address))
+            .map(|c| c.key().0.clone())
+            .collect();
+        for home_id in homes {
+            self.count(&home_id, |s| s.undelivered += 1);
+        }
+        if feedback.suppresses() {
+            info!("Suppressing email to {}: {:?}", address, feedback);
+            self.suppressed.insert(address.clone(), Suppression { address, feedback: feedback.clone(), at: Utc::now() });
+        }
+        if let Ok(hooks) = self.hooks.read() {
+            for hook in hooks.iter() {
+                hook.on_feedback(&feedback);
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Outbound Delivery Channels
+//!
+//! Channels that push alerts and morning summaries out of the system to
+//! user-configured destinations.
+
+pub mod webhook;
+pub mod siem;
+pub mod router;
+pub mod templates;
+pub mod sms;
+pub mod email;
+pub mod admin;
+pub mod vms;
+pub mod central_station;
+
+pub use webhook::{
+    WebhookDispatcher, WebhookConfig, WebhookEndpoint, WebhookEventType,
+    WebhookPayload, WebhookDeliveryRecord, WebhookUrlError, DeadLetter, sign_payload, verify_signature,
+    validate_webhook_url, is_public_address,
+};
+
+pub use siem::{
+    SiemExporter, SiemConfig, SiemEvent, SiemEventKind, SiemFieldMapping, SiemFormat,
+    SiemTransport, SiemError, SiemMetrics, format_cef, format_syslog,
+};
+
+pub use router::{
+    NotificationRouter, NotificationPreferences, NotificationSeverity, Notification,
+    QuietHours, RouteDecision, RoutedNotification, RouteOutcome, CooldownConfig, CooldownVerdict,
+    AlertPhase, AlertRef, LogicalAlert,
+};
+
+pub use templates::{NotificationTemplate, NotificationVars, TemplateError, TemplateStore};
+
+pub use sms::{
+    DeliveryStats, HttpSmsProvider, SmsCallback, SmsConfig, SmsContact, SmsDispatcher, SmsError,
+    SmsProvider, SmsStatus, TwilioProvider, normalize_number,
+};
+
+pub use email::{
+    EmailContact, EmailDispatcher, EmailError, EmailFeedback, EmailFeedbackHook, EmailMessage, EmailProvider,
+    InlineImage, SesProvider, SmtpProvider, SnsNotice, parse_sns, render_summary,
+};
+
+pub use admin::AdminChannel;
+
+pub use vms::{
+    BookmarkContext, BookmarkRecord, IntegrationStats, IntegrationView, RequestTemplate, VmsAuth, VmsBookmarker,
+    VmsConfig, VmsError, VmsIntegration, VmsIntegrationRequest, VmsProvider, VmsRequest, build_request,
+};
+
+pub use central_station::{
+    AlarmContext, CentralStation, CentralStationConfig, CentralStationError, CentralStationExporter,
+    CentralStationRequest, ContactIdEvent, HeartbeatKind, MessageKind, Qualifier, SeverityCodes, StationRecord,
+    StationStats, StationView, contact_id_checksum, dc09_crc, dc09_frame, dc09_message, parse_ack,
+};
@@ -0,0 +1,633 @@
+//! Notification routing
+//!
+//! Every outbound notification passes through the router before a delivery
+//! channel sends it. Preferences are stored per home (the default) and per
+//! user (overrides), and decide per channel whether to send now, hold for the
+//! user's digest, or drop. Before that, repeats for the same incident or zone
+//! are held back for a cooldown window unless the threat has grown. SMS and
+//! email marked for delivery are handed to their dispatchers when attached.
+//!
+//! Homes can opt into two-phase alerts: a bare heads-up pushed the moment an
+//! event's provisional probability crosses the threshold, then an update once
+//! VPS analysis and the narrative are in. Both carry the same alert id, so a
+//! push or websocket client replaces the heads-up instead of showing two
+//! alerts. Heads-ups go to push and websocket only and are never held for
+//! the digest; the update is not subject to cooldown, since it edits an alert
+//! the user already has.
+
+use super::email::{render_summary, EmailDispatcher};
+use super::sms::SmsDispatcher;
+use super::templates::TemplateStore;
+use super::webhook::{WebhookDeliveryRecord, WebhookDispatcher};
+use crate::api::models::AlertInfo;
+use crate::clock::{system_clock, Clock};
+use crate::i18n::{localizer, t};
+use crate::overnight::{DeliveryChannel, MorningSummary};
+use crate::thinking::AlertDecision;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSeverity {
+    Info, // Summaries, status changes
+    Standard,
+    Elevated,
+    Critical,
+}
+
+impl NotificationSeverity {
+    /// None for decisions that never notify (Ignore, Wait)
+    pub fn from_decision(decision: &AlertDecision) -> Option<Self> {
+        match decision {
+            AlertDecision::Ignore | AlertDecision::Wait => None,
+            AlertDecision::Standard => Some(Self::Standard),
+            AlertDecision::Elevated => Some(Self::Elevated),
+            AlertDecision::Critical => Some(Self::Critical),
+        }
+    }
+
+    fn from_threat_level(level: &str) -> Self {
+        match level.to_ascii_lowercase().as_str() {
+            "critical" => Self::Critical,
+            "elevated" | "high" => Self::Elevated,
+            "standard" | "medium" | "low" => Self::Standard,
+            _ => Self::Info,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime, // May be earlier than start to span midnight
+    pub timezone: String,
+    #[serde(default = "default_true")]
+    pub allow_critical: bool, // Critical alerts still go out during quiet hours
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl QuietHours {
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let tz = self.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+        let local = at.with_timezone(&tz).time();
+        if self.start <= self.end {
+            local >= self.start && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    pub quiet_hours: Option<QuietHours>,
+    pub min_severity: HashMap<DeliveryChannel, NotificationSeverity>, // Channels not listed use default_min_severity
+    pub default_min_severity: NotificationSeverity,
+    pub digest_only: bool, // Hold everything below critical for the digest
+    pub muted_channels: Vec<DeliveryChannel>,
+    pub heads_up: bool,    // Two-phase alerts: push a heads-up before analysis finishes
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            quiet_hours: None,
+            min_severity: HashMap::new(),
+            default_min_severity: NotificationSeverity::Info,
+            digest_only: false,
+            muted_channels: Vec::new(),
+            heads_up: false,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    fn min_for(&self, channel: &DeliveryChannel) -> NotificationSeverity {
+        self.min_severity.get(channel).copied().unwrap_or(self.default_min_severity)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub home_id: String,
+    pub severity: NotificationSeverity,
+    pub title: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub incident_id: Option<u64>,
+    #[serde(default)]
+    pub zone: Option<String>, // Camera or zone; catches repeats across re-tracked incidents
+    #[serde(default)]
+    pub probability: Option<f64>,
+    #[serde(default)]
+    pub entity: Option<String>, // Tracked person or entity the notification is about
+    #[serde(default)]
+    pub entity_status: Option<String>, // e.g. "Person has now been at the door for 4 minutes"
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+    #[serde(default)]
+    pub alert: Option<AlertRef>, // Set on both phases of a two-phase alert
+}
+
+impl Notification {
+    fn phase(&self) -> Option<AlertPhase> {
+        self.alert.map(|a| a.phase)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertPhase {
+    HeadsUp, // Sent on the provisional probability alone
+    Update,  // The enriched alert, replacing the heads-up
+}
+
+/// Ties the phases of one logical alert together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertRef {
+    pub alert_id: Uuid,
+    pub phase: AlertPhase,
+}
+
+/// A two-phase alert as the user sees it, from heads-up to its update
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalAlert {
+    pub alert_id: Uuid,
+    pub home_id: String,
+    pub incident_id: Option<u64>, // Known from the update if the heads-up opened the incident
+    pub severity: NotificationSeverity, // Latest phase's
+    pub heads_up_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// Channels light enough for a heads-up; everything else waits for the update
+const HEADS_UP_CHANNELS: [DeliveryChannel; 2] = [DeliveryChannel::Push, DeliveryChannel::WebSocket];
+
+#[derive(Debug, Clone)]
+pub struct CooldownConfig {
+    pub incident_window: Duration,
+    pub zone_window: Duration,
+    pub escalation_delta: f64, // Probability rise that breaks through a cooldown
+}
+
+impl Default for CooldownConfig {
+    fn default() -> Self {
+        Self {
+            incident_window: Duration::minutes(5),
+            zone_window: Duration::minutes(2),
+            escalation_delta: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownVerdict {
+    Send,
+    Escalate, // Inside a cooldown, but severity or probability rose materially
+    Suppress,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum CooldownKey {
+    Incident(String, u64),
+    Zone(String, String),
+}
+
+#[derive(Debug, Clone)]
+struct LastSent {
+    at: DateTime<Utc>,
+    severity: NotificationSeverity,
+    probability: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", content = "reason", rename_all = "snake_case")]
+pub enum RouteDecision {
+    Deliver,
+    Digest(String),
+    Drop(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutedNotification {
+    pub user_id: Option<String>, // None for home-level destinations (webhooks)
+    pub channel: DeliveryChannel,
+    pub decision: RouteDecision,
+    pub title: String, // Rendered for this channel
+    pub body: String,
+    pub alert: Option<AlertRef>, // e.g. the push collapse id, so an update replaces its heads-up
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteOutcome {
+    pub cooldown: CooldownVerdict,
+    pub deliveries: Vec<RoutedNotification>,
+}
+
+pub struct NotificationRouter {
+    preferences: DashMap<(String, Option<String>), NotificationPreferences>,
+    digests: DashMap<(String, Option<String>), Vec<Notification>>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+    cooldown: CooldownConfig,
+    last_sent: DashMap<CooldownKey, LastSent>,
+    suppressed: DashMap<(String, u64), u32>, // Per incident
+    acknowledged: DashMap<(String, u64), NotificationSeverity>, // Severity the user has seen, per incident
+    templates: Arc<TemplateStore>,
+    sms: Option<Arc<SmsDispatcher>>,
+    email: Option<Arc<EmailDispatcher>>,
+    alerts: DashMap<Uuid, LogicalAlert>, // Two-phase alerts whose heads-up was delivered
+    clock: Arc<dyn Clock>, // "Now" for summaries and webhook checks that carry no time of their own
+}
+
+impl Default for NotificationRouter {
+    fn default() -> Self {
+        Self {
+            preferences: DashMap::new(),
+            digests: DashMap::new(),
+            webhooks: None,
+            cooldown: CooldownConfig::default(),
+            last_sent: DashMap::new(),
+            suppressed: DashMap::new(),
+            acknowledged: DashMap::new(),
+            templates: Arc::default(),
+            sms: None,
+            email: None,
+            alerts: DashMap::new(),
+            clock: system_clock(),
+        }
+    }
+}
+
+impl NotificationRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time summary notifications and webhook route checks by `clock`, so quiet
+    /// hours can be exercised against a simulated one
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_webhooks(mut self, dispatcher: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(dispatcher);
+        self
+    }
+
+    pub fn webhooks(&self) -> Option<&Arc<WebhookDispatcher>> {
+        self.webhooks.as_ref()
+    }
+
+    pub fn with_cooldown(mut self, config: CooldownConfig) -> Self {
+        self.cooldown = config;
+        self
+    }
+
+    /// Share the template store, e.g. with the API that edits it
+    pub fn with_templates(mut self, templates: Arc<TemplateStore>) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    pub fn templates(&self) -> &Arc<TemplateStore> {
+        &self.templates
+    }
+
+    pub fn with_sms(mut self, sms: Arc<SmsDispatcher>) -> Self {
+        self.sms = Some(sms);
+        self
+    }
+
+    pub fn sms(&self) -> Option<&Arc<SmsDispatcher>> {
+        self.sms.as_ref()
+    }
+
+    pub fn with_email(mut self, email: Arc<EmailDispatcher>) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    pub fn email(&self) -> Option<&Arc<EmailDispatcher>> {
+        self.email.as_ref()
+    }
+
+    /// Set the home default (user_id None) or a user's override
+    pub fn set_preferences(&self, home_id: &str, user_id: Option<&str>, prefs: NotificationPreferences) {
+        self.preferences.insert((home_id.to_string(), user_id.map(str::to_string)), prefs);
+    }
+
+    /// Effective preferences: the user's own, else the home default, else defaults
+    pub fn preferences(&self, home_id: &str, user_id: Option<&str>) -> NotificationPreferences {
+        user_id
+            .and_then(|u| self.preferences.get(&(home_id.to_string(), Some(u.to_string()))))
+            .or_else(|| self.preferences.get(&(home_id.to_string(), None)))
+            .map(|p| p.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn clear_preferences(&self, home_id: &str, user_id: Option<&str>) -> bool {
+        self.preferences.remove(&(home_id.to_string(), user_id.map(str::to_string))).is_some()
+    }
+
+    /// Whether anyone in the home has turned on two-phase alerts
+    pub fn heads_up_enabled(&self, home_id: &str) -> bool {
+        self.preferences.iter().any(|p| p.key().0 == home_id && p.heads_up)
+    }
+
+    /// A two-phase alert by id, while it is remembered
+    pub fn alert(&self, alert_id: Uuid) -> Option<LogicalAlert> {
+        self.alerts.get(&alert_id).map(|a| a.clone())
+    }
+
+    /// Decide what to do with a notification for one recipient and channel
+    pub fn decide(&self, notification: &Notification, user_id: Option<&str>, channel: &DeliveryChannel, at: DateTime<Utc>) -> RouteDecision {
+        let prefs = self.preferences(&notification.home_id, user_id);
+        let critical = notification.severity == NotificationSeverity::Critical;
+
+        if prefs.muted_channels.contains(channel) {
+            return RouteDecision::Drop(format!("{:?} is muted", channel));
+        }
+        if notification.phase() == Some(AlertPhase::HeadsUp) && !prefs.heads_up {
+            return RouteDecision::Drop("heads-up alerts are off".to_string());
+        }
+        // An update, even a stand-down, edits an alert the user already has
+        if notification.severity < prefs.min_for(channel) && notification.phase() != Some(AlertPhase::Update) {
+            return RouteDecision::Drop(format!("below {:?} minimum for {:?}", prefs.min_for(channel), channel));
+        }
+        if let Some(quiet) = &prefs.quiet_hours {
+            if quiet.contains(at) && !(critical && quiet.allow_critical) {
+                return RouteDecision::Digest("quiet hours".to_string());
+            }
+        }
+        if prefs.digest_only && !critical {
+            return RouteDecision::Digest("digest-only mode".to_string());
+        }
+        RouteDecision::Deliver
+    }
+
+    /// Whether a notification is a repeat inside its incident or zone cooldown; a
+    /// sent (or escalated) notification restarts both windows
+    pub fn check_cooldown(&self, notification: &Notification, at: DateTime<Utc>) -> CooldownVerdict {
+        if let Some(id) = notification.incident_id {
+            let key = (notification.home_id.clone(), id);
+            if self.acknowledged.get(&key).is_some_and(|seen| notification.severity <= *seen) {
+                *self.suppressed.entry(key).or_default() += 1;
+                return CooldownVerdict::Suppress;
+            }
+        }
+        let keys: Vec<(CooldownKey, Duration)> = [
+            notification.incident_id.map(|id| (CooldownKey::Incident(notification.home_id.clone(), id), self.cooldown.incident_window)),
+            notification.zone.clone().map(|z| (CooldownKey::Zone(notification.home_id.clone(), z), self.cooldown.zone_window)),
+        ].into_iter().flatten().collect();
+
+        let mut verdict = CooldownVerdict::Send;
+        for (key, window) in &keys {
+            let Some(last) = self.last_sent.get(key) else { continue };
+            if at - last.at >= *window {
+                continue;
+            }
+            let risen = match (notification.probability, last.probability) {
+                (Some(now), Some(before)) => now - before >= self.cooldown.escalation_delta,
+                _ => false,
+            };
+            if notification.severity > last.severity || risen {
+                verdict = CooldownVerdict::Escalate;
+            } else {
+                verdict = CooldownVerdict::Suppress;
+                break;
+            }
+        }
+
+        match verdict {
+            CooldownVerdict::Suppress => {
+                if let Some(id) = notification.incident_id {
+                    *self.suppressed.entry((notification.home_id.clone(), id)).or_default() += 1;
+                }
+            }
+            _ => self.record_sent(notification, at),
+        }
+        verdict
+    }
+
+    // Restart the incident and zone cooldown windows
+    fn record_sent(&self, notification: &Notification, at: DateTime<Utc>) {
+        let keys = [
+            notification.incident_id.map(|id| CooldownKey::Incident(notification.home_id.clone(), id)),
+            notification.zone.clone().map(|z| CooldownKey::Zone(notification.home_id.clone(), z)),
+        ];
+        for key in keys.into_iter().flatten() {
+            self.last_sent.insert(key, LastSent {
+                at,
+                severity: notification.severity,
+                probability: notification.probability,
+            });
+        }
+    }
+
+    /// The user has seen an incident's alert; hold further ones unless it escalates past `severity`
+    pub fn acknowledge(&self, home_id: &str, incident_id: u64, severity: NotificationSeverity) {
+        self.acknowledged.insert((home_id.to_string(), incident_id), severity);
+    }
+
+    /// Notifications held back by cooldown for an incident
+    pub fn suppressed_count(&self, home_id: &str, incident_id: u64) -> u32 {
+        self.suppressed.get(&(home_id.to_string(), incident_id)).map_or(0, |c| *c)
+    }
+
+    /// Forget cooldown state older than the longest window, and two-phase
+    /// alerts that have not been updated for an hour
+    pub fn prune_cooldowns(&self, now: DateTime<Utc>) {
+        let horizon = self.cooldown.incident_window.max(self.cooldown.zone_window);
+        self.last_sent.retain(|_, last| now - last.at < horizon);
+        self.alerts.retain(|_, alert| now - alert.updated_at.unwrap_or(alert.heads_up_at) < Duration::hours(1));
+    }
+
+    /// Apply cooldown, then route to each recipient's channels, queueing held
+    /// notifications for their digest
+    pub fn route(&self, notification: &Notification, recipients: &[(Option<String>, Vec<DeliveryChannel>)], at: DateTime<Utc>) -> RouteOutcome {
+        let heads_up = notification.phase() == Some(AlertPhase::HeadsUp);
+        let updates = notification.alert.filter(|a| a.phase == AlertPhase::Update && self.alerts.contains_key(&a.alert_id));
+        let cooldown = match updates {
+            Some(_) => {
+                self.record_sent(notification, at);
+                CooldownVerdict::Send
+            }
+            None => self.check_cooldown(notification, at),
+        };
+        let mut routed = Vec::new();
+        for (user_id, channels) in recipients {
+            let mut queued = false;
+            for channel in channels.iter().filter(|c| !heads_up || HEADS_UP_CHANNELS.contains(*c)) {
+                let (title, body) = self.templates.render(notification, channel);
+                if cooldown == CooldownVerdict::Suppress {
+                    routed.push(RoutedNotification {
+                        user_id: user_id.clone(),
+                        channel: channel.clone(),
+                        decision: RouteDecision::Drop("repeat within cooldown".to_string()),
+                        title,
+                        body,
+                        alert: notification.alert,
+                    });
+                    continue;
+                }
+                let decision = match self.decide(notification, user_id.as_deref(), channel, at) {
+                    RouteDecision::Digest(reason) if heads_up => RouteDecision::Drop(format!("{}; heads-ups are not held for the digest", reason)),
+                    decision => decision,
+                };
+                if matches!(decision, RouteDecision::Digest(_)) && !queued {
+                    self.digests
+                        .entry((notification.home_id.clone(), user_id.clone()))
+                        .or_default()
+                        .push(notification.clone());
+                    queued = true;
+                }
+                routed.push(RoutedNotification { user_id: user_id.clone(), channel: channel.clone(), decision, title, body, alert: notification.alert });
+            }
+        }
+        if let Some(alert) = notification.alert {
+            self.track_alert(notification, alert, &routed, at);
+        }
+        if let Some(sms) = &self.sms {
+            sms.spawn_deliveries(&notification.home_id, &routed);
+        }
+        if let Some(email) = &self.email {
+            email.spawn_deliveries(&notification.home_id, &routed);
+        }
+        RouteOutcome { cooldown, deliveries: routed }
+    }
+
+    // Remember a delivered heads-up so its update can replace it
+    fn track_alert(&self, notification: &Notification, alert: AlertRef, routed: &[RoutedNotification], at: DateTime<Utc>) {
+        match alert.phase {
+            AlertPhase::HeadsUp if routed.iter().any(|r| r.decision == RouteDecision::Deliver) => {
+                self.alerts.insert(alert.alert_id, LogicalAlert {
+                    alert_id: alert.alert_id,
+                    home_id: notification.home_id.clone(),
+                    incident_id: notification.incident_id,
+                    severity: notification.severity,
+                    heads_up_at: at,
+                    updated_at: None,
+                });
+            }
+            AlertPhase::HeadsUp => {}
+            AlertPhase::Update => {
+                if let Some(mut tracked) = self.alerts.get_mut(&alert.alert_id) {
+                    tracked.incident_id = notification.incident_id.or(tracked.incident_id);
+                    tracked.severity = notification.severity;
+                    tracked.updated_at = Some(at);
+                }
+            }
+        }
+    }
+
+    /// Held notifications for a recipient, oldest first; the queue is emptied
+    pub fn drain_digest(&self, home_id: &str, user_id: Option<&str>) -> Vec<Notification> {
+        self.digests
+            .remove(&(home_id.to_string(), user_id.map(str::to_string)))
+            .map(|(_, held)| held)
+            .unwrap_or_default()
+    }
+
+    /// Send an alert to the home's webhooks if its preferences allow it now
+    pub async fn dispatch_alert(&self, alert: &AlertInfo) -> Vec<WebhookDeliveryRecord> {
+        let severity = NotificationSeverity::from_threat_level(&alert.threat_level);
+        let locale = self.templates.language(&alert.home_id);
+        let notification = Notification {
+            home_id: alert.home_id.clone(),
+            severity,
+            title: t(&locale, "alert-title", &[("severity", localizer().severity(&locale, severity)), ("zone", alert.camera.clone())]),
+            body: alert.description.clone(),
+            created_at: alert.timestamp,
+            incident_id: None,
+            zone: Some(alert.camera.clone()),
+            probability: None,
+            entity: None,
+            entity_status: None,
+            thumbnail_url: None,
+            alert: None,
+        };
+        if !self.allow_webhook(&notification) {
+            return Vec::new();
+        }
+        match &self.webhooks {
+            Some(dispatcher) => dispatcher.dispatch_alert(alert).await,
+            None => Vec::new(),
+        }
+    }
+
+    fn summary_notification(&self, summary: &MorningSummary) -> Notification {
+        Notification {
+            home_id: summary.home_id.clone(),
+            severity: if summary.requires_attention { NotificationSeverity::Standard } else { NotificationSeverity::Info },
+            title: t(&self.templates.language(&summary.home_id), "summary-title", &[("date", summary.summary_date.to_string())]),
+            body: summary.narrative.clone(),
+            created_at: self.clock.now(),
+            incident_id: None,
+            zone: None,
+            probability: None,
+            entity: None,
+            entity_status: None,
+            thumbnail_url: None,
+            alert: None,
+        }
+    }
+
+    /// Send a morning summary to the home's webhooks if its preferences allow it now
+    pub async fn dispatch_summary(&self, summary: &MorningSummary) -> Vec<WebhookDeliveryRecord> {
+        let notification = self.summary_notification(summary);
+        if !self.allow_webhook(&notification) {
+            return Vec::new();
+        }
+        match &self.webhooks {
+            Some(dispatcher) => dispatcher.dispatch_summary(summary).await,
+            None => Vec::new(),
+        }
+    }
+
+    /// Email the HTML morning summary to every registered address whose
+    /// preferences allow email now; `thumbnails` are snapshot bytes by URL.
+    /// Returns how many were sent.
+    pub async fn email_summary(&self, summary: &MorningSummary, thumbnails: &HashMap<String, Vec<u8>>) -> usize {
+        let Some(email) = &self.email else {
+            return 0;
+        };
+        let message = match render_summary(summary, &self.templates.language(&summary.home_id), thumbnails) {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("Morning summary email for {} not rendered: {}", summary.home_id, e);
+                return 0;
+            }
+        };
+        let notification = self.summary_notification(summary);
+        let now = self.clock.now();
+        let mut sent = 0;
+        for contact in email.contacts(&summary.home_id) {
+            if self.decide(&notification, Some(&contact.user_id), &DeliveryChannel::Email, now) != RouteDecision::Deliver {
+                continue;
+            }
+            match email.deliver(&summary.home_id, &contact.user_id, &message).await {
+                Ok(_) => sent += 1,
+                Err(e) => tracing::warn!("Morning summary email to {} not sent: {}", contact.user_id, e),
+            }
+        }
+        sent
+    }
+
+    fn allow_webhook(&self, notification: &Notification) -> bool {
+        let outcome = self.route(notification, &[(None, vec![DeliveryChannel::Webhook])], self.clock.now());
+        outcome.deliveries.iter().all(|r| r.decision == RouteDecision::Deliver)
+    }
+}
@@ -0,0 +1,464 @@
+//! SIEM export over CEF or RFC 5424 syslog
+//!
+//! Events are queued without blocking the pipeline, batched, and written to a TCP or
+//! TLS collector by a background task. A dropped connection is re-established with
+//! exponential backoff and the unsent batch is retried, so short collector outages
+//! lose nothing as long as the queue does not overflow.
+
+use crate::api::models::AlertInfo;
+use crate::thinking::{AlertDecision, ThinkingAIResult};
+use crate::validation::Validate;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_rustls::rustls::{self, OwnedTrustAnchor, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+use tracing::{info, warn};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SiemError {
+    #[error("SIEM export queue is full")]
+    QueueFull,
+
+    #[error("SIEM connection failed: {0}")]
+    Connection(String),
+
+    #[error("Invalid SIEM host name: {0}")]
+    InvalidHost(String),
+
+    #[error("Invalid SIEM setting {0}: {1}")]
+    Config(&'static str, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SiemFormat {
+    Cef,    // ArcSight Common Event Format, one event per line
+    Syslog, // RFC 5424 with structured data, octet-counted framing (RFC 6587)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SiemTransport {
+    Tcp,
+    Tls,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SiemEventKind {
+    Alert,
+    Incident,
+}
+
+// One exportable record; `fields` uses our own names, mapped on output
+#[derive(Debug, Clone, Serialize)]
+pub struct SiemEvent {
+    pub kind: SiemEventKind,
+    pub signature_id: String,
+    pub name: String,
+    pub severity: u8, // CEF scale, 0-10
+    pub timestamp: DateTime<Utc>,
+    pub fields: BTreeMap<String, String>,
+}
+
+impl SiemEvent {
+    pub fn from_alert(alert: &AlertInfo) -> Self {
+        let severity = match alert.threat_level.to_ascii_lowercase().as_str() {
+            "critical" => 10,
+            "high" | "elevated" => 8,
+            "medium" | "standard" => 5,
+            "low" => 3,
+            _ => 5,
+        };
+
+        let mut fields = BTreeMap::new();
+        fields.insert("alert_id".to_string(), alert.id.to_string());
+        fields.insert("home_id".to_string(), alert.home_id.clone());
+        fields.insert("camera".to_string(), alert.camera.clone());
+        fields.insert("threat_level".to_string(), alert.threat_level.clone());
+        fields.insert("status".to_string(), format!("{:?}", alert.status));
+        fields.insert("description".to_string(), alert.description.clone());
+
+        Self {
+            kind: SiemEventKind::Alert,
+            signature_id: "novin-alert".to_string(),
+            name: "Security alert".to_string(),
+            severity,
+            timestamp: alert.timestamp,
+            fields,
+        }
+    }
+
+    pub fn from_thinking_result(home_id: &str, result: &ThinkingAIResult) -> Self {
+        let severity = match result.alert_decision {
+            AlertDecision::Critical => 10,
+            AlertDecision::Elevated => 8,
+            AlertDecision::Standard => 5,
+            AlertDecision::Wait => 3,
+            AlertDecision::Ignore => 1,
+        };
+
+        let mut fields = BTreeMap::new();
+        fields.insert("home_id".to_string(), home_id.to_string());
+        fields.insert("incident_id".to_string(), result.incident_id.to_string());
+        fields.insert("probability".to_string(), format!("{:.4}", result.calibrated_probability));
+        fields.insert("decision".to_string(), format!("{:?}", result.alert_decision));
+        fields.insert("description".to_string(), result.narrative_summary.clone());
+        fields.insert("explanation".to_string(), result.explanation.render());
+        fields.insert("config_hash".to_string(), result.explanation.config_hash.clone());
+
+        Self {
+            kind: SiemEventKind::Incident,
+            signature_id: format!("novin-incident-{:?}", result.alert_decision).to_lowercase(),
+            name: "Incident assessment".to_string(),
+            severity,
+            timestamp: Utc::now(),
+            fields,
+        }
+    }
+}
+
+// Maps our field names to CEF extension keys or syslog SD-PARAM names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiemFieldMapping {
+    pub fields: HashMap<String, String>,
+    pub include_unmapped: bool, // Unmapped fields are emitted under their own name
+}
+
+impl SiemFieldMapping {
+    pub fn cef_default() -> Self {
+        let fields = [
+            ("home_id", "cs1"),
+            ("camera", "cs2"),
+            ("threat_level", "cs3"),
+            ("incident_id", "cn1"),
+            ("probability", "cfp1"),
+            ("decision", "act"),
+            ("alert_id", "externalId"),
+            ("description", "msg"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        Self { fields, include_unmapped: false }
+    }
+
+    pub fn identity() -> Self {
+        Self { fields: HashMap::new(), include_unmapped: true }
+    }
+
+    fn map<'a>(&'a self, field: &'a str) -> Option<&'a str> {
+        match self.fields.get(field) {
+            Some(mapped) => Some(mapped.as_str()),
+            None if self.include_unmapped => Some(field),
+            None => None,
+        }
+    }
+}
+
+fn cef_escape_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn cef_escape_extension(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
+
+/// Render an event as a single CEF line (without trailing newline)
+pub fn format_cef(event: &SiemEvent, mapping: &SiemFieldMapping) -> String {
+    let mut extension = vec![format!("rt={}", event.timestamp.timestamp_millis())];
+    for (field, value) in &event.fields {
+        let Some(key) = mapping.map(field) else { continue };
+        extension.push(format!("{}={}", key, cef_escape_extension(value)));
+        // Custom string/number slots need a label so the SIEM knows what they hold
+        if key.starts_with("cs") || key.starts_with("cn") || key.starts_with("cfp") {
+            extension.push(format!("{}Label={}", key, cef_escape_extension(field)));
+        }
+    }
+
+    format!(
+        "CEF:0|Novin|InsaneAISecurity|{}|{}|{}|{}|{}",
+        env!("CARGO_PKG_VERSION"),
+        cef_escape_header(&event.signature_id),
+        cef_escape_header(&event.name),
+        event.severity.min(10),
+        extension.join(" ")
+    )
+}
+
+fn sd_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace(']', "\\]")
+}
+
+// SD-NAME may not contain '=', ' ', ']', '"' and is limited to 32 printable characters
+fn sd_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_graphic() && !matches!(c, '=' | ']' | '"'))
+        .take(32)
+        .collect()
+}
+
+/// Render an event as an RFC 5424 message (without framing)
+pub fn format_syslog(event: &SiemEvent, mapping: &SiemFieldMapping, hostname: &str, app_name: &str) -> String {
+    // local0 facility; map CEF severity 0-10 onto syslog 7 (debug) .. 1 (alert)
+    let syslog_severity = match event.severity {
+        9..=10 => 1,
+        7..=8 => 2,
+        5..=6 => 4,
+        3..=4 => 5,
+        _ => 6,
+    };
+    let pri = 16 * 8 + syslog_severity;
+
+    let params: Vec<String> = event.fields
+        .iter()
+        .filter(|(field, _)| field.as_str() != "description")
+        .filter_map(|(field, value)| mapping.map(field).map(|key| format!("{}=\"{}\"", sd_name(key), sd_escape(value))))
+        .collect();
+    let structured_data = if params.is_empty() {
+        "-".to_string()
+    } else {
+        format!("[novin@32473 {}]", params.join(" "))
+    };
+    let msg = event.fields.get("description").cloned().unwrap_or_else(|| event.name.clone());
+
+    format!(
+        "<{}>1 {} {} {} - {} {} {}",
+        pri,
+        event.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        if hostname.is_empty() { "-" } else { hostname },
+        app_name,
+        sd_name(&event.signature_id),
+        structured_data,
+        msg
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct SiemConfig {
+    pub host: String,
+    pub port: u16,
+    pub transport: SiemTransport,
+    pub format: SiemFormat,
+    pub mapping: SiemFieldMapping,
+    pub hostname: String, // Reported in syslog HOSTNAME
+    pub batch_size: usize,
+    pub flush_interval: Duration,
+    pub queue_capacity: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for SiemConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 6514,
+            transport: SiemTransport::Tls,
+            format: SiemFormat::Cef,
+            mapping: SiemFieldMapping::cef_default(),
+            hostname: "novin".to_string(),
+            batch_size: 100,
+            flush_interval: Duration::from_secs(2),
+            queue_capacity: 10_000,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl SiemConfig {
+    /// Config from the environment; None unless NOVIN_SIEM_HOST is set
+    pub fn from_env() -> Result<Option<Self>, SiemError> {
+        let Ok(host) = std::env::var("NOVIN_SIEM_HOST") else {
+            return Ok(None);
+        };
+        let mut config = Self { host, ..Self::default() };
+        if let Ok(v) = std::env::var("NOVIN_SIEM_PORT") {
+            config.port = v.parse().map_err(|_| SiemError::Config("NOVIN_SIEM_PORT", v))?;
+        }
+        if let Ok(v) = std::env::var("NOVIN_SIEM_TRANSPORT") {
+            config.transport = match v.to_ascii_lowercase().as_str() {
+                "tcp" => SiemTransport::Tcp,
+                "tls" => SiemTransport::Tls,
+                _ => return Err(SiemError::Config("NOVIN_SIEM_TRANSPORT", v)),
+            };
+        }
+        if let Ok(v) = std::env::var("NOVIN_SIEM_FORMAT") {
+            (config.format, config.mapping) = match v.to_ascii_lowercase().as_str() {
+                "cef" => (SiemFormat::Cef, SiemFieldMapping::cef_default()),
+                "syslog" => (SiemFormat::Syslog, SiemFieldMapping::identity()),
+                _ => return Err(SiemError::Config("NOVIN_SIEM_FORMAT", v)),
+            };
+        }
+        config.validate().map_err(|e| SiemError::Config("config", e.to_string()))?;
+        Ok(Some(config))
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SiemMetrics {
+    pub sent: u64,
+    pub dropped: u64,
+    pub reconnects: u64,
+}
+
+#[derive(Debug, Default)]
+struct SiemCounters {
+    sent: AtomicU64,
+    dropped: AtomicU64,
+    reconnects: AtomicU64,
+}
+
+type SiemStream = Box<dyn AsyncWrite + Unpin + Send>;
+
+// Handle used by the pipeline; the connection lives in a background task
+pub struct SiemExporter {
+    sender: mpsc::Sender<SiemEvent>,
+    counters: Arc<SiemCounters>,
+}
+
+impl SiemExporter {
+    /// Start the background writer task
+    pub fn start(config: SiemConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        let counters = Arc::new(SiemCounters::default());
+        tokio::spawn(run_writer(config, receiver, counters.clone()));
+        Self { sender, counters }
+    }
+
+    /// Queue an event without waiting on the network
+    pub fn export(&self, event: SiemEvent) -> Result<(), SiemError> {
+        self.sender.try_send(event).map_err(|_| {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+            SiemError::QueueFull
+        })
+    }
+
+    pub fn metrics(&self) -> SiemMetrics {
+        SiemMetrics {
+            sent: self.counters.sent.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            reconnects: self.counters.reconnects.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn encode(config: &SiemConfig, event: &SiemEvent) -> Vec<u8> {
+    match config.format {
+        SiemFormat::Cef => format!("{}\n", format_cef(event, &config.mapping)).into_bytes(),
+        SiemFormat::Syslog => {
+            let msg = format_syslog(event, &config.mapping, &config.hostname, "novin-security");
+            format!("{} {}", msg.len(), msg).into_bytes()
+        }
+    }
+}
+
+async fn connect(config: &SiemConfig) -> Result<SiemStream, SiemError> {
+    let tcp = TcpStream::connect((config.host.as_str(), config.port)).await
+        .map_err(|e| SiemError::Connection(e.to_string()))?;
+    tcp.set_nodelay(true).ok();
+
+    match config.transport {
+        SiemTransport::Tcp => Ok(Box::new(tcp)),
+        SiemTransport::Tls => {
+            let mut roots = RootCertStore::empty();
+            roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(ta.subject, ta.spki, ta.name_constraints)
+            }));
+            let tls_config = rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            let server_name = ServerName::try_from(config.host.as_str())
+                .map_err(|_| SiemError::InvalidHost(config.host.clone()))?;
+            let stream = TlsConnector::from(Arc::new(tls_config))
+                .connect(server_name, tcp)
+                .await
+                .map_err(|e| SiemError::Connection(e.to_string()))?;
+            Ok(Box::new(stream))
+        }
+    }
+}
+
+async fn run_writer(config: SiemConfig, mut receiver: mpsc::Receiver<SiemEvent>, counters: Arc<SiemCounters>) {
+    let mut pending: VecDeque<SiemEvent> = VecDeque::new();
+    let mut stream: Option<SiemStream> = None;
+    let mut backoff = config.initial_backoff;
+    let mut ticker = tokio::time::interval(config.flush_interval);
+    let mut closed = false;
+
+    loop {
+        // Collect until a batch is full or the flush interval fires
+        if !closed && pending.len() < config.batch_size {
+            tokio::select! {
+                received = receiver.recv() => match received {
+                    Some(event) => {
+                        if pending.len() >= config.queue_capacity {
+                            pending.pop_front();
+                            counters.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        pending.push_back(event);
+                        if pending.len() < config.batch_size {
+                            continue;
+                        }
+                    }
+                    None => closed = true,
+                },
+                _ = ticker.tick() => {}
+            }
+        }
+
+        if pending.is_empty() {
+            if closed {
+                return;
+            }
+            continue;
+        }
+
+        if stream.is_none() {
+            match connect(&config).await {
+                Ok(s) => {
+                    info!("Connected to SIEM collector {}:{}", config.host, config.port);
+                    stream = Some(s);
+                    backoff = config.initial_backoff;
+                }
+                Err(e) => {
+                    warn!("SIEM connect failed, retrying in {:?}: {}", backoff, e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(config.max_backoff);
+                    continue;
+                }
+            }
+        }
+
+        let batch_len = pending.len().min(config.batch_size);
+        let payload: Vec<u8> = pending.iter().take(batch_len).flat_map(|e| encode(&config, e)).collect();
+
+        let conn = stream.as_mut().expect("connected above");
+        let result = match conn.write_all(&payload).await {
+            Ok(()) => conn.flush().await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                pending.drain(..batch_len);
+                counters.sent.fetch_add(batch_len as u64, Ordering::Relaxed);
+            }
+            Err(e) => {
+                // Keep the batch; it is resent once the connection is back
+                warn!("SIEM write failed, reconnecting: {}", e);
+                stream = None;
+                counters.reconnects.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
@@ -0,0 +1,528 @@
+//! SMS delivery
+//!
+//! Text messages go out through an `SmsProvider`: Twilio, or a generic HTTP
+//! gateway that takes a JSON request and signs its callbacks the way our own
+//! webhooks are signed. Each user who wants SMS registers one phone number
+//! per home (E.164). Replying STOP to any message opts that number out
+//! everywhere, as carriers require, until it replies START. Providers report
+//! delivery progress to a status callback, and the terminal outcome of every
+//! message is counted in the home's `DeliveryStats`.
+
+use super::router::{RouteDecision, RoutedNotification};
+use super::webhook::verify_signature;
+use crate::overnight::DeliveryChannel;
+use async_trait::async_trait;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+pub const STATUS_CALLBACK_PATH: &str = "/api/sms/status";
+pub const INBOUND_PATH: &str = "/api/sms/inbound";
+
+const OPT_OUT_KEYWORDS: [&str; 6] = ["STOP", "STOPALL", "UNSUBSCRIBE", "CANCEL", "END", "QUIT"];
+const OPT_IN_KEYWORDS: [&str; 3] = ["START", "UNSTOP", "YES"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum SmsError {
+    #[error("No SMS provider is configured")]
+    NotConfigured,
+
+    #[error("'{0}' is not an E.164 phone number")]
+    InvalidNumber(String),
+
+    #[error("No phone number registered for {0}")]
+    NoNumber(String),
+
+    #[error("{0} has opted out of SMS")]
+    OptedOut(String),
+
+    #[error("Provider rejected the message ({status}): {body}")]
+    Provider { status: u16, body: String },
+
+    #[error("Provider request failed: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmsStatus {
+    Queued,
+    Sent,
+    Delivered,
+    Undelivered,
+    Failed,
+}
+
+impl SmsStatus {
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Delivered | Self::Undelivered | Self::Failed)
+    }
+
+    /// Twilio's MessageStatus values, or our own snake_case names
+    pub fn parse(status: &str) -> Option<Self> {
+        match status.to_ascii_lowercase().as_str() {
+            "accepted" | "scheduled" | "queued" | "sending" => Some(Self::Queued),
+            "sent" => Some(Self::Sent),
+            "delivered" | "read" => Some(Self::Delivered),
+            "undelivered" | "canceled" => Some(Self::Undelivered),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// A number in E.164 form: '+', then 8 to 15 digits. Spaces, dashes, dots
+/// and brackets are dropped; a leading 00 becomes '+'.
+pub fn normalize_number(number: &str) -> Result<String, SmsError> {
+    let compact: String = number.chars().filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')')).collect();
+    let digits = compact.strip_prefix('+').or_else(|| compact.strip_prefix("00"))
+        .ok_or_else(|| SmsError::InvalidNumber(number.to_string()))?;
+    let valid = (8..=15).contains(&digits.len())
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && !digits.starts_with('0');
+    if valid {
+        Ok(format!("+{}", digits))
+    } else {
+        Err(SmsError::InvalidNumber(number.to_string()))
+    }
+}
+
+/// A provider callback, already authenticated and parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SmsCallback {
+    Status { message_id: String, status: SmsStatus, error: Option<String> },
+    Inbound { from: String, body: String },
+}
+
+#[async_trait]
+pub trait SmsProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Send a message; returns the provider's message id
+    async fn send(&self, to: &str, body: &str, status_callback: Option<&str>) -> Result<String, SmsError>;
+
+    /// Header carrying the provider's callback signature
+    fn signature_header(&self) -> &'static str;
+
+    /// Authenticate and parse a callback posted to `url`
+    fn callback(&self, url: &str, signature: Option<&str>, body: &[u8]) -> Option<SmsCallback>;
+}
+
+/// Twilio Programmable Messaging
+pub struct TwilioProvider {
+    account_sid: String,
+    auth_token: String,
+    from: String, // Number or messaging service SID (MG...)
+    base_url: String,
+    client: Client,
+}
+
+impl TwilioProvider {
+    pub fn new(account_sid: String, auth_token: String, from: String) -> Self {
+        Self {
+            account_sid,
+            auth_token,
+            from,
+            base_url: "https://api.twilio.com".to_string(),
+            client: Client::builder().timeout(std::time::Duration::from_secs(10)).build().unwrap_or_default(),
+        }
+    }
+
+    /// Point at another API host, e.g. a regional edge or a test server
+    pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// X-Twilio-Signature: base64 HMAC-SHA1 of the URL followed by every
+    /// form parameter's name and value, sorted by name
+    pub fn sign(&self, url: &str, params: &BTreeMap<String, String>) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(self.auth_token.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(url.as_bytes());
+        for (name, value) in params {
+            mac.update(name.as_bytes());
+            mac.update(value.as_bytes());
+        }
+        base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+}
+
+#[derive(Deserialize)]
+struct TwilioMessage {
+    sid: String,
+}
+
+#[async_trait]
+impl SmsProvider for TwilioProvider {
+    fn name(&self) -> &'static str {
+        "twilio"
+    }
+
+    async fn send(&self, to: &str, body: &str, status_callback: Option<&str>) -> Result<String, SmsError> {
+        let url = format!("{}/2010-04-01/Accounts/{}/Messages.json", self.base_url, self.account_sid);
+        let sender = if self.from.starts_with("MG") { "MessagingServiceSid" } else { "From" };
+        let mut form = vec![("To", to), (sender, self.from.as_str()), ("Body", body)];
+        if let Some(callback) = status_callback {
+            form.push(("StatusCallback", callback));
+        }
+        let response = self.client.post(url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .form(&form)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SmsError::Provider { status: status.as_u16(), body: response.text().await.unwrap_or_default() });
+        }
+        Ok(response.json::<TwilioMessage>().await?.sid)
+    }
+
+    fn signature_header(&self) -> &'static str {
+        "X-Twilio-Signature"
+    }
+
+    fn callback(&self, url: &str, signature: Option<&str>, body: &[u8]) -> Option<SmsCallback> {
+        let params: BTreeMap<String, String> = url::form_urlencoded::parse(body).into_owned().collect();
+        let expected = self.sign(url, &params);
+        let valid = signature.is_some_and(|s| s.as_bytes().len() == expected.len() && s.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0);
+        if !valid {
+            return None;
+        }
+        if let (Some(id), Some(status)) = (params.get("MessageSid"), params.get("MessageStatus")) {
+            return SmsStatus::parse(status).map(|status| SmsCallback::Status {
+                message_id: id.clone(),
+                status,
+                error: params.get("ErrorCode").cloned(),
+            });
+        }
+        Some(SmsCallback::Inbound { from: params.get("From")?.clone(), body: params.get("Body").cloned().unwrap_or_default() })
+    }
+}
+
+/// Any gateway that accepts `{"to", "body", "status_callback"}` as JSON and
+/// answers with `{"id"}`. Callbacks are JSON too, either
+/// `{"id", "status", "error"}` or `{"from", "body"}`, signed with the shared
+/// secret like our outgoing webhooks.
+pub struct HttpSmsProvider {
+    url: String,
+    token: Option<String>, // Sent as a bearer token
+    secret: String,
+    client: Client,
+}
+
+impl HttpSmsProvider {
+    pub fn new(url: String, token: Option<String>, secret: String) -> Self {
+        Self {
+            url,
+            token,
+            secret,
+            client: Client::builder().timeout(std::time::Duration::from_secs(10)).build().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HttpSmsCallback {
+    id: Option<String>,
+    status: Option<String>,
+    error: Option<String>,
+    from: Option<String>,
+    body: Option<String>,
+}
+
+#[async_trait]
+impl SmsProvider for HttpSmsProvider {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn send(&self, to: &str, body: &str, status_callback: Option<&str>) -> Result<String, SmsError> {
+        let payload = serde_json::json!({ "to": to, "body": body, "status_callback": status_callback });
+        let mut request = self.client.post(&self.url).json(&payload);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SmsError::Provider { status: status.as_u16(), body: response.text().await.unwrap_or_default() });
+        }
+        let reply: serde_json::Value = response.json().await?;
+        reply.get("id").and_then(|id| id.as_str()).map(str::to_string)
+            .ok_or_else(|| SmsError::Provider { status: status.as_u16(), body: "response has no message id".to_string() })
+    }
+
+    fn signature_header(&self) -> &'static str {
+        super::webhook::SIGNATURE_HEADER
+    }
+
+    fn callback(&self, _url: &str, signature: Option<&str>, body: &[u8]) -> Option<SmsCallback> {
+        if !verify_signature(&self.secret, signature?, body, 300) {
+            return None;
+        }
+        let callback: HttpSmsCallback = serde_json::from_slice(body).ok()?;
+        match callback {
+            HttpSmsCallback { id: Some(message_id), status: Some(status), error, .. } => {
+                SmsStatus::parse(&status).map(|status| SmsCallback::Status { message_id, status, error })
+            }
+            HttpSmsCallback { from: Some(from), body, .. } => Some(SmsCallback::Inbound { from, body: body.unwrap_or_default() }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmsContact {
+    pub user_id: String,
+    pub phone_number: String,
+    pub opted_out: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcomes of a home's messages on one channel, each counted once it
+/// reaches a final status
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryStats {
+    pub sent: u64,            // Accepted by the provider
+    pub delivered: u64,
+    pub undelivered: u64,     // Reported undeliverable, or bounced
+    pub failed: u64,          // Rejected by the provider, or reported failed
+    pub skipped_opted_out: u64, // Opted out, or suppressed after a complaint
+    pub skipped_no_contact: u64, // No number or address registered
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SentSms {
+    pub home_id: String,
+    pub user_id: String,
+    pub status: SmsStatus,
+    pub error: Option<String>,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SmsConfig {
+    pub max_chars: usize,                 // Longer texts are cut, keeping whole segments affordable
+    pub callback_base_url: Option<String>, // Public base URL providers post callbacks to
+    pub message_retention: Duration,       // How long message ids are kept for status callbacks
+}
+
+impl Default for SmsConfig {
+    fn default() -> Self {
+        Self {
+            max_chars: 306, // Two concatenated GSM-7 segments
+            callback_base_url: None,
+            message_retention: Duration::days(3),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SmsDispatcher {
+    provider: Option<Arc<dyn SmsProvider>>,
+    config: SmsConfig,
+    contacts: DashMap<(String, String), SmsContact>, // Per home and user
+    opted_out: DashMap<String, DateTime<Utc>>,        // Per number, across homes
+    messages: DashMap<String, SentSms>,               // Per provider message id
+    stats: DashMap<String, DeliveryStats>,            // Per home
+}
+
+impl SmsDispatcher {
+    pub fn new(provider: Arc<dyn SmsProvider>, config: SmsConfig) -> Self {
+        Self { provider: Some(provider), config, ..Self::default() }
+    }
+
+    /// Twilio from NOVIN_TWILIO_ACCOUNT_SID, NOVIN_TWILIO_AUTH_TOKEN and
+    /// NOVIN_TWILIO_FROM, else a generic gateway from NOVIN_SMS_HTTP_URL,
+    /// NOVIN_SMS_HTTP_SECRET and optionally NOVIN_SMS_HTTP_TOKEN. Callbacks
+    /// are requested when NOVIN_SMS_CALLBACK_BASE_URL is set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let provider: Arc<dyn SmsProvider> = match (var("NOVIN_TWILIO_ACCOUNT_SID"), var("NOVIN_TWILIO_AUTH_TOKEN"), var("NOVIN_TWILIO_FROM")) {
+            (Some(sid), Some(token), Some(from)) => Arc::new(TwilioProvider::new(sid, token, from)),
+            _ => Arc::new(HttpSmsProvider::new(var("NOVIN_SMS_HTTP_URL")?, var("NOVIN_SMS_HTTP_TOKEN"), var("NOVIN_SMS_HTTP_SECRET")?)),
+        };
+        let config = SmsConfig {
+            callback_base_url: var("NOVIN_SMS_CALLBACK_BASE_URL").map(|u| u.trim_end_matches('/').to_string()),
+            ..SmsConfig::default()
+        };
+        Some(Self::new(provider, config))
+    }
+
+    pub fn provider(&self) -> Option<&Arc<dyn SmsProvider>> {
+        self.provider.as_ref()
+    }
+
+    pub fn set_number(&self, home_id: &str, user_id: &str, number: &str) -> Result<SmsContact, SmsError> {
+        let phone_number = normalize_number(number)?;
+        let contact = SmsContact {
+            user_id: user_id.to_string(),
+            opted_out: self.opted_out.contains_key(&phone_number),
+            phone_number,
+            updated_at: Utc::now(),
+        };
+        self.contacts.insert((home_id.to_string(), user_id.to_string()), contact.clone());
+        Ok(contact)
+    }
+
+    pub fn remove_number(&self, home_id: &str, user_id: &str) -> bool {
+        self.contacts.remove(&(home_id.to_string(), user_id.to_string())).is_some()
+    }
+
+    pub fn contact(&self, home_id: &str, user_id: &str) -> Option<SmsContact> {
+        self.contacts.get(&(home_id.to_string(), user_id.to_string())).map(|c| SmsContact {
+            opted_out: self.opted_out.contains_key(&c.phone_number),
+            ..c.clone()
+        })
+    }
+
+    /// Registered numbers for a home, by user
+    pub fn contacts(&self, home_id: &str) -> Vec<SmsContact> {
+        let mut contacts: Vec<SmsContact> = self.contacts.iter()
+            .filter(|c| c.key().0 == home_id)
+            .filter_map(|c| self.contact(home_id, &c.key().1))
+            .collect();
+        contacts.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        contacts
+    }
+
+    pub fn stats(&self, home_id: &str) -> DeliveryStats {
+        self.stats.get(home_id).map(|s| s.clone()).unwrap_or_default()
+    }
+
+    pub fn message(&self, message_id: &str) -> Option<SentSms> {
+        self.messages.get(message_id).map(|m| m.clone())
+    }
+
+    fn count(&self, home_id: &str, apply: impl FnOnce(&mut DeliveryStats)) {
+        apply(&mut self.stats.entry(home_id.to_string()).or_default());
+    }
+
+    fn text(&self, title: &str, body: &str) -> String {
+        let text = if body.is_empty() { title.to_string() } else { format!("{}: {}", title, body) };
+        if text.chars().count() <= self.config.max_chars {
+            return text;
+        }
+        let mut cut: String = text.chars().take(self.config.max_chars.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    }
+
+    /// Send one message to a user's registered number; returns the provider's message id
+    pub async fn deliver(&self, home_id: &str, user_id: &str, title: &str, body: &str) -> Result<String, SmsError> {
+        let provider = self.provider.as_ref().ok_or(SmsError::NotConfigured)?;
+        let Some(contact) = self.contact(home_id, user_id) else {
+            self.count(home_id, |s| s.skipped_no_contact += 1);
+            return Err(SmsError::NoNumber(user_id.to_string()));
+        };
+        if contact.opted_out {
+            self.count(home_id, |s| s.skipped_opted_out += 1);
+            return Err(SmsError::OptedOut(user_id.to_string()));
+        }
+        let callback = self.config.callback_base_url.as_ref().map(|base| format!("{}{}", base, STATUS_CALLBACK_PATH));
+        match provider.send(&contact.phone_number, &self.text(title, body), callback.as_deref()).await {
+            Ok(message_id) => {
+                self.count(home_id, |s| s.sent += 1);
+                let now = Utc::now();
+                let retention = self.config.message_retention;
+                self.messages.retain(|_, m| now - m.sent_at < retention);
+                self.messages.insert(message_id.clone(), SentSms {
+                    home_id: home_id.to_string(),
+                    user_id: user_id.to_string(),
+                    status: SmsStatus::Queued,
+                    error: None,
+                    sent_at: now,
+                });
+                Ok(message_id)
+            }
+            Err(e) => {
+                self.count(home_id, |s| s.failed += 1);
+                Err(e)
+            }
+        }
+    }
+
+    /// Send every routed SMS marked for delivery, in the background
+    pub fn spawn_deliveries(self: &Arc<Self>, home_id: &str, deliveries: &[RoutedNotification]) {
+        let due: Vec<(String, String, String)> = deliveries.iter()
+            .filter(|d| d.channel == DeliveryChannel::SMS && d.decision == RouteDecision::Deliver)
+            .filter_map(|d| Some((d.user_id.clone()?, d.title.clone(), d.body.clone())))
+            .collect();
+        if due.is_empty() || self.provider.is_none() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("{} SMS for {} not sent: no async runtime", due.len(), home_id);
+            return;
+        };
+        let dispatcher = self.clone();
+        let home_id = home_id.to_string();
+        runtime.spawn(async move {
+            for (user_id, title, body) in due {
+                if let Err(e) = dispatcher.deliver(&home_id, &user_id, &title, &body).await {
+                    warn!("SMS to {} in {} not sent: {}", user_id, home_id, e);
+                }
+            }
+        });
+    }
+
+    /// Authenticate a callback posted to `path` (one of the callback paths)
+    /// and apply it; false when it is not from the provider
+    pub fn receive(&self, path: &str, signature: Option<&str>, body: &[u8]) -> bool {
+        let Some(provider) = &self.provider else {
+            return false;
+        };
+        let url = format!("{}{}", self.config.callback_base_url.as_deref().unwrap_or_default(), path);
+        match provider.callback(&url, signature, body) {
+            Some(callback) => {
+                self.handle_callback(callback);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Apply an authenticated provider callback. A message's final status is
+    /// counted once; later reports for it are ignored.
+    pub fn handle_callback(&self, callback: SmsCallback) {
+        match callback {
+            SmsCallback::Status { message_id, status, error } => {
+                let Some(mut message) = self.messages.get_mut(&message_id) else {
+                    return;
+                };
+                if message.status.is_final() {
+                    return;
+                }
+                message.status = status;
+                message.error = error;
+                let home_id = message.home_id.clone();
+                drop(message);
+                match status {
+                    SmsStatus::Delivered => self.count(&home_id, |s| s.delivered += 1),
+                    SmsStatus::Undelivered => self.count(&home_id, |s| s.undelivered += 1),
+                    SmsStatus::Failed => self.count(&home_id, |s| s.failed += 1),
+                    SmsStatus::Queued | SmsStatus::Sent => {}
+                }
+            }
+            SmsCallback::Inbound { from, body } => {
+                let Ok(number) = normalize_number(&from) else {
+                    return;
+                };
+                let keyword = body.trim().to_ascii_uppercase();
+                if OPT_OUT_KEYWORDS.contains(&keyword.as_str()) {
+                    info!("{} opted out of SMS", number);
+                    self.opted_out.insert(number, Utc::now());
+                } else if OPT_IN_KEYWORDS.contains(&keyword.as_str()) && self.opted_out.remove(&number).is_some() {
+                    info!("{} opted back in to SMS", number);
+                }
+            }
+        }
+    }
+}
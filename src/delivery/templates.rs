@@ -0,0 +1,191 @@
+//! Notification templates
+//!
+//! Titles and bodies of outbound notifications are rendered from minijinja
+//! templates rather than fixed strings, so wording can change over the API
+//! without a redeploy. A template applies to one language and either one
+//! delivery channel or all of them, and is set either for every home or for
+//! one home. Each home picks its language, which is also the locale its
+//! built-in text is translated into (see `crate::i18n`). Lookup goes from the most specific
+//! template (this home, this language, this channel) to the least, then falls
+//! back to English, and finally to the text the notification was built with.
+//!
+//! Templates see the notification's variables: `home_id`, `severity`,
+//! `severity_name` (translated), `incident_id`, `entity`, `entity_status`,
+//! `zone`, `probability`, `probability_pct`, `thumbnail_url`, and the built-in
+//! `title` and `summary`.
+
+use super::router::{Notification, NotificationSeverity};
+use crate::overnight::DeliveryChannel;
+use dashmap::DashMap;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+#[derive(thiserror::Error, Debug)]
+pub enum TemplateError {
+    #[error("Invalid {field} template: {reason}")]
+    Syntax { field: &'static str, reason: String },
+
+    #[error("Template failed to render: {0}")]
+    Render(String),
+
+    #[error("Invalid language '{0}'")]
+    Language(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationTemplate {
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub channel: Option<DeliveryChannel>, // None applies to every channel
+    pub title: String,
+    pub body: String,
+}
+
+fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
+
+/// Everything a template can refer to
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationVars {
+    pub home_id: String,
+    pub severity: NotificationSeverity,
+    pub severity_name: String,
+    pub incident_id: Option<u64>,
+    pub entity: Option<String>,
+    pub entity_status: Option<String>,
+    pub zone: Option<String>,
+    pub probability: Option<f64>,
+    pub probability_pct: Option<u32>,
+    pub thumbnail_url: Option<String>,
+    pub title: String,
+    pub summary: String,
+}
+
+impl NotificationVars {
+    pub fn from_notification(notification: &Notification, locale: &str) -> Self {
+        Self {
+            home_id: notification.home_id.clone(),
+            severity: notification.severity,
+            severity_name: crate::i18n::localizer().severity(locale, notification.severity),
+            incident_id: notification.incident_id,
+            entity: notification.entity.clone(),
+            entity_status: notification.entity_status.clone(),
+            zone: notification.zone.clone(),
+            probability: notification.probability,
+            probability_pct: notification.probability.map(|p| (p * 100.0).round().clamp(0.0, 100.0) as u32),
+            thumbnail_url: notification.thumbnail_url.clone(),
+            title: notification.title.clone(),
+            summary: notification.body.clone(),
+        }
+    }
+}
+
+fn check_language(language: &str) -> Result<(), TemplateError> {
+    let valid = !language.is_empty()
+        && language.len() <= 16
+        && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(TemplateError::Language(language.to_string()))
+    }
+}
+
+impl NotificationTemplate {
+    /// Language is well-formed and both parts compile
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        check_language(&self.language)?;
+        let env = Environment::new();
+        for (field, source) in [("title", &self.title), ("body", &self.body)] {
+            env.template_from_str(source).map_err(|e| TemplateError::Syntax { field, reason: e.to_string() })?;
+        }
+        Ok(())
+    }
+
+    pub fn render(&self, vars: &NotificationVars) -> Result<(String, String), TemplateError> {
+        let env = Environment::new();
+        let title = env.render_str(&self.title, vars).map_err(|e| TemplateError::Render(e.to_string()))?;
+        let body = env.render_str(&self.body, vars).map_err(|e| TemplateError::Render(e.to_string()))?;
+        Ok((title.trim().to_string(), body.trim().to_string()))
+    }
+}
+
+// None for templates that apply to every home
+type TemplateKey = (Option<String>, String, Option<DeliveryChannel>);
+
+#[derive(Debug, Default)]
+pub struct TemplateStore {
+    templates: DashMap<TemplateKey, NotificationTemplate>,
+    languages: DashMap<String, String>, // Per home
+}
+
+impl TemplateStore {
+    /// Set a template for one home, or for every home with `home_id` None
+    pub fn set(&self, home_id: Option<&str>, template: NotificationTemplate) -> Result<(), TemplateError> {
+        template.validate()?;
+        let key = (home_id.map(str::to_string), template.language.clone(), template.channel.clone());
+        self.templates.insert(key, template);
+        Ok(())
+    }
+
+    pub fn remove(&self, home_id: Option<&str>, language: &str, channel: Option<DeliveryChannel>) -> bool {
+        self.templates.remove(&(home_id.map(str::to_string), language.to_string(), channel)).is_some()
+    }
+
+    /// Templates set for one home (or for every home), sorted by language then channel
+    pub fn list(&self, home_id: Option<&str>) -> Vec<NotificationTemplate> {
+        let mut templates: Vec<NotificationTemplate> = self.templates.iter()
+            .filter(|t| t.key().0.as_deref() == home_id)
+            .map(|t| t.value().clone())
+            .collect();
+        templates.sort_by(|a, b| (&a.language, format!("{:?}", a.channel)).cmp(&(&b.language, format!("{:?}", b.channel))));
+        templates
+    }
+
+    pub fn set_language(&self, home_id: &str, language: &str) -> Result<(), TemplateError> {
+        check_language(language)?;
+        self.languages.insert(home_id.to_string(), language.to_string());
+        Ok(())
+    }
+
+    pub fn language(&self, home_id: &str) -> String {
+        self.languages.get(home_id).map_or_else(default_language, |l| l.clone())
+    }
+
+    /// Most specific template for a home and channel, in the home's language or English
+    pub fn resolve(&self, home_id: &str, channel: &DeliveryChannel) -> Option<NotificationTemplate> {
+        let language = self.language(home_id);
+        let languages = [language.as_str(), DEFAULT_LANGUAGE];
+        let home = Some(home_id.to_string());
+        for language in languages.iter().take(if language == DEFAULT_LANGUAGE { 1 } else { 2 }) {
+            for scope in [&home, &None] {
+                for channel in [Some(channel.clone()), None] {
+                    if let Some(template) = self.templates.get(&(scope.clone(), language.to_string(), channel)) {
+                        return Some(template.clone());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Title and body for one channel; the notification's own text when no
+    /// template applies or the template fails
+    pub fn render(&self, notification: &Notification, channel: &DeliveryChannel) -> (String, String) {
+        let fallback = || (notification.title.clone(), notification.body.clone());
+        let Some(template) = self.resolve(&notification.home_id, channel) else {
+            return fallback();
+        };
+        match template.render(&NotificationVars::from_notification(notification, &self.language(&notification.home_id))) {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                tracing::warn!("Notification template for {} ({:?}, {}) skipped: {}", notification.home_id, channel, template.language, e);
+                fallback()
+            }
+        }
+    }
+}
@@ -0,0 +1,543 @@
+//! VMS and NVR bookmarks on alert
+//!
+//! When an incident alerts, the recording systems watching the home are told
+//! so its footage is easy to find later and kept past the usual retention:
+//! Frigate gets a manual event on the camera, Blue Iris a trigger with a memo,
+//! and anything else (Milestone XProtect, Synology, a home-grown NVR) an HTTP
+//! request built from a template. Each integration maps our camera ids to the
+//! VMS's own camera names and leaves unmapped cameras alone. A camera is
+//! bookmarked once per incident, the first time its decision reaches the
+//! integration's minimum. Calls go out without holding up the pipeline; every
+//! attempt lands in the home's history and the integration's success counts.
+
+use crate::thinking::AlertDecision;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use reqwest::{Client, Method};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Instant;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+// Label Frigate files our manual events under
+const FRIGATE_LABEL: &str = "novin";
+
+#[derive(thiserror::Error, Debug)]
+pub enum VmsError {
+    #[error("Integration needs a name")]
+    MissingName,
+
+    #[error("'{0}' is not an http(s) URL")]
+    InvalidUrl(String),
+
+    #[error("Integration maps no cameras")]
+    NoCameras,
+
+    #[error("Minimum decision must be Standard, Elevated or Critical")]
+    InvalidMinimum,
+
+    #[error("'{0}' is not an HTTP method")]
+    InvalidMethod(String),
+
+    #[error("Home already has the maximum of {0} VMS integrations")]
+    TooMany(usize),
+
+    #[error("VMS integration {0} not found")]
+    NotFound(Uuid),
+
+    #[error("Camera '{0}' is not mapped by this integration")]
+    UnmappedCamera(String),
+
+    #[error("VMS rejected the request ({status}): {body}")]
+    Rejected { status: u16, body: String },
+
+    #[error("VMS request failed: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct VmsConfig {
+    pub max_per_home: usize,
+    pub history_len: usize,      // Attempts kept per home
+    pub timeout: std::time::Duration,
+    pub bookmarked_ttl: Duration, // How long "already bookmarked for this incident" is remembered
+}
+
+impl Default for VmsConfig {
+    fn default() -> Self {
+        Self {
+            max_per_home: 10,
+            history_len: 500,
+            timeout: std::time::Duration::from_secs(10),
+            bookmarked_ttl: Duration::hours(24),
+        }
+    }
+}
+
+/// A request built from placeholders: {camera}, {home_id}, {incident_id},
+/// {decision}, {timestamp} (RFC 3339), {epoch} (Unix seconds), {duration_secs}
+/// and {memo}. Values are percent-encoded in the path and JSON-escaped in the body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestTemplate {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub path: String, // Appended to the integration's base URL
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VmsProvider {
+    Frigate,                  // POST /api/events/{camera}/novin/create
+    BlueIris,                 // GET /admin?camera={camera}&trigger&memo=...
+    Generic(RequestTemplate), // e.g. POST /api/rest/v1/bookmarks on a Milestone API gateway
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VmsAuth {
+    #[default]
+    None,
+    Basic { username: String, password: String }, // Blue Iris takes these as user and pw query parameters
+    Bearer { token: String },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VmsIntegrationRequest {
+    pub name: String,
+    pub provider: VmsProvider,
+    pub base_url: String,
+    #[serde(default)]
+    pub auth: VmsAuth,
+    pub cameras: BTreeMap<String, String>, // Our camera id -> the VMS's camera name or id
+    #[serde(default = "default_min_decision")]
+    pub min_decision: AlertDecision,
+    #[serde(default = "default_record_secs")]
+    pub record_secs: u32, // How much footage the bookmark or recording covers
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_min_decision() -> AlertDecision {
+    AlertDecision::Standard
+}
+
+fn default_record_secs() -> u32 {
+    60
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmsIntegration {
+    pub id: Uuid,
+    pub home_id: String,
+    pub name: String,
+    pub provider: VmsProvider,
+    pub base_url: String,
+    pub auth: VmsAuth,
+    pub cameras: BTreeMap<String, String>,
+    pub min_decision: AlertDecision,
+    pub record_secs: u32,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl VmsIntegration {
+    /// Copy safe to return from the API, with credentials blanked
+    pub fn redacted(&self) -> Self {
+        let auth = match &self.auth {
+            VmsAuth::None => VmsAuth::None,
+            VmsAuth::Basic { username, .. } => VmsAuth::Basic { username: username.clone(), password: "***".to_string() },
+            VmsAuth::Bearer { .. } => VmsAuth::Bearer { token: "***".to_string() },
+        };
+        Self { auth, ..self.clone() }
+    }
+}
+
+/// What an alert is bookmarked for
+#[derive(Debug, Clone)]
+pub struct BookmarkContext {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub camera: String,
+    pub decision: AlertDecision,
+    pub at: DateTime<Utc>,
+}
+
+impl BookmarkContext {
+    pub fn memo(&self) -> String {
+        format!("Novin {:?} alert, incident {}", self.decision, self.incident_id)
+    }
+}
+
+/// A fully built VMS call
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VmsRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// Build the call that bookmarks `vms_camera` on `integration` for `context`
+pub fn build_request(integration: &VmsIntegration, vms_camera: &str, context: &BookmarkContext) -> VmsRequest {
+    let base = integration.base_url.trim_end_matches('/');
+    let mut headers = Vec::new();
+    match &integration.auth {
+        VmsAuth::Bearer { token } => headers.push(("Authorization".to_string(), format!("Bearer {}", token))),
+        VmsAuth::Basic { username, password } if !matches!(integration.provider, VmsProvider::BlueIris) => {
+            use base64::Engine;
+            let encoded = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+            headers.push(("Authorization".to_string(), format!("Basic {}", encoded)));
+        }
+        _ => {}
+    }
+
+    match &integration.provider {
+        VmsProvider::Frigate => {
+            headers.push(("Content-Type".to_string(), "application/json".to_string()));
+            let body = serde_json::json!({
+                "sub_label": context.memo(),
+                "duration": integration.record_secs,
+                "include_recording": true,
+                "draw": {},
+            });
+            VmsRequest {
+                method: "POST".to_string(),
+                url: format!("{}/api/events/{}/{}/create", base, encode(vms_camera), FRIGATE_LABEL),
+                headers,
+                body: Some(body.to_string()),
+            }
+        }
+        VmsProvider::BlueIris => {
+            let mut url = format!("{}/admin?camera={}&trigger&memo={}", base, encode(vms_camera), encode(&context.memo()));
+            if let VmsAuth::Basic { username, password } = &integration.auth {
+                url.push_str(&format!("&user={}&pw={}", encode(username), encode(password)));
+            }
+            VmsRequest { method: "GET".to_string(), url, headers, body: None }
+        }
+        VmsProvider::Generic(template) => {
+            let values = template_values(integration, vms_camera, context);
+            headers.extend(template.headers.iter().map(|(k, v)| (k.clone(), render(v, &values, |s| s.to_string()))));
+            VmsRequest {
+                method: template.method.to_ascii_uppercase(),
+                url: format!("{}{}", base, render(&template.path, &values, encode)),
+                headers,
+                body: template.body.as_ref().map(|b| render(b, &values, json_escape)),
+            }
+        }
+    }
+}
+
+fn template_values(integration: &VmsIntegration, vms_camera: &str, context: &BookmarkContext) -> Vec<(&'static str, String)> {
+    vec![
+        ("camera", vms_camera.to_string()),
+        ("home_id", context.home_id.clone()),
+        ("incident_id", context.incident_id.to_string()),
+        ("decision", format!("{:?}", context.decision)),
+        ("timestamp", context.at.to_rfc3339()),
+        ("epoch", context.at.timestamp().to_string()),
+        ("duration_secs", integration.record_secs.to_string()),
+        ("memo", context.memo()),
+    ]
+}
+
+fn render(template: &str, values: &[(&str, String)], escape: impl Fn(&str) -> String) -> String {
+    values.iter().fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), &escape(value)))
+}
+
+// Percent-encode everything but RFC 3986 unreserved characters, so values are safe in a path or a query
+fn encode(value: &str) -> String {
+    value.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+fn severity_rank(decision: &AlertDecision) -> u8 {
+    match decision {
+        AlertDecision::Critical => 3,
+        AlertDecision::Elevated => 2,
+        AlertDecision::Standard => 1,
+        AlertDecision::Wait | AlertDecision::Ignore => 0,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BookmarkRecord {
+    pub integration_id: Uuid,
+    pub integration: String,
+    pub home_id: String,
+    pub incident_id: u64,
+    pub camera: String,
+    pub vms_camera: String,
+    pub decision: AlertDecision,
+    pub at: DateTime<Utc>,
+    pub success: bool,
+    pub status: Option<u16>, // HTTP status, when the VMS answered
+    pub error: Option<String>,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IntegrationStats {
+    pub attempts: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl IntegrationStats {
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.succeeded as f64 / self.attempts as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationView {
+    pub integration: VmsIntegration, // Credentials redacted
+    pub stats: IntegrationStats,
+    pub success_rate: Option<f64>,
+}
+
+pub struct VmsBookmarker {
+    config: VmsConfig,
+    http: Client,
+    integrations: DashMap<String, Vec<VmsIntegration>>,
+    stats: DashMap<Uuid, IntegrationStats>,
+    history: DashMap<String, VecDeque<BookmarkRecord>>,
+    bookmarked: DashMap<(Uuid, u64, String), DateTime<Utc>>, // (integration, incident, camera) -> when
+}
+
+impl Default for VmsBookmarker {
+    fn default() -> Self {
+        Self::new(VmsConfig::default())
+    }
+}
+
+impl VmsBookmarker {
+    pub fn new(config: VmsConfig) -> Self {
+        let http = Client::builder().timeout(config.timeout).build().unwrap_or_else(|_| Client::new());
+        Self {
+            config,
+            http,
+            integrations: DashMap::new(),
+            stats: DashMap::new(),
+            history: DashMap::new(),
+            bookmarked: DashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &VmsConfig {
+        &self.config
+    }
+
+    pub fn create(&self, home_id: &str, request: VmsIntegrationRequest, now: DateTime<Utc>) -> Result<VmsIntegration, VmsError> {
+        if request.name.trim().is_empty() {
+            return Err(VmsError::MissingName);
+        }
+        let base_url = request.base_url.trim().trim_end_matches('/').to_string();
+        if url::Url::parse(&base_url).map(|u| !matches!(u.scheme(), "http" | "https")).unwrap_or(true) {
+            return Err(VmsError::InvalidUrl(request.base_url));
+        }
+        if request.cameras.is_empty() {
+            return Err(VmsError::NoCameras);
+        }
+        if severity_rank(&request.min_decision) == 0 {
+            return Err(VmsError::InvalidMinimum);
+        }
+        if let VmsProvider::Generic(template) = &request.provider {
+            Method::from_bytes(template.method.to_ascii_uppercase().as_bytes()).map_err(|_| VmsError::InvalidMethod(template.method.clone()))?;
+        }
+
+        let integration = VmsIntegration {
+            id: Uuid::new_v4(),
+            home_id: home_id.to_string(),
+            name: request.name.trim().to_string(),
+            provider: request.provider,
+            base_url,
+            auth: request.auth,
+            cameras: request.cameras,
+            min_decision: request.min_decision,
+            record_secs: request.record_secs,
+            enabled: request.enabled,
+            created_at: now,
+        };
+        let mut home = self.integrations.entry(home_id.to_string()).or_default();
+        if home.len() >= self.config.max_per_home {
+            return Err(VmsError::TooMany(self.config.max_per_home));
+        }
+        home.push(integration.clone());
+        Ok(integration)
+    }
+
+    pub fn remove(&self, home_id: &str, id: Uuid) -> Result<VmsIntegration, VmsError> {
+        let mut home = self.integrations.get_mut(home_id).ok_or(VmsError::NotFound(id))?;
+        let index = home.iter().position(|i| i.id == id).ok_or(VmsError::NotFound(id))?;
+        self.stats.remove(&id);
+        Ok(home.remove(index))
+    }
+
+    /// The home's integrations, credentials redacted, with their success counts
+    pub fn list(&self, home_id: &str) -> Vec<IntegrationView> {
+        let Some(home) = self.integrations.get(home_id) else {
+            return Vec::new();
+        };
+        home.iter()
+            .map(|integration| {
+                let stats = self.stats.get(&integration.id).map(|s| s.clone()).unwrap_or_default();
+                IntegrationView { integration: integration.redacted(), success_rate: stats.success_rate(), stats }
+            })
+            .collect()
+    }
+
+    /// Bookmark attempts, newest first
+    pub fn history(&self, home_id: &str, limit: usize) -> Vec<BookmarkRecord> {
+        self.history.get(home_id)
+            .map(|h| h.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Integrations (and their camera names) still to bookmark for an alert.
+    /// Each is returned once per incident and camera.
+    pub fn plan(&self, context: &BookmarkContext) -> Vec<(VmsIntegration, String)> {
+        let rank = severity_rank(&context.decision);
+        if rank == 0 {
+            return Vec::new();
+        }
+        self.bookmarked.retain(|_, at| *at + self.config.bookmarked_ttl > context.at);
+        let Some(home) = self.integrations.get(&context.home_id) else {
+            return Vec::new();
+        };
+        let mut due = Vec::new();
+        for integration in home.iter().filter(|i| i.enabled && rank >= severity_rank(&i.min_decision)) {
+            let Some(vms_camera) = integration.cameras.get(&context.camera) else {
+                continue;
+            };
+            let key = (integration.id, context.incident_id, context.camera.clone());
+            if self.bookmarked.contains_key(&key) {
+                continue;
+            }
+            self.bookmarked.insert(key, context.at);
+            due.push((integration.clone(), vms_camera.clone()));
+        }
+        due
+    }
+
+    /// Bookmark the alert on every integration that maps its camera
+    pub async fn bookmark(&self, context: &BookmarkContext) -> Vec<BookmarkRecord> {
+        let mut records = Vec::new();
+        for (integration, vms_camera) in self.plan(context) {
+            records.push(self.send(&integration, &vms_camera, context).await);
+        }
+        records
+    }
+
+    /// Send a bookmark for `camera` now, whatever the decision and whether it was already bookmarked
+    pub async fn test(&self, home_id: &str, id: Uuid, camera: &str, now: DateTime<Utc>) -> Result<BookmarkRecord, VmsError> {
+        let integration = self.integrations.get(home_id)
+            .and_then(|home| home.iter().find(|i| i.id == id).cloned())
+            .ok_or(VmsError::NotFound(id))?;
+        let vms_camera = integration.cameras.get(camera).cloned().ok_or_else(|| VmsError::UnmappedCamera(camera.to_string()))?;
+        let context = BookmarkContext {
+            home_id: home_id.to_string(),
+            incident_id: 0,
+            camera: camera.to_string(),
+            decision: integration.min_decision.clone(),
+            at: now,
+        };
+        Ok(self.send(&integration, &vms_camera, &context).await)
+    }
+
+    /// Send one planned bookmark, recording the outcome
+    pub async fn send(&self, integration: &VmsIntegration, vms_camera: &str, context: &BookmarkContext) -> BookmarkRecord {
+        let request = build_request(integration, vms_camera, context);
+        let started = Instant::now();
+        let outcome = self.execute(&request).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let (status, error) = match &outcome {
+            Ok(status) => (Some(*status), None),
+            Err(VmsError::Rejected { status, .. }) => (Some(*status), outcome.as_ref().err().map(|e| e.to_string())),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let record = BookmarkRecord {
+            integration_id: integration.id,
+            integration: integration.name.clone(),
+            home_id: context.home_id.clone(),
+            incident_id: context.incident_id,
+            camera: context.camera.clone(),
+            vms_camera: vms_camera.to_string(),
+            decision: context.decision.clone(),
+            at: context.at,
+            success: outcome.is_ok(),
+            status,
+            error,
+            latency_ms,
+        };
+        match &record.error {
+            None => info!("Bookmarked incident {} on {} camera {}", context.incident_id, integration.name, vms_camera),
+            Some(e) => warn!("Bookmark for incident {} on {} failed: {}", context.incident_id, integration.name, e),
+        }
+        self.track(&record);
+        record
+    }
+
+    async fn execute(&self, request: &VmsRequest) -> Result<u16, VmsError> {
+        let method = Method::from_bytes(request.method.as_bytes()).map_err(|_| VmsError::InvalidMethod(request.method.clone()))?;
+        let mut builder = self.http.request(method, &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+        let response = builder.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(VmsError::Rejected { status: status.as_u16(), body });
+        }
+        Ok(status.as_u16())
+    }
+
+    fn track(&self, record: &BookmarkRecord) {
+        {
+            let mut stats = self.stats.entry(record.integration_id).or_default();
+            stats.attempts += 1;
+            if record.success {
+                stats.succeeded += 1;
+                stats.last_success = Some(record.at);
+            } else {
+                stats.failed += 1;
+                stats.last_failure = Some(record.at);
+                stats.last_error = record.error.clone();
+            }
+        }
+        let mut history = self.history.entry(record.home_id.clone()).or_default();
+        history.push_back(record.clone());
+        while history.len() > self.config.history_len {
+            history.pop_front();
+        }
+    }
+}
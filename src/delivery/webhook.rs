@@ -0,0 +1,534 @@
+use crate::api::models::AlertInfo;
+use crate::automations::{AutomationExecutor, PlannedAction};
+use crate::overnight::MorningSummary;
+use crate::thinking::{IncidentLifecycleHook, IncidentTransition};
+use crate::vacation::{PresenceAction, PresenceActuator};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Novin-Signature";
+pub const DELIVERY_ID_HEADER: &str = "X-Novin-Delivery";
+pub const EVENT_TYPE_HEADER: &str = "X-Novin-Event";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    Alert,
+    MorningSummary,
+    IncidentLifecycle,
+    PresenceSimulation, // Vacation-mode device switches for home-automation hubs
+    SnapshotRequest,    // A client asked a camera bridge for a fresh frame
+    AutomationAction,   // A device command from a household automation
+}
+
+// A user-configured destination for outgoing webhooks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub id: Uuid,
+    pub home_id: String,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String, // Shared secret used for HMAC-SHA256 signing, never echoed back
+    pub event_types: Vec<WebhookEventType>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WebhookEndpoint {
+    pub fn new(home_id: &str, url: String, secret: String, event_types: Vec<WebhookEventType>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            home_id: home_id.to_string(),
+            url,
+            secret,
+            event_types,
+            enabled: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn accepts(&self, event_type: WebhookEventType) -> bool {
+        self.enabled && (self.event_types.is_empty() || self.event_types.contains(&event_type))
+    }
+}
+
+// Body POSTed to the endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub delivery_id: Uuid,
+    pub event_type: WebhookEventType,
+    pub home_id: String,
+    pub created_at: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+// One row in the delivery log, written per attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryRecord {
+    pub delivery_id: Uuid,
+    pub endpoint_id: Uuid,
+    pub home_id: String,
+    pub url: String,
+    pub event_type: WebhookEventType,
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+// A delivery that exhausted its retries, kept so an operator can redeliver it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub endpoint_id: Uuid,
+    pub url: String,
+    pub payload: WebhookPayload,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub failed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration, // Doubled after every failed attempt
+    pub request_timeout: Duration,
+    pub log_capacity: usize,       // Most recent attempts kept for the API
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            request_timeout: Duration::from_secs(5),
+            log_capacity: 1000,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum WebhookUrlError {
+    #[error("Invalid webhook URL: {0}")]
+    Invalid(String),
+
+    #[error("Webhook URLs must use https")]
+    InsecureScheme,
+
+    #[error("Webhook host {0} could not be resolved")]
+    Unresolvable(String),
+
+    #[error("Webhook host {host} resolves to non-public address {addr}")]
+    NonPublicAddress { host: String, addr: IpAddr },
+}
+
+/// Check a user-supplied endpoint before anything is sent to it: https only,
+/// and every address the host resolves to must be public, so deliveries can't
+/// be pointed at loopback, private networks or link-local metadata services
+pub async fn validate_webhook_url(raw: &str) -> Result<url::Url, WebhookUrlError> {
+    let url = url::Url::parse(raw).map_err(|e| WebhookUrlError::Invalid(e.to_string()))?;
+    if url.scheme() != "https" {
+        return Err(WebhookUrlError::InsecureScheme);
+    }
+    let host = match url.host() {
+        Some(url::Host::Domain(domain)) => domain.to_string(),
+        Some(url::Host::Ipv4(ip)) => ip.to_string(),
+        Some(url::Host::Ipv6(ip)) => ip.to_string(),
+        None => return Err(WebhookUrlError::Invalid("missing host".to_string())),
+    };
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<IpAddr> = tokio::net::lookup_host((host.as_str(), port)).await
+        .map_err(|_| WebhookUrlError::Unresolvable(host.clone()))?
+        .map(|a| a.ip())
+        .collect();
+    if addrs.is_empty() {
+        return Err(WebhookUrlError::Unresolvable(host));
+    }
+    if let Some(addr) = addrs.into_iter().find(|a| !is_public_address(*a)) {
+        return Err(WebhookUrlError::NonPublicAddress { host, addr });
+    }
+    Ok(url)
+}
+
+/// False for loopback, private, shared (CGNAT), link-local, unspecified and broadcast addresses
+pub fn is_public_address(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(ip) => {
+            let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64; // 100.64.0.0/10
+            !(ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified() || ip.is_broadcast() || shared)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(v4));
+            }
+            let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00; // fc00::/7
+            let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;   // fe80::/10
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+// Resolves webhook hosts for every connection and refuses non-public answers, so
+// a host that passed validation can't later be re-pointed (DNS rebinding) at
+// loopback, private networks or the metadata service
+struct PublicOnlyResolver;
+
+impl reqwest::dns::Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if addrs.is_empty() {
+                return Err(Box::new(WebhookUrlError::Unresolvable(host)) as Box<dyn std::error::Error + Send + Sync>);
+            }
+            if let Some(addr) = addrs.iter().find(|a| !is_public_address(a.ip())) {
+                return Err(Box::new(WebhookUrlError::NonPublicAddress { host, addr: addr.ip() }) as Box<dyn std::error::Error + Send + Sync>);
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Compute the signature header value: `t=<unix_ts>,v1=<hex(hmac_sha256(secret, "<ts>.<body>"))>`
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Verify a signature header as a receiver would, rejecting stale timestamps
+pub fn verify_signature(secret: &str, header: &str, body: &[u8], tolerance_secs: i64) -> bool {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.split_once('=') {
+            Some(("t", v)) => timestamp = v.parse::<i64>().ok(),
+            Some(("v1", v)) => signature = hex::decode(v).ok(),
+            _ => {}
+        }
+    }
+
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return false;
+    };
+    if (Utc::now().timestamp() - timestamp).abs() > tolerance_secs {
+        return false;
+    }
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+// Signs and delivers alert/summary payloads to registered endpoints
+pub struct WebhookDispatcher {
+    client: Client,
+    config: WebhookConfig,
+    endpoints: RwLock<HashMap<String, Vec<WebhookEndpoint>>>, // home_id -> endpoints
+    log: RwLock<VecDeque<WebhookDeliveryRecord>>,
+    dead_letters: RwLock<VecDeque<DeadLetter>>, // Bounded by log_capacity, oldest dropped first
+}
+
+impl WebhookDispatcher {
+    pub fn new(config: WebhookConfig) -> Self {
+        let client = Client::builder()
+            .timeout(config.request_timeout)
+            .user_agent("Novin-Webhooks/1.0")
+            // A redirect would send the signed payload to a target that was never validated
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicOnlyResolver))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            config,
+            endpoints: RwLock::new(HashMap::new()),
+            log: RwLock::new(VecDeque::new()),
+            dead_letters: RwLock::new(VecDeque::new()),
+        }
+    }
+
+    pub async fn register_endpoint(&self, endpoint: WebhookEndpoint) -> Uuid {
+        let id = endpoint.id;
+        self.endpoints.write().await
+            .entry(endpoint.home_id.clone())
+            .or_default()
+            .push(endpoint);
+        id
+    }
+
+    pub async fn remove_endpoint(&self, home_id: &str, endpoint_id: Uuid) -> bool {
+        let mut endpoints = self.endpoints.write().await;
+        match endpoints.get_mut(home_id) {
+            Some(list) => {
+                let before = list.len();
+                list.retain(|e| e.id != endpoint_id);
+                list.len() != before
+            }
+            None => false,
+        }
+    }
+
+    pub async fn list_endpoints(&self, home_id: &str) -> Vec<WebhookEndpoint> {
+        self.endpoints.read().await.get(home_id).cloned().unwrap_or_default()
+    }
+
+    /// Replace an endpoint's signing secret with a fresh random one and return it
+    pub async fn rotate_secret(&self, home_id: &str, endpoint_id: Uuid) -> Option<String> {
+        let mut endpoints = self.endpoints.write().await;
+        let endpoint = endpoints.get_mut(home_id)?.iter_mut().find(|e| e.id == endpoint_id)?;
+        endpoint.secret = hex::encode(rand::random::<[u8; 32]>());
+        Some(endpoint.secret.clone())
+    }
+
+    /// Deliveries that exhausted their retries, oldest first; all homes when `home_id` is None
+    pub async fn dead_letters(&self, home_id: Option<&str>) -> Vec<DeadLetter> {
+        self.dead_letters.read().await
+            .iter()
+            .filter(|d| home_id.map_or(true, |h| d.payload.home_id == h))
+            .cloned()
+            .collect()
+    }
+
+    /// Redeliver dead letters to their endpoints. Letters whose endpoint was
+    /// removed are discarded; ones that fail again go back on the queue.
+    pub async fn flush_dead_letters(&self, home_id: Option<&str>) -> Vec<WebhookDeliveryRecord> {
+        let letters: Vec<DeadLetter> = {
+            let mut queue = self.dead_letters.write().await;
+            let (matching, rest): (VecDeque<DeadLetter>, VecDeque<DeadLetter>) = queue.drain(..)
+                .partition(|d| home_id.map_or(true, |h| d.payload.home_id == h));
+            *queue = rest;
+            matching.into()
+        };
+
+        let mut results = Vec::new();
+        for letter in letters {
+            let endpoint = self.list_endpoints(&letter.payload.home_id).await
+                .into_iter()
+                .find(|e| e.id == letter.endpoint_id);
+            match endpoint {
+                Some(endpoint) => results.push(self.deliver_with_retries(&endpoint, &letter.payload).await),
+                None => info!(delivery=%letter.payload.delivery_id, "dropping dead letter for removed endpoint"),
+            }
+        }
+        results
+    }
+
+    /// Deliver an alert to every endpoint of the home subscribed to alerts
+    pub async fn dispatch_alert(&self, alert: &AlertInfo) -> Vec<WebhookDeliveryRecord> {
+        let data = serde_json::to_value(alert).unwrap_or(serde_json::Value::Null);
+        self.dispatch(&alert.home_id, WebhookEventType::Alert, data).await
+    }
+
+    /// Deliver a morning summary to every endpoint of the home subscribed to summaries
+    pub async fn dispatch_summary(&self, summary: &MorningSummary) -> Vec<WebhookDeliveryRecord> {
+        let data = serde_json::to_value(summary).unwrap_or(serde_json::Value::Null);
+        self.dispatch(&summary.home_id, WebhookEventType::MorningSummary, data).await
+    }
+
+    /// Deliver an incident state change to every endpoint of the home subscribed to lifecycle events
+    pub async fn dispatch_transition(&self, transition: &IncidentTransition) -> Vec<WebhookDeliveryRecord> {
+        let data = serde_json::to_value(transition).unwrap_or(serde_json::Value::Null);
+        self.dispatch(&transition.home_id, WebhookEventType::IncidentLifecycle, data).await
+    }
+
+    /// Ask a home's automation endpoints to switch a device for presence simulation
+    pub async fn dispatch_presence(&self, action: &PresenceAction) -> Vec<WebhookDeliveryRecord> {
+        let data = serde_json::to_value(action).unwrap_or(serde_json::Value::Null);
+        self.dispatch(&action.home_id, WebhookEventType::PresenceSimulation, data).await
+    }
+
+    /// Send an automation's device command to the home's automation hub
+    pub async fn dispatch_automation(&self, action: &PlannedAction) -> Vec<WebhookDeliveryRecord> {
+        let data = serde_json::to_value(action).unwrap_or(serde_json::Value::Null);
+        self.dispatch(&action.home_id, WebhookEventType::AutomationAction, data).await
+    }
+
+    /// Ask a home's camera bridge to capture a frame; it arrives later as a normal event
+    pub async fn dispatch_snapshot_request(&self, home_id: &str, camera: &str, request_id: &str) -> Vec<WebhookDeliveryRecord> {
+        let data = serde_json::json!({ "camera": camera, "request_id": request_id });
+        self.dispatch(home_id, WebhookEventType::SnapshotRequest, data).await
+    }
+
+    /// Send a test alert to every endpoint of the home subscribed to alerts
+    pub async fn dispatch_test(&self, home_id: &str, title: &str, body: &str) -> Vec<WebhookDeliveryRecord> {
+        let data = serde_json::json!({ "test": true, "title": title, "body": body });
+        self.dispatch(home_id, WebhookEventType::Alert, data).await
+    }
+
+    /// Query the delivery log, newest first
+    pub async fn delivery_log(&self, home_id: &str, limit: usize) -> Vec<WebhookDeliveryRecord> {
+        self.log.read().await
+            .iter()
+            .rev()
+            .filter(|r| r.home_id == home_id)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    async fn dispatch(&self, home_id: &str, event_type: WebhookEventType, data: serde_json::Value) -> Vec<WebhookDeliveryRecord> {
+        let targets: Vec<WebhookEndpoint> = self.list_endpoints(home_id).await
+            .into_iter()
+            .filter(|e| e.accepts(event_type))
+            .collect();
+
+        let mut results = Vec::new();
+        for endpoint in targets {
+            let payload = WebhookPayload {
+                delivery_id: Uuid::new_v4(),
+                event_type,
+                home_id: home_id.to_string(),
+                created_at: Utc::now(),
+                data: data.clone(),
+            };
+            results.push(self.deliver_with_retries(&endpoint, &payload).await);
+        }
+        results
+    }
+
+    async fn deliver_with_retries(&self, endpoint: &WebhookEndpoint, payload: &WebhookPayload) -> WebhookDeliveryRecord {
+        let body = serde_json::to_vec(payload).unwrap_or_default();
+        let mut backoff = self.config.initial_backoff;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let record = self.attempt_delivery(endpoint, payload, &body, attempt).await;
+            let retryable = match record.status_code {
+                Some(code) => code == 429 || code >= 500,
+                None => true, // Network errors and timeouts
+            };
+            self.append_log(record.clone()).await;
+
+            if record.success {
+                info!(url=%endpoint.url, delivery=%payload.delivery_id, attempt, "webhook delivered");
+                return record;
+            }
+            if !retryable || attempt >= self.config.max_attempts {
+                warn!(url=%endpoint.url, delivery=%payload.delivery_id, attempt, err=?record.error, "webhook delivery failed");
+                self.dead_letter(endpoint, payload, &record).await;
+                return record;
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    async fn attempt_delivery(&self, endpoint: &WebhookEndpoint, payload: &WebhookPayload, body: &[u8], attempt: u32) -> WebhookDeliveryRecord {
+        // Re-sign per attempt so the timestamp stays within receivers' tolerance
+        let signature = sign_payload(&endpoint.secret, Utc::now().timestamp(), body);
+        let event_type = serde_json::to_value(payload.event_type)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+
+        let result = self.client.post(&endpoint.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(DELIVERY_ID_HEADER, payload.delivery_id.to_string())
+            .header(EVENT_TYPE_HEADER, event_type)
+            .body(body.to_vec())
+            .send()
+            .await;
+
+        let (status_code, success, error) = match result {
+            Ok(resp) => {
+                let status = resp.status();
+                (Some(status.as_u16()), status.is_success(), (!status.is_success()).then(|| format!("HTTP {}", status)))
+            }
+            Err(e) => (None, false, Some(e.to_string())),
+        };
+
+        WebhookDeliveryRecord {
+            delivery_id: payload.delivery_id,
+            endpoint_id: endpoint.id,
+            home_id: payload.home_id.clone(),
+            url: endpoint.url.clone(),
+            event_type: payload.event_type,
+            attempt,
+            status_code,
+            success,
+            error,
+            attempted_at: Utc::now(),
+        }
+    }
+
+    async fn dead_letter(&self, endpoint: &WebhookEndpoint, payload: &WebhookPayload, record: &WebhookDeliveryRecord) {
+        let mut queue = self.dead_letters.write().await;
+        if queue.len() >= self.config.log_capacity {
+            queue.pop_front();
+        }
+        queue.push_back(DeadLetter {
+            endpoint_id: endpoint.id,
+            url: endpoint.url.clone(),
+            payload: payload.clone(),
+            attempts: record.attempt,
+            last_error: record.error.clone(),
+            failed_at: record.attempted_at,
+        });
+    }
+
+    async fn append_log(&self, record: WebhookDeliveryRecord) {
+        let mut log = self.log.write().await;
+        if log.len() >= self.config.log_capacity {
+            log.pop_front();
+        }
+        log.push_back(record);
+    }
+}
+
+impl Default for WebhookDispatcher {
+    fn default() -> Self {
+        Self::new(WebhookConfig::default())
+    }
+}
+
+#[async_trait]
+impl IncidentLifecycleHook for WebhookDispatcher {
+    async fn on_transition(&self, transition: &IncidentTransition) {
+        self.dispatch_transition(transition).await;
+    }
+}
+
+#[async_trait]
+impl PresenceActuator for WebhookDispatcher {
+    async fn actuate(&self, action: &PresenceAction) {
+        self.dispatch_presence(action).await;
+    }
+}
+
+#[async_trait]
+impl AutomationExecutor for WebhookDispatcher {
+    async fn execute(&self, action: &PlannedAction) -> Result<(), String> {
+        let records = self.dispatch_automation(action).await;
+        if records.is_empty() {
+            return Err("no webhook endpoint accepts automation actions".to_string());
+        }
+        if records.iter().any(|r| r.success) {
+            return Ok(());
+        }
+        Err(records.iter().filter_map(|r| r.error.clone()).next().unwrap_or_else(|| "every endpoint rejected the action".to_string()))
+    }
+}
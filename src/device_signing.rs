@@ -0,0 +1,225 @@
+// src/device_signing.rs
+
+// Ed25519 signatures on events from enrolled edge devices. Each device
+// enrolls a public key for its home; it signs a canonical encoding of every
+// RawEvent it sends and the pipeline verifies the signature before anything
+// else runs. A bad signature, an unknown or revoked device, or a stale
+// timestamp is rejected outright. Unsigned events from older devices are
+// accepted, down-weighted or rejected according to UnsignedPolicy, so a home
+// can migrate device by device before it turns rejection on.
+
+use crate::pipeline::RawEvent;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SigningError {
+    #[error("Device {0} is not enrolled")]
+    UnknownDevice(String),
+    #[error("Device {0} has been revoked")]
+    Revoked(String),
+    #[error("Device {device} is enrolled for another home than {home}")]
+    HomeMismatch { device: String, home: String },
+    #[error("Invalid public key: {0}")]
+    InvalidKey(String),
+    #[error("Signature does not verify for device {0}")]
+    BadSignature(String),
+    #[error("Event timestamp is {0}s away from server time")]
+    ClockSkew(i64),
+    #[error("Unsigned events are not accepted")]
+    Unsigned,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsignedPolicy {
+    Accept,
+    #[default]
+    DownWeight,
+    Reject,
+}
+
+#[derive(Debug, Clone)]
+pub struct SigningConfig {
+    pub unsigned: UnsignedPolicy,
+    pub unsigned_weight: f64,     // Evidence reliability for unsigned events under DownWeight
+    pub max_clock_skew_secs: i64, // Bounds replay of captured signed events
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        Self {
+            unsigned: UnsignedPolicy::DownWeight,
+            unsigned_weight: 0.5,
+            max_clock_skew_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceKey {
+    pub device_id: String,
+    pub home_id: String,
+    pub public_key: String, // Base64 of the 32-byte Ed25519 key
+    pub enrolled_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventSignature {
+    pub device_id: String,
+    pub signature: String, // Base64 of the 64-byte signature over canonical_bytes
+}
+
+// How far an event's evidence can be trusted after verification
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventTrust {
+    Signed { device_id: String },
+    Unsigned { weight: f64 },
+}
+
+impl EventTrust {
+    pub fn evidence_weight(&self) -> f64 {
+        match self {
+            EventTrust::Signed { .. } => 1.0,
+            EventTrust::Unsigned { weight } => *weight,
+        }
+    }
+}
+
+/// The bytes a device signs: a versioned, newline-separated encoding of the
+/// event's identifying fields with the payload hashed
+pub fn canonical_bytes(event: &RawEvent) -> Vec<u8> {
+    format!(
+        "novin-event-v1\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        event.event_id,
+        event.home_id,
+        event.sensor_id,
+        event.user_id,
+        event.timestamp,
+        hex::encode(Sha256::digest(event.data.as_bytes())),
+        event.image_url.as_deref().unwrap_or(""),
+    ).into_bytes()
+}
+
+/// Sign an event as an enrolled device would
+pub fn sign_event(device_id: &str, key: &SigningKey, event: &RawEvent) -> EventSignature {
+    EventSignature {
+        device_id: device_id.to_string(),
+        signature: BASE64.encode(key.sign(&canonical_bytes(event)).to_bytes()),
+    }
+}
+
+#[derive(Default)]
+pub struct DeviceKeyRegistry {
+    config: SigningConfig,
+    devices: DashMap<String, DeviceKey>,
+}
+
+impl DeviceKeyRegistry {
+    pub fn new(config: SigningConfig) -> Self {
+        Self { config, devices: DashMap::new() }
+    }
+
+    pub fn config(&self) -> &SigningConfig {
+        &self.config
+    }
+
+    /// Enroll or re-key a device; re-enrolling clears a revocation
+    pub fn enroll(&self, home_id: &str, device_id: &str, public_key: &str, now: DateTime<Utc>) -> Result<DeviceKey, SigningError> {
+        parse_key(public_key)?;
+        if let Some(existing) = self.devices.get(device_id) {
+            if existing.home_id != home_id {
+                return Err(SigningError::HomeMismatch { device: device_id.to_string(), home: home_id.to_string() });
+            }
+        }
+        let key = DeviceKey {
+            device_id: device_id.to_string(),
+            home_id: home_id.to_string(),
+            public_key: public_key.to_string(),
+            enrolled_at: now,
+            revoked_at: None,
+        };
+        self.devices.insert(device_id.to_string(), key.clone());
+        Ok(key)
+    }
+
+    pub fn revoke(&self, home_id: &str, device_id: &str, now: DateTime<Utc>) -> Result<DeviceKey, SigningError> {
+        let mut key = self.devices.get_mut(device_id)
+            .filter(|k| k.home_id == home_id)
+            .ok_or_else(|| SigningError::UnknownDevice(device_id.to_string()))?;
+        key.revoked_at.get_or_insert(now);
+        Ok(key.clone())
+    }
+
+    pub fn devices(&self, home_id: &str) -> Vec<DeviceKey> {
+        let mut keys: Vec<DeviceKey> = self.devices.iter().filter(|k| k.home_id == home_id).map(|k| k.clone()).collect();
+        keys.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        keys
+    }
+
+    pub fn all_devices(&self) -> Vec<DeviceKey> {
+        let mut keys: Vec<DeviceKey> = self.devices.iter().map(|k| k.clone()).collect();
+        keys.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        keys
+    }
+
+    /// Replace all enrolled keys, e.g. from a backup; keys are checked first
+    pub fn restore(&self, keys: Vec<DeviceKey>) -> Result<(), SigningError> {
+        for key in &keys {
+            parse_key(&key.public_key)?;
+        }
+        self.devices.clear();
+        for key in keys {
+            self.devices.insert(key.device_id.clone(), key);
+        }
+        Ok(())
+    }
+
+    /// Verify an event's signature, or apply the unsigned policy when it has none
+    pub fn verify(&self, event: &RawEvent, signature: Option<&EventSignature>, now: DateTime<Utc>) -> Result<EventTrust, SigningError> {
+        let Some(signature) = signature else {
+            return match self.config.unsigned {
+                UnsignedPolicy::Accept => Ok(EventTrust::Unsigned { weight: 1.0 }),
+                UnsignedPolicy::DownWeight => Ok(EventTrust::Unsigned { weight: self.config.unsigned_weight }),
+                UnsignedPolicy::Reject => Err(SigningError::Unsigned),
+            };
+        };
+
+        let device = self.devices.get(&signature.device_id)
+            .map(|k| k.clone())
+            .ok_or_else(|| SigningError::UnknownDevice(signature.device_id.clone()))?;
+        if device.revoked_at.is_some() {
+            return Err(SigningError::Revoked(device.device_id));
+        }
+        if device.home_id != event.home_id {
+            return Err(SigningError::HomeMismatch { device: device.device_id, home: event.home_id.clone() });
+        }
+
+        let bytes: [u8; 64] = BASE64.decode(&signature.signature).ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| SigningError::BadSignature(device.device_id.clone()))?;
+        parse_key(&device.public_key)?
+            .verify(&canonical_bytes(event), &Signature::from_bytes(&bytes))
+            .map_err(|_| SigningError::BadSignature(device.device_id.clone()))?;
+
+        // Checked after the signature so the timestamp itself is authenticated
+        let skew = event.timestamp - now.timestamp();
+        if skew.abs() > self.config.max_clock_skew_secs {
+            return Err(SigningError::ClockSkew(skew));
+        }
+        Ok(EventTrust::Signed { device_id: device.device_id })
+    }
+}
+
+fn parse_key(public_key: &str) -> Result<VerifyingKey, SigningError> {
+    let bytes: [u8; 32] = BASE64.decode(public_key)
+        .map_err(|e| SigningError::InvalidKey(e.to_string()))?
+        .try_into()
+        .map_err(|_| SigningError::InvalidKey("expected 32 bytes".to_string()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| SigningError::InvalidKey(e.to_string()))
+}
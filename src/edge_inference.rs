@@ -0,0 +1,354 @@
+// src/edge_inference.rs
+
+// On-device inference for when the VPS path is disabled. Person and vehicle
+// detection and face embedding run from local ONNX model files; which models
+// run is chosen per ProcessingLevel. The tract backend is behind the `onnx`
+// feature; without it the engine only accepts models supplied in code.
+
+use crate::pipeline::ProcessingLevel;
+use crate::validation::DaemonConfig;
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeModelKind {
+    PersonDetector,
+    VehicleDetector,
+    FaceEmbedder,
+    Liveness, // Face presentation-attack detection; one row holding the live probability
+}
+
+impl EdgeModelKind {
+    fn label(&self) -> &'static str {
+        match self {
+            EdgeModelKind::PersonDetector => "person",
+            EdgeModelKind::VehicleDetector => "vehicle",
+            EdgeModelKind::FaceEmbedder => "face",
+            EdgeModelKind::Liveness => "liveness",
+        }
+    }
+}
+
+// One model file on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeModelSpec {
+    pub kind: EdgeModelKind,
+    pub file: String,           // Relative to EdgeInferenceConfig::model_dir
+    pub input_size: (u32, u32), // Width, height the model expects
+    #[serde(default)]
+    pub sha256: Option<String>, // Verified before loading when set
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EdgeInferenceConfig {
+    pub model_dir: PathBuf,
+    pub models: Vec<EdgeModelSpec>,
+    pub basic: Vec<EdgeModelKind>, // Models run at each ProcessingLevel
+    pub advanced: Vec<EdgeModelKind>,
+    pub priority: Vec<EdgeModelKind>,
+    pub score_threshold: f32,
+}
+
+impl Default for EdgeInferenceConfig {
+    fn default() -> Self {
+        Self {
+            model_dir: PathBuf::from("models"),
+            models: Vec::new(),
+            basic: vec![EdgeModelKind::PersonDetector],
+            advanced: vec![EdgeModelKind::PersonDetector, EdgeModelKind::VehicleDetector],
+            priority: vec![EdgeModelKind::PersonDetector, EdgeModelKind::VehicleDetector, EdgeModelKind::FaceEmbedder, EdgeModelKind::Liveness],
+            score_threshold: 0.5,
+        }
+    }
+}
+
+impl EdgeInferenceConfig {
+    pub fn models_for(&self, level: ProcessingLevel) -> &[EdgeModelKind] {
+        match level {
+            ProcessingLevel::Basic => &self.basic,
+            ProcessingLevel::Advanced => &self.advanced,
+            ProcessingLevel::Priority => &self.priority,
+        }
+    }
+
+    pub fn path_for(&self, spec: &EdgeModelSpec) -> PathBuf {
+        self.model_dir.join(&spec.file)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Detection {
+    pub label: String,
+    pub score: f32,
+    pub bbox: [f32; 4], // x1, y1, x2, y2 in model input pixels
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EdgeInferenceResult {
+    pub detections: Vec<Detection>,
+    pub face_embedding: Option<Vec<f32>>,
+    pub liveness: Option<f32>,
+    pub models_run: Vec<EdgeModelKind>,
+    pub latency_ms: u64,
+}
+
+#[derive(Error, Debug)]
+pub enum EdgeError {
+    #[error("Model file not found: {0}")]
+    ModelMissing(PathBuf),
+
+    #[error("Checksum mismatch for {0}")]
+    ChecksumMismatch(PathBuf),
+
+    #[error("Could not load model: {0}")]
+    Load(String),
+
+    #[error("Inference failed: {0}")]
+    Inference(String),
+
+    #[error("Could not decode image: {0}")]
+    Image(String),
+
+    #[error("Invalid edge inference config: {0}")]
+    Config(String),
+
+    #[error("Built without the `onnx` feature")]
+    FeatureDisabled,
+
+    #[error("Model file I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A loaded model; returns the first output as rows of its last dimension
+pub trait EdgeModel: Send + Sync {
+    fn input_size(&self) -> (u32, u32);
+    fn run(&self, image: &RgbImage) -> Result<Vec<Vec<f32>>, EdgeError>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelFileStatus {
+    pub kind: EdgeModelKind,
+    pub path: PathBuf,
+    pub present: bool,
+    pub size_bytes: u64,
+    pub checksum_ok: Option<bool>, // None when no checksum is configured
+}
+
+// Model file checks and installs
+pub struct ModelManager;
+
+impl ModelManager {
+    fn sha256_file(path: &Path) -> Result<String, EdgeError> {
+        let bytes = std::fs::read(path)?;
+        Ok(hex::encode(Sha256::digest(&bytes)))
+    }
+
+    /// Check a model file exists and matches its checksum
+    pub fn verify(config: &EdgeInferenceConfig, spec: &EdgeModelSpec) -> Result<PathBuf, EdgeError> {
+        let path = config.path_for(spec);
+        if !path.is_file() {
+            return Err(EdgeError::ModelMissing(path));
+        }
+        if let Some(expected) = &spec.sha256 {
+            if !Self::sha256_file(&path)?.eq_ignore_ascii_case(expected) {
+                return Err(EdgeError::ChecksumMismatch(path));
+            }
+        }
+        Ok(path)
+    }
+
+    /// Status of every configured model file
+    pub fn status(config: &EdgeInferenceConfig) -> Vec<ModelFileStatus> {
+        config.models.iter()
+            .map(|spec| {
+                let path = config.path_for(spec);
+                let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let present = path.is_file();
+                let checksum_ok = match (&spec.sha256, present) {
+                    (Some(expected), true) => Some(Self::sha256_file(&path).map(|h| h.eq_ignore_ascii_case(expected)).unwrap_or(false)),
+                    _ => None,
+                };
+                ModelFileStatus { kind: spec.kind, path, present, size_bytes, checksum_ok }
+            })
+            .collect()
+    }
+
+    /// Write a downloaded model into place (via a temp file so a crash never leaves half a model)
+    pub fn install(config: &EdgeInferenceConfig, spec: &EdgeModelSpec, bytes: &[u8]) -> Result<PathBuf, EdgeError> {
+        let path = config.path_for(spec);
+        if let Some(expected) = &spec.sha256 {
+            if !hex::encode(Sha256::digest(bytes)).eq_ignore_ascii_case(expected) {
+                return Err(EdgeError::ChecksumMismatch(path));
+            }
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+}
+
+#[cfg(feature = "onnx")]
+mod tract_backend {
+    use super::{EdgeError, EdgeModel};
+    use image::RgbImage;
+    use std::path::Path;
+    use tract_onnx::prelude::*;
+
+    pub struct TractModel {
+        plan: TypedRunnableModel<TypedModel>,
+        input: (u32, u32),
+    }
+
+    impl TractModel {
+        pub fn load(path: &Path, input: (u32, u32)) -> Result<Self, EdgeError> {
+            let load = |e: TractError| EdgeError::Load(format!("{}: {}", path.display(), e));
+            let plan = tract_onnx::onnx()
+                .model_for_path(path).map_err(load)?
+                .with_input_fact(0, f32::fact([1, 3, input.1 as usize, input.0 as usize]).into()).map_err(load)?
+                .into_optimized().map_err(load)?
+                .into_runnable().map_err(load)?;
+            Ok(Self { plan, input })
+        }
+    }
+
+    impl EdgeModel for TractModel {
+        fn input_size(&self) -> (u32, u32) {
+            self.input
+        }
+
+        fn run(&self, image: &RgbImage) -> Result<Vec<Vec<f32>>, EdgeError> {
+            let (w, h) = self.input;
+            // NCHW, scaled to 0..1
+            let tensor: Tensor = tract_ndarray::Array4::from_shape_fn((1, 3, h as usize, w as usize), |(_, c, y, x)| {
+                image.get_pixel(x as u32, y as u32)[c] as f32 / 255.0
+            }).into();
+            let outputs = self.plan.run(tvec!(tensor.into()))
+                .map_err(|e| EdgeError::Inference(e.to_string()))?;
+            let view = outputs[0].to_array_view::<f32>()
+                .map_err(|e| EdgeError::Inference(e.to_string()))?;
+            let cols = view.shape().last().copied().unwrap_or(1).max(1);
+            let flat: Vec<f32> = view.iter().copied().collect();
+            Ok(flat.chunks(cols).map(|c| c.to_vec()).collect())
+        }
+    }
+}
+
+pub struct EdgeInferenceEngine {
+    config: EdgeInferenceConfig,
+    models: HashMap<EdgeModelKind, Arc<dyn EdgeModel>>,
+}
+
+impl EdgeInferenceEngine {
+    /// An engine with no models; add them with `with_model`
+    pub fn empty(config: EdgeInferenceConfig) -> Self {
+        Self { config, models: HashMap::new() }
+    }
+
+    /// Verify and load every configured model file
+    pub fn load(config: EdgeInferenceConfig) -> Result<Self, EdgeError> {
+        let mut engine = Self::empty(config);
+        for spec in engine.config.models.clone() {
+            let path = ModelManager::verify(&engine.config, &spec)?;
+            let model = Self::load_file(&path, spec.input_size)?;
+            info!("Loaded edge model {:?} from {}", spec.kind, path.display());
+            engine.models.insert(spec.kind, model);
+        }
+        Ok(engine)
+    }
+
+    /// Load and warm up the models named in a daemon config file; None when it names none
+    pub fn from_config_file(path: &Path) -> Result<Option<Self>, EdgeError> {
+        let config = DaemonConfig::load_validated(path).map_err(|e| EdgeError::Config(e.to_string()))?;
+        let Some(edge) = config.edge_inference else {
+            return Ok(None);
+        };
+        let engine = Self::load(edge)?;
+        let took = engine.warm_up()?;
+        info!("Edge models ready: {:?} (warm-up {}ms)", engine.loaded(), took.as_millis());
+        Ok(Some(engine))
+    }
+
+    #[cfg(feature = "onnx")]
+    fn load_file(path: &Path, input: (u32, u32)) -> Result<Arc<dyn EdgeModel>, EdgeError> {
+        Ok(Arc::new(tract_backend::TractModel::load(path, input)?))
+    }
+
+    #[cfg(not(feature = "onnx"))]
+    fn load_file(_path: &Path, _input: (u32, u32)) -> Result<Arc<dyn EdgeModel>, EdgeError> {
+        Err(EdgeError::FeatureDisabled)
+    }
+
+    pub fn with_model(mut self, kind: EdgeModelKind, model: Arc<dyn EdgeModel>) -> Self {
+        self.models.insert(kind, model);
+        self
+    }
+
+    pub fn loaded(&self) -> Vec<EdgeModelKind> {
+        let mut kinds: Vec<EdgeModelKind> = self.models.keys().copied().collect();
+        kinds.sort_by_key(|k| k.label());
+        kinds
+    }
+
+    /// Run each model once on a blank frame so the first real event is not slow
+    pub fn warm_up(&self) -> Result<Duration, EdgeError> {
+        let started = Instant::now();
+        for model in self.models.values() {
+            let (w, h) = model.input_size();
+            model.run(&RgbImage::new(w, h))?;
+        }
+        Ok(started.elapsed())
+    }
+
+    /// Run the models selected for `level` on an encoded image
+    pub fn infer(&self, image_bytes: &[u8], level: ProcessingLevel) -> Result<EdgeInferenceResult, EdgeError> {
+        let started = Instant::now();
+        let image = image::load_from_memory(image_bytes)
+            .map_err(|e| EdgeError::Image(e.to_string()))?
+            .to_rgb8();
+
+        let mut result = EdgeInferenceResult::default();
+        for kind in self.config.models_for(level) {
+            let Some(model) = self.models.get(kind) else { continue };
+            let (w, h) = model.input_size();
+            let resized = image::imageops::resize(&image, w, h, image::imageops::FilterType::Triangle);
+            let rows = model.run(&resized)?;
+            match kind {
+                EdgeModelKind::FaceEmbedder => {
+                    result.face_embedding = rows.into_iter().next().map(normalize);
+                }
+                EdgeModelKind::Liveness => {
+                    result.liveness = rows.first().and_then(|row| row.first()).map(|p| p.clamp(0.0, 1.0));
+                }
+                // Detector rows are [x1, y1, x2, y2, score, ...]
+                _ => result.detections.extend(rows.into_iter()
+                    .filter(|row| row.len() >= 5 && row[4] >= self.config.score_threshold)
+                    .map(|row| Detection {
+                        label: kind.label().to_string(),
+                        score: row[4],
+                        bbox: [row[0], row[1], row[2], row[3]],
+                    })),
+            }
+            result.models_run.push(*kind);
+        }
+        result.latency_ms = started.elapsed().as_millis() as u64;
+        Ok(result)
+    }
+}
+
+fn normalize(v: Vec<f32>) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 { v.into_iter().map(|x| x / norm).collect() } else { v }
+}
@@ -0,0 +1,235 @@
+// src/embeddings.rs
+//
+// Per-home store of appearance and gait embeddings returned by the VPS. New
+// detections are matched against known entities by cosine similarity so the same
+// person can be followed across cameras and visits without face recognition.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EmbeddingError {
+    #[error("Embedding has {got} dimensions, store expects {expected}")]
+    DimensionMismatch { expected: usize, got: usize },
+
+    #[error("Embedding is empty, zero or contains non-finite values")]
+    InvalidVector,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EmbeddingKind {
+    Appearance, // Clothing/body appearance re-id vector
+    Gait,       // Walking pattern vector from a short track
+}
+
+#[derive(Debug, Clone)]
+pub struct EmbeddingStoreConfig {
+    pub max_per_entity: usize,       // Oldest samples are dropped beyond this
+    pub appearance_threshold: f32,   // Minimum cosine similarity for a match
+    pub gait_threshold: f32,
+    pub appearance_weight: f32,      // Blend when both kinds are available
+}
+
+impl Default for EmbeddingStoreConfig {
+    fn default() -> Self {
+        Self {
+            max_per_entity: 20,
+            appearance_threshold: 0.82,
+            gait_threshold: 0.78,
+            appearance_weight: 0.6,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StoredEmbedding {
+    entity_id: Uuid,
+    kind: EmbeddingKind,
+    vector: Vec<f32>, // L2-normalized
+    observed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingMatch {
+    pub entity_id: Uuid,
+    pub similarity: f32,
+}
+
+// Embeddings extracted for a single detection
+#[derive(Debug, Clone, Default)]
+pub struct DetectionEmbeddings {
+    pub appearance: Option<Vec<f32>>,
+    pub gait: Option<Vec<f32>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityLink {
+    pub entity_id: Uuid,
+    pub similarity: f32,
+    pub is_new: bool,
+}
+
+pub(crate) fn normalize(vector: &[f32]) -> Result<Vec<f32>, EmbeddingError> {
+    if vector.is_empty() || vector.iter().any(|v| !v.is_finite()) {
+        return Err(EmbeddingError::InvalidVector);
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm <= f32::EPSILON {
+        return Err(EmbeddingError::InvalidVector);
+    }
+    Ok(vector.iter().map(|v| v / norm).collect())
+}
+
+pub(crate) fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[derive(Debug, Default)]
+struct HomeIndex {
+    entries: Vec<StoredEmbedding>,
+    dimensions: std::collections::HashMap<EmbeddingKind, usize>,
+}
+
+pub struct EmbeddingStore {
+    config: EmbeddingStoreConfig,
+    homes: DashMap<String, HomeIndex>,
+}
+
+impl EmbeddingStore {
+    pub fn new(config: EmbeddingStoreConfig) -> Self {
+        Self { config, homes: DashMap::new() }
+    }
+
+    /// Add a sample for an entity
+    pub fn insert(&self, home_id: &str, entity_id: Uuid, kind: EmbeddingKind, vector: &[f32]) -> Result<(), EmbeddingError> {
+        let vector = normalize(vector)?;
+        let mut index = self.homes.entry(home_id.to_string()).or_default();
+
+        // The first sample of each kind fixes the dimension for the home
+        let expected = *index.dimensions.entry(kind).or_insert(vector.len());
+        if expected != vector.len() {
+            return Err(EmbeddingError::DimensionMismatch { expected, got: vector.len() });
+        }
+
+        index.entries.push(StoredEmbedding { entity_id, kind, vector, observed_at: Utc::now() });
+
+        let samples = index.entries.iter().filter(|e| e.entity_id == entity_id && e.kind == kind).count();
+        if samples > self.config.max_per_entity {
+            if let Some(oldest) = index.entries
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.entity_id == entity_id && e.kind == kind)
+                .min_by_key(|(_, e)| e.observed_at)
+                .map(|(i, _)| i)
+            {
+                index.entries.remove(oldest);
+            }
+        }
+        Ok(())
+    }
+
+    /// Best match per entity, most similar first
+    pub fn nearest(&self, home_id: &str, kind: EmbeddingKind, query: &[f32], k: usize) -> Result<Vec<EmbeddingMatch>, EmbeddingError> {
+        let query = normalize(query)?;
+        let Some(index) = self.homes.get(home_id) else {
+            return Ok(Vec::new());
+        };
+        if let Some(&expected) = index.dimensions.get(&kind) {
+            if expected != query.len() {
+                return Err(EmbeddingError::DimensionMismatch { expected, got: query.len() });
+            }
+        }
+
+        let mut best: std::collections::HashMap<Uuid, f32> = std::collections::HashMap::new();
+        for entry in index.entries.iter().filter(|e| e.kind == kind) {
+            let similarity = dot(&entry.vector, &query);
+            let slot = best.entry(entry.entity_id).or_insert(f32::MIN);
+            *slot = slot.max(similarity);
+        }
+
+        let mut matches: Vec<EmbeddingMatch> = best
+            .into_iter()
+            .map(|(entity_id, similarity)| EmbeddingMatch { entity_id, similarity })
+            .collect();
+        matches.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+        matches.truncate(k);
+        Ok(matches)
+    }
+
+    /// Link a detection to a known entity, or register it as a new one
+    pub fn link_detection(&self, home_id: &str, detection: &DetectionEmbeddings) -> Result<Option<EntityLink>, EmbeddingError> {
+        let appearance = match &detection.appearance {
+            Some(v) => self.nearest(home_id, EmbeddingKind::Appearance, v, 5)?,
+            None => Vec::new(),
+        };
+        let gait = match &detection.gait {
+            Some(v) => self.nearest(home_id, EmbeddingKind::Gait, v, 5)?,
+            None => Vec::new(),
+        };
+        if detection.appearance.is_none() && detection.gait.is_none() {
+            return Ok(None);
+        }
+
+        // Score each candidate; when both modalities exist both must clear their threshold
+        let mut candidates: std::collections::HashMap<Uuid, (Option<f32>, Option<f32>)> = std::collections::HashMap::new();
+        for m in &appearance {
+            candidates.entry(m.entity_id).or_default().0 = Some(m.similarity);
+        }
+        for m in &gait {
+            candidates.entry(m.entity_id).or_default().1 = Some(m.similarity);
+        }
+
+        let w = self.config.appearance_weight;
+        let best = candidates
+            .into_iter()
+            .filter_map(|(entity_id, (a, g))| {
+                let a_ok = a.map_or(detection.appearance.is_none(), |s| s >= self.config.appearance_threshold);
+                let g_ok = g.map_or(detection.gait.is_none() || a.is_some(), |s| s >= self.config.gait_threshold);
+                if !(a_ok && g_ok) {
+                    return None;
+                }
+                let score = match (a, g) {
+                    (Some(a), Some(g)) => w * a + (1.0 - w) * g,
+                    (Some(a), None) => a,
+                    (None, Some(g)) => g,
+                    (None, None) => return None,
+                };
+                Some((entity_id, score))
+            })
+            .max_by(|x, y| x.1.partial_cmp(&y.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let link = match best {
+            Some((entity_id, similarity)) => EntityLink { entity_id, similarity, is_new: false },
+            None => EntityLink { entity_id: Uuid::new_v4(), similarity: 0.0, is_new: true },
+        };
+
+        // Keep learning the entity's look/gait from every linked detection
+        if let Some(v) = &detection.appearance {
+            self.insert(home_id, link.entity_id, EmbeddingKind::Appearance, v)?;
+        }
+        if let Some(v) = &detection.gait {
+            self.insert(home_id, link.entity_id, EmbeddingKind::Gait, v)?;
+        }
+        Ok(Some(link))
+    }
+
+    pub fn remove_entity(&self, home_id: &str, entity_id: Uuid) {
+        if let Some(mut index) = self.homes.get_mut(home_id) {
+            index.entries.retain(|e| e.entity_id != entity_id);
+        }
+    }
+
+    pub fn entity_count(&self, home_id: &str) -> usize {
+        self.homes.get(home_id)
+            .map(|index| index.entries.iter().map(|e| e.entity_id).collect::<std::collections::HashSet<_>>().len())
+            .unwrap_or(0)
+    }
+}
+
+impl Default for EmbeddingStore {
+    fn default() -> Self {
+        Self::new(EmbeddingStoreConfig::default())
+    }
+}
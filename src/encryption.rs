@@ -0,0 +1,296 @@
+// src/encryption.rs
+
+// Envelope encryption for what the system keeps on disk. Every home gets its
+// own random data key; data keys are never stored in the clear, only wrapped
+// by a master key held by a `MasterKeyProvider` (a key file on the box, or a
+// KMS in deployments that have one). Snapshots and incident records are
+// sealed with the home's data key using XChaCha20-Poly1305, with the home id
+// and the record's name bound in as associated data so a sealed file cannot
+// be moved to another home or renamed into another record. A stolen disk
+// then holds ciphertext and wrapped keys, and nothing readable without the
+// master key.
+//
+// Sealed data is laid out as MAGIC | 24-byte nonce | ciphertext and tag.
+
+use crate::home_files;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const MAGIC: &[u8; 4] = b"NVE1";
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EncryptionError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Master key unavailable: {0}")]
+    MasterKey(String),
+
+    #[error("Data key for home '{0}' could not be unwrapped")]
+    Unwrap(String),
+
+    #[error("Data is not sealed or is truncated")]
+    Format,
+
+    #[error("Decryption failed: wrong key, wrong record or tampered data")]
+    Decrypt,
+
+    #[error("Failed to encode key record: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+/// Holds the master key that wraps per-home data keys
+///
+/// Calls happen once per home per process (unwrapped keys are cached), so a
+/// KMS-backed implementation may block on the network.
+pub trait MasterKeyProvider: Send + Sync {
+    /// Identifies the master key, recorded with each wrapped data key
+    fn key_id(&self) -> String;
+
+    fn wrap(&self, home_id: &str, data_key: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+
+    fn unwrap(&self, home_id: &str, wrapped: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+}
+
+fn seal_with(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    let cipher = XChaCha20Poly1305::new_from_slice(key).map_err(|_| EncryptionError::Format)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher.encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| EncryptionError::Format)?;
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn open_with(key: &[u8], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    if !is_sealed(sealed) || sealed.len() < MAGIC.len() + NONCE_LEN {
+        return Err(EncryptionError::Format);
+    }
+    let cipher = XChaCha20Poly1305::new_from_slice(key).map_err(|_| EncryptionError::Format)?;
+    let (nonce, ciphertext) = sealed[MAGIC.len()..].split_at(NONCE_LEN);
+    cipher.decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| EncryptionError::Decrypt)
+}
+
+/// Whether bytes carry the sealed-data header
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Master key read from a file of 64 hex characters
+pub struct FileKeyProvider {
+    key: [u8; KEY_LEN],
+}
+
+impl FileKeyProvider {
+    pub fn load(path: &Path) -> Result<Self, EncryptionError> {
+        let text = std::fs::read_to_string(path)?;
+        let bytes = hex::decode(text.trim()).map_err(|e| EncryptionError::MasterKey(format!("{}: {}", path.display(), e)))?;
+        let key = bytes.try_into().map_err(|_| EncryptionError::MasterKey(format!("{}: expected {} bytes", path.display(), KEY_LEN)))?;
+        Ok(Self { key })
+    }
+
+    pub fn from_bytes(key: [u8; KEY_LEN]) -> Self {
+        Self { key }
+    }
+}
+
+impl MasterKeyProvider for FileKeyProvider {
+    fn key_id(&self) -> String {
+        format!("file:{}", &hex::encode(Sha256::digest(self.key))[..16])
+    }
+
+    fn wrap(&self, home_id: &str, data_key: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        seal_with(&self.key, data_key, home_id.as_bytes())
+    }
+
+    fn unwrap(&self, home_id: &str, wrapped: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        open_with(&self.key, wrapped, home_id.as_bytes()).map_err(|_| EncryptionError::Unwrap(home_id.to_string()))
+    }
+}
+
+/// Keyring for the master key file named by NOVIN_MASTER_KEY_FILE, with
+/// wrapped home keys under `dir`; None when encryption is not configured
+pub fn keyring_from_env(dir: PathBuf) -> Result<Option<Arc<HomeKeyring>>, EncryptionError> {
+    let Ok(path) = std::env::var("NOVIN_MASTER_KEY_FILE") else {
+        return Ok(None);
+    };
+    let provider = Arc::new(FileKeyProvider::load(Path::new(&path))?);
+    Ok(Some(Arc::new(HomeKeyring::persistent(provider, dir)?)))
+}
+
+/// A home's data key as stored: wrapped by the master key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrappedKey {
+    pub home_id: String,
+    pub master_key_id: String,
+    pub wrapped: String, // Hex
+    pub created_at: DateTime<Utc>,
+}
+
+/// Per-home data keys, created on first use
+pub struct HomeKeyring {
+    provider: Arc<dyn MasterKeyProvider>,
+    dir: Option<PathBuf>,
+    wrapped: DashMap<String, WrappedKey>,
+    keys: DashMap<String, Vec<u8>>, // Unwrapped, in memory only
+}
+
+impl HomeKeyring {
+    pub fn new(provider: Arc<dyn MasterKeyProvider>) -> Self {
+        Self { provider, dir: None, wrapped: DashMap::new(), keys: DashMap::new() }
+    }
+
+    /// Keyring whose wrapped keys live in `dir`, loading any already there
+    pub fn persistent(provider: Arc<dyn MasterKeyProvider>, dir: PathBuf) -> Result<Self, EncryptionError> {
+        std::fs::create_dir_all(&dir)?;
+        let wrapped = DashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let key: WrappedKey = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            wrapped.insert(key.home_id.clone(), key);
+        }
+        Ok(Self { provider, dir: Some(dir), wrapped, keys: DashMap::new() })
+    }
+
+    pub fn master_key_id(&self) -> String {
+        self.provider.key_id()
+    }
+
+    /// Homes that have a data key
+    pub fn homes(&self) -> Vec<String> {
+        let mut homes: Vec<String> = self.wrapped.iter().map(|k| k.key().clone()).collect();
+        homes.sort();
+        homes
+    }
+
+    /// Every wrapped key, for backups
+    pub fn wrapped_keys(&self) -> Vec<WrappedKey> {
+        let mut keys: Vec<WrappedKey> = self.wrapped.iter().map(|k| k.value().clone()).collect();
+        keys.sort_by(|a, b| a.home_id.cmp(&b.home_id));
+        keys
+    }
+
+    /// Add wrapped keys from a backup for homes this keyring has no key for
+    pub fn import(&self, keys: Vec<WrappedKey>) -> Result<usize, EncryptionError> {
+        let mut imported = 0;
+        for key in keys {
+            if let Entry::Vacant(slot) = self.wrapped.entry(key.home_id.clone()) {
+                self.persist(&key)?;
+                slot.insert(key);
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+
+    fn data_key(&self, home_id: &str) -> Result<Vec<u8>, EncryptionError> {
+        if let Some(key) = self.keys.get(home_id) {
+            return Ok(key.clone());
+        }
+        // The entry stays locked while a new key is made, so a home never gets two
+        let key = match self.wrapped.entry(home_id.to_string()) {
+            Entry::Occupied(stored) => {
+                let wrapped = hex::decode(&stored.get().wrapped).map_err(|_| EncryptionError::Unwrap(home_id.to_string()))?;
+                self.provider.unwrap(home_id, &wrapped)?
+            }
+            Entry::Vacant(slot) => {
+                let mut key = vec![0u8; KEY_LEN];
+                rand::rngs::OsRng.fill_bytes(&mut key);
+                let stored = WrappedKey {
+                    home_id: home_id.to_string(),
+                    master_key_id: self.provider.key_id(),
+                    wrapped: hex::encode(self.provider.wrap(home_id, &key)?),
+                    created_at: Utc::now(),
+                };
+                self.persist(&stored)?;
+                slot.insert(stored);
+                key
+            }
+        };
+        self.keys.insert(home_id.to_string(), key.clone());
+        Ok(key)
+    }
+
+    fn persist(&self, key: &WrappedKey) -> Result<(), EncryptionError> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        home_files::write_json(dir, &key.home_id, &serde_json::to_vec_pretty(key)?)?;
+        Ok(())
+    }
+
+    /// Seal data for a home; `record` names what it is and must match on open
+    pub fn seal(&self, home_id: &str, record: &str, plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        seal_with(&self.data_key(home_id)?, plaintext, &associated_data(home_id, record))
+    }
+
+    pub fn open(&self, home_id: &str, record: &str, sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        open_with(&self.data_key(home_id)?, sealed, &associated_data(home_id, record))
+    }
+}
+
+fn associated_data(home_id: &str, record: &str) -> Vec<u8> {
+    format!("{}\u{0}{}", home_id, record).into_bytes()
+}
+
+/// Snapshots kept on disk, one sealed file per image under a directory per home
+pub struct EncryptedStore {
+    keyring: Arc<HomeKeyring>,
+    dir: PathBuf,
+}
+
+impl EncryptedStore {
+    pub fn new(keyring: Arc<HomeKeyring>, dir: PathBuf) -> Result<Self, EncryptionError> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { keyring, dir })
+    }
+
+    pub fn keyring(&self) -> &Arc<HomeKeyring> {
+        &self.keyring
+    }
+
+    fn image_path(&self, home_id: &str, url: &str) -> (PathBuf, String) {
+        let digest = hex::encode(Sha256::digest(url.as_bytes()));
+        let path = self.dir.join(home_files::file_stem(home_id)).join(format!("{}.sealed", digest));
+        (path, format!("image:{}", digest))
+    }
+
+    /// Keep a snapshot, keyed by the URL it was fetched from
+    pub fn put_image(&self, home_id: &str, url: &str, image: &[u8]) -> Result<(), EncryptionError> {
+        let (path, record) = self.image_path(home_id, url);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, self.keyring.seal(home_id, &record, image)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// A stored snapshot, or None if it was never kept
+    pub fn image(&self, home_id: &str, url: &str) -> Result<Option<Vec<u8>>, EncryptionError> {
+        let (path, record) = self.image_path(home_id, url);
+        match std::fs::read(&path) {
+            Ok(sealed) => self.keyring.open(home_id, &record, &sealed).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
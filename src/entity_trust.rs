@@ -0,0 +1,269 @@
+// src/entity_trust.rs
+
+// Trust earned by the people a home sees again and again. Re-identification
+// gives each recurring person a stable entity id; every visit verified as
+// benign (homeowner feedback or operator disposition) raises that entity's
+// trust score, and the score decays with a half-life so someone who stopped
+// coming gradually becomes a stranger again. Homeowners can pin an entity as
+// trusted or revoke its trust outright; a visit confirmed as a threat revokes
+// it too. The score feeds identity evidence: a trusted entity's detections
+// carry a negative identity LLR in proportion to the trust. Every change is
+// kept in a per-home audit log, and with a data directory both scores and log
+// are written to one JSON file per home so they survive restarts.
+
+use crate::home_files;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TrustError {
+    #[error("Unknown entity '{0}'")]
+    NotFound(String),
+
+    #[error("Failed to persist trust scores: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to encode trust scores: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct TrustConfig {
+    pub half_life_days: f64,    // Unpinned scores halve after this long without a benign visit
+    pub visit_gain: f64,        // Share of the remaining distance to 1.0 gained per benign visit
+    pub max_identity_llr: f64,  // Identity LLR magnitude of a fully trusted entity
+    pub min_score: f64,         // Below this an entity contributes no identity evidence
+    pub audit_len: usize,       // Audit entries kept per home
+}
+
+impl Default for TrustConfig {
+    fn default() -> Self {
+        Self {
+            half_life_days: 30.0,
+            visit_gain: 0.2,
+            max_identity_llr: 2.0,
+            min_score: 0.05,
+            audit_len: 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityTrust {
+    pub entity_id: String,
+    pub score: f64,               // 0.0 to 1.0 as of `updated_at`; see `TrustStore::list` for the decayed value
+    pub pinned: bool,             // Marked trusted by hand; does not decay
+    pub revoked: bool,            // Trust withdrawn; benign visits no longer raise it
+    pub benign_visits: u32,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustChangeKind {
+    BenignVisit,
+    MarkedTrusted,
+    Revoked,
+    ThreatConfirmed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustChange {
+    pub entity_id: String,
+    pub kind: TrustChangeKind,
+    pub from: f64, // Decayed score just before the change
+    pub to: f64,
+    pub actor: Option<String>, // User who made a manual change
+    pub note: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HomeTrust {
+    home_id: String,
+    entities: HashMap<String, EntityTrust>,
+    audit: VecDeque<TrustChange>,
+}
+
+#[derive(Debug, Default)]
+pub struct TrustStore {
+    config: TrustConfig,
+    homes: DashMap<String, HomeTrust>,
+    dir: Option<PathBuf>,
+}
+
+impl TrustStore {
+    pub fn new(config: TrustConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// Store backed by `dir`, loading any scores already there
+    pub fn persistent(config: TrustConfig, dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let homes = DashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path).map(|text| serde_json::from_str::<HomeTrust>(&text)) {
+                Ok(Ok(trust)) => {
+                    homes.insert(trust.home_id.clone(), trust);
+                }
+                Ok(Err(e)) => tracing::warn!("Skipping unreadable trust scores {}: {}", path.display(), e),
+                Err(e) => tracing::warn!("Skipping trust scores {}: {}", path.display(), e),
+            }
+        }
+        Ok(Self { config, homes, dir: Some(dir) })
+    }
+
+    pub fn config(&self) -> &TrustConfig {
+        &self.config
+    }
+
+    fn decayed(&self, trust: &EntityTrust, now: DateTime<Utc>) -> f64 {
+        if trust.pinned || trust.revoked {
+            return trust.score;
+        }
+        let days = (now - trust.updated_at).num_seconds().max(0) as f64 / 86_400.0;
+        trust.score * 0.5f64.powf(days / self.config.half_life_days)
+    }
+
+    /// Current trust in an entity; 0 for one never seen
+    pub fn score(&self, home_id: &str, entity_id: &str, now: DateTime<Utc>) -> f64 {
+        self.homes.get(home_id)
+            .and_then(|h| h.entities.get(entity_id).map(|t| self.decayed(t, now)))
+            .unwrap_or(0.0)
+    }
+
+    /// Identity evidence for a detection of this entity, if it is trusted at all
+    pub fn identity_llr(&self, home_id: &str, entity_id: &str, now: DateTime<Utc>) -> Option<f64> {
+        let score = self.score(home_id, entity_id, now);
+        (score >= self.config.min_score).then(|| -score * self.config.max_identity_llr)
+    }
+
+    /// Every entity with a trust record, most trusted first, scores decayed to `now`
+    pub fn list(&self, home_id: &str, now: DateTime<Utc>) -> Vec<EntityTrust> {
+        let Some(home) = self.homes.get(home_id) else {
+            return Vec::new();
+        };
+        let mut entities: Vec<EntityTrust> = home.entities.values()
+            .map(|t| EntityTrust { score: self.decayed(t, now), ..t.clone() })
+            .collect();
+        entities.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.entity_id.cmp(&b.entity_id)));
+        entities
+    }
+
+    /// Trust changes for a home, newest first, optionally for one entity
+    pub fn audit(&self, home_id: &str, entity_id: Option<&str>) -> Vec<TrustChange> {
+        self.homes.get(home_id)
+            .map(|h| h.audit.iter().rev().filter(|c| entity_id.is_none() || entity_id == Some(c.entity_id.as_str())).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// A visit by this entity was verified as benign
+    pub fn record_benign_visit(&self, home_id: &str, entity_id: &str, now: DateTime<Utc>) -> Result<f64, TrustError> {
+        self.change(home_id, entity_id, TrustChangeKind::BenignVisit, None, None, now, |trust, current| {
+            trust.benign_visits += 1;
+            if trust.revoked || trust.pinned {
+                current
+            } else {
+                current + (1.0 - current) * self.config.visit_gain
+            }
+        })
+    }
+
+    /// A visit by this entity was confirmed as a threat; its trust is withdrawn
+    pub fn record_threat(&self, home_id: &str, entity_id: &str, now: DateTime<Utc>) -> Result<f64, TrustError> {
+        self.change(home_id, entity_id, TrustChangeKind::ThreatConfirmed, None, None, now, |trust, _| {
+            trust.pinned = false;
+            trust.revoked = true;
+            0.0
+        })
+    }
+
+    /// Pin an entity as fully trusted, e.g. a family member or regular carer
+    pub fn mark_trusted(&self, home_id: &str, entity_id: &str, actor: &str, note: Option<String>, now: DateTime<Utc>) -> Result<EntityTrust, TrustError> {
+        self.change(home_id, entity_id, TrustChangeKind::MarkedTrusted, Some(actor), note, now, |trust, _| {
+            trust.pinned = true;
+            trust.revoked = false;
+            1.0
+        })?;
+        self.get(home_id, entity_id).ok_or_else(|| TrustError::NotFound(entity_id.to_string()))
+    }
+
+    /// Withdraw trust from an entity the home has a record of
+    pub fn revoke(&self, home_id: &str, entity_id: &str, actor: &str, note: Option<String>, now: DateTime<Utc>) -> Result<EntityTrust, TrustError> {
+        if self.get(home_id, entity_id).is_none() {
+            return Err(TrustError::NotFound(entity_id.to_string()));
+        }
+        self.change(home_id, entity_id, TrustChangeKind::Revoked, Some(actor), note, now, |trust, _| {
+            trust.pinned = false;
+            trust.revoked = true;
+            0.0
+        })?;
+        self.get(home_id, entity_id).ok_or_else(|| TrustError::NotFound(entity_id.to_string()))
+    }
+
+    fn get(&self, home_id: &str, entity_id: &str) -> Option<EntityTrust> {
+        self.homes.get(home_id).and_then(|h| h.entities.get(entity_id).cloned())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn change(
+        &self,
+        home_id: &str,
+        entity_id: &str,
+        kind: TrustChangeKind,
+        actor: Option<&str>,
+        note: Option<String>,
+        now: DateTime<Utc>,
+        apply: impl FnOnce(&mut EntityTrust, f64) -> f64,
+    ) -> Result<f64, TrustError> {
+        let (to, snapshot) = {
+            let mut home = self.homes.entry(home_id.to_string())
+                .or_insert_with(|| HomeTrust { home_id: home_id.to_string(), ..HomeTrust::default() });
+            let mut trust = home.entities.get(entity_id).cloned().unwrap_or_else(|| EntityTrust {
+                entity_id: entity_id.to_string(),
+                score: 0.0,
+                pinned: false,
+                revoked: false,
+                benign_visits: 0,
+                updated_at: now,
+            });
+            let from = self.decayed(&trust, now);
+            let to = apply(&mut trust, from).clamp(0.0, 1.0);
+            trust.score = to;
+            trust.updated_at = now;
+            home.entities.insert(entity_id.to_string(), trust);
+            home.audit.push_back(TrustChange {
+                entity_id: entity_id.to_string(),
+                kind,
+                from,
+                to,
+                actor: actor.map(str::to_string),
+                note,
+                at: now,
+            });
+            while home.audit.len() > self.config.audit_len {
+                home.audit.pop_front();
+            }
+            (to, self.dir.is_some().then(|| home.clone()))
+        };
+        if let Some(home) = snapshot {
+            self.persist(&home)?;
+        }
+        Ok(to)
+    }
+
+    fn persist(&self, home: &HomeTrust) -> Result<(), TrustError> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        home_files::write_json(dir, &home.home_id, &serde_json::to_vec_pretty(home)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+//! Calendar-aware prior adjustment
+//!
+//! Replaces fixed UTC hour-of-day risk tables with the home's local time (DST aware),
+//! actual darkness computed from its latitude/longitude, and a public holiday calendar.
+//! A winter evening at 16:30 is dark and scored like night; a summer evening at 21:00
+//! is still light and is not.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HolidayCalendar {
+    None,
+    US,
+    GB, // England & Wales bank holidays
+}
+
+// Location and calendar for one home
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarConfig {
+    pub latitude: Option<f64>,  // Without a location darkness falls back to fixed hours
+    pub longitude: Option<f64>,
+    pub timezone: String,       // IANA name, e.g. "Europe/London"
+    pub holidays: HolidayCalendar,
+    pub custom_holidays: Vec<NaiveDate>, // School holidays, local events, etc.
+    pub twilight_minutes: i64,  // Window either side of sunrise/sunset treated as twilight
+    pub dark_prior_logit: f64,     // Added to the threat prior after dark
+    pub twilight_prior_logit: f64, // Added during twilight
+    pub holiday_prior_logit: f64,  // Added on holidays, when homes are more often empty
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            latitude: None,
+            longitude: None,
+            timezone: "UTC".to_string(),
+            holidays: HolidayCalendar::None,
+            custom_holidays: Vec::new(),
+            twilight_minutes: 30,
+            dark_prior_logit: 0.4,
+            twilight_prior_logit: 0.2,
+            holiday_prior_logit: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SunTimes {
+    Normal { sunrise: DateTime<Utc>, sunset: DateTime<Utc> },
+    PolarDay,   // Sun never sets on this date
+    PolarNight, // Sun never rises on this date
+}
+
+/// Sunrise and sunset for a date using the NOAA solar position approximation
+///
+/// Accurate to within a few minutes outside the polar regions, which is plenty for
+/// deciding whether a camera frame was taken in daylight.
+pub fn compute_sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> SunTimes {
+    let gamma = 2.0 * std::f64::consts::PI / 365.0 * (date.ordinal() as f64 - 1.0);
+
+    // Equation of time (minutes) and solar declination (radians)
+    let eqtime = 229.18 * (0.000075 + 0.001868 * gamma.cos() - 0.032077 * gamma.sin()
+        - 0.014615 * (2.0 * gamma).cos() - 0.040849 * (2.0 * gamma).sin());
+    let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos() + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos() + 0.00148 * (3.0 * gamma).sin();
+
+    // 90.833 degrees accounts for refraction and the solar disc radius
+    let lat = latitude.to_radians();
+    let cos_ha = 90.833_f64.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+    if cos_ha > 1.0 {
+        return SunTimes::PolarNight;
+    }
+    if cos_ha < -1.0 {
+        return SunTimes::PolarDay;
+    }
+    let ha = cos_ha.acos().to_degrees();
+
+    let midnight = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight is valid"));
+    let at_minutes = |m: f64| midnight + Duration::seconds((m * 60.0).round() as i64);
+
+    SunTimes::Normal {
+        sunrise: at_minutes(720.0 - 4.0 * (longitude + ha) - eqtime),
+        sunset: at_minutes(720.0 - 4.0 * (longitude - ha) - eqtime),
+    }
+}
+
+// Easter Sunday (Gregorian), anonymous computus
+fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("computus yields a valid date")
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n)
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> Option<NaiveDate> {
+    (1..=5).rev().find_map(|n| nth_weekday(year, month, weekday, n))
+}
+
+impl HolidayCalendar {
+    /// Public holidays for a year
+    pub fn holidays(&self, year: i32) -> Vec<NaiveDate> {
+        let ymd = |m, d| NaiveDate::from_ymd_opt(year, m, d);
+        let dates = match self {
+            HolidayCalendar::None => vec![],
+            HolidayCalendar::US => vec![
+                ymd(1, 1),                                 // New Year's Day
+                nth_weekday(year, 1, Weekday::Mon, 3),     // Martin Luther King Jr. Day
+                nth_weekday(year, 2, Weekday::Mon, 3),     // Presidents' Day
+                last_weekday(year, 5, Weekday::Mon),       // Memorial Day
+                ymd(6, 19),                                // Juneteenth
+                ymd(7, 4),                                 // Independence Day
+                nth_weekday(year, 9, Weekday::Mon, 1),     // Labor Day
+                nth_weekday(year, 10, Weekday::Mon, 2),    // Columbus Day
+                ymd(11, 11),                               // Veterans Day
+                nth_weekday(year, 11, Weekday::Thu, 4),    // Thanksgiving
+                ymd(12, 25),                               // Christmas Day
+            ],
+            HolidayCalendar::GB => {
+                let easter = easter_sunday(year);
+                vec![
+                    ymd(1, 1),                             // New Year's Day
+                    Some(easter - Duration::days(2)),      // Good Friday
+                    Some(easter + Duration::days(1)),      // Easter Monday
+                    nth_weekday(year, 5, Weekday::Mon, 1), // Early May bank holiday
+                    last_weekday(year, 5, Weekday::Mon),   // Spring bank holiday
+                    last_weekday(year, 8, Weekday::Mon),   // Summer bank holiday
+                    ymd(12, 25),                           // Christmas Day
+                    ymd(12, 26),                           // Boxing Day
+                ]
+            }
+        };
+        dates.into_iter().flatten().collect()
+    }
+}
+
+// Calendar and daylight context used by risk scoring and prior computation
+#[derive(Debug, Clone)]
+pub struct CalendarPriorAdjuster {
+    config: CalendarConfig,
+    tz: Tz,
+}
+
+impl CalendarPriorAdjuster {
+    pub fn new(config: CalendarConfig) -> Self {
+        let tz = config.timezone.parse::<Tz>().unwrap_or_else(|_| {
+            warn!("Unknown timezone '{}', using UTC", config.timezone);
+            Tz::UTC
+        });
+        Self { config, tz }
+    }
+
+    pub fn config(&self) -> &CalendarConfig {
+        &self.config
+    }
+
+    pub fn local_time(&self, at: DateTime<Utc>) -> DateTime<Tz> {
+        at.with_timezone(&self.tz)
+    }
+
+    /// Sunrise/sunset for the local date of `at`, if the home's location is known
+    pub fn sun_times(&self, at: DateTime<Utc>) -> Option<SunTimes> {
+        let (lat, lon) = (self.config.latitude?, self.config.longitude?);
+        Some(compute_sun_times(self.local_time(at).date_naive(), lat, lon))
+    }
+
+    pub fn is_dark(&self, at: DateTime<Utc>) -> bool {
+        match self.sun_times(at) {
+            Some(SunTimes::Normal { sunrise, sunset }) => at < sunrise || at >= sunset,
+            Some(SunTimes::PolarDay) => false,
+            Some(SunTimes::PolarNight) => true,
+            // No location: same night window as the old hour table
+            None => matches!(self.local_time(at).hour(), 22..=23 | 0..=5),
+        }
+    }
+
+    pub fn is_twilight(&self, at: DateTime<Utc>) -> bool {
+        let window = Duration::minutes(self.config.twilight_minutes);
+        match self.sun_times(at) {
+            Some(SunTimes::Normal { sunrise, sunset }) => {
+                (at - sunrise).abs() <= window || (at - sunset).abs() <= window
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.config.custom_holidays.contains(&date)
+            || self.config.holidays.holidays(date.year()).contains(&date)
+    }
+
+    /// Weekend or holiday in the home's timezone
+    pub fn is_day_off(&self, at: DateTime<Utc>) -> bool {
+        let local = self.local_time(at);
+        matches!(local.weekday(), Weekday::Sat | Weekday::Sun) || self.is_holiday(local.date_naive())
+    }
+
+    /// Time-of-day risk in [0, 1], driven by darkness rather than fixed UTC hours
+    pub fn time_risk(&self, at: DateTime<Utc>) -> f64 {
+        let hour = self.local_time(at).hour();
+        if self.is_dark(at) {
+            match hour {
+                2..=5 => 0.8, // Deep night
+                _ => 0.6,     // Dark evenings/mornings, including 4pm in winter
+            }
+        } else if self.is_twilight(at) {
+            0.4
+        } else {
+            match hour {
+                9..=17 if !self.is_day_off(at) => 0.2, // Working daylight hours
+                9..=17 => 0.25,                        // Holiday daytime, more homes empty
+                6..=8 => 0.3,
+                _ => 0.25,
+            }
+        }
+    }
+
+    /// Background activity level used by the environmental risk estimate
+    pub fn activity_risk(&self, at: DateTime<Utc>) -> f64 {
+        let hour = self.local_time(at).hour();
+        if self.is_dark(at) {
+            return 0.3;
+        }
+        match hour {
+            9..=17 if !self.is_day_off(at) => 0.2, // Business hours weekday
+            14..=15 => 0.15,                       // School dismissal time
+            18..=20 => 0.25,                       // Light evening activity
+            _ => 0.3,
+        }
+    }
+
+    /// Logit offset applied to the Bayesian threat prior at time `at`
+    pub fn prior_logit_adjustment(&self, at: DateTime<Utc>) -> f64 {
+        let mut adjustment = if self.is_dark(at) {
+            self.config.dark_prior_logit
+        } else if self.is_twilight(at) {
+            self.config.twilight_prior_logit
+        } else {
+            0.0
+        };
+        if self.is_holiday(self.local_time(at).date_naive()) {
+            adjustment += self.config.holiday_prior_logit;
+        }
+        adjustment
+    }
+
+    /// Convenience wrapper for event timestamps in unix seconds
+    pub fn prior_logit_adjustment_ts(&self, ts: f64) -> f64 {
+        match Utc.timestamp_opt(ts.floor() as i64, 0).single() {
+            Some(at) if ts.is_finite() => self.prior_logit_adjustment(at),
+            _ => 0.0,
+        }
+    }
+}
+
+impl Default for CalendarPriorAdjuster {
+    fn default() -> Self {
+        Self::new(CalendarConfig::default())
+    }
+}
+
+// Calendars by home, so darkness and holidays follow each home's own location,
+// timezone and country. Shared by the thinking prior and the reconnaissance analyzer.
+#[derive(Debug, Default)]
+pub struct HomeCalendars {
+    homes: DashMap<String, CalendarPriorAdjuster>,
+    fallback: Option<CalendarPriorAdjuster>, // Deployment-wide calendar for homes not yet onboarded
+}
+
+impl HomeCalendars {
+    pub fn new(fallback: Option<CalendarConfig>) -> Self {
+        Self {
+            homes: DashMap::new(),
+            fallback: fallback.map(CalendarPriorAdjuster::new),
+        }
+    }
+
+    /// Register or replace the calendar of a home
+    pub fn register(&self, home_id: &str, config: CalendarConfig) {
+        self.homes.insert(home_id.to_string(), CalendarPriorAdjuster::new(config));
+    }
+
+    fn with_calendar<T>(&self, home_id: &str, f: impl FnOnce(&CalendarPriorAdjuster) -> T) -> Option<T> {
+        match self.homes.get(home_id) {
+            Some(calendar) => Some(f(&calendar)),
+            None => self.fallback.as_ref().map(f),
+        }
+    }
+
+    /// Prior logit offset for the home at `ts` (unix seconds); 0 without a calendar
+    pub fn prior_logit_adjustment_ts(&self, home_id: &str, ts: f64) -> f64 {
+        self.with_calendar(home_id, |c| c.prior_logit_adjustment_ts(ts)).unwrap_or(0.0)
+    }
+
+    /// Time-of-day risk at the home, if it has a calendar
+    pub fn time_risk(&self, home_id: &str, at: DateTime<Utc>) -> Option<f64> {
+        self.with_calendar(home_id, |c| c.time_risk(at))
+    }
+}
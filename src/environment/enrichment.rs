@@ -0,0 +1,216 @@
+//! Weather and light enrichment
+//!
+//! Combines cached provider weather with computed daylight for a home and turns
+//! them into a visual-evidence reliability factor. Fog, heavy rain and darkness
+//! all make camera-derived evidence (face identity, behaviour) less trustworthy,
+//! so fusion shrinks those LLRs toward zero instead of trusting them fully.
+
+use super::calendar::{CalendarConfig, CalendarPriorAdjuster, SunTimes};
+use super::weather::{WeatherCondition, WeatherConditions, WeatherError, WeatherProvider};
+use crate::core::EnvironmentalContext;
+use crate::validation::Validate;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LightCondition {
+    Daylight,
+    Twilight,
+    Dark,
+}
+
+// Weather and light at a home at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    pub light: LightCondition,
+    pub sunrise: Option<DateTime<Utc>>,
+    pub sunset: Option<DateTime<Utc>>,
+    pub weather: Option<WeatherConditions>, // None when the provider is unavailable
+    pub visual_reliability: f64,            // Multiplier in [0.3, 1] for visual LLRs
+}
+
+impl EnvironmentSnapshot {
+    /// Tags in the style of `EnvironmentalContext::ambient_conditions`
+    pub fn ambient_conditions(&self) -> Vec<String> {
+        let mut tags = vec![match self.light {
+            LightCondition::Daylight => "daylight",
+            LightCondition::Twilight => "twilight",
+            LightCondition::Dark => "dark",
+        }.to_string()];
+
+        if let Some(weather) = &self.weather {
+            tags.push(match weather.condition {
+                WeatherCondition::Clear => "clear",
+                WeatherCondition::Cloudy => "cloudy",
+                WeatherCondition::Fog => "fog",
+                WeatherCondition::Drizzle => "drizzle",
+                WeatherCondition::Rain => "rain",
+                WeatherCondition::HeavyRain => "heavy_rain",
+                WeatherCondition::Snow => "snow",
+                WeatherCondition::Thunderstorm => "thunderstorm",
+            }.to_string());
+        }
+        tags.push(if self.visual_reliability < 0.7 { "reduced_visibility" } else { "clear_visibility" }.to_string());
+        tags
+    }
+}
+
+/// Reliability of camera evidence under the given weather and light
+pub fn visual_reliability(weather: Option<&WeatherConditions>, light: LightCondition) -> f64 {
+    let mut factor = match weather.map(|w| w.condition) {
+        Some(WeatherCondition::Fog) => 0.5,
+        Some(WeatherCondition::HeavyRain) | Some(WeatherCondition::Thunderstorm) => 0.6,
+        Some(WeatherCondition::Snow) => 0.7,
+        Some(WeatherCondition::Rain) => 0.85,
+        Some(WeatherCondition::Drizzle) => 0.9,
+        _ => 1.0,
+    };
+
+    // Reported visibility under 1km caps reliability regardless of the condition code
+    if let Some(visibility) = weather.and_then(|w| w.visibility_m).filter(|v| v.is_finite()) {
+        if visibility < 1000.0 {
+            factor = factor.min((visibility / 1000.0).max(0.3));
+        }
+    }
+
+    factor *= match light {
+        LightCondition::Daylight => 1.0,
+        LightCondition::Twilight => 0.9,
+        LightCondition::Dark => 0.85, // IR footage loses colour and facial detail
+    };
+
+    factor.clamp(0.3, 1.0)
+}
+
+#[derive(Debug, Clone)]
+pub struct EnrichmentConfig {
+    pub weather_ttl: Duration, // How long fetched conditions are reused
+    pub max_cached_locations: u64,
+    pub weather_url: Option<String>, // Open-Meteo compatible endpoint; None uses the public API
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            weather_ttl: Duration::from_secs(600),
+            max_cached_locations: 10_000,
+            weather_url: None,
+        }
+    }
+}
+
+impl EnrichmentConfig {
+    /// Enrichment settings from the environment; None when NOVIN_WEATHER=off
+    pub fn from_env() -> Result<Option<Self>, WeatherError> {
+        if std::env::var("NOVIN_WEATHER").is_ok_and(|v| v.eq_ignore_ascii_case("off")) {
+            return Ok(None);
+        }
+        let mut config = Self::default();
+        if let Ok(v) = std::env::var("NOVIN_WEATHER_TTL_S") {
+            let secs: u64 = v.parse().map_err(|_| WeatherError::Config("NOVIN_WEATHER_TTL_S", v))?;
+            config.weather_ttl = Duration::from_secs(secs);
+        }
+        if let Ok(v) = std::env::var("NOVIN_WEATHER_URL") {
+            url::Url::parse(&v).map_err(|e| WeatherError::Config("NOVIN_WEATHER_URL", e.to_string()))?;
+            config.weather_url = Some(v.trim_end_matches('/').to_string());
+        }
+        config.validate().map_err(|e| WeatherError::Config("config", e.to_string()))?;
+        Ok(Some(config))
+    }
+}
+
+// Per-home weather and light lookup with a shared weather cache
+pub struct EnvironmentEnricher {
+    provider: Arc<dyn WeatherProvider>,
+    cache: Cache<(i64, i64), WeatherConditions>, // Keyed by location rounded to ~1km
+    homes: DashMap<String, CalendarPriorAdjuster>,
+}
+
+impl EnvironmentEnricher {
+    pub fn new(provider: Arc<dyn WeatherProvider>, config: EnrichmentConfig) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(config.max_cached_locations)
+            .time_to_live(config.weather_ttl)
+            .build();
+
+        Self {
+            provider,
+            cache,
+            homes: DashMap::new(),
+        }
+    }
+
+    /// Register or update the location and calendar of a home
+    pub fn register_home(&self, home_id: &str, calendar: CalendarConfig) {
+        self.homes.insert(home_id.to_string(), CalendarPriorAdjuster::new(calendar));
+    }
+
+    /// Weather and light for a home; unknown homes get a neutral daylight snapshot
+    pub async fn snapshot(&self, home_id: &str, at: DateTime<Utc>) -> EnvironmentSnapshot {
+        let Some(calendar) = self.homes.get(home_id).map(|c| c.clone()) else {
+            return EnvironmentSnapshot {
+                light: LightCondition::Daylight,
+                sunrise: None,
+                sunset: None,
+                weather: None,
+                visual_reliability: 1.0,
+            };
+        };
+
+        let light = if calendar.is_dark(at) {
+            LightCondition::Dark
+        } else if calendar.is_twilight(at) {
+            LightCondition::Twilight
+        } else {
+            LightCondition::Daylight
+        };
+        let (sunrise, sunset) = match calendar.sun_times(at) {
+            Some(SunTimes::Normal { sunrise, sunset }) => (Some(sunrise), Some(sunset)),
+            _ => (None, None),
+        };
+
+        let location = calendar.config().latitude.zip(calendar.config().longitude);
+        let weather = match location {
+            Some((lat, lon)) => self.weather_at(lat, lon).await,
+            None => None,
+        };
+
+        EnvironmentSnapshot {
+            light,
+            sunrise,
+            sunset,
+            visual_reliability: visual_reliability(weather.as_ref(), light),
+            weather,
+        }
+    }
+
+    /// Fill in ambient conditions on a context from the current snapshot
+    pub async fn enrich(&self, home_id: &str, context: &mut EnvironmentalContext) {
+        let snapshot = self.snapshot(home_id, Utc::now()).await;
+        context.ambient_conditions = snapshot.ambient_conditions();
+        context.environment = Some(snapshot);
+    }
+
+    async fn weather_at(&self, lat: f64, lon: f64) -> Option<WeatherConditions> {
+        let key = ((lat * 100.0).round() as i64, (lon * 100.0).round() as i64);
+        if let Some(cached) = self.cache.get(&key).await {
+            return Some(cached);
+        }
+
+        match self.provider.current(lat, lon).await {
+            Ok(conditions) => {
+                self.cache.insert(key, conditions.clone()).await;
+                Some(conditions)
+            }
+            Err(e) => {
+                warn!("Weather provider {} failed: {}", self.provider.name(), e);
+                None
+            }
+        }
+    }
+}
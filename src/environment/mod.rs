@@ -0,0 +1,21 @@
+//! Environmental Context Enrichment
+//!
+//! Calendar, daylight and weather information about a home's location,
+//! used to adjust priors and evidence reliability.
+
+pub mod calendar;
+pub mod weather;
+pub mod enrichment;
+
+pub use calendar::{
+    CalendarConfig, CalendarPriorAdjuster, HolidayCalendar, HomeCalendars, SunTimes,
+    compute_sun_times,
+};
+pub use weather::{
+    OpenMeteoProvider, StaticWeatherProvider, WeatherCondition, WeatherConditions,
+    WeatherError, WeatherProvider,
+};
+pub use enrichment::{
+    EnrichmentConfig, EnvironmentEnricher, EnvironmentSnapshot, LightCondition,
+    visual_reliability,
+};
@@ -0,0 +1,170 @@
+//! Local weather providers
+//!
+//! Weather is fetched through the `WeatherProvider` trait so deployments can swap
+//! the default Open-Meteo client for a commercial feed or a fixed test value.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WeatherError {
+    #[error("Weather provider request failed: {0}")]
+    Request(String),
+
+    #[error("Weather provider returned an unexpected response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Invalid weather config {0}: {1}")]
+    Config(&'static str, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeatherCondition {
+    Clear,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    HeavyRain,
+    Snow,
+    Thunderstorm,
+}
+
+impl WeatherCondition {
+    /// Map a WMO weather interpretation code to a condition
+    pub fn from_wmo_code(code: u32) -> Self {
+        match code {
+            0 => WeatherCondition::Clear,
+            1..=3 => WeatherCondition::Cloudy,
+            45 | 48 => WeatherCondition::Fog,
+            51..=57 => WeatherCondition::Drizzle,
+            61 | 63 | 66 | 80 | 81 => WeatherCondition::Rain,
+            65 | 67 | 82 => WeatherCondition::HeavyRain,
+            71..=77 | 85 | 86 => WeatherCondition::Snow,
+            95..=99 => WeatherCondition::Thunderstorm,
+            _ => WeatherCondition::Cloudy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherConditions {
+    pub condition: WeatherCondition,
+    pub visibility_m: Option<f64>,
+    pub precipitation_mm_h: Option<f64>,
+    pub temperature_c: Option<f64>,
+    pub observed_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait WeatherProvider: Send + Sync {
+    /// Current conditions at a location
+    async fn current(&self, latitude: f64, longitude: f64) -> Result<WeatherConditions, WeatherError>;
+
+    fn name(&self) -> &str;
+}
+
+// Open-Meteo current conditions, no API key required
+pub struct OpenMeteoProvider {
+    client: Client,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoResponse {
+    current: OpenMeteoCurrent,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoCurrent {
+    weather_code: Option<u32>,
+    visibility: Option<f64>,
+    precipitation: Option<f64>,
+    temperature_2m: Option<f64>,
+}
+
+impl OpenMeteoProvider {
+    pub fn new() -> Self {
+        Self::with_base_url("https://api.open-meteo.com".to_string())
+    }
+
+    pub fn with_base_url(base_url: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client, base_url }
+    }
+}
+
+impl Default for OpenMeteoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for OpenMeteoProvider {
+    async fn current(&self, latitude: f64, longitude: f64) -> Result<WeatherConditions, WeatherError> {
+        let url = format!(
+            "{}/v1/forecast?latitude={:.4}&longitude={:.4}&current=weather_code,visibility,precipitation,temperature_2m",
+            self.base_url, latitude, longitude
+        );
+
+        let response = self.client.get(&url).send().await
+            .map_err(|e| WeatherError::Request(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(WeatherError::Request(format!("HTTP {}", response.status())));
+        }
+        let body: OpenMeteoResponse = response.json().await
+            .map_err(|e| WeatherError::InvalidResponse(e.to_string()))?;
+
+        let current = body.current;
+        Ok(WeatherConditions {
+            condition: current.weather_code
+                .map(WeatherCondition::from_wmo_code)
+                .ok_or_else(|| WeatherError::InvalidResponse("missing weather_code".to_string()))?,
+            visibility_m: current.visibility,
+            precipitation_mm_h: current.precipitation,
+            temperature_c: current.temperature_2m,
+            observed_at: Utc::now(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "open-meteo"
+    }
+}
+
+// Fixed conditions, for tests and deployments without outbound access
+pub struct StaticWeatherProvider {
+    conditions: WeatherConditions,
+}
+
+impl StaticWeatherProvider {
+    pub fn new(condition: WeatherCondition) -> Self {
+        Self {
+            conditions: WeatherConditions {
+                condition,
+                visibility_m: None,
+                precipitation_mm_h: None,
+                temperature_c: None,
+                observed_at: Utc::now(),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for StaticWeatherProvider {
+    async fn current(&self, _latitude: f64, _longitude: f64) -> Result<WeatherConditions, WeatherError> {
+        Ok(WeatherConditions { observed_at: Utc::now(), ..self.conditions.clone() })
+    }
+
+    fn name(&self) -> &str {
+        "static"
+    }
+}
@@ -0,0 +1,276 @@
+// src/escalation_rules.rs
+
+// Rules that sit above the fused AlertDecision. The probability engine scores
+// evidence; some situations are serious no matter what the score says. The
+// built-in rule: a detection on an interior camera while every resident is
+// confirmed away goes straight to Critical and starts the home's escalation
+// chain, an ordered list of people to alert, each after a delay, until
+// someone acknowledges or the incident closes.
+
+use crate::core::{ThreatContext, ThreatVector, ZoneClass};
+use crate::household::Occupancy;
+use crate::overnight::DeliveryChannel;
+use crate::thinking::AlertDecision;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// What a rule wants done with a decision
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleOutcome {
+    pub decision: AlertDecision,
+    pub reason: String,
+    pub start_escalation: bool,
+}
+
+pub trait EscalationRule: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// None leaves the decision alone
+    fn evaluate(&self, context: &ThreatContext, decision: &AlertDecision) -> Option<RuleOutcome>;
+}
+
+/// Interior detection while all residents are confirmed away: Critical, bypassing thresholds
+#[derive(Debug, Default)]
+pub struct InteriorWhileAwayRule;
+
+impl EscalationRule for InteriorWhileAwayRule {
+    fn name(&self) -> &str {
+        "interior_while_away"
+    }
+
+    fn evaluate(&self, context: &ThreatContext, _decision: &AlertDecision) -> Option<RuleOutcome> {
+        if context.zone_class != Some(ZoneClass::Interior) || context.occupancy != Some(Occupancy::Empty) {
+            return None;
+        }
+        Some(RuleOutcome {
+            decision: AlertDecision::Critical,
+            reason: "interior activity while all residents are away".to_string(),
+            start_escalation: true,
+        })
+    }
+}
+
+/// Incidents classified as `vector` alert at `floor` or above, e.g. every
+/// burglary at least Elevated. Not part of the default engine; a floor of
+/// Critical also starts the escalation chain.
+#[derive(Debug, Clone)]
+pub struct VectorFloorRule {
+    name: String,
+    pub vector: ThreatVector,
+    pub floor: AlertDecision,
+}
+
+impl VectorFloorRule {
+    pub fn new(vector: ThreatVector, floor: AlertDecision) -> Self {
+        Self { name: format!("{}_floor", vector), vector, floor }
+    }
+}
+
+impl EscalationRule for VectorFloorRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&self, context: &ThreatContext, decision: &AlertDecision) -> Option<RuleOutcome> {
+        if context.threat_vector != Some(self.vector) || severity_rank(decision) >= severity_rank(&self.floor) {
+            return None;
+        }
+        Some(RuleOutcome {
+            decision: self.floor.clone(),
+            reason: format!("{} alerts at {:?} or above", self.vector.label(), self.floor),
+            start_escalation: self.floor == AlertDecision::Critical,
+        })
+    }
+}
+
+fn severity_rank(decision: &AlertDecision) -> u8 {
+    match decision {
+        AlertDecision::Critical => 3,
+        AlertDecision::Elevated => 2,
+        AlertDecision::Standard => 1,
+        AlertDecision::Wait | AlertDecision::Ignore => 0,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleVerdict {
+    pub decision: AlertDecision,
+    pub rule: Option<String>, // Rule that changed the decision, if any
+    pub reason: Option<String>,
+    pub start_escalation: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscalationStep {
+    pub after_secs: i64,            // Delay from the start of the escalation
+    pub label: String,              // e.g. "Owner", "Neighbor Sam", "Monitoring center"
+    #[serde(default)]
+    pub user_id: Option<String>,    // Resident or contact account; None sends to the event's account
+    pub channels: Vec<DeliveryChannel>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EscalationChain {
+    pub steps: Vec<EscalationStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveEscalation {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub account_user_id: String, // Receives steps without their own recipient
+    pub reason: String,
+    pub started_at: DateTime<Utc>,
+    pub next_step: usize,
+    pub acknowledged_by: Option<String>,
+}
+
+/// A chain step whose delay has passed
+#[derive(Debug, Clone)]
+pub struct DueStep {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub account_user_id: String,
+    pub reason: String,
+    pub step: EscalationStep,
+    pub step_index: usize,
+}
+
+pub struct EscalationEngine {
+    rules: Vec<Arc<dyn EscalationRule>>,
+    zone_classes: DashMap<String, HashMap<String, ZoneClass>>, // home -> camera -> class
+    chains: DashMap<String, EscalationChain>,
+    active: DashMap<(String, u64), ActiveEscalation>,
+}
+
+impl Default for EscalationEngine {
+    fn default() -> Self {
+        Self::new().with_rule(Arc::new(InteriorWhileAwayRule))
+    }
+}
+
+impl std::fmt::Debug for EscalationEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EscalationEngine")
+            .field("rules", &self.rules.iter().map(|r| r.name().to_string()).collect::<Vec<_>>())
+            .field("active", &self.active.len())
+            .finish()
+    }
+}
+
+impl EscalationEngine {
+    /// Engine with no rules
+    pub fn new() -> Self {
+        Self { rules: Vec::new(), zone_classes: DashMap::new(), chains: DashMap::new(), active: DashMap::new() }
+    }
+
+    pub fn with_rule(mut self, rule: Arc<dyn EscalationRule>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn set_zone_classes(&self, home_id: &str, classes: HashMap<String, ZoneClass>) {
+        self.zone_classes.insert(home_id.to_string(), classes);
+    }
+
+    pub fn zone_class(&self, home_id: &str, camera: &str) -> Option<ZoneClass> {
+        self.zone_classes.get(home_id).and_then(|c| c.get(camera).copied())
+    }
+
+    /// First rule that fires decides; otherwise the engine's decision stands
+    pub fn apply(&self, context: &ThreatContext, decision: &AlertDecision) -> RuleVerdict {
+        for rule in &self.rules {
+            if let Some(outcome) = rule.evaluate(context, decision) {
+                return RuleVerdict {
+                    decision: outcome.decision,
+                    rule: Some(rule.name().to_string()),
+                    reason: Some(outcome.reason),
+                    start_escalation: outcome.start_escalation,
+                };
+            }
+        }
+        RuleVerdict { decision: decision.clone(), rule: None, reason: None, start_escalation: false }
+    }
+
+    pub fn set_chain(&self, home_id: &str, mut chain: EscalationChain) {
+        chain.steps.sort_by_key(|s| s.after_secs);
+        self.chains.insert(home_id.to_string(), chain);
+    }
+
+    /// The home's chain; without one, the incident's account is alerted once by push
+    pub fn chain(&self, home_id: &str) -> EscalationChain {
+        self.chains.get(home_id).map(|c| c.clone()).unwrap_or_else(|| EscalationChain {
+            steps: vec![EscalationStep {
+                after_secs: 0,
+                label: "Account holder".to_string(),
+                user_id: None,
+                channels: vec![DeliveryChannel::Push, DeliveryChannel::WebSocket],
+            }],
+        })
+    }
+
+    /// Begin escalating an incident; a running escalation for it is left as is
+    pub fn start(&self, home_id: &str, incident_id: u64, account_user_id: &str, reason: &str, at: DateTime<Utc>) -> bool {
+        let key = (home_id.to_string(), incident_id);
+        if self.active.contains_key(&key) {
+            return false;
+        }
+        self.active.insert(key, ActiveEscalation {
+            home_id: home_id.to_string(),
+            incident_id,
+            account_user_id: account_user_id.to_string(),
+            reason: reason.to_string(),
+            started_at: at,
+            next_step: 0,
+            acknowledged_by: None,
+        });
+        true
+    }
+
+    /// Stop an escalation; later steps are not sent
+    pub fn acknowledge(&self, home_id: &str, incident_id: u64, by: &str) -> Option<ActiveEscalation> {
+        let mut escalation = self.active.get_mut(&(home_id.to_string(), incident_id))?;
+        escalation.acknowledged_by.get_or_insert_with(|| by.to_string());
+        Some(escalation.clone())
+    }
+
+    pub fn cancel(&self, home_id: &str, incident_id: u64) {
+        self.active.remove(&(home_id.to_string(), incident_id));
+    }
+
+    pub fn active(&self, home_id: &str) -> Vec<ActiveEscalation> {
+        self.active.iter().filter(|e| e.home_id == home_id).map(|e| e.clone()).collect()
+    }
+
+    /// Steps whose delay has passed, each returned once; finished and
+    /// acknowledged escalations are dropped
+    pub fn due_steps(&self, now: DateTime<Utc>) -> Vec<DueStep> {
+        let mut due = Vec::new();
+        for mut entry in self.active.iter_mut() {
+            let escalation = entry.value_mut();
+            if escalation.acknowledged_by.is_some() {
+                continue;
+            }
+            let chain = self.chain(&escalation.home_id);
+            while let Some(step) = chain.steps.get(escalation.next_step) {
+                if escalation.started_at + Duration::seconds(step.after_secs) > now {
+                    break;
+                }
+                due.push(DueStep {
+                    home_id: escalation.home_id.clone(),
+                    incident_id: escalation.incident_id,
+                    account_user_id: escalation.account_user_id.clone(),
+                    reason: escalation.reason.clone(),
+                    step: step.clone(),
+                    step_index: escalation.next_step,
+                });
+                escalation.next_step += 1;
+            }
+        }
+        self.active.retain(|_, e| e.acknowledged_by.is_none() && e.next_step < self.chain(&e.home_id).steps.len());
+        due
+    }
+}
@@ -0,0 +1,164 @@
+// src/explanation.rs
+
+// Structured explanations for assessments. Instead of a free-text trace, every
+// score carries the factors that produced it (signed contribution and share of
+// the total), the single change most likely to flip the outcome, and a hash of
+// the configuration it was scored under so two explanations can be compared.
+// `render` turns one back into a sentence for summaries and logs.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Raises,
+    Lowers,
+    Neutral,
+}
+
+impl Direction {
+    pub fn of(contribution: f64) -> Self {
+        if contribution > 1e-9 {
+            Direction::Raises
+        } else if contribution < -1e-9 {
+            Direction::Lowers
+        } else {
+            Direction::Neutral
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplanationFactor {
+    pub name: String,
+    pub contribution: f64, // Signed, in the scorer's own units (LLR for the thinking AI)
+    pub weight: f64,       // |contribution| as a share of all factors, 0.0 to 1.0
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyCounterfactual {
+    pub description: String,
+    pub delta: f64,              // Change to the score if it happened
+    pub crosses_threshold: bool, // Whether it alone would change the decision
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Explanation {
+    pub headline: String,
+    pub factors: Vec<ExplanationFactor>, // Largest weight first
+    pub key_counterfactual: Option<KeyCounterfactual>,
+    pub config_hash: String, // Empty when the scoring configuration is unknown
+    #[serde(default)]
+    pub data_quality: Vec<String>, // Inputs repaired before scoring, one line each
+    #[serde(default)]
+    pub cautions: Vec<String>, // Doubts about the evidence itself, e.g. a possibly spoofed face
+    #[serde(default)]
+    pub saturated: Vec<String>, // Factors clamped at a cap, so stronger evidence would not have counted
+}
+
+impl Explanation {
+    /// Build from raw (name, contribution) pairs; weights and directions are derived
+    pub fn new(headline: impl Into<String>, contributions: Vec<(String, f64)>, config_hash: String) -> Self {
+        let total: f64 = contributions.iter()
+            .map(|(_, c)| if c.is_finite() { c.abs() } else { 0.0 })
+            .sum();
+        let mut factors: Vec<ExplanationFactor> = contributions.into_iter()
+            .filter(|(_, c)| c.is_finite())
+            .map(|(name, contribution)| ExplanationFactor {
+                weight: if total > 0.0 { contribution.abs() / total } else { 0.0 },
+                direction: Direction::of(contribution),
+                name,
+                contribution,
+            })
+            .collect();
+        factors.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(std::cmp::Ordering::Equal));
+
+        Self { headline: headline.into(), factors, key_counterfactual: None, config_hash, data_quality: Vec::new(), cautions: Vec::new(), saturated: Vec::new() }
+    }
+
+    pub fn with_counterfactual(mut self, counterfactual: Option<KeyCounterfactual>) -> Self {
+        self.key_counterfactual = counterfactual;
+        self
+    }
+
+    pub fn with_data_quality(mut self, flags: Vec<String>) -> Self {
+        self.data_quality = flags;
+        self
+    }
+
+    pub fn with_saturated(mut self, saturated: Vec<String>) -> Self {
+        self.saturated = saturated;
+        self
+    }
+
+    pub fn with_caution(mut self, caution: impl Into<String>) -> Self {
+        self.cautions.push(caution.into());
+        self
+    }
+
+    /// Legacy free-text traces become a headline with no factors
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self { headline: text.into(), ..Self::default() }
+    }
+
+    pub fn top_factors(&self, n: usize) -> impl Iterator<Item = &ExplanationFactor> {
+        self.factors.iter().filter(|f| f.direction != Direction::Neutral).take(n)
+    }
+
+    /// Net contribution of every factor
+    pub fn net_contribution(&self) -> f64 {
+        self.factors.iter().map(|f| f.contribution).sum()
+    }
+
+    /// Human-readable text, e.g. for narratives and CLI output
+    pub fn render(&self) -> String {
+        let mut text = self.headline.clone();
+
+        let raising: Vec<String> = self.top_factors(3)
+            .filter(|f| f.direction == Direction::Raises)
+            .map(|f| format!("{} ({:.0}%)", f.name, f.weight * 100.0))
+            .collect();
+        let lowering: Vec<String> = self.top_factors(3)
+            .filter(|f| f.direction == Direction::Lowers)
+            .map(|f| format!("{} ({:.0}%)", f.name, f.weight * 100.0))
+            .collect();
+        if !raising.is_empty() {
+            push_sentence(&mut text, &format!("Raised by {}", raising.join(", ")));
+        }
+        if !lowering.is_empty() {
+            push_sentence(&mut text, &format!("Lowered by {}", lowering.join(", ")));
+        }
+        if let Some(cf) = self.key_counterfactual.as_ref().filter(|cf| cf.crosses_threshold) {
+            push_sentence(&mut text, &format!("Would change with: {}", cf.description));
+        }
+        if !self.data_quality.is_empty() {
+            push_sentence(&mut text, &format!("Scored on repaired inputs ({} field(s))", self.data_quality.len()));
+        }
+        if !self.saturated.is_empty() {
+            push_sentence(&mut text, &format!("Capped: {}", self.saturated.join(", ")));
+        }
+        for caution in &self.cautions {
+            push_sentence(&mut text, caution.trim_end_matches('.'));
+        }
+        text
+    }
+}
+
+fn push_sentence(text: &mut String, sentence: &str) {
+    if !text.is_empty() {
+        if !text.ends_with('.') {
+            text.push('.');
+        }
+        text.push(' ');
+    }
+    text.push_str(sentence);
+    text.push('.');
+}
+
+/// Short stable fingerprint of a scoring configuration
+pub fn config_hash<T: Serialize>(config: &T) -> String {
+    let bytes = serde_json::to_vec(config).unwrap_or_default();
+    hex::encode(&Sha256::digest(&bytes)[..8])
+}
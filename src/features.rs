@@ -0,0 +1,97 @@
+// src/features.rs
+//
+// Subscription tier → capability table. Every tier-dependent decision in the
+// pipeline asks the gate instead of matching on SubscriptionTier directly, so
+// plan changes happen here only.
+
+use crate::pipeline::SubscriptionTier;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    ThinkingAI,      // Incident reasoning, questions, counterfactuals
+    OvernightReview, // Suppress overnight alerts into a morning summary
+    VideoClips,      // Clip capture around incidents
+    LlmNarratives,   // LLM-written incident summaries
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum FeatureGateError {
+    #[error("{feature:?} is not included in the {tier:?} plan (requires {required:?})")]
+    NotIncluded {
+        feature: Feature,
+        tier: SubscriptionTier,
+        required: Option<SubscriptionTier>, // Cheapest tier that has it
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TierCapabilities {
+    pub features: HashSet<Feature>,
+    pub retention_days: u32, // How long events, incidents and snapshots are kept
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureGate {
+    tiers: HashMap<SubscriptionTier, TierCapabilities>,
+}
+
+impl Default for FeatureGate {
+    fn default() -> Self {
+        let mut tiers = HashMap::new();
+        tiers.insert(SubscriptionTier::Free, TierCapabilities {
+            features: HashSet::new(),
+            retention_days: 1,
+        });
+        tiers.insert(SubscriptionTier::Standard, TierCapabilities {
+            features: [Feature::OvernightReview].into_iter().collect(),
+            retention_days: 7,
+        });
+        tiers.insert(SubscriptionTier::Premium, TierCapabilities {
+            features: [
+                Feature::ThinkingAI,
+                Feature::OvernightReview,
+                Feature::VideoClips,
+                Feature::LlmNarratives,
+            ].into_iter().collect(),
+            retention_days: 30,
+        });
+        Self { tiers }
+    }
+}
+
+impl FeatureGate {
+    pub fn new(tiers: HashMap<SubscriptionTier, TierCapabilities>) -> Self {
+        Self { tiers }
+    }
+
+    pub fn capabilities(&self, tier: &SubscriptionTier) -> Option<&TierCapabilities> {
+        self.tiers.get(tier)
+    }
+
+    pub fn allows(&self, tier: &SubscriptionTier, feature: Feature) -> bool {
+        self.tiers.get(tier).map_or(false, |caps| caps.features.contains(&feature))
+    }
+
+    /// Ok when the tier includes the feature, otherwise an error naming the tier that does
+    pub fn require(&self, tier: &SubscriptionTier, feature: Feature) -> Result<(), FeatureGateError> {
+        if self.allows(tier, feature) {
+            return Ok(());
+        }
+        let required = [SubscriptionTier::Free, SubscriptionTier::Standard, SubscriptionTier::Premium]
+            .into_iter()
+            .find(|t| self.allows(t, feature));
+        Err(FeatureGateError::NotIncluded { feature, tier: tier.clone(), required })
+    }
+
+    /// Data retention for the tier; unknown tiers get the shortest configured window
+    pub fn retention(&self, tier: &SubscriptionTier) -> chrono::Duration {
+        let days = self.tiers.get(tier)
+            .map(|caps| caps.retention_days)
+            .or_else(|| self.tiers.values().map(|caps| caps.retention_days).min())
+            .unwrap_or(1);
+        chrono::Duration::days(days as i64)
+    }
+}
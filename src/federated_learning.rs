@@ -0,0 +1,330 @@
+// src/federated_learning.rs
+
+// Opt-in federated averaging of learned LLR channel weights. Each node
+// summarizes how its homes' weights moved away from the last global model,
+// clips every home's move, averages them and adds Gaussian noise calibrated
+// for (epsilon, delta) differential privacy before uploading. Home ids and
+// raw labels never leave the node; nodes with too few contributing homes do
+// not upload at all. The VPS merges updates for a round weighted by how many
+// homes each represents, and nodes periodically pull the merged model back in
+// as their population prior. Nothing runs unless `enabled` is set.
+
+use crate::clock::{system_clock, Clock};
+use crate::thinking::{ChannelWeights, OnlineWeightLearner};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+pub enum FederatedError {
+    #[error("Federated learning is not enabled")]
+    Disabled,
+
+    #[error("Transport error: {0}")]
+    Transport(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FederatedLearningConfig {
+    pub enabled: bool,
+    pub epsilon: f64,          // Privacy budget per upload
+    pub delta: f64,
+    pub clip_norm: f64,        // L2 bound on any one home's contribution
+    pub min_home_updates: u64, // Labels a home needs before it contributes
+    pub min_homes: u32,        // Contributing homes a node needs before it uploads
+    pub sync_interval_secs: u64,
+}
+
+impl Default for FederatedLearningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            epsilon: 1.0,
+            delta: 1e-5,
+            clip_norm: 0.5,
+            min_home_updates: 5,
+            min_homes: 3,
+            sync_interval_secs: 6 * 3600,
+        }
+    }
+}
+
+impl FederatedLearningConfig {
+    /// Gaussian mechanism noise for a mean of `homes` clipped contributions
+    pub fn noise_sigma(&self, homes: u32) -> f64 {
+        let sensitivity = self.clip_norm / homes.max(1) as f64;
+        sensitivity * (2.0 * (1.25 / self.delta).ln()).sqrt() / self.epsilon
+    }
+}
+
+// Global model as published by the VPS
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GlobalModel {
+    pub round: u64,
+    pub weights: ChannelWeights,
+}
+
+impl Default for GlobalModel {
+    fn default() -> Self {
+        Self { round: 0, weights: ChannelWeights::default() }
+    }
+}
+
+// What a node uploads: a noised mean move in channel weights, nothing per-home
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUpdate {
+    pub node_id: Uuid,
+    pub base_round: u64,    // Global round the deltas are relative to
+    pub contributors: u32,  // Homes averaged into the delta
+    pub delta: [f64; 7],    // ChannelWeights::to_vector layout
+    pub epsilon: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait FederationTransport: Send + Sync {
+    async fn upload(&self, update: &ModelUpdate) -> Result<(), FederatedError>;
+    async fn pull_global(&self) -> Result<Option<GlobalModel>, FederatedError>;
+}
+
+/// Talks to the VPS federated-learning endpoints
+pub struct VpsFederationTransport {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl VpsFederationTransport {
+    pub fn new(base_url: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self { client, base_url: base_url.trim_end_matches('/').to_string() }
+    }
+}
+
+#[async_trait]
+impl FederationTransport for VpsFederationTransport {
+    async fn upload(&self, update: &ModelUpdate) -> Result<(), FederatedError> {
+        let url = format!("{}/v1/federated/updates", self.base_url);
+        let response = self.client.post(&url).json(update).send().await
+            .map_err(|e| FederatedError::Transport(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(FederatedError::Transport(format!("upload returned {}", response.status())));
+        }
+        Ok(())
+    }
+
+    async fn pull_global(&self) -> Result<Option<GlobalModel>, FederatedError> {
+        let url = format!("{}/v1/federated/global", self.base_url);
+        let response = self.client.get(&url).send().await
+            .map_err(|e| FederatedError::Transport(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(FederatedError::Transport(format!("pull returned {}", response.status())));
+        }
+        response.json().await.map(Some).map_err(|e| FederatedError::Transport(e.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SyncOutcome {
+    pub uploaded: Option<u32>, // Contributors in the uploaded update, if one was sent
+    pub global_round: u64,     // Round in effect after the pull
+}
+
+pub struct FederatedLearningNode {
+    config: FederatedLearningConfig,
+    node_id: Uuid,
+    learner: Option<Arc<OnlineWeightLearner>>,
+    transport: Option<Arc<dyn FederationTransport>>,
+    global: RwLock<GlobalModel>,
+    persist_to: Option<PathBuf>, // Learner weights file rewritten after a new global model
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for FederatedLearningNode {
+    fn default() -> Self {
+        Self {
+            config: FederatedLearningConfig::default(),
+            node_id: Uuid::default(),
+            learner: None,
+            transport: None,
+            global: RwLock::default(),
+            persist_to: None,
+            clock: system_clock(),
+        }
+    }
+}
+
+impl std::fmt::Debug for FederatedLearningNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FederatedLearningNode")
+            .field("enabled", &self.config.enabled)
+            .field("node_id", &self.node_id)
+            .field("global_round", &self.global_model().round)
+            .finish()
+    }
+}
+
+impl FederatedLearningNode {
+    pub fn new(config: FederatedLearningConfig, learner: Arc<OnlineWeightLearner>, transport: Arc<dyn FederationTransport>) -> Self {
+        Self {
+            config,
+            node_id: Uuid::new_v4(),
+            learner: Some(learner),
+            transport: Some(transport),
+            global: RwLock::new(GlobalModel::default()),
+            persist_to: None,
+            clock: system_clock(),
+        }
+    }
+
+    pub fn with_persistence(mut self, path: PathBuf) -> Self {
+        self.persist_to = Some(path);
+        self
+    }
+
+    /// Stamp uploaded updates and applied global weights with `clock`'s time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled && self.learner.is_some() && self.transport.is_some()
+    }
+
+    pub fn global_model(&self) -> GlobalModel {
+        self.global.read().map(|g| *g).unwrap_or_default()
+    }
+
+    /// Clipped, averaged and noised move of this node's homes away from the
+    /// global model; None if too few homes have enough labels
+    pub fn summarize<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<ModelUpdate> {
+        let learner = self.learner.as_ref()?;
+        let global = self.global_model();
+        let base = global.weights.to_vector();
+
+        let deltas: Vec<[f64; 7]> = learner.home_weights().into_iter()
+            .filter(|(_, learned)| learned.updates >= self.config.min_home_updates)
+            .map(|(_, learned)| {
+                let mut delta = learned.weights.to_vector();
+                delta.iter_mut().zip(base).for_each(|(d, b)| *d -= b);
+                clip(delta, self.config.clip_norm)
+            })
+            .collect();
+        let contributors = deltas.len() as u32;
+        if contributors < self.config.min_homes.max(1) {
+            return None;
+        }
+
+        let sigma = self.config.noise_sigma(contributors);
+        let mut mean = [0.0; 7];
+        for delta in &deltas {
+            mean.iter_mut().zip(delta).for_each(|(m, d)| *m += d / contributors as f64);
+        }
+        mean.iter_mut().for_each(|m| *m += sigma * standard_normal(rng));
+
+        Some(ModelUpdate {
+            node_id: self.node_id,
+            base_round: global.round,
+            contributors,
+            delta: mean,
+            epsilon: self.config.epsilon,
+            created_at: self.clock.now(),
+        })
+    }
+
+    /// Upload this node's update, then pull and apply the latest global model
+    pub async fn sync(&self) -> Result<SyncOutcome, FederatedError> {
+        if !self.enabled() {
+            return Err(FederatedError::Disabled);
+        }
+        let (Some(learner), Some(transport)) = (&self.learner, &self.transport) else {
+            return Err(FederatedError::Disabled);
+        };
+
+        let update = self.summarize(&mut rand::thread_rng());
+        if let Some(update) = &update {
+            transport.upload(update).await?;
+        }
+
+        if let Some(global) = transport.pull_global().await? {
+            if global.round > self.global_model().round {
+                learner.set_population(global.weights, self.clock.now());
+                if let Ok(mut current) = self.global.write() {
+                    *current = global;
+                }
+                info!("Applied federated global model round {}", global.round);
+                if let Some(path) = &self.persist_to {
+                    if let Err(e) = learner.save(path) {
+                        warn!("Failed to persist weights after federated sync to {}: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+
+        Ok(SyncOutcome { uploaded: update.map(|u| u.contributors), global_round: self.global_model().round })
+    }
+
+    /// Sync on a fixed interval; does nothing unless enabled
+    pub fn spawn(self: Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.enabled() {
+            return None;
+        }
+        let every = Duration::from_secs(self.config.sync_interval_secs.max(60));
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.sync().await {
+                    warn!("Federated sync failed: {}", e);
+                }
+            }
+        }))
+    }
+}
+
+/// Server-side FedAvg: move the global model by the contributor-weighted mean
+/// of updates made against its current round; stale updates are ignored
+pub fn merge_updates(global: &GlobalModel, updates: &[ModelUpdate], server_lr: f64) -> GlobalModel {
+    let current: Vec<&ModelUpdate> = updates.iter()
+        .filter(|u| u.base_round == global.round && u.contributors > 0 && u.delta.iter().all(|d| d.is_finite()))
+        .collect();
+    let total: f64 = current.iter().map(|u| u.contributors as f64).sum();
+    if total == 0.0 {
+        return *global;
+    }
+
+    let mut merged = global.weights.to_vector();
+    for update in current {
+        let share = server_lr * update.contributors as f64 / total;
+        merged.iter_mut().zip(update.delta).for_each(|(w, d)| *w += share * d);
+    }
+    GlobalModel { round: global.round + 1, weights: ChannelWeights::from_vector(merged) }
+}
+
+fn clip(mut v: [f64; 7], max_norm: f64) -> [f64; 7] {
+    let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > max_norm && norm > 0.0 {
+        v.iter_mut().for_each(|x| *x *= max_norm / norm);
+    }
+    v
+}
+
+// Box–Muller
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
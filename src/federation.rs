@@ -0,0 +1,332 @@
+// src/federation.rs
+//
+// Opt-in neighborhood threat sharing. Homes that join share anonymized incident
+// signatures (keyed hashes of vehicle plates, coarse prowler patterns) with nearby
+// members. When the same signature is reported by several nearby homes within the
+// time window, every member in the area gets a raised threat prior.
+
+use crate::validation::Validate;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::RwLock;
+use tracing::info;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum FederationError {
+    #[error("Home {0} has not joined the federation")]
+    NotAMember(String),
+
+    #[error("Home {0} has revoked federation sharing")]
+    Revoked(String),
+
+    #[error("A federation shared key is required")]
+    MissingKey,
+
+    #[error("Invalid federation setting {0}: {1}")]
+    Config(&'static str, String),
+}
+
+// How much a member's reports count towards corroboration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrustLevel {
+    Untrusted, // Reports are stored but ignored
+    Neighbor,  // Self-enrolled member
+    Verified,  // Address verified by the operator
+}
+
+impl TrustLevel {
+    pub fn weight(&self) -> f64 {
+        match self {
+            TrustLevel::Untrusted => 0.0,
+            TrustLevel::Neighbor => 0.5,
+            TrustLevel::Verified => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignatureKind {
+    VehiclePlate,
+    ProwlerPattern,
+}
+
+// Anonymized signature as shared with the federation; never contains raw plates or home ids
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentSignature {
+    pub kind: SignatureKind,
+    pub digest: String,
+    pub observed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FederationConfig {
+    pub shared_key: String,      // Federation-wide HMAC key so equal plates hash equally
+    pub window: Duration,        // How long a report counts ("tonight")
+    pub radius_km: f64,          // Members within this distance are neighbors
+    pub min_reporting_homes: f64, // Trust-weighted homes needed before the prior moves
+    pub llr_per_home: f64,       // Prior logit added per weighted corroborating home
+    pub max_prior_boost: f64,
+}
+
+impl Default for FederationConfig {
+    fn default() -> Self {
+        Self {
+            shared_key: String::new(),
+            window: Duration::hours(8),
+            radius_km: 2.0,
+            min_reporting_homes: 1.5,
+            llr_per_home: 0.3,
+            max_prior_boost: 1.5,
+        }
+    }
+}
+
+impl FederationConfig {
+    /// Config from the environment; None unless NOVIN_FEDERATION_KEY is set
+    pub fn from_env() -> Result<Option<Self>, FederationError> {
+        let Ok(shared_key) = std::env::var("NOVIN_FEDERATION_KEY") else {
+            return Ok(None);
+        };
+        let mut config = Self { shared_key, ..Self::default() };
+        if let Ok(v) = std::env::var("NOVIN_FEDERATION_RADIUS_KM") {
+            config.radius_km = v.parse().map_err(|_| FederationError::Config("NOVIN_FEDERATION_RADIUS_KM", v))?;
+        }
+        if let Ok(v) = std::env::var("NOVIN_FEDERATION_WINDOW_HOURS") {
+            let hours: i64 = v.parse().map_err(|_| FederationError::Config("NOVIN_FEDERATION_WINDOW_HOURS", v))?;
+            config.window = Duration::hours(hours);
+        }
+        Ok(Some(config))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FederationMember {
+    pub member_id: Uuid, // Pseudonym used in shared reports
+    #[serde(skip_serializing)]
+    pub home_id: String,
+    pub trust: TrustLevel,
+    pub joined_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    location: (f64, f64), // Rounded to ~1km so exact addresses are never held
+}
+
+#[derive(Debug, Clone)]
+struct SharedReport {
+    member_id: Uuid,
+    signature: IncidentSignature,
+}
+
+// Corroboration of one signature around a home
+#[derive(Debug, Clone, Serialize)]
+pub struct Corroboration {
+    pub kind: SignatureKind,
+    pub digest: String,
+    pub reporting_homes: usize,
+    pub weighted_homes: f64,
+    pub prior_boost: f64,
+}
+
+/// Keyed hash of a normalized plate ("ab12 cde" and "AB12CDE" match)
+pub fn hash_plate(shared_key: &str, plate: &str) -> String {
+    let normalized: String = plate.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_uppercase();
+    keyed_digest(shared_key, "plate", &normalized)
+}
+
+/// Keyed hash of a prowler pattern: zone sequence plus hour bucket, no imagery
+pub fn hash_prowler_pattern(shared_key: &str, zones: &[String], observed_at: DateTime<Utc>) -> String {
+    let hour_bucket = observed_at.timestamp() / 3600 / 2; // 2-hour buckets
+    keyed_digest(shared_key, "prowler", &format!("{}|{}", zones.join(">"), hour_bucket))
+}
+
+/// Plate from a `plate=` token in an event's sensor data, if the camera read one
+pub fn plate_from_payload(data: &str) -> Option<&str> {
+    data.split(['|', ',', ';'])
+        .filter_map(|token| token.split_once('='))
+        .find(|(name, _)| name.trim() == "plate")
+        .map(|(_, value)| value.trim())
+        .filter(|plate| !plate.is_empty())
+}
+
+fn keyed_digest(shared_key: &str, domain: &str, value: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(shared_key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(domain.as_bytes());
+    mac.update(b":");
+    mac.update(value.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn coarse_location(latitude: f64, longitude: f64) -> (f64, f64) {
+    ((latitude * 100.0).round() / 100.0, (longitude * 100.0).round() / 100.0)
+}
+
+fn distance_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (a.0.to_radians(), a.1.to_radians());
+    let (lat2, lon2) = (b.0.to_radians(), b.1.to_radians());
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * 6371.0 * h.sqrt().asin()
+}
+
+// In-process federation hub shared by all homes on this deployment
+pub struct FederationHub {
+    config: FederationConfig,
+    members: RwLock<HashMap<String, FederationMember>>, // home_id -> member
+    reports: RwLock<Vec<SharedReport>>,
+}
+
+impl FederationHub {
+    /// Hub for `config`. Plates are few enough to hash exhaustively, so without
+    /// a secret shared key anyone could reverse the digests; an empty key is refused.
+    pub fn new(config: FederationConfig) -> Result<Self, FederationError> {
+        if config.shared_key.trim().is_empty() {
+            return Err(FederationError::MissingKey);
+        }
+        config.validate().map_err(|e| FederationError::Config("config", e.to_string()))?;
+        Ok(Self {
+            config,
+            members: RwLock::new(HashMap::new()),
+            reports: RwLock::new(Vec::new()),
+        })
+    }
+
+    /// Opt a home in; rejoining after revocation issues a fresh pseudonym
+    pub async fn join(&self, home_id: &str, latitude: f64, longitude: f64, trust: TrustLevel) -> Uuid {
+        let member = FederationMember {
+            member_id: Uuid::new_v4(),
+            home_id: home_id.to_string(),
+            trust,
+            joined_at: Utc::now(),
+            revoked_at: None,
+            location: coarse_location(latitude, longitude),
+        };
+        let member_id = member.member_id;
+        self.members.write().await.insert(home_id.to_string(), member);
+        info!("Home {} joined neighborhood federation", home_id);
+        member_id
+    }
+
+    /// Opt a home out; its past reports stop counting immediately
+    pub async fn revoke(&self, home_id: &str) -> Result<(), FederationError> {
+        let member_id = {
+            let mut members = self.members.write().await;
+            let member = members.get_mut(home_id)
+                .ok_or_else(|| FederationError::NotAMember(home_id.to_string()))?;
+            member.revoked_at = Some(Utc::now());
+            member.member_id
+        };
+        self.reports.write().await.retain(|r| r.member_id != member_id);
+        info!("Home {} revoked neighborhood federation sharing", home_id);
+        Ok(())
+    }
+
+    pub async fn set_trust(&self, home_id: &str, trust: TrustLevel) -> Result<(), FederationError> {
+        let mut members = self.members.write().await;
+        let member = members.get_mut(home_id)
+            .ok_or_else(|| FederationError::NotAMember(home_id.to_string()))?;
+        member.trust = trust;
+        Ok(())
+    }
+
+    pub async fn member(&self, home_id: &str) -> Option<FederationMember> {
+        self.members.read().await.get(home_id).cloned()
+    }
+
+    /// Share a vehicle sighting; the plate is hashed before it leaves this call
+    pub async fn share_plate(&self, home_id: &str, plate: &str, observed_at: DateTime<Utc>) -> Result<(), FederationError> {
+        let signature = IncidentSignature {
+            kind: SignatureKind::VehiclePlate,
+            digest: hash_plate(&self.config.shared_key, plate),
+            observed_at,
+        };
+        self.share(home_id, signature).await
+    }
+
+    /// Share a prowler pattern (zone sequence) seen around a home
+    pub async fn share_prowler_pattern(&self, home_id: &str, zones: &[String], observed_at: DateTime<Utc>) -> Result<(), FederationError> {
+        let signature = IncidentSignature {
+            kind: SignatureKind::ProwlerPattern,
+            digest: hash_prowler_pattern(&self.config.shared_key, zones, observed_at),
+            observed_at,
+        };
+        self.share(home_id, signature).await
+    }
+
+    pub async fn share(&self, home_id: &str, signature: IncidentSignature) -> Result<(), FederationError> {
+        let member_id = {
+            let members = self.members.read().await;
+            let member = members.get(home_id)
+                .ok_or_else(|| FederationError::NotAMember(home_id.to_string()))?;
+            if member.revoked_at.is_some() {
+                return Err(FederationError::Revoked(home_id.to_string()));
+            }
+            member.member_id
+        };
+
+        let mut reports = self.reports.write().await;
+        let cutoff = Utc::now() - self.config.window;
+        reports.retain(|r| r.signature.observed_at >= cutoff);
+        reports.push(SharedReport { member_id, signature });
+        Ok(())
+    }
+
+    /// Signatures reported by enough nearby members within the window
+    pub async fn corroborations(&self, home_id: &str, at: DateTime<Utc>) -> Vec<Corroboration> {
+        let members = self.members.read().await;
+        let Some(me) = members.get(home_id).filter(|m| m.revoked_at.is_none()) else {
+            return Vec::new();
+        };
+
+        let neighbors: HashMap<Uuid, &FederationMember> = members
+            .values()
+            .filter(|m| m.revoked_at.is_none())
+            .filter(|m| distance_km(me.location, m.location) <= self.config.radius_km)
+            .map(|m| (m.member_id, m))
+            .collect();
+
+        let cutoff = at - self.config.window;
+        let mut by_signature: HashMap<(SignatureKind, String), HashSet<Uuid>> = HashMap::new();
+        for report in self.reports.read().await.iter() {
+            if report.signature.observed_at < cutoff || report.signature.observed_at > at {
+                continue;
+            }
+            if neighbors.contains_key(&report.member_id) {
+                by_signature
+                    .entry((report.signature.kind, report.signature.digest.clone()))
+                    .or_default()
+                    .insert(report.member_id);
+            }
+        }
+
+        by_signature
+            .into_iter()
+            .filter_map(|((kind, digest), reporters)| {
+                let weighted_homes: f64 = reporters.iter().map(|id| neighbors[id].trust.weight()).sum();
+                if weighted_homes < self.config.min_reporting_homes {
+                    return None;
+                }
+                Some(Corroboration {
+                    kind,
+                    digest,
+                    reporting_homes: reporters.len(),
+                    weighted_homes,
+                    prior_boost: (weighted_homes * self.config.llr_per_home).min(self.config.max_prior_boost),
+                })
+            })
+            .collect()
+    }
+
+    /// Prior logit boost for a home from the strongest corroborated signature nearby
+    pub async fn area_prior_boost(&self, home_id: &str, at: DateTime<Utc>) -> f64 {
+        self.corroborations(home_id, at).await
+            .iter()
+            .map(|c| c.prior_boost)
+            .fold(0.0, f64::max)
+    }
+}
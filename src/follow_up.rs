@@ -0,0 +1,303 @@
+// src/follow_up.rs
+
+// Follow-up scheduler for abstentions: an incident that scores Wait gets a
+// timer. New evidence while it waits escalates it to Standard; if nothing
+// arrives before the last delay it resolves to Ignore. Timers are kept in a
+// durable store so a restart does not leave incidents waiting forever.
+
+use crate::thinking::AlertDecision;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub struct FollowUpConfig {
+    pub delays: Vec<Duration>, // Re-evaluation points after the Wait; the last one resolves to Ignore
+}
+
+impl Default for FollowUpConfig {
+    fn default() -> Self {
+        Self {
+            delays: vec![Duration::seconds(60), Duration::seconds(180)],
+        }
+    }
+}
+
+// A pending re-evaluation for one waiting incident
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FollowUp {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub user_id: String,
+    pub zone: Option<String>,
+    pub event_count: usize, // Events in the incident when it started waiting
+    pub attempt: usize,     // Index into FollowUpConfig::delays
+    pub scheduled_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+}
+
+// What to do with a waiting incident
+#[derive(Debug, Clone, PartialEq)]
+pub enum FollowUpResolution {
+    Escalate(AlertDecision), // New information arrived, alert now
+    Ignore,                  // Nothing arrived before the last delay
+}
+
+#[derive(Error, Debug)]
+pub enum FollowUpError {
+    #[error("Follow-up storage error: {0}")]
+    Storage(String),
+}
+
+// Durable backing store so pending follow-ups survive restarts
+#[async_trait]
+pub trait FollowUpStore: Send + Sync {
+    async fn init(&self) -> Result<(), FollowUpError> {
+        Ok(())
+    }
+    async fn put(&self, follow_up: &FollowUp) -> Result<(), FollowUpError>;
+    async fn remove(&self, home_id: &str, incident_id: u64) -> Result<(), FollowUpError>;
+    async fn load_all(&self) -> Result<Vec<FollowUp>, FollowUpError>;
+}
+
+// SQLite implementation backed by the `follow_ups` table
+pub struct SqliteFollowUpStore {
+    pool: SqlitePool,
+}
+
+impl SqliteFollowUpStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl FollowUpStore for SqliteFollowUpStore {
+    // Creates the table if the migrations have not been run
+    async fn init(&self) -> Result<(), FollowUpError> {
+        sqlx::query(include_str!("api/migrations/006_follow_ups.sql"))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FollowUpError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn put(&self, follow_up: &FollowUp) -> Result<(), FollowUpError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO follow_ups (home_id, incident_id, user_id, zone, event_count, attempt, scheduled_at, due_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&follow_up.home_id)
+        .bind(follow_up.incident_id as i64)
+        .bind(&follow_up.user_id)
+        .bind(&follow_up.zone)
+        .bind(follow_up.event_count as i64)
+        .bind(follow_up.attempt as i64)
+        .bind(follow_up.scheduled_at.timestamp())
+        .bind(follow_up.due_at.timestamp())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FollowUpError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn remove(&self, home_id: &str, incident_id: u64) -> Result<(), FollowUpError> {
+        sqlx::query("DELETE FROM follow_ups WHERE home_id = ? AND incident_id = ?")
+            .bind(home_id)
+            .bind(incident_id as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FollowUpError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn load_all(&self) -> Result<Vec<FollowUp>, FollowUpError> {
+        let rows: Vec<(String, i64, String, Option<String>, i64, i64, i64, i64)> = sqlx::query_as(
+            "SELECT home_id, incident_id, user_id, zone, event_count, attempt, scheduled_at, due_at FROM follow_ups",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FollowUpError::Storage(e.to_string()))?;
+
+        Ok(rows.into_iter()
+            .map(|(home_id, incident_id, user_id, zone, event_count, attempt, scheduled_at, due_at)| FollowUp {
+                home_id,
+                incident_id: incident_id as u64,
+                user_id,
+                zone,
+                event_count: event_count as usize,
+                attempt: attempt as usize,
+                scheduled_at: DateTime::from_timestamp(scheduled_at, 0).unwrap_or_else(Utc::now),
+                due_at: DateTime::from_timestamp(due_at, 0).unwrap_or_else(Utc::now),
+            })
+            .collect())
+    }
+}
+
+pub struct FollowUpScheduler {
+    config: FollowUpConfig,
+    pending: RwLock<HashMap<(String, u64), FollowUp>>,
+    store: Option<Arc<dyn FollowUpStore>>,
+}
+
+impl FollowUpScheduler {
+    pub fn new(config: FollowUpConfig) -> Self {
+        Self {
+            config,
+            pending: RwLock::new(HashMap::new()),
+            store: None,
+        }
+    }
+
+    pub fn with_store(config: FollowUpConfig, store: Arc<dyn FollowUpStore>) -> Self {
+        let mut scheduler = Self::new(config);
+        scheduler.store = Some(store);
+        scheduler
+    }
+
+    /// Load follow-ups persisted before a restart; overdue ones fire on the next tick
+    pub async fn restore(&self) -> Result<usize, FollowUpError> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        store.init().await?;
+        let loaded = store.load_all().await?;
+        let mut pending = self.pending.write().await;
+        for follow_up in loaded {
+            pending.insert((follow_up.home_id.clone(), follow_up.incident_id), follow_up);
+        }
+        Ok(pending.len())
+    }
+
+    fn delay(&self, attempt: usize) -> Option<Duration> {
+        self.config.delays.get(attempt).copied()
+    }
+
+    /// Feed every fresh assessment of an incident through the scheduler
+    ///
+    /// A Wait starts a timer; another Wait with more events than when the timer
+    /// started escalates; any other decision settles the incident on its own.
+    pub async fn on_assessment(
+        &self,
+        home_id: &str,
+        incident_id: u64,
+        user_id: &str,
+        zone: Option<&str>,
+        decision: &AlertDecision,
+        event_count: usize,
+        now: DateTime<Utc>,
+    ) -> Result<Option<FollowUpResolution>, FollowUpError> {
+        let key = (home_id.to_string(), incident_id);
+        let existing = self.pending.read().await.get(&key).cloned();
+
+        match (decision, existing) {
+            (AlertDecision::Wait, Some(waiting)) if event_count > waiting.event_count => {
+                self.complete(home_id, incident_id).await?;
+                Ok(Some(FollowUpResolution::Escalate(AlertDecision::Standard)))
+            }
+            (AlertDecision::Wait, Some(_)) => Ok(None),
+            (AlertDecision::Wait, None) => {
+                let Some(delay) = self.delay(0) else {
+                    return Ok(None);
+                };
+                let follow_up = FollowUp {
+                    home_id: home_id.to_string(),
+                    incident_id,
+                    user_id: user_id.to_string(),
+                    zone: zone.map(str::to_string),
+                    event_count,
+                    attempt: 0,
+                    scheduled_at: now,
+                    due_at: now + delay,
+                };
+                self.save(follow_up).await?;
+                Ok(None)
+            }
+            (_, Some(_)) => {
+                self.complete(home_id, incident_id).await?;
+                Ok(None)
+            }
+            (_, None) => Ok(None),
+        }
+    }
+
+    /// Follow-ups whose delay has elapsed
+    pub async fn due(&self, now: DateTime<Utc>) -> Vec<FollowUp> {
+        let mut due: Vec<FollowUp> = self.pending.read().await
+            .values()
+            .filter(|f| f.due_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|f| f.due_at);
+        due
+    }
+
+    /// Settle a due follow-up given the incident's re-evaluation
+    ///
+    /// `reassessed` is None when the incident no longer exists (expired or lost
+    /// to a restart); with nothing new to go on that resolves to Ignore.
+    pub async fn on_due(
+        &self,
+        follow_up: &FollowUp,
+        reassessed: Option<&AlertDecision>,
+        event_count: usize,
+        now: DateTime<Utc>,
+    ) -> Result<Option<FollowUpResolution>, FollowUpError> {
+        let resolution = match reassessed {
+            Some(AlertDecision::Wait) if event_count > follow_up.event_count => {
+                Some(FollowUpResolution::Escalate(AlertDecision::Standard))
+            }
+            Some(AlertDecision::Wait) => match self.delay(follow_up.attempt + 1) {
+                Some(delay) => {
+                    let mut next = follow_up.clone();
+                    next.attempt += 1;
+                    next.due_at = now + delay;
+                    self.save(next).await?;
+                    return Ok(None);
+                }
+                None => Some(FollowUpResolution::Ignore),
+            },
+            Some(AlertDecision::Ignore) | None => Some(FollowUpResolution::Ignore),
+            Some(decision) => Some(FollowUpResolution::Escalate(decision.clone())),
+        };
+        self.complete(&follow_up.home_id, follow_up.incident_id).await?;
+        Ok(resolution)
+    }
+
+    /// Pending follow-ups for a home, soonest first
+    pub async fn pending(&self, home_id: &str) -> Vec<FollowUp> {
+        let mut pending: Vec<FollowUp> = self.pending.read().await
+            .values()
+            .filter(|f| f.home_id == home_id)
+            .cloned()
+            .collect();
+        pending.sort_by_key(|f| f.due_at);
+        pending
+    }
+
+    async fn save(&self, follow_up: FollowUp) -> Result<(), FollowUpError> {
+        if let Some(store) = &self.store {
+            store.put(&follow_up).await?;
+        }
+        self.pending.write().await.insert((follow_up.home_id.clone(), follow_up.incident_id), follow_up);
+        Ok(())
+    }
+
+    async fn complete(&self, home_id: &str, incident_id: u64) -> Result<(), FollowUpError> {
+        self.pending.write().await.remove(&(home_id.to_string(), incident_id));
+        if let Some(store) = &self.store {
+            store.remove(home_id, incident_id).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for FollowUpScheduler {
+    fn default() -> Self {
+        Self::new(FollowUpConfig::default())
+    }
+}
@@ -0,0 +1,335 @@
+// src/guest_access.rs
+
+// Guest profiles: people the household expects for a while, like the
+// babysitter on Friday evenings or the builders this week. A profile combines
+// when the guest is expected (weekly windows in the home's timezone, within an
+// overall validity period), how they are recognised (re-identified entities,
+// face/appearance samples enrolled for them, and optionally a visitor access
+// code issued for the period) and when it stops applying: a profile expires
+// on its own at `expires_at` and is dropped after that.
+//
+// A detection matching a guest inside their window is strong benign identity
+// evidence and counts as an expected visit. A match outside the window earns
+// nothing, cancels any benign evidence the guest's own access code carried,
+// and is called out in the incident narrative, since the babysitter's code
+// used at 3am on a Tuesday is exactly what the household wants to hear about.
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::embeddings::{EmbeddingError, EmbeddingKind, EmbeddingStore};
+use crate::visitor_tokens::{IssueTokenRequest, TokenError, VisitorTokenStore};
+
+#[derive(thiserror::Error, Debug)]
+pub enum GuestError {
+    #[error("Guest access ends before it starts or has already ended")]
+    InvalidWindow,
+
+    #[error("Guest access of {0} days exceeds the allowed maximum")]
+    TooLong(i64),
+
+    #[error("Unknown timezone '{0}'")]
+    UnknownTimezone(String),
+
+    #[error("Guest window starts and ends at the same time")]
+    EmptyWindow,
+
+    #[error("A guest profile needs a name")]
+    MissingName,
+
+    #[error("Guest access is not enabled")]
+    Disabled,
+
+    #[error("Not available on this server: {0}")]
+    Unsupported(&'static str),
+
+    #[error("Guest profile {0} not found")]
+    NotFound(Uuid),
+
+    #[error("Face enrollment failed: {0}")]
+    Enrollment(#[from] EmbeddingError),
+
+    #[error("Access code not issued: {0}")]
+    Token(#[from] TokenError),
+}
+
+#[derive(Debug, Clone)]
+pub struct GuestAccessConfig {
+    pub expected_identity_llr: f64, // Identity evidence for a guest seen inside their window
+    pub max_validity: Duration,
+}
+
+impl Default for GuestAccessConfig {
+    fn default() -> Self {
+        Self {
+            expected_identity_llr: -3.0,
+            max_validity: Duration::days(90),
+        }
+    }
+}
+
+/// A weekly window in the home's timezone; `end` before `start` runs past midnight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuestWindow {
+    #[serde(default)]
+    pub days: Vec<Weekday>, // Days the window starts on; empty means every day
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl GuestWindow {
+    fn contains(&self, local: chrono::NaiveDateTime) -> bool {
+        let (date, time) = (local.date(), local.time());
+        let starts_on = |day: Weekday| self.days.is_empty() || self.days.contains(&day);
+        if self.start < self.end {
+            starts_on(date.weekday()) && time >= self.start && time < self.end
+        } else {
+            // Overnight: the evening part on the start day, the early part on the day after
+            (starts_on(date.weekday()) && time >= self.start) || (starts_on(date.weekday().pred()) && time < self.end)
+        }
+    }
+
+    fn describe(&self) -> String {
+        let days = match self.days.as_slice() {
+            [] => "daily".to_string(),
+            days => days.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("/"),
+        };
+        format!("{} {}-{}", days, self.start.format("%H:%M"), self.end.format("%H:%M"))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuestProfileRequest {
+    pub name: String, // "Babysitter", "Builders"
+    pub starts_at: Option<DateTime<Utc>>, // Defaults to now
+    pub expires_at: DateTime<Utc>,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    #[serde(default)]
+    pub windows: Vec<GuestWindow>, // Empty: expected any time until expiry
+    #[serde(default)]
+    pub entity_ids: Vec<String>, // People already re-identified, e.g. from an earlier incident
+    #[serde(default)]
+    pub face_embeddings: Vec<Vec<f32>>, // Appearance samples to enroll as a new entity
+    #[serde(default)]
+    pub issue_code: bool, // Also issue a visitor access code valid for the whole period
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GuestProfile {
+    pub id: Uuid,
+    pub home_id: String,
+    pub name: String,
+    pub starts_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub timezone: String,
+    pub windows: Vec<GuestWindow>,
+    pub entity_ids: Vec<String>,
+    pub token_id: Option<Uuid>, // Visitor code issued with the profile
+    pub created_at: DateTime<Utc>,
+}
+
+impl GuestProfile {
+    pub fn is_expired(&self, at: DateTime<Utc>) -> bool {
+        at >= self.expires_at
+    }
+
+    /// Whether the guest is expected at `at`
+    pub fn is_expected(&self, at: DateTime<Utc>) -> bool {
+        if at < self.starts_at || self.is_expired(at) {
+            return false;
+        }
+        if self.windows.is_empty() {
+            return true;
+        }
+        let tz: Tz = self.timezone.parse().unwrap_or(Tz::UTC);
+        let local = at.with_timezone(&tz).naive_local();
+        self.windows.iter().any(|w| w.contains(local))
+    }
+
+    pub fn describe_windows(&self) -> String {
+        match self.windows.is_empty() {
+            true => format!("until {}", self.expires_at.format("%Y-%m-%d %H:%M UTC")),
+            false => self.windows.iter().map(GuestWindow::describe).collect::<Vec<_>>().join(", "),
+        }
+    }
+}
+
+/// A new profile; the access code, if one was issued, is only returned here
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedGuest {
+    pub profile: GuestProfile,
+    pub access_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuestMatchKind {
+    Entity, // Re-identified as an enrolled person
+    Code,   // Entered the guest's access code
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GuestMatch {
+    pub guest_id: Uuid,
+    pub name: String,
+    pub matched_by: GuestMatchKind,
+    pub within_window: bool,
+    pub windows: String, // Human-readable expected windows
+}
+
+impl GuestMatch {
+    /// Sentence for the incident narrative
+    pub fn narrative(&self) -> String {
+        match self.within_window {
+            true => format!("Matches guest profile '{}' within their expected window ({}).", self.name, self.windows),
+            false => format!("Guest '{}' seen OUTSIDE their expected window ({}).", self.name, self.windows),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GuestRegistry {
+    config: GuestAccessConfig,
+    homes: DashMap<String, Vec<GuestProfile>>,
+}
+
+impl GuestRegistry {
+    pub fn new(config: GuestAccessConfig) -> Self {
+        Self { config, homes: DashMap::new() }
+    }
+
+    pub fn config(&self) -> &GuestAccessConfig {
+        &self.config
+    }
+
+    /// Create a profile, enrolling face samples and issuing a code where asked and possible
+    pub fn create(
+        &self,
+        home_id: &str,
+        request: GuestProfileRequest,
+        tokens: Option<&VisitorTokenStore>,
+        embeddings: Option<&EmbeddingStore>,
+        now: DateTime<Utc>,
+    ) -> Result<CreatedGuest, GuestError> {
+        let name = request.name.trim().to_string();
+        if name.is_empty() {
+            return Err(GuestError::MissingName);
+        }
+        let starts_at = request.starts_at.unwrap_or(now);
+        if request.expires_at <= starts_at || request.expires_at <= now {
+            return Err(GuestError::InvalidWindow);
+        }
+        if request.expires_at - starts_at > self.config.max_validity {
+            return Err(GuestError::TooLong((request.expires_at - starts_at).num_days()));
+        }
+        request.timezone.parse::<Tz>().map_err(|_| GuestError::UnknownTimezone(request.timezone.clone()))?;
+        if request.windows.iter().any(|w| w.start == w.end) {
+            return Err(GuestError::EmptyWindow);
+        }
+
+        if !request.face_embeddings.is_empty() && embeddings.is_none() {
+            return Err(GuestError::Unsupported("face enrollment needs appearance re-identification"));
+        }
+        if request.issue_code && tokens.is_none() {
+            return Err(GuestError::Unsupported("access codes need visitor tokens"));
+        }
+
+        let mut entity_ids = request.entity_ids;
+        if let (Some(store), false) = (embeddings, request.face_embeddings.is_empty()) {
+            let entity = Uuid::new_v4();
+            for sample in &request.face_embeddings {
+                if let Err(e) = store.insert(home_id, entity, EmbeddingKind::Appearance, sample) {
+                    store.remove_entity(home_id, entity);
+                    return Err(e.into());
+                }
+            }
+            entity_ids.push(format!("entity_{}", entity));
+        }
+        let issued = match (tokens, request.issue_code) {
+            (Some(tokens), true) => Some(tokens.issue_at(home_id, IssueTokenRequest {
+                label: format!("Guest: {}", name),
+                valid_from: Some(starts_at),
+                valid_until: request.expires_at,
+                max_uses: None,
+            }, now)?),
+            _ => None,
+        };
+
+        let profile = GuestProfile {
+            id: Uuid::new_v4(),
+            home_id: home_id.to_string(),
+            name,
+            starts_at,
+            expires_at: request.expires_at,
+            timezone: request.timezone,
+            windows: request.windows,
+            entity_ids,
+            token_id: issued.as_ref().map(|i| i.token.id),
+            created_at: now,
+        };
+        let mut profiles = self.homes.entry(home_id.to_string()).or_default();
+        profiles.retain(|p| !p.is_expired(now));
+        profiles.push(profile.clone());
+        Ok(CreatedGuest { profile, access_code: issued.map(|i| i.code) })
+    }
+
+    /// Profiles that have not expired, soonest to expire first
+    pub fn list(&self, home_id: &str, now: DateTime<Utc>) -> Vec<GuestProfile> {
+        let mut profiles: Vec<GuestProfile> = self.homes.get(home_id)
+            .map(|p| p.iter().filter(|p| !p.is_expired(now)).cloned().collect())
+            .unwrap_or_default();
+        profiles.sort_by_key(|p| p.expires_at);
+        profiles
+    }
+
+    /// End a profile early, revoking its access code
+    pub fn remove(&self, home_id: &str, guest_id: Uuid, tokens: Option<&VisitorTokenStore>) -> Result<GuestProfile, GuestError> {
+        let mut profiles = self.homes.get_mut(home_id).ok_or(GuestError::NotFound(guest_id))?;
+        let index = profiles.iter().position(|p| p.id == guest_id).ok_or(GuestError::NotFound(guest_id))?;
+        let profile = profiles.remove(index);
+        if let (Some(tokens), Some(token_id)) = (tokens, profile.token_id) {
+            let _ = tokens.revoke(home_id, token_id);
+        }
+        Ok(profile)
+    }
+
+    /// The unexpired guest a detection belongs to, by re-identified entity or by access code
+    pub fn match_visit(&self, home_id: &str, entity_id: &str, token_id: Option<Uuid>, at: DateTime<Utc>) -> Option<GuestMatch> {
+        let profiles = self.homes.get(home_id)?;
+        let mut matches = profiles.iter().filter(|p| !p.is_expired(at)).filter_map(|p| {
+            let matched_by = if p.entity_ids.iter().any(|e| e == entity_id) {
+                GuestMatchKind::Entity
+            } else if token_id.is_some() && p.token_id == token_id {
+                GuestMatchKind::Code
+            } else {
+                return None;
+            };
+            Some(GuestMatch {
+                guest_id: p.id,
+                name: p.name.clone(),
+                matched_by,
+                within_window: p.is_expected(at),
+                windows: p.describe_windows(),
+            })
+        }).collect::<Vec<_>>();
+        // A person on several profiles is explained by any one that expects them now
+        matches.sort_by_key(|m| !m.within_window);
+        matches.into_iter().next()
+    }
+
+    /// Drop profiles that expired before `before`
+    pub fn prune_expired(&self, before: DateTime<Utc>) {
+        for mut profiles in self.homes.iter_mut() {
+            profiles.retain(|p| !p.is_expired(before));
+        }
+        self.homes.retain(|_, profiles| !profiles.is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+// src/home_files.rs
+//
+// One file per home for the stores that keep their state on disk. Names are
+// the hex of the home id: sanitizing would map "a.b" and "a_b" to the same
+// file, letting one home overwrite another's data.
+
+use std::path::{Path, PathBuf};
+
+/// Collision-free file name for a home id
+pub fn file_stem(home_id: &str) -> String {
+    hex::encode(home_id.as_bytes())
+}
+
+/// `<dir>/<hex id>.json`
+pub fn json_path(dir: &Path, home_id: &str) -> PathBuf {
+    dir.join(format!("{}.json", file_stem(home_id)))
+}
+
+/// Write a home's JSON file via a temp file so a crash never leaves half a file
+pub fn write_json(dir: &Path, home_id: &str, bytes: &[u8]) -> std::io::Result<()> {
+    let path = json_path(dir, home_id);
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(tmp, path)
+}
@@ -0,0 +1,244 @@
+// src/household.rs
+
+// Multi-user households. Each home has residents with a household role
+// (owner, adult, teen, guest), their own delivery channels and severity
+// threshold, and a presence state reported by their phone or geofence.
+// Presence is aggregated into a DwellingState whose away probability feeds
+// the thinking AI, and alerts fan out to every resident whose threshold the
+// severity meets instead of only the account that owns the camera.
+
+use crate::delivery::NotificationSeverity;
+use crate::overnight::DeliveryChannel;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum HouseholdError {
+    #[error("Resident {0} not found")]
+    NotFound(String),
+    #[error("Resident {0} already belongs to this home")]
+    Duplicate(String),
+    #[error("A home must keep at least one owner")]
+    LastOwner,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HouseholdRole {
+    Owner,
+    Adult,
+    Teen,
+    Guest,
+}
+
+impl HouseholdRole {
+    /// Channels a new resident gets unless they choose their own
+    pub fn default_channels(&self) -> Vec<DeliveryChannel> {
+        match self {
+            HouseholdRole::Owner | HouseholdRole::Adult => vec![DeliveryChannel::Push, DeliveryChannel::WebSocket, DeliveryChannel::Email],
+            HouseholdRole::Teen | HouseholdRole::Guest => vec![DeliveryChannel::Push],
+        }
+    }
+
+    pub fn default_min_severity(&self) -> NotificationSeverity {
+        match self {
+            HouseholdRole::Owner => NotificationSeverity::Info,
+            HouseholdRole::Adult => NotificationSeverity::Standard,
+            HouseholdRole::Teen => NotificationSeverity::Elevated,
+            HouseholdRole::Guest => NotificationSeverity::Critical,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Presence {
+    Home,
+    Away,
+    #[default]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Resident {
+    pub user_id: String,
+    pub name: String,
+    pub role: HouseholdRole,
+    pub channels: Vec<DeliveryChannel>,
+    pub min_severity: NotificationSeverity,
+    pub presence: Presence,
+    pub presence_updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ResidentRequest {
+    pub user_id: String,
+    pub name: String,
+    pub role: HouseholdRole,
+    pub channels: Option<Vec<DeliveryChannel>>,       // Defaults by role
+    pub min_severity: Option<NotificationSeverity>,   // Defaults by role
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Occupancy {
+    Occupied, // At least one resident is home
+    Empty,    // Every resident with known presence is away and none are unknown
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DwellingState {
+    pub home_id: String,
+    pub occupancy: Occupancy,
+    pub residents_home: usize,
+    pub residents_away: usize,
+    pub residents_unknown: usize,
+    pub away_prob: f64, // Fed to the thinking AI as the event's away probability
+}
+
+#[derive(Debug, Clone)]
+pub struct HouseholdConfig {
+    pub occupied_away_prob: f64,
+    pub empty_away_prob: f64,
+    pub unknown_away_prob: f64, // When no resident's presence is known
+    pub stale_after: chrono::Duration, // Presence older than this counts as unknown
+}
+
+impl Default for HouseholdConfig {
+    fn default() -> Self {
+        Self {
+            occupied_away_prob: 0.05,
+            empty_away_prob: 0.95,
+            unknown_away_prob: 0.5,
+            stale_after: chrono::Duration::hours(12),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct HouseholdRegistry {
+    config: HouseholdConfig,
+    residents: DashMap<String, Vec<Resident>>,
+}
+
+impl HouseholdRegistry {
+    pub fn new(config: HouseholdConfig) -> Self {
+        Self { config, residents: DashMap::new() }
+    }
+
+    pub fn add_resident(&self, home_id: &str, request: ResidentRequest) -> Result<Resident, HouseholdError> {
+        let mut residents = self.residents.entry(home_id.to_string()).or_default();
+        if residents.iter().any(|r| r.user_id == request.user_id) {
+            return Err(HouseholdError::Duplicate(request.user_id));
+        }
+        let resident = Resident {
+            channels: request.channels.unwrap_or_else(|| request.role.default_channels()),
+            min_severity: request.min_severity.unwrap_or_else(|| request.role.default_min_severity()),
+            user_id: request.user_id,
+            name: request.name,
+            role: request.role,
+            presence: Presence::Unknown,
+            presence_updated_at: None,
+        };
+        residents.push(resident.clone());
+        Ok(resident)
+    }
+
+    /// Replace a resident's role, channels and threshold; presence is kept
+    pub fn update_resident(&self, home_id: &str, request: ResidentRequest) -> Result<Resident, HouseholdError> {
+        let mut residents = self.residents.get_mut(home_id).ok_or_else(|| HouseholdError::NotFound(request.user_id.clone()))?;
+        let owners = residents.iter().filter(|r| r.role == HouseholdRole::Owner).count();
+        let resident = residents.iter_mut()
+            .find(|r| r.user_id == request.user_id)
+            .ok_or_else(|| HouseholdError::NotFound(request.user_id.clone()))?;
+        if resident.role == HouseholdRole::Owner && request.role != HouseholdRole::Owner && owners == 1 {
+            return Err(HouseholdError::LastOwner);
+        }
+        resident.channels = request.channels.unwrap_or_else(|| request.role.default_channels());
+        resident.min_severity = request.min_severity.unwrap_or_else(|| request.role.default_min_severity());
+        resident.name = request.name;
+        resident.role = request.role;
+        Ok(resident.clone())
+    }
+
+    pub fn remove_resident(&self, home_id: &str, user_id: &str) -> Result<Resident, HouseholdError> {
+        let mut residents = self.residents.get_mut(home_id).ok_or_else(|| HouseholdError::NotFound(user_id.to_string()))?;
+        let index = residents.iter().position(|r| r.user_id == user_id).ok_or_else(|| HouseholdError::NotFound(user_id.to_string()))?;
+        let owners = residents.iter().filter(|r| r.role == HouseholdRole::Owner).count();
+        if residents[index].role == HouseholdRole::Owner && owners == 1 && residents.len() > 1 {
+            return Err(HouseholdError::LastOwner);
+        }
+        Ok(residents.remove(index))
+    }
+
+    /// Every home's residents, for backups
+    pub fn all_residents(&self) -> BTreeMap<String, Vec<Resident>> {
+        self.residents.iter().map(|e| (e.key().clone(), e.value().clone())).collect()
+    }
+
+    /// Replace all residents, e.g. from a backup
+    pub fn restore_residents(&self, residents: BTreeMap<String, Vec<Resident>>) {
+        self.residents.clear();
+        for (home_id, list) in residents {
+            self.residents.insert(home_id, list);
+        }
+    }
+
+    pub fn residents(&self, home_id: &str) -> Vec<Resident> {
+        self.residents.get(home_id).map(|r| r.clone()).unwrap_or_default()
+    }
+
+    pub fn set_presence(&self, home_id: &str, user_id: &str, presence: Presence, at: DateTime<Utc>) -> Result<Resident, HouseholdError> {
+        let mut residents = self.residents.get_mut(home_id).ok_or_else(|| HouseholdError::NotFound(user_id.to_string()))?;
+        let resident = residents.iter_mut()
+            .find(|r| r.user_id == user_id)
+            .ok_or_else(|| HouseholdError::NotFound(user_id.to_string()))?;
+        resident.presence = presence;
+        resident.presence_updated_at = Some(at);
+        Ok(resident.clone())
+    }
+
+    /// Aggregate resident presence; None for homes without residents
+    pub fn dwelling_state(&self, home_id: &str, now: DateTime<Utc>) -> Option<DwellingState> {
+        let residents = self.residents.get(home_id).filter(|r| !r.is_empty())?;
+        let effective = |r: &Resident| match r.presence_updated_at {
+            Some(at) if now - at <= self.config.stale_after => r.presence,
+            _ => Presence::Unknown,
+        };
+        let count = |p: Presence| residents.iter().filter(|r| effective(r) == p).count();
+        let (home, away, unknown) = (count(Presence::Home), count(Presence::Away), count(Presence::Unknown));
+
+        let (occupancy, away_prob) = if home > 0 {
+            (Occupancy::Occupied, self.config.occupied_away_prob)
+        } else if unknown == 0 {
+            (Occupancy::Empty, self.config.empty_away_prob)
+        } else {
+            // Nobody known to be home: lean towards empty by the share known to be away
+            let known_away = away as f64 / (away + unknown) as f64;
+            let prob = self.config.unknown_away_prob + (self.config.empty_away_prob - self.config.unknown_away_prob) * known_away;
+            (Occupancy::Unknown, prob)
+        };
+
+        Some(DwellingState {
+            home_id: home_id.to_string(),
+            occupancy,
+            residents_home: home,
+            residents_away: away,
+            residents_unknown: unknown,
+            away_prob,
+        })
+    }
+
+    /// Residents whose threshold the severity meets, with their channels
+    pub fn recipients(&self, home_id: &str, severity: NotificationSeverity) -> Vec<(Option<String>, Vec<DeliveryChannel>)> {
+        self.residents(home_id)
+            .into_iter()
+            .filter(|r| severity >= r.min_severity && !r.channels.is_empty())
+            .map(|r| (Some(r.user_id), r.channels))
+            .collect()
+    }
+}
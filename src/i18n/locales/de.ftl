@@ -0,0 +1,35 @@
+# Deutsch
+
+severity-info = Info
+severity-standard = Standard
+severity-elevated = Erhöht
+severity-critical = Kritisch
+
+alert-title = Alarm ({ $severity }): { $zone }
+escalation-title = Kritischer Alarm: Vorfall { $incident } erfordert Aufmerksamkeit
+heads-up-body = Möglicher Eindringling ({ $probability } %). Details folgen nach der Auswertung der Aufnahmen.
+alert-cleared-title = Entwarnung: { $zone }
+self-test-title = Testalarm Ihres Sicherheitssystems
+self-test-body = Dies ist ein Test aus dem Selbsttest. Sie müssen nichts tun.
+
+tamper-blinded-title = Kamera geblendet: { $camera }
+tamper-blinded-body = Ein helles Licht überstrahlt das Bild; ihre Erkennungen zählen weniger, bis der Alarm aufgehoben wird.
+tamper-covered-title = Kamera abgedeckt: { $camera }
+tamper-covered-body = Das Objektiv scheint abgedeckt oder geschwärzt; ihre Erkennungen zählen weniger, bis der Alarm aufgehoben wird.
+tamper-moved-title = Kamera verschoben: { $camera }
+tamper-moved-body = Das Bild entspricht nicht mehr der üblichen Szene dieser Kamera; prüfen Sie die Halterung und heben Sie den Alarm danach auf.
+
+summary-title = Morgenbericht für { $date }
+summary-quiet = Ruhige Nacht
+summary-no-alerts =
+    .one = { $count } Ereignis in der Nacht, keines hätte Alarm ausgelöst
+    .other = { $count } Ereignisse in der Nacht, keines hätte Alarm ausgelöst
+summary-alerts =
+    .one = { $count } Ereignis in der Nacht, davon { $alerts } mit Alarm
+    .other = { $count } Ereignisse in der Nacht, davon { $alerts } mit Alarm
+summary-entity =
+    .one = Eine unbekannte Person hat zwischen { $start } und { $end } { $events } Ereignisse an einer Kamera ausgelöst
+    .other = Eine unbekannte Person hat zwischen { $start } und { $end } { $events } Ereignisse an { $count } Kameras ausgelöst
+summary-vacation = Urlaubsübersicht, alle Aktivitäten seit dem letzten Bericht: { $summary }
+summary-email-highlights = Ereignisse, die einen Blick wert sind
+summary-email-footer = Sie erhalten diesen Bericht, weil E-Mail in Ihren Benachrichtigungseinstellungen aktiviert ist.
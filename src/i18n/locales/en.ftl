@@ -0,0 +1,35 @@
+# English; every other catalog falls back to these messages
+
+severity-info = Info
+severity-standard = Standard
+severity-elevated = Elevated
+severity-critical = Critical
+
+alert-title = { $severity } alert on { $zone }
+escalation-title = Critical alert: incident { $incident } needs attention
+heads-up-body = Possible intruder ({ $probability }%). Details follow once the footage is analyzed.
+alert-cleared-title = All clear on { $zone }
+self-test-title = Test alert from your security system
+self-test-body = This is a test from the self-test. No action is needed.
+
+tamper-blinded-title = Camera blinded: { $camera }
+tamper-blinded-body = A bright light is washing out the view; its detections count for less until the alert is cleared.
+tamper-covered-title = Camera covered: { $camera }
+tamper-covered-body = The lens appears covered or blacked out; its detections count for less until the alert is cleared.
+tamper-moved-title = Camera moved: { $camera }
+tamper-moved-body = The view no longer matches this camera's usual scene; check its mounting and clear the alert once fixed.
+
+summary-title = Morning summary for { $date }
+summary-quiet = Quiet night
+summary-no-alerts =
+    .one = { $count } event overnight, none would have alerted
+    .other = { $count } events overnight, none would have alerted
+summary-alerts =
+    .one = { $count } event overnight, { $alerts } would have alerted
+    .other = { $count } events overnight, { $alerts } would have alerted
+summary-entity =
+    .one = One unknown person triggered { $events } events on one camera between { $start } and { $end }
+    .other = One unknown person triggered { $events } events across { $count } cameras between { $start } and { $end }
+summary-vacation = Vacation digest, all activity since the last summary: { $summary }
+summary-email-highlights = Events worth a look
+summary-email-footer = You receive this summary because email is turned on in your notification settings.
@@ -0,0 +1,35 @@
+# Español
+
+severity-info = Información
+severity-standard = Estándar
+severity-elevated = Elevada
+severity-critical = Crítica
+
+alert-title = Alerta { $severity }: { $zone }
+escalation-title = Alerta crítica: el incidente { $incident } requiere atención
+heads-up-body = Posible intruso ({ $probability } %). Los detalles llegarán cuando se analicen las imágenes.
+alert-cleared-title = Todo en orden: { $zone }
+self-test-title = Alerta de prueba de su sistema de seguridad
+self-test-body = Esta es una prueba del autodiagnóstico. No es necesario hacer nada.
+
+tamper-blinded-title = Cámara deslumbrada: { $camera }
+tamper-blinded-body = Una luz intensa satura la imagen; sus detecciones cuentan menos hasta que se borre la alerta.
+tamper-covered-title = Cámara tapada: { $camera }
+tamper-covered-body = La lente parece tapada u oscurecida; sus detecciones cuentan menos hasta que se borre la alerta.
+tamper-moved-title = Cámara movida: { $camera }
+tamper-moved-body = La imagen ya no coincide con la escena habitual de esta cámara; revise su montaje y borre la alerta después.
+
+summary-title = Resumen matutino del { $date }
+summary-quiet = Noche tranquila
+summary-no-alerts =
+    .one = { $count } evento durante la noche, ninguno habría generado alerta
+    .other = { $count } eventos durante la noche, ninguno habría generado alerta
+summary-alerts =
+    .one = { $count } evento durante la noche, { $alerts } con alerta
+    .other = { $count } eventos durante la noche, { $alerts } con alerta
+summary-entity =
+    .one = Una persona desconocida generó { $events } eventos en una cámara entre las { $start } y las { $end }
+    .other = Una persona desconocida generó { $events } eventos en { $count } cámaras entre las { $start } y las { $end }
+summary-vacation = Resumen de vacaciones, toda la actividad desde el último resumen: { $summary }
+summary-email-highlights = Eventos que conviene revisar
+summary-email-footer = Recibe este resumen porque el correo electrónico está activado en su configuración de notificaciones.
@@ -0,0 +1,35 @@
+# Français
+
+severity-info = Info
+severity-standard = Standard
+severity-elevated = Élevée
+severity-critical = Critique
+
+alert-title = Alerte { $severity } : { $zone }
+escalation-title = Alerte critique : l'incident { $incident } demande votre attention
+heads-up-body = Intrus possible ({ $probability } %). Les détails suivront après l'analyse des images.
+alert-cleared-title = Fin d'alerte : { $zone }
+self-test-title = Alerte de test de votre système de sécurité
+self-test-body = Ceci est un test de l'autodiagnostic. Aucune action n'est nécessaire.
+
+tamper-blinded-title = Caméra éblouie : { $camera }
+tamper-blinded-body = Une lumière vive sature l'image ; ses détections comptent moins jusqu'à la levée de l'alerte.
+tamper-covered-title = Caméra masquée : { $camera }
+tamper-covered-body = L'objectif semble couvert ou obstrué ; ses détections comptent moins jusqu'à la levée de l'alerte.
+tamper-moved-title = Caméra déplacée : { $camera }
+tamper-moved-body = L'image ne correspond plus à la scène habituelle de cette caméra ; vérifiez sa fixation puis levez l'alerte.
+
+summary-title = Résumé du matin du { $date }
+summary-quiet = Nuit calme
+summary-no-alerts =
+    .one = { $count } événement cette nuit, aucun n'aurait déclenché d'alerte
+    .other = { $count } événements cette nuit, aucun n'aurait déclenché d'alerte
+summary-alerts =
+    .one = { $count } événement cette nuit, dont { $alerts } avec alerte
+    .other = { $count } événements cette nuit, dont { $alerts } avec alerte
+summary-entity =
+    .one = Une personne inconnue a déclenché { $events } événements sur une caméra entre { $start } et { $end }
+    .other = Une personne inconnue a déclenché { $events } événements sur { $count } caméras entre { $start } et { $end }
+summary-vacation = Résumé de vacances, toute l'activité depuis le dernier résumé : { $summary }
+summary-email-highlights = Événements à examiner
+summary-email-footer = Vous recevez ce résumé car l'e-mail est activé dans vos paramètres de notification.
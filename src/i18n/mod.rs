@@ -0,0 +1,227 @@
+//! Localization
+//!
+//! User-facing text — alert titles, tamper alerts, severity names and the
+//! morning summary narrative — comes from per-locale message catalogs in a
+//! subset of Fluent syntax:
+//!
+//! ```text
+//! # Comment
+//! alert-title = { $severity } alert on { $zone }
+//! summary-no-alerts =
+//!     .one = { $count } event overnight, none would have alerted
+//!     .other = { $count } events overnight, none would have alerted
+//! ```
+//!
+//! Messages with `.one` / `.other` variants are picked by a count. English,
+//! German, French and Spanish are built in; catalogs in NOVIN_LOCALES_DIR
+//! (`<locale>.ftl`) add locales or override built-in messages. A home's locale
+//! is its notification language (see `delivery::TemplateStore`). Lookups fall
+//! back from `de-AT` to `de`, then to English, then to the message id.
+
+use crate::delivery::NotificationSeverity;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+const BUILTIN: [(&str, &str); 4] = [
+    ("en", include_str!("locales/en.ftl")),
+    ("de", include_str!("locales/de.ftl")),
+    ("fr", include_str!("locales/fr.ftl")),
+    ("es", include_str!("locales/es.ftl")),
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum I18nError {
+    #[error("{locale}.ftl line {line}: {reason}")]
+    Parse { locale: String, line: usize, reason: String },
+
+    #[error("Failed to read catalogs: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>, // Variants are stored as "<id>.<variant>"
+}
+
+impl Catalog {
+    pub fn parse(locale: &str, source: &str) -> Result<Self, I18nError> {
+        let mut messages = HashMap::new();
+        let mut current: Option<String> = None; // Message whose variants follow
+        for (i, raw) in source.lines().enumerate() {
+            let error = |reason: &str| I18nError::Parse { locale: locale.to_string(), line: i + 1, reason: reason.to_string() };
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| error("expected 'id = text'"))?;
+            let (key, value) = (key.trim(), value.trim());
+            if raw.starts_with(char::is_whitespace) {
+                let variant = key.strip_prefix('.').ok_or_else(|| error("indented lines must be '.variant = text'"))?;
+                let id = current.as_ref().ok_or_else(|| error("variant without a message"))?;
+                messages.insert(format!("{}.{}", id, variant), value.to_string());
+                continue;
+            }
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(error("message ids are letters, digits, '-' and '_'"));
+            }
+            if value.is_empty() {
+                current = Some(key.to_string());
+            } else {
+                current = None;
+                messages.insert(key.to_string(), value.to_string());
+            }
+        }
+        Ok(Self { messages })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.messages.get(id).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    fn merge(&mut self, other: Catalog) {
+        self.messages.extend(other.messages);
+    }
+}
+
+/// Replace `{ $name }` placeholders; unknown names are left as written
+fn interpolate(pattern: &str, args: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start..start + end + 1];
+        let name = placeholder[1..placeholder.len() - 1].trim().trim_start_matches('$');
+        match args.iter().find(|(k, _)| *k == name) {
+            Some((_, value)) => out.push_str(value),
+            None => out.push_str(placeholder),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn plural_variant(locale: &str, count: u64) -> &'static str {
+    // French treats zero as singular
+    match (language(locale), count) {
+        ("fr", 0 | 1) | (_, 1) => "one",
+        _ => "other",
+    }
+}
+
+fn language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Localizer {
+    catalogs: HashMap<String, Catalog>,
+}
+
+impl Localizer {
+    /// The catalogs shipped with the crate
+    pub fn builtin() -> Self {
+        let mut localizer = Self::default();
+        for (locale, source) in BUILTIN {
+            // Built-in catalogs are covered by tests
+            if let Ok(catalog) = Catalog::parse(locale, source) {
+                localizer.add(locale, catalog);
+            }
+        }
+        localizer
+    }
+
+    /// Add a locale, or override messages of one already loaded
+    pub fn add(&mut self, locale: &str, catalog: Catalog) {
+        self.catalogs.entry(locale.to_lowercase()).or_default().merge(catalog);
+    }
+
+    /// Load every `<locale>.ftl` in a directory
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize, I18nError> {
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(locale) = path.file_stem().and_then(|s| s.to_str()).filter(|_| path.extension().is_some_and(|e| e == "ftl")) else {
+                continue;
+            };
+            let catalog = Catalog::parse(locale, &std::fs::read_to_string(&path)?)?;
+            self.add(locale, catalog);
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    pub fn locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.catalogs.keys().cloned().collect();
+        locales.sort();
+        locales
+    }
+
+    fn lookup(&self, locale: &str, id: &str) -> Option<&str> {
+        let locale = locale.to_lowercase();
+        [locale.as_str(), language(&locale), DEFAULT_LOCALE].into_iter()
+            .find_map(|l| self.catalogs.get(l).and_then(|c| c.get(id)))
+    }
+
+    pub fn message(&self, locale: &str, id: &str, args: &[(&str, String)]) -> String {
+        match self.lookup(locale, id) {
+            Some(pattern) => interpolate(pattern, args),
+            None => id.to_string(),
+        }
+    }
+
+    /// A message with `.one` / `.other` variants, picked by `count` (also passed as `$count`)
+    pub fn plural(&self, locale: &str, id: &str, count: u64, args: &[(&str, String)]) -> String {
+        let mut args = args.to_vec();
+        args.push(("count", count.to_string()));
+        let variant = format!("{}.{}", id, plural_variant(locale, count));
+        match self.lookup(locale, &variant).or_else(|| self.lookup(locale, &format!("{}.other", id))) {
+            Some(pattern) => interpolate(pattern, &args),
+            None => self.message(locale, id, &args),
+        }
+    }
+
+    pub fn severity(&self, locale: &str, severity: NotificationSeverity) -> String {
+        let id = match severity {
+            NotificationSeverity::Info => "severity-info",
+            NotificationSeverity::Standard => "severity-standard",
+            NotificationSeverity::Elevated => "severity-elevated",
+            NotificationSeverity::Critical => "severity-critical",
+        };
+        self.message(locale, id, &[])
+    }
+}
+
+/// Built-in catalogs plus any in NOVIN_LOCALES_DIR, loaded once
+pub fn localizer() -> &'static Localizer {
+    static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+    LOCALIZER.get_or_init(|| {
+        let mut localizer = Localizer::builtin();
+        if let Ok(dir) = std::env::var("NOVIN_LOCALES_DIR") {
+            match localizer.load_dir(Path::new(&dir)) {
+                Ok(n) => tracing::info!("Loaded {} locale catalog(s) from {}", n, dir),
+                Err(e) => tracing::warn!("Ignoring locale catalogs in {}: {}", dir, e),
+            }
+        }
+        localizer
+    })
+}
+
+/// Shorthand for `localizer().message`
+pub fn t(locale: &str, id: &str, args: &[(&str, String)]) -> String {
+    localizer().message(locale, id, args)
+}
@@ -0,0 +1,303 @@
+// src/idempotency.rs
+
+use async_trait::async_trait;
+use chrono::Utc;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+// Key used to detect webhook retries: the same event_id may legitimately
+// appear for two different homes, so both are part of the key
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IdempotencyKey {
+    pub home_id: String,
+    pub event_id: Uuid,
+}
+
+impl IdempotencyKey {
+    pub fn new(home_id: &str, event_id: Uuid) -> Self {
+        Self {
+            home_id: home_id.to_string(),
+            event_id,
+        }
+    }
+}
+
+// Configuration for the dedup layer
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    pub enabled: bool,
+    pub dedup_window: Duration, // How long an event_id is remembered
+    pub max_entries: u64,       // Upper bound on the in-memory window
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dedup_window: Duration::from_secs(600), // 10 minutes covers typical camera retry policies
+            max_entries: 100_000,
+        }
+    }
+}
+
+// Processing state of a key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DedupState {
+    InFlight,
+    Completed { response: String }, // Serialized result returned to retries
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupRecord {
+    pub state: DedupState,
+    pub first_seen: i64,
+}
+
+// Outcome of trying to claim an event for processing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Claim {
+    Acquired,                 // First time we see this event, caller must process it
+    InFlight,                 // Another worker is processing the same event right now
+    Completed(String),        // Already processed, contains the stored response
+}
+
+#[derive(Error, Debug)]
+pub enum IdempotencyError {
+    #[error("Idempotency storage error: {0}")]
+    Storage(String),
+}
+
+// Durable backing store so the dedup window survives restarts
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    async fn get(&self, key: &IdempotencyKey) -> Result<Option<DedupRecord>, IdempotencyError>;
+    async fn put(&self, key: &IdempotencyKey, record: &DedupRecord) -> Result<(), IdempotencyError>;
+    async fn remove(&self, key: &IdempotencyKey) -> Result<(), IdempotencyError>;
+    async fn purge_older_than(&self, cutoff_ts: i64) -> Result<u64, IdempotencyError>;
+}
+
+// SQLite implementation backed by the `processed_events` table
+pub struct SqliteIdempotencyStore {
+    pool: SqlitePool,
+    schema: OnceCell<()>,
+}
+
+impl SqliteIdempotencyStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool, schema: OnceCell::new() }
+    }
+
+    // Creates the dedup table on first use if the migrations have not been run
+    pub async fn ensure_schema(&self) -> Result<(), IdempotencyError> {
+        self.schema.get_or_try_init(|| async {
+            sqlx::query(include_str!("api/migrations/005_processed_events.sql"))
+                .execute(&self.pool)
+                .await
+                .map(|_| ())
+                .map_err(|e| IdempotencyError::Storage(e.to_string()))
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for SqliteIdempotencyStore {
+    async fn get(&self, key: &IdempotencyKey) -> Result<Option<DedupRecord>, IdempotencyError> {
+        self.ensure_schema().await?;
+        let row: Option<(String, Option<String>, i64)> = sqlx::query_as(
+            "SELECT state, response, first_seen FROM processed_events WHERE home_id = ? AND event_id = ?",
+        )
+        .bind(&key.home_id)
+        .bind(key.event_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| IdempotencyError::Storage(e.to_string()))?;
+
+        Ok(row.map(|(state, response, first_seen)| DedupRecord {
+            state: match (state.as_str(), response) {
+                ("completed", Some(response)) => DedupState::Completed { response },
+                _ => DedupState::InFlight,
+            },
+            first_seen,
+        }))
+    }
+
+    async fn put(&self, key: &IdempotencyKey, record: &DedupRecord) -> Result<(), IdempotencyError> {
+        self.ensure_schema().await?;
+        let (state, response) = match &record.state {
+            DedupState::InFlight => ("in_flight", None),
+            DedupState::Completed { response } => ("completed", Some(response.clone())),
+        };
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO processed_events (home_id, event_id, state, response, first_seen) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&key.home_id)
+        .bind(key.event_id.to_string())
+        .bind(state)
+        .bind(response)
+        .bind(record.first_seen)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| IdempotencyError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn remove(&self, key: &IdempotencyKey) -> Result<(), IdempotencyError> {
+        self.ensure_schema().await?;
+        sqlx::query("DELETE FROM processed_events WHERE home_id = ? AND event_id = ?")
+            .bind(&key.home_id)
+            .bind(key.event_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| IdempotencyError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn purge_older_than(&self, cutoff_ts: i64) -> Result<u64, IdempotencyError> {
+        self.ensure_schema().await?;
+        let result = sqlx::query("DELETE FROM processed_events WHERE first_seen < ?")
+            .bind(cutoff_ts)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| IdempotencyError::Storage(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+}
+
+// In-memory dedup window with optional durable store behind it
+pub struct IdempotencyGuard {
+    config: IdempotencyConfig,
+    window: Cache<IdempotencyKey, DedupRecord>,
+    store: Option<Arc<dyn IdempotencyStore>>,
+}
+
+impl IdempotencyGuard {
+    pub fn new(config: IdempotencyConfig) -> Self {
+        let window = Cache::builder()
+            .max_capacity(config.max_entries)
+            .time_to_live(config.dedup_window)
+            .build();
+
+        Self {
+            config,
+            window,
+            store: None,
+        }
+    }
+
+    pub fn with_store(config: IdempotencyConfig, store: Arc<dyn IdempotencyStore>) -> Self {
+        let mut guard = Self::new(config);
+        guard.store = Some(store);
+        guard
+    }
+
+    pub fn set_store(&mut self, store: Arc<dyn IdempotencyStore>) {
+        self.store = Some(store);
+    }
+
+    /// Atomically claim an event for processing
+    pub async fn claim(&self, key: &IdempotencyKey) -> Result<Claim, IdempotencyError> {
+        if !self.config.enabled {
+            return Ok(Claim::Acquired);
+        }
+
+        let now = Utc::now().timestamp();
+        let entry = self.window
+            .entry(key.clone())
+            .or_insert_with(async {
+                DedupRecord {
+                    state: DedupState::InFlight,
+                    first_seen: now,
+                }
+            })
+            .await;
+
+        // Someone else already holds this key in the in-memory window
+        if !entry.is_fresh() {
+            return Ok(Self::claim_from_record(entry.into_value()));
+        }
+
+        // Fresh in memory: consult the durable store in case we restarted mid-window
+        if let Some(store) = &self.store {
+            if let Some(record) = store.get(key).await? {
+                if self.within_window(record.first_seen, now) {
+                    info!(home=%key.home_id, event=%key.event_id, "duplicate event found in durable dedup store");
+                    self.window.insert(key.clone(), record.clone()).await;
+                    return Ok(Self::claim_from_record(record));
+                }
+            }
+
+            store.put(key, &DedupRecord { state: DedupState::InFlight, first_seen: now }).await?;
+        }
+
+        Ok(Claim::Acquired)
+    }
+
+    /// Record the response for a processed event so retries receive the same answer
+    pub async fn complete(&self, key: &IdempotencyKey, response: String) -> Result<(), IdempotencyError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let first_seen = self.window
+            .get(key)
+            .await
+            .map(|r| r.first_seen)
+            .unwrap_or_else(|| Utc::now().timestamp());
+        let record = DedupRecord {
+            state: DedupState::Completed { response },
+            first_seen,
+        };
+
+        self.window.insert(key.clone(), record.clone()).await;
+        if let Some(store) = &self.store {
+            store.put(key, &record).await?;
+        }
+        Ok(())
+    }
+
+    /// Release a claim after a failed attempt so the camera's retry is processed
+    pub async fn release(&self, key: &IdempotencyKey) {
+        if !self.config.enabled {
+            return;
+        }
+
+        self.window.invalidate(key).await;
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(key).await {
+                warn!(home=%key.home_id, event=%key.event_id, err=%e, "failed to release dedup claim");
+            }
+        }
+    }
+
+    /// Drop durable records that fell out of the dedup window
+    pub async fn purge_expired(&self) -> Result<u64, IdempotencyError> {
+        match &self.store {
+            Some(store) => {
+                let cutoff = Utc::now().timestamp() - self.config.dedup_window.as_secs() as i64;
+                store.purge_older_than(cutoff).await
+            }
+            None => Ok(0),
+        }
+    }
+
+    fn within_window(&self, first_seen: i64, now: i64) -> bool {
+        now - first_seen <= self.config.dedup_window.as_secs() as i64
+    }
+
+    fn claim_from_record(record: DedupRecord) -> Claim {
+        match record.state {
+            DedupState::InFlight => Claim::InFlight,
+            DedupState::Completed { response } => Claim::Completed(response),
+        }
+    }
+}
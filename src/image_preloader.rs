@@ -4,15 +4,21 @@ use std::time::Duration;
 use std::sync::atomic::{AtomicU32, Ordering};
 use tokio::sync::{mpsc, Mutex, Semaphore};
 use reqwest::Client;
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
+use base64::Engine;
+use md5::{Digest, Md5};
+use sha2::Sha256;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn, error};
 use moka::future::Cache;
 use dashmap::DashMap;
 use url::Url;
+use crate::camera_registry::{CameraRegistry, SnapshotTrust};
+use crate::image_transcode::{ImageTranscoder, Rendition, TranscodeError};
+use crate::overnight::DeliveryChannel;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Priority {
     Low,
     Normal,
@@ -26,6 +32,7 @@ pub struct ImageDownloadRequest {
     pub event_id: Uuid,
     pub priority: Priority,
     pub callback: Option<tokio::sync::oneshot::Sender<Result<Bytes, ImageError>>>,
+    pub client: Option<Client>, // Overrides the shared client, e.g. certificate-pinned for a camera
 }
 
 #[derive(Debug, thiserror::Error, Clone)]
@@ -46,6 +53,14 @@ pub enum ImageError {
     UnsupportedContentType(String),
     #[error("invalid image format")]
     InvalidFormat,
+    #[error("unexpected content-range in ranged response")]
+    InvalidRange,
+    #[error("checksum mismatch ({0})")]
+    ChecksumMismatch(String),
+    #[error("transcode failed: {0}")]
+    Transcode(String),
+    #[error("untrusted snapshot source: {0}")]
+    UntrustedSource(String),
 }
 
 pub struct ImagePreloader {
@@ -57,6 +72,8 @@ pub struct ImagePreloader {
     inflight: Arc<Mutex<HashMap<String, Vec<tokio::sync::oneshot::Sender<Result<Bytes, ImageError>>>>>>,
     per_host: Arc<DashMap<String, Arc<Semaphore>>>,
     client: Client,
+    transcoder: std::sync::RwLock<Option<Arc<ImageTranscoder>>>, // Per-channel thumbnails/WebP/AVIF
+    renditions: Cache<(String, DeliveryChannel), Rendition>,
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +86,76 @@ struct CacheEntry {
 // Constants for size limits and validation
 const MAX_BYTES: usize = 5 * 1024 * 1024;   // 5MB cap
 const RANGE_BYTES: usize = 2 * 1024 * 1024; // 2MB precheck
+const CHUNK_BYTES: usize = 1024 * 1024;     // Follow-up range size
+const MAX_RESUME_ATTEMPTS: u32 = 3;         // Consecutive interrupted chunks before giving up
+
+// Parsed `Content-Range: bytes start-end/total`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRange {
+    pub start: u64,
+    pub end: u64,
+    pub total: Option<u64>, // None for `*`
+}
+
+pub fn parse_content_range(value: &str) -> Option<ContentRange> {
+    let spec = value.trim().strip_prefix("bytes ")?;
+    let (span, total) = spec.split_once('/')?;
+    let (start, end) = span.split_once('-')?;
+    let (start, end) = (start.trim().parse::<u64>().ok()?, end.trim().parse::<u64>().ok()?);
+    let total = match total.trim() {
+        "*" => None,
+        t => Some(t.parse::<u64>().ok()?),
+    };
+    if end < start || total.map_or(false, |t| end >= t) {
+        return None;
+    }
+    Some(ContentRange { start, end, total })
+}
+
+// Digest the server advertised for a body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedChecksum {
+    Md5(Vec<u8>),
+    Sha256(Vec<u8>),
+}
+
+impl ExpectedChecksum {
+    /// Content-MD5 (base64) wins; otherwise a strong ETag that is a bare hex MD5/SHA-256
+    /// digest (S3, most object stores). Opaque ETags are only used as If-Range validators.
+    pub fn from_headers(headers: &reqwest::header::HeaderMap, full_body: bool) -> Option<Self> {
+        let md5 = if full_body { Self::content_md5(headers) } else { None };
+        md5.or_else(|| {
+            let etag = headers.get(reqwest::header::ETAG)?.to_str().ok()?;
+            if etag.starts_with("W/") {
+                return None;
+            }
+            let raw = hex::decode(etag.trim_matches('"')).ok()?;
+            match raw.len() {
+                16 => Some(Self::Md5(raw)),
+                32 => Some(Self::Sha256(raw)),
+                _ => None,
+            }
+        })
+    }
+
+    fn content_md5(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let value = headers.get("content-md5")?.to_str().ok()?;
+        let raw = base64::engine::general_purpose::STANDARD.decode(value.trim()).ok()?;
+        (raw.len() == 16).then_some(Self::Md5(raw))
+    }
+
+    pub fn verify(&self, body: &[u8]) -> Result<(), ImageError> {
+        let (expected, actual, name) = match self {
+            Self::Md5(expected) => (expected, Md5::digest(body).to_vec(), "md5"),
+            Self::Sha256(expected) => (expected, Sha256::digest(body).to_vec(), "sha256"),
+        };
+        if &actual == expected {
+            Ok(())
+        } else {
+            Err(ImageError::ChecksumMismatch(format!("{} expected {}, got {}", name, hex::encode(expected), hex::encode(actual))))
+        }
+    }
+}
 
 impl ImagePreloader {
     pub fn new() -> Self {
@@ -141,6 +228,54 @@ impl ImagePreloader {
             inflight,
             per_host,
             client,
+            transcoder: std::sync::RwLock::new(None),
+            renditions: Cache::builder()
+                .max_capacity(20 * 1024 * 1024) // 20 MB of renditions
+                .time_to_live(Duration::from_secs(3600))
+                .weigher(|_k: &(String, DeliveryChannel), v: &Rendition| v.data.len() as u32)
+                .build(),
+        }
+    }
+
+    /// Enable the thumbnail/transcoding stage
+    pub fn with_transcoder(self, transcoder: Arc<ImageTranscoder>) -> Self {
+        self.set_transcoder(transcoder);
+        self
+    }
+
+    /// Enable the thumbnail/transcoding stage on a preloader that is already shared
+    pub fn set_transcoder(&self, transcoder: Arc<ImageTranscoder>) {
+        if let Ok(mut current) = self.transcoder.write() {
+            *current = Some(transcoder);
+        }
+    }
+
+    /// Image sized and encoded for a delivery channel. Falls back to the original
+    /// bytes when no transcoder is configured.
+    pub async fn get_rendition(&self, url: String, event_id: Uuid, channel: DeliveryChannel) -> Result<Rendition, ImageError> {
+        let key = (url.clone(), channel.clone());
+        if let Some(rendition) = self.renditions.get(&key).await {
+            return Ok(rendition);
+        }
+
+        let source = self.download_image_sync(url, event_id).await?;
+        let transcoder = self.transcoder.read().ok().and_then(|t| t.clone());
+        let Some(transcoder) = transcoder else {
+            return Ok(Rendition::original(source));
+        };
+
+        match transcoder.transcode(source.clone(), &channel).await {
+            Ok(rendition) => {
+                info!(event=%event_id, channel=?channel, bytes=rendition.data.len(), ms=rendition.encode_ms, "image rendition ready");
+                self.renditions.insert(key, rendition.clone()).await;
+                Ok(rendition)
+            }
+            // Over budget: deliver the original rather than nothing
+            Err(TranscodeError::BudgetExceeded) => {
+                warn!(event=%event_id, channel=?channel, "transcode budget exhausted, sending original");
+                Ok(Rendition::original(source))
+            }
+            Err(e) => Err(ImageError::Transcode(e.to_string())),
         }
     }
 
@@ -151,22 +286,56 @@ impl ImagePreloader {
             event_id,
             priority,
             callback: None,
+            client: None,
         };
         
-        let tx = match request.priority {
+        if let Err(e) = self.queue_for(request.priority).try_send(request) {
+            warn!("Queue full, dropping preload: {}", e);
+        }
+    }
+
+    fn queue_for(&self, priority: Priority) -> &mpsc::Sender<ImageDownloadRequest> {
+        match priority {
             Priority::Critical => &self.q_crit,
             Priority::High => &self.q_high,
             Priority::Normal => &self.q_norm,
             Priority::Low => &self.q_low,
-        };
-        
-        if let Err(e) = tx.try_send(request) {
-            warn!("Queue full, dropping preload: {}", e);
         }
     }
 
     /// Download image immediately and return result
     pub async fn download_image_sync(&self, url: String, event_id: Uuid) -> Result<Bytes, ImageError> {
+        self.download_with(url, event_id, None, Priority::High).await
+    }
+
+    /// Download image now, queued at `priority` behind more important fetches
+    pub async fn download_image_at(&self, url: String, event_id: Uuid, priority: Priority) -> Result<Bytes, ImageError> {
+        self.download_with(url, event_id, None, priority).await
+    }
+
+    /// Check a snapshot URL against the camera's pin; refused URLs are errors,
+    /// flagged ones are logged and allowed
+    pub fn verify_snapshot(&self, cameras: &CameraRegistry, home_id: &str, camera_id: &str, url: &str) -> Result<SnapshotTrust, ImageError> {
+        let trust = cameras.check_url(home_id, camera_id, url).map_err(|e| ImageError::UntrustedSource(e.to_string()))?;
+        if let SnapshotTrust::Flagged(reason) = &trust {
+            warn!(home=%home_id, camera=%camera_id, url=%url, reason=%reason, "snapshot from unpinned source");
+        }
+        Ok(trust)
+    }
+
+    /// Download a camera's snapshot after verifying the URL, over the camera's
+    /// certificate-pinned client when it has one
+    pub async fn download_snapshot(&self, cameras: &CameraRegistry, home_id: &str, camera_id: &str, url: String, event_id: Uuid) -> Result<Bytes, ImageError> {
+        self.download_snapshot_at(cameras, home_id, camera_id, url, event_id, Priority::High).await
+    }
+
+    /// As `download_snapshot`, queued at `priority`
+    pub async fn download_snapshot_at(&self, cameras: &CameraRegistry, home_id: &str, camera_id: &str, url: String, event_id: Uuid, priority: Priority) -> Result<Bytes, ImageError> {
+        self.verify_snapshot(cameras, home_id, camera_id, &url)?;
+        self.download_with(url, event_id, cameras.client_for(home_id, camera_id), priority).await
+    }
+
+    async fn download_with(&self, url: String, event_id: Uuid, client: Option<Client>, priority: Priority) -> Result<Bytes, ImageError> {
         // Check cache first
         if let Some(cached) = self.get_cached_image(&url).await {
             return Ok(cached);
@@ -178,11 +347,12 @@ impl ImagePreloader {
         let request = ImageDownloadRequest {
             url,
             event_id,
-            priority: Priority::High,
+            priority,
             callback: Some(tx),
+            client,
         };
         
-        self.q_high.send(request).await
+        self.queue_for(priority).send(request).await
             .map_err(|_| ImageError::Cancelled)?;
         
         // Wait for download to complete
@@ -257,6 +427,7 @@ impl ImagePreloader {
 
         // Perform download with priority-based timeout
         let deadline = Self::deadline_for(&req.priority);
+        let client = req.client.clone().unwrap_or(client);
         let result = tokio::time::timeout(deadline, Self::download_image(&client, &req.url))
             .await
             .unwrap_or(Err(ImageError::Timeout));
@@ -327,6 +498,9 @@ impl ImagePreloader {
     }
 
     async fn download_image(client: &Client, url: &str) -> Result<Bytes, ImageError> {
+        let mut expected: Option<ExpectedChecksum> = None;
+        let mut validator: Option<String> = None;
+
         // HEAD request to check content type and size
         if let Ok(head) = client.head(url).send().await {
             if !head.status().is_success() {
@@ -352,6 +526,10 @@ impl ImagePreloader {
                     }
                 }
             }
+
+            // Whole-entity checksum; Content-MD5 on HEAD describes the full body
+            expected = ExpectedChecksum::from_headers(head.headers(), true);
+            validator = Self::strong_etag(head.headers());
         }
 
         // GET with Range header for initial validation
@@ -372,56 +550,149 @@ impl ImagePreloader {
             }
         }
 
-        let content_range = resp.headers().get(reqwest::header::CONTENT_RANGE);
-        let prefix = resp.bytes().await.map_err(Self::map_net_error)?;
-
         // If server ignored Range (200 without Content-Range), this is the full body
-        if status == reqwest::StatusCode::OK && content_range.is_none() {
-            if prefix.len() > MAX_BYTES {
-                return Err(ImageError::TooLarge(prefix.len()));
-            }
-            if !Self::looks_like_image(&prefix) {
-                return Err(ImageError::InvalidFormat);
+        if status != reqwest::StatusCode::PARTIAL_CONTENT {
+            let expected = ExpectedChecksum::from_headers(resp.headers(), true).or(expected);
+            let mut body = BytesMut::new();
+            Self::read_capped(&mut resp, &mut body, MAX_BYTES).await?;
+            return Self::finish(body.freeze(), expected.as_ref());
+        }
+
+        let range = resp.headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_content_range)
+            .ok_or(ImageError::InvalidRange)?;
+        if range.start != 0 {
+            return Err(ImageError::InvalidRange);
+        }
+        if let Some(total) = range.total {
+            if total as usize > MAX_BYTES {
+                return Err(ImageError::TooLarge(total as usize));
             }
-            return Ok(prefix);
         }
-        
-        if prefix.len() < RANGE_BYTES {
-            // Likely got full body already
-            if !Self::looks_like_image(&prefix) {
+        expected = expected.or_else(|| ExpectedChecksum::from_headers(resp.headers(), false));
+        validator = validator.or_else(|| Self::strong_etag(resp.headers()));
+
+        let mut body = BytesMut::with_capacity(range.total.map_or(RANGE_BYTES, |t| t as usize));
+        let mut chunk_checksum = ExpectedChecksum::content_md5(resp.headers());
+        let mut chunk_start = 0;
+        let mut requested = RANGE_BYTES;
+        let mut failures = 0;
+        let mut read = Self::read_capped(&mut resp, &mut body, MAX_BYTES).await;
+
+        // Continue with ranged requests from wherever the body stopped, even mid-chunk
+        loop {
+            let mut short_range = false;
+            match read {
+                Ok(()) => {
+                    // Per-response Content-MD5 on a 206 covers just that range
+                    if let Some(sum) = chunk_checksum.take() {
+                        sum.verify(&body[chunk_start..])?;
+                    }
+                    short_range = body.len() - chunk_start < requested;
+                    failures = 0;
+                }
+                Err(ImageError::Network(_)) | Err(ImageError::Timeout) if failures < MAX_RESUME_ATTEMPTS => {
+                    failures += 1;
+                    chunk_checksum = None; // Partial range can't be checked against it
+                    warn!(url, offset = body.len(), attempt = failures, "resuming interrupted image download");
+                }
+                Err(e) => return Err(e),
+            }
+
+            let offset = body.len();
+            let done = match range.total {
+                Some(total) => offset as u64 >= total,
+                // Unknown length: a complete but short range means we reached the end
+                None => short_range,
+            };
+            if done {
+                break;
+            }
+            if offset >= 16 && !Self::looks_like_image(&body) {
                 return Err(ImageError::InvalidFormat);
             }
-            return Ok(prefix);
-        }
 
-        // Validate image format from prefix
-        if !Self::looks_like_image(&prefix) {
-            return Err(ImageError::InvalidFormat);
-        }
+            let end = match range.total {
+                Some(total) => (offset + CHUNK_BYTES).min(total as usize) - 1,
+                None => offset + CHUNK_BYTES - 1,
+            };
+            let mut request = client.get(url)
+                .header(reqwest::header::RANGE, format!("bytes={}-{}", offset, end));
+            if let Some(etag) = &validator {
+                request = request.header(reqwest::header::IF_RANGE, etag.as_str());
+            }
 
-        // Fetch full content (bounded)
-        let full = client.get(url).send().await
-            .map_err(Self::map_net_error)?;
-        
-        if !full.status().is_success() {
-            return Err(ImageError::HttpStatus { status: full.status().as_u16() });
+            resp = match request.send().await {
+                Ok(r) => r,
+                Err(e) => {
+                    read = Err(Self::map_net_error(e));
+                    continue;
+                }
+            };
+
+            match resp.status() {
+                reqwest::StatusCode::PARTIAL_CONTENT => {
+                    let next = resp.headers()
+                        .get(reqwest::header::CONTENT_RANGE)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(parse_content_range)
+                        .ok_or(ImageError::InvalidRange)?;
+                    if next.start as usize != offset || (range.total.is_some() && next.total != range.total) {
+                        return Err(ImageError::InvalidRange);
+                    }
+                }
+                // If-Range failed: the image changed under us, take the fresh full body instead
+                reqwest::StatusCode::OK => {
+                    warn!(url, "image changed during ranged download, refetching in full");
+                    let expected = ExpectedChecksum::from_headers(resp.headers(), true);
+                    let mut fresh = BytesMut::new();
+                    Self::read_capped(&mut resp, &mut fresh, MAX_BYTES).await?;
+                    return Self::finish(fresh.freeze(), expected.as_ref());
+                }
+                // Unknown-length resource ended exactly on a chunk boundary
+                reqwest::StatusCode::RANGE_NOT_SATISFIABLE if range.total.is_none() => break,
+                status => return Err(ImageError::HttpStatus { status: status.as_u16() }),
+            }
+
+            chunk_start = offset;
+            requested = end + 1 - offset;
+            chunk_checksum = ExpectedChecksum::content_md5(resp.headers());
+            read = Self::read_capped(&mut resp, &mut body, MAX_BYTES).await;
         }
-        
-        // Validate content-type on full request too
-        if let Some(ct) = full.headers().get(reqwest::header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) {
-            if !Self::ct_allows_image(ct) {
-                return Err(ImageError::UnsupportedContentType(ct.to_string()));
+
+        Self::finish(body.freeze(), expected.as_ref())
+    }
+
+    // Stream a body into `buf`, failing as soon as it would exceed `limit`.
+    // Bytes received before a network error stay in `buf` so the caller can resume.
+    async fn read_capped(resp: &mut reqwest::Response, buf: &mut BytesMut, limit: usize) -> Result<(), ImageError> {
+        while let Some(chunk) = resp.chunk().await.map_err(Self::map_net_error)? {
+            if buf.len() + chunk.len() > limit {
+                return Err(ImageError::TooLarge(buf.len() + chunk.len()));
             }
+            buf.extend_from_slice(&chunk);
         }
-        
-        let full_bytes = full.bytes().await
-            .map_err(Self::map_net_error)?;
-        
-        if full_bytes.len() > MAX_BYTES {
-            return Err(ImageError::TooLarge(full_bytes.len()));
+        Ok(())
+    }
+
+    fn finish(body: Bytes, expected: Option<&ExpectedChecksum>) -> Result<Bytes, ImageError> {
+        if !Self::looks_like_image(&body) {
+            return Err(ImageError::InvalidFormat);
         }
-        
-        Ok(full_bytes)
+        if let Some(sum) = expected {
+            sum.verify(&body)?;
+        }
+        Ok(body)
+    }
+
+    // Weak validators can't be used with If-Range
+    fn strong_etag(headers: &reqwest::header::HeaderMap) -> Option<String> {
+        headers.get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .filter(|etag| !etag.starts_with("W/"))
+            .map(|etag| etag.to_string())
     }
 
     /// Extract image URLs from various data formats
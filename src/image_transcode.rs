@@ -0,0 +1,236 @@
+// src/image_transcode.rs
+//
+// Thumbnails and WebP/AVIF renditions for push payloads and dashboard previews.
+// Each delivery channel gets its own size/format; encoding runs on the blocking
+// pool behind a small semaphore so transcoding can't starve event processing.
+
+use crate::overnight::DeliveryChannel;
+use bytes::Bytes;
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{ColorType, DynamicImage, ImageEncoder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum TranscodeError {
+    #[error("Could not decode source image: {0}")]
+    Decode(String),
+
+    #[error("Could not encode {0:?}: {1}")]
+    Encode(OutputFormat, String),
+
+    #[error("Source image {width}x{height} exceeds pixel limit")]
+    TooLarge { width: u32, height: u32 },
+
+    #[error("CPU budget exhausted")]
+    BudgetExceeded,
+
+    #[error("No rendition configured for channel {0:?}")]
+    NoRendition(DeliveryChannel),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OutputFormat {
+    Jpeg,
+    WebP, // Lossless; the encoder ignores quality
+    Avif,
+}
+
+impl OutputFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::WebP => "image/webp",
+            OutputFormat::Avif => "image/avif",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenditionSpec {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub format: OutputFormat,
+    pub quality: u8,     // 1-100, JPEG/AVIF
+    pub avif_speed: u8,  // 1 (slow, small) - 10 (fast)
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscodeConfig {
+    pub renditions: HashMap<DeliveryChannel, RenditionSpec>,
+    pub max_source_pixels: u64,     // Refuse decompression bombs
+    pub max_concurrent: usize,      // Parallel encodes on the blocking pool
+    pub queue_wait: Duration,       // How long to wait for a slot before giving up
+    pub max_encode_time: Duration,  // Per-image wall clock budget
+}
+
+impl Default for TranscodeConfig {
+    fn default() -> Self {
+        let mut renditions = HashMap::new();
+        // Push payloads are tiny (APNs/FCM attachments); WebP keeps them small everywhere
+        renditions.insert(DeliveryChannel::Push, RenditionSpec {
+            max_width: 320, max_height: 240, format: OutputFormat::WebP, quality: 70, avif_speed: 8,
+        });
+        renditions.insert(DeliveryChannel::Dashboard, RenditionSpec {
+            max_width: 640, max_height: 480, format: OutputFormat::Avif, quality: 60, avif_speed: 8,
+        });
+        // Email clients still have patchy WebP/AVIF support
+        renditions.insert(DeliveryChannel::Email, RenditionSpec {
+            max_width: 640, max_height: 480, format: OutputFormat::Jpeg, quality: 80, avif_speed: 8,
+        });
+        renditions.insert(DeliveryChannel::SMS, RenditionSpec {
+            max_width: 320, max_height: 240, format: OutputFormat::Jpeg, quality: 70, avif_speed: 8,
+        });
+
+        Self {
+            renditions,
+            max_source_pixels: 40_000_000,
+            max_concurrent: 2,
+            queue_wait: Duration::from_millis(500),
+            max_encode_time: Duration::from_secs(3),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rendition {
+    pub format: Option<OutputFormat>, // None when the original was passed through
+    pub content_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub data: Bytes,
+    pub encode_ms: u64,
+}
+
+impl Rendition {
+    /// Untouched source bytes, for when transcoding is disabled or over budget
+    pub fn original(source: Bytes) -> Self {
+        let guessed = image::guess_format(&source).ok();
+        let content_type = match guessed {
+            Some(image::ImageFormat::Jpeg) => "image/jpeg",
+            Some(image::ImageFormat::Png) => "image/png",
+            Some(image::ImageFormat::Gif) => "image/gif",
+            Some(image::ImageFormat::WebP) => "image/webp",
+            _ => "application/octet-stream",
+        };
+        let (width, height) = image::io::Reader::new(Cursor::new(&source))
+            .with_guessed_format()
+            .ok()
+            .and_then(|r| r.into_dimensions().ok())
+            .unwrap_or((0, 0));
+        Self { format: None, content_type, width, height, data: source, encode_ms: 0 }
+    }
+}
+
+/// Decode, downscale and encode one image. CPU-bound; call from a blocking context.
+pub fn render(source: &[u8], spec: &RenditionSpec, max_source_pixels: u64) -> Result<Rendition, TranscodeError> {
+    let started = Instant::now();
+
+    let reader = image::io::Reader::new(Cursor::new(source))
+        .with_guessed_format()
+        .map_err(|e| TranscodeError::Decode(e.to_string()))?;
+    let (width, height) = reader.into_dimensions()
+        .map_err(|e| TranscodeError::Decode(e.to_string()))?;
+    if width as u64 * height as u64 > max_source_pixels {
+        return Err(TranscodeError::TooLarge { width, height });
+    }
+
+    let decoded = image::load_from_memory(source)
+        .map_err(|e| TranscodeError::Decode(e.to_string()))?;
+    let thumb = if decoded.width() > spec.max_width || decoded.height() > spec.max_height {
+        decoded.thumbnail(spec.max_width, spec.max_height)
+    } else {
+        decoded
+    };
+
+    let data = encode(&thumb, spec)?;
+    Ok(Rendition {
+        format: Some(spec.format),
+        content_type: spec.format.mime_type(),
+        width: thumb.width(),
+        height: thumb.height(),
+        data: Bytes::from(data),
+        encode_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+fn encode(img: &DynamicImage, spec: &RenditionSpec) -> Result<Vec<u8>, TranscodeError> {
+    let mut out = Vec::new();
+    let (w, h) = (img.width(), img.height());
+    let quality = spec.quality.clamp(1, 100);
+    let result = match spec.format {
+        OutputFormat::Jpeg => {
+            let rgb = img.to_rgb8();
+            JpegEncoder::new_with_quality(&mut out, quality).write_image(&rgb, w, h, ColorType::Rgb8)
+        }
+        OutputFormat::WebP => {
+            let rgba = img.to_rgba8();
+            WebPEncoder::new_lossless(&mut out).write_image(&rgba, w, h, ColorType::Rgba8)
+        }
+        OutputFormat::Avif => {
+            let rgba = img.to_rgba8();
+            AvifEncoder::new_with_speed_quality(&mut out, spec.avif_speed.clamp(1, 10), quality)
+                .write_image(&rgba, w, h, ColorType::Rgba8)
+        }
+    };
+    result.map_err(|e| TranscodeError::Encode(spec.format, e.to_string()))?;
+    Ok(out)
+}
+
+pub struct ImageTranscoder {
+    config: TranscodeConfig,
+    cpu_slots: Arc<Semaphore>,
+}
+
+impl ImageTranscoder {
+    pub fn new(config: TranscodeConfig) -> Self {
+        let cpu_slots = Arc::new(Semaphore::new(config.max_concurrent.max(1)));
+        Self { config, cpu_slots }
+    }
+
+    pub fn config(&self) -> &TranscodeConfig {
+        &self.config
+    }
+
+    pub fn spec_for(&self, channel: &DeliveryChannel) -> Option<&RenditionSpec> {
+        self.config.renditions.get(channel)
+    }
+
+    /// Produce the rendition configured for a delivery channel within the CPU budget
+    pub async fn transcode(&self, source: Bytes, channel: &DeliveryChannel) -> Result<Rendition, TranscodeError> {
+        let spec = self.spec_for(channel)
+            .cloned()
+            .ok_or_else(|| TranscodeError::NoRendition(channel.clone()))?;
+
+        // Shed work rather than queue when every slot is busy
+        let permit = tokio::time::timeout(self.config.queue_wait, self.cpu_slots.clone().acquire_owned())
+            .await
+            .map_err(|_| TranscodeError::BudgetExceeded)?
+            .map_err(|_| TranscodeError::BudgetExceeded)?;
+
+        let format = spec.format;
+        let max_pixels = self.config.max_source_pixels;
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit; // Slot is held until the encode actually finishes
+            render(&source, &spec, max_pixels)
+        });
+
+        match tokio::time::timeout(self.config.max_encode_time, task).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(TranscodeError::Encode(format, e.to_string())),
+            Err(_) => Err(TranscodeError::BudgetExceeded),
+        }
+    }
+}
+
+impl Default for ImageTranscoder {
+    fn default() -> Self {
+        Self::new(TranscodeConfig::default())
+    }
+}
@@ -0,0 +1,173 @@
+// src/ingest.rs
+
+// Event ingestion without copying payloads. A submitted event arrives as one
+// `Bytes` request body; it is deserialized borrowing from that buffer, and the
+// sensor payload becomes an `EventPayload` slice of the same buffer. Cloning
+// the payload on its way through the stages (the VPS request, failover
+// retries, red-team replays) then bumps a reference count instead of copying
+// the string. A payload containing JSON escapes cannot be borrowed and is
+// copied once; `ingest_stats` counts how often that happens and what it cost,
+// which is what matters when hundreds of cameras post at once.
+
+use bytes::Bytes;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+use crate::pipeline::RawEvent;
+
+#[derive(thiserror::Error, Debug)]
+pub enum IngestError {
+    #[error("Event payload is not valid UTF-8")]
+    NotUtf8,
+
+    #[error("Invalid event body: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A sensor payload: UTF-8 text in a shared, cheaply cloned buffer
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct EventPayload(Bytes);
+
+impl EventPayload {
+    pub fn from_bytes(bytes: Bytes) -> Result<Self, IngestError> {
+        std::str::from_utf8(&bytes).map_err(|_| IngestError::NotUtf8)?;
+        Ok(Self(bytes))
+    }
+
+    /// Payload for a field deserialized from `body`: a slice of the body when
+    /// the field borrowed from it, else a copy of the unescaped text
+    pub fn from_field(body: &Bytes, field: Cow<'_, str>) -> Self {
+        match field {
+            Cow::Borrowed(text) if is_within(body, text) => {
+                STATS.shared.fetch_add(1, Ordering::Relaxed);
+                Self(body.slice_ref(text.as_bytes()))
+            }
+            field => {
+                STATS.copied.fetch_add(1, Ordering::Relaxed);
+                STATS.bytes_copied.fetch_add(field.len() as u64, Ordering::Relaxed);
+                Self(Bytes::from(field.into_owned()))
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Checked on construction; checking again allocates nothing
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    pub fn bytes(&self) -> &Bytes {
+        &self.0
+    }
+}
+
+// `Bytes::slice_ref` panics on a subslice of some other buffer
+fn is_within(body: &Bytes, text: &str) -> bool {
+    let outer = body.as_ptr_range();
+    let inner = text.as_bytes().as_ptr_range();
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+impl std::ops::Deref for EventPayload {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Debug for EventPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl std::fmt::Display for EventPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for EventPayload {
+    fn from(text: String) -> Self {
+        Self(Bytes::from(text))
+    }
+}
+
+impl From<&'static str> for EventPayload {
+    fn from(text: &'static str) -> Self {
+        Self(Bytes::from_static(text.as_bytes()))
+    }
+}
+
+impl Serialize for EventPayload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EventPayload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Payload copies made at ingestion, since start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct IngestStats {
+    pub shared: u64,       // Payloads sliced from the request body
+    pub copied: u64,       // Payloads that had escapes and were copied
+    pub bytes_copied: u64,
+}
+
+struct Counters {
+    shared: AtomicU64,
+    copied: AtomicU64,
+    bytes_copied: AtomicU64,
+}
+
+static STATS: Counters = Counters {
+    shared: AtomicU64::new(0),
+    copied: AtomicU64::new(0),
+    bytes_copied: AtomicU64::new(0),
+};
+
+pub fn ingest_stats() -> IngestStats {
+    IngestStats {
+        shared: STATS.shared.load(Ordering::Relaxed),
+        copied: STATS.copied.load(Ordering::Relaxed),
+        bytes_copied: STATS.bytes_copied.load(Ordering::Relaxed),
+    }
+}
+
+#[derive(Deserialize)]
+struct RawEventRef<'a> {
+    event_id: Uuid,
+    #[serde(borrow)]
+    sensor_id: Cow<'a, str>,
+    timestamp: i64,
+    #[serde(borrow)]
+    data: Cow<'a, str>,
+    #[serde(borrow)]
+    user_id: Cow<'a, str>,
+    #[serde(borrow)]
+    home_id: Cow<'a, str>,
+    #[serde(borrow, default)]
+    image_url: Option<Cow<'a, str>>,
+}
+
+/// Parse a JSON `RawEvent` whose payload stays in `body`
+pub fn parse_raw_event(body: Bytes) -> Result<RawEvent, IngestError> {
+    let event: RawEventRef<'_> = serde_json::from_slice(&body)?;
+    Ok(RawEvent {
+        event_id: event.event_id,
+        sensor_id: event.sensor_id.into_owned(),
+        timestamp: event.timestamp,
+        data: EventPayload::from_field(&body, event.data),
+        user_id: event.user_id.into_owned(),
+        home_id: event.home_id.into_owned(),
+        image_url: event.image_url.map(Cow::into_owned),
+        image_data: None,
+    })
+}
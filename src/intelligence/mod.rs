@@ -5,6 +5,8 @@ pub mod emergent;
 pub mod adaptive;
 pub mod meta_learning;
 
+pub use crate::knowledge_graph::{Edge, EdgeKind, GraphQuery, KnowledgeGraph, Node, NodeKind};
+
 use crate::core::*;
 use crate::SecurityResult;
 use async_trait::async_trait;
@@ -106,6 +108,13 @@ impl IntelligenceEngine {
         
         // RECURSIVE DOUBT: Apply one more layer of self-skepticism
         let calibrated_confidence = self.calibrate_confidence_with_doubt(&final_assessment).await?;
+
+        // Record what was observed in the knowledge graph
+        let psychological = self.psychological_profiler.analyze_psychological_profiles(entities, context).await?;
+        let mut knowledge_graph_updates = final_assessment.knowledge_graph_updates;
+        knowledge_graph_updates.extend(
+            self.knowledge_graph.update_knowledge(entities, context, &psychological.profiles, Utc::now()),
+        );
         
         Ok(IntelligenceResult {
             confidence: calibrated_confidence,
@@ -113,11 +122,19 @@ impl IntelligenceEngine {
             emergent_patterns: final_assessment.emergent_patterns,
             adaptive_insights: final_assessment.adaptive_insights,
             meta_learning_updates: final_assessment.meta_learning_updates,
-            knowledge_graph_updates: final_assessment.knowledge_graph_updates,
+            knowledge_graph_updates,
             reasoning_chains: final_assessment.reasoning_chains,
         })
     }
 
+    pub fn knowledge_graph(&self) -> &KnowledgeGraph {
+        &self.knowledge_graph
+    }
+
+    pub fn knowledge_graph_mut(&mut self) -> &mut KnowledgeGraph {
+        &mut self.knowledge_graph
+    }
+
     /// THINKING AI METHOD 1: Generate initial hypothesis with explicit reasoning
     async fn generate_initial_hypothesis(&mut self, entities: &[Entity], context: &EnvironmentalContext) -> SecurityResult<ThinkingAssessment> {
         let mut reasoning_chain = vec!["Initial hypothesis generation".to_string()];
@@ -729,8 +746,6 @@ pub struct AdaptiveLearningSystem;
 #[derive(Debug, Default)]
 pub struct MetaLearningEngine;
 #[derive(Debug, Default)]
-pub struct KnowledgeGraph;
-#[derive(Debug, Default)]
 pub struct ReasoningEngine;
 #[derive(Debug, Default)]
 pub struct PersonalityAnalyzer;
@@ -891,20 +906,6 @@ impl MetaLearningEngine {
     }
 }
 
-impl KnowledgeGraph {
-    pub fn new() -> Self { Self }
-    
-    pub async fn update_knowledge(
-        &mut self,
-        _psychological: &PsychologicalAnalysisResult,
-        _emergent: &EmergentPatternAnalysis,
-        _adaptive: &AdaptiveInsights,
-        _meta: &MetaKnowledge,
-    ) -> SecurityResult<()> {
-        Ok(())
-    }
-}
-
 impl ReasoningEngine {
     pub fn new() -> Self { Self }
     
@@ -0,0 +1,484 @@
+//! In-memory property graph of what the system has learned about a home
+//!
+//! Nodes are entities, zones, incidents and vehicles carrying free-form JSON
+//! properties; edges are typed relationships ("seen_with", "frequents", ...) that
+//! count how often they were observed. The graph serializes to a versioned JSON
+//! envelope so it survives restarts.
+//!
+//! `KnowledgeGraphStore` keeps one graph per home. The pipeline records every
+//! assessed incident in it (the person, the cameras as zones, and whoever else
+//! was around at the time), and the intelligence engine merges its analyses
+//! through `update_knowledge`.
+
+use crate::core::{EnvironmentalContext, Entity, PsychologicalProfile};
+use crate::home_files;
+use crate::schema::{from_versioned_json, to_versioned_json, SchemaError, Versioned};
+use crate::thinking::Incident;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Entity,
+    Zone,
+    Incident,
+    Vehicle,
+}
+
+impl NodeKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            NodeKind::Entity => "entity",
+            NodeKind::Zone => "zone",
+            NodeKind::Incident => "incident",
+            NodeKind::Vehicle => "vehicle",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    SeenWith,   // entity <-> entity, present at the same time
+    Frequents,  // entity -> zone
+    InvolvedIn, // entity/vehicle -> incident
+    OccurredAt, // incident -> zone
+    Arrived,    // entity -> vehicle
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Node {
+    pub id: String,
+    pub kind: NodeKind,
+    pub properties: HashMap<String, Value>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+    pub count: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct KnowledgeGraph {
+    #[serde(default)]
+    home_id: String, // Set for graphs kept by a KnowledgeGraphStore
+    nodes: HashMap<String, Node>,
+    edges: Vec<Edge>,
+    #[serde(skip)]
+    edge_index: HashMap<(String, String, EdgeKind), usize>,
+    #[serde(skip)]
+    adjacency: HashMap<String, Vec<usize>>, // node id -> incident edge positions (both directions)
+}
+
+impl KnowledgeGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn node_id(kind: NodeKind, key: &str) -> String {
+        format!("{}:{}", kind.prefix(), key)
+    }
+
+    /// Insert a node or merge properties into an existing one
+    pub fn upsert_node(&mut self, kind: NodeKind, key: &str, properties: HashMap<String, Value>, at: DateTime<Utc>) -> String {
+        let id = Self::node_id(kind, key);
+        let node = self.nodes.entry(id.clone()).or_insert_with(|| Node {
+            id: id.clone(),
+            kind,
+            properties: HashMap::new(),
+            first_seen: at,
+            last_seen: at,
+        });
+        node.properties.extend(properties);
+        node.last_seen = node.last_seen.max(at);
+        id
+    }
+
+    /// Record a relationship; repeated observations increase its count
+    pub fn upsert_edge(&mut self, from: &str, to: &str, kind: EdgeKind, at: DateTime<Utc>) {
+        // seen_with is symmetric, so store it once in a canonical direction
+        let (from, to) = if kind == EdgeKind::SeenWith && from > to { (to, from) } else { (from, to) };
+        let key = (from.to_string(), to.to_string(), kind.clone());
+
+        if let Some(&pos) = self.edge_index.get(&key) {
+            let edge = &mut self.edges[pos];
+            edge.count += 1;
+            edge.last_seen = edge.last_seen.max(at);
+            return;
+        }
+
+        let pos = self.edges.len();
+        self.edges.push(Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+            count: 1,
+            first_seen: at,
+            last_seen: at,
+        });
+        self.edge_index.insert(key, pos);
+        self.adjacency.entry(from.to_string()).or_default().push(pos);
+        if from != to {
+            self.adjacency.entry(to.to_string()).or_default().push(pos);
+        }
+    }
+
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Edges touching a node, with the node on the other end
+    pub fn neighbors(&self, id: &str, kind: Option<&EdgeKind>) -> Vec<(&Edge, &Node)> {
+        self.adjacency.get(id)
+            .into_iter()
+            .flatten()
+            .map(|&pos| &self.edges[pos])
+            .filter(|e| kind.map_or(true, |k| &e.kind == k))
+            .filter_map(|e| {
+                let other = if e.from == id { &e.to } else { &e.from };
+                self.nodes.get(other).map(|n| (e, n))
+            })
+            .collect()
+    }
+
+    /// Entities that have been seen together with the given entity
+    pub fn seen_with(&self, entity: &str) -> Vec<(&Node, u64)> {
+        self.neighbors(entity, Some(&EdgeKind::SeenWith))
+            .into_iter()
+            .map(|(e, n)| (n, e.count))
+            .collect()
+    }
+
+    /// Entities that frequent a zone at least `min_count` times
+    pub fn frequenters_of(&self, zone: &str, min_count: u64) -> Vec<(&Node, u64)> {
+        self.neighbors(zone, Some(&EdgeKind::Frequents))
+            .into_iter()
+            .filter(|(e, _)| e.count >= min_count)
+            .map(|(e, n)| (n, e.count))
+            .collect()
+    }
+
+    pub fn query(&self) -> GraphQuery<'_> {
+        GraphQuery { graph: self, kind: None, property: None, hops: Vec::new() }
+    }
+
+    /// Drop edges and nodes not seen since `cutoff`
+    pub fn prune_older_than(&mut self, cutoff: DateTime<Utc>) {
+        self.edges.retain(|e| e.last_seen >= cutoff);
+        self.nodes.retain(|_, n| n.last_seen >= cutoff);
+        let nodes = &self.nodes;
+        self.edges.retain(|e| nodes.contains_key(&e.from) && nodes.contains_key(&e.to));
+        self.rebuild_indexes();
+    }
+
+    /// Insert nodes and edges observed in one analysis pass; returns a description of changes.
+    /// `profiles` line up with `entities` when the profiler returned one per entity.
+    pub fn update_knowledge(
+        &mut self,
+        entities: &[Entity],
+        context: &EnvironmentalContext,
+        profiles: &[PsychologicalProfile],
+        at: DateTime<Utc>,
+    ) -> Vec<String> {
+        let (nodes_before, edges_before) = (self.node_count(), self.edge_count());
+
+        let mut zone_props = HashMap::new();
+        zone_props.insert("ambient_conditions".to_string(), serde_json::json!(context.ambient_conditions));
+        let zone = self.upsert_node(NodeKind::Zone, &context.location, zone_props, at);
+
+        let profiles = (profiles.len() == entities.len()).then_some(profiles);
+
+        let mut entity_ids = Vec::with_capacity(entities.len());
+        for (i, entity) in entities.iter().enumerate() {
+            let mut props = HashMap::new();
+            props.insert("interaction_count".to_string(), serde_json::json!(entity.interaction_count));
+            if let Some(profile) = &entity.profile {
+                for (k, v) in profile {
+                    props.insert(format!("profile.{}", k), serde_json::json!(v));
+                }
+            }
+            if let Some(profiles) = profiles {
+                for (k, v) in &profiles[i] {
+                    props.insert(format!("psych.{}", k), serde_json::json!(v));
+                }
+            }
+
+            let id = self.upsert_node(NodeKind::Entity, &entity.id.to_string(), props, entity.last_seen.unwrap_or(at));
+            self.upsert_edge(&id, &zone, EdgeKind::Frequents, at);
+            entity_ids.push(id);
+        }
+
+        for (i, a) in entity_ids.iter().enumerate() {
+            for b in &entity_ids[i + 1..] {
+                self.upsert_edge(a, b, EdgeKind::SeenWith, at);
+            }
+        }
+
+        vec![format!(
+            "Knowledge graph: +{} nodes, +{} edges ({} entities at {})",
+            self.node_count() - nodes_before,
+            self.edge_count() - edges_before,
+            entities.len(),
+            context.location
+        )]
+    }
+
+    /// Link an incident to the zone it happened in and the entities involved
+    pub fn record_incident(&mut self, incident_key: &str, zone: &str, entities: &[String], at: DateTime<Utc>) -> String {
+        let incident = self.upsert_node(NodeKind::Incident, incident_key, HashMap::new(), at);
+        let zone = self.upsert_node(NodeKind::Zone, zone, HashMap::new(), at);
+        self.upsert_edge(&incident, &zone, EdgeKind::OccurredAt, at);
+        for entity in entities {
+            self.upsert_edge(entity, &incident, EdgeKind::InvolvedIn, at);
+        }
+        incident
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<(), SchemaError> {
+        let json = to_versioned_json(self)?;
+        tokio::fs::write(path, json).await
+            .map_err(|e| SchemaError::Migration(format!("write {}: {}", path.display(), e)))
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, SchemaError> {
+        let json = tokio::fs::read_to_string(path).await
+            .map_err(|e| SchemaError::Migration(format!("read {}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    fn from_json(json: &str) -> Result<Self, SchemaError> {
+        let mut graph: KnowledgeGraph = from_versioned_json(json)?;
+        graph.rebuild_indexes();
+        Ok(graph)
+    }
+
+    fn rebuild_indexes(&mut self) {
+        self.edge_index.clear();
+        self.adjacency.clear();
+        for (pos, e) in self.edges.iter().enumerate() {
+            self.edge_index.insert((e.from.clone(), e.to.clone(), e.kind.clone()), pos);
+            self.adjacency.entry(e.from.clone()).or_default().push(pos);
+            if e.from != e.to {
+                self.adjacency.entry(e.to.clone()).or_default().push(pos);
+            }
+        }
+    }
+}
+
+impl Versioned for KnowledgeGraph {
+    const SCHEMA: &'static str = "knowledge_graph";
+    const CURRENT_VERSION: u32 = 1;
+
+    fn migrate(from_version: u32, _data: Value) -> Result<Value, SchemaError> {
+        Err(SchemaError::UnsupportedVersion { schema: Self::SCHEMA.to_string(), version: from_version })
+    }
+}
+
+/// Small traversal query: select start nodes, then follow typed edges hop by hop
+///
+/// `graph.query().id("zone:back_garden").hop(EdgeKind::Frequents, 3).run()` returns
+/// entities seen in the back garden at least 3 times.
+pub struct GraphQuery<'a> {
+    graph: &'a KnowledgeGraph,
+    kind: Option<NodeKind>,
+    property: Option<(String, Value)>,
+    hops: Vec<(EdgeKind, u64)>,
+}
+
+impl<'a> GraphQuery<'a> {
+    pub fn kind(mut self, kind: NodeKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.property = Some(("id".to_string(), Value::String(id.to_string())));
+        self
+    }
+
+    pub fn property(mut self, key: &str, value: Value) -> Self {
+        self.property = Some((key.to_string(), value));
+        self
+    }
+
+    pub fn hop(mut self, kind: EdgeKind, min_count: u64) -> Self {
+        self.hops.push((kind, min_count));
+        self
+    }
+
+    pub fn run(self) -> Vec<&'a Node> {
+        let graph = self.graph;
+        let mut current: Vec<&Node> = graph.nodes
+            .values()
+            .filter(|n| self.kind.map_or(true, |k| n.kind == k))
+            .filter(|n| match &self.property {
+                Some((key, value)) if key == "id" => value.as_str() == Some(n.id.as_str()),
+                Some((key, value)) => n.properties.get(key) == Some(value),
+                None => true,
+            })
+            .collect();
+
+        for (kind, min_count) in &self.hops {
+            let mut seen = HashSet::new();
+            current = current
+                .iter()
+                .flat_map(|n| graph.neighbors(&n.id, Some(kind)))
+                .filter(|(e, _)| e.count >= *min_count)
+                .map(|(_, n)| n)
+                .filter(|n| seen.insert(n.id.clone()))
+                .collect();
+        }
+
+        current.sort_by(|a, b| a.id.cmp(&b.id));
+        current
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KnowledgeGraphConfig {
+    pub co_presence_secs: i64, // People seen within this long of each other count as seen together
+    pub retention_days: i64,
+}
+
+impl Default for KnowledgeGraphConfig {
+    fn default() -> Self {
+        Self { co_presence_secs: 120, retention_days: 180 }
+    }
+}
+
+/// One knowledge graph per home, optionally persisted as `<home>.json` under a directory
+#[derive(Debug, Default)]
+pub struct KnowledgeGraphStore {
+    config: KnowledgeGraphConfig,
+    homes: DashMap<String, KnowledgeGraph>,
+    dir: Option<PathBuf>,
+}
+
+impl KnowledgeGraphStore {
+    pub fn new(config: KnowledgeGraphConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// Store backed by `dir`, loading any graphs already there
+    pub fn persistent(config: KnowledgeGraphConfig, dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let homes = DashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path).map(|text| KnowledgeGraph::from_json(&text)) {
+                Ok(Ok(graph)) => {
+                    homes.insert(graph.home_id.clone(), graph);
+                }
+                Ok(Err(e)) => tracing::warn!("Skipping unreadable knowledge graph {}: {}", path.display(), e),
+                Err(e) => tracing::warn!("Skipping knowledge graph {}: {}", path.display(), e),
+            }
+        }
+        Ok(Self { config, homes, dir: Some(dir) })
+    }
+
+    pub fn config(&self) -> &KnowledgeGraphConfig {
+        &self.config
+    }
+
+    /// Record an assessed incident: the person frequents each camera's zone, is
+    /// involved in the incident, and was seen with anyone else around at the time
+    pub fn record_incident(&self, home_id: &str, incident: &Incident, at: DateTime<Utc>) -> Result<(), SchemaError> {
+        let mut graph = self.homes.entry(home_id.to_string()).or_insert_with(|| KnowledgeGraph {
+            home_id: home_id.to_string(),
+            ..KnowledgeGraph::default()
+        });
+
+        let co_present: Vec<String> = graph.nodes.values()
+            .filter(|n| n.kind == NodeKind::Entity && (n.last_seen - at).num_seconds().abs() <= self.config.co_presence_secs)
+            .map(|n| n.id.clone())
+            .collect();
+
+        let mut props = HashMap::new();
+        props.insert("event_count".to_string(), serde_json::json!(incident.events.len()));
+        props.insert("threat_vector".to_string(), serde_json::json!(incident.threat_vector));
+        let person = graph.upsert_node(NodeKind::Entity, &incident.person_session_id, HashMap::new(), at);
+        let incident_id = graph.upsert_node(NodeKind::Incident, &incident.id.to_string(), props, at);
+        graph.upsert_edge(&person, &incident_id, EdgeKind::InvolvedIn, at);
+
+        let mut cameras: Vec<&String> = incident.cameras.iter().collect();
+        cameras.sort();
+        for camera in cameras {
+            let zone = graph.upsert_node(NodeKind::Zone, camera, HashMap::new(), at);
+            graph.upsert_edge(&person, &zone, EdgeKind::Frequents, at);
+            graph.upsert_edge(&incident_id, &zone, EdgeKind::OccurredAt, at);
+        }
+        for other in co_present.iter().filter(|id| **id != person) {
+            graph.upsert_edge(&person, other, EdgeKind::SeenWith, at);
+        }
+
+        self.persist(&graph)
+    }
+
+    /// Merge an intelligence analysis pass into a home's graph
+    pub fn update_knowledge(
+        &self,
+        home_id: &str,
+        entities: &[Entity],
+        context: &EnvironmentalContext,
+        profiles: &[PsychologicalProfile],
+        at: DateTime<Utc>,
+    ) -> Result<Vec<String>, SchemaError> {
+        let mut graph = self.homes.entry(home_id.to_string()).or_insert_with(|| KnowledgeGraph {
+            home_id: home_id.to_string(),
+            ..KnowledgeGraph::default()
+        });
+        let changes = graph.update_knowledge(entities, context, profiles, at);
+        self.persist(&graph)?;
+        Ok(changes)
+    }
+
+    /// Run a read-only query against a home's graph
+    pub fn with_graph<R>(&self, home_id: &str, f: impl FnOnce(&KnowledgeGraph) -> R) -> Option<R> {
+        self.homes.get(home_id).map(|graph| f(&graph))
+    }
+
+    /// Drop what no home has seen within the retention period
+    pub fn prune(&self, now: DateTime<Utc>) {
+        let cutoff = now - Duration::days(self.config.retention_days);
+        for mut graph in self.homes.iter_mut() {
+            let before = (graph.node_count(), graph.edge_count());
+            graph.prune_older_than(cutoff);
+            if (graph.node_count(), graph.edge_count()) != before {
+                if let Err(e) = self.persist(&graph) {
+                    tracing::warn!("Could not save pruned knowledge graph for {}: {}", graph.home_id, e);
+                }
+            }
+        }
+    }
+
+    fn persist(&self, graph: &KnowledgeGraph) -> Result<(), SchemaError> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        home_files::write_json(dir, &graph.home_id, &to_versioned_json(graph)?)
+            .map_err(|e| SchemaError::Migration(format!("write {}: {}", home_files::json_path(dir, &graph.home_id).display(), e)))
+    }
+}
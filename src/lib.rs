@@ -8,10 +8,67 @@ pub mod api;
 pub mod prediction;
 pub mod core;
 pub mod pipeline;
+pub mod ingest;
 pub mod vps_client;
+pub mod circuit_breaker;
 pub mod thinking;
 pub mod overnight;
 pub mod image_preloader;
+pub mod snapshot_priority;
+pub mod image_transcode;
+pub mod delivery;
+pub mod idempotency;
+pub mod environment;
+pub mod federation;
+pub mod schema;
+pub mod embeddings;
+pub mod features;
+pub mod metering;
+pub mod validation;
+pub mod visitor_tokens;
+pub mod guest_access;
+pub mod follow_up;
+pub mod probability_series;
+pub mod edge_inference;
+pub mod explanation;
+pub mod vacation;
+pub mod household;
+pub mod arming;
+pub mod camera_registry;
+pub mod camera_health;
+pub mod dataset_export;
+pub mod annotations;
+pub mod adaptive_thresholds;
+pub mod self_test;
+pub mod watchdog;
+pub mod device_signing;
+pub mod backup;
+pub mod federated_learning;
+pub mod sanitization;
+pub mod onboarding;
+pub mod redaction;
+pub mod tracker;
+pub mod counter_surveillance;
+pub mod knowledge_graph;
+pub mod zone_graph;
+pub mod activity_baseline;
+pub mod entity_trust;
+pub mod encryption;
+pub mod home_files;
+pub mod escalation_rules;
+pub mod automations;
+pub mod tamper;
+pub mod anti_spoofing;
+pub mod red_team;
+pub mod pipeline_stages;
+pub mod load_shedding;
+pub mod i18n;
+pub mod bench_report;
+pub mod clock;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+#[cfg(feature = "client")]
+pub mod client;
 
 // pub mod observability;
 // pub mod config;
@@ -0,0 +1,267 @@
+// src/load_shedding.rs
+
+// Load shedding for event storms. A thunderstorm or a windy night can set off
+// every motion sensor in a home at once, and processing each of those events
+// in full (VPS analysis, fusion, notification) buries the few that matter.
+// `LoadShedder` counts events per home over a sliding window; once the rate
+// passes `max_events_per_window` the home is overloaded until it falls back
+// below `resume_fraction` of that limit. While overloaded, events from
+// low-priority cameras (street, yard and perimeter views by default) are
+// sampled: one in `sample_every` still goes through, the rest are folded into
+// a single "high activity" meta-incident for the home instead of each opening
+// its own. Doorbell presses and interior cameras are never shed.
+//
+// The shedder is a stage middleware that acts before Sanitize, so a shed
+// event costs a window update and nothing else. The server enables it unless
+// NOVIN_LOAD_SHED=off; the limits come from NOVIN_LOAD_SHED_MAX_EVENTS,
+// NOVIN_LOAD_SHED_WINDOW_S and NOVIN_LOAD_SHED_SAMPLE_EVERY.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use tracing::{info, warn};
+
+use crate::onboarding::ZoneKind;
+use crate::pipeline_stages::{PipelineRun, Stage, StageControl, StageMiddleware};
+
+#[derive(thiserror::Error, Debug)]
+pub enum LoadSheddingError {
+    #[error("Invalid load shedding setting {0}: {1}")]
+    Config(&'static str, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoadSheddingConfig {
+    pub window_s: u64,
+    pub max_events_per_window: usize, // Per home; above this the home is overloaded
+    pub resume_fraction: f64,         // Overload ends below this share of the limit
+    pub sample_every: u64,            // While overloaded, one low-priority event in this many is processed
+}
+
+impl Default for LoadSheddingConfig {
+    fn default() -> Self {
+        Self { window_s: 10, max_events_per_window: 60, resume_fraction: 0.5, sample_every: 5 }
+    }
+}
+
+impl LoadSheddingConfig {
+    /// Config from the environment; None when NOVIN_LOAD_SHED=off
+    pub fn from_env() -> Result<Option<Self>, LoadSheddingError> {
+        if std::env::var("NOVIN_LOAD_SHED").is_ok_and(|v| v.eq_ignore_ascii_case("off")) {
+            return Ok(None);
+        }
+        let mut config = Self::default();
+        if let Ok(v) = std::env::var("NOVIN_LOAD_SHED_MAX_EVENTS") {
+            config.max_events_per_window = v.parse().map_err(|_| LoadSheddingError::Config("NOVIN_LOAD_SHED_MAX_EVENTS", v))?;
+        }
+        if let Ok(v) = std::env::var("NOVIN_LOAD_SHED_WINDOW_S") {
+            config.window_s = v.parse().map_err(|_| LoadSheddingError::Config("NOVIN_LOAD_SHED_WINDOW_S", v))?;
+        }
+        if let Ok(v) = std::env::var("NOVIN_LOAD_SHED_SAMPLE_EVERY") {
+            config.sample_every = v.parse().map_err(|_| LoadSheddingError::Config("NOVIN_LOAD_SHED_SAMPLE_EVERY", v))?;
+        }
+        config.validate()?;
+        Ok(Some(config))
+    }
+
+    pub fn validate(&self) -> Result<(), LoadSheddingError> {
+        if self.window_s == 0 {
+            return Err(LoadSheddingError::Config("window_s", "must be positive".to_string()));
+        }
+        if self.max_events_per_window == 0 {
+            return Err(LoadSheddingError::Config("max_events_per_window", "must be positive".to_string()));
+        }
+        if self.sample_every == 0 {
+            return Err(LoadSheddingError::Config("sample_every", "must be positive".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.resume_fraction) {
+            return Err(LoadSheddingError::Config("resume_fraction", format!("must be between 0 and 1, got {}", self.resume_fraction)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CameraPriority {
+    Protected, // Never shed
+    #[default]
+    Normal,    // Processed in full; counts towards the rate
+    Low,       // Sampled while the home is overloaded
+}
+
+impl From<ZoneKind> for CameraPriority {
+    fn from(kind: ZoneKind) -> Self {
+        match kind {
+            ZoneKind::Interior => CameraPriority::Protected,
+            ZoneKind::Street | ZoneKind::Perimeter | ZoneKind::Yard => CameraPriority::Low,
+            _ => CameraPriority::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Process,
+    Sampled,    // Low priority under load, processed as this window's sample
+    Aggregated, // Folded into the high-activity incident, not processed
+}
+
+/// Many low-priority events summarized as one, while a home was overloaded
+#[derive(Debug, Clone, Serialize)]
+pub struct HighActivityIncident {
+    pub home_id: String,
+    pub started_at: DateTime<Utc>,
+    pub last_event_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>, // None while the home is still overloaded
+    pub aggregated: u64,                 // Events folded in without processing
+    pub sampled: u64,                    // Low-priority events still processed
+    pub cameras: BTreeMap<String, u64>,  // Aggregated events per camera
+}
+
+#[derive(Default)]
+struct HomeLoad {
+    window: VecDeque<DateTime<Utc>>,
+    overloaded: bool,
+    low_seen: u64, // Low-priority events since the overload began, for sampling
+    priorities: HashMap<String, CameraPriority>,
+    incident: Option<HighActivityIncident>,
+}
+
+impl HomeLoad {
+    fn observe(&mut self, at: DateTime<Utc>, config: &LoadSheddingConfig) {
+        let cutoff = at - chrono::Duration::seconds(config.window_s as i64);
+        while self.window.front().is_some_and(|t| *t <= cutoff) {
+            self.window.pop_front();
+        }
+        self.window.push_back(at);
+
+        let rate = self.window.len();
+        if !self.overloaded && rate > config.max_events_per_window {
+            self.overloaded = true;
+            self.low_seen = 0;
+        } else if self.overloaded && (rate as f64) < config.max_events_per_window as f64 * config.resume_fraction {
+            self.overloaded = false;
+            if let Some(incident) = self.incident.as_mut().filter(|i| i.ended_at.is_none()) {
+                incident.ended_at = Some(at);
+            }
+        }
+    }
+
+    fn incident(&mut self, home_id: &str, at: DateTime<Utc>) -> &mut HighActivityIncident {
+        if !self.incident.as_ref().is_some_and(|i| i.ended_at.is_none()) {
+            self.incident = Some(HighActivityIncident {
+                home_id: home_id.to_string(),
+                started_at: at,
+                last_event_at: at,
+                ended_at: None,
+                aggregated: 0,
+                sampled: 0,
+                cameras: BTreeMap::new(),
+            });
+        }
+        let incident = self.incident.as_mut().expect("just opened");
+        incident.last_event_at = at;
+        incident
+    }
+}
+
+pub struct LoadShedder {
+    config: LoadSheddingConfig,
+    homes: DashMap<String, HomeLoad>,
+}
+
+impl Default for LoadShedder {
+    fn default() -> Self {
+        Self::new(LoadSheddingConfig::default())
+    }
+}
+
+impl LoadShedder {
+    pub fn new(config: LoadSheddingConfig) -> Self {
+        Self { config, homes: DashMap::new() }
+    }
+
+    pub fn config(&self) -> &LoadSheddingConfig {
+        &self.config
+    }
+
+    /// Camera priorities for a home; cameras not listed are Normal
+    pub fn set_camera_priorities(&self, home_id: &str, priorities: HashMap<String, CameraPriority>) {
+        self.homes.entry(home_id.to_string()).or_default().priorities = priorities;
+    }
+
+    /// Decide whether an event is processed, and count it towards the home's rate
+    pub fn admit(&self, home_id: &str, camera: &str, data: &str, at: DateTime<Utc>) -> Admission {
+        let mut home = self.homes.entry(home_id.to_string()).or_default();
+        let was_overloaded = home.overloaded;
+        home.observe(at, &self.config);
+        if home.overloaded && !was_overloaded {
+            warn!("Home {} is over {} events in {}s; shedding low-priority cameras", home_id, self.config.max_events_per_window, self.config.window_s);
+        } else if was_overloaded && !home.overloaded {
+            info!("Home {} is back under its event limit", home_id);
+        }
+
+        let priority = if is_doorbell(camera, data) {
+            CameraPriority::Protected
+        } else {
+            home.priorities.get(camera).copied().unwrap_or_default()
+        };
+        if !home.overloaded || priority != CameraPriority::Low {
+            return Admission::Process;
+        }
+
+        let sample = home.low_seen % self.config.sample_every == 0;
+        home.low_seen += 1;
+        let incident = home.incident(home_id, at);
+        if sample {
+            incident.sampled += 1;
+            Admission::Sampled
+        } else {
+            incident.aggregated += 1;
+            *incident.cameras.entry(camera.to_string()).or_default() += 1;
+            Admission::Aggregated
+        }
+    }
+
+    /// The home's current high-activity incident, or the last one to end
+    pub fn high_activity(&self, home_id: &str) -> Option<HighActivityIncident> {
+        self.homes.get(home_id).and_then(|h| h.incident.clone())
+    }
+
+    pub fn is_overloaded(&self, home_id: &str) -> bool {
+        self.homes.get(home_id).is_some_and(|h| h.overloaded)
+    }
+}
+
+// Doorbell presses are reported in the payload ("doorbell=true") or come
+// from a sensor named for the doorbell
+fn is_doorbell(camera: &str, data: &str) -> bool {
+    camera.to_ascii_lowercase().contains("doorbell")
+        || data.split(['|', ',', '&', ' '])
+            .any(|pair| matches!(pair.trim().to_ascii_lowercase().as_str(), "doorbell=true" | "rang_doorbell=true"))
+}
+
+#[async_trait]
+impl StageMiddleware for LoadShedder {
+    fn name(&self) -> &str {
+        "load_shedding"
+    }
+
+    async fn before(&self, stage: Stage, run: &mut PipelineRun) -> StageControl {
+        if stage != Stage::Sanitize {
+            return StageControl::Continue;
+        }
+        let event = &run.event;
+        match self.admit(&event.home_id, &event.sensor_id, &event.data, run.event_time) {
+            Admission::Process => StageControl::Continue,
+            Admission::Sampled => {
+                run.notes.push("Sampled during high activity".to_string());
+                StageControl::Continue
+            }
+            Admission::Aggregated => StageControl::Halt("high activity: folded into the home's high-activity incident".to_string()),
+        }
+    }
+}
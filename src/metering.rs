@@ -0,0 +1,225 @@
+// src/metering.rs
+//
+// Billable usage counters per account, rolled up by UTC day. The pipeline, image
+// preloader and LLM client record units as they are consumed; the billing export
+// API reads the daily rollups. API calls are counted per account by hour
+// (requests, errors, rate-limit rejections) alongside image bytes fetched, so
+// integrators can see their own consumption over time.
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BillableUnit {
+    EventsProcessed,
+    VpsCalls,
+    ImageBytes, // Reported as GB in exports
+    LlmTokens,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub account_id: String,
+    pub date: NaiveDate,
+    pub events_processed: u64,
+    pub vps_calls: u64,
+    pub image_bytes: u64,
+    pub llm_tokens: u64,
+}
+
+impl DailyUsage {
+    pub fn image_gb(&self) -> f64 {
+        self.image_bytes as f64 / 1_000_000_000.0
+    }
+
+    fn add(&mut self, unit: BillableUnit, amount: u64) {
+        let counter = match unit {
+            BillableUnit::EventsProcessed => &mut self.events_processed,
+            BillableUnit::VpsCalls => &mut self.vps_calls,
+            BillableUnit::ImageBytes => &mut self.image_bytes,
+            BillableUnit::LlmTokens => &mut self.llm_tokens,
+        };
+        *counter = counter.saturating_add(amount);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageWindow {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl UsageWindow {
+    fn duration(&self) -> Duration {
+        match self {
+            UsageWindow::Hour => Duration::hours(1),
+            UsageWindow::Day => Duration::days(1),
+        }
+    }
+}
+
+/// API consumption of one account in one time window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiUsage {
+    pub account_id: String,
+    pub window_start: DateTime<Utc>,
+    pub requests: u64,
+    pub errors: u64,       // 4xx and 5xx responses other than 429
+    pub rate_limited: u64, // 429 responses
+    pub image_bytes: u64,
+}
+
+impl ApiUsage {
+    fn empty(account_id: &str, window_start: DateTime<Utc>) -> Self {
+        Self { account_id: account_id.to_string(), window_start, requests: 0, errors: 0, rate_limited: 0, image_bytes: 0 }
+    }
+
+    fn merge(&mut self, other: &ApiUsage) {
+        self.requests += other.requests;
+        self.errors += other.errors;
+        self.rate_limited += other.rate_limited;
+        self.image_bytes += other.image_bytes;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiUsageReport {
+    pub account_id: String,
+    pub window: UsageWindow,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub windows: Vec<ApiUsage>, // Only windows with activity, oldest first
+    pub total: ApiUsage,
+}
+
+fn hour_of(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.duration_trunc(Duration::hours(1)).unwrap_or(at)
+}
+
+#[derive(Debug, Default)]
+pub struct UsageMeter {
+    days: DashMap<(String, NaiveDate), DailyUsage>,
+    api_hours: DashMap<(String, DateTime<Utc>), ApiUsage>,
+}
+
+impl UsageMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, account_id: &str, unit: BillableUnit, amount: u64) {
+        self.record_at(account_id, unit, amount, Utc::now());
+    }
+
+    pub fn record_at(&self, account_id: &str, unit: BillableUnit, amount: u64, at: DateTime<Utc>) {
+        if amount == 0 {
+            return;
+        }
+        let date = at.date_naive();
+        self.days
+            .entry((account_id.to_string(), date))
+            .or_insert_with(|| DailyUsage { account_id: account_id.to_string(), date, ..Default::default() })
+            .add(unit, amount);
+        if unit == BillableUnit::ImageBytes {
+            self.api_hour(account_id, at).image_bytes += amount;
+        }
+    }
+
+    fn api_hour(&self, account_id: &str, at: DateTime<Utc>) -> dashmap::mapref::one::RefMut<'_, (String, DateTime<Utc>), ApiUsage> {
+        let hour = hour_of(at);
+        self.api_hours
+            .entry((account_id.to_string(), hour))
+            .or_insert_with(|| ApiUsage::empty(account_id, hour))
+    }
+
+    /// Count one API request and classify its response status
+    pub fn record_api_call(&self, account_id: &str, status: u16, at: DateTime<Utc>) {
+        let mut usage = self.api_hour(account_id, at);
+        usage.requests += 1;
+        match status {
+            429 => usage.rate_limited += 1,
+            s if s >= 400 => usage.errors += 1,
+            _ => {}
+        }
+    }
+
+    /// API usage for one account in [from, to), grouped into windows
+    pub fn api_usage(&self, account_id: &str, from: DateTime<Utc>, to: DateTime<Utc>, window: UsageWindow) -> ApiUsageReport {
+        let mut windows: Vec<ApiUsage> = Vec::new();
+        let mut hours: Vec<ApiUsage> = self.api_hours
+            .iter()
+            .filter(|e| e.account_id == account_id && e.window_start >= hour_of(from) && e.window_start < to)
+            .map(|e| e.value().clone())
+            .collect();
+        hours.sort_by_key(|h| h.window_start);
+        for hour in hours {
+            let start = hour.window_start.duration_trunc(window.duration()).unwrap_or(hour.window_start);
+            match windows.last_mut() {
+                Some(last) if last.window_start == start => last.merge(&hour),
+                _ => {
+                    let mut usage = ApiUsage::empty(account_id, start);
+                    usage.merge(&hour);
+                    windows.push(usage);
+                }
+            }
+        }
+        let mut total = ApiUsage::empty(account_id, from);
+        for usage in &windows {
+            total.merge(usage);
+        }
+        ApiUsageReport { account_id: account_id.to_string(), window, from, to, windows, total }
+    }
+
+    /// Daily rollups in [from, to] (inclusive), optionally for one account, ordered by account then day
+    pub fn rollups(&self, account_id: Option<&str>, from: NaiveDate, to: NaiveDate) -> Vec<DailyUsage> {
+        let mut rows: Vec<DailyUsage> = self.days
+            .iter()
+            .filter(|e| e.date >= from && e.date <= to)
+            .filter(|e| account_id.map_or(true, |a| e.account_id == a))
+            .map(|e| e.value().clone())
+            .collect();
+        rows.sort_by(|a, b| (&a.account_id, a.date).cmp(&(&b.account_id, b.date)));
+        rows
+    }
+
+    /// Sum of the rollups for one account over a period
+    pub fn total(&self, account_id: &str, from: NaiveDate, to: NaiveDate) -> DailyUsage {
+        let mut total = DailyUsage { account_id: account_id.to_string(), date: from, ..Default::default() };
+        for day in self.rollups(Some(account_id), from, to) {
+            total.events_processed += day.events_processed;
+            total.vps_calls += day.vps_calls;
+            total.image_bytes += day.image_bytes;
+            total.llm_tokens += day.llm_tokens;
+        }
+        total
+    }
+
+    /// Drop rollups older than `before` once they have been billed
+    pub fn prune_before(&self, before: NaiveDate) {
+        self.days.retain(|(_, date), _| *date >= before);
+        self.api_hours.retain(|(_, hour), _| hour.date_naive() >= before);
+    }
+}
+
+/// CSV with one row per account and day, image usage in GB
+pub fn export_csv(rows: &[DailyUsage]) -> String {
+    let mut out = String::from("account_id,date,events_processed,vps_calls,image_gb,llm_tokens\n");
+    for row in rows {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{:.6},{}",
+            row.account_id.replace(',', "_"),
+            row.date,
+            row.events_processed,
+            row.vps_calls,
+            row.image_gb(),
+            row.llm_tokens,
+        );
+    }
+    out
+}
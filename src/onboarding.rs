@@ -0,0 +1,304 @@
+// src/onboarding.rs
+
+// Starter configuration for a new home. The onboarding wizard sends cameras,
+// the zone each one watches, household details and a sensitivity profile;
+// `generate` turns that into zone risk priors, an overnight review window
+// fitted to the home's timezone and latitude, and an alert threshold. The
+// result is validated as a whole and persisted with a write-then-rename, so a
+// home never ends up with half a config.
+
+use crate::environment::{compute_sun_times, CalendarConfig, HolidayCalendar, SunTimes};
+use crate::home_files;
+use crate::overnight::OvernightConfig;
+use crate::thinking::{CameraOverlap, UncertaintyPolicy};
+use crate::validation::{ConfigError, Validate};
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(thiserror::Error, Debug)]
+pub enum OnboardingError {
+    #[error("Invalid onboarding request: {0}")]
+    Invalid(#[from] ConfigError),
+
+    #[error("Could not persist home config: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Could not encode home config: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitivityProfile {
+    Conservative, // Fewer, surer alerts
+    #[default]
+    Balanced,
+    Vigilant,     // Alert earlier and accept more false alarms
+}
+
+impl SensitivityProfile {
+    pub fn alert_threshold_logit(&self) -> f64 {
+        match self {
+            SensitivityProfile::Conservative => -1.0986, // logit(0.25)
+            SensitivityProfile::Balanced => -1.7346,     // logit(0.15), the engine default
+            SensitivityProfile::Vigilant => -2.4423,     // logit(0.08)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneKind {
+    FrontDoor,
+    BackDoor,
+    Garage,
+    Driveway,
+    Yard,
+    Perimeter,
+    Interior,
+    Street,
+}
+
+impl ZoneKind {
+    /// Starting prior shift: entry points that intruders favour score higher,
+    /// public-facing views where passers-by are normal score lower
+    pub fn default_prior_logit(&self) -> f64 {
+        match self {
+            ZoneKind::BackDoor => 0.6,
+            ZoneKind::Garage => 0.4,
+            ZoneKind::Yard | ZoneKind::Perimeter => 0.3,
+            ZoneKind::Interior => 0.2,
+            ZoneKind::FrontDoor => 0.0,
+            ZoneKind::Driveway => -0.2,
+            ZoneKind::Street => -0.8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraSetup {
+    pub camera_id: String,
+    pub zone: String, // Name shown to the user, e.g. "Back garden"
+    pub kind: ZoneKind,
+    #[serde(default)]
+    pub overlaps_with: Vec<String>, // Cameras seeing the same spot, so one visitor is one incident
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HouseholdInfo {
+    pub residents: u32,
+    pub has_pets: bool,     // Indoor cameras see animals; interior motion counts for less
+    pub has_children: bool, // Yards see play at odd hours
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingRequest {
+    pub timezone: String,
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    #[serde(default = "default_holidays")]
+    pub holidays: HolidayCalendar,
+    pub cameras: Vec<CameraSetup>,
+    #[serde(default)]
+    pub household: HouseholdInfo,
+    #[serde(default)]
+    pub profile: SensitivityProfile,
+    #[serde(default)]
+    pub uncertainty: Option<UncertaintyPolicy>, // Overrides the profile's interval policy
+    #[serde(default)]
+    pub share_with_neighbors: bool, // Opt into neighborhood federation; needs a location
+}
+
+fn default_holidays() -> HolidayCalendar {
+    HolidayCalendar::None
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZonePrior {
+    pub zone: String,
+    pub kind: ZoneKind,
+    pub cameras: Vec<String>,
+    pub prior_logit: f64,
+}
+
+// Everything onboarding decides for one home
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeConfig {
+    pub home_id: String,
+    pub profile: SensitivityProfile,
+    pub alert_threshold_logit: f64,
+    pub calendar: CalendarConfig,
+    pub overnight: OvernightConfig,
+    pub zones: Vec<ZonePrior>,
+    #[serde(default)]
+    pub camera_overlaps: Vec<CameraOverlap>,
+    #[serde(default)]
+    pub uncertainty: Option<UncertaintyPolicy>, // None follows the profile
+    #[serde(default)]
+    pub share_with_neighbors: bool,
+    pub household: HouseholdInfo,
+    pub created_at: DateTime<Utc>,
+}
+
+impl HomeConfig {
+    /// How uncertain an alert may be before it is held for a re-check
+    pub fn uncertainty_policy(&self) -> UncertaintyPolicy {
+        self.uncertainty.clone().unwrap_or_else(|| UncertaintyPolicy::for_profile(self.profile))
+    }
+
+    /// Prior shift for each camera, from the zone it watches
+    pub fn camera_priors(&self) -> HashMap<String, f64> {
+        self.zones.iter()
+            .flat_map(|z| z.cameras.iter().map(move |c| (c.clone(), z.prior_logit)))
+            .collect()
+    }
+}
+
+/// Build a starter config. `today` picks the date the overnight window is fitted to.
+pub fn generate(home_id: &str, request: &OnboardingRequest, today: NaiveDate) -> Result<HomeConfig, OnboardingError> {
+    request.validate()?;
+
+    let calendar = CalendarConfig {
+        latitude: request.latitude,
+        longitude: request.longitude,
+        timezone: request.timezone.clone(),
+        holidays: request.holidays,
+        ..CalendarConfig::default()
+    };
+    let (review_start_time, review_end_time) = overnight_window(&calendar, today);
+    let overnight = OvernightConfig {
+        home_id: home_id.to_string(),
+        review_start_time,
+        review_end_time,
+        summary_delivery_time: review_end_time + chrono::Duration::hours(1),
+        timezone: request.timezone.clone(),
+        ..OvernightConfig::default()
+    };
+
+    // One prior per named zone; cameras sharing a zone share its prior
+    let mut zones: Vec<ZonePrior> = Vec::new();
+    for camera in &request.cameras {
+        match zones.iter_mut().find(|z| z.zone == camera.zone) {
+            Some(zone) => zone.cameras.push(camera.camera_id.clone()),
+            None => zones.push(ZonePrior {
+                zone: camera.zone.clone(),
+                kind: camera.kind,
+                cameras: vec![camera.camera_id.clone()],
+                prior_logit: zone_prior(camera.kind, &request.household),
+            }),
+        }
+    }
+
+    let camera_overlaps = request.cameras.iter()
+        .filter(|c| !c.overlaps_with.is_empty())
+        .map(|c| CameraOverlap::new(std::iter::once(c.camera_id.clone()).chain(c.overlaps_with.iter().cloned()).collect()))
+        .collect();
+
+    let config = HomeConfig {
+        home_id: home_id.to_string(),
+        profile: request.profile,
+        alert_threshold_logit: request.profile.alert_threshold_logit(),
+        calendar,
+        overnight,
+        zones,
+        camera_overlaps,
+        uncertainty: request.uncertainty.clone(),
+        share_with_neighbors: request.share_with_neighbors,
+        household: request.household.clone(),
+        created_at: Utc::now(),
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+fn zone_prior(kind: ZoneKind, household: &HouseholdInfo) -> f64 {
+    let mut prior = kind.default_prior_logit();
+    if household.has_pets && kind == ZoneKind::Interior {
+        prior -= 0.5;
+    }
+    if household.has_children && kind == ZoneKind::Yard {
+        prior -= 0.2;
+    }
+    prior
+}
+
+/// Review window in local time: from three hours after sunset (between 21:00
+/// and midnight) to half an hour before sunrise (between 05:00 and 07:30).
+/// Without a location, or in polar day or night, it is 22:00–06:00.
+pub fn overnight_window(calendar: &CalendarConfig, date: NaiveDate) -> (NaiveTime, NaiveTime) {
+    let fallback = (hm(22, 0), hm(6, 0));
+    let (Some(lat), Some(lon), Ok(tz)) = (calendar.latitude, calendar.longitude, calendar.timezone.parse::<Tz>()) else {
+        return fallback;
+    };
+    let SunTimes::Normal { sunrise, sunset } = compute_sun_times(date, lat, lon) else {
+        return fallback;
+    };
+    let local = |at: DateTime<Utc>| at.with_timezone(&tz).time();
+    let start = round_to_half_hour(local(sunset + chrono::Duration::hours(3)));
+    let end = round_to_half_hour(local(sunrise - chrono::Duration::minutes(30)));
+    // Sunset + 3h wraps past midnight only in summer at high latitudes; cap it there
+    let start = if start < hm(12, 0) { hm(23, 59) } else { start.clamp(hm(21, 0), hm(23, 59)) };
+    (start, end.clamp(hm(5, 0), hm(7, 30)))
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).expect("valid wall-clock time")
+}
+
+fn round_to_half_hour(time: NaiveTime) -> NaiveTime {
+    let minutes = (time.hour() * 60 + time.minute() + 15) / 30 * 30;
+    if minutes >= 24 * 60 { hm(23, 59) } else { hm(minutes / 60, minutes % 60) }
+}
+
+/// Home configs, kept in memory and optionally one JSON file per home
+#[derive(Debug, Default)]
+pub struct HomeConfigStore {
+    homes: DashMap<String, HomeConfig>,
+    dir: Option<PathBuf>,
+}
+
+impl HomeConfigStore {
+    /// Store backed by `dir`, loading any configs already there
+    pub fn persistent(dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let homes = DashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path).map(|text| serde_json::from_str::<HomeConfig>(&text)) {
+                Ok(Ok(config)) => {
+                    homes.insert(config.home_id.clone(), config);
+                }
+                Ok(Err(e)) => tracing::warn!("Skipping unreadable home config {}: {}", path.display(), e),
+                Err(e) => tracing::warn!("Skipping home config {}: {}", path.display(), e),
+            }
+        }
+        Ok(Self { homes, dir: Some(dir) })
+    }
+
+    pub fn get(&self, home_id: &str) -> Option<HomeConfig> {
+        self.homes.get(home_id).map(|c| c.clone())
+    }
+
+    pub fn all(&self) -> Vec<HomeConfig> {
+        self.homes.iter().map(|c| c.clone()).collect()
+    }
+
+    /// Persist a config, then publish it
+    pub fn save(&self, config: HomeConfig) -> Result<(), OnboardingError> {
+        if let Some(dir) = &self.dir {
+            home_files::write_json(dir, &config.home_id, &serde_json::to_vec_pretty(&config)?)?;
+        }
+        self.homes.insert(config.home_id.clone(), config);
+        Ok(())
+    }
+}
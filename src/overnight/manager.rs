@@ -1,6 +1,9 @@
 use super::*;
+use super::window::next_local_time;
+use std::collections::HashMap;
 use crate::pipeline::RawEvent;
-use crate::thinking::{ThinkingAIProcessor, AlertDecision};
+use crate::thinking::AlertDecision;
+use crate::clock::Clock;
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -9,7 +12,8 @@ use serde::{Serialize, Deserialize};
 
 pub struct OvernightReviewManager {
     storage: Arc<dyn OvernightStorage>,
-    thinking_ai: Arc<RwLock<ThinkingAIProcessor>>,
+    configs: RwLock<HashMap<String, OvernightConfig>>, // Homes without one use OvernightConfig::default()
+    clock: Arc<dyn Clock>, // Dates summaries and stamps deliveries
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +23,10 @@ pub struct OvernightEventAnalysis {
     pub timestamp: DateTime<Utc>,
     pub analysis_summary: String,
     pub suppressed_alert_level: Option<AlertDecision>,
+    #[serde(default)]
+    pub camera: Option<String>,
+    #[serde(default)]
+    pub entity_id: Option<uuid::Uuid>, // Person re-identified across the night's detections
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,46 +36,213 @@ pub struct MorningSummary {
     pub event_count: usize,
     pub narrative: String,
     pub requires_attention: bool,
+    #[serde(default)]
+    pub event_ids: Vec<uuid::Uuid>, // Events covered, marked delivered once the summary goes out
+    #[serde(default)]
+    pub highlights: Vec<SummaryHighlight>, // Events that would have alerted, most severe first
+    #[serde(default)]
+    pub entities: Vec<EntityActivity>, // People behind more than one event, earliest first
+}
+
+/// Several of the night's events linked to one re-identified person
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityActivity {
+    pub entity_id: uuid::Uuid,
+    pub event_count: usize,
+    pub cameras: Vec<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub narrative: String,
+    pub event_ids: Vec<uuid::Uuid>,
+}
+
+/// One event that would have alerted, for the detailed (email) summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryHighlight {
+    pub event_id: uuid::Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub level: AlertDecision,
+    pub summary: String,
+    #[serde(default)]
+    pub thumbnail_url: Option<String>, // Filled in by the pipeline from the matching incident
+    #[serde(default)]
+    pub entity_id: Option<uuid::Uuid>, // Set when the highlight stands for all of one person's events
+}
+
+const MAX_HIGHLIGHTS: usize = 10;
+
+fn severity_rank(level: &AlertDecision) -> u8 {
+    match level {
+        AlertDecision::Critical => 3,
+        AlertDecision::Elevated => 2,
+        AlertDecision::Standard => 1,
+        AlertDecision::Wait | AlertDecision::Ignore => 0,
+    }
+}
+
+// Group events by the person they were linked to; only people behind two or more events are reported
+fn link_entities(events: &[OvernightEventAnalysis], tz: chrono_tz::Tz, locale: &str) -> Vec<EntityActivity> {
+    let mut by_entity: HashMap<uuid::Uuid, Vec<&OvernightEventAnalysis>> = HashMap::new();
+    for event in events {
+        if let Some(entity_id) = event.entity_id {
+            by_entity.entry(entity_id).or_default().push(event);
+        }
+    }
+    // 2:10am in English, 02:10 elsewhere
+    let time_format = if locale.starts_with("en") { "%-I:%M%P" } else { "%H:%M" };
+    let mut entities: Vec<EntityActivity> = by_entity.into_iter()
+        .filter(|(_, linked)| linked.len() > 1)
+        .map(|(entity_id, mut linked)| {
+            linked.sort_by_key(|e| e.timestamp);
+            let mut cameras: Vec<String> = linked.iter().filter_map(|e| e.camera.clone()).collect();
+            cameras.sort();
+            cameras.dedup();
+            let (first_seen, last_seen) = (linked[0].timestamp, linked[linked.len() - 1].timestamp);
+            let narrative = crate::i18n::localizer().plural(locale, "summary-entity", cameras.len().max(1) as u64, &[
+                ("events", linked.len().to_string()),
+                ("start", first_seen.with_timezone(&tz).format(time_format).to_string()),
+                ("end", last_seen.with_timezone(&tz).format(time_format).to_string()),
+            ]);
+            EntityActivity {
+                entity_id,
+                event_count: linked.len(),
+                cameras,
+                first_seen,
+                last_seen,
+                narrative,
+                event_ids: linked.iter().map(|e| e.event_id).collect(),
+            }
+        })
+        .collect();
+    entities.sort_by_key(|e| e.first_seen);
+    entities
 }
 
 impl OvernightReviewManager {
-    pub fn new(storage: Arc<dyn OvernightStorage>, thinking_ai: Arc<RwLock<ThinkingAIProcessor>>) -> Self {
-        Self { storage, thinking_ai }
+    /// A manager over `storage` that reads the time from `clock`
+    pub fn new(storage: Arc<dyn OvernightStorage>, clock: Arc<dyn Clock>) -> Self {
+        Self { storage, configs: RwLock::new(HashMap::new()), clock }
+    }
+
+    pub fn storage(&self) -> &Arc<dyn OvernightStorage> {
+        &self.storage
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    async fn config_for(&self, home_id: &str) -> OvernightConfig {
+        self.configs.read().await.get(home_id).cloned().unwrap_or_default()
     }
     
-    pub async fn is_in_review_period(&self, _home_id: &str, _event_time: DateTime<Utc>) -> Result<bool> {
-        Ok(true)
+    /// Whether an event falls inside the home's review window, in the home's timezone
+    pub async fn is_in_review_period(&self, home_id: &str, event_time: DateTime<Utc>) -> Result<bool> {
+        let config = self.config_for(home_id).await;
+        if !config.enabled {
+            return Ok(false);
+        }
+        Ok(ReviewWindow::from_config(&config)?.contains(event_time))
+    }
+
+    /// When the home's next morning summary is due
+    pub async fn next_delivery(&self, home_id: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        Self::calculate_next_delivery_static(&self.config_for(home_id).await, after)
+    }
+
+    /// Next local `summary_delivery_time` strictly after `after`, as a UTC instant
+    pub fn calculate_next_delivery_static(config: &OvernightConfig, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let window = ReviewWindow::from_config(config)?;
+        Ok(next_local_time(window.tz, config.summary_delivery_time, after))
     }
     
     pub async fn process_for_overnight_review(&self, event: &RawEvent) -> Result<OvernightEventAnalysis> {
         Ok(OvernightEventAnalysis {
             event_id: event.event_id,
             home_id: event.home_id.clone(),
-            timestamp: DateTime::from_timestamp(event.timestamp, 0).unwrap_or_else(|| Utc::now()),
+            timestamp: DateTime::from_timestamp(event.timestamp, 0).unwrap_or_else(|| self.now()),
             analysis_summary: "Processed overnight".to_string(),
             suppressed_alert_level: Some(AlertDecision::Standard),
+            camera: Some(event.sensor_id.clone()),
+            entity_id: None,
         })
     }
     
-    pub async fn store_overnight_event(&self, _analysis: OvernightEventAnalysis) -> Result<()> {
-        Ok(())
+    pub async fn store_overnight_event(&self, analysis: OvernightEventAnalysis) -> Result<()> {
+        self.storage.store_event(&analysis).await
     }
     
-    pub async fn generate_morning_summary(&self, home_id: &str) -> Result<MorningSummary> {
+    /// Summarize every event still waiting for a morning delivery, in the home's locale
+    pub async fn generate_morning_summary(&self, home_id: &str, locale: &str) -> Result<MorningSummary> {
+        let events = self.storage.pending_events(home_id).await?;
+        let alerts: Vec<&AlertDecision> = events.iter()
+            .filter_map(|e| e.suppressed_alert_level.as_ref())
+            .filter(|level| !matches!(level, AlertDecision::Ignore | AlertDecision::Wait))
+            .collect();
+        let requires_attention = alerts.iter().any(|level| matches!(level, AlertDecision::Elevated | AlertDecision::Critical));
+
+        let localizer = crate::i18n::localizer();
+        let tz = ReviewWindow::from_config(&self.config_for(home_id).await).map_or(chrono_tz::Tz::UTC, |w| w.tz);
+        let entities = link_entities(&events, tz, locale);
+        let mut narrative = match (events.len(), alerts.len()) {
+            (0, _) => localizer.message(locale, "summary-quiet", &[]),
+            (n, 0) => localizer.plural(locale, "summary-no-alerts", n as u64, &[]),
+            (n, a) => localizer.plural(locale, "summary-alerts", n as u64, &[("alerts", a.to_string())]),
+        };
+        for entity in &entities {
+            narrative = format!("{}. {}", narrative, entity.narrative);
+        }
+
+        let mut highlights: Vec<SummaryHighlight> = events.iter()
+            .filter_map(|e| e.suppressed_alert_level.clone().filter(|l| severity_rank(l) > 0).map(|level| SummaryHighlight {
+                event_id: e.event_id,
+                timestamp: e.timestamp,
+                level,
+                summary: e.analysis_summary.clone(),
+                thumbnail_url: None,
+                entity_id: None,
+            }))
+            .collect();
+        // One highlight per linked person: their most severe event, described by their whole visit
+        for entity in &entities {
+            let mut linked: Vec<SummaryHighlight> = Vec::new();
+            highlights.retain(|h| match entity.event_ids.contains(&h.event_id) {
+                true => { linked.push(h.clone()); false }
+                false => true,
+            });
+            if let Some(mut top) = linked.into_iter().max_by(|a, b| severity_rank(&a.level).cmp(&severity_rank(&b.level)).then(b.timestamp.cmp(&a.timestamp))) {
+                top.summary = entity.narrative.clone();
+                top.entity_id = Some(entity.entity_id);
+                highlights.push(top);
+            }
+        }
+        highlights.sort_by(|a, b| severity_rank(&b.level).cmp(&severity_rank(&a.level)).then(a.timestamp.cmp(&b.timestamp)));
+        highlights.truncate(MAX_HIGHLIGHTS);
+
         Ok(MorningSummary {
             home_id: home_id.to_string(),
-            summary_date: Utc::now().date_naive(),
-            event_count: 0,
-            narrative: "Quiet night".to_string(),
-            requires_attention: false,
+            summary_date: self.now().date_naive(),
+            event_count: events.len(),
+            narrative,
+            requires_attention,
+            event_ids: events.iter().map(|e| e.event_id).collect(),
+            highlights,
+            entities,
         })
     }
+
+    /// Mark a summary's events as delivered so they are not summarized again
+    pub async fn mark_summary_delivered(&self, summary: &MorningSummary) -> Result<()> {
+        self.storage.mark_delivered(&summary.home_id, &summary.event_ids, self.now()).await
+    }
     
-    pub async fn update_config(&self, _config: OvernightConfig) -> Result<()> {
+    pub async fn update_config(&self, config: OvernightConfig) -> Result<()> {
+        ReviewWindow::from_config(&config)?;
+        self.configs.write().await.insert(config.home_id.clone(), config);
         Ok(())
     }
     
-    pub async fn get_config(&self, _home_id: &str) -> Option<OvernightConfig> {
-        None
+    pub async fn get_config(&self, home_id: &str) -> Option<OvernightConfig> {
+        self.configs.read().await.get(home_id).cloned()
     }
 }
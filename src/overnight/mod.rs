@@ -4,11 +4,13 @@ pub mod config;
 pub mod storage;
 pub mod summary;
 pub mod manager;
+pub mod window;
 
 // Re-export key types
-pub use manager::{OvernightReviewManager, OvernightEventAnalysis, MorningSummary};
-pub use storage::{OvernightStorageFactory, OvernightStorage};
+pub use manager::{EntityActivity, OvernightReviewManager, OvernightEventAnalysis, MorningSummary, SummaryHighlight};
+pub use storage::{InMemoryStorage, OvernightStorage, OvernightStorageBackend, OvernightStorageFactory, SqliteOvernightStorage};
 pub use summary::SummaryTone;
+pub use window::ReviewWindow;
 
 use chrono::NaiveTime;
 use serde::{Deserialize, Serialize};
@@ -22,6 +24,8 @@ pub struct OvernightConfig {
     pub timezone: String,
     pub enabled: bool,
     pub delivery_channels: Vec<DeliveryChannel>,
+    #[serde(default)]
+    pub storage: OvernightStorageBackend,
 }
 
 impl Default for OvernightConfig {
@@ -34,17 +38,19 @@ impl Default for OvernightConfig {
             timezone: "UTC".to_string(),
             enabled: true,
             delivery_channels: vec![DeliveryChannel::Push, DeliveryChannel::WebSocket],
+            storage: OvernightStorageBackend::Memory,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
 pub enum DeliveryChannel {
     Push,
     Email,
     WebSocket,
     SMS,
     Dashboard,
+    Webhook, // Signed POST to user-configured URLs, see crate::delivery::webhook
 }
 
 pub type OvernightResult<T> = anyhow::Result<T>;
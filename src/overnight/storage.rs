@@ -1,14 +1,164 @@
-use anyhow::Result;
+use super::{OvernightConfig, OvernightError, OvernightEventAnalysis, OvernightResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::{OnceCell, RwLock};
+use uuid::Uuid;
 
+// Where overnight events wait for the morning summary
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum OvernightStorageBackend {
+    #[default]
+    Memory,                         // Lost on restart
+    Sqlite { database_url: String }, // e.g. "sqlite://overnight.db?mode=rwc"
+}
+
+#[async_trait]
 pub trait OvernightStorage: Send + Sync {
-    fn store(&self, data: &str) -> Result<()>;
+    async fn store_event(&self, analysis: &OvernightEventAnalysis) -> OvernightResult<()>;
+
+    /// Events not yet covered by a delivered summary, oldest first
+    async fn pending_events(&self, home_id: &str) -> OvernightResult<Vec<OvernightEventAnalysis>>;
+
+    async fn mark_delivered(&self, home_id: &str, event_ids: &[Uuid], at: DateTime<Utc>) -> OvernightResult<()>;
 }
 
-pub struct InMemoryStorage;
+#[derive(Default)]
+pub struct InMemoryStorage {
+    events: RwLock<HashMap<String, Vec<OvernightEventAnalysis>>>,
+}
 
+#[async_trait]
 impl OvernightStorage for InMemoryStorage {
-    fn store(&self, _data: &str) -> Result<()> {
+    async fn store_event(&self, analysis: &OvernightEventAnalysis) -> OvernightResult<()> {
+        let mut events = self.events.write().await;
+        let home = events.entry(analysis.home_id.clone()).or_default();
+        home.retain(|e| e.event_id != analysis.event_id);
+        home.push(analysis.clone());
+        Ok(())
+    }
+
+    async fn pending_events(&self, home_id: &str) -> OvernightResult<Vec<OvernightEventAnalysis>> {
+        let mut pending = self.events.read().await.get(home_id).cloned().unwrap_or_default();
+        pending.sort_by_key(|e| e.timestamp);
+        Ok(pending)
+    }
+
+    async fn mark_delivered(&self, home_id: &str, event_ids: &[Uuid], _at: DateTime<Utc>) -> OvernightResult<()> {
+        if let Some(home) = self.events.write().await.get_mut(home_id) {
+            home.retain(|e| !event_ids.contains(&e.event_id));
+        }
+        Ok(())
+    }
+}
+
+// SQLite implementation backed by the `overnight_events` table. Delivered rows
+// are kept with their delivery time so a summary can be audited afterwards.
+pub struct SqliteOvernightStorage {
+    pool: SqlitePool,
+    schema: OnceCell<()>,
+}
+
+impl SqliteOvernightStorage {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool, schema: OnceCell::new() }
+    }
+
+    // Creates the table on first use if the migrations have not been run, and
+    // adds the camera and entity columns to tables created before they existed
+    async fn ensure_schema(&self) -> OvernightResult<()> {
+        self.schema.get_or_try_init(|| async {
+            sqlx::query(include_str!("../api/migrations/007_overnight_events.sql"))
+                .execute(&self.pool)
+                .await
+                .map_err(storage_error)?;
+            let columns: Vec<(String,)> = sqlx::query_as("SELECT name FROM pragma_table_info('overnight_events')")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(storage_error)?;
+            if !columns.iter().any(|(name,)| name == "entity_id") {
+                sqlx::query(include_str!("../api/migrations/009_overnight_entities.sql"))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(storage_error)?;
+            }
+            Ok::<(), anyhow::Error>(())
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> anyhow::Error {
+    OvernightError::Storage(e.to_string()).into()
+}
+
+#[async_trait]
+impl OvernightStorage for SqliteOvernightStorage {
+    async fn store_event(&self, analysis: &OvernightEventAnalysis) -> OvernightResult<()> {
+        self.ensure_schema().await?;
+        let level = analysis.suppressed_alert_level.as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(storage_error)?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO overnight_events (event_id, home_id, timestamp, analysis_summary, suppressed_alert_level, camera, entity_id, delivered_at) VALUES (?, ?, ?, ?, ?, ?, ?, NULL)",
+        )
+        .bind(analysis.event_id.to_string())
+        .bind(&analysis.home_id)
+        .bind(analysis.timestamp.timestamp())
+        .bind(&analysis.analysis_summary)
+        .bind(level)
+        .bind(&analysis.camera)
+        .bind(analysis.entity_id.map(|id| id.to_string()))
+        .execute(&self.pool)
+        .await
+        .map_err(storage_error)?;
+        Ok(())
+    }
+
+    async fn pending_events(&self, home_id: &str) -> OvernightResult<Vec<OvernightEventAnalysis>> {
+        self.ensure_schema().await?;
+        let rows: Vec<(String, String, i64, String, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT event_id, home_id, timestamp, analysis_summary, suppressed_alert_level, camera, entity_id FROM overnight_events WHERE home_id = ? AND delivered_at IS NULL ORDER BY timestamp",
+        )
+        .bind(home_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage_error)?;
+
+        rows.into_iter()
+            .map(|(event_id, home_id, timestamp, analysis_summary, level, camera, entity_id)| {
+                Ok(OvernightEventAnalysis {
+                    event_id: Uuid::parse_str(&event_id).map_err(storage_error)?,
+                    home_id,
+                    timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_default(),
+                    analysis_summary,
+                    suppressed_alert_level: level.as_deref().map(serde_json::from_str).transpose().map_err(storage_error)?,
+                    camera,
+                    entity_id: entity_id.as_deref().map(Uuid::parse_str).transpose().map_err(storage_error)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn mark_delivered(&self, home_id: &str, event_ids: &[Uuid], at: DateTime<Utc>) -> OvernightResult<()> {
+        self.ensure_schema().await?;
+        let mut tx = self.pool.begin().await.map_err(storage_error)?;
+        for event_id in event_ids {
+            sqlx::query("UPDATE overnight_events SET delivered_at = ? WHERE home_id = ? AND event_id = ?")
+                .bind(at.timestamp())
+                .bind(home_id)
+                .bind(event_id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(storage_error)?;
+        }
+        tx.commit().await.map_err(storage_error)?;
         Ok(())
     }
 }
@@ -17,6 +167,24 @@ pub struct OvernightStorageFactory;
 
 impl OvernightStorageFactory {
     pub fn create_in_memory() -> Arc<dyn OvernightStorage> {
-        Arc::new(InMemoryStorage)
+        Arc::new(InMemoryStorage::default())
+    }
+
+    /// Share an existing pool, e.g. the API database
+    pub fn create_sqlite(pool: SqlitePool) -> Arc<dyn OvernightStorage> {
+        Arc::new(SqliteOvernightStorage::new(pool))
+    }
+
+    /// Backend selected by the home's overnight configuration
+    pub async fn create(config: &OvernightConfig) -> OvernightResult<Arc<dyn OvernightStorage>> {
+        match &config.storage {
+            OvernightStorageBackend::Memory => Ok(Self::create_in_memory()),
+            OvernightStorageBackend::Sqlite { database_url } => {
+                let pool = SqlitePool::connect(database_url).await.map_err(storage_error)?;
+                let storage = SqliteOvernightStorage::new(pool);
+                storage.ensure_schema().await?;
+                Ok(Arc::new(storage))
+            }
+        }
     }
 }
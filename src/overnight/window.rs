@@ -0,0 +1,65 @@
+//! Review windows in the home's own timezone
+//!
+//! A window is a pair of local wall-clock times. Each night is resolved to a
+//! pair of UTC instants in the home's chrono-tz zone, so a window that crosses
+//! midnight or a DST change still has the right length and is never skipped.
+//! Local times that fall in a spring-forward gap move to just after the gap;
+//! times that occur twice in the autumn take the first occurrence.
+
+use super::{OvernightConfig, OvernightError};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReviewWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub tz: Tz,
+}
+
+impl ReviewWindow {
+    pub fn from_config(config: &OvernightConfig) -> Result<Self, OvernightError> {
+        let tz = config.timezone.parse::<Tz>()
+            .map_err(|_| OvernightError::Config(format!("unknown timezone '{}'", config.timezone)))?;
+        Ok(Self { start: config.review_start_time, end: config.review_end_time, tz })
+    }
+
+    /// UTC bounds of the night that starts on a local date
+    pub fn night_starting(&self, date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let end_date = if self.end <= self.start { date + Duration::days(1) } else { date };
+        (
+            resolve_local(self.tz, date.and_time(self.start)),
+            resolve_local(self.tz, end_date.and_time(self.end)),
+        )
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let today = at.with_timezone(&self.tz).date_naive();
+        [today - Duration::days(1), today].into_iter().any(|date| {
+            let (start, end) = self.night_starting(date);
+            start <= at && at < end
+        })
+    }
+}
+
+/// First occurrence of a local wall-clock time strictly after `after`
+pub fn next_local_time(tz: Tz, time: NaiveTime, after: DateTime<Utc>) -> DateTime<Utc> {
+    let today = after.with_timezone(&tz).date_naive();
+    (0..=2)
+        .map(|days| resolve_local(tz, (today + Duration::days(days)).and_time(time)))
+        .find(|candidate| *candidate > after)
+        .expect("a local time recurs within two days")
+}
+
+/// Map a local wall-clock time to an instant, handling DST gaps and overlaps
+pub fn resolve_local(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local).earliest() {
+        Some(resolved) => resolved.with_timezone(&Utc),
+        None => {
+            // Inside a gap: keep the offset in force before it, which lands the
+            // same distance past the gap as the wall clock jumped
+            let before = tz.offset_from_utc_datetime(&(local - Duration::hours(6))).fix();
+            Utc.from_utc_datetime(&(local - Duration::seconds(before.local_minus_utc() as i64)))
+        }
+    }
+}
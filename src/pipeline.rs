@@ -1,18 +1,83 @@
 // src/pipeline.rs
 
-use crate::vps_client::{VpsApiClient, VpsProcessingRequest};
-use crate::thinking::{ThinkingAIProcessor, ThinkingAIConfig, Event, Evidence, LLRExtractor, DemoLLRExtractor};
-use crate::overnight::{OvernightReviewManager, OvernightStorageFactory};
-use crate::image_preloader::{ImagePreloader, Priority, extract_image_url};
+use crate::vps_client::{VpsApiClient, VpsPool, VpsProcessingRequest, VpsProcessingResponse};
+use crate::thinking::{ThinkingAIProcessor, ThinkingAIConfig, ThinkingAIResult, AlertDecision, Event, Evidence, Incident, LLRExtractor, DemoLLRExtractor};
+use crate::thinking::{EvidenceBundle, BundleError, export_incident_bundle, MoClusterIndex};
+use crate::thinking::{IncidentLifecycleHook, IncidentStatus, IncidentTransition, LifecycleError};
+use crate::thinking::{IncidentLabel, WhatIfReport, compare_configs, ChannelWeights, OnlineWeightLearner, OutcomeSource};
+use crate::thinking::{SensorReliability, SensorReliabilityModel, EscalationSurvivalModel, PriorModelRegistry, sigmoid};
+use crate::thinking::{AdversarialAnalyzer, HandoffConfig, HandoffRequest};
+use crate::overnight::{OvernightEventAnalysis, OvernightReviewManager, OvernightStorage, OvernightStorageFactory, DeliveryChannel};
+use crate::household::HouseholdRegistry;
+use crate::tracker::EntityTracker;
+use crate::zone_graph::ZoneGraph;
+use crate::counter_surveillance::{CounterSurveillanceSystem, Sighting};
+use crate::knowledge_graph::KnowledgeGraphStore;
+use crate::activity_baseline::ActivityBaseline;
+use crate::entity_trust::{TrustError, TrustStore};
+use crate::annotations::{AnnotationError, AnnotationRequest, AnnotationStore, IncidentAnnotation, TagEffect};
+use crate::encryption::EncryptedStore;
+use crate::escalation_rules::EscalationEngine;
+use crate::tamper::{TamperCheck, TamperDetector, TamperError};
+use crate::anti_spoofing::{LivenessSource, SpoofDetector};
+use crate::snapshot_priority::SnapshotPriorityPolicy;
+use crate::probability_series::{PointSource, ProbabilityHistory, ProbabilityPoint};
+use crate::prediction::{PredictionComponent, PredictionScorer};
+use crate::automations::{AutomationEngine, AutomationExecutor, TriggerContext};
+use crate::core::{ThreatContext, ZoneClass};
+use crate::arming::ArmingRegistry;
+use crate::camera_registry::CameraRegistry;
+use crate::camera_health::{reported_fps, CameraHealthRegistry};
+use crate::adaptive_thresholds::{AdaptiveThresholds, ThresholdDrift, ThresholdVersion};
+use crate::backup::{BackupArchive, BackupBuilder, BackupError, BackupSection};
+use crate::backup::{CAMERA_PINS_PATH, CHANNEL_WEIGHTS_PATH, DEVICE_KEYS_PATH, RESIDENTS_PATH, SENSOR_RELIABILITY_PATH};
+use crate::device_signing::{DeviceKeyRegistry, EventSignature, SigningError};
+use crate::vacation::VacationRegistry;
+use crate::image_preloader::{ImagePreloader, ImageError, Priority, extract_image_url};
+use crate::image_transcode::{ImageTranscoder, Rendition};
+use crate::environment::EnvironmentEnricher;
+use crate::federation::{plate_from_payload, FederationError, FederationHub, TrustLevel};
+use crate::features::{Feature, FeatureGate, FeatureGateError};
+use crate::metering::{BillableUnit, UsageMeter};
+use crate::i18n::{localizer, t};
+use crate::delivery::{SiemExporter, SiemEvent, NotificationRouter, Notification, NotificationSeverity, CooldownVerdict};
+use crate::delivery::{AlertPhase, AlertRef, RouteDecision, WebhookEventType};
+use crate::delivery::email::render_alert;
+use crate::delivery::{AlarmContext, BookmarkContext, CentralStationExporter, VmsBookmarker};
+use crate::clock::{system_clock, Clock};
+use crate::embeddings::{EmbeddingStore, DetectionEmbeddings};
+use crate::visitor_tokens::{TokenEvidence, VisitorTokenStore, extract_access_code};
+use crate::guest_access::{CreatedGuest, GuestError, GuestMatchKind, GuestProfileRequest, GuestRegistry};
+use crate::self_test::{shadow_home, ChannelCheck, ScenarioCheck, SelfTestReport, SeverityRoute, StageProbe, SyntheticEvents, SyntheticScenario, CHECKED_SEVERITIES};
+use crate::follow_up::{FollowUpScheduler, FollowUpResolution};
+use crate::edge_inference::EdgeInferenceEngine;
+use crate::sanitization::{SanitizationConfig, SanitizationError, Sanitizer};
+use crate::redaction::FaceRegion;
+use crate::pipeline_stages::{PipelineRun, Stage, StageControl, StageMiddleware};
+use crate::load_shedding::{CameraPriority, HighActivityIncident, LoadShedder};
+#[cfg(feature = "chaos")]
+use crate::chaos::{ChaosInjector, ChaosStorage};
+use crate::ingest::EventPayload;
+use crate::idempotency::{IdempotencyGuard, IdempotencyConfig, IdempotencyKey, IdempotencyStore, Claim};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::RwLock;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use bytes::Bytes;
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
+
+/// Job id of events analysed locally because no VPS endpoint could be reached
+pub const LOCAL_FALLBACK_JOB_ID: &str = "local-fallback";
+
+/// One of an incident's snapshots with the camera it came from and the detector's faces in it
+#[derive(Debug, Clone)]
+pub struct IncidentSnapshot {
+    pub camera: String,
+    pub image: Bytes,
+    pub faces: Option<Vec<FaceRegion>>, // None when no face detector ran on it
+}
 
 // Represents the user's subscription tier
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -28,10 +93,14 @@ pub struct PipelineConfig {
     pub tier_routing: HashMap<SubscriptionTier, ProcessingLevel>,
     pub thinking_ai_config: ThinkingAIConfig,
     pub overnight_enabled: bool,
+    pub idempotency: IdempotencyConfig,
+    pub feature_gate: FeatureGate, // What each subscription tier is allowed to use
+    pub vps_enabled: bool,         // When false, events are analyzed by the on-device models
+    pub sanitization: SanitizationConfig, // NaN/Inf handling before fusion
 }
 
 // Processing level for an event
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProcessingLevel {
     Basic,    // Minimal processing
     Advanced, // Enhanced analysis
@@ -44,7 +113,7 @@ pub struct RawEvent {
     pub event_id: Uuid,
     pub sensor_id: String,
     pub timestamp: i64,
-    pub data: String, // e.g., base64 encoded image or sensor reading
+    pub data: EventPayload, // e.g., base64 encoded image or sensor reading; shares the request buffer
     pub user_id: String,
     pub home_id: String, // Added home_id for thinking AI
     pub image_url: Option<String>, // Direct image URL for faster processing
@@ -68,11 +137,58 @@ pub struct ProcessedEvent {
 // The main event pipeline
 pub struct EventPipeline {
     config: PipelineConfig,
-    vps_client: VpsApiClient,
+    vps_client: Arc<VpsPool>,
     thinking_ai: ThinkingAIProcessor,
     llr_extractor: DemoLLRExtractor,
     overnight_manager: Option<Arc<OvernightReviewManager>>, // NEW: Overnight review manager
     image_preloader: Arc<ImagePreloader>, // NEW: Image preloader for faster processing
+    idempotency: IdempotencyGuard, // Dedup of retried webhooks keyed by (home_id, event_id)
+    environment: Option<Arc<EnvironmentEnricher>>, // Weather/light enrichment for visual evidence
+    federation: Option<Arc<FederationHub>>, // Opt-in neighborhood threat sharing
+    siem: Option<Arc<SiemExporter>>, // Enterprise SIEM export of incident assessments
+    embeddings: Option<Arc<EmbeddingStore>>, // Appearance/gait re-identification across detections
+    metering: Option<Arc<UsageMeter>>, // Billable usage per account
+    mo_clusters: Option<Arc<MoClusterIndex>>, // Repeat-visitor clustering of past incidents
+    visitor_tokens: Option<Arc<VisitorTokenStore>>, // Homeowner-issued doorbell/keypad codes
+    guests: Option<Arc<GuestRegistry>>, // Time-boxed guest profiles: expected windows, enrolled faces and codes
+    notifications: Option<Arc<NotificationRouter>>, // Preferences and cooldowns before any send
+    weight_learner: Option<(Arc<OnlineWeightLearner>, Option<std::path::PathBuf>)>, // Learned LLR channel weights, with persistence path
+    follow_ups: Option<Arc<FollowUpScheduler>>, // Timers for incidents that scored Wait
+    sensor_reliability: Option<(Arc<SensorReliabilityModel>, Option<std::path::PathBuf>)>, // Learned per-sensor reliability, with persistence path
+    lifecycle_hooks: Vec<Arc<dyn IncidentLifecycleHook>>, // Told about every incident state change
+    edge: Option<Arc<EdgeInferenceEngine>>, // On-device models used when the VPS path is disabled
+    adversarial: Option<(Arc<dyn AdversarialAnalyzer>, HandoffConfig)>, // Second opinion for mid-band incidents
+    vacations: Option<Arc<VacationRegistry>>, // Homes in extended-away mode
+    household: Option<Arc<HouseholdRegistry>>, // Residents, their presence and alert thresholds
+    arming: Option<Arc<ArmingRegistry>>, // Per-home armed/disarmed state
+    cameras: Option<Arc<CameraRegistry>>, // Pinned snapshot hosts and certificates per camera
+    devices: Option<Arc<DeviceKeyRegistry>>, // Enrolled edge device keys for event signatures
+    escalation: Option<Arc<EscalationSurvivalModel>>, // Time-to-entry-attempt curves from labeled incidents
+    prior_model: Option<Arc<PriorModelRegistry>>, // User-edited base rates per situation
+    tracker: Option<Arc<EntityTracker>>, // Per-person Approaching/AtDoor/Loitering/... state and real dwell time
+    zone_graph: Option<Arc<ZoneGraph>>, // Learned zone-to-zone moves; rare paths add behavior evidence
+    counter_surveillance: Option<Arc<CounterSurveillanceSystem>>, // Trajectories per subject; casing adds behavior evidence
+    knowledge_graph: Option<Arc<KnowledgeGraphStore>>, // Per-home graph of people, zones and incidents
+    activity_baseline: Option<Arc<ActivityBaseline>>, // Usual events per zone and hour of the week; busy slots add behavior evidence
+    trust: Option<Arc<TrustStore>>, // Decaying trust per re-identified person; trusted people add negative identity evidence
+    annotations: Option<Arc<AnnotationStore>>, // Household notes and tags per incident, and the watchlist they build
+    encryption: Option<Arc<EncryptedStore>>, // Sealed on-disk snapshots and per-home keys for encrypted backups
+    escalation_rules: Option<Arc<EscalationEngine>>, // Rules above the fused decision and per-home escalation chains
+    tamper: Option<Arc<TamperDetector>>, // Per-camera scene references for blinding, covering and moved cameras
+    anti_spoofing: Option<Arc<SpoofDetector>>, // Doubts face matches whose liveness score suggests a photo or screen
+    snapshot_priority: Option<Arc<SnapshotPriorityPolicy>>, // Fetch order for snapshots from incident probability and zone
+    probability_history: Option<Arc<ProbabilityHistory>>, // Durable per-incident probability series for charting
+    prediction_scoring: Option<Arc<PredictionScorer>>, // Quoted probabilities matched against outcomes for the model report card
+    automations: Option<(Arc<AutomationEngine>, Arc<dyn AutomationExecutor>)>, // Household device automations on alert decisions
+    vms: Option<Arc<VmsBookmarker>>, // Bookmarks alerts on the home's NVR or VMS recordings
+    central_station: Option<Arc<CentralStationExporter>>, // Reports alarms to professional monitoring over SIA DC-09
+    clock: Arc<dyn Clock>, // Source of "now" for every stage; simulated in tests and replays
+    camera_health: Option<Arc<CameraHealthRegistry>>, // Registered cameras' last-seen times and frame rates
+    thresholds: Option<Arc<AdaptiveThresholds>>, // Versioned per-home alert thresholds with learned modifiers
+    middleware: Vec<Arc<dyn StageMiddleware>>, // Hooks around each processing stage, outermost first
+    load_shedding: Option<Arc<LoadShedder>>, // Samples low-priority cameras when a home's event rate spikes
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<ChaosInjector>>,
 }
 
 impl EventPipeline {
@@ -80,23 +196,70 @@ impl EventPipeline {
         let thinking_ai = ThinkingAIProcessor::new(config.thinking_ai_config.clone());
         let llr_extractor = DemoLLRExtractor::default();
         let image_preloader = Arc::new(ImagePreloader::new());
+        let idempotency = IdempotencyGuard::new(config.idempotency.clone());
         
         // Initialize overnight system if enabled
         let overnight_manager = if config.overnight_enabled {
             let storage = OvernightStorageFactory::create_in_memory();
-            let thinking_ai_arc = Arc::new(RwLock::new(thinking_ai.clone()));
-            Some(Arc::new(OvernightReviewManager::new(storage, thinking_ai_arc)))
+            Some(Arc::new(OvernightReviewManager::new(storage, system_clock())))
         } else {
             None
         };
 
         EventPipeline {
             config,
-            vps_client,
+            vps_client: Arc::new(VpsPool::single(vps_client)),
             thinking_ai,
             llr_extractor,
             overnight_manager,
             image_preloader,
+            idempotency,
+            environment: None,
+            federation: None,
+            siem: None,
+            embeddings: None,
+            metering: None,
+            mo_clusters: None,
+            visitor_tokens: None,
+            guests: None,
+            notifications: None,
+            weight_learner: None,
+            follow_ups: None,
+            sensor_reliability: None,
+            lifecycle_hooks: Vec::new(),
+            edge: None,
+            adversarial: None,
+            vacations: None,
+            household: None,
+            arming: None,
+            cameras: None,
+            devices: None,
+            escalation: None,
+            prior_model: None,
+            tracker: None,
+            zone_graph: None,
+            counter_surveillance: None,
+            knowledge_graph: None,
+            activity_baseline: None,
+            trust: None,
+            annotations: None,
+            encryption: None,
+            escalation_rules: None,
+            tamper: None,
+            anti_spoofing: None,
+            snapshot_priority: None,
+            probability_history: None,
+            prediction_scoring: None,
+            automations: None,
+            vms: None,
+            central_station: None,
+            clock: system_clock(),
+            camera_health: None,
+            thresholds: None,
+            middleware: Vec::new(),
+            load_shedding: None,
+            #[cfg(feature = "chaos")]
+            chaos: None,
         }
     }
 
@@ -109,17 +272,408 @@ impl EventPipeline {
         let thinking_ai = ThinkingAIProcessor::new(config.thinking_ai_config.clone());
         let llr_extractor = DemoLLRExtractor::default();
         let image_preloader = Arc::new(ImagePreloader::new());
+        let idempotency = IdempotencyGuard::new(config.idempotency.clone());
 
         EventPipeline {
             config,
-            vps_client,
+            vps_client: Arc::new(VpsPool::single(vps_client)),
             thinking_ai,
             llr_extractor,
             overnight_manager: Some(overnight_manager),
             image_preloader,
+            idempotency,
+            environment: None,
+            federation: None,
+            siem: None,
+            embeddings: None,
+            metering: None,
+            mo_clusters: None,
+            visitor_tokens: None,
+            guests: None,
+            notifications: None,
+            weight_learner: None,
+            follow_ups: None,
+            sensor_reliability: None,
+            lifecycle_hooks: Vec::new(),
+            edge: None,
+            adversarial: None,
+            vacations: None,
+            household: None,
+            arming: None,
+            cameras: None,
+            devices: None,
+            escalation: None,
+            prior_model: None,
+            tracker: None,
+            zone_graph: None,
+            counter_surveillance: None,
+            knowledge_graph: None,
+            activity_baseline: None,
+            trust: None,
+            annotations: None,
+            encryption: None,
+            escalation_rules: None,
+            tamper: None,
+            anti_spoofing: None,
+            snapshot_priority: None,
+            probability_history: None,
+            prediction_scoring: None,
+            automations: None,
+            vms: None,
+            central_station: None,
+            clock: system_clock(),
+            camera_health: None,
+            thresholds: None,
+            middleware: Vec::new(),
+            load_shedding: None,
+            #[cfg(feature = "chaos")]
+            chaos: None,
+        }
+    }
+
+    // Route VPS traffic across several endpoints instead of the single client
+    pub fn with_vps_pool(mut self, pool: VpsPool) -> Self {
+        self.vps_client = Arc::new(pool);
+        self
+    }
+
+    // Scale visual evidence by local weather and light before fusion
+    pub fn with_environment_enricher(mut self, enricher: Arc<EnvironmentEnricher>) -> Self {
+        self.environment = Some(enricher);
+        self
+    }
+
+    // Raise priors when nearby opted-in homes report the same signatures
+    pub fn with_federation(mut self, hub: Arc<FederationHub>) -> Self {
+        self.federation = Some(hub);
+        self
+    }
+
+    // Forward incident assessments to an enterprise SIEM
+    pub fn with_siem_exporter(mut self, exporter: Arc<SiemExporter>) -> Self {
+        self.siem = Some(exporter);
+        self
+    }
+
+    // Link detections to known entities by appearance/gait similarity
+    pub fn with_embedding_store(mut self, store: Arc<EmbeddingStore>) -> Self {
+        self.embeddings = Some(store);
+        self
+    }
+
+    // Generate per-channel thumbnails and WebP/AVIF renditions of snapshots
+    pub fn with_image_transcoder(self, transcoder: Arc<ImageTranscoder>) -> Self {
+        self.image_preloader.set_transcoder(transcoder);
+        self
+    }
+
+    // Only trust snapshot URLs on each camera's pinned hosts
+    pub fn with_camera_registry(mut self, cameras: Arc<CameraRegistry>) -> Self {
+        self.cameras = Some(cameras);
+        self
+    }
+
+    // Verify edge device signatures and apply the unsigned-event policy
+    pub fn with_device_signing(mut self, devices: Arc<DeviceKeyRegistry>) -> Self {
+        self.devices = Some(devices);
+        self
+    }
+
+    // Quote the chance of an entry attempt in Critical alerts, learned from labeled incidents
+    pub fn with_escalation_survival(mut self, model: Arc<EscalationSurvivalModel>) -> Self {
+        self.escalation = Some(model);
+        self
+    }
+
+    // Score incidents from user-edited base rates and feed them labeled outcomes
+    pub fn with_prior_model(mut self, registry: Arc<PriorModelRegistry>) -> Self {
+        self.thinking_ai.set_prior_model(registry.clone());
+        self.prior_model = Some(registry);
+        self
+    }
+
+    // Follow each person through Approaching/AtDoor/Loitering/Departing for dwell evidence and notification text
+    pub fn with_entity_tracker(mut self, tracker: Arc<EntityTracker>) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
+    // Score each person's moves between zones against the home's learned paths
+    pub fn with_zone_graph(mut self, graph: Arc<ZoneGraph>) -> Self {
+        self.zone_graph = Some(graph);
+        self
+    }
+
+    // Record each assessed incident in the home's knowledge graph
+    pub fn with_knowledge_graph(mut self, store: Arc<KnowledgeGraphStore>) -> Self {
+        self.knowledge_graph = Some(store);
+        self
+    }
+
+    // Score positioned sightings for loitering across the street, repeated slow passes and camera gaze
+    pub fn with_counter_surveillance(mut self, system: Arc<CounterSurveillanceSystem>) -> Self {
+        self.counter_surveillance = Some(system);
+        self
+    }
+
+    // Compare each zone's activity with its usual level for the hour of the week
+    pub fn with_activity_baseline(mut self, baseline: Arc<ActivityBaseline>) -> Self {
+        self.activity_baseline = Some(baseline);
+        self
+    }
+
+    // Keep snapshots on disk sealed with per-home keys, and encrypt incident stores in backups
+    pub fn with_encryption(mut self, store: Arc<EncryptedStore>) -> Self {
+        self.encryption = Some(store);
+        self
+    }
+
+    // Turn verified benign visits into trust, and trust into identity evidence
+    pub fn with_entity_trust(mut self, trust: Arc<TrustStore>) -> Self {
+        self.trust = Some(trust);
+        self
+    }
+
+    // Household notes on incidents: quoted in later narratives about the same person, tags feed trust and the watchlist
+    pub fn with_annotations(mut self, annotations: Arc<AnnotationStore>) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    // Let rules such as interior-while-away override the decision and start escalation chains
+    pub fn with_escalation_rules(mut self, engine: Arc<EscalationEngine>) -> Self {
+        self.escalation_rules = Some(engine);
+        self
+    }
+
+    // Compare event snapshots with each camera's reference view and discount tampered cameras
+    pub fn with_tamper_detection(mut self, detector: Arc<TamperDetector>) -> Self {
+        self.tamper = Some(detector);
+        self
+    }
+
+    // Fetch snapshots of likely threats and night-time back-of-house cameras ahead of the rest
+    pub fn with_snapshot_priority(mut self, policy: Arc<SnapshotPriorityPolicy>) -> Self {
+        self.snapshot_priority = Some(policy);
+        self
+    }
+
+    // Keep every assessment's probability after the incident itself has expired
+    pub fn with_probability_history(mut self, history: Arc<ProbabilityHistory>) -> Self {
+        self.probability_history = Some(history);
+        self
+    }
+
+    // Record the prior, fused and escalation probabilities so they can be scored once outcomes are known
+    pub fn with_prediction_scoring(mut self, scorer: Arc<PredictionScorer>) -> Self {
+        self.prediction_scoring = Some(scorer);
+        self
+    }
+
+    // Fire household automations on final decisions; `executor` carries their device commands
+    pub fn with_automations(mut self, engine: Arc<AutomationEngine>, executor: Arc<dyn AutomationExecutor>) -> Self {
+        self.automations = Some((engine, executor));
+        self
+    }
+
+    // Bookmark alerting cameras on the home's NVRs and VMSs so the footage is easy to find
+    pub fn with_vms_bookmarks(mut self, vms: Arc<VmsBookmarker>) -> Self {
+        self.vms = Some(vms);
+        self
+    }
+
+    // Report alarms to the home's central station as Contact ID events; add it as a lifecycle hook too for restores
+    pub fn with_central_station(mut self, exporter: Arc<CentralStationExporter>) -> Self {
+        self.central_station = Some(exporter);
+        self
+    }
+
+    // Liveness scores from the VPS or a local model scale back benign face-match evidence
+    pub fn with_anti_spoofing(mut self, detector: Arc<SpoofDetector>) -> Self {
+        self.anti_spoofing = Some(detector);
+        self
+    }
+
+    // Track registered cameras' liveness; silent, recovering and frame-dropping cameras count for less
+    pub fn with_camera_health(mut self, registry: Arc<CameraHealthRegistry>) -> Self {
+        self.camera_health = Some(registry);
+        self
+    }
+
+    // Per-home thresholds that learn from labels and survive restarts; stored thresholds take effect now
+    pub fn with_adaptive_thresholds(mut self, thresholds: Arc<AdaptiveThresholds>) -> Self {
+        for (home, logit) in thresholds.effective() {
+            self.thinking_ai.set_alert_threshold(&home, logit);
+        }
+        self.thresholds = Some(thresholds);
+        self
+    }
+
+    // Wrap every stage in a middleware; the first added sees each stage first
+    pub fn with_middleware(mut self, middleware: Arc<dyn StageMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    // Shed low-priority events under load; runs ahead of every other middleware
+    pub fn with_load_shedding(mut self, shedder: Arc<LoadShedder>) -> Self {
+        self.middleware.insert(0, shedder.clone());
+        self.load_shedding = Some(shedder);
+        self
+    }
+
+    // Count billable units (events, VPS calls, image bytes) per account
+    pub fn with_usage_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.metering = Some(meter);
+        self
+    }
+
+    // Record incidents for modus operandi clustering and boost priors on matches
+    pub fn with_mo_clustering(mut self, index: Arc<MoClusterIndex>) -> Self {
+        self.mo_clusters = Some(index);
+        self
+    }
+
+    // Verify doorbell/keypad access codes and treat verified visitors as known
+    pub fn with_visitor_tokens(mut self, store: Arc<VisitorTokenStore>) -> Self {
+        self.visitor_tokens = Some(store);
+        self
+    }
+
+    // Guests seen inside their expected window count as benign; outside it they are flagged
+    pub fn with_guest_access(mut self, guests: Arc<GuestRegistry>) -> Self {
+        self.guests = Some(guests);
+        self
+    }
+
+    // Route alert notifications through quiet hours, preferences and cooldowns
+    pub fn with_notification_router(mut self, router: Arc<NotificationRouter>) -> Self {
+        self.notifications = Some(router);
+        self
+    }
+
+    // Scale LLR channels by weights learned from feedback; saved to `persist_to` after each update
+    pub fn with_weight_learner(mut self, learner: Arc<OnlineWeightLearner>, persist_to: Option<std::path::PathBuf>) -> Self {
+        self.weight_learner = Some((learner, persist_to));
+        self
+    }
+
+    // Discount evidence from sensors that often disagree with final outcomes
+    pub fn with_sensor_reliability(mut self, model: Arc<SensorReliabilityModel>, persist_to: Option<std::path::PathBuf>) -> Self {
+        self.sensor_reliability = Some((model, persist_to));
+        self
+    }
+
+    // Re-evaluate Wait decisions later instead of dropping them
+    pub fn with_follow_up_scheduler(mut self, scheduler: Arc<FollowUpScheduler>) -> Self {
+        self.follow_ups = Some(scheduler);
+        self
+    }
+
+    // Run detection and face embedding locally instead of on the VPS
+    pub fn with_edge_inference(mut self, engine: Arc<EdgeInferenceEngine>) -> Self {
+        self.edge = Some(engine);
+        self
+    }
+
+    // Inject VPS, snapshot and storage faults; set before with_overnight_storage
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<ChaosInjector>) -> Self {
+        self.vps_client.install_chaos(chaos.clone());
+        self.chaos = Some(chaos);
+        self
+    }
+
+    #[cfg(feature = "chaos")]
+    pub fn chaos(&self) -> Option<&Arc<ChaosInjector>> {
+        self.chaos.as_ref()
+    }
+
+    // Keep overnight events in durable storage instead of memory
+    pub fn with_overnight_storage(mut self, storage: Arc<dyn OvernightStorage>) -> Self {
+        #[cfg(feature = "chaos")]
+        let storage: Arc<dyn OvernightStorage> = match &self.chaos {
+            Some(chaos) => Arc::new(ChaosStorage::new(storage, chaos.clone())),
+            None => storage,
+        };
+        if self.overnight_manager.is_some() {
+            self.overnight_manager = Some(Arc::new(OvernightReviewManager::new(storage, self.clock.clone())));
+        }
+        self
+    }
+
+    // Read the time from `clock` everywhere: pipeline stages, incident expiry and the overnight manager
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.thinking_ai.set_clock(clock.clone());
+        // The overnight manager takes its clock at construction; rebuild it over the same storage
+        self.overnight_manager = self.overnight_manager.take()
+            .map(|manager| Arc::new(OvernightReviewManager::new(manager.storage().clone(), clock.clone())));
+        self.clock = clock;
+        self
+    }
+
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    // Heightened profile for homes on vacation
+    pub fn with_vacation_mode(mut self, vacations: Arc<VacationRegistry>) -> Self {
+        self.vacations = Some(vacations);
+        self
+    }
+
+    // Fan alerts out to residents and take away probability from their presence
+    pub fn with_household(mut self, household: Arc<HouseholdRegistry>) -> Self {
+        self.household = Some(household);
+        self
+    }
+
+    // Silence alerts for disarmed homes and take occupancy from the arming mode
+    pub fn with_arming(mut self, arming: Arc<ArmingRegistry>) -> Self {
+        self.arming = Some(arming);
+        self
+    }
+
+    // Forward incidents in the probability band for adversarial analysis
+    pub fn with_adversarial_handoff(mut self, analyzer: Arc<dyn AdversarialAnalyzer>, config: HandoffConfig) -> Self {
+        self.adversarial = Some((analyzer, config));
+        self
+    }
+
+    // Publish incident state changes (webhooks, WebSocket clients, callbacks)
+    pub fn with_lifecycle_hook(mut self, hook: Arc<dyn IncidentLifecycleHook>) -> Self {
+        self.lifecycle_hooks.push(hook);
+        self
+    }
+
+    fn meter(&self, account_id: &str, unit: BillableUnit, amount: u64) {
+        if let Some(meter) = &self.metering {
+            meter.record(account_id, unit, amount);
         }
     }
 
+    // Attach a durable dedup store so retries are still detected after a restart
+    pub fn with_idempotency_store(mut self, store: Arc<dyn IdempotencyStore>) -> Self {
+        self.idempotency.set_store(store);
+        self
+    }
+
+    /// Drop durable dedup records older than the dedup window
+    pub async fn purge_dedup_records(&self) -> Result<u64, PipelineError> {
+        self.idempotency.purge_expired().await.map_err(|e| PipelineError::IdempotencyError(e.to_string()))
+    }
+
+    /// Fail with a clear error when the tier doesn't include a feature
+    pub fn require_feature(&self, tier: &SubscriptionTier, feature: Feature) -> Result<(), PipelineError> {
+        self.config.feature_gate.require(tier, feature)
+            .map_err(PipelineError::FeatureGated)
+    }
+
+    /// How long data is retained for a tier
+    pub fn retention_for(&self, tier: &SubscriptionTier) -> chrono::Duration {
+        self.config.feature_gate.retention(tier)
+    }
+
     // Determines processing level based on subscription tier
     fn get_processing_level(&self, tier: &SubscriptionTier) -> ProcessingLevel {
         *self.config.tier_routing.get(tier).unwrap_or(&ProcessingLevel::Basic)
@@ -133,7 +687,7 @@ impl EventPipeline {
         Event {
             ts: raw_event.timestamp as f64,
             cam: raw_event.sensor_id.clone(),
-            person_track: format!("track_{}", raw_event.event_id.to_string()[..8].to_string()),
+            person_track: format!("track_{}", &raw_event.event_id.simple().encode_lower(&mut Uuid::encode_buffer())[..8]),
             rang_doorbell: false, // TODO: Extract from sensor data
             knocked: false,       // TODO: Extract from sensor data
             dwell_s: 15.0,       // TODO: Extract from sensor data
@@ -150,12 +704,9 @@ impl EventPipeline {
         
         // Step 1: Start image download immediately if URL present
         let image_download_task = if raw_event.image_data.is_none() {
-            if let Some(image_url) = raw_event.image_url.as_ref().or_else(|| extract_image_url(&raw_event.data)) {
+            if let Some(image_url) = self.snapshot_url(&raw_event) {
                 info!("Starting async image download for: {}", image_url);
-                Some(self.image_preloader.download_image_sync(
-                    image_url.clone(), 
-                    raw_event.event_id
-                ))
+                Some(self.fetch_snapshot(&raw_event, image_url))
             } else {
                 None
             }
@@ -172,6 +723,7 @@ impl EventPipeline {
             match download_task.await {
                 Ok(image_data) => {
                     info!("Image downloaded successfully ({} bytes)", image_data.len());
+                    self.meter(&raw_event.user_id, BillableUnit::ImageBytes, image_data.len() as u64);
                     raw_event.image_data = Some(image_data);
                 }
                 Err(e) => {
@@ -189,6 +741,41 @@ impl EventPipeline {
         self.image_preloader.preload_image(url, event_id, Priority::Normal);
     }
 
+    /// Per-home calendars the thinking prior reads, to share with other time-aware scorers
+    pub fn home_calendars(&self) -> Arc<crate::environment::HomeCalendars> {
+        self.thinking_ai.calendars()
+    }
+
+    /// Start background health polling of every VPS endpoint so outages open circuits early
+    pub fn start_vps_health_pollers(&self) -> Vec<tokio::task::JoinHandle<()>> {
+        self.vps_client.spawn_health_pollers()
+    }
+
+    /// Get per-endpoint VPS health and circuit breaker metrics
+    pub fn get_vps_endpoint_status(&self) -> Vec<crate::vps_client::VpsEndpointStatus> {
+        self.vps_client.endpoint_status()
+    }
+
+    /// Hit/miss counts of the VPS response cache
+    pub fn get_vps_cache_stats(&self) -> crate::vps_client::VpsCacheStats {
+        self.vps_client.cache_stats()
+    }
+
+    /// How often each evidence channel was fused at a cap; chronic saturation points at a miscalibrated extractor
+    pub fn get_evidence_saturation(&self) -> Vec<crate::thinking::ChannelSaturation> {
+        self.thinking_ai.saturation_report()
+    }
+
+    /// Snapshot sized and encoded for a delivery channel (push thumbnail, dashboard preview, ...)
+    pub async fn get_image_rendition(
+        &self,
+        url: String,
+        event_id: Uuid,
+        channel: DeliveryChannel,
+    ) -> Result<Rendition, ImageError> {
+        self.image_preloader.get_rendition(url, event_id, channel).await
+    }
+
     /// Get image preloader statistics
     pub async fn get_image_cache_stats(&self) -> crate::image_preloader::CacheStats {
         self.image_preloader.get_cache_stats().await
@@ -205,6 +792,7 @@ impl EventPipeline {
             llr_identity: 0.2,
             llr_presence: 0.2,
             llr_token: 0.0,
+            ..Default::default()
         }
     }
 
@@ -221,6 +809,7 @@ impl EventPipeline {
     ) -> Result<ProcessedEvent, PipelineError> {
         // Create VPS processing request with image data
         let vps_request = VpsProcessingRequest {
+            api_key: String::new(),
             event_id: raw_event.event_id.to_string(),
             sensor_data: raw_event.data.clone(),
             image_data: raw_event.image_data.clone(),
@@ -228,159 +817,1865 @@ impl EventPipeline {
             user_context: format!("user:{}, home:{}", raw_event.user_id, raw_event.home_id),
         };
 
-        // Send to VPS for processing
-        let vps_response = self.vps_client.process_event(vps_request).await
-            .map_err(|e| PipelineError::VpsError(format!("VPS processing failed: {}", e)))?;
+        // Send to VPS for processing, falling back to local-only analysis when the circuit is open
+        let vps_result = self.vps_client.process_event(&raw_event.home_id, vps_request).await;
+        let reached_vps = match &vps_result {
+            Ok(response) => !response.from_cache,
+            Err(e) => !VpsApiClient::is_circuit_open_error(e.as_ref()),
+        };
+        if reached_vps {
+            self.meter(&raw_event.user_id, BillableUnit::VpsCalls, 1);
+        }
+        let (vps_job_id, result_summary) = match vps_result {
+            Ok(vps_response) => (vps_response.job_id, vps_response.summary),
+            Err(e) if VpsApiClient::is_circuit_open_error(e.as_ref()) => {
+                warn!("VPS unavailable, processing event {} locally", raw_event.event_id);
+                (LOCAL_FALLBACK_JOB_ID.to_string(), "Processed locally (VPS unavailable)".to_string())
+            }
+            Err(e) => return Err(PipelineError::VpsError(format!("VPS processing failed: {}", e))),
+        };
 
-        // Create thinking AI event
-        let thinking_event = self.create_thinking_event(&raw_event);
-        
-        // Process with thinking AI
-        let thinking_result = self.thinking_ai.process_event(
-            &raw_event.home_id,
-            thinking_event
-        ).await;
-
-        let thinking_analysis = match thinking_result {
-            Ok(analysis) => Some(analysis),
-            Err(e) => {
-                warn!("Thinking AI processing failed: {}", e);
-                None
+        // Process with thinking AI when the plan includes it
+        let thinking_analysis = if self.config.feature_gate.allows(&tier, Feature::ThinkingAI) {
+            let thinking_event = self.create_thinking_event(&raw_event);
+            let thinking_result = self.thinking_ai.process_event(
+                &raw_event.home_id,
+                thinking_event
+            ).await;
+
+            match thinking_result {
+                Ok(analysis) => Some(analysis),
+                Err(e) => {
+                    warn!("Thinking AI processing failed: {}", e);
+                    None
+                }
             }
+        } else {
+            None
         };
 
         Ok(ProcessedEvent {
             original_event_id: raw_event.event_id,
-            processing_timestamp: chrono::Utc::now().timestamp(),
+            processing_timestamp: self.clock.now().timestamp(),
             tier,
             processing_level: format!("{:?}", processing_level),
-            vps_job_id: vps_response.job_id,
+            vps_job_id,
             status: "completed".to_string(),
-            result_summary: vps_response.summary,
+            result_summary,
             thinking_ai_analysis: thinking_analysis,
             overnight_suppressed: false,
         })
     }
 
-    // UPDATED: Main event processing method with overnight integration
+    // Main entry point: dedups retried webhooks before running the pipeline
     pub async fn process_event(&mut self, event: RawEvent, tier: SubscriptionTier, api_key: &str) -> Result<ProcessedEvent, PipelineError> {
-        // Check if event is during overnight review period
-        if let Some(overnight_mgr) = &self.overnight_manager {
-            let event_time = DateTime::from_timestamp(event.timestamp, 0).unwrap_or_else(|| Utc::now());
-            
-            if overnight_mgr.is_in_review_period(&event.home_id, event_time).await
-                .map_err(|e| PipelineError::OvernightError(e.to_string()))? 
-            {
-                // Process for overnight review (analyze but don't alert)
-                let analysis = overnight_mgr.process_for_overnight_review(&event).await
-                    .map_err(|e| PipelineError::OvernightError(e.to_string()))?;
-                
-                // Store for morning summary
-                overnight_mgr.store_overnight_event(analysis).await
-                    .map_err(|e| PipelineError::OvernightError(e.to_string()))?;
-
-                // Return suppressed event response
-                return Ok(ProcessedEvent {
-                    original_event_id: event.event_id,
-                    processing_timestamp: Utc::now().timestamp(),
-                    tier,
-                    processing_level: "overnight_suppressed".to_string(),
-                    vps_job_id: "overnight".to_string(),
-                    status: "suppressed_for_overnight_review".to_string(),
-                    result_summary: "Event processed and stored for morning review".to_string(),
-                    thinking_ai_analysis: None,
-                    overnight_suppressed: true,
-                });
-            }
-        }
-
-        // Continue with normal pipeline processing if not in overnight period
-        let processing_level = self.get_processing_level(&tier);
+        self.process_signed_event(event, None, tier, api_key).await
+    }
 
-        // Process with VPS API
-        let request = VpsProcessingRequest {
-            api_key,
-            event_id: &event.event_id.to_string(),
-            sensor_data: &event.data,
-            processing_level: &format!("{:?}", processing_level).to_lowercase(),
+    // Entry point for edge devices that sign their events. Verification runs
+    // before the idempotency claim so a forged event cannot take a real
+    // event's id; without a device registry signatures are ignored
+    pub async fn process_signed_event(&mut self, event: RawEvent, signature: Option<&EventSignature>, tier: SubscriptionTier, api_key: &str) -> Result<ProcessedEvent, PipelineError> {
+        let evidence_weight = match &self.devices {
+            Some(devices) => {
+                let trust = devices.verify(&event, signature, self.clock.now()).map_err(|e| {
+                    warn!("Rejected event {} for home {}: {}", event.event_id, event.home_id, e);
+                    PipelineError::SignatureError(e)
+                })?;
+                trust.evidence_weight()
+            }
+            None => 1.0,
         };
 
-        let vps_response = self.vps_client.submit_event_for_processing(&request).await
-            .map_err(|e| PipelineError::VpsSubmissionError(format!("{}", e).into()))?;
+        let dedup_key = IdempotencyKey::new(&event.home_id, event.event_id);
+        let account_id = event.user_id.clone();
 
-        // Process with Thinking AI for Premium tier
-        let thinking_ai_analysis = if matches!(tier, SubscriptionTier::Premium) {
-            let thinking_event = self.create_thinking_event(&event);
-            
-            if let Some(result) = self.thinking_ai.process_event(&event.home_id, thinking_event) {
-                Some(self.thinking_ai.format_thinking_block(&result))
-            } else {
-                None
+        match self.idempotency.claim(&dedup_key).await
+            .map_err(|e| PipelineError::IdempotencyError(e.to_string()))?
+        {
+            Claim::Acquired => {}
+            Claim::InFlight => {
+                info!("Event {} for home {} is already being processed", dedup_key.event_id, dedup_key.home_id);
+                return Err(PipelineError::DuplicateInFlight(dedup_key.event_id));
             }
-        } else {
-            None
-        };
+            Claim::Completed(previous) => {
+                info!("Event {} for home {} already processed, replaying stored result", dedup_key.event_id, dedup_key.home_id);
+                return serde_json::from_str(&previous)
+                    .map_err(|e| PipelineError::IdempotencyError(format!("Corrupt stored result: {}", e)));
+            }
+        }
 
-        let mut result_summary = "Processing initiated with VPS".to_string();
-        if thinking_ai_analysis.is_some() {
-            result_summary.push_str(" + ThinkingAI analysis");
+        let result = self.process_event_once(event, tier, api_key, evidence_weight).await;
+
+        match &result {
+            Ok(processed) => {
+                let stored = serde_json::to_string(processed)
+                    .map_err(|e| PipelineError::IdempotencyError(e.to_string()))?;
+                if let Err(e) = self.idempotency.complete(&dedup_key, stored).await {
+                    warn!("Failed to record processed event {}: {}", dedup_key.event_id, e);
+                }
+                // Replays of retried webhooks return above and are not billed twice
+                self.meter(&account_id, BillableUnit::EventsProcessed, 1);
+            }
+            Err(_) => self.idempotency.release(&dedup_key).await,
         }
 
-        Ok(ProcessedEvent {
-            original_event_id: event.event_id,
-            processing_timestamp: Utc::now().timestamp(),
-            tier,
-            processing_level: request.processing_level.to_string(),
-            vps_job_id: vps_response.job_id,
-            status: vps_response.status,
-            result_summary,
-            thinking_ai_analysis,
-            overnight_suppressed: false,
-        })
+        result
     }
 
-    // NEW: Generate morning summary for a home
-    pub async fn generate_morning_summary(&self, home_id: &str) -> Result<Option<crate::overnight::MorningSummary>, PipelineError> {
-        if let Some(overnight_mgr) = &self.overnight_manager {
-            overnight_mgr.generate_morning_summary(home_id).await
-                .map(Some)
-                .map_err(|e| PipelineError::OvernightError(e.to_string()))
-        } else {
-            Ok(None)
+    // Run the event through the stage chain, with middleware around each stage
+    async fn process_event_once(&mut self, event: RawEvent, tier: SubscriptionTier, api_key: &str, evidence_weight: f64) -> Result<ProcessedEvent, PipelineError> {
+        let processing_level = self.get_processing_level(&tier);
+        let mut run = PipelineRun::new(event, tier, api_key, processing_level, evidence_weight);
+        let middleware = self.middleware.clone();
+
+        for stage in Stage::ALL {
+            let mut control = StageControl::Continue;
+            for layer in &middleware {
+                control = layer.before(stage, &mut run).await;
+                match &control {
+                    StageControl::Continue => continue,
+                    StageControl::Skip => info!("Middleware {} skipped the {:?} stage for event {}", layer.name(), stage, run.event.event_id),
+                    StageControl::Halt(reason) => {
+                        info!("Middleware {} halted event {} before the {:?} stage: {}", layer.name(), run.event.event_id, stage, reason);
+                        return Ok(self.halted_event(&run, layer.name(), stage, reason));
+                    }
+                }
+                break;
+            }
+            if control == StageControl::Continue {
+                self.run_stage(stage, &mut run).await?;
+                for layer in middleware.iter().rev() {
+                    layer.after(stage, &mut run).await;
+                }
+            }
+            if let Some(finished) = run.finished.take() {
+                return Ok(finished);
+            }
         }
+
+        Ok(self.processed_event(run))
     }
 
-    // NEW: Update overnight configuration for a home
-    pub async fn update_overnight_config(&self, config: crate::overnight::OvernightConfig) -> Result<(), PipelineError> {
-        if let Some(overnight_mgr) = &self.overnight_manager {
-            overnight_mgr.update_config(config).await
-                .map_err(|e| PipelineError::OvernightError(e.to_string()))
-        } else {
-            Err(PipelineError::OvernightError("Overnight system not enabled".to_string()))
+    async fn run_stage(&mut self, stage: Stage, run: &mut PipelineRun) -> Result<(), PipelineError> {
+        match stage {
+            Stage::Sanitize => self.sanitize_stage(run),
+            Stage::Enrich => {
+                self.enrich_stage(run).await;
+                Ok(())
+            }
+            Stage::Image => {
+                self.image_stage(run);
+                Ok(())
+            }
+            Stage::Vps => self.vps_stage(run).await,
+            Stage::Thinking => self.thinking_stage(run).await,
+            Stage::Rules => {
+                self.rules_stage(run).await;
+                Ok(())
+            }
+            Stage::Notify => {
+                self.notify_stage(run).await;
+                Ok(())
+            }
         }
     }
 
-    // NEW: Get overnight configuration for a home
-    pub async fn get_overnight_config(&self, home_id: &str) -> Option<crate::overnight::OvernightConfig> {
-        if let Some(overnight_mgr) = &self.overnight_manager {
-            overnight_mgr.get_config(home_id).await
-        } else {
-            None
+    // Repair non-finite and out-of-range inputs; a rejected event fails here
+    fn sanitize_inputs(&self, thinking_event: &mut Event, event_id: Uuid) -> Result<Vec<String>, PipelineError> {
+        let quality = Sanitizer::new(self.config.sanitization.clone())
+            .sanitize_event(thinking_event, self.clock.now().timestamp() as f64)
+            .map_err(PipelineError::SanitizationError)?;
+        if !quality.is_clean() {
+            warn!("Event {} had {} repaired input(s): {}", event_id, quality.issues.len(), quality.flags().join("; "));
         }
+        Ok(quality.flags())
     }
-}
 
-// UPDATED: Custom errors for the pipeline
-#[derive(Error, Debug)]
-pub enum PipelineError {
-    #[error("Failed to submit event to VPS: {0}")]
-    VpsSubmissionError(Box<dyn std::error::Error + Send + Sync>),
+    // Sanitize: build the thinking event for tiers that include it and repair its inputs
+    fn sanitize_stage(&self, run: &mut PipelineRun) -> Result<(), PipelineError> {
+        if !self.config.feature_gate.allows(&run.tier, Feature::ThinkingAI) {
+            return Ok(());
+        }
+        let mut thinking_event = self.create_thinking_event(&run.event);
+        run.data_quality.extend(self.sanitize_inputs(&mut thinking_event, run.event.event_id)?);
+        run.thinking_event = Some(thinking_event);
+        Ok(())
+    }
 
-    #[error("Overnight review system error: {0}")]
-    OvernightError(String), // NEW: Overnight system errors
+    // Enrich: visitor codes, occupancy, signature trust and the home's learned models
+    async fn enrich_stage(&mut self, run: &mut PipelineRun) {
+        let event = &run.event;
+        // Codes are checked for every tier so use counts and lockouts stay accurate
+        run.token_evidence = self.visitor_tokens.as_ref().zip(extract_access_code(&event.data))
+            .map(|(store, code)| store.evidence_for(&event.home_id, &code, run.event_time));
+        run.vacation = self.vacations.as_ref().and_then(|v| v.active(&event.home_id, run.event_time));
+        if let Some(health) = &self.camera_health {
+            health.record_event(&event.home_id, &event.sensor_id, run.event_time, reported_fps(&event.data));
+        }
 
-    #[error("An unknown pipeline error occurred")]
-    Unknown,
+        let Some(thinking_event) = run.thinking_event.as_mut() else {
+            return;
+        };
+        if let Some(state) = self.household.as_ref().and_then(|h| h.dwelling_state(&event.home_id, run.event_time)) {
+            thinking_event.away_prob = state.away_prob;
+        }
+        if let Some(away_prob) = self.arming.as_ref().and_then(|a| a.away_prob(&event.home_id)) {
+            thinking_event.away_prob = away_prob;
+        }
+        if let (Some(_), Some(registry)) = (&run.vacation, &self.vacations) {
+            thinking_event.away_prob = registry.config().away_prob;
+        }
+        if let Some(token) = &run.token_evidence {
+            apply_token_evidence(thinking_event, token);
+        }
+        // Unsigned events could be spoofed, so their evidence counts for less
+        if run.evidence_weight < 1.0 {
+            thinking_event.evidence = thinking_event.evidence.discounted(run.evidence_weight);
+        }
+
+        if let Some((learner, _)) = &self.weight_learner {
+            self.thinking_ai.set_channel_weights(&event.home_id, learner.weights(&event.home_id));
+        }
+        if self.sensor_reliability.is_some() || self.tamper.is_some() || self.camera_health.is_some() {
+            let mut reliabilities = self.sensor_reliability.as_ref()
+                .map(|(model, _)| model.home_reliabilities(&event.home_id))
+                .unwrap_or_default();
+            let tampered = self.tamper.as_ref().map(|t| t.reliability_factors(&event.home_id)).unwrap_or_default();
+            let unhealthy = self.camera_health.as_ref().map(|h| h.reliability_factors(&event.home_id, run.event_time)).unwrap_or_default();
+            for (camera, factor) in tampered.into_iter().chain(unhealthy) {
+                *reliabilities.entry(camera).or_insert(1.0) *= factor;
+            }
+            self.thinking_ai.set_sensor_reliability(&event.home_id, reliabilities);
+        }
+        if let Some(enricher) = &self.environment {
+            let snapshot = enricher.snapshot(&event.home_id, self.clock.now()).await;
+            self.thinking_ai.set_visual_reliability(&event.home_id, snapshot.visual_reliability);
+        }
+    }
+
+    // Image: tamper checks on the attached snapshot and the verified snapshot URL
+    fn image_stage(&self, run: &mut PipelineRun) {
+        let event = &run.event;
+        if let (Some(detector), Some(image)) = (&self.tamper, &event.image_data) {
+            if detector.due(&event.home_id, &event.sensor_id, run.event_time) {
+                if let Err(e) = self.check_camera_tamper(&event.home_id, &event.sensor_id, &event.user_id, image, run.event_time) {
+                    warn!("Tamper check skipped for camera {}: {}", event.sensor_id, e);
+                }
+            }
+        }
+        run.snapshot_url = self.snapshot_url(event);
+        if let (Some(store), Some(url), Some(image)) = (&self.encryption, &run.snapshot_url, &event.image_data) {
+            if let Err(e) = store.put_image(&event.home_id, url, image) {
+                warn!("Snapshot for event {} not stored: {}", event.event_id, e);
+            }
+        }
+    }
+
+    // VPS: overnight suppression, then VPS or on-device analysis, re-identification and tracking
+    async fn vps_stage(&mut self, run: &mut PipelineRun) -> Result<(), PipelineError> {
+        // Check if event is during overnight review period; on vacation every alert goes out at once
+        let overnight_manager = self.overnight_manager.clone()
+            .filter(|_| run.vacation.is_none())
+            .filter(|_| self.config.feature_gate.allows(&run.tier, Feature::OvernightReview));
+        let mut overnight_review = None;
+        if let Some(overnight_mgr) = overnight_manager {
+            if overnight_mgr.is_in_review_period(&run.event.home_id, run.event_time).await
+                .map_err(|e| PipelineError::OvernightError(e.to_string()))?
+            {
+                overnight_review = Some(overnight_mgr);
+            }
+        }
+
+        if overnight_review.is_none() {
+            self.send_heads_up(run);
+        }
+
+        let event = &run.event;
+        let vps_response = if self.config.vps_enabled {
+            let request = VpsProcessingRequest {
+                api_key: run.api_key.clone(),
+                event_id: event.event_id.to_string(),
+                sensor_data: event.data.clone(),
+                image_data: event.image_data.clone(),
+                processing_level: format!("{:?}", run.processing_level).to_lowercase(),
+                user_context: format!("user:{}, home:{}", event.user_id, event.home_id),
+            };
+            // Routed to the home's endpoint in the pool, failing over to the next one
+            let vps_response = self.vps_client.process_event(&event.home_id, request).await;
+            let reached_vps = match &vps_response {
+                Ok(response) => !response.from_cache,
+                Err(e) => !VpsApiClient::is_circuit_open_error(e.as_ref()),
+            };
+            if reached_vps {
+                self.meter(&event.user_id, BillableUnit::VpsCalls, 1);
+            }
+            match vps_response {
+                Ok(response) => response,
+                // Every endpoint is tripped or down: analyse here rather than drop the event
+                Err(e) if VpsApiClient::is_circuit_open_error(e.as_ref()) => {
+                    warn!("VPS unavailable, processing event {} locally", event.event_id);
+                    self.local_fallback(event, run.processing_level).await
+                }
+                Err(e) => return Err(PipelineError::VpsSubmissionError(format!("{}", e).into())),
+            }
+        } else {
+            self.run_edge_inference(event, run.processing_level).await?
+        };
+
+        if let Some(overnight_mgr) = overnight_review {
+            // Analyze but don't alert; re-identification lets the morning summary group the night's events by person
+            let mut analysis = overnight_mgr.process_for_overnight_review(event).await
+                .map_err(|e| PipelineError::OvernightError(e.to_string()))?;
+            if let Some(store) = &self.embeddings {
+                let detection = DetectionEmbeddings {
+                    appearance: vps_response.appearance_embedding.clone(),
+                    gait: vps_response.gait_embedding.clone(),
+                };
+                match store.link_detection(&event.home_id, &detection) {
+                    Ok(link) => analysis.entity_id = link.map(|l| l.entity_id),
+                    Err(e) => warn!("Embedding link skipped for overnight event {}: {}", event.event_id, e),
+                }
+            }
+
+            // Store for morning summary
+            overnight_mgr.store_overnight_event(analysis).await
+                .map_err(|e| PipelineError::OvernightError(e.to_string()))?;
+            run.finished = Some(ProcessedEvent {
+                original_event_id: run.event.event_id,
+                processing_timestamp: self.clock.now().timestamp(),
+                tier: run.tier.clone(),
+                processing_level: "overnight_suppressed".to_string(),
+                vps_job_id: "overnight".to_string(),
+                status: "suppressed_for_overnight_review".to_string(),
+                result_summary: "Event processed and stored for morning review".to_string(),
+                thinking_ai_analysis: None,
+                overnight_suppressed: true,
+            });
+            return Ok(());
+        }
+
+        if let Some(thinking_event) = run.thinking_event.as_mut() {
+            // Re-identified people share a track, so their detections join the same incident
+            if let Some(store) = &self.embeddings {
+                let detection = DetectionEmbeddings {
+                    appearance: vps_response.appearance_embedding.clone(),
+                    gait: vps_response.gait_embedding.clone(),
+                };
+                match store.link_detection(&event.home_id, &detection) {
+                    Ok(Some(link)) => thinking_event.person_track = format!("entity_{}", link.entity_id),
+                    Ok(None) => {}
+                    Err(e) => warn!("Embedding link skipped for event {}: {}", event.event_id, e),
+                }
+            }
+
+            // With the track settled, a valid code vouches for this person on this camera for the rest of the visit
+            if let Some(store) = &self.visitor_tokens {
+                match &run.token_evidence {
+                    Some(token) => store.open_suppression(&event.home_id, token, &thinking_event.cam, &thinking_event.person_track, run.event_time),
+                    None => {
+                        run.token_evidence = store.suppression_evidence(&event.home_id, &thinking_event.cam, &thinking_event.person_track, run.event_time);
+                        if let Some(token) = &run.token_evidence {
+                            apply_token_evidence(thinking_event, token);
+                        }
+                    }
+                }
+            }
+
+            let identity_before_face = thinking_event.evidence.llr_identity;
+            // People the home has come to trust count as identity evidence, unless a visitor code says more
+            if let Some(llr) = self.trust.as_ref().and_then(|t| t.identity_llr(&event.home_id, &thinking_event.person_track, run.event_time)) {
+                if llr.abs() > thinking_event.evidence.llr_identity.abs() {
+                    thinking_event.evidence.llr_identity = llr;
+                }
+            }
+            // ...and people the household put on its watchlist count against them
+            if let Some(llr) = self.annotations.as_ref().and_then(|a| a.watchlist_llr(&event.home_id, &thinking_event.person_track)) {
+                if llr > thinking_event.evidence.llr_identity {
+                    thinking_event.evidence.llr_identity = llr;
+                }
+            }
+
+            // Expected guests inside their window are strong benign evidence; outside it their own code counts for nothing
+            if let Some(guests) = &self.guests {
+                let token_id = run.token_evidence.as_ref().and_then(|t| t.token_id);
+                run.guest = guests.match_visit(&event.home_id, &thinking_event.person_track, token_id, run.event_time);
+                match &run.guest {
+                    Some(guest) if guest.within_window => {
+                        let llr = guests.config().expected_identity_llr;
+                        if llr.abs() > thinking_event.evidence.llr_identity.abs() {
+                            thinking_event.evidence.llr_identity = llr;
+                        }
+                        thinking_event.expected_window = true;
+                    }
+                    Some(guest) => {
+                        warn!("Guest '{}' seen outside their window at {}", guest.name, event.home_id);
+                        if guest.matched_by == GuestMatchKind::Code {
+                            thinking_event.evidence.llr_identity = thinking_event.evidence.llr_identity.max(0.0);
+                            thinking_event.evidence.llr_token = thinking_event.evidence.llr_token.max(0.0);
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            // A face that may be a photo or screen earns less from matching; a near-certain spoof earns nothing
+            if let (Some(detector), Some(liveness)) = (&self.anti_spoofing, vps_response.liveness_score) {
+                let source = if self.config.vps_enabled { LivenessSource::Vps } else { LivenessSource::Edge };
+                let face_matched = (thinking_event.evidence.llr_identity - identity_before_face).abs() > f64::EPSILON;
+                let face_llr = if face_matched { thinking_event.evidence.llr_identity } else { 0.0 };
+                if let Some(spoof) = detector.assess(source, liveness, face_llr) {
+                    warn!("Possible spoofed face on {} at {} (liveness {:.2})", thinking_event.cam, event.home_id, spoof.liveness);
+                    if face_matched {
+                        thinking_event.evidence.llr_identity = spoof.identity_llr_after;
+                    }
+                    let guest_by_face = run.guest.as_ref().is_some_and(|g| g.matched_by == GuestMatchKind::Entity);
+                    if guest_by_face && spoof.discount >= 1.0 {
+                        thinking_event.expected_window = false;
+                    }
+                    run.spoof = Some(spoof);
+                }
+            }
+
+            // Dwell is the real time the person has been around, and loitering counts as behavior evidence
+            if let Some(tracker) = &self.tracker {
+                let interacted = thinking_event.rang_doorbell || thinking_event.knocked;
+                let update = tracker.observe(&event.home_id, &thinking_event.person_track, &thinking_event.cam, thinking_event.ts, interacted);
+                if let Some(dwell) = update.dwell_increment_s {
+                    thinking_event.dwell_s = dwell;
+                }
+                if update.entity.state == crate::tracker::TrackState::Loitering {
+                    thinking_event.evidence.llr_behavior += tracker.config().loitering_behavior_llr;
+                }
+                for transition in &update.transitions {
+                    info!("Track {} in {}: {:?} -> {:?} ({})", transition.track_id, event.home_id, transition.from, transition.to, transition.message);
+                }
+            }
+
+            // A path visitors rarely take counts as behavior evidence
+            if let Some(graph) = &self.zone_graph {
+                if let Some(step) = graph.observe(&event.home_id, &thinking_event.person_track, &thinking_event.cam, thinking_event.ts) {
+                    if step.behavior_llr > 0.0 {
+                        info!("Track {} in {} moved {} -> {} (p={:.3}), behavior LLR +{:.2}", thinking_event.person_track, event.home_id, step.from, step.to, step.probability, step.behavior_llr);
+                        thinking_event.evidence.llr_behavior += step.behavior_llr;
+                    }
+                }
+            }
+
+            // Someone casing the property counts as behavior evidence
+            if let Some(system) = &self.counter_surveillance {
+                if let Some(sighting) = Sighting::from_payload(event.data.as_str(), event.event_id, &thinking_event.person_track, run.event_time) {
+                    let subject = sighting.subject_key.clone();
+                    system.record_sighting(&event.home_id, sighting).await;
+                    if let Some(indicators) = system.assess_subject(&event.home_id, &subject, run.event_time).await {
+                        let llr = indicators.behavior_llr(system.config());
+                        if llr > 0.0 {
+                            info!("Subject {} in {} may be casing the property (score {:.2}), behavior LLR +{:.2}", subject, event.home_id, indicators.score, llr);
+                            thinking_event.evidence.llr_behavior += llr;
+                        }
+                    }
+                }
+            }
+
+            // Far more activity than usual for this zone and hour of the week counts as behavior evidence
+            if let Some(baseline) = &self.activity_baseline {
+                let deviation = baseline.observe(&event.home_id, &thinking_event.cam, run.event_time);
+                if deviation.behavior_llr > 0.0 {
+                    info!("{} in {} at {:.1}x its usual activity, behavior LLR +{:.2}", deviation.zone, event.home_id, deviation.ratio, deviation.behavior_llr);
+                    thinking_event.evidence.llr_behavior += deviation.behavior_llr;
+                    run.baseline = Some(deviation);
+                }
+            }
+        }
+        run.vps_response = Some(vps_response);
+        Ok(())
+    }
+
+    // Thinking: prior shifts, a last sanitization pass, then fusion into the incident
+    async fn thinking_stage(&mut self, run: &mut PipelineRun) -> Result<(), PipelineError> {
+        let Some(mut thinking_event) = run.thinking_event.take() else {
+            return Ok(());
+        };
+        let event = &run.event;
+
+        // Prior shifts: neighborhood reports plus known repeat-prowler patterns
+        let mut prior_offset = 0.0;
+        if let Some(hub) = &self.federation {
+            prior_offset += hub.area_prior_boost(&event.home_id, self.clock.now()).await;
+        }
+        if let Some(index) = &self.mo_clusters {
+            let mut provisional = self.thinking_ai
+                .track_incident(&event.home_id, &thinking_event.person_track)
+                .unwrap_or_else(|| Incident::new(0, thinking_event.ts, thinking_event.person_track.clone()));
+            provisional.add_event(thinking_event.clone());
+            if let Some(matched) = index.match_incident(&event.home_id, &provisional).await {
+                info!("Event {} matches MO cluster {} (distance {:.2})", event.event_id, matched.cluster_id, matched.distance);
+                prior_offset += matched.prior_boost;
+            }
+        }
+        if let (Some(_), Some(registry)) = (&run.vacation, &self.vacations) {
+            prior_offset += registry.config().prior_logit_boost;
+        }
+        if self.federation.is_some() || self.mo_clusters.is_some() || self.vacations.is_some() {
+            self.thinking_ai.set_prior_offset(&event.home_id, prior_offset);
+        }
+
+        // Last step before fusion: nothing non-finite gets into the incident
+        run.data_quality.extend(self.sanitize_inputs(&mut thinking_event, event.event_id)?);
+
+        let person_track = thinking_event.person_track.clone();
+        let Some(mut result) = self.thinking_ai.process_flagged_event(&event.home_id, thinking_event, run.data_quality.clone()) else {
+            return Ok(());
+        };
+        // Whether this is an expected guest, whether the face may be spoofed, and what the household has said about this person before
+        if let Some(spoof) = &run.spoof {
+            result.explanation.cautions.push(spoof.narrative());
+        }
+        let context: Vec<String> = run.guest.as_ref().map(|g| g.narrative()).into_iter()
+            .chain(run.spoof.as_ref().map(|s| s.narrative()))
+            .chain(run.baseline.as_ref().map(|b| b.narrative()))
+            .chain(self.annotations.as_ref().and_then(|a| a.entity_context(&event.home_id, &person_track)))
+            .collect();
+        if !context.is_empty() {
+            result.narrative_summary.push(' ');
+            result.narrative_summary.push_str(&context.join(" "));
+            self.thinking_ai.set_narrative(&event.home_id, result.incident_id, result.narrative_summary.clone());
+        }
+        if let Some(url) = run.snapshot_url.clone() {
+            let faces = run.vps_response.as_ref().and_then(|r| r.faces.clone());
+            self.thinking_ai.attach_snapshot(&event.home_id, result.incident_id, url, faces);
+        }
+        if self.mo_clusters.is_some() || self.knowledge_graph.is_some() {
+            if let Some(incident) = self.thinking_ai.find_incident(&event.home_id, result.incident_id) {
+                if let Some(index) = &self.mo_clusters {
+                    index.record(&event.home_id, &incident).await;
+                }
+                if let Some(graph) = &self.knowledge_graph {
+                    if let Err(e) = graph.record_incident(&event.home_id, &incident, run.event_time) {
+                        warn!("Knowledge graph not saved for incident {} in {}: {}", incident.id, event.home_id, e);
+                    }
+                }
+            }
+        }
+        run.result = Some(result);
+        Ok(())
+    }
+
+    // Rules: adversarial second opinion, decision overrides and follow-up timers
+    async fn rules_stage(&mut self, run: &mut PipelineRun) {
+        let Some(result) = run.result.as_mut() else {
+            return;
+        };
+        let event = &run.event;
+        let event_time = run.event_time;
+        let vacation = &run.vacation;
+
+        if let Some((analyzer, config)) = self.adversarial.clone() {
+            let request = self.thinking_ai.find_incident(&event.home_id, result.incident_id)
+                .filter(|incident| config.should_forward(incident, result.calibrated_probability))
+                .map(|incident| HandoffRequest::new(&event.home_id, &incident, result.calibrated_probability, &result.fused_evidence));
+            if let Some(request) = request {
+                match crate::thinking::adversarial_handoff::forward(analyzer.as_ref(), &request, &config).await {
+                    Ok(assessment) => {
+                        self.thinking_ai.apply_adversarial_assessment(&event.home_id, result.incident_id, assessment);
+                        if let Some(updated) = self.thinking_ai.reassess_incident(&event.home_id, result.incident_id) {
+                            *result = updated;
+                        }
+                    }
+                    Err(e) => warn!("Adversarial handoff skipped for incident {}: {}", result.incident_id, e),
+                }
+            }
+        }
+        if vacation.as_ref().is_some_and(|v| v.is_interior(&event.sensor_id)) && result.alert_decision != AlertDecision::Critical {
+            info!("Interior activity on {} while home {} is on vacation, escalating incident {}", event.sensor_id, event.home_id, result.incident_id);
+            self.thinking_ai.override_decision(&event.home_id, result.incident_id, AlertDecision::Critical, "escalated: interior activity while on vacation");
+            result.alert_decision = AlertDecision::Critical;
+        }
+        if let Some(engine) = self.escalation_rules.clone() {
+            let zone_class = engine.zone_class(&event.home_id, &event.sensor_id)
+                .or_else(|| vacation.as_ref().filter(|v| v.is_interior(&event.sensor_id)).map(|_| ZoneClass::Interior));
+            let occupancy = match vacation {
+                Some(_) => Some(crate::household::Occupancy::Empty),
+                None => self.household.as_ref().and_then(|h| h.dwelling_state(&event.home_id, event_time)).map(|s| s.occupancy),
+            };
+            let context = ThreatContext {
+                entity_id: event.event_id,
+                threat_indicators: HashMap::from([("calibrated_probability".to_string(), result.calibrated_probability)]),
+                environmental_factors: HashMap::new(),
+                temporal_context: event_time,
+                confidence: result.calibrated_probability,
+                zone_class,
+                occupancy,
+                threat_vector: Some(result.threat_vector),
+            };
+            let verdict = engine.apply(&context, &result.alert_decision);
+            let reason = verdict.reason.unwrap_or_default();
+            if verdict.decision != result.alert_decision {
+                info!("Rule {:?} set incident {} in {} to {:?}: {}", verdict.rule, result.incident_id, event.home_id, verdict.decision, reason);
+                self.thinking_ai.override_decision(&event.home_id, result.incident_id, verdict.decision.clone(), &format!("escalated: {}", reason));
+                result.alert_decision = verdict.decision;
+            }
+            if verdict.start_escalation {
+                engine.start(&event.home_id, result.incident_id, &event.user_id, &reason, self.clock.now());
+            }
+        }
+        if let Some(scheduler) = self.follow_ups.clone() {
+            let event_count = self.thinking_ai.find_incident(&event.home_id, result.incident_id).map_or(0, |i| i.events.len());
+            let resolution = scheduler.on_assessment(
+                &event.home_id,
+                result.incident_id,
+                &event.user_id,
+                Some(&event.sensor_id),
+                &result.alert_decision,
+                event_count,
+                self.clock.now(),
+            ).await;
+            match resolution {
+                Ok(Some(FollowUpResolution::Escalate(decision))) => {
+                    info!("Waiting incident {} escalated to {:?} on new evidence", result.incident_id, decision);
+                    self.thinking_ai.override_decision(&event.home_id, result.incident_id, decision.clone(), "escalated: new evidence while waiting");
+                    result.alert_decision = decision;
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Follow-up scheduling failed for incident {}: {}", result.incident_id, e),
+            }
+        }
+        self.share_with_federation(event, result, event_time).await;
+        self.record_probability(&event.home_id, result.incident_id, Some(event.event_id), PointSource::Event, self.clock.now()).await;
+        self.record_predictions(&event.home_id, result.incident_id, true, self.clock.now());
+    }
+
+    // Anything worth alerting on goes to the neighborhood as anonymized signatures:
+    // the plate the camera read, if any, and the incident's zone sequence
+    async fn share_with_federation(&self, event: &RawEvent, result: &ThinkingAIResult, event_time: DateTime<Utc>) {
+        let Some(hub) = &self.federation else {
+            return;
+        };
+        if result.alert_decision == AlertDecision::Ignore {
+            return;
+        }
+        let mut shared = Ok(());
+        if let Some(plate) = plate_from_payload(event.data.as_str()) {
+            shared = hub.share_plate(&event.home_id, plate, event_time).await;
+        }
+        let incident = self.thinking_ai.find_incident(&event.home_id, result.incident_id);
+        if let (true, Some(incident)) = (shared.is_ok(), incident) {
+            let mut zones: Vec<String> = incident.events.iter().map(|e| e.cam.clone()).collect();
+            zones.dedup();
+            shared = hub.share_prowler_pattern(&event.home_id, &zones, event_time).await;
+        }
+        match shared {
+            Ok(()) => debug!("Shared incident {} from {} with the neighborhood", result.incident_id, event.home_id),
+            // Homes that have not opted in simply do not share
+            Err(FederationError::NotAMember(_) | FederationError::Revoked(_)) => {}
+            Err(e) => warn!("Federation sharing skipped for incident {}: {}", result.incident_id, e),
+        }
+    }
+
+    // Notify: vacation digest, notifications, lifecycle hooks and SIEM export
+    async fn notify_stage(&mut self, run: &mut PipelineRun) {
+        let Some(result) = run.result.as_ref() else {
+            return;
+        };
+        let event = &run.event;
+        if let (Some(_), Some(overnight_mgr)) = (&run.vacation, &self.overnight_manager) {
+            // The morning summary becomes a digest of the whole day
+            let digest_entry = OvernightEventAnalysis {
+                event_id: event.event_id,
+                home_id: event.home_id.clone(),
+                timestamp: run.event_time,
+                analysis_summary: result.narrative_summary.clone(),
+                suppressed_alert_level: Some(result.alert_decision.clone()),
+                camera: Some(event.sensor_id.clone()),
+                entity_id: None,
+            };
+            if let Err(e) = overnight_mgr.store_overnight_event(digest_entry).await {
+                warn!("Vacation digest entry skipped for event {}: {}", event.event_id, e);
+            }
+        }
+        self.notify(&event.home_id, &event.user_id, &event.sensor_id, result, run.heads_up);
+        self.run_automations(&event.home_id, result.incident_id, &result.alert_decision, &event.sensor_id).await;
+        self.bookmark_recordings(&event.home_id, result.incident_id, &result.alert_decision, &event.sensor_id);
+        self.report_to_central_station(&event.home_id, result.incident_id, &result.alert_decision, &event.sensor_id);
+        self.dispatch_transitions();
+        if let Some(siem) = &self.siem {
+            if let Err(e) = siem.export(SiemEvent::from_thinking_result(&event.home_id, result)) {
+                warn!("SIEM export skipped for incident {}: {}", result.incident_id, e);
+            }
+        }
+    }
+
+    fn processed_event(&self, run: PipelineRun) -> ProcessedEvent {
+        let thinking_ai_analysis = run.result.as_ref().map(|result| self.thinking_ai.format_thinking_block(result));
+        let mut result_summary = "Processing initiated with VPS".to_string();
+        if thinking_ai_analysis.is_some() {
+            result_summary.push_str(" + ThinkingAI analysis");
+        }
+        if let Some(label) = run.token_evidence.as_ref().and_then(|t| t.label.as_ref()) {
+            result_summary.push_str(&format!(" + visitor token '{}'", label));
+        }
+        for note in &run.notes {
+            result_summary.push_str(&format!(" + {}", note));
+        }
+        let (vps_job_id, status) = match run.vps_response {
+            Some(response) => (response.job_id, response.status),
+            None => ("skipped".to_string(), "completed".to_string()),
+        };
+
+        ProcessedEvent {
+            original_event_id: run.event.event_id,
+            processing_timestamp: self.clock.now().timestamp(),
+            tier: run.tier,
+            processing_level: format!("{:?}", run.processing_level).to_lowercase(),
+            vps_job_id,
+            status,
+            result_summary,
+            thinking_ai_analysis,
+            overnight_suppressed: false,
+        }
+    }
+
+    // Recorded as handled so a retried webhook is not run through the chain again
+    fn halted_event(&self, run: &PipelineRun, middleware: &str, stage: Stage, reason: &str) -> ProcessedEvent {
+        ProcessedEvent {
+            original_event_id: run.event.event_id,
+            processing_timestamp: self.clock.now().timestamp(),
+            tier: run.tier.clone(),
+            processing_level: format!("{:?}", run.processing_level).to_lowercase(),
+            vps_job_id: run.vps_response.as_ref().map_or_else(|| "none".to_string(), |r| r.job_id.clone()),
+            status: "halted".to_string(),
+            result_summary: format!("Halted by {} before the {:?} stage: {}", middleware, stage, reason),
+            thinking_ai_analysis: None,
+            overnight_suppressed: false,
+        }
+    }
+
+    // The event's snapshot URL, unless the camera registry refuses it
+    fn snapshot_url(&self, event: &RawEvent) -> Option<String> {
+        let url = event.image_url.clone().or_else(|| extract_image_url(&event.data))?;
+        let Some(cameras) = &self.cameras else {
+            return Some(url);
+        };
+        match self.image_preloader.verify_snapshot(cameras, &event.home_id, &event.sensor_id, &url) {
+            Ok(_) => Some(url),
+            Err(e) => {
+                warn!("Ignoring snapshot for event {}: {}", event.event_id, e);
+                None
+            }
+        }
+    }
+
+    async fn fetch_snapshot(&self, event: &RawEvent, url: String) -> Result<Bytes, ImageError> {
+        let priority = self.snapshot_priority_for(event);
+        let image = match &self.cameras {
+            Some(cameras) => self.image_preloader.download_snapshot_at(cameras, &event.home_id, &event.sensor_id, url, event.event_id, priority).await,
+            None => self.image_preloader.download_image_at(url, event.event_id, priority).await,
+        };
+        self.corrupt_for_chaos(image)
+    }
+
+    // Snapshots default to High; with a policy, from the camera's live incident and zone
+    fn snapshot_priority_for(&self, event: &RawEvent) -> Priority {
+        let Some(policy) = &self.snapshot_priority else {
+            return Priority::High;
+        };
+        let live = self.thinking_ai.live_probability(&event.home_id, &event.sensor_id);
+        let at = DateTime::from_timestamp(event.timestamp, 0).unwrap_or_else(|| self.clock.now());
+        policy.priority(&event.home_id, &event.sensor_id, live, at)
+    }
+
+    // Snapshot corruption under fault injection; passes through otherwise
+    fn corrupt_for_chaos(&self, image: Result<Bytes, ImageError>) -> Result<Bytes, ImageError> {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.chaos {
+            return image.map(|bytes| chaos.corrupt_image(bytes));
+        }
+        image
+    }
+
+    // Analyze the event's image with the on-device models, shaped like a VPS response
+    async fn run_edge_inference(&self, event: &RawEvent, level: ProcessingLevel) -> Result<VpsProcessingResponse, PipelineError> {
+        let engine = self.edge.clone()
+            .ok_or_else(|| PipelineError::EdgeInferenceError("VPS disabled and no edge models configured".to_string()))?;
+        let image = match (&event.image_data, self.snapshot_url(event)) {
+            (Some(bytes), _) => bytes.clone(),
+            (None, Some(url)) => self.fetch_snapshot(event, url).await
+                .map_err(|e| PipelineError::EdgeInferenceError(e.to_string()))?,
+            (None, None) => return Err(PipelineError::EdgeInferenceError("event has no image".to_string())),
+        };
+
+        // Inference is CPU-bound; keep it off the async workers
+        let result = tokio::task::spawn_blocking(move || engine.infer(&image, level))
+            .await
+            .map_err(|e| PipelineError::EdgeInferenceError(e.to_string()))?
+            .map_err(|e| PipelineError::EdgeInferenceError(e.to_string()))?;
+        info!("Edge inference for event {}: {} detection(s) in {}ms", event.event_id, result.detections.len(), result.latency_ms);
+
+        Ok(VpsProcessingResponse {
+            job_id: format!("edge-{}", event.event_id),
+            status: "completed".to_string(),
+            result_url: None,
+            error_message: None,
+            appearance_embedding: result.face_embedding,
+            gait_embedding: None,
+            liveness_score: result.liveness.map(f64::from),
+            faces: None,
+            from_cache: false,
+        })
+    }
+
+    // Stand-in for the VPS analysis: edge models when configured, otherwise the sensor data alone
+    async fn local_fallback(&self, event: &RawEvent, level: ProcessingLevel) -> VpsProcessingResponse {
+        let edge = match &self.edge {
+            Some(_) => self.run_edge_inference(event, level).await
+                .map_err(|e| warn!("Edge fallback failed for event {}: {}", event.event_id, e))
+                .ok(),
+            None => None,
+        };
+        let mut response = edge.unwrap_or_else(|| VpsProcessingResponse {
+            job_id: String::new(),
+            status: "completed".to_string(),
+            result_url: None,
+            error_message: None,
+            appearance_embedding: None,
+            gait_embedding: None,
+            liveness_score: None,
+            faces: None,
+            from_cache: false,
+        });
+        response.job_id = LOCAL_FALLBACK_JOB_ID.to_string();
+        response
+    }
+
+    // Send an alert for a result through preferences and cooldowns
+    fn notify(&mut self, home_id: &str, user_id: &str, zone: &str, result: &ThinkingAIResult, heads_up: Option<Uuid>) {
+        let Some(router) = self.notifications.clone() else {
+            return;
+        };
+        // After a heads-up, an incident that no longer alerts still gets its update: the all-clear
+        let (severity, cleared) = match (NotificationSeverity::from_decision(&result.alert_decision), heads_up) {
+            (Some(severity), _) => (severity, false),
+            (None, Some(_)) => (NotificationSeverity::Info, true),
+            (None, None) => return,
+        };
+        if self.arming.as_ref().is_some_and(|a| !a.alerts_enabled(home_id)) {
+            return;
+        }
+        let locale = router.templates().language(home_id);
+        let mut body = result.narrative_summary.clone();
+        let incident = self.thinking_ai.find_incident(home_id, result.incident_id);
+        let entity = incident.as_ref().map(|i| i.person_session_id.clone());
+        let thumbnail_url = incident.as_ref().and_then(|i| i.snapshot_urls.last().cloned());
+        let track = self.tracker.as_ref().zip(entity.as_ref())
+            .and_then(|(tracker, session)| tracker.get(home_id, session));
+        let entity_status = track.map(|t| t.status_line());
+        if let Some(status) = &entity_status {
+            body = format!("{}\n{}.", body, status);
+        }
+        if severity == NotificationSeverity::Critical {
+            let estimate = self.escalation.as_ref().zip(self.thinking_ai.find_incident(home_id, result.incident_id))
+                .and_then(|(model, incident)| model.estimate_for(&incident, incident.last_updated));
+            if let Some(estimate) = estimate {
+                body = format!("{}\n{}.", body, estimate.statement());
+                if let Some(scorer) = &self.prediction_scoring {
+                    scorer.record(home_id, result.incident_id, PredictionComponent::Escalation, estimate.probability, Some(estimate.horizon_secs), self.clock.now());
+                }
+            }
+        }
+        let title = match cleared {
+            true => t(&locale, "alert-cleared-title", &[("zone", zone.to_string())]),
+            false => t(&locale, "alert-title", &[("severity", localizer().severity(&locale, severity)), ("zone", zone.to_string())]),
+        };
+        let notification = Notification {
+            home_id: home_id.to_string(),
+            severity,
+            title,
+            body,
+            created_at: self.clock.now(),
+            incident_id: Some(result.incident_id),
+            zone: Some(zone.to_string()),
+            probability: Some(result.calibrated_probability),
+            entity,
+            entity_status,
+            thumbnail_url,
+            alert: heads_up.map(|alert_id| AlertRef { alert_id, phase: AlertPhase::Update }),
+        };
+        let recipients = self.recipients(home_id, user_id, severity);
+        let outcome = router.route(&notification, &recipients, self.clock.now());
+        if outcome.cooldown == CooldownVerdict::Suppress {
+            self.thinking_ai.record_suppressed_notification(home_id, result.incident_id);
+        }
+    }
+
+    // Two-phase alerts: push a heads-up as soon as the provisional probability
+    // crosses the threshold, before VPS analysis; Notify sends the update
+    fn send_heads_up(&self, run: &mut PipelineRun) {
+        let (Some(router), Some(thinking_event)) = (&self.notifications, &run.thinking_event) else {
+            return;
+        };
+        let home_id = &run.event.home_id;
+        if !router.heads_up_enabled(home_id) || self.arming.as_ref().is_some_and(|a| !a.alerts_enabled(home_id)) {
+            return;
+        }
+        let provisional = self.thinking_ai.provisional_assessment(home_id, thinking_event);
+        let Some(severity) = NotificationSeverity::from_decision(&provisional.decision) else {
+            return;
+        };
+        let locale = router.templates().language(home_id);
+        let zone = &run.event.sensor_id;
+        let alert_id = Uuid::new_v4();
+        let notification = Notification {
+            home_id: home_id.clone(),
+            severity,
+            title: t(&locale, "alert-title", &[("severity", localizer().severity(&locale, severity)), ("zone", zone.clone())]),
+            body: t(&locale, "heads-up-body", &[("probability", format!("{:.0}", provisional.probability * 100.0))]),
+            created_at: self.clock.now(),
+            incident_id: provisional.incident_id,
+            zone: Some(zone.clone()),
+            probability: Some(provisional.probability),
+            entity: None,
+            entity_status: None,
+            thumbnail_url: run.snapshot_url.clone(),
+            alert: Some(AlertRef { alert_id, phase: AlertPhase::HeadsUp }),
+        };
+        let outcome = router.route(&notification, &self.recipients(home_id, &run.event.user_id, severity), self.clock.now());
+        if outcome.deliveries.iter().any(|d| d.decision == RouteDecision::Deliver) {
+            info!("Heads-up {} sent for event {} at {:.0}%", alert_id, run.event.event_id, provisional.probability * 100.0);
+            run.heads_up = Some(alert_id);
+        }
+    }
+
+    // Residents above their own threshold; homes without residents notify the event's account
+    fn recipients(&self, home_id: &str, user_id: &str, severity: NotificationSeverity) -> Vec<(Option<String>, Vec<DeliveryChannel>)> {
+        match self.household.as_ref().map(|h| (h.residents(home_id).is_empty(), h.recipients(home_id, severity))) {
+            Some((false, recipients)) => recipients,
+            _ => vec![(Some(user_id.to_string()), vec![DeliveryChannel::Push, DeliveryChannel::WebSocket])],
+        }
+    }
+
+    /// Compare a camera snapshot with its reference view, alerting the home
+    /// when blinding, covering or a moved camera is confirmed
+    pub fn check_camera_tamper(&self, home_id: &str, camera_id: &str, user_id: &str, snapshot: &[u8], at: DateTime<Utc>) -> Result<TamperCheck, TamperError> {
+        let Some(detector) = &self.tamper else {
+            return Err(TamperError::Disabled);
+        };
+        let check = detector.check(home_id, camera_id, snapshot, at)?;
+        let (Some(alert), Some(router)) = (&check.raised, &self.notifications) else {
+            return Ok(check);
+        };
+        warn!("Camera {} in {} looks tampered with: {:?} (similarity {:.2})", camera_id, home_id, alert.kind, alert.similarity);
+        // Blinding and covering are security alerts, a moved camera is maintenance
+        let severity = if alert.kind.is_security() { NotificationSeverity::Elevated } else { NotificationSeverity::Info };
+        let message = match alert.kind {
+            crate::tamper::TamperKind::Blinded => "tamper-blinded",
+            crate::tamper::TamperKind::Covered => "tamper-covered",
+            crate::tamper::TamperKind::ViewpointShift => "tamper-moved",
+        };
+        let locale = router.templates().language(home_id);
+        let notification = Notification {
+            home_id: home_id.to_string(),
+            severity,
+            title: t(&locale, &format!("{}-title", message), &[("camera", camera_id.to_string())]),
+            body: t(&locale, &format!("{}-body", message), &[]),
+            created_at: at,
+            incident_id: None,
+            zone: Some(camera_id.to_string()),
+            probability: None,
+            entity: None,
+            entity_status: None,
+            thumbnail_url: None,
+            alert: None,
+        };
+        router.route(&notification, &self.recipients(home_id, user_id, severity), at);
+        Ok(check)
+    }
+
+    /// Attach narratives generated after the fact (deferred for LLM budget)
+    /// to their incidents; returns how many were replaced
+    pub fn attach_narratives(&mut self, narratives: Vec<(String, u64, String)>) -> usize {
+        narratives.into_iter()
+            .filter(|(home_id, incident_id, narrative)| self.thinking_ai.set_narrative(home_id, *incident_id, narrative.clone()))
+            .count()
+    }
+
+    /// Send escalation chain steps that have come due; escalations for closed
+    /// incidents are dropped. Returns how many steps were sent.
+    pub fn run_escalations(&self, now: DateTime<Utc>) -> usize {
+        let Some(engine) = &self.escalation_rules else {
+            return 0;
+        };
+        let mut sent = 0;
+        for due in engine.due_steps(now) {
+            let open = self.thinking_ai.find_incident(&due.home_id, due.incident_id).is_some_and(|i| i.status.is_active());
+            if !open {
+                engine.cancel(&due.home_id, due.incident_id);
+                continue;
+            }
+            let Some(router) = &self.notifications else {
+                continue;
+            };
+            // Chain steps are deliberate repeats, so they go out without an
+            // incident or zone for cooldown to match on
+            let notification = Notification {
+                home_id: due.home_id.clone(),
+                severity: NotificationSeverity::Critical,
+                title: t(&router.templates().language(&due.home_id), "escalation-title", &[("incident", due.incident_id.to_string())]),
+                body: format!("{} ({}, step {}).", due.reason, due.step.label, due.step_index + 1),
+                created_at: now,
+                incident_id: None,
+                zone: None,
+                probability: None,
+                entity: None,
+                entity_status: None,
+                thumbnail_url: None,
+                alert: None,
+            };
+            let recipient = due.step.user_id.clone().unwrap_or(due.account_user_id.clone());
+            router.route(&notification, &[(Some(recipient), due.step.channels.clone())], now);
+            sent += 1;
+        }
+        sent
+    }
+
+    /// Re-evaluate waiting incidents whose delay has elapsed; returns how many were settled
+    pub async fn run_follow_ups(&mut self, now: DateTime<Utc>) -> usize {
+        let Some(scheduler) = self.follow_ups.clone() else {
+            return 0;
+        };
+
+        let mut settled = 0;
+        for follow_up in scheduler.due(now).await {
+            let event_count = self.thinking_ai.find_incident(&follow_up.home_id, follow_up.incident_id).map_or(0, |i| i.events.len());
+            let reassessed = self.thinking_ai.reassess_incident(&follow_up.home_id, follow_up.incident_id);
+            let resolution = scheduler.on_due(&follow_up, reassessed.as_ref().map(|r| &r.alert_decision), event_count, now).await;
+            match resolution {
+                Ok(Some(FollowUpResolution::Escalate(decision))) => {
+                    info!("Waiting incident {} escalated to {:?} on follow-up", follow_up.incident_id, decision);
+                    self.thinking_ai.override_decision(&follow_up.home_id, follow_up.incident_id, decision.clone(), "escalated on follow-up");
+                    if let Some(mut result) = reassessed {
+                        result.alert_decision = decision;
+                        let zone = follow_up.zone.clone().unwrap_or_default();
+                        // A follow-up is a fresh alert, not the update to an event's heads-up
+                        self.notify(&follow_up.home_id, &follow_up.user_id, &zone, &result, None);
+                        self.run_automations(&follow_up.home_id, follow_up.incident_id, &result.alert_decision, &zone).await;
+                        self.bookmark_recordings(&follow_up.home_id, follow_up.incident_id, &result.alert_decision, &zone);
+                        self.report_to_central_station(&follow_up.home_id, follow_up.incident_id, &result.alert_decision, &zone);
+                    }
+                    settled += 1;
+                }
+                Ok(Some(FollowUpResolution::Ignore)) if reassessed.as_ref().is_some_and(|r| r.interval.held.is_some()) => {
+                    // Still too uncertain after every re-check; better a doubtful alert than none
+                    let Some(mut result) = reassessed else { continue };
+                    let decision = result.interval.held.clone().unwrap_or(AlertDecision::Standard);
+                    info!("Incident {} still uncertain after follow-ups, alerting {:?}", follow_up.incident_id, decision);
+                    self.thinking_ai.override_decision(&follow_up.home_id, follow_up.incident_id, decision.clone(), "alerted: still uncertain after re-checks");
+                    result.alert_decision = decision;
+                    let zone = follow_up.zone.clone().unwrap_or_default();
+                    self.notify(&follow_up.home_id, &follow_up.user_id, &zone, &result, None);
+                    self.run_automations(&follow_up.home_id, follow_up.incident_id, &result.alert_decision, &zone).await;
+                    self.bookmark_recordings(&follow_up.home_id, follow_up.incident_id, &result.alert_decision, &zone);
+                    self.report_to_central_station(&follow_up.home_id, follow_up.incident_id, &result.alert_decision, &zone);
+                    settled += 1;
+                }
+                Ok(Some(FollowUpResolution::Ignore)) => {
+                    self.thinking_ai.override_decision(&follow_up.home_id, follow_up.incident_id, AlertDecision::Ignore, "resolved: nothing new while waiting");
+                    let ts = now.timestamp() as f64;
+                    if let Err(e) = self.thinking_ai.close_incident(&follow_up.home_id, follow_up.incident_id, IncidentStatus::Resolved, ts, "nothing new while waiting") {
+                        info!("Follow-up for incident {} not closed: {}", follow_up.incident_id, e);
+                    }
+                    settled += 1;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Follow-up for incident {} failed: {}", follow_up.incident_id, e),
+            }
+            self.record_probability(&follow_up.home_id, follow_up.incident_id, None, PointSource::FollowUp, now).await;
+            self.record_predictions(&follow_up.home_id, follow_up.incident_id, false, now);
+        }
+        self.dispatch_transitions();
+        settled
+    }
+
+    // Fire the home's automations for a final decision, running immediate actions now
+    async fn run_automations(&self, home_id: &str, incident_id: u64, decision: &AlertDecision, zone: &str) {
+        let Some((engine, executor)) = &self.automations else {
+            return;
+        };
+        let context = TriggerContext {
+            home_id: home_id.to_string(),
+            incident_id,
+            decision: decision.clone(),
+            zone: zone.to_string(),
+            at: self.clock.now(),
+        };
+        let immediate = engine.trigger(&context);
+        crate::automations::execute_all(engine, executor.as_ref(), immediate).await;
+    }
+
+    // Ask the home's NVRs and VMSs to bookmark the camera, in the background so a slow VMS never delays alerts
+    fn bookmark_recordings(&self, home_id: &str, incident_id: u64, decision: &AlertDecision, camera: &str) {
+        let Some(vms) = &self.vms else {
+            return;
+        };
+        let context = BookmarkContext {
+            home_id: home_id.to_string(),
+            incident_id,
+            camera: camera.to_string(),
+            decision: decision.clone(),
+            at: self.clock.now(),
+        };
+        let due = vms.plan(&context);
+        if due.is_empty() {
+            return;
+        }
+        let vms = vms.clone();
+        tokio::spawn(async move {
+            for (integration, vms_camera) in due {
+                vms.send(&integration, &vms_camera, &context).await;
+            }
+        });
+    }
+
+    // Report the alarm to the home's central station, in the background like the VMS bookmarks
+    fn report_to_central_station(&self, home_id: &str, incident_id: u64, decision: &AlertDecision, camera: &str) {
+        let Some(exporter) = &self.central_station else {
+            return;
+        };
+        let context = AlarmContext {
+            home_id: home_id.to_string(),
+            incident_id,
+            camera: camera.to_string(),
+            decision: decision.clone(),
+            at: self.clock.now(),
+        };
+        let Some((station, event)) = exporter.plan(&context) else {
+            return;
+        };
+        let exporter = exporter.clone();
+        tokio::spawn(async move {
+            exporter.report(&station, event, context.incident_id, context.at).await;
+        });
+    }
+
+    // Append the incident's latest assessment, after any overrides, to its durable series
+    async fn record_probability(&self, home_id: &str, incident_id: u64, event_id: Option<Uuid>, source: PointSource, at: DateTime<Utc>) {
+        let Some(history) = &self.probability_history else {
+            return;
+        };
+        let Some(incident) = self.thinking_ai.find_incident(home_id, incident_id) else {
+            return;
+        };
+        let Some(latest) = incident.probability_trace.last() else {
+            return;
+        };
+        let point = ProbabilityPoint {
+            at,
+            event_id,
+            event_count: latest.event_count,
+            fused_llr: latest.fused_llr,
+            calibrated_probability: latest.calibrated_probability,
+            decision: latest.decision.clone(),
+            source,
+        };
+        if let Err(e) = history.record(home_id, incident_id, point).await {
+            warn!("Probability of incident {} not recorded: {}", incident_id, e);
+        }
+    }
+
+    // An entry attempt in the new event settles the home's open escalation windows, then
+    // the incident's base rate and latest fused probability are recorded for scoring
+    fn record_predictions(&self, home_id: &str, incident_id: u64, new_event: bool, at: DateTime<Utc>) {
+        let (Some(scorer), Some(incident)) = (&self.prediction_scoring, self.thinking_ai.find_incident(home_id, incident_id)) else {
+            return;
+        };
+        let entry_threshold = self.escalation.as_ref().map_or(1.0, |m| m.config().entry_llr_threshold);
+        if new_event && incident.events.last().is_some_and(|e| e.evidence.llr_entry >= entry_threshold) {
+            scorer.record_entry_attempt(home_id, at);
+        }
+        let prior = sigmoid(self.thinking_ai.prior_logit_at(home_id, incident.started_at));
+        scorer.record(home_id, incident_id, PredictionComponent::Prior, prior, None, at);
+        if let Some(latest) = incident.probability_trace.last() {
+            scorer.record(home_id, incident_id, PredictionComponent::Fusion, latest.calibrated_probability, None, at);
+        }
+    }
+
+    /// Close or dismiss an incident by hand
+    pub fn close_incident(&mut self, home_id: &str, incident_id: u64, status: IncidentStatus, reason: &str) -> Result<IncidentTransition, LifecycleError> {
+        let transition = self.thinking_ai.close_incident(home_id, incident_id, status, self.clock.now().timestamp() as f64, reason)?;
+        self.dispatch_transitions();
+        Ok(transition)
+    }
+
+    /// Merge `source_id` into `target_id` and return the re-scored combined incident
+    pub fn merge_incidents(&mut self, home_id: &str, target_id: u64, source_id: u64) -> Result<Incident, LifecycleError> {
+        self.thinking_ai.merge_incidents(home_id, target_id, source_id, self.clock.now().timestamp() as f64)?;
+        self.dispatch_transitions();
+        self.thinking_ai.find_incident(home_id, target_id).ok_or(LifecycleError::NotFound(target_id))
+    }
+
+    /// Split events out of an incident; returns the re-scored (original, split-off) incidents
+    pub fn split_incident(&mut self, home_id: &str, incident_id: u64, event_indices: &[usize]) -> Result<(Incident, Incident), LifecycleError> {
+        let (_, split) = self.thinking_ai.split_incident(home_id, incident_id, event_indices)?;
+        self.dispatch_transitions();
+        let original = self.thinking_ai.find_incident(home_id, incident_id).ok_or(LifecycleError::NotFound(incident_id))?;
+        let split = self.thinking_ai.find_incident(home_id, split.incident_id).ok_or(LifecycleError::NotFound(split.incident_id))?;
+        Ok((original, split))
+    }
+
+    /// Expire incidents that have gone quiet for longer than the TTL
+    pub fn expire_incidents(&mut self, now: DateTime<Utc>) {
+        self.thinking_ai.expire_incidents(now.timestamp() as f64);
+        if let Some(tracker) = &self.tracker {
+            for transition in tracker.sweep(now.timestamp() as f64) {
+                info!("Track {} in {}: {:?} -> Lost ({})", transition.track_id, transition.home_id, transition.from, transition.message);
+            }
+        }
+        if let Some(graph) = &self.zone_graph {
+            graph.forget_before(now.timestamp() as f64 - graph.config().max_gap_s);
+        }
+        self.dispatch_transitions();
+    }
+
+    // Hand recorded transitions to the hooks without holding up event processing
+    fn dispatch_transitions(&mut self) {
+        let transitions = self.thinking_ai.take_transitions();
+        if transitions.is_empty() || self.lifecycle_hooks.is_empty() {
+            return;
+        }
+        let hooks = self.lifecycle_hooks.clone();
+        tokio::spawn(async move {
+            for transition in &transitions {
+                for hook in &hooks {
+                    hook.on_transition(transition).await;
+                }
+            }
+        });
+    }
+
+    /// Open incidents across all homes, for monitoring dashboards
+    pub fn open_incidents(&self) -> Vec<(String, Incident)> {
+        self.thinking_ai.open_incidents()
+    }
+
+    /// Look up one incident within a home
+    pub fn find_incident(&self, home_id: &str, incident_id: u64) -> Option<Incident> {
+        self.thinking_ai.find_incident(home_id, incident_id)
+    }
+
+    /// One of an incident's snapshots with the camera that took it (the camera of the
+    /// incident's latest event unless given), from the preload cache or fetched again
+    pub async fn incident_snapshot(&self, home_id: &str, incident_id: u64, index: usize, camera_id: Option<String>) -> Result<IncidentSnapshot, PipelineError> {
+        let incident = self.thinking_ai.find_incident(home_id, incident_id)
+            .ok_or(PipelineError::IncidentNotFound(incident_id))?;
+        let url = incident.snapshot_urls.get(index)
+            .cloned()
+            .ok_or_else(|| PipelineError::SnapshotUnavailable(format!("incident {} has no snapshot {}", incident_id, index)))?;
+        let camera = camera_id
+            .or_else(|| incident.events.last().map(|e| e.cam.clone()))
+            .unwrap_or_default();
+        let faces = incident.snapshot_faces.get(&url).cloned();
+        let snapshot = |image: Bytes| IncidentSnapshot { camera: camera.clone(), image, faces: faces.clone() };
+        if let Some(bytes) = self.image_preloader.get_cached_image(&url).await {
+            return Ok(snapshot(bytes));
+        }
+        match self.encryption.as_ref().map(|store| store.image(home_id, &url)) {
+            Some(Ok(Some(image))) => return Ok(snapshot(Bytes::from(image))),
+            Some(Err(e)) => warn!("Stored snapshot for incident {} unreadable: {}", incident_id, e),
+            _ => {}
+        }
+        let image = match &self.cameras {
+            Some(cameras) => self.image_preloader.download_snapshot(cameras, home_id, &camera, url, Uuid::nil()).await,
+            None => self.image_preloader.download_image_sync(url, Uuid::nil()).await,
+        };
+        image.map(snapshot).map_err(|e| PipelineError::SnapshotUnavailable(e.to_string()))
+    }
+
+    /// Homes that have sent events since start or restore
+    pub fn homes(&self) -> Vec<String> {
+        self.thinking_ai.homes()
+    }
+
+    /// Every stored incident for a home, open or closed, for history queries
+    pub fn home_incidents(&self, home_id: &str) -> Vec<Incident> {
+        self.thinking_ai.home_incidents(home_id).into_iter().map(|e| e.incident).collect()
+    }
+
+    /// The home's high-activity meta-incident from load shedding, if it has had one
+    pub fn high_activity(&self, home_id: &str) -> Option<HighActivityIncident> {
+        self.load_shedding.as_ref().and_then(|s| s.high_activity(home_id))
+    }
+
+    /// Replay a home's stored incidents under a candidate config and compare the alerts with the current one
+    pub fn what_if(&self, home_id: &str, candidate: &ThinkingAIConfig, labels: &HashMap<u64, IncidentLabel>) -> WhatIfReport {
+        let incidents = self.thinking_ai.home_incidents(home_id);
+        compare_configs(&incidents, labels, self.thinking_ai.config(), candidate)
+    }
+
+    /// Create a guest profile, enrolling its face samples and issuing its access code through the pipeline's stores
+    pub fn create_guest(&self, home_id: &str, request: GuestProfileRequest) -> Result<CreatedGuest, GuestError> {
+        let guests = self.guests.as_ref().ok_or(GuestError::Disabled)?;
+        guests.create(home_id, request, self.visitor_tokens.as_deref(), self.embeddings.as_deref(), self.clock.now())
+    }
+
+    /// Self-test: every synthetic scenario through every stage under the home's
+    /// shadow id, then the home's delivery channels at every severity; `deliver`
+    /// also sends a real test message to each SMS, email and webhook destination
+    pub async fn self_test(&mut self, home_id: &str, user_id: &str, tier: SubscriptionTier, deliver: bool) -> SelfTestReport {
+        let started_at = self.clock.now();
+        let scenarios = self.run_self_test_scenarios(home_id, user_id, tier).await;
+        let (channels, problems) = self.check_delivery_channels(home_id, user_id, deliver).await;
+        SelfTestReport::new(home_id, started_at, scenarios, channels, problems)
+    }
+
+    /// Process one event per synthetic scenario with a stage probe around the chain
+    pub async fn run_self_test_scenarios(&mut self, home_id: &str, user_id: &str, tier: SubscriptionTier) -> Vec<ScenarioCheck> {
+        let shadow = shadow_home(home_id);
+        let probe = Arc::new(StageProbe::default());
+        // Outermost, so the probe sees every stage the chain enters; removed again below
+        self.middleware.insert(0, probe.clone());
+        self.middleware.insert(1, Arc::new(SyntheticEvents::self_test()));
+
+        let mut scenarios = Vec::new();
+        for scenario in SyntheticScenario::ALL {
+            let event = scenario.event(&shadow, user_id, self.clock.now());
+            let event_id = event.event_id;
+            probe.watch(event_id);
+            let stages = match self.process_event_once(event, tier.clone(), "self-test", 1.0).await {
+                Ok(processed) => probe.stages(event_id, None, Some(&processed.result_summary)),
+                Err(e) => probe.stages(event_id, Some(&e.to_string()), None),
+            };
+            let (decision, probability) = probe.outcome(event_id);
+            scenarios.push(ScenarioCheck::new(scenario, event_id, stages, decision, probability));
+        }
+        self.middleware.drain(..2);
+        info!("Self-test for {}: {} scenario(s) run under {}", home_id, scenarios.len(), shadow);
+        scenarios
+    }
+
+    /// Each recipient and channel the home would notify, what their preferences
+    /// would do at each severity right now, and whether the channel has somewhere
+    /// to deliver to; also returns problems outside the matrix
+    pub async fn check_delivery_channels(&self, home_id: &str, user_id: &str, deliver: bool) -> (Vec<ChannelCheck>, Vec<String>) {
+        let Some(router) = &self.notifications else {
+            return (Vec::new(), vec!["notifications are not configured on this server".to_string()]);
+        };
+        let now = self.clock.now();
+        let locale = router.templates().language(home_id);
+        let (title, body) = (t(&locale, "self-test-title", &[]), t(&locale, "self-test-body", &[]));
+        let test_notification = |severity| Notification {
+            home_id: home_id.to_string(),
+            severity,
+            title: title.clone(),
+            body: body.clone(),
+            created_at: now,
+            incident_id: None,
+            zone: None,
+            probability: None,
+            entity: None,
+            entity_status: None,
+            thumbnail_url: None,
+            alert: None,
+        };
+
+        let by_severity: Vec<_> = CHECKED_SEVERITIES.iter().map(|&s| (s, self.recipients(home_id, user_id, s))).collect();
+        let mut targets: Vec<(Option<String>, DeliveryChannel)> = Vec::new();
+        for (user, channel) in by_severity.iter().flat_map(|(_, r)| r.iter().flat_map(|(u, cs)| cs.iter().map(move |c| (u.clone(), c.clone())))) {
+            if !targets.contains(&(user.clone(), channel.clone())) {
+                targets.push((user, channel));
+            }
+        }
+        let webhook_endpoints = match router.webhooks() {
+            Some(dispatcher) => dispatcher.list_endpoints(home_id).await.iter().filter(|e| e.accepts(WebhookEventType::Alert)).count(),
+            None => 0,
+        };
+        // Webhooks are a home-level destination, not in any resident's channel list
+        if router.webhooks().is_some() && !targets.iter().any(|(_, c)| *c == DeliveryChannel::Webhook) {
+            targets.push((None, DeliveryChannel::Webhook));
+        }
+
+        let mut checks = Vec::new();
+        let mut webhook_tested = false;
+        for (user, channel) in targets {
+            let routes = by_severity.iter().map(|(severity, recipients)| {
+                let listed = user.is_none() || recipients.iter().any(|(u, cs)| *u == user && cs.contains(&channel));
+                SeverityRoute {
+                    severity: *severity,
+                    decision: listed.then(|| router.decide(&test_notification(*severity), user.as_deref(), &channel, now)),
+                }
+            }).collect();
+            let configured = Self::channel_configured(router, home_id, user.as_deref(), &channel, webhook_endpoints);
+            let mut check = ChannelCheck::new(user.clone(), channel.clone(), configured, routes);
+            if deliver && check.configured && !(channel == DeliveryChannel::Webhook && webhook_tested) {
+                webhook_tested |= channel == DeliveryChannel::Webhook;
+                if let Some(outcome) = Self::send_test_message(router, home_id, user.as_deref(), &channel, &title, &body).await {
+                    check.record_delivery(outcome);
+                }
+            }
+            checks.push(check);
+        }
+        (checks, Vec::new())
+    }
+
+    // Whether a channel has somewhere to deliver to; Push, WebSocket and the dashboard reach the app directly
+    fn channel_configured(router: &NotificationRouter, home_id: &str, user_id: Option<&str>, channel: &DeliveryChannel, webhook_endpoints: usize) -> Result<(), String> {
+        match channel {
+            DeliveryChannel::SMS => {
+                let sms = router.sms().filter(|s| s.provider().is_some()).ok_or("no SMS provider is configured")?;
+                match user_id.and_then(|u| sms.contact(home_id, u)) {
+                    None => Err("no phone number registered".to_string()),
+                    Some(contact) if contact.opted_out => Err("opted out of SMS".to_string()),
+                    Some(_) => Ok(()),
+                }
+            }
+            DeliveryChannel::Email => {
+                let email = router.email().filter(|e| e.provider().is_some()).ok_or("no email provider is configured")?;
+                match user_id.and_then(|u| email.contact(home_id, u)) {
+                    None => Err("no email address registered".to_string()),
+                    Some(contact) if contact.suppressed => Err("address suppressed after a bounce or complaint".to_string()),
+                    Some(_) => Ok(()),
+                }
+            }
+            DeliveryChannel::Webhook if webhook_endpoints == 0 => Err("no webhook endpoint subscribed to alerts".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    // Send one real test message on a channel that leaves the server; None for in-app channels
+    async fn send_test_message(router: &NotificationRouter, home_id: &str, user_id: Option<&str>, channel: &DeliveryChannel, title: &str, body: &str) -> Option<Result<(), String>> {
+        match (channel, user_id) {
+            (DeliveryChannel::SMS, Some(user)) => {
+                Some(router.sms()?.deliver(home_id, user, title, body).await.map(|_| ()).map_err(|e| e.to_string()))
+            }
+            (DeliveryChannel::Email, Some(user)) => {
+                Some(router.email()?.deliver(home_id, user, &render_alert(title, body)).await.map(|_| ()).map_err(|e| e.to_string()))
+            }
+            (DeliveryChannel::Webhook, _) => {
+                let records = router.webhooks()?.dispatch_test(home_id, title, body).await;
+                Some(match records.into_iter().find(|r| !r.success) {
+                    Some(failed) => Err(format!("{}: {}", failed.url, failed.error.unwrap_or_else(|| format!("status {:?}", failed.status_code)))),
+                    None => Ok(()),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Attach a household note and tags to an incident; trust tags pin its person as trusted, watch tags revoke their trust
+    pub fn annotate_incident(&self, home_id: &str, incident_id: u64, author: &str, request: AnnotationRequest) -> Result<IncidentAnnotation, PipelineError> {
+        let annotations = self.annotations.as_ref()
+            .ok_or_else(|| PipelineError::AnnotationError(AnnotationError::Invalid("annotations are not enabled".to_string())))?;
+        let incident = self.thinking_ai.find_incident(home_id, incident_id)
+            .ok_or(PipelineError::IncidentNotFound(incident_id))?;
+        let entity = Some(incident.person_session_id.as_str()).filter(|e| !e.is_empty());
+        let now = self.clock.now();
+        let annotation = annotations.annotate(home_id, incident_id, entity, author, request, now)
+            .map_err(PipelineError::AnnotationError)?;
+        if let (Some(trust), Some(entity)) = (&self.trust, entity) {
+            let updated = match annotation.effect() {
+                Some(TagEffect::Trust) => trust.mark_trusted(home_id, entity, author, annotation.note.clone(), now).map(|_| ()),
+                Some(TagEffect::Watch) => trust.revoke(home_id, entity, author, annotation.note.clone(), now).map(|_| ()),
+                None => Ok(()),
+            };
+            if let Err(e) = updated.or_else(|e| if matches!(e, TrustError::NotFound(_)) { Ok(()) } else { Err(e) }) {
+                warn!("Trust for {} in {} not updated from annotation: {}", entity, home_id, e);
+            }
+        }
+        Ok(annotation)
+    }
+
+    /// Learn from a labeled outcome (homeowner feedback or operator disposition) for an incident
+    pub fn record_outcome(&self, home_id: &str, incident_id: u64, label: IncidentLabel, source: OutcomeSource) -> Result<ChannelWeights, PipelineError> {
+        if let Some(scorer) = &self.prediction_scoring {
+            scorer.resolve_incident(home_id, incident_id, label == IncidentLabel::Threat, self.clock.now());
+        }
+        if let (Some(model), Some(incident)) = (&self.escalation, self.thinking_ai.find_incident(home_id, incident_id)) {
+            model.record_outcome(&incident, label);
+        }
+        if let (Some(priors), Some(incident)) = (&self.prior_model, self.thinking_ai.find_incident(home_id, incident_id)) {
+            priors.record_feedback(home_id, &incident, label);
+        }
+        if let (Some(thresholds), Some(incident)) = (&self.thresholds, self.thinking_ai.find_incident(home_id, incident_id)) {
+            let alerted = incident.probability_trace.iter().any(|p| !matches!(p.decision, AlertDecision::Wait | AlertDecision::Ignore));
+            match thresholds.learn(home_id, alerted, label, self.clock.now()) {
+                Ok(Some(version)) => self.thinking_ai.set_alert_threshold(home_id, version.effective_logit),
+                Ok(None) => {}
+                Err(e) => warn!("Learned threshold for {} not saved: {}", home_id, e),
+            }
+        }
+        if let (Some(trust), Some(incident)) = (&self.trust, self.thinking_ai.find_incident(home_id, incident_id)) {
+            let now = self.clock.now();
+            let updated = match label {
+                IncidentLabel::Benign => trust.record_benign_visit(home_id, &incident.person_session_id, now),
+                IncidentLabel::Threat => trust.record_threat(home_id, &incident.person_session_id, now),
+            };
+            if let Err(e) = updated {
+                warn!("Trust for {} in {} not updated: {}", incident.person_session_id, home_id, e);
+            }
+        }
+        let (learner, persist_to) = self.weight_learner.as_ref()
+            .ok_or_else(|| PipelineError::LearningError("Weight learning not enabled".to_string()))?;
+        let incident = self.thinking_ai.find_incident(home_id, incident_id)
+            .ok_or(PipelineError::IncidentNotFound(incident_id))?;
+
+        let config = self.thinking_ai.config();
+        let evidence = incident.fused_evidence(config.pos_cap, config.neg_cap);
+        let prior_logit = self.thinking_ai.prior_logit_at(home_id, incident.started_at);
+        learner.record_outcome(home_id, &evidence, prior_logit, label, source, self.clock.now());
+
+        if let Some(path) = persist_to {
+            if let Err(e) = learner.save(path) {
+                warn!("Failed to persist learned weights to {}: {}", path.display(), e);
+            }
+        }
+
+        // The same label grades each sensor that contributed to the incident
+        if let Some((model, persist_to)) = &self.sensor_reliability {
+            model.record_outcome(home_id, incident, label, self.clock.now());
+            if let Some(path) = persist_to {
+                if let Err(e) = model.save(path) {
+                    warn!("Failed to persist sensor reliability to {}: {}", path.display(), e);
+                }
+            }
+        }
+        Ok(learner.weights(home_id))
+    }
+
+    /// How a home's alert threshold has drifted from the default, version by version
+    pub fn threshold_drift(&self, home_id: &str) -> Option<ThresholdDrift> {
+        self.thresholds.as_ref().map(|t| t.drift(home_id))
+    }
+
+    /// Put a home's threshold back to the default, dropping what it learned
+    pub fn reset_thresholds(&self, home_id: &str, actor: &str) -> Result<ThresholdVersion, PipelineError> {
+        let thresholds = self.thresholds.as_ref()
+            .ok_or_else(|| PipelineError::LearningError("Adaptive thresholds not enabled".to_string()))?;
+        let version = thresholds.reset(home_id, actor, self.clock.now())
+            .map_err(|e| PipelineError::LearningError(e.to_string()))?;
+        self.thinking_ai.set_alert_threshold(home_id, version.effective_logit);
+        Ok(version)
+    }
+
+    /// Learned reliability of each of a home's sensors
+    pub fn sensor_reliability(&self, home_id: &str) -> Option<Vec<SensorReliability>> {
+        self.sensor_reliability.as_ref().map(|(model, _)| model.report(home_id))
+    }
+
+    /// How often answering each question type changed a decision, and the factor applied to its estimate
+    pub fn question_value(&self) -> Vec<crate::thinking::QuestionValue> {
+        self.thinking_ai.voi_calibrator().report()
+    }
+
+    /// Weights currently applied to a home's evidence channels
+    pub fn channel_weights(&self, home_id: &str) -> Option<ChannelWeights> {
+        self.weight_learner.as_ref().map(|(learner, _)| learner.weights(home_id))
+    }
+
+    /// Snapshot incident stores, entity registries and learned calibrations
+    /// (plus the daemon config file, if given) into one archive
+    pub fn backup(&self, config: Option<&std::path::Path>) -> Result<Vec<u8>, PipelineError> {
+        let mut builder = BackupBuilder::new();
+        if let Some(store) = &self.encryption {
+            builder = builder.with_encryption(store.keyring().clone());
+        }
+        for (home_id, snapshot) in self.thinking_ai.incident_snapshots() {
+            builder.add_incident_store(&home_id, &snapshot).map_err(PipelineError::BackupError)?;
+        }
+        if let Some(household) = &self.household {
+            builder.add_json(BackupSection::EntityRegistry, RESIDENTS_PATH, &household.all_residents()).map_err(PipelineError::BackupError)?;
+        }
+        if let Some(cameras) = &self.cameras {
+            builder.add_json(BackupSection::EntityRegistry, CAMERA_PINS_PATH, &cameras.all_pins()).map_err(PipelineError::BackupError)?;
+        }
+        if let Some(devices) = &self.devices {
+            builder.add_json(BackupSection::EntityRegistry, DEVICE_KEYS_PATH, &devices.all_devices()).map_err(PipelineError::BackupError)?;
+        }
+        if let Some((learner, _)) = &self.weight_learner {
+            let json = learner.to_json().map_err(|e| PipelineError::BackupError(BackupError::Archive(e.to_string())))?;
+            builder.add(BackupSection::Calibration, CHANNEL_WEIGHTS_PATH, None, json);
+        }
+        if let Some((model, _)) = &self.sensor_reliability {
+            let json = model.to_json().map_err(|e| PipelineError::BackupError(BackupError::Archive(e.to_string())))?;
+            builder.add(BackupSection::Calibration, SENSOR_RELIABILITY_PATH, None, json);
+        }
+        if let Some(path) = config {
+            builder.add_config_file(path).map_err(PipelineError::BackupError)?;
+        }
+        builder.finish().map_err(PipelineError::BackupError)
+    }
+
+    /// Replace in-memory state with a backup's. The whole archive is validated
+    /// first; sections for components this pipeline was built without are skipped.
+    /// Restored calibrations are written to their persistence paths. Archives
+    /// with encrypted incident stores need `BackupArchive::with_keyring` first.
+    pub fn restore(&mut self, archive: &BackupArchive) -> Result<usize, PipelineError> {
+        archive.validate().map_err(PipelineError::BackupError)?;
+
+        let stores = archive.incident_stores().map_err(PipelineError::BackupError)?;
+        let restored = stores.len();
+        for (home_id, snapshot) in stores {
+            self.thinking_ai.restore_incident_store(&home_id, snapshot);
+        }
+        if let (Some(household), Some(residents)) = (&self.household, archive.residents().map_err(PipelineError::BackupError)?) {
+            household.restore_residents(residents);
+        }
+        if let (Some(cameras), Some(pins)) = (&self.cameras, archive.camera_pins().map_err(PipelineError::BackupError)?) {
+            for pin in cameras.all_pins() {
+                cameras.remove(&pin.home_id, &pin.camera_id);
+            }
+            for pin in pins {
+                cameras.register(pin).map_err(|e| PipelineError::BackupError(BackupError::invalid(CAMERA_PINS_PATH, e)))?;
+            }
+        }
+        if let (Some(devices), Some(keys)) = (&self.devices, archive.device_keys().map_err(PipelineError::BackupError)?) {
+            devices.restore(keys).map_err(|e| PipelineError::BackupError(BackupError::invalid(DEVICE_KEYS_PATH, e)))?;
+        }
+        if let (Some((learner, path)), Some(json)) = (&self.weight_learner, archive.text(CHANNEL_WEIGHTS_PATH).map_err(PipelineError::BackupError)?) {
+            learner.restore_json(json).map_err(|e| PipelineError::BackupError(BackupError::invalid(CHANNEL_WEIGHTS_PATH, e)))?;
+            if let Some(path) = path {
+                learner.save(path).map_err(|e| PipelineError::BackupError(BackupError::Io(e)))?;
+            }
+        }
+        if let (Some((model, path)), Some(json)) = (&self.sensor_reliability, archive.text(SENSOR_RELIABILITY_PATH).map_err(PipelineError::BackupError)?) {
+            model.restore_json(json).map_err(|e| PipelineError::BackupError(BackupError::invalid(SENSOR_RELIABILITY_PATH, e)))?;
+            if let Some(path) = path {
+                model.save(path).map_err(|e| PipelineError::BackupError(BackupError::Io(e)))?;
+            }
+        }
+        info!("Restored {} incident store(s) from backup taken {}", restored, archive.manifest().created_at);
+        Ok(restored)
+    }
+
+    /// Export snapshots, narrative, probability trace and decisions for an incident as a ZIP
+    pub async fn export_incident_evidence(&self, home_id: &str, incident_id: u64) -> Result<EvidenceBundle, PipelineError> {
+        let incident = self.thinking_ai.find_incident(home_id, incident_id)
+            .ok_or(PipelineError::EvidenceExportError(BundleError::IncidentNotFound(incident_id)))?;
+        export_incident_bundle(home_id, &incident, &self.image_preloader, self.encryption.as_deref(), self.clock.now()).await
+            .map_err(PipelineError::EvidenceExportError)
+    }
+
+    // NEW: Generate morning summary for a home
+    pub async fn generate_morning_summary(&self, home_id: &str) -> Result<Option<crate::overnight::MorningSummary>, PipelineError> {
+        if let Some(overnight_mgr) = &self.overnight_manager {
+            let locale = self.locale(home_id);
+            let mut summary = overnight_mgr.generate_morning_summary(home_id, &locale).await
+                .map_err(|e| PipelineError::OvernightError(e.to_string()))?;
+            if self.vacations.as_ref().and_then(|v| v.active(home_id, self.clock.now())).is_some() {
+                summary.narrative = t(&locale, "summary-vacation", &[("summary", summary.narrative)]);
+            }
+            // Each highlight shows the latest snapshot of the incident it belongs to
+            let incidents = self.thinking_ai.home_incidents(home_id);
+            for highlight in &mut summary.highlights {
+                let ts = highlight.timestamp.timestamp() as f64;
+                highlight.thumbnail_url = incidents.iter()
+                    .map(|entry| &entry.incident)
+                    .find(|i| i.started_at - 1.0 <= ts && ts <= i.last_updated + 1.0)
+                    .and_then(|i| i.snapshot_urls.last().cloned());
+            }
+            Ok(Some(summary))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Snapshot bytes for a summary's highlights, by URL, from the preload
+    /// cache or the encrypted store; nothing is fetched from cameras
+    pub async fn summary_thumbnails(&self, summary: &crate::overnight::MorningSummary) -> HashMap<String, Vec<u8>> {
+        let mut thumbnails = HashMap::new();
+        for url in summary.highlights.iter().filter_map(|h| h.thumbnail_url.as_ref()) {
+            if thumbnails.contains_key(url) {
+                continue;
+            }
+            let image = match self.image_preloader.get_cached_image(url).await {
+                Some(bytes) => Some(bytes.to_vec()),
+                None => self.encryption.as_ref().and_then(|store| store.image(&summary.home_id, url).ok().flatten()),
+            };
+            if let Some(image) = image {
+                thumbnails.insert(url.clone(), image);
+            }
+        }
+        thumbnails
+    }
+
+    // The home's locale for built-in text: its notification language, else English
+    fn locale(&self, home_id: &str) -> String {
+        self.notifications.as_ref()
+            .map_or_else(|| crate::i18n::DEFAULT_LOCALE.to_string(), |router| router.templates().language(home_id))
+    }
+
+    // Mark a delivered morning summary's events so they are not summarized again
+    pub async fn mark_morning_summary_delivered(&self, summary: &crate::overnight::MorningSummary) -> Result<(), PipelineError> {
+        match &self.overnight_manager {
+            Some(overnight_mgr) => overnight_mgr.mark_summary_delivered(summary).await
+                .map_err(|e| PipelineError::OvernightError(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    // NEW: Update overnight configuration for a home
+    pub async fn update_overnight_config(&self, config: crate::overnight::OvernightConfig) -> Result<(), PipelineError> {
+        if let Some(overnight_mgr) = &self.overnight_manager {
+            overnight_mgr.update_config(config).await
+                .map_err(|e| PipelineError::OvernightError(e.to_string()))
+        } else {
+            Err(PipelineError::OvernightError("Overnight system not enabled".to_string()))
+        }
+    }
+
+    /// Apply an onboarding config: threshold and zone priors to scoring, the
+    /// calendar to environment enrichment, and the review window to overnight
+    /// review when it is enabled
+    pub async fn apply_home_config(&mut self, config: &crate::onboarding::HomeConfig) -> Result<(), PipelineError> {
+        let threshold_logit = match &self.thresholds {
+            Some(thresholds) => {
+                if let Err(e) = thresholds.onboard(&config.home_id, config.alert_threshold_logit, config.created_at, self.clock.now()) {
+                    warn!("Threshold history for {} not saved: {}", config.home_id, e);
+                }
+                thresholds.current(&config.home_id).effective_logit
+            }
+            None => config.alert_threshold_logit,
+        };
+        self.thinking_ai.set_alert_threshold(&config.home_id, threshold_logit);
+        self.thinking_ai.set_zone_priors(&config.home_id, config.camera_priors());
+        self.thinking_ai.set_camera_overlaps(&config.home_id, config.camera_overlaps.clone());
+        self.thinking_ai.set_uncertainty_policy(&config.home_id, config.uncertainty_policy());
+        self.thinking_ai.set_calendar(&config.home_id, config.calendar.clone());
+        if let Some(tracker) = &self.tracker {
+            let door_cameras = config.zones.iter()
+                .filter(|z| matches!(z.kind, crate::onboarding::ZoneKind::FrontDoor | crate::onboarding::ZoneKind::BackDoor))
+                .flat_map(|z| z.cameras.iter().cloned())
+                .collect();
+            tracker.set_door_cameras(&config.home_id, door_cameras);
+        }
+        if let Some(engine) = &self.escalation_rules {
+            let classes = config.zones.iter()
+                .flat_map(|z| {
+                    let class = match z.kind {
+                        crate::onboarding::ZoneKind::Interior => ZoneClass::Interior,
+                        _ => ZoneClass::Exterior,
+                    };
+                    z.cameras.iter().map(move |c| (c.clone(), class))
+                })
+                .collect();
+            engine.set_zone_classes(&config.home_id, classes);
+        }
+        if let Some(graph) = &self.zone_graph {
+            let camera_zones = config.zones.iter()
+                .flat_map(|z| z.cameras.iter().map(move |c| (c.clone(), z.zone.clone())))
+                .collect();
+            graph.set_layout(&config.home_id, camera_zones);
+        }
+        if let Some(baseline) = &self.activity_baseline {
+            let camera_zones = config.zones.iter()
+                .flat_map(|z| z.cameras.iter().map(move |c| (c.clone(), z.zone.clone())))
+                .collect();
+            baseline.set_layout(&config.home_id, camera_zones, &config.calendar.timezone);
+        }
+        if let Some(priors) = &self.prior_model {
+            let camera_zones = config.zones.iter()
+                .flat_map(|z| z.cameras.iter().map(move |c| (c.clone(), z.zone.clone())))
+                .collect();
+            priors.set_layout(&config.home_id, camera_zones, &config.calendar.timezone);
+        }
+        if let Some(policy) = &self.snapshot_priority {
+            let zones = config.zones.iter()
+                .flat_map(|z| z.cameras.iter().map(move |c| (c.clone(), z.kind)))
+                .collect();
+            policy.set_layout(&config.home_id, zones, &config.calendar.timezone);
+        }
+        if let Some(shedder) = &self.load_shedding {
+            let priorities = config.zones.iter()
+                .flat_map(|z| z.cameras.iter().map(move |c| (c.clone(), CameraPriority::from(z.kind))))
+                .collect();
+            shedder.set_camera_priorities(&config.home_id, priorities);
+        }
+        if let Some(enricher) = &self.environment {
+            enricher.register_home(&config.home_id, config.calendar.clone());
+        }
+        if let Some(hub) = &self.federation {
+            let active = hub.member(&config.home_id).await.is_some_and(|m| m.revoked_at.is_none());
+            match (config.share_with_neighbors, config.calendar.latitude, config.calendar.longitude) {
+                (true, Some(latitude), Some(longitude)) if !active => {
+                    hub.join(&config.home_id, latitude, longitude, TrustLevel::Neighbor).await;
+                }
+                (false, _, _) if active => hub.revoke(&config.home_id).await
+                    .unwrap_or_else(|e| warn!("Federation opt-out for {} failed: {}", config.home_id, e)),
+                _ => {}
+            }
+        }
+        if self.overnight_manager.is_some() {
+            self.update_overnight_config(config.overnight.clone()).await?;
+        }
+        Ok(())
+    }
+
+    // NEW: Get overnight configuration for a home
+    pub async fn get_overnight_config(&self, home_id: &str) -> Option<crate::overnight::OvernightConfig> {
+        if let Some(overnight_mgr) = &self.overnight_manager {
+            overnight_mgr.get_config(home_id).await
+        } else {
+            None
+        }
+    }
+}
+
+// UPDATED: Custom errors for the pipeline
+#[derive(Error, Debug)]
+pub enum PipelineError {
+    #[error("Failed to submit event to VPS: {0}")]
+    VpsSubmissionError(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("VPS processing error: {0}")]
+    VpsError(String),
+
+    #[error("Overnight review system error: {0}")]
+    OvernightError(String), // NEW: Overnight system errors
+
+    #[error("Event {0} is already being processed")]
+    DuplicateInFlight(Uuid),
+
+    #[error("Idempotency layer error: {0}")]
+    IdempotencyError(String),
+
+    #[error("Evidence export failed: {0}")]
+    EvidenceExportError(BundleError),
+
+    #[error("{0}")]
+    FeatureGated(FeatureGateError),
+
+    #[error("Incident {0} not found")]
+    IncidentNotFound(u64),
+
+    #[error("Learning error: {0}")]
+    LearningError(String),
+
+    #[error("Edge inference error: {0}")]
+    EdgeInferenceError(String),
+
+    #[error("Event signature rejected: {0}")]
+    SignatureError(SigningError),
+
+    #[error("Backup error: {0}")]
+    BackupError(BackupError),
+
+    #[error("Invalid event input: {0}")]
+    SanitizationError(SanitizationError),
+
+    #[error("Snapshot unavailable: {0}")]
+    SnapshotUnavailable(String),
+
+    #[error("Annotation rejected: {0}")]
+    AnnotationError(AnnotationError),
+
+    #[error("An unknown pipeline error occurred")]
+    Unknown,
+}
+
+// A code's evidence replaces the event's token channel, and its identity channel when verified
+fn apply_token_evidence(thinking_event: &mut Event, token: &TokenEvidence) {
+    thinking_event.token = token.token_id.map(|id| id.to_string());
+    thinking_event.evidence.llr_token = token.token_llr;
+    if let Some(identity) = token.identity_llr {
+        thinking_event.evidence.llr_identity = identity;
+    }
 }
 
 impl Default for PipelineConfig {
@@ -394,6 +2689,10 @@ impl Default for PipelineConfig {
             tier_routing,
             thinking_ai_config: ThinkingAIConfig::default(),
             overnight_enabled: true, // NEW: Default to enabled
+            idempotency: IdempotencyConfig::default(),
+            feature_gate: FeatureGate::default(),
+            vps_enabled: true,
+            sanitization: SanitizationConfig::default(),
         }
     }
 }
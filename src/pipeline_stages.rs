@@ -0,0 +1,195 @@
+// src/pipeline_stages.rs
+
+// Named stages of event processing and the middleware hooks around them.
+// EventPipeline runs every event through the same fixed chain:
+//
+//   sanitize -> enrich -> image -> vps -> thinking -> rules -> notify
+//
+// Middleware sees the in-flight run before and after each stage, much like a
+// tower layer around a service. Before a stage it can let it run, skip it, or
+// halt the event altogether (a compliance filter dropping events from a
+// camera it may not record, say); after a stage it can inspect or adjust what
+// the stage produced. Middleware is added in code with
+// `EventPipeline::with_middleware`, or by name through a `MiddlewareRegistry`
+// of factories; the server builds the names listed in NOVIN_STAGE_MIDDLEWARE.
+// Built in are `stage_timing` and, for demo servers, `synthetic_events`.
+
+use crate::guest_access::GuestMatch;
+use crate::activity_baseline::BaselineDeviation;
+use crate::anti_spoofing::SpoofAssessment;
+use crate::self_test::SyntheticEvents;
+use crate::pipeline::{ProcessedEvent, ProcessingLevel, RawEvent, SubscriptionTier};
+use crate::thinking::{Event, ThinkingAIResult};
+use crate::vacation::VacationMode;
+use crate::visitor_tokens::TokenEvidence;
+use crate::vps_client::VpsProcessingResponse;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::info;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MiddlewareError {
+    #[error("Unknown stage middleware '{0}'")]
+    Unknown(String),
+
+    #[error("Middleware '{0}' is already registered")]
+    Duplicate(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Sanitize, // Extract evidence and repair non-finite or out-of-range inputs
+    Enrich,   // Visitor codes, occupancy, signature trust, learned weights and reliabilities
+    Image,    // Snapshot checks: tamper detection and pinned-host verification
+    Vps,      // Overnight suppression, VPS or on-device analysis, re-identification, tracking
+    Thinking, // Prior shifts and incident fusion
+    Rules,    // Adversarial second opinion, escalation rules and follow-up timers
+    Notify,   // Notifications, lifecycle hooks, SIEM export and vacation digest
+}
+
+impl Stage {
+    pub const ALL: [Stage; 7] = [Stage::Sanitize, Stage::Enrich, Stage::Image, Stage::Vps, Stage::Thinking, Stage::Rules, Stage::Notify];
+}
+
+/// What to do with the stage a middleware is about to see
+#[derive(Debug, Clone, PartialEq)]
+pub enum StageControl {
+    Continue,
+    Skip,         // Leave the stage out; later stages still run
+    Halt(String), // Stop processing the event; it is recorded as handled with this reason
+}
+
+/// One event on its way through the stages
+pub struct PipelineRun {
+    pub event: RawEvent,
+    pub tier: SubscriptionTier,
+    pub event_time: DateTime<Utc>,
+    pub thinking_event: Option<Event>,                // Built by Sanitize, fused by Thinking
+    pub data_quality: Vec<String>,                    // Inputs repaired along the way
+    pub snapshot_url: Option<String>,                 // Set by Image once the host is verified
+    pub vps_response: Option<VpsProcessingResponse>,
+    pub result: Option<ThinkingAIResult>,             // Set by Thinking, adjusted by Rules
+    pub notes: Vec<String>,                           // Appended to the processed event's summary
+    pub(crate) api_key: String,
+    pub(crate) processing_level: ProcessingLevel,
+    pub(crate) evidence_weight: f64,                  // Below 1 for unsigned events
+    pub(crate) token_evidence: Option<TokenEvidence>,
+    pub(crate) vacation: Option<VacationMode>,
+    pub(crate) guest: Option<GuestMatch>,             // Set by Vps when the person is on a guest profile
+    pub(crate) spoof: Option<SpoofAssessment>,        // Set by Vps when the face may be a photo or screen
+    pub(crate) baseline: Option<BaselineDeviation>,   // Set by Vps when the zone is far busier than usual
+    pub(crate) finished: Option<ProcessedEvent>,      // Set by a stage that ends the run early
+    pub(crate) heads_up: Option<uuid::Uuid>,          // Alert id of a delivered heads-up, for Notify to update
+}
+
+impl PipelineRun {
+    pub(crate) fn new(event: RawEvent, tier: SubscriptionTier, api_key: &str, processing_level: ProcessingLevel, evidence_weight: f64) -> Self {
+        let event_time = DateTime::from_timestamp(event.timestamp, 0).unwrap_or_else(Utc::now);
+        Self {
+            event,
+            tier,
+            event_time,
+            thinking_event: None,
+            data_quality: Vec::new(),
+            snapshot_url: None,
+            vps_response: None,
+            result: None,
+            notes: Vec::new(),
+            api_key: api_key.to_string(),
+            processing_level,
+            evidence_weight,
+            token_evidence: None,
+            vacation: None,
+            guest: None,
+            spoof: None,
+            baseline: None,
+            finished: None,
+            heads_up: None,
+        }
+    }
+
+    pub fn processing_level(&self) -> ProcessingLevel {
+        self.processing_level
+    }
+
+    /// Whether a stage has already produced the final result
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_some()
+    }
+}
+
+#[async_trait]
+pub trait StageMiddleware: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn before(&self, _stage: Stage, _run: &mut PipelineRun) -> StageControl {
+        StageControl::Continue
+    }
+
+    async fn after(&self, _stage: Stage, _run: &mut PipelineRun) {}
+}
+
+type MiddlewareFactory = Box<dyn Fn() -> Arc<dyn StageMiddleware> + Send + Sync>;
+
+/// Middleware constructors by name, for building the chain from configuration
+pub struct MiddlewareRegistry {
+    factories: HashMap<String, MiddlewareFactory>,
+}
+
+impl Default for MiddlewareRegistry {
+    fn default() -> Self {
+        let mut registry = Self { factories: HashMap::new() };
+        let _ = registry.register("stage_timing", || Arc::new(StageTiming::default()));
+        let _ = registry.register("synthetic_events", || Arc::new(SyntheticEvents::demo()));
+        registry
+    }
+}
+
+impl MiddlewareRegistry {
+    pub fn register(&mut self, name: &str, factory: impl Fn() -> Arc<dyn StageMiddleware> + Send + Sync + 'static) -> Result<(), MiddlewareError> {
+        if self.factories.contains_key(name) {
+            return Err(MiddlewareError::Duplicate(name.to_string()));
+        }
+        self.factories.insert(name.to_string(), Box::new(factory));
+        Ok(())
+    }
+
+    /// Instantiate the named middleware, in order
+    pub fn build(&self, names: &[String]) -> Result<Vec<Arc<dyn StageMiddleware>>, MiddlewareError> {
+        names.iter()
+            .map(|name| self.factories.get(name).map(|f| f()).ok_or_else(|| MiddlewareError::Unknown(name.clone())))
+            .collect()
+    }
+}
+
+/// Logs how long each stage took for each event
+#[derive(Default)]
+pub struct StageTiming {
+    started: Mutex<HashMap<(uuid::Uuid, Stage), Instant>>,
+}
+
+#[async_trait]
+impl StageMiddleware for StageTiming {
+    fn name(&self) -> &str {
+        "stage_timing"
+    }
+
+    async fn before(&self, stage: Stage, run: &mut PipelineRun) -> StageControl {
+        if let Ok(mut started) = self.started.lock() {
+            started.insert((run.event.event_id, stage), Instant::now());
+        }
+        StageControl::Continue
+    }
+
+    async fn after(&self, stage: Stage, run: &mut PipelineRun) {
+        let started = self.started.lock().ok().and_then(|mut s| s.remove(&(run.event.event_id, stage)));
+        if let Some(started) = started {
+            info!("Event {} stage {:?} took {:?}", run.event.event_id, stage, started.elapsed());
+        }
+    }
+}
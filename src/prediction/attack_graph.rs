@@ -0,0 +1,295 @@
+//! Attack path modeling over a home's zones
+//!
+//! Zones (street, driveway, side gate, back door, ...) are nodes and physical
+//! adjacency gives the edges. Each edge carries the probability that an
+//! intruder standing in one zone moves next to the other. Before any history
+//! that probability favours moving inward (towards entry points); observed
+//! zone sequences from past incidents then update it as a Dirichlet posterior,
+//! so well-worn routes dominate. Queries return the fewest-hop path and the
+//! most likely path (Dijkstra over -ln p) to each entry point.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AttackGraphError {
+    #[error("Unknown zone '{0}'")]
+    UnknownZone(String),
+
+    #[error("Zone '{0}' is defined twice")]
+    DuplicateZone(String),
+}
+
+// Ordered from outside in; moves to a higher role are the expected direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneRole {
+    Outside,  // Street, sidewalk
+    Approach, // Driveway, yard, side gate
+    Entry,    // Doors, windows, garage
+    Interior,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttackNode {
+    pub zone: String,
+    pub role: ZoneRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttackEdge {
+    pub from: usize,
+    pub to: usize,
+    pub prior: f64,       // Share of the move from `from` before history, sums to 1 per node
+    pub observed: u32,    // Times this move was seen in past incidents
+    pub probability: f64, // Posterior probability of this move given the intruder is at `from`
+}
+
+#[derive(Debug, Clone)]
+pub struct AttackGraphConfig {
+    pub inward_bias: f64,     // Prior weight of an inward move relative to a sideways or outward one
+    pub prior_strength: f64,  // Pseudo-observations behind the prior at each node
+}
+
+impl Default for AttackGraphConfig {
+    fn default() -> Self {
+        Self {
+            inward_bias: 3.0,
+            prior_strength: 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttackPath {
+    pub zones: Vec<String>,
+    pub step_probabilities: Vec<f64>, // One per move
+    pub probability: f64,             // Product of the step probabilities
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttackGraph {
+    pub nodes: Vec<AttackNode>,
+    pub edges: Vec<AttackEdge>,
+    pub prior_strength: f64,
+}
+
+impl AttackGraph {
+    /// Build a graph from zone roles and undirected physical adjacency
+    pub fn from_adjacency(
+        zones: &[(&str, ZoneRole)],
+        adjacency: &[(&str, &str)],
+        config: &AttackGraphConfig,
+    ) -> Result<Self, AttackGraphError> {
+        let mut nodes: Vec<AttackNode> = Vec::with_capacity(zones.len());
+        for (zone, role) in zones {
+            if nodes.iter().any(|n| n.zone == *zone) {
+                return Err(AttackGraphError::DuplicateZone(zone.to_string()));
+            }
+            nodes.push(AttackNode { zone: zone.to_string(), role: *role });
+        }
+        let mut graph = Self { nodes, edges: Vec::new(), prior_strength: config.prior_strength };
+
+        for (a, b) in adjacency {
+            let (a, b) = (graph.index(a)?, graph.index(b)?);
+            for (from, to) in [(a, b), (b, a)] {
+                if from != to && graph.edge(from, to).is_none() {
+                    graph.edges.push(AttackEdge { from, to, prior: 0.0, observed: 0, probability: 0.0 });
+                }
+            }
+        }
+
+        // Prior: inward moves weigh `inward_bias`, everything else 1, normalized per node
+        for from in 0..graph.nodes.len() {
+            let weight = |e: &AttackEdge, nodes: &[AttackNode]| {
+                if nodes[e.to].role > nodes[e.from].role { config.inward_bias } else { 1.0 }
+            };
+            let total: f64 = graph.edges.iter().filter(|e| e.from == from).map(|e| weight(e, &graph.nodes)).sum();
+            for i in 0..graph.edges.len() {
+                if graph.edges[i].from == from {
+                    graph.edges[i].prior = weight(&graph.edges[i], &graph.nodes) / total;
+                }
+            }
+        }
+        graph.update_probabilities();
+        Ok(graph)
+    }
+
+    pub fn index(&self, zone: &str) -> Result<usize, AttackGraphError> {
+        self.nodes.iter().position(|n| n.zone == zone).ok_or_else(|| AttackGraphError::UnknownZone(zone.to_string()))
+    }
+
+    fn edge(&self, from: usize, to: usize) -> Option<&AttackEdge> {
+        self.edges.iter().find(|e| e.from == from && e.to == to)
+    }
+
+    /// Count the moves in a zone sequence from a past incident; repeats of the
+    /// same zone and moves between non-adjacent zones are ignored
+    pub fn observe_path(&mut self, zones: &[&str]) -> Result<usize, AttackGraphError> {
+        let indices = zones.iter().map(|z| self.index(z)).collect::<Result<Vec<_>, _>>()?;
+        let mut counted = 0;
+        for pair in indices.windows(2) {
+            if let Some(edge) = self.edges.iter_mut().find(|e| e.from == pair[0] && e.to == pair[1]) {
+                edge.observed += 1;
+                counted += 1;
+            }
+        }
+        self.update_probabilities();
+        Ok(counted)
+    }
+
+    pub fn observed_transitions(&self) -> u32 {
+        self.edges.iter().map(|e| e.observed).sum()
+    }
+
+    // Dirichlet posterior mean: (n_ij + s·prior_ij) / (n_i + s)
+    fn update_probabilities(&mut self) {
+        let mut totals: HashMap<usize, u32> = HashMap::new();
+        for edge in &self.edges {
+            *totals.entry(edge.from).or_default() += edge.observed;
+        }
+        for edge in &mut self.edges {
+            let n = totals[&edge.from] as f64;
+            edge.probability = (edge.observed as f64 + self.prior_strength * edge.prior) / (n + self.prior_strength);
+        }
+    }
+
+    pub fn entry_points(&self) -> impl Iterator<Item = &AttackNode> {
+        self.nodes.iter().filter(|n| n.role == ZoneRole::Entry)
+    }
+
+    /// Fewest-hop path between two zones
+    pub fn shortest_path(&self, from: &str, to: &str) -> Result<Option<AttackPath>, AttackGraphError> {
+        let (start, goal) = (self.index(from)?, self.index(to)?);
+        let mut previous: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut seen = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::from([start]);
+        seen[start] = true;
+        while let Some(node) = queue.pop_front() {
+            if node == goal {
+                return Ok(Some(self.path_to(goal, &previous)));
+            }
+            for edge in self.edges.iter().filter(|e| e.from == node) {
+                if !seen[edge.to] {
+                    seen[edge.to] = true;
+                    previous[edge.to] = Some(node);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Most likely path between two zones, optionally limited to `max_steps` moves
+    pub fn most_likely_path(&self, from: &str, to: &str, max_steps: Option<usize>) -> Result<Option<AttackPath>, AttackGraphError> {
+        let goal = self.index(to)?;
+        Ok(self.most_likely_paths_from(from, max_steps)?.into_iter().find(|p| p.zones.last() == Some(&self.nodes[goal].zone)))
+    }
+
+    /// Most likely path from a zone to every reachable zone, best first. A
+    /// path never revisits a zone, and `max_steps` bounds its number of moves.
+    pub fn most_likely_paths_from(&self, from: &str, max_steps: Option<usize>) -> Result<Vec<AttackPath>, AttackGraphError> {
+        let start = self.index(from)?;
+        let max_steps = max_steps.unwrap_or(self.nodes.len());
+
+        // Dijkstra over (node, steps) so the step bound stays exact
+        let mut best: HashMap<(usize, usize), f64> = HashMap::new();
+        let mut previous: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        let mut heap = BinaryHeap::from([Candidate { cost: 0.0, node: start, steps: 0 }]);
+        best.insert((start, 0), 0.0);
+        let mut settled: HashMap<usize, (usize, f64)> = HashMap::new(); // node -> (steps, cost)
+        while let Some(Candidate { cost, node, steps }) = heap.pop() {
+            if best.get(&(node, steps)).is_some_and(|b| cost > *b) {
+                continue;
+            }
+            settled.entry(node).or_insert((steps, cost));
+            if steps == max_steps {
+                continue;
+            }
+            for edge in self.edges.iter().filter(|e| e.from == node && e.probability > 0.0) {
+                if self.revisits(edge.to, (node, steps), &previous) {
+                    continue;
+                }
+                let next = (edge.to, steps + 1);
+                let next_cost = cost - edge.probability.ln();
+                if best.get(&next).map_or(true, |b| next_cost < *b) {
+                    best.insert(next, next_cost);
+                    previous.insert(next, (node, steps));
+                    heap.push(Candidate { cost: next_cost, node: edge.to, steps: steps + 1 });
+                }
+            }
+        }
+
+        let mut paths: Vec<AttackPath> = settled.into_iter()
+            .filter(|(node, _)| *node != start)
+            .map(|(node, (steps, _))| {
+                let mut chain = vec![node];
+                let mut at = (node, steps);
+                while let Some(prev) = previous.get(&at) {
+                    chain.push(prev.0);
+                    at = *prev;
+                }
+                chain.reverse();
+                self.path_through(&chain)
+            })
+            .collect();
+        paths.sort_by(|a, b| b.probability.partial_cmp(&a.probability).unwrap_or(Ordering::Equal).then_with(|| a.zones.cmp(&b.zones)));
+        Ok(paths)
+    }
+
+    fn revisits(&self, node: usize, mut at: (usize, usize), previous: &HashMap<(usize, usize), (usize, usize)>) -> bool {
+        loop {
+            if at.0 == node {
+                return true;
+            }
+            match previous.get(&at) {
+                Some(prev) => at = *prev,
+                None => return false,
+            }
+        }
+    }
+
+    fn path_to(&self, goal: usize, previous: &[Option<usize>]) -> AttackPath {
+        let mut chain = vec![goal];
+        while let Some(prev) = previous[*chain.last().unwrap_or(&goal)] {
+            chain.push(prev);
+        }
+        chain.reverse();
+        self.path_through(&chain)
+    }
+
+    fn path_through(&self, chain: &[usize]) -> AttackPath {
+        let step_probabilities: Vec<f64> = chain.windows(2)
+            .map(|pair| self.edge(pair[0], pair[1]).map_or(0.0, |e| e.probability))
+            .collect();
+        AttackPath {
+            zones: chain.iter().map(|i| self.nodes[*i].zone.clone()).collect(),
+            probability: step_probabilities.iter().product(),
+            step_probabilities,
+        }
+    }
+}
+
+// Min-heap entry on accumulated -ln p
+#[derive(Debug, PartialEq)]
+struct Candidate {
+    cost: f64,
+    node: usize,
+    steps: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+            .then_with(|| other.steps.cmp(&self.steps))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -1 +1,225 @@
-// Prediction fusion module placeholder
+//! Probabilistic ensemble fusion across the prediction engines
+//!
+//! Each predictor (temporal, causal, behavioral, emergent) produces one
+//! `ThreatPrediction` per requested horizon. The fusion layer combines them with
+//! a weighted linear opinion pool and reports a confidence interval from the
+//! mixture variance, so disagreement between predictors widens the interval
+//! instead of being averaged away. Weights are learned online from outcome
+//! feedback with a multiplicative (Hedge) update on each predictor's Brier loss.
+
+use super::ThreatPrediction;
+use crate::core::ThreatVector;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PredictorSource {
+    Temporal,
+    Causal,
+    Behavioral,
+    Emergent,
+}
+
+impl PredictorSource {
+    pub const ALL: [PredictorSource; 4] = [
+        PredictorSource::Temporal,
+        PredictorSource::Causal,
+        PredictorSource::Behavioral,
+        PredictorSource::Emergent,
+    ];
+}
+
+#[derive(Debug, Clone)]
+pub struct FusionConfig {
+    pub learning_rate: f64,       // Hedge step size applied to Brier loss
+    pub min_weight: f64,          // Floor so a predictor can recover after a bad streak
+    pub pseudo_observations: f64, // Beta concentration used for each predictor's own uncertainty
+    pub z_score: f64,             // 1.96 for a 95% interval
+}
+
+impl Default for FusionConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.5,
+            min_weight: 0.02,
+            pseudo_observations: 20.0,
+            z_score: 1.96,
+        }
+    }
+}
+
+// Fused result for a single horizon
+#[derive(Debug, Clone)]
+pub struct FusedHorizonPrediction {
+    pub horizon: Duration,
+    pub threat_level: f64,
+    pub confidence_interval: (f64, f64),
+    pub confidence: f64,    // 1 - interval width
+    pub disagreement: f64,  // Between-predictor standard deviation
+    pub distribution: HashMap<ThreatVector, f64>, // Fused threat-vector distribution
+    pub weights_used: HashMap<PredictorSource, f64>,
+}
+
+// Running per-predictor calibration statistics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PredictorPerformance {
+    pub outcomes_seen: u64,
+    pub mean_brier: f64,
+}
+
+#[derive(Debug)]
+pub struct PredictionFusionLayer {
+    config: FusionConfig,
+    weights: HashMap<PredictorSource, f64>,
+    performance: HashMap<PredictorSource, PredictorPerformance>,
+}
+
+impl PredictionFusionLayer {
+    pub fn new() -> Self {
+        Self::with_config(FusionConfig::default())
+    }
+
+    pub fn with_config(config: FusionConfig) -> Self {
+        let uniform = 1.0 / PredictorSource::ALL.len() as f64;
+        Self {
+            config,
+            weights: PredictorSource::ALL.iter().map(|s| (*s, uniform)).collect(),
+            performance: HashMap::new(),
+        }
+    }
+
+    /// Fuse per-source predictions; `inputs[source][i]` is the prediction for `horizons[i]`
+    pub fn fuse(
+        &self,
+        inputs: &[(PredictorSource, &[ThreatPrediction])],
+        horizons: &[Duration],
+    ) -> Vec<FusedHorizonPrediction> {
+        horizons
+            .iter()
+            .enumerate()
+            .filter_map(|(i, horizon)| {
+                let members: Vec<(PredictorSource, &ThreatPrediction)> = inputs
+                    .iter()
+                    .filter_map(|(source, preds)| preds.get(i).map(|p| (*source, p)))
+                    .filter(|(_, p)| p.threat_level.is_finite())
+                    .collect();
+                self.fuse_horizon(*horizon, &members)
+            })
+            .collect()
+    }
+
+    fn fuse_horizon(
+        &self,
+        horizon: Duration,
+        members: &[(PredictorSource, &ThreatPrediction)],
+    ) -> Option<FusedHorizonPrediction> {
+        if members.is_empty() {
+            return None;
+        }
+
+        // Renormalize weights over the predictors that actually produced a forecast
+        let total: f64 = members.iter().map(|(s, _)| self.weight(*s)).sum();
+        let weights_used: HashMap<PredictorSource, f64> = members
+            .iter()
+            .map(|(s, _)| (*s, self.weight(*s) / total))
+            .collect();
+
+        let mean: f64 = members
+            .iter()
+            .map(|(s, p)| weights_used[s] * p.threat_level.clamp(0.0, 1.0))
+            .sum();
+
+        // Law of total variance for a mixture: E[Var] + Var[E]
+        let kappa = self.config.pseudo_observations;
+        let within: f64 = members
+            .iter()
+            .map(|(s, p)| {
+                let q = p.threat_level.clamp(0.0, 1.0);
+                weights_used[s] * q * (1.0 - q) / (kappa + 1.0)
+            })
+            .sum();
+        let between: f64 = members
+            .iter()
+            .map(|(s, p)| weights_used[s] * (p.threat_level.clamp(0.0, 1.0) - mean).powi(2))
+            .sum();
+        let std_dev = (within + between).sqrt();
+
+        let lower = (mean - self.config.z_score * std_dev).max(0.0);
+        let upper = (mean + self.config.z_score * std_dev).min(1.0);
+
+        let mut distribution: HashMap<ThreatVector, f64> = HashMap::new();
+        for (s, p) in members {
+            let mass: f64 = p.probability_distribution.values().filter(|v| v.is_finite()).sum();
+            if mass <= 0.0 {
+                continue;
+            }
+            for (vector, prob) in &p.probability_distribution {
+                if prob.is_finite() {
+                    *distribution.entry(*vector).or_insert(0.0) += weights_used[s] * prob / mass;
+                }
+            }
+        }
+        let dist_total: f64 = distribution.values().sum();
+        if dist_total > 0.0 {
+            distribution.values_mut().for_each(|v| *v /= dist_total);
+        }
+
+        Some(FusedHorizonPrediction {
+            horizon,
+            threat_level: mean,
+            confidence_interval: (lower, upper),
+            confidence: 1.0 - (upper - lower),
+            disagreement: between.sqrt(),
+            distribution,
+            weights_used,
+        })
+    }
+
+    /// Update predictor weights from an observed outcome for one horizon
+    pub fn record_outcome(&mut self, forecasts: &[(PredictorSource, f64)], threat_occurred: bool) {
+        let y = if threat_occurred { 1.0 } else { 0.0 };
+
+        for (source, p) in forecasts {
+            if !p.is_finite() {
+                continue;
+            }
+            let brier = (p.clamp(0.0, 1.0) - y).powi(2);
+
+            let perf = self.performance.entry(*source).or_default();
+            perf.outcomes_seen += 1;
+            perf.mean_brier += (brier - perf.mean_brier) / perf.outcomes_seen as f64;
+
+            let w = self.weights.entry(*source).or_insert(self.config.min_weight);
+            *w *= (-self.config.learning_rate * brier).exp();
+        }
+
+        // Renormalize, then apply the floor and renormalize again
+        let total: f64 = self.weights.values().sum();
+        if total > 0.0 {
+            for w in self.weights.values_mut() {
+                *w = (*w / total).max(self.config.min_weight);
+            }
+            let total: f64 = self.weights.values().sum();
+            self.weights.values_mut().for_each(|w| *w /= total);
+        }
+    }
+
+    pub fn weight(&self, source: PredictorSource) -> f64 {
+        *self.weights.get(&source).unwrap_or(&self.config.min_weight)
+    }
+
+    pub fn weights(&self) -> &HashMap<PredictorSource, f64> {
+        &self.weights
+    }
+
+    pub fn performance(&self) -> &HashMap<PredictorSource, PredictorPerformance> {
+        &self.performance
+    }
+}
+
+impl Default for PredictionFusionLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,12 @@
 //! Advanced threat prediction engine with multi-horizon forecasting
 
+pub mod attack_graph;
+pub mod fusion;
+pub mod scoring;
 
-
-
-
+pub use attack_graph::{AttackEdge, AttackGraph, AttackGraphConfig, AttackGraphError, AttackNode, AttackPath, ZoneRole};
+pub use fusion::{PredictionFusionLayer, PredictorSource, FusedHorizonPrediction, FusionConfig};
+pub use scoring::{CalibrationBin, ComponentReport, OutcomeKind, Prediction, PredictionComponent, PredictionScorer, PredictionScoringConfig, ReportCard, ScoredPrediction};
 
 use crate::core::*;
 use crate::SecurityResult;
@@ -41,21 +44,52 @@ impl ThreatPredictionEngine {
         &self,
         _context: &EnvironmentalContext,
         _entities: &[Entity],
-        _prediction_horizons: &[Duration],
+        prediction_horizons: &[Duration],
     ) -> SecurityResult<MultiHorizonPrediction> {
-        // Simplified implementation for compilation
+        // Individual predictors are still stubs and return no forecasts yet
         let predictions = Vec::new();
-        
+        let temporal_predictions: Vec<ThreatPrediction> = predictions.clone();
+        let causal_predictions: Vec<ThreatPrediction> = predictions.clone();
+        let behavioral_predictions: Vec<ThreatPrediction> = predictions.clone();
+        let emergent_predictions: Vec<ThreatPrediction> = predictions;
+
+        let fused_predictions = self.fusion_layer.fuse(
+            &[
+                (PredictorSource::Temporal, &temporal_predictions),
+                (PredictorSource::Causal, &causal_predictions),
+                (PredictorSource::Behavioral, &behavioral_predictions),
+                (PredictorSource::Emergent, &emergent_predictions),
+            ],
+            prediction_horizons,
+        );
+
+        // Overall confidence is the mean over horizons; no forecasts means no confidence
+        let fusion_confidence = if fused_predictions.is_empty() {
+            0.0
+        } else {
+            fused_predictions.iter().map(|f| f.confidence).sum::<f64>() / fused_predictions.len() as f64
+        };
+
         Ok(MultiHorizonPrediction {
-            temporal_predictions: predictions.clone(),
-            causal_predictions: predictions.clone(),
-            behavioral_predictions: predictions.clone(),
-            emergent_predictions: predictions,
-            fusion_confidence: 0.5,
+            temporal_predictions,
+            causal_predictions,
+            behavioral_predictions,
+            emergent_predictions,
+            fused_predictions,
+            fusion_confidence,
             meta_prediction: None,
         })
     }
 
+    /// Feed an observed outcome back so fusion weights favour well-calibrated predictors
+    pub fn record_outcome(&mut self, forecasts: &[(PredictorSource, f64)], threat_occurred: bool) {
+        self.fusion_layer.record_outcome(forecasts, threat_occurred);
+    }
+
+    pub fn fusion_weights(&self) -> &HashMap<PredictorSource, f64> {
+        self.fusion_layer.weights()
+    }
+
     /// Generate immediate threat assessment 
     pub async fn assess_immediate_threats(
         &self,
@@ -68,26 +102,63 @@ impl ThreatPredictionEngine {
             entity_id: entities.first().map(|e| e.id).unwrap_or_else(|| Uuid::new_v4()),
             timestamp: Utc::now(),
             threat_probability: threat_probability.clone(),
-            // threat_vector: create_default_threat_vector(), // TODO: Implement proper ThreatVector
+            threat_vector: ThreatVector::Unknown, // No predictor classifies yet
             severity: ThreatSeverity::High,
             recommended_actions: self.generate_immediate_actions(&threat_probability)?,
         })
     }
 
-    /// Generate sequence-based threat predictions
+    /// Generate sequence-based threat predictions: the most likely route from
+    /// outside to any entry point across the given graphs, within
+    /// `max_sequence_length` moves. `branch_probabilities` holds the best
+    /// route probability to each entry point.
     pub async fn predict_sequence_threats(
         &self,
-        _attack_graphs: &[AttackGraph],
-        _max_sequence_length: usize,
+        attack_graphs: &[AttackGraph],
+        max_sequence_length: usize,
     ) -> SecurityResult<SequenceThreatPrediction> {
-        let sequence_probabilities = SequenceProbabilities { values: vec![0.5] };
+        let mut best: Option<(&AttackGraph, AttackPath)> = None;
+        let mut branch_probabilities: HashMap<String, f64> = HashMap::new();
+        for graph in attack_graphs {
+            for start in graph.nodes.iter().filter(|n| n.role == ZoneRole::Outside) {
+                let paths = graph.most_likely_paths_from(&start.zone, Some(max_sequence_length))?;
+                for path in paths.into_iter().filter(|p| p.zones.last().is_some_and(|z| graph.entry_points().any(|e| &e.zone == z))) {
+                    let entry = path.zones.last().cloned().unwrap_or_default();
+                    let branch = branch_probabilities.entry(entry).or_insert(0.0);
+                    *branch = branch.max(path.probability);
+                    if best.as_ref().map_or(true, |(_, b)| path.probability > b.probability) {
+                        best = Some((graph, path));
+                    }
+                }
+            }
+        }
+
+        let (attack_sequence, sequence_probabilities, confidence) = match &best {
+            Some((graph, path)) => {
+                let steps: Vec<AttackStep> = path.zones.windows(2).zip(&path.step_probabilities)
+                    .map(|(pair, p)| AttackStep {
+                        step_id: Uuid::new_v4(),
+                        description: format!("{} → {}", pair[0], pair[1]),
+                        probability: *p,
+                    })
+                    .collect();
+                // Cumulative probability of having made it through each step
+                let values = path.step_probabilities.iter()
+                    .scan(1.0, |acc, p| { *acc *= p; Some(*acc) })
+                    .collect();
+                let probabilities = SequenceProbabilities { values };
+                let confidence = self.calculate_sequence_confidence(graph, &probabilities)?;
+                (steps, probabilities, confidence)
+            }
+            None => (Vec::new(), SequenceProbabilities { values: Vec::new() }, 0.0),
+        };
 
         Ok(SequenceThreatPrediction {
             sequence_id: Uuid::new_v4(),
-            attack_sequence: vec![],
-            sequence_probabilities: sequence_probabilities.clone(),
-            branch_probabilities: HashMap::new(),
-            confidence: self.calculate_sequence_confidence(&sequence_probabilities)?,
+            attack_sequence,
+            sequence_probabilities,
+            branch_probabilities,
+            confidence,
         })
     }
 
@@ -102,8 +173,13 @@ impl ThreatPredictionEngine {
         Ok(actions)
     }
 
-    fn calculate_sequence_confidence(&self, _probabilities: &SequenceProbabilities) -> SecurityResult<f64> {
-        Ok(0.75)
+    // Grows with the history behind the edge weights; a graph on priors alone is a guess
+    fn calculate_sequence_confidence(&self, graph: &AttackGraph, probabilities: &SequenceProbabilities) -> SecurityResult<f64> {
+        if probabilities.values.is_empty() {
+            return Ok(0.0);
+        }
+        let observed = graph.observed_transitions() as f64;
+        Ok(observed / (observed + graph.prior_strength * graph.nodes.len().max(1) as f64))
     }
 }
 
@@ -111,8 +187,8 @@ impl ThreatPredictionEngine {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThreatPrediction {
     pub threat_level: f64,
-    pub threat_types: Vec<String>,
-    pub probability_distribution: HashMap<String, f64>,
+    pub threat_types: Vec<ThreatVector>,
+    pub probability_distribution: HashMap<ThreatVector, f64>,
     pub causal_factors: Vec<String>,
     pub intervention_points: Vec<Intervention>,
 }
@@ -132,7 +208,7 @@ pub struct ImmediateThreatAssessment {
     pub entity_id: Uuid,
     pub timestamp: DateTime<Utc>,
     pub threat_probability: ThreatProbability,
-    // pub threat_vector: ThreatVector, // TODO: Implement proper initialization
+    pub threat_vector: ThreatVector,
     pub severity: ThreatSeverity,
     pub recommended_actions: Vec<SimpleAction>,
 }
@@ -143,6 +219,7 @@ pub struct MultiHorizonPrediction {
     pub causal_predictions: Vec<ThreatPrediction>,
     pub behavioral_predictions: Vec<ThreatPrediction>,
     pub emergent_predictions: Vec<ThreatPrediction>,
+    pub fused_predictions: Vec<FusedHorizonPrediction>,
     pub fusion_confidence: f64,
     pub meta_prediction: Option<MetaThreatPrediction>,
 }
@@ -191,13 +268,6 @@ impl EmergentThreatDetector {
     pub fn new() -> Self { Self }
 }
 
-#[derive(Debug)]
-pub struct PredictionFusionLayer;
-
-impl PredictionFusionLayer {
-    pub fn new() -> Self { Self }
-}
-
 #[derive(Debug)]
 pub struct ModelCache;
 
@@ -206,12 +276,6 @@ impl ModelCache {
 }
 
 // Missing type definitions
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AttackGraph {
-    pub nodes: Vec<String>,
-    pub edges: Vec<(usize, usize)>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttackStep {
     pub step_id: Uuid,
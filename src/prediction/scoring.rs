@@ -0,0 +1,314 @@
+//! Prediction outcome scoring
+//!
+//! Closes the loop on the probabilities the system quotes. Each model
+//! component's prediction for an incident is recorded with when it was made
+//! and, for predictions about the future, its horizon: the base rate before
+//! any evidence, the fused calibrated threat probability (latest assessment
+//! wins), and the escalation model's chance of an entry attempt within its
+//! window. Predictions are matched against what actually happened: household
+//! or operator feedback settles the threat predictions, an entry attempt at
+//! the home inside the window settles an escalation prediction as a hit, and
+//! a window that runs out settles it as a miss. Settled predictions feed a
+//! report card per component: Brier score, its skill against always quoting
+//! the observed base rate, and a calibration curve. A card for every period
+//! is kept so drift shows up week over week.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct PredictionScoringConfig {
+    pub calibration_bins: usize,
+    pub pending_ttl: Duration,   // Threat predictions nobody gave feedback on are dropped after this
+    pub retention: Duration,     // Settled predictions kept for report cards
+    pub report_period: Duration, // One report card per period
+    pub max_report_cards: usize,
+}
+
+impl Default for PredictionScoringConfig {
+    fn default() -> Self {
+        Self {
+            calibration_bins: 10,
+            pending_ttl: Duration::days(30),
+            retention: Duration::days(180),
+            report_period: Duration::weeks(1),
+            max_report_cards: 52,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PredictionComponent {
+    Prior,      // Base rate for the situation, before any evidence
+    Fusion,     // Calibrated threat probability of the incident
+    Escalation, // Chance of an entry attempt within the horizon
+}
+
+impl PredictionComponent {
+    pub const ALL: [PredictionComponent; 3] = [PredictionComponent::Prior, PredictionComponent::Fusion, PredictionComponent::Escalation];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeKind {
+    Feedback,       // The incident was labeled threat or benign
+    EntryAttempt,   // An entry attempt at the home inside the horizon
+    HorizonElapsed, // The horizon ran out with no entry attempt
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Prediction {
+    pub id: Uuid,
+    pub home_id: String,
+    pub incident_id: u64,
+    pub component: PredictionComponent,
+    pub probability: f64,
+    pub made_at: DateTime<Utc>,
+    pub horizon_secs: Option<f64>, // None: settled by feedback, whenever it comes
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ScoredPrediction {
+    pub prediction: Prediction,
+    pub occurred: bool,
+    pub outcome: OutcomeKind,
+    pub resolved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CalibrationBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub predictions: usize,
+    pub mean_predicted: f64,
+    pub observed_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ComponentReport {
+    pub component: PredictionComponent,
+    pub predictions: usize,
+    pub occurred: usize,
+    pub brier_score: Option<f64>,
+    pub reference_brier: Option<f64>, // Brier of always quoting the observed base rate
+    pub brier_skill: Option<f64>,     // 1 - brier / reference; above 0 beats the base rate
+    pub calibration: Vec<CalibrationBin>, // Empty bins left out
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ReportCard {
+    pub home_id: Option<String>, // None: every home
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub pending: usize, // Predictions still waiting for an outcome
+    pub components: Vec<ComponentReport>,
+}
+
+#[derive(Debug, Default)]
+pub struct PredictionScorer {
+    config: PredictionScoringConfig,
+    pending: Mutex<Vec<Prediction>>,
+    scored: Mutex<Vec<ScoredPrediction>>,
+    report_cards: Mutex<VecDeque<ReportCard>>, // Newest last
+    last_period: Mutex<Option<i64>>,
+}
+
+impl PredictionScorer {
+    pub fn new(config: PredictionScoringConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &PredictionScoringConfig {
+        &self.config
+    }
+
+    /// Record a component's prediction for an incident. The fused probability
+    /// replaces the incident's earlier one while it waits for feedback; the
+    /// prior and an escalation window are only recorded when none is pending.
+    pub fn record(&self, home_id: &str, incident_id: u64, component: PredictionComponent, probability: f64, horizon_secs: Option<f64>, at: DateTime<Utc>) {
+        if !probability.is_finite() {
+            return;
+        }
+        let Ok(mut pending) = self.pending.lock() else {
+            return;
+        };
+        let existing = pending.iter_mut().find(|p| p.home_id == home_id && p.incident_id == incident_id && p.component == component);
+        match (existing, component) {
+            (Some(prediction), PredictionComponent::Fusion) => {
+                prediction.probability = probability.clamp(0.0, 1.0);
+                prediction.made_at = at;
+            }
+            (Some(_), _) => {}
+            (None, _) => pending.push(Prediction {
+                id: Uuid::new_v4(),
+                home_id: home_id.to_string(),
+                incident_id,
+                component,
+                probability: probability.clamp(0.0, 1.0),
+                made_at: at,
+                horizon_secs,
+            }),
+        }
+    }
+
+    /// Settle an incident's predictions from its label; a benign label also
+    /// settles its escalation window as a miss. Returns how many were settled.
+    pub fn resolve_incident(&self, home_id: &str, incident_id: u64, threat: bool, at: DateTime<Utc>) -> usize {
+        self.settle(at, |p| {
+            if p.home_id != home_id || p.incident_id != incident_id {
+                return None;
+            }
+            match p.component {
+                PredictionComponent::Escalation if threat => None,
+                _ => Some((threat, OutcomeKind::Feedback)),
+            }
+        })
+    }
+
+    /// An entry attempt at a home settles its open escalation windows as hits
+    pub fn record_entry_attempt(&self, home_id: &str, at: DateTime<Utc>) -> usize {
+        self.settle(at, |p| {
+            let open = p.component == PredictionComponent::Escalation
+                && p.home_id == home_id
+                && p.made_at <= at
+                && p.horizon_secs.is_some_and(|h| at <= p.made_at + Duration::milliseconds((h * 1000.0) as i64));
+            open.then_some((true, OutcomeKind::EntryAttempt))
+        })
+    }
+
+    /// Settle windows that ran out as misses, drop threat predictions nobody
+    /// labeled and forget settled ones past retention
+    pub fn expire(&self, now: DateTime<Utc>) -> usize {
+        let settled = self.settle(now, |p| {
+            let horizon = p.horizon_secs?;
+            (now > p.made_at + Duration::milliseconds((horizon * 1000.0) as i64)).then_some((false, OutcomeKind::HorizonElapsed))
+        });
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.retain(|p| p.horizon_secs.is_some() || now - p.made_at <= self.config.pending_ttl);
+        }
+        if let Ok(mut scored) = self.scored.lock() {
+            scored.retain(|s| now - s.resolved_at <= self.config.retention);
+        }
+        settled
+    }
+
+    fn settle(&self, at: DateTime<Utc>, outcome: impl Fn(&Prediction) -> Option<(bool, OutcomeKind)>) -> usize {
+        let (Ok(mut pending), Ok(mut scored)) = (self.pending.lock(), self.scored.lock()) else {
+            return 0;
+        };
+        let before = scored.len();
+        pending.retain(|p| match outcome(p) {
+            Some((occurred, kind)) => {
+                scored.push(ScoredPrediction { prediction: p.clone(), occurred, outcome: kind, resolved_at: at });
+                false
+            }
+            None => true,
+        });
+        scored.len() - before
+    }
+
+    /// Report card over predictions settled in `[start, end)`, for one home or all of them
+    pub fn report_card(&self, home_id: Option<&str>, start: DateTime<Utc>, end: DateTime<Utc>, now: DateTime<Utc>) -> ReportCard {
+        let scored: Vec<ScoredPrediction> = self.scored.lock()
+            .map(|s| s.iter()
+                .filter(|s| s.resolved_at >= start && s.resolved_at < end)
+                .filter(|s| home_id.map_or(true, |h| s.prediction.home_id == h))
+                .cloned()
+                .collect())
+            .unwrap_or_default();
+        let pending = self.pending.lock()
+            .map(|p| p.iter().filter(|p| home_id.map_or(true, |h| p.home_id == h)).count())
+            .unwrap_or(0);
+        ReportCard {
+            home_id: home_id.map(str::to_string),
+            period_start: start,
+            period_end: end,
+            generated_at: now,
+            pending,
+            components: PredictionComponent::ALL.iter()
+                .map(|component| {
+                    let outcomes: Vec<(f64, bool)> = scored.iter()
+                        .filter(|s| s.prediction.component == *component)
+                        .map(|s| (s.prediction.probability, s.occurred))
+                        .collect();
+                    component_report(*component, &outcomes, self.config.calibration_bins.max(1))
+                })
+                .collect(),
+        }
+    }
+
+    /// Close the previous period's report card once a new period has started;
+    /// the first call only marks the current period
+    pub fn roll_report_card(&self, now: DateTime<Utc>) -> Option<ReportCard> {
+        let period_secs = self.config.report_period.num_seconds().max(1);
+        let period = now.timestamp().div_euclid(period_secs);
+        let mut last = self.last_period.lock().ok()?;
+        let previous = last.replace(period);
+        if previous.map_or(true, |p| p >= period) {
+            return None;
+        }
+        let start = DateTime::from_timestamp((period - 1) * period_secs, 0)?;
+        let card = self.report_card(None, start, start + self.config.report_period, now);
+        let mut cards = self.report_cards.lock().ok()?;
+        cards.push_back(card.clone());
+        while cards.len() > self.config.max_report_cards {
+            cards.pop_front();
+        }
+        Some(card)
+    }
+
+    /// Periodic report cards, newest first
+    pub fn report_cards(&self, limit: usize) -> Vec<ReportCard> {
+        self.report_cards.lock()
+            .map(|cards| cards.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn component_report(component: PredictionComponent, outcomes: &[(f64, bool)], bins: usize) -> ComponentReport {
+    let n = outcomes.len();
+    let occurred = outcomes.iter().filter(|(_, o)| *o).count();
+    if n == 0 {
+        return ComponentReport { component, predictions: 0, occurred: 0, brier_score: None, reference_brier: None, brier_skill: None, calibration: Vec::new() };
+    }
+    let outcome = |o: bool| if o { 1.0 } else { 0.0 };
+    let brier = outcomes.iter().map(|(p, o)| (p - outcome(*o)).powi(2)).sum::<f64>() / n as f64;
+    let base_rate = occurred as f64 / n as f64;
+    let reference = base_rate * (1.0 - base_rate);
+    let skill = (reference > 0.0).then(|| 1.0 - brier / reference);
+
+    let calibration = (0..bins)
+        .filter_map(|b| {
+            let (lower, upper) = (b as f64 / bins as f64, (b + 1) as f64 / bins as f64);
+            let in_bin: Vec<&(f64, bool)> = outcomes.iter()
+                .filter(|(p, _)| *p >= lower && (*p < upper || (b == bins - 1 && *p <= upper)))
+                .collect();
+            if in_bin.is_empty() {
+                return None;
+            }
+            let count = in_bin.len() as f64;
+            Some(CalibrationBin {
+                lower,
+                upper,
+                predictions: in_bin.len(),
+                mean_predicted: in_bin.iter().map(|(p, _)| p).sum::<f64>() / count,
+                observed_rate: in_bin.iter().filter(|(_, o)| *o).count() as f64 / count,
+            })
+        })
+        .collect();
+
+    ComponentReport {
+        component,
+        predictions: n,
+        occurred,
+        brier_score: Some(brier),
+        reference_brier: Some(reference),
+        brier_skill: skill,
+        calibration,
+    }
+}
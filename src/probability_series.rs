@@ -0,0 +1,279 @@
+// src/probability_series.rs
+
+// How confidence in an incident evolved. The in-memory probability trace goes
+// when the incident expires, so every assessment (each fused event, and each
+// follow-up re-check) also appends a point to a durable series: when it
+// happened, the event behind it, the fused LLR, the calibrated probability and
+// the decision after rules ran. Apps chart the series to show a household why
+// an alert went out, and reviewers use it after the fact. A chart request can
+// ask for fewer points; the first and last points, the peak and every decision
+// change are always kept so the shape of the incident survives downsampling.
+// Points older than the retention window are pruned.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::{OnceCell, RwLock};
+use uuid::Uuid;
+
+use crate::thinking::AlertDecision;
+
+#[derive(Error, Debug)]
+pub enum ProbabilitySeriesError {
+    #[error("Probability series storage error: {0}")]
+    Storage(String),
+}
+
+fn storage_error(e: impl std::fmt::Display) -> ProbabilitySeriesError {
+    ProbabilitySeriesError::Storage(e.to_string())
+}
+
+#[derive(Debug, Clone)]
+pub struct ProbabilitySeriesConfig {
+    pub retention: Duration,
+    pub max_chart_points: usize, // Upper bound on points returned by one chart request
+}
+
+impl Default for ProbabilitySeriesConfig {
+    fn default() -> Self {
+        Self { retention: Duration::days(90), max_chart_points: 500 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PointSource {
+    Event,    // A new event was fused into the incident
+    FollowUp, // A waiting incident was re-checked
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProbabilityPoint {
+    pub at: DateTime<Utc>,
+    pub event_id: Option<Uuid>,
+    pub event_count: usize,
+    pub fused_llr: f64,
+    pub calibrated_probability: f64,
+    pub decision: AlertDecision,
+    pub source: PointSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ProbabilityChart {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub total_points: usize,          // Points recorded, before downsampling
+    pub peak_probability: f64,
+    pub points: Vec<ProbabilityPoint>, // Oldest first
+}
+
+// Durable backing store so series outlive the incidents they describe
+#[async_trait]
+pub trait ProbabilitySeriesStore: Send + Sync {
+    async fn append(&self, home_id: &str, incident_id: u64, point: &ProbabilityPoint) -> Result<(), ProbabilitySeriesError>;
+
+    /// Every point of one incident, oldest first
+    async fn series(&self, home_id: &str, incident_id: u64) -> Result<Vec<ProbabilityPoint>, ProbabilitySeriesError>;
+
+    /// Drop points recorded before `before`; returns how many went
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ProbabilitySeriesError>;
+}
+
+#[derive(Default)]
+pub struct InMemoryProbabilitySeriesStore {
+    series: RwLock<HashMap<(String, u64), Vec<ProbabilityPoint>>>,
+}
+
+#[async_trait]
+impl ProbabilitySeriesStore for InMemoryProbabilitySeriesStore {
+    async fn append(&self, home_id: &str, incident_id: u64, point: &ProbabilityPoint) -> Result<(), ProbabilitySeriesError> {
+        self.series.write().await.entry((home_id.to_string(), incident_id)).or_default().push(point.clone());
+        Ok(())
+    }
+
+    async fn series(&self, home_id: &str, incident_id: u64) -> Result<Vec<ProbabilityPoint>, ProbabilitySeriesError> {
+        Ok(self.series.read().await.get(&(home_id.to_string(), incident_id)).cloned().unwrap_or_default())
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ProbabilitySeriesError> {
+        let mut series = self.series.write().await;
+        let mut removed = 0;
+        for points in series.values_mut() {
+            let len = points.len();
+            points.retain(|p| p.at >= before);
+            removed += (len - points.len()) as u64;
+        }
+        series.retain(|_, points| !points.is_empty());
+        Ok(removed)
+    }
+}
+
+// SQLite implementation backed by the `probability_series` table
+pub struct SqliteProbabilitySeriesStore {
+    pool: SqlitePool,
+    schema: OnceCell<()>,
+}
+
+impl SqliteProbabilitySeriesStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool, schema: OnceCell::new() }
+    }
+
+    // Creates the table on first use if the migrations have not been run
+    async fn ensure_schema(&self) -> Result<(), ProbabilitySeriesError> {
+        self.schema.get_or_try_init(|| async {
+            sqlx::query(include_str!("api/migrations/008_probability_series.sql"))
+                .execute(&self.pool)
+                .await
+                .map(|_| ())
+                .map_err(storage_error)
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProbabilitySeriesStore for SqliteProbabilitySeriesStore {
+    async fn append(&self, home_id: &str, incident_id: u64, point: &ProbabilityPoint) -> Result<(), ProbabilitySeriesError> {
+        self.ensure_schema().await?;
+        let decision = serde_json::to_string(&point.decision).map_err(storage_error)?;
+        let source = serde_json::to_string(&point.source).map_err(storage_error)?;
+        sqlx::query(
+            "INSERT INTO probability_series (home_id, incident_id, seq, at, event_id, event_count, fused_llr, calibrated_probability, decision, source) \
+             SELECT ?, ?, COALESCE(MAX(seq), 0) + 1, ?, ?, ?, ?, ?, ?, ? FROM probability_series WHERE home_id = ? AND incident_id = ?",
+        )
+        .bind(home_id)
+        .bind(incident_id as i64)
+        .bind(point.at.timestamp_millis())
+        .bind(point.event_id.map(|id| id.to_string()))
+        .bind(point.event_count as i64)
+        .bind(point.fused_llr)
+        .bind(point.calibrated_probability)
+        .bind(decision)
+        .bind(source)
+        .bind(home_id)
+        .bind(incident_id as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(storage_error)?;
+        Ok(())
+    }
+
+    async fn series(&self, home_id: &str, incident_id: u64) -> Result<Vec<ProbabilityPoint>, ProbabilitySeriesError> {
+        self.ensure_schema().await?;
+        let rows: Vec<(i64, Option<String>, i64, f64, f64, String, String)> = sqlx::query_as(
+            "SELECT at, event_id, event_count, fused_llr, calibrated_probability, decision, source FROM probability_series WHERE home_id = ? AND incident_id = ? ORDER BY seq",
+        )
+        .bind(home_id)
+        .bind(incident_id as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage_error)?;
+
+        rows.into_iter()
+            .map(|(at, event_id, event_count, fused_llr, calibrated_probability, decision, source)| {
+                Ok(ProbabilityPoint {
+                    at: DateTime::from_timestamp_millis(at).unwrap_or_default(),
+                    event_id: event_id.as_deref().map(Uuid::parse_str).transpose().map_err(storage_error)?,
+                    event_count: event_count as usize,
+                    fused_llr,
+                    calibrated_probability,
+                    decision: serde_json::from_str(&decision).map_err(storage_error)?,
+                    source: serde_json::from_str(&source).map_err(storage_error)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ProbabilitySeriesError> {
+        self.ensure_schema().await?;
+        let result = sqlx::query("DELETE FROM probability_series WHERE at < ?")
+            .bind(before.timestamp_millis())
+            .execute(&self.pool)
+            .await
+            .map_err(storage_error)?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct ProbabilityHistory {
+    config: ProbabilitySeriesConfig,
+    store: Arc<dyn ProbabilitySeriesStore>,
+}
+
+impl Default for ProbabilityHistory {
+    fn default() -> Self {
+        Self::new(ProbabilitySeriesConfig::default(), Arc::new(InMemoryProbabilitySeriesStore::default()))
+    }
+}
+
+impl ProbabilityHistory {
+    pub fn new(config: ProbabilitySeriesConfig, store: Arc<dyn ProbabilitySeriesStore>) -> Self {
+        Self { config, store }
+    }
+
+    pub fn config(&self) -> &ProbabilitySeriesConfig {
+        &self.config
+    }
+
+    pub async fn record(&self, home_id: &str, incident_id: u64, point: ProbabilityPoint) -> Result<(), ProbabilitySeriesError> {
+        self.store.append(home_id, incident_id, &point).await
+    }
+
+    /// The incident's series, cut down to at most `max_points` (and never more
+    /// than the configured limit); None when nothing was recorded for it
+    pub async fn chart(&self, home_id: &str, incident_id: u64, max_points: Option<usize>) -> Result<Option<ProbabilityChart>, ProbabilitySeriesError> {
+        let points = self.store.series(home_id, incident_id).await?;
+        if points.is_empty() {
+            return Ok(None);
+        }
+        let limit = max_points.unwrap_or(self.config.max_chart_points).clamp(2, self.config.max_chart_points.max(2));
+        Ok(Some(ProbabilityChart {
+            home_id: home_id.to_string(),
+            incident_id,
+            total_points: points.len(),
+            peak_probability: points.iter().map(|p| p.calibrated_probability).fold(0.0, f64::max),
+            points: downsample(points, limit),
+        }))
+    }
+
+    /// Drop points past the retention window
+    pub async fn prune(&self, now: DateTime<Utc>) -> Result<u64, ProbabilitySeriesError> {
+        self.store.prune(now - self.config.retention).await
+    }
+}
+
+// Keep at most `limit` points (at least two): the first, the last, the peak and
+// every decision change come first, then evenly spaced points fill what is left
+fn downsample(points: Vec<ProbabilityPoint>, limit: usize) -> Vec<ProbabilityPoint> {
+    if points.len() <= limit {
+        return points;
+    }
+    let last = points.len() - 1;
+    let peak = points.iter().enumerate()
+        .max_by(|a, b| a.1.calibrated_probability.total_cmp(&b.1.calibrated_probability))
+        .map_or(0, |(i, _)| i);
+    let mut keep: Vec<usize> = vec![0, last, peak];
+    keep.extend((1..points.len()).filter(|&i| points[i].decision != points[i - 1].decision));
+    keep.sort_unstable();
+    keep.dedup();
+    if keep.len() > limit {
+        // Too many decision changes; spread the budget across them, ends included
+        keep = (0..limit).map(|k| keep[k * (keep.len() - 1) / (limit - 1)]).collect();
+        keep.dedup();
+    }
+
+    let spare = limit.saturating_sub(keep.len());
+    if spare > 0 {
+        let step = last as f64 / (spare + 1) as f64;
+        keep.extend((1..=spare).map(|k| (k as f64 * step).round() as usize));
+        keep.sort_unstable();
+        keep.dedup();
+    }
+    keep.into_iter().map(|i| points[i].clone()).collect()
+}
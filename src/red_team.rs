@@ -0,0 +1,287 @@
+// src/red_team.rs
+
+// Synthetic adversarial scenarios. Each generator produces a labeled stream
+// of events for one home: the RawEvent a camera integration would send and
+// the extracted evidence the scoring engine sees, with every event marked
+// hostile or benign. Scenarios cover patterns a per-incident view tends to
+// miss: reconnaissance spread thinly over several nights, a flood of decoy
+// entities hiding a real approach, and an intruder presenting a resident's
+// face. Running a scenario through a ThinkingAIProcessor scores how well it
+// was detected: whether and when hostile incidents alerted, and how many
+// benign ones alerted alongside.
+
+use crate::pipeline::RawEvent;
+use crate::thinking::{AlertDecision, Event, Evidence, ThinkingAIConfig, ThinkingAIProcessor};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+const DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioKind {
+    SlowRecon,        // Short night-time visits on different cameras over several days
+    EntityFlood,      // Many decoy tracks at once while one person goes for the back door
+    SpoofedKnownFace, // An intruder showing a resident's face (photo, mask) at night
+}
+
+impl ScenarioKind {
+    pub const ALL: [ScenarioKind; 3] = [ScenarioKind::SlowRecon, ScenarioKind::EntityFlood, ScenarioKind::SpoofedKnownFace];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScenarioKind::SlowRecon => "slow-recon",
+            ScenarioKind::EntityFlood => "entity-flood",
+            ScenarioKind::SpoofedKnownFace => "spoofed-face",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RedTeamConfig {
+    pub seed: u64,
+    pub start_ts: i64,       // Midnight UTC of the first day
+    pub recon_days: i64,     // Nights the reconnaissance spans
+    pub flood_decoys: usize, // Decoy tracks in the flood
+    pub benign_per_day: usize,
+}
+
+impl Default for RedTeamConfig {
+    fn default() -> Self {
+        Self { seed: 7, start_ts: 1_700_006_400, recon_days: 5, flood_decoys: 30, benign_per_day: 4 }
+    }
+}
+
+#[derive(Debug)]
+pub struct ScenarioStep {
+    pub raw: RawEvent,
+    pub event: Event,
+    pub hostile: bool,
+}
+
+impl ScenarioStep {
+    /// A copy of the raw event for submitting to a pipeline
+    pub fn raw_event(&self) -> RawEvent {
+        RawEvent {
+            event_id: self.raw.event_id,
+            sensor_id: self.raw.sensor_id.clone(),
+            timestamp: self.raw.timestamp,
+            data: self.raw.data.clone(),
+            user_id: self.raw.user_id.clone(),
+            home_id: self.raw.home_id.clone(),
+            image_url: self.raw.image_url.clone(),
+            image_data: self.raw.image_data.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Scenario {
+    pub kind: ScenarioKind,
+    pub home_id: String,
+    pub steps: Vec<ScenarioStep>, // In time order
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioScore {
+    pub scenario: ScenarioKind,
+    pub hostile_events: usize,
+    pub benign_events: usize,
+    pub hostile_incidents: usize,
+    pub detected_incidents: usize,          // Hostile incidents that alerted
+    pub detected: bool,                     // At least one hostile incident alerted
+    pub time_to_detect_s: Option<f64>,      // From the first hostile event to the first hostile alert
+    pub peak_hostile_probability: f64,
+    pub benign_incidents: usize,
+    pub false_alerts: usize,                // Benign incidents that alerted
+}
+
+impl ScenarioScore {
+    pub fn detection_rate(&self) -> f64 {
+        if self.hostile_incidents == 0 {
+            return 0.0;
+        }
+        self.detected_incidents as f64 / self.hostile_incidents as f64
+    }
+}
+
+fn alerted(decision: &AlertDecision) -> bool {
+    matches!(decision, AlertDecision::Standard | AlertDecision::Elevated | AlertDecision::Critical)
+}
+
+struct Builder {
+    kind: ScenarioKind,
+    home_id: String,
+    rng: StdRng,
+    steps: Vec<ScenarioStep>,
+}
+
+impl Builder {
+    fn new(kind: ScenarioKind, config: &RedTeamConfig) -> Self {
+        Self {
+            kind,
+            home_id: format!("redteam_{}_{}", kind.name(), config.seed),
+            rng: StdRng::seed_from_u64(config.seed ^ kind as u64),
+            steps: Vec::new(),
+        }
+    }
+
+    fn jitter(&mut self, llr: f64) -> f64 {
+        llr + self.rng.gen_range(-0.1..0.1)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(&mut self, ts: i64, cam: &str, track: &str, hostile: bool, rang: bool, dwell_s: f64, away_prob: f64, evidence: Evidence) {
+        let evidence = Evidence {
+            llr_time: self.jitter(evidence.llr_time),
+            llr_entry: self.jitter(evidence.llr_entry),
+            llr_behavior: self.jitter(evidence.llr_behavior),
+            ..evidence
+        };
+        let raw = RawEvent {
+            event_id: Uuid::from_u128(self.rng.gen()),
+            sensor_id: cam.to_string(),
+            timestamp: ts,
+            data: format!("person_detected=true|dwell_time={:.0}s|doorbell={}", dwell_s, rang).into(),
+            user_id: format!("{}_owner", self.home_id),
+            home_id: self.home_id.clone(),
+            image_url: None,
+            image_data: None,
+        };
+        let event = Event {
+            ts: ts as f64,
+            cam: cam.to_string(),
+            person_track: track.to_string(),
+            rang_doorbell: rang,
+            knocked: false,
+            dwell_s,
+            away_prob,
+            expected_window: false,
+            token: None,
+            evidence,
+        };
+        self.steps.push(ScenarioStep { raw, event, hostile });
+    }
+
+    /// Daytime callers who ring the bell and leave
+    fn benign_day(&mut self, day_start: i64, count: usize) {
+        for i in 0..count {
+            let ts = day_start + self.rng.gen_range(9 * 3600..18 * 3600);
+            let evidence = Evidence { llr_time: -0.8, llr_entry: -0.6, llr_behavior: -0.5, llr_identity: 0.0, llr_presence: -0.3, llr_token: 0.0, ..Default::default() };
+            self.push(ts, "cam_front_door", &format!("visitor_{}_{}", day_start, i), false, true, 40.0, 0.5, evidence);
+        }
+    }
+
+    fn finish(mut self) -> Scenario {
+        self.steps.sort_by_key(|s| s.raw.timestamp);
+        Scenario { kind: self.kind, home_id: self.home_id, steps: self.steps }
+    }
+}
+
+/// Build a scenario's labeled event stream
+pub fn generate(kind: ScenarioKind, config: &RedTeamConfig) -> Scenario {
+    let mut b = Builder::new(kind, config);
+    match kind {
+        ScenarioKind::SlowRecon => {
+            let cameras = ["cam_side_gate", "cam_back_garden", "cam_driveway", "cam_back_door"];
+            for day in 0..config.recon_days {
+                let day_start = config.start_ts + day * DAY;
+                b.benign_day(day_start, config.benign_per_day);
+                // One short look per night, a new camera each time, never lingering
+                let ts = day_start + b.rng.gen_range(3600..4 * 3600);
+                let cam = cameras[day as usize % cameras.len()];
+                let dwell_s = b.rng.gen_range(15.0..40.0);
+                let evidence = Evidence { llr_time: 0.6, llr_entry: 0.2, llr_behavior: 0.3, llr_identity: 0.0, llr_presence: 0.2, llr_token: 0.0, ..Default::default() };
+                b.push(ts, cam, &format!("recon_{}", day), true, false, dwell_s, 0.3, evidence);
+            }
+        }
+        ScenarioKind::EntityFlood => {
+            b.benign_day(config.start_ts, config.benign_per_day);
+            let start = config.start_ts + 20 * 3600;
+            let cameras = ["cam_front_door", "cam_driveway", "cam_street"];
+            for i in 0..config.flood_decoys {
+                let ts = start + b.rng.gen_range(0..300);
+                let cam = cameras[i % cameras.len()];
+                let evidence = Evidence { llr_time: 0.1, llr_entry: -0.4, llr_behavior: 0.1, llr_identity: 0.0, llr_presence: 0.0, llr_token: 0.0, ..Default::default() };
+                b.push(ts, cam, &format!("decoy_{}", i), false, false, 20.0, 0.4, evidence);
+            }
+            // The real approach, in the middle of the noise
+            for (i, cam) in ["cam_side_gate", "cam_back_garden", "cam_back_door"].iter().enumerate() {
+                let evidence = Evidence { llr_time: 0.2, llr_entry: 0.4 + 0.5 * i as f64, llr_behavior: 0.6, llr_identity: 0.0, llr_presence: 0.3, llr_token: 0.0, ..Default::default() };
+                b.push(start + 120 + 40 * i as i64, cam, "intruder", true, false, 40.0, 0.4, evidence);
+            }
+        }
+        ScenarioKind::SpoofedKnownFace => {
+            for day in 0..2 {
+                let day_start = config.start_ts + day * DAY;
+                b.benign_day(day_start, config.benign_per_day);
+                // The resident coming home in the evening
+                let evidence = Evidence { llr_time: -0.3, llr_entry: -0.2, llr_behavior: -0.4, llr_identity: -2.0, llr_presence: -0.5, llr_token: 0.0, ..Default::default() };
+                b.push(day_start + 19 * 3600, "cam_front_door", &format!("resident_{}", day), false, false, 15.0, 0.6, evidence);
+            }
+            // Resident's face, but at 3am at the back door of an empty house, working the lock
+            let ts = config.start_ts + DAY + 3 * 3600;
+            for (i, cam) in ["cam_back_garden", "cam_back_door", "cam_back_door"].iter().enumerate() {
+                let evidence = Evidence { llr_time: 1.0, llr_entry: 0.8 + 0.4 * i as f64, llr_behavior: 0.8, llr_identity: -2.0, llr_presence: 0.8, llr_token: 0.0, ..Default::default() };
+                b.push(ts + 30 * i as i64, cam, "spoofer", true, false, 60.0, 0.95, evidence);
+            }
+        }
+    }
+    b.finish()
+}
+
+/// Feed a scenario through a fresh processor and score detection
+pub fn run(scenario: &Scenario, config: ThinkingAIConfig) -> ScenarioScore {
+    let processor = ThinkingAIProcessor::new(config);
+    let mut hostile_incidents: HashSet<u64> = HashSet::new();
+    let mut benign_incidents: HashSet<u64> = HashSet::new();
+    let mut alerted_incidents: HashSet<u64> = HashSet::new();
+    let mut peak: HashMap<u64, f64> = HashMap::new();
+    let mut first_hostile_ts = None;
+    let mut first_detection_ts = None;
+
+    for step in &scenario.steps {
+        processor.expire_incidents(step.event.ts);
+        let Some(result) = processor.process_event(&scenario.home_id, step.event.clone()) else {
+            continue;
+        };
+        let id = result.incident_id;
+        if step.hostile {
+            hostile_incidents.insert(id);
+            first_hostile_ts.get_or_insert(step.event.ts);
+        } else {
+            benign_incidents.insert(id);
+        }
+        let p = peak.entry(id).or_insert(0.0);
+        *p = p.max(result.calibrated_probability);
+        if alerted(&result.alert_decision) {
+            alerted_incidents.insert(id);
+            if step.hostile {
+                first_detection_ts.get_or_insert(step.event.ts);
+            }
+        }
+    }
+
+    // An incident joined by both kinds of event counts as hostile
+    let benign_incidents: HashSet<u64> = benign_incidents.difference(&hostile_incidents).copied().collect();
+    let detected_incidents = hostile_incidents.intersection(&alerted_incidents).count();
+    ScenarioScore {
+        scenario: scenario.kind,
+        hostile_events: scenario.steps.iter().filter(|s| s.hostile).count(),
+        benign_events: scenario.steps.iter().filter(|s| !s.hostile).count(),
+        hostile_incidents: hostile_incidents.len(),
+        detected_incidents,
+        detected: detected_incidents > 0,
+        time_to_detect_s: first_detection_ts.zip(first_hostile_ts).map(|(d, h)| d - h),
+        peak_hostile_probability: hostile_incidents.iter().filter_map(|id| peak.get(id)).copied().fold(0.0, f64::max),
+        benign_incidents: benign_incidents.len(),
+        false_alerts: benign_incidents.intersection(&alerted_incidents).count(),
+    }
+}
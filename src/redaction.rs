@@ -0,0 +1,322 @@
+// src/redaction.rs
+
+// Share-safe snapshots. Before an alert image leaves the household (forwarded
+// to a neighbor, posted to a community group) faces that match the home's
+// resident gallery are blacked out and each camera's privacy zones are
+// blurred. The redacted image is kept behind an unguessable, expiring link;
+// the original never leaves the server through this path.
+
+use crate::embeddings::{dot, normalize, EmbeddingError};
+use bytes::Bytes;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use image::codecs::jpeg::JpegEncoder;
+use image::{ColorType, ImageEncoder, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RedactionError {
+    #[error("Could not decode snapshot: {0}")]
+    Decode(String),
+
+    #[error("Could not encode redacted snapshot: {0}")]
+    Encode(String),
+
+    #[error("Snapshot {width}x{height} exceeds pixel limit")]
+    TooLarge { width: u32, height: u32 },
+
+    #[error("Region {0:?} is outside the unit square")]
+    InvalidRegion(NormRect),
+
+    #[error("Invalid face embedding: {0}")]
+    Embedding(#[from] EmbeddingError),
+}
+
+/// Rectangle in fractions of image width and height, so it survives resizing
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl NormRect {
+    pub fn is_valid(&self) -> bool {
+        [self.x, self.y, self.width, self.height].iter().all(|v| v.is_finite())
+            && self.x >= 0.0 && self.y >= 0.0
+            && self.width > 0.0 && self.height > 0.0
+            && self.x + self.width <= 1.0 + f32::EPSILON
+            && self.y + self.height <= 1.0 + f32::EPSILON
+    }
+
+    /// Grow by `fraction` of its size on every side, staying inside the image
+    pub fn padded(&self, fraction: f32) -> NormRect {
+        let (dx, dy) = (self.width * fraction, self.height * fraction);
+        let x = (self.x - dx).max(0.0);
+        let y = (self.y - dy).max(0.0);
+        NormRect {
+            x,
+            y,
+            width: (self.x + self.width + dx).min(1.0) - x,
+            height: (self.y + self.height + dy).min(1.0) - y,
+        }
+    }
+
+    // Pixel bounds (x, y, width, height); None when the rect rounds to nothing
+    fn to_pixels(self, image_width: u32, image_height: u32) -> Option<(u32, u32, u32, u32)> {
+        let x0 = (self.x * image_width as f32).floor().clamp(0.0, image_width as f32) as u32;
+        let y0 = (self.y * image_height as f32).floor().clamp(0.0, image_height as f32) as u32;
+        let x1 = ((self.x + self.width) * image_width as f32).ceil().clamp(0.0, image_width as f32) as u32;
+        let y1 = ((self.y + self.height) * image_height as f32).ceil().clamp(0.0, image_height as f32) as u32;
+        (x1 > x0 && y1 > y0).then(|| (x0, y0, x1 - x0, y1 - y0))
+    }
+}
+
+/// Part of a camera's view that is always blurred when shared, e.g. a neighbor's window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrivacyZone {
+    pub name: String,
+    pub rect: NormRect,
+}
+
+/// A face found in the snapshot, with its embedding when the detector produced one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaceRegion {
+    pub rect: NormRect,
+    #[serde(default)]
+    pub embedding: Option<Vec<f32>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    pub face_match_threshold: f32, // Minimum cosine similarity to a gallery face
+    pub redact_unmatched_faces: bool, // Also black out faces that match nobody
+    pub face_padding: f32,         // Fraction of the face box added on each side
+    pub blur_sigma: f32,
+    pub jpeg_quality: u8,
+    pub max_source_pixels: u64,
+    pub max_faces_per_resident: usize,
+    pub default_link_ttl: Duration,
+    pub max_link_ttl: Duration,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            face_match_threshold: 0.6,
+            redact_unmatched_faces: false,
+            face_padding: 0.15,
+            blur_sigma: 12.0,
+            jpeg_quality: 85,
+            max_source_pixels: 40_000_000,
+            max_faces_per_resident: 10,
+            default_link_ttl: Duration::hours(24),
+            max_link_ttl: Duration::days(7),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedactionReport {
+    pub redacted_faces: usize,
+    pub unmatched_faces: usize, // Faces left visible because they match no resident
+    pub blurred_zones: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedactedSnapshot {
+    pub data: Bytes, // JPEG
+    pub width: u32,
+    pub height: u32,
+    pub report: RedactionReport,
+}
+
+/// Blur `zones`, then black out `faces`. CPU-bound; call from a blocking context.
+pub fn redact(source: &[u8], faces: &[NormRect], zones: &[NormRect], config: &RedactionConfig) -> Result<RedactedSnapshot, RedactionError> {
+    if let Some(bad) = faces.iter().chain(zones).find(|r| !r.is_valid()) {
+        return Err(RedactionError::InvalidRegion(*bad));
+    }
+    let (width, height) = image::io::Reader::new(Cursor::new(source))
+        .with_guessed_format()
+        .map_err(|e| RedactionError::Decode(e.to_string()))?
+        .into_dimensions()
+        .map_err(|e| RedactionError::Decode(e.to_string()))?;
+    if width as u64 * height as u64 > config.max_source_pixels {
+        return Err(RedactionError::TooLarge { width, height });
+    }
+    let mut img: RgbImage = image::load_from_memory(source)
+        .map_err(|e| RedactionError::Decode(e.to_string()))?
+        .to_rgb8();
+
+    let mut blurred_zones = 0;
+    for zone in zones {
+        let Some((x, y, w, h)) = zone.to_pixels(width, height) else { continue };
+        let region = image::imageops::crop_imm(&img, x, y, w, h).to_image();
+        let blurred = image::imageops::blur(&region, config.blur_sigma);
+        image::imageops::replace(&mut img, &blurred, x as i64, y as i64);
+        blurred_zones += 1;
+    }
+
+    // Solid fill rather than blur: a blurred face can still be recognized
+    let mut redacted_faces = 0;
+    for face in faces {
+        let Some((x, y, w, h)) = face.padded(config.face_padding).to_pixels(width, height) else { continue };
+        for py in y..y + h {
+            for px in x..x + w {
+                img.put_pixel(px, py, Rgb([0, 0, 0]));
+            }
+        }
+        redacted_faces += 1;
+    }
+
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, config.jpeg_quality.clamp(1, 100))
+        .write_image(&img, width, height, ColorType::Rgb8)
+        .map_err(|e| RedactionError::Encode(e.to_string()))?;
+    Ok(RedactedSnapshot {
+        data: Bytes::from(out),
+        width,
+        height,
+        report: RedactionReport { redacted_faces, unmatched_faces: 0, blurred_zones },
+    })
+}
+
+#[derive(Debug, Clone)]
+struct GalleryFace {
+    user_id: String,
+    vector: Vec<f32>, // L2-normalized
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedSnapshot {
+    pub token: String,
+    pub home_id: String,
+    pub incident_id: u64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub report: RedactionReport,
+    #[serde(skip)]
+    pub data: Bytes,
+}
+
+/// Resident face gallery, per-camera privacy zones and the share links made from them
+#[derive(Debug, Default)]
+pub struct SnapshotSharing {
+    config: RedactionConfig,
+    gallery: DashMap<String, Vec<GalleryFace>>,               // home_id -> enrolled resident faces
+    privacy_zones: DashMap<(String, String), Vec<PrivacyZone>>, // (home_id, camera_id)
+    links: DashMap<String, SharedSnapshot>,                   // token -> redacted image
+}
+
+impl SnapshotSharing {
+    pub fn new(config: RedactionConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &RedactionConfig {
+        &self.config
+    }
+
+    /// Add a face embedding for a resident; the oldest is dropped past the per-resident cap
+    pub fn enroll_face(&self, home_id: &str, user_id: &str, embedding: &[f32]) -> Result<usize, RedactionError> {
+        let vector = normalize(embedding)?;
+        let mut faces = self.gallery.entry(home_id.to_string()).or_default();
+        if let Some(dim) = faces.first().map(|f| f.vector.len()) {
+            if dim != vector.len() {
+                return Err(EmbeddingError::DimensionMismatch { expected: dim, got: vector.len() }.into());
+            }
+        }
+        faces.push(GalleryFace { user_id: user_id.to_string(), vector });
+        let mine: Vec<usize> = faces.iter().enumerate().filter(|(_, f)| f.user_id == user_id).map(|(i, _)| i).collect();
+        if mine.len() > self.config.max_faces_per_resident {
+            faces.remove(mine[0]);
+        }
+        Ok(faces.iter().filter(|f| f.user_id == user_id).count())
+    }
+
+    /// Forget every face enrolled for a resident
+    pub fn remove_faces(&self, home_id: &str, user_id: &str) -> usize {
+        let Some(mut faces) = self.gallery.get_mut(home_id) else { return 0 };
+        let before = faces.len();
+        faces.retain(|f| f.user_id != user_id);
+        before - faces.len()
+    }
+
+    /// Resident whose enrolled face best matches, if any clears the threshold
+    pub fn match_resident(&self, home_id: &str, embedding: &[f32]) -> Option<(String, f32)> {
+        let query = normalize(embedding).ok()?;
+        let faces = self.gallery.get(home_id)?;
+        faces.iter()
+            .filter(|f| f.vector.len() == query.len())
+            .map(|f| (f.user_id.clone(), dot(&f.vector, &query)))
+            .filter(|(_, similarity)| *similarity >= self.config.face_match_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    pub fn set_privacy_zones(&self, home_id: &str, camera_id: &str, zones: Vec<PrivacyZone>) -> Result<(), RedactionError> {
+        if let Some(bad) = zones.iter().find(|z| !z.rect.is_valid()) {
+            return Err(RedactionError::InvalidRegion(bad.rect));
+        }
+        let key = (home_id.to_string(), camera_id.to_string());
+        if zones.is_empty() {
+            self.privacy_zones.remove(&key);
+        } else {
+            self.privacy_zones.insert(key, zones);
+        }
+        Ok(())
+    }
+
+    pub fn privacy_zones(&self, home_id: &str, camera_id: &str) -> Vec<PrivacyZone> {
+        self.privacy_zones.get(&(home_id.to_string(), camera_id.to_string()))
+            .map(|z| z.clone())
+            .unwrap_or_default()
+    }
+
+    /// Redact a snapshot for sharing: resident faces blacked out, the camera's privacy zones blurred
+    pub fn redact_for_share(&self, home_id: &str, camera_id: &str, source: &[u8], faces: &[FaceRegion]) -> Result<RedactedSnapshot, RedactionError> {
+        let mut to_redact = Vec::new();
+        let mut unmatched = 0;
+        for face in faces {
+            let resident = face.embedding.as_deref().and_then(|e| self.match_resident(home_id, e));
+            if resident.is_some() || self.config.redact_unmatched_faces {
+                to_redact.push(face.rect);
+            } else {
+                unmatched += 1;
+            }
+        }
+        let zones: Vec<NormRect> = self.privacy_zones(home_id, camera_id).into_iter().map(|z| z.rect).collect();
+        let mut redacted = redact(source, &to_redact, &zones, &self.config)?;
+        redacted.report.unmatched_faces = unmatched;
+        Ok(redacted)
+    }
+
+    /// Keep a redacted snapshot behind a fresh token until `ttl` (capped) passes
+    pub fn publish(&self, home_id: &str, incident_id: u64, snapshot: RedactedSnapshot, ttl: Option<Duration>) -> SharedSnapshot {
+        let now = Utc::now();
+        self.links.retain(|_, link| link.expires_at > now);
+        let ttl = ttl.unwrap_or(self.config.default_link_ttl).min(self.config.max_link_ttl);
+        let link = SharedSnapshot {
+            token: hex::encode(rand::random::<[u8; 16]>()),
+            home_id: home_id.to_string(),
+            incident_id,
+            created_at: now,
+            expires_at: now + ttl,
+            report: snapshot.report,
+            data: snapshot.data,
+        };
+        self.links.insert(link.token.clone(), link.clone());
+        link
+    }
+
+    /// The shared image for a token, unless it has expired
+    pub fn shared(&self, token: &str) -> Option<SharedSnapshot> {
+        let link = self.links.get(token)?.clone();
+        (link.expires_at > Utc::now()).then_some(link)
+    }
+
+    pub fn revoke(&self, token: &str) -> Option<SharedSnapshot> {
+        self.links.remove(token).map(|(_, link)| link)
+    }
+}
@@ -0,0 +1,167 @@
+// src/sanitization.rs
+
+// Input sanitation for thinking-AI events, run just before fusion. NaN and
+// infinite evidence channels, timestamps and probabilities either replace
+// with configured defaults or reject the event, depending on the policy.
+// Finite values outside their valid range (a negative dwell, an away
+// probability of 1.3, a timestamp a day in the future) are always normalized.
+// Every change is recorded so the incident's explanation can say its inputs
+// were repaired.
+
+use crate::thinking::{Event, Evidence};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum SanitizationError {
+    #[error("Event rejected for non-finite input: {}", .0.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("; "))]
+    Rejected(Vec<DataQualityIssue>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NonFinitePolicy {
+    #[default]
+    Replace, // Substitute the configured default and flag the event
+    Reject,  // Refuse the event outright
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SanitizationConfig {
+    pub non_finite: NonFinitePolicy,
+    pub default_llr: f64,          // Replacement for a non-finite evidence channel
+    pub max_abs_llr: f64,          // Finite channels beyond this are clamped
+    pub default_away_prob: f64,    // Replacement for a non-finite away probability
+    pub max_future_skew_secs: f64, // Timestamps further ahead than this are set to now
+}
+
+impl Default for SanitizationConfig {
+    fn default() -> Self {
+        Self {
+            non_finite: NonFinitePolicy::Replace,
+            default_llr: 0.0,
+            max_abs_llr: 20.0,
+            default_away_prob: 0.5,
+            max_future_skew_secs: 300.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    NotANumber,
+    Infinite,
+    OutOfRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataQualityIssue {
+    pub field: String,
+    pub kind: IssueKind,
+    pub replaced_with: f64,
+}
+
+impl fmt::Display for DataQualityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            IssueKind::NotANumber => "was NaN",
+            IssueKind::Infinite => "was infinite",
+            IssueKind::OutOfRange => "was out of range",
+        };
+        write!(f, "{} {}, used {}", self.field, what, self.replaced_with)
+    }
+}
+
+// What sanitation changed on one event; empty when the inputs were clean
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataQuality {
+    pub issues: Vec<DataQualityIssue>,
+}
+
+impl DataQuality {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// One line per repaired field, for explanations
+    pub fn flags(&self) -> Vec<String> {
+        self.issues.iter().map(|i| i.to_string()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Sanitizer {
+    config: SanitizationConfig,
+}
+
+impl Sanitizer {
+    pub fn new(config: SanitizationConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &SanitizationConfig {
+        &self.config
+    }
+
+    /// Repair an event in place, or reject it under NonFinitePolicy::Reject
+    pub fn sanitize_event(&self, event: &mut Event, now_ts: f64) -> Result<DataQuality, SanitizationError> {
+        let mut issues = Vec::new();
+
+        event.ts = if !event.ts.is_finite() {
+            issues.push(issue("ts", event.ts, now_ts));
+            now_ts
+        } else if event.ts > now_ts + self.config.max_future_skew_secs || event.ts < 0.0 {
+            issues.push(DataQualityIssue { field: "ts".to_string(), kind: IssueKind::OutOfRange, replaced_with: now_ts });
+            now_ts
+        } else {
+            event.ts
+        };
+        event.away_prob = self.probability("away_prob", event.away_prob, self.config.default_away_prob, &mut issues);
+        event.dwell_s = self.bounded("dwell_s", event.dwell_s, 0.0, 0.0, f64::MAX, &mut issues);
+        self.sanitize_evidence(&mut event.evidence, &mut issues);
+
+        let non_finite = issues.iter().any(|i| i.kind != IssueKind::OutOfRange);
+        if non_finite && self.config.non_finite == NonFinitePolicy::Reject {
+            return Err(SanitizationError::Rejected(issues));
+        }
+        Ok(DataQuality { issues })
+    }
+
+    pub fn sanitize_evidence(&self, evidence: &mut Evidence, issues: &mut Vec<DataQualityIssue>) {
+        let (default, cap) = (self.config.default_llr, self.config.max_abs_llr.abs());
+        evidence.llr_time = self.bounded("evidence.llr_time", evidence.llr_time, default, -cap, cap, issues);
+        evidence.llr_entry = self.bounded("evidence.llr_entry", evidence.llr_entry, default, -cap, cap, issues);
+        evidence.llr_behavior = self.bounded("evidence.llr_behavior", evidence.llr_behavior, default, -cap, cap, issues);
+        evidence.llr_identity = self.bounded("evidence.llr_identity", evidence.llr_identity, default, -cap, cap, issues);
+        evidence.llr_presence = self.bounded("evidence.llr_presence", evidence.llr_presence, default, -cap, cap, issues);
+        evidence.llr_token = self.bounded("evidence.llr_token", evidence.llr_token, default, -cap, cap, issues);
+        for (name, llr) in evidence.custom.iter_mut() {
+            *llr = self.bounded(&format!("evidence.custom.{}", name), *llr, default, -cap, cap, issues);
+        }
+    }
+
+    /// A probability forced into [0, 1], with `default` for non-finite input
+    pub fn probability(&self, field: &str, value: f64, default: f64, issues: &mut Vec<DataQualityIssue>) -> f64 {
+        self.bounded(field, value, default, 0.0, 1.0, issues)
+    }
+
+    fn bounded(&self, field: &str, value: f64, default: f64, min: f64, max: f64, issues: &mut Vec<DataQualityIssue>) -> f64 {
+        if !value.is_finite() {
+            issues.push(issue(field, value, default));
+            default
+        } else if value < min || value > max {
+            let clamped = value.clamp(min, max);
+            issues.push(DataQualityIssue { field: field.to_string(), kind: IssueKind::OutOfRange, replaced_with: clamped });
+            clamped
+        } else {
+            value
+        }
+    }
+}
+
+fn issue(field: &str, value: f64, replaced_with: f64) -> DataQualityIssue {
+    let kind = if value.is_nan() { IssueKind::NotANumber } else { IssueKind::Infinite };
+    DataQualityIssue { field: field.to_string(), kind, replaced_with }
+}
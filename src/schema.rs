@@ -0,0 +1,155 @@
+// src/schema.rs
+//
+// Versioned envelopes for persisted JSON. Every persisted type declares its schema
+// name and current version plus a migration step for each older version. Loading
+// walks the steps one version at a time on the raw JSON, so a snapshot written by
+// any earlier release deserializes into today's struct. JSON written before
+// envelopes existed (bare objects) is treated as version 1.
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::ThreatAssessment;
+use crate::explanation::Explanation;
+use crate::thinking::IncidentStoreSnapshot;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SchemaError {
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Expected schema '{expected}', found '{found}'")]
+    SchemaMismatch { expected: String, found: String },
+
+    #[error("Schema '{schema}' version {version} is newer than supported version {current}")]
+    FromFuture { schema: String, version: u32, current: u32 },
+
+    #[error("No migration from '{schema}' version {version}")]
+    UnsupportedVersion { schema: String, version: u32 },
+
+    #[error("Migration failed: {0}")]
+    Migration(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionedEnvelope<T> {
+    pub schema: String,
+    pub version: u32,
+    pub written_at: DateTime<Utc>,
+    pub data: T,
+}
+
+pub trait Versioned: Serialize + DeserializeOwned {
+    const SCHEMA: &'static str;
+    const CURRENT_VERSION: u32;
+
+    /// Upgrade raw data from `from_version` to `from_version + 1`
+    fn migrate(from_version: u32, data: Value) -> Result<Value, SchemaError>;
+}
+
+/// Wrap a value in an envelope tagged with its current schema version
+pub fn to_versioned_json<T: Versioned>(value: &T) -> Result<String, SchemaError> {
+    let envelope = VersionedEnvelope {
+        schema: T::SCHEMA.to_string(),
+        version: T::CURRENT_VERSION,
+        written_at: Utc::now(),
+        data: value,
+    };
+    Ok(serde_json::to_string(&envelope)?)
+}
+
+/// Load an enveloped or legacy bare value, migrating it to the current version
+pub fn from_versioned_json<T: Versioned>(json: &str) -> Result<T, SchemaError> {
+    let raw: Value = serde_json::from_str(json)?;
+
+    let is_envelope = raw.get("schema").map_or(false, Value::is_string)
+        && raw.get("version").map_or(false, Value::is_u64)
+        && raw.get("data").is_some();
+
+    let (mut version, mut data) = if is_envelope {
+        let envelope: VersionedEnvelope<Value> = serde_json::from_value(raw)?;
+        if envelope.schema != T::SCHEMA {
+            return Err(SchemaError::SchemaMismatch {
+                expected: T::SCHEMA.to_string(),
+                found: envelope.schema,
+            });
+        }
+        (envelope.version, envelope.data)
+    } else {
+        (1, raw)
+    };
+
+    if version > T::CURRENT_VERSION {
+        return Err(SchemaError::FromFuture {
+            schema: T::SCHEMA.to_string(),
+            version,
+            current: T::CURRENT_VERSION,
+        });
+    }
+    while version < T::CURRENT_VERSION {
+        data = T::migrate(version, data)?;
+        version += 1;
+    }
+
+    Ok(serde_json::from_value(data)?)
+}
+
+fn object_mut<'a>(data: &'a mut Value, path: &str) -> Result<&'a mut serde_json::Map<String, Value>, SchemaError> {
+    data.as_object_mut()
+        .ok_or_else(|| SchemaError::Migration(format!("expected object at {}", path)))
+}
+
+impl Versioned for ThreatAssessment {
+    const SCHEMA: &'static str = "threat_assessment";
+    const CURRENT_VERSION: u32 = 3;
+
+    fn migrate(from_version: u32, mut data: Value) -> Result<Value, SchemaError> {
+        match from_version {
+            // v2 added weather/light enrichment to the environmental context
+            1 => {
+                let root = object_mut(&mut data, "$")?;
+                let context = root.get_mut("environmental_context")
+                    .ok_or_else(|| SchemaError::Migration("missing environmental_context".to_string()))?;
+                object_mut(context, "$.environmental_context")?
+                    .entry("environment")
+                    .or_insert(Value::Null);
+                Ok(data)
+            }
+            // v3 replaced the free-text trace with a structured explanation
+            2 => {
+                let root = object_mut(&mut data, "$")?;
+                let trace = match root.remove("explainability_trace") {
+                    Some(Value::String(text)) => text,
+                    _ => String::new(),
+                };
+                root.insert("explanation".to_string(), serde_json::to_value(Explanation::from_text(trace))?);
+                Ok(data)
+            }
+            version => Err(SchemaError::UnsupportedVersion { schema: Self::SCHEMA.to_string(), version }),
+        }
+    }
+}
+
+impl Versioned for IncidentStoreSnapshot {
+    const SCHEMA: &'static str = "incident_store";
+    const CURRENT_VERSION: u32 = 1;
+
+    fn migrate(from_version: u32, _data: Value) -> Result<Value, SchemaError> {
+        Err(SchemaError::UnsupportedVersion { schema: Self::SCHEMA.to_string(), version: from_version })
+    }
+}
+
+// chrono::Duration has no serde support; persist it as whole seconds
+pub mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &chrono::Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(duration.num_seconds())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<chrono::Duration, D::Error> {
+        Ok(chrono::Duration::seconds(i64::deserialize(deserializer)?))
+    }
+}
@@ -0,0 +1,384 @@
+// src/self_test.rs
+
+// Built-in self-test and demo events. A self-test pushes one synthetic event
+// per scenario (a parcel delivery, someone lingering, a break-in) through the
+// real stage chain and records, with a probe middleware, which stages ran,
+// which were skipped and where an event stopped or failed. It then checks
+// every delivery channel the home would use at each severity: whether the
+// channel is set up (an SMS number, an email address, a webhook endpoint),
+// what the recipient's preferences would do with an alert right now, and,
+// when asked, whether a real test message goes out. The result is a health
+// matrix for the installer or the onboarding wizard's "test my setup" button.
+//
+// Synthetic events run under a shadow home id (`<home>:self-test`), so they
+// never open incidents, train trust or move tracks in the real home. Their
+// payload names the scenario (`self_test=break_in`); the `synthetic_events`
+// middleware replaces the extracted evidence with the scenario's own, which
+// is what makes the scenarios land on different severities. Listed in
+// NOVIN_STAGE_MIDDLEWARE, the same middleware turns a server into a demo that
+// reacts believably to the events from `demo_events`.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::delivery::router::{NotificationSeverity, RouteDecision};
+use crate::overnight::DeliveryChannel;
+use crate::pipeline::RawEvent;
+use crate::pipeline_stages::{PipelineRun, Stage, StageControl, StageMiddleware};
+use crate::thinking::{AlertDecision, Event, Evidence};
+
+const MARKER: &str = "self_test=";
+const SHADOW_SUFFIX: &str = ":self-test";
+
+/// Severities every delivery channel is checked at
+pub const CHECKED_SEVERITIES: [NotificationSeverity; 4] = [
+    NotificationSeverity::Info,
+    NotificationSeverity::Standard,
+    NotificationSeverity::Elevated,
+    NotificationSeverity::Critical,
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum SelfTestError {
+    #[error("Unknown synthetic scenario '{0}'")]
+    UnknownScenario(String),
+}
+
+/// Home id the synthetic events of a self-test run under
+pub fn shadow_home(home_id: &str) -> String {
+    format!("{}{}", home_id, SHADOW_SUFFIX)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyntheticScenario {
+    Delivery,  // Rings the bell, leaves a parcel: should stay quiet
+    Lingering, // Hangs around the front for minutes: severity is up to the home's thresholds
+    BreakIn,   // Forced entry while the home is empty: should alert
+}
+
+impl SyntheticScenario {
+    pub const ALL: [SyntheticScenario; 3] = [SyntheticScenario::Delivery, SyntheticScenario::Lingering, SyntheticScenario::BreakIn];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyntheticScenario::Delivery => "delivery",
+            SyntheticScenario::Lingering => "lingering",
+            SyntheticScenario::BreakIn => "break_in",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, SelfTestError> {
+        Self::ALL.into_iter()
+            .find(|s| s.name() == name)
+            .ok_or_else(|| SelfTestError::UnknownScenario(name.to_string()))
+    }
+
+    /// Whether the scenario should notify anyone; None when it depends on the home
+    pub fn expects_alert(&self) -> Option<bool> {
+        match self {
+            SyntheticScenario::Delivery => Some(false),
+            SyntheticScenario::Lingering => None,
+            SyntheticScenario::BreakIn => Some(true),
+        }
+    }
+
+    fn camera(&self) -> &'static str {
+        match self {
+            SyntheticScenario::Delivery | SyntheticScenario::Lingering => "self_test_front_door",
+            SyntheticScenario::BreakIn => "self_test_back_door",
+        }
+    }
+
+    /// The scenario's event at `at`
+    pub fn event(&self, home_id: &str, user_id: &str, at: DateTime<Utc>) -> RawEvent {
+        RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: self.camera().to_string(),
+            timestamp: at.timestamp(),
+            data: format!("{}{}|person_detected=true", MARKER, self.name()).into(),
+            user_id: user_id.to_string(),
+            home_id: home_id.to_string(),
+            image_url: None,
+            image_data: None,
+        }
+    }
+
+    // Context and evidence a real detection of this kind would carry
+    fn shape(&self, event: &mut Event) {
+        let (evidence, rang_doorbell, dwell_s, away_prob) = match self {
+            SyntheticScenario::Delivery => (Evidence {
+                llr_time: -0.5,
+                llr_entry: -0.5,
+                llr_behavior: -1.0,
+                llr_identity: -1.5,
+                ..Default::default()
+            }, true, 20.0, 0.2),
+            SyntheticScenario::Lingering => (Evidence {
+                llr_time: 0.8,
+                llr_behavior: 1.5,
+                llr_identity: 0.3,
+                ..Default::default()
+            }, false, 240.0, 0.6),
+            SyntheticScenario::BreakIn => (Evidence {
+                llr_time: 2.0,
+                llr_entry: 3.0,
+                llr_behavior: 2.5,
+                llr_identity: 1.0,
+                llr_presence: 1.5,
+                ..Default::default()
+            }, false, 90.0, 0.95),
+        };
+        event.evidence = evidence;
+        event.rang_doorbell = rang_doorbell;
+        event.dwell_s = dwell_s;
+        event.away_prob = away_prob;
+    }
+}
+
+/// The scenario an event's payload names, if any
+pub fn scenario_of(event: &RawEvent) -> Option<SyntheticScenario> {
+    event.data.as_str().split('|')
+        .find_map(|field| field.strip_prefix(MARKER))
+        .and_then(|name| SyntheticScenario::parse(name).ok())
+}
+
+/// A demo stream: `count` events cycling through the scenarios, `spacing` apart from `start`
+pub fn demo_events(home_id: &str, user_id: &str, start: DateTime<Utc>, spacing: Duration, count: usize) -> Vec<RawEvent> {
+    (0..count)
+        .map(|i| SyntheticScenario::ALL[i % SyntheticScenario::ALL.len()].event(home_id, user_id, start + spacing * i as i32))
+        .collect()
+}
+
+/// Gives synthetic events their scenario's evidence once Sanitize has built the thinking event
+pub struct SyntheticEvents {
+    shadow_only: bool, // Leave events for real homes alone
+}
+
+impl SyntheticEvents {
+    /// For self-tests: only events under a shadow home are shaped
+    pub fn self_test() -> Self {
+        Self { shadow_only: true }
+    }
+
+    /// For demo servers: every marked event is shaped
+    pub fn demo() -> Self {
+        Self { shadow_only: false }
+    }
+}
+
+#[async_trait]
+impl StageMiddleware for SyntheticEvents {
+    fn name(&self) -> &str {
+        "synthetic_events"
+    }
+
+    async fn after(&self, stage: Stage, run: &mut PipelineRun) {
+        if stage != Stage::Sanitize || (self.shadow_only && !run.event.home_id.ends_with(SHADOW_SUFFIX)) {
+            return;
+        }
+        if let (Some(scenario), Some(event)) = (scenario_of(&run.event), run.thinking_event.as_mut()) {
+            scenario.shape(event);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    Skipped,    // Left out by middleware or not part of the plan
+    Failed,
+    NotReached, // An earlier stage ended the event
+    Warning,    // Works, but not as a household would expect
+}
+
+#[derive(Default)]
+struct ProbeRecord {
+    before: Vec<Stage>,
+    after: Vec<Stage>,
+    decision: Option<AlertDecision>,
+    probability: Option<f64>,
+}
+
+/// Records which stages each probed event entered and completed; the outermost middleware during a self-test
+#[derive(Default)]
+pub struct StageProbe {
+    records: Mutex<HashMap<Uuid, ProbeRecord>>,
+}
+
+impl StageProbe {
+    pub fn watch(&self, event_id: Uuid) {
+        if let Ok(mut records) = self.records.lock() {
+            records.insert(event_id, ProbeRecord::default());
+        }
+    }
+
+    /// Stage results for a watched event: `error` if processing failed, else the processed summary
+    pub fn stages(&self, event_id: Uuid, error: Option<&str>, summary: Option<&str>) -> Vec<StageCheck> {
+        let records = self.records.lock().ok();
+        let Some(record) = records.as_ref().and_then(|r| r.get(&event_id)) else {
+            return Vec::new();
+        };
+        let last_entered = record.before.last().copied();
+        Stage::ALL.iter().map(|&stage| {
+            let (status, detail) = match (record.before.contains(&stage), record.after.contains(&stage)) {
+                (true, true) => (CheckStatus::Passed, None),
+                (true, false) if last_entered != Some(stage) => (CheckStatus::Skipped, None),
+                // The last stage entered never completed: it failed, or middleware skipped it or halted the event
+                (true, false) => match error {
+                    Some(e) => (CheckStatus::Failed, Some(e.to_string())),
+                    None => (CheckStatus::Skipped, summary.map(str::to_string)),
+                },
+                (false, _) => (CheckStatus::NotReached, summary.map(str::to_string)),
+            };
+            StageCheck { stage, status, detail }
+        }).collect()
+    }
+
+    /// Final decision and probability the event reached, if fusion ran
+    pub fn outcome(&self, event_id: Uuid) -> (Option<AlertDecision>, Option<f64>) {
+        self.records.lock().ok()
+            .and_then(|r| r.get(&event_id).map(|r| (r.decision.clone(), r.probability)))
+            .unwrap_or((None, None))
+    }
+}
+
+#[async_trait]
+impl StageMiddleware for StageProbe {
+    fn name(&self) -> &str {
+        "self_test_probe"
+    }
+
+    async fn before(&self, stage: Stage, run: &mut PipelineRun) -> StageControl {
+        if let Some(record) = self.records.lock().ok().as_mut().and_then(|r| r.get_mut(&run.event.event_id)) {
+            record.before.push(stage);
+        }
+        StageControl::Continue
+    }
+
+    async fn after(&self, stage: Stage, run: &mut PipelineRun) {
+        if let Some(record) = self.records.lock().ok().as_mut().and_then(|r| r.get_mut(&run.event.event_id)) {
+            record.after.push(stage);
+            if let Some(result) = &run.result {
+                record.decision = Some(result.alert_decision.clone());
+                record.probability = Some(result.calibrated_probability);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StageCheck {
+    pub stage: Stage,
+    pub status: CheckStatus,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioCheck {
+    pub scenario: SyntheticScenario,
+    pub event_id: Uuid,
+    pub stages: Vec<StageCheck>,
+    pub decision: Option<AlertDecision>,
+    pub probability: Option<f64>,
+    pub status: CheckStatus, // Worst stage, or Warning when the decision is not what the scenario expects
+    pub detail: Option<String>,
+}
+
+impl ScenarioCheck {
+    pub fn new(scenario: SyntheticScenario, event_id: Uuid, stages: Vec<StageCheck>, decision: Option<AlertDecision>, probability: Option<f64>) -> Self {
+        let failed = stages.iter().find(|s| s.status == CheckStatus::Failed);
+        let (status, detail) = match (failed, scenario.expects_alert(), &decision) {
+            (Some(stage), _, _) => (CheckStatus::Failed, Some(format!("{:?} stage failed: {}", stage.stage, stage.detail.as_deref().unwrap_or("no detail")))),
+            (None, _, _) if stages.iter().any(|s| s.status == CheckStatus::NotReached) => {
+                (CheckStatus::Warning, Some("stopped before reaching every stage".to_string()))
+            }
+            (None, Some(expected), Some(reached)) if NotificationSeverity::from_decision(reached).is_some() != expected => {
+                let verdict = if expected { "did not alert" } else { "alerted" };
+                (CheckStatus::Warning, Some(format!("{} ({:?})", verdict, reached)))
+            }
+            _ => (CheckStatus::Passed, None),
+        };
+        Self { scenario, event_id, stages, decision, probability, status, detail }
+    }
+}
+
+/// What a recipient's preferences would do with an alert of one severity
+#[derive(Debug, Clone, Serialize)]
+pub struct SeverityRoute {
+    pub severity: NotificationSeverity,
+    pub decision: Option<RouteDecision>, // None: not a recipient at this severity
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelCheck {
+    pub user_id: Option<String>, // None for home-level destinations (webhooks)
+    pub channel: DeliveryChannel,
+    pub configured: bool,        // Has a number, address or endpoint to send to
+    pub routes: Vec<SeverityRoute>,
+    pub delivered: Option<bool>, // Test message outcome, when one was sent
+    pub status: CheckStatus,
+    pub detail: Option<String>,
+}
+
+impl ChannelCheck {
+    pub fn new(user_id: Option<String>, channel: DeliveryChannel, configured: Result<(), String>, routes: Vec<SeverityRoute>) -> Self {
+        let critical_delivers = routes.iter()
+            .any(|r| r.severity == NotificationSeverity::Critical && r.decision == Some(RouteDecision::Deliver));
+        let (status, detail) = match configured {
+            Err(reason) => (CheckStatus::Failed, Some(reason)),
+            Ok(()) if !critical_delivers => (CheckStatus::Warning, Some("a critical alert would not be delivered right now".to_string())),
+            Ok(()) => (CheckStatus::Passed, None),
+        };
+        Self { user_id, channel, configured: status != CheckStatus::Failed, routes, delivered: None, status, detail }
+    }
+
+    /// Record a test message's outcome
+    pub fn record_delivery(&mut self, outcome: Result<(), String>) {
+        self.delivered = Some(outcome.is_ok());
+        if let Err(e) = outcome {
+            self.status = CheckStatus::Failed;
+            self.detail = Some(format!("test message failed: {}", e));
+        }
+    }
+}
+
+/// The health matrix: stages by scenario, channels by recipient
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub home_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub scenarios: Vec<ScenarioCheck>,
+    pub channels: Vec<ChannelCheck>,
+    pub issues: Vec<String>, // One line per failure or warning, for the wizard to show
+    pub healthy: bool,       // No failures; warnings are allowed
+}
+
+impl SelfTestReport {
+    /// `problems` are failures found outside the matrix, e.g. no notification router
+    pub fn new(home_id: &str, started_at: DateTime<Utc>, scenarios: Vec<ScenarioCheck>, channels: Vec<ChannelCheck>, problems: Vec<String>) -> Self {
+        let healthy = problems.is_empty()
+            && scenarios.iter().all(|s| s.status != CheckStatus::Failed)
+            && channels.iter().all(|c| c.status != CheckStatus::Failed);
+        let mut issues = problems;
+        issues.extend(scenarios.iter().filter_map(|s| s.detail.as_ref().map(|d| format!("{}: {}", s.scenario.name(), d))));
+        issues.extend(channels.iter().filter_map(|c| c.detail.as_ref().map(|d| {
+            format!("{:?} for {}: {}", c.channel, c.user_id.as_deref().unwrap_or("the home"), d)
+        })));
+        Self {
+            home_id: home_id.to_string(),
+            started_at,
+            finished_at: Utc::now(),
+            scenarios,
+            channels,
+            issues,
+            healthy,
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// src/snapshot_priority.rs
+
+// Which snapshot to fetch first when the image preloader is busy. The fetch
+// priority starts from the live probability of the incident already open on
+// the event's camera: a likely threat is fetched ahead of everything, a
+// camera with nothing going on waits behind the rest. The camera's zone then
+// shifts it: after dark the back of the house (back door, yard, interior) is
+// raised two levels and the other private approaches one, while street views
+// are lowered. A person in the back garden at night is therefore a Critical
+// fetch even before their incident has a probability. Zones and the home's
+// timezone come from the onboarding config.
+
+use chrono::{DateTime, Timelike, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use std::collections::HashMap;
+
+use crate::image_preloader::Priority;
+use crate::onboarding::ZoneKind;
+
+#[derive(Debug, Clone)]
+pub struct SnapshotPriorityConfig {
+    pub critical_probability: f64, // Live incident probability fetched as Critical
+    pub high_probability: f64,
+    pub low_probability: f64,      // Below this an open incident's camera is fetched as Low
+    pub night_start_hour: u32,     // Local hours counted as night, wrapping past midnight
+    pub night_end_hour: u32,
+}
+
+impl Default for SnapshotPriorityConfig {
+    fn default() -> Self {
+        Self {
+            critical_probability: 0.5,
+            high_probability: 0.15,
+            low_probability: 0.03,
+            night_start_hour: 21,
+            night_end_hour: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HomeLayout {
+    timezone: Tz,
+    zones: HashMap<String, ZoneKind>, // Camera -> zone kind
+}
+
+#[derive(Debug, Default)]
+pub struct SnapshotPriorityPolicy {
+    config: SnapshotPriorityConfig,
+    homes: DashMap<String, HomeLayout>,
+}
+
+const LEVELS: [Priority; 4] = [Priority::Low, Priority::Normal, Priority::High, Priority::Critical];
+
+impl SnapshotPriorityPolicy {
+    pub fn new(config: SnapshotPriorityConfig) -> Self {
+        Self { config, homes: DashMap::new() }
+    }
+
+    pub fn config(&self) -> &SnapshotPriorityConfig {
+        &self.config
+    }
+
+    /// Zone kind of each camera and the timezone that decides when it is night
+    pub fn set_layout(&self, home_id: &str, zones: HashMap<String, ZoneKind>, timezone: &str) {
+        let timezone = timezone.parse().unwrap_or(Tz::UTC);
+        self.homes.insert(home_id.to_string(), HomeLayout { timezone, zones });
+    }
+
+    /// Fetch priority for a snapshot from `camera`, given the live probability
+    /// of the incident open on it, if any
+    pub fn priority(&self, home_id: &str, camera: &str, live_probability: Option<f64>, at: DateTime<Utc>) -> Priority {
+        let base = match live_probability {
+            Some(p) if p >= self.config.critical_probability => Priority::Critical,
+            Some(p) if p >= self.config.high_probability => Priority::High,
+            Some(p) if p < self.config.low_probability => Priority::Low,
+            _ => Priority::Normal,
+        };
+        let Some(layout) = self.homes.get(home_id) else {
+            return base;
+        };
+        let Some(kind) = layout.zones.get(camera) else {
+            return base;
+        };
+        let hour = at.with_timezone(&layout.timezone).hour();
+        let night = if self.config.night_start_hour <= self.config.night_end_hour {
+            hour >= self.config.night_start_hour && hour < self.config.night_end_hour
+        } else {
+            hour >= self.config.night_start_hour || hour < self.config.night_end_hour
+        };
+        let shift: i32 = match (kind, night) {
+            (ZoneKind::BackDoor | ZoneKind::Yard | ZoneKind::Interior, true) => 2,
+            (ZoneKind::FrontDoor | ZoneKind::Garage | ZoneKind::Perimeter, true) => 1,
+            (ZoneKind::Interior, false) => 1,
+            (ZoneKind::Street, _) => -1,
+            _ => 0,
+        };
+        let level = LEVELS.iter().position(|p| *p == base).unwrap_or(1) as i32 + shift;
+        LEVELS[level.clamp(0, LEVELS.len() as i32 - 1) as usize]
+    }
+}
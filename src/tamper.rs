@@ -0,0 +1,258 @@
+// src/tamper.rs
+
+// Scene change detection per camera. Each camera keeps a small grayscale
+// reference of its normal view; periodic snapshots are compared against it to
+// spot blinding (a light aimed at the lens), covering (tape, paint, a bag) and
+// large viewpoint shifts (the camera was turned away). A confirmed change
+// raises an alert and the camera's evidence is discounted until someone clears
+// it. Healthy snapshots slowly refresh the reference so daylight changes do
+// not accumulate into false alarms.
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const THUMB_SIZE: u32 = 32;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TamperError {
+    #[error("Could not decode snapshot: {0}")]
+    Decode(String),
+
+    #[error("No tamper alert for camera {0}")]
+    NotTampered(String),
+
+    #[error("Tamper detection is not enabled")]
+    Disabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TamperKind {
+    Blinded,        // Frame washed out by a bright light
+    Covered,        // Detail gone: lens covered, sprayed or blacked out
+    ViewpointShift, // Scene no longer matches the reference: camera moved
+}
+
+impl TamperKind {
+    /// Blinding and covering look deliberate; a shifted view is usually maintenance
+    pub fn is_security(&self) -> bool {
+        matches!(self, TamperKind::Blinded | TamperKind::Covered)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TamperConfig {
+    pub check_interval: Duration,  // Minimum time between checks of one camera from event snapshots
+    pub confirm_after: u32,        // Consecutive suspicious snapshots before an alert
+    pub saturated_mean: f32,       // Mean brightness above this, with little contrast, is blinding
+    pub flat_std: f32,             // Contrast below this counts as "little"
+    pub covered_edge_ratio: f32,   // Edge energy below this fraction of the reference is covering
+    pub min_similarity: f32,       // Correlation with the reference below this is a viewpoint shift
+    pub reference_blend: f32,      // Weight of a healthy snapshot when refreshing the reference
+    pub tampered_reliability: f64, // Evidence reliability of a camera with an open alert
+}
+
+impl Default for TamperConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::minutes(5),
+            confirm_after: 2,
+            saturated_mean: 0.9,
+            flat_std: 0.06,
+            covered_edge_ratio: 0.2,
+            min_similarity: 0.4,
+            reference_blend: 0.1,
+            tampered_reliability: 0.3,
+        }
+    }
+}
+
+/// Downscaled grayscale view with the statistics the checks use
+#[derive(Debug, Clone)]
+struct SceneFingerprint {
+    pixels: Vec<f32>, // THUMB_SIZE x THUMB_SIZE, 0..1
+    mean: f32,
+    std: f32,
+    edge_energy: f32, // Mean absolute difference between neighbouring pixels
+}
+
+impl SceneFingerprint {
+    fn from_image(bytes: &[u8]) -> Result<Self, TamperError> {
+        let gray = image::load_from_memory(bytes).map_err(|e| TamperError::Decode(e.to_string()))?.to_luma8();
+        let thumb = image::imageops::resize(&gray, THUMB_SIZE, THUMB_SIZE, FilterType::Triangle);
+        Ok(Self::from_pixels(thumb.pixels().map(|p| p.0[0] as f32 / 255.0).collect()))
+    }
+
+    fn from_pixels(pixels: Vec<f32>) -> Self {
+        let n = pixels.len().max(1) as f32;
+        let mean = pixels.iter().sum::<f32>() / n;
+        let std = (pixels.iter().map(|p| (p - mean).powi(2)).sum::<f32>() / n).sqrt();
+        let size = THUMB_SIZE as usize;
+        let mut edges = 0.0;
+        for y in 0..size {
+            for x in 0..size {
+                let p = pixels[y * size + x];
+                if x + 1 < size {
+                    edges += (p - pixels[y * size + x + 1]).abs();
+                }
+                if y + 1 < size {
+                    edges += (p - pixels[(y + 1) * size + x]).abs();
+                }
+            }
+        }
+        Self { pixels, mean, std, edge_energy: edges / n }
+    }
+
+    /// Zero-mean normalized correlation; insensitive to overall brightness and contrast
+    fn similarity(&self, other: &SceneFingerprint) -> f32 {
+        if self.std < f32::EPSILON || other.std < f32::EPSILON {
+            return 0.0;
+        }
+        let n = self.pixels.len().max(1) as f32;
+        let covariance = self.pixels.iter().zip(&other.pixels)
+            .map(|(a, b)| (a - self.mean) * (b - other.mean))
+            .sum::<f32>() / n;
+        covariance / (self.std * other.std)
+    }
+
+    fn blend(&mut self, other: &SceneFingerprint, weight: f32) {
+        let pixels = self.pixels.iter().zip(&other.pixels).map(|(a, b)| a * (1.0 - weight) + b * weight).collect();
+        *self = Self::from_pixels(pixels);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TamperAlert {
+    pub home_id: String,
+    pub camera_id: String,
+    pub kind: TamperKind,
+    pub detected_at: DateTime<Utc>,
+    pub similarity: f32, // Correlation with the reference when raised
+}
+
+/// Outcome of comparing one snapshot with the camera's reference
+#[derive(Debug, Clone, Serialize)]
+pub struct TamperCheck {
+    pub camera_id: String,
+    pub finding: Option<TamperKind>, // What this snapshot looks like; None when normal
+    pub similarity: f32,
+    pub baseline: bool,              // The snapshot became the camera's first reference
+    pub raised: Option<TamperAlert>, // Set only when this snapshot confirmed a new alert
+}
+
+#[derive(Debug, Default)]
+struct CameraScene {
+    reference: Option<SceneFingerprint>,
+    last_check: Option<DateTime<Utc>>,
+    suspect: Option<(TamperKind, u32)>,
+    alert: Option<TamperAlert>,
+}
+
+#[derive(Debug, Default)]
+pub struct TamperDetector {
+    config: TamperConfig,
+    cameras: DashMap<(String, String), CameraScene>,
+}
+
+impl TamperDetector {
+    pub fn new(config: TamperConfig) -> Self {
+        Self { config, cameras: DashMap::new() }
+    }
+
+    pub fn config(&self) -> &TamperConfig {
+        &self.config
+    }
+
+    /// Whether enough time has passed since the camera was last checked
+    pub fn due(&self, home_id: &str, camera_id: &str, at: DateTime<Utc>) -> bool {
+        self.cameras.get(&(home_id.to_string(), camera_id.to_string()))
+            .and_then(|c| c.last_check)
+            .map_or(true, |last| at - last >= self.config.check_interval)
+    }
+
+    /// Compare a snapshot with the camera's reference; the first snapshot becomes the reference
+    pub fn check(&self, home_id: &str, camera_id: &str, snapshot: &[u8], at: DateTime<Utc>) -> Result<TamperCheck, TamperError> {
+        let scene = SceneFingerprint::from_image(snapshot)?;
+        let mut entry = self.cameras.entry((home_id.to_string(), camera_id.to_string())).or_default();
+        let camera = entry.value_mut();
+        camera.last_check = Some(at);
+
+        let Some(reference) = camera.reference.as_mut() else {
+            camera.reference = Some(scene);
+            return Ok(TamperCheck { camera_id: camera_id.to_string(), finding: None, similarity: 1.0, baseline: true, raised: None });
+        };
+        let similarity = scene.similarity(reference);
+        let finding = if scene.mean >= self.config.saturated_mean && scene.std <= self.config.flat_std {
+            Some(TamperKind::Blinded)
+        } else if scene.edge_energy < reference.edge_energy * self.config.covered_edge_ratio && scene.std <= self.config.flat_std {
+            Some(TamperKind::Covered)
+        } else if similarity < self.config.min_similarity {
+            Some(TamperKind::ViewpointShift)
+        } else {
+            None
+        };
+
+        let mut raised = None;
+        match finding {
+            None => {
+                camera.suspect = None;
+                if camera.alert.is_none() {
+                    reference.blend(&scene, self.config.reference_blend);
+                }
+            }
+            Some(kind) => {
+                let count = match camera.suspect {
+                    Some((previous, count)) if previous == kind => count + 1,
+                    _ => 1,
+                };
+                camera.suspect = Some((kind, count));
+                if count >= self.config.confirm_after && camera.alert.is_none() {
+                    let alert = TamperAlert {
+                        home_id: home_id.to_string(),
+                        camera_id: camera_id.to_string(),
+                        kind,
+                        detected_at: at,
+                        similarity,
+                    };
+                    camera.alert = Some(alert.clone());
+                    raised = Some(alert);
+                }
+            }
+        }
+        Ok(TamperCheck { camera_id: camera_id.to_string(), finding, similarity, baseline: false, raised })
+    }
+
+    /// Close a camera's alert. With `rebaseline` the next snapshot becomes the
+    /// new reference, for a camera that was deliberately moved.
+    pub fn clear(&self, home_id: &str, camera_id: &str, rebaseline: bool) -> Result<TamperAlert, TamperError> {
+        let mut camera = self.cameras.get_mut(&(home_id.to_string(), camera_id.to_string()))
+            .ok_or_else(|| TamperError::NotTampered(camera_id.to_string()))?;
+        let alert = camera.alert.take().ok_or_else(|| TamperError::NotTampered(camera_id.to_string()))?;
+        camera.suspect = None;
+        if rebaseline {
+            camera.reference = None;
+        }
+        Ok(alert)
+    }
+
+    /// Open alerts for a home
+    pub fn alerts(&self, home_id: &str) -> Vec<TamperAlert> {
+        let mut alerts: Vec<TamperAlert> = self.cameras.iter()
+            .filter(|c| c.key().0 == home_id)
+            .filter_map(|c| c.alert.clone())
+            .collect();
+        alerts.sort_by_key(|a| a.detected_at);
+        alerts
+    }
+
+    /// Reliability multipliers for the home's cameras with open alerts
+    pub fn reliability_factors(&self, home_id: &str) -> HashMap<String, f64> {
+        self.cameras.iter()
+            .filter(|c| c.key().0 == home_id && c.alert.is_some())
+            .map(|c| (c.key().1.clone(), self.config.tampered_reliability))
+            .collect()
+    }
+}
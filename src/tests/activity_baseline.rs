@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod activity_baseline_tests {
+    use crate::activity_baseline::{ActivityBaseline, ActivityBaselineConfig};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use std::collections::HashMap;
+
+    // Tuesday 2026-01-06 22:10 UTC, the first of several weeks of history
+    fn tuesday_night(week: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 6, 22, 10, 0).unwrap() + Duration::weeks(week)
+    }
+
+    fn baseline() -> ActivityBaseline {
+        let baseline = ActivityBaseline::new(ActivityBaselineConfig::default());
+        baseline.set_layout("home_1", HashMap::from([("cam_garden".to_string(), "back garden".to_string())]), "UTC");
+        baseline
+    }
+
+    #[test]
+    fn test_busy_slot_scores_behavior_evidence_and_reads_as_a_multiple_of_usual() {
+        let baseline = baseline();
+        // Two events every Tuesday night for four weeks
+        for week in 0..4 {
+            for minute in [0, 20] {
+                baseline.observe("home_1", "cam_garden", tuesday_night(week) + Duration::minutes(minute));
+            }
+        }
+
+        let at = tuesday_night(4);
+        let mut last = None;
+        for minute in 0..6 {
+            last = Some(baseline.observe("home_1", "cam_garden", at + Duration::minutes(minute * 5)));
+        }
+        let deviation = last.unwrap();
+        assert_eq!(deviation.zone, "back garden");
+        assert_eq!(deviation.observed, 6);
+        assert!((deviation.expected - 2.0).abs() < 0.1);
+        assert!(deviation.behavior_llr > 0.0 && deviation.behavior_llr <= baseline.config().max_llr);
+        assert_eq!(deviation.narrative(), "Activity in the back garden was 3x the usual for a Tuesday night.");
+    }
+
+    #[test]
+    fn test_nothing_is_scored_before_enough_history_or_at_the_usual_level() {
+        let baseline = baseline();
+        for minute in 0..10 {
+            let early = baseline.observe("home_1", "cam_garden", tuesday_night(0) + Duration::minutes(minute));
+            assert_eq!(early.behavior_llr, 0.0);
+        }
+        for week in 1..5 {
+            for minute in 0..10 {
+                baseline.observe("home_1", "cam_garden", tuesday_night(week) + Duration::minutes(minute));
+            }
+        }
+        let usual = baseline.observe("home_1", "cam_garden", tuesday_night(5));
+        assert_eq!(usual.behavior_llr, 0.0);
+
+        let view = baseline.view("home_1", tuesday_night(5));
+        assert!(view.scoring);
+        assert_eq!(view.zones.len(), 1);
+        assert!(view.zones[0].expected[1][22] > 0.0);
+        assert_eq!(view.zones[0].expected[1][21], 0.0);
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod adaptive_thresholds_tests {
+    use crate::adaptive_thresholds::{AdaptiveThresholdConfig, AdaptiveThresholds, ThresholdSource};
+    use crate::thinking::IncidentLabel;
+    use chrono::{Duration, TimeZone, Utc};
+
+    const DEFAULT: f64 = -1.7346;
+
+    #[test]
+    fn test_labels_move_the_threshold_within_bounds_and_reset_restores_default() {
+        let config = AdaptiveThresholdConfig { step: 0.1, max_modifier: 0.25, ..AdaptiveThresholdConfig::default() };
+        let store = AdaptiveThresholds::new(config, DEFAULT);
+        let now = Utc.with_ymd_and_hms(2026, 5, 1, 9, 0, 0).unwrap();
+        assert_eq!(store.current("home_1").version, 0);
+
+        store.onboard("home_1", -1.0, now - Duration::days(1), now).unwrap().unwrap();
+        // Labels that agree with the decision teach nothing
+        assert!(store.learn("home_1", true, IncidentLabel::Threat, now).unwrap().is_none());
+        for _ in 0..4 {
+            store.learn("home_1", true, IncidentLabel::Benign, now).unwrap();
+        }
+        let current = store.current("home_1");
+        assert_eq!(current.source, ThresholdSource::Learned);
+        assert!((current.modifier - 0.25).abs() < 1e-9, "modifier capped at the bound");
+        assert!((current.effective_logit + 0.75).abs() < 1e-9);
+        assert_eq!(current.version, 4, "the capped fourth label records nothing");
+
+        let drift = store.drift("home_1");
+        assert!((drift.drift_logit - (-0.75 - DEFAULT)).abs() < 1e-9);
+        assert_eq!(drift.history.len(), 4);
+
+        let reset = store.reset("home_1", "owner", now).unwrap();
+        assert_eq!((reset.source, reset.effective_logit, reset.modifier), (ThresholdSource::Reset, DEFAULT, 0.0));
+        assert_eq!(store.effective(), vec![("home_1".to_string(), DEFAULT)]);
+    }
+
+    #[test]
+    fn test_history_survives_restart_and_reapplied_config_records_nothing() {
+        let dir = std::env::temp_dir().join(format!("novin_thresholds_{}", uuid::Uuid::new_v4()));
+        let configured_at = Utc.with_ymd_and_hms(2026, 5, 1, 9, 0, 0).unwrap();
+        {
+            let store = AdaptiveThresholds::persistent(AdaptiveThresholdConfig::default(), DEFAULT, dir.clone()).unwrap();
+            store.onboard("home/1", -1.2, configured_at, configured_at).unwrap();
+            store.learn("home/1", false, IncidentLabel::Threat, configured_at + Duration::hours(1)).unwrap();
+        }
+
+        let store = AdaptiveThresholds::persistent(AdaptiveThresholdConfig::default(), DEFAULT, dir.clone()).unwrap();
+        let current = store.current("home/1");
+        assert_eq!(current.version, 2);
+        assert!((current.effective_logit - (-1.25)).abs() < 1e-9);
+        // Saved configs are re-applied at startup; the learned modifier must survive that
+        assert!(store.onboard("home/1", -1.2, configured_at, configured_at + Duration::days(1)).unwrap().is_none());
+        assert_eq!(store.current("home/1").version, 2);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod adversarial_handoff_tests {
+    use crate::tests::support;
+    use crate::thinking::{
+        AdversarialAnalyzer, AdversarialFindings, Event, Evidence, HandoffConfig, HandoffError, HandoffRequest,
+        ThinkingAIConfig, ThinkingAIProcessor,
+    };
+    use crate::thinking::adversarial_handoff::forward;
+    use crate::counter_surveillance::{CounterSurveillanceSystem, ReconnaissanceAnalyzer, Sighting, SubjectKind};
+    use async_trait::async_trait;
+    use chrono::DateTime;
+    use std::sync::Arc;
+
+    struct Recon;
+
+    #[async_trait]
+    impl AdversarialAnalyzer for Recon {
+        async fn analyze(&self, _request: &HandoffRequest) -> Result<AdversarialFindings, HandoffError> {
+            Ok(AdversarialFindings {
+                threat_adjustment_llr: 5.0, // Deliberately out of bounds
+                countermeasures: vec!["Turn on exterior lighting".to_string()],
+                summary: "possible reconnaissance".to_string(),
+            })
+        }
+    }
+
+    fn event(behavior: f64) -> Event {
+        support::event(1_700_000_000.0).cam("side_gate").dwell(60.0)
+            .evidence(Evidence { llr_behavior: behavior, llr_identity: 0.5, ..Default::default() })
+            .build()
+    }
+
+    #[test]
+    fn test_band_is_half_open() {
+        let config = HandoffConfig { lower: 0.2, upper: 0.5, ..HandoffConfig::default() };
+        assert!(config.in_band(0.2));
+        assert!(config.in_band(0.49));
+        assert!(!config.in_band(0.5));
+        assert!(!config.in_band(0.1));
+    }
+
+    #[tokio::test]
+    async fn test_findings_flow_back_bounded() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let before = processor.process_event("home_1", event(0.5)).unwrap();
+        let config = HandoffConfig { lower: 0.0, upper: 1.0, ..HandoffConfig::default() };
+
+        let incident = processor.find_incident("home_1", before.incident_id).unwrap();
+        assert!(config.should_forward(&incident, before.calibrated_probability));
+        let request = HandoffRequest::new("home_1", &incident, before.calibrated_probability, &before.fused_evidence);
+        let assessment = forward(&Recon, &request, &config).await.unwrap();
+        assert_eq!(assessment.adjustment_llr, config.max_adjustment_llr);
+
+        processor.apply_adversarial_assessment("home_1", before.incident_id, assessment);
+        let after = processor.reassess_incident("home_1", before.incident_id).unwrap();
+        assert!(after.calibrated_probability > before.calibrated_probability);
+        assert!(after.narrative_summary.contains("possible reconnaissance"));
+
+        // Already forwarded at this probability: not sent again until it rises
+        let incident = processor.find_incident("home_1", before.incident_id).unwrap();
+        assert!(!config.should_forward(&incident, before.calibrated_probability));
+    }
+
+    #[tokio::test]
+    async fn test_reconnaissance_analyzer_reads_the_home_trajectory() {
+        let surveillance = Arc::new(CounterSurveillanceSystem::new());
+        for i in 0..20 {
+            surveillance.record_sighting("home_1", Sighting {
+                entity_id: uuid::Uuid::nil(),
+                subject_key: "track_a".to_string(),
+                kind: SubjectKind::Person,
+                at: DateTime::from_timestamp(1_699_999_800 + i * 10, 0).unwrap(),
+                x_m: 0.0,
+                y_m: 15.0,
+                speed_mps: 0.1,
+                facing_camera: false,
+            }).await;
+        }
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let result = processor.process_event("home_1", event(0.5)).unwrap();
+        let incident = processor.find_incident("home_1", result.incident_id).unwrap();
+        let analyzer = ReconnaissanceAnalyzer::new(surveillance);
+
+        let request = HandoffRequest::new("home_1", &incident, result.calibrated_probability, &result.fused_evidence);
+        let findings = analyzer.analyze(&request).await.unwrap();
+        assert!(findings.threat_adjustment_llr > 0.5);
+        assert!(findings.summary.contains("watched the house from across the street"));
+        assert!(findings.countermeasures.iter().any(|c| c.contains("opposite side of the street")));
+
+        // The same track id in another home has no history
+        let request = HandoffRequest::new("home_2", &incident, result.calibrated_probability, &result.fused_evidence);
+        let findings = analyzer.analyze(&request).await.unwrap();
+        assert_eq!(findings.threat_adjustment_llr, 0.0);
+        assert_eq!(findings.summary, "no reconnaissance pattern found");
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod annotations_tests {
+    use crate::annotations::{AnnotationConfig, AnnotationQuery, AnnotationRequest, AnnotationStore, TagEffect};
+    use chrono::Utc;
+
+    fn request(note: Option<&str>, tags: &[&str]) -> AnnotationRequest {
+        AnnotationRequest { note: note.map(str::to_string), tags: tags.iter().map(|t| t.to_string()).collect() }
+    }
+
+    #[test]
+    fn test_annotations_are_searchable_and_quoted_for_the_same_entity() {
+        let store = AnnotationStore::new(AnnotationConfig::default());
+        let now = Utc::now();
+        store.annotate("home_1", 7, Some("entity_3"), "user_1", request(Some("Same van as last week"), &["#Vehicle", "vehicle"]), now).unwrap();
+        store.annotate("home_1", 9, Some("entity_4"), "user_1", request(Some("That was my brother"), &["family"]), now).unwrap();
+        assert!(store.annotate("home_1", 9, None, "user_1", request(Some("   "), &[]), now).is_err());
+
+        let van = store.search("home_1", &AnnotationQuery { q: Some("VAN".to_string()), ..AnnotationQuery::default() });
+        assert_eq!(van.len(), 1);
+        assert_eq!(van[0].tags, vec!["vehicle".to_string()]);
+        assert_eq!(store.search("home_1", &AnnotationQuery { tag: Some("family".to_string()), ..AnnotationQuery::default() })[0].incident_id, 9);
+        assert!(store.search("home_2", &AnnotationQuery::default()).is_empty());
+
+        let context = store.entity_context("home_1", "entity_4").unwrap();
+        assert!(context.contains("\"That was my brother\" (incident 9)"));
+        assert!(context.contains("tagged family"));
+        assert!(store.entity_context("home_1", "entity_5").is_none());
+    }
+
+    #[test]
+    fn test_watch_tags_build_the_watchlist_and_trust_tags_clear_it() {
+        let store = AnnotationStore::new(AnnotationConfig::default());
+        let now = Utc::now();
+        let watched = store.annotate("home_1", 3, Some("entity_8"), "user_1", request(Some("Tried the side gate"), &["suspicious"]), now).unwrap();
+        assert_eq!(watched.effect(), Some(TagEffect::Watch));
+        assert_eq!(store.watchlist("home_1").len(), 1);
+        assert_eq!(store.watchlist_llr("home_1", "entity_8"), Some(store.config().watchlist_identity_llr));
+        assert!(store.entity_context("home_1", "entity_8").unwrap().ends_with("on the watchlist."));
+
+        // Untagged notes leave the list alone; a trust tag takes the person off it
+        store.annotate("home_1", 4, Some("entity_8"), "user_1", request(Some("Back again"), &[]), now).unwrap();
+        assert_eq!(store.watchlist("home_1").len(), 1);
+        let trusted = store.annotate("home_1", 5, Some("entity_8"), "user_2", request(Some("New gardener"), &["trusted"]), now).unwrap();
+        assert_eq!(trusted.effect(), Some(TagEffect::Trust));
+        assert!(store.watchlist_llr("home_1", "entity_8").is_none());
+        assert!(store.unwatch("home_1", "entity_8").is_err());
+    }
+}
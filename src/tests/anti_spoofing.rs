@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod anti_spoofing_tests {
+    use crate::anti_spoofing::{LivenessSource, SpoofConfig, SpoofDetector};
+    use crate::explanation::Explanation;
+
+    #[test]
+    fn test_face_match_evidence_scales_back_with_spoof_likelihood() {
+        let detector = SpoofDetector::new(SpoofConfig { suspect_above: 0.5, full_discount_at: 0.9 });
+
+        // A live face leaves the match alone
+        assert!(detector.assess(LivenessSource::Vps, 0.8, -2.0).is_none());
+        assert!(detector.assess(LivenessSource::Vps, f64::NAN, -2.0).is_none());
+
+        let doubted = detector.assess(LivenessSource::Vps, 0.3, -2.0).unwrap();
+        assert!((doubted.spoof_likelihood - 0.7).abs() < 1e-9);
+        assert!((doubted.discount - 0.5).abs() < 1e-9);
+        assert!((doubted.identity_llr_after + 1.0).abs() < 1e-9);
+
+        let photo = detector.assess(LivenessSource::Edge, 0.02, -2.0).unwrap();
+        assert_eq!(photo.discount, 1.0);
+        assert_eq!(photo.identity_llr_after, 0.0);
+
+        // Identity evidence pointing toward a threat is not softened
+        let watchlisted = detector.assess(LivenessSource::Vps, 0.1, 1.5).unwrap();
+        assert_eq!(watchlisted.identity_llr_after, 1.5);
+    }
+
+    #[test]
+    fn test_spoof_suspicion_is_called_out_in_the_explanation() {
+        let spoof = SpoofDetector::default().assess(LivenessSource::Vps, 0.2, -3.0).unwrap();
+        let narrative = spoof.narrative();
+        assert!(narrative.contains("liveness 0.20"));
+        assert!(narrative.contains("counts for 75% less"));
+
+        let explanation = Explanation::new("Threat logit +0.40", vec![("identity".to_string(), -0.75)], String::new())
+            .with_caution(narrative);
+        let text = explanation.render();
+        assert!(text.ends_with("counts for 75% less."), "{}", text);
+        assert!(text.contains("Possible spoofed face"));
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod api_pagination_tests {
+    use crate::api::pagination::{paginate, ListQuery, SortField, SortOrder};
+    use crate::delivery::NotificationSeverity;
+    use axum::http::StatusCode;
+    use chrono::{TimeZone, Utc};
+
+    // (id, ts, probability)
+    fn rows() -> Vec<(u64, f64, f64)> {
+        (1..=7).map(|i| (i, 1_700_000_000.0 + i as f64 * 60.0, (i % 3) as f64 / 3.0)).collect()
+    }
+
+    fn key(row: &(u64, f64, f64), sort: SortField) -> (f64, String) {
+        let key = match sort {
+            SortField::Time => row.1,
+            SortField::Probability => row.2,
+        };
+        (key, row.0.to_string())
+    }
+
+    fn ids(rows: &[(u64, f64, f64)]) -> Vec<u64> {
+        rows.iter().map(|r| r.0).collect()
+    }
+
+    #[test]
+    fn test_cursor_walks_every_row_once_newest_first() {
+        let mut query = ListQuery { limit: Some(3), ..ListQuery::default() };
+        let mut seen = Vec::new();
+        loop {
+            let page = paginate(rows(), &query, key).unwrap();
+            assert_eq!(page.total, 7);
+            seen.extend(ids(&page.items));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(seen, vec![7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_probability_sort_breaks_ties_by_id() {
+        let query = ListQuery { sort: SortField::Probability, order: SortOrder::Asc, limit: Some(4), ..ListQuery::default() };
+        let first = paginate(rows(), &query, key).unwrap();
+        assert_eq!(ids(&first.items), vec![3, 6, 1, 4]);
+
+        let next = ListQuery { cursor: first.next_cursor, ..query };
+        let second = paginate(rows(), &next, key).unwrap();
+        assert_eq!(ids(&second.items), vec![7, 2, 5]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_cursor_rejected_for_other_sort_or_garbage() {
+        let query = ListQuery { limit: Some(2), ..ListQuery::default() };
+        let cursor = paginate(rows(), &query, key).unwrap().next_cursor;
+
+        let resorted = ListQuery { cursor, order: SortOrder::Asc, ..query.clone() };
+        assert_eq!(paginate(rows(), &resorted, key).unwrap_err(), StatusCode::BAD_REQUEST);
+
+        let garbage = ListQuery { cursor: Some("not-a-cursor".to_string()), ..query };
+        assert_eq!(paginate(rows(), &garbage, key).unwrap_err(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_time_range_and_severity_filters() {
+        let query = ListQuery {
+            since: Some(Utc.timestamp_opt(1_700_000_120, 0).unwrap()),
+            until: Some(Utc.timestamp_opt(1_700_000_300, 0).unwrap()),
+            min_severity: Some(NotificationSeverity::Elevated),
+            ..ListQuery::default()
+        };
+        let in_range: Vec<u64> = rows().into_iter().filter(|r| query.in_range(r.1)).map(|r| r.0).collect();
+        assert_eq!(in_range, vec![2, 3, 4]);
+
+        assert!(query.meets_severity(Some(NotificationSeverity::Critical)));
+        assert!(!query.meets_severity(Some(NotificationSeverity::Standard)));
+        assert!(!query.meets_severity(None));
+        assert!(ListQuery::default().meets_severity(None));
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        assert_eq!(ListQuery::default().limit(), 50);
+        assert_eq!(ListQuery { limit: Some(0), ..ListQuery::default() }.limit(), 1);
+        assert_eq!(ListQuery { limit: Some(10_000), ..ListQuery::default() }.limit(), 500);
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod api_server_config_tests {
+    use crate::api::{ApiConfig, ApiConfigError, TlsConfig};
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> Result<ApiConfig, ApiConfigError> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        ApiConfig::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_plain_http_by_default() {
+        let config = config(&[("NOVIN_API_PORT", "8443")]).unwrap();
+        assert_eq!(config.port, 8443);
+        assert!(config.tls.is_none());
+    }
+
+    #[test]
+    fn test_acme_config() {
+        let config = config(&[
+            ("NOVIN_ACME_DOMAINS", "Home.example.com, cam.example.com"),
+            ("NOVIN_ACME_EMAIL", "me@example.com"),
+            ("NOVIN_DATA_DIR", "/var/lib/novin"),
+        ]).unwrap();
+        let Some(TlsConfig::Acme(acme)) = config.tls else {
+            panic!("expected ACME");
+        };
+        assert_eq!(acme.domains, vec!["home.example.com", "cam.example.com"]);
+        assert_eq!(acme.cache_dir, std::path::PathBuf::from("/var/lib/novin/acme"));
+        assert!(acme.production);
+    }
+
+    #[test]
+    fn test_incomplete_tls_is_refused() {
+        assert!(matches!(config(&[("NOVIN_TLS_CERT", "cert.pem")]), Err(ApiConfigError::IncompleteCertificate)));
+        assert!(matches!(config(&[("NOVIN_ACME_DOMAINS", "home.example.com")]), Err(ApiConfigError::MissingAcmeContact)));
+        assert!(matches!(
+            config(&[("NOVIN_TLS_CERT", "c.pem"), ("NOVIN_TLS_KEY", "k.pem"), ("NOVIN_ACME_DOMAINS", "a.example.com"), ("NOVIN_ACME_EMAIL", "a@b.c")]),
+            Err(ApiConfigError::ConflictingTls)
+        ));
+    }
+}
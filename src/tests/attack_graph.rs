@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod attack_graph_tests {
+    use crate::prediction::{AttackGraph, AttackGraphConfig, AttackGraphError, ThreatPredictionEngine, ZoneRole};
+
+    fn home() -> AttackGraph {
+        AttackGraph::from_adjacency(
+            &[
+                ("street", ZoneRole::Outside),
+                ("driveway", ZoneRole::Approach),
+                ("side_gate", ZoneRole::Approach),
+                ("front_door", ZoneRole::Entry),
+                ("back_door", ZoneRole::Entry),
+            ],
+            &[
+                ("street", "driveway"),
+                ("driveway", "front_door"),
+                ("driveway", "side_gate"),
+                ("side_gate", "back_door"),
+            ],
+            &AttackGraphConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prior_favours_moving_inward() {
+        let graph = home();
+        let driveway = graph.index("driveway").unwrap();
+        let outgoing: Vec<_> = graph.edges.iter().filter(|e| e.from == driveway).collect();
+        assert!((outgoing.iter().map(|e| e.probability).sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let to_street = outgoing.iter().find(|e| graph.nodes[e.to].zone == "street").unwrap();
+        let to_door = outgoing.iter().find(|e| graph.nodes[e.to].zone == "front_door").unwrap();
+        assert!(to_door.probability > to_street.probability);
+    }
+
+    #[test]
+    fn test_history_shifts_the_most_likely_path() {
+        let mut graph = home();
+        let shortest = graph.shortest_path("street", "back_door").unwrap().unwrap();
+        assert_eq!(shortest.zones, vec!["street", "driveway", "side_gate", "back_door"]);
+
+        let before = graph.most_likely_path("street", "back_door", None).unwrap().unwrap().probability;
+        for _ in 0..10 {
+            graph.observe_path(&["street", "driveway", "side_gate", "back_door"]).unwrap();
+        }
+        let after = graph.most_likely_path("street", "back_door", None).unwrap().unwrap().probability;
+        assert!(after > before);
+
+        // Too few moves allowed to get round the back
+        assert!(graph.most_likely_path("street", "back_door", Some(2)).unwrap().is_none());
+        assert_eq!(graph.observe_path(&["street", "garden"]), Err(AttackGraphError::UnknownZone("garden".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_prediction_follows_the_graph() {
+        let mut graph = home();
+        for _ in 0..10 {
+            graph.observe_path(&["street", "driveway", "side_gate", "back_door"]).unwrap();
+        }
+        let prediction = ThreatPredictionEngine::new().predict_sequence_threats(&[graph], 5).await.unwrap();
+
+        let steps: Vec<&str> = prediction.attack_sequence.iter().map(|s| s.description.as_str()).collect();
+        assert_eq!(steps, vec!["street → driveway", "driveway → side_gate", "side_gate → back_door"]);
+        assert!(prediction.branch_probabilities["back_door"] > prediction.branch_probabilities["front_door"]);
+        assert!(prediction.confidence > 0.0);
+
+        let empty = ThreatPredictionEngine::new().predict_sequence_threats(&[], 5).await.unwrap();
+        assert!(empty.attack_sequence.is_empty());
+        assert_eq!(empty.confidence, 0.0);
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod auth_scopes_tests {
+    use crate::api::auth::{AuthUser, Role, Scope};
+    use crate::api::monitoring::MonitoringBoard;
+    use crate::api::pagination::ListQuery;
+    use crate::api::{household, incidents, vacation, visitor_tokens};
+    use crate::tests::support::{app_state, homeowner};
+    use axum::extract::{FromRequestParts, Path, Query, State};
+    use axum::http::{Request, StatusCode};
+
+    fn user(role: Role) -> AuthUser {
+        AuthUser {
+            user_id: format!("{:?}", role).to_lowercase(),
+            username: format!("{:?}", role),
+            role,
+            scopes: role.default_scopes(),
+            homes: None,
+        }
+    }
+
+    #[test]
+    fn test_role_scopes() {
+        let homeowner = user(Role::Homeowner);
+        let operator = user(Role::Operator);
+        let supervisor = user(Role::Supervisor);
+
+        assert_eq!(homeowner.require(Scope::MonitoringRead), Err(StatusCode::FORBIDDEN));
+        assert!(operator.require(Scope::MonitoringClaim).is_ok());
+        assert_eq!(operator.require(Scope::MonitoringOverride), Err(StatusCode::FORBIDDEN));
+        assert!(supervisor.require(Scope::MonitoringOverride).is_ok());
+    }
+
+    async fn extract(authorization: Option<&str>) -> Result<AuthUser, StatusCode> {
+        let mut request = Request::builder().uri("/api/homes/home_1/vacation");
+        if let Some(value) = authorization {
+            request = request.header("authorization", value);
+        }
+        let (mut parts, _) = request.body(()).unwrap().into_parts();
+        AuthUser::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_a_bearer_token_are_unauthorized() {
+        for header in [None, Some("Basic dXNlcjpwYXNz"), Some("Bearer "), Some("bearer abc")] {
+            assert_eq!(extract(header).await.unwrap_err(), StatusCode::UNAUTHORIZED, "{:?}", header);
+        }
+        assert_eq!(extract(Some("Bearer not-a-jwt")).await.unwrap_err(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_board_update_leaves_no_entry() {
+        let board = MonitoringBoard::new();
+        let rejected = board.update("home_1", 7, |s| {
+            s.claimed_by = Some("op_1".to_string());
+            Err(StatusCode::PRECONDITION_REQUIRED)
+        }).await;
+        assert_eq!(rejected.unwrap_err(), StatusCode::PRECONDITION_REQUIRED);
+        assert!(!board.is_tracked("home_1", 7).await);
+        assert_eq!(board.state("home_1", 7).await.claimed_by, None);
+
+        board.update("home_1", 7, |s| { s.claimed_by = Some("op_1".to_string()); Ok(()) }).await.unwrap();
+        assert!(board.is_tracked("home_1", 7).await);
+    }
+
+    #[tokio::test]
+    async fn test_home_handlers_reject_other_homes() {
+        let state = app_state().await;
+        let path = || Path("home_1".to_string());
+        let outsider = || homeowner(&["home_2"]);
+
+        let listed = incidents::list_incidents(State(state.clone()), outsider(), path(), Query(ListQuery::default())).await;
+        assert_eq!(listed.err(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(household::list_residents(State(state.clone()), outsider(), path()).await.err(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(vacation::get_vacation(State(state.clone()), outsider(), path()).await.err(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(visitor_tokens::list_tokens(State(state.clone()), outsider(), path()).await.err(), Some(StatusCode::FORBIDDEN));
+
+        // The home's own users still get through
+        assert!(household::list_residents(State(state.clone()), homeowner(&["home_1"]), path()).await.is_ok());
+        assert!(vacation::get_vacation(State(state), homeowner(&["home_1"]), path()).await.is_ok());
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod automations_tests {
+    use crate::automations::{
+        execute_all, AutomationAction, AutomationEngine, AutomationError, AutomationExecutor, AutomationRequest, AutomationTrigger,
+        ExecutionStatus, PlannedAction, TimeWindow, TriggerContext,
+    };
+    use crate::thinking::AlertDecision;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingExecutor {
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl AutomationExecutor for RecordingExecutor {
+        async fn execute(&self, action: &PlannedAction) -> Result<(), String> {
+            self.sent.lock().unwrap().push(format!("{}:{}", action.device, action.command));
+            Ok(())
+        }
+    }
+
+    fn night_garden() -> AutomationRequest {
+        AutomationRequest {
+            name: "Back garden at night".to_string(),
+            trigger: AutomationTrigger {
+                decisions: vec![AlertDecision::Critical],
+                zones: vec!["back_garden".to_string()],
+                between: Some(TimeWindow { start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(), end: NaiveTime::from_hms_opt(6, 0, 0).unwrap() }),
+            },
+            actions: vec![
+                AutomationAction { device: "garden_lights".to_string(), command: "on".to_string(), delay_secs: 0, unless_acknowledged: false },
+                AutomationAction { device: "siren".to_string(), command: "start".to_string(), delay_secs: 30, unless_acknowledged: true },
+            ],
+            timezone: "UTC".to_string(),
+            enabled: true,
+        }
+    }
+
+    fn context(decision: AlertDecision, zone: &str, at: DateTime<Utc>) -> TriggerContext {
+        TriggerContext { home_id: "home_1".to_string(), incident_id: 7, decision, zone: zone.to_string(), at }
+    }
+
+    #[tokio::test]
+    async fn test_trigger_runs_lights_now_and_acknowledgement_cancels_siren() {
+        let engine = AutomationEngine::default();
+        let executor = RecordingExecutor::default();
+        let night = Utc.with_ymd_and_hms(2026, 3, 1, 23, 30, 0).unwrap();
+        engine.create("home_1", night_garden(), night).unwrap();
+
+        let immediate = engine.trigger(&context(AlertDecision::Critical, "back_garden", night));
+        assert_eq!(immediate.len(), 1);
+        execute_all(&engine, &executor, immediate).await;
+        assert_eq!(*executor.sent.lock().unwrap(), vec!["garden_lights:on".to_string()]);
+        assert_eq!(engine.pending("home_1").len(), 1);
+
+        // Fires once per incident
+        assert!(engine.trigger(&context(AlertDecision::Critical, "back_garden", night + Duration::seconds(5))).is_empty());
+        assert_eq!(engine.pending("home_1").len(), 1);
+
+        let cancelled = engine.acknowledge("home_1", 7, "user_1", night + Duration::seconds(10));
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].status, ExecutionStatus::Cancelled);
+        assert!(engine.due(night + Duration::seconds(60)).is_empty());
+
+        let history = engine.history("home_1", 10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action.device, "siren");
+        assert_eq!(history[1].status, ExecutionStatus::Executed);
+    }
+
+    #[test]
+    fn test_preview_respects_decision_zone_and_window_without_firing() {
+        let engine = AutomationEngine::default();
+        let night = Utc.with_ymd_and_hms(2026, 3, 1, 2, 0, 0).unwrap();
+        let day = Utc.with_ymd_and_hms(2026, 3, 1, 14, 0, 0).unwrap();
+        engine.create("home_1", night_garden(), night).unwrap();
+
+        let planned = engine.preview(&context(AlertDecision::Critical, "back_garden", night));
+        assert_eq!(planned.len(), 2);
+        assert_eq!(planned[1].run_at, night + Duration::seconds(30));
+        assert!(engine.preview(&context(AlertDecision::Critical, "back_garden", day)).is_empty());
+        assert!(engine.preview(&context(AlertDecision::Elevated, "back_garden", night)).is_empty());
+        assert!(engine.preview(&context(AlertDecision::Critical, "front_door", night)).is_empty());
+        assert!(engine.pending("home_1").is_empty());
+        assert!(engine.history("home_1", 10).is_empty());
+
+        let mut invalid = night_garden();
+        invalid.actions[1].delay_secs = -5;
+        assert_eq!(engine.create("home_1", invalid, night), Err(AutomationError::NegativeDelay("siren".to_string())));
+    }
+}
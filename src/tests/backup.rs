@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod backup_tests {
+    use crate::backup::{BackupArchive, BackupBuilder, BackupError, BackupSection, CAMERA_PINS_PATH, SENSOR_RELIABILITY_PATH};
+    use crate::camera_registry::CameraPin;
+    use crate::thinking::IncidentStoreSnapshot;
+    use std::io::{Cursor, Write};
+
+    fn pin() -> CameraPin {
+        CameraPin {
+            camera_id: "front_door".to_string(),
+            home_id: "home_1".to_string(),
+            hosts: vec!["cam1.local".to_string()],
+            cert_sha256: Vec::new(),
+        }
+    }
+
+    fn sample() -> Vec<u8> {
+        let mut builder = BackupBuilder::new();
+        builder.add_incident_store("home_1", &IncidentStoreSnapshot { ttl_secs: 600.0, id_counter: 7, incidents: Vec::new(), merged_tracks: Vec::new() }).unwrap();
+        builder.add_json(BackupSection::EntityRegistry, CAMERA_PINS_PATH, &vec![pin()]).unwrap();
+        builder.add(BackupSection::Calibration, SENSOR_RELIABILITY_PATH, None, br#"{"sensors":[]}"#.to_vec());
+        builder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let archive = BackupArchive::open(&sample()).unwrap();
+        archive.validate().unwrap();
+
+        let stores = archive.incident_stores().unwrap();
+        assert_eq!(stores.len(), 1);
+        assert_eq!(stores[0].0, "home_1");
+        assert_eq!(stores[0].1.id_counter, 7);
+        assert_eq!(archive.camera_pins().unwrap(), Some(vec![pin()]));
+        assert_eq!(archive.device_keys().unwrap(), None);
+        assert!(archive.config().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tampered_entries_are_refused() {
+        // Rewrite the archive with one entry changed but the original manifest
+        let original = BackupArchive::open(&sample()).unwrap();
+        let manifest = serde_json::to_vec(original.manifest()).unwrap();
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("manifest.json", zip::write::FileOptions::default()).unwrap();
+        zip.write_all(&manifest).unwrap();
+        for entry in &original.manifest().entries {
+            zip.start_file(entry.path.clone(), zip::write::FileOptions::default()).unwrap();
+            let data = if entry.path == SENSOR_RELIABILITY_PATH { b"{}".to_vec() } else { original.entry(&entry.path).unwrap().to_vec() };
+            zip.write_all(&data).unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+
+        assert!(matches!(BackupArchive::open(&bytes), Err(BackupError::Checksum(path)) if path == SENSOR_RELIABILITY_PATH));
+    }
+
+    #[test]
+    fn test_invalid_content_fails_validation_and_paths_cannot_escape() {
+        let mut builder = BackupBuilder::new();
+        builder.add(BackupSection::EntityRegistry, CAMERA_PINS_PATH, None, b"not json".to_vec());
+        let archive = BackupArchive::open(&builder.finish().unwrap()).unwrap();
+        assert!(matches!(archive.validate(), Err(BackupError::InvalidEntry { .. })));
+
+        let mut builder = BackupBuilder::new();
+        builder.add(BackupSection::Config, "../daemon.yaml", None, Vec::new());
+        assert!(matches!(BackupArchive::open(&builder.finish().unwrap()), Err(BackupError::UnsafePath(_))));
+    }
+}
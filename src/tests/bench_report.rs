@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod bench_report_tests {
+    use crate::bench_report::{compare, read_estimates, render, Baseline};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_reads_nested_criterion_results() {
+        let dir = std::env::temp_dir().join(format!("criterion_{}", uuid::Uuid::new_v4()));
+        for (id, mean) in [("fusion/fused_evidence/10", 120.5), ("calibration/temperature", 4.2)] {
+            let new = dir.join(id).join("new");
+            std::fs::create_dir_all(&new).unwrap();
+            std::fs::write(new.join("estimates.json"), format!(r#"{{"mean":{{"point_estimate":{}}},"median":{{"point_estimate":0}}}}"#, mean)).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("report")).unwrap();
+
+        let means = read_estimates(&dir).unwrap();
+        assert_eq!(means.len(), 2);
+        assert_eq!(means["fusion/fused_evidence/10"], 120.5);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_flags_slowdowns_past_the_threshold() {
+        let baseline = Baseline {
+            threshold_pct: 10.0,
+            benchmarks: BTreeMap::from([("a".to_string(), 100.0), ("b".to_string(), 100.0)]),
+        };
+        let current = BTreeMap::from([("a".to_string(), 109.0), ("b".to_string(), 125.0), ("c".to_string(), 50.0)]);
+
+        let deltas = compare(&baseline, &current);
+        assert_eq!(deltas[0].id, "b");
+        assert!(deltas[0].regressed);
+        assert!(!deltas.iter().find(|d| d.id == "a").unwrap().regressed);
+        assert_eq!(deltas.iter().find(|d| d.id == "c").unwrap().baseline_ns, None);
+        assert!(render(&deltas, 10.0).contains("1 regression(s)"));
+    }
+}
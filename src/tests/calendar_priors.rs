@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod calendar_priors_tests {
+    use crate::counter_surveillance::{CounterSurveillanceSystem, ReconnaissanceAnalyzer};
+    use crate::environment::{compute_sun_times, CalendarConfig, CalendarPriorAdjuster, HolidayCalendar, HomeCalendars, SunTimes};
+    use crate::thinking::{AdversarialAnalyzer, Evidence, HandoffRequest, ThinkingAIConfig, ThinkingAIProcessor};
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn london_config() -> CalendarConfig {
+        CalendarConfig {
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            timezone: "Europe/London".to_string(),
+            holidays: HolidayCalendar::GB,
+            ..CalendarConfig::default()
+        }
+    }
+
+    fn sydney_config() -> CalendarConfig {
+        CalendarConfig {
+            latitude: Some(-33.8688),
+            longitude: Some(151.2093),
+            timezone: "Australia/Sydney".to_string(),
+            ..CalendarConfig::default()
+        }
+    }
+
+    fn london() -> CalendarPriorAdjuster {
+        CalendarPriorAdjuster::new(london_config())
+    }
+
+    #[test]
+    fn test_winter_sunset_in_london_is_before_4pm() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        match compute_sun_times(date, 51.5074, -0.1278) {
+            SunTimes::Normal { sunrise, sunset } => {
+                assert_eq!(sunrise.format("%H").to_string(), "08");
+                assert_eq!(sunset.format("%H").to_string(), "15");
+            }
+            other => panic!("unexpected sun times: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_same_clock_time_scored_by_darkness() {
+        let calendar = london();
+        // 16:30 local time in both cases (GMT in winter, BST in summer)
+        let winter = Utc.with_ymd_and_hms(2024, 12, 18, 16, 30, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2024, 6, 19, 15, 30, 0).unwrap();
+
+        assert!(calendar.is_dark(winter));
+        assert!(!calendar.is_dark(summer));
+        assert!(calendar.time_risk(winter) > calendar.time_risk(summer));
+        assert!(calendar.prior_logit_adjustment(winter) > calendar.prior_logit_adjustment(summer));
+    }
+
+    #[test]
+    fn test_gb_moveable_holidays() {
+        let calendar = london();
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 3, 29).unwrap())); // Good Friday
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 8, 26).unwrap())); // Summer bank holiday
+        assert!(!calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 8, 19).unwrap()));
+    }
+
+    #[test]
+    fn test_each_home_is_scored_by_its_own_calendar() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        processor.set_calendar("home_london", london_config());
+        processor.set_calendar("home_sydney", sydney_config());
+
+        // 22:00 in London, 09:00 the next morning in Sydney
+        let ts = Utc.with_ymd_and_hms(2024, 12, 18, 22, 0, 0).unwrap().timestamp() as f64;
+        let flat = ThinkingAIConfig::default().prior_logit;
+        assert_eq!(processor.prior_logit_at("home_london", ts), flat + london_config().dark_prior_logit);
+        assert_eq!(processor.prior_logit_at("home_sydney", ts), flat);
+        assert_eq!(processor.prior_logit_at("home_unknown", ts), flat);
+
+        // A deployment-wide calendar covers homes that have none of their own
+        let calendars = HomeCalendars::new(Some(london_config()));
+        calendars.register("home_sydney", sydney_config());
+        assert!(calendars.prior_logit_adjustment_ts("home_unknown", ts) > 0.0);
+        assert_eq!(calendars.prior_logit_adjustment_ts("home_sydney", ts), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_reconnaissance_after_dark_weighs_more() {
+        let calendars = Arc::new(HomeCalendars::default());
+        calendars.register("home_1", london_config());
+        let analyzer = ReconnaissanceAnalyzer::new(Arc::new(CounterSurveillanceSystem::new())).with_calendars(calendars);
+        let walk = |at: chrono::DateTime<Utc>| HandoffRequest {
+            home_id: "home_1".to_string(),
+            incident_id: 1,
+            probability: 0.3,
+            evidence: Evidence::default(),
+            cameras: vec!["back_garden".to_string(), "front".to_string(), "side_gate".to_string()],
+            event_count: 3,
+            total_dwell_s: 40.0,
+            started_at: at.timestamp() as f64 - 60.0,
+            last_updated: at.timestamp() as f64,
+            person_track: "track_1".to_string(),
+        };
+
+        // 03:00 and 13:00 on a December weekday in London
+        let night = analyzer.analyze(&walk(Utc.with_ymd_and_hms(2024, 12, 18, 3, 0, 0).unwrap())).await.unwrap();
+        let day = analyzer.analyze(&walk(Utc.with_ymd_and_hms(2024, 12, 18, 13, 0, 0).unwrap())).await.unwrap();
+        assert!((day.threat_adjustment_llr - 0.3).abs() < 1e-9);
+        assert!((night.threat_adjustment_llr - 0.6).abs() < 1e-9);
+        assert!(night.summary.ends_with("after dark"));
+        assert!(!day.summary.contains("after dark"));
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod camera_dedup_tests {
+    use crate::tests::support;
+    use crate::thinking::{CameraOverlap, Event, Evidence, ThinkingAIConfig, ThinkingAIProcessor};
+
+    fn event(ts: f64, cam: &str, track: &str) -> Event {
+        support::event(ts).cam(cam).track(track).evidence(Evidence { llr_behavior: 0.4, ..Default::default() }).build()
+    }
+
+    #[test]
+    fn test_overlapping_cameras_share_one_incident() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        processor.set_camera_overlaps("home_1", vec![CameraOverlap::new(vec!["porch_left".to_string(), "porch_right".to_string()])]);
+
+        let first = processor.process_event("home_1", event(1000.0, "porch_left", "left_7")).unwrap();
+        let second = processor.process_event("home_1", event(1001.5, "porch_right", "right_3")).unwrap();
+        assert_eq!(first.incident_id, second.incident_id);
+        // The rest of the aliased track follows, even after the window
+        let later = processor.process_event("home_1", event(1030.0, "porch_right", "right_3")).unwrap();
+        assert_eq!(later.incident_id, first.incident_id);
+
+        // Outside the window, or on a camera outside the group, is someone else
+        let late = processor.process_event("home_1", event(1100.0, "porch_right", "right_9")).unwrap();
+        assert_ne!(late.incident_id, first.incident_id);
+        let garden = processor.process_event("home_1", event(1030.5, "garden", "garden_1")).unwrap();
+        assert_ne!(garden.incident_id, first.incident_id);
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod camera_health_tests {
+    use crate::camera_health::{reported_fps, CameraHealthError, CameraHealthRegistry, CameraRegistrationRequest, CameraStatus};
+    use crate::pipeline::SubscriptionTier;
+    use chrono::{DateTime, Duration, Utc};
+
+    fn request(expected_fps: Option<f64>) -> CameraRegistrationRequest {
+        CameraRegistrationRequest {
+            user_id: "user_1".to_string(),
+            name: Some("Back garden".to_string()),
+            expected_fps,
+            max_silence_s: 3600,
+            tier: SubscriptionTier::Standard,
+        }
+    }
+
+    fn t0() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    #[test]
+    fn test_each_camera_has_its_own_rotatable_token() {
+        let registry = CameraHealthRegistry::default();
+        let first = registry.register("home_1", "cam_back", request(None), t0()).unwrap();
+        let other = registry.register("home_1", "cam_front", request(None), t0()).unwrap();
+
+        assert_eq!(registry.authenticate("cam_back", &first.ingest_token).unwrap().home_id, "home_1");
+        assert_eq!(registry.authenticate("cam_back", &other.ingest_token).unwrap_err(), CameraHealthError::InvalidToken("cam_back".to_string()));
+        assert_eq!(registry.register("home_2", "cam_back", request(None), t0()).unwrap_err(), CameraHealthError::OtherHome("cam_back".to_string()));
+
+        // Registering again rotates the token
+        let rotated = registry.register("home_1", "cam_back", request(None), t0()).unwrap();
+        assert!(registry.authenticate("cam_back", &first.ingest_token).is_err());
+        assert!(registry.authenticate("cam_back", &rotated.ingest_token).is_ok());
+        assert!(!serde_json::to_string(&rotated.camera).unwrap().contains("token"));
+    }
+
+    #[test]
+    fn test_health_reports_silent_recovering_and_slow_cameras() {
+        let registry = CameraHealthRegistry::default();
+        registry.register("home_1", "cam_back", request(None), t0()).unwrap();
+        registry.register("home_1", "cam_front", request(Some(15.0)), t0()).unwrap();
+        registry.register("home_1", "cam_side", request(None), t0()).unwrap();
+        registry.record_event("home_1", "cam_back", t0(), None);
+        registry.record_event("home_1", "cam_front", t0() + Duration::hours(2), reported_fps("motion=true|fps=4"));
+
+        let now = t0() + Duration::hours(2);
+        let health = registry.health("home_1", now);
+        let statuses: Vec<(&str, CameraStatus)> = health.iter().map(|h| (h.camera_id.as_str(), h.status)).collect();
+        assert_eq!(statuses, vec![("cam_back", CameraStatus::Silent), ("cam_front", CameraStatus::LowFrameRate), ("cam_side", CameraStatus::NeverSeen)]);
+        assert_eq!(registry.silent("home_1", now)[0].silent_for_s, Some(7200));
+
+        // Back after the silence: counts for less until the recovery window passes
+        registry.record_event("home_1", "cam_back", now, None);
+        let factors = registry.reliability_factors("home_1", now);
+        assert_eq!(factors["cam_back"], 0.7);
+        assert!((factors["cam_front"] - 0.5).abs() < 1e-9);
+        assert!(!registry.reliability_factors("home_1", now + Duration::minutes(15)).contains_key("cam_back"));
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod camera_registry_tests {
+    use crate::camera_registry::{CameraPin, CameraRegistry, PinError, PinPolicy, SnapshotTrust};
+
+    fn pin(hosts: &[&str], certs: &[&str]) -> CameraPin {
+        CameraPin {
+            camera_id: "front_door".to_string(),
+            home_id: "home_1".to_string(),
+            hosts: hosts.iter().map(|h| h.to_string()).collect(),
+            cert_sha256: certs.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_unregistered_cameras_are_not_checked() {
+        let registry = CameraRegistry::default();
+        assert_eq!(registry.check_url("home_1", "garage", "http://anything.example/x.jpg"), Ok(SnapshotTrust::Unregistered));
+        assert!(registry.client_for("home_1", "garage").is_none());
+    }
+
+    #[test]
+    fn test_enforce_refuses_unexpected_hosts() {
+        let registry = CameraRegistry::new(PinPolicy::Enforce);
+        registry.register(pin(&["cam1.local", "*.cams.example.com"], &[])).unwrap();
+
+        assert_eq!(registry.check_url("home_1", "front_door", "http://cam1.local/snap.jpg"), Ok(SnapshotTrust::Pinned));
+        assert_eq!(registry.check_url("home_1", "front_door", "https://door.cams.example.com/snap.jpg"), Ok(SnapshotTrust::Pinned));
+        assert!(matches!(
+            registry.check_url("home_1", "front_door", "https://evil.example.net/snap.jpg"),
+            Err(PinError::UnexpectedHost { .. })
+        ));
+        // The wildcard needs a subdomain and a real label boundary
+        assert!(registry.check_url("home_1", "front_door", "https://cams.example.com/snap.jpg").is_err());
+        assert!(registry.check_url("home_1", "front_door", "https://evilcams.example.com/snap.jpg").is_err());
+    }
+
+    #[test]
+    fn test_flag_policy_lets_mismatches_through() {
+        let registry = CameraRegistry::new(PinPolicy::Flag);
+        registry.register(pin(&["cam1.local"], &[])).unwrap();
+        assert!(matches!(registry.check_url("home_1", "front_door", "https://evil.example.net/snap.jpg"), Ok(SnapshotTrust::Flagged(_))));
+    }
+
+    #[test]
+    fn test_certificate_pins_require_https_and_valid_fingerprints() {
+        let registry = CameraRegistry::default();
+        let fingerprint = "ab".repeat(32);
+        registry.register(pin(&["cam1.local"], &[&fingerprint])).unwrap();
+        assert_eq!(registry.check_url("home_1", "front_door", "http://cam1.local/snap.jpg"), Err(PinError::InsecureScheme("front_door".to_string())));
+        assert_eq!(registry.check_url("home_1", "front_door", "https://cam1.local/snap.jpg"), Ok(SnapshotTrust::Pinned));
+
+        assert!(matches!(registry.register(pin(&["cam1.local"], &["not-hex"])), Err(PinError::InvalidFingerprint(_))));
+        assert!(matches!(registry.register(pin(&[], &[])), Err(PinError::NoHosts(_))));
+    }
+
+    #[test]
+    fn test_home_pins_are_scoped() {
+        let registry = CameraRegistry::default();
+        registry.register(pin(&["cam1.local"], &[])).unwrap();
+        assert_eq!(registry.home_pins("home_1").len(), 1);
+        assert!(registry.home_pins("home_2").is_empty());
+        assert!(registry.remove("home_1", "front_door").is_some());
+        assert!(registry.home_pins("home_1").is_empty());
+    }
+
+    #[test]
+    fn test_same_camera_id_in_two_homes_is_pinned_independently() {
+        let registry = CameraRegistry::new(PinPolicy::Enforce);
+        registry.register(pin(&["cam1.local"], &[])).unwrap();
+        registry.register(CameraPin { home_id: "home_2".to_string(), ..pin(&["cam2.local"], &[]) }).unwrap();
+
+        // Neither home's pin replaces or vouches for the other's
+        assert_eq!(registry.check_url("home_1", "front_door", "http://cam1.local/snap.jpg"), Ok(SnapshotTrust::Pinned));
+        assert!(registry.check_url("home_1", "front_door", "http://cam2.local/snap.jpg").is_err());
+        assert_eq!(registry.check_url("home_2", "front_door", "http://cam2.local/snap.jpg"), Ok(SnapshotTrust::Pinned));
+        assert!(registry.check_url("home_2", "front_door", "http://cam1.local/snap.jpg").is_err());
+        assert_eq!(registry.check_url("home_3", "front_door", "http://cam1.local/snap.jpg"), Ok(SnapshotTrust::Unregistered));
+
+        assert!(registry.remove("home_2", "front_door").is_some());
+        assert_eq!(registry.home_pins("home_1").len(), 1);
+        assert!(registry.get("home_2", "front_door").is_none());
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod central_station_tests {
+    use crate::delivery::{
+        dc09_crc, dc09_frame, parse_ack, AlarmContext, CentralStationError, CentralStationExporter,
+        CentralStationRequest, ContactIdEvent, MessageKind, Qualifier,
+    };
+    use crate::thinking::{AlertDecision, IncidentStatus, IncidentTransition};
+    use chrono::{TimeZone, Utc};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn request(host: &str, port: u16) -> CentralStationRequest {
+        serde_json::from_value(serde_json::json!({
+            "host": host,
+            "port": port,
+            "account": "1234",
+            "zones": { "porch": 15, "garden": 7 },
+        }))
+        .unwrap()
+    }
+
+    fn context(incident_id: u64, decision: AlertDecision) -> AlarmContext {
+        AlarmContext {
+            home_id: "home_1".to_string(),
+            incident_id,
+            camera: "porch".to_string(),
+            decision,
+            at: Utc.with_ymd_and_hms(2026, 3, 1, 23, 5, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_contact_id_and_dc09_encoding() {
+        let event = ContactIdEvent { qualifier: Qualifier::New, code: 130, partition: 1, zone: 15 };
+        assert_eq!(event.data(), "1130 01 015");
+        assert_eq!(event.message("1234"), "123418113001015E");
+        assert_eq!(dc09_crc(b"123456789"), 0xBB3D);
+
+        let message = "\"ADM-CID\"0001L0#1234[#1234|1130 01 015]_23:05:00,03-01-2026";
+        let frame = dc09_frame(message);
+        assert_eq!(frame, format!("\n{:04X}0{:03X}{}\r", dc09_crc(message.as_bytes()), message.len(), message));
+
+        assert!(parse_ack(&dc09_frame("\"ACK\"0001L0#1234[]"), 1).is_ok());
+        assert!(matches!(parse_ack(&dc09_frame("\"NAK\"0000R0L0A0[]"), 1), Err(CentralStationError::Refused(_))));
+        assert!(matches!(parse_ack(&dc09_frame("\"ACK\"0002L0#1234[]"), 1), Err(CentralStationError::UnexpectedResponse(_))));
+    }
+
+    #[test]
+    fn test_alarms_escalate_once_per_severity_then_restore() {
+        let exporter = CentralStationExporter::default();
+        let now = Utc::now();
+        let mut bad = request("receiver.example", 5000);
+        bad.account = "12G4".to_string();
+        assert!(matches!(exporter.configure("home_1", bad, now), Err(CentralStationError::InvalidAccount(_))));
+        exporter.configure("home_1", request("receiver.example", 5000), now).unwrap();
+
+        // Standard stays below the default minimum of Elevated
+        assert!(exporter.plan(&context(9, AlertDecision::Standard)).is_none());
+        let (_, event) = exporter.plan(&context(9, AlertDecision::Elevated)).unwrap();
+        assert_eq!((event.code, event.zone, event.qualifier), (130, 15, Qualifier::New));
+        assert!(exporter.plan(&context(9, AlertDecision::Elevated)).is_none());
+        let (_, event) = exporter.plan(&context(9, AlertDecision::Critical)).unwrap();
+        assert_eq!(event.code, 139);
+
+        let closed = IncidentTransition {
+            home_id: "home_1".to_string(),
+            incident_id: 9,
+            from: IncidentStatus::Open,
+            to: IncidentStatus::Resolved,
+            ts: 1_772_406_300.0,
+            reason: "resolved".to_string(),
+        };
+        let (_, restore) = exporter.plan_restore(&closed).unwrap();
+        assert_eq!((restore.qualifier, restore.code, restore.zone), (Qualifier::Restore, 139, 15));
+        assert!(exporter.plan_restore(&closed).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_messages_wait_for_the_receiver_ack() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            // Acknowledge each frame with its own sequence number
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let mut buf = vec![0u8; 512];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let frame = String::from_utf8_lossy(&buf[..n]).to_string();
+                let quote = frame.find('"').unwrap();
+                let after_id = quote + 1 + frame[quote + 1..].find('"').unwrap() + 1;
+                let sequence = &frame[after_id..after_id + 4];
+                let ack = dc09_frame(&format!("\"ACK\"{}L0#1234[]", sequence));
+                socket.write_all(ack.as_bytes()).await.ok();
+            }
+        });
+
+        let exporter = CentralStationExporter::default();
+        let now = Utc::now();
+        let station = exporter.configure("home_1", request("127.0.0.1", port), now).unwrap();
+        let record = exporter.test("home_1", now).await.unwrap();
+        assert!(record.acknowledged, "{:?}", record.error);
+        assert_eq!(record.sequence, 1);
+        assert!(record.message.contains("[#1234|1601 01 000]"));
+
+        assert_eq!(exporter.due_heartbeats(now).len(), 1);
+        let heartbeat = exporter.heartbeat(&station, now).await;
+        assert!(heartbeat.acknowledged && heartbeat.message.starts_with("\"NULL\"0002"));
+        assert!(exporter.due_heartbeats(now).is_empty());
+
+        let view = exporter.get("home_1").unwrap();
+        assert!(view.supervised);
+        assert_eq!((view.stats.sent, view.stats.acknowledged), (2, 2));
+        let history = exporter.history("home_1", 10);
+        assert_eq!(history[0].kind, MessageKind::Heartbeat);
+        assert_eq!(history[1].kind, MessageKind::Test);
+    }
+}
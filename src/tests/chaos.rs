@@ -0,0 +1,68 @@
+#[cfg(all(test, feature = "chaos"))]
+mod chaos_tests {
+    use crate::chaos::{ChaosConfig, ChaosError, ChaosInjector, ChaosStorage};
+    use crate::overnight::{InMemoryStorage, OvernightEventAnalysis, OvernightStorage};
+    use bytes::Bytes;
+    use chrono::Utc;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn injector(config: ChaosConfig) -> Arc<ChaosInjector> {
+        Arc::new(ChaosInjector::new(ChaosConfig { seed: Some(11), ..config }).unwrap())
+    }
+
+    fn analysis() -> OvernightEventAnalysis {
+        OvernightEventAnalysis {
+            event_id: Uuid::new_v4(),
+            home_id: "home_a".to_string(),
+            timestamp: Utc::now(),
+            analysis_summary: "Person at front door".to_string(),
+            suppressed_alert_level: None,
+            camera: None,
+            entity_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn zero_rates_inject_nothing() {
+        let chaos = injector(ChaosConfig::default());
+        let image = Bytes::from_static(b"\xff\xd8\xff\xe0 jpeg bytes");
+        for _ in 0..100 {
+            assert!(chaos.vps_call().await.is_ok());
+            assert_eq!(chaos.corrupt_image(image.clone()), image);
+            assert!(!chaos.drop_write());
+        }
+        assert_eq!(chaos.stats(), Default::default());
+    }
+
+    #[tokio::test]
+    async fn certain_faults_always_fire() {
+        let chaos = injector(ChaosConfig { vps_failure_rate: 1.0, image_corruption_rate: 1.0, ..Default::default() });
+        assert_eq!(chaos.vps_call().await, Err(ChaosError::VpsFailure));
+
+        let image = Bytes::from(vec![0u8; 256]);
+        let corrupted = chaos.corrupt_image(image.clone());
+        assert!(!corrupted.is_empty());
+        assert_ne!(corrupted, image);
+        assert_eq!(chaos.stats().vps_failures, 1);
+        assert_eq!(chaos.stats().images_corrupted, 1);
+    }
+
+    #[test]
+    fn rates_outside_unit_interval_are_rejected() {
+        let config = ChaosConfig { storage_drop_rate: 1.5, ..Default::default() };
+        assert!(matches!(ChaosInjector::new(config), Err(ChaosError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn storage_drops_writes_but_not_reads() {
+        let inner: Arc<dyn OvernightStorage> = Arc::new(InMemoryStorage::default());
+        let dropping = ChaosStorage::new(inner.clone(), injector(ChaosConfig { storage_drop_rate: 1.0, ..Default::default() }));
+        assert!(dropping.store_event(&analysis()).await.is_err());
+        assert!(dropping.pending_events("home_a").await.unwrap().is_empty());
+
+        let passing = ChaosStorage::new(inner, injector(ChaosConfig::default()));
+        passing.store_event(&analysis()).await.unwrap();
+        assert_eq!(passing.pending_events("home_a").await.unwrap().len(), 1);
+    }
+}
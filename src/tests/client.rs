@@ -0,0 +1,70 @@
+#[cfg(all(test, feature = "client"))]
+mod client_tests {
+    use crate::api::models::ApiResponse;
+    use crate::api::pagination::{ListQuery, Page};
+    use crate::api::incidents::IncidentRow;
+    use crate::api::websocket::{ClientCommand, ClientRequest, CommandErrorCode, WebSocketUpdate};
+    use crate::client::{ClientError, NovinClient, ServerMessage};
+    use crate::thinking::{AlertDecision, IncidentStatus};
+    use axum::{extract::Query, http::{HeaderMap, StatusCode}, routing::get, Json, Router};
+    use std::collections::HashMap;
+
+    async fn incidents(headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> Result<Json<ApiResponse<Page<IncidentRow>>>, StatusCode> {
+        if headers.get("authorization").and_then(|v| v.to_str().ok()) != Some("Bearer token_1") {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        let row = IncidentRow {
+            incident_id: 7,
+            status: IncidentStatus::Open,
+            started_at: 1_700_000_000.0,
+            last_updated: 1_700_000_030.0,
+            cameras: vec!["back_garden".to_string()],
+            event_count: 2,
+            probability: Some(0.62),
+            decision: Some(AlertDecision::Critical),
+            severity: None,
+            summary: None,
+        };
+        let next_cursor = query.get("limit").map(|l| format!("after-{}", l));
+        Ok(Json(ApiResponse::success(Page { items: vec![row], next_cursor, total: 1 })))
+    }
+
+    #[tokio::test]
+    async fn test_list_incidents_unwraps_envelope_and_surfaces_status() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/api/homes/:home_id/incidents", get(incidents));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = NovinClient::new(&format!("http://{}/", addr), "token_1").unwrap();
+        let query = ListQuery { limit: Some(5), ..ListQuery::default() };
+        let page = client.list_incidents("home_1", &query).await.unwrap();
+        assert_eq!(page.items[0].incident_id, 7);
+        assert_eq!(page.items[0].decision, Some(AlertDecision::Critical));
+        assert_eq!(page.next_cursor.as_deref(), Some("after-5"));
+
+        let wrong = NovinClient::new(&format!("http://{}", addr), "other").unwrap();
+        match wrong.list_incidents("home_1", &ListQuery::default()).await {
+            Err(ClientError::Status { status, .. }) => assert_eq!(status, 401),
+            other => panic!("expected a 401, got {:?}", other),
+        }
+        assert!(matches!(NovinClient::new("ftp://example.com", "t"), Err(ClientError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_websocket_messages_round_trip_with_server_types() {
+        let request = ClientRequest {
+            request_id: "r1".to_string(),
+            command: ClientCommand::AcknowledgeAlert { home_id: "home_1".to_string(), incident_id: 7 },
+        };
+        let parsed: ClientRequest = serde_json::from_str(&serde_json::to_string(&request).unwrap()).unwrap();
+        assert_eq!(parsed.command, request.command);
+
+        let update = serde_json::json!({ "type": "alert_acknowledged", "home_id": "home_1", "incident_id": 7, "by": "user_1" });
+        assert!(matches!(serde_json::from_value(update).unwrap(), ServerMessage::Update(WebSocketUpdate::AlertAcknowledged { incident_id: 7, .. })));
+        let reply = serde_json::json!({ "type": "reply", "request_id": "r1", "data": { "ok": true } });
+        assert!(matches!(serde_json::from_value(reply).unwrap(), ServerMessage::Reply(r) if r.request_id == "r1"));
+        let error = serde_json::json!({ "type": "error", "request_id": "r2", "code": "not_found", "message": "no such incident" });
+        assert!(matches!(serde_json::from_value(error).unwrap(), ServerMessage::Error(e) if e.code == CommandErrorCode::NotFound));
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod clock_tests {
+    use crate::clock::{Clock, SimulatedClock};
+    use crate::overnight::{OvernightReviewManager, OvernightStorageFactory};
+    use crate::tests::support;
+    use crate::thinking::{
+        Event, Evidence, IncidentLabel, IncidentStatus, OnlineWeightLearner, OutcomeSource, PriorGuardrails,
+        PriorModelRegistry, PriorRule, ThinkingAIConfig, ThinkingAIProcessor,
+    };
+    use chrono::{Duration, NaiveDate, TimeZone, Utc};
+    use std::sync::Arc;
+
+    fn event(ts: f64) -> Event {
+        support::event(ts).cam("porch").track("track_1").evidence(Evidence { llr_behavior: 0.4, ..Default::default() }).build()
+    }
+
+    #[test]
+    fn test_simulated_clock_drives_incident_expiry() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 22, 0, 0).unwrap();
+        let clock = Arc::new(SimulatedClock::new(start));
+        let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        processor.set_clock(clock.clone());
+
+        let incident_id = processor.process_event("home_1", event(clock.now_ts())).unwrap().incident_id;
+        processor.take_transitions();
+        clock.advance(Duration::seconds(120));
+        processor.expire_due_incidents();
+        assert!(processor.find_incident("home_1", incident_id).unwrap().status.is_active());
+        assert!(processor.take_transitions().is_empty());
+
+        // Past the 180s TTL only once the simulated clock says so
+        assert_eq!(clock.advance(Duration::seconds(61)), start + Duration::seconds(181));
+        processor.expire_due_incidents();
+        assert_eq!(processor.find_incident("home_1", incident_id).unwrap().status, IncidentStatus::Expired);
+        let transitions = processor.take_transitions();
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].to, IncidentStatus::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_overnight_manager_dates_summaries_by_its_clock() {
+        let clock = Arc::new(SimulatedClock::new(Utc.with_ymd_and_hms(2026, 3, 2, 7, 0, 0).unwrap()));
+        let manager = OvernightReviewManager::new(OvernightStorageFactory::create_in_memory(), clock.clone());
+        let summary = manager.generate_morning_summary("home_1", "en").await.unwrap();
+        assert_eq!(summary.summary_date, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+
+        clock.set(Utc.with_ymd_and_hms(2025, 12, 31, 7, 0, 0).unwrap());
+        let summary = manager.generate_morning_summary("home_1", "en").await.unwrap();
+        assert_eq!(summary.summary_date, NaiveDate::from_ymd_opt(2025, 12, 31).unwrap());
+    }
+
+    #[test]
+    fn test_prior_edits_and_learned_weights_are_stamped_by_the_clock() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap();
+        let clock = Arc::new(SimulatedClock::new(at));
+
+        let priors = PriorModelRegistry::new(PriorGuardrails::default(), ThinkingAIConfig::default().prior_logit)
+            .with_clock(clock.clone());
+        let rule = PriorRule { entity: None, zone: Some("Back garden".to_string()), time: None, occupancy: None, base_rate: 0.2 };
+        assert_eq!(priors.edit("home_1", vec![rule], "owner", "quieter street").unwrap().created_at, at);
+        clock.advance(Duration::hours(1));
+        assert_eq!(priors.rollback("home_1", 0, "owner").unwrap().created_at, at + Duration::hours(1));
+
+        let learner = OnlineWeightLearner::default();
+        learner.record_outcome("home_1", &event(0.0).evidence, -2.0, IncidentLabel::Benign, OutcomeSource::UserFeedback, clock.now());
+        assert_eq!(learner.population().last_updated, Some(at + Duration::hours(1)));
+    }
+}
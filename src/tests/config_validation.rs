@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod config_validation_tests {
+    use crate::environment::CalendarConfig;
+    use crate::pipeline::PipelineConfig;
+    use crate::thinking::ThinkingAIConfig;
+    use crate::validation::{ConfigError, DaemonConfig, Validate};
+
+    #[test]
+    fn test_defaults_are_valid() {
+        assert!(PipelineConfig::default().validate().is_ok());
+        assert!(DaemonConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_reports_every_issue_with_path() {
+        let mut config = ThinkingAIConfig::default();
+        config.temperature = 0.0;
+        config.incident_ttl_secs = -1.0;
+        config.calendar = Some(CalendarConfig {
+            timezone: "Mars/Olympus_Mons".to_string(),
+            ..CalendarConfig::default()
+        });
+
+        let Err(ConfigError::Invalid(issues)) = config.validate() else {
+            panic!("expected validation errors");
+        };
+        let paths: Vec<&str> = issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["incident_ttl_secs", "temperature", "calendar.timezone"]);
+    }
+
+    #[test]
+    fn test_daemon_config_file() {
+        let dir = std::env::temp_dir().join(format!("novin_cfg_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("daemon.yaml");
+        std::fs::write(&path, "thinking_ai:\n  temperature: 0.0\n").unwrap();
+
+        let err = DaemonConfig::load_validated(&path).unwrap_err();
+        assert!(err.to_string().contains("thinking_ai.temperature"));
+
+        std::fs::write(&path, "thinking_ai:\n  temperature: 1.2\n").unwrap();
+        assert_eq!(DaemonConfig::load_validated(&path).unwrap().thinking_ai.temperature, 1.2);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
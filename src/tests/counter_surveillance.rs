@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod counter_surveillance_tests {
+    use crate::counter_surveillance::*;
+    use crate::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier};
+    use crate::tests::support::mock_vps_url;
+    use crate::vps_client::VpsApiClient;
+    use chrono::{DateTime, Duration, Utc};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn sighting(key: &str, kind: SubjectKind, secs: i64, x_m: f64, y_m: f64, speed_mps: f64, facing_camera: bool) -> Sighting {
+        Sighting { entity_id: Uuid::nil(), subject_key: key.to_string(), kind, at: at(secs), x_m, y_m, speed_mps, facing_camera }
+    }
+
+    #[tokio::test]
+    async fn test_waiting_across_the_street_is_suspicious() {
+        let system = CounterSurveillanceSystem::new();
+        for i in 0..20 {
+            system.record_sighting("home_1", sighting("p1", SubjectKind::Person, i * 10, 3.0, 15.0, 0.1, false)).await;
+        }
+        let indicators = system.assess_subject("home_1", "p1", at(200)).await.unwrap();
+        assert_eq!(indicators.opposite_dwell.measured, 190.0);
+        assert_eq!(indicators.opposite_dwell.observations, 19);
+        assert_eq!(indicators.repeated_passes.measured, 0.0);
+        assert!(indicators.suspicious);
+        assert!(indicators.behavior_llr(system.config()) > 0.0);
+
+        // Same subject key, another home: nothing seen there
+        assert!(system.assess_subject("home_2", "p1", at(200)).await.is_none());
+        // Outside the pass window the trajectory is gone
+        assert!(system.assess_subject("home_1", "p1", at(200) + Duration::hours(5)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_slow_passes_count_but_fast_ones_do_not() {
+        let system = CounterSurveillanceSystem::new();
+        for pass in 0..5 {
+            let start = pass * 1800;
+            for step in 0..5 {
+                let x = -20.0 + step as f64 * 10.0;
+                system.record_sighting("home_1", sighting("AB123", SubjectKind::Vehicle, start + step * 5, x, 5.0, 2.0, false)).await;
+                system.record_sighting("home_1", sighting("CD456", SubjectKind::Vehicle, start + step * 5, x, 5.0, 15.0, false)).await;
+            }
+        }
+        let now = at(4 * 1800 + 30);
+
+        let slow = system.assess_subject("home_1", "AB123", now).await.unwrap();
+        assert_eq!(slow.repeated_passes.measured, 4.0);
+        assert_eq!(slow.repeated_passes.strength, 1.0);
+        assert_eq!(slow.opposite_dwell.measured, 0.0);
+        assert!(slow.suspicious);
+
+        let fast = system.assess_subject("home_1", "CD456", now).await.unwrap();
+        assert_eq!(fast.repeated_passes.measured, 0.0);
+        assert!(!fast.suspicious);
+        assert_eq!(fast.behavior_llr(system.config()), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_facing_the_camera_builds_gaze() {
+        let system = CounterSurveillanceSystem::new();
+        for i in 0..5 {
+            system.record_sighting("home_1", sighting("p2", SubjectKind::Person, i * 5, 0.0, 2.0, 0.0, true)).await;
+        }
+        let indicators = system.assess_subject("home_1", "p2", at(30)).await.unwrap();
+        assert_eq!(indicators.camera_gaze.measured, 20.0);
+        assert_eq!(indicators.camera_gaze.strength, 1.0);
+        assert_eq!(indicators.opposite_dwell.measured, 0.0);
+    }
+
+    #[test]
+    fn test_sightings_parse_from_sensor_data() {
+        let vehicle = Sighting::from_payload("pos_x=1.5,pos_y=12|speed=3.2;facing_camera=true,plate=ab123", Uuid::nil(), "track_1", at(0)).unwrap();
+        assert_eq!((vehicle.kind, vehicle.subject_key.as_str()), (SubjectKind::Vehicle, "AB123"));
+        assert_eq!((vehicle.x_m, vehicle.y_m, vehicle.speed_mps), (1.5, 12.0, 3.2));
+        assert!(vehicle.facing_camera);
+
+        let person = Sighting::from_payload("pos_x=-4,pos_y=9", Uuid::nil(), "track_1", at(0)).unwrap();
+        assert_eq!((person.kind, person.subject_key.as_str(), person.speed_mps), (SubjectKind::Person, "track_1", 0.0));
+        assert!(!person.facing_camera);
+        let named = Sighting::from_payload("pos_x=0,pos_y=9,subject=red_jacket", Uuid::nil(), "track_1", at(0)).unwrap();
+        assert_eq!(named.subject_key, "red_jacket");
+
+        assert!(Sighting::from_payload(r#"{"motion":true}"#, Uuid::nil(), "track_1", at(0)).is_none());
+        assert!(Sighting::from_payload("pos_x=NaN,pos_y=3", Uuid::nil(), "track_1", at(0)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_records_positioned_events() {
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let system = Arc::new(CounterSurveillanceSystem::new());
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new(mock_vps_url().to_string()))
+            .with_counter_surveillance(system.clone());
+
+        for i in 0..20 {
+            let event = RawEvent {
+                event_id: Uuid::new_v4(),
+                sensor_id: "front".to_string(),
+                timestamp: 1_700_000_000 + i * 10,
+                data: "pos_x=2,pos_y=15,speed=0.2,subject=red_jacket".into(),
+                user_id: "user_1".to_string(),
+                home_id: "home_1".to_string(),
+                image_url: None,
+                image_data: None,
+            };
+            pipeline.process_event(event, SubscriptionTier::Premium, "key").await.unwrap();
+        }
+
+        let indicators = system.assess_subject("home_1", "red_jacket", at(200)).await.unwrap();
+        assert_eq!(indicators.opposite_dwell.observations, 19);
+        assert!(indicators.suspicious);
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod dataset_export_tests {
+    use crate::dataset_export::{build_rows, export, pseudonymize, DatasetFormat};
+    use crate::tests::support;
+    use crate::thinking::{AlertDecision, Evidence, Incident, IncidentLabel, IncidentSnapshotEntry};
+    use std::collections::HashMap;
+
+    fn entry(id: u64, behavior: f64) -> IncidentSnapshotEntry {
+        let ts = 1_700_000_000.0 + id as f64 * 3600.0;
+        let mut incident = Incident::new(id, ts, format!("track_{}", id));
+        for i in 0..2 {
+            incident.add_event(support::event(ts + i as f64 * 30.0).track(&format!("track_{}", id)).dwell(30.0).away(0.8)
+                .token("secret-guest-code")
+                .evidence(Evidence { llr_behavior: behavior, ..Default::default() })
+                .build());
+        }
+        incident.attach_snapshot("https://cdn.example.com/home_42/front_door.jpg".to_string());
+        incident.record_assessment(behavior * 2.0, 0.7, AlertDecision::Elevated, "Person lingering at 12 Elm Street");
+        IncidentSnapshotEntry { home: "home_42".to_string(), person_session: format!("track_{}", id), incident }
+    }
+
+    #[test]
+    fn test_rows_sum_llrs_and_skip_unlabeled_incidents() {
+        let incidents = vec![entry(1, 1.5), entry(2, -0.5)];
+        let labels: HashMap<u64, IncidentLabel> = [(1, IncidentLabel::Threat)].into_iter().collect();
+
+        let rows = build_rows(&incidents, &labels, "salt");
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row.llr.iter().find(|(c, _)| c == "behavior").map(|(_, v)| *v), Some(3.0));
+        assert_eq!(row.event_count, 2);
+        assert_eq!(row.duration_s, 30.0);
+        assert!(row.token_presented);
+        assert_eq!(row.final_decision, "elevated");
+        assert_eq!(row.home, pseudonymize("salt", "home_42"));
+        assert_ne!(pseudonymize("salt", "home_42"), pseudonymize("other", "home_42"));
+    }
+
+    #[test]
+    fn test_csv_export_strips_identifying_details() {
+        let incidents = vec![entry(1, 1.5), entry(2, -0.5)];
+        let labels: HashMap<u64, IncidentLabel> = [(1, IncidentLabel::Threat), (2, IncidentLabel::Benign)].into_iter().collect();
+
+        let mut out = Vec::new();
+        let rows = export(&incidents, &labels, "salt", DatasetFormat::Csv, &mut out).unwrap();
+        assert_eq!(rows, 2);
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("home,llr_time,llr_entry,llr_behavior"));
+        assert!(lines[1].ends_with(",elevated,threat"));
+        assert!(lines[2].ends_with(",elevated,benign"));
+        for secret in ["home_42", "track_", "front_door", "secret-guest-code", "cdn.example.com", "Elm Street"] {
+            assert!(!csv.contains(secret), "{} leaked into the dataset", secret);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod device_signing_tests {
+    use crate::api::devices::{enroll_device, EnrollDeviceRequest};
+    use crate::device_signing::{sign_event, DeviceKeyRegistry, EventTrust, SigningConfig, SigningError, UnsignedPolicy};
+    use crate::pipeline::RawEvent;
+    use crate::tests::support::{app_state, homeowner};
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::Json;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    use chrono::{TimeZone, Utc};
+    use ed25519_dalek::SigningKey;
+    use uuid::Uuid;
+
+    fn event(timestamp: i64) -> RawEvent {
+        RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: "front_door".to_string(),
+            timestamp,
+            data: r#"{"motion":true}"#.into(),
+            user_id: "user_1".to_string(),
+            home_id: "home_1".to_string(),
+            image_url: None,
+            image_data: None,
+        }
+    }
+
+    fn enrolled(config: SigningConfig) -> (DeviceKeyRegistry, SigningKey) {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let registry = DeviceKeyRegistry::new(config);
+        let public = BASE64.encode(key.verifying_key().to_bytes());
+        registry.enroll("home_1", "hub_1", &public, Utc::now()).unwrap();
+        (registry, key)
+    }
+
+    #[test]
+    fn test_valid_signature_verifies_and_tampering_fails() {
+        let (registry, key) = enrolled(SigningConfig::default());
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let mut raw = event(now.timestamp());
+        let signature = sign_event("hub_1", &key, &raw);
+
+        assert_eq!(registry.verify(&raw, Some(&signature), now), Ok(EventTrust::Signed { device_id: "hub_1".to_string() }));
+
+        raw.data = r#"{"motion":false}"#.into();
+        assert_eq!(registry.verify(&raw, Some(&signature), now), Err(SigningError::BadSignature("hub_1".to_string())));
+    }
+
+    #[test]
+    fn test_revoked_foreign_and_stale_events_are_rejected() {
+        let (registry, key) = enrolled(SigningConfig::default());
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        let stale = event(now.timestamp() - 3600);
+        let signature = sign_event("hub_1", &key, &stale);
+        assert!(matches!(registry.verify(&stale, Some(&signature), now), Err(SigningError::ClockSkew(_))));
+
+        let mut foreign = event(now.timestamp());
+        foreign.home_id = "home_2".to_string();
+        let signature = sign_event("hub_1", &key, &foreign);
+        assert!(matches!(registry.verify(&foreign, Some(&signature), now), Err(SigningError::HomeMismatch { .. })));
+
+        let raw = event(now.timestamp());
+        let signature = sign_event("hub_1", &key, &raw);
+        registry.revoke("home_1", "hub_1", now).unwrap();
+        assert_eq!(registry.verify(&raw, Some(&signature), now), Err(SigningError::Revoked("hub_1".to_string())));
+    }
+
+    #[test]
+    fn test_unsigned_policy() {
+        let now = Utc::now();
+        let raw = event(now.timestamp());
+
+        let (registry, _) = enrolled(SigningConfig::default());
+        assert_eq!(registry.verify(&raw, None, now).unwrap().evidence_weight(), 0.5);
+
+        let (registry, _) = enrolled(SigningConfig { unsigned: UnsignedPolicy::Reject, ..SigningConfig::default() });
+        assert_eq!(registry.verify(&raw, None, now), Err(SigningError::Unsigned));
+    }
+
+    #[test]
+    fn test_enrollment_rejects_bad_keys_and_other_homes() {
+        let (registry, key) = enrolled(SigningConfig::default());
+        assert!(matches!(registry.enroll("home_1", "hub_2", "not-a-key", Utc::now()), Err(SigningError::InvalidKey(_))));
+
+        let public = BASE64.encode(key.verifying_key().to_bytes());
+        assert!(matches!(registry.enroll("home_2", "hub_1", &public, Utc::now()), Err(SigningError::HomeMismatch { .. })));
+        assert_eq!(registry.devices("home_1").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_devices_are_only_enrolled_by_the_homes_users() {
+        let state = app_state().await;
+        let request = || Json(EnrollDeviceRequest {
+            device_id: "hub_1".to_string(),
+            public_key: BASE64.encode(SigningKey::from_bytes(&[7u8; 32]).verifying_key().to_bytes()),
+        });
+
+        let foreign = enroll_device(State(state.clone()), homeowner(&["home_2"]), Path("home_1".to_string()), request()).await;
+        assert_eq!(foreign.err(), Some(StatusCode::FORBIDDEN));
+        assert!(state.devices.devices("home_1").is_empty());
+
+        assert!(enroll_device(State(state), homeowner(&["home_1"]), Path("home_1".to_string()), request()).await.is_ok());
+    }
+}
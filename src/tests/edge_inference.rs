@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod edge_inference_tests {
+    use crate::edge_inference::{EdgeError, EdgeInferenceConfig, EdgeInferenceEngine, EdgeModel, EdgeModelKind, EdgeModelSpec, ModelManager};
+    use crate::pipeline::{EventPipeline, PipelineConfig, ProcessingLevel, RawEvent, SubscriptionTier};
+    use crate::tests::support::mock_vps_url;
+    use crate::vps_client::VpsApiClient;
+    use image::RgbImage;
+    use std::sync::Arc;
+
+    struct FixedModel(Vec<Vec<f32>>);
+
+    impl EdgeModel for FixedModel {
+        fn input_size(&self) -> (u32, u32) {
+            (8, 8)
+        }
+        fn run(&self, _image: &RgbImage) -> Result<Vec<Vec<f32>>, EdgeError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn png() -> Vec<u8> {
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(RgbImage::new(16, 16))
+            .write_to(&mut bytes, image::ImageOutputFormat::Png)
+            .unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_models_selected_per_processing_level() {
+        let engine = EdgeInferenceEngine::empty(EdgeInferenceConfig::default())
+            .with_model(EdgeModelKind::PersonDetector, Arc::new(FixedModel(vec![
+                vec![1.0, 1.0, 5.0, 7.0, 0.9, 0.0],
+                vec![0.0, 0.0, 1.0, 1.0, 0.2, 0.0], // Below threshold
+            ])))
+            .with_model(EdgeModelKind::FaceEmbedder, Arc::new(FixedModel(vec![vec![3.0, 4.0]])));
+        assert!(engine.warm_up().is_ok());
+
+        let basic = engine.infer(&png(), ProcessingLevel::Basic).unwrap();
+        assert_eq!(basic.detections.len(), 1);
+        assert_eq!(basic.detections[0].label, "person");
+        assert!(basic.face_embedding.is_none());
+
+        let priority = engine.infer(&png(), ProcessingLevel::Priority).unwrap();
+        assert_eq!(priority.face_embedding, Some(vec![0.6, 0.8]));
+        assert_eq!(priority.models_run, vec![EdgeModelKind::PersonDetector, EdgeModelKind::FaceEmbedder]);
+    }
+
+    #[test]
+    fn test_model_install_checks_sha256() {
+        let dir = std::env::temp_dir().join(format!("edge_models_{}", uuid::Uuid::new_v4()));
+        let config = EdgeInferenceConfig { model_dir: dir.clone(), ..EdgeInferenceConfig::default() };
+        let spec = EdgeModelSpec {
+            kind: EdgeModelKind::PersonDetector,
+            file: "person.onnx".to_string(),
+            input_size: (640, 640),
+            sha256: Some("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".to_string()), // "hello"
+        };
+
+        assert!(matches!(ModelManager::verify(&config, &spec), Err(EdgeError::ModelMissing(_))));
+        assert!(matches!(ModelManager::install(&config, &spec, b"tampered"), Err(EdgeError::ChecksumMismatch(_))));
+        ModelManager::install(&config, &spec, b"hello").unwrap();
+        assert!(ModelManager::verify(&config, &spec).is_ok());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_engine_from_daemon_config_file() {
+        let dir = std::env::temp_dir().join(format!("edge_config_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("daemon.json");
+
+        std::fs::write(&path, "{}").unwrap();
+        assert!(EdgeInferenceEngine::from_config_file(&path).unwrap().is_none());
+
+        std::fs::write(&path, r#"{"edge_inference": {"models": []}}"#).unwrap();
+        let engine = EdgeInferenceEngine::from_config_file(&path).unwrap().unwrap();
+        assert!(engine.loaded().is_empty());
+
+        std::fs::write(&path, r#"{"edge_inference": {"score_threshold": 2.0}}"#).unwrap();
+        assert!(matches!(EdgeInferenceEngine::from_config_file(&path), Err(EdgeError::Config(_))));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_analyzes_on_device_when_vps_disabled() {
+        let engine = EdgeInferenceEngine::empty(EdgeInferenceConfig::default())
+            .with_model(EdgeModelKind::PersonDetector, Arc::new(FixedModel(vec![vec![1.0, 1.0, 5.0, 7.0, 0.9, 0.0]])));
+        let config = || PipelineConfig { vps_enabled: false, overnight_enabled: false, ..PipelineConfig::default() };
+        let event = || RawEvent {
+            event_id: uuid::Uuid::new_v4(),
+            sensor_id: "cam_front".to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            data: "motion".into(),
+            user_id: "user_1".to_string(),
+            home_id: "home_1".to_string(),
+            image_url: None,
+            image_data: Some(png().into()),
+        };
+
+        let mut without = EventPipeline::new(config(), VpsApiClient::new(mock_vps_url().to_string()));
+        assert!(without.process_event(event(), SubscriptionTier::Premium, "key").await.is_err());
+
+        let mut pipeline = EventPipeline::new(config(), VpsApiClient::new(mock_vps_url().to_string()))
+            .with_edge_inference(Arc::new(engine));
+        let processed = pipeline.process_event(event(), SubscriptionTier::Premium, "key").await.unwrap();
+        assert!(processed.vps_job_id.starts_with("edge-"));
+    }
+}
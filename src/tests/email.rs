@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod email_tests {
+    use crate::delivery::{parse_sns, render_summary, EmailDispatcher, EmailError, EmailFeedback, EmailProvider, SnsNotice};
+    use crate::overnight::{MorningSummary, SummaryHighlight};
+    use crate::thinking::AlertDecision;
+    use async_trait::async_trait;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    struct FakeProvider;
+
+    #[async_trait]
+    impl EmailProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        async fn send(&self, message: lettre::Message) -> Result<String, EmailError> {
+            Ok(format!("{} bytes", message.formatted().len()))
+        }
+    }
+
+    fn summary() -> MorningSummary {
+        MorningSummary {
+            home_id: "home_1".to_string(),
+            summary_date: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+            event_count: 4,
+            narrative: "4 events overnight, 1 would have alerted".to_string(),
+            requires_attention: true,
+            event_ids: Vec::new(),
+            highlights: vec![SummaryHighlight {
+                event_id: uuid::Uuid::new_v4(),
+                timestamp: Utc.with_ymd_and_hms(2026, 3, 14, 2, 13, 0).unwrap(),
+                level: AlertDecision::Critical,
+                summary: "Person tried the <back> door".to_string(),
+                thumbnail_url: Some("http://cam/1.jpg".to_string()),
+                entity_id: None,
+            }],
+            entities: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_summary_html_is_colour_coded_with_inline_thumbnails() {
+        let thumbnails = HashMap::from([("http://cam/1.jpg".to_string(), vec![0xff, 0xd8, 0xff, 0xe0])]);
+        let message = render_summary(&summary(), "en", &thumbnails).unwrap();
+
+        assert_eq!(message.subject, "Morning summary for 2026-03-14");
+        assert!(message.html.contains("#c62828"));
+        assert!(message.html.contains("Person tried the &lt;back&gt; door"));
+        assert!(message.html.contains("cid:highlight-0@novin"));
+        assert_eq!(message.inline.len(), 1);
+        assert_eq!(message.inline[0].content_type, "image/jpeg");
+        assert!(message.text.contains("[Critical] 02:13 UTC"));
+
+        let mime = message.to_mime(&"Novin <alerts@example.com>".parse().unwrap(), &"me@example.com".parse().unwrap()).unwrap();
+        let raw = String::from_utf8_lossy(&mime.formatted()).to_string();
+        assert!(raw.contains("multipart/alternative") && raw.contains("multipart/related"));
+    }
+
+    #[test]
+    fn test_sns_bounces_and_complaints_are_parsed() {
+        let message = r#"{"notificationType":"Bounce","bounce":{"bounceType":"Permanent","bouncedRecipients":[{"emailAddress":"gone@example.com"}]}}"#;
+        let body = serde_json::json!({ "Type": "Notification", "Message": message }).to_string();
+        assert_eq!(
+            parse_sns(body.as_bytes()),
+            Some(SnsNotice::Feedback(vec![EmailFeedback::Bounce { address: "gone@example.com".to_string(), permanent: true }]))
+        );
+        assert_eq!(parse_sns(b"not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_complaints_suppress_the_address() {
+        let email = EmailDispatcher::new(Arc::new(FakeProvider), "alerts@example.com").unwrap();
+        email.set_address("home_1", "alice", "Alice@Example.com").unwrap();
+        let message = render_summary(&summary(), "en", &HashMap::new()).unwrap();
+        email.deliver("home_1", "alice", &message).await.unwrap();
+
+        email.handle_feedback(EmailFeedback::Bounce { address: "alice@example.com".to_string(), permanent: false });
+        email.deliver("home_1", "alice", &message).await.unwrap();
+        email.handle_feedback(EmailFeedback::Complaint { address: "alice@example.com".to_string() });
+        assert!(matches!(email.deliver("home_1", "alice", &message).await, Err(EmailError::Suppressed(_))));
+
+        let stats = email.stats("home_1");
+        assert_eq!((stats.sent, stats.undelivered, stats.skipped_opted_out), (2, 2, 1));
+        assert!(email.unsuppress("ALICE@example.com"));
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod embeddings_tests {
+    use crate::embeddings::{DetectionEmbeddings, EmbeddingError, EmbeddingKind, EmbeddingStore};
+
+    #[test]
+    fn test_similar_appearance_links_to_same_entity() {
+        let store = EmbeddingStore::default();
+        let first = DetectionEmbeddings { appearance: Some(vec![0.9, 0.1, 0.0, 0.2]), gait: None };
+        let link = store.link_detection("home_1", &first).unwrap().unwrap();
+        assert!(link.is_new);
+
+        // Same person from another camera: slightly different vector
+        let again = DetectionEmbeddings { appearance: Some(vec![0.88, 0.12, 0.01, 0.22]), gait: None };
+        let relink = store.link_detection("home_1", &again).unwrap().unwrap();
+        assert!(!relink.is_new);
+        assert_eq!(relink.entity_id, link.entity_id);
+
+        // Different person
+        let other = DetectionEmbeddings { appearance: Some(vec![0.0, 0.1, 0.95, 0.0]), gait: None };
+        let other_link = store.link_detection("home_1", &other).unwrap().unwrap();
+        assert_ne!(other_link.entity_id, link.entity_id);
+        assert_eq!(store.entity_count("home_1"), 2);
+
+        // Homes never share entities
+        assert!(store.nearest("home_2", EmbeddingKind::Appearance, &[0.9, 0.1, 0.0, 0.2], 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_bad_vectors() {
+        let store = EmbeddingStore::default();
+        let id = uuid::Uuid::new_v4();
+        store.insert("home_1", id, EmbeddingKind::Gait, &[1.0, 0.0, 0.0]).unwrap();
+        assert!(matches!(
+            store.insert("home_1", id, EmbeddingKind::Gait, &[1.0, 0.0]),
+            Err(EmbeddingError::DimensionMismatch { expected: 3, got: 2 })
+        ));
+        assert!(matches!(
+            store.insert("home_1", id, EmbeddingKind::Gait, &[f32::NAN, 0.0, 1.0]),
+            Err(EmbeddingError::InvalidVector)
+        ));
+    }
+}
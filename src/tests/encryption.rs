@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod encryption_tests {
+    use crate::backup::{BackupArchive, BackupBuilder, BackupError};
+    use crate::encryption::{is_sealed, EncryptedStore, FileKeyProvider, HomeKeyring};
+    use crate::image_preloader::ImagePreloader;
+    use crate::thinking::{export_incident_bundle, Incident, IncidentStoreSnapshot};
+    use chrono::Utc;
+    use std::sync::Arc;
+
+    fn keyring(master: u8) -> Arc<HomeKeyring> {
+        Arc::new(HomeKeyring::new(Arc::new(FileKeyProvider::from_bytes([master; 32]))))
+    }
+
+    #[test]
+    fn test_sealed_data_is_bound_to_home_and_record() {
+        let keyring = keyring(1);
+        let sealed = keyring.seal("home_1", "image:abc", b"jpeg bytes").unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.windows(10).any(|w| w == b"jpeg bytes"));
+
+        assert_eq!(keyring.open("home_1", "image:abc", &sealed).unwrap(), b"jpeg bytes");
+        assert!(keyring.open("home_1", "image:def", &sealed).is_err());
+        assert!(keyring.open("home_2", "image:abc", &sealed).is_err());
+    }
+
+    #[test]
+    fn test_keys_survive_restart_only_with_the_master_key() {
+        let dir = std::env::temp_dir().join(format!("keyring_{}", uuid::Uuid::new_v4()));
+        let provider = Arc::new(FileKeyProvider::from_bytes([7; 32]));
+        let store = EncryptedStore::new(Arc::new(HomeKeyring::persistent(provider.clone(), dir.join("keys")).unwrap()), dir.join("images")).unwrap();
+        store.put_image("home_1", "http://cam/snap.jpg", b"frame").unwrap();
+
+        let reopened = EncryptedStore::new(Arc::new(HomeKeyring::persistent(provider, dir.join("keys")).unwrap()), dir.join("images")).unwrap();
+        assert_eq!(reopened.image("home_1", "http://cam/snap.jpg").unwrap(), Some(b"frame".to_vec()));
+        assert_eq!(reopened.image("home_1", "http://cam/other.jpg").unwrap(), None);
+
+        let wrong_master = Arc::new(FileKeyProvider::from_bytes([8; 32]));
+        let stolen = HomeKeyring::persistent(wrong_master, dir.join("keys"))
+            .and_then(|k| EncryptedStore::new(Arc::new(k), dir.join("images")))
+            .and_then(|s| s.image("home_1", "http://cam/snap.jpg"));
+        assert!(stolen.is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_similar_home_ids_keep_separate_keys_and_images() {
+        let dir = std::env::temp_dir().join(format!("keyring_{}", uuid::Uuid::new_v4()));
+        let provider = Arc::new(FileKeyProvider::from_bytes([7; 32]));
+        let store = EncryptedStore::new(Arc::new(HomeKeyring::persistent(provider.clone(), dir.join("keys")).unwrap()), dir.join("images")).unwrap();
+        store.put_image("a.b", "http://cam/snap.jpg", b"first").unwrap();
+        store.put_image("a_b", "http://cam/snap.jpg", b"second").unwrap();
+
+        let reopened = EncryptedStore::new(Arc::new(HomeKeyring::persistent(provider, dir.join("keys")).unwrap()), dir.join("images")).unwrap();
+        assert_eq!(reopened.keyring().homes(), vec!["a.b".to_string(), "a_b".to_string()]);
+        assert_eq!(reopened.image("a.b", "http://cam/snap.jpg").unwrap(), Some(b"first".to_vec()));
+        assert_eq!(reopened.image("a_b", "http://cam/snap.jpg").unwrap(), Some(b"second".to_vec()));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_evidence_bundles_include_snapshots_from_the_encrypted_store() {
+        let dir = std::env::temp_dir().join(format!("keyring_{}", uuid::Uuid::new_v4()));
+        let store = EncryptedStore::new(keyring(7), dir.join("images")).unwrap();
+        store.put_image("home_1", "http://cam/snap.jpg", b"frame").unwrap();
+        let mut incident = Incident::new(1, 0.0, "t1".to_string());
+        incident.snapshot_urls = vec!["http://cam/snap.jpg".to_string(), "http://cam/gone.jpg".to_string()];
+
+        // Nothing is left in the preload cache, as after a restart
+        let bundle = export_incident_bundle("home_1", &incident, &ImagePreloader::new(), Some(&store), Utc::now()).await.unwrap();
+        assert!(bundle.manifest.files.iter().any(|f| f.path == "snapshots/001_snap.jpg" && f.size_bytes == 5));
+        assert_eq!(bundle.manifest.missing_snapshots, vec!["http://cam/gone.jpg".to_string()]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_encrypted_backup_restores_with_the_master_key() {
+        let snapshot = IncidentStoreSnapshot { ttl_secs: 600.0, id_counter: 3, incidents: Vec::new(), merged_tracks: Vec::new() };
+        let mut builder = BackupBuilder::new().with_encryption(keyring(1));
+        builder.add_incident_store("home_1", &snapshot).unwrap();
+        let bytes = builder.finish().unwrap();
+
+        let archive = BackupArchive::open(&bytes).unwrap();
+        assert!(matches!(archive.incident_stores(), Err(BackupError::InvalidEntry { .. })));
+
+        // A fresh keyring on the same master key picks the home key up from the archive
+        let archive = BackupArchive::open(&bytes).unwrap().with_keyring(keyring(1)).unwrap();
+        archive.validate().unwrap();
+        assert_eq!(archive.incident_stores().unwrap()[0].1.id_counter, 3);
+
+        let wrong_master = BackupArchive::open(&bytes).unwrap().with_keyring(keyring(2)).unwrap();
+        assert!(matches!(wrong_master.incident_stores(), Err(BackupError::Encryption { .. })));
+    }
+}
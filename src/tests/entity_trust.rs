@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod entity_trust_tests {
+    use crate::entity_trust::{TrustChangeKind, TrustConfig, TrustError, TrustStore};
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_benign_visits_build_trust_that_decays() {
+        let store = TrustStore::new(TrustConfig::default());
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        assert_eq!(store.identity_llr("home_1", "entity_a", start), None);
+
+        for _ in 0..5 {
+            store.record_benign_visit("home_1", "entity_a", start).unwrap();
+        }
+        let visited = start;
+        let score = store.score("home_1", "entity_a", visited);
+        assert!((score - (1.0 - 0.8f64.powi(5))).abs() < 1e-9);
+        assert!((store.identity_llr("home_1", "entity_a", visited).unwrap() + score * 2.0).abs() < 1e-9);
+
+        // One half-life later the score has halved
+        assert!((store.score("home_1", "entity_a", visited + Duration::days(30)) - score / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pin_revoke_and_audit() {
+        let store = TrustStore::new(TrustConfig::default());
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        assert!(matches!(store.revoke("home_1", "entity_b", "owner", None, now), Err(TrustError::NotFound(_))));
+
+        store.mark_trusted("home_1", "entity_b", "owner", Some("Cleaner".to_string()), now).unwrap();
+        assert_eq!(store.score("home_1", "entity_b", now + Duration::days(365)), 1.0);
+
+        store.revoke("home_1", "entity_b", "owner", None, now + Duration::days(2)).unwrap();
+        store.record_benign_visit("home_1", "entity_b", now + Duration::days(3)).unwrap();
+        assert_eq!(store.score("home_1", "entity_b", now + Duration::days(3)), 0.0);
+
+        let audit = store.audit("home_1", Some("entity_b"));
+        let kinds: Vec<TrustChangeKind> = audit.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![TrustChangeKind::BenignVisit, TrustChangeKind::Revoked, TrustChangeKind::MarkedTrusted]);
+        assert_eq!(audit[2].actor.as_deref(), Some("owner"));
+        assert_eq!(audit[1].from, 1.0);
+    }
+
+    #[test]
+    fn test_trust_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("entity_trust_{}", uuid::Uuid::new_v4()));
+        let now = Utc::now();
+        let store = TrustStore::persistent(TrustConfig::default(), dir.clone()).unwrap();
+        store.mark_trusted("home/1", "entity_c", "owner", None, now).unwrap();
+        // Would share a file under a sanitized name, so the second write would drop the first
+        store.mark_trusted("home_1", "entity_d", "owner", None, now).unwrap();
+
+        let reloaded = TrustStore::persistent(TrustConfig::default(), dir.clone()).unwrap();
+        assert_eq!(reloaded.score("home/1", "entity_c", now), 1.0);
+        assert_eq!(reloaded.audit("home/1", None).len(), 1);
+        assert_eq!(reloaded.score("home_1", "entity_d", now), 1.0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
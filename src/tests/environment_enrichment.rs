@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod environment_enrichment_tests {
+    use crate::environment::{
+        CalendarConfig, EnrichmentConfig, EnvironmentEnricher, LightCondition, StaticWeatherProvider,
+        WeatherCondition, WeatherConditions, WeatherError, WeatherProvider,
+    };
+    use crate::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier};
+    use crate::tests::support::mock_vps_url;
+    use crate::thinking::Evidence;
+    use crate::vps_client::VpsApiClient;
+    use async_trait::async_trait;
+    use chrono::{TimeZone, Utc};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    // Fog everywhere, counting how often the provider is asked
+    struct CountingProvider {
+        inner: StaticWeatherProvider,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl WeatherProvider for CountingProvider {
+        async fn current(&self, latitude: f64, longitude: f64) -> Result<WeatherConditions, WeatherError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.current(latitude, longitude).await
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fog_lowers_visual_reliability() {
+        let calendar = CalendarConfig {
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            timezone: "Europe/London".to_string(),
+            ..CalendarConfig::default()
+        };
+        let midday = Utc.with_ymd_and_hms(2024, 6, 19, 12, 0, 0).unwrap();
+
+        let clear = EnvironmentEnricher::new(Arc::new(StaticWeatherProvider::new(WeatherCondition::Clear)), EnrichmentConfig::default());
+        clear.register_home("home_1", calendar.clone());
+        let foggy = EnvironmentEnricher::new(Arc::new(StaticWeatherProvider::new(WeatherCondition::Fog)), EnrichmentConfig::default());
+        foggy.register_home("home_1", calendar);
+
+        let clear = clear.snapshot("home_1", midday).await;
+        let foggy = foggy.snapshot("home_1", midday).await;
+
+        assert_eq!(clear.light, LightCondition::Daylight);
+        assert_eq!(clear.visual_reliability, 1.0);
+        assert!(foggy.visual_reliability < 0.6);
+        assert!(foggy.ambient_conditions().contains(&"fog".to_string()));
+    }
+
+    #[test]
+    fn test_visual_reliability_only_shrinks_camera_channels() {
+        let evidence = Evidence {
+            llr_time: 0.5,
+            llr_entry: 0.4,
+            llr_behavior: 1.0,
+            llr_identity: -2.0,
+            llr_presence: 0.3,
+            llr_token: 0.0,
+            ..Default::default()
+        };
+        let scaled = evidence.with_visual_reliability(0.5);
+
+        assert_eq!(scaled.llr_behavior, 0.5);
+        assert_eq!(scaled.llr_identity, -1.0);
+        assert_eq!(scaled.llr_time, evidence.llr_time);
+        assert_eq!(scaled.llr_entry, evidence.llr_entry);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_consults_weather_for_located_homes() {
+        let provider = Arc::new(CountingProvider { inner: StaticWeatherProvider::new(WeatherCondition::Fog), calls: AtomicUsize::new(0) });
+        let enricher = Arc::new(EnvironmentEnricher::new(provider.clone(), EnrichmentConfig::default()));
+        enricher.register_home("home_located", CalendarConfig {
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            timezone: "Europe/London".to_string(),
+            ..CalendarConfig::default()
+        });
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new(mock_vps_url().to_string()))
+            .with_environment_enricher(enricher);
+
+        for home in ["home_located", "home_located", "home_unlocated"] {
+            let event = RawEvent {
+                event_id: Uuid::new_v4(),
+                sensor_id: "cam_front".to_string(),
+                timestamp: Utc::now().timestamp(),
+                data: "motion".into(),
+                user_id: "user_1".to_string(),
+                home_id: home.to_string(),
+                image_url: None,
+                image_data: None,
+            };
+            pipeline.process_event(event, SubscriptionTier::Premium, "key").await.unwrap();
+        }
+
+        // The located home is looked up once and then served from the cache; the other never is
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+}
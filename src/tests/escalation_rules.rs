@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod escalation_rules_tests {
+    use crate::core::{ThreatContext, ZoneClass};
+    use crate::escalation_rules::{EscalationChain, EscalationEngine, EscalationStep};
+    use crate::household::Occupancy;
+    use crate::overnight::DeliveryChannel;
+    use crate::thinking::AlertDecision;
+    use chrono::{Duration, TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn context(zone_class: Option<ZoneClass>, occupancy: Option<Occupancy>) -> ThreatContext {
+        ThreatContext {
+            entity_id: uuid::Uuid::new_v4(),
+            threat_indicators: HashMap::new(),
+            environmental_factors: HashMap::new(),
+            temporal_context: Utc::now(),
+            confidence: 0.1,
+            zone_class,
+            occupancy,
+            threat_vector: None,
+        }
+    }
+
+    fn step(after_secs: i64, label: &str) -> EscalationStep {
+        EscalationStep { after_secs, label: label.to_string(), user_id: Some(label.to_lowercase()), channels: vec![DeliveryChannel::Push] }
+    }
+
+    #[test]
+    fn test_interior_while_away_bypasses_thresholds() {
+        let engine = EscalationEngine::default();
+        let verdict = engine.apply(&context(Some(ZoneClass::Interior), Some(Occupancy::Empty)), &AlertDecision::Ignore);
+        assert_eq!(verdict.decision, AlertDecision::Critical);
+        assert!(verdict.start_escalation);
+
+        for (zone, occupancy) in [
+            (Some(ZoneClass::Exterior), Some(Occupancy::Empty)),
+            (Some(ZoneClass::Interior), Some(Occupancy::Occupied)),
+            (Some(ZoneClass::Interior), None),
+            (None, Some(Occupancy::Empty)),
+        ] {
+            let verdict = engine.apply(&context(zone, occupancy), &AlertDecision::Ignore);
+            assert_eq!(verdict.decision, AlertDecision::Ignore);
+            assert!(!verdict.start_escalation);
+        }
+    }
+
+    #[test]
+    fn test_chain_steps_come_due_in_order_until_acknowledged() {
+        let engine = EscalationEngine::default();
+        engine.set_chain("home_1", EscalationChain { steps: vec![step(300, "Neighbor"), step(0, "Owner"), step(600, "Monitoring")] });
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        assert!(engine.start("home_1", 7, "account", "interior activity", start));
+        assert!(!engine.start("home_1", 7, "account", "again", start));
+
+        let due = engine.due_steps(start);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].step.label, "Owner");
+        assert!(engine.due_steps(start + Duration::seconds(299)).is_empty());
+        assert_eq!(engine.due_steps(start + Duration::seconds(300))[0].step.label, "Neighbor");
+
+        engine.acknowledge("home_1", 7, "owner").unwrap();
+        assert!(engine.due_steps(start + Duration::seconds(900)).is_empty());
+        assert!(engine.active("home_1").is_empty());
+    }
+}
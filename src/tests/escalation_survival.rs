@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod escalation_survival_tests {
+    use crate::tests::support;
+    use crate::thinking::{
+        AlertDecision, EscalationSurvivalConfig, EscalationSurvivalModel, Event, Evidence, HazardCurve, Incident, IncidentLabel,
+        RiskLevel, SurvivalSample,
+    };
+
+    fn event(ts: f64, entry: f64) -> Event {
+        support::event(ts).cam("back_door").away(0.9)
+            .evidence(Evidence { llr_entry: entry, llr_behavior: 1.0, ..Default::default() })
+            .build()
+    }
+
+    // Critical at t=1000; an entry attempt `attempt_after` seconds later, or none until t=2200
+    fn incident(id: u64, attempt_after: Option<f64>) -> Incident {
+        let mut incident = Incident::new(id, 1000.0, "track_a".to_string());
+        incident.add_event(event(1000.0, 0.0));
+        incident.record_assessment(3.0, 0.9, AlertDecision::Critical, "Person at back door");
+        match attempt_after {
+            Some(after) => incident.add_event(event(1000.0 + after, 2.0)),
+            None => incident.add_event(event(2200.0, 0.0)),
+        }
+        incident
+    }
+
+    #[test]
+    fn test_kaplan_meier_handles_censoring() {
+        let samples = [
+            SurvivalSample { level: RiskLevel::Critical, duration_secs: 100.0, attempted: true },
+            SurvivalSample { level: RiskLevel::Critical, duration_secs: 200.0, attempted: false },
+            SurvivalSample { level: RiskLevel::Critical, duration_secs: 300.0, attempted: true },
+            SurvivalSample { level: RiskLevel::Critical, duration_secs: 400.0, attempted: false },
+        ];
+        let curve = HazardCurve::fit(Some(RiskLevel::Critical), &samples);
+        assert!((curve.survival_at(150.0) - 0.75).abs() < 1e-9);
+        // The censored sample leaves the risk set: 2 at risk at t=300
+        assert!((curve.survival_at(300.0) - 0.375).abs() < 1e-9);
+        assert_eq!(curve.survival_at(50.0), 1.0);
+    }
+
+    #[test]
+    fn test_estimate_from_labeled_incidents() {
+        let model = EscalationSurvivalModel::new(EscalationSurvivalConfig::default());
+        for id in 0..4 {
+            model.record_outcome(&incident(id, Some(300.0)), IncidentLabel::Threat);
+        }
+        for id in 4..10 {
+            model.record_outcome(&incident(id, None), IncidentLabel::Threat);
+        }
+
+        let estimate = model.estimate(RiskLevel::Critical, 0.0).unwrap();
+        assert!((estimate.probability - 0.4).abs() < 1e-9);
+        assert!(!estimate.pooled);
+        assert_eq!(estimate.statement(), "If unresolved, 40% chance of entry attempt within 10 min");
+
+        // Having already waited past the only attempts, the risk drops
+        assert_eq!(model.estimate(RiskLevel::Critical, 400.0).unwrap().probability, 0.0);
+    }
+
+    #[test]
+    fn test_benign_door_openings_are_censored_and_small_history_gives_nothing() {
+        let model = EscalationSurvivalModel::new(EscalationSurvivalConfig::default());
+        let samples = model.samples_for(&incident(1, Some(300.0)), IncidentLabel::Benign);
+        assert_eq!(samples.len(), 1);
+        assert!(!samples[0].attempted);
+
+        model.record_outcome(&incident(1, Some(300.0)), IncidentLabel::Threat);
+        assert!(model.estimate(RiskLevel::Critical, 0.0).is_none());
+    }
+}
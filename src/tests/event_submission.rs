@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod event_submission_tests {
+    use crate::api::events::submit_event;
+    use crate::idempotency::{DedupState, IdempotencyKey, IdempotencyStore, SqliteIdempotencyStore};
+    use crate::tests::support::{app_state, mock_vps_calls};
+    use axum::extract::State;
+    use bytes::Bytes;
+    use uuid::Uuid;
+
+    fn body(event_id: Uuid) -> Bytes {
+        Bytes::from(serde_json::to_vec(&serde_json::json!({
+            "event_id": event_id,
+            "sensor_id": "front_door",
+            "data": "{\"motion\":true}",
+            "user_id": "user_1",
+            "home_id": "home_1",
+            "api_key": "key_1",
+            "subscription_tier": "Standard",
+        })).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_resubmitted_event_replays_the_first_result() {
+        let state = app_state().await;
+        let event_id = Uuid::new_v4();
+
+        let first = submit_event(State(state.clone()), body(event_id)).await.unwrap().0;
+        assert_eq!(first.status, "processed", "{}", first.message);
+        let second = submit_event(State(state.clone()), body(event_id)).await.unwrap().0;
+        assert_eq!(second.status, "processed");
+        assert_eq!((second.message, second.processed_at), (first.message, first.processed_at));
+        assert_eq!(mock_vps_calls(&event_id.to_string()), 1);
+
+        // The result was recorded in the database, so it survives a restart
+        let store = SqliteIdempotencyStore::new(state.db_pool.clone());
+        let record = store.get(&IdempotencyKey::new("home_1", event_id)).await.unwrap().unwrap();
+        assert!(matches!(record.state, DedupState::Completed { .. }));
+        assert!(store.get(&IdempotencyKey::new("home_2", event_id)).await.unwrap().is_none());
+    }
+}
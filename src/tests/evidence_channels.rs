@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod evidence_channels_tests {
+    use crate::tests::support;
+    use crate::thinking::evidence_channels::{parse_channel_readings, register_channel, ChannelDescriptor, ChannelError, ChannelFusion};
+    use crate::thinking::{Event, Evidence, Incident};
+
+    fn event(ts: f64, evidence: Evidence) -> Event {
+        support::event(ts).cam("cam_fence").track("t1").evidence(evidence).build()
+    }
+
+    #[test]
+    fn test_registered_channel_is_parsed_fused_and_summed() {
+        let mut lidar = ChannelDescriptor::new("test_lidar_fence", "LIDAR fence", 1.0, 2.0);
+        lidar.fusion = ChannelFusion::Mean;
+        register_channel(lidar.clone()).unwrap();
+        assert_eq!(register_channel(lidar), Err(ChannelError::Duplicate("test_lidar_fence".to_string())));
+        assert!(matches!(register_channel(ChannelDescriptor::new("identity", "Identity", 1.0, 1.0)), Err(ChannelError::Duplicate(_))));
+
+        let readings = parse_channel_readings("person_detected=true|ch.test_lidar_fence=5.0|ch.test_unregistered=1.0");
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings["test_lidar_fence"], 1.0); // Capped by the descriptor
+
+        let mut incident = Incident::new(1, 0.0, "t1".to_string());
+        for (ts, llr) in [(0.0, 1.0), (5.0, 0.0)] {
+            let mut evidence = Evidence::default();
+            evidence.set("test_lidar_fence", llr);
+            evidence.set("test_unregistered", 3.0);
+            incident.add_event(event(ts, evidence));
+        }
+        let fused = incident.fused_evidence(1.6, 3.0);
+        assert_eq!(fused.get("test_lidar_fence"), Some(0.5));
+        assert_eq!(fused.get("test_unregistered"), None);
+        assert!((fused.sum() - 0.5).abs() < 1e-9);
+        assert!(fused.channels().iter().any(|(name, _)| name == "test_lidar_fence"));
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod evidence_export_auth_tests {
+    use crate::api::auth::{AuthUser, Role};
+    use crate::api::incidents::export_evidence_bundle;
+    use crate::tests::support::{app_state, homeowner};
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_bundles_are_only_exported_to_the_homes_users() {
+        let state = app_state().await;
+        let path = || Path(("home_1".to_string(), 42));
+
+        let other_home = export_evidence_bundle(State(state.clone()), homeowner(&["home_2"]), path()).await;
+        assert_eq!(other_home.err(), Some(StatusCode::FORBIDDEN));
+
+        let billing = AuthUser {
+            user_id: "billing".to_string(),
+            username: "billing".to_string(),
+            role: Role::Billing,
+            scopes: Role::Billing.default_scopes(),
+            homes: None,
+        };
+        assert_eq!(export_evidence_bundle(State(state.clone()), billing, path()).await.err(), Some(StatusCode::FORBIDDEN));
+
+        // Allowed through, and an unknown incident is a 404 rather than an export failure
+        let own_home = export_evidence_bundle(State(state), homeowner(&["home_1"]), path()).await;
+        assert_eq!(own_home.err(), Some(StatusCode::NOT_FOUND));
+    }
+}
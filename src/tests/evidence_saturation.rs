@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod evidence_saturation_tests {
+    use crate::tests::support;
+    use crate::thinking::{Event, Evidence, Incident, Saturation, ThinkingAIConfig, ThinkingAIProcessor};
+
+    fn event(ts: f64, track: &str, evidence: Evidence) -> Event {
+        support::event(ts).cam("front").track(track).dwell(30.0).evidence(evidence).build()
+    }
+
+    #[test]
+    fn test_fusion_records_which_cap_each_channel_hit() {
+        let mut incident = Incident::new(1, 0.0, "track_1".to_string());
+        incident.add_event(event(0.0, "track_1", Evidence { llr_time: 0.5, llr_behavior: 5.0, llr_identity: -4.0, ..Default::default() }));
+
+        let fused = incident.fused_evidence(1.6, 3.0);
+        assert_eq!(fused.llr_behavior, 1.6);
+        assert_eq!(fused.saturation("behavior"), Some(Saturation::Positive));
+        assert_eq!(fused.saturation("identity"), Some(Saturation::Negative));
+        assert_eq!(fused.saturation("time"), None);
+        assert_eq!(fused.saturated.len(), 2);
+    }
+
+    #[test]
+    fn test_chronic_saturation_is_counted_and_explained() {
+        let config = ThinkingAIConfig { saturation_min_assessments: 3, saturation_warn_rate: 0.5, ..ThinkingAIConfig::default() };
+        let processor = ThinkingAIProcessor::new(config);
+        let mut last = None;
+        for (i, home) in ["home_a", "home_b", "home_c"].iter().enumerate() {
+            let evidence = Evidence { llr_behavior: 4.0, llr_entry: 0.2, ..Default::default() };
+            last = processor.process_event(home, event(1000.0 + i as f64, "track_1", evidence));
+        }
+
+        let behavior = processor.channel_saturation("behavior").unwrap();
+        assert_eq!((behavior.assessments, behavior.positive, behavior.negative), (3, 3, 0));
+        assert!(behavior.chronic);
+        let entry = processor.saturation_report().into_iter().find(|c| c.channel == "entry").unwrap();
+        assert_eq!(entry.rate(), 0.0);
+        assert!(!entry.chronic);
+
+        let explanation = last.unwrap().explanation;
+        assert_eq!(explanation.saturated, vec!["behavior at the positive cap".to_string()]);
+        assert!(explanation.cautions.iter().any(|c| c.contains("behavior sat at a cap in 100% of 3 assessments")));
+        assert!(explanation.render().contains("Capped: behavior at the positive cap"));
+    }
+}
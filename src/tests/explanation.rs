@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod explanation_tests {
+    use crate::explanation::{config_hash, Direction, Explanation};
+    use crate::tests::support;
+    use crate::thinking::{Evidence, ThinkingAIConfig, ThinkingAIProcessor};
+
+    #[test]
+    fn test_weights_and_directions() {
+        let explanation = Explanation::new(
+            "test",
+            vec![("behavior".to_string(), 1.5), ("token".to_string(), -0.5), ("time".to_string(), 0.0)],
+            String::new(),
+        );
+        let total: f64 = explanation.factors.iter().map(|f| f.weight).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(explanation.factors[0].name, "behavior");
+        assert_eq!(explanation.factors[0].direction, Direction::Raises);
+        assert_eq!(explanation.factors[1].direction, Direction::Lowers);
+
+        let text = explanation.render();
+        assert!(text.contains("Raised by behavior (75%)"));
+        assert!(text.contains("Lowered by token (25%)"));
+        assert!(!text.contains("time"));
+    }
+
+    #[test]
+    fn test_thinking_result_carries_explanation() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let event = support::event(1_700_000_000.0).cam("back_door").track("track_1").dwell(90.0).away(0.9)
+            .evidence(Evidence { llr_time: 0.4, llr_entry: 0.8, llr_behavior: 0.6, llr_identity: 0.3, llr_presence: 0.2, ..Default::default() })
+            .build();
+        let result = processor.process_event("home_1", event).unwrap();
+
+        let explanation = &result.explanation;
+        assert_eq!(explanation.config_hash.len(), 16);
+        assert!(explanation.factors.iter().any(|f| f.name == "entry" && f.direction == Direction::Raises));
+        assert!(explanation.factors.iter().any(|f| f.name == "prior" && f.direction == Direction::Lowers));
+        assert!((explanation.net_contribution() - (result.fused_evidence.sum() + ThinkingAIConfig::default().prior_logit)).abs() < 1e-6);
+
+        assert_eq!(explanation.config_hash, config_hash(&ThinkingAIConfig::default()));
+        assert!(processor.format_thinking_block(&result).contains("Why: "));
+    }
+}
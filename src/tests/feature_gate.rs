@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod feature_gate_tests {
+    use crate::features::{Feature, FeatureGate, FeatureGateError};
+    use crate::pipeline::SubscriptionTier;
+
+    #[test]
+    fn test_default_tier_capabilities() {
+        let gate = FeatureGate::default();
+        assert!(gate.allows(&SubscriptionTier::Premium, Feature::ThinkingAI));
+        assert!(gate.allows(&SubscriptionTier::Standard, Feature::OvernightReview));
+        assert!(!gate.allows(&SubscriptionTier::Standard, Feature::ThinkingAI));
+        assert!(!gate.allows(&SubscriptionTier::Free, Feature::OvernightReview));
+
+        assert_eq!(gate.retention(&SubscriptionTier::Free).num_days(), 1);
+        assert_eq!(gate.retention(&SubscriptionTier::Premium).num_days(), 30);
+    }
+
+    #[test]
+    fn test_gated_feature_names_required_tier() {
+        let gate = FeatureGate::default();
+        assert!(gate.require(&SubscriptionTier::Premium, Feature::LlmNarratives).is_ok());
+
+        let err = gate.require(&SubscriptionTier::Free, Feature::OvernightReview).unwrap_err();
+        assert_eq!(err, FeatureGateError::NotIncluded {
+            feature: Feature::OvernightReview,
+            tier: SubscriptionTier::Free,
+            required: Some(SubscriptionTier::Standard),
+        });
+        assert!(err.to_string().contains("Standard"));
+    }
+}
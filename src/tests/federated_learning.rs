@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod federated_learning_tests {
+    use crate::federated_learning::{
+        merge_updates, FederatedError, FederatedLearningConfig, FederatedLearningNode, FederationTransport, GlobalModel,
+        ModelUpdate,
+    };
+    use crate::thinking::{ChannelWeights, OnlineWeightLearner};
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct StubTransport {
+        uploads: Mutex<Vec<ModelUpdate>>,
+        global: Option<GlobalModel>,
+    }
+
+    #[async_trait]
+    impl FederationTransport for StubTransport {
+        async fn upload(&self, update: &ModelUpdate) -> Result<(), FederatedError> {
+            self.uploads.lock().unwrap().push(update.clone());
+            Ok(())
+        }
+
+        async fn pull_global(&self) -> Result<Option<GlobalModel>, FederatedError> {
+            Ok(self.global)
+        }
+    }
+
+    // `homes` homes whose entry weight moved by `entry_move`, each with `updates` labels
+    fn learner(homes: usize, entry_move: f64, updates: u64) -> Arc<OnlineWeightLearner> {
+        let mut weights = ChannelWeights::default();
+        weights.entry += entry_move;
+        let homes: serde_json::Map<String, serde_json::Value> = (0..homes)
+            .map(|i| (format!("home_{}", i), serde_json::json!({ "weights": weights, "updates": updates, "last_updated": null })))
+            .collect();
+        let json = serde_json::json!({
+            "population": { "weights": ChannelWeights::default(), "updates": 0, "last_updated": null },
+            "homes": homes,
+        });
+        let learner = OnlineWeightLearner::default();
+        learner.restore_json(&json.to_string()).unwrap();
+        Arc::new(learner)
+    }
+
+    fn config() -> FederatedLearningConfig {
+        FederatedLearningConfig { enabled: true, ..Default::default() }
+    }
+
+    fn update(base_round: u64, contributors: u32, entry: f64) -> ModelUpdate {
+        ModelUpdate {
+            node_id: Uuid::new_v4(),
+            base_round,
+            contributors,
+            delta: [0.0, entry, 0.0, 0.0, 0.0, 0.0, 0.0],
+            epsilon: 1.0,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn too_few_homes_upload_nothing() {
+        let node = FederatedLearningNode::new(config(), learner(2, 0.2, 10), Arc::new(StubTransport::default()));
+        assert!(node.summarize(&mut StdRng::seed_from_u64(1)).is_none());
+
+        // Enough homes, but not enough labels each
+        let node = FederatedLearningNode::new(config(), learner(5, 0.2, 2), Arc::new(StubTransport::default()));
+        assert!(node.summarize(&mut StdRng::seed_from_u64(1)).is_none());
+    }
+
+    #[test]
+    fn home_moves_are_clipped_before_noise() {
+        let quiet = FederatedLearningConfig { epsilon: 1e9, ..config() };
+        let node = FederatedLearningNode::new(quiet.clone(), learner(4, 3.0, 10), Arc::new(StubTransport::default()));
+        let update = node.summarize(&mut StdRng::seed_from_u64(7)).unwrap();
+
+        assert_eq!(update.contributors, 4);
+        assert!((update.delta[1] - quiet.clip_norm).abs() < 1e-3);
+        assert!(update.delta.iter().enumerate().filter(|(i, _)| *i != 1).all(|(_, d)| d.abs() < 1e-3));
+    }
+
+    #[test]
+    fn noise_shrinks_with_more_homes() {
+        let config = config();
+        assert!(config.noise_sigma(100) < config.noise_sigma(3));
+        assert!(FederatedLearningConfig { epsilon: 0.5, ..config.clone() }.noise_sigma(3) > config.noise_sigma(3));
+    }
+
+    #[test]
+    fn merge_weights_by_contributors_and_drops_stale_updates() {
+        let global = GlobalModel { round: 4, weights: ChannelWeights::default() };
+        let merged = merge_updates(&global, &[update(4, 3, 0.4), update(4, 1, -0.4), update(3, 50, 5.0)], 1.0);
+
+        assert_eq!(merged.round, 5);
+        assert!((merged.weights.entry - 1.2).abs() < 1e-9);
+        assert_eq!(merge_updates(&global, &[update(2, 3, 0.4)], 1.0), global);
+    }
+
+    #[tokio::test]
+    async fn sync_uploads_and_applies_newer_global() {
+        let mut weights = ChannelWeights::default();
+        weights.entry = 1.3;
+        let transport = Arc::new(StubTransport { global: Some(GlobalModel { round: 2, weights }), ..Default::default() });
+        let learner = learner(3, 0.1, 10);
+        let node = FederatedLearningNode::new(config(), learner.clone(), transport.clone());
+
+        let outcome = node.sync().await.unwrap();
+        assert_eq!(outcome.uploaded, Some(3));
+        assert_eq!(outcome.global_round, 2);
+        assert_eq!(transport.uploads.lock().unwrap().len(), 1);
+        assert!((learner.population().weights.entry - 1.3).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn disabled_node_does_nothing() {
+        let node = FederatedLearningNode::new(FederatedLearningConfig::default(), learner(5, 0.1, 10), Arc::new(StubTransport::default()));
+        assert!(matches!(node.sync().await, Err(FederatedError::Disabled)));
+        assert!(Arc::new(node).spawn().is_none());
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod federation_tests {
+    use crate::environment::HolidayCalendar;
+    use crate::federation::{plate_from_payload, FederationConfig, FederationError, FederationHub, SignatureKind, TrustLevel};
+    use crate::onboarding::{generate, CameraSetup, HouseholdInfo, OnboardingRequest, SensitivityProfile, ZoneKind};
+    use crate::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier};
+    use crate::tests::support::mock_vps_url;
+    use crate::thinking::UncertaintyPolicy;
+    use crate::vps_client::VpsApiClient;
+    use chrono::Utc;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn hub() -> FederationHub {
+        FederationHub::new(FederationConfig {
+            shared_key: "neighborhood-key".to_string(),
+            ..FederationConfig::default()
+        }).unwrap()
+    }
+
+    fn onboarding(share_with_neighbors: bool) -> OnboardingRequest {
+        OnboardingRequest {
+            timezone: "Europe/London".to_string(),
+            latitude: Some(51.5),
+            longitude: Some(-0.12),
+            holidays: HolidayCalendar::None,
+            cameras: vec![CameraSetup { camera_id: "cam_drive".to_string(), zone: "Driveway".to_string(), kind: ZoneKind::Driveway, overlaps_with: Vec::new() }],
+            household: HouseholdInfo::default(),
+            profile: SensitivityProfile::Balanced,
+            uncertainty: None,
+            share_with_neighbors,
+        }
+    }
+
+    #[test]
+    fn test_hub_refuses_to_run_without_a_key() {
+        assert!(matches!(FederationHub::new(FederationConfig::default()), Err(FederationError::MissingKey)));
+        let blank = FederationConfig { shared_key: "   ".to_string(), ..FederationConfig::default() };
+        assert!(matches!(FederationHub::new(blank), Err(FederationError::MissingKey)));
+        let short = FederationConfig { shared_key: "abc".to_string(), ..FederationConfig::default() };
+        assert!(matches!(FederationHub::new(short), Err(FederationError::Config(..))));
+
+        assert_eq!(plate_from_payload("pos_x=1,plate= AB12CDE |speed=2"), Some("AB12CDE"));
+        assert_eq!(plate_from_payload("plate=,pos_x=1"), None);
+        assert_eq!(plate_from_payload(r#"{"motion":true}"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_vehicle_seen_at_nearby_homes_raises_prior() {
+        let hub = hub();
+        hub.join("home_a", 51.5000, -0.1200, TrustLevel::Verified).await;
+        hub.join("home_b", 51.5030, -0.1210, TrustLevel::Verified).await;
+        hub.join("home_c", 51.5050, -0.1190, TrustLevel::Neighbor).await;
+        hub.join("far_away", 53.4800, -2.2400, TrustLevel::Verified).await;
+
+        let now = Utc::now();
+        assert_eq!(hub.area_prior_boost("home_c", now).await, 0.0);
+
+        hub.share_plate("home_a", "AB12 CDE", now).await.unwrap();
+        hub.share_plate("home_b", "ab12cde", now).await.unwrap();
+
+        let boost = hub.area_prior_boost("home_c", now).await;
+        assert!(boost > 0.0);
+        assert_eq!(hub.area_prior_boost("far_away", now).await, 0.0);
+
+        let corroborations = hub.corroborations("home_c", now).await;
+        assert_eq!(corroborations.len(), 1);
+        assert_eq!(corroborations[0].reporting_homes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_member_reports_stop_counting() {
+        let hub = hub();
+        hub.join("home_a", 51.5000, -0.1200, TrustLevel::Verified).await;
+        hub.join("home_b", 51.5030, -0.1210, TrustLevel::Verified).await;
+        hub.join("home_c", 51.5050, -0.1190, TrustLevel::Verified).await;
+
+        let now = Utc::now();
+        hub.share_plate("home_a", "XY99ZZZ", now).await.unwrap();
+        hub.share_plate("home_b", "XY99ZZZ", now).await.unwrap();
+        assert!(hub.area_prior_boost("home_c", now).await > 0.0);
+
+        hub.revoke("home_b").await.unwrap();
+        assert_eq!(hub.area_prior_boost("home_c", now).await, 0.0);
+        assert!(hub.share_plate("home_b", "XY99ZZZ", now).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_shares_plates_from_opted_in_homes() {
+        let hub = Arc::new(hub());
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new(mock_vps_url().to_string()))
+            .with_federation(hub.clone());
+
+        let homes = [("home_a", true), ("home_b", true), ("home_c", true), ("home_d", false)];
+        for (home, share) in homes {
+            let mut home_config = generate(home, &onboarding(share), Utc::now().date_naive()).unwrap();
+            // Every event alerts, so each opted-in home shares what it saw
+            home_config.alert_threshold_logit = -8.0;
+            home_config.uncertainty = Some(UncertaintyPolicy { enabled: false, ..UncertaintyPolicy::default() });
+            pipeline.apply_home_config(&home_config).await.unwrap();
+        }
+        assert_eq!(hub.member("home_a").await.unwrap().trust, TrustLevel::Neighbor);
+        assert!(hub.member("home_d").await.is_none());
+
+        for (home, _) in homes {
+            let event = RawEvent {
+                event_id: Uuid::new_v4(),
+                sensor_id: "cam_drive".to_string(),
+                timestamp: Utc::now().timestamp() - 60,
+                data: "pos_x=1,pos_y=8,plate=ab12 cde".into(),
+                user_id: "user_1".to_string(),
+                home_id: home.to_string(),
+                image_url: None,
+                image_data: None,
+            };
+            pipeline.process_event(event, SubscriptionTier::Premium, "key").await.unwrap();
+        }
+
+        // Three self-enrolled homes reported the plate; home_d saw it too but shares nothing
+        let corroborations = hub.corroborations("home_a", Utc::now()).await;
+        let plate = corroborations.iter().find(|c| c.kind == SignatureKind::VehiclePlate).unwrap();
+        assert_eq!(plate.reporting_homes, 3);
+        assert!(hub.area_prior_boost("home_b", Utc::now()).await > 0.0);
+
+        // Opting out at the next config update revokes membership
+        pipeline.apply_home_config(&generate("home_c", &onboarding(false), Utc::now().date_naive()).unwrap()).await.unwrap();
+        assert!(hub.member("home_c").await.unwrap().revoked_at.is_some());
+        assert!(hub.corroborations("home_a", Utc::now()).await.iter().all(|c| c.reporting_homes < 3));
+    }
+}
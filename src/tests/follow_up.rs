@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod follow_up_tests {
+    use crate::follow_up::{FollowUp, FollowUpConfig, FollowUpError, FollowUpResolution, FollowUpScheduler, FollowUpStore};
+    use crate::thinking::AlertDecision;
+    use async_trait::async_trait;
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct MemoryStore {
+        rows: Mutex<HashMap<(String, u64), FollowUp>>,
+    }
+
+    #[async_trait]
+    impl FollowUpStore for MemoryStore {
+        async fn put(&self, follow_up: &FollowUp) -> Result<(), FollowUpError> {
+            self.rows.lock().unwrap().insert((follow_up.home_id.clone(), follow_up.incident_id), follow_up.clone());
+            Ok(())
+        }
+        async fn remove(&self, home_id: &str, incident_id: u64) -> Result<(), FollowUpError> {
+            self.rows.lock().unwrap().remove(&(home_id.to_string(), incident_id));
+            Ok(())
+        }
+        async fn load_all(&self) -> Result<Vec<FollowUp>, FollowUpError> {
+            Ok(self.rows.lock().unwrap().values().cloned().collect())
+        }
+    }
+
+    fn config() -> FollowUpConfig {
+        FollowUpConfig { delays: vec![Duration::seconds(60), Duration::seconds(120)] }
+    }
+
+    #[tokio::test]
+    async fn test_new_evidence_while_waiting_escalates() {
+        let scheduler = FollowUpScheduler::new(config());
+        let now = Utc::now();
+        let first = scheduler.on_assessment("home_1", 7, "user_1", Some("front_door"), &AlertDecision::Wait, 1, now).await.unwrap();
+        assert_eq!(first, None);
+        assert_eq!(scheduler.pending("home_1").await.len(), 1);
+
+        let second = scheduler.on_assessment("home_1", 7, "user_1", Some("front_door"), &AlertDecision::Wait, 2, now).await.unwrap();
+        assert_eq!(second, Some(FollowUpResolution::Escalate(AlertDecision::Standard)));
+        assert!(scheduler.pending("home_1").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_quiet_incident_resolves_to_ignore_after_last_delay() {
+        let scheduler = FollowUpScheduler::new(config());
+        let now = Utc::now();
+        scheduler.on_assessment("home_1", 7, "user_1", None, &AlertDecision::Wait, 1, now).await.unwrap();
+        assert!(scheduler.due(now + Duration::seconds(30)).await.is_empty());
+
+        // First delay: still Wait, so it waits once more
+        let due = scheduler.due(now + Duration::seconds(61)).await;
+        assert_eq!(due.len(), 1);
+        let outcome = scheduler.on_due(&due[0], Some(&AlertDecision::Wait), 1, now + Duration::seconds(61)).await.unwrap();
+        assert_eq!(outcome, None);
+
+        let due = scheduler.due(now + Duration::seconds(200)).await;
+        assert_eq!(due[0].attempt, 1);
+        let outcome = scheduler.on_due(&due[0], Some(&AlertDecision::Wait), 1, now + Duration::seconds(200)).await.unwrap();
+        assert_eq!(outcome, Some(FollowUpResolution::Ignore));
+        assert!(scheduler.pending("home_1").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_timers_survive_restart() {
+        let store = Arc::new(MemoryStore::default());
+        let now = Utc::now();
+        let before = FollowUpScheduler::with_store(config(), store.clone());
+        before.on_assessment("home_1", 3, "user_1", None, &AlertDecision::Wait, 1, now).await.unwrap();
+
+        let after = FollowUpScheduler::with_store(config(), store.clone());
+        assert_eq!(after.restore().await.unwrap(), 1);
+        let due = after.due(now + Duration::seconds(61)).await;
+        assert_eq!(due.len(), 1);
+
+        // The incident was lost with the restart: nothing new, so it resolves quietly
+        let outcome = after.on_due(&due[0], None, 0, now + Duration::seconds(61)).await.unwrap();
+        assert_eq!(outcome, Some(FollowUpResolution::Ignore));
+        assert!(store.rows.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod guest_access_tests {
+    use crate::api::guests::create_guest;
+    use crate::guest_access::{GuestAccessConfig, GuestMatchKind, GuestProfileRequest, GuestRegistry, GuestWindow};
+    use crate::tests::support::{app_state, homeowner};
+    use crate::visitor_tokens::VisitorTokenStore;
+    use axum::extract::{Path, State};
+    use axum::Json;
+    use chrono::{Duration, NaiveTime, TimeZone, Utc, Weekday};
+
+    fn babysitter(expires_in_days: i64, issue_code: bool) -> GuestProfileRequest {
+        GuestProfileRequest {
+            name: "Babysitter".to_string(),
+            starts_at: None,
+            expires_at: Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap() + Duration::days(expires_in_days),
+            timezone: "Europe/London".to_string(),
+            windows: vec![GuestWindow {
+                days: vec![Weekday::Fri],
+                start: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            }],
+            entity_ids: vec!["entity_sitter".to_string()],
+            face_embeddings: Vec::new(),
+            issue_code,
+        }
+    }
+
+    #[test]
+    fn test_guest_matches_inside_and_outside_their_window() {
+        let registry = GuestRegistry::new(GuestAccessConfig::default());
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap(); // Monday
+        registry.create("home_1", babysitter(7, false), None, None, now).unwrap();
+
+        let friday_evening = Utc.with_ymd_and_hms(2026, 3, 6, 19, 30, 0).unwrap();
+        let expected = registry.match_visit("home_1", "entity_sitter", None, friday_evening).unwrap();
+        assert!(expected.within_window);
+        assert_eq!(expected.matched_by, GuestMatchKind::Entity);
+
+        let tuesday_night = Utc.with_ymd_and_hms(2026, 3, 3, 3, 0, 0).unwrap();
+        let flagged = registry.match_visit("home_1", "entity_sitter", None, tuesday_night).unwrap();
+        assert!(!flagged.within_window);
+        assert!(flagged.narrative().contains("OUTSIDE their expected window (Fri 18:00-23:00)"));
+
+        assert!(registry.match_visit("home_1", "entity_stranger", None, friday_evening).is_none());
+    }
+
+    #[test]
+    fn test_profile_expires_and_its_code_identifies_the_guest() {
+        let registry = GuestRegistry::new(GuestAccessConfig::default());
+        let tokens = VisitorTokenStore::default();
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        let created = registry.create("home_1", babysitter(5, true), Some(&tokens), None, now).unwrap();
+        assert!(created.access_code.is_some());
+        assert!(registry.create("home_1", babysitter(5, false), None, None, now + Duration::days(6)).is_err());
+
+        let token_id = created.profile.token_id;
+        let friday_evening = Utc.with_ymd_and_hms(2026, 3, 6, 19, 30, 0).unwrap();
+        let by_code = registry.match_visit("home_1", "entity_unknown", token_id, friday_evening).unwrap();
+        assert_eq!(by_code.matched_by, GuestMatchKind::Code);
+
+        // Past expiry the profile no longer explains anyone, and is pruned
+        let after = now + Duration::days(5);
+        assert!(registry.match_visit("home_1", "entity_sitter", None, after).is_none());
+        registry.prune_expired(after);
+        assert!(registry.list("home_1", now).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_app_state_enrolls_guest_faces() {
+        let state = app_state().await;
+        let mut request = babysitter(0, false);
+        request.expires_at = Utc::now() + Duration::days(3);
+        request.entity_ids.clear();
+        request.face_embeddings = vec![vec![0.9, 0.1, 0.0, 0.2], vec![0.88, 0.12, 0.01, 0.22]];
+
+        let created = create_guest(State(state), homeowner(&["home_1"]), Path("home_1".to_string()), Json(request)).await.unwrap();
+        let entity_ids = &created.0.data.profile.entity_ids;
+        assert_eq!(entity_ids.len(), 1);
+        assert!(entity_ids[0].starts_with("entity_"));
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod household_tests {
+    use crate::delivery::NotificationSeverity;
+    use crate::household::{HouseholdError, HouseholdRegistry, HouseholdRole, Occupancy, Presence, ResidentRequest};
+    use crate::overnight::DeliveryChannel;
+    use chrono::{Duration, Utc};
+
+    fn request(user_id: &str, role: HouseholdRole) -> ResidentRequest {
+        ResidentRequest { user_id: user_id.to_string(), name: user_id.to_string(), role, channels: None, min_severity: None }
+    }
+
+    fn household() -> HouseholdRegistry {
+        let registry = HouseholdRegistry::default();
+        registry.add_resident("home_1", request("alex", HouseholdRole::Owner)).unwrap();
+        registry.add_resident("home_1", request("sam", HouseholdRole::Adult)).unwrap();
+        registry.add_resident("home_1", request("kim", HouseholdRole::Teen)).unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_recipients_follow_role_thresholds() {
+        let registry = household();
+        let ids = |severity| -> Vec<String> {
+            registry.recipients("home_1", severity).into_iter().filter_map(|(id, _)| id).collect()
+        };
+        assert_eq!(ids(NotificationSeverity::Info), vec!["alex"]);
+        assert_eq!(ids(NotificationSeverity::Standard), vec!["alex", "sam"]);
+        assert_eq!(ids(NotificationSeverity::Elevated), vec!["alex", "sam", "kim"]);
+
+        let teen = registry.recipients("home_1", NotificationSeverity::Critical).into_iter().find(|(id, _)| id.as_deref() == Some("kim")).unwrap();
+        assert_eq!(teen.1, vec![DeliveryChannel::Push]);
+    }
+
+    #[test]
+    fn test_presence_aggregates_into_dwelling_state() {
+        let registry = household();
+        let now = Utc::now();
+        let state = registry.dwelling_state("home_1", now).unwrap();
+        assert_eq!(state.occupancy, Occupancy::Unknown);
+        assert_eq!(state.away_prob, 0.5);
+
+        for user in ["alex", "sam", "kim"] {
+            registry.set_presence("home_1", user, Presence::Away, now).unwrap();
+        }
+        let state = registry.dwelling_state("home_1", now).unwrap();
+        assert_eq!(state.occupancy, Occupancy::Empty);
+        assert!(state.away_prob > 0.9);
+
+        registry.set_presence("home_1", "kim", Presence::Home, now).unwrap();
+        assert_eq!(registry.dwelling_state("home_1", now).unwrap().occupancy, Occupancy::Occupied);
+
+        // Stale reports stop counting
+        let later = now + Duration::days(1);
+        assert_eq!(registry.dwelling_state("home_1", later).unwrap().occupancy, Occupancy::Unknown);
+        assert!(registry.dwelling_state("home_2", now).is_none());
+    }
+
+    #[test]
+    fn test_last_owner_is_kept() {
+        let registry = household();
+        assert_eq!(registry.remove_resident("home_1", "alex"), Err(HouseholdError::LastOwner));
+        assert_eq!(registry.update_resident("home_1", request("alex", HouseholdRole::Adult)), Err(HouseholdError::LastOwner));
+        assert!(matches!(registry.add_resident("home_1", request("sam", HouseholdRole::Guest)), Err(HouseholdError::Duplicate(_))));
+        assert!(registry.remove_resident("home_1", "sam").is_ok());
+    }
+}
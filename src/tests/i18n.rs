@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod i18n_tests {
+    use crate::delivery::NotificationSeverity;
+    use crate::i18n::{Catalog, Localizer};
+
+    #[test]
+    fn test_builtin_catalogs_translate_every_english_message() {
+        let localizer = Localizer::builtin();
+        assert_eq!(localizer.locales(), vec!["de", "en", "es", "fr"]);
+        let english = Catalog::parse("en", include_str!("../i18n/locales/en.ftl")).unwrap();
+        for (locale, source) in [("de", include_str!("../i18n/locales/de.ftl")), ("fr", include_str!("../i18n/locales/fr.ftl")), ("es", include_str!("../i18n/locales/es.ftl"))] {
+            assert_eq!(Catalog::parse(locale, source).unwrap().len(), english.len(), "{} is missing messages", locale);
+        }
+    }
+
+    #[test]
+    fn test_messages_interpolate_pluralize_and_fall_back() {
+        let mut localizer = Localizer::builtin();
+        let args = [("severity", localizer.severity("de-AT", NotificationSeverity::Critical)), ("zone", "Haustür".to_string())];
+        assert_eq!(localizer.message("de-AT", "alert-title", &args), "Alarm (Kritisch): Haustür");
+        assert_eq!(localizer.plural("en", "summary-no-alerts", 1, &[]), "1 event overnight, none would have alerted");
+        assert_eq!(localizer.plural("en", "summary-alerts", 3, &[("alerts", "2".to_string())]), "3 events overnight, 2 would have alerted");
+
+        localizer.add("nl", Catalog::parse("nl", "summary-quiet = Rustige nacht").unwrap());
+        assert_eq!(localizer.message("nl", "summary-quiet", &[]), "Rustige nacht");
+        assert_eq!(localizer.message("nl", "severity-elevated", &[]), "Elevated");
+        assert_eq!(localizer.message("nl", "no-such-message", &[]), "no-such-message");
+        assert!(Catalog::parse("nl", "    .one = orphan").is_err());
+    }
+}
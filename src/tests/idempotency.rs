@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod idempotency_tests {
+    use crate::idempotency::*;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn guard() -> IdempotencyGuard {
+        IdempotencyGuard::new(IdempotencyConfig {
+            enabled: true,
+            dedup_window: Duration::from_secs(60),
+            max_entries: 1_000,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retry_replays_completed_result() {
+        let guard = guard();
+        let key = IdempotencyKey::new("home_1", Uuid::new_v4());
+
+        assert_eq!(guard.claim(&key).await.unwrap(), Claim::Acquired);
+        assert_eq!(guard.claim(&key).await.unwrap(), Claim::InFlight);
+
+        guard.complete(&key, "{\"status\":\"completed\"}".to_string()).await.unwrap();
+        assert_eq!(
+            guard.claim(&key).await.unwrap(),
+            Claim::Completed("{\"status\":\"completed\"}".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_same_event_id_different_home_is_not_duplicate() {
+        let guard = guard();
+        let event_id = Uuid::new_v4();
+
+        assert_eq!(guard.claim(&IdempotencyKey::new("home_1", event_id)).await.unwrap(), Claim::Acquired);
+        assert_eq!(guard.claim(&IdempotencyKey::new("home_2", event_id)).await.unwrap(), Claim::Acquired);
+    }
+
+    #[tokio::test]
+    async fn test_release_allows_retry_after_failure() {
+        let guard = guard();
+        let key = IdempotencyKey::new("home_1", Uuid::new_v4());
+
+        assert_eq!(guard.claim(&key).await.unwrap(), Claim::Acquired);
+        guard.release(&key).await;
+        assert_eq!(guard.claim(&key).await.unwrap(), Claim::Acquired);
+    }
+}
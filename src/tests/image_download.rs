@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod image_download_tests {
+    use crate::image_preloader::{parse_content_range, ContentRange, ExpectedChecksum, ImageError};
+    use reqwest::header::{HeaderMap, HeaderValue, ETAG};
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(
+            parse_content_range("bytes 0-2097151/4500000"),
+            Some(ContentRange { start: 0, end: 2097151, total: Some(4500000) })
+        );
+        assert_eq!(parse_content_range("bytes 100-199/*").unwrap().total, None);
+        assert!(parse_content_range("bytes 200-100/300").is_none());
+        assert!(parse_content_range("bytes 0-300/300").is_none());
+        assert!(parse_content_range("items 0-1/2").is_none());
+    }
+
+    #[test]
+    fn test_checksum_from_headers_and_verify() {
+        let body = b"\xFF\xD8\xFFjpeg body";
+
+        // Content-MD5 is base64 of the raw digest
+        let mut headers = HeaderMap::new();
+        headers.insert("content-md5", HeaderValue::from_static("912aPnzr1EFtKLUDLHfPXA=="));
+        let md5 = ExpectedChecksum::from_headers(&headers, true).unwrap();
+        assert!(md5.verify(body).is_ok());
+
+        // Hex MD5 ETag as served by object stores
+        let digest = hex::encode(<md5::Md5 as md5::Digest>::digest(body));
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_str(&format!("\"{}\"", digest)).unwrap());
+        let etag = ExpectedChecksum::from_headers(&headers, false).unwrap();
+        assert!(etag.verify(body).is_ok());
+        assert!(matches!(etag.verify(b"tampered"), Err(ImageError::ChecksumMismatch(_))));
+
+        // Opaque and weak ETags carry no checksum
+        headers.insert(ETAG, HeaderValue::from_static("W/\"abc\""));
+        assert!(ExpectedChecksum::from_headers(&headers, false).is_none());
+        headers.insert(ETAG, HeaderValue::from_static("\"v3-5f1c\""));
+        assert!(ExpectedChecksum::from_headers(&headers, false).is_none());
+    }
+}
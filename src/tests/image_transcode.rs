@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod image_transcode_tests {
+    use crate::image_transcode::{render, ImageTranscoder, OutputFormat, Rendition, RenditionSpec, TranscodeError};
+    use crate::overnight::DeliveryChannel;
+    use crate::tests::support::app_state;
+    use axum::{http::header, routing::get, Router};
+    use bytes::Bytes;
+    use std::io::Cursor;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let img = image::RgbImage::from_pixel(width, height, image::Rgb([40, 90, 160]));
+        let mut out = Vec::new();
+        image::DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut out), image::ImageOutputFormat::Png)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn test_thumbnail_keeps_aspect_ratio() {
+        let spec = RenditionSpec { max_width: 320, max_height: 240, format: OutputFormat::Jpeg, quality: 70, avif_speed: 8 };
+        let rendition = render(&png(1280, 720), &spec, 40_000_000).unwrap();
+        assert_eq!((rendition.width, rendition.height), (320, 180));
+        assert_eq!(rendition.content_type, "image/jpeg");
+        assert!(rendition.data.starts_with(&[0xFF, 0xD8, 0xFF]));
+    }
+
+    #[test]
+    fn test_rejects_oversized_source() {
+        let spec = RenditionSpec { max_width: 64, max_height: 64, format: OutputFormat::WebP, quality: 70, avif_speed: 8 };
+        assert!(matches!(render(&png(200, 200), &spec, 10_000), Err(TranscodeError::TooLarge { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_channel_rendition_and_passthrough() {
+        let transcoder = ImageTranscoder::default();
+        let push = transcoder.transcode(Bytes::from(png(800, 600)), &DeliveryChannel::Push).await.unwrap();
+        assert_eq!(push.format, Some(OutputFormat::WebP));
+        assert_eq!(&push.data[8..12], b"WEBP");
+        assert!(push.width <= 320 && push.height <= 240);
+
+        assert!(matches!(
+            transcoder.transcode(Bytes::from(png(8, 8)), &DeliveryChannel::Webhook).await,
+            Err(TranscodeError::NoRendition(_))
+        ));
+
+        let original = Rendition::original(Bytes::from(png(8, 8)));
+        assert_eq!(original.content_type, "image/png");
+        assert_eq!((original.width, original.height), (8, 8));
+    }
+
+    #[tokio::test]
+    async fn test_app_state_serves_push_thumbnails() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/snapshot.png", listener.local_addr().unwrap());
+        let camera = Router::new().route("/snapshot.png", get(|| async { ([(header::CONTENT_TYPE, "image/png")], png(1280, 720)) }));
+        tokio::spawn(async move { axum::serve(listener, camera).await.unwrap() });
+
+        let state = app_state().await;
+        let push = state.pipeline.read().await
+            .get_image_rendition(url, uuid::Uuid::new_v4(), DeliveryChannel::Push)
+            .await
+            .unwrap();
+        assert_eq!(push.format, Some(OutputFormat::WebP));
+        assert_eq!((push.width, push.height), (320, 180));
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod incident_editing_tests {
+    use crate::tests::support;
+    use crate::thinking::{Event, Evidence, IncidentStatus, IncidentStore, LifecycleError, ThinkingAIConfig, ThinkingAIProcessor};
+
+    fn event(ts: f64, cam: &str, track: &str) -> Event {
+        support::event(ts).cam(cam).track(track)
+            .evidence(Evidence { llr_time: 0.5, llr_entry: 0.5, llr_behavior: 0.5, ..Default::default() })
+            .build()
+    }
+
+    #[test]
+    fn test_merge_combines_events_and_routes_later_events_to_target() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let a = processor.process_event("home_1", event(1000.0, "front_door", "track_a")).unwrap().incident_id;
+        let b = processor.process_event("home_1", event(1010.0, "back_yard", "track_b")).unwrap().incident_id;
+
+        let merged = processor.merge_incidents("home_1", a, b, 1020.0).unwrap();
+        assert_eq!(merged.incident_id, a);
+        let incident = processor.find_incident("home_1", a).unwrap();
+        assert_eq!(incident.events.len(), 2);
+        assert!(incident.cameras.contains("back_yard"));
+        assert!(processor.find_incident("home_1", b).is_none());
+        assert!(processor.take_transitions().iter().any(|t| t.incident_id == b && t.to == IncidentStatus::Resolved));
+
+        // The merged-away track keeps feeding the combined incident
+        let next = processor.process_event("home_1", event(1030.0, "back_yard", "track_b")).unwrap();
+        assert_eq!(next.incident_id, a);
+        assert_eq!(processor.find_incident("home_1", a).unwrap().events.len(), 3);
+    }
+
+    #[test]
+    fn test_split_moves_events_and_rescores_both() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let id = processor.process_event("home_1", event(1000.0, "front_door", "track_a")).unwrap().incident_id;
+        processor.process_event("home_1", event(1010.0, "driveway", "track_a")).unwrap();
+        processor.process_event("home_1", event(1020.0, "front_door", "track_a")).unwrap();
+
+        let (original, split) = processor.split_incident("home_1", id, &[1]).unwrap();
+        assert_eq!(original.incident_id, id);
+        assert_ne!(split.incident_id, id);
+
+        let remaining = processor.find_incident("home_1", id).unwrap();
+        assert_eq!(remaining.events.len(), 2);
+        assert!(!remaining.cameras.contains("driveway"));
+        let moved = processor.find_incident("home_1", split.incident_id).unwrap();
+        assert_eq!(moved.events.len(), 1);
+        assert_eq!(moved.events[0].cam, "driveway");
+        assert_eq!(moved.probability_trace.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_edits_are_rejected() {
+        let mut store = IncidentStore::new(600.0);
+        let id = store.upsert_event("home_1", event(1000.0, "front_door", "track_a"));
+        store.upsert_event("home_1", event(1010.0, "front_door", "track_a"));
+
+        assert!(matches!(store.merge("home_1", id, id, 1020.0), Err(LifecycleError::InvalidEdit(_))));
+        assert_eq!(store.merge("home_1", id, 99, 1020.0), Err(LifecycleError::NotFound(99)));
+        assert!(matches!(store.split("home_1", id, &[]), Err(LifecycleError::InvalidEdit(_))));
+        assert!(matches!(store.split("home_1", id, &[0, 1]), Err(LifecycleError::InvalidEdit(_))));
+        assert!(matches!(store.split("home_1", id, &[5]), Err(LifecycleError::InvalidEdit(_))));
+        assert_eq!(store.get_incident("home_1", "track_a").unwrap().events.len(), 2);
+    }
+}
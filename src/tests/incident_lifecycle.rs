@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod incident_lifecycle_tests {
+    use crate::tests::support;
+    use crate::thinking::{Event, IncidentStatus, IncidentStore, LifecycleError, ThinkingAIConfig, ThinkingAIProcessor};
+
+    fn event(ts: f64, track: &str) -> Event {
+        support::event(ts).track(track).build()
+    }
+
+    #[test]
+    fn test_quiet_incident_expires_with_transition() {
+        let mut store = IncidentStore::new(60.0);
+        let id = store.upsert_event("home_1", event(1000.0, "track_a"));
+        store.expire_stale(1100.0);
+
+        let incident = store.get_incident("home_1", "track_a").unwrap();
+        assert_eq!(incident.status, IncidentStatus::Expired);
+        assert_eq!(store.transitions.len(), 1);
+        assert_eq!(store.transitions[0].incident_id, id);
+        assert_eq!(store.transitions[0].from, IncidentStatus::Open);
+
+        // The same person later starts a fresh incident
+        let next = store.upsert_event("home_1", event(1110.0, "track_a"));
+        assert_ne!(next, id);
+    }
+
+    #[test]
+    fn test_dismiss_closes_once() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let result = processor.process_event("home_1", event(1000.0, "track_a")).unwrap();
+
+        let transition = processor.close_incident("home_1", result.incident_id, IncidentStatus::Dismissed, 1010.0, "neighbor").unwrap();
+        assert_eq!(transition.to, IncidentStatus::Dismissed);
+        assert!(processor.open_incidents().is_empty());
+        assert!(processor.take_transitions().iter().any(|t| t.to == IncidentStatus::Dismissed));
+
+        let again = processor.close_incident("home_1", result.incident_id, IncidentStatus::Resolved, 1020.0, "late");
+        assert_eq!(again, Err(LifecycleError::AlreadyClosed(result.incident_id, IncidentStatus::Dismissed)));
+        assert_eq!(
+            processor.close_incident("home_1", result.incident_id, IncidentStatus::Expired, 1020.0, ""),
+            Err(LifecycleError::InvalidTarget(IncidentStatus::Expired))
+        );
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod ingest_tests {
+    use crate::ingest::{parse_raw_event, EventPayload};
+    use bytes::Bytes;
+
+    fn body(data: &str) -> Bytes {
+        Bytes::from(format!(
+            r#"{{"event_id":"7f0c2a1e-5b7d-4d1a-9c3e-2f4b6a8d0e11","sensor_id":"front_door","timestamp":1700000000,"data":{},"user_id":"user_1","home_id":"home_1"}}"#,
+            data
+        ))
+    }
+
+    fn within(payload: &EventPayload, body: &Bytes) -> bool {
+        let range = body.as_ptr_range();
+        range.contains(&payload.bytes().as_ptr())
+    }
+
+    #[test]
+    fn test_plain_payload_shares_the_request_buffer() {
+        let body = body(r#""person_detected=true|dwell_time=45s""#);
+        let event = parse_raw_event(body.clone()).unwrap();
+        assert_eq!(&*event.data, "person_detected=true|dwell_time=45s");
+        assert!(within(&event.data, &body));
+        assert_eq!(event.home_id, "home_1");
+
+        // Clones through the pipeline still point at the same bytes
+        let cloned = event.data.clone();
+        assert_eq!(cloned.bytes().as_ptr(), event.data.bytes().as_ptr());
+    }
+
+    #[test]
+    fn test_escaped_payload_is_unescaped_and_serializes_as_text() {
+        let body = body(r#""{\"access_code\":\"4821\"}""#);
+        let event = parse_raw_event(body.clone()).unwrap();
+        assert_eq!(&*event.data, r#"{"access_code":"4821"}"#);
+        assert!(!within(&event.data, &body));
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["data"], r#"{"access_code":"4821"}"#);
+        assert!(EventPayload::from_bytes(Bytes::from_static(&[0xff, 0xfe])).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod knowledge_graph_tests {
+    use crate::api::tracking::{knowledge_graph, KnowledgeGraphQuery};
+    use crate::core::{Entity, EnvironmentalContext, TimeContext};
+    use crate::knowledge_graph::*;
+    use crate::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier};
+    use crate::tests::support::{self, app_state, homeowner, mock_vps_url};
+    use crate::thinking::Incident;
+    use crate::vps_client::VpsApiClient;
+    use axum::extract::{Path, Query, State};
+    use axum::http::StatusCode;
+    use chrono::{DateTime, Duration, Utc};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn incident(id: u64, person: &str, cams: &[&str], secs: i64) -> Incident {
+        let ts = (1_700_000_000 + secs) as f64;
+        let mut incident = Incident::new(id, ts, person.to_string());
+        for cam in cams {
+            incident.add_event(support::event(ts).cam(cam).track(person).dwell(20.0).build());
+        }
+        incident
+    }
+
+    #[test]
+    fn test_edges_count_and_queries_follow_them() {
+        let mut graph = KnowledgeGraph::new();
+        let a = graph.upsert_node(NodeKind::Entity, "a", HashMap::new(), at(0));
+        let b = graph.upsert_node(NodeKind::Entity, "b", HashMap::new(), at(0));
+        let garden = graph.upsert_node(NodeKind::Zone, "back_garden", HashMap::new(), at(0));
+        graph.upsert_edge(&b, &a, EdgeKind::SeenWith, at(0));
+        graph.upsert_edge(&a, &b, EdgeKind::SeenWith, at(10));
+        for i in 0..3 {
+            graph.upsert_edge(&a, &garden, EdgeKind::Frequents, at(i));
+        }
+        graph.upsert_edge(&b, &garden, EdgeKind::Frequents, at(0));
+
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.seen_with(&a).iter().map(|(n, c)| (n.id.as_str(), *c)).collect::<Vec<_>>(), vec![("entity:b", 2)]);
+        let regulars: Vec<&str> = graph.query().id(&garden).hop(EdgeKind::Frequents, 3).run().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(regulars, vec!["entity:a"]);
+        assert_eq!(graph.frequenters_of(&garden, 1).len(), 2);
+
+        graph.upsert_node(NodeKind::Entity, "b", HashMap::new(), at(86_400));
+        graph.prune_older_than(at(3600));
+        assert!(graph.node(&a).is_none());
+        assert_eq!((graph.node_count(), graph.edge_count()), (1, 0));
+    }
+
+    #[test]
+    fn test_analyses_become_nodes_and_edges() {
+        let entities: Vec<Entity> = (0..2).map(|i| Entity {
+            id: Uuid::from_u128(i + 1),
+            profile: None,
+            last_seen: Some(at(0)),
+            interaction_count: 3,
+        }).collect();
+        let context = EnvironmentalContext {
+            location: "driveway".to_string(),
+            ambient_conditions: vec!["dark".to_string()],
+            time_context: TimeContext::Night,
+            environment: None,
+        };
+        let profiles = vec![HashMap::from([("nervousness".to_string(), 0.7)]), HashMap::new()];
+
+        let mut graph = KnowledgeGraph::new();
+        let changes = graph.update_knowledge(&entities, &context, &profiles, at(0));
+        assert!(changes[0].contains("+3 nodes, +3 edges"));
+        let first = KnowledgeGraph::node_id(NodeKind::Entity, &Uuid::from_u128(1).to_string());
+        assert_eq!(graph.node(&first).unwrap().properties["psych.nervousness"], 0.7);
+        assert_eq!(graph.frequenters_of("zone:driveway", 1).len(), 2);
+        assert_eq!(graph.seen_with(&first).len(), 1);
+    }
+
+    #[test]
+    fn test_store_links_incidents_and_survives_reload() {
+        let dir = std::env::temp_dir().join(format!("novin_kg_{}", Uuid::new_v4()));
+        let store = KnowledgeGraphStore::persistent(KnowledgeGraphConfig::default(), dir.clone()).unwrap();
+        store.record_incident("home_1", &incident(1, "track_a", &["front", "side_gate"], 0), at(0)).unwrap();
+        store.record_incident("home_1", &incident(2, "track_b", &["front"], 30), at(30)).unwrap();
+        store.record_incident("home_1", &incident(3, "track_c", &["front"], 3600), at(3600)).unwrap();
+        store.record_incident("home_2", &incident(1, "track_a", &["garage"], 0), at(0)).unwrap();
+
+        let reloaded = KnowledgeGraphStore::persistent(KnowledgeGraphConfig::default(), dir.clone()).unwrap();
+        for s in [&store, &reloaded] {
+            let (with_a, involved, zones) = s.with_graph("home_1", |g| (
+                g.seen_with("entity:track_a").iter().map(|(n, _)| n.id.clone()).collect::<Vec<_>>(),
+                g.query().id("entity:track_a").hop(EdgeKind::InvolvedIn, 1).run().iter().map(|n| n.id.clone()).collect::<Vec<_>>(),
+                g.query().id("incident:1").hop(EdgeKind::OccurredAt, 1).run().len(),
+            )).unwrap();
+            assert_eq!(with_a, vec!["entity:track_b"]);
+            assert_eq!(involved, vec!["incident:1"]);
+            assert_eq!(zones, 2);
+            assert!(s.with_graph("home_2", |g| g.node("zone:front").is_none()).unwrap());
+        }
+
+        reloaded.prune(at(3600) + Duration::days(181));
+        assert_eq!(reloaded.with_graph("home_1", |g| g.node_count()), Some(0));
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_records_assessed_incidents() {
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let store = Arc::new(KnowledgeGraphStore::default());
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new(mock_vps_url().to_string()))
+            .with_knowledge_graph(store.clone());
+        let event = RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: "porch".to_string(),
+            timestamp: 1_700_000_000,
+            data: r#"{"motion":true}"#.into(),
+            user_id: "user_1".to_string(),
+            home_id: "home_1".to_string(),
+            image_url: None,
+            image_data: None,
+        };
+        pipeline.process_event(event, SubscriptionTier::Premium, "key").await.unwrap();
+
+        let (incidents, at_porch) = store.with_graph("home_1", |g| (
+            g.query().kind(NodeKind::Incident).run().len(),
+            g.query().id("zone:porch").hop(EdgeKind::Frequents, 1).run().len(),
+        )).unwrap();
+        assert_eq!((incidents, at_porch), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_api_returns_a_node_and_its_neighbors_for_the_callers_home() {
+        let state = app_state().await;
+        let home = format!("home_{}", Uuid::new_v4().simple());
+        state.knowledge_graph.record_incident(&home, &incident(7, "track_a", &["front"], 0), at(0)).unwrap();
+        let query = |node: &str| Query(KnowledgeGraphQuery { node: node.to_string(), edge: None, min_count: 0 });
+
+        let view = knowledge_graph(State(state.clone()), homeowner(&[home.as_str()]), Path(home.clone()), query("zone:front")).await.unwrap().0.data;
+        assert_eq!(view.node.kind, NodeKind::Zone);
+        let neighbors: Vec<&str> = view.neighbors.iter().map(|n| n.node.id.as_str()).collect();
+        assert_eq!(neighbors, vec!["entity:track_a", "incident:7"]);
+
+        let missing = knowledge_graph(State(state.clone()), homeowner(&[home.as_str()]), Path(home.clone()), query("zone:garage")).await;
+        assert_eq!(missing.err(), Some(StatusCode::NOT_FOUND));
+        let other = knowledge_graph(State(state), homeowner(&["home_2"]), Path(home), query("zone:front")).await;
+        assert_eq!(other.err(), Some(StatusCode::FORBIDDEN));
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod llm_budget_tests {
+    use crate::thinking::llm_client::{LLMSummaryRequest, LLMTokenUsage};
+    use crate::thinking::{Admission, DeferredNarrative, LlmBudget, LlmBudgetConfig, LlmPriority};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn budget() -> LlmBudget {
+        LlmBudget::new(LlmBudgetConfig { home_daily_calls: 5, critical_reserve: 0.4, ..LlmBudgetConfig::default() })
+    }
+
+    fn deferred(incident_id: u64, priority: LlmPriority, minute: i64) -> DeferredNarrative {
+        DeferredNarrative {
+            home_id: "home_1".to_string(),
+            incident_id,
+            priority,
+            request: LLMSummaryRequest {
+                decision: "Standard".to_string(),
+                location: "cam_front".to_string(),
+                dwell_time: 30.0,
+                rang_doorbell: false,
+                knocked: false,
+                threat_probability: 0.2,
+            },
+            details: String::new(),
+            queued_at: Utc.timestamp_opt(1_700_000_000, 0).unwrap() + Duration::minutes(minute),
+        }
+    }
+
+    #[test]
+    fn test_reserve_is_kept_for_critical_incidents() {
+        let budget = budget();
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        // 60% of 5 calls: three non-critical calls fit
+        for _ in 0..3 {
+            assert_eq!(budget.admit("home_1", LlmPriority::Elevated, now), Admission::Admitted);
+        }
+        assert!(matches!(budget.admit("home_1", LlmPriority::Elevated, now), Admission::Denied(_)));
+        assert_eq!(budget.admit("home_1", LlmPriority::Standard, now), Admission::Deferred);
+        assert_eq!(budget.admit("home_1", LlmPriority::Critical, now), Admission::Admitted);
+        assert_eq!(budget.admit("home_2", LlmPriority::Standard, now), Admission::Admitted);
+
+        // A new day starts with a fresh budget
+        assert_eq!(budget.admit("home_1", LlmPriority::Elevated, now + Duration::days(1)), Admission::Admitted);
+    }
+
+    #[test]
+    fn test_usage_corrects_the_estimate() {
+        let budget = budget();
+        let now = Utc::now();
+        budget.admit("home_1", LlmPriority::Critical, now);
+        budget.record("home_1", Some(&LLMTokenUsage { prompt_tokens: 900, completion_tokens: 100 }));
+        let report = budget.report(now);
+        assert_eq!(report.global.tokens, 1000);
+        assert_eq!(report.homes[0].usage.calls, 1);
+    }
+
+    #[test]
+    fn test_deferred_narratives_run_by_priority_when_budget_frees_up() {
+        let budget = budget();
+        let day1 = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        for _ in 0..3 {
+            budget.admit("home_1", LlmPriority::Standard, day1);
+        }
+        budget.defer(deferred(1, LlmPriority::Background, 0));
+        budget.defer(deferred(2, LlmPriority::Standard, 1));
+        budget.defer(deferred(2, LlmPriority::Standard, 2)); // Replaces the earlier request
+        assert!(budget.take_deferred(day1, 10).is_empty());
+        assert_eq!(budget.deferred_len(), 2);
+
+        let ready = budget.take_deferred(day1 + Duration::days(1), 1);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].incident_id, 2);
+        assert_eq!(budget.deferred_len(), 1);
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod load_shedding_tests {
+    use crate::load_shedding::{Admission, CameraPriority, LoadShedder, LoadSheddingConfig};
+    use chrono::{DateTime, Duration, Utc};
+    use std::collections::HashMap;
+
+    fn shedder() -> LoadShedder {
+        let shedder = LoadShedder::new(LoadSheddingConfig { window_s: 10, max_events_per_window: 10, resume_fraction: 0.5, sample_every: 4 });
+        shedder.set_camera_priorities("home_1", HashMap::from([
+            ("street".to_string(), CameraPriority::Low),
+            ("hallway".to_string(), CameraPriority::Protected),
+        ]));
+        shedder
+    }
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap() + Duration::milliseconds(ms)
+    }
+
+    #[test]
+    fn test_storm_samples_low_priority_cameras_into_one_incident() {
+        let shedder = shedder();
+        let admissions: Vec<Admission> = (0..30)
+            .map(|i| shedder.admit("home_1", "street", "motion=true", at(i * 100)))
+            .collect();
+
+        assert!(admissions[..10].iter().all(|a| *a == Admission::Process));
+        assert!(shedder.is_overloaded("home_1"));
+        let sampled = admissions.iter().filter(|a| **a == Admission::Sampled).count();
+        let aggregated = admissions.iter().filter(|a| **a == Admission::Aggregated).count();
+        assert_eq!(sampled, 5);
+        assert_eq!(aggregated, 15);
+
+        let incident = shedder.high_activity("home_1").unwrap();
+        assert_eq!(incident.aggregated, 15);
+        assert_eq!(incident.cameras["street"], 15);
+        assert!(incident.ended_at.is_none());
+
+        // Quiet for a window: the surge ends and the incident closes
+        assert_eq!(shedder.admit("home_1", "street", "motion=true", at(20_000)), Admission::Process);
+        assert!(!shedder.is_overloaded("home_1"));
+        assert!(shedder.high_activity("home_1").unwrap().ended_at.is_some());
+    }
+
+    #[test]
+    fn test_doorbell_and_interior_events_are_never_shed() {
+        let shedder = shedder();
+        for i in 0..20 {
+            shedder.admit("home_1", "street", "motion=true", at(i * 100));
+        }
+        assert!(shedder.is_overloaded("home_1"));
+
+        assert_eq!(shedder.admit("home_1", "hallway", "motion=true", at(2_100)), Admission::Process);
+        assert_eq!(shedder.admit("home_1", "street", "person_detected=true|doorbell=true", at(2_200)), Admission::Process);
+        assert_eq!(shedder.admit("home_1", "front_doorbell", "motion=true", at(2_300)), Admission::Process);
+        // Cameras without a configured priority are processed in full too
+        assert_eq!(shedder.admit("home_1", "driveway", "motion=true", at(2_400)), Admission::Process);
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod local_socket_tests {
+    use crate::api::auth::{AuthUser, LocalPeer};
+    use crate::api::local_socket::{self, LocalSocketConfig, LocalSocketError};
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixStream;
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| map.get(name).cloned()
+    }
+
+    #[test]
+    fn test_config_is_off_without_a_path_and_checks_peer_credentials() {
+        assert!(LocalSocketConfig::from_vars(vars(&[])).unwrap().is_none());
+        assert!(matches!(
+            LocalSocketConfig::from_vars(vars(&[("NOVIN_LOCAL_SOCKET", "/tmp/a.sock"), ("NOVIN_LOCAL_SOCKET_UIDS", "1001,x")])),
+            Err(LocalSocketError::Invalid { name: "NOVIN_LOCAL_SOCKET_UIDS", .. })
+        ));
+
+        let config = LocalSocketConfig::from_vars(vars(&[
+            ("NOVIN_LOCAL_SOCKET", "/tmp/a.sock"),
+            ("NOVIN_LOCAL_SOCKET_MODE", "0600"),
+            ("NOVIN_LOCAL_SOCKET_UIDS", "1001, 1002"),
+            ("NOVIN_LOCAL_SOCKET_GIDS", "44"),
+        ])).unwrap().unwrap();
+        assert_eq!(config.mode, 0o600);
+
+        let peer = |uid, gid| LocalPeer { uid, gid, pid: None };
+        assert!(config.authorize(&peer(0, 0), Some(500)).is_ok());
+        assert!(config.authorize(&peer(500, 500), Some(500)).is_ok());
+        assert!(config.authorize(&peer(1002, 1002), Some(500)).is_ok());
+        assert!(config.authorize(&peer(2000, 44), Some(500)).is_ok());
+        assert!(matches!(config.authorize(&peer(2000, 2000), Some(500)), Err(LocalSocketError::Denied { uid: 2000, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_socket_requests_authenticate_as_the_local_peer() {
+        let path = std::env::temp_dir().join(format!("novin_{}.sock", uuid::Uuid::new_v4()));
+        let config = LocalSocketConfig { path: path.clone(), ..LocalSocketConfig::default() };
+        let listener = local_socket::listen(&config).unwrap();
+        let router = Router::new().route("/whoami", get(|user: AuthUser| async move { user.user_id }));
+        tokio::spawn(local_socket::serve(listener, config, router));
+
+        // The test runs as the server's own user, which is always admitted
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream.write_all(b"GET /whoami HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let uid = std::fs::metadata("/proc/self").map(|m| std::os::unix::fs::MetadataExt::uid(&m)).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with(&format!("local:{}", uid)));
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod metering_tests {
+    use crate::metering::{export_csv, BillableUnit, UsageMeter, UsageWindow};
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn test_daily_rollups_per_account() {
+        let meter = UsageMeter::new();
+        let day1 = Utc.with_ymd_and_hms(2024, 3, 1, 23, 59, 0).unwrap();
+        let day2 = Utc.with_ymd_and_hms(2024, 3, 2, 0, 1, 0).unwrap();
+
+        meter.record_at("acct_a", BillableUnit::EventsProcessed, 1, day1);
+        meter.record_at("acct_a", BillableUnit::EventsProcessed, 1, day1);
+        meter.record_at("acct_a", BillableUnit::ImageBytes, 1_500_000_000, day1);
+        meter.record_at("acct_a", BillableUnit::VpsCalls, 1, day2);
+        meter.record_at("acct_b", BillableUnit::LlmTokens, 420, day2);
+
+        let d1 = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let d2 = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+
+        let a = meter.rollups(Some("acct_a"), d1, d2);
+        assert_eq!(a.len(), 2);
+        assert_eq!(a[0].events_processed, 2);
+        assert!((a[0].image_gb() - 1.5).abs() < 1e-9);
+        assert_eq!(a[1].vps_calls, 1);
+
+        assert_eq!(meter.rollups(None, d2, d2).len(), 2);
+        assert_eq!(meter.total("acct_b", d1, d2).llm_tokens, 420);
+
+        let csv = export_csv(&a);
+        assert!(csv.starts_with("account_id,date,"));
+        assert!(csv.contains("acct_a,2024-03-01,2,0,1.500000,0"));
+    }
+
+    #[test]
+    fn test_api_usage_windows() {
+        let meter = UsageMeter::new();
+        let at = |h, m| Utc.with_ymd_and_hms(2024, 3, 1, h, m, 0).unwrap();
+
+        meter.record_api_call("acct_a", 200, at(9, 5));
+        meter.record_api_call("acct_a", 404, at(9, 40));
+        meter.record_api_call("acct_a", 429, at(10, 1));
+        meter.record_api_call("acct_b", 500, at(10, 2));
+        meter.record_at("acct_a", BillableUnit::ImageBytes, 2048, at(10, 30));
+
+        let hourly = meter.api_usage("acct_a", at(0, 0), at(23, 0), UsageWindow::Hour);
+        assert_eq!(hourly.windows.len(), 2);
+        assert_eq!((hourly.windows[0].requests, hourly.windows[0].errors), (2, 1));
+        assert_eq!((hourly.windows[1].rate_limited, hourly.windows[1].image_bytes), (1, 2048));
+
+        let daily = meter.api_usage("acct_a", at(0, 0), at(23, 0), UsageWindow::Day);
+        assert_eq!(daily.windows.len(), 1);
+        assert_eq!(daily.total.requests, 3);
+        assert_eq!(daily.total.errors, 1);
+    }
+}
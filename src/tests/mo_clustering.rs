@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod mo_clustering_tests {
+    use crate::tests::support;
+    use crate::thinking::{AlertDecision, Evidence, Incident, MoClusterIndex};
+    use chrono::{TimeZone, Utc};
+
+    fn incident(id: u64, day: u32, hour: u32, cam: &str, dwell_s: f64, rang: bool, probability: f64) -> Incident {
+        let ts = Utc.with_ymd_and_hms(2024, 5, day, hour, 10, 0).unwrap().timestamp() as f64;
+        let mut inc = Incident::new(id, ts, format!("track_{}", id));
+        inc.add_event(support::event(ts).cam(cam).track(&format!("track_{}", id)).doorbell(rang).dwell(dwell_s)
+            .evidence(Evidence { llr_time: 0.5, llr_entry: 0.3, llr_behavior: 0.6, llr_identity: 0.4, ..Default::default() })
+            .build());
+        inc.record_assessment(1.0, probability, AlertDecision::Standard, "");
+        inc
+    }
+
+    #[tokio::test]
+    async fn test_repeat_prowler_clusters_and_boosts_prior() {
+        let index = MoClusterIndex::default();
+        // Same 2am side-gate lurker on three different nights
+        for (id, day) in [(1, 1), (2, 8), (3, 15)] {
+            index.record("home_1", &incident(id, day, 2, "side_gate", 90.0, false, 0.6)).await;
+        }
+        // Afternoon deliveries at the front door are a separate, benign pattern
+        for (id, day) in [(4, 2), (5, 9), (6, 16)] {
+            index.record("home_1", &incident(id, day, 14, "front_door", 20.0, true, 0.05)).await;
+        }
+
+        let now = Utc.with_ymd_and_hms(2024, 5, 20, 0, 0, 0).unwrap();
+        assert_eq!(index.recluster(now).await, 2);
+
+        let clusters = index.clusters("home_1").await;
+        let prowler = clusters.iter().find(|c| c.suspicious).unwrap();
+        assert_eq!(prowler.incident_ids, vec![1, 2, 3]);
+        assert_eq!(prowler.distinct_days, 3);
+
+        let returning = index.match_incident("home_1", &incident(7, 19, 2, "side_gate", 80.0, false, 0.2)).await.unwrap();
+        assert_eq!(returning.cluster_id, prowler.cluster_id);
+        assert!(returning.prior_boost > 0.0);
+
+        // Matching the benign cluster never boosts
+        assert!(index.match_incident("home_1", &incident(8, 19, 14, "front_door", 20.0, true, 0.05)).await.is_none());
+    }
+}
@@ -1 +1,104 @@
 pub mod person_detection;
+pub mod idempotency;
+pub mod webhook_signing;
+pub mod calendar_priors;
+pub mod environment_enrichment;
+pub mod federation;
+pub mod siem_format;
+pub mod auth_scopes;
+pub mod schema_versioning;
+pub mod embeddings;
+pub mod image_download;
+pub mod image_transcode;
+pub mod feature_gate;
+pub mod metering;
+pub mod config_validation;
+pub mod mo_clustering;
+pub mod visitor_tokens;
+pub mod notification_routing;
+pub mod what_if;
+pub mod weight_learning;
+pub mod follow_up;
+pub mod incident_lifecycle;
+pub mod sensor_reliability;
+pub mod edge_inference;
+pub mod adversarial_handoff;
+pub mod explanation;
+pub mod overnight_storage;
+pub mod overnight_window;
+pub mod vacation;
+pub mod household;
+pub mod api_pagination;
+pub mod openapi;
+pub mod websocket_commands;
+pub mod camera_registry;
+pub mod device_signing;
+pub mod backup;
+pub mod attack_graph;
+pub mod escalation_survival;
+pub mod federated_learning;
+pub mod chaos;
+pub mod probability_properties;
+pub mod sanitization;
+pub mod webhook_dead_letters;
+pub mod vps_cache;
+pub mod incident_editing;
+pub mod onboarding;
+pub mod prior_model;
+pub mod redaction;
+pub mod tracker;
+pub mod escalation_rules;
+pub mod tamper;
+pub mod llm_budget;
+pub mod voi_calibration;
+pub mod red_team;
+pub mod evidence_channels;
+pub mod pipeline_stages;
+pub mod zone_graph;
+pub mod camera_dedup;
+pub mod notification_templates;
+pub mod i18n;
+pub mod uncertainty;
+pub mod entity_trust;
+pub mod encryption;
+pub mod api_server_config;
+pub mod sms;
+pub mod email;
+pub mod processor_sharding;
+pub mod ingest;
+pub mod bench_report;
+pub mod load_shedding;
+pub mod two_phase_alerts;
+pub mod camera_health;
+pub mod dataset_export;
+pub mod annotations;
+pub mod guest_access;
+pub mod self_test;
+pub mod watchdog;
+pub mod adaptive_thresholds;
+pub mod anti_spoofing;
+pub mod snapshot_priority;
+pub mod probability_series;
+pub mod automations;
+pub mod client;
+pub mod local_socket;
+pub mod activity_baseline;
+pub mod overnight_entities;
+pub mod prediction_scoring;
+pub mod vms_bookmarks;
+pub mod llm_guard;
+pub mod clock;
+pub mod evidence_saturation;
+pub mod central_station;
+pub mod threat_vector;
+#[cfg(test)]
+pub mod support;
+pub mod webhook_registration;
+pub mod evidence_export_auth;
+pub mod event_submission;
+pub mod counter_surveillance;
+pub mod knowledge_graph;
+pub mod siem_export;
+pub mod vps_health;
+pub mod vps_pool;
+pub mod prediction_fusion;
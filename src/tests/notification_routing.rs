@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod notification_routing_tests {
+    use crate::delivery::{CooldownVerdict, Notification, NotificationPreferences, NotificationRouter, NotificationSeverity, QuietHours, RouteDecision};
+    use crate::overnight::DeliveryChannel;
+    use chrono::{Duration, NaiveTime, TimeZone, Utc};
+
+    fn notification(severity: NotificationSeverity) -> Notification {
+        Notification {
+            home_id: "home_1".to_string(),
+            severity,
+            title: "Person at front door".to_string(),
+            body: String::new(),
+            created_at: Utc::now(),
+            incident_id: None,
+            zone: None,
+            probability: None,
+            entity: None,
+            entity_status: None,
+            thumbnail_url: None,
+            alert: None,
+        }
+    }
+
+    #[test]
+    fn test_quiet_hours_hold_non_critical_for_digest() {
+        let router = NotificationRouter::new();
+        router.set_preferences("home_1", None, NotificationPreferences {
+            quiet_hours: Some(QuietHours {
+                start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+                timezone: "America/New_York".to_string(),
+                allow_critical: true,
+            }),
+            ..Default::default()
+        });
+
+        // 03:00 UTC is 23:00 in New York during DST
+        let night = Utc.with_ymd_and_hms(2024, 6, 1, 3, 0, 0).unwrap();
+        let day = Utc.with_ymd_and_hms(2024, 6, 1, 16, 0, 0).unwrap();
+        let push = DeliveryChannel::Push;
+
+        assert!(matches!(router.decide(&notification(NotificationSeverity::Standard), Some("alice"), &push, night), RouteDecision::Digest(_)));
+        assert_eq!(router.decide(&notification(NotificationSeverity::Critical), Some("alice"), &push, night), RouteDecision::Deliver);
+        assert_eq!(router.decide(&notification(NotificationSeverity::Standard), Some("alice"), &push, day), RouteDecision::Deliver);
+
+        let recipients = vec![(Some("alice".to_string()), vec![DeliveryChannel::Push, DeliveryChannel::Email])];
+        router.route(&notification(NotificationSeverity::Standard), &recipients, night);
+        assert_eq!(router.drain_digest("home_1", Some("alice")).len(), 1);
+        assert!(router.drain_digest("home_1", Some("alice")).is_empty());
+    }
+
+    #[test]
+    fn test_user_override_and_channel_minimum() {
+        let router = NotificationRouter::new();
+        let mut prefs = NotificationPreferences::default();
+        prefs.min_severity.insert(DeliveryChannel::SMS, NotificationSeverity::Critical);
+        router.set_preferences("home_1", Some("bob"), prefs);
+
+        let elevated = notification(NotificationSeverity::Elevated);
+        let now = Utc::now();
+        assert!(matches!(router.decide(&elevated, Some("bob"), &DeliveryChannel::SMS, now), RouteDecision::Drop(_)));
+        assert_eq!(router.decide(&elevated, Some("bob"), &DeliveryChannel::Push, now), RouteDecision::Deliver);
+        // Other users fall back to the home defaults
+        assert_eq!(router.decide(&elevated, Some("carol"), &DeliveryChannel::SMS, now), RouteDecision::Deliver);
+    }
+
+    #[test]
+    fn test_cooldown_suppresses_repeats_until_threat_rises() {
+        let router = NotificationRouter::new();
+        let t0 = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let alert = |severity, probability| Notification {
+            incident_id: Some(7),
+            zone: Some("front_door".to_string()),
+            probability: Some(probability),
+            ..notification(severity)
+        };
+
+        assert_eq!(router.check_cooldown(&alert(NotificationSeverity::Standard, 0.20), t0), CooldownVerdict::Send);
+        assert_eq!(router.check_cooldown(&alert(NotificationSeverity::Standard, 0.22), t0 + Duration::seconds(30)), CooldownVerdict::Suppress);
+        assert_eq!(router.check_cooldown(&alert(NotificationSeverity::Standard, 0.40), t0 + Duration::seconds(60)), CooldownVerdict::Escalate);
+        assert_eq!(router.check_cooldown(&alert(NotificationSeverity::Elevated, 0.41), t0 + Duration::seconds(90)), CooldownVerdict::Escalate);
+        assert_eq!(router.check_cooldown(&alert(NotificationSeverity::Elevated, 0.41), t0 + Duration::minutes(10)), CooldownVerdict::Send);
+        assert_eq!(router.suppressed_count("home_1", 7), 1);
+
+        let outcome = router.route(&alert(NotificationSeverity::Elevated, 0.41), &[(None, vec![DeliveryChannel::Push])], t0 + Duration::minutes(11));
+        assert_eq!(outcome.cooldown, CooldownVerdict::Suppress);
+        assert!(matches!(outcome.deliveries[0].decision, RouteDecision::Drop(_)));
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod notification_templates_tests {
+    use crate::delivery::{Notification, NotificationRouter, NotificationSeverity, NotificationTemplate, RouteDecision, TemplateStore};
+    use crate::overnight::DeliveryChannel;
+    use chrono::Utc;
+
+    fn notification() -> Notification {
+        Notification {
+            home_id: "home_1".to_string(),
+            severity: NotificationSeverity::Elevated,
+            title: "Elevated alert on porch".to_string(),
+            body: "Person lingering at the porch.".to_string(),
+            created_at: Utc::now(),
+            incident_id: Some(4),
+            zone: Some("porch".to_string()),
+            probability: Some(0.634),
+            entity: Some("track_9".to_string()),
+            entity_status: None,
+            thumbnail_url: Some("https://cdn.example/snap.jpg".to_string()),
+            alert: None,
+        }
+    }
+
+    fn template(language: &str, channel: Option<DeliveryChannel>, title: &str, body: &str) -> NotificationTemplate {
+        NotificationTemplate { language: language.to_string(), channel, title: title.to_string(), body: body.to_string() }
+    }
+
+    #[test]
+    fn test_most_specific_template_wins_with_fallbacks() {
+        let store = TemplateStore::default();
+        assert_eq!(store.render(&notification(), &DeliveryChannel::Push), ("Elevated alert on porch".to_string(), "Person lingering at the porch.".to_string()));
+
+        store.set(None, template("en", None, "Alert: {{ zone }}", "{{ summary }} ({{ probability_pct }}%)")).unwrap();
+        store.set(Some("home_1"), template("de", Some(DeliveryChannel::SMS), "Alarm: {{ zone }}", "{{ entity }} {{ thumbnail_url }}")).unwrap();
+
+        // English default until the home picks German, then German only where one exists
+        assert_eq!(store.render(&notification(), &DeliveryChannel::SMS).0, "Alert: porch");
+        store.set_language("home_1", "de").unwrap();
+        assert_eq!(store.render(&notification(), &DeliveryChannel::SMS), ("Alarm: porch".to_string(), "track_9 https://cdn.example/snap.jpg".to_string()));
+        assert_eq!(store.render(&notification(), &DeliveryChannel::Push).1, "Person lingering at the porch. (63%)");
+
+        assert!(store.set(None, template("en", None, "{% if %}", "")).is_err());
+        assert!(store.set_language("home_1", "de DE").is_err());
+    }
+
+    #[test]
+    fn test_router_renders_text_per_channel() {
+        let router = NotificationRouter::new();
+        router.templates().set(None, template("en", Some(DeliveryChannel::SMS), "{{ severity }}", "{{ zone }}")).unwrap();
+        let outcome = router.route(&notification(), &[(Some("alice".to_string()), vec![DeliveryChannel::Push, DeliveryChannel::SMS])], Utc::now());
+        assert!(outcome.deliveries.iter().all(|d| d.decision == RouteDecision::Deliver));
+        assert_eq!(outcome.deliveries[0].title, "Elevated alert on porch");
+        assert_eq!((outcome.deliveries[1].title.as_str(), outcome.deliveries[1].body.as_str()), ("elevated", "porch"));
+    }
+}
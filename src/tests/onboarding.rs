@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod onboarding_tests {
+    use crate::environment::HolidayCalendar;
+    use crate::onboarding::{generate, CameraSetup, HomeConfigStore, HouseholdInfo, OnboardingError, OnboardingRequest, SensitivityProfile, ZoneKind};
+    use crate::tests::support;
+    use crate::thinking::{Evidence, ThinkingAIConfig, ThinkingAIProcessor};
+    use chrono::{NaiveDate, NaiveTime};
+
+    fn camera(id: &str, zone: &str, kind: ZoneKind) -> CameraSetup {
+        CameraSetup { camera_id: id.to_string(), zone: zone.to_string(), kind, overlaps_with: Vec::new() }
+    }
+
+    fn request(profile: SensitivityProfile) -> OnboardingRequest {
+        OnboardingRequest {
+            timezone: "Europe/London".to_string(),
+            latitude: Some(51.5),
+            longitude: Some(-0.12),
+            holidays: HolidayCalendar::GB,
+            cameras: vec![
+                camera("cam_front", "Front door", ZoneKind::FrontDoor),
+                camera("cam_back", "Back garden", ZoneKind::Yard),
+                camera("cam_back_2", "Back garden", ZoneKind::Yard),
+                camera("cam_lounge", "Lounge", ZoneKind::Interior),
+            ],
+            household: HouseholdInfo { residents: 3, has_pets: true, has_children: false },
+            profile,
+            uncertainty: None,
+            share_with_neighbors: false,
+        }
+    }
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_generates_zones_window_and_threshold() {
+        let winter = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let config = generate("home_1", &request(SensitivityProfile::Vigilant), winter).unwrap();
+
+        assert_eq!(config.zones.len(), 3);
+        let garden = config.zones.iter().find(|z| z.zone == "Back garden").unwrap();
+        assert_eq!(garden.cameras, vec!["cam_back", "cam_back_2"]);
+        let lounge = config.zones.iter().find(|z| z.zone == "Lounge").unwrap();
+        assert!(lounge.prior_logit < ZoneKind::Interior.default_prior_logit()); // Pets soften interior motion
+        assert_eq!(config.alert_threshold_logit, SensitivityProfile::Vigilant.alert_threshold_logit());
+
+        // London sunset in January is ~16:20, so the window opens at the 21:00 floor
+        assert_eq!(config.overnight.review_start_time, hm(21, 0));
+        assert!(config.overnight.review_end_time >= hm(5, 0) && config.overnight.review_end_time <= hm(7, 30));
+        assert_eq!(config.overnight.timezone, "Europe/London");
+    }
+
+    #[test]
+    fn test_summer_window_opens_later() {
+        let summer = NaiveDate::from_ymd_opt(2026, 6, 21).unwrap();
+        let config = generate("home_1", &request(SensitivityProfile::Balanced), summer).unwrap();
+        // Sunset ~21:20 BST, so three hours later is past midnight and capped
+        assert_eq!(config.overnight.review_start_time, hm(23, 59));
+    }
+
+    #[test]
+    fn test_invalid_requests_rejected() {
+        let mut duplicate = request(SensitivityProfile::Balanced);
+        duplicate.cameras.push(camera("cam_front", "Porch", ZoneKind::FrontDoor));
+        assert!(matches!(generate("home_1", &duplicate, NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()), Err(OnboardingError::Invalid(_))));
+
+        let mut bad_tz = request(SensitivityProfile::Balanced);
+        bad_tz.timezone = "Mars/Olympus".to_string();
+        assert!(matches!(generate("home_1", &bad_tz, NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()), Err(OnboardingError::Invalid(_))));
+
+        let mut unlocated = request(SensitivityProfile::Balanced);
+        unlocated.share_with_neighbors = true;
+        unlocated.latitude = None;
+        assert!(matches!(generate("home_1", &unlocated, NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()), Err(OnboardingError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_store_persists_and_reloads() {
+        let dir = std::env::temp_dir().join(format!("home_configs_{}", uuid::Uuid::new_v4()));
+        let config = generate("home/1", &request(SensitivityProfile::Conservative), NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()).unwrap();
+
+        HomeConfigStore::persistent(dir.clone()).unwrap().save(config).unwrap();
+        let reloaded = HomeConfigStore::persistent(dir.clone()).unwrap();
+        let restored = reloaded.get("home/1").expect("config reloaded");
+        assert_eq!(restored.profile, SensitivityProfile::Conservative);
+        assert_eq!(restored.zones.len(), 3);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_zone_priors_raise_risk_for_riskier_zones() {
+        let event = |cam: &str, track: &str| support::event(1000.0).cam(cam).track(track).dwell(30.0)
+            .evidence(Evidence { llr_time: 0.5, llr_entry: 0.5, llr_behavior: 0.5, ..Default::default() })
+            .build();
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        processor.set_zone_priors("home_1", [("cam_back".to_string(), 0.6), ("cam_street".to_string(), -0.8)].into_iter().collect());
+
+        let back = processor.process_event("home_1", event("cam_back", "a")).unwrap();
+        let street = processor.process_event("home_1", event("cam_street", "b")).unwrap();
+        assert!(back.calibrated_probability > street.calibrated_probability);
+    }
+}
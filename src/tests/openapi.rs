@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod openapi_tests {
+    use crate::api::openapi::ApiDoc;
+    use utoipa::OpenApi;
+
+    #[test]
+    fn test_spec_documents_routes_and_schemas() {
+        let spec = ApiDoc::openapi();
+        for path in ["/api/homes/{home_id}/incidents", "/api/homes/{home_id}/events", "/api/homes/{home_id}/residents", "/api/monitoring/incidents"] {
+            assert!(spec.paths.paths.contains_key(path), "missing {}", path);
+        }
+        let components = spec.components.expect("components");
+        for schema in ["IncidentPageResponse", "IncidentRow", "Resident", "NotificationSeverity"] {
+            assert!(components.schemas.contains_key(schema), "missing schema {}", schema);
+        }
+        assert!(components.security_schemes.contains_key("bearer_auth"));
+    }
+
+    #[test]
+    fn test_spec_serializes_to_json() {
+        let json = ApiDoc::openapi().to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(value["paths"]["/api/homes/{home_id}/incidents"]["get"]["parameters"].as_array().unwrap().len() > 1);
+    }
+}
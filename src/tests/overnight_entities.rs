@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod overnight_entities_tests {
+    use crate::clock::system_clock;
+    use crate::overnight::{OvernightEventAnalysis, OvernightReviewManager, OvernightStorage, OvernightStorageFactory, SqliteOvernightStorage};
+    use crate::thinking::AlertDecision;
+    use chrono::{TimeZone, Utc};
+    use sqlx::SqlitePool;
+    use uuid::Uuid;
+
+    fn analysis(minute: u32, camera: &str, entity_id: Option<Uuid>, level: AlertDecision) -> OvernightEventAnalysis {
+        OvernightEventAnalysis {
+            event_id: Uuid::new_v4(),
+            home_id: "home_1".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 2, 2, minute, 0).unwrap(),
+            analysis_summary: "Person detected".to_string(),
+            suppressed_alert_level: Some(level),
+            camera: Some(camera.to_string()),
+            entity_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summary_reports_one_person_behind_linked_events() {
+        let manager = OvernightReviewManager::new(OvernightStorageFactory::create_in_memory(), system_clock());
+        let prowler = Uuid::new_v4();
+        let cameras = ["cam_gate", "cam_side", "cam_back", "cam_back", "cam_side", "cam_back"];
+        for (i, camera) in cameras.iter().enumerate() {
+            let level = if i == 3 { AlertDecision::Critical } else { AlertDecision::Standard };
+            manager.store_overnight_event(analysis(10 + 3 * i as u32, camera, Some(prowler), level)).await.unwrap();
+        }
+        manager.store_overnight_event(analysis(40, "cam_gate", Some(Uuid::new_v4()), AlertDecision::Standard)).await.unwrap();
+        manager.store_overnight_event(analysis(45, "cam_gate", None, AlertDecision::Elevated)).await.unwrap();
+
+        let summary = manager.generate_morning_summary("home_1", "en").await.unwrap();
+        assert_eq!(summary.event_count, 8);
+        assert_eq!(summary.entities.len(), 1);
+        let entity = &summary.entities[0];
+        assert_eq!(entity.entity_id, prowler);
+        assert_eq!(entity.event_count, 6);
+        assert_eq!(entity.cameras, vec!["cam_back", "cam_gate", "cam_side"]);
+        assert_eq!(entity.narrative, "One unknown person triggered 6 events across 3 cameras between 2:10am and 2:25am");
+        assert!(summary.narrative.ends_with(&entity.narrative));
+
+        // The prowler's six events collapse into one highlight, at their most severe level
+        assert_eq!(summary.highlights.len(), 3);
+        assert_eq!(summary.highlights[0].level, AlertDecision::Critical);
+        assert_eq!(summary.highlights[0].entity_id, Some(prowler));
+        assert_eq!(summary.highlights[0].summary, entity.narrative);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_tables_from_before_entity_linking_are_upgraded() {
+        let dir = std::env::temp_dir().join(format!("overnight_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", dir.join("overnight.db").display())).await.unwrap();
+        sqlx::query(include_str!("../api/migrations/007_overnight_events.sql")).execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO overnight_events (event_id, home_id, timestamp, analysis_summary) VALUES (?, 'home_1', 0, 'Older event')")
+            .bind(Uuid::new_v4().to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let storage = SqliteOvernightStorage::new(pool);
+        let entity = Uuid::new_v4();
+        storage.store_event(&analysis(10, "cam_gate", Some(entity), AlertDecision::Standard)).await.unwrap();
+        let pending = storage.pending_events("home_1").await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!((pending[0].camera.as_deref(), pending[0].entity_id), (None, None));
+        assert_eq!((pending[1].camera.as_deref(), pending[1].entity_id), (Some("cam_gate"), Some(entity)));
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
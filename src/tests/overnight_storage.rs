@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod overnight_storage_tests {
+    use crate::clock::system_clock;
+    use crate::overnight::{
+        OvernightConfig, OvernightEventAnalysis, OvernightReviewManager, OvernightStorage, OvernightStorageBackend,
+        OvernightStorageFactory,
+    };
+    use crate::thinking::AlertDecision;
+    use chrono::{TimeZone, Utc};
+
+    fn analysis(home_id: &str, minute: u32, level: Option<AlertDecision>) -> OvernightEventAnalysis {
+        OvernightEventAnalysis {
+            event_id: uuid::Uuid::new_v4(),
+            home_id: home_id.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 3, 2, 2, minute, 0).unwrap(),
+            analysis_summary: "Person at side gate".to_string(),
+            suppressed_alert_level: level,
+            camera: None,
+            entity_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_events_survive_new_manager() {
+        let dir = std::env::temp_dir().join(format!("overnight_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = OvernightConfig {
+            home_id: "home_1".to_string(),
+            storage: OvernightStorageBackend::Sqlite {
+                database_url: format!("sqlite://{}?mode=rwc", dir.join("overnight.db").display()),
+            },
+            ..OvernightConfig::default()
+        };
+        let storage = OvernightStorageFactory::create(&config).await.unwrap();
+        let manager = OvernightReviewManager::new(storage, system_clock());
+        manager.store_overnight_event(analysis("home_1", 10, Some(AlertDecision::Elevated))).await.unwrap();
+        manager.store_overnight_event(analysis("home_1", 5, Some(AlertDecision::Ignore))).await.unwrap();
+        manager.store_overnight_event(analysis("home_2", 7, None)).await.unwrap();
+        drop(manager);
+
+        // Simulated restart: a fresh connection sees the same events
+        let storage = OvernightStorageFactory::create(&config).await.unwrap();
+        let pending = storage.pending_events("home_1").await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert!(pending[0].timestamp < pending[1].timestamp);
+        assert_eq!(pending[1].suppressed_alert_level, Some(AlertDecision::Elevated));
+
+        let manager = OvernightReviewManager::new(storage.clone(), system_clock());
+        let summary = manager.generate_morning_summary("home_1", "en").await.unwrap();
+        assert_eq!(summary.event_count, 2);
+        assert!(summary.requires_attention);
+
+        manager.mark_summary_delivered(&summary).await.unwrap();
+        assert!(storage.pending_events("home_1").await.unwrap().is_empty());
+        assert_eq!(storage.pending_events("home_2").await.unwrap().len(), 1);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod overnight_window_tests {
+    use crate::clock::system_clock;
+    use crate::overnight::window::{next_local_time, resolve_local};
+    use crate::overnight::{OvernightConfig, OvernightReviewManager, OvernightStorageFactory, ReviewWindow};
+    use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn new_york_config() -> OvernightConfig {
+        OvernightConfig {
+            home_id: "home_1".to_string(),
+            timezone: "America/New_York".to_string(),
+            ..OvernightConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_window_across_spring_forward() {
+        let window = ReviewWindow::from_config(&new_york_config()).unwrap();
+        let (start, end) = window.night_starting(NaiveDate::from_ymd_opt(2024, 3, 9).unwrap());
+        assert_eq!(start, utc(2024, 3, 10, 3, 0)); // 22:00 EST
+        assert_eq!(end, utc(2024, 3, 10, 10, 0));  // 06:00 EDT
+        assert_eq!((end - start).num_hours(), 7);
+
+        assert!(window.contains(utc(2024, 3, 10, 9, 30)));  // 05:30 EDT
+        assert!(!window.contains(utc(2024, 3, 10, 10, 30))); // 06:30 EDT, a fixed offset would say 05:30
+    }
+
+    #[test]
+    fn test_window_across_fall_back() {
+        let window = ReviewWindow::from_config(&new_york_config()).unwrap();
+        let (start, end) = window.night_starting(NaiveDate::from_ymd_opt(2024, 11, 2).unwrap());
+        assert_eq!((end - start).num_hours(), 9);
+
+        assert!(window.contains(utc(2024, 11, 3, 10, 30)));  // 05:30 EST
+        assert!(!window.contains(utc(2024, 11, 3, 11, 30))); // 06:30 EST
+        // The hour that happens twice is inside the window both times
+        assert!(window.contains(utc(2024, 11, 3, 5, 30)));
+        assert!(window.contains(utc(2024, 11, 3, 6, 30)));
+    }
+
+    #[test]
+    fn test_daytime_window_and_midnight_crossing() {
+        let mut config = new_york_config();
+        config.timezone = "Europe/London".to_string();
+        config.review_start_time = NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+        config.review_end_time = NaiveTime::from_hms_opt(15, 0, 0).unwrap();
+        let window = ReviewWindow::from_config(&config).unwrap();
+        assert!(window.contains(utc(2024, 7, 1, 12, 30))); // 13:30 BST
+        assert!(!window.contains(utc(2024, 7, 1, 14, 30)));
+
+        let night = ReviewWindow::from_config(&new_york_config()).unwrap();
+        assert!(night.contains(utc(2024, 1, 16, 4, 59)));  // 23:59 EST
+        assert!(night.contains(utc(2024, 1, 16, 5, 1)));   // 00:01 EST next day
+        assert!(!night.contains(utc(2024, 1, 15, 20, 0))); // 15:00 EST
+    }
+
+    #[test]
+    fn test_gaps_and_overlaps_resolve() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let gap = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        assert_eq!(resolve_local(tz, gap), utc(2024, 3, 10, 7, 30)); // 03:30 EDT
+        let overlap = NaiveDate::from_ymd_opt(2024, 11, 3).unwrap().and_hms_opt(1, 30, 0).unwrap();
+        assert_eq!(resolve_local(tz, overlap), utc(2024, 11, 3, 5, 30)); // First, EDT
+
+        // A delivery time inside the gap still happens that day
+        let delivery = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        assert_eq!(next_local_time(tz, delivery, utc(2024, 3, 10, 5, 0)), utc(2024, 3, 10, 7, 30));
+    }
+
+    #[tokio::test]
+    async fn test_manager_uses_home_timezone() {
+        let manager = OvernightReviewManager::new(OvernightStorageFactory::create_in_memory(), system_clock());
+        manager.update_config(new_york_config()).await.unwrap();
+
+        assert!(manager.is_in_review_period("home_1", utc(2024, 3, 10, 9, 30)).await.unwrap());
+        assert!(!manager.is_in_review_period("home_1", utc(2024, 3, 10, 10, 30)).await.unwrap());
+
+        let next = manager.next_delivery("home_1", utc(2024, 3, 10, 3, 0)).await.unwrap();
+        assert_eq!(next, utc(2024, 3, 10, 11, 0)); // 07:00 EDT
+        let next = OvernightReviewManager::calculate_next_delivery_static(&new_york_config(), utc(2024, 3, 9, 13, 0)).unwrap();
+        assert_eq!(next, utc(2024, 3, 10, 11, 0)); // 08:00 EST on the 9th, so the next one is after the DST change
+
+        let mut bad = new_york_config();
+        bad.timezone = "Mars/Olympus".to_string();
+        assert!(manager.update_config(bad).await.is_err());
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod pipeline_stages_tests {
+    use crate::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier};
+    use crate::pipeline_stages::{MiddlewareError, MiddlewareRegistry, PipelineRun, Stage, StageControl, StageMiddleware};
+    use crate::vps_client::VpsApiClient;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    // Drops events from cameras the home may not record
+    struct RestrictedCameras;
+
+    #[async_trait]
+    impl StageMiddleware for RestrictedCameras {
+        fn name(&self) -> &str {
+            "restricted_cameras"
+        }
+
+        async fn before(&self, stage: Stage, run: &mut PipelineRun) -> StageControl {
+            if stage == Stage::Sanitize && run.event.sensor_id == "neighbor_facing" {
+                return StageControl::Halt("camera faces a neighbor's property".to_string());
+            }
+            StageControl::Continue
+        }
+    }
+
+    #[test]
+    fn test_registry_builds_named_middleware_in_order() {
+        let mut registry = MiddlewareRegistry::default();
+        registry.register("restricted_cameras", || Arc::new(RestrictedCameras)).unwrap();
+        assert!(matches!(registry.register("stage_timing", || Arc::new(RestrictedCameras)), Err(MiddlewareError::Duplicate(_))));
+
+        let built = registry.build(&["restricted_cameras".to_string(), "stage_timing".to_string()]).unwrap();
+        let names: Vec<&str> = built.iter().map(|m| m.name()).collect();
+        assert_eq!(names, vec!["restricted_cameras", "stage_timing"]);
+        assert!(matches!(registry.build(&["missing".to_string()]), Err(MiddlewareError::Unknown(_))));
+        assert_eq!(Stage::ALL.first(), Some(&Stage::Sanitize));
+        assert_eq!(Stage::ALL.last(), Some(&Stage::Notify));
+    }
+
+    #[tokio::test]
+    async fn test_halting_middleware_records_event_as_handled() {
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new("http://localhost:0".to_string()))
+            .with_middleware(Arc::new(RestrictedCameras));
+        let event = RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: "neighbor_facing".to_string(),
+            timestamp: 1_700_000_000,
+            data: r#"{"motion":true}"#.into(),
+            user_id: "user_1".to_string(),
+            home_id: "home_1".to_string(),
+            image_url: None,
+            image_data: None,
+        };
+
+        let processed = pipeline.process_event(event, SubscriptionTier::Premium, "key").await.unwrap();
+        assert_eq!(processed.status, "halted");
+        assert!(processed.result_summary.contains("restricted_cameras"));
+        assert!(processed.thinking_ai_analysis.is_none());
+    }
+}
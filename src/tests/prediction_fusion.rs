@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod prediction_fusion_tests {
+    use crate::core::ThreatVector;
+    use crate::prediction::{PredictionFusionLayer, PredictorSource, ThreatPrediction};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn prediction(threat_level: f64, distribution: &[(ThreatVector, f64)]) -> ThreatPrediction {
+        ThreatPrediction {
+            threat_level,
+            threat_types: distribution.iter().map(|(v, _)| *v).collect(),
+            probability_distribution: distribution.iter().copied().collect::<HashMap<_, _>>(),
+            causal_factors: Vec::new(),
+            intervention_points: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_disagreement_widens_the_interval() {
+        let fusion = PredictionFusionLayer::new();
+        let horizons = [Duration::from_secs(300)];
+        let agree = [prediction(0.5, &[]), prediction(0.5, &[])];
+        let split = [prediction(0.1, &[]), prediction(0.9, &[])];
+
+        let agreed = fusion.fuse(&[(PredictorSource::Temporal, &agree[..1]), (PredictorSource::Causal, &agree[1..])], &horizons);
+        let disputed = fusion.fuse(&[(PredictorSource::Temporal, &split[..1]), (PredictorSource::Causal, &split[1..])], &horizons);
+
+        // Same mean under uniform weights, but only the split forecast carries disagreement
+        assert!((agreed[0].threat_level - 0.5).abs() < 1e-9);
+        assert!((disputed[0].threat_level - 0.5).abs() < 1e-9);
+        assert_eq!(agreed[0].disagreement, 0.0);
+        assert!((disputed[0].disagreement - 0.4).abs() < 1e-9);
+
+        let width = |f: &crate::prediction::FusedHorizonPrediction| f.confidence_interval.1 - f.confidence_interval.0;
+        assert!(width(&disputed[0]) > width(&agreed[0]));
+        assert!(disputed[0].confidence < agreed[0].confidence);
+        assert!(disputed[0].confidence_interval.0 >= 0.0 && disputed[0].confidence_interval.1 <= 1.0);
+    }
+
+    #[test]
+    fn test_fuses_each_horizon_over_the_predictors_that_forecast_it() {
+        let fusion = PredictionFusionLayer::new();
+        let horizons = [Duration::from_secs(60), Duration::from_secs(3600), Duration::from_secs(86_400)];
+        let temporal = [
+            prediction(0.2, &[(ThreatVector::Burglary, 2.0), (ThreatVector::Casing, 2.0)]),
+            prediction(0.4, &[(ThreatVector::Burglary, 1.0)]),
+        ];
+        let behavioral = [
+            prediction(0.6, &[(ThreatVector::Casing, 1.0)]),
+            prediction(f64::NAN, &[]), // Dropped, not propagated
+        ];
+
+        let fused = fusion.fuse(&[(PredictorSource::Temporal, &temporal), (PredictorSource::Behavioral, &behavioral)], &horizons);
+
+        // No predictor covers the day-ahead horizon, so it is left out rather than guessed
+        assert_eq!(fused.len(), 2);
+        assert!((fused[0].threat_level - 0.4).abs() < 1e-9);
+        assert!((fused[0].weights_used.values().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        // Each member's distribution is normalized before pooling
+        assert!((fused[0].distribution[&ThreatVector::Burglary] - 0.25).abs() < 1e-9);
+        assert!((fused[0].distribution[&ThreatVector::Casing] - 0.75).abs() < 1e-9);
+
+        assert_eq!(fused[1].weights_used.len(), 1);
+        assert_eq!(fused[1].weights_used[&PredictorSource::Temporal], 1.0);
+        assert!((fused[1].threat_level - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_outcomes_shift_weight_to_the_calibrated_predictor() {
+        let mut fusion = PredictionFusionLayer::new();
+        for _ in 0..20 {
+            fusion.record_outcome(&[(PredictorSource::Temporal, 0.9), (PredictorSource::Emergent, 0.1)], true);
+        }
+
+        assert!(fusion.weight(PredictorSource::Temporal) > fusion.weight(PredictorSource::Emergent));
+        assert!(fusion.weight(PredictorSource::Emergent) >= 0.02 / 1.1); // Floored, so it can recover
+        assert!((fusion.weights().values().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let performance = &fusion.performance()[&PredictorSource::Temporal];
+        assert_eq!(performance.outcomes_seen, 20);
+        assert!((performance.mean_brier - 0.01).abs() < 1e-9);
+
+        // The learned weights now pull the fused forecast toward the better predictor
+        let temporal = [prediction(0.8, &[])];
+        let emergent = [prediction(0.2, &[])];
+        let fused = fusion.fuse(&[(PredictorSource::Temporal, &temporal), (PredictorSource::Emergent, &emergent)], &[Duration::from_secs(300)]);
+        assert!(fused[0].threat_level > 0.5);
+    }
+}
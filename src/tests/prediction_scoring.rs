@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod prediction_scoring_tests {
+    use crate::prediction::{PredictionComponent, PredictionScorer, PredictionScoringConfig};
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_feedback_and_entry_attempts_settle_predictions_into_a_report_card() {
+        let scorer = PredictionScorer::new(PredictionScoringConfig::default());
+        let t0 = Utc.with_ymd_and_hms(2026, 5, 4, 1, 0, 0).unwrap();
+
+        // The fused probability is replaced by later assessments until feedback arrives
+        scorer.record("home_1", 1, PredictionComponent::Fusion, 0.4, None, t0);
+        scorer.record("home_1", 1, PredictionComponent::Fusion, 0.9, None, t0 + Duration::minutes(1));
+        scorer.record("home_1", 2, PredictionComponent::Fusion, 0.2, None, t0);
+        scorer.record("home_1", 1, PredictionComponent::Escalation, 0.7, Some(600.0), t0);
+        scorer.record("home_1", 2, PredictionComponent::Escalation, 0.1, Some(600.0), t0);
+
+        assert_eq!(scorer.record_entry_attempt("home_1", t0 + Duration::minutes(5)), 2);
+        assert_eq!(scorer.resolve_incident("home_1", 1, true, t0 + Duration::hours(1)), 1);
+        assert_eq!(scorer.resolve_incident("home_1", 2, false, t0 + Duration::hours(1)), 1);
+
+        let card = scorer.report_card(Some("home_1"), t0, t0 + Duration::days(1), t0 + Duration::days(1));
+        assert_eq!(card.pending, 0);
+        let fusion = card.components.iter().find(|c| c.component == PredictionComponent::Fusion).unwrap();
+        assert_eq!((fusion.predictions, fusion.occurred), (2, 1));
+        // (0.9 - 1)^2 and (0.2 - 0)^2, averaged
+        assert!((fusion.brier_score.unwrap() - 0.025).abs() < 1e-9);
+        assert!(fusion.brier_skill.unwrap() > 0.0);
+        assert_eq!(fusion.calibration.len(), 2);
+
+        let escalation = card.components.iter().find(|c| c.component == PredictionComponent::Escalation).unwrap();
+        assert_eq!((escalation.predictions, escalation.occurred), (2, 2));
+        let prior = card.components.iter().find(|c| c.component == PredictionComponent::Prior).unwrap();
+        assert_eq!(prior.predictions, 0);
+        assert!(prior.brier_score.is_none());
+    }
+
+    #[test]
+    fn test_elapsed_horizons_count_as_misses_and_periods_close_into_report_cards() {
+        let scorer = PredictionScorer::new(PredictionScoringConfig { report_period: Duration::days(1), ..PredictionScoringConfig::default() });
+        let t0 = Utc.with_ymd_and_hms(2026, 5, 4, 12, 0, 0).unwrap();
+        assert!(scorer.roll_report_card(t0).is_none());
+
+        scorer.record("home_1", 1, PredictionComponent::Escalation, 0.8, Some(600.0), t0);
+        assert_eq!(scorer.expire(t0 + Duration::minutes(5)), 0);
+        // An entry attempt after the window no longer counts
+        assert_eq!(scorer.record_entry_attempt("home_1", t0 + Duration::minutes(20)), 0);
+        assert_eq!(scorer.expire(t0 + Duration::minutes(20)), 1);
+
+        let card = scorer.roll_report_card(t0 + Duration::days(1)).unwrap();
+        assert_eq!(card.period_start, Utc.with_ymd_and_hms(2026, 5, 4, 0, 0, 0).unwrap());
+        let escalation = card.components.iter().find(|c| c.component == PredictionComponent::Escalation).unwrap();
+        assert_eq!((escalation.predictions, escalation.occurred), (1, 0));
+        assert!((escalation.brier_score.unwrap() - 0.64).abs() < 1e-9);
+        assert!(scorer.roll_report_card(t0 + Duration::days(1) + Duration::hours(1)).is_none());
+        assert_eq!(scorer.report_cards(10).len(), 1);
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod prior_model_tests {
+    use crate::household::Occupancy;
+    use crate::tests::support;
+    use crate::thinking::{
+        Event, Evidence, IncidentLabel, PriorEditError, PriorGuardrails, PriorModelRegistry, PriorRule, ThinkingAIConfig,
+        ThinkingAIProcessor, TimeBucket,
+    };
+    use std::sync::Arc;
+
+    // 1000s after the epoch is 00:16 UTC, a night-time event
+    fn event(cam: &str, track: &str) -> Event {
+        support::event(1000.0).cam(cam).track(track).dwell(30.0).away(0.9)
+            .evidence(Evidence { llr_time: 0.5, llr_entry: 0.5, llr_behavior: 0.5, ..Default::default() })
+            .build()
+    }
+
+    fn registry() -> Arc<PriorModelRegistry> {
+        let registry = PriorModelRegistry::new(PriorGuardrails::default(), ThinkingAIConfig::default().prior_logit);
+        registry.set_layout("home_1", [("cam_back".to_string(), "Back garden".to_string())].into_iter().collect(), "UTC");
+        Arc::new(registry)
+    }
+
+    fn rule(zone: &str, base_rate: f64) -> PriorRule {
+        PriorRule { entity: None, zone: Some(zone.to_string()), time: None, occupancy: None, base_rate }
+    }
+
+    #[test]
+    fn test_most_specific_rule_sets_the_prior() {
+        let priors = registry();
+        let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        processor.set_prior_model(priors.clone());
+        let before = processor.process_event("home_1", event("cam_back", "a")).unwrap();
+
+        let night_empty = PriorRule { time: Some(TimeBucket::Night), occupancy: Some(Occupancy::Empty), ..rule("Back garden", 0.3) };
+        priors.edit("home_1", vec![night_empty, rule("Back garden", 0.05)], "owner", "raise night risk").unwrap();
+        let after = processor.process_event("home_1", event("cam_back", "b")).unwrap();
+        assert!(after.calibrated_probability > before.calibrated_probability);
+    }
+
+    #[test]
+    fn test_guardrails_reject_out_of_range_and_large_steps() {
+        let priors = registry();
+        assert!(matches!(priors.edit("home_1", vec![rule("Back garden", 0.9)], "owner", ""), Err(PriorEditError::Guardrail(_))));
+        assert!(matches!(priors.edit("home_1", vec![rule("Back garden", 0.001)], "owner", ""), Err(PriorEditError::Guardrail(_))));
+        assert!(!priors.check("home_1", &[rule("Attic", 0.1)]).is_empty()); // Not one of the home's zones
+        assert!(priors.check("home_1", &[rule("Back garden", 0.2)]).is_empty());
+        assert_eq!(priors.current("home_1").version, 0);
+    }
+
+    #[test]
+    fn test_edits_must_agree_with_feedback() {
+        let priors = registry();
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        for i in 0..10 {
+            let result = processor.process_event("home_1", Event { ts: 1000.0 + i as f64 * 1000.0, ..event("cam_back", &format!("t{}", i)) }).unwrap();
+            priors.record_feedback("home_1", &processor.find_incident("home_1", result.incident_id).unwrap(), IncidentLabel::Benign);
+        }
+
+        let violations = priors.check("home_1", &[rule("Back garden", 0.4)]);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("labeled outcomes"));
+        assert!(priors.check("home_1", &[rule("Back garden", 0.08)]).is_empty());
+    }
+
+    #[test]
+    fn test_rollback_restores_earlier_rules_as_new_version() {
+        let priors = registry();
+        priors.edit("home_1", vec![rule("Back garden", 0.2)], "owner", "first").unwrap();
+        priors.edit("home_1", vec![rule("Back garden", 0.3)], "owner", "second").unwrap();
+
+        let restored = priors.rollback("home_1", 1, "owner").unwrap();
+        assert_eq!(restored.version, 3);
+        assert_eq!(restored.rolled_back_from, Some(1));
+        assert_eq!(priors.current("home_1").rules, vec![rule("Back garden", 0.2)]);
+        assert_eq!(priors.history("home_1").len(), 3);
+        assert_eq!(priors.rollback("home_1", 9, "owner").unwrap_err(), PriorEditError::UnknownVersion(9));
+    }
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod probability_properties_tests {
+    use crate::tests::support;
+    use crate::thinking::{
+        calibrate_logit, clamp_llr, logit, sigmoid, Event, Evidence, Incident, ProbabilityCalibrator, ThinkingAIConfig,
+        ThinkingAIProcessor,
+    };
+    use proptest::prelude::*;
+    use std::sync::Arc;
+
+    fn event(ts: f64, evidence: Evidence) -> Event {
+        support::event(ts).evidence(evidence).build()
+    }
+
+    fn evidence() -> impl Strategy<Value = Evidence> {
+        let llr = prop::num::f64::ANY;
+        (llr, llr, llr, llr, llr, llr).prop_map(|(t, e, b, i, p, k)| Evidence {
+            llr_time: t, llr_entry: e, llr_behavior: b, llr_identity: i, llr_presence: p, llr_token: k,
+            ..Default::default()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn sigmoid_is_a_bounded_monotone_probability(a in prop::num::f64::ANY, b in prop::num::f64::ANY) {
+            let (pa, pb) = (sigmoid(a), sigmoid(b));
+            prop_assert!((0.0..=1.0).contains(&pa));
+            if a.is_nan() {
+                prop_assert_eq!(pa, 0.5);
+            }
+            if a <= b {
+                prop_assert!(pa <= pb);
+            }
+        }
+
+        #[test]
+        fn sigmoid_is_symmetric(x in -700.0f64..700.0) {
+            prop_assert!((sigmoid(-x) - (1.0 - sigmoid(x))).abs() < 1e-12);
+        }
+
+        #[test]
+        fn logit_inverts_sigmoid(x in -20.0f64..20.0) {
+            prop_assert!((logit(sigmoid(x)) - x).abs() < 1e-6);
+        }
+
+        #[test]
+        fn logit_is_always_finite(p in prop::num::f64::ANY) {
+            prop_assert!(logit(p).is_finite());
+        }
+
+        #[test]
+        fn clamped_llrs_stay_within_caps(llr in prop::num::f64::ANY, neg in 0.0f64..50.0, pos in 0.0f64..50.0) {
+            let clamped = clamp_llr(llr, neg, pos);
+            prop_assert!(clamped >= -neg && clamped <= pos);
+            if llr.is_nan() {
+                prop_assert_eq!(clamped, 0.0);
+            }
+        }
+
+        #[test]
+        fn clamp_llr_never_panics_on_bad_caps(llr in prop::num::f64::ANY, neg in prop::num::f64::ANY, pos in prop::num::f64::ANY) {
+            prop_assert!(!clamp_llr(llr, neg, pos).is_nan());
+        }
+
+        #[test]
+        fn calibration_is_bounded_by_the_odds_cap(
+            raw in prop::num::f64::ANY,
+            mean in -5.0f64..5.0,
+            temperature in prop::num::f64::ANY,
+            cap in 0.0f64..20.0,
+        ) {
+            let p = calibrate_logit(raw, mean, temperature, cap);
+            prop_assert!(p >= sigmoid(-cap) && p <= sigmoid(cap));
+        }
+
+        #[test]
+        fn calibration_is_monotone(a in -100.0f64..100.0, b in -100.0f64..100.0, temperature in 0.0f64..10.0) {
+            if a <= b {
+                prop_assert!(calibrate_logit(a, 0.0, temperature, 8.0) <= calibrate_logit(b, 0.0, temperature, 8.0));
+            }
+        }
+
+        #[test]
+        fn fused_evidence_is_finite_and_capped(events in prop::collection::vec(evidence(), 1..6)) {
+            let mut incident = Incident::new(1, 0.0, "track_a".to_string());
+            for (i, ev) in events.into_iter().enumerate() {
+                incident.add_event(event(i as f64, ev));
+            }
+            let fused = incident.fused_evidence(3.0, 4.0);
+            for llr in [fused.llr_time, fused.llr_entry, fused.llr_behavior, fused.llr_identity, fused.llr_presence, fused.llr_token] {
+                prop_assert!((-4.0..=3.0).contains(&llr));
+            }
+            prop_assert!(fused.capped_sum(3.0, 4.0).is_finite());
+        }
+    }
+
+    #[derive(Debug)]
+    struct Fixed(f64);
+
+    impl ProbabilityCalibrator for Fixed {
+        fn calibrate(&self, _raw_logit: f64) -> f64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn processor_uses_the_injected_calibrator() {
+        let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        processor.set_calibrator(Arc::new(Fixed(0.42)));
+        let evidence = Evidence { llr_time: 1.0, llr_entry: 0.5, llr_behavior: 0.0, llr_identity: 0.0, llr_presence: 0.0, llr_token: 0.0, ..Default::default() };
+        let result = processor.process_event("home_a", event(100.0, evidence)).unwrap();
+        assert_eq!(result.calibrated_probability, 0.42);
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod probability_series_tests {
+    use crate::probability_series::{InMemoryProbabilitySeriesStore, PointSource, ProbabilityHistory, ProbabilityPoint, ProbabilitySeriesConfig};
+    use crate::thinking::AlertDecision;
+    use chrono::{DateTime, Duration, Utc};
+    use std::sync::Arc;
+
+    fn point(at: DateTime<Utc>, event_count: usize, probability: f64, decision: AlertDecision) -> ProbabilityPoint {
+        ProbabilityPoint {
+            at,
+            event_id: None,
+            event_count,
+            fused_llr: 0.0,
+            calibrated_probability: probability,
+            decision,
+            source: PointSource::Event,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_downsampled_chart_keeps_ends_peak_and_decision_changes() {
+        let history = ProbabilityHistory::default();
+        let start = Utc::now();
+        for i in 0..100 {
+            let (probability, decision) = match i {
+                40 => (0.9, AlertDecision::Critical),
+                41..=59 => (0.4, AlertDecision::Elevated),
+                _ => (0.05, AlertDecision::Ignore),
+            };
+            history.record("home_1", 7, point(start + Duration::seconds(i), i as usize + 1, probability, decision)).await.unwrap();
+        }
+
+        let chart = history.chart("home_1", 7, Some(10)).await.unwrap().unwrap();
+        assert_eq!(chart.total_points, 100);
+        assert_eq!(chart.peak_probability, 0.9);
+        assert!(chart.points.len() <= 10);
+        let counts: Vec<usize> = chart.points.iter().map(|p| p.event_count).collect();
+        for kept in [1, 41, 42, 61, 100] {
+            assert!(counts.contains(&kept), "event_count {} missing from {:?}", kept, counts);
+        }
+        assert!(counts.windows(2).all(|w| w[0] < w[1]));
+
+        let full = history.chart("home_1", 7, None).await.unwrap().unwrap();
+        assert_eq!(full.points.len(), 100);
+        assert!(history.chart("home_1", 8, None).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_points_past_retention_are_pruned() {
+        let config = ProbabilitySeriesConfig { retention: Duration::days(30), ..ProbabilitySeriesConfig::default() };
+        let history = ProbabilityHistory::new(config, Arc::new(InMemoryProbabilitySeriesStore::default()));
+        let now = Utc::now();
+        history.record("home_1", 1, point(now - Duration::days(40), 1, 0.2, AlertDecision::Standard)).await.unwrap();
+        history.record("home_1", 2, point(now - Duration::days(40), 1, 0.1, AlertDecision::Ignore)).await.unwrap();
+        history.record("home_1", 2, point(now - Duration::days(1), 2, 0.3, AlertDecision::Elevated)).await.unwrap();
+
+        assert_eq!(history.prune(now).await.unwrap(), 2);
+        assert!(history.chart("home_1", 1, None).await.unwrap().is_none());
+        let kept = history.chart("home_1", 2, None).await.unwrap().unwrap();
+        assert_eq!(kept.points.len(), 1);
+        assert_eq!(kept.points[0].decision, AlertDecision::Elevated);
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod processor_sharding_tests {
+    use crate::tests::support;
+    use crate::thinking::{Event, Evidence, ThinkingAIConfig, ThinkingAIProcessor};
+    use std::sync::Arc;
+
+    fn event(ts: f64, track: &str) -> Event {
+        support::event(ts).track(track).dwell(20.0).evidence(Evidence { llr_behavior: 0.6, ..Default::default() }).build()
+    }
+
+    #[test]
+    fn test_homes_process_concurrently_without_an_outer_lock() {
+        let processor = Arc::new(ThinkingAIProcessor::new(ThinkingAIConfig::default()));
+        let handles: Vec<_> = (0..4)
+            .map(|h| {
+                let processor = processor.clone();
+                std::thread::spawn(move || {
+                    let home = format!("home_{}", h);
+                    for i in 0..50 {
+                        let track = format!("track_{}", i % 5);
+                        processor.process_event(&home, event(1000.0 + i as f64, &track)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(processor.homes(), vec!["home_0", "home_1", "home_2", "home_3"]);
+        for h in 0..4 {
+            let incidents = processor.home_incidents(&format!("home_{}", h));
+            assert_eq!(incidents.len(), 5);
+            assert_eq!(incidents.iter().map(|e| e.incident.events.len()).sum::<usize>(), 50);
+        }
+        assert_eq!(processor.open_incidents().len(), 20);
+    }
+
+    #[test]
+    fn test_lookups_return_copies_that_outlive_later_events() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let result = processor.process_event("home_1", event(1000.0, "track_a")).unwrap();
+        let before = processor.find_incident("home_1", result.incident_id).unwrap();
+
+        processor.process_event("home_1", event(1010.0, "track_a")).unwrap();
+        assert_eq!(before.events.len(), 1);
+        assert_eq!(processor.track_incident("home_1", "track_a").unwrap().events.len(), 2);
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod red_team_tests {
+    use crate::red_team::{generate, run, RedTeamConfig, ScenarioKind};
+    use crate::thinking::ThinkingAIConfig;
+
+    #[test]
+    fn test_same_seed_generates_same_scenario() {
+        let config = RedTeamConfig::default();
+        for kind in ScenarioKind::ALL {
+            let a = generate(kind, &config);
+            let b = generate(kind, &config);
+            assert_eq!(a.steps.len(), b.steps.len());
+            assert!(a.steps.iter().zip(&b.steps).all(|(x, y)| x.raw.event_id == y.raw.event_id && x.raw.timestamp == y.raw.timestamp));
+            assert!(a.steps.iter().any(|s| s.hostile));
+            assert!(a.steps.iter().any(|s| !s.hostile));
+            assert!(a.steps.windows(2).all(|w| w[0].raw.timestamp <= w[1].raw.timestamp));
+        }
+    }
+
+    #[test]
+    fn test_recon_spans_configured_nights() {
+        let config = RedTeamConfig { recon_days: 4, ..RedTeamConfig::default() };
+        let scenario = generate(ScenarioKind::SlowRecon, &config);
+        let score = run(&scenario, ThinkingAIConfig::default());
+        assert_eq!(score.hostile_events, 4);
+        assert_eq!(score.benign_events, 4 * config.benign_per_day);
+        assert!(score.hostile_incidents >= 1);
+        assert!(score.detected_incidents <= score.hostile_incidents);
+    }
+}
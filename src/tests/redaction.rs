@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod redaction_tests {
+    use crate::redaction::{FaceRegion, NormRect, PrivacyZone, RedactionError, SnapshotSharing};
+    use crate::thinking::Incident;
+    use crate::vps_client::VpsProcessingResponse;
+    use std::io::Cursor;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let img = image::RgbImage::from_pixel(width, height, image::Rgb([220, 220, 220]));
+        let mut out = Vec::new();
+        image::DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut out), image::ImageOutputFormat::Png)
+            .unwrap();
+        out
+    }
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> NormRect {
+        NormRect { x, y, width, height }
+    }
+
+    fn brightness(jpeg: &[u8], x: u32, y: u32) -> u8 {
+        image::load_from_memory(jpeg).unwrap().to_rgb8().get_pixel(x, y).0[0]
+    }
+
+    #[test]
+    fn test_only_resident_faces_are_redacted() {
+        let sharing = SnapshotSharing::default();
+        sharing.enroll_face("home_1", "alice", &[1.0, 0.0, 0.0]).unwrap();
+
+        let faces = vec![
+            FaceRegion { rect: rect(0.1, 0.1, 0.2, 0.2), embedding: Some(vec![0.95, 0.1, 0.0]) }, // Alice
+            FaceRegion { rect: rect(0.6, 0.6, 0.2, 0.2), embedding: Some(vec![0.0, 1.0, 0.0]) },  // A stranger
+        ];
+        let redacted = sharing.redact_for_share("home_1", "cam_front", &png(100, 100), &faces).unwrap();
+
+        assert_eq!(redacted.report.redacted_faces, 1);
+        assert_eq!(redacted.report.unmatched_faces, 1);
+        assert!(brightness(&redacted.data, 20, 20) < 30);
+        assert!(brightness(&redacted.data, 70, 70) > 180);
+    }
+
+    #[test]
+    fn test_privacy_zones_are_validated_and_applied() {
+        let sharing = SnapshotSharing::default();
+        let outside = PrivacyZone { name: "bad".to_string(), rect: rect(0.8, 0.0, 0.5, 0.5) };
+        assert!(matches!(sharing.set_privacy_zones("home_1", "cam_front", vec![outside]), Err(RedactionError::InvalidRegion(_))));
+
+        let window = PrivacyZone { name: "Neighbor window".to_string(), rect: rect(0.0, 0.5, 0.5, 0.5) };
+        sharing.set_privacy_zones("home_1", "cam_front", vec![window]).unwrap();
+        let redacted = sharing.redact_for_share("home_1", "cam_front", &png(64, 64), &[]).unwrap();
+        assert_eq!(redacted.report.blurred_zones, 1);
+        let other_camera = sharing.redact_for_share("home_1", "cam_back", &png(64, 64), &[]).unwrap();
+        assert_eq!(other_camera.report.blurred_zones, 0);
+    }
+
+    #[test]
+    fn test_share_links_expire_and_revoke() {
+        let sharing = SnapshotSharing::default();
+        let redacted = sharing.redact_for_share("home_1", "cam_front", &png(32, 32), &[]).unwrap();
+
+        let link = sharing.publish("home_1", 7, redacted.clone(), None);
+        assert_eq!(sharing.shared(&link.token).unwrap().incident_id, 7);
+        assert!(sharing.revoke(&link.token).is_some());
+        assert!(sharing.shared(&link.token).is_none());
+
+        let expired = sharing.publish("home_1", 7, redacted, Some(chrono::Duration::seconds(-1)));
+        assert!(sharing.shared(&expired.token).is_none());
+    }
+
+    #[test]
+    fn test_detected_faces_travel_with_the_incident_snapshot() {
+        let response: VpsProcessingResponse = serde_json::from_str(
+            r#"{"job_id": "j1", "status": "completed", "result_url": null, "error_message": null,
+                "faces": [{"rect": {"x": 0.1, "y": 0.1, "width": 0.2, "height": 0.3}}]}"#,
+        ).unwrap();
+        let faces = response.faces.unwrap();
+        assert_eq!(faces.len(), 1);
+
+        let mut incident = Incident::new(1, 0.0, "track_1".to_string());
+        incident.attach_snapshot_faces("https://cam/1.jpg".to_string(), faces);
+        incident.attach_snapshot("https://cam/2.jpg".to_string()); // No detector ran on this one
+        let restored: Incident = serde_json::from_str(&serde_json::to_string(&incident).unwrap()).unwrap();
+        assert_eq!(restored.snapshot_urls.len(), 2);
+        assert_eq!(restored.snapshot_faces["https://cam/1.jpg"][0].rect.height, 0.3);
+        assert!(!restored.snapshot_faces.contains_key("https://cam/2.jpg"));
+
+        let legacy: VpsProcessingResponse = serde_json::from_str(r#"{"job_id": "j2", "status": "completed", "result_url": null, "error_message": null}"#).unwrap();
+        assert!(legacy.faces.is_none());
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod sanitization_tests {
+    use crate::sanitization::{IssueKind, NonFinitePolicy, SanitizationConfig, SanitizationError, Sanitizer};
+    use crate::tests::support;
+    use crate::thinking::{Event, Evidence, ThinkingAIConfig, ThinkingAIProcessor};
+
+    fn event(ts: f64, evidence: Evidence) -> Event {
+        support::event(ts).dwell(20.0).away(0.4).evidence(evidence).build()
+    }
+
+    fn evidence(entry: f64) -> Evidence {
+        Evidence { llr_time: 0.5, llr_entry: entry, llr_behavior: 0.2, llr_identity: 0.0, llr_presence: 0.0, llr_token: 0.0, ..Default::default() }
+    }
+
+    #[test]
+    fn clean_events_pass_untouched() {
+        let mut ev = event(1_000.0, evidence(1.0));
+        let quality = Sanitizer::default().sanitize_event(&mut ev, 1_000.0).unwrap();
+        assert!(quality.is_clean());
+        assert_eq!(ev.evidence.llr_entry, 1.0);
+    }
+
+    #[test]
+    fn non_finite_inputs_are_replaced_and_flagged() {
+        let mut ev = event(f64::NAN, evidence(f64::INFINITY));
+        ev.away_prob = f64::NAN;
+        ev.dwell_s = -5.0;
+        let quality = Sanitizer::default().sanitize_event(&mut ev, 2_000.0).unwrap();
+
+        assert_eq!(ev.ts, 2_000.0);
+        assert_eq!(ev.away_prob, 0.5);
+        assert_eq!(ev.dwell_s, 0.0);
+        assert_eq!(ev.evidence.llr_entry, 0.0);
+        let kinds: Vec<(&str, IssueKind)> = quality.issues.iter().map(|i| (i.field.as_str(), i.kind)).collect();
+        assert!(kinds.contains(&("ts", IssueKind::NotANumber)));
+        assert!(kinds.contains(&("evidence.llr_entry", IssueKind::Infinite)));
+        assert!(kinds.contains(&("dwell_s", IssueKind::OutOfRange)));
+    }
+
+    #[test]
+    fn future_timestamps_and_huge_llrs_are_normalized_even_when_rejecting() {
+        let sanitizer = Sanitizer::new(SanitizationConfig { non_finite: NonFinitePolicy::Reject, ..Default::default() });
+        let mut ev = event(10_000.0, evidence(500.0));
+        let quality = sanitizer.sanitize_event(&mut ev, 1_000.0).unwrap();
+        assert_eq!(ev.ts, 1_000.0);
+        assert_eq!(ev.evidence.llr_entry, 20.0);
+        assert_eq!(quality.issues.len(), 2);
+
+        let mut bad = event(1_000.0, evidence(f64::NAN));
+        assert!(matches!(sanitizer.sanitize_event(&mut bad, 1_000.0), Err(SanitizationError::Rejected(issues)) if issues.len() == 1));
+    }
+
+    #[test]
+    fn flags_reach_the_explanation() {
+        let mut ev = event(1_000.0, evidence(f64::NAN));
+        let quality = Sanitizer::default().sanitize_event(&mut ev, 1_000.0).unwrap();
+
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let result = processor.process_flagged_event("home_a", ev, quality.flags()).unwrap();
+        assert!(result.calibrated_probability.is_finite());
+        assert_eq!(result.explanation.data_quality, vec!["evidence.llr_entry was NaN, used 0".to_string()]);
+        assert!(result.explanation.render().contains("repaired inputs"));
+    }
+}
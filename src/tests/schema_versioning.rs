@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod schema_versioning_tests {
+    use crate::core::ThreatAssessment;
+    use crate::schema::{from_versioned_json, to_versioned_json, SchemaError};
+    use crate::thinking::{IncidentStore, IncidentStoreSnapshot};
+
+    #[test]
+    fn test_legacy_threat_assessment_fixture_migrates() {
+        let assessment: ThreatAssessment =
+            from_versioned_json(include_str!("fixtures/threat_assessment_v1.json")).unwrap();
+
+        assert_eq!(assessment.environmental_context.location, "back_garden");
+        assert!(assessment.environmental_context.environment.is_none());
+        assert_eq!(assessment.temporal_horizon.num_seconds(), 900);
+        assert_eq!(assessment.explanation.headline, "Unknown person loitering in back garden at night");
+        assert!(assessment.explanation.factors.is_empty());
+
+        // Round-trips through the current envelope
+        let json = to_versioned_json(&assessment).unwrap();
+        assert!(json.contains("\"version\":3"));
+        let reloaded: ThreatAssessment = from_versioned_json(&json).unwrap();
+        assert_eq!(reloaded.threat_level, assessment.threat_level);
+    }
+
+    #[test]
+    fn test_incident_store_fixture_loads() {
+        let snapshot: IncidentStoreSnapshot =
+            from_versioned_json(include_str!("fixtures/incident_store_v1.json")).unwrap();
+        let store = IncidentStore::from_snapshot(snapshot);
+
+        let incident = store.get_incident("home_1", "track_42").unwrap();
+        assert_eq!(incident.id, 7);
+        assert_eq!(incident.events.len(), 1);
+        assert_eq!(store.id_counter, 8);
+    }
+
+    #[test]
+    fn test_wrong_schema_and_future_versions_rejected() {
+        let store_json = include_str!("fixtures/incident_store_v1.json");
+        assert!(matches!(
+            from_versioned_json::<ThreatAssessment>(store_json),
+            Err(SchemaError::SchemaMismatch { .. })
+        ));
+
+        let future = store_json.replacen("\"version\": 1", "\"version\": 99", 1);
+        assert!(matches!(
+            from_versioned_json::<IncidentStoreSnapshot>(&future),
+            Err(SchemaError::FromFuture { .. })
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod self_test_tests {
+    use crate::delivery::{NotificationPreferences, NotificationRouter, RouteDecision, WebhookConfig, WebhookDispatcher};
+    use crate::overnight::DeliveryChannel;
+    use crate::pipeline::{EventPipeline, PipelineConfig, SubscriptionTier};
+    use crate::pipeline_stages::Stage;
+    use crate::self_test::{demo_events, scenario_of, shadow_home, CheckStatus, SyntheticScenario};
+    use crate::vps_client::VpsApiClient;
+    use chrono::{Duration, Utc};
+    use std::sync::Arc;
+
+    fn pipeline() -> EventPipeline {
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        EventPipeline::new(config, VpsApiClient::new("http://localhost:0".to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_vps_fails_its_stage_for_every_scenario() {
+        let mut pipeline = pipeline();
+        let report = pipeline.self_test("home_1", "user_1", SubscriptionTier::Premium, false).await;
+
+        assert_eq!(report.scenarios.len(), SyntheticScenario::ALL.len());
+        for scenario in &report.scenarios {
+            let status = |stage: Stage| scenario.stages.iter().find(|s| s.stage == stage).map(|s| s.status);
+            assert_eq!(status(Stage::Sanitize), Some(CheckStatus::Passed));
+            assert_eq!(status(Stage::Image), Some(CheckStatus::Passed));
+            assert_eq!(status(Stage::Vps), Some(CheckStatus::Failed));
+            assert_eq!(status(Stage::Notify), Some(CheckStatus::NotReached));
+            assert_eq!(scenario.status, CheckStatus::Failed);
+        }
+        assert!(!report.healthy);
+        assert!(report.channels.is_empty());
+        assert!(report.issues.iter().any(|i| i.contains("notifications are not configured")));
+
+        let events = demo_events("home_1", "user_1", Utc::now(), Duration::seconds(20), 4);
+        let scenarios: Vec<_> = events.iter().filter_map(scenario_of).collect();
+        assert_eq!(scenarios, vec![SyntheticScenario::Delivery, SyntheticScenario::Lingering, SyntheticScenario::BreakIn, SyntheticScenario::Delivery]);
+        assert_eq!(shadow_home("home_1"), "home_1:self-test");
+    }
+
+    #[tokio::test]
+    async fn test_channel_matrix_flags_muted_channels_and_missing_webhooks() {
+        let router = NotificationRouter::new().with_webhooks(Arc::new(WebhookDispatcher::new(WebhookConfig::default())));
+        router.set_preferences("home_1", None, NotificationPreferences {
+            muted_channels: vec![DeliveryChannel::WebSocket],
+            ..Default::default()
+        });
+        let pipeline = pipeline().with_notification_router(Arc::new(router));
+
+        let (channels, problems) = pipeline.check_delivery_channels("home_1", "user_1", true).await;
+        assert!(problems.is_empty());
+        let check = |channel: DeliveryChannel| channels.iter().find(|c| c.channel == channel).unwrap();
+
+        let push = check(DeliveryChannel::Push);
+        assert_eq!(push.status, CheckStatus::Passed);
+        assert_eq!(push.routes.len(), 4);
+        assert!(push.routes.iter().all(|r| r.decision == Some(RouteDecision::Deliver)));
+        assert_eq!(push.delivered, None);
+
+        let websocket = check(DeliveryChannel::WebSocket);
+        assert_eq!(websocket.status, CheckStatus::Warning);
+        assert!(matches!(websocket.routes[3].decision, Some(RouteDecision::Drop(_))));
+
+        let webhook = check(DeliveryChannel::Webhook);
+        assert_eq!(webhook.user_id, None);
+        assert_eq!(webhook.status, CheckStatus::Failed);
+        assert!(!webhook.configured);
+        assert_eq!(webhook.delivered, None);
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod sensor_reliability_tests {
+    use crate::tests::support;
+    use crate::thinking::{Event, Evidence, Incident, IncidentLabel, SensorReliabilityConfig, SensorReliabilityModel};
+    use chrono::Utc;
+
+    fn event(cam: &str, behavior: f64) -> Event {
+        support::event(1000.0).cam(cam).evidence(Evidence { llr_behavior: behavior, ..Default::default() }).build()
+    }
+
+    #[test]
+    fn test_sensor_that_cries_wolf_loses_reliability() {
+        let model = SensorReliabilityModel::new(SensorReliabilityConfig::default());
+        // The driveway camera flags every benign visit; the door camera agrees with outcomes
+        for i in 0..8 {
+            let mut incident = Incident::new(i, 1000.0, "track_a".to_string());
+            incident.add_event(event("driveway", 1.2));
+            incident.add_event(event("front_door", -0.8));
+            model.record_outcome("home_1", &incident, IncidentLabel::Benign, Utc::now());
+        }
+
+        let driveway = model.reliability("home_1", "driveway").unwrap();
+        let door = model.reliability("home_1", "front_door").unwrap();
+        assert!(driveway < 0.6, "driveway {}", driveway);
+        assert!(door > 0.85, "door {}", door);
+        assert!(model.reliability("home_2", "driveway").is_none());
+    }
+
+    #[test]
+    fn test_discounting_shrinks_evidence_toward_zero() {
+        let mut incident = Incident::new(1, 1000.0, "track_a".to_string());
+        incident.add_event(event("driveway", 2.0));
+
+        let plain = incident.fused_evidence(5.0, 5.0);
+        let discounted = incident.fused_evidence_with(5.0, 5.0, |cam| (cam == "driveway").then_some(0.5));
+        assert!(discounted.llr_behavior > 0.0);
+        assert!(discounted.llr_behavior < plain.llr_behavior);
+        // Full reliability leaves evidence untouched
+        assert_eq!(incident.fused_evidence_with(5.0, 5.0, |_| Some(1.0)).llr_behavior, plain.llr_behavior);
+    }
+}
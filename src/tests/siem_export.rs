@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod siem_export_tests {
+    use crate::delivery::{SiemConfig, SiemExporter, SiemFieldMapping, SiemFormat, SiemTransport};
+    use crate::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier};
+    use crate::tests::support::mock_vps_url;
+    use crate::vps_client::VpsApiClient;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_pipeline_incident_reaches_the_collector() {
+        let collector = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let exporter = Arc::new(SiemExporter::start(SiemConfig {
+            host: "127.0.0.1".to_string(),
+            port: collector.local_addr().unwrap().port(),
+            transport: SiemTransport::Tcp,
+            format: SiemFormat::Cef,
+            mapping: SiemFieldMapping::cef_default(),
+            flush_interval: Duration::from_millis(20),
+            ..SiemConfig::default()
+        }));
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new(mock_vps_url().to_string()))
+            .with_siem_exporter(exporter.clone());
+
+        let event = RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: "back_door".to_string(),
+            timestamp: 1_700_000_000,
+            data: r#"{"motion":true}"#.into(),
+            user_id: "user_1".to_string(),
+            home_id: "home_siem".to_string(),
+            image_url: None,
+            image_data: None,
+        };
+        pipeline.process_event(event, SubscriptionTier::Premium, "key").await.unwrap();
+
+        let line = tokio::time::timeout(Duration::from_secs(5), async {
+            let (socket, _) = collector.accept().await.unwrap();
+            let mut line = String::new();
+            BufReader::new(socket).read_line(&mut line).await.unwrap();
+            line
+        }).await.expect("the exporter's task should deliver the queued incident");
+
+        assert!(line.starts_with("CEF:0|Novin|"));
+        assert!(line.contains("|novin-incident-"));
+        assert!(line.contains("cs1=home_siem cs1Label=home_id"));
+        assert_eq!(exporter.metrics().dropped, 0);
+    }
+}
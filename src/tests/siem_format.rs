@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod siem_format_tests {
+    use crate::delivery::{format_cef, format_syslog, SiemEvent, SiemEventKind, SiemFieldMapping};
+    use chrono::{TimeZone, Utc};
+    use std::collections::BTreeMap;
+
+    fn sample_event() -> SiemEvent {
+        let mut fields = BTreeMap::new();
+        fields.insert("home_id".to_string(), "home_1".to_string());
+        fields.insert("decision".to_string(), "Critical".to_string());
+        fields.insert("description".to_string(), "Unknown person at back door\nprob=0.72".to_string());
+        SiemEvent {
+            kind: SiemEventKind::Incident,
+            signature_id: "novin-incident-critical".to_string(),
+            name: "Incident | assessment".to_string(),
+            severity: 10,
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 5, 3, 12, 0).unwrap(),
+            fields,
+        }
+    }
+
+    #[test]
+    fn test_cef_escapes_and_labels_custom_fields() {
+        let line = format_cef(&sample_event(), &SiemFieldMapping::cef_default());
+
+        assert!(line.starts_with("CEF:0|Novin|InsaneAISecurity|"));
+        assert!(line.contains("|novin-incident-critical|Incident \\| assessment|10|"));
+        assert!(line.contains("cs1=home_1 cs1Label=home_id"));
+        assert!(line.contains("act=Critical"));
+        assert!(line.contains("msg=Unknown person at back door\\nprob\\=0.72"));
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn test_syslog_structured_data() {
+        let msg = format_syslog(&sample_event(), &SiemFieldMapping::identity(), "edge-1", "novin-security");
+
+        assert!(msg.starts_with("<129>1 2024-01-05T03:12:00.000Z edge-1 novin-security - novin-incident-critical "));
+        assert!(msg.contains("[novin@32473 decision=\"Critical\" home_id=\"home_1\"]"));
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod sms_tests {
+    use crate::delivery::{normalize_number, SmsCallback, SmsConfig, SmsDispatcher, SmsError, SmsProvider, SmsStatus, TwilioProvider};
+    use async_trait::async_trait;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct FakeProvider {
+        sent: AtomicU32,
+    }
+
+    #[async_trait]
+    impl SmsProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        async fn send(&self, _to: &str, _body: &str, _status_callback: Option<&str>) -> Result<String, SmsError> {
+            Ok(format!("SM{}", self.sent.fetch_add(1, Ordering::SeqCst)))
+        }
+
+        fn signature_header(&self) -> &'static str {
+            "X-Fake-Signature"
+        }
+
+        fn callback(&self, _url: &str, _signature: Option<&str>, _body: &[u8]) -> Option<SmsCallback> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_numbers_are_normalized_to_e164() {
+        assert_eq!(normalize_number("+44 7700 900123").unwrap(), "+447700900123");
+        assert_eq!(normalize_number("0049 (30) 1234-5678").unwrap(), "+493012345678");
+        assert!(normalize_number("07700 900123").is_err());
+        assert!(normalize_number("+44 77OO").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_opt_out_and_final_status_counted_once() {
+        let provider = Arc::new(FakeProvider::default());
+        let sms = SmsDispatcher::new(provider.clone(), SmsConfig::default());
+        sms.set_number("home_1", "alice", "+447700900123").unwrap();
+
+        let id = sms.deliver("home_1", "alice", "Critical alert", "Person at the back door").await.unwrap();
+        sms.handle_callback(SmsCallback::Status { message_id: id.clone(), status: SmsStatus::Delivered, error: None });
+        sms.handle_callback(SmsCallback::Status { message_id: id, status: SmsStatus::Failed, error: Some("30003".to_string()) });
+        assert!(matches!(sms.deliver("home_1", "bob", "t", "b").await, Err(SmsError::NoNumber(_))));
+
+        sms.handle_callback(SmsCallback::Inbound { from: "+447700900123".to_string(), body: " stop ".to_string() });
+        assert!(sms.contact("home_1", "alice").unwrap().opted_out);
+        assert!(matches!(sms.deliver("home_1", "alice", "t", "b").await, Err(SmsError::OptedOut(_))));
+        sms.handle_callback(SmsCallback::Inbound { from: "+447700900123".to_string(), body: "START".to_string() });
+        sms.deliver("home_1", "alice", "t", "b").await.unwrap();
+
+        let stats = sms.stats("home_1");
+        assert_eq!((stats.sent, stats.delivered, stats.failed), (2, 1, 0));
+        assert_eq!((stats.skipped_no_contact, stats.skipped_opted_out), (1, 1));
+        assert_eq!(provider.sent.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_twilio_callbacks_need_a_valid_signature() {
+        let twilio = TwilioProvider::new("AC123".to_string(), "secret".to_string(), "+15005550006".to_string());
+        let url = "https://home.example.com/api/sms/status";
+        let body = b"MessageSid=SM1&MessageStatus=undelivered&ErrorCode=30005";
+        let params: BTreeMap<String, String> = url::form_urlencoded::parse(body).into_owned().collect();
+        let signature = twilio.sign(url, &params);
+
+        assert_eq!(
+            twilio.callback(url, Some(&signature), body),
+            Some(SmsCallback::Status { message_id: "SM1".to_string(), status: SmsStatus::Undelivered, error: Some("30005".to_string()) })
+        );
+        assert_eq!(twilio.callback(url, Some(&signature), b"MessageSid=SM1&MessageStatus=delivered"), None);
+        assert_eq!(twilio.callback(url, None, body), None);
+    }
+}
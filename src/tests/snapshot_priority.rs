@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod snapshot_priority_tests {
+    use crate::image_preloader::Priority;
+    use crate::onboarding::ZoneKind;
+    use crate::snapshot_priority::{SnapshotPriorityConfig, SnapshotPriorityPolicy};
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn policy() -> SnapshotPriorityPolicy {
+        let policy = SnapshotPriorityPolicy::new(SnapshotPriorityConfig::default());
+        let zones: HashMap<String, ZoneKind> = [
+            ("garden_cam".to_string(), ZoneKind::Yard),
+            ("front_cam".to_string(), ZoneKind::FrontDoor),
+            ("street_cam".to_string(), ZoneKind::Street),
+        ].into_iter().collect();
+        policy.set_layout("home_1", zones, "Europe/London");
+        policy
+    }
+
+    #[test]
+    fn test_back_garden_at_night_is_fetched_first() {
+        let policy = policy();
+        let night = Utc.with_ymd_and_hms(2026, 1, 10, 23, 30, 0).unwrap();
+        let noon = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+
+        assert_eq!(policy.priority("home_1", "garden_cam", None, night), Priority::Critical);
+        assert_eq!(policy.priority("home_1", "front_cam", None, night), Priority::High);
+        assert_eq!(policy.priority("home_1", "garden_cam", None, noon), Priority::Normal);
+        assert_eq!(policy.priority("home_1", "street_cam", None, night), Priority::Low);
+        // Unknown homes and cameras keep the probability-only priority
+        assert_eq!(policy.priority("home_2", "garden_cam", None, night), Priority::Normal);
+    }
+
+    #[test]
+    fn test_live_incident_probability_sets_the_base_priority() {
+        let policy = policy();
+        let noon = Utc.with_ymd_and_hms(2026, 6, 10, 11, 0, 0).unwrap();
+
+        assert_eq!(policy.priority("home_1", "front_cam", Some(0.7), noon), Priority::Critical);
+        assert_eq!(policy.priority("home_1", "front_cam", Some(0.2), noon), Priority::High);
+        assert_eq!(policy.priority("home_1", "front_cam", Some(0.01), noon), Priority::Low);
+        // A likely threat on the street view still outranks a quiet camera
+        assert_eq!(policy.priority("home_1", "street_cam", Some(0.7), noon), Priority::High);
+        assert!(Priority::Critical > Priority::High && Priority::Normal > Priority::Low);
+    }
+}
@@ -0,0 +1,211 @@
+// Shared fixtures: a full AppState for API handler tests, mock VPSes and
+// thinking-AI events
+
+use crate::api::auth::{AuthUser, Role};
+use crate::api::routes::AppState;
+use crate::thinking::{Event, Evidence};
+use crate::vps_client::{VpsProcessingRequest, VpsProcessingResponse};
+use axum::{extract::State, http::StatusCode, routing::{get, post}, Json, Router};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// AppState on a fresh database file, persisting under a per-run temp dir.
+/// The pipeline talks to the process-wide mock VPS.
+pub async fn app_state() -> AppState {
+    static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+    let dir = DATA_DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("novin_state_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::env::set_var("NOVIN_DATA_DIR", &dir);
+        std::env::set_var("VPS_API_URL", mock_vps_url());
+        // Located test homes must not reach out to the public weather API
+        std::env::set_var("NOVIN_WEATHER", "off");
+        dir
+    });
+    assert_eq!(std::env::var_os("NOVIN_DATA_DIR").as_deref(), Some(dir.as_os_str()));
+    // A file, not sqlite::memory:, so every pooled connection sees the same tables
+    let db = dir.join(format!("novin_{}.db", uuid::Uuid::new_v4()));
+    let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", db.display())).await.unwrap();
+    AppState::new(pool)
+}
+
+/// Homeowner whose token covers only `homes`
+pub fn homeowner(homes: &[&str]) -> AuthUser {
+    AuthUser {
+        user_id: "owner_1".to_string(),
+        username: "owner".to_string(),
+        role: Role::Homeowner,
+        scopes: Role::Homeowner.default_scopes(),
+        homes: Some(homes.iter().map(|h| h.to_string()).collect()),
+    }
+}
+
+fn vps_calls() -> &'static Mutex<HashMap<String, usize>> {
+    static CALLS: OnceLock<Mutex<HashMap<String, usize>>> = OnceLock::new();
+    CALLS.get_or_init(Default::default)
+}
+
+/// How many times the mock VPS was asked to process `event_id`
+pub fn mock_vps_calls(event_id: &str) -> usize {
+    vps_calls().lock().unwrap().get(event_id).copied().unwrap_or(0)
+}
+
+/// Base URL of a mock VPS that completes every job. It runs on its own
+/// thread so it outlives the runtime of whichever test started it.
+pub fn mock_vps_url() -> &'static str {
+    static URL: OnceLock<String> = OnceLock::new();
+    URL.get_or_init(|| {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                let app = Router::new()
+                    .route("/health", get(|| async { "OK" }))
+                    .route("/v1/process", post(|Json(request): Json<VpsProcessingRequest>| async move {
+                        *vps_calls().lock().unwrap().entry(request.event_id.clone()).or_default() += 1;
+                        Json(completed(&request))
+                    }));
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        url
+    })
+}
+
+fn completed(request: &VpsProcessingRequest) -> VpsProcessingResponse {
+    VpsProcessingResponse {
+        job_id: format!("job_{}", request.event_id),
+        status: "completed".to_string(),
+        result_url: None,
+        error_message: None,
+        appearance_embedding: None,
+        gait_embedding: None,
+        liveness_score: None,
+        faces: None,
+        from_cache: false,
+    }
+}
+
+/// A mock VPS that can be taken down: while unhealthy both its health check
+/// and its processing endpoint answer 503
+#[derive(Clone)]
+pub struct ControllableVps {
+    pub url: String,
+    healthy: Arc<AtomicBool>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl ControllableVps {
+    /// Serve on the current runtime
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let vps = Self {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            healthy: Arc::new(AtomicBool::new(true)),
+            calls: Arc::new(AtomicUsize::new(0)),
+        };
+        let app = Router::new()
+            .route("/health", get(|State(vps): State<ControllableVps>| async move {
+                if vps.is_healthy() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE }
+            }))
+            .route("/v1/process", post(|State(vps): State<ControllableVps>, Json(request): Json<VpsProcessingRequest>| async move {
+                if !vps.is_healthy() {
+                    return Err(StatusCode::SERVICE_UNAVAILABLE);
+                }
+                vps.calls.fetch_add(1, Ordering::Relaxed);
+                Ok(Json(completed(&request)))
+            }))
+            .with_state(vps.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        vps
+    }
+
+    pub fn set_healthy(&self, healthy: bool) {
+        self.healthy.store(healthy, Ordering::Relaxed);
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Events this VPS processed successfully
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+/// Poll `condition` every 10ms until it holds, failing the test after 5s
+pub async fn eventually(what: &str, mut condition: impl FnMut() -> bool) {
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !condition() {
+        assert!(tokio::time::Instant::now() < deadline, "timed out waiting for {}", what);
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+}
+
+/// Thinking-AI event at `ts`: a 10s visit to front_door by track_a, no
+/// doorbell or knock, even odds the home is empty and no evidence. Override
+/// what a test cares about, then `build()`.
+pub fn event(ts: f64) -> EventBuilder {
+    EventBuilder(Event {
+        ts,
+        cam: "front_door".to_string(),
+        person_track: "track_a".to_string(),
+        rang_doorbell: false,
+        knocked: false,
+        dwell_s: 10.0,
+        away_prob: 0.5,
+        expected_window: false,
+        token: None,
+        evidence: Evidence::default(),
+    })
+}
+
+pub struct EventBuilder(Event);
+
+impl EventBuilder {
+    pub fn cam(mut self, cam: &str) -> Self {
+        self.0.cam = cam.to_string();
+        self
+    }
+
+    pub fn track(mut self, track: &str) -> Self {
+        self.0.person_track = track.to_string();
+        self
+    }
+
+    pub fn doorbell(mut self, rang: bool) -> Self {
+        self.0.rang_doorbell = rang;
+        self
+    }
+
+    pub fn dwell(mut self, secs: f64) -> Self {
+        self.0.dwell_s = secs;
+        self
+    }
+
+    pub fn away(mut self, prob: f64) -> Self {
+        self.0.away_prob = prob;
+        self
+    }
+
+    pub fn token(mut self, code: &str) -> Self {
+        self.0.token = Some(code.to_string());
+        self
+    }
+
+    pub fn evidence(mut self, evidence: Evidence) -> Self {
+        self.0.evidence = evidence;
+        self
+    }
+
+    pub fn build(self) -> Event {
+        self.0
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tamper_tests {
+    use crate::tamper::{TamperDetector, TamperKind};
+    use chrono::{Duration, TimeZone, Utc};
+    use std::io::Cursor;
+
+    fn png(pixel: impl Fn(u32, u32) -> u8) -> Vec<u8> {
+        let img = image::GrayImage::from_fn(64, 64, |x, y| image::Luma([pixel(x, y)]));
+        let mut out = Vec::new();
+        image::DynamicImage::ImageLuma8(img)
+            .write_to(&mut Cursor::new(&mut out), image::ImageOutputFormat::Png)
+            .unwrap();
+        out
+    }
+
+    // Left-to-right gradient with a bright block, like a wall and a door
+    fn scene(x: u32, y: u32) -> u8 {
+        let door = if (40..52).contains(&x) && (16..60).contains(&y) { 80 } else { 0 };
+        (x * 2 + door) as u8
+    }
+
+    #[test]
+    fn test_covering_is_confirmed_and_discounts_the_camera() {
+        let detector = TamperDetector::default();
+        let t0 = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        assert!(detector.check("home_1", "cam_door", &png(scene), t0).unwrap().baseline);
+        assert_eq!(detector.check("home_1", "cam_door", &png(scene), t0).unwrap().finding, None);
+
+        let first = detector.check("home_1", "cam_door", &png(|_, _| 10), t0 + Duration::minutes(5)).unwrap();
+        assert_eq!(first.finding, Some(TamperKind::Covered));
+        assert!(first.raised.is_none());
+        let second = detector.check("home_1", "cam_door", &png(|_, _| 10), t0 + Duration::minutes(10)).unwrap();
+        assert_eq!(second.raised.unwrap().kind, TamperKind::Covered);
+        assert_eq!(detector.reliability_factors("home_1").get("cam_door"), Some(&detector.config().tampered_reliability));
+
+        detector.clear("home_1", "cam_door", false).unwrap();
+        assert!(detector.alerts("home_1").is_empty());
+        assert!(detector.reliability_factors("home_1").is_empty());
+    }
+
+    #[test]
+    fn test_blinding_and_moved_camera_are_told_apart() {
+        let detector = TamperDetector::default();
+        let t0 = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        detector.check("home_1", "cam_yard", &png(scene), t0).unwrap();
+
+        let blinded = detector.check("home_1", "cam_yard", &png(|_, _| 252), t0).unwrap();
+        assert_eq!(blinded.finding, Some(TamperKind::Blinded));
+
+        let flipped = |x: u32, y: u32| scene(63 - x, y);
+        assert_eq!(detector.check("home_1", "cam_yard", &png(flipped), t0).unwrap().finding, Some(TamperKind::ViewpointShift));
+
+        // Brighter light on the same scene is not a change
+        let brighter = |x: u32, y: u32| scene(x, y).saturating_add(40);
+        assert_eq!(detector.check("home_1", "cam_yard", &png(brighter), t0).unwrap().finding, None);
+        assert!(!detector.due("home_1", "cam_yard", t0 + Duration::minutes(1)));
+    }
+}
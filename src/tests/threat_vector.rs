@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod threat_vector_tests {
+    use crate::core::{ThreatContext, ThreatVector};
+    use crate::escalation_rules::{EscalationEngine, VectorFloorRule};
+    use crate::prediction::ThreatPrediction;
+    use crate::tests::support;
+    use crate::thinking::{register_channel, AlertDecision, ChannelDescriptor, Event, Evidence, Incident, MoClusterIndex, ThinkingAIConfig, ThinkingAIProcessor};
+    use chrono::Utc;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Arc;
+
+    fn event(ts: f64, cam: &str, rang_doorbell: bool, evidence: Evidence) -> Event {
+        support::event(ts).cam(cam).track("track_1").doorbell(rang_doorbell).dwell(30.0).evidence(evidence).build()
+    }
+
+    fn incident(events: Vec<Event>) -> Incident {
+        let mut incident = Incident::new(1, 1000.0, "track_1".to_string());
+        for ev in events {
+            incident.add_event(ev);
+        }
+        incident
+    }
+
+    fn context(threat_vector: Option<ThreatVector>) -> ThreatContext {
+        ThreatContext {
+            entity_id: uuid::Uuid::new_v4(),
+            threat_indicators: HashMap::new(),
+            environmental_factors: HashMap::new(),
+            temporal_context: Utc::now(),
+            confidence: 0.4,
+            zone_class: None,
+            occupancy: None,
+            threat_vector,
+        }
+    }
+
+    #[test]
+    fn test_names_are_stable_and_legacy_names_parse() {
+        assert_eq!(serde_json::to_string(&ThreatVector::PackageTheft).unwrap(), "\"package_theft\"");
+        for vector in ThreatVector::ALL {
+            let json = serde_json::to_string(&vector).unwrap();
+            assert_eq!(serde_json::from_str::<ThreatVector>(&json).unwrap(), vector);
+        }
+        assert_eq!(serde_json::from_str::<ThreatVector>("\"porch_piracy\"").unwrap(), ThreatVector::PackageTheft);
+        assert_eq!("Break-In".parse::<ThreatVector>().unwrap(), ThreatVector::Burglary);
+        assert_eq!(serde_json::from_str::<ThreatVector>("\"arson\"").unwrap(), ThreatVector::Unknown);
+
+        let prediction = ThreatPrediction {
+            threat_level: 0.4,
+            threat_types: vec![ThreatVector::Casing],
+            probability_distribution: HashMap::from([(ThreatVector::Casing, 0.7), (ThreatVector::Burglary, 0.3)]),
+            causal_factors: Vec::new(),
+            intervention_points: Vec::new(),
+        };
+        let json = serde_json::to_value(&prediction).unwrap();
+        assert_eq!(json["threat_types"], serde_json::json!(["casing"]));
+        assert_eq!(json["probability_distribution"]["burglary"], 0.3);
+        let back: ThreatPrediction = serde_json::from_value(json).unwrap();
+        assert_eq!(back.probability_distribution[&ThreatVector::Casing], 0.7);
+    }
+
+    #[test]
+    fn test_classification_follows_the_evidence() {
+        let behavior = Evidence { llr_behavior: 0.8, ..Default::default() };
+        let entry = incident(vec![event(1000.0, "back_door", false, Evidence { llr_entry: 1.0, ..Default::default() })]);
+        assert_eq!(entry.classify_vector(&entry.fused_evidence(1.6, 3.0)), ThreatVector::Burglary);
+
+        let casing = incident(vec![event(1000.0, "front", false, behavior.clone()), event(1030.0, "side_gate", false, behavior.clone())]);
+        assert_eq!(casing.classify_vector(&casing.fused_evidence(1.6, 3.0)), ThreatVector::Casing);
+
+        let trespass = incident(vec![event(1000.0, "side_gate", false, behavior.clone())]);
+        assert_eq!(trespass.classify_vector(&trespass.fused_evidence(1.6, 3.0)), ThreatVector::Trespass);
+
+        let visitor = incident(vec![event(1000.0, "front", true, behavior.clone()), event(1030.0, "side_gate", false, behavior)]);
+        assert_eq!(visitor.classify_vector(&visitor.fused_evidence(1.6, 3.0)), ThreatVector::Unknown);
+
+        let mut descriptor = ChannelDescriptor::new("tv_package_removed", "Package removed", 2.0, 1.0);
+        descriptor.vector = Some(ThreatVector::PackageTheft);
+        register_channel(descriptor).unwrap();
+        let mut evidence = Evidence::default();
+        evidence.custom.insert("tv_package_removed".to_string(), 1.2);
+        let theft = incident(vec![event(1000.0, "porch", true, evidence)]);
+        assert_eq!(theft.classify_vector(&theft.fused_evidence(1.6, 3.0)), ThreatVector::PackageTheft);
+    }
+
+    #[test]
+    fn test_assessment_names_the_pattern() {
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let result = processor.process_event("home_1", event(1000.0, "back_door", false, Evidence { llr_entry: 1.2, ..Default::default() })).unwrap();
+        assert_eq!(result.threat_vector, ThreatVector::Burglary);
+        assert!(result.narrative_summary.contains("Pattern: possible break-in."));
+        let stored = processor.find_incident("home_1", result.incident_id).unwrap();
+        assert_eq!(stored.threat_vector, ThreatVector::Burglary);
+    }
+
+    #[test]
+    fn test_vector_floor_rule_raises_only_its_vector() {
+        let engine = EscalationEngine::new()
+            .with_rule(Arc::new(VectorFloorRule::new(ThreatVector::Burglary, AlertDecision::Elevated)))
+            .with_rule(Arc::new(VectorFloorRule::new(ThreatVector::Vandalism, AlertDecision::Critical)));
+
+        let verdict = engine.apply(&context(Some(ThreatVector::Burglary)), &AlertDecision::Standard);
+        assert_eq!(verdict.decision, AlertDecision::Elevated);
+        assert_eq!(verdict.rule.as_deref(), Some("burglary_floor"));
+        assert!(!verdict.start_escalation);
+
+        let verdict = engine.apply(&context(Some(ThreatVector::Burglary)), &AlertDecision::Critical);
+        assert_eq!((verdict.decision, verdict.rule), (AlertDecision::Critical, None));
+
+        let verdict = engine.apply(&context(Some(ThreatVector::Vandalism)), &AlertDecision::Ignore);
+        assert_eq!(verdict.decision, AlertDecision::Critical);
+        assert!(verdict.start_escalation);
+
+        for other in [None, Some(ThreatVector::Trespass)] {
+            assert_eq!(engine.apply(&context(other), &AlertDecision::Standard).rule, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_counts_incidents_per_vector() {
+        let index = MoClusterIndex::default();
+        for (id, vector) in [(1, ThreatVector::Casing), (2, ThreatVector::Casing), (3, ThreatVector::Burglary)] {
+            let mut inc = incident(vec![event(1000.0 * id as f64, "side_gate", false, Evidence::default())]);
+            inc.id = id;
+            inc.threat_vector = vector;
+            index.record("home_1", &inc).await;
+        }
+
+        let counts = index.threat_vector_counts("home_1").await;
+        assert_eq!(counts.len(), ThreatVector::ALL.len());
+        assert_eq!((counts[&ThreatVector::Casing], counts[&ThreatVector::Burglary], counts[&ThreatVector::Vandalism]), (2, 1, 0));
+        let json: BTreeMap<String, usize> = serde_json::from_value(serde_json::to_value(&counts).unwrap()).unwrap();
+        assert_eq!(json["casing"], 2);
+        assert!(index.threat_vector_counts("home_2").await.values().all(|c| *c == 0));
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tracker_tests {
+    use crate::tracker::{EntityTracker, TrackState, TrackerConfig};
+
+    fn tracker() -> EntityTracker {
+        let tracker = EntityTracker::new(TrackerConfig::default());
+        tracker.set_door_cameras("home_1", ["cam_front_door".to_string()].into_iter().collect());
+        tracker
+    }
+
+    #[test]
+    fn test_visit_moves_through_states() {
+        let tracker = tracker();
+        let states = |update: crate::tracker::TrackUpdate| update.transitions.iter().map(|t| t.to).collect::<Vec<_>>();
+
+        assert_eq!(states(tracker.observe("home_1", "p1", "cam_drive", 0.0, false)), vec![TrackState::Approaching]);
+        assert_eq!(states(tracker.observe("home_1", "p1", "cam_front_door", 20.0, false)), vec![TrackState::AtDoor]);
+        assert!(states(tracker.observe("home_1", "p1", "cam_front_door", 80.0, false)).is_empty());
+        let loitering = tracker.observe("home_1", "p1", "cam_front_door", 260.0, false);
+        assert_eq!(loitering.entity.state, TrackState::Loitering);
+        assert_eq!(loitering.entity.status_line(), "Person has now been at the door for 4 minutes without ringing or knocking");
+        assert_eq!(loitering.dwell_increment_s, Some(60.0)); // Gap capped at max_gap_dwell_s
+
+        assert_eq!(states(tracker.observe("home_1", "p1", "cam_drive", 280.0, false)), vec![TrackState::Departing]);
+        let lost = tracker.sweep(400.0);
+        assert_eq!(lost.len(), 1);
+        assert_eq!(lost[0].to, TrackState::Lost);
+        assert!(tracker.active("home_1").is_empty());
+        assert_eq!(tracker.recent_transitions("home_1").len(), 5);
+    }
+
+    #[test]
+    fn test_ringing_the_bell_is_not_loitering() {
+        let tracker = tracker();
+        tracker.observe("home_1", "p2", "cam_front_door", 0.0, true);
+        let update = tracker.observe("home_1", "p2", "cam_front_door", 240.0, false);
+        assert_eq!(update.entity.state, TrackState::AtDoor);
+        assert_eq!(update.entity.status_line(), "Person has now been at the door for 4 minutes");
+    }
+
+    #[test]
+    fn test_lost_track_seen_again_starts_new_visit() {
+        let tracker = tracker();
+        tracker.observe("home_1", "p3", "cam_drive", 0.0, false);
+        tracker.sweep(200.0);
+        let update = tracker.observe("home_1", "p3", "cam_drive", 500.0, false);
+        assert_eq!(update.entity.state, TrackState::Approaching);
+        assert_eq!(update.entity.dwell_s(), 0.0);
+        assert_eq!(update.dwell_increment_s, None);
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod two_phase_alerts_tests {
+    use crate::delivery::{AlertPhase, AlertRef, CooldownVerdict, Notification, NotificationPreferences, NotificationRouter, NotificationSeverity, RouteDecision};
+    use crate::overnight::DeliveryChannel;
+    use crate::tests::support;
+    use crate::thinking::{Evidence, ThinkingAIConfig, ThinkingAIProcessor};
+    use chrono::{Duration, TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn notification(alert: AlertRef, severity: NotificationSeverity) -> Notification {
+        Notification {
+            home_id: "home_1".to_string(),
+            severity,
+            title: "Elevated alert on back_door".to_string(),
+            body: String::new(),
+            created_at: Utc::now(),
+            incident_id: None,
+            zone: Some("back_door".to_string()),
+            probability: Some(0.4),
+            entity: None,
+            entity_status: None,
+            thumbnail_url: None,
+            alert: Some(alert),
+        }
+    }
+
+    #[test]
+    fn test_update_replaces_heads_up_as_one_alert() {
+        let router = NotificationRouter::new();
+        router.set_preferences("home_1", None, NotificationPreferences { heads_up: true, ..Default::default() });
+        assert!(router.heads_up_enabled("home_1"));
+        let t0 = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let alert_id = Uuid::new_v4();
+        let recipients = vec![(Some("alice".to_string()), vec![DeliveryChannel::Push, DeliveryChannel::SMS])];
+
+        // The heads-up skips SMS, and counts for the zone's cooldown
+        let heads_up = notification(AlertRef { alert_id, phase: AlertPhase::HeadsUp }, NotificationSeverity::Elevated);
+        let outcome = router.route(&heads_up, &recipients, t0);
+        assert_eq!(outcome.deliveries.len(), 1);
+        assert_eq!(outcome.deliveries[0].channel, DeliveryChannel::Push);
+        assert_eq!(outcome.deliveries[0].decision, RouteDecision::Deliver);
+
+        // Same zone, same severity, seconds later: only an update gets through
+        let update = Notification {
+            incident_id: Some(3),
+            body: "Person tried the back door handle.".to_string(),
+            ..notification(AlertRef { alert_id, phase: AlertPhase::Update }, NotificationSeverity::Elevated)
+        };
+        let outcome = router.route(&update, &recipients, t0 + Duration::seconds(20));
+        assert_eq!(outcome.cooldown, CooldownVerdict::Send);
+        assert!(outcome.deliveries.iter().all(|d| d.decision == RouteDecision::Deliver && d.alert.map(|a| a.alert_id) == Some(alert_id)));
+        let other = notification(AlertRef { alert_id: Uuid::new_v4(), phase: AlertPhase::Update }, NotificationSeverity::Elevated);
+        assert_eq!(router.route(&other, &recipients, t0 + Duration::seconds(30)).cooldown, CooldownVerdict::Suppress);
+
+        let tracked = router.alert(alert_id).unwrap();
+        assert_eq!(tracked.incident_id, Some(3));
+        assert_eq!(tracked.updated_at, Some(t0 + Duration::seconds(20)));
+    }
+
+    #[test]
+    fn test_heads_up_needs_opt_in_and_provisional_score_stores_nothing() {
+        let router = NotificationRouter::new();
+        let heads_up = notification(AlertRef { alert_id: Uuid::new_v4(), phase: AlertPhase::HeadsUp }, NotificationSeverity::Standard);
+        assert!(!router.heads_up_enabled("home_1"));
+        assert!(matches!(router.decide(&heads_up, Some("alice"), &DeliveryChannel::Push, Utc::now()), RouteDecision::Drop(_)));
+
+        let processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        let event = support::event(1_700_000_000.0).cam("back_door").track("track_1").dwell(60.0).away(0.9)
+            .evidence(Evidence { llr_behavior: 2.0, llr_time: 1.0, ..Default::default() })
+            .build();
+        let provisional = processor.provisional_assessment("home_1", &event);
+        assert_eq!(provisional.incident_id, None);
+        assert!(processor.home_incidents("home_1").is_empty());
+
+        let result = processor.process_event("home_1", event).unwrap();
+        assert!((result.calibrated_probability - provisional.probability).abs() < 1e-9);
+    }
+}
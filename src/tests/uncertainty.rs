@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod uncertainty_tests {
+    use crate::onboarding::SensitivityProfile;
+    use crate::tests::support;
+    use crate::thinking::uncertainty::{hold, interval};
+    use crate::thinking::{
+        logit, sigmoid, AlertDecision, Event, Evidence, ProbabilityCalibrator, ThinkingAIConfig, ThinkingAIProcessor, UncertaintyPolicy,
+    };
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct Uncalibrated;
+
+    impl ProbabilityCalibrator for Uncalibrated {
+        fn calibrate(&self, raw_logit: f64) -> f64 {
+            sigmoid(raw_logit)
+        }
+    }
+
+    fn event(ts: f64) -> Event {
+        support::event(ts).cam("cam_back").track("track_1").dwell(40.0).away(0.9)
+            .evidence(Evidence { llr_behavior: 1.2, llr_entry: 0.6, ..Default::default() })
+            .build()
+    }
+
+    #[test]
+    fn test_interval_straddling_threshold_is_held() {
+        let policy = UncertaintyPolicy::for_profile(SensitivityProfile::Balanced);
+        let threshold = 0.15;
+
+        // Just over the threshold with a wide interval: most of the doubt is below it
+        let wide = interval(&Uncalibrated, logit(threshold) + 0.1, 0.5, threshold, 0.95);
+        assert!(wide.lower < threshold && wide.upper > threshold);
+        assert!((wide.below_threshold_share - 0.42).abs() < 0.01);
+        assert!(hold(&policy, &AlertDecision::Standard, &wide));
+        assert!(!hold(&policy, &AlertDecision::Critical, &wide));
+        assert!(!hold(&UncertaintyPolicy::for_profile(SensitivityProfile::Vigilant), &AlertDecision::Standard, &wide));
+
+        // The same score measured tightly alerts
+        let tight = interval(&Uncalibrated, logit(threshold) + 0.1, 0.02, threshold, 0.95);
+        assert!(tight.below_threshold_share < 0.01);
+        assert!(!hold(&policy, &AlertDecision::Standard, &tight));
+    }
+
+    #[test]
+    fn test_processor_waits_instead_of_alerting() {
+        let mut processor = ThinkingAIProcessor::new(ThinkingAIConfig::default());
+        processor.set_calibrator(Arc::new(Uncalibrated));
+        let lenient = UncertaintyPolicy { enabled: false, ..UncertaintyPolicy::default() };
+        let strict = UncertaintyPolicy { max_below_threshold: 0.2, hold_critical: true, ..UncertaintyPolicy::default() };
+        processor.set_uncertainty_policy("home_a", lenient);
+        processor.set_uncertainty_policy("home_b", strict);
+
+        let baseline = processor.process_event("home_a", event(1000.0)).unwrap();
+        // Put both thresholds just under the score so any decision is an alert
+        let threshold = logit(baseline.calibrated_probability) - 0.05;
+        processor.set_alert_threshold("home_a", threshold);
+        processor.set_alert_threshold("home_b", threshold);
+
+        let alerted = processor.reassess_incident("home_a", baseline.incident_id).unwrap();
+        assert!(matches!(alerted.alert_decision, AlertDecision::Standard | AlertDecision::Elevated | AlertDecision::Critical));
+        assert_eq!(alerted.interval.held, None);
+
+        let held = processor.process_event("home_b", event(1000.0)).unwrap();
+        assert_eq!(held.alert_decision, AlertDecision::Wait);
+        assert_eq!(held.interval.held, Some(alerted.alert_decision));
+        assert!(held.narrative_summary.contains("re-check"));
+    }
+}
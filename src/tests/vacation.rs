@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod vacation_tests {
+    use crate::vacation::{PresenceSchedule, VacationError, VacationRegistry, VacationRequest};
+    use chrono::{Duration, NaiveTime, TimeZone, Utc};
+
+    fn request() -> VacationRequest {
+        VacationRequest {
+            starts_at: None,
+            ends_at: None,
+            interior_zones: vec!["hallway_motion".to_string()],
+            timezone: "Europe/London".to_string(),
+            presence_simulation: vec![PresenceSchedule {
+                device: "living_room_lights".to_string(),
+                on_at: NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+                off_at: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+                jitter_minutes: 20,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_activation_window_and_zones() {
+        let registry = VacationRegistry::default();
+        let now = Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap();
+
+        let mut scheduled = request();
+        scheduled.starts_at = Some(now + Duration::days(1));
+        scheduled.ends_at = Some(now + Duration::days(8));
+        let mode = registry.activate("home_1", scheduled, now).unwrap();
+        assert!(registry.active("home_1", now).is_none());
+        assert!(registry.active("home_1", now + Duration::days(2)).is_some());
+        assert!(registry.active("home_1", now + Duration::days(9)).is_none());
+        assert!(mode.is_interior("hallway_motion"));
+        assert!(!mode.is_interior("front_door"));
+
+        let mut reversed = request();
+        reversed.ends_at = Some(now - Duration::hours(1));
+        assert_eq!(registry.activate("home_2", reversed, now), Err(VacationError::InvalidWindow));
+
+        assert!(registry.deactivate("home_1").is_some());
+        assert!(registry.get("home_1").is_none());
+    }
+
+    #[test]
+    fn test_presence_actions_follow_local_time_with_jitter() {
+        let registry = VacationRegistry::default();
+        let start = Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+        registry.activate("home_1", request(), start).unwrap();
+
+        // One local day: lights on after 19:00 BST and off after 23:00 BST
+        let actions = registry.due_actions(start, start + Duration::days(1));
+        assert_eq!(actions.len(), 2);
+        let on = &actions[0];
+        assert!(on.on);
+        let on_earliest = Utc.with_ymd_and_hms(2024, 7, 1, 18, 0, 0).unwrap();
+        assert!(on.at >= on_earliest && on.at <= on_earliest + Duration::minutes(20));
+        assert!(!actions[1].on);
+
+        // Ticks that split the day still fire each switch exactly once
+        let mut ticked = Vec::new();
+        let mut since = start;
+        while since < start + Duration::days(1) {
+            let now = since + Duration::minutes(7);
+            ticked.extend(registry.due_actions(since, now));
+            since = now;
+        }
+        assert_eq!(ticked, actions);
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod visitor_tokens_tests {
+    use crate::visitor_tokens::{extract_access_code, IssueTokenRequest, TokenVerification, VisitorTokenConfig, VisitorTokenStore};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn request(max_uses: Option<u32>) -> IssueTokenRequest {
+        IssueTokenRequest {
+            label: "Dog walker".to_string(),
+            valid_from: None,
+            valid_until: Utc::now() + Duration::hours(1),
+            max_uses,
+        }
+    }
+
+    #[test]
+    fn test_code_is_time_boxed_and_opens_suppression() {
+        let store = VisitorTokenStore::default();
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let issued = store.issue_at("home_1", IssueTokenRequest {
+            label: "Grocery delivery".to_string(),
+            valid_from: Some(now + Duration::hours(1)),
+            valid_until: now + Duration::hours(3),
+            max_uses: Some(1),
+        }, now).unwrap();
+        assert_eq!(issued.code.len(), 6);
+
+        let early = store.verify("home_1", &issued.code, now);
+        assert_eq!(early, TokenVerification::NotYetValid { token_id: issued.token.id });
+        assert!(store.verify("home_2", &issued.code, now + Duration::hours(2)) == TokenVerification::Unknown);
+
+        let at = now + Duration::hours(2);
+        let evidence = store.evidence_for("home_1", &issued.code, at);
+        assert_eq!(evidence.token_id, Some(issued.token.id));
+        assert!(evidence.identity_llr.unwrap() < -2.0);
+        store.open_suppression("home_1", &evidence, "front_door", "entity_4", at);
+
+        // Follow-up events of the same person on the same camera inherit the verified evidence for a while
+        let follow_up = store.suppression_evidence("home_1", "front_door", "entity_4", at + Duration::minutes(5)).unwrap();
+        assert!(follow_up.from_suppression);
+        assert!(store.suppression_evidence("home_1", "front_door", "entity_4", at + Duration::minutes(30)).is_none());
+
+        // ...but not anyone else, nor the same person elsewhere
+        assert!(store.suppression_evidence("home_1", "front_door", "entity_9", at + Duration::minutes(5)).is_none());
+        assert!(store.suppression_evidence("home_1", "back_door", "entity_4", at + Duration::minutes(5)).is_none());
+
+        // Single-use code can't be replayed
+        let replay = store.evidence_for("home_1", &issued.code, at + Duration::minutes(1));
+        assert!(replay.token_id.is_none() && replay.token_llr > 0.0);
+        assert_eq!(store.get("home_1", issued.token.id).unwrap().uses, 1);
+    }
+
+    #[test]
+    fn test_revoked_code_is_rejected() {
+        let store = VisitorTokenStore::default();
+        let issued = store.issue("home_1", request(None)).unwrap();
+        store.revoke("home_1", issued.token.id).unwrap();
+        assert!(matches!(store.verify("home_1", &issued.code, Utc::now()), TokenVerification::Revoked { .. }));
+        assert_eq!(extract_access_code(r#"{"keypad_code": " 042911 "}"#), Some("042911".to_string()));
+    }
+
+    #[test]
+    fn test_wrong_codes_lock_the_home_out() {
+        let store = VisitorTokenStore::default();
+        let issued = store.issue("home_1", request(None)).unwrap();
+        let wrong = if issued.code == "000000" { "000001" } else { "000000" };
+        let now = Utc::now();
+        for _ in 0..store.config().max_failed_codes {
+            assert_eq!(store.verify("home_1", wrong, now), TokenVerification::Unknown);
+        }
+
+        // Even the right code is refused until the lockout passes; other homes are unaffected
+        assert_eq!(store.verify("home_1", &issued.code, now), TokenVerification::RateLimited);
+        assert_eq!(store.verify("home_2", wrong, now), TokenVerification::Unknown);
+        assert!(store.verify("home_1", &issued.code, now + store.config().lockout).is_valid());
+    }
+
+    #[test]
+    fn test_tokens_survive_restart() {
+        let dir = std::env::temp_dir().join(format!("visitor_tokens_{}", uuid::Uuid::new_v4()));
+        let store = VisitorTokenStore::persistent(VisitorTokenConfig::default(), dir.clone()).unwrap();
+        let issued = store.issue("home_1", request(Some(2))).unwrap();
+        assert!(store.verify("home_1", &issued.code, Utc::now()).is_valid());
+
+        let reloaded = VisitorTokenStore::persistent(VisitorTokenConfig::default(), dir.clone()).unwrap();
+        assert_eq!(reloaded.get("home_1", issued.token.id).unwrap().uses, 1);
+        assert!(reloaded.verify("home_1", &issued.code, Utc::now()).is_valid());
+        assert!(matches!(reloaded.verify("home_1", &issued.code, Utc::now()), TokenVerification::Exhausted { .. }));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod vms_bookmarks_tests {
+    use crate::delivery::{
+        build_request, BookmarkContext, RequestTemplate, VmsAuth, VmsBookmarker, VmsError, VmsIntegrationRequest, VmsProvider,
+    };
+    use crate::thinking::AlertDecision;
+    use axum::{extract::Path, http::StatusCode, routing::post, Router};
+    use chrono::{Duration, TimeZone, Utc};
+    use std::collections::BTreeMap;
+
+    fn request(provider: VmsProvider, base_url: &str) -> VmsIntegrationRequest {
+        VmsIntegrationRequest {
+            name: "Garage NVR".to_string(),
+            provider,
+            base_url: base_url.to_string(),
+            auth: VmsAuth::None,
+            cameras: BTreeMap::from([("back_garden".to_string(), "garden cam".to_string())]),
+            min_decision: AlertDecision::Elevated,
+            record_secs: 90,
+            enabled: true,
+        }
+    }
+
+    fn context(decision: AlertDecision) -> BookmarkContext {
+        BookmarkContext {
+            home_id: "home_1".to_string(),
+            incident_id: 42,
+            camera: "back_garden".to_string(),
+            decision,
+            at: Utc.with_ymd_and_hms(2026, 3, 1, 23, 30, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_requests_are_built_per_provider() {
+        let vms = VmsBookmarker::default();
+        let now = Utc::now();
+        let frigate = vms.create("home_1", request(VmsProvider::Frigate, "http://frigate.local:5000/"), now).unwrap();
+        let built = build_request(&frigate, "garden cam", &context(AlertDecision::Critical));
+        assert_eq!(built.method, "POST");
+        assert_eq!(built.url, "http://frigate.local:5000/api/events/garden%20cam/novin/create");
+        let body: serde_json::Value = serde_json::from_str(built.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["duration"], 90);
+        assert_eq!(body["sub_label"], "Novin Critical alert, incident 42");
+
+        let mut blue_iris = request(VmsProvider::BlueIris, "http://bi.local:81");
+        blue_iris.auth = VmsAuth::Basic { username: "novin".to_string(), password: "s3cret&x".to_string() };
+        let blue_iris = vms.create("home_1", blue_iris, now).unwrap();
+        let built = build_request(&blue_iris, "garden cam", &context(AlertDecision::Critical));
+        assert_eq!(built.method, "GET");
+        assert!(built.url.starts_with("http://bi.local:81/admin?camera=garden%20cam&trigger&memo=Novin%20Critical"));
+        assert!(built.url.ends_with("&user=novin&pw=s3cret%26x"));
+        assert!(built.headers.is_empty());
+        assert!(matches!(vms.list("home_1")[1].integration.auth, VmsAuth::Basic { ref password, .. } if password == "***"));
+
+        // Milestone through the generic template
+        let template = RequestTemplate {
+            method: "post".to_string(),
+            path: "/api/rest/v1/bookmarks?device={camera}".to_string(),
+            headers: BTreeMap::from([("X-Incident".to_string(), "{incident_id}".to_string())]),
+            body: Some(r#"{"header":"{memo}","timeBegin":"{timestamp}","seconds":{duration_secs}}"#.to_string()),
+        };
+        let mut milestone = request(VmsProvider::Generic(template), "https://xprotect.local");
+        milestone.auth = VmsAuth::Bearer { token: "tok".to_string() };
+        let milestone = vms.create("home_1", milestone, now).unwrap();
+        let built = build_request(&milestone, "garden cam", &context(AlertDecision::Elevated));
+        assert_eq!(built.method, "POST");
+        assert_eq!(built.url, "https://xprotect.local/api/rest/v1/bookmarks?device=garden%20cam");
+        assert!(built.headers.contains(&("Authorization".to_string(), "Bearer tok".to_string())));
+        assert!(built.headers.contains(&("X-Incident".to_string(), "42".to_string())));
+        let body: serde_json::Value = serde_json::from_str(built.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["seconds"], 90);
+        assert_eq!(body["timeBegin"], "2026-03-01T23:30:00+00:00");
+
+        assert!(matches!(vms.create("home_1", request(VmsProvider::Frigate, "ftp://nvr"), now), Err(VmsError::InvalidUrl(_))));
+        let mut quiet = request(VmsProvider::Frigate, "http://nvr");
+        quiet.min_decision = AlertDecision::Wait;
+        assert!(matches!(vms.create("home_1", quiet, now), Err(VmsError::InvalidMinimum)));
+    }
+
+    #[tokio::test]
+    async fn test_bookmarks_once_per_incident_and_tracks_success() {
+        async fn create_event(Path((camera, _label)): Path<(String, String)>) -> StatusCode {
+            if camera == "garden cam" { StatusCode::OK } else { StatusCode::NOT_FOUND }
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/api/events/:camera/:label/create", post(create_event));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let vms = VmsBookmarker::default();
+        let mut config = request(VmsProvider::Frigate, &format!("http://{}", addr));
+        config.cameras.insert("driveway".to_string(), "missing".to_string());
+        let frigate = vms.create("home_1", config, Utc::now()).unwrap();
+
+        // Below the integration's minimum, and cameras it does not map, are left alone
+        assert!(vms.bookmark(&context(AlertDecision::Standard)).await.is_empty());
+        let mut porch = context(AlertDecision::Critical);
+        porch.camera = "porch".to_string();
+        assert!(vms.bookmark(&porch).await.is_empty());
+
+        let records = vms.bookmark(&context(AlertDecision::Elevated)).await;
+        assert_eq!(records.len(), 1);
+        assert!(records[0].success);
+        assert_eq!(records[0].status, Some(200));
+        // Escalating the same incident does not bookmark it again
+        assert!(vms.bookmark(&context(AlertDecision::Critical)).await.is_empty());
+
+        let mut driveway = context(AlertDecision::Critical);
+        driveway.camera = "driveway".to_string();
+        driveway.at += Duration::seconds(10);
+        let failed = vms.bookmark(&driveway).await;
+        assert!(!failed[0].success);
+        assert_eq!(failed[0].status, Some(404));
+
+        let view = &vms.list("home_1")[0];
+        assert_eq!((view.stats.attempts, view.stats.succeeded, view.stats.failed), (2, 1, 1));
+        assert_eq!(view.success_rate, Some(0.5));
+        assert!(view.stats.last_error.is_some());
+        let history = vms.history("home_1", 10);
+        assert_eq!(history[0].camera, "driveway");
+
+        let test = vms.test("home_1", frigate.id, "back_garden", Utc::now()).await.unwrap();
+        assert!(test.success);
+        assert!(matches!(vms.test("home_1", frigate.id, "porch", Utc::now()).await, Err(VmsError::UnmappedCamera(_))));
+    }
+}
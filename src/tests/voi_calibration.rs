@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod voi_calibration_tests {
+    use crate::tests::support;
+    use crate::thinking::{
+        AlertDecision, Event, Incident, PendingQuestions, Question, QuestionKind, QuestionProposal, VoiCalibrator,
+    };
+
+    fn event(cam: &str, rang_doorbell: bool) -> Event {
+        support::event(1000.0).cam(cam).track("t1").doorbell(rang_doorbell).build()
+    }
+
+    fn pending(question: Question) -> PendingQuestions {
+        let mut incident = Incident::new(1, 1000.0, "t1".to_string());
+        incident.add_event(event("cam_front", false));
+        PendingQuestions::new(&incident, AlertDecision::Standard, &[QuestionProposal::new(question, 0.1)])
+    }
+
+    #[test]
+    fn test_answers_are_matched_to_questions() {
+        let doorbell = pending(Question::AwaitDoorbell);
+        assert!(doorbell.answers(QuestionKind::AwaitDoorbell, &event("cam_front", true)));
+        assert!(!doorbell.answers(QuestionKind::AwaitDoorbell, &event("cam_front", false)));
+        assert!(doorbell.answers(QuestionKind::RequestSecondAngle, &event("cam_side", false)));
+        assert!(!doorbell.answers(QuestionKind::RequestSecondAngle, &event("cam_front", false)));
+    }
+
+    #[test]
+    fn test_questions_that_change_decisions_rank_first() {
+        let voi = VoiCalibrator::default();
+        let mut proposals = vec![
+            QuestionProposal::new(Question::RequestSecondAngle { cam: "Cam-2".to_string() }, 0.2),
+            QuestionProposal::new(Question::AwaitDoorbell, 0.1),
+        ];
+        voi.calibrate(&mut proposals);
+        assert!(matches!(proposals[0].q, Question::RequestSecondAngle { .. })); // No history yet
+
+        for _ in 0..30 {
+            voi.record_answers(&pending(Question::AwaitDoorbell), &event("cam_front", true), &AlertDecision::Ignore);
+            voi.record_answers(&pending(Question::RequestSecondAngle { cam: "Cam-2".to_string() }), &event("cam_side", false), &AlertDecision::Standard);
+        }
+        assert!(voi.factor(QuestionKind::AwaitDoorbell) > 1.0);
+        assert!(voi.factor(QuestionKind::RequestSecondAngle) < 1.0);
+
+        voi.calibrate(&mut proposals);
+        assert!(matches!(proposals[0].q, Question::AwaitDoorbell));
+        assert_eq!(proposals[0].expected_entropy_reduction, 0.1);
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod vps_cache_tests {
+    use crate::vps_client::{VpsCacheConfig, VpsProcessingRequest, VpsProcessingResponse, VpsResponseCache};
+    use bytes::Bytes;
+    use std::time::Duration;
+
+    fn request(image: Option<&'static [u8]>, level: &str) -> VpsProcessingRequest {
+        VpsProcessingRequest {
+            api_key: String::new(),
+            event_id: uuid::Uuid::new_v4().to_string(),
+            sensor_data: "person_detected=true".to_string(),
+            image_data: image.map(Bytes::from_static),
+            processing_level: level.to_string(),
+            user_context: "user:u1, home:h1".to_string(),
+        }
+    }
+
+    fn response(job_id: &str) -> VpsProcessingResponse {
+        VpsProcessingResponse {
+            job_id: job_id.to_string(),
+            status: "completed".to_string(),
+            result_url: None,
+            error_message: None,
+            appearance_embedding: None,
+            gait_embedding: None,
+            liveness_score: None,
+            faces: None,
+            from_cache: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identical_image_and_level_hits() {
+        let cache = VpsResponseCache::new(VpsCacheConfig::default());
+        let first = request(Some(b"jpeg-bytes"), "Standard");
+        assert!(cache.get(&first).await.is_none());
+        cache.insert(&first, &response("job_1")).await;
+
+        // A different event carrying the same frame reuses the analysis
+        let hit = cache.get(&request(Some(b"jpeg-bytes"), "Standard")).await.expect("cached");
+        assert_eq!(hit.job_id, "job_1");
+        assert!(hit.from_cache);
+
+        assert!(cache.get(&request(Some(b"jpeg-bytes"), "Premium")).await.is_none());
+        assert!(cache.get(&request(Some(b"other-frame"), "Standard")).await.is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+        assert!((stats.hit_rate - 0.25).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_image_or_disabled_cache_bypass() {
+        let cache = VpsResponseCache::new(VpsCacheConfig::default());
+        let no_image = request(None, "Standard");
+        cache.insert(&no_image, &response("job_1")).await;
+        assert!(cache.get(&no_image).await.is_none());
+
+        let disabled = VpsResponseCache::new(VpsCacheConfig { enabled: false, ..VpsCacheConfig::default() });
+        let with_image = request(Some(b"jpeg-bytes"), "Standard");
+        disabled.insert(&with_image, &response("job_1")).await;
+        assert!(disabled.get(&with_image).await.is_none());
+        assert_eq!(disabled.stats().hits + disabled.stats().misses, 0);
+    }
+
+    #[tokio::test]
+    async fn test_entries_expire_after_ttl() {
+        let cache = VpsResponseCache::new(VpsCacheConfig { ttl: Duration::from_millis(50), ..VpsCacheConfig::default() });
+        let req = request(Some(b"jpeg-bytes"), "Standard");
+        cache.insert(&req, &response("job_1")).await;
+        assert!(cache.get(&req).await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(cache.get(&req).await.is_none());
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod vps_health_tests {
+    use crate::circuit_breaker::CircuitState;
+    use crate::pipeline::{EventPipeline, PipelineConfig};
+    use crate::tests::support::{eventually, ControllableVps};
+    use crate::vps_client::{VpsApiClient, VpsClientConfig};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pollers_open_the_circuit_during_an_outage_and_close_it_after() {
+        let vps = ControllableVps::start().await;
+        let client = VpsApiClient::with_config(vps.url.clone(), VpsClientConfig {
+            health_poll_interval: Duration::from_millis(20),
+            ..VpsClientConfig::default()
+        });
+        let pipeline = EventPipeline::new(PipelineConfig::default(), client);
+        let pollers = pipeline.start_vps_health_pollers();
+        assert_eq!(pollers.len(), 1);
+
+        vps.set_healthy(false);
+        eventually("the circuit to open", || {
+            let status = &pipeline.get_vps_endpoint_status()[0];
+            !status.last_health_ok && !status.available && status.circuit.state == CircuitState::Open
+        }).await;
+
+        vps.set_healthy(true);
+        eventually("the circuit to allow traffic again", || {
+            let status = &pipeline.get_vps_endpoint_status()[0];
+            status.last_health_ok && status.available
+        }).await;
+        assert_eq!(pipeline.get_vps_endpoint_status()[0].circuit.times_opened, 1);
+        pollers.iter().for_each(|p| p.abort());
+    }
+}
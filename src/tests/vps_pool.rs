@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod vps_pool_tests {
+    use crate::pipeline::{EventPipeline, PipelineConfig, RawEvent, SubscriptionTier, LOCAL_FALLBACK_JOB_ID};
+    use crate::tests::support::{eventually, ControllableVps};
+    use crate::vps_client::{parse_endpoints, VpsApiClient, VpsClientConfig, VpsEndpoint, VpsPool, VpsPoolConfig, VpsProcessingRequest};
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn endpoint(url: &str, weight: u32, region: Option<&str>) -> VpsEndpoint {
+        VpsEndpoint { url: url.to_string(), weight, region: region.map(str::to_string) }
+    }
+
+    fn pool(endpoints: Vec<VpsEndpoint>, preferred_region: Option<&str>) -> VpsPool {
+        VpsPool::new(VpsPoolConfig {
+            endpoints,
+            preferred_region: preferred_region.map(str::to_string),
+            client: VpsClientConfig { health_poll_interval: Duration::from_millis(20), ..VpsClientConfig::default() },
+            ..VpsPoolConfig::default()
+        })
+    }
+
+    fn request() -> VpsProcessingRequest {
+        VpsProcessingRequest {
+            api_key: String::new(),
+            event_id: Uuid::new_v4().to_string(),
+            sensor_data: "motion=true".into(),
+            image_data: None,
+            processing_level: "standard".to_string(),
+            user_context: "user:u1".to_string(),
+        }
+    }
+
+    fn pinned_to(pool: &VpsPool, url: &str) -> String {
+        (0..).map(|i| format!("home_{}", i)).find(|h| pool.route_for(h).is_some_and(|e| e.url == url)).unwrap()
+    }
+
+    #[test]
+    fn test_homes_stick_to_endpoints_in_proportion_to_weight() {
+        let pool = pool(vec![
+            endpoint("http://a.test", 1, None),
+            endpoint("http://b.test", 1, None),
+            endpoint("http://c.test", 4, None),
+            endpoint("http://drained.test", 0, None),
+        ], None);
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for i in 0..3000 {
+            let home = format!("home_{}", i);
+            let url = pool.route_for(&home).unwrap().url.clone();
+            assert_eq!(pool.route_for(&home).unwrap().url, url);
+            *counts.entry(url).or_default() += 1;
+        }
+        assert!((1800..2200).contains(&counts["http://c.test"]), "{:?}", counts);
+        assert!(counts["http://a.test"] > 350 && counts["http://b.test"] > 350, "{:?}", counts);
+        assert!(!counts.contains_key("http://drained.test"));
+        assert_eq!(pool.endpoint_status().len(), 3);
+    }
+
+    #[test]
+    fn test_preferred_region_wins_over_weight() {
+        let pool = pool(vec![endpoint("http://eu.test", 1, Some("eu-west")), endpoint("http://us.test", 10, Some("us-east"))], Some("eu-west"));
+        assert!((0..200).all(|i| pool.route_for(&format!("home_{}", i)).unwrap().url == "http://eu.test"));
+    }
+
+    #[test]
+    fn test_endpoints_parse_from_config() {
+        let endpoints = parse_endpoints(r#"[{"url": "https://eu.vps.test", "weight": 2, "region": "eu-west"}, {"url": "https://us.vps.test", "weight": 1, "region": null}]"#).unwrap();
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!((endpoints[0].weight, endpoints[0].region.as_deref()), (2, Some("eu-west")));
+        assert!(parse_endpoints(r#"[{"url": "not a url", "weight": 1, "region": null}]"#).is_err());
+        assert!(parse_endpoints(r#"[{"url": "https://eu.vps.test", "weight": 0, "region": null}]"#).is_err());
+        assert!(parse_endpoints("https://eu.vps.test").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_endpoint_fails_over_and_comes_back() {
+        let (a, b) = (ControllableVps::start().await, ControllableVps::start().await);
+        let pool = pool(vec![endpoint(&a.url, 1, None), endpoint(&b.url, 1, None)], None);
+        let home = pinned_to(&pool, &a.url);
+
+        pool.process_event(&home, request()).await.unwrap();
+        assert_eq!((a.calls(), b.calls()), (1, 0));
+
+        // A request that fails on the pinned endpoint is retried on the next one
+        a.set_healthy(false);
+        pool.process_event(&home, request()).await.unwrap();
+        assert_eq!((a.calls(), b.calls()), (1, 1));
+
+        // Once the health probe opens A's circuit the home goes straight to B, until A recovers
+        let pollers = pool.spawn_health_pollers();
+        eventually("the home to move to B", || pool.route_for(&home).unwrap().url == b.url).await;
+        a.set_healthy(true);
+        eventually("the home to return to A", || pool.route_for(&home).unwrap().url == a.url).await;
+        pollers.iter().for_each(|p| p.abort());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_routes_events_through_the_pool() {
+        let (a, b) = (ControllableVps::start().await, ControllableVps::start().await);
+        let pool = pool(vec![endpoint(&a.url, 1, None), endpoint(&b.url, 1, None)], None);
+        let home = pinned_to(&pool, &b.url);
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new(a.url.clone())).with_vps_pool(pool);
+        let event = |home: &str| RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: "front".to_string(),
+            timestamp: 1_700_000_000,
+            data: r#"{"motion":true}"#.into(),
+            user_id: "user_1".to_string(),
+            home_id: home.to_string(),
+            image_url: None,
+            image_data: None,
+        };
+
+        pipeline.process_event(event(&home), SubscriptionTier::Premium, "key").await.unwrap();
+        assert_eq!((a.calls(), b.calls()), (0, 1));
+        b.set_healthy(false);
+        pipeline.process_event(event(&home), SubscriptionTier::Premium, "key").await.unwrap();
+        assert_eq!((a.calls(), b.calls()), (1, 1));
+        assert_eq!(pipeline.get_vps_endpoint_status().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_processes_locally_when_no_endpoint_is_available() {
+        let vps = ControllableVps::start().await;
+        let pool = pool(vec![endpoint(&vps.url, 1, None)], None);
+        let mut config = PipelineConfig::default();
+        config.overnight_enabled = false;
+        let mut pipeline = EventPipeline::new(config, VpsApiClient::new(vps.url.clone())).with_vps_pool(pool);
+
+        vps.set_healthy(false);
+        let pollers = pipeline.start_vps_health_pollers();
+        eventually("the circuit to open", || !pipeline.get_vps_endpoint_status()[0].available).await;
+
+        let event = RawEvent {
+            event_id: Uuid::new_v4(),
+            sensor_id: "front".to_string(),
+            timestamp: 1_700_000_000,
+            data: r#"{"motion":true}"#.into(),
+            user_id: "user_1".to_string(),
+            home_id: "home_1".to_string(),
+            image_url: None,
+            image_data: None,
+        };
+        let processed = pipeline.process_event(event, SubscriptionTier::Premium, "key").await.unwrap();
+        assert_eq!(processed.vps_job_id, LOCAL_FALLBACK_JOB_ID);
+        assert_eq!(vps.calls(), 0);
+        pollers.iter().for_each(|p| p.abort());
+    }
+}
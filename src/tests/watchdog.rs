@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod watchdog_tests {
+    use crate::thinking::{AlertDecision, Incident, IncidentStatus};
+    use crate::watchdog::{waiting_since, HealthCondition, HealthSeverity, QueueLane, WaitingIncident, Watchdog, WatchdogConfig};
+    use chrono::{Duration, TimeZone, Utc};
+    use std::time::Instant;
+
+    #[test]
+    fn test_stuck_incident_and_stalled_lane_alert_once_until_cleared() {
+        let watchdog = Watchdog::new(WatchdogConfig::default());
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let mut incident = Incident::new(7, (now - Duration::minutes(45)).timestamp() as f64, "track_7".to_string());
+        incident.record_assessment(0.2, 0.4, AlertDecision::Standard, "Person at door");
+        incident.probability_trace.last_mut().unwrap().ts = (now - Duration::minutes(50)).timestamp() as f64;
+        incident.record_assessment(0.1, 0.3, AlertDecision::Wait, "Waiting for more");
+        incident.probability_trace.last_mut().unwrap().ts = (now - Duration::minutes(40)).timestamp() as f64;
+        incident.status = IncidentStatus::Waiting;
+        let since = waiting_since(&incident).unwrap();
+        assert_eq!(since, now - Duration::minutes(40));
+
+        let waiting = vec![WaitingIncident { home_id: "home_1".to_string(), incident_id: 7, waiting_since: since, follow_up_pending: false }];
+        let lanes = vec![
+            QueueLane { name: "follow_ups".to_string(), depth: 3, oldest_at: Some(now - Duration::minutes(10)), max_age: Duration::minutes(2) },
+            QueueLane { name: "narratives_standard".to_string(), depth: 1, oldest_at: Some(now - Duration::hours(1)), max_age: Duration::hours(26) },
+        ];
+        let alerts = watchdog.check(Instant::now(), now, &waiting, &lanes);
+        assert_eq!(alerts.len(), 2);
+        assert!(matches!(alerts[0].condition, HealthCondition::StuckIncident { incident_id: 7, waiting_s: 2400, .. }));
+        assert_eq!(alerts[0].severity, HealthSeverity::Critical);
+        assert!(matches!(&alerts[1].condition, HealthCondition::LaneStalled { lane, depth: 3, .. } if lane == "follow_ups"));
+
+        // Still stuck a minute later: not repeated
+        assert!(watchdog.check(Instant::now(), now + Duration::minutes(1), &waiting, &lanes).is_empty());
+        // Cleared, then stuck again: alerted afresh
+        assert!(watchdog.check(Instant::now(), now + Duration::minutes(2), &[], &[]).is_empty());
+        assert_eq!(watchdog.check(Instant::now(), now + Duration::minutes(3), &waiting, &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_missed_ticks_and_clock_jumps_are_reported() {
+        let watchdog = Watchdog::new(WatchdogConfig::default());
+        let every = std::time::Duration::from_secs(10);
+        watchdog.register_job("escalations", every);
+        watchdog.register_job("follow_ups", every);
+        let start = Instant::now();
+        let wall = Utc::now();
+
+        // follow_ups keeps ticking, but its wall clock leaps 5 minutes in 10 seconds
+        watchdog.tick_at("follow_ups", start + every, wall + Duration::seconds(10));
+        watchdog.tick_at("follow_ups", start + every * 2, wall + Duration::seconds(320));
+        watchdog.tick_at("follow_ups", start + every * 4, wall + Duration::seconds(340));
+
+        let alerts = watchdog.check(start + every * 4, wall + Duration::seconds(340), &[], &[]);
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts.iter().any(|a| matches!(&a.condition, HealthCondition::ClockJump { job, jump_s: 300 } if job == "follow_ups")));
+        assert!(alerts.iter().any(|a| matches!(&a.condition, HealthCondition::MissedTicks { job, period_s: 10, .. } if job == "escalations")));
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod webhook_dead_letters_tests {
+    use crate::delivery::{WebhookConfig, WebhookDispatcher, WebhookEndpoint, WebhookEventType};
+    use std::time::Duration;
+
+    fn dispatcher() -> WebhookDispatcher {
+        WebhookDispatcher::new(WebhookConfig {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(1),
+            request_timeout: Duration::from_millis(200),
+            log_capacity: 100,
+        })
+    }
+
+    async fn unreachable_endpoint(dispatcher: &WebhookDispatcher, home_id: &str) -> uuid::Uuid {
+        // Port 9 (discard) is closed on test hosts, so every attempt fails with a network error
+        let endpoint = WebhookEndpoint::new(home_id, "http://127.0.0.1:9/hook".to_string(), "secret".to_string(), vec![WebhookEventType::SnapshotRequest]);
+        dispatcher.register_endpoint(endpoint).await
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_delivery_is_dead_lettered_and_requeued_on_flush() {
+        let dispatcher = dispatcher();
+        let endpoint_id = unreachable_endpoint(&dispatcher, "home_1").await;
+        unreachable_endpoint(&dispatcher, "home_2").await;
+
+        dispatcher.dispatch_snapshot_request("home_1", "front_door", "req_1").await;
+        dispatcher.dispatch_snapshot_request("home_2", "back_yard", "req_2").await;
+
+        let letters = dispatcher.dead_letters(Some("home_1")).await;
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].endpoint_id, endpoint_id);
+        assert_eq!(dispatcher.dead_letters(None).await.len(), 2);
+
+        let results = dispatcher.flush_dead_letters(Some("home_1")).await;
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].success);
+        // Still unreachable, so the letter is back on the queue; home_2's was untouched
+        assert_eq!(dispatcher.dead_letters(Some("home_1")).await.len(), 1);
+        assert_eq!(dispatcher.dead_letters(None).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_flush_discards_letters_for_removed_endpoints() {
+        let dispatcher = dispatcher();
+        let endpoint_id = unreachable_endpoint(&dispatcher, "home_1").await;
+        dispatcher.dispatch_snapshot_request("home_1", "front_door", "req_1").await;
+
+        assert!(dispatcher.remove_endpoint("home_1", endpoint_id).await);
+        assert!(dispatcher.flush_dead_letters(None).await.is_empty());
+        assert!(dispatcher.dead_letters(None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_secret_replaces_secret() {
+        let dispatcher = dispatcher();
+        let endpoint_id = unreachable_endpoint(&dispatcher, "home_1").await;
+
+        let secret = dispatcher.rotate_secret("home_1", endpoint_id).await.expect("endpoint exists");
+        assert_eq!(secret.len(), 64);
+        assert_ne!(secret, "secret");
+        assert_eq!(dispatcher.list_endpoints("home_1").await[0].secret, secret);
+        assert!(dispatcher.rotate_secret("home_2", endpoint_id).await.is_none());
+    }
+}
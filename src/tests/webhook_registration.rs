@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod webhook_registration_tests {
+    use crate::api::webhooks::{delete_webhook, get_delivery_log, list_webhooks, register_webhook, DeliveryLogQuery, RegisterWebhookRequest};
+    use crate::delivery::{validate_webhook_url, WebhookConfig, WebhookDispatcher, WebhookEndpoint, WebhookEventType, WebhookUrlError};
+    use crate::tests::support::{app_state, homeowner};
+    use axum::extract::{Json, Path, Query, State};
+    use axum::http::StatusCode;
+    use axum::response::Redirect;
+    use axum::routing::post;
+    use axum::Router;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn request(url: &str) -> Json<RegisterWebhookRequest> {
+        Json(RegisterWebhookRequest { url: url.to_string(), secret: "super-secret-key-123".to_string(), event_types: Vec::new() })
+    }
+
+    #[tokio::test]
+    async fn test_urls_must_be_https_and_resolve_to_public_addresses() {
+        assert_eq!(validate_webhook_url("http://93.184.216.34/hook").await.unwrap_err(), WebhookUrlError::InsecureScheme);
+        assert!(matches!(validate_webhook_url("not a url").await, Err(WebhookUrlError::Invalid(_))));
+        for url in [
+            "https://127.0.0.1/hook",
+            "https://localhost:8443/hook",
+            "https://10.0.0.5/hook",
+            "https://192.168.1.20/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://0.0.0.0/hook",
+            "https://100.64.1.1/hook",
+            "https://[::1]/hook",
+            "https://[fd00::1]/hook",
+            "https://[fe80::1]/hook",
+            "https://[::ffff:192.168.1.20]/hook",
+        ] {
+            assert!(matches!(validate_webhook_url(url).await, Err(WebhookUrlError::NonPublicAddress { .. })), "{}", url);
+        }
+        assert!(validate_webhook_url("https://93.184.216.34/hook").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handlers_are_scoped_to_the_callers_homes() {
+        let state = app_state().await;
+        let owner = homeowner(&["home_1"]);
+
+        let denied = register_webhook(State(state.clone()), owner.clone(), Path("home_2".to_string()), request("https://93.184.216.34/hook")).await;
+        assert_eq!(denied.err(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(list_webhooks(State(state.clone()), owner.clone(), Path("home_2".to_string())).await.err(), Some(StatusCode::FORBIDDEN));
+        let log = get_delivery_log(State(state.clone()), owner.clone(), Path("home_2".to_string()), Query(DeliveryLogQuery { limit: None })).await;
+        assert_eq!(log.err(), Some(StatusCode::FORBIDDEN));
+
+        let private = register_webhook(State(state.clone()), owner.clone(), Path("home_1".to_string()), request("https://10.0.0.5/hook")).await;
+        assert_eq!(private.err(), Some(StatusCode::BAD_REQUEST));
+
+        let endpoint = register_webhook(State(state.clone()), owner.clone(), Path("home_1".to_string()), request("https://93.184.216.34/hook"))
+            .await.unwrap().0.data;
+        let listed = list_webhooks(State(state.clone()), owner.clone(), Path("home_1".to_string())).await.unwrap().0.data;
+        assert_eq!(listed.len(), 1);
+
+        let other = homeowner(&["home_2"]);
+        assert_eq!(delete_webhook(State(state.clone()), other, Path(("home_1".to_string(), endpoint.id))).await, StatusCode::FORBIDDEN);
+        assert_eq!(delete_webhook(State(state), owner, Path(("home_1".to_string(), endpoint.id))).await, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_deliveries_neither_follow_redirects_nor_connect_to_rebound_hosts() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new()
+            .route("/hook", post(move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+                async { StatusCode::OK }
+            }))
+            .route("/redirect", post(|| async { Redirect::temporary("/hook") }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let dispatcher = WebhookDispatcher::new(WebhookConfig {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(1),
+            request_timeout: Duration::from_millis(500),
+            log_capacity: 100,
+        });
+        let register = |home_id: &str, url: String| {
+            let endpoint = WebhookEndpoint::new(home_id, url, "secret".to_string(), vec![WebhookEventType::Alert]);
+            dispatcher.register_endpoint(endpoint)
+        };
+        // Registered directly, as if the name had resolved publicly when it was validated
+        register("home_1", format!("http://localhost:{}/hook", port)).await;
+        register("home_2", format!("http://127.0.0.1:{}/redirect", port)).await;
+
+        let rebound = dispatcher.dispatch_test("home_1", "Test", "").await;
+        assert!(!rebound[0].success);
+        assert_eq!(rebound[0].status_code, None);
+
+        let redirected = dispatcher.dispatch_test("home_2", "Test", "").await;
+        assert_eq!(redirected[0].status_code, Some(307));
+        assert!(!redirected[0].success);
+        assert_eq!(hits.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod webhook_signing_tests {
+    use crate::delivery::{sign_payload, verify_signature};
+    use chrono::Utc;
+
+    #[test]
+    fn test_signature_round_trip() {
+        let body = br#"{"event_type":"alert","home_id":"home_1"}"#;
+        let header = sign_payload("super-secret-key-123", Utc::now().timestamp(), body);
+
+        assert!(header.starts_with("t="));
+        assert!(verify_signature("super-secret-key-123", &header, body, 300));
+    }
+
+    #[test]
+    fn test_tampered_body_or_wrong_secret_rejected() {
+        let body = br#"{"event_type":"alert","home_id":"home_1"}"#;
+        let header = sign_payload("super-secret-key-123", Utc::now().timestamp(), body);
+
+        assert!(!verify_signature("super-secret-key-123", &header, br#"{"event_type":"alert","home_id":"home_2"}"#, 300));
+        assert!(!verify_signature("another-secret-key-456", &header, body, 300));
+    }
+
+    #[test]
+    fn test_stale_signature_rejected() {
+        let body = b"{}";
+        let header = sign_payload("super-secret-key-123", Utc::now().timestamp() - 3600, body);
+
+        assert!(!verify_signature("super-secret-key-123", &header, body, 300));
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod websocket_commands_tests {
+    use crate::api::websocket::{execute, parse_request, ClientCommand, ClientRequest, CommandErrorCode, CommandReply, WebSocketUpdate};
+    use crate::arming::{ArmingMode, ArmingRegistry};
+    use crate::delivery::{CooldownVerdict, Notification, NotificationRouter, NotificationSeverity};
+    use crate::tests::support::{app_state, homeowner};
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_parse_commands() {
+        let request = parse_request(r#"{"request_id":"r1","command":"acknowledge_alert","home_id":"home_1","incident_id":7}"#).unwrap();
+        assert_eq!(request.request_id, "r1");
+        assert_eq!(request.command, ClientCommand::AcknowledgeAlert { home_id: "home_1".to_string(), incident_id: 7 });
+
+        let request = parse_request(r#"{"request_id":"r2","command":"set_arming","home_id":"home_1","mode":"away"}"#).unwrap();
+        assert_eq!(request.command, ClientCommand::SetArming { home_id: "home_1".to_string(), mode: ArmingMode::Away });
+    }
+
+    #[test]
+    fn test_bad_requests_echo_the_request_id() {
+        let error = parse_request(r#"{"request_id":"r3","command":"self_destruct"}"#).unwrap_err();
+        assert_eq!(error.request_id.as_deref(), Some("r3"));
+        assert_eq!(error.code, CommandErrorCode::BadRequest);
+
+        let error = parse_request("not json").unwrap_err();
+        assert!(error.request_id.is_none());
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "bad_request");
+    }
+
+    #[test]
+    fn test_reply_envelope() {
+        let reply = CommandReply { request_id: "r4".to_string(), data: serde_json::json!({ "ok": true }) };
+        let json = serde_json::to_value(&reply).unwrap();
+        assert_eq!(json["type"], "reply");
+        assert_eq!(json["request_id"], "r4");
+    }
+
+    #[test]
+    fn test_arming_modes() {
+        let arming = ArmingRegistry::default();
+        assert!(arming.alerts_enabled("home_1"));
+        assert_eq!(arming.away_prob("home_1"), None);
+
+        arming.set("home_1", ArmingMode::Disarmed, "alex", Utc::now());
+        assert!(!arming.alerts_enabled("home_1"));
+
+        arming.set("home_1", ArmingMode::Away, "alex", Utc::now());
+        assert!(arming.alerts_enabled("home_1"));
+        assert_eq!(arming.away_prob("home_1"), Some(0.95));
+        assert_eq!(arming.get("home_1").unwrap().changed_by, "alex");
+    }
+
+    #[test]
+    fn test_acknowledged_incident_only_alerts_on_escalation() {
+        let router = NotificationRouter::new();
+        let now = Utc::now();
+        let notification = |severity| Notification {
+            home_id: "home_1".to_string(),
+            severity,
+            title: "Alert".to_string(),
+            body: String::new(),
+            created_at: now,
+            incident_id: Some(7),
+            zone: None,
+            probability: None,
+            entity: None,
+            entity_status: None,
+            thumbnail_url: None,
+            alert: None,
+        };
+
+        router.acknowledge("home_1", 7, NotificationSeverity::Elevated);
+        // Past every cooldown window, so only the acknowledgement can hold it back
+        let later = now + Duration::days(1);
+        assert_eq!(router.check_cooldown(&notification(NotificationSeverity::Elevated), later), CooldownVerdict::Suppress);
+        assert_eq!(router.check_cooldown(&notification(NotificationSeverity::Critical), later), CooldownVerdict::Send);
+        assert_eq!(router.suppressed_count("home_1", 7), 1);
+    }
+
+    #[tokio::test]
+    async fn test_commands_are_scoped_to_the_callers_homes() {
+        let state = app_state().await;
+        let commands = [
+            ClientCommand::SetArming { home_id: "home_1".to_string(), mode: ArmingMode::Disarmed },
+            ClientCommand::AcknowledgeAlert { home_id: "home_1".to_string(), incident_id: 7 },
+            ClientCommand::RequestSnapshot { home_id: "home_1".to_string(), camera: "front_door".to_string() },
+        ];
+        for command in commands {
+            let request = ClientRequest { request_id: "r5".to_string(), command };
+            let error = execute(&state, &homeowner(&["home_2"]), request).await.unwrap_err();
+            assert_eq!(error.code, CommandErrorCode::Forbidden);
+        }
+        assert!(state.arming.get("home_1").is_none());
+
+        let request = ClientRequest { request_id: "r6".to_string(), command: ClientCommand::SetArming { home_id: "home_1".to_string(), mode: ArmingMode::Disarmed } };
+        assert!(execute(&state, &homeowner(&["home_1"]), request).await.is_ok());
+
+        // Pushed updates are filtered on the same home id
+        let update = WebSocketUpdate::ArmingChanged { home_id: "home_1".to_string(), mode: ArmingMode::Away, by: "alex".to_string() };
+        assert!(homeowner(&["home_1"]).can_access_home(update.home_id()));
+        assert!(!homeowner(&["home_2"]).can_access_home(update.home_id()));
+    }
+}
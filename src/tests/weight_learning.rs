@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod weight_learning_tests {
+    use crate::thinking::{Evidence, IncidentLabel, OnlineWeightLearner, OutcomeSource, WeightLearnerConfig};
+    use chrono::Utc;
+
+    fn evidence(behavior: f64, identity: f64) -> Evidence {
+        Evidence { llr_time: 0.0, llr_entry: 0.0, llr_behavior: behavior, llr_identity: identity, llr_presence: 0.0, llr_token: 0.0, ..Default::default() }
+    }
+
+    #[test]
+    fn test_benign_feedback_shrinks_misleading_channel_for_that_home() {
+        let learner = OnlineWeightLearner::new(WeightLearnerConfig::default());
+        // Gardener at home_1 keeps tripping the behavior channel
+        for _ in 0..50 {
+            learner.record_outcome("home_1", &evidence(1.5, 0.0), -2.0, IncidentLabel::Benign, OutcomeSource::UserFeedback, Utc::now());
+        }
+        let home = learner.weights("home_1");
+        assert!(home.behavior < 0.9, "behavior weight {}", home.behavior);
+        assert!((home.identity - 1.0).abs() < 1e-9, "untouched channel moved");
+
+        // A home without feedback uses the population, which moves less than home_1
+        let other = learner.weights("home_2");
+        assert!(other.behavior > home.behavior);
+    }
+
+    #[test]
+    fn test_learned_weights_persist() {
+        let path = std::env::temp_dir().join(format!("llr_weights_{}.json", uuid::Uuid::new_v4()));
+        let learner = OnlineWeightLearner::new(WeightLearnerConfig::default());
+        learner.record_outcome("home_1", &evidence(0.5, 1.0), -2.0, IncidentLabel::Threat, OutcomeSource::OperatorDisposition, Utc::now());
+        learner.save(&path).unwrap();
+
+        let restored = OnlineWeightLearner::load(WeightLearnerConfig::default(), &path).unwrap();
+        assert_eq!(restored.weights("home_1"), learner.weights("home_1"));
+        assert_eq!(restored.learned("home_1").unwrap().updates, 1);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod what_if_tests {
+    use crate::tests::support;
+    use crate::thinking::{compare_configs, Evidence, Incident, IncidentLabel, IncidentSnapshotEntry, ThinkingAIConfig};
+    use std::collections::HashMap;
+
+    fn entry(id: u64, behavior: f64, identity: f64) -> IncidentSnapshotEntry {
+        let ts = 1_700_000_000.0 + id as f64 * 3600.0;
+        let mut incident = Incident::new(id, ts, format!("track_{}", id));
+        incident.add_event(support::event(ts).track(&format!("track_{}", id)).dwell(30.0)
+            .evidence(Evidence { llr_behavior: behavior, llr_identity: identity, ..Default::default() })
+            .build());
+        IncidentSnapshotEntry { home: "home_1".to_string(), person_session: format!("track_{}", id), incident }
+    }
+
+    #[test]
+    fn test_raising_threshold_drops_false_positive_without_missing_threat() {
+        let incidents = vec![entry(1, 1.5, 1.0), entry(2, 0.0, 0.0), entry(3, 0.0, -1.5)];
+        let labels: HashMap<u64, IncidentLabel> = [
+            (1, IncidentLabel::Threat),
+            (2, IncidentLabel::Benign),
+            (3, IncidentLabel::Benign),
+        ].into_iter().collect();
+
+        let baseline = ThinkingAIConfig::default();
+        let candidate = ThinkingAIConfig { alert_threshold_logit: -1.0986, ..ThinkingAIConfig::default() }; // logit(0.25)
+
+        let report = compare_configs(&incidents, &labels, &baseline, &candidate);
+        assert_eq!(report.baseline.alerted_incidents, 2);
+        assert_eq!(report.baseline.false_positives, 1);
+        assert_eq!(report.candidate.alerted_incidents, 1);
+        assert_eq!(report.false_positive_delta, -1);
+        assert_eq!(report.missed_alert_delta, 0);
+        assert_eq!(report.no_longer_alerted, vec![2]);
+    }
+}
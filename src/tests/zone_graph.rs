@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod zone_graph_tests {
+    use crate::zone_graph::{ZoneGraph, ZoneGraphConfig};
+    use std::collections::HashMap;
+
+    fn graph() -> ZoneGraph {
+        let graph = ZoneGraph::new(ZoneGraphConfig { min_transitions: 20, ..ZoneGraphConfig::default() });
+        graph.set_layout("home_1", HashMap::from([
+            ("cam_gate".to_string(), "Front gate".to_string()),
+            ("cam_porch".to_string(), "Porch".to_string()),
+            ("cam_street".to_string(), "Street".to_string()),
+            ("cam_garden".to_string(), "Back garden".to_string()),
+        ]));
+        graph
+    }
+
+    #[test]
+    fn test_rare_path_scores_behavior_evidence_once_learned() {
+        let graph = graph();
+        for i in 0..30 {
+            let track = format!("visitor_{}", i);
+            let ts = i as f64 * 1000.0;
+            graph.observe("home_1", &track, "cam_street", ts);
+            graph.observe("home_1", &track, "cam_gate", ts + 10.0);
+            let step = graph.observe("home_1", &track, "cam_porch", ts + 20.0).unwrap();
+            if i >= 20 {
+                assert_eq!(step.behavior_llr, 0.0);
+            }
+        }
+
+        let step = graph.observe("home_1", "prowler", "cam_street", 50_000.0);
+        assert!(step.is_none());
+        let step = graph.observe("home_1", "prowler", "cam_garden", 50_010.0).unwrap();
+        assert_eq!((step.from.as_str(), step.to.as_str()), ("Street", "Back garden"));
+        assert!(step.probability < 0.05);
+        assert!(step.behavior_llr > 0.0 && step.behavior_llr <= graph.config().max_llr);
+
+        let view = graph.view("home_1");
+        assert!(view.scoring);
+        assert_eq!(view.transitions, 61);
+        assert_eq!(view.edges[0].count, 30);
+    }
+
+    #[test]
+    fn test_no_evidence_before_history_or_across_long_gaps() {
+        let graph = graph();
+        graph.observe("home_1", "t1", "cam_street", 0.0);
+        assert_eq!(graph.observe("home_1", "t1", "cam_garden", 10.0).unwrap().behavior_llr, 0.0);
+        assert!(graph.observe("home_1", "t1", "cam_porch", 10.0 + graph.config().max_gap_s + 1.0).is_none());
+        assert!(!graph.view("home_1").scoring);
+    }
+}
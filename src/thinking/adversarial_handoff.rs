@@ -0,0 +1,136 @@
+//! Adversarial handoff
+//!
+//! Incidents whose probability enters a configured band are forwarded once to
+//! an adversarial analyzer (reconnaissance, deception, repeated passes). Its
+//! findings come back as a bounded LLR adjustment on the behavior channel,
+//! recommended countermeasures, and a line in the incident narrative. The
+//! band is half-open, [lower, upper): incidents already above `upper` alert
+//! on their own and are not held up by the extra analysis.
+
+use super::{Evidence, Incident};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandoffConfig {
+    pub lower: f64,               // Inclusive
+    pub upper: f64,               // Exclusive
+    pub rehandoff_delta: f64,     // Forward again once probability rises this much
+    pub max_adjustment_llr: f64,  // Bound on what the analyzer may add or remove
+    pub timeout: Duration,
+}
+
+impl Default for HandoffConfig {
+    fn default() -> Self {
+        Self {
+            lower: 0.15,
+            upper: 0.5,
+            rehandoff_delta: 0.1,
+            max_adjustment_llr: 1.0,
+            timeout: Duration::from_millis(500),
+        }
+    }
+}
+
+impl HandoffConfig {
+    pub fn in_band(&self, probability: f64) -> bool {
+        probability >= self.lower && probability < self.upper
+    }
+
+    /// Whether an incident at `probability` should be (re-)forwarded
+    pub fn should_forward(&self, incident: &Incident, probability: f64) -> bool {
+        if !self.in_band(probability) {
+            return false;
+        }
+        match &incident.adversarial {
+            Some(previous) => probability >= previous.forwarded_at_probability + self.rehandoff_delta,
+            None => true,
+        }
+    }
+}
+
+// What the analyzer sees of an incident
+#[derive(Debug, Clone, Serialize)]
+pub struct HandoffRequest {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub probability: f64,
+    pub evidence: Evidence,
+    pub cameras: Vec<String>,
+    pub event_count: usize,
+    pub total_dwell_s: f64,
+    pub started_at: f64,
+    pub last_updated: f64,
+    pub person_track: String,
+}
+
+impl HandoffRequest {
+    pub fn new(home_id: &str, incident: &Incident, probability: f64, evidence: &Evidence) -> Self {
+        let mut cameras: Vec<String> = incident.cameras.iter().cloned().collect();
+        cameras.sort();
+        Self {
+            home_id: home_id.to_string(),
+            incident_id: incident.id,
+            probability,
+            evidence: evidence.clone(),
+            cameras,
+            event_count: incident.events.len(),
+            total_dwell_s: incident.total_dwell(),
+            started_at: incident.started_at,
+            last_updated: incident.last_updated,
+            person_track: incident.person_session_id.clone(),
+        }
+    }
+}
+
+// Analyzer output, before bounding
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdversarialFindings {
+    pub threat_adjustment_llr: f64, // Positive raises the threat
+    pub countermeasures: Vec<String>,
+    pub summary: String,
+}
+
+// Stored on the incident once findings are applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdversarialAssessment {
+    pub adjustment_llr: f64,
+    pub countermeasures: Vec<String>,
+    pub summary: String,
+    pub forwarded_at_probability: f64,
+}
+
+#[derive(Error, Debug)]
+pub enum HandoffError {
+    #[error("Adversarial analysis timed out")]
+    Timeout,
+    #[error("Adversarial analysis failed: {0}")]
+    Analysis(String),
+}
+
+#[async_trait]
+pub trait AdversarialAnalyzer: Send + Sync {
+    async fn analyze(&self, request: &HandoffRequest) -> Result<AdversarialFindings, HandoffError>;
+}
+
+/// Bound the analyzer's findings before they touch the incident
+pub fn assessment_from(findings: AdversarialFindings, forwarded_at_probability: f64, config: &HandoffConfig) -> AdversarialAssessment {
+    let adjustment = if findings.threat_adjustment_llr.is_finite() { findings.threat_adjustment_llr } else { 0.0 };
+    AdversarialAssessment {
+        adjustment_llr: adjustment.clamp(-config.max_adjustment_llr, config.max_adjustment_llr),
+        countermeasures: findings.countermeasures,
+        summary: findings.summary,
+        forwarded_at_probability,
+    }
+}
+
+/// Forward a request, giving up after the configured timeout
+pub async fn forward(analyzer: &dyn AdversarialAnalyzer, request: &HandoffRequest, config: &HandoffConfig) -> Result<AdversarialAssessment, HandoffError> {
+    let findings = tokio::time::timeout(config.timeout, analyzer.analyze(request))
+        .await
+        .map_err(|_| HandoffError::Timeout)??;
+    Ok(assessment_from(findings, request.probability, config))
+}
@@ -0,0 +1,106 @@
+//! Cross-camera deduplication
+//!
+//! Two cameras covering the same porch each report the visitor under their
+//! own track, which would open two incidents for one person. Homes declare
+//! which cameras have overlapping fields of view; a detection on one of them
+//! that lands within the group's window of a detection on another, under a
+//! different track, is taken to be the same person. Its track is aliased to
+//! the one seen first, so the detection — and the rest of that track — joins
+//! the existing incident instead of starting a new one.
+
+use super::Event;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraOverlap {
+    pub cameras: Vec<String>,
+    #[serde(default = "default_window_s")]
+    pub window_s: f64, // Largest gap between sightings on two of the cameras that is still one person
+}
+
+fn default_window_s() -> f64 {
+    4.0
+}
+
+impl CameraOverlap {
+    pub fn new(cameras: Vec<String>) -> Self {
+        Self { cameras, window_s: default_window_s() }
+    }
+
+    fn covers(&self, a: &str, b: &str) -> bool {
+        a != b && self.cameras.iter().any(|c| c == a) && self.cameras.iter().any(|c| c == b)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Detection {
+    ts: f64,
+    cam: String,
+    track: String, // Already resolved to its canonical track
+}
+
+// Sightings and aliases for one home, locked on their own
+#[derive(Debug, Clone, Default)]
+struct HomeSightings {
+    recent: VecDeque<Detection>,
+    aliases: HashMap<String, (String, f64)>, // track -> canonical track, last seen
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CameraDeduplicator {
+    alias_ttl_s: f64, // Aliases for tracks not seen for this long are dropped
+    overlaps: DashMap<String, Vec<CameraOverlap>>,
+    homes: DashMap<String, HomeSightings>,
+}
+
+impl CameraDeduplicator {
+    pub fn new(alias_ttl_s: f64) -> Self {
+        Self { alias_ttl_s, ..Self::default() }
+    }
+
+    pub fn set_overlaps(&self, home: &str, overlaps: Vec<CameraOverlap>) {
+        if overlaps.is_empty() {
+            self.overlaps.remove(home);
+        } else {
+            self.overlaps.insert(home.to_string(), overlaps);
+        }
+    }
+
+    pub fn overlaps(&self, home: &str) -> Vec<CameraOverlap> {
+        self.overlaps.get(home).map(|o| o.clone()).unwrap_or_default()
+    }
+
+    /// The track `event` should be filed under, when that differs from its own
+    pub fn canonical_track(&self, home: &str, event: &Event) -> Option<String> {
+        let overlaps = self.overlaps.get(home)?;
+        let max_window = overlaps.iter().map(|o| o.window_s).fold(0.0, f64::max);
+        let ttl = self.alias_ttl_s;
+        let mut sightings = self.homes.entry(home.to_string()).or_default();
+        let HomeSightings { recent, aliases } = &mut *sightings;
+        aliases.retain(|_, (_, seen)| event.ts - *seen <= ttl);
+
+        let canonical = match aliases.get(&event.person_track) {
+            Some((canonical, _)) => Some(canonical.clone()),
+            None => recent.iter()
+                .filter(|d| d.track != event.person_track)
+                .filter(|d| overlaps.iter().any(|o| o.covers(&d.cam, &event.cam) && (event.ts - d.ts).abs() <= o.window_s))
+                .min_by(|a, b| (event.ts - a.ts).abs().total_cmp(&(event.ts - b.ts).abs()))
+                .map(|d| d.track.clone()),
+        };
+        if let Some(canonical) = &canonical {
+            aliases.insert(event.person_track.clone(), (canonical.clone(), event.ts));
+        }
+
+        recent.push_back(Detection {
+            ts: event.ts,
+            cam: event.cam.clone(),
+            track: canonical.clone().unwrap_or_else(|| event.person_track.clone()),
+        });
+        while recent.front().is_some_and(|d| event.ts - d.ts > max_window) {
+            recent.pop_front();
+        }
+        canonical
+    }
+}
@@ -1,4 +1,5 @@
 use super::incident_engine::Evidence;
+use super::evidence_channels::channel_descriptor;
 
 #[derive(Clone, Debug)]
 pub struct CounterfactualSuggestion { pub description: String, pub delta_llr: f64 }
@@ -11,6 +12,12 @@ pub fn minimal_changes_to_threshold(fused: &Evidence, prior_logit: f64, threshol
         CounterfactualSuggestion{ description:"Approach via public path".to_string(),        delta_llr:-0.6 },
         CounterfactualSuggestion{ description:"Recognized family/guest".to_string(),         delta_llr:-1.8 },
     ];
+    // A custom channel pointing toward threat could be cleared by whatever its descriptor names
+    for (name, llr) in fused.custom.iter().filter(|(_, llr)| **llr > 0.0) {
+        if let Some(description) = channel_descriptor(name).and_then(|d| d.counterfactual) {
+            candidates.push(CounterfactualSuggestion{ description, delta_llr: -llr });
+        }
+    }
     let mut logit = prior_logit + fused.sum();
     let mut chosen = Vec::new();
     candidates.sort_by(|a,b| a.delta_llr.partial_cmp(&b.delta_llr).unwrap());
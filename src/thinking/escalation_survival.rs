@@ -0,0 +1,230 @@
+//! Time-to-intrusion estimates
+//!
+//! Survival analysis over labeled incidents. For each alert level an incident
+//! reached, the clock starts when it first reached that level and stops at
+//! the first entry attempt: an event with strong entry evidence in an
+//! incident later labeled a threat. Incidents that ended without one, and
+//! benign incidents (residents open doors too), are censored at their last
+//! event. Kaplan–Meier curves per level give the hazard over time, so an
+//! alert can say how likely an entry attempt is within the next few minutes
+//! if nobody intervenes. Levels with too little history fall back to the
+//! curve pooled over all levels, and to no estimate at all below that.
+
+use super::{AlertDecision, Incident, IncidentLabel};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub struct EscalationSurvivalConfig {
+    pub entry_llr_threshold: f64, // Entry-channel LLR that counts as an attempt
+    pub min_samples: usize,       // Incidents behind a curve before it is used
+    pub horizon_secs: f64,        // Window quoted in alert payloads
+}
+
+impl Default for EscalationSurvivalConfig {
+    fn default() -> Self {
+        Self {
+            entry_llr_threshold: 1.0,
+            min_samples: 10,
+            horizon_secs: 600.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    Standard,
+    Elevated,
+    Critical,
+}
+
+impl RiskLevel {
+    pub const ALL: [RiskLevel; 3] = [RiskLevel::Standard, RiskLevel::Elevated, RiskLevel::Critical];
+
+    pub fn from_decision(decision: &AlertDecision) -> Option<Self> {
+        match decision {
+            AlertDecision::Standard => Some(Self::Standard),
+            AlertDecision::Elevated => Some(Self::Elevated),
+            AlertDecision::Critical => Some(Self::Critical),
+            AlertDecision::Ignore | AlertDecision::Wait => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurvivalSample {
+    pub level: RiskLevel,
+    pub duration_secs: f64, // From reaching the level to the attempt, or to censoring
+    pub attempted: bool,    // False when censored
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SurvivalPoint {
+    pub t_secs: f64,
+    pub at_risk: usize,
+    pub attempts: usize,
+    pub hazard: f64,   // attempts / at_risk at this time
+    pub survival: f64, // P(no attempt by t)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HazardCurve {
+    pub level: Option<RiskLevel>, // None for the pooled curve
+    pub samples: usize,
+    pub attempts: usize,
+    pub points: Vec<SurvivalPoint>,
+}
+
+impl HazardCurve {
+    /// Kaplan–Meier estimate from (possibly censored) samples
+    pub fn fit(level: Option<RiskLevel>, samples: &[SurvivalSample]) -> Self {
+        let mut sorted: Vec<&SurvivalSample> = samples.iter().filter(|s| s.duration_secs.is_finite()).collect();
+        sorted.sort_by(|a, b| a.duration_secs.total_cmp(&b.duration_secs));
+
+        let mut points = Vec::new();
+        let mut survival = 1.0;
+        let mut at_risk = sorted.len();
+        let mut i = 0;
+        while i < sorted.len() {
+            let t = sorted[i].duration_secs;
+            let tied = sorted[i..].iter().take_while(|s| s.duration_secs == t).count();
+            let attempts = sorted[i..i + tied].iter().filter(|s| s.attempted).count();
+            if attempts > 0 {
+                let hazard = attempts as f64 / at_risk as f64;
+                survival *= 1.0 - hazard;
+                points.push(SurvivalPoint { t_secs: t, at_risk, attempts, hazard, survival });
+            }
+            at_risk -= tied;
+            i += tied;
+        }
+
+        Self {
+            level,
+            samples: sorted.len(),
+            attempts: sorted.iter().filter(|s| s.attempted).count(),
+            points,
+        }
+    }
+
+    /// P(no attempt by t)
+    pub fn survival_at(&self, t_secs: f64) -> f64 {
+        self.points.iter().take_while(|p| p.t_secs <= t_secs).last().map_or(1.0, |p| p.survival)
+    }
+
+    /// P(attempt within `horizon` | none in the first `elapsed` seconds)
+    pub fn conditional_probability(&self, elapsed_secs: f64, horizon_secs: f64) -> f64 {
+        let now = self.survival_at(elapsed_secs.max(0.0));
+        if now <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.survival_at(elapsed_secs.max(0.0) + horizon_secs) / now).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EscalationEstimate {
+    pub level: RiskLevel,
+    pub probability: f64,
+    pub horizon_secs: f64,
+    pub samples: usize,
+    pub pooled: bool, // The level had too little history; estimate uses all levels
+}
+
+impl EscalationEstimate {
+    /// One line for alert payloads
+    pub fn statement(&self) -> String {
+        let minutes = (self.horizon_secs / 60.0).round().max(1.0);
+        format!(
+            "If unresolved, {:.0}% chance of entry attempt within {} min",
+            self.probability * 100.0,
+            minutes
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EscalationSurvivalModel {
+    config: EscalationSurvivalConfig,
+    samples: RwLock<Vec<SurvivalSample>>,
+}
+
+impl EscalationSurvivalModel {
+    pub fn new(config: EscalationSurvivalConfig) -> Self {
+        Self { config, samples: RwLock::new(Vec::new()) }
+    }
+
+    pub fn config(&self) -> &EscalationSurvivalConfig {
+        &self.config
+    }
+
+    /// One sample per alert level the incident reached
+    pub fn samples_for(&self, incident: &Incident, label: IncidentLabel) -> Vec<SurvivalSample> {
+        let attempt_at = (label == IncidentLabel::Threat)
+            .then(|| incident.events.iter().find(|e| e.evidence.llr_entry >= self.config.entry_llr_threshold).map(|e| e.ts))
+            .flatten();
+        let end = incident.last_updated;
+
+        RiskLevel::ALL.iter().filter_map(|level| {
+            let reached = incident.probability_trace.iter()
+                .find(|p| RiskLevel::from_decision(&p.decision).is_some_and(|l| l == *level))?
+                .ts;
+            match attempt_at {
+                // Already attempted before this level was reached; says nothing about its hazard
+                Some(at) if at < reached => None,
+                Some(at) => Some(SurvivalSample { level: *level, duration_secs: at - reached, attempted: true }),
+                None => Some(SurvivalSample { level: *level, duration_secs: (end - reached).max(0.0), attempted: false }),
+            }
+        }).collect()
+    }
+
+    pub fn record_outcome(&self, incident: &Incident, label: IncidentLabel) {
+        let samples = self.samples_for(incident, label);
+        if let Ok(mut all) = self.samples.write() {
+            all.extend(samples);
+        }
+    }
+
+    /// Curve for one level, or pooled over all levels with None
+    pub fn curve(&self, level: Option<RiskLevel>) -> HazardCurve {
+        let all = self.samples.read().map(|s| s.clone()).unwrap_or_default();
+        let samples: Vec<SurvivalSample> = all.into_iter().filter(|s| level.map_or(true, |l| s.level == l)).collect();
+        HazardCurve::fit(level, &samples)
+    }
+
+    pub fn curves(&self) -> HashMap<RiskLevel, HazardCurve> {
+        RiskLevel::ALL.iter().map(|l| (*l, self.curve(Some(*l)))).collect()
+    }
+
+    /// Chance of an entry attempt within the configured horizon for an incident
+    /// that has been at `level` for `elapsed_secs`; None without enough history
+    pub fn estimate(&self, level: RiskLevel, elapsed_secs: f64) -> Option<EscalationEstimate> {
+        let own = self.curve(Some(level));
+        let (curve, pooled) = if own.samples >= self.config.min_samples {
+            (own, false)
+        } else {
+            let all = self.curve(None);
+            if all.samples < self.config.min_samples {
+                return None;
+            }
+            (all, true)
+        };
+        Some(EscalationEstimate {
+            level,
+            probability: curve.conditional_probability(elapsed_secs, self.config.horizon_secs),
+            horizon_secs: self.config.horizon_secs,
+            samples: curve.samples,
+            pooled,
+        })
+    }
+
+    /// Estimate for an incident's current level, timed from when it first reached it
+    pub fn estimate_for(&self, incident: &Incident, now_ts: f64) -> Option<EscalationEstimate> {
+        let level = RiskLevel::from_decision(&incident.probability_trace.last()?.decision)?;
+        let reached = incident.probability_trace.iter()
+            .find(|p| RiskLevel::from_decision(&p.decision) == Some(level))?
+            .ts;
+        self.estimate(level, now_ts - reached)
+    }
+}
@@ -0,0 +1,161 @@
+//! Incident Evidence Bundle Export
+//!
+//! Packages everything known about an incident (snapshots, narrative, probability
+//! trace and decisions) into a single ZIP suitable for police or insurance claims.
+//! A manifest with SHA-256 digests of every file is included so recipients can
+//! check the bundle was not altered after export.
+
+use super::incident_engine::Incident;
+use super::AlertDecision;
+use crate::encryption::EncryptedStore;
+use crate::image_preloader::ImagePreloader;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BundleError {
+    #[error("Incident {0} not found")]
+    IncidentNotFound(u64),
+
+    #[error("Failed to build archive: {0}")]
+    Archive(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleFileEntry {
+    pub path: String,
+    pub sha256: String,
+    pub size_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleManifest {
+    pub incident_id: u64,
+    pub home_id: String,
+    pub exported_at: DateTime<Utc>,
+    pub incident_started_at: f64,
+    pub incident_last_updated: f64,
+    pub cameras: Vec<String>,
+    pub event_count: usize,
+    pub files: Vec<BundleFileEntry>,
+    pub missing_snapshots: Vec<String>, // URLs in neither the cache nor the encrypted store
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DecisionChange {
+    ts: f64,
+    from: Option<AlertDecision>,
+    to: AlertDecision,
+    calibrated_probability: f64,
+}
+
+// Finished bundle ready to be streamed to the client
+#[derive(Debug)]
+pub struct EvidenceBundle {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    pub manifest: BundleManifest,
+}
+
+/// Build a ZIP evidence bundle for an incident, stamped with `exported_at`.
+/// Snapshots come from the preload cache, else the encrypted on-disk store.
+pub async fn export_incident_bundle(
+    home_id: &str,
+    incident: &Incident,
+    preloader: &ImagePreloader,
+    stored: Option<&EncryptedStore>,
+    exported_at: DateTime<Utc>,
+) -> Result<EvidenceBundle, BundleError> {
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+
+    // Narrative
+    let narrative = incident.last_narrative.clone()
+        .unwrap_or_else(|| "No narrative was generated for this incident.".to_string());
+    files.push(("narrative.txt".to_string(), narrative.into_bytes()));
+
+    // Full probability trace
+    let trace = serde_json::to_vec_pretty(&incident.probability_trace)
+        .map_err(|e| BundleError::Archive(e.to_string()))?;
+    files.push(("probability_trace.json".to_string(), trace));
+
+    // Decision changes only, which is what a reviewer usually wants to see
+    let mut decisions: Vec<DecisionChange> = Vec::new();
+    for point in &incident.probability_trace {
+        let previous = decisions.last().map(|d| d.to.clone());
+        if previous.as_ref() != Some(&point.decision) {
+            decisions.push(DecisionChange {
+                ts: point.ts,
+                from: previous,
+                to: point.decision.clone(),
+                calibrated_probability: point.calibrated_probability,
+            });
+        }
+    }
+    let decisions = serde_json::to_vec_pretty(&decisions)
+        .map_err(|e| BundleError::Archive(e.to_string()))?;
+    files.push(("decisions.json".to_string(), decisions));
+
+    // Snapshots still held by the image cache or the encrypted store
+    let mut missing_snapshots = Vec::new();
+    for (i, url) in incident.snapshot_urls.iter().enumerate() {
+        let image = match preloader.get_cached_image(url).await {
+            Some(bytes) => Some(bytes.to_vec()),
+            None => match stored.map(|store| store.image(home_id, url)) {
+                Some(Ok(image)) => image,
+                Some(Err(e)) => {
+                    tracing::warn!("Stored snapshot for incident {} unreadable: {}", incident.id, e);
+                    None
+                }
+                None => None,
+            },
+        };
+        match image {
+            Some(bytes) => {
+                let name = url.rsplit('/').next().unwrap_or("snapshot").split('?').next().unwrap_or("snapshot");
+                files.push((format!("snapshots/{:03}_{}", i + 1, name), bytes));
+            }
+            None => missing_snapshots.push(url.clone()),
+        }
+    }
+
+    let mut cameras: Vec<String> = incident.cameras.iter().cloned().collect();
+    cameras.sort();
+
+    let manifest = BundleManifest {
+        incident_id: incident.id,
+        home_id: home_id.to_string(),
+        exported_at,
+        incident_started_at: incident.started_at,
+        incident_last_updated: incident.last_updated,
+        cameras,
+        event_count: incident.events.len(),
+        files: files.iter().map(|(path, data)| BundleFileEntry {
+            path: path.clone(),
+            sha256: hex::encode(Sha256::digest(data)),
+            size_bytes: data.len(),
+        }).collect(),
+        missing_snapshots,
+    };
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| BundleError::Archive(e.to_string()))?;
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (path, data) in std::iter::once(("manifest.json".to_string(), manifest_json)).chain(files) {
+        zip.start_file(path, options).map_err(|e| BundleError::Archive(e.to_string()))?;
+        zip.write_all(&data).map_err(|e| BundleError::Archive(e.to_string()))?;
+    }
+    let bytes = zip.finish()
+        .map_err(|e| BundleError::Archive(e.to_string()))?
+        .into_inner();
+
+    Ok(EvidenceBundle {
+        filename: format!("incident_{}_{}_{}.zip", home_id, incident.id, exported_at.format("%Y%m%dT%H%M%SZ")),
+        bytes,
+        manifest,
+    })
+}
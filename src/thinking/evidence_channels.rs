@@ -0,0 +1,221 @@
+//! Evidence channel registry
+//!
+//! The six built-in channels (time, entry, behavior, identity, presence,
+//! token) are fields on `Evidence`. Deployments with other sensors — a LIDAR
+//! fence line, smart lock events, a glass-break microphone — register extra
+//! channels here and report them in `Evidence::custom` by name. A descriptor
+//! says how a channel's readings are capped, how an incident's readings are
+//! fused, and how far to trust the sensor before any learned reliability
+//! exists. Fusion, summaries, explanations and counterfactuals walk every
+//! channel through `Evidence::channels`, so a new channel needs no changes
+//! there. Readings for channels that were never registered are dropped.
+
+use super::Evidence;
+use crate::core::ThreatVector;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+
+pub const BUILTIN_CHANNELS: [&str; 6] = ["time", "entry", "behavior", "identity", "presence", "token"];
+
+/// Prefix marking a custom channel reading in raw sensor data, e.g. `ch.lidar_fence=1.2`
+pub const READING_PREFIX: &str = "ch.";
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ChannelError {
+    #[error("Channel '{0}' is already registered")]
+    Duplicate(String),
+
+    #[error("Invalid channel '{0}': {1}")]
+    Invalid(String, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelFusion {
+    Mean,      // Average over the incident's events, like time and behavior
+    #[default]
+    Strongest, // Largest magnitude reading wins, like identity and token
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelDescriptor {
+    pub name: String,  // Lowercase key used in `Evidence::custom` and raw readings
+    pub label: String, // Shown in summaries and explanations
+    pub pos_cap: f64,  // Per-reading cap, applied before the incident-wide caps
+    pub neg_cap: f64,
+    #[serde(default)]
+    pub fusion: ChannelFusion,
+    #[serde(default = "default_reliability")]
+    pub default_reliability: f64, // Trust in the sensor, as in `Evidence::discounted`
+    #[serde(default)]
+    pub counterfactual: Option<String>, // What would clear a positive reading, for counterfactuals
+    #[serde(default)]
+    pub vector: Option<ThreatVector>, // Threat a strong positive reading points to, e.g. glass-break → burglary
+}
+
+fn default_reliability() -> f64 {
+    1.0
+}
+
+impl ChannelDescriptor {
+    pub fn new(name: &str, label: &str, pos_cap: f64, neg_cap: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            pos_cap,
+            neg_cap,
+            fusion: ChannelFusion::default(),
+            default_reliability: default_reliability(),
+            counterfactual: None,
+            vector: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ChannelError> {
+        let invalid = |reason: &str| Err(ChannelError::Invalid(self.name.clone(), reason.to_string()));
+        if self.name.is_empty() || !self.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return invalid("name must be lowercase letters, digits and underscores");
+        }
+        if BUILTIN_CHANNELS.contains(&self.name.as_str()) {
+            return Err(ChannelError::Duplicate(self.name.clone()));
+        }
+        if !(self.pos_cap.is_finite() && self.pos_cap >= 0.0 && self.neg_cap.is_finite() && self.neg_cap >= 0.0) {
+            return invalid("caps must be finite and non-negative");
+        }
+        if !(0.0..=1.0).contains(&self.default_reliability) {
+            return invalid("default_reliability must be within [0, 1]");
+        }
+        Ok(())
+    }
+
+    /// A single reading, capped and discounted by the default reliability
+    pub fn normalize(&self, llr: f64) -> f64 {
+        if !llr.is_finite() {
+            return 0.0;
+        }
+        let capped = llr.clamp(-self.neg_cap, self.pos_cap);
+        let mut single = Evidence::default();
+        single.custom.insert(self.name.clone(), capped);
+        single.discounted(self.default_reliability).custom.get(&self.name).copied().unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ChannelRegistry {
+    channels: BTreeMap<String, ChannelDescriptor>,
+}
+
+impl ChannelRegistry {
+    pub fn register(&mut self, descriptor: ChannelDescriptor) -> Result<(), ChannelError> {
+        descriptor.validate()?;
+        if self.channels.contains_key(&descriptor.name) {
+            return Err(ChannelError::Duplicate(descriptor.name));
+        }
+        self.channels.insert(descriptor.name.clone(), descriptor);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ChannelDescriptor> {
+        self.channels.get(name)
+    }
+
+    pub fn descriptors(&self) -> Vec<ChannelDescriptor> {
+        self.channels.values().cloned().collect()
+    }
+
+    /// Normalized readings from pipe-separated `ch.<name>=<llr>` tokens for registered channels
+    pub fn parse_readings(&self, data: &str) -> BTreeMap<String, f64> {
+        data.split(['|', ',', ';'])
+            .filter_map(|token| token.trim().strip_prefix(READING_PREFIX)?.split_once('='))
+            .filter_map(|(name, value)| {
+                let descriptor = self.channels.get(name.trim())?;
+                Some((descriptor.name.clone(), descriptor.normalize(value.trim().parse().ok()?)))
+            })
+            .collect()
+    }
+}
+
+fn registry() -> &'static RwLock<ChannelRegistry> {
+    static REGISTRY: OnceLock<RwLock<ChannelRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(ChannelRegistry::default()))
+}
+
+/// Add a custom channel for the whole process
+pub fn register_channel(descriptor: ChannelDescriptor) -> Result<(), ChannelError> {
+    registry().write().map_err(|_| ChannelError::Invalid(descriptor.name.clone(), "registry unavailable".to_string()))?.register(descriptor)
+}
+
+pub fn channel_descriptor(name: &str) -> Option<ChannelDescriptor> {
+    registry().read().ok()?.get(name).cloned()
+}
+
+pub fn channel_descriptors() -> Vec<ChannelDescriptor> {
+    registry().read().map(|r| r.descriptors()).unwrap_or_default()
+}
+
+/// Custom channel readings in a raw event's sensor data
+pub fn parse_channel_readings(data: &str) -> BTreeMap<String, f64> {
+    registry().read().map(|r| r.parse_readings(data)).unwrap_or_default()
+}
+
+/// Display label for any channel, built-in or custom
+pub fn channel_label(name: &str) -> String {
+    channel_descriptor(name).map_or_else(|| name.to_string(), |d| d.label)
+}
+
+/// Which cap a fused channel ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Saturation {
+    Positive, // At pos_cap: more evidence in this direction would not have counted
+    Negative, // At -neg_cap
+}
+
+impl Saturation {
+    /// The cap an unclamped value reaches, if any; a zero reading never saturates
+    pub fn of(llr: f64, neg_cap: f64, pos_cap: f64) -> Option<Self> {
+        if llr > 0.0 && llr >= pos_cap {
+            Some(Saturation::Positive)
+        } else if llr < 0.0 && llr <= -neg_cap {
+            Some(Saturation::Negative)
+        } else {
+            None
+        }
+    }
+}
+
+/// How often one channel's fused value sat at a cap across assessments
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChannelSaturation {
+    pub channel: String,
+    pub assessments: u64, // Assessments the channel took part in
+    pub positive: u64,
+    pub negative: u64,
+    pub chronic: bool, // Saturated often enough to suspect a miscalibrated extractor
+}
+
+impl ChannelSaturation {
+    pub fn new(channel: &str) -> Self {
+        Self { channel: channel.to_string(), ..Self::default() }
+    }
+
+    pub fn record(&mut self, saturation: Option<Saturation>) {
+        self.assessments += 1;
+        match saturation {
+            Some(Saturation::Positive) => self.positive += 1,
+            Some(Saturation::Negative) => self.negative += 1,
+            None => {}
+        }
+    }
+
+    /// Share of assessments where the channel sat at either cap
+    pub fn rate(&self) -> f64 {
+        if self.assessments == 0 { 0.0 } else { (self.positive + self.negative) as f64 / self.assessments as f64 }
+    }
+
+    pub fn with_chronic(mut self, min_assessments: u64, warn_rate: f64) -> Self {
+        self.chronic = self.assessments >= min_assessments && self.rate() >= warn_rate;
+        self
+    }
+}
@@ -1,6 +1,15 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use super::AlertDecision;
+use crate::core::ThreatVector;
+use crate::redaction::FaceRegion;
+use super::evidence_channels::{channel_descriptor, ChannelFusion, Saturation, BUILTIN_CHANNELS};
+use super::lifecycle::{IncidentTransition, LifecycleError};
+use super::adversarial_handoff::AdversarialAssessment;
+use super::probability::clamp_llr;
+pub use super::probability::{sigmoid, calibrate_logit};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Evidence {
     pub llr_time: f64,
     pub llr_entry: f64,
@@ -8,17 +17,74 @@ pub struct Evidence {
     pub llr_identity: f64,
     pub llr_presence: f64,
     pub llr_token: f64,
+    /// Registered custom channels by name (see `evidence_channels`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, f64>,
+    /// Channels whose fused value was clamped at a cap, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub saturated: BTreeMap<String, Saturation>,
 }
 impl Evidence {
     pub fn sum(&self) -> f64 {
         self.llr_time + self.llr_entry + self.llr_behavior + self.llr_identity + self.llr_presence + self.llr_token
+            + self.custom.values().sum::<f64>()
+    }
+    /// Every channel by name, built-in ones first
+    pub fn channels(&self) -> Vec<(String, f64)> {
+        let builtin = [self.llr_time, self.llr_entry, self.llr_behavior, self.llr_identity, self.llr_presence, self.llr_token];
+        BUILTIN_CHANNELS.iter().map(|n| n.to_string()).zip(builtin)
+            .chain(self.custom.iter().map(|(n, v)| (n.clone(), *v)))
+            .collect()
+    }
+    pub fn get(&self, channel: &str) -> Option<f64> {
+        match channel {
+            "time" => Some(self.llr_time), "entry" => Some(self.llr_entry), "behavior" => Some(self.llr_behavior),
+            "identity" => Some(self.llr_identity), "presence" => Some(self.llr_presence), "token" => Some(self.llr_token),
+            _ => self.custom.get(channel).copied(),
+        }
+    }
+    pub fn set(&mut self, channel: &str, llr: f64) {
+        match channel {
+            "time" => self.llr_time = llr, "entry" => self.llr_entry = llr, "behavior" => self.llr_behavior = llr,
+            "identity" => self.llr_identity = llr, "presence" => self.llr_presence = llr, "token" => self.llr_token = llr,
+            _ => { self.custom.insert(channel.to_string(), llr); }
+        }
+    }
+    /// Set a channel to `llr` clamped to the caps, recording whether it saturated
+    pub fn set_capped(&mut self, channel: &str, llr: f64, neg_cap: f64, pos_cap: f64) {
+        self.set(channel, clamp_llr(llr, neg_cap, pos_cap));
+        match Saturation::of(llr, neg_cap, pos_cap) {
+            Some(saturation) => { self.saturated.insert(channel.to_string(), saturation); }
+            None => { self.saturated.remove(channel); }
+        }
+    }
+    pub fn saturation(&self, channel: &str) -> Option<Saturation> {
+        self.saturated.get(channel).copied()
     }
     pub fn capped_sum(&self, pos_cap: f64, neg_cap: f64) -> f64 {
-        self.sum().clamp(-neg_cap, pos_cap)
+        clamp_llr(self.sum(), neg_cap, pos_cap)
+    }
+    /// Shrink camera-derived channels (identity, behavior) when visibility is poor
+    pub fn with_visual_reliability(&self, reliability: f64) -> Evidence {
+        let r = if reliability.is_finite() { reliability.clamp(0.0, 1.0) } else { 1.0 };
+        Evidence { llr_identity: self.llr_identity * r, llr_behavior: self.llr_behavior * r, ..self.clone() }
+    }
+    /// Treat a sensor's reading as informative with probability `reliability`:
+    /// each likelihood ratio becomes r·LR + (1 − r)
+    pub fn discounted(&self, reliability: f64) -> Evidence {
+        if !reliability.is_finite() || reliability >= 1.0 { return self.clone(); }
+        let r = reliability.max(0.0);
+        let d = |llr: f64| if llr > 0.0 { llr + (r + (1.0 - r) * (-llr).exp()).ln() } else { (r * llr.exp_m1()).ln_1p() };
+        Evidence {
+            llr_time: d(self.llr_time), llr_entry: d(self.llr_entry), llr_behavior: d(self.llr_behavior),
+            llr_identity: d(self.llr_identity), llr_presence: d(self.llr_presence), llr_token: d(self.llr_token),
+            custom: self.custom.iter().map(|(n, v)| (n.clone(), d(*v))).collect(),
+            saturated: self.saturated.clone(),
+        }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Event {
     pub ts: f64,
     pub cam: String,
@@ -32,10 +98,30 @@ pub struct Event {
     pub evidence: Evidence,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum IncidentStatus { Open, Closed }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub enum IncidentStatus {
+    Open,
+    Waiting, // Last assessment abstained; a follow-up is pending
+    #[serde(alias = "Closed")]
+    Resolved,
+    Expired,   // No events within the TTL
+    Dismissed, // Closed by a person as not worth acting on
+}
+impl IncidentStatus {
+    pub fn is_active(&self) -> bool { matches!(self, IncidentStatus::Open | IncidentStatus::Waiting) }
+}
+
+// One assessment of an incident, recorded each time a new event is fused
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProbabilityTracePoint {
+    pub ts: f64,
+    pub event_count: usize,
+    pub fused_llr: f64,
+    pub calibrated_probability: f64,
+    pub decision: AlertDecision,
+}
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Incident {
     pub id: u64,
     pub started_at: f64,
@@ -45,51 +131,197 @@ pub struct Incident {
     pub cameras: HashSet<String>,
     pub suppressed_count: u32,
     pub status: IncidentStatus,
+    pub probability_trace: Vec<ProbabilityTracePoint>,
+    pub snapshot_urls: Vec<String>,
+    pub last_narrative: Option<String>,
+    #[serde(default)]
+    pub adversarial: Option<AdversarialAssessment>, // Findings from the adversarial handoff, if forwarded
+    #[serde(default)]
+    pub data_quality: Vec<String>, // Inputs sanitization had to repair, across all events
+    #[serde(default)]
+    pub threat_vector: ThreatVector, // From the latest assessment
+    #[serde(default)]
+    pub snapshot_faces: HashMap<String, Vec<FaceRegion>>, // Detector's faces per snapshot URL, for share redaction
 }
 impl Incident {
     pub fn new(id: u64, start_ts: f64, person_session_id: String) -> Self {
-        Self { id, started_at: start_ts, last_updated: start_ts, person_session_id, events: Vec::new(), cameras: HashSet::new(), suppressed_count: 0, status: IncidentStatus::Open }
+        Self { id, started_at: start_ts, last_updated: start_ts, person_session_id, events: Vec::new(), cameras: HashSet::new(), suppressed_count: 0, status: IncidentStatus::Open, probability_trace: Vec::new(), snapshot_urls: Vec::new(), last_narrative: None, adversarial: None, data_quality: Vec::new(), threat_vector: ThreatVector::Unknown, snapshot_faces: HashMap::new() }
+    }
+    pub fn record_assessment(&mut self, fused_llr: f64, calibrated_probability: f64, decision: AlertDecision, narrative: &str) {
+        self.probability_trace.push(ProbabilityTracePoint { ts: self.last_updated, event_count: self.events.len(), fused_llr, calibrated_probability, decision });
+        self.last_narrative = Some(narrative.to_string());
     }
+    pub fn attach_snapshot(&mut self, url: String) { if !self.snapshot_urls.contains(&url) { self.snapshot_urls.push(url); } }
+    pub fn attach_snapshot_faces(&mut self, url: String, faces: Vec<FaceRegion>) { self.snapshot_faces.insert(url.clone(), faces); self.attach_snapshot(url); }
     pub fn add_event(&mut self, ev: Event) { self.last_updated = ev.ts.max(self.last_updated); self.cameras.insert(ev.cam.clone()); self.events.push(ev); }
     pub fn total_dwell(&self) -> f64 { self.events.iter().map(|e| e.dwell_s).sum() }
     pub fn latest(&self) -> Option<&Event> { self.events.last() }
+    /// What the incident looks like given its fused evidence. Entry evidence means burglary; a
+    /// registered channel with a vector vouches for it on a strong reading; an unannounced visitor
+    /// behaving suspiciously is casing across several cameras or a long dwell, else trespass.
+    /// Anyone who rang or knocked, and anything without positive behavior, stays Unknown.
+    pub fn classify_vector(&self, fused: &Evidence) -> ThreatVector {
+        const STRONG_LLR: f64 = 0.5; const CASING_DWELL_S: f64 = 120.0;
+        if fused.llr_entry >= STRONG_LLR { return ThreatVector::Burglary; }
+        let vouched = fused.custom.iter().filter(|(_, llr)| **llr >= STRONG_LLR)
+            .filter_map(|(name, llr)| Some((channel_descriptor(name)?.vector?, *llr)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((vector, _)) = vouched { return vector; }
+        let announced = self.events.iter().any(|e| e.rang_doorbell || e.knocked);
+        if announced || fused.llr_behavior <= 0.0 || fused.sum() <= 0.0 { return ThreatVector::Unknown; }
+        if self.cameras.len() >= 2 || self.total_dwell() >= CASING_DWELL_S { ThreatVector::Casing } else { ThreatVector::Trespass }
+    }
     pub fn fused_evidence(&self, pos_cap: f64, neg_cap: f64) -> Evidence {
+        self.fused_evidence_with(pos_cap, neg_cap, |_| None)
+    }
+    /// Fuse with each event discounted by its sensor's learned reliability, where known
+    pub fn fused_evidence_with(&self, pos_cap: f64, neg_cap: f64, reliability: impl Fn(&str) -> Option<f64>) -> Evidence {
         let mut llr_time: f64 = 0.0; let mut llr_entry: f64 = 0.0; let mut llr_behavior: f64 = 0.0;
         let mut llr_identity: f64 = 0.0; let mut llr_presence: f64 = 0.0; let mut llr_token: f64 = 0.0;
+        let mut custom: BTreeMap<String, f64> = BTreeMap::new(); let mut custom_sums: BTreeMap<String, f64> = BTreeMap::new();
         let n = self.events.len().max(1) as f64;
         for e in &self.events {
-            llr_time += e.evidence.llr_time; llr_entry += e.evidence.llr_entry; llr_behavior += e.evidence.llr_behavior;
-            if e.evidence.llr_identity.abs() > llr_identity.abs() { llr_identity = e.evidence.llr_identity; }
-            if e.evidence.llr_presence.abs() > llr_presence.abs() { llr_presence = e.evidence.llr_presence; }
-            if e.evidence.llr_token.abs() > llr_token.abs() { llr_token = e.evidence.llr_token; }
+            let ev = match reliability(&e.cam) { Some(r) => e.evidence.discounted(r), None => e.evidence.clone() };
+            llr_time += ev.llr_time; llr_entry += ev.llr_entry; llr_behavior += ev.llr_behavior;
+            if ev.llr_identity.abs() > llr_identity.abs() { llr_identity = ev.llr_identity; }
+            if ev.llr_presence.abs() > llr_presence.abs() { llr_presence = ev.llr_presence; }
+            if ev.llr_token.abs() > llr_token.abs() { llr_token = ev.llr_token; }
+            for (name, llr) in ev.custom {
+                *custom_sums.entry(name.clone()).or_insert(0.0) += llr;
+                let strongest = custom.entry(name).or_insert(0.0);
+                if llr.abs() > strongest.abs() { *strongest = llr; }
+            }
         }
-        Evidence {
-            llr_time: (llr_time/n).clamp(-neg_cap,pos_cap),
-            llr_entry:(llr_entry/n).clamp(-neg_cap,pos_cap),
-            llr_behavior:(llr_behavior/n).clamp(-neg_cap,pos_cap),
-            llr_identity: llr_identity.clamp(-neg_cap,pos_cap),
-            llr_presence: llr_presence.clamp(-neg_cap,pos_cap),
-            llr_token: llr_token.clamp(-neg_cap,pos_cap),
+        let mut fused = Evidence::default();
+        let builtin = [llr_time/n, llr_entry/n, llr_behavior/n, llr_identity, llr_presence, llr_token];
+        for (name, llr) in BUILTIN_CHANNELS.iter().zip(builtin) {
+            fused.set_capped(name, llr, neg_cap, pos_cap);
         }
+        // Custom channels fuse as their descriptor says; unregistered ones are dropped
+        for (name, strongest) in custom {
+            let Some(descriptor) = channel_descriptor(&name) else { continue };
+            let llr = match descriptor.fusion {
+                ChannelFusion::Mean => custom_sums[&name] / n,
+                ChannelFusion::Strongest => strongest,
+            };
+            fused.set_capped(&name, llr, neg_cap, pos_cap);
+        }
+        fused
     }
 }
 
 #[derive(Clone, Debug)]
-pub struct IncidentStore { pub incidents: HashMap<(String,String), Incident>, pub ttl_secs: f64, pub id_counter: u64 }
+pub struct IncidentStore {
+    pub incidents: HashMap<(String,String), Incident>, pub ttl_secs: f64, pub id_counter: u64, pub transitions: Vec<IncidentTransition>,
+    pub merged_tracks: HashMap<(String,String), String>, // Track merged away -> session of the incident that absorbed it
+}
 impl IncidentStore {
-    pub fn new(ttl_secs: f64) -> Self { Self { incidents: HashMap::new(), ttl_secs, id_counter: 1 } }
+    pub fn new(ttl_secs: f64) -> Self { Self { incidents: HashMap::new(), ttl_secs, id_counter: 1, transitions: Vec::new(), merged_tracks: HashMap::new() } }
     pub fn upsert_event(&mut self, home: &str, ev: Event) -> u64 {
-        let key = (home.to_string(), ev.person_track.clone());
+        let track = (home.to_string(), ev.person_track.clone());
+        let key = match self.merged_tracks.get(&track) { Some(session) => (home.to_string(), session.clone()), None => track };
         let now = ev.ts;
-        self.incidents.retain(|_, inc| now - inc.last_updated <= self.ttl_secs && inc.status==IncidentStatus::Open);
-        if let Some(inc) = self.incidents.get_mut(&key) { inc.add_event(ev); inc.id }
+        self.expire_stale(now);
+        if let Some(inc) = self.incidents.get_mut(&key).filter(|i| i.status.is_active()) { inc.add_event(ev); inc.id }
         else { let id=self.id_counter; self.id_counter+=1; let mut inc=Incident::new(id, now, key.1.clone()); inc.add_event(ev); self.incidents.insert(key, inc); id }
     }
+    /// Mark active incidents past the TTL as Expired and drop closed ones past it
+    pub fn expire_stale(&mut self, now: f64) {
+        let ttl = self.ttl_secs;
+        self.incidents.retain(|_, inc| inc.status.is_active() || now - inc.last_updated <= ttl);
+        for ((home, _), inc) in self.incidents.iter_mut() {
+            if inc.status.is_active() && now - inc.last_updated > ttl {
+                self.transitions.push(IncidentTransition::new(home, inc, IncidentStatus::Expired, now, "no events within TTL"));
+                inc.status = IncidentStatus::Expired;
+            }
+        }
+    }
+    /// Move an incident to a new status, recording the transition for lifecycle hooks
+    pub fn set_status(&mut self, home: &str, incident_id: u64, to: IncidentStatus, ts: f64, reason: &str) -> Option<IncidentTransition> {
+        let inc = self.incidents.iter_mut().find(|((h, _), i)| h == home && i.id == incident_id).map(|(_, i)| i)?;
+        if inc.status == to { return None; }
+        let transition = IncidentTransition::new(home, inc, to, ts, reason);
+        inc.status = to;
+        self.transitions.push(transition.clone());
+        Some(transition)
+    }
+    /// Fold `source_id` into `target_id`: events, cameras and snapshots move over, the source
+    /// is recorded as Resolved and removed, and later events on its track join the target
+    pub fn merge(&mut self, home: &str, target_id: u64, source_id: u64, ts: f64) -> Result<(), LifecycleError> {
+        if target_id == source_id { return Err(LifecycleError::InvalidEdit(format!("cannot merge incident {} into itself", target_id))); }
+        let target_key = self.key_of(home, target_id).ok_or(LifecycleError::NotFound(target_id))?;
+        let source_key = self.key_of(home, source_id).ok_or(LifecycleError::NotFound(source_id))?;
+        let source = self.incidents.remove(&source_key).ok_or(LifecycleError::NotFound(source_id))?;
+        let reason = format!("merged into incident {}", target_id);
+        if source.status.is_active() { self.transitions.push(IncidentTransition::new(home, &source, IncidentStatus::Resolved, ts, &reason)); }
+        for (track, session) in self.merged_tracks.iter_mut() { if track.0 == home && *session == source_key.1 { *session = target_key.1.clone(); } }
+        self.merged_tracks.insert(source_key, target_key.1.clone());
+
+        let target = self.incidents.get_mut(&target_key).ok_or(LifecycleError::NotFound(target_id))?;
+        target.started_at = target.started_at.min(source.started_at);
+        target.suppressed_count += source.suppressed_count;
+        for url in source.snapshot_urls { target.attach_snapshot(url); }
+        for (url, faces) in source.snapshot_faces { target.snapshot_faces.entry(url).or_insert(faces); }
+        for flag in source.data_quality { if !target.data_quality.contains(&flag) { target.data_quality.push(flag); } }
+        for ev in source.events { target.add_event(ev); }
+        target.events.sort_by(|a, b| a.ts.total_cmp(&b.ts));
+        Ok(())
+    }
+    /// Move the events at `event_indices` out of an incident into a new one and return its id.
+    /// At least one event must stay behind.
+    pub fn split(&mut self, home: &str, incident_id: u64, event_indices: &[usize]) -> Result<u64, LifecycleError> {
+        let key = self.key_of(home, incident_id).ok_or(LifecycleError::NotFound(incident_id))?;
+        let inc = self.incidents.get(&key).ok_or(LifecycleError::NotFound(incident_id))?;
+        let picked: HashSet<usize> = event_indices.iter().copied().collect();
+        if picked.is_empty() || picked.len() >= inc.events.len() { return Err(LifecycleError::InvalidEdit("a split must move some but not all events".to_string())); }
+        if let Some(i) = picked.iter().find(|&&i| i >= inc.events.len()) { return Err(LifecycleError::InvalidEdit(format!("incident {} has no event {}", incident_id, i))); }
+
+        let new_id = self.id_counter; self.id_counter += 1;
+        let inc = self.incidents.get_mut(&key).ok_or(LifecycleError::NotFound(incident_id))?;
+        let (moved, kept): (Vec<(usize, Event)>, Vec<(usize, Event)>) = inc.events.drain(..).enumerate().partition(|(i, _)| picked.contains(i));
+        inc.events = kept.into_iter().map(|(_, e)| e).collect();
+        inc.cameras = inc.events.iter().map(|e| e.cam.clone()).collect();
+        inc.started_at = inc.events.iter().map(|e| e.ts).fold(f64::INFINITY, f64::min);
+        inc.last_updated = inc.events.iter().map(|e| e.ts).fold(f64::NEG_INFINITY, f64::max);
+
+        let moved: Vec<Event> = moved.into_iter().map(|(_, e)| e).collect();
+        let start = moved.iter().map(|e| e.ts).fold(f64::INFINITY, f64::min);
+        let mut split = Incident::new(new_id, start, format!("{}/split-{}", key.1, new_id));
+        split.data_quality = inc.data_quality.clone();
+        for ev in moved { split.add_event(ev); }
+        self.incidents.insert((home.to_string(), split.person_session_id.clone()), split);
+        Ok(new_id)
+    }
+    fn key_of(&self, home: &str, incident_id: u64) -> Option<(String, String)> {
+        self.incidents.iter().find(|((h, _), i)| h == home && i.id == incident_id).map(|(k, _)| k.clone())
+    }
     pub fn get_incident(&self, home: &str, person_session: &str) -> Option<&Incident> { self.incidents.get(&(home.to_string(), person_session.to_string())) }
     pub fn get_incident_mut(&mut self, home: &str, person_session: &str) -> Option<&mut Incident> { self.incidents.get_mut(&(home.to_string(), person_session.to_string())) }
+    pub fn snapshot(&self) -> IncidentStoreSnapshot {
+        let mut incidents: Vec<IncidentSnapshotEntry> = self.incidents.iter()
+            .map(|((home, session), inc)| IncidentSnapshotEntry { home: home.clone(), person_session: session.clone(), incident: inc.clone() })
+            .collect();
+        incidents.sort_by_key(|e| e.incident.id);
+        let mut merged_tracks: Vec<MergedTrack> = self.merged_tracks.iter()
+            .map(|((home, track), session)| MergedTrack { home: home.clone(), track: track.clone(), session: session.clone() })
+            .collect();
+        merged_tracks.sort_by(|a, b| (&a.home, &a.track).cmp(&(&b.home, &b.track)));
+        IncidentStoreSnapshot { ttl_secs: self.ttl_secs, id_counter: self.id_counter, incidents, merged_tracks }
+    }
+    pub fn from_snapshot(snapshot: IncidentStoreSnapshot) -> Self {
+        let incidents = snapshot.incidents.into_iter().map(|e| ((e.home, e.person_session), e.incident)).collect();
+        let merged_tracks = snapshot.merged_tracks.into_iter().map(|m| ((m.home, m.track), m.session)).collect();
+        Self { incidents, ttl_secs: snapshot.ttl_secs, id_counter: snapshot.id_counter, transitions: Vec::new(), merged_tracks }
+    }
 }
 
-pub fn sigmoid(x: f64) -> f64 { 1.0/(1.0+(-x).exp()) }
-pub fn calibrate_logit(raw_logit: f64, mean: f64, temperature: f64, odds_cap: f64) -> f64 {
-    let z = (raw_logit - mean) / temperature.max(1.0); sigmoid(z.clamp(-odds_cap, odds_cap))
-}
+// Serializable form of an IncidentStore (tuple map keys do not survive JSON)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IncidentSnapshotEntry { pub home: String, pub person_session: String, pub incident: Incident }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MergedTrack { pub home: String, pub track: String, pub session: String }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IncidentStoreSnapshot { pub ttl_secs: f64, pub id_counter: u64, pub incidents: Vec<IncidentSnapshotEntry>, #[serde(default)] pub merged_tracks: Vec<MergedTrack> }
+
@@ -0,0 +1,52 @@
+//! Incident lifecycle
+//!
+//! Incidents move Open ⇄ Waiting while evidence arrives and end Resolved,
+//! Expired or Dismissed. Every change is recorded as a transition so hooks
+//! (webhooks, WebSocket clients, callbacks) see closures instead of incidents
+//! silently disappearing.
+
+use super::{Incident, IncidentStatus};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncidentTransition {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub from: IncidentStatus,
+    pub to: IncidentStatus,
+    pub ts: f64,
+    pub reason: String,
+}
+
+impl IncidentTransition {
+    pub fn new(home_id: &str, incident: &Incident, to: IncidentStatus, ts: f64, reason: &str) -> Self {
+        Self {
+            home_id: home_id.to_string(),
+            incident_id: incident.id,
+            from: incident.status,
+            to,
+            ts,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LifecycleError {
+    #[error("Incident {0} not found")]
+    NotFound(u64),
+    #[error("Incident {0} is already {1:?}")]
+    AlreadyClosed(u64, IncidentStatus),
+    #[error("{0:?} cannot be set manually")]
+    InvalidTarget(IncidentStatus),
+    #[error("Invalid incident edit: {0}")]
+    InvalidEdit(String),
+}
+
+/// Receives every incident state change
+#[async_trait]
+pub trait IncidentLifecycleHook: Send + Sync {
+    async fn on_transition(&self, transition: &IncidentTransition);
+}
@@ -0,0 +1,309 @@
+//! LLM usage budgets
+//!
+//! Narratives from the LLM service cost tokens on every assessment. The budget
+//! caps daily calls and tokens per home and across the deployment, and admits
+//! requests by priority: part of each budget is held back for Critical
+//! incidents, so a busy evening of deliveries cannot starve a real alert.
+//! Non-urgent narratives that do not fit are queued and generated later when
+//! budget frees up (the next day, at the latest); Elevated ones fall back to
+//! the rule-based summary straight away. Counters reset at UTC midnight.
+
+use super::llm_client::{LLMSummaryRequest, LLMTokenUsage};
+use chrono::{DateTime, NaiveDate, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmPriority {
+    Background, // Nothing notable; a narrative is nice to have
+    Standard,
+    Elevated,
+    Critical,
+}
+
+impl LlmPriority {
+    /// Same bands the summarizer reports to the LLM service
+    pub fn from_probability(p: f64) -> Self {
+        match p {
+            p if p >= 0.5 => LlmPriority::Critical,
+            p if p >= 0.3 => LlmPriority::Elevated,
+            p if p >= 0.15 => LlmPriority::Standard,
+            _ => LlmPriority::Background,
+        }
+    }
+
+    fn deferrable(&self) -> bool {
+        matches!(self, LlmPriority::Background | LlmPriority::Standard)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmBudgetConfig {
+    pub global_daily_tokens: u64,
+    pub global_daily_calls: u32,
+    pub home_daily_tokens: u64,
+    pub home_daily_calls: u32,
+    pub critical_reserve: f64,          // Share of each budget only Critical requests may use
+    pub estimated_tokens_per_call: u64, // Reserved at admission, corrected once usage is reported
+    pub max_deferred: usize,            // Queue length; the lowest priority, oldest entry is dropped past it
+}
+
+impl Default for LlmBudgetConfig {
+    fn default() -> Self {
+        Self {
+            global_daily_tokens: 2_000_000,
+            global_daily_calls: 5_000,
+            home_daily_tokens: 20_000,
+            home_daily_calls: 50,
+            critical_reserve: 0.2,
+            estimated_tokens_per_call: 400,
+            max_deferred: 1_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Admission {
+    Admitted,
+    Deferred,       // Queue the request and use the rule-based summary for now
+    Denied(String), // Use the rule-based summary
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmUsage {
+    pub calls: u32,
+    pub tokens: u64,
+    pub deferred: u32,
+    pub denied: u32,
+}
+
+#[derive(Debug, Clone)]
+struct DayUsage {
+    day: NaiveDate,
+    usage: LlmUsage,
+}
+
+impl DayUsage {
+    fn new(day: NaiveDate) -> Self {
+        Self { day, usage: LlmUsage { calls: 0, tokens: 0, deferred: 0, denied: 0 } }
+    }
+
+    fn roll(&mut self, day: NaiveDate) {
+        if self.day != day {
+            *self = Self::new(day);
+        }
+    }
+
+    /// Whether one more call fits in `share` of the limits
+    fn fits(&self, calls: u32, tokens: u64, share: f64, estimate: u64) -> bool {
+        (self.usage.calls as f64) < calls as f64 * share
+            && (self.usage.tokens + estimate) as f64 <= tokens as f64 * share
+    }
+}
+
+/// A narrative waiting for budget
+#[derive(Debug, Clone)]
+pub struct DeferredNarrative {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub priority: LlmPriority,
+    pub request: LLMSummaryRequest,
+    pub details: String, // Technical line appended to the narrative
+    pub queued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HomeLlmUsage {
+    pub home_id: String,
+    pub usage: LlmUsage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmUsageReport {
+    pub day: NaiveDate,
+    pub global: LlmUsage,
+    pub global_limits: (u32, u64), // Daily (calls, tokens)
+    pub home_limits: (u32, u64),
+    pub homes: Vec<HomeLlmUsage>,
+    pub deferred_pending: usize,
+}
+
+#[derive(Debug)]
+pub struct LlmBudget {
+    config: LlmBudgetConfig,
+    global: Mutex<DayUsage>,
+    homes: DashMap<String, DayUsage>,
+    deferred: Mutex<VecDeque<DeferredNarrative>>,
+}
+
+impl Default for LlmBudget {
+    fn default() -> Self {
+        Self::new(LlmBudgetConfig::default())
+    }
+}
+
+impl LlmBudget {
+    pub fn new(config: LlmBudgetConfig) -> Self {
+        Self {
+            config,
+            global: Mutex::new(DayUsage::new(NaiveDate::MIN)),
+            homes: DashMap::new(),
+            deferred: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn config(&self) -> &LlmBudgetConfig {
+        &self.config
+    }
+
+    /// Decide whether a call may go ahead now; an admitted call is counted
+    /// against both budgets at the estimated token cost
+    pub fn admit(&self, home_id: &str, priority: LlmPriority, now: DateTime<Utc>) -> Admission {
+        let day = now.date_naive();
+        let share = if priority == LlmPriority::Critical { 1.0 } else { 1.0 - self.config.critical_reserve };
+        let estimate = self.config.estimated_tokens_per_call;
+        let Ok(mut global) = self.global.lock() else {
+            return Admission::Denied("budget unavailable".to_string());
+        };
+        global.roll(day);
+        let mut home = self.homes.entry(home_id.to_string()).or_insert_with(|| DayUsage::new(day));
+        home.roll(day);
+
+        let blocked = if !global.fits(self.config.global_daily_calls, self.config.global_daily_tokens, share, estimate) {
+            Some("global daily LLM budget exhausted")
+        } else if !home.fits(self.config.home_daily_calls, self.config.home_daily_tokens, share, estimate) {
+            Some("home daily LLM budget exhausted")
+        } else {
+            None
+        };
+        match blocked {
+            None => {
+                for usage in [&mut global.usage, &mut home.usage] {
+                    usage.calls += 1;
+                    usage.tokens += estimate;
+                }
+                Admission::Admitted
+            }
+            Some(_) if priority.deferrable() => {
+                home.usage.deferred += 1;
+                global.usage.deferred += 1;
+                Admission::Deferred
+            }
+            Some(reason) => {
+                home.usage.denied += 1;
+                global.usage.denied += 1;
+                Admission::Denied(reason.to_string())
+            }
+        }
+    }
+
+    /// Replace an admitted call's estimate with the usage the service reported
+    pub fn record(&self, home_id: &str, usage: Option<&LLMTokenUsage>) {
+        let actual = usage.map_or(self.config.estimated_tokens_per_call, |u| u.prompt_tokens + u.completion_tokens);
+        let correct = |tokens: &mut u64| *tokens = (*tokens + actual).saturating_sub(self.config.estimated_tokens_per_call);
+        if let Ok(mut global) = self.global.lock() {
+            correct(&mut global.usage.tokens);
+        }
+        if let Some(mut home) = self.homes.get_mut(home_id) {
+            correct(&mut home.usage.tokens);
+        }
+    }
+
+    /// Queue a narrative for later; a newer request for the same incident replaces the old one
+    pub fn defer(&self, narrative: DeferredNarrative) {
+        let Ok(mut queue) = self.deferred.lock() else { return };
+        queue.retain(|d| !(d.home_id == narrative.home_id && d.incident_id == narrative.incident_id));
+        queue.push_back(narrative);
+        while queue.len() > self.config.max_deferred {
+            let lowest = queue.iter().enumerate().min_by_key(|(_, d)| d.priority).map(|(i, _)| i);
+            if let Some(index) = lowest {
+                queue.remove(index);
+            }
+        }
+    }
+
+    /// Deferred narratives that can now be generated, highest priority and
+    /// oldest first, each admitted against the budget; at most `max`
+    pub fn take_deferred(&self, now: DateTime<Utc>, max: usize) -> Vec<DeferredNarrative> {
+        let Ok(mut queue) = self.deferred.lock() else { return Vec::new() };
+        queue.make_contiguous().sort_by(|a, b| b.priority.cmp(&a.priority).then(a.queued_at.cmp(&b.queued_at)));
+        let mut ready = Vec::new();
+        let mut waiting = VecDeque::new();
+        while let Some(narrative) = queue.pop_front() {
+            if ready.len() < max && self.admit_queued(&narrative.home_id, now) {
+                ready.push(narrative);
+            } else {
+                waiting.push_back(narrative);
+            }
+        }
+        *queue = waiting;
+        ready
+    }
+
+    // Queued requests are admitted like Standard ones but not re-counted as deferred
+    fn admit_queued(&self, home_id: &str, now: DateTime<Utc>) -> bool {
+        let day = now.date_naive();
+        let share = 1.0 - self.config.critical_reserve;
+        let estimate = self.config.estimated_tokens_per_call;
+        let Ok(mut global) = self.global.lock() else { return false };
+        global.roll(day);
+        let mut home = self.homes.entry(home_id.to_string()).or_insert_with(|| DayUsage::new(day));
+        home.roll(day);
+        if !global.fits(self.config.global_daily_calls, self.config.global_daily_tokens, share, estimate)
+            || !home.fits(self.config.home_daily_calls, self.config.home_daily_tokens, share, estimate)
+        {
+            return false;
+        }
+        for usage in [&mut global.usage, &mut home.usage] {
+            usage.calls += 1;
+            usage.tokens += estimate;
+        }
+        true
+    }
+
+    pub fn deferred_len(&self) -> usize {
+        self.deferred.lock().map_or(0, |q| q.len())
+    }
+
+    /// Queued narratives per priority lane, with when the oldest of each was queued
+    pub fn deferred_lanes(&self) -> Vec<(LlmPriority, usize, DateTime<Utc>)> {
+        let Ok(queue) = self.deferred.lock() else { return Vec::new() };
+        let mut lanes: Vec<(LlmPriority, usize, DateTime<Utc>)> = Vec::new();
+        for entry in queue.iter() {
+            match lanes.iter_mut().find(|(p, _, _)| *p == entry.priority) {
+                Some((_, depth, oldest)) => {
+                    *depth += 1;
+                    *oldest = (*oldest).min(entry.queued_at);
+                }
+                None => lanes.push((entry.priority, 1, entry.queued_at)),
+            }
+        }
+        lanes
+    }
+
+    /// Today's usage, globally and for every home with activity
+    pub fn report(&self, now: DateTime<Utc>) -> LlmUsageReport {
+        let day = now.date_naive();
+        let global = self.global.lock()
+            .ok()
+            .filter(|g| g.day == day)
+            .map(|g| g.usage.clone())
+            .unwrap_or_else(|| DayUsage::new(day).usage);
+        let mut homes: Vec<HomeLlmUsage> = self.homes.iter()
+            .filter(|h| h.day == day)
+            .map(|h| HomeLlmUsage { home_id: h.key().clone(), usage: h.usage.clone() })
+            .collect();
+        homes.sort_by(|a, b| b.usage.tokens.cmp(&a.usage.tokens));
+        LlmUsageReport {
+            day,
+            global,
+            global_limits: (self.config.global_daily_calls, self.config.global_daily_tokens),
+            home_limits: (self.config.home_daily_calls, self.config.home_daily_tokens),
+            homes,
+            deferred_pending: self.deferred_len(),
+        }
+    }
+}
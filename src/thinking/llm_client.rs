@@ -1,8 +1,12 @@
 use reqwest;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
+use crate::metering::{BillableUnit, UsageMeter};
+use super::llm_budget::{Admission, LlmBudget, LlmPriority};
+use super::llm_guard::LlmGuard;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LLMSummaryRequest {
     pub decision: String,
     pub location: String,
@@ -20,11 +24,21 @@ pub struct LLMSummaryResponse {
     pub model: Option<String>,
     pub error: Option<String>,
     pub fallback_reason: Option<String>,
+    #[serde(default)]
+    pub usage: Option<LLMTokenUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LLMTokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
 }
 
 pub struct LLMClient {
     client: reqwest::Client,
     base_url: String,
+    meter: Option<Arc<UsageMeter>>,
+    guard: Option<Arc<LlmGuard>>,
 }
 
 impl LLMClient {
@@ -37,29 +51,99 @@ impl LLMClient {
         Self {
             client,
             base_url: base_url.unwrap_or_else(|| "http://127.0.0.1:8765".to_string()),
+            meter: None,
+            guard: None,
         }
     }
-    
-    /// Attempt to get an LLM-generated summary
-    pub async fn get_summary(&self, request: LLMSummaryRequest) -> Option<String> {
+
+    /// Bill token usage reported by the LLM service
+    pub fn with_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.meter = Some(meter);
+        self
+    }
+
+    /// Check responses against the strict schema and output rules, redacting summaries
+    pub fn with_guard(mut self, guard: Arc<LlmGuard>) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    /// Like `get_summary`, charging the tokens used to `account_id`
+    pub async fn get_summary_for_account(&self, account_id: &str, request: LLMSummaryRequest) -> Option<String> {
         match self.try_get_summary(request).await {
-            Ok(response) if response.success => {
-                if let Some(summary) = response.summary {
-                    Some(format!("🤖 {}", summary))  // Prefix to indicate LLM generated
-                } else {
-                    None
+            Ok(response) => {
+                if let (Some(meter), Some(usage)) = (&self.meter, &response.usage) {
+                    meter.record(account_id, BillableUnit::LlmTokens, usage.prompt_tokens + usage.completion_tokens);
                 }
+                self.summary_from(None, response)
+            }
+            Err(e) => {
+                eprintln!("LLM service error: {}", e);
+                None
             }
+        }
+    }
+    
+    /// Like `get_summary`, admitted against `budget` first. None when the
+    /// budget denied or deferred the request at `now`, as well as on failure.
+    pub async fn get_budgeted_summary(&self, budget: &LlmBudget, home_id: &str, priority: LlmPriority, request: LLMSummaryRequest, now: chrono::DateTime<chrono::Utc>) -> (Admission, Option<String>) {
+        let admission = budget.admit(home_id, priority, now);
+        if admission != Admission::Admitted {
+            return (admission, None);
+        }
+        let summary = self.try_summary_for_budget(budget, home_id, request).await;
+        (admission, summary)
+    }
+
+    /// Call for a request the budget has already admitted, recording its usage
+    pub async fn try_summary_for_budget(&self, budget: &LlmBudget, home_id: &str, request: LLMSummaryRequest) -> Option<String> {
+        match self.try_get_summary(request).await {
             Ok(response) => {
-                eprintln!("LLM summary failed: {:?}", response.error);
+                budget.record(home_id, response.usage.as_ref());
+                self.summary_from(Some(home_id), response)
+            }
+            Err(e) => {
+                eprintln!("LLM service error: {}", e);
                 None
             }
+        }
+    }
+
+    /// Attempt to get an LLM-generated summary
+    pub async fn get_summary(&self, request: LLMSummaryRequest) -> Option<String> {
+        match self.try_get_summary(request).await {
+            Ok(response) => self.summary_from(None, response),
             Err(e) => {
                 eprintln!("LLM service error: {}", e);
                 None
             }
         }
     }
+
+    fn summary_from(&self, home_id: Option<&str>, response: LLMSummaryResponse) -> Option<String> {
+        if !response.success {
+            eprintln!("LLM summary failed: {:?}", response.error);
+            return None;
+        }
+        let summary = match &self.guard {
+            Some(guard) => match guard.check_summary(home_id, response.summary.as_deref()?) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::warn!(home = home_id.unwrap_or("unknown"), "LLM summary rejected: {}", e);
+                    return None;
+                }
+            },
+            None => response.summary?,
+        };
+        Some(format!("🤖 {}", summary))  // Prefix to indicate LLM generated
+    }
+
+    fn parse(&self, body: &str) -> Result<LLMSummaryResponse, Box<dyn std::error::Error + Send + Sync>> {
+        match &self.guard {
+            Some(guard) => Ok(guard.parse_response(body)?),
+            None => Ok(serde_json::from_str(body)?),
+        }
+    }
     
     async fn try_get_summary(&self, request: LLMSummaryRequest) -> Result<LLMSummaryResponse, Box<dyn std::error::Error + Send + Sync>> {
         let url = format!("{}/summary", self.base_url);
@@ -72,12 +156,12 @@ impl LLMClient {
             
         let status = response.status();
             
+        let body = response.text().await?;
         if status.is_success() {
-            let summary_response: LLMSummaryResponse = response.json().await?;
-            Ok(summary_response)
+            self.parse(&body)
         } else {
             // Try to parse error response
-            match response.json::<LLMSummaryResponse>().await {
+            match self.parse(&body) {
                 Ok(error_response) => Ok(error_response),
                 Err(_) => Ok(LLMSummaryResponse {
                     success: false,
@@ -86,6 +170,7 @@ impl LLMClient {
                     model: None,
                     error: Some(format!("HTTP {}", status)),
                     fallback_reason: None,
+                    usage: None,
                 })
             }
         }
@@ -0,0 +1,335 @@
+//! LLM guardrails
+//!
+//! The LLM service sees only what a narrative needs, and nothing it returns is
+//! trusted. Event-derived strings (camera and zone names) are cleaned before
+//! they go out: control characters and markup are stripped, anything that
+//! reads like an instruction to the model is replaced with a placeholder, and
+//! residents' names, street addresses, email addresses and phone numbers are
+//! redacted. Responses must match the expected schema exactly (no unknown
+//! fields, the right types) and carry a bounded plain-text summary without
+//! links, markup or prompt fragments, or they are discarded in favour of the
+//! rule-based summary. A kill switch turns the LLM path off for the whole
+//! deployment or for single homes, from the environment or the admin API.
+
+use super::llm_client::{LLMSummaryRequest, LLMSummaryResponse};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+const FILTERED: &str = "[filtered]";
+
+// Event-derived text containing any of these is treated as an injection attempt
+const INJECTION_MARKERS: [&str; 16] = [
+    "ignore", "disregard", "instruction", "prompt", "system:", "assistant:", "you are", "act as",
+    "pretend", "jailbreak", "override", "forget", "<|", "```", "{{", "[inst]",
+];
+
+// A summary containing any of these echoes prompt structure rather than describing the scene
+const OUTPUT_MARKERS: [&str; 7] = ["system prompt", "system:", "assistant:", "<|", "[inst]", "as an ai", "instructions"];
+
+const STREET_SUFFIXES: [&str; 24] = [
+    "street", "st", "road", "rd", "avenue", "ave", "lane", "ln", "drive", "dr", "court", "ct", "close",
+    "way", "boulevard", "blvd", "place", "pl", "crescent", "terrace", "gardens", "grove", "mews", "row",
+];
+
+const DECISIONS: [&str; 4] = ["Critical", "Elevated", "Standard", "Normal"];
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum LlmGuardError {
+    #[error("LLM narratives are disabled: {0}")]
+    Disabled(String),
+
+    #[error("LLM response does not match the schema: {0}")]
+    Schema(String),
+
+    #[error("LLM summary rejected: {0}")]
+    UnsafeOutput(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmGuardConfig {
+    pub max_field_chars: usize,   // Event-derived strings are cut to this
+    pub max_summary_chars: usize, // Longer summaries are rejected
+}
+
+impl Default for LlmGuardConfig {
+    fn default() -> Self {
+        Self { max_field_chars: 64, max_summary_chars: 800 }
+    }
+}
+
+/// A thrown kill switch; `home_id` is None for the whole deployment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KillSwitch {
+    pub home_id: Option<String>,
+    pub reason: String,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmGuardReport {
+    pub global: Option<KillSwitch>,
+    pub homes: Vec<KillSwitch>,
+    pub filtered_inputs: u64,    // Event-derived strings replaced as injection attempts
+    pub redactions: u64,         // Names, addresses, emails and phone numbers removed, both directions
+    pub rejected_responses: u64, // Responses failing the schema or output checks
+}
+
+#[derive(Debug, Default)]
+pub struct LlmGuard {
+    config: LlmGuardConfig,
+    global: Mutex<Option<KillSwitch>>,
+    homes: DashMap<String, KillSwitch>,
+    terms: DashMap<String, HashSet<String>>, // Home -> lowercase words to redact, e.g. residents' names
+    filtered_inputs: AtomicU64,
+    redactions: AtomicU64,
+    rejected_responses: AtomicU64,
+}
+
+impl LlmGuard {
+    pub fn new(config: LlmGuardConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &LlmGuardConfig {
+        &self.config
+    }
+
+    /// Guard with kill switches from NOVIN_LLM_DISABLED (1/true/yes turns the
+    /// LLM off everywhere) and NOVIN_LLM_DISABLED_HOMES (comma separated)
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let guard = Self::default();
+        let now = Utc::now();
+        if var("NOVIN_LLM_DISABLED").is_some_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes")) {
+            guard.disable(None, "NOVIN_LLM_DISABLED", now);
+        }
+        for home in var("NOVIN_LLM_DISABLED_HOMES").unwrap_or_default().split(',').map(str::trim).filter(|h| !h.is_empty()) {
+            guard.disable(Some(home), "NOVIN_LLM_DISABLED_HOMES", now);
+        }
+        guard
+    }
+
+    /// Whether a home may use the LLM path
+    pub fn allows(&self, home_id: &str) -> Result<(), LlmGuardError> {
+        if let Some(switch) = self.global.lock().ok().and_then(|g| g.clone()) {
+            return Err(LlmGuardError::Disabled(switch.reason));
+        }
+        match self.homes.get(home_id) {
+            Some(switch) => Err(LlmGuardError::Disabled(switch.reason.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Turn the LLM path off for one home, or everywhere when `home_id` is None
+    pub fn disable(&self, home_id: Option<&str>, reason: &str, now: DateTime<Utc>) -> KillSwitch {
+        let switch = KillSwitch { home_id: home_id.map(str::to_string), reason: reason.to_string(), since: now };
+        match home_id {
+            Some(home) => {
+                self.homes.insert(home.to_string(), switch.clone());
+            }
+            None => {
+                if let Ok(mut global) = self.global.lock() {
+                    *global = Some(switch.clone());
+                }
+            }
+        }
+        switch
+    }
+
+    /// Lift a kill switch; returns whether one was set
+    pub fn enable(&self, home_id: Option<&str>) -> bool {
+        match home_id {
+            Some(home) => self.homes.remove(home).is_some(),
+            None => self.global.lock().map(|mut g| g.take().is_some()).unwrap_or(false),
+        }
+    }
+
+    /// Words that must never reach the LLM for a home, such as residents' names
+    pub fn set_redaction_terms(&self, home_id: &str, terms: impl IntoIterator<Item = String>) {
+        let words: HashSet<String> = terms.into_iter()
+            .flat_map(|t| t.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>())
+            .filter(|w| w.chars().count() >= 2)
+            .collect();
+        self.terms.insert(home_id.to_string(), words);
+    }
+
+    /// The request with event-derived strings cleaned and redacted and numbers bounded
+    pub fn sanitize_request(&self, home_id: &str, mut request: LLMSummaryRequest) -> LLMSummaryRequest {
+        let location = self.clean_field(&request.location);
+        request.location = self.redact(Some(home_id), &location);
+        if !DECISIONS.contains(&request.decision.as_str()) {
+            request.decision = "Normal".to_string();
+        }
+        request.dwell_time = if request.dwell_time.is_finite() { request.dwell_time.max(0.0) } else { 0.0 };
+        request.threat_probability = if request.threat_probability.is_finite() { request.threat_probability.clamp(0.0, 1.0) } else { 0.0 };
+        request
+    }
+
+    /// Parse a response body, refusing anything but the exact expected shape
+    pub fn parse_response(&self, body: &str) -> Result<LLMSummaryResponse, LlmGuardError> {
+        let parsed = serde_json::from_str::<Value>(body)
+            .map_err(|e| LlmGuardError::Schema(e.to_string()))
+            .and_then(|value| check_schema(&value).map(|_| value))
+            .and_then(|value| serde_json::from_value(value).map_err(|e| LlmGuardError::Schema(e.to_string())));
+        if parsed.is_err() {
+            self.rejected_responses.fetch_add(1, Ordering::Relaxed);
+        }
+        parsed
+    }
+
+    /// A summary safe to show the household, redacted; errors when it has to be discarded
+    pub fn check_summary(&self, home_id: Option<&str>, summary: &str) -> Result<String, LlmGuardError> {
+        let rejected = |reason: &str| {
+            self.rejected_responses.fetch_add(1, Ordering::Relaxed);
+            Err(LlmGuardError::UnsafeOutput(reason.to_string()))
+        };
+        let summary = summary.trim();
+        let lower = summary.to_lowercase();
+        if summary.is_empty() {
+            return rejected("empty");
+        }
+        if summary.chars().count() > self.config.max_summary_chars {
+            return rejected("too long");
+        }
+        if summary.chars().any(|c| c.is_control() && c != '\n') {
+            return rejected("control characters");
+        }
+        if ["http://", "https://", "www."].iter().any(|l| lower.contains(l)) {
+            return rejected("contains a link");
+        }
+        if summary.contains('<') || summary.contains('>') || summary.contains("```") {
+            return rejected("contains markup");
+        }
+        if OUTPUT_MARKERS.iter().any(|m| lower.contains(m)) {
+            return rejected("echoes prompt structure");
+        }
+        Ok(self.redact(home_id, summary))
+    }
+
+    pub fn report(&self) -> LlmGuardReport {
+        let mut homes: Vec<KillSwitch> = self.homes.iter().map(|s| s.clone()).collect();
+        homes.sort_by(|a, b| a.home_id.cmp(&b.home_id));
+        LlmGuardReport {
+            global: self.global.lock().ok().and_then(|g| g.clone()),
+            homes,
+            filtered_inputs: self.filtered_inputs.load(Ordering::Relaxed),
+            redactions: self.redactions.load(Ordering::Relaxed),
+            rejected_responses: self.rejected_responses.load(Ordering::Relaxed),
+        }
+    }
+
+    // Plain characters only, bounded, and a placeholder for anything that looks like an instruction
+    fn clean_field(&self, text: &str) -> String {
+        let lower = text.to_lowercase();
+        if INJECTION_MARKERS.iter().any(|m| lower.contains(m)) {
+            self.filtered_inputs.fetch_add(1, Ordering::Relaxed);
+            return FILTERED.to_string();
+        }
+        let kept: String = text.chars()
+            .map(|c| if c.is_alphanumeric() || " _-.,'@+()".contains(c) { c } else { ' ' })
+            .collect();
+        let cleaned: String = kept.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(self.config.max_field_chars).collect();
+        if cleaned.is_empty() { "camera".to_string() } else { cleaned }
+    }
+
+    // Names, street addresses, emails and phone numbers, line by line
+    fn redact(&self, home_id: Option<&str>, text: &str) -> String {
+        let terms = home_id.and_then(|h| self.terms.get(h).map(|t| t.clone())).unwrap_or_default();
+        let mut count = 0;
+        let lines: Vec<String> = text.lines().map(|line| redact_line(line, &terms, &mut count)).collect();
+        if count > 0 {
+            self.redactions.fetch_add(count, Ordering::Relaxed);
+        }
+        lines.join("\n")
+    }
+}
+
+fn check_schema(value: &Value) -> Result<(), LlmGuardError> {
+    let schema = |reason: String| Err(LlmGuardError::Schema(reason));
+    let Some(object) = value.as_object() else {
+        return schema("not an object".to_string());
+    };
+    if !object.get("success").is_some_and(Value::is_boolean) {
+        return schema("'success' must be a boolean".to_string());
+    }
+    for (key, field) in object {
+        let valid = match key.as_str() {
+            "success" => true,
+            "summary" | "style" | "model" | "error" | "fallback_reason" => field.is_string() || field.is_null(),
+            "usage" => field.is_null() || field.as_object().is_some_and(|usage| {
+                usage.len() == 2 && ["prompt_tokens", "completion_tokens"].iter().all(|k| usage.get(*k).is_some_and(Value::is_u64))
+            }),
+            _ => return schema(format!("unexpected field '{}'", key)),
+        };
+        if !valid {
+            return schema(format!("'{}' has the wrong type", key));
+        }
+    }
+    Ok(())
+}
+
+fn redact_line(line: &str, terms: &HashSet<String>, count: &mut u64) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let mut out: Vec<String> = Vec::with_capacity(words.len());
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        let bare = bare_word(word);
+
+        if bare.contains('@') && bare.rsplit('@').next().is_some_and(|domain| domain.contains('.')) {
+            out.push(word.replace(bare, "[email]"));
+            *count += 1;
+            i += 1;
+            continue;
+        }
+
+        let run = words[i..].iter().take_while(|w| is_phone_part(w)).count();
+        let digits: usize = words[i..i + run].iter().map(|w| w.chars().filter(char::is_ascii_digit).count()).sum();
+        if run > 0 && (10..=15).contains(&digits) {
+            out.push("[phone]".to_string());
+            *count += 1;
+            i += run;
+            continue;
+        }
+
+        // "12 Elm Street", "4b Station Road": a house number, then capitalized words ending in a street suffix
+        if bare.chars().next().is_some_and(|c| c.is_ascii_digit()) && bare.len() <= 5 {
+            let street = (1..=3).take_while(|j| i + j < words.len() && words[i + j].chars().next().is_some_and(char::is_uppercase))
+                .find(|j| STREET_SUFFIXES.contains(&bare_word(words[i + j]).to_lowercase().as_str()));
+            if let Some(j) = street {
+                let last = words[i + j];
+                out.push(last.replace(bare_word(last), "[address]"));
+                *count += 1;
+                i += j + 1;
+                continue;
+            }
+        }
+
+        if !bare.is_empty() && terms.contains(&bare.to_lowercase()) {
+            out.push(word.replace(bare, "[name]"));
+            *count += 1;
+        } else {
+            out.push(word.to_string());
+        }
+        i += 1;
+    }
+    out.join(" ")
+}
+
+// The word without surrounding punctuation, possessive included ("Sam's," -> "Sam")
+fn bare_word(word: &str) -> &str {
+    let trimmed = word.trim_matches(|c: char| !(c.is_alphanumeric() || c == '@'));
+    trimmed.strip_suffix("'s").unwrap_or(trimmed)
+}
+
+fn is_phone_part(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_digit()) && word.chars().all(|c| c.is_ascii_digit() || "+-().".contains(c))
+}
@@ -1,6 +1,7 @@
 //! LLR Evidence Integration Interface
 
 use super::Evidence;
+use super::evidence_channels::parse_channel_readings;
 use crate::pipeline::RawEvent;
 
 pub trait LLRExtractor {
@@ -30,6 +31,8 @@ impl LLRExtractor for DemoLLRExtractor {
             llr_identity: self.extract_identity_llr(event),
             llr_presence: self.extract_presence_llr(event),
             llr_token: self.extract_token_llr(event),
+            custom: parse_channel_readings(&event.data),
+            ..Default::default()
         }
     }
     
@@ -0,0 +1,321 @@
+//! Modus operandi clustering
+//!
+//! Groups a home's historical incidents by behavioral signature (time of day,
+//! approach path, dwell pattern, entity evidence) so a prowler who comes back on
+//! different nights shows up as one cluster. Clustering is a periodic offline job
+//! (DBSCAN over a weighted signature distance); matching a live incident against
+//! the last run is cheap and feeds a prior boost. History also carries each
+//! incident's threat vector, so a home can see what kinds of threat it gets.
+
+use super::incident_engine::Incident;
+use crate::clock::Clock;
+use crate::core::ThreatVector;
+use chrono::{DateTime, NaiveDate, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone)]
+pub struct MoClusterConfig {
+    pub timezone: String,         // Local time of day is what repeats, not UTC
+    pub eps: f64,                 // Max signature distance for neighbors (0-1)
+    pub min_incidents: usize,     // DBSCAN min points
+    pub min_distinct_days: usize, // A "repeat" visitor must span several days
+    pub history_days: i64,        // Incidents older than this are forgotten
+    pub suspicious_probability: f64, // Clusters below this mean peak probability are routine (postman)
+    pub max_prior_boost: f64,     // Logit added for an exact match with a suspicious cluster
+}
+
+impl Default for MoClusterConfig {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".to_string(),
+            eps: 0.25,
+            min_incidents: 3,
+            min_distinct_days: 2,
+            history_days: 90,
+            suspicious_probability: 0.3,
+            max_prior_boost: 1.0,
+        }
+    }
+}
+
+// Behavioral fingerprint of one incident
+#[derive(Debug, Clone, Serialize)]
+pub struct BehaviorSignature {
+    pub hour_of_day: f64,        // Local, fractional
+    pub entry_camera: Option<String>,
+    pub cameras: Vec<String>,    // Sorted set of cameras visited
+    pub log_dwell: f64,          // ln(1 + total dwell seconds)
+    pub event_count: usize,
+    pub rang_doorbell: bool,
+    pub knocked: bool,
+    pub llr_identity: f64,       // Mean entity evidence
+    pub llr_behavior: f64,
+}
+
+impl BehaviorSignature {
+    pub fn from_incident(incident: &Incident, tz: Tz) -> Self {
+        let local = Utc.timestamp_opt(incident.started_at as i64, 0)
+            .single()
+            .unwrap_or_else(Utc::now)
+            .with_timezone(&tz);
+        let n = incident.events.len().max(1) as f64;
+        let mut cameras: Vec<String> = incident.cameras.iter().cloned().collect();
+        cameras.sort();
+
+        Self {
+            hour_of_day: local.hour() as f64 + local.minute() as f64 / 60.0,
+            entry_camera: incident.events.first().map(|e| e.cam.clone()),
+            cameras,
+            log_dwell: incident.total_dwell().max(0.0).ln_1p(),
+            event_count: incident.events.len(),
+            rang_doorbell: incident.events.iter().any(|e| e.rang_doorbell),
+            knocked: incident.events.iter().any(|e| e.knocked),
+            llr_identity: incident.events.iter().map(|e| e.evidence.llr_identity).sum::<f64>() / n,
+            llr_behavior: incident.events.iter().map(|e| e.evidence.llr_behavior).sum::<f64>() / n,
+        }
+    }
+
+    /// Weighted distance in [0, 1]
+    pub fn distance(&self, other: &BehaviorSignature) -> f64 {
+        // Circular time of day: 23:30 and 00:30 are an hour apart
+        let dh = (self.hour_of_day - other.hour_of_day).abs();
+        let time = dh.min(24.0 - dh) / 12.0;
+
+        let a: HashSet<&String> = self.cameras.iter().collect();
+        let b: HashSet<&String> = other.cameras.iter().collect();
+        let union = a.union(&b).count().max(1) as f64;
+        let jaccard = 1.0 - a.intersection(&b).count() as f64 / union;
+        let entry = if self.entry_camera == other.entry_camera { 0.0 } else { 1.0 };
+        let path = 0.6 * jaccard + 0.4 * entry;
+
+        let dwell = ((self.log_dwell - other.log_dwell).abs() / 3.0).min(1.0);
+        let interaction = ((self.rang_doorbell != other.rang_doorbell) as u8 as f64
+            + (self.knocked != other.knocked) as u8 as f64) / 2.0;
+        let entity = (((self.llr_identity - other.llr_identity).abs()
+            + (self.llr_behavior - other.llr_behavior).abs()) / 4.0).min(1.0);
+
+        0.30 * time + 0.25 * path + 0.15 * dwell + 0.15 * interaction + 0.15 * entity
+    }
+}
+
+// Historical incident as kept for clustering
+#[derive(Debug, Clone)]
+struct HistoricalIncident {
+    incident_id: u64,
+    started_at: DateTime<Utc>,
+    peak_probability: f64,
+    threat_vector: ThreatVector,
+    signature: BehaviorSignature,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MoCluster {
+    pub cluster_id: String,
+    pub home_id: String,
+    pub incident_ids: Vec<u64>,
+    pub exemplar: BehaviorSignature, // Medoid of the cluster
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub distinct_days: usize,
+    pub mean_peak_probability: f64,
+    pub suspicious: bool,
+    pub threat_vectors: BTreeMap<ThreatVector, usize>, // Members per vector
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterMatch {
+    pub cluster_id: String,
+    pub distance: f64,
+    pub prior_boost: f64,
+}
+
+/// DBSCAN over signatures; returns groups of indices (noise is dropped)
+fn dbscan(signatures: &[&BehaviorSignature], eps: f64, min_points: usize) -> Vec<Vec<usize>> {
+    let n = signatures.len();
+    let neighbors: Vec<Vec<usize>> = (0..n)
+        .map(|i| (0..n).filter(|&j| signatures[i].distance(signatures[j]) <= eps).collect())
+        .collect();
+
+    let mut assigned = vec![false; n];
+    let mut clusters = Vec::new();
+    for i in 0..n {
+        if assigned[i] || neighbors[i].len() < min_points {
+            continue;
+        }
+        let mut members = Vec::new();
+        let mut frontier = vec![i];
+        assigned[i] = true;
+        while let Some(p) = frontier.pop() {
+            members.push(p);
+            if neighbors[p].len() < min_points {
+                continue; // Border point: joins but doesn't expand
+            }
+            for &q in &neighbors[p] {
+                if !assigned[q] {
+                    assigned[q] = true;
+                    frontier.push(q);
+                }
+            }
+        }
+        members.sort_unstable();
+        clusters.push(members);
+    }
+    clusters
+}
+
+pub struct MoClusterIndex {
+    config: MoClusterConfig,
+    tz: Tz,
+    history: RwLock<HashMap<String, HashMap<u64, HistoricalIncident>>>,
+    clusters: RwLock<HashMap<String, Vec<MoCluster>>>,
+}
+
+impl MoClusterIndex {
+    pub fn new(config: MoClusterConfig) -> Self {
+        let tz = config.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+        Self {
+            config,
+            tz,
+            history: RwLock::new(HashMap::new()),
+            clusters: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record (or refresh) an incident in a home's history
+    pub async fn record(&self, home_id: &str, incident: &Incident) {
+        let peak_probability = incident.probability_trace.iter()
+            .map(|p| p.calibrated_probability)
+            .fold(0.0, f64::max);
+        let started_at = Utc.timestamp_opt(incident.started_at as i64, 0).single().unwrap_or_else(Utc::now);
+        self.history.write().await
+            .entry(home_id.to_string())
+            .or_default()
+            .insert(incident.id, HistoricalIncident {
+                incident_id: incident.id,
+                started_at,
+                peak_probability,
+                threat_vector: incident.threat_vector,
+                signature: BehaviorSignature::from_incident(incident, self.tz),
+            });
+    }
+
+    /// The offline job: forget old history and rebuild every home's clusters
+    pub async fn recluster(&self, now: DateTime<Utc>) -> usize {
+        let cutoff = now - chrono::Duration::days(self.config.history_days);
+        let mut history = self.history.write().await;
+        for incidents in history.values_mut() {
+            incidents.retain(|_, h| h.started_at >= cutoff);
+        }
+        history.retain(|_, incidents| !incidents.is_empty());
+
+        let mut rebuilt = HashMap::new();
+        let mut total = 0;
+        for (home_id, incidents) in history.iter() {
+            let mut rows: Vec<&HistoricalIncident> = incidents.values().collect();
+            rows.sort_by_key(|h| h.incident_id);
+            let clusters = self.cluster_home(home_id, &rows);
+            total += clusters.len();
+            rebuilt.insert(home_id.clone(), clusters);
+        }
+        drop(history);
+
+        *self.clusters.write().await = rebuilt;
+        total
+    }
+
+    fn cluster_home(&self, home_id: &str, rows: &[&HistoricalIncident]) -> Vec<MoCluster> {
+        let signatures: Vec<&BehaviorSignature> = rows.iter().map(|h| &h.signature).collect();
+        dbscan(&signatures, self.config.eps, self.config.min_incidents)
+            .into_iter()
+            .filter_map(|members| {
+                let days: HashSet<NaiveDate> = members.iter()
+                    .map(|&i| rows[i].started_at.with_timezone(&self.tz).date_naive())
+                    .collect();
+                if days.len() < self.config.min_distinct_days {
+                    return None;
+                }
+
+                // Medoid: member with the smallest total distance to the others
+                let medoid = *members.iter().min_by(|&&a, &&b| {
+                    let da: f64 = members.iter().map(|&j| signatures[a].distance(signatures[j])).sum();
+                    let db: f64 = members.iter().map(|&j| signatures[b].distance(signatures[j])).sum();
+                    da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
+                })?;
+
+                let incident_ids: Vec<u64> = members.iter().map(|&i| rows[i].incident_id).collect();
+                let mean_peak_probability = members.iter().map(|&i| rows[i].peak_probability).sum::<f64>()
+                    / members.len() as f64;
+                let mut threat_vectors = BTreeMap::new();
+                for &i in &members {
+                    *threat_vectors.entry(rows[i].threat_vector).or_insert(0) += 1;
+                }
+                Some(MoCluster {
+                    cluster_id: format!("mo_{}_{}", home_id, incident_ids[0]),
+                    home_id: home_id.to_string(),
+                    exemplar: signatures[medoid].clone(),
+                    first_seen: members.iter().map(|&i| rows[i].started_at).min()?,
+                    last_seen: members.iter().map(|&i| rows[i].started_at).max()?,
+                    distinct_days: days.len(),
+                    suspicious: mean_peak_probability >= self.config.suspicious_probability,
+                    mean_peak_probability,
+                    threat_vectors,
+                    incident_ids,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn clusters(&self, home_id: &str) -> Vec<MoCluster> {
+        self.clusters.read().await.get(home_id).cloned().unwrap_or_default()
+    }
+
+    /// Incidents in the home's retained history per threat vector, every vector listed
+    pub async fn threat_vector_counts(&self, home_id: &str) -> BTreeMap<ThreatVector, usize> {
+        let mut counts: BTreeMap<ThreatVector, usize> = ThreatVector::ALL.iter().map(|v| (*v, 0)).collect();
+        if let Some(incidents) = self.history.read().await.get(home_id) {
+            for h in incidents.values() {
+                *counts.entry(h.threat_vector).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Closest suspicious cluster within eps of the incident, with the prior boost it earns
+    pub async fn match_incident(&self, home_id: &str, incident: &Incident) -> Option<ClusterMatch> {
+        let signature = BehaviorSignature::from_incident(incident, self.tz);
+        let clusters = self.clusters.read().await;
+        clusters.get(home_id)?
+            .iter()
+            .filter(|c| c.suspicious && !c.incident_ids.contains(&incident.id))
+            .map(|c| (c, c.exemplar.distance(&signature)))
+            .filter(|(_, d)| *d <= self.config.eps)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(c, distance)| ClusterMatch {
+                cluster_id: c.cluster_id.clone(),
+                distance,
+                prior_boost: self.config.max_prior_boost * (1.0 - distance / self.config.eps),
+            })
+    }
+
+    /// Run `recluster` on a fixed interval, as of `clock`'s time
+    pub fn spawn_reclustering(self: Arc<Self>, every: std::time::Duration, clock: Arc<dyn Clock>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                let found = self.recluster(clock.now()).await;
+                tracing::info!("MO clustering run found {} cluster(s)", found);
+            }
+        })
+    }
+}
+
+impl Default for MoClusterIndex {
+    fn default() -> Self {
+        Self::new(MoClusterConfig::default())
+    }
+}
@@ -10,13 +10,34 @@ pub mod decision_counterfactuals;
 pub mod summarizer;
 pub mod llr_integration;
 pub mod llm_client;
+pub mod llm_budget;
+pub mod llm_guard;
+pub mod evidence_bundle;
+pub mod mo_clustering;
+pub mod what_if;
+pub mod weight_learning;
+pub mod lifecycle;
+pub mod sensor_reliability;
+pub mod adversarial_handoff;
+pub mod escalation_survival;
+pub mod probability;
+pub mod prior_model;
+pub mod voi_calibration;
+pub mod evidence_channels;
+pub mod camera_dedup;
+pub mod uncertainty;
 
 // Re-export key types for easy access
 pub use incident_engine::{
-    Evidence, Event, Incident, IncidentStore, IncidentStatus,
+    Evidence, Event, Incident, IncidentStore, IncidentStatus, ProbabilityTracePoint,
+    IncidentStoreSnapshot, IncidentSnapshotEntry, MergedTrack,
     sigmoid, calibrate_logit
 };
 
+pub use probability::{
+    clamp_llr, logit, ProbabilityCalibrator, TemperatureCalibration, PROBABILITY_EPSILON
+};
+
 pub use active_reasoner::{
     Question, QuestionProposal, ReasonerConfig, generate_questions
 };
@@ -26,13 +47,71 @@ pub use decision_counterfactuals::{
 };
 
 pub use summarizer::{
-    summarize_incident
+    run_deferred_narratives, set_llm_budget, set_llm_guard, summarize_incident
+};
+
+pub use llm_budget::{
+    Admission, DeferredNarrative, HomeLlmUsage, LlmBudget, LlmBudgetConfig, LlmPriority, LlmUsage, LlmUsageReport
 };
 
+pub use llm_guard::{KillSwitch, LlmGuard, LlmGuardConfig, LlmGuardError, LlmGuardReport};
+
 pub use llr_integration::{LLRExtractor, DemoLLRExtractor};
 
+pub use evidence_bundle::{EvidenceBundle, BundleError, export_incident_bundle};
+
+pub use mo_clustering::{
+    BehaviorSignature, ClusterMatch, MoCluster, MoClusterConfig, MoClusterIndex
+};
+
+pub use weight_learning::{
+    ChannelWeights, LearnedWeights, OnlineWeightLearner, OutcomeSource, WeightLearnerConfig
+};
+
+pub use sensor_reliability::{
+    SensorReliability, SensorReliabilityConfig, SensorReliabilityModel, SensorStats
+};
+
+pub use adversarial_handoff::{
+    AdversarialAnalyzer, AdversarialAssessment, AdversarialFindings, HandoffConfig, HandoffError, HandoffRequest
+};
+
+pub use lifecycle::{IncidentLifecycleHook, IncidentTransition, LifecycleError};
+
+pub use escalation_survival::{
+    EscalationEstimate, EscalationSurvivalConfig, EscalationSurvivalModel, HazardCurve, RiskLevel, SurvivalPoint, SurvivalSample
+};
+
+pub use prior_model::{
+    CellFeedback, EntityClass, OutcomeTally, PriorCell, PriorEditError, PriorGuardrails, PriorModelRegistry, PriorRule, PriorVersion, TimeBucket
+};
+
+pub use voi_calibration::{
+    PendingQuestions, QuestionKind, QuestionStats, QuestionValue, VoiCalibrationConfig, VoiCalibrator
+};
+
+pub use evidence_channels::{
+    ChannelDescriptor, ChannelError, ChannelFusion, ChannelRegistry, ChannelSaturation, Saturation, channel_descriptors, register_channel
+};
+
+pub use camera_dedup::{CameraDeduplicator, CameraOverlap};
+
+pub use uncertainty::{ProbabilityInterval, UncertaintyPolicy};
+
+pub use what_if::{
+    ConfigOutcome, IncidentLabel, ReplayedIncident, WhatIfReport, compare_configs, replay
+};
+
+use dashmap::DashMap;
+use crate::core::ThreatVector;
+use crate::environment::{CalendarConfig, HomeCalendars};
+use crate::explanation::{config_hash, Explanation, KeyCounterfactual};
+use crate::redaction::FaceRegion;
+use evidence_channels::channel_label;
+
 /// Configuration for the thinking AI system
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct ThinkingAIConfig {
     /// TTL for incidents in seconds
     pub incident_ttl_secs: f64,
@@ -52,6 +131,15 @@ pub struct ThinkingAIConfig {
     pub alert_threshold_logit: f64,
     /// Reasoner configuration
     pub reasoner_config: ReasonerConfig,
+    /// Default calendar for darkness/holiday prior adjustment, for homes without
+    /// their own (None keeps a flat prior until a home's calendar is set)
+    pub calendar: Option<CalendarConfig>,
+    /// Extra evidence channels from deployment-specific sensors, registered at startup
+    pub custom_channels: Vec<ChannelDescriptor>,
+    /// Share of assessments at a cap above which a channel's saturation is chronic
+    pub saturation_warn_rate: f64,
+    /// Assessments a channel needs before its saturation can be called chronic
+    pub saturation_min_assessments: u64,
 }
 
 impl Default for ThinkingAIConfig {
@@ -66,6 +154,10 @@ impl Default for ThinkingAIConfig {
             neg_cap: 3.0,
             alert_threshold_logit: -1.7346, // logit(0.15)
             reasoner_config: ReasonerConfig::default(),
+            calendar: None,
+            custom_channels: Vec::new(),
+            saturation_warn_rate: 0.3,
+            saturation_min_assessments: 50,
         }
     }
 }
@@ -80,10 +172,23 @@ pub struct ThinkingAIResult {
     pub top_questions: Vec<QuestionProposal>,
     pub counterfactuals: Vec<CounterfactualSuggestion>,
     pub alert_decision: AlertDecision,
+    pub channel_weights: Option<ChannelWeights>, // Learned per-channel scaling applied, if any
+    pub explanation: Explanation,
+    pub interval: ProbabilityInterval, // Uncertainty around calibrated_probability
+    pub threat_vector: ThreatVector,
+}
+
+/// A quick score for an event not yet fused: what its incident would look
+/// like with it, before VPS analysis, adversarial review or rules
+#[derive(Debug, Clone)]
+pub struct ProvisionalAssessment {
+    pub incident_id: Option<u64>, // The open incident the event would join, if any
+    pub probability: f64,
+    pub decision: AlertDecision,  // Without the uncertainty hold
 }
 
 /// Alert decision based on thinking AI analysis with severity levels
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub enum AlertDecision {
     /// No action needed - threat probability is very low
     Ignore,
@@ -123,72 +228,611 @@ impl AlertDecision {
     }
 }
 
-/// Main thinking AI processor that orchestrates the entire analysis pipeline
+// Incidents and open questions for one home. Each shard sits behind its own
+// DashMap entry, so events for different homes never wait on each other.
+#[derive(Debug, Clone)]
+struct HomeShard {
+    store: IncidentStore,
+    pending_questions: std::collections::HashMap<u64, PendingQuestions>, // Latest proposals per incident
+}
+
+/// Main thinking AI processor that orchestrates the entire analysis pipeline.
+///
+/// Per-home state is sharded, so every per-home method takes `&self` and
+/// the processor can be shared across tasks without an outer lock.
 #[derive(Debug, Clone)]
 pub struct ThinkingAIProcessor {
     config: ThinkingAIConfig,
-    incident_stores: std::collections::HashMap<String, IncidentStore>,
+    calendars: std::sync::Arc<HomeCalendars>, // Per-home location, timezone and holidays
+    shards: DashMap<String, HomeShard>,
+    visual_reliability: DashMap<String, f64>, // Per-home weather/light factor
+    prior_offsets: DashMap<String, f64>, // Per-home prior shifts, e.g. neighborhood reports
+    channel_weights: DashMap<String, ChannelWeights>, // Per-home weights learned from outcomes
+    sensor_reliability: DashMap<String, std::collections::HashMap<String, f64>>, // Per-home, per-sensor learned reliability
+    alert_thresholds: DashMap<String, f64>, // Per-home alert threshold logit, from onboarding profiles
+    zone_priors: DashMap<String, std::collections::HashMap<String, f64>>, // Per-home, per-camera zone prior logit
+    prior_model: Option<std::sync::Arc<PriorModelRegistry>>, // User-edited base rates; override the config and zone priors where a rule matches
+    calibrator: std::sync::Arc<dyn ProbabilityCalibrator>, // Fused logit -> reported probability
+    voi: std::sync::Arc<VoiCalibrator>, // Question rankings scaled by how often answers changed decisions
+    dedup: CameraDeduplicator, // Folds one person seen on overlapping cameras into one incident
+    uncertainty: DashMap<String, UncertaintyPolicy>, // Per-home interval policy, from onboarding profiles
+    clock: std::sync::Arc<dyn crate::clock::Clock>, // Decides when idle incidents pass their TTL
+    saturation: DashMap<String, ChannelSaturation>, // Per-channel counts of assessments fused at a cap
+    config_hash: String,
 }
 
 impl ThinkingAIProcessor {
     pub fn new(config: ThinkingAIConfig) -> Self {
+        for channel in &config.custom_channels {
+            match register_channel(channel.clone()) {
+                Ok(()) | Err(ChannelError::Duplicate(_)) => {}
+                Err(e) => tracing::warn!("Ignoring custom evidence channel: {}", e),
+            }
+        }
         Self {
+            calendars: std::sync::Arc::new(HomeCalendars::new(config.calendar.clone())),
+            shards: DashMap::new(),
+            visual_reliability: DashMap::new(),
+            prior_offsets: DashMap::new(),
+            channel_weights: DashMap::new(),
+            sensor_reliability: DashMap::new(),
+            alert_thresholds: DashMap::new(),
+            zone_priors: DashMap::new(),
+            prior_model: None,
+            calibrator: std::sync::Arc::new(TemperatureCalibration {
+                mean_logit: config.mean_logit,
+                temperature: config.temperature,
+                odds_cap: config.odds_cap,
+            }),
+            voi: std::sync::Arc::new(VoiCalibrator::default()),
+            dedup: CameraDeduplicator::new(config.incident_ttl_secs),
+            uncertainty: DashMap::new(),
+            clock: crate::clock::system_clock(),
+            saturation: DashMap::new(),
+            config_hash: config_hash(&config),
             config,
-            incident_stores: std::collections::HashMap::new(),
         }
     }
 
+    /// Expire idle incidents and date their summaries by `clock`; replays swap
+    /// in a simulated one so TTLs follow recorded event times
+    pub fn set_clock(&mut self, clock: std::sync::Arc<dyn crate::clock::Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn clock(&self) -> &std::sync::Arc<dyn crate::clock::Clock> {
+        &self.clock
+    }
+
+    /// Share question-value history, e.g. with the pipeline's reporting
+    pub fn set_voi_calibrator(&mut self, voi: std::sync::Arc<VoiCalibrator>) {
+        self.voi = voi;
+    }
+
+    pub fn voi_calibrator(&self) -> &std::sync::Arc<VoiCalibrator> {
+        &self.voi
+    }
+
+    /// Replace the temperature calibration from the config, e.g. with a fitted curve
+    pub fn set_calibrator(&mut self, calibrator: std::sync::Arc<dyn ProbabilityCalibrator>) {
+        self.calibrator = calibrator;
+    }
+
+    /// Set how far camera evidence for a home can be trusted under current conditions
+    pub fn set_visual_reliability(&self, home: &str, reliability: f64) {
+        self.visual_reliability.insert(home.to_string(), reliability);
+    }
+
+    /// Shift the threat prior for a home, e.g. from corroborated neighborhood reports
+    pub fn set_prior_offset(&self, home: &str, logit: f64) {
+        if logit == 0.0 {
+            self.prior_offsets.remove(home);
+        } else {
+            self.prior_offsets.insert(home.to_string(), logit);
+        }
+    }
+
+    /// Scale a home's evidence channels by weights learned from labeled outcomes
+    pub fn set_channel_weights(&self, home: &str, weights: ChannelWeights) {
+        self.channel_weights.insert(home.to_string(), weights);
+    }
+
+    /// Discount each sensor's evidence by how often it agreed with final outcomes
+    pub fn set_sensor_reliability(&self, home: &str, reliability: std::collections::HashMap<String, f64>) {
+        if reliability.is_empty() {
+            self.sensor_reliability.remove(home);
+        } else {
+            self.sensor_reliability.insert(home.to_string(), reliability);
+        }
+    }
+
+    /// Override the configured alert threshold for one home
+    pub fn set_alert_threshold(&self, home: &str, threshold_logit: f64) {
+        self.alert_thresholds.insert(home.to_string(), threshold_logit);
+    }
+
+    pub fn alert_threshold(&self, home: &str) -> f64 {
+        self.alert_thresholds.get(home).map_or(self.config.alert_threshold_logit, |t| *t)
+    }
+
+    /// Groups of cameras with overlapping fields of view, whose simultaneous
+    /// detections are one person
+    pub fn set_camera_overlaps(&self, home: &str, overlaps: Vec<CameraOverlap>) {
+        self.dedup.set_overlaps(home, overlaps);
+    }
+
+    /// How much doubt about an alert a home tolerates before it is held as Wait
+    pub fn set_uncertainty_policy(&self, home: &str, policy: UncertaintyPolicy) {
+        self.uncertainty.insert(home.to_string(), policy);
+    }
+
+    pub fn uncertainty_policy(&self, home: &str) -> UncertaintyPolicy {
+        self.uncertainty.get(home).map(|p| p.clone()).unwrap_or_default()
+    }
+
+    /// Prior shift per camera for a home, from the zone each camera watches
+    pub fn set_zone_priors(&self, home: &str, camera_priors: std::collections::HashMap<String, f64>) {
+        if camera_priors.is_empty() {
+            self.zone_priors.remove(home);
+        } else {
+            self.zone_priors.insert(home.to_string(), camera_priors);
+        }
+    }
+
+    // Riskiest zone the incident has been seen in; 0 when no zone priors are set
+    fn zone_prior(&self, home: &str, incident: &Incident) -> f64 {
+        let Some(priors) = self.zone_priors.get(home) else { return 0.0 };
+        incident.cameras.iter()
+            .filter_map(|cam| priors.get(cam).copied())
+            .reduce(f64::max)
+            .unwrap_or(0.0)
+    }
+
+    /// Use the home's location, timezone and holidays for its darkness and holiday prior
+    pub fn set_calendar(&self, home: &str, calendar: CalendarConfig) {
+        self.calendars.register(home, calendar);
+    }
+
+    /// The per-home calendars, for other scorers that weigh time of day
+    pub fn calendars(&self) -> std::sync::Arc<HomeCalendars> {
+        self.calendars.clone()
+    }
+
+    /// Take base rates from user-edited prior rules where one matches
+    pub fn set_prior_model(&mut self, registry: std::sync::Arc<PriorModelRegistry>) {
+        self.prior_model = Some(registry);
+    }
+
+    // Starting prior before calendar and offset adjustments: an edited base
+    // rate when a rule covers the incident, else the config prior plus zone shift
+    fn base_prior_logit(&self, home: &str, incident: &Incident) -> f64 {
+        self.prior_model.as_ref()
+            .and_then(|m| m.base_logit(home, incident))
+            .unwrap_or_else(|| self.config.prior_logit + self.zone_prior(home, incident))
+    }
+
+    /// Prior logit for a home at the given event time, adjusted for darkness, holidays and offsets
+    pub fn prior_logit_at(&self, home: &str, ts: f64) -> f64 {
+        self.config.prior_logit + self.prior_adjustment(home, ts)
+    }
+
+    fn prior_adjustment(&self, home: &str, ts: f64) -> f64 {
+        let adjustment = self.calendars.prior_logit_adjustment_ts(home, ts);
+        let offset = self.prior_offsets.get(home).map_or(0.0, |o| *o);
+        adjustment + offset
+    }
+
     /// Process an event through the thinking AI pipeline
-    pub fn process_event(&mut self, home: &str, event: Event) -> Option<ThinkingAIResult> {
-        // Get or create incident store for this home
-        let store = self.incident_stores
-            .entry(home.to_string())
-            .or_insert_with(|| IncidentStore::new(self.config.incident_ttl_secs));
-
-        // Upsert event into incident store
-        let incident_id = store.upsert_event(home, event);
-
-        // Get the incident for analysis
-        if let Some(incident) = store.incidents.values().find(|i| i.id == incident_id) {
-            // Fuse evidence
-            let fused = incident.fused_evidence(self.config.pos_cap, self.config.neg_cap);
-            
-            // Calibrate probability
-            let raw_logit = self.config.prior_logit + fused.sum();
-            let calibrated_prob = calibrate_logit(
-                raw_logit,
-                self.config.mean_logit,
-                self.config.temperature,
-                self.config.odds_cap
-            );
-
-            // Generate narrative summary
-            let summary = summarize_incident(incident, &fused, calibrated_prob, incident.suppressed_count);
-
-            // Generate questions
-            let questions = generate_questions(incident, &fused, self.config.prior_logit, &self.config.reasoner_config);
-
-            // Generate counterfactuals
-            let counterfactuals = minimal_changes_to_threshold(&fused, self.config.prior_logit, self.config.alert_threshold_logit);
-
-            // Make alert decision
-            let alert_decision = AlertDecision::from_probability(
-                calibrated_prob,
-                sigmoid(self.config.alert_threshold_logit),
-                sigmoid(self.config.alert_threshold_logit) * 0.5 // Wait threshold is half of alert threshold
-            );
-
-            Some(ThinkingAIResult {
-                incident_id,
-                fused_evidence: fused,
-                calibrated_probability: calibrated_prob,
-                narrative_summary: summary,
-                top_questions: questions.into_iter().take(5).collect(),
-                counterfactuals,
+    pub fn process_event(&self, home: &str, event: Event) -> Option<ThinkingAIResult> {
+        self.process_flagged_event(home, event, Vec::new())
+    }
+
+    /// Process an event whose inputs were repaired by sanitization; the flags
+    /// stay on the incident and show up in its explanation
+    pub fn process_flagged_event(&self, home: &str, mut event: Event, data_quality: Vec<String>) -> Option<ThinkingAIResult> {
+        // The same person on an overlapping camera joins the incident already open for them
+        if let Some(track) = self.dedup.canonical_track(home, &event) {
+            tracing::debug!("Track {} on {} in {} deduplicated into track {}", event.person_track, event.cam, home, track);
+            event.person_track = track;
+        }
+
+        // Upsert event into this home's incident store, creating it on first sight
+        let (incident_id, pending) = {
+            let mut shard = self.shards
+                .entry(home.to_string())
+                .or_insert_with(|| HomeShard {
+                    store: IncidentStore::new(self.config.incident_ttl_secs),
+                    pending_questions: std::collections::HashMap::new(),
+                });
+            let incident_id = shard.store.upsert_event(home, event.clone());
+            if !data_quality.is_empty() {
+                if let Some(incident) = shard.store.incidents.values_mut().find(|i| i.id == incident_id) {
+                    incident.data_quality.extend(data_quality);
+                }
+            }
+
+            // Did this event answer what the last assessment asked?
+            (incident_id, shard.pending_questions.remove(&incident_id))
+        };
+        let result = self.reassess_incident(home, incident_id)?;
+        if let Some(pending) = pending {
+            self.voi.record_answers(&pending, &event, &result.alert_decision);
+        }
+        Some(result)
+    }
+
+    /// Re-score an incident with the current priors and weights without adding evidence
+    pub fn reassess_incident(&self, home: &str, incident_id: u64) -> Option<ThinkingAIResult> {
+        let result = self.assess(home, incident_id)?;
+
+        // Keep a trace of every assessment for later review and evidence export
+        if let Some(mut shard) = self.shards.get_mut(home) {
+            let HomeShard { store, pending_questions } = &mut *shard;
+            if let Some(incident) = store.incidents.values_mut().find(|i| i.id == incident_id) {
+                incident.threat_vector = result.threat_vector;
+                incident.record_assessment(
+                    result.fused_evidence.sum(),
+                    result.calibrated_probability,
+                    result.alert_decision.clone(),
+                    &result.narrative_summary,
+                );
+                let pending = PendingQuestions::new(incident, result.alert_decision.clone(), &result.top_questions);
+                self.voi.record_proposed(&pending);
+                pending_questions.insert(incident_id, pending);
+            }
+        }
+        self.sync_status(home, incident_id, &result.alert_decision);
+
+        Some(result)
+    }
+
+    fn assess(&self, home: &str, incident_id: u64) -> Option<ThinkingAIResult> {
+        // Read-locks only this home's shard; nothing below writes to the shards
+        let shard = self.shards.get(home)?;
+        let incident = shard.store.incidents.values().find(|i| i.id == incident_id)?;
+        let channel_weights = self.channel_weights.get(home).map(|w| *w);
+        let prior_logit = self.base_prior_logit(home, incident) + self.prior_adjustment(home, incident.last_updated) + channel_weights.map_or(0.0, |w| w.bias);
+        let threshold_logit = self.alert_threshold(home);
+
+        // Fuse evidence
+        let fused = self.fuse(home, incident, channel_weights.as_ref());
+        self.record_saturation(&fused);
+
+        // Calibrate probability
+        let raw_logit = prior_logit + fused.sum();
+        let calibrated_prob = self.calibrator.calibrate(raw_logit);
+
+        // Generate narrative summary
+        let mut summary = summarize_incident(home, incident, &fused, calibrated_prob, incident.suppressed_count, self.clock.now());
+        let threat_vector = incident.classify_vector(&fused);
+        if threat_vector != ThreatVector::Unknown {
+            summary.push_str(&format!(" Pattern: {}.", threat_vector.label()));
+        }
+        if let Some(adversarial) = incident.adversarial.as_ref().filter(|a| !a.summary.is_empty()) {
+            summary.push_str(&format!(" Adversarial review: {}.", adversarial.summary));
+            if !adversarial.countermeasures.is_empty() {
+                summary.push_str(&format!(" Suggested: {}.", adversarial.countermeasures.join("; ")));
+            }
+        }
+
+        // Generate questions
+        let mut questions = generate_questions(incident, &fused, prior_logit, &self.config.reasoner_config);
+        self.voi.calibrate(&mut questions);
+
+        // Generate counterfactuals
+        let counterfactuals = minimal_changes_to_threshold(&fused, prior_logit, threshold_logit);
+
+        // Make alert decision
+        let mut alert_decision = AlertDecision::from_probability(
+            calibrated_prob,
+            sigmoid(threshold_logit),
+            sigmoid(threshold_logit) * 0.5 // Wait threshold is half of alert threshold
+        );
+
+        // Hold alerts whose interval reaches too far below the threshold
+        let policy = self.uncertainty_policy(home);
+        let sd = uncertainty::logit_sd(incident, policy.base_logit_sd, |subset| self.fuse(home, subset, channel_weights.as_ref()).sum());
+        let mut interval = uncertainty::interval(self.calibrator.as_ref(), raw_logit, sd, sigmoid(threshold_logit), policy.confidence_level);
+        if uncertainty::hold(&policy, &alert_decision, &interval) {
+            summary.push_str(&format!(
+                " Holding {:?} alert for a re-check: {:.0}% of the {:.0}% interval ({:.0}%-{:.0}%) is below the threshold.",
                 alert_decision,
+                interval.below_threshold_share * 100.0,
+                interval.confidence_level * 100.0,
+                interval.lower * 100.0,
+                interval.upper * 100.0,
+            ));
+            interval.held = Some(std::mem::replace(&mut alert_decision, AlertDecision::Wait));
+        }
+
+        let mut explanation = explain(&fused, prior_logit, raw_logit, threshold_logit, &counterfactuals, self.config_hash.clone())
+            .with_data_quality(incident.data_quality.clone());
+        for channel in fused.saturated.keys().filter_map(|name| self.channel_saturation(name)).filter(|c| c.chronic) {
+            explanation = explanation.with_caution(format!(
+                "{} sat at a cap in {:.0}% of {} assessments; its extractor may be miscalibrated",
+                channel_label(&channel.channel), channel.rate() * 100.0, channel.assessments,
+            ));
+        }
+
+        Some(ThinkingAIResult {
+            incident_id,
+            fused_evidence: fused,
+            calibrated_probability: calibrated_prob,
+            narrative_summary: summary,
+            top_questions: questions.into_iter().take(5).collect(),
+            counterfactuals,
+            alert_decision,
+            channel_weights,
+            explanation,
+            interval,
+            threat_vector,
+        })
+    }
+
+    /// Score an event against the incident it would join, without storing it
+    pub fn provisional_assessment(&self, home: &str, event: &Event) -> ProvisionalAssessment {
+        // Overlapping-camera dedup records sightings, so it waits for the real fusion
+        let open = self.track_incident(home, &event.person_track);
+        let incident_id = open.as_ref().map(|i| i.id);
+        let mut incident = open.unwrap_or_else(|| Incident::new(0, event.ts, event.person_track.clone()));
+        incident.add_event(event.clone());
+
+        let channel_weights = self.channel_weights.get(home).map(|w| *w);
+        let prior_logit = self.base_prior_logit(home, &incident) + self.prior_adjustment(home, incident.last_updated) + channel_weights.map_or(0.0, |w| w.bias);
+        let probability = self.calibrator.calibrate(prior_logit + self.fuse(home, &incident, channel_weights.as_ref()).sum());
+        let threshold = sigmoid(self.alert_threshold(home));
+        ProvisionalAssessment {
+            incident_id,
+            probability,
+            decision: AlertDecision::from_probability(probability, threshold, threshold * 0.5),
+        }
+    }
+
+    // Fused evidence for an incident, after sensor, channel, visibility and adversarial adjustments
+    fn fuse(&self, home: &str, incident: &Incident, channel_weights: Option<&ChannelWeights>) -> Evidence {
+        let visual_reliability = self.visual_reliability.get(home).map_or(1.0, |r| *r);
+        let sensors = self.sensor_reliability.get(home);
+        let mut fused = incident.fused_evidence_with(self.config.pos_cap, self.config.neg_cap, |cam| sensors.as_ref().and_then(|s| s.get(cam).copied()));
+        if let Some(weights) = channel_weights {
+            fused = fused.scaled(weights);
+        }
+        let mut fused = fused.with_visual_reliability(visual_reliability);
+        if let Some(adversarial) = &incident.adversarial {
+            fused.set_capped("behavior", fused.llr_behavior + adversarial.adjustment_llr, self.config.neg_cap, self.config.pos_cap);
+        }
+        fused
+    }
+
+    /// Record a decision made outside normal scoring, e.g. a resolved Wait follow-up
+    pub fn override_decision(&self, home: &str, incident_id: u64, decision: AlertDecision, note: &str) {
+        if let Some(incident) = self.shards.get_mut(home).as_mut()
+            .and_then(|s| s.store.incidents.values_mut().find(|i| i.id == incident_id))
+        {
+            let (fused_llr, probability) = incident.probability_trace.last()
+                .map(|p| (p.fused_llr, p.calibrated_probability))
+                .unwrap_or((0.0, 0.0));
+            let narrative = match &incident.last_narrative {
+                Some(previous) => format!("{} ({})", previous, note),
+                None => note.to_string(),
+            };
+            incident.record_assessment(fused_llr, probability, decision.clone(), &narrative);
+        }
+        self.sync_status(home, incident_id, &decision);
+    }
+
+    // Waiting mirrors a Wait decision; closed incidents are left alone
+    fn sync_status(&self, home: &str, incident_id: u64, decision: &AlertDecision) {
+        let Some(mut shard) = self.shards.get_mut(home) else { return };
+        let store = &mut shard.store;
+        let Some(incident) = store.incidents.values().find(|i| i.id == incident_id) else { return };
+        if !incident.status.is_active() {
+            return;
+        }
+        let (to, reason) = match decision {
+            AlertDecision::Wait => (IncidentStatus::Waiting, "assessment abstained"),
+            _ => (IncidentStatus::Open, "assessment decided"),
+        };
+        let ts = incident.last_updated;
+        store.set_status(home, incident_id, to, ts, reason);
+    }
+
+    /// Close an incident by hand (or on auto-resolution); only Resolved and Dismissed may be set
+    pub fn close_incident(&self, home: &str, incident_id: u64, to: IncidentStatus, ts: f64, reason: &str) -> Result<IncidentTransition, LifecycleError> {
+        if !matches!(to, IncidentStatus::Resolved | IncidentStatus::Dismissed) {
+            return Err(LifecycleError::InvalidTarget(to));
+        }
+        let mut shard = self.shards.get_mut(home).ok_or(LifecycleError::NotFound(incident_id))?;
+        let store = &mut shard.store;
+        let status = store.incidents.values()
+            .find(|i| i.id == incident_id)
+            .map(|i| i.status)
+            .ok_or(LifecycleError::NotFound(incident_id))?;
+        if !status.is_active() {
+            return Err(LifecycleError::AlreadyClosed(incident_id, status));
+        }
+        store.set_status(home, incident_id, to, ts, reason).ok_or(LifecycleError::AlreadyClosed(incident_id, status))
+    }
+
+    /// Merge `source_id` into `target_id` after correlation split one visit in two,
+    /// then re-score the combined incident
+    pub fn merge_incidents(&self, home: &str, target_id: u64, source_id: u64, ts: f64) -> Result<ThinkingAIResult, LifecycleError> {
+        self.shards.get_mut(home).ok_or(LifecycleError::NotFound(target_id))?
+            .store.merge(home, target_id, source_id, ts)?;
+        self.reassess_incident(home, target_id).ok_or(LifecycleError::NotFound(target_id))
+    }
+
+    /// Move events out of an incident that correlation wrongly joined, then re-score
+    /// both halves. Returns (original, split-off) results.
+    pub fn split_incident(&self, home: &str, incident_id: u64, event_indices: &[usize]) -> Result<(ThinkingAIResult, ThinkingAIResult), LifecycleError> {
+        let split_id = self.shards.get_mut(home).ok_or(LifecycleError::NotFound(incident_id))?
+            .store.split(home, incident_id, event_indices)?;
+        let original = self.reassess_incident(home, incident_id).ok_or(LifecycleError::NotFound(incident_id))?;
+        let split = self.reassess_incident(home, split_id).ok_or(LifecycleError::NotFound(split_id))?;
+        Ok((original, split))
+    }
+
+    /// Expire incidents in every home that have seen no events within the TTL
+    pub fn expire_incidents(&self, now_ts: f64) {
+        for mut shard in self.shards.iter_mut() {
+            let HomeShard { store, pending_questions } = &mut *shard;
+            store.expire_stale(now_ts);
+            // Questions about finished incidents can no longer be answered
+            pending_questions.retain(|id, _| store.incidents.values().any(|i| i.id == *id && i.status.is_active()));
+        }
+    }
+
+    /// Expire incidents past their TTL as of the processor's clock
+    pub fn expire_due_incidents(&self) {
+        self.expire_incidents(self.clock.now_ts());
+    }
+
+    /// Transitions recorded since the last call, oldest first
+    pub fn take_transitions(&self) -> Vec<IncidentTransition> {
+        let mut transitions: Vec<IncidentTransition> = self.shards.iter_mut()
+            .flat_map(|mut s| s.store.transitions.drain(..).collect::<Vec<_>>())
+            .collect();
+        transitions.sort_by(|a, b| a.ts.partial_cmp(&b.ts).unwrap_or(std::cmp::Ordering::Equal));
+        transitions
+    }
+
+    /// All open incidents across every home, as (home_id, incident)
+    pub fn open_incidents(&self) -> Vec<(String, Incident)> {
+        self.shards
+            .iter()
+            .flat_map(|shard| {
+                shard.store.incidents.values()
+                    .filter(|i| i.status.is_active())
+                    .map(|i| (shard.key().clone(), i.clone()))
+                    .collect::<Vec<_>>()
             })
-        } else {
-            None
+            .collect()
+    }
+
+    pub fn config(&self) -> &ThinkingAIConfig {
+        &self.config
+    }
+
+    fn record_saturation(&self, fused: &Evidence) {
+        let (min_assessments, warn_rate) = (self.config.saturation_min_assessments, self.config.saturation_warn_rate);
+        for (name, _) in fused.channels() {
+            let mut counts = self.saturation.entry(name.clone()).or_insert_with(|| ChannelSaturation::new(&name));
+            let was_chronic = counts.clone().with_chronic(min_assessments, warn_rate).chronic;
+            counts.record(fused.saturation(&name));
+            if !was_chronic && counts.clone().with_chronic(min_assessments, warn_rate).chronic {
+                tracing::warn!("Evidence channel '{}' saturates in {:.0}% of {} assessments; check its extractor's calibration", name, counts.rate() * 100.0, counts.assessments);
+            }
+        }
+    }
+
+    /// One channel's saturation counts, with the chronic flag from the config
+    pub fn channel_saturation(&self, channel: &str) -> Option<ChannelSaturation> {
+        self.saturation.get(channel).map(|c| c.clone().with_chronic(self.config.saturation_min_assessments, self.config.saturation_warn_rate))
+    }
+
+    /// Saturation counts for every channel assessed so far, sorted by name
+    pub fn saturation_report(&self) -> Vec<ChannelSaturation> {
+        let mut report: Vec<ChannelSaturation> = self.saturation.iter()
+            .map(|c| c.value().clone().with_chronic(self.config.saturation_min_assessments, self.config.saturation_warn_rate))
+            .collect();
+        report.sort_by(|a, b| a.channel.cmp(&b.channel));
+        report
+    }
+
+    /// Every home's incident store, for backups
+    pub fn incident_snapshots(&self) -> Vec<(String, IncidentStoreSnapshot)> {
+        let mut snapshots: Vec<(String, IncidentStoreSnapshot)> = self.shards.iter()
+            .map(|shard| (shard.key().clone(), shard.store.snapshot()))
+            .collect();
+        snapshots.sort_by(|a, b| a.0.cmp(&b.0));
+        snapshots
+    }
+
+    /// Replace a home's incident store, e.g. from a backup
+    pub fn restore_incident_store(&self, home: &str, snapshot: IncidentStoreSnapshot) {
+        self.shards.insert(home.to_string(), HomeShard {
+            store: IncidentStore::from_snapshot(snapshot),
+            pending_questions: std::collections::HashMap::new(),
+        });
+    }
+
+    /// Homes with an incident store, sorted
+    pub fn homes(&self) -> Vec<String> {
+        let mut homes: Vec<String> = self.shards.iter().map(|shard| shard.key().clone()).collect();
+        homes.sort();
+        homes
+    }
+
+    /// Stored incidents for a home, in the serializable snapshot form
+    pub fn home_incidents(&self, home: &str) -> Vec<IncidentSnapshotEntry> {
+        self.shards.get(home)
+            .map(|shard| shard.store.snapshot().incidents.into_iter().filter(|e| e.home == home).collect())
+            .unwrap_or_default()
+    }
+
+    /// Latest probability of the most likely active incident seen on a camera
+    pub fn live_probability(&self, home: &str, camera: &str) -> Option<f64> {
+        self.shards.get(home)?.store.incidents.values()
+            .filter(|i| i.status.is_active() && i.cameras.contains(camera))
+            .filter_map(|i| i.probability_trace.last().map(|p| p.calibrated_probability))
+            .reduce(f64::max)
+    }
+
+    /// Look up an incident by id within a home; a copy, so the shard is not held
+    pub fn find_incident(&self, home: &str, incident_id: u64) -> Option<Incident> {
+        self.shards.get(home)?.store.incidents.values().find(|i| i.id == incident_id).cloned()
+    }
+
+    /// Replace an incident's narrative, e.g. with an LLM one generated after
+    /// the fact; false when the incident is unknown
+    pub fn set_narrative(&self, home: &str, incident_id: u64, narrative: String) -> bool {
+        let mut shard = self.shards.get_mut(home);
+        let incident = shard.as_mut()
+            .and_then(|shard| shard.store.incidents.values_mut().find(|i| i.id == incident_id));
+        match incident {
+            Some(incident) => {
+                incident.last_narrative = Some(narrative);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Open incident currently tracking a person session, if any
+    pub fn track_incident(&self, home: &str, person_track: &str) -> Option<Incident> {
+        let shard = self.shards.get(home)?;
+        let store = &shard.store;
+        let session = store.merged_tracks.get(&(home.to_string(), person_track.to_string())).map_or(person_track, |s| s.as_str());
+        store.get_incident(home, session).filter(|i| i.status.is_active()).cloned()
+    }
+
+    /// Attach adversarial findings to an incident; they apply from the next assessment
+    pub fn apply_adversarial_assessment(&self, home: &str, incident_id: u64, assessment: AdversarialAssessment) {
+        if let Some(incident) = self.shards.get_mut(home).as_mut()
+            .and_then(|s| s.store.incidents.values_mut().find(|i| i.id == incident_id))
+        {
+            incident.adversarial = Some(assessment);
+        }
+    }
+
+    /// Count a notification for an incident that was held back by cooldown
+    pub fn record_suppressed_notification(&self, home: &str, incident_id: u64) {
+        if let Some(incident) = self.shards.get_mut(home).as_mut()
+            .and_then(|s| s.store.incidents.values_mut().find(|i| i.id == incident_id))
+        {
+            incident.suppressed_count += 1;
+        }
+    }
+
+    /// Associate a snapshot URL with an incident so it can be exported later, with
+    /// the faces the detector found in it when one ran
+    pub fn attach_snapshot(&self, home: &str, incident_id: u64, url: String, faces: Option<Vec<FaceRegion>>) {
+        if let Some(incident) = self.shards.get_mut(home).as_mut()
+            .and_then(|s| s.store.incidents.values_mut().find(|i| i.id == incident_id))
+        {
+            match faces {
+                Some(faces) => incident.attach_snapshot_faces(url, faces),
+                None => incident.attach_snapshot(url),
+            }
         }
     }
 
@@ -200,11 +844,20 @@ impl ThinkingAIProcessor {
         output.push_str(&result.narrative_summary);
         output.push_str("\n\nDecision: ");
         output.push_str(&format!("{:?}", result.alert_decision));
+        output.push_str("\nWhy: ");
+        output.push_str(&result.explanation.render());
+
+        if let Some(w) = &result.channel_weights {
+            output.push_str(&format!(
+                "\nLearned channel weights: time×{:.2} entry×{:.2} behavior×{:.2} identity×{:.2} presence×{:.2} token×{:.2} (prior {:+.2})",
+                w.time, w.entry, w.behavior, w.identity, w.presence, w.token, w.bias
+            ));
+        }
         
         if !result.top_questions.is_empty() {
             output.push_str("\n\nSelf-Questions (Value of Information):\n");
             for (i, q) in result.top_questions.iter().enumerate() {
-                output.push_str(&format!("  {}. {:?} (ΔH≈{:.3})\n", i+1, q.q, q.expected_entropy_reduction));
+                output.push_str(&format!("  {}. {:?} (ΔH≈{:.3}, calibrated {:.3})\n", i+1, q.q, q.expected_entropy_reduction, q.calibrated_value));
             }
         }
 
@@ -219,3 +872,31 @@ impl ThinkingAIProcessor {
         output
     }
 }
+
+// Channels and prior as signed LLR factors; the key counterfactual is the
+// largest single downgrade, flagged if it alone brings the logit under threshold
+fn explain(
+    fused: &Evidence,
+    prior_logit: f64,
+    raw_logit: f64,
+    threshold_logit: f64,
+    counterfactuals: &[CounterfactualSuggestion],
+    config_hash: String,
+) -> Explanation {
+    let contributions: Vec<(String, f64)> = std::iter::once(("prior".to_string(), prior_logit))
+        .chain(fused.channels())
+        .collect();
+    let headline = format!("Threat logit {:+.2} against threshold {:+.2}", raw_logit, threshold_logit);
+    let key = counterfactuals.first().map(|cf| KeyCounterfactual {
+        description: cf.description.clone(),
+        delta: cf.delta_llr,
+        crosses_threshold: raw_logit + cf.delta_llr <= threshold_logit,
+    });
+    let saturated = fused.saturated.iter()
+        .map(|(name, saturation)| format!("{} at the {} cap", channel_label(name), match saturation {
+            Saturation::Positive => "positive",
+            Saturation::Negative => "negative",
+        }))
+        .collect();
+    Explanation::new(headline, contributions, config_hash).with_counterfactual(key).with_saturated(saturated)
+}
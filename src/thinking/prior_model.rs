@@ -0,0 +1,432 @@
+//! Editable base rates
+//!
+//! An incident's starting belief normally comes from the global prior plus the
+//! zone shift chosen at onboarding. Advanced users can override it with base
+//! rates for specific situations, keyed by who is seen (entity class), where
+//! (zone), when (time bucket) and whether anyone is home (dwelling state). A
+//! rule may leave any of those open; the most specific matching rule wins.
+//!
+//! Edits pass guardrails before they take effect: base rates stay within fixed
+//! bounds, one edit may only move a situation's prior so far, and a rate may
+//! not contradict the labeled outcomes already seen for the situations it
+//! covers. Every accepted edit is kept as a version so a bad one can be rolled
+//! back.
+
+use super::incident_engine::{Event, Incident};
+use super::probability::{logit, sigmoid};
+use super::what_if::IncidentLabel;
+use crate::clock::{system_clock, Clock};
+use crate::household::Occupancy;
+use chrono::{DateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum PriorEditError {
+    #[error("Edit rejected by guardrails: {}", .0.join("; "))]
+    Guardrail(Vec<String>),
+
+    #[error("Unknown prior version {0}")]
+    UnknownVersion(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityClass {
+    Unknown,
+    Known,   // Recognized resident or trusted face
+    Visitor, // Presented an access token
+}
+
+impl EntityClass {
+    pub fn of(event: &Event) -> Self {
+        if event.token.is_some() {
+            EntityClass::Visitor
+        } else if event.evidence.llr_identity <= -1.0 {
+            EntityClass::Known
+        } else {
+            EntityClass::Unknown
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeBucket {
+    Morning,   // 06:00–12:00 local
+    Afternoon, // 12:00–18:00
+    Evening,   // 18:00–22:00
+    Night,     // 22:00–06:00
+}
+
+impl TimeBucket {
+    pub fn of_hour(hour: u32) -> Self {
+        match hour {
+            6..=11 => TimeBucket::Morning,
+            12..=17 => TimeBucket::Afternoon,
+            18..=21 => TimeBucket::Evening,
+            _ => TimeBucket::Night,
+        }
+    }
+}
+
+/// Dwelling state from an event's away probability, on the household presence scale
+pub fn occupancy_of(away_prob: f64) -> Occupancy {
+    if away_prob >= 0.7 {
+        Occupancy::Empty
+    } else if away_prob <= 0.3 {
+        Occupancy::Occupied
+    } else {
+        Occupancy::Unknown
+    }
+}
+
+/// One fully specified situation an incident can be in
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PriorCell {
+    pub entity: EntityClass,
+    pub zone: String,
+    pub time: TimeBucket,
+    pub occupancy: Occupancy,
+}
+
+/// Base rate for every situation matching the fields that are set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriorRule {
+    #[serde(default)]
+    pub entity: Option<EntityClass>,
+    #[serde(default)]
+    pub zone: Option<String>,
+    #[serde(default)]
+    pub time: Option<TimeBucket>,
+    #[serde(default)]
+    pub occupancy: Option<Occupancy>,
+    pub base_rate: f64, // Probability an incident in this situation is a threat
+}
+
+impl PriorRule {
+    pub fn matches(&self, cell: &PriorCell) -> bool {
+        self.entity.map_or(true, |e| e == cell.entity)
+            && self.zone.as_ref().map_or(true, |z| *z == cell.zone)
+            && self.time.map_or(true, |t| t == cell.time)
+            && self.occupancy.map_or(true, |o| o == cell.occupancy)
+    }
+
+    fn specificity(&self) -> usize {
+        [self.entity.is_some(), self.zone.is_some(), self.time.is_some(), self.occupancy.is_some()]
+            .iter()
+            .filter(|set| **set)
+            .count()
+    }
+
+    fn same_key(&self, other: &PriorRule) -> bool {
+        self.entity == other.entity && self.zone == other.zone && self.time == other.time && self.occupancy == other.occupancy
+    }
+
+    // The situation the rule describes, with open fields filled from `fallback`
+    fn representative(&self, fallback: &PriorCell) -> PriorCell {
+        PriorCell {
+            entity: self.entity.unwrap_or(fallback.entity),
+            zone: self.zone.clone().unwrap_or_else(|| fallback.zone.clone()),
+            time: self.time.unwrap_or(fallback.time),
+            occupancy: self.occupancy.unwrap_or(fallback.occupancy),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorVersion {
+    pub version: u32,
+    pub rules: Vec<PriorRule>,
+    pub author: String,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+    pub rolled_back_from: Option<u32>, // Set when this version restores an earlier one
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeTally {
+    pub threats: u32,
+    pub benign: u32,
+}
+
+impl OutcomeTally {
+    pub fn total(&self) -> u32 {
+        self.threats + self.benign
+    }
+
+    /// Observed threat rate with one pseudo-count each way
+    pub fn smoothed_rate(&self) -> f64 {
+        (self.threats as f64 + 1.0) / (self.total() as f64 + 2.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellFeedback {
+    pub cell: PriorCell,
+    pub outcomes: OutcomeTally,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriorGuardrails {
+    pub min_base_rate: f64,
+    pub max_base_rate: f64,
+    pub max_logit_step: f64,           // Largest move of any situation's prior in one edit
+    pub min_feedback: u32,             // Labeled outcomes needed before feedback constrains a rule
+    pub max_feedback_divergence: f64,  // Logit distance allowed from the observed threat rate
+}
+
+impl Default for PriorGuardrails {
+    fn default() -> Self {
+        Self {
+            min_base_rate: 0.001,
+            max_base_rate: 0.5,
+            max_logit_step: 2.0,
+            min_feedback: 5,
+            max_feedback_divergence: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct HomePriors {
+    history: Vec<PriorVersion>,
+    camera_zones: HashMap<String, String>,
+    timezone: Option<Tz>,
+    feedback: HashMap<PriorCell, OutcomeTally>,
+}
+
+impl HomePriors {
+    fn rules(&self) -> &[PriorRule] {
+        self.history.last().map(|v| v.rules.as_slice()).unwrap_or(&[])
+    }
+
+    fn known_zones(&self) -> Vec<&String> {
+        let mut zones: Vec<&String> = self.camera_zones.values().collect();
+        zones.sort();
+        zones.dedup();
+        zones
+    }
+}
+
+/// Per-home prior rules with edit history and outcome feedback
+#[derive(Debug)]
+pub struct PriorModelRegistry {
+    homes: DashMap<String, HomePriors>,
+    guardrails: PriorGuardrails,
+    default_prior_logit: f64, // Engine prior for situations no rule covers
+    clock: Arc<dyn Clock>, // Stamps each edit and rollback
+}
+
+impl Default for PriorModelRegistry {
+    fn default() -> Self {
+        Self::new(PriorGuardrails::default(), 0.0)
+    }
+}
+
+impl PriorModelRegistry {
+    pub fn new(guardrails: PriorGuardrails, default_prior_logit: f64) -> Self {
+        Self { homes: DashMap::new(), guardrails, default_prior_logit, clock: system_clock() }
+    }
+
+    /// Date prior versions and rollbacks by `clock` rather than the wall clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn guardrails(&self) -> &PriorGuardrails {
+        &self.guardrails
+    }
+
+    /// Which zone each camera watches and the home's timezone for time buckets
+    pub fn set_layout(&self, home: &str, camera_zones: HashMap<String, String>, timezone: &str) {
+        let mut priors = self.homes.entry(home.to_string()).or_default();
+        priors.camera_zones = camera_zones;
+        priors.timezone = timezone.parse().ok();
+    }
+
+    /// The situation of an incident's latest event
+    pub fn cell_for(&self, home: &str, incident: &Incident) -> Option<PriorCell> {
+        let event = incident.events.last()?;
+        let priors = self.homes.get(home);
+        let zone = priors.as_ref()
+            .and_then(|p| p.camera_zones.get(&event.cam).cloned())
+            .unwrap_or_else(|| event.cam.clone());
+        let tz = priors.as_ref().and_then(|p| p.timezone).unwrap_or(Tz::UTC);
+        let hour = tz.timestamp_opt(event.ts as i64, 0).single().map_or(0, |t| t.hour());
+        Some(PriorCell {
+            entity: EntityClass::of(event),
+            zone,
+            time: TimeBucket::of_hour(hour),
+            occupancy: occupancy_of(event.away_prob),
+        })
+    }
+
+    /// Base prior logit from the most specific matching rule; None when no rule matches
+    pub fn base_logit(&self, home: &str, incident: &Incident) -> Option<f64> {
+        let cell = self.cell_for(home, incident)?;
+        let priors = self.homes.get(home)?;
+        best_match(priors.rules(), &cell).map(|rule| logit(rule.base_rate))
+    }
+
+    /// Count a labeled outcome against the situation the incident was in
+    pub fn record_feedback(&self, home: &str, incident: &Incident, label: IncidentLabel) {
+        let Some(cell) = self.cell_for(home, incident) else { return };
+        let mut priors = self.homes.entry(home.to_string()).or_default();
+        let tally = priors.feedback.entry(cell).or_default();
+        match label {
+            IncidentLabel::Threat => tally.threats += 1,
+            IncidentLabel::Benign => tally.benign += 1,
+        }
+    }
+
+    pub fn feedback(&self, home: &str) -> Vec<CellFeedback> {
+        self.homes.get(home)
+            .map(|p| p.feedback.iter().map(|(cell, outcomes)| CellFeedback { cell: cell.clone(), outcomes: *outcomes }).collect())
+            .unwrap_or_default()
+    }
+
+    /// Active rules; version 0 means nothing has been edited yet
+    pub fn current(&self, home: &str) -> PriorVersion {
+        self.homes.get(home)
+            .and_then(|p| p.history.last().cloned())
+            .unwrap_or_else(|| PriorVersion {
+                version: 0,
+                rules: Vec::new(),
+                author: String::new(),
+                note: String::new(),
+                created_at: Utc.timestamp_opt(0, 0).unwrap(),
+                rolled_back_from: None,
+            })
+    }
+
+    pub fn history(&self, home: &str) -> Vec<PriorVersion> {
+        self.homes.get(home).map(|p| p.history.clone()).unwrap_or_default()
+    }
+
+    /// Guardrail violations a rule set would hit; empty when it would be accepted
+    pub fn check(&self, home: &str, rules: &[PriorRule]) -> Vec<String> {
+        let empty = HomePriors::default();
+        let priors = self.homes.get(home);
+        let priors = priors.as_deref().unwrap_or(&empty);
+        let g = &self.guardrails;
+        let mut violations = Vec::new();
+        let known_zones = priors.known_zones();
+
+        for (i, rule) in rules.iter().enumerate() {
+            let label = describe(rule);
+            if !rule.base_rate.is_finite() || rule.base_rate < g.min_base_rate || rule.base_rate > g.max_base_rate {
+                violations.push(format!("{}: base rate {} outside [{}, {}]", label, rule.base_rate, g.min_base_rate, g.max_base_rate));
+                continue;
+            }
+            if let Some(zone) = &rule.zone {
+                if !known_zones.is_empty() && !known_zones.contains(&zone) {
+                    violations.push(format!("{}: unknown zone '{}'", label, zone));
+                }
+            }
+            if rules[..i].iter().any(|r| r.same_key(rule)) {
+                violations.push(format!("{}: duplicate rule", label));
+            }
+        }
+        if !violations.is_empty() {
+            return violations;
+        }
+
+        // Compare old and new priors over every situation either rule set or the feedback touches
+        let fallback = PriorCell { entity: EntityClass::Unknown, zone: String::new(), time: TimeBucket::Night, occupancy: Occupancy::Unknown };
+        let mut cells: HashSet<PriorCell> = priors.feedback.keys().cloned().collect();
+        cells.extend(rules.iter().chain(priors.rules()).map(|r| r.representative(&fallback)));
+        for cell in &cells {
+            let before = best_match(priors.rules(), cell).map_or(self.default_prior_logit, |r| logit(r.base_rate));
+            let after = best_match(rules, cell).map_or(self.default_prior_logit, |r| logit(r.base_rate));
+            if (after - before).abs() > g.max_logit_step {
+                violations.push(format!(
+                    "{}: prior moves from {:.3} to {:.3}, more than one edit allows",
+                    describe_cell(cell), sigmoid(before), sigmoid(after)
+                ));
+            }
+        }
+
+        // A rule must stay near the threat rate actually observed in the situations it covers
+        for rule in rules {
+            let observed = priors.feedback.iter()
+                .filter(|(cell, _)| best_match(rules, cell).is_some_and(|r| r.same_key(rule)))
+                .fold(OutcomeTally::default(), |acc, (_, t)| OutcomeTally { threats: acc.threats + t.threats, benign: acc.benign + t.benign });
+            if observed.total() < g.min_feedback {
+                continue;
+            }
+            let rate = observed.smoothed_rate();
+            if (logit(rule.base_rate) - logit(rate)).abs() > g.max_feedback_divergence {
+                violations.push(format!(
+                    "{}: base rate {} contradicts {} labeled outcomes ({} threats, observed rate {:.3})",
+                    describe(rule), rule.base_rate, observed.total(), observed.threats, rate
+                ));
+            }
+        }
+        violations.sort();
+        violations
+    }
+
+    /// Replace a home's rules if they pass the guardrails, recording a new version
+    pub fn edit(&self, home: &str, rules: Vec<PriorRule>, author: &str, note: &str) -> Result<PriorVersion, PriorEditError> {
+        let violations = self.check(home, &rules);
+        if !violations.is_empty() {
+            return Err(PriorEditError::Guardrail(violations));
+        }
+        Ok(self.push_version(home, rules, author, note, None))
+    }
+
+    /// Restore an earlier version's rules as a new version. Guardrails are not
+    /// re-applied: the rules were accepted once and rollback must always work.
+    pub fn rollback(&self, home: &str, version: u32, author: &str) -> Result<PriorVersion, PriorEditError> {
+        let rules = if version == 0 {
+            Vec::new()
+        } else {
+            self.homes.get(home)
+                .and_then(|p| p.history.iter().find(|v| v.version == version).map(|v| v.rules.clone()))
+                .ok_or(PriorEditError::UnknownVersion(version))?
+        };
+        Ok(self.push_version(home, rules, author, &format!("rollback to version {}", version), Some(version)))
+    }
+
+    fn push_version(&self, home: &str, rules: Vec<PriorRule>, author: &str, note: &str, rolled_back_from: Option<u32>) -> PriorVersion {
+        let mut priors = self.homes.entry(home.to_string()).or_default();
+        let version = PriorVersion {
+            version: priors.history.last().map_or(1, |v| v.version + 1),
+            rules,
+            author: author.to_string(),
+            note: note.to_string(),
+            created_at: self.clock.now(),
+            rolled_back_from,
+        };
+        priors.history.push(version.clone());
+        version
+    }
+}
+
+// Most specific rule matching the cell; among equals the later rule wins
+fn best_match<'a>(rules: &'a [PriorRule], cell: &PriorCell) -> Option<&'a PriorRule> {
+    rules.iter()
+        .filter(|r| r.matches(cell))
+        .max_by_key(|r| r.specificity())
+}
+
+fn describe(rule: &PriorRule) -> String {
+    let part = |v: Option<String>| v.unwrap_or_else(|| "*".to_string());
+    format!(
+        "rule [{} / {} / {} / {}]",
+        part(rule.entity.map(|e| format!("{:?}", e))),
+        part(rule.zone.clone()),
+        part(rule.time.map(|t| format!("{:?}", t))),
+        part(rule.occupancy.map(|o| format!("{:?}", o))),
+    )
+}
+
+fn describe_cell(cell: &PriorCell) -> String {
+    format!("situation [{:?} / {} / {:?} / {:?}]", cell.entity, cell.zone, cell.time, cell.occupancy)
+}
@@ -0,0 +1,75 @@
+//! Probability math shared by fusion, calibration and decisions
+//!
+//! Pure functions with fixed behavior at the edges, so one bad reading cannot
+//! turn a whole assessment into NaN. A NaN log-likelihood ratio carries no
+//! evidence and counts as 0; a NaN logit is maximally uncertain and maps to
+//! 0.5; infinities saturate. Calibration sits behind `ProbabilityCalibrator`
+//! so tests and experiments can swap it out without touching fusion.
+
+use std::fmt::Debug;
+
+/// Probabilities are kept this far from 0 and 1 before taking a logit
+pub const PROBABILITY_EPSILON: f64 = 1e-12;
+
+/// Logistic function; NaN maps to 0.5
+pub fn sigmoid(x: f64) -> f64 {
+    if x.is_nan() {
+        0.5
+    } else if x >= 0.0 {
+        1.0 / (1.0 + (-x).exp())
+    } else {
+        // Same value, without overflowing exp() for large negative x
+        let e = x.exp();
+        e / (1.0 + e)
+    }
+}
+
+/// Inverse of sigmoid; p is clamped away from 0 and 1 and NaN maps to 0
+pub fn logit(p: f64) -> f64 {
+    if p.is_nan() {
+        return 0.0;
+    }
+    let p = p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+    (p / (1.0 - p)).ln()
+}
+
+/// Bound a log-likelihood ratio to [-neg_cap, pos_cap]; NaN is no evidence
+pub fn clamp_llr(llr: f64, neg_cap: f64, pos_cap: f64) -> f64 {
+    if llr.is_nan() {
+        return 0.0;
+    }
+    let (neg_cap, pos_cap) = (sanitize_cap(neg_cap), sanitize_cap(pos_cap));
+    llr.clamp(-neg_cap, pos_cap)
+}
+
+// Caps must be non-negative for clamp() to be well-formed; NaN means uncapped
+fn sanitize_cap(cap: f64) -> f64 {
+    if cap.is_nan() { f64::INFINITY } else { cap.max(0.0) }
+}
+
+/// Temperature-scaled, odds-capped probability of a raw logit. Temperatures
+/// below 1 (and NaN) are treated as 1, so calibration never sharpens.
+pub fn calibrate_logit(raw_logit: f64, mean: f64, temperature: f64, odds_cap: f64) -> f64 {
+    let temperature = if temperature.is_nan() { 1.0 } else { temperature.max(1.0) };
+    let z = (raw_logit - mean) / temperature;
+    sigmoid(clamp_llr(z, odds_cap, odds_cap))
+}
+
+/// Maps a fused logit to a reported probability
+pub trait ProbabilityCalibrator: Send + Sync + Debug {
+    fn calibrate(&self, raw_logit: f64) -> f64;
+}
+
+/// The default: calibrate_logit with the configured mean, temperature and cap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureCalibration {
+    pub mean_logit: f64,
+    pub temperature: f64,
+    pub odds_cap: f64,
+}
+
+impl ProbabilityCalibrator for TemperatureCalibration {
+    fn calibrate(&self, raw_logit: f64) -> f64 {
+        calibrate_logit(raw_logit, self.mean_logit, self.temperature, self.odds_cap)
+    }
+}
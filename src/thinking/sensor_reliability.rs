@@ -0,0 +1,186 @@
+//! Per-sensor reliability
+//!
+//! Each camera or sensor earns a reliability from how often the evidence it
+//! contributed pointed the same way as an incident's final outcome. The
+//! estimate is a Beta posterior, so a sensor with few labeled incidents stays
+//! close to the prior and is not applied to fusion until it has enough.
+
+use super::{Incident, IncidentLabel};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct SensorReliabilityConfig {
+    pub prior_reliability: f64, // Beta prior mean
+    pub prior_strength: f64,    // Pseudo-observations behind the prior
+    pub min_observations: f64,  // Labeled contributions before fusion uses the estimate
+    pub min_llr: f64,           // Events with weaker evidence say nothing about the sensor
+    pub floor: f64,             // Never discount a sensor below this
+}
+
+impl Default for SensorReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            prior_reliability: 0.85,
+            prior_strength: 10.0,
+            min_observations: 5.0,
+            min_llr: 0.1,
+            floor: 0.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SensorStats {
+    pub agreements: f64,
+    pub disagreements: f64,
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
+impl SensorStats {
+    pub fn observations(&self) -> f64 {
+        self.agreements + self.disagreements
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorReliability {
+    pub sensor_id: String,
+    pub reliability: f64,
+    pub applied: bool, // False until min_observations is reached
+    pub agreements: f64,
+    pub disagreements: f64,
+}
+
+// On-disk form (tuple map keys do not survive JSON)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ReliabilityFile {
+    sensors: Vec<ReliabilityRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ReliabilityRecord {
+    home_id: String,
+    sensor_id: String,
+    stats: SensorStats,
+}
+
+#[derive(Debug, Default)]
+pub struct SensorReliabilityModel {
+    config: SensorReliabilityConfig,
+    sensors: DashMap<(String, String), SensorStats>,
+}
+
+impl SensorReliabilityModel {
+    pub fn new(config: SensorReliabilityConfig) -> Self {
+        Self { config, sensors: DashMap::new() }
+    }
+
+    fn posterior(&self, stats: &SensorStats) -> f64 {
+        let alpha = self.config.prior_reliability * self.config.prior_strength + stats.agreements;
+        let beta = (1.0 - self.config.prior_reliability) * self.config.prior_strength + stats.disagreements;
+        (alpha / (alpha + beta)).clamp(self.config.floor, 1.0)
+    }
+
+    /// Credit or debit every sensor that contributed to a labeled incident, as of `now`
+    pub fn record_outcome(&self, home_id: &str, incident: &Incident, label: IncidentLabel, now: DateTime<Utc>) {
+        let threat = label == IncidentLabel::Threat;
+        for event in &incident.events {
+            let llr = event.evidence.sum();
+            if !llr.is_finite() || llr.abs() < self.config.min_llr {
+                continue;
+            }
+            let mut stats = self.sensors.entry((home_id.to_string(), event.cam.clone())).or_default();
+            if (llr > 0.0) == threat {
+                stats.agreements += 1.0;
+            } else {
+                stats.disagreements += 1.0;
+            }
+            stats.last_updated = Some(now);
+        }
+    }
+
+    /// Reliability of one sensor, if it has enough labeled history to be applied
+    pub fn reliability(&self, home_id: &str, sensor_id: &str) -> Option<f64> {
+        let stats = self.sensors.get(&(home_id.to_string(), sensor_id.to_string()))?;
+        (stats.observations() >= self.config.min_observations).then(|| self.posterior(&stats))
+    }
+
+    /// Applied reliabilities for a home's sensors, keyed by sensor id
+    pub fn home_reliabilities(&self, home_id: &str) -> HashMap<String, f64> {
+        self.sensors.iter()
+            .filter(|e| e.key().0 == home_id && e.value().observations() >= self.config.min_observations)
+            .map(|e| (e.key().1.clone(), self.posterior(e.value())))
+            .collect()
+    }
+
+    /// Every sensor of a home with its estimate, including ones not applied yet
+    pub fn report(&self, home_id: &str) -> Vec<SensorReliability> {
+        let mut report: Vec<SensorReliability> = self.sensors.iter()
+            .filter(|e| e.key().0 == home_id)
+            .map(|e| SensorReliability {
+                sensor_id: e.key().1.clone(),
+                reliability: self.posterior(e.value()),
+                applied: e.value().observations() >= self.config.min_observations,
+                agreements: e.value().agreements,
+                disagreements: e.value().disagreements,
+            })
+            .collect();
+        report.sort_by(|a, b| a.sensor_id.cmp(&b.sensor_id));
+        report
+    }
+
+    /// Estimates pooled across homes by sensor id, for processes without a home context
+    pub fn pooled(&self) -> HashMap<String, f64> {
+        let mut pooled: HashMap<String, SensorStats> = HashMap::new();
+        for entry in self.sensors.iter() {
+            let stats = pooled.entry(entry.key().1.clone()).or_default();
+            stats.agreements += entry.value().agreements;
+            stats.disagreements += entry.value().disagreements;
+        }
+        pooled.iter().map(|(sensor, stats)| (sensor.clone(), self.posterior(stats))).collect()
+    }
+
+    /// All sensor statistics in the on-disk JSON form
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        let file = ReliabilityFile {
+            sensors: self.sensors.iter()
+                .map(|e| ReliabilityRecord { home_id: e.key().0.clone(), sensor_id: e.key().1.clone(), stats: e.value().clone() })
+                .collect(),
+        };
+        serde_json::to_vec_pretty(&file)
+    }
+
+    /// Replace all sensor statistics with ones written by `to_json`
+    pub fn restore_json(&self, json: &str) -> serde_json::Result<()> {
+        let file: ReliabilityFile = serde_json::from_str(json)?;
+        self.sensors.clear();
+        for record in file.sensors {
+            self.sensors.insert((record.home_id, record.sensor_id), record.stats);
+        }
+        Ok(())
+    }
+
+    /// Write all sensor statistics as JSON (via a temp file so a crash never leaves half a file)
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = self.to_json().map_err(std::io::Error::other)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// Restore statistics saved by `save`; a missing file starts from the prior
+    pub fn load(config: SensorReliabilityConfig, path: &Path) -> std::io::Result<Self> {
+        let model = Self::new(config);
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(model),
+            Err(e) => return Err(e),
+        };
+        model.restore_json(&text).map_err(std::io::Error::other)?;
+        Ok(model)
+    }
+}
@@ -1,21 +1,45 @@
 use super::incident_engine::{Incident, Evidence};
+use super::evidence_channels::channel_label;
+use super::llm_budget::{Admission, DeferredNarrative, LlmBudget, LlmPriority};
 use super::llm_client::{LLMClient, LLMSummaryRequest};
-use std::sync::OnceLock;
+use super::llm_guard::LlmGuard;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, OnceLock};
 
 // Global LLM client for reuse across calls
 static LLM_CLIENT: OnceLock<LLMClient> = OnceLock::new();
 
+// Budget every narrative is admitted against, once one is installed
+static LLM_BUDGET: OnceLock<Arc<LlmBudget>> = OnceLock::new();
+
+// Guardrails on everything sent to and received from the LLM; from the environment unless one is installed first
+static LLM_GUARD: OnceLock<Arc<LlmGuard>> = OnceLock::new();
+
 fn get_llm_client() -> &'static LLMClient {
-    LLM_CLIENT.get_or_init(|| LLMClient::new(None))
+    LLM_CLIENT.get_or_init(|| LLMClient::new(None).with_guard(llm_guard().clone()))
+}
+
+fn llm_guard() -> &'static Arc<LlmGuard> {
+    LLM_GUARD.get_or_init(|| Arc::new(LlmGuard::from_env()))
+}
+
+/// Install the LLM guard; only takes effect before the first narrative is generated
+pub fn set_llm_guard(guard: Arc<LlmGuard>) -> bool {
+    LLM_GUARD.set(guard).is_ok()
+}
+
+/// Put LLM narratives under a usage budget; only the first budget installed takes effect
+pub fn set_llm_budget(budget: Arc<LlmBudget>) -> bool {
+    LLM_BUDGET.set(budget).is_ok()
 }
 
 /// Generate incident summary, trying LLM first with rule-based fallback
-pub fn summarize_incident(inc: &Incident, fused: &Evidence, calibrated_p: f64, suppressed: u32) -> String {
+pub fn summarize_incident(home: &str, inc: &Incident, fused: &Evidence, calibrated_p: f64, suppressed: u32, now: DateTime<Utc>) -> String {
+    let details = technical_details(fused, calibrated_p, suppressed);
     // Try LLM summary first (async in sync context)
     if let Ok(runtime) = tokio::runtime::Runtime::new() {
-        if let Some(llm_summary) = runtime.block_on(try_llm_summary(inc, fused, calibrated_p)) {
-            return format!("{}\n\n📊 Technical Details: threat={:.1}%, LLR={:+.2}, suppressed={}", 
-                llm_summary, calibrated_p * 100.0, fused.sum(), suppressed);
+        if let Some(llm_summary) = runtime.block_on(try_llm_summary(home, inc, calibrated_p, &details, now)) {
+            return format!("{}\n\n{}", llm_summary, details);
         }
     }
     
@@ -23,7 +47,36 @@ pub fn summarize_incident(inc: &Incident, fused: &Evidence, calibrated_p: f64, s
     rule_based_summary(inc, fused, calibrated_p, suppressed)
 }
 
-async fn try_llm_summary(inc: &Incident, fused: &Evidence, calibrated_p: f64) -> Option<String> {
+fn technical_details(fused: &Evidence, calibrated_p: f64, suppressed: u32) -> String {
+    format!("📊 Technical Details: threat={:.1}%, LLR={:+.2}, suppressed={}", calibrated_p * 100.0, fused.sum(), suppressed)
+}
+
+/// Generate narratives that were deferred for budget and can now be afforded;
+/// as of `now`; returns (home, incident id, narrative) for each one that succeeded
+pub async fn run_deferred_narratives(max: usize, now: DateTime<Utc>) -> Vec<(String, u64, String)> {
+    let Some(budget) = LLM_BUDGET.get() else {
+        return Vec::new();
+    };
+    let client = get_llm_client();
+    let mut narratives = Vec::new();
+    for deferred in budget.take_deferred(now, max) {
+        // Switched off since it was queued; the rule-based summary already stands
+        if llm_guard().allows(&deferred.home_id).is_err() {
+            continue;
+        }
+        match client.try_summary_for_budget(budget, &deferred.home_id, deferred.request).await {
+            Some(summary) => narratives.push((deferred.home_id, deferred.incident_id, format!("{}\n\n{}", summary, deferred.details))),
+            None => continue,
+        }
+    }
+    narratives
+}
+
+async fn try_llm_summary(home: &str, inc: &Incident, calibrated_p: f64, details: &str, now: DateTime<Utc>) -> Option<String> {
+    let guard = llm_guard();
+    if guard.allows(home).is_err() {
+        return None;
+    }
     let client = get_llm_client();
     
     // Extract key information from incident
@@ -47,16 +100,33 @@ async fn try_llm_summary(inc: &Incident, fused: &Evidence, calibrated_p: f64) ->
         .map(|e| e.cam.clone())
         .unwrap_or_else(|| "front_door".to_string());
     
-    let request = LLMSummaryRequest {
+    let request = guard.sanitize_request(home, LLMSummaryRequest {
         decision: decision.to_string(),
         location,
         dwell_time: total_dwell,
         rang_doorbell,
         knocked,
         threat_probability: calibrated_p,
-    };
+    });
     
-    client.get_summary(request).await
+    let Some(budget) = LLM_BUDGET.get() else {
+        return client.get_summary(request).await;
+    };
+    let priority = LlmPriority::from_probability(calibrated_p);
+    match client.get_budgeted_summary(budget, home, priority, request.clone(), now).await {
+        (Admission::Deferred, _) => {
+            budget.defer(DeferredNarrative {
+                home_id: home.to_string(),
+                incident_id: inc.id,
+                priority,
+                request,
+                details: details.to_string(),
+                queued_at: now,
+            });
+            None
+        }
+        (_, summary) => summary,
+    }
 }
 
 /// Rule-based fallback summary (original implementation)
@@ -67,10 +137,13 @@ fn rule_based_summary(inc: &Incident, fused: &Evidence, calibrated_p: f64, suppr
     let doors = if inc.events.iter().any(|e| e.rang_doorbell) { "rang doorbell".to_string() }
         else if inc.events.iter().any(|e| e.knocked) { "knocked".to_string() }
         else { "no doorbell/knock".to_string() };
+    let custom: String = fused.custom.iter()
+        .map(|(name, llr)| format!(", {}={:+.2}", channel_label(name), llr))
+        .collect();
     format!(
-        "🔔 Front Door Activity\nTotal dwell {:.0}s over {:.0}s window, {}.\nFused LLR: time={:+.2}, entry={:+.2}, behavior={:+.2}, identity={:+.2}, presence={:+.2}, token={:+.2}.\nCalibrated threat: {:.1}%\nSuppressed duplicates: {}",
+        "🔔 Front Door Activity\nTotal dwell {:.0}s over {:.0}s window, {}.\nFused LLR: time={:+.2}, entry={:+.2}, behavior={:+.2}, identity={:+.2}, presence={:+.2}, token={:+.2}{}.\nCalibrated threat: {:.1}%\nSuppressed duplicates: {}",
         inc.total_dwell(), duration, doors,
-        fused.llr_time, fused.llr_entry, fused.llr_behavior, fused.llr_identity, fused.llr_presence, fused.llr_token,
+        fused.llr_time, fused.llr_entry, fused.llr_behavior, fused.llr_identity, fused.llr_presence, fused.llr_token, custom,
         calibrated_p*100.0, suppressed
     )
 }
@@ -0,0 +1,147 @@
+//! Confidence-interval aware alerting
+//!
+//! A calibrated probability just over the alert threshold can come from one
+//! ambiguous detection or from a dozen that agree. Each assessment therefore
+//! carries an interval around its probability: a leave-one-out jackknife over
+//! the incident's events measures how much the fused score depends on any one
+//! of them, on top of a base model uncertainty that shrinks as events
+//! accumulate. When too much of that interval lies below the alert threshold,
+//! the alert is held as Wait so the follow-up scheduler re-checks it once more
+//! evidence has had a chance to arrive. How much doubt a home tolerates comes
+//! from its sensitivity profile.
+
+use super::incident_engine::Incident;
+use super::probability::{logit, ProbabilityCalibrator};
+use super::AlertDecision;
+use crate::onboarding::SensitivityProfile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UncertaintyPolicy {
+    pub enabled: bool,
+    pub confidence_level: f64,     // Two-sided coverage of the reported interval
+    pub max_below_threshold: f64,  // Largest share of the interval below the threshold that still alerts
+    pub base_logit_sd: f64,        // Model uncertainty of a single-event incident, in logits
+    pub hold_critical: bool,       // Whether Critical alerts can be held too
+}
+
+impl Default for UncertaintyPolicy {
+    fn default() -> Self {
+        Self::for_profile(SensitivityProfile::default())
+    }
+}
+
+impl UncertaintyPolicy {
+    /// Conservative homes want surer alerts; vigilant ones would rather hear early
+    pub fn for_profile(profile: SensitivityProfile) -> Self {
+        match profile {
+            SensitivityProfile::Conservative => Self {
+                enabled: true,
+                confidence_level: 0.95,
+                max_below_threshold: 0.2,
+                base_logit_sd: 0.5,
+                hold_critical: false,
+            },
+            SensitivityProfile::Balanced => Self {
+                enabled: true,
+                confidence_level: 0.95,
+                max_below_threshold: 0.35,
+                base_logit_sd: 0.5,
+                hold_critical: false,
+            },
+            SensitivityProfile::Vigilant => Self {
+                enabled: false,
+                confidence_level: 0.95,
+                max_below_threshold: 0.5,
+                base_logit_sd: 0.5,
+                hold_critical: false,
+            },
+        }
+    }
+}
+
+/// Interval around an assessment's calibrated probability
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProbabilityInterval {
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence_level: f64,
+    pub below_threshold_share: f64,      // Estimated chance the true probability is under the alert threshold
+    pub held: Option<AlertDecision>,     // The alert held back as Wait, if any
+}
+
+/// Standard deviation of the fused logit. `fuse` scores a subset of the
+/// incident's events; the jackknife spread over leaving each one out is added
+/// to the base uncertainty, which shrinks with the square root of the count.
+pub fn logit_sd(incident: &Incident, base_logit_sd: f64, fuse: impl Fn(&Incident) -> f64) -> f64 {
+    let n = incident.events.len();
+    let base = base_logit_sd / (n.max(1) as f64).sqrt();
+    if n < 2 {
+        return base;
+    }
+    let scores: Vec<f64> = (0..n)
+        .map(|skip| {
+            let mut subset = incident.clone();
+            subset.events.remove(skip);
+            fuse(&subset)
+        })
+        .collect();
+    let mean = scores.iter().sum::<f64>() / n as f64;
+    let jackknife = (n - 1) as f64 / n as f64 * scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>();
+    (base.powi(2) + jackknife).sqrt()
+}
+
+/// Interval for a raw logit with the given spread, in calibrated probability
+pub fn interval(
+    calibrator: &dyn ProbabilityCalibrator,
+    raw_logit: f64,
+    sd: f64,
+    threshold_probability: f64,
+    confidence_level: f64,
+) -> ProbabilityInterval {
+    let z = normal_quantile(0.5 + confidence_level.clamp(0.5, 0.999) / 2.0);
+    let lower = calibrator.calibrate(raw_logit - z * sd);
+    let upper = calibrator.calibrate(raw_logit + z * sd);
+    // Calibration is monotone, so measure the threshold in calibrated logits
+    let centre = logit(calibrator.calibrate(raw_logit));
+    let spread = (logit(upper) - logit(lower)) / (2.0 * z);
+    let below_threshold_share = if spread > 0.0 {
+        normal_cdf((logit(threshold_probability) - centre) / spread)
+    } else if centre < logit(threshold_probability) {
+        1.0
+    } else {
+        0.0
+    };
+    ProbabilityInterval { lower, upper, confidence_level, below_threshold_share, held: None }
+}
+
+/// Wait instead of `decision` when the policy finds the interval too uncertain
+pub fn hold(policy: &UncertaintyPolicy, decision: &AlertDecision, interval: &ProbabilityInterval) -> bool {
+    let alerting = match decision {
+        AlertDecision::Standard | AlertDecision::Elevated => true,
+        AlertDecision::Critical => policy.hold_critical,
+        AlertDecision::Ignore | AlertDecision::Wait => false,
+    };
+    policy.enabled && alerting && interval.below_threshold_share > policy.max_below_threshold
+}
+
+/// Standard normal CDF (Abramowitz and Stegun 7.1.26, error below 1.5e-7)
+pub fn normal_cdf(x: f64) -> f64 {
+    if x.is_nan() {
+        return 0.5;
+    }
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs() / std::f64::consts::SQRT_2);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-(x * x) / 2.0).exp();
+    if x >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
+
+// Inverse of normal_cdf by bisection; only used for a handful of coverage levels
+fn normal_quantile(p: f64) -> f64 {
+    let (mut lo, mut hi) = (-8.0, 8.0);
+    for _ in 0..60 {
+        let mid = (lo + hi) / 2.0;
+        if normal_cdf(mid) < p { lo = mid } else { hi = mid }
+    }
+    (lo + hi) / 2.0
+}
@@ -0,0 +1,167 @@
+//! Value-of-information calibration
+//!
+//! The reasoner ranks questions by model-estimated entropy reduction. Whether
+//! answering a question actually changes anything is an empirical matter: a
+//! doorbell ring may move the probability a lot on paper yet rarely flip a
+//! decision in practice. Each time an incident's next event answers one of
+//! the questions its last assessment proposed, we record whether the decision
+//! changed. Per question type, the smoothed decision-change rate relative to
+//! the average over all types scales the model estimate, so types that
+//! historically change decisions rise to the top of the list.
+
+use super::active_reasoner::{Question, QuestionProposal};
+use super::{AlertDecision, Event, Incident};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    RequestSecondAngle,
+    AwaitDoorbell,
+    ImproveFaceCapture,
+    CheckDeliveryToken,
+}
+
+impl Question {
+    pub fn kind(&self) -> QuestionKind {
+        match self {
+            Question::RequestSecondAngle { .. } => QuestionKind::RequestSecondAngle,
+            Question::AwaitDoorbell => QuestionKind::AwaitDoorbell,
+            Question::ImproveFaceCapture => QuestionKind::ImproveFaceCapture,
+            Question::CheckDeliveryToken => QuestionKind::CheckDeliveryToken,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VoiCalibrationConfig {
+    pub prior_strength: f64, // Pseudo-answers at the average rate behind each type
+    pub min_factor: f64,
+    pub max_factor: f64,
+}
+
+impl Default for VoiCalibrationConfig {
+    fn default() -> Self {
+        Self { prior_strength: 10.0, min_factor: 0.25, max_factor: 4.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct QuestionStats {
+    pub proposed: u64,
+    pub answered: u64,
+    pub changed_decision: u64,
+    pub predicted_sum: f64, // Model entropy reduction summed over answered proposals
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestionValue {
+    pub kind: QuestionKind,
+    pub stats: QuestionStats,
+    pub change_rate: f64, // Smoothed share of answers that changed the decision
+    pub factor: f64,      // Multiplier applied to the model estimate
+}
+
+/// Questions proposed by an incident's latest assessment, with what is needed
+/// to tell whether the next event answers them
+#[derive(Debug, Clone)]
+pub struct PendingQuestions {
+    pub decision: AlertDecision,
+    pub questions: Vec<(QuestionKind, f64)>, // Kind and model estimate
+    cameras: HashSet<String>,
+    identity_llr: f64,
+}
+
+impl PendingQuestions {
+    pub fn new(incident: &Incident, decision: AlertDecision, proposals: &[QuestionProposal]) -> Self {
+        Self {
+            decision,
+            questions: proposals.iter().map(|p| (p.q.kind(), p.expected_entropy_reduction)).collect(),
+            cameras: incident.cameras.clone(),
+            identity_llr: incident.events.iter().map(|e| e.evidence.llr_identity.abs()).fold(0.0, f64::max),
+        }
+    }
+
+    /// Whether `event` provides the answer a question was waiting for
+    pub fn answers(&self, kind: QuestionKind, event: &Event) -> bool {
+        match kind {
+            QuestionKind::AwaitDoorbell => event.rang_doorbell || event.knocked,
+            QuestionKind::CheckDeliveryToken => event.token.is_some(),
+            QuestionKind::RequestSecondAngle => !self.cameras.contains(&event.cam),
+            QuestionKind::ImproveFaceCapture => event.evidence.llr_identity.abs() > self.identity_llr,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct VoiCalibrator {
+    config: VoiCalibrationConfig,
+    stats: DashMap<QuestionKind, QuestionStats>,
+}
+
+impl VoiCalibrator {
+    pub fn new(config: VoiCalibrationConfig) -> Self {
+        Self { config, stats: DashMap::new() }
+    }
+
+    pub fn record_proposed(&self, pending: &PendingQuestions) {
+        for (kind, _) in &pending.questions {
+            self.stats.entry(*kind).or_default().proposed += 1;
+        }
+    }
+
+    /// Record every pending question `event` answered, given the decision after it
+    pub fn record_answers(&self, pending: &PendingQuestions, event: &Event, decision: &AlertDecision) {
+        let changed = *decision != pending.decision;
+        for (kind, predicted) in pending.questions.iter().filter(|(kind, _)| pending.answers(*kind, event)) {
+            let mut stats = self.stats.entry(*kind).or_default();
+            stats.answered += 1;
+            stats.predicted_sum += predicted;
+            if changed {
+                stats.changed_decision += 1;
+            }
+        }
+    }
+
+    fn mean_rate(&self) -> Option<f64> {
+        let (answered, changed) = self.stats.iter().fold((0, 0), |(a, c), s| (a + s.answered, c + s.changed_decision));
+        (answered > 0 && changed > 0).then(|| changed as f64 / answered as f64)
+    }
+
+    fn value_of(&self, kind: QuestionKind, stats: QuestionStats, mean: Option<f64>) -> QuestionValue {
+        let Some(mean) = mean else {
+            return QuestionValue { kind, stats, change_rate: 0.0, factor: 1.0 };
+        };
+        let m = self.config.prior_strength;
+        let change_rate = (stats.changed_decision as f64 + m * mean) / (stats.answered as f64 + m);
+        let factor = (change_rate / mean).clamp(self.config.min_factor, self.config.max_factor);
+        QuestionValue { kind, stats, change_rate, factor }
+    }
+
+    /// Multiplier for a question type's model estimate; 1.0 without history
+    pub fn factor(&self, kind: QuestionKind) -> f64 {
+        let stats = self.stats.get(&kind).map(|s| *s).unwrap_or_default();
+        self.value_of(kind, stats, self.mean_rate()).factor
+    }
+
+    /// Rescale proposals by their type's factor and re-rank them
+    pub fn calibrate(&self, proposals: &mut [QuestionProposal]) {
+        let mean = self.mean_rate();
+        for proposal in proposals.iter_mut() {
+            let kind = proposal.q.kind();
+            let stats = self.stats.get(&kind).map(|s| *s).unwrap_or_default();
+            proposal.calibrated_value = proposal.expected_entropy_reduction * self.value_of(kind, stats, mean).factor;
+        }
+        proposals.sort_by(|a, b| b.calibrated_value.total_cmp(&a.calibrated_value));
+    }
+
+    /// Per-type history and current factors
+    pub fn report(&self) -> Vec<QuestionValue> {
+        let mean = self.mean_rate();
+        let mut values: Vec<QuestionValue> = self.stats.iter().map(|s| self.value_of(*s.key(), *s.value(), mean)).collect();
+        values.sort_by(|a, b| b.factor.total_cmp(&a.factor));
+        values
+    }
+}
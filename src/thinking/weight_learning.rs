@@ -0,0 +1,267 @@
+//! Outcome-based LLR weight learning
+//!
+//! Learns a scale per evidence channel from labeled outcomes (homeowner
+//! feedback, monitoring-center dispositions) with online logistic regression.
+//! A population model is learned from every home; each home's weights are
+//! regularized toward it, so a home with little feedback behaves like the
+//! population and drifts only as its own labels accumulate.
+
+use super::{sigmoid, Evidence, IncidentLabel};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelWeights {
+    pub time: f64,
+    pub entry: f64,
+    pub behavior: f64,
+    pub identity: f64,
+    pub presence: f64,
+    pub token: f64,
+    pub bias: f64, // Added to the prior logit
+}
+
+impl Default for ChannelWeights {
+    fn default() -> Self {
+        Self { time: 1.0, entry: 1.0, behavior: 1.0, identity: 1.0, presence: 1.0, token: 1.0, bias: 0.0 }
+    }
+}
+
+impl ChannelWeights {
+    fn as_array(&self) -> [f64; 6] {
+        [self.time, self.entry, self.behavior, self.identity, self.presence, self.token]
+    }
+
+    fn from_array(w: [f64; 6], bias: f64) -> Self {
+        Self { time: w[0], entry: w[1], behavior: w[2], identity: w[3], presence: w[4], token: w[5], bias }
+    }
+
+    /// Channels then bias, the layout of federated weight updates
+    pub fn to_vector(&self) -> [f64; 7] {
+        let w = self.as_array();
+        [w[0], w[1], w[2], w[3], w[4], w[5], self.bias]
+    }
+
+    pub fn from_vector(v: [f64; 7]) -> Self {
+        Self::from_array([v[0], v[1], v[2], v[3], v[4], v[5]], v[6])
+    }
+}
+
+fn channels(e: &Evidence) -> [f64; 6] {
+    [e.llr_time, e.llr_entry, e.llr_behavior, e.llr_identity, e.llr_presence, e.llr_token]
+}
+
+impl Evidence {
+    /// Scale each built-in channel by its learned weight; custom channels pass through
+    pub fn scaled(&self, w: &ChannelWeights) -> Evidence {
+        Evidence {
+            llr_time: self.llr_time * w.time,
+            llr_entry: self.llr_entry * w.entry,
+            llr_behavior: self.llr_behavior * w.behavior,
+            llr_identity: self.llr_identity * w.identity,
+            llr_presence: self.llr_presence * w.presence,
+            llr_token: self.llr_token * w.token,
+            custom: self.custom.clone(),
+            saturated: self.saturated.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeSource {
+    UserFeedback,
+    OperatorDisposition,
+}
+
+#[derive(Debug, Clone)]
+pub struct WeightLearnerConfig {
+    pub learning_rate: f64,
+    pub population_l2: f64, // Pulls population weights toward 1.0
+    pub home_l2: f64,       // Pulls each home toward the population
+    pub operator_weight: f64, // Operator dispositions count more than app feedback
+    pub min_weight: f64,
+    pub max_weight: f64,
+    pub max_bias: f64,
+}
+
+impl Default for WeightLearnerConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.05,
+            population_l2: 0.01,
+            home_l2: 0.05,
+            operator_weight: 2.0,
+            min_weight: 0.0,
+            max_weight: 3.0,
+            max_bias: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearnedWeights {
+    pub weights: ChannelWeights,
+    pub updates: u64,
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
+impl Default for LearnedWeights {
+    fn default() -> Self {
+        Self { weights: ChannelWeights::default(), updates: 0, last_updated: None }
+    }
+}
+
+// On-disk form
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WeightFile {
+    population: LearnedWeights,
+    homes: HashMap<String, LearnedWeights>,
+}
+
+#[derive(Debug, Default)]
+pub struct OnlineWeightLearner {
+    config: WeightLearnerConfig,
+    population: RwLock<LearnedWeights>,
+    homes: DashMap<String, LearnedWeights>,
+}
+
+impl OnlineWeightLearner {
+    pub fn new(config: WeightLearnerConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Weights in effect for a home: its own once it has feedback, else the population's
+    pub fn weights(&self, home_id: &str) -> ChannelWeights {
+        match self.homes.get(home_id) {
+            Some(learned) => learned.weights,
+            None => self.population.read().map(|p| p.weights).unwrap_or_default(),
+        }
+    }
+
+    pub fn learned(&self, home_id: &str) -> Option<LearnedWeights> {
+        self.homes.get(home_id).map(|l| l.clone())
+    }
+
+    pub fn population(&self) -> LearnedWeights {
+        self.population.read().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    /// Every home that has its own weights
+    pub fn home_weights(&self) -> Vec<(String, LearnedWeights)> {
+        self.homes.iter().map(|e| (e.key().clone(), e.value().clone())).collect()
+    }
+
+    /// Replace the population weights, e.g. with a merged global model; homes
+    /// keep their own weights and are pulled toward the new population as they learn
+    pub fn set_population(&self, weights: ChannelWeights, at: DateTime<Utc>) {
+        let mut population = match self.population.write() {
+            Ok(p) => p,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        population.weights = weights;
+        population.last_updated = Some(at);
+    }
+
+    /// One SGD step on the log loss of an incident's outcome
+    ///
+    /// `evidence` is the unweighted fused evidence and `prior_logit` the prior the
+    /// incident was scored with, so the model learns from what the weights saw.
+    /// `now` is recorded as the time the weights were last updated.
+    pub fn record_outcome(&self, home_id: &str, evidence: &Evidence, prior_logit: f64, label: IncidentLabel, source: OutcomeSource, now: DateTime<Utc>) {
+        let y = match label {
+            IncidentLabel::Threat => 1.0,
+            IncidentLabel::Benign => 0.0,
+        };
+        let importance = match source {
+            OutcomeSource::UserFeedback => 1.0,
+            OutcomeSource::OperatorDisposition => self.config.operator_weight,
+        };
+        let x = channels(evidence);
+        if !x.iter().all(|v| v.is_finite()) || !prior_logit.is_finite() {
+            return;
+        }
+
+        let population = {
+            let mut population = match self.population.write() {
+                Ok(p) => p,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            population.weights = self.step(&population.weights, &x, prior_logit, y, importance, &ChannelWeights::default(), self.config.population_l2);
+            population.updates += 1;
+            population.last_updated = Some(now);
+            population.weights
+        };
+
+        let mut home = self.homes.entry(home_id.to_string()).or_insert_with(|| LearnedWeights {
+            weights: population,
+            ..Default::default()
+        });
+        home.weights = self.step(&home.weights, &x, prior_logit, y, importance, &population, self.config.home_l2);
+        home.updates += 1;
+        home.last_updated = Some(now);
+    }
+
+    fn step(&self, current: &ChannelWeights, x: &[f64; 6], prior_logit: f64, y: f64, importance: f64, anchor: &ChannelWeights, l2: f64) -> ChannelWeights {
+        let w = current.as_array();
+        let a = anchor.as_array();
+        let logit = prior_logit + current.bias + w.iter().zip(x).map(|(w, x)| w * x).sum::<f64>();
+        let error = (sigmoid(logit) - y) * importance;
+        let lr = self.config.learning_rate;
+
+        let mut next = [0.0; 6];
+        for i in 0..6 {
+            let grad = error * x[i] + l2 * (w[i] - a[i]);
+            next[i] = (w[i] - lr * grad).clamp(self.config.min_weight, self.config.max_weight);
+        }
+        let bias_grad = error + l2 * (current.bias - anchor.bias);
+        let bias = (current.bias - lr * bias_grad).clamp(-self.config.max_bias, self.config.max_bias);
+        ChannelWeights::from_array(next, bias)
+    }
+
+    /// All learned weights in the on-disk JSON form
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        let file = WeightFile {
+            population: self.population(),
+            homes: self.homes.iter().map(|e| (e.key().clone(), e.value().clone())).collect(),
+        };
+        serde_json::to_vec_pretty(&file)
+    }
+
+    /// Replace all learned weights with ones written by `to_json`
+    pub fn restore_json(&self, json: &str) -> serde_json::Result<()> {
+        let file: WeightFile = serde_json::from_str(json)?;
+        if let Ok(mut population) = self.population.write() {
+            *population = file.population;
+        }
+        self.homes.clear();
+        for (home, weights) in file.homes {
+            self.homes.insert(home, weights);
+        }
+        Ok(())
+    }
+
+    /// Write all learned weights as JSON (via a temp file so a crash never leaves half a file)
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = self.to_json().map_err(std::io::Error::other)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// Restore weights saved by `save`; a missing file starts from defaults
+    pub fn load(config: WeightLearnerConfig, path: &Path) -> std::io::Result<Self> {
+        let learner = Self::new(config);
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(learner),
+            Err(e) => return Err(e),
+        };
+        learner.restore_json(&text).map_err(std::io::Error::other)?;
+        Ok(learner)
+    }
+}
@@ -0,0 +1,151 @@
+//! What-if threshold tuning
+//!
+//! Replays stored incidents event by event through a fresh processor built from
+//! a candidate ThinkingAIConfig and compares the alerts it would have raised
+//! with the current config. Feedback labels turn the difference into false
+//! positives avoided and threats missed.
+
+use super::{AlertDecision, IncidentSnapshotEntry, ThinkingAIConfig, ThinkingAIProcessor};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentLabel {
+    Threat, // Confirmed intrusion or attempt
+    Benign, // Delivery, neighbor, family
+}
+
+// Replay result for one incident under one config
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayedIncident {
+    pub incident_id: u64,
+    pub peak_probability: f64,
+    pub peak_decision: AlertDecision,
+    pub alerted: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigOutcome {
+    pub alerted_incidents: usize,
+    pub alerts_by_decision: BTreeMap<String, usize>,
+    pub true_positives: usize,
+    pub false_positives: usize, // Alerted on a Benign label
+    pub missed_alerts: usize,   // Stayed quiet on a Threat label
+    pub unlabeled_alerts: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatIfReport {
+    pub incidents_replayed: usize,
+    pub labeled_incidents: usize,
+    pub baseline: ConfigOutcome,
+    pub candidate: ConfigOutcome,
+    pub alert_count_delta: i64,
+    pub false_positive_delta: i64,
+    pub missed_alert_delta: i64,
+    pub newly_alerted: Vec<u64>,
+    pub no_longer_alerted: Vec<u64>,
+}
+
+fn is_alert(decision: &AlertDecision) -> bool {
+    matches!(decision, AlertDecision::Standard | AlertDecision::Elevated | AlertDecision::Critical)
+}
+
+fn severity_rank(decision: &AlertDecision) -> u8 {
+    match decision {
+        AlertDecision::Ignore => 0,
+        AlertDecision::Wait => 1,
+        AlertDecision::Standard => 2,
+        AlertDecision::Elevated => 3,
+        AlertDecision::Critical => 4,
+    }
+}
+
+/// Re-run every incident's events, in time order, under `config`
+///
+/// Each incident gets its own track so incidents never merge; a shorter TTL in
+/// the candidate can still split one incident, and the strongest split counts.
+pub fn replay(incidents: &[IncidentSnapshotEntry], config: &ThinkingAIConfig) -> Vec<ReplayedIncident> {
+    let processor = ThinkingAIProcessor::new(config.clone());
+    let mut ordered: Vec<&IncidentSnapshotEntry> = incidents.iter().collect();
+    ordered.sort_by(|a, b| a.incident.started_at.partial_cmp(&b.incident.started_at).unwrap_or(std::cmp::Ordering::Equal));
+
+    ordered.into_iter()
+        .map(|entry| {
+            let mut peak = ReplayedIncident {
+                incident_id: entry.incident.id,
+                peak_probability: 0.0,
+                peak_decision: AlertDecision::Ignore,
+                alerted: false,
+            };
+            for event in &entry.incident.events {
+                let mut event = event.clone();
+                event.person_track = format!("replay_{}", entry.incident.id);
+                if let Some(result) = processor.process_event(&entry.home, event) {
+                    peak.peak_probability = peak.peak_probability.max(result.calibrated_probability);
+                    if severity_rank(&result.alert_decision) > severity_rank(&peak.peak_decision) {
+                        peak.peak_decision = result.alert_decision;
+                    }
+                }
+            }
+            peak.alerted = is_alert(&peak.peak_decision);
+            peak
+        })
+        .collect()
+}
+
+fn outcome(replayed: &[ReplayedIncident], labels: &HashMap<u64, IncidentLabel>) -> ConfigOutcome {
+    let mut outcome = ConfigOutcome::default();
+    for r in replayed {
+        let label = labels.get(&r.incident_id);
+        if r.alerted {
+            outcome.alerted_incidents += 1;
+            *outcome.alerts_by_decision.entry(format!("{:?}", r.peak_decision)).or_default() += 1;
+            match label {
+                Some(IncidentLabel::Threat) => outcome.true_positives += 1,
+                Some(IncidentLabel::Benign) => outcome.false_positives += 1,
+                None => outcome.unlabeled_alerts += 1,
+            }
+        } else if label == Some(&IncidentLabel::Threat) {
+            outcome.missed_alerts += 1;
+        }
+    }
+    outcome
+}
+
+/// Compare the alerts `candidate` would have raised on stored incidents with `baseline`
+pub fn compare_configs(
+    incidents: &[IncidentSnapshotEntry],
+    labels: &HashMap<u64, IncidentLabel>,
+    baseline: &ThinkingAIConfig,
+    candidate: &ThinkingAIConfig,
+) -> WhatIfReport {
+    let before = replay(incidents, baseline);
+    let after = replay(incidents, candidate);
+    let was_alerted: HashMap<u64, bool> = before.iter().map(|r| (r.incident_id, r.alerted)).collect();
+
+    let mut newly_alerted = Vec::new();
+    let mut no_longer_alerted = Vec::new();
+    for r in &after {
+        match (was_alerted.get(&r.incident_id).copied().unwrap_or(false), r.alerted) {
+            (false, true) => newly_alerted.push(r.incident_id),
+            (true, false) => no_longer_alerted.push(r.incident_id),
+            _ => {}
+        }
+    }
+
+    let baseline = outcome(&before, labels);
+    let candidate = outcome(&after, labels);
+    WhatIfReport {
+        incidents_replayed: incidents.len(),
+        labeled_incidents: incidents.iter().filter(|e| labels.contains_key(&e.incident.id)).count(),
+        alert_count_delta: candidate.alerted_incidents as i64 - baseline.alerted_incidents as i64,
+        false_positive_delta: candidate.false_positives as i64 - baseline.false_positives as i64,
+        missed_alert_delta: candidate.missed_alerts as i64 - baseline.missed_alerts as i64,
+        baseline,
+        candidate,
+        newly_alerted,
+        no_longer_alerted,
+    }
+}
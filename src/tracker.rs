@@ -0,0 +1,289 @@
+// src/tracker.rs
+
+// Per-entity state machines fed by successive detections of the same track.
+// Each tracked person moves through Approaching, AtDoor, Loitering, Departing
+// and Lost; the tracker measures how long they have really been around (rather
+// than the per-event placeholder dwell), reports every state change, and gives
+// notifications a live line such as "Person has now been at the door for 4
+// minutes".
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackState {
+    Approaching, // Seen away from the door
+    AtDoor,      // On a door camera
+    Loitering,   // At the door, or hanging around, for longer than a visit takes
+    Departing,   // Back on a non-door camera after being at the door
+    Lost,        // Not seen for a while
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackerConfig {
+    pub loiter_after_s: f64,       // Time at the door (or around the property) before Loitering
+    pub lost_after_s: f64,         // Silence before a track is Lost
+    pub forget_after_s: f64,       // Lost tracks are dropped after this
+    pub max_gap_dwell_s: f64,      // Longest gap between sightings counted as dwell
+    pub loitering_behavior_llr: f64, // Added to behavior evidence while Loitering
+    pub transition_log: usize,     // Recent transitions kept per home
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            loiter_after_s: 120.0,
+            lost_after_s: 90.0,
+            forget_after_s: 3600.0,
+            max_gap_dwell_s: 60.0,
+            loitering_behavior_llr: 0.6,
+            transition_log: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedEntity {
+    pub home_id: String,
+    pub track_id: String,
+    pub state: TrackState,
+    pub first_seen: f64,
+    pub last_seen: f64,
+    pub state_since: f64,
+    pub door_since: Option<f64>, // Start of the current stay at the door
+    pub cameras: Vec<String>,
+    pub interacted: bool,        // Rang or knocked
+}
+
+impl TrackedEntity {
+    /// Seconds between first and latest sighting
+    pub fn dwell_s(&self) -> f64 {
+        self.last_seen - self.first_seen
+    }
+
+    /// Seconds at the door in the current stay; 0 when not there
+    pub fn door_dwell_s(&self) -> f64 {
+        self.door_since.map_or(0.0, |since| self.last_seen - since)
+    }
+
+    /// One line for notifications, e.g. "Person has now been at the door for 4 minutes"
+    pub fn status_line(&self) -> String {
+        match self.state {
+            TrackState::Approaching => format!("Person approaching, seen for {}", minutes(self.dwell_s())),
+            TrackState::AtDoor => format!("Person has now been at the door for {}", minutes(self.door_dwell_s())),
+            TrackState::Loitering if self.door_since.is_some() => {
+                format!("Person has now been at the door for {} without ringing or knocking", minutes(self.door_dwell_s()))
+            }
+            TrackState::Loitering => format!("Person has been around the property for {}", minutes(self.dwell_s())),
+            TrackState::Departing => format!("Person is leaving after {}", minutes(self.dwell_s())),
+            TrackState::Lost => format!("Person last seen {} after arriving", minutes(self.dwell_s())),
+        }
+    }
+}
+
+fn minutes(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as i64;
+    match minutes {
+        m if m < 1 => "less than a minute".to_string(),
+        1 => "1 minute".to_string(),
+        m => format!("{} minutes", m),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackTransition {
+    pub home_id: String,
+    pub track_id: String,
+    pub from: Option<TrackState>, // None for a new track
+    pub to: TrackState,
+    pub ts: f64,
+    pub dwell_s: f64,
+    pub message: String,
+}
+
+/// Result of feeding one detection to the tracker
+#[derive(Debug, Clone)]
+pub struct TrackUpdate {
+    pub entity: TrackedEntity,
+    pub dwell_increment_s: Option<f64>, // Time since the previous sighting; None for a new track
+    pub transitions: Vec<TrackTransition>,
+}
+
+#[derive(Debug, Default)]
+pub struct EntityTracker {
+    config: TrackerConfig,
+    tracks: DashMap<(String, String), TrackedEntity>,
+    door_cameras: DashMap<String, HashSet<String>>, // Per home, from onboarding zones
+    transitions: DashMap<String, Mutex<VecDeque<TrackTransition>>>,
+}
+
+impl EntityTracker {
+    pub fn new(config: TrackerConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &TrackerConfig {
+        &self.config
+    }
+
+    /// Cameras that watch a door for this home. Without any, a camera counts
+    /// as a door camera when its id mentions "door".
+    pub fn set_door_cameras(&self, home_id: &str, cameras: HashSet<String>) {
+        self.door_cameras.insert(home_id.to_string(), cameras);
+    }
+
+    fn is_door_camera(&self, home_id: &str, camera: &str) -> bool {
+        match self.door_cameras.get(home_id) {
+            Some(cameras) if !cameras.is_empty() => cameras.contains(camera),
+            _ => camera.to_lowercase().contains("door"),
+        }
+    }
+
+    /// Feed one detection of `track_id`; out-of-order sightings only refresh the camera list
+    pub fn observe(&self, home_id: &str, track_id: &str, camera: &str, ts: f64, interacted: bool) -> TrackUpdate {
+        let at_door = self.is_door_camera(home_id, camera);
+        let key = (home_id.to_string(), track_id.to_string());
+        let mut transitions = Vec::new();
+
+        let mut entry = self.tracks.entry(key).or_insert_with(|| TrackedEntity {
+            home_id: home_id.to_string(),
+            track_id: track_id.to_string(),
+            state: TrackState::Lost, // Replaced below
+            first_seen: ts,
+            last_seen: ts,
+            state_since: ts,
+            door_since: None,
+            cameras: Vec::new(),
+            interacted: false,
+        });
+        let entity = entry.value_mut();
+        let is_new = entity.cameras.is_empty();
+        if !entity.cameras.iter().any(|c| c == camera) {
+            entity.cameras.push(camera.to_string());
+        }
+        if !is_new && ts < entity.last_seen {
+            return TrackUpdate { entity: entity.clone(), dwell_increment_s: Some(0.0), transitions };
+        }
+
+        let gap = ts - entity.last_seen;
+        let returning = entity.state == TrackState::Lost && !is_new;
+        if returning {
+            // A lost track seen again starts a fresh visit
+            entity.first_seen = ts;
+            entity.door_since = None;
+            entity.interacted = false;
+        }
+        entity.last_seen = ts;
+        entity.interacted |= interacted;
+        let was_at_door = entity.door_since.is_some();
+        if at_door {
+            entity.door_since.get_or_insert(ts);
+        } else {
+            entity.door_since = None;
+        }
+
+        let previous = (!is_new).then_some(entity.state);
+        let next = if at_door {
+            if !entity.interacted && entity.door_dwell_s() >= self.config.loiter_after_s {
+                TrackState::Loitering
+            } else {
+                TrackState::AtDoor
+            }
+        } else {
+            match previous {
+                None | Some(TrackState::Lost) => TrackState::Approaching,
+                Some(TrackState::AtDoor) => TrackState::Departing,
+                Some(TrackState::Loitering) if was_at_door => TrackState::Departing,
+                Some(TrackState::Approaching) if entity.dwell_s() >= self.config.loiter_after_s => TrackState::Loitering,
+                Some(state) => state,
+            }
+        };
+        if previous != Some(next) {
+            entity.state = next;
+            entity.state_since = ts;
+            transitions.push(TrackTransition {
+                home_id: home_id.to_string(),
+                track_id: track_id.to_string(),
+                from: previous,
+                to: next,
+                ts,
+                dwell_s: entity.dwell_s(),
+                message: entity.status_line(),
+            });
+        }
+
+        let dwell_increment_s = (!is_new && !returning).then(|| gap.min(self.config.max_gap_dwell_s));
+        let snapshot = entity.clone();
+        drop(entry);
+        self.log(home_id, &transitions);
+        TrackUpdate { entity: snapshot, dwell_increment_s, transitions }
+    }
+
+    /// Mark tracks not seen for `lost_after_s` as Lost and forget long-lost ones
+    pub fn sweep(&self, now: f64) -> Vec<TrackTransition> {
+        let mut transitions = Vec::new();
+        for mut entry in self.tracks.iter_mut() {
+            let entity = entry.value_mut();
+            if entity.state != TrackState::Lost && now - entity.last_seen >= self.config.lost_after_s {
+                let from = entity.state;
+                entity.state = TrackState::Lost;
+                entity.state_since = now;
+                entity.door_since = None;
+                transitions.push(TrackTransition {
+                    home_id: entity.home_id.clone(),
+                    track_id: entity.track_id.clone(),
+                    from: Some(from),
+                    to: TrackState::Lost,
+                    ts: now,
+                    dwell_s: entity.dwell_s(),
+                    message: entity.status_line(),
+                });
+            }
+        }
+        let forget_after = self.config.forget_after_s;
+        self.tracks.retain(|_, e| e.state != TrackState::Lost || now - e.last_seen < forget_after);
+        for transition in &transitions {
+            self.log(&transition.home_id, std::slice::from_ref(transition));
+        }
+        transitions
+    }
+
+    pub fn get(&self, home_id: &str, track_id: &str) -> Option<TrackedEntity> {
+        self.tracks.get(&(home_id.to_string(), track_id.to_string())).map(|e| e.clone())
+    }
+
+    /// Tracks for a home that are not Lost
+    pub fn active(&self, home_id: &str) -> Vec<TrackedEntity> {
+        let mut tracks: Vec<TrackedEntity> = self.tracks.iter()
+            .filter(|e| e.home_id == home_id && e.state != TrackState::Lost)
+            .map(|e| e.clone())
+            .collect();
+        tracks.sort_by(|a, b| a.first_seen.total_cmp(&b.first_seen));
+        tracks
+    }
+
+    /// Recent transitions for a home, oldest first
+    pub fn recent_transitions(&self, home_id: &str) -> Vec<TrackTransition> {
+        self.transitions.get(home_id)
+            .and_then(|log| log.lock().ok().map(|l| l.iter().cloned().collect()))
+            .unwrap_or_default()
+    }
+
+    fn log(&self, home_id: &str, transitions: &[TrackTransition]) {
+        if transitions.is_empty() {
+            return;
+        }
+        let log = self.transitions.entry(home_id.to_string()).or_default();
+        let Ok(mut log) = log.lock() else { return };
+        for transition in transitions {
+            tracing::debug!("Track {} in {}: {:?} -> {:?}", transition.track_id, home_id, transition.from, transition.to);
+            log.push_back(transition.clone());
+            while log.len() > self.config.transition_log {
+                log.pop_front();
+            }
+        }
+    }
+}
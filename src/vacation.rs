@@ -0,0 +1,189 @@
+// src/vacation.rs
+
+// Vacation / extended-away mode. While a home is on vacation the pipeline
+// treats the occupants as away (higher away probability and a raised prior),
+// alerts go out immediately instead of waiting for the overnight summary, the
+// summary becomes a digest of the whole day's activity, and any event on an
+// interior zone escalates to Critical. Homes can also hand lights and other
+// actuators a presence-simulation schedule that runs in the home's timezone.
+
+use crate::overnight::window::resolve_local;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum VacationError {
+    #[error("Vacation ends before it starts")]
+    InvalidWindow,
+    #[error("Unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("Presence schedule for '{0}' turns on and off at the same time")]
+    EmptySchedule(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct VacationConfig {
+    pub away_prob: f64,          // Replaces the event's away probability
+    pub prior_logit_boost: f64,  // Added to the home's prior while away
+}
+
+impl Default for VacationConfig {
+    fn default() -> Self {
+        Self {
+            away_prob: 0.97,
+            prior_logit_boost: 0.7,
+        }
+    }
+}
+
+// One device switched on and off at local times, e.g. living-room lights
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresenceSchedule {
+    pub device: String,
+    pub on_at: NaiveTime,
+    pub off_at: NaiveTime,
+    #[serde(default)]
+    pub jitter_minutes: u32, // Each switch is delayed by up to this much, varying day to day
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VacationRequest {
+    pub starts_at: Option<DateTime<Utc>>, // Defaults to now
+    pub ends_at: Option<DateTime<Utc>>,   // Open-ended until turned off
+    #[serde(default)]
+    pub interior_zones: Vec<String>,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    #[serde(default)]
+    pub presence_simulation: Vec<PresenceSchedule>,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VacationMode {
+    pub home_id: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub interior_zones: Vec<String>,
+    pub timezone: String,
+    pub presence_simulation: Vec<PresenceSchedule>,
+}
+
+impl VacationMode {
+    pub fn is_active(&self, at: DateTime<Utc>) -> bool {
+        at >= self.starts_at && self.ends_at.map_or(true, |end| at < end)
+    }
+
+    pub fn is_interior(&self, zone: &str) -> bool {
+        self.interior_zones.iter().any(|z| z == zone)
+    }
+}
+
+// A device switch that came due
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PresenceAction {
+    pub home_id: String,
+    pub device: String,
+    pub on: bool,
+    pub at: DateTime<Utc>,
+}
+
+/// Drives lights or other actuators, e.g. through a home-automation webhook
+#[async_trait]
+pub trait PresenceActuator: Send + Sync {
+    async fn actuate(&self, action: &PresenceAction);
+}
+
+#[derive(Default)]
+pub struct VacationRegistry {
+    config: VacationConfig,
+    homes: DashMap<String, VacationMode>,
+}
+
+impl VacationRegistry {
+    pub fn new(config: VacationConfig) -> Self {
+        Self { config, homes: DashMap::new() }
+    }
+
+    pub fn config(&self) -> &VacationConfig {
+        &self.config
+    }
+
+    pub fn activate(&self, home_id: &str, request: VacationRequest, now: DateTime<Utc>) -> Result<VacationMode, VacationError> {
+        let starts_at = request.starts_at.unwrap_or(now);
+        if request.ends_at.is_some_and(|end| end <= starts_at) {
+            return Err(VacationError::InvalidWindow);
+        }
+        request.timezone.parse::<Tz>().map_err(|_| VacationError::UnknownTimezone(request.timezone.clone()))?;
+        if let Some(schedule) = request.presence_simulation.iter().find(|s| s.on_at == s.off_at) {
+            return Err(VacationError::EmptySchedule(schedule.device.clone()));
+        }
+
+        let mode = VacationMode {
+            home_id: home_id.to_string(),
+            starts_at,
+            ends_at: request.ends_at,
+            interior_zones: request.interior_zones,
+            timezone: request.timezone,
+            presence_simulation: request.presence_simulation,
+        };
+        self.homes.insert(home_id.to_string(), mode.clone());
+        Ok(mode)
+    }
+
+    pub fn deactivate(&self, home_id: &str) -> Option<VacationMode> {
+        self.homes.remove(home_id).map(|(_, mode)| mode)
+    }
+
+    /// The home's vacation, including one scheduled to start later
+    pub fn get(&self, home_id: &str) -> Option<VacationMode> {
+        self.homes.get(home_id).map(|m| m.clone())
+    }
+
+    /// The home's vacation if it is in effect at `at`
+    pub fn active(&self, home_id: &str, at: DateTime<Utc>) -> Option<VacationMode> {
+        self.homes.get(home_id).filter(|m| m.is_active(at)).map(|m| m.clone())
+    }
+
+    /// Device switches due in (since, now] across every home on vacation, in time order
+    pub fn due_actions(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> Vec<PresenceAction> {
+        let mut actions = Vec::new();
+        for mode in self.homes.iter() {
+            let Ok(tz) = mode.timezone.parse::<Tz>() else { continue };
+            for schedule in &mode.presence_simulation {
+                for (time, on) in [(schedule.on_at, true), (schedule.off_at, false)] {
+                    // Yesterday's date too, so a jittered switch near midnight is not missed
+                    let first = (since - Duration::days(1)).with_timezone(&tz).date_naive();
+                    let last = now.with_timezone(&tz).date_naive();
+                    for offset in 0..=(last - first).num_days() {
+                        let date = first + Duration::days(offset);
+                        let at = resolve_local(tz, date.and_time(time)) + jitter(&mode.home_id, &schedule.device, date, on, schedule.jitter_minutes);
+                        if at > since && at <= now && mode.is_active(at) {
+                            actions.push(PresenceAction { home_id: mode.home_id.clone(), device: schedule.device.clone(), on, at });
+                        }
+                    }
+                }
+            }
+        }
+        actions.sort_by_key(|a| a.at);
+        actions
+    }
+}
+
+// Stable per-day delay so a restart replays the same schedule
+fn jitter(home_id: &str, device: &str, date: chrono::NaiveDate, on: bool, max_minutes: u32) -> Duration {
+    if max_minutes == 0 {
+        return Duration::zero();
+    }
+    let mut hasher = DefaultHasher::new();
+    (home_id, device, date, on).hash(&mut hasher);
+    Duration::minutes((hasher.finish() % (max_minutes as u64 + 1)) as i64)
+}
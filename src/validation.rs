@@ -0,0 +1,501 @@
+// src/validation.rs
+//
+// Startup validation for config structs. Bad values (zero temperature, negative
+// TTLs, unknown timezones) otherwise show up much later as odd probabilities or
+// incidents that never close. Each struct reports every problem it finds with a
+// dotted path, so one run of `--check-config` lists everything to fix.
+
+use crate::delivery::SiemConfig;
+use crate::edge_inference::EdgeInferenceConfig;
+use crate::embeddings::EmbeddingStoreConfig;
+use crate::environment::{CalendarConfig, EnrichmentConfig};
+use crate::federated_learning::FederatedLearningConfig;
+use crate::federation::FederationConfig;
+use crate::idempotency::IdempotencyConfig;
+use crate::image_transcode::TranscodeConfig;
+use crate::onboarding::{HomeConfig, OnboardingRequest};
+use crate::overnight::{OvernightConfig, OvernightStorageBackend};
+use crate::pipeline::{PipelineConfig, SubscriptionTier};
+use crate::sanitization::SanitizationConfig;
+use crate::visitor_tokens::VisitorTokenConfig;
+use crate::thinking::{ReasonerConfig, ThinkingAIConfig, UncertaintyPolicy};
+use crate::SystemConfig;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Could not read config file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Could not parse config file: {0}")]
+    Parse(String),
+
+    #[error("{} invalid config value(s):\n  {}", .0.len(), .0.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("\n  "))]
+    Invalid(Vec<ConfigIssue>),
+}
+
+// Collects issues under a path prefix
+pub struct Issues<'a> {
+    prefix: String,
+    out: &'a mut Vec<ConfigIssue>,
+}
+
+impl<'a> Issues<'a> {
+    pub fn new(prefix: &str, out: &'a mut Vec<ConfigIssue>) -> Self {
+        Self { prefix: prefix.to_string(), out }
+    }
+
+    fn path(&self, field: &str) -> String {
+        if self.prefix.is_empty() { field.to_string() } else { format!("{}.{}", self.prefix, field) }
+    }
+
+    pub fn push(&mut self, field: &str, message: impl Into<String>) {
+        let path = self.path(field);
+        self.out.push(ConfigIssue { path, message: message.into() });
+    }
+
+    pub fn nested(&mut self, field: &str) -> Issues<'_> {
+        Issues { prefix: self.path(field), out: &mut *self.out }
+    }
+
+    pub fn finite(&mut self, field: &str, value: f64) -> bool {
+        if value.is_finite() {
+            true
+        } else {
+            self.push(field, format!("must be a finite number, got {}", value));
+            false
+        }
+    }
+
+    pub fn positive(&mut self, field: &str, value: f64) {
+        if self.finite(field, value) && value <= 0.0 {
+            self.push(field, format!("must be greater than 0, got {}", value));
+        }
+    }
+
+    pub fn probability(&mut self, field: &str, value: f64) {
+        if self.finite(field, value) && !(0.0..=1.0).contains(&value) {
+            self.push(field, format!("must be between 0 and 1, got {}", value));
+        }
+    }
+
+    pub fn timezone(&mut self, field: &str, value: &str) {
+        if value.parse::<Tz>().is_err() {
+            self.push(field, format!("unknown IANA timezone '{}'", value));
+        }
+    }
+
+    pub fn nonzero_duration(&mut self, field: &str, value: std::time::Duration) {
+        if value.is_zero() {
+            self.push(field, "must be longer than 0");
+        }
+    }
+}
+
+pub trait Validate {
+    fn collect_issues(&self, issues: &mut Issues<'_>);
+
+    /// All problems with this config, or Ok when it is usable
+    fn validate(&self) -> Result<(), ConfigError> {
+        let mut found = Vec::new();
+        self.collect_issues(&mut Issues::new("", &mut found));
+        if found.is_empty() { Ok(()) } else { Err(ConfigError::Invalid(found)) }
+    }
+}
+
+impl Validate for SystemConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        issues.nonzero_duration("prediction_horizon", self.prediction_horizon);
+        issues.positive("learning_rate", self.learning_rate);
+        if self.learning_rate > 1.0 {
+            issues.push("learning_rate", format!("must be at most 1, got {}", self.learning_rate));
+        }
+        issues.probability("safety_threshold", self.safety_threshold);
+    }
+}
+
+impl Validate for ReasonerConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        issues.finite("ring_llr", self.ring_llr);
+        issues.finite("token_llr", self.token_llr);
+        issues.finite("face_gain_llr", self.face_gain_llr);
+        issues.probability("p_ring_given_context", self.p_ring_given_context);
+        issues.probability("p_token_available", self.p_token_available);
+        issues.probability("p_second_angle_available", self.p_second_angle_available);
+        issues.probability("p_face_improvable", self.p_face_improvable);
+    }
+}
+
+impl Validate for ThinkingAIConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        issues.positive("incident_ttl_secs", self.incident_ttl_secs);
+        issues.finite("prior_logit", self.prior_logit);
+        issues.finite("mean_logit", self.mean_logit);
+        // Calibration divides by temperature
+        issues.positive("temperature", self.temperature);
+        issues.positive("odds_cap", self.odds_cap);
+        issues.positive("pos_cap", self.pos_cap);
+        issues.positive("neg_cap", self.neg_cap);
+        issues.finite("alert_threshold_logit", self.alert_threshold_logit);
+        self.reasoner_config.collect_issues(&mut issues.nested("reasoner_config"));
+        if let Some(calendar) = &self.calendar {
+            calendar.collect_issues(&mut issues.nested("calendar"));
+        }
+        for (i, channel) in self.custom_channels.iter().enumerate() {
+            if let Err(e) = channel.validate() {
+                issues.push(&format!("custom_channels[{}]", i), e.to_string());
+            }
+        }
+    }
+}
+
+impl Validate for CalendarConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        issues.timezone("timezone", &self.timezone);
+        match (self.latitude, self.longitude) {
+            (Some(lat), Some(lon)) => {
+                if !(-90.0..=90.0).contains(&lat) {
+                    issues.push("latitude", format!("must be between -90 and 90, got {}", lat));
+                }
+                if !(-180.0..=180.0).contains(&lon) {
+                    issues.push("longitude", format!("must be between -180 and 180, got {}", lon));
+                }
+            }
+            (None, None) => {}
+            _ => issues.push("latitude", "latitude and longitude must be set together"),
+        }
+        if !(0..=180).contains(&self.twilight_minutes) {
+            issues.push("twilight_minutes", format!("must be between 0 and 180, got {}", self.twilight_minutes));
+        }
+        issues.finite("dark_prior_logit", self.dark_prior_logit);
+        issues.finite("twilight_prior_logit", self.twilight_prior_logit);
+        issues.finite("holiday_prior_logit", self.holiday_prior_logit);
+    }
+}
+
+impl Validate for OvernightConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if self.home_id.trim().is_empty() {
+            issues.push("home_id", "must not be empty");
+        }
+        issues.timezone("timezone", &self.timezone);
+        if self.review_start_time == self.review_end_time {
+            issues.push("review_end_time", "review window has zero length");
+        }
+        if self.enabled && self.delivery_channels.is_empty() {
+            issues.push("delivery_channels", "at least one channel is needed to deliver the morning summary");
+        }
+        if let OvernightStorageBackend::Sqlite { database_url } = &self.storage {
+            if !database_url.starts_with("sqlite:") {
+                issues.push("storage.database_url", format!("expected a sqlite: URL, got '{}'", database_url));
+            }
+        }
+    }
+}
+
+impl Validate for IdempotencyConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if self.enabled {
+            issues.nonzero_duration("dedup_window", self.dedup_window);
+            if self.max_entries == 0 {
+                issues.push("max_entries", "must be greater than 0");
+            }
+        }
+    }
+}
+
+impl Validate for PipelineConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        for tier in [SubscriptionTier::Free, SubscriptionTier::Standard, SubscriptionTier::Premium] {
+            if !self.tier_routing.contains_key(&tier) {
+                issues.push("tier_routing", format!("no processing level for {:?}", tier));
+            }
+            if self.feature_gate.capabilities(&tier).is_none() {
+                issues.push("feature_gate", format!("no capabilities for {:?}", tier));
+            } else if self.feature_gate.retention(&tier).num_days() < 1 {
+                issues.push("feature_gate", format!("retention for {:?} must be at least 1 day", tier));
+            }
+        }
+        self.thinking_ai_config.collect_issues(&mut issues.nested("thinking_ai_config"));
+        self.idempotency.collect_issues(&mut issues.nested("idempotency"));
+        self.sanitization.collect_issues(&mut issues.nested("sanitization"));
+    }
+}
+
+impl Validate for SanitizationConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        issues.finite("default_llr", self.default_llr);
+        issues.positive("max_abs_llr", self.max_abs_llr);
+        issues.probability("default_away_prob", self.default_away_prob);
+        if issues.finite("max_future_skew_secs", self.max_future_skew_secs) && self.max_future_skew_secs < 0.0 {
+            issues.push("max_future_skew_secs", "must not be negative");
+        }
+    }
+}
+
+impl Validate for EnrichmentConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        issues.nonzero_duration("weather_ttl", self.weather_ttl);
+        if self.max_cached_locations == 0 {
+            issues.push("max_cached_locations", "must be greater than 0");
+        }
+    }
+}
+
+impl Validate for FederationConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if self.shared_key.len() < 16 {
+            issues.push("shared_key", "must be at least 16 characters");
+        }
+        if self.window <= chrono::Duration::zero() {
+            issues.push("window", "must be longer than 0");
+        }
+        issues.positive("radius_km", self.radius_km);
+        issues.positive("min_reporting_homes", self.min_reporting_homes);
+        issues.finite("llr_per_home", self.llr_per_home);
+        issues.finite("max_prior_boost", self.max_prior_boost);
+    }
+}
+
+impl Validate for SiemConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if self.host.trim().is_empty() {
+            issues.push("host", "must not be empty");
+        }
+        if self.port == 0 {
+            issues.push("port", "must not be 0");
+        }
+        if self.batch_size == 0 {
+            issues.push("batch_size", "must be greater than 0");
+        }
+        if self.queue_capacity < self.batch_size {
+            issues.push("queue_capacity", "must be at least batch_size");
+        }
+        issues.nonzero_duration("flush_interval", self.flush_interval);
+        issues.nonzero_duration("initial_backoff", self.initial_backoff);
+        if self.max_backoff < self.initial_backoff {
+            issues.push("max_backoff", "must be at least initial_backoff");
+        }
+    }
+}
+
+impl Validate for EmbeddingStoreConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if self.max_per_entity == 0 {
+            issues.push("max_per_entity", "must be greater than 0");
+        }
+        // Cosine similarity lives in [-1, 1]
+        for (field, value) in [("appearance_threshold", self.appearance_threshold), ("gait_threshold", self.gait_threshold)] {
+            if !value.is_finite() || !(-1.0..=1.0).contains(&value) {
+                issues.push(field, format!("must be between -1 and 1, got {}", value));
+            }
+        }
+        issues.probability("appearance_weight", self.appearance_weight as f64);
+    }
+}
+
+impl Validate for TranscodeConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if self.max_concurrent == 0 {
+            issues.push("max_concurrent", "must be greater than 0");
+        }
+        if self.max_source_pixels == 0 {
+            issues.push("max_source_pixels", "must be greater than 0");
+        }
+        issues.nonzero_duration("max_encode_time", self.max_encode_time);
+        for (channel, spec) in &self.renditions {
+            let mut spec_issues = issues.nested(&format!("renditions.{:?}", channel));
+            if spec.max_width == 0 || spec.max_height == 0 {
+                spec_issues.push("max_width", "dimensions must be greater than 0");
+            }
+            if !(1..=100).contains(&spec.quality) {
+                spec_issues.push("quality", format!("must be between 1 and 100, got {}", spec.quality));
+            }
+            if !(1..=10).contains(&spec.avif_speed) {
+                spec_issues.push("avif_speed", format!("must be between 1 and 10, got {}", spec.avif_speed));
+            }
+        }
+    }
+}
+
+impl Validate for VisitorTokenConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if !(4..=12).contains(&self.code_digits) {
+            issues.push("code_digits", format!("must be between 4 and 12, got {}", self.code_digits));
+        }
+        if self.max_window <= chrono::Duration::zero() {
+            issues.push("max_window", "must be positive");
+        }
+        if issues.finite("verified_identity_llr", self.verified_identity_llr) && self.verified_identity_llr > 0.0 {
+            issues.push("verified_identity_llr", "must not be positive; a valid code is exculpatory");
+        }
+        issues.finite("verified_token_llr", self.verified_token_llr);
+        issues.finite("rejected_token_llr", self.rejected_token_llr);
+        if self.suppression < chrono::Duration::zero() {
+            issues.push("suppression", "must not be negative");
+        }
+    }
+}
+
+// Config file read by the security daemon
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonConfig {
+    pub system: SystemConfig,
+    pub thinking_ai: ThinkingAIConfig,
+    pub overnight: Vec<OvernightConfig>,
+    pub edge_inference: Option<EdgeInferenceConfig>, // On-device models, loaded and warmed up at start
+    pub federated_learning: FederatedLearningConfig, // Opt-in cross-home weight sharing
+}
+
+impl DaemonConfig {
+    /// Read a YAML or JSON (by extension) config file without validating it
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text, path.extension().and_then(|e| e.to_str()))
+    }
+
+    /// Parse config text as JSON when `extension` is "json", YAML otherwise
+    pub fn parse(text: &str, extension: Option<&str>) -> Result<Self, ConfigError> {
+        match extension {
+            Some("json") => serde_json::from_str(text).map_err(|e| ConfigError::Parse(e.to_string())),
+            _ => serde_yaml::from_str(text).map_err(|e| ConfigError::Parse(e.to_string())),
+        }
+    }
+
+    /// Load and validate; the daemon refuses to start on any error
+    pub fn load_validated(path: &Path) -> Result<Self, ConfigError> {
+        let config = Self::load(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl Validate for DaemonConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        self.system.collect_issues(&mut issues.nested("system"));
+        self.thinking_ai.collect_issues(&mut issues.nested("thinking_ai"));
+        for (i, overnight) in self.overnight.iter().enumerate() {
+            overnight.collect_issues(&mut issues.nested(&format!("overnight[{}]", i)));
+        }
+        if let Some(edge) = &self.edge_inference {
+            edge.collect_issues(&mut issues.nested("edge_inference"));
+        }
+        self.federated_learning.collect_issues(&mut issues.nested("federated_learning"));
+    }
+}
+
+impl Validate for FederatedLearningConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if !self.enabled {
+            return;
+        }
+        issues.positive("epsilon", self.epsilon);
+        if !(self.delta > 0.0 && self.delta < 1.0) {
+            issues.push("delta", format!("must be within (0, 1), got {}", self.delta));
+        }
+        issues.positive("clip_norm", self.clip_norm);
+        if self.min_homes == 0 {
+            issues.push("min_homes", "must be at least 1");
+        }
+        if self.sync_interval_secs < 60 {
+            issues.push("sync_interval_secs", "must be at least 60");
+        }
+    }
+}
+
+impl Validate for EdgeInferenceConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if !(0.0..=1.0).contains(&self.score_threshold) {
+            issues.push("score_threshold", format!("must be within [0, 1], got {}", self.score_threshold));
+        }
+        for (i, spec) in self.models.iter().enumerate() {
+            if spec.input_size.0 == 0 || spec.input_size.1 == 0 {
+                issues.push(&format!("models[{}].input_size", i), "must be non-zero");
+            }
+            if spec.file.trim().is_empty() {
+                issues.push(&format!("models[{}].file", i), "must not be empty");
+            }
+        }
+        if self.models.iter().map(|m| m.kind).collect::<std::collections::HashSet<_>>().len() != self.models.len() {
+            issues.push("models", "each model kind may be listed only once");
+        }
+    }
+}
+
+impl Validate for OnboardingRequest {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        CalendarConfig {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            timezone: self.timezone.clone(),
+            ..CalendarConfig::default()
+        }.collect_issues(issues);
+        if self.share_with_neighbors && (self.latitude.is_none() || self.longitude.is_none()) {
+            issues.push("share_with_neighbors", "needs latitude and longitude");
+        }
+        if self.cameras.is_empty() {
+            issues.push("cameras", "at least one camera is needed");
+        }
+        let mut seen = std::collections::HashSet::new();
+        for (i, camera) in self.cameras.iter().enumerate() {
+            if camera.camera_id.trim().is_empty() {
+                issues.push(&format!("cameras[{}].camera_id", i), "must not be empty");
+            } else if !seen.insert(camera.camera_id.as_str()) {
+                issues.push(&format!("cameras[{}].camera_id", i), format!("'{}' is listed twice", camera.camera_id));
+            }
+            if camera.zone.trim().is_empty() {
+                issues.push(&format!("cameras[{}].zone", i), "must not be empty");
+            }
+        }
+        for (i, camera) in self.cameras.iter().enumerate() {
+            for other in camera.overlaps_with.iter().filter(|o| !seen.contains(o.as_str()) || **o == camera.camera_id) {
+                issues.push(&format!("cameras[{}].overlaps_with", i), format!("'{}' is not another listed camera", other));
+            }
+        }
+    }
+}
+
+impl Validate for UncertaintyPolicy {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        if issues.finite("confidence_level", self.confidence_level) && !(0.5..1.0).contains(&self.confidence_level) {
+            issues.push("confidence_level", format!("must be at least 0.5 and below 1, got {}", self.confidence_level));
+        }
+        issues.probability("max_below_threshold", self.max_below_threshold);
+        issues.positive("base_logit_sd", self.base_logit_sd);
+    }
+}
+
+impl Validate for HomeConfig {
+    fn collect_issues(&self, issues: &mut Issues<'_>) {
+        issues.finite("alert_threshold_logit", self.alert_threshold_logit);
+        if let Some(policy) = &self.uncertainty {
+            policy.collect_issues(&mut issues.nested("uncertainty"));
+        }
+        self.calendar.collect_issues(&mut issues.nested("calendar"));
+        if self.share_with_neighbors && (self.calendar.latitude.is_none() || self.calendar.longitude.is_none()) {
+            issues.push("share_with_neighbors", "needs calendar.latitude and calendar.longitude");
+        }
+        self.overnight.collect_issues(&mut issues.nested("overnight"));
+        for (i, zone) in self.zones.iter().enumerate() {
+            if issues.finite(&format!("zones[{}].prior_logit", i), zone.prior_logit) && zone.prior_logit.abs() > 3.0 {
+                issues.push(&format!("zones[{}].prior_logit", i), format!("must be within [-3, 3], got {}", zone.prior_logit));
+            }
+        }
+    }
+}
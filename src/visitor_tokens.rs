@@ -0,0 +1,409 @@
+// src/visitor_tokens.rs
+//
+// Time-boxed visitor access codes. A homeowner issues a code for a delivery or
+// service window; the doorbell or keypad reports what the visitor entered and a
+// successful verification becomes strong exculpatory identity evidence plus a
+// short suppression window for the rest of the visit, limited to the camera and
+// person track that entered the code. Codes are stored hashed and only returned
+// once, when issued; repeated wrong codes lock the home's keypads for a while.
+
+use crate::home_files;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    #[error("Token window is empty or reversed")]
+    InvalidWindow,
+    #[error("Token window of {0} hours exceeds the allowed maximum")]
+    WindowTooLong(i64),
+    #[error("Token {0} not found")]
+    NotFound(Uuid),
+    #[error("Tokens could not be saved: {0}")]
+    Storage(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct VisitorTokenConfig {
+    pub code_digits: u32,
+    pub max_window: Duration,
+    pub verified_identity_llr: f64, // Identity LLR for a visitor who entered a valid code
+    pub verified_token_llr: f64,
+    pub rejected_token_llr: f64,    // A wrong or stale code is mildly suspicious
+    pub suppression: Duration,      // Follow-up events after a valid code inherit its evidence
+    pub max_failed_codes: usize,    // Wrong codes a home accepts within `lockout` before checks stop
+    pub lockout: Duration,
+}
+
+impl Default for VisitorTokenConfig {
+    fn default() -> Self {
+        Self {
+            code_digits: 6,
+            max_window: Duration::days(7),
+            verified_identity_llr: -2.5,
+            verified_token_llr: -1.0,
+            rejected_token_llr: 0.5,
+            suppression: Duration::minutes(10),
+            max_failed_codes: 5,
+            lockout: Duration::minutes(15),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisitorToken {
+    pub id: Uuid,
+    pub home_id: String,
+    pub label: String, // "Grocery delivery", "Dog walker"
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub revoked: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    code_hash: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueTokenRequest {
+    pub label: String,
+    pub valid_from: Option<DateTime<Utc>>, // Defaults to now
+    pub valid_until: DateTime<Utc>,
+    pub max_uses: Option<u32>,
+}
+
+/// A freshly issued token; the only time the plaintext code is available
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedToken {
+    pub code: String,
+    pub token: VisitorToken,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum TokenVerification {
+    Valid { token_id: Uuid, label: String },
+    NotYetValid { token_id: Uuid },
+    Expired { token_id: Uuid },
+    Exhausted { token_id: Uuid },
+    Revoked { token_id: Uuid },
+    Unknown,
+    RateLimited, // Too many wrong codes; not checked at all
+}
+
+impl TokenVerification {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, TokenVerification::Valid { .. })
+    }
+}
+
+/// Evidence a code (or an open suppression window) contributes to an event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenEvidence {
+    pub token_id: Option<Uuid>,
+    pub label: Option<String>,
+    pub identity_llr: Option<f64>, // Replaces camera identity evidence when verified
+    pub token_llr: f64,
+    pub from_suppression: bool,
+}
+
+// Follow-up window opened by a valid code, for the person who entered it
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenSuppression {
+    pub token_id: Uuid,
+    pub label: String,
+    pub camera: String,
+    pub track: String,
+    pub until: DateTime<Utc>,
+}
+
+// On-disk form of a home's tokens; unlike the API view it keeps the code hashes
+#[derive(Serialize, Deserialize)]
+struct HomeTokens {
+    home_id: String,
+    tokens: Vec<StoredToken>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredToken {
+    #[serde(flatten)]
+    token: VisitorToken,
+    code_hash: String,
+}
+
+#[derive(Debug, Default)]
+pub struct VisitorTokenStore {
+    config: VisitorTokenConfig,
+    tokens: DashMap<String, Vec<VisitorToken>>,
+    suppressions: DashMap<String, Vec<TokenSuppression>>,
+    failures: DashMap<String, VecDeque<DateTime<Utc>>>, // Recent wrong codes per home
+    dir: Option<PathBuf>,
+}
+
+fn hash_code(home_id: &str, code: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}:{}", home_id, code.trim()).as_bytes()))
+}
+
+impl VisitorTokenStore {
+    pub fn new(config: VisitorTokenConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Store backed by `dir`, loading any tokens already there
+    pub fn persistent(config: VisitorTokenConfig, dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let tokens = DashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path).map(|text| serde_json::from_str::<HomeTokens>(&text)) {
+                Ok(Ok(home)) => {
+                    let restored = home.tokens.into_iter()
+                        .map(|stored| VisitorToken { code_hash: stored.code_hash, ..stored.token })
+                        .collect();
+                    tokens.insert(home.home_id, restored);
+                }
+                Ok(Err(e)) => tracing::warn!("Skipping unreadable visitor tokens {}: {}", path.display(), e),
+                Err(e) => tracing::warn!("Skipping visitor tokens {}: {}", path.display(), e),
+            }
+        }
+        Ok(Self { config, tokens, dir: Some(dir), ..Default::default() })
+    }
+
+    pub fn config(&self) -> &VisitorTokenConfig {
+        &self.config
+    }
+
+    pub fn issue(&self, home_id: &str, request: IssueTokenRequest) -> Result<IssuedToken, TokenError> {
+        self.issue_at(home_id, request, Utc::now())
+    }
+
+    pub fn issue_at(&self, home_id: &str, request: IssueTokenRequest, now: DateTime<Utc>) -> Result<IssuedToken, TokenError> {
+        let valid_from = request.valid_from.unwrap_or(now);
+        if request.valid_until <= valid_from || request.valid_until <= now {
+            return Err(TokenError::InvalidWindow);
+        }
+        if request.valid_until - valid_from > self.config.max_window {
+            return Err(TokenError::WindowTooLong((request.valid_until - valid_from).num_hours()));
+        }
+
+        let mut tokens = self.tokens.entry(home_id.to_string()).or_default();
+        // Regenerate on the rare collision with another live code for the same home
+        let code = loop {
+            let candidate = self.generate_code();
+            let hash = hash_code(home_id, &candidate);
+            if !tokens.iter().any(|t| t.code_hash == hash && !t.revoked && t.valid_until > now) {
+                break candidate;
+            }
+        };
+        let token = VisitorToken {
+            id: Uuid::new_v4(),
+            home_id: home_id.to_string(),
+            label: request.label,
+            valid_from,
+            valid_until: request.valid_until,
+            max_uses: request.max_uses,
+            uses: 0,
+            revoked: false,
+            created_at: now,
+            last_used_at: None,
+            code_hash: hash_code(home_id, &code),
+        };
+        tokens.push(token.clone());
+        drop(tokens);
+        self.persist(home_id)?;
+        Ok(IssuedToken { code, token })
+    }
+
+    fn generate_code(&self) -> String {
+        let modulus = 10u128.pow(self.config.code_digits.clamp(4, 12));
+        let value = Uuid::new_v4().as_u128() % modulus;
+        format!("{:0width$}", value, width = self.config.code_digits.clamp(4, 12) as usize)
+    }
+
+    /// Tokens for a home, newest first
+    pub fn list(&self, home_id: &str) -> Vec<VisitorToken> {
+        let mut tokens = self.tokens.get(home_id).map(|t| t.clone()).unwrap_or_default();
+        tokens.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        tokens
+    }
+
+    pub fn get(&self, home_id: &str, token_id: Uuid) -> Option<VisitorToken> {
+        self.tokens.get(home_id)?.iter().find(|t| t.id == token_id).cloned()
+    }
+
+    pub fn revoke(&self, home_id: &str, token_id: Uuid) -> Result<VisitorToken, TokenError> {
+        let revoked = {
+            let mut tokens = self.tokens.get_mut(home_id).ok_or(TokenError::NotFound(token_id))?;
+            let token = tokens.iter_mut().find(|t| t.id == token_id).ok_or(TokenError::NotFound(token_id))?;
+            token.revoked = true;
+            token.clone()
+        };
+        if let Some(mut suppressions) = self.suppressions.get_mut(home_id) {
+            suppressions.retain(|s| s.token_id != token_id);
+        }
+        self.persist(home_id)?;
+        Ok(revoked)
+    }
+
+    /// Check a code reported by the doorbell/keypad; a valid code is consumed once.
+    /// Every rejected code counts towards the home's lockout.
+    pub fn verify(&self, home_id: &str, code: &str, at: DateTime<Utc>) -> TokenVerification {
+        if self.locked_out(home_id, at) {
+            return TokenVerification::RateLimited;
+        }
+        let outcome = self.check(home_id, code, at);
+        if outcome.is_valid() {
+            if let Err(e) = self.persist(home_id) {
+                tracing::warn!("Visitor token use for {} not saved: {}", home_id, e);
+            }
+        } else {
+            let mut failures = self.failures.entry(home_id.to_string()).or_default();
+            failures.retain(|t| *t > at - self.config.lockout);
+            failures.push_back(at);
+        }
+        outcome
+    }
+
+    fn locked_out(&self, home_id: &str, at: DateTime<Utc>) -> bool {
+        self.failures.get(home_id).is_some_and(|failures| {
+            failures.iter().filter(|t| **t > at - self.config.lockout).count() >= self.config.max_failed_codes
+        })
+    }
+
+    fn check(&self, home_id: &str, code: &str, at: DateTime<Utc>) -> TokenVerification {
+        let hash = hash_code(home_id, code);
+        let Some(mut tokens) = self.tokens.get_mut(home_id) else {
+            return TokenVerification::Unknown;
+        };
+
+        // Several tokens may share a hash over time; prefer one that's usable now
+        let mut outcome = TokenVerification::Unknown;
+        for token in tokens.iter_mut().filter(|t| t.code_hash == hash) {
+            let result = if token.revoked {
+                TokenVerification::Revoked { token_id: token.id }
+            } else if at < token.valid_from {
+                TokenVerification::NotYetValid { token_id: token.id }
+            } else if at > token.valid_until {
+                TokenVerification::Expired { token_id: token.id }
+            } else if token.max_uses.map_or(false, |max| token.uses >= max) {
+                TokenVerification::Exhausted { token_id: token.id }
+            } else {
+                token.uses += 1;
+                token.last_used_at = Some(at);
+                TokenVerification::Valid { token_id: token.id, label: token.label.clone() }
+            };
+            outcome = result;
+            if outcome.is_valid() {
+                break;
+            }
+        }
+        outcome
+    }
+
+    /// Evidence from a code carried by an event
+    pub fn evidence_for(&self, home_id: &str, code: &str, at: DateTime<Utc>) -> TokenEvidence {
+        match self.verify(home_id, code, at) {
+            TokenVerification::Valid { token_id, label } => TokenEvidence {
+                token_id: Some(token_id),
+                label: Some(label),
+                identity_llr: Some(self.config.verified_identity_llr),
+                token_llr: self.config.verified_token_llr,
+                from_suppression: false,
+            },
+            _ => TokenEvidence {
+                token_id: None,
+                label: None,
+                identity_llr: None,
+                token_llr: self.config.rejected_token_llr,
+                from_suppression: false,
+            },
+        }
+    }
+
+    /// Let the person on `track` who entered a valid code keep its evidence on `camera` for the rest of the visit
+    pub fn open_suppression(&self, home_id: &str, evidence: &TokenEvidence, camera: &str, track: &str, at: DateTime<Utc>) {
+        let (Some(token_id), Some(label), false) = (evidence.token_id, &evidence.label, evidence.from_suppression) else {
+            return;
+        };
+        let mut suppressions = self.suppressions.entry(home_id.to_string()).or_default();
+        suppressions.retain(|s| s.until >= at && !(s.camera == camera && s.track == track));
+        suppressions.push(TokenSuppression {
+            token_id,
+            label: label.clone(),
+            camera: camera.to_string(),
+            track: track.to_string(),
+            until: at + self.config.suppression,
+        });
+    }
+
+    /// Suppression window still open at `at` for this camera and track, if any
+    pub fn active_suppression(&self, home_id: &str, camera: &str, track: &str, at: DateTime<Utc>) -> Option<TokenSuppression> {
+        let mut suppressions = self.suppressions.get_mut(home_id)?;
+        suppressions.retain(|s| s.until >= at);
+        suppressions.iter().find(|s| s.camera == camera && s.track == track).cloned()
+    }
+
+    /// Evidence a follow-up event without a code inherits from an open suppression window
+    pub fn suppression_evidence(&self, home_id: &str, camera: &str, track: &str, at: DateTime<Utc>) -> Option<TokenEvidence> {
+        self.active_suppression(home_id, camera, track, at).map(|s| TokenEvidence {
+            token_id: Some(s.token_id),
+            label: Some(s.label),
+            identity_llr: Some(self.config.verified_identity_llr),
+            token_llr: self.config.verified_token_llr,
+            from_suppression: true,
+        })
+    }
+
+    /// Drop tokens that expired before `before`
+    pub fn prune_expired(&self, before: DateTime<Utc>) {
+        let mut pruned = Vec::new();
+        for mut tokens in self.tokens.iter_mut() {
+            let count = tokens.len();
+            tokens.retain(|t| t.valid_until >= before);
+            if tokens.len() != count {
+                pruned.push(tokens.key().clone());
+            }
+        }
+        self.tokens.retain(|_, tokens| !tokens.is_empty());
+        for home_id in pruned {
+            if let Err(e) = self.persist(&home_id) {
+                tracing::warn!("Pruned visitor tokens for {} not saved: {}", home_id, e);
+            }
+        }
+    }
+
+    fn persist(&self, home_id: &str) -> Result<(), TokenError> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let tokens = self.tokens.get(home_id)
+            .map(|tokens| tokens.iter().map(|t| StoredToken { token: t.clone(), code_hash: t.code_hash.clone() }).collect())
+            .unwrap_or_default();
+        let record = HomeTokens { home_id: home_id.to_string(), tokens };
+        let json = serde_json::to_vec_pretty(&record).map_err(|e| TokenError::Storage(e.to_string()))?;
+        home_files::write_json(dir, home_id, &json).map_err(|e| TokenError::Storage(e.to_string()))
+    }
+}
+
+/// Access code reported by a doorbell or keypad in the event payload, if any
+pub fn extract_access_code(data: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(data).ok()?;
+    ["access_code", "keypad_code", "token"].iter()
+        .find_map(|key| json.get(*key))
+        .and_then(|v| match v {
+            serde_json::Value::String(s) => Some(s.trim().to_string()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .filter(|code| !code.is_empty())
+}
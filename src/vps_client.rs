@@ -1,57 +1,171 @@
 // src/vps_client.rs
 
+#[cfg(feature = "chaos")]
+use crate::chaos::ChaosInjector;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitMetrics, CircuitState};
+use moka::future::Cache;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use bytes::Bytes;
+use crate::ingest::EventPayload;
+use crate::redaction::FaceRegion;
+use std::collections::hash_map::DefaultHasher;
 use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
 
 // Represents the response from the VPS API for a processing request
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VpsProcessingResponse {
     pub job_id: String,
     pub status: String,
     pub result_url: Option<String>,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub appearance_embedding: Option<Vec<f32>>, // Person re-id vector, when the VPS extracted one
+    #[serde(default)]
+    pub gait_embedding: Option<Vec<f32>>,
+    #[serde(default)]
+    pub liveness_score: Option<f64>, // Probability the face is a live person, not a photo or screen
+    #[serde(default)]
+    pub faces: Option<Vec<FaceRegion>>, // Faces found in the snapshot; None when no face detector ran
+    #[serde(skip)]
+    pub from_cache: bool, // Served by VpsResponseCache; job_id is the original job's
 }
 
 // Represents the payload for a processing request
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VpsProcessingRequest {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub api_key: String, // The submitting account's key, for usage attribution on the VPS
     pub event_id: String,
-    pub sensor_data: String,
+    pub sensor_data: EventPayload, // Shared with the RawEvent, so failover retries copy no payload
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_data: Option<Bytes>, // Pre-downloaded image data
     pub processing_level: String,
     pub user_context: String,
 }
 
+// Errors raised by the client itself rather than the remote API
+#[derive(Debug, thiserror::Error)]
+pub enum VpsClientError {
+    #[error("VPS circuit breaker is open, failing fast")]
+    CircuitOpen,
+
+    #[error("No healthy VPS endpoint available")]
+    NoHealthyEndpoint,
+
+    #[error("Invalid VPS setting {0}: {1}")]
+    Config(&'static str, String),
+}
+
+// Configuration for resilience behaviour of the client
+#[derive(Debug, Clone)]
+pub struct VpsClientConfig {
+    pub request_timeout: Duration,
+    pub circuit_breaker: CircuitBreakerConfig,
+    pub health_poll_interval: Duration,
+    pub health_timeout: Duration,
+}
+
+impl Default for VpsClientConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(10),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            health_poll_interval: Duration::from_secs(15),
+            health_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
 // A client for interacting with the real VPS API
 #[derive(Debug)]
 pub struct VpsApiClient {
     client: Client,
     api_base_url: String,
+    config: VpsClientConfig,
+    breaker: Arc<CircuitBreaker>,
+    healthy: Arc<AtomicBool>, // Result of the last background health probe
+    #[cfg(feature = "chaos")]
+    chaos: std::sync::OnceLock<Arc<ChaosInjector>>,
 }
 
 impl VpsApiClient {
     // Creates a new API client
     pub fn new(api_base_url: String) -> Self {
+        Self::with_config(api_base_url, VpsClientConfig::default())
+    }
+
+    // Creates a client with custom timeouts and breaker thresholds
+    pub fn with_config(api_base_url: String, config: VpsClientConfig) -> Self {
+        let client = Client::builder()
+            .timeout(config.request_timeout)
+            .build()
+            .expect("Failed to create HTTP client");
+        let breaker = Arc::new(CircuitBreaker::new(
+            format!("vps:{}", api_base_url),
+            config.circuit_breaker.clone(),
+        ));
+
         VpsApiClient {
-            client: Client::new(),
+            client,
             api_base_url,
+            config,
+            breaker,
+            healthy: Arc::new(AtomicBool::new(true)),
+            #[cfg(feature = "chaos")]
+            chaos: std::sync::OnceLock::new(),
         }
     }
 
+    // Inject faults into every later request; the first injector installed wins
+    #[cfg(feature = "chaos")]
+    pub fn install_chaos(&self, chaos: Arc<ChaosInjector>) {
+        let _ = self.chaos.set(chaos);
+    }
+
     // Submits an event for processing to the VPS
     pub async fn process_event(
         &self,
         request: VpsProcessingRequest,
-    ) -> Result<VpsProcessingResponse, Box<dyn Error>> {
+    ) -> Result<VpsProcessingResponse, Box<dyn Error + Send + Sync>> {
+        if !self.breaker.try_acquire() {
+            return Err(Box::new(VpsClientError::CircuitOpen));
+        }
+
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = self.chaos.get() {
+            if let Err(e) = chaos.vps_call().await {
+                self.breaker.record_failure();
+                return Err(Box::new(e));
+            }
+        }
+
         let url = format!("{}/v1/process", self.api_base_url);
-        
-        let response = self.client.post(&url)
-            .json(request)
+
+        let response = match self.client.post(&url)
+            .json(&request)
             .send()
-            .await?;
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                self.breaker.record_failure();
+                return Err(e.into());
+            }
+        };
+
+        // Only server-side errors count against the breaker; 4xx means the VPS is up
+        if response.status().is_server_error() {
+            self.breaker.record_failure();
+        } else {
+            self.breaker.record_success();
+        }
 
         if response.status().is_success() {
             let processing_response = response.json::<VpsProcessingResponse>().await?;
@@ -61,4 +175,412 @@ impl VpsApiClient {
             Err(format!("API Error: {}", error_text).into())
         }
     }
+
+    /// Whether calls are currently expected to reach the VPS
+    pub fn is_available(&self) -> bool {
+        self.breaker.state() != CircuitState::Open
+    }
+
+    /// Whether an error means no VPS could be reached without waiting on a timeout
+    pub fn is_circuit_open_error(err: &(dyn Error + 'static)) -> bool {
+        matches!(
+            err.downcast_ref::<VpsClientError>(),
+            Some(VpsClientError::CircuitOpen) | Some(VpsClientError::NoHealthyEndpoint)
+        )
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.api_base_url
+    }
+
+    pub fn circuit_metrics(&self) -> CircuitMetrics {
+        self.breaker.metrics()
+    }
+
+    pub fn last_health_ok(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Probe the VPS health endpoint once and update breaker state
+    pub async fn probe_health(&self) -> bool {
+        let url = format!("{}/health", self.api_base_url);
+        let ok = match self.client.get(&url).timeout(self.config.health_timeout).send().await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        };
+
+        let was_ok = self.healthy.swap(ok, Ordering::Relaxed);
+        if ok {
+            if !was_ok {
+                info!("VPS health probe recovered: {}", self.api_base_url);
+            }
+            self.breaker.allow_probe();
+        } else {
+            if was_ok {
+                warn!("VPS health probe failed: {}", self.api_base_url);
+            }
+            self.breaker.trip();
+        }
+        ok
+    }
+
+    /// Start polling the health endpoint in the background
+    pub fn spawn_health_poller(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let client = self.clone();
+        let mut ticker = tokio::time::interval(self.config.health_poll_interval);
+        tokio::spawn(async move {
+            loop {
+                ticker.tick().await;
+                client.probe_health().await;
+            }
+        })
+    }
+}
+
+// Configuration for the VPS response cache
+#[derive(Debug, Clone)]
+pub struct VpsCacheConfig {
+    pub enabled: bool,
+    pub ttl: Duration,     // How long an analysis stays reusable
+    pub max_entries: u64,  // Least recently used entries are evicted beyond this
+}
+
+impl Default for VpsCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: Duration::from_secs(60),
+            max_entries: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct VpsCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+    pub hit_rate: f64,
+}
+
+// Reuses VPS analyses for identical snapshots
+//
+// Keyed by the SHA-256 of the image bytes plus the processing level, so a
+// camera re-sending the same frame, or a burst of duplicate events, costs one
+// VPS call per TTL. Requests without pre-downloaded image data are never cached
+// because sensor text alone does not identify what the VPS would see.
+pub struct VpsResponseCache {
+    config: VpsCacheConfig,
+    entries: Cache<(String, String), VpsProcessingResponse>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl std::fmt::Debug for VpsResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VpsResponseCache")
+            .field("config", &self.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl VpsResponseCache {
+    pub fn new(config: VpsCacheConfig) -> Self {
+        let entries = Cache::builder()
+            .max_capacity(config.max_entries)
+            .time_to_live(config.ttl)
+            .build();
+
+        Self {
+            config,
+            entries,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn key(&self, request: &VpsProcessingRequest) -> Option<(String, String)> {
+        if !self.config.enabled {
+            return None;
+        }
+        let image = request.image_data.as_ref().filter(|i| !i.is_empty())?;
+        Some((hex::encode(Sha256::digest(image)), request.processing_level.clone()))
+    }
+
+    /// Cached analysis for the request's image, counting a hit or miss
+    pub async fn get(&self, request: &VpsProcessingRequest) -> Option<VpsProcessingResponse> {
+        let key = self.key(request)?;
+        match self.entries.get(&key).await {
+            Some(mut response) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                response.from_cache = true;
+                Some(response)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    pub async fn insert(&self, request: &VpsProcessingRequest, response: &VpsProcessingResponse) {
+        if let Some(key) = self.key(request) {
+            self.entries.insert(key, response.clone()).await;
+        }
+    }
+
+    pub fn stats(&self) -> VpsCacheStats {
+        let (hits, misses) = (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed));
+        VpsCacheStats {
+            hits,
+            misses,
+            entries: self.entries.entry_count(),
+            hit_rate: if hits + misses == 0 { 0.0 } else { hits as f64 / (hits + misses) as f64 },
+        }
+    }
+}
+
+// A single VPS backend in a multi-endpoint deployment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VpsEndpoint {
+    pub url: String,
+    pub weight: u32,            // Relative share of homes routed to this endpoint
+    pub region: Option<String>, // e.g. "eu-west", used for region preference
+}
+
+// Configuration for a pool of VPS endpoints
+#[derive(Debug, Clone)]
+pub struct VpsPoolConfig {
+    pub endpoints: Vec<VpsEndpoint>,
+    pub preferred_region: Option<String>,
+    pub max_attempts: usize, // Endpoints tried per event before giving up
+    pub client: VpsClientConfig,
+    pub cache: VpsCacheConfig,
+}
+
+impl Default for VpsPoolConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            preferred_region: None,
+            max_attempts: 2,
+            client: VpsClientConfig::default(),
+            cache: VpsCacheConfig::default(),
+        }
+    }
+}
+
+impl VpsPoolConfig {
+    /// Config from the environment; None unless VPS_ENDPOINTS lists the endpoints
+    /// as JSON, e.g. `[{"url": "https://eu.vps.example.com", "weight": 2, "region": "eu-west"}]`
+    pub fn from_env() -> Result<Option<Self>, VpsClientError> {
+        let Ok(json) = std::env::var("VPS_ENDPOINTS") else {
+            return Ok(None);
+        };
+        let mut config = Self {
+            endpoints: parse_endpoints(&json)?,
+            preferred_region: std::env::var("VPS_PREFERRED_REGION").ok().filter(|r| !r.is_empty()),
+            ..Self::default()
+        };
+        if let Ok(v) = std::env::var("VPS_MAX_ATTEMPTS") {
+            config.max_attempts = v.parse().map_err(|_| VpsClientError::Config("VPS_MAX_ATTEMPTS", v))?;
+        }
+        Ok(Some(config))
+    }
+}
+
+/// Endpoints from a JSON list; at least one must take traffic
+pub fn parse_endpoints(json: &str) -> Result<Vec<VpsEndpoint>, VpsClientError> {
+    let endpoints: Vec<VpsEndpoint> = serde_json::from_str(json)
+        .map_err(|e| VpsClientError::Config("VPS_ENDPOINTS", e.to_string()))?;
+    if let Some(bad) = endpoints.iter().find(|e| url::Url::parse(&e.url).is_err()) {
+        return Err(VpsClientError::Config("VPS_ENDPOINTS", format!("'{}' is not a URL", bad.url)));
+    }
+    if !endpoints.iter().any(|e| e.weight > 0) {
+        return Err(VpsClientError::Config("VPS_ENDPOINTS", "no endpoint has a weight above 0".to_string()));
+    }
+    Ok(endpoints)
+}
+
+// Health and routing snapshot for one endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct VpsEndpointStatus {
+    pub url: String,
+    pub region: Option<String>,
+    pub weight: u32,
+    pub available: bool,
+    pub last_health_ok: bool,
+    pub circuit: CircuitMetrics,
+}
+
+#[derive(Debug)]
+struct PoolMember {
+    endpoint: VpsEndpoint,
+    client: Arc<VpsApiClient>,
+}
+
+// Client-side load balancer over several VPS endpoints
+//
+// Homes are pinned to an endpoint with weighted rendezvous hashing so a home keeps
+// hitting the same backend (warm caches, ordered processing) while the overall load
+// follows the configured weights. When the pinned endpoint's circuit is open the next
+// endpoint in the home's ranking is used, which keeps failover sticky as well.
+#[derive(Debug)]
+pub struct VpsPool {
+    members: Vec<PoolMember>,
+    preferred_region: Option<String>,
+    max_attempts: usize,
+    cache: VpsResponseCache,
+}
+
+impl VpsPool {
+    pub fn new(config: VpsPoolConfig) -> Self {
+        let members = config.endpoints
+            .into_iter()
+            .filter(|e| e.weight > 0)
+            .map(|endpoint| PoolMember {
+                client: Arc::new(VpsApiClient::with_config(endpoint.url.clone(), config.client.clone())),
+                endpoint,
+            })
+            .collect();
+
+        Self {
+            members,
+            preferred_region: config.preferred_region,
+            max_attempts: config.max_attempts.max(1),
+            cache: VpsResponseCache::new(config.cache),
+        }
+    }
+
+    // Wraps an existing single client so callers that only have one VPS keep working
+    pub fn single(client: VpsApiClient) -> Self {
+        let endpoint = VpsEndpoint {
+            url: client.base_url().to_string(),
+            weight: 1,
+            region: None,
+        };
+        Self {
+            members: vec![PoolMember { endpoint, client: Arc::new(client) }],
+            preferred_region: None,
+            max_attempts: 1,
+            cache: VpsResponseCache::new(VpsCacheConfig::default()),
+        }
+    }
+
+    // Replace the response cache, e.g. to disable it or change its TTL
+    pub fn with_cache(mut self, config: VpsCacheConfig) -> Self {
+        self.cache = VpsResponseCache::new(config);
+        self
+    }
+
+    /// Submit an event, routed by home_id with failover to the next ranked endpoint.
+    /// Identical snapshots within the cache TTL are answered from the cache.
+    pub async fn process_event(
+        &self,
+        home_id: &str,
+        request: VpsProcessingRequest,
+    ) -> Result<VpsProcessingResponse, Box<dyn Error + Send + Sync>> {
+        if let Some(cached) = self.cache.get(&request).await {
+            return Ok(cached);
+        }
+
+        let mut last_error: Option<Box<dyn Error + Send + Sync>> = None;
+        let mut attempts = 0;
+
+        for member in self.ranked_members(home_id) {
+            if attempts >= self.max_attempts {
+                break;
+            }
+            if !member.client.is_available() {
+                continue;
+            }
+            attempts += 1;
+
+            match member.client.process_event(request.clone()).await {
+                Ok(response) => {
+                    self.cache.insert(&request, &response).await;
+                    return Ok(response);
+                }
+                Err(e) => {
+                    warn!("VPS endpoint {} failed for home {}: {}", member.endpoint.url, home_id, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| Box::new(VpsClientError::NoHealthyEndpoint)))
+    }
+
+    #[cfg(feature = "chaos")]
+    pub fn install_chaos(&self, chaos: Arc<ChaosInjector>) {
+        for member in &self.members {
+            member.client.install_chaos(chaos.clone());
+        }
+    }
+
+    /// Endpoint the given home is currently pinned to
+    pub fn route_for(&self, home_id: &str) -> Option<&VpsEndpoint> {
+        self.ranked_members(home_id)
+            .into_iter()
+            .find(|m| m.client.is_available())
+            .map(|m| &m.endpoint)
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.members.iter().any(|m| m.client.is_available())
+    }
+
+    pub fn cache_stats(&self) -> VpsCacheStats {
+        self.cache.stats()
+    }
+
+    pub fn endpoint_status(&self) -> Vec<VpsEndpointStatus> {
+        self.members
+            .iter()
+            .map(|m| VpsEndpointStatus {
+                url: m.endpoint.url.clone(),
+                region: m.endpoint.region.clone(),
+                weight: m.endpoint.weight,
+                available: m.client.is_available(),
+                last_health_ok: m.client.last_health_ok(),
+                circuit: m.client.circuit_metrics(),
+            })
+            .collect()
+    }
+
+    /// Start one health poller per endpoint
+    pub fn spawn_health_pollers(&self) -> Vec<tokio::task::JoinHandle<()>> {
+        self.members.iter().map(|m| m.client.spawn_health_poller()).collect()
+    }
+
+    // Preferred-region endpoints first, then by descending rendezvous score
+    fn ranked_members(&self, home_id: &str) -> Vec<&PoolMember> {
+        let mut ranked: Vec<(&PoolMember, bool, f64)> = self.members
+            .iter()
+            .map(|m| {
+                let in_region = match (&self.preferred_region, &m.endpoint.region) {
+                    (Some(preferred), Some(region)) => preferred == region,
+                    _ => false,
+                };
+                (m, in_region, Self::rendezvous_score(home_id, &m.endpoint))
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then(b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        ranked.into_iter().map(|(m, _, _)| m).collect()
+    }
+
+    // Weighted rendezvous score: -weight / ln(u) with u uniform in (0, 1)
+    fn rendezvous_score(home_id: &str, endpoint: &VpsEndpoint) -> f64 {
+        let mut hasher = DefaultHasher::new();
+        home_id.hash(&mut hasher);
+        endpoint.url.hash(&mut hasher);
+        let h = hasher.finish();
+        let u = ((h >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+        -(endpoint.weight as f64) / u.ln()
+    }
 }
@@ -0,0 +1,242 @@
+// src/watchdog.rs
+
+// Internal watchdog for the server's own health. Three things go wrong
+// quietly in a long-running daemon: an incident parks in Waiting and no
+// follow-up ever settles it; a queue stops draining (one priority lane of
+// deferred narratives starving behind the others, follow-ups piling up past
+// their due time); and a periodic job stops ticking, or sees the wall clock
+// jump between ticks (an NTP step, a suspended VM), which skews every timer
+// keyed on wall time. Background jobs report each tick here, and a periodic
+// `check` turns whatever is wrong into `HealthAlert`s. A condition that
+// persists is alerted again only after `realert_after`, and forgotten once
+// it clears. Alerts go to the admin delivery channel, never to households.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::thinking::{AlertDecision, Incident, IncidentStatus};
+
+#[derive(Debug, Clone)]
+pub struct WatchdogConfig {
+    pub max_waiting: Duration,           // An incident Waiting longer than this is stuck
+    pub missed_ticks: u32,               // A job silent for this many periods has stalled
+    pub max_clock_jump: Duration,        // Wall and monotonic time may disagree by this much between ticks
+    pub follow_up_lag: Duration,         // A due follow-up not settled within this is overdue
+    pub narrative_lane_max_age: Duration, // Deferred narratives are generated the next day at the latest
+    pub realert_after: Duration,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            max_waiting: Duration::minutes(30),
+            missed_ticks: 3,
+            max_clock_jump: Duration::seconds(30),
+            follow_up_lag: Duration::minutes(2),
+            narrative_lane_max_age: Duration::hours(26),
+            realert_after: Duration::hours(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthSeverity {
+    Warning,
+    Critical, // Alerts may be going missing
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HealthCondition {
+    StuckIncident { home_id: String, incident_id: u64, waiting_s: i64, follow_up_pending: bool },
+    LaneStalled { lane: String, depth: usize, oldest_age_s: i64 },
+    MissedTicks { job: String, period_s: u64, silent_s: u64 },
+    ClockJump { job: String, jump_s: i64 }, // Positive when the wall clock moved further than the monotonic one
+}
+
+impl HealthCondition {
+    // Identity for de-duplication: the same condition keeps its key while it persists
+    fn key(&self) -> String {
+        match self {
+            HealthCondition::StuckIncident { home_id, incident_id, .. } => format!("stuck:{}:{}", home_id, incident_id),
+            HealthCondition::LaneStalled { lane, .. } => format!("lane:{}", lane),
+            HealthCondition::MissedTicks { job, .. } => format!("ticks:{}", job),
+            HealthCondition::ClockJump { job, .. } => format!("clock:{}", job),
+        }
+    }
+
+    pub fn severity(&self) -> HealthSeverity {
+        match self {
+            // Nothing will ever settle it; its household hears nothing more
+            HealthCondition::StuckIncident { follow_up_pending: false, .. } => HealthSeverity::Critical,
+            HealthCondition::MissedTicks { .. } => HealthSeverity::Critical,
+            _ => HealthSeverity::Warning,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            HealthCondition::StuckIncident { home_id, incident_id, waiting_s, follow_up_pending } => format!(
+                "Incident {} in {} has been Waiting for {} min{}",
+                incident_id, home_id, waiting_s / 60,
+                if *follow_up_pending { "" } else { " with no follow-up scheduled" },
+            ),
+            HealthCondition::LaneStalled { lane, depth, oldest_age_s } => {
+                format!("Queue lane {} is not draining: {} item(s), oldest {} min old", lane, depth, oldest_age_s / 60)
+            }
+            HealthCondition::MissedTicks { job, period_s, silent_s } => {
+                format!("Job {} has not ticked for {}s (every {}s expected)", job, silent_s, period_s)
+            }
+            HealthCondition::ClockJump { job, jump_s } => {
+                format!("Wall clock jumped {:+}s between ticks of {}; wall-time timers may fire early or late", jump_s, job)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthAlert {
+    pub id: Uuid,
+    pub severity: HealthSeverity,
+    pub condition: HealthCondition,
+    pub message: String,
+    pub raised_at: DateTime<Utc>,
+}
+
+impl HealthAlert {
+    pub fn new(condition: HealthCondition, raised_at: DateTime<Utc>) -> Self {
+        Self { id: Uuid::new_v4(), severity: condition.severity(), message: condition.message(), condition, raised_at }
+    }
+}
+
+/// A queue as the watchdog sees it
+#[derive(Debug, Clone)]
+pub struct QueueLane {
+    pub name: String,
+    pub depth: usize,
+    pub oldest_at: Option<DateTime<Utc>>,
+    pub max_age: Duration, // The oldest item may be this old before the lane counts as stalled
+}
+
+#[derive(Debug, Clone)]
+pub struct WaitingIncident {
+    pub home_id: String,
+    pub incident_id: u64,
+    pub waiting_since: DateTime<Utc>,
+    pub follow_up_pending: bool,
+}
+
+/// When an incident's current Waiting spell began: the first of its trailing Wait assessments
+pub fn waiting_since(incident: &Incident) -> Option<DateTime<Utc>> {
+    if incident.status != IncidentStatus::Waiting {
+        return None;
+    }
+    let since = incident.probability_trace.iter().rev()
+        .take_while(|p| p.decision == AlertDecision::Wait)
+        .last()
+        .map_or(incident.last_updated, |p| p.ts);
+    DateTime::from_timestamp(since as i64, 0)
+}
+
+struct JobClock {
+    period: std::time::Duration,
+    last_mono: Instant,
+    last_wall: DateTime<Utc>,
+}
+
+pub struct Watchdog {
+    config: WatchdogConfig,
+    jobs: Mutex<HashMap<String, JobClock>>,
+    jumps: Mutex<Vec<HealthCondition>>,          // Seen at a tick, raised by the next check
+    raised: Mutex<HashMap<String, DateTime<Utc>>>, // Condition key -> last alerted
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new(WatchdogConfig::default())
+    }
+}
+
+impl Watchdog {
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self { config, jobs: Mutex::new(HashMap::new()), jumps: Mutex::new(Vec::new()), raised: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn config(&self) -> &WatchdogConfig {
+        &self.config
+    }
+
+    /// Expect `job` to tick every `period` from now on
+    pub fn register_job(&self, job: &str, period: std::time::Duration) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.insert(job.to_string(), JobClock { period, last_mono: Instant::now(), last_wall: Utc::now() });
+        }
+    }
+
+    pub fn tick(&self, job: &str) {
+        self.tick_at(job, Instant::now(), Utc::now());
+    }
+
+    /// Record a tick of a registered job, noting a wall-clock jump since its previous tick
+    pub fn tick_at(&self, job: &str, mono: Instant, wall: DateTime<Utc>) {
+        let Ok(mut jobs) = self.jobs.lock() else { return };
+        let Some(clock) = jobs.get_mut(job) else { return };
+        let mono_elapsed = mono.saturating_duration_since(clock.last_mono).as_millis() as i64;
+        let jump = Duration::milliseconds((wall - clock.last_wall).num_milliseconds() - mono_elapsed);
+        if jump.num_milliseconds().abs() > self.config.max_clock_jump.num_milliseconds() {
+            warn!("Wall clock jumped {}s between ticks of {}", jump.num_seconds(), job);
+            if let Ok(mut jumps) = self.jumps.lock() {
+                jumps.push(HealthCondition::ClockJump { job: job.to_string(), jump_s: jump.num_seconds() });
+            }
+        }
+        clock.last_mono = mono;
+        clock.last_wall = wall;
+    }
+
+    /// Everything wrong right now that has not been alerted recently
+    pub fn check(&self, mono: Instant, now: DateTime<Utc>, waiting: &[WaitingIncident], lanes: &[QueueLane]) -> Vec<HealthAlert> {
+        let mut conditions: Vec<HealthCondition> = self.jumps.lock().map(|mut j| j.drain(..).collect()).unwrap_or_default();
+        if let Ok(jobs) = self.jobs.lock() {
+            for (job, clock) in jobs.iter() {
+                let silent = mono.saturating_duration_since(clock.last_mono);
+                if silent > clock.period * self.config.missed_ticks {
+                    conditions.push(HealthCondition::MissedTicks { job: job.clone(), period_s: clock.period.as_secs(), silent_s: silent.as_secs() });
+                }
+            }
+        }
+        for incident in waiting {
+            let waiting_for = now - incident.waiting_since;
+            if waiting_for > self.config.max_waiting {
+                conditions.push(HealthCondition::StuckIncident {
+                    home_id: incident.home_id.clone(),
+                    incident_id: incident.incident_id,
+                    waiting_s: waiting_for.num_seconds(),
+                    follow_up_pending: incident.follow_up_pending,
+                });
+            }
+        }
+        for lane in lanes.iter().filter(|l| l.depth > 0) {
+            if let Some(age) = lane.oldest_at.map(|t| now - t).filter(|age| *age > lane.max_age) {
+                conditions.push(HealthCondition::LaneStalled { lane: lane.name.clone(), depth: lane.depth, oldest_age_s: age.num_seconds() });
+            }
+        }
+
+        let Ok(mut raised) = self.raised.lock() else { return Vec::new() };
+        let current: HashSet<String> = conditions.iter().map(HealthCondition::key).collect();
+        raised.retain(|key, _| current.contains(key));
+        conditions.into_iter().filter_map(|condition| {
+            let key = condition.key();
+            if raised.get(&key).is_some_and(|at| now - *at < self.config.realert_after) {
+                return None;
+            }
+            raised.insert(key, now);
+            Some(HealthAlert::new(condition, now))
+        }).collect()
+    }
+}
@@ -0,0 +1,171 @@
+// src/zone_graph.rs
+
+// Learned graph of how people move between a home's zones. Every time a
+// tracked person shows up in a different zone than where they were last seen,
+// the move is counted as an edge (front gate -> porch, driveway -> side
+// passage). Visitors follow a handful of well-worn paths, so once a home has
+// enough history a move the graph has rarely or never seen (street straight
+// into the back garden) is surprising, and that surprise becomes positive
+// behavior evidence for the person's incident. Each move is scored against
+// the graph as it was before the move is learned.
+
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Clone)]
+pub struct ZoneGraphConfig {
+    pub smoothing: f64,        // Pseudo-count added to every possible edge
+    pub min_transitions: u64,  // Moves a home must have seen before any is scored
+    pub max_gap_s: f64,        // Longer gaps between sightings are not counted as a move
+    pub surprise_floor: f64,   // Surprise (-ln p) below this scores nothing
+    pub llr_per_nat: f64,      // Behavior LLR per nat of surprise above the floor
+    pub max_llr: f64,
+}
+
+impl Default for ZoneGraphConfig {
+    fn default() -> Self {
+        Self {
+            smoothing: 0.5,
+            min_transitions: 50,
+            max_gap_s: 300.0,
+            surprise_floor: 10f64.ln(), // Moves taken less than one time in ten
+            llr_per_nat: 0.4,
+            max_llr: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PathStep {
+    pub from: String,
+    pub to: String,
+    pub probability: f64, // Smoothed chance of this move from `from`, before it was learned
+    pub surprise: f64,    // -ln(probability)
+    pub behavior_llr: f64, // 0 until the home has enough history
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneEdge {
+    pub from: String,
+    pub to: String,
+    pub count: u64,
+    pub probability: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneGraphView {
+    pub home_id: String,
+    pub zones: Vec<String>,
+    pub transitions: u64,
+    pub scoring: bool, // Whether moves are scored yet
+    pub edges: Vec<ZoneEdge>,
+}
+
+#[derive(Debug, Default)]
+struct HomeGraph {
+    edges: HashMap<(String, String), u64>,
+    outgoing: HashMap<String, u64>,
+    zones: BTreeSet<String>,
+    transitions: u64,
+}
+
+impl HomeGraph {
+    fn probability(&self, from: &str, to: &str, smoothing: f64) -> f64 {
+        let count = self.edges.get(&(from.to_string(), to.to_string())).copied().unwrap_or(0);
+        let out = self.outgoing.get(from).copied().unwrap_or(0);
+        // Every other known zone is a possible destination, plus one for zones not seen yet
+        let destinations = self.zones.iter().filter(|z| z.as_str() != from).count().max(1) + 1;
+        (count as f64 + smoothing) / (out as f64 + smoothing * destinations as f64)
+    }
+
+    fn learn(&mut self, from: &str, to: &str) {
+        *self.edges.entry((from.to_string(), to.to_string())).or_insert(0) += 1;
+        *self.outgoing.entry(from.to_string()).or_insert(0) += 1;
+        self.zones.insert(from.to_string());
+        self.zones.insert(to.to_string());
+        self.transitions += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ZoneGraph {
+    config: ZoneGraphConfig,
+    graphs: DashMap<String, HomeGraph>,
+    camera_zones: DashMap<String, HashMap<String, String>>, // Per home, from onboarding zones
+    last_zone: DashMap<(String, String), (String, f64)>,    // (home, track) -> zone and time last seen
+}
+
+impl ZoneGraph {
+    pub fn new(config: ZoneGraphConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn config(&self) -> &ZoneGraphConfig {
+        &self.config
+    }
+
+    /// Zone watched by each camera. Cameras without one count as their own zone.
+    pub fn set_layout(&self, home_id: &str, camera_zones: HashMap<String, String>) {
+        self.camera_zones.insert(home_id.to_string(), camera_zones);
+    }
+
+    fn zone_of(&self, home_id: &str, camera: &str) -> String {
+        self.camera_zones.get(home_id)
+            .and_then(|zones| zones.get(camera).cloned())
+            .unwrap_or_else(|| camera.to_string())
+    }
+
+    /// Feed one sighting of `track_id`; returns the scored move when it changed zone
+    pub fn observe(&self, home_id: &str, track_id: &str, camera: &str, ts: f64) -> Option<PathStep> {
+        let zone = self.zone_of(home_id, camera);
+        let previous = self.last_zone.get(&(home_id.to_string(), track_id.to_string())).map(|p| p.clone());
+        if previous.as_ref().is_some_and(|(_, seen)| ts < *seen) {
+            return None; // Out of order
+        }
+        self.last_zone.insert((home_id.to_string(), track_id.to_string()), (zone.clone(), ts));
+        let (from, seen) = previous?;
+        if from == zone || ts - seen > self.config.max_gap_s {
+            return None;
+        }
+
+        let mut graph = self.graphs.entry(home_id.to_string()).or_default();
+        let probability = graph.probability(&from, &zone, self.config.smoothing);
+        let surprise = -probability.ln();
+        let behavior_llr = if graph.transitions >= self.config.min_transitions {
+            ((surprise - self.config.surprise_floor) * self.config.llr_per_nat).clamp(0.0, self.config.max_llr)
+        } else {
+            0.0
+        };
+        graph.learn(&from, &zone);
+        Some(PathStep { from, to: zone, probability, surprise, behavior_llr })
+    }
+
+    /// Forget where tracks last seen before `cutoff` were
+    pub fn forget_before(&self, cutoff: f64) {
+        self.last_zone.retain(|_, (_, seen)| *seen >= cutoff);
+    }
+
+    /// The learned graph for a home, most travelled edges first
+    pub fn view(&self, home_id: &str) -> ZoneGraphView {
+        let Some(graph) = self.graphs.get(home_id) else {
+            return ZoneGraphView { home_id: home_id.to_string(), zones: Vec::new(), transitions: 0, scoring: false, edges: Vec::new() };
+        };
+        let mut edges: Vec<ZoneEdge> = graph.edges.iter()
+            .map(|((from, to), count)| ZoneEdge {
+                from: from.clone(),
+                to: to.clone(),
+                count: *count,
+                probability: graph.probability(from, to, self.config.smoothing),
+            })
+            .collect();
+        edges.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.from.cmp(&b.from)).then_with(|| a.to.cmp(&b.to)));
+        ZoneGraphView {
+            home_id: home_id.to_string(),
+            zones: graph.zones.iter().cloned().collect(),
+            transitions: graph.transitions,
+            scoring: graph.transitions >= self.config.min_transitions,
+            edges,
+        }
+    }
+}